@@ -0,0 +1,251 @@
+//! Command-line parsing for `rustack-admin`.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+/// Binary name reported in `--help` / `--version` output.
+pub const BIN_NAME: &str = "rustack-admin";
+
+/// A parsed command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    /// The `--endpoint-url` flag, if given.
+    pub endpoint: Option<String>,
+    /// The command to run.
+    pub command: Command,
+}
+
+/// A `rustack-admin` command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Print help text and exit.
+    Help,
+    /// Print the version and exit.
+    Version,
+    /// List buckets, or the objects under an `s3://bucket/prefix`.
+    S3Ls {
+        /// The bucket and prefix to list; every bucket if unset.
+        location: Option<String>,
+    },
+    /// Upload a file, or a directory with `--recursive`, to S3.
+    S3Cp {
+        /// The local file or directory.
+        source: PathBuf,
+        /// The `s3://bucket/key` destination.
+        destination: String,
+        /// Upload every file under `source`.
+        recursive: bool,
+    },
+    /// Verify the stored data of a bucket's objects.
+    S3Scrub {
+        /// The bucket to scrub.
+        bucket: String,
+    },
+    /// Write a DynamoDB table's items as JSON Lines.
+    DynamoDbDump {
+        /// The table to dump.
+        table: String,
+        /// The output file; stdout if unset.
+        out: Option<PathBuf>,
+    },
+    /// Put the items of a JSON Lines file into a DynamoDB table.
+    DynamoDbLoad {
+        /// The table to load into.
+        table: String,
+        /// The JSON Lines file.
+        input: PathBuf,
+    },
+    /// Reset the state of every service that supports it.
+    Reset,
+}
+
+/// Parse a command line, including argv\[0\].
+///
+/// `--help` and `--version` win over everything else, so they work even
+/// with an incomplete command.
+///
+/// # Errors
+///
+/// Returns an error describing an unknown command or flag, a flag missing
+/// its value, or a wrong number of arguments.
+pub fn parse_args<I, S>(args: I) -> Result<Invocation>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut endpoint = None;
+    let mut out = None;
+    let mut recursive = false;
+    let mut positional = Vec::new();
+
+    let mut iter = args.into_iter().skip(1).map(|arg| arg.as_ref().to_owned());
+    while let Some(arg) = iter.next() {
+        let mut value = |flag: &str| {
+            iter.next()
+                .ok_or_else(|| anyhow::anyhow!("flag '{flag}' requires a value"))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(invocation(endpoint, Command::Help)),
+            "-V" | "--version" => return Ok(invocation(endpoint, Command::Version)),
+            "--endpoint-url" => endpoint = Some(value("--endpoint-url")?),
+            "-o" | "--out" => out = Some(PathBuf::from(value("--out")?)),
+            "-r" | "--recursive" => recursive = true,
+            other if other.starts_with("--endpoint-url=") => {
+                endpoint = Some(other["--endpoint-url=".len()..].to_owned());
+            }
+            other if other.starts_with('-') && other.len() > 1 => {
+                anyhow::bail!("unrecognised flag '{other}'");
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let positional: Vec<&str> = positional.iter().map(String::as_str).collect();
+    let command = match positional.as_slice() {
+        [] => Command::Help,
+        ["s3", "ls"] => Command::S3Ls { location: None },
+        ["s3", "ls", location] => Command::S3Ls {
+            location: Some((*location).to_owned()),
+        },
+        ["s3", "cp", source, destination] => Command::S3Cp {
+            source: PathBuf::from(source),
+            destination: (*destination).to_owned(),
+            recursive,
+        },
+        ["s3", "scrub", location] => Command::S3Scrub {
+            bucket: crate::s3::parse_location(location)?.0,
+        },
+        ["dynamodb", "dump", table] => Command::DynamoDbDump {
+            table: (*table).to_owned(),
+            out,
+        },
+        ["dynamodb", "load", table, input] => Command::DynamoDbLoad {
+            table: (*table).to_owned(),
+            input: PathBuf::from(input),
+        },
+        ["reset"] => Command::Reset,
+        _ => anyhow::bail!("unrecognised command '{}'", positional.join(" ")),
+    };
+    if recursive && !matches!(command, Command::S3Cp { .. }) {
+        anyhow::bail!("'--recursive' only applies to 's3 cp'");
+    }
+    Ok(invocation(endpoint, command))
+}
+
+fn invocation(endpoint: Option<String>, command: Command) -> Invocation {
+    Invocation { endpoint, command }
+}
+
+/// Render the `--help` text.
+#[must_use]
+pub fn help_text() -> String {
+    format!(
+        "{BIN_NAME} {version}\n\
+         Inspect, seed, and reset a running Rustack server.\n\
+         \n\
+         USAGE:\n    \
+         {BIN_NAME} [--endpoint-url <URL>] <COMMAND>\n\
+         \n\
+         COMMANDS:\n    \
+         s3 ls [s3://<bucket>[/<prefix>]]         List buckets with object counts, or a bucket's objects\n    \
+         s3 cp <path> s3://<bucket>/<key>         Upload a file\n    \
+         s3 cp <dir> s3://<bucket>/<prefix> -r    Upload every file under a directory\n    \
+         s3 scrub s3://<bucket>                   Verify the stored data of a bucket's objects\n    \
+         dynamodb dump <table> [--out <file>]     Write a table's items as JSON Lines (default: stdout)\n    \
+         dynamodb load <table> <file>             Put the items of a JSON Lines file into a table\n    \
+         reset                                    Reset the state of S3 and DynamoDB\n\
+         \n\
+         FLAGS:\n    \
+         --endpoint-url <URL>  Gateway endpoint (default: RUSTACK_ENDPOINT, AWS_ENDPOINT_URL, or http://localhost:4566)\n    \
+         -r, --recursive       Upload a directory with 's3 cp'\n    \
+         -o, --out <FILE>      Output file of 'dynamodb dump'\n    \
+         -h, --help            Print this help message and exit\n    \
+         -V, --version         Print version information and exit\n",
+        version = env!("CARGO_PKG_VERSION"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Invocation> {
+        parse_args(std::iter::once(BIN_NAME).chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn test_should_parse_commands() {
+        assert_eq!(parse(&[]).unwrap().command, Command::Help);
+        assert_eq!(
+            parse(&["s3", "ls"]).unwrap().command,
+            Command::S3Ls { location: None }
+        );
+        assert_eq!(
+            parse(&[
+                "--endpoint-url",
+                "http://rustack:4566",
+                "s3",
+                "cp",
+                "seed",
+                "s3://b/p",
+                "-r"
+            ])
+            .unwrap(),
+            Invocation {
+                endpoint: Some("http://rustack:4566".to_owned()),
+                command: Command::S3Cp {
+                    source: PathBuf::from("seed"),
+                    destination: "s3://b/p".to_owned(),
+                    recursive: true,
+                },
+            }
+        );
+        assert_eq!(
+            parse(&["dynamodb", "dump", "users", "--out", "users.jsonl"])
+                .unwrap()
+                .command,
+            Command::DynamoDbDump {
+                table: "users".to_owned(),
+                out: Some(PathBuf::from("users.jsonl")),
+            }
+        );
+        assert_eq!(
+            parse(&["dynamodb", "load", "users", "users.jsonl"])
+                .unwrap()
+                .command,
+            Command::DynamoDbLoad {
+                table: "users".to_owned(),
+                input: PathBuf::from("users.jsonl"),
+            }
+        );
+        assert_eq!(
+            parse(&["--endpoint-url=http://h:1", "reset"]).unwrap(),
+            Invocation {
+                endpoint: Some("http://h:1".to_owned()),
+                command: Command::Reset,
+            }
+        );
+        assert_eq!(
+            parse(&["s3", "scrub", "s3://data"]).unwrap().command,
+            Command::S3Scrub {
+                bucket: "data".to_owned(),
+            }
+        );
+        assert_eq!(parse(&["reset", "--help"]).unwrap().command, Command::Help);
+    }
+
+    #[test]
+    fn test_should_reject_malformed_command_lines() {
+        for args in [
+            &["s3"][..],
+            &["s3", "rm", "s3://b/k"],
+            &["dynamodb", "load", "users"],
+            &["reset", "--nope"],
+            &["reset", "--recursive"],
+            &["dynamodb", "dump", "users", "--out"],
+        ] {
+            assert!(parse(args).is_err(), "{args:?}");
+        }
+    }
+}
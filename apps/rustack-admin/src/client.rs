@@ -0,0 +1,164 @@
+//! Minimal HTTP client for the Rustack gateway.
+//!
+//! Each request opens its own HTTP/1.1 connection: the admin commands issue
+//! few, mostly sequential requests, so pooling would buy nothing. Requests
+//! are unsigned, which the gateway accepts while
+//! `<SERVICE>_SKIP_SIGNATURE_VALIDATION` is on (the default).
+
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use http::{Method, Request, StatusCode, Uri, header};
+use http_body_util::{BodyExt, Full};
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpStream;
+
+/// Environment variable naming the gateway endpoint.
+pub const ENDPOINT_ENV: &str = "RUSTACK_ENDPOINT";
+
+/// Endpoint used when none is configured.
+pub const DEFAULT_ENDPOINT: &str = "http://localhost:4566";
+
+/// A response from the gateway, read in full.
+#[derive(Debug, Clone)]
+pub struct Response {
+    /// The response status.
+    pub status: StatusCode,
+    /// The response body.
+    pub body: Bytes,
+}
+
+impl Response {
+    /// The body as text, for error messages.
+    #[must_use]
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// Client for a Rustack gateway reachable over plain HTTP.
+#[derive(Debug, Clone)]
+pub struct AdminClient {
+    authority: String,
+}
+
+impl AdminClient {
+    /// Create a client for the gateway at `endpoint`, such as
+    /// `http://localhost:4566`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `endpoint` is not an `http://` URL with a host and
+    /// no path.
+    pub fn new(endpoint: &str) -> Result<Self> {
+        let uri: Uri = endpoint
+            .parse()
+            .with_context(|| format!("invalid endpoint {endpoint}"))?;
+        if uri.scheme_str() != Some("http") {
+            anyhow::bail!("endpoint {endpoint} must be an http:// URL");
+        }
+        if uri.path_and_query().is_some_and(|p| p.as_str() != "/") {
+            anyhow::bail!("endpoint {endpoint} must not have a path");
+        }
+        let authority = uri
+            .authority()
+            .with_context(|| format!("endpoint {endpoint} has no host"))?;
+        let authority = if authority.port().is_some() {
+            authority.to_string()
+        } else {
+            format!("{authority}:80")
+        };
+        Ok(Self { authority })
+    }
+
+    /// Create a client for the endpoint given by `--endpoint-url`, else
+    /// `RUSTACK_ENDPOINT`, else `AWS_ENDPOINT_URL`, else
+    /// [`DEFAULT_ENDPOINT`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chosen endpoint is invalid.
+    pub fn from_env(endpoint_flag: Option<&str>) -> Result<Self> {
+        let endpoint = endpoint_flag
+            .map(str::to_owned)
+            .or_else(|| std::env::var(ENDPOINT_ENV).ok())
+            .or_else(|| std::env::var("AWS_ENDPOINT_URL").ok())
+            .unwrap_or_else(|| DEFAULT_ENDPOINT.to_owned());
+        Self::new(endpoint.trim_end_matches('/'))
+    }
+
+    /// Send a request and read the whole response.
+    ///
+    /// `path` includes the query string. `headers` are sent in addition to
+    /// `Host` and `Content-Length`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the gateway cannot be reached or the exchange
+    /// fails. Error statuses are returned as responses.
+    pub async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        headers: &[(&str, &str)],
+        body: Bytes,
+    ) -> Result<Response> {
+        let mut request = Request::builder()
+            .method(method.clone())
+            .uri(path)
+            .header(header::HOST, &self.authority)
+            .header(header::CONTENT_LENGTH, body.len());
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        let request = request
+            .body(Full::new(body))
+            .with_context(|| format!("invalid request {method} {path}"))?;
+
+        let stream = TcpStream::connect(&self.authority)
+            .await
+            .with_context(|| format!("cannot connect to {}", self.authority))?;
+        let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+            .await
+            .context("HTTP handshake failed")?;
+        let connection = tokio::spawn(connection);
+
+        let response = sender
+            .send_request(request)
+            .await
+            .with_context(|| format!("{method} {path} failed"))?;
+        let status = response.status();
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .with_context(|| format!("reading the response to {method} {path} failed"))?
+            .to_bytes();
+        drop(sender);
+        // The connection finishes once the sender is gone; its outcome does
+        // not matter after the response was read in full.
+        let _ = connection.await;
+
+        Ok(Response { status, body })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_accept_only_plain_http_endpoints() {
+        let client = AdminClient::new("http://localhost:4566").unwrap();
+        assert_eq!(client.authority, "localhost:4566");
+        let client = AdminClient::new("http://rustack/").unwrap();
+        assert_eq!(client.authority, "rustack:80");
+
+        for endpoint in [
+            "https://localhost:4566",
+            "localhost:4566",
+            "http://host/path",
+        ] {
+            assert!(AdminClient::new(endpoint).is_err(), "{endpoint}");
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! DynamoDB commands: dump a table to JSON Lines and load it back.
+//!
+//! Each line holds one item in DynamoDB's attribute-value JSON, the shape
+//! `Scan` returns and `PutItem` accepts:
+//!
+//! ```json
+//! {"pk":{"S":"user#1"},"age":{"N":"42"}}
+//! ```
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use http::Method;
+use rustack_dynamodb_model::{
+    AttributeValue, DynamoDBOperation,
+    input::{BatchWriteItemInput, ScanInput},
+    output::{BatchWriteItemOutput, ScanOutput},
+    types::{PutRequest, WriteRequest},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::client::AdminClient;
+
+/// Most items `BatchWriteItem` accepts in one request.
+const MAX_BATCH_WRITE_ITEMS: usize = 25;
+
+/// Most attempts at writing a batch's unprocessed items.
+const MAX_BATCH_WRITE_ATTEMPTS: usize = 5;
+
+/// A DynamoDB item.
+type Item = HashMap<String, AttributeValue>;
+
+/// Write every item of `table` to `out` as JSON Lines, following scan pages
+/// to the end. Returns the number of items written.
+///
+/// # Errors
+///
+/// Returns an error if a scan fails or `out` cannot be written.
+pub async fn dump(
+    client: &AdminClient,
+    table: &str,
+    out: &mut (impl AsyncWrite + Unpin),
+) -> Result<u64> {
+    let mut count = 0;
+    let mut input = ScanInput {
+        table_name: table.to_owned(),
+        ..ScanInput::default()
+    };
+    loop {
+        let page: ScanOutput = call(client, DynamoDBOperation::Scan, &input)
+            .await
+            .with_context(|| format!("scanning {table} failed"))?;
+        for item in page.items.unwrap_or_default() {
+            let mut line = serde_json::to_vec(&item)?;
+            line.push(b'\n');
+            out.write_all(&line).await?;
+            count += 1;
+        }
+        let Some(key) = page.last_evaluated_key else {
+            out.flush().await?;
+            return Ok(count);
+        };
+        input.exclusive_start_key = key;
+    }
+}
+
+/// Put every item read from `lines`, one JSON item per line, into `table`.
+/// Blank lines are skipped. Returns the number of items written.
+///
+/// # Errors
+///
+/// Returns an error if a line is not an item, a write fails, or items stay
+/// unprocessed after retrying.
+pub async fn load(
+    client: &AdminClient,
+    table: &str,
+    lines: impl AsyncBufRead + Unpin,
+) -> Result<u64> {
+    let mut count = 0;
+    let mut batch = Vec::with_capacity(MAX_BATCH_WRITE_ITEMS);
+    let mut lines = lines.lines();
+    let mut line_number = 0;
+    while let Some(line) = lines.next_line().await? {
+        line_number += 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let item: Item = serde_json::from_str(&line)
+            .with_context(|| format!("line {line_number} is not a DynamoDB item"))?;
+        batch.push(item);
+        if batch.len() == MAX_BATCH_WRITE_ITEMS {
+            count += write_batch(client, table, std::mem::take(&mut batch)).await?;
+        }
+    }
+    if !batch.is_empty() {
+        count += write_batch(client, table, batch).await?;
+    }
+    Ok(count)
+}
+
+/// Put `items` into `table` with `BatchWriteItem`, retrying unprocessed
+/// items.
+async fn write_batch(client: &AdminClient, table: &str, items: Vec<Item>) -> Result<u64> {
+    let count = items.len() as u64;
+    let mut requests: Vec<WriteRequest> = items
+        .into_iter()
+        .map(|item| WriteRequest {
+            put_request: Some(PutRequest { item }),
+            delete_request: None,
+        })
+        .collect();
+    for _ in 0..MAX_BATCH_WRITE_ATTEMPTS {
+        let input = BatchWriteItemInput {
+            request_items: HashMap::from([(table.to_owned(), requests)]),
+            ..BatchWriteItemInput::default()
+        };
+        let output: BatchWriteItemOutput = call(client, DynamoDBOperation::BatchWriteItem, &input)
+            .await
+            .with_context(|| format!("writing to {table} failed"))?;
+        requests = output.unprocessed_items.into_values().flatten().collect();
+        if requests.is_empty() {
+            return Ok(count);
+        }
+    }
+    anyhow::bail!(
+        "{} items to {table} were still unprocessed after {MAX_BATCH_WRITE_ATTEMPTS} attempts",
+        requests.len()
+    )
+}
+
+/// Call the DynamoDB operation `operation` with `input`.
+async fn call<I: Serialize, O: DeserializeOwned>(
+    client: &AdminClient,
+    operation: DynamoDBOperation,
+    input: &I,
+) -> Result<O> {
+    let operation = operation.as_str();
+    let target = format!("DynamoDB_20120810.{operation}");
+    let headers = [
+        ("X-Amz-Target", target.as_str()),
+        ("Content-Type", "application/x-amz-json-1.0"),
+    ];
+    let body = Bytes::from(serde_json::to_vec(input)?);
+    let response = client.send(Method::POST, "/", &headers, body).await?;
+    if !response.status.is_success() {
+        let error: serde_json::Value = serde_json::from_slice(&response.body).unwrap_or_default();
+        let code = error["__type"]
+            .as_str()
+            .and_then(|kind| kind.rsplit('#').next())
+            .unwrap_or("error");
+        let message = error["Message"]
+            .as_str()
+            .or_else(|| error["message"].as_str())
+            .map_or_else(|| response.text(), str::to_owned);
+        anyhow::bail!("{code}: {message}");
+    }
+    serde_json::from_slice(&response.body)
+        .with_context(|| format!("unexpected {operation} response: {}", response.text()))
+}
+
+#[cfg(test)]
+mod tests {
+    use rustack_server::ServerBuilder;
+    use serde_json::json;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_should_dump_and_load_a_table() {
+        let server = ServerBuilder::new()
+            .services(&["dynamodb"])
+            .start()
+            .await
+            .unwrap();
+        let client = AdminClient::new(&server.endpoint_url()).unwrap();
+        for table in ["source", "copy"] {
+            let input = json!({
+                "TableName": table,
+                "KeySchema": [{"AttributeName": "pk", "KeyType": "HASH"}],
+                "AttributeDefinitions": [{"AttributeName": "pk", "AttributeType": "S"}],
+                "BillingMode": "PAY_PER_REQUEST",
+            });
+            call::<_, serde_json::Value>(&client, DynamoDBOperation::CreateTable, &input)
+                .await
+                .unwrap();
+        }
+
+        // More than one batch, with a blank line in the middle.
+        let mut seed = String::new();
+        for i in 0..30 {
+            seed.push_str(
+                &json!({"pk": {"S": format!("item#{i:02}")}, "n": {"N": i.to_string()}})
+                    .to_string(),
+            );
+            seed.push('\n');
+            if i == 10 {
+                seed.push('\n');
+            }
+        }
+        assert_eq!(load(&client, "source", seed.as_bytes()).await.unwrap(), 30);
+
+        let mut dumped = Vec::new();
+        assert_eq!(dump(&client, "source", &mut dumped).await.unwrap(), 30);
+        assert_eq!(load(&client, "copy", dumped.as_slice()).await.unwrap(), 30);
+        let mut copied = Vec::new();
+        dump(&client, "copy", &mut copied).await.unwrap();
+        let sorted = |lines: &[u8]| {
+            let mut lines: Vec<serde_json::Value> = lines
+                .split(|&b| b == b'\n')
+                .filter(|line| !line.is_empty())
+                .map(|line| serde_json::from_slice(line).unwrap())
+                .collect();
+            lines.sort_by_key(|item| item["pk"]["S"].as_str().unwrap_or_default().to_owned());
+            lines
+        };
+        assert_eq!(sorted(&copied), sorted(&dumped));
+        assert_eq!(
+            sorted(&copied)[7],
+            json!({"pk": {"S": "item#07"}, "n": {"N": "7"}})
+        );
+
+        let err = load(&client, "source", "not json\n".as_bytes())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("line 1"), "{err:#}");
+        let err = dump(&client, "missing", &mut Vec::new()).await.unwrap_err();
+        assert!(
+            format!("{err:#}").contains("ResourceNotFoundException"),
+            "{err:#}"
+        );
+        server.shutdown().await.unwrap();
+    }
+}
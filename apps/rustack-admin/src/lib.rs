@@ -0,0 +1,283 @@
+//! Admin tool for a running Rustack server.
+//!
+//! `rustack-admin` inspects and seeds state over the gateway's HTTP API,
+//! without crafting AWS CLI commands:
+//!
+//! ```text
+//! rustack-admin s3 ls
+//! rustack-admin s3 cp ./fixtures s3://seed/fixtures --recursive
+//! rustack-admin s3 scrub s3://seed
+//! rustack-admin dynamodb dump users --out users.jsonl
+//! rustack-admin dynamodb load users users.jsonl
+//! rustack-admin reset
+//! ```
+//!
+//! S3 and DynamoDB commands use the services' own APIs; `s3 scrub` and
+//! `reset` call the gateway's `POST /_localstack/s3/scrub/<bucket>` and
+//! `POST /_localstack/state/reset` endpoints. The endpoint comes
+//! from `--endpoint-url`, `RUSTACK_ENDPOINT`, or `AWS_ENDPOINT_URL`, in that
+//! order, and defaults to `http://localhost:4566`.
+
+pub mod cli;
+pub mod client;
+pub mod dynamodb;
+pub mod s3;
+
+use std::{fmt::Write as _, path::Path};
+
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use http::Method;
+use tokio::io::{AsyncWrite, AsyncWriteExt, BufReader};
+
+use crate::{
+    cli::{Command, Invocation},
+    client::AdminClient,
+};
+
+/// Path of the gateway's state reset endpoint.
+const RESET_PATH: &str = "/_localstack/state/reset";
+
+/// Reset the state of every service that supports it, returning their
+/// names.
+///
+/// # Errors
+///
+/// Returns an error if the request fails.
+pub async fn reset(client: &AdminClient) -> Result<Vec<String>> {
+    let response = client
+        .send(Method::POST, RESET_PATH, &[], Bytes::new())
+        .await?;
+    if !response.status.is_success() {
+        anyhow::bail!(
+            "reset failed with HTTP {}: {}",
+            response.status,
+            response.text()
+        );
+    }
+    let body: serde_json::Value =
+        serde_json::from_slice(&response.body).context("unexpected reset response")?;
+    Ok(body["reset"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|name| name.as_str().map(str::to_owned))
+        .collect())
+}
+
+/// Run a parsed command, writing its report to `out`.
+///
+/// A command whose output is data rather than a report, such as
+/// `dynamodb dump` without `--out`, writes the data to `out` and returns its
+/// summary for the caller to print apart from it, on stderr.
+///
+/// `--help` and `--version` are the caller's to handle; they print nothing
+/// here.
+///
+/// # Errors
+///
+/// Returns an error if the endpoint is invalid or the command fails.
+pub async fn run(
+    invocation: Invocation,
+    out: &mut (impl AsyncWrite + Unpin),
+) -> Result<Option<String>> {
+    let client = AdminClient::from_env(invocation.endpoint.as_deref())?;
+    let report = match invocation.command {
+        Command::Help | Command::Version => String::new(),
+        Command::S3Ls { location: None } => {
+            let mut report = format!("{:>10} {:>14}  BUCKET\n", "OBJECTS", "BYTES");
+            for bucket in s3::list_buckets(&client).await? {
+                writeln!(
+                    report,
+                    "{:>10} {:>14}  {}",
+                    bucket.object_count, bucket.size, bucket.name
+                )?;
+            }
+            report
+        }
+        Command::S3Ls {
+            location: Some(location),
+        } => {
+            let (bucket, prefix) = s3::parse_location(&location)?;
+            let mut report = String::new();
+            for object in s3::list_objects(&client, &bucket, &prefix).await? {
+                writeln!(
+                    report,
+                    "{}  {:>12}  {}",
+                    object.last_modified, object.size, object.key
+                )?;
+            }
+            report
+        }
+        Command::S3Cp {
+            source,
+            destination,
+            recursive,
+        } => copy(&client, &source, &destination, recursive).await?,
+        Command::S3Scrub { bucket } => {
+            let scrub = s3::scrub(&client, &bucket).await?;
+            let mut report = format!(
+                "checked {} objects ({} bytes) in {bucket}, {} corrupt\n",
+                scrub.objects_checked,
+                scrub.bytes_checked,
+                scrub.mismatches.len()
+            );
+            for mismatch in scrub.mismatches {
+                writeln!(
+                    report,
+                    "corrupt: s3://{bucket}/{} (version {}): {}",
+                    mismatch.key, mismatch.version_id, mismatch.reason
+                )?;
+            }
+            report
+        }
+        Command::DynamoDbDump { table, out: None } => {
+            let count = dynamodb::dump(&client, &table, out).await?;
+            out.flush().await?;
+            return Ok(Some(format!("dumped {count} items from {table}\n")));
+        }
+        Command::DynamoDbDump {
+            table,
+            out: Some(path),
+        } => {
+            let mut file = tokio::fs::File::create(&path)
+                .await
+                .with_context(|| format!("cannot create {}", path.display()))?;
+            let count = dynamodb::dump(&client, &table, &mut file).await?;
+            format!("dumped {count} items from {table} to {}\n", path.display())
+        }
+        Command::DynamoDbLoad { table, input } => {
+            let file = tokio::fs::File::open(&input)
+                .await
+                .with_context(|| format!("cannot open {}", input.display()))?;
+            let count = dynamodb::load(&client, &table, BufReader::new(file)).await?;
+            format!("loaded {count} items into {table}\n")
+        }
+        Command::Reset => format!("reset {}\n", reset(&client).await?.join(", ")),
+    };
+    out.write_all(report.as_bytes()).await?;
+    out.flush().await?;
+    Ok(None)
+}
+
+/// Upload `source` to the `s3://bucket/key` `destination`, reporting each
+/// uploaded file.
+async fn copy(
+    client: &AdminClient,
+    source: &Path,
+    destination: &str,
+    recursive: bool,
+) -> Result<String> {
+    let (bucket, key) = s3::parse_location(destination)?;
+    if recursive {
+        let keys = s3::upload_dir(client, source, &bucket, &key).await?;
+        let mut report = String::new();
+        for key in keys {
+            writeln!(report, "upload: s3://{bucket}/{key}")?;
+        }
+        return Ok(report);
+    }
+
+    // A key ending in `/`, or none at all, names a folder for the file.
+    let key = if key.is_empty() || key.ends_with('/') {
+        let name = source
+            .file_name()
+            .with_context(|| format!("{} is not a file", source.display()))?;
+        format!("{key}{}", name.to_string_lossy())
+    } else {
+        key
+    };
+    s3::upload_file(client, source, &bucket, &key).await?;
+    Ok(format!("upload: s3://{bucket}/{key}\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use rustack_server::ServerBuilder;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_should_copy_list_and_reset_through_commands() {
+        let server = ServerBuilder::new()
+            .services(&["s3", "dynamodb"])
+            .start()
+            .await
+            .unwrap();
+        let client = AdminClient::new(&server.endpoint_url()).unwrap();
+        client
+            .send(Method::PUT, "/seed", &[], Bytes::new())
+            .await
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("hello.txt");
+        tokio::fs::write(&file, "hello").await.unwrap();
+
+        let run_command = |args: Vec<String>| async move {
+            let invocation = cli::parse_args(args).unwrap();
+            let mut out = Vec::new();
+            assert_eq!(run(invocation, &mut out).await.unwrap(), None);
+            String::from_utf8(out).unwrap()
+        };
+        let endpoint = server.endpoint_url();
+        let command = |args: &[&str]| {
+            ["rustack-admin", "--endpoint-url", endpoint.as_str()]
+                .iter()
+                .chain(args)
+                .map(|arg| (*arg).to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        let file_arg = file.to_string_lossy().into_owned();
+        let report = run_command(command(&["s3", "cp", &file_arg, "s3://seed/docs/"])).await;
+        assert_eq!(report, "upload: s3://seed/docs/hello.txt\n");
+        let report = run_command(command(&["s3", "ls", "s3://seed/docs"])).await;
+        assert!(
+            report.ends_with("            5  docs/hello.txt\n"),
+            "{report}"
+        );
+        let report = run_command(command(&["s3", "ls"])).await;
+        assert!(
+            report.contains("         1              5  seed\n"),
+            "{report}"
+        );
+
+        let report = run_command(command(&["s3", "scrub", "s3://seed"])).await;
+        assert_eq!(report, "checked 1 objects (5 bytes) in seed, 0 corrupt\n");
+
+        let create_table = r#"{
+            "TableName": "users",
+            "KeySchema": [{"AttributeName": "pk", "KeyType": "HASH"}],
+            "AttributeDefinitions": [{"AttributeName": "pk", "AttributeType": "S"}],
+            "BillingMode": "PAY_PER_REQUEST"
+        }"#;
+        let headers = [
+            ("X-Amz-Target", "DynamoDB_20120810.CreateTable"),
+            ("Content-Type", "application/x-amz-json-1.0"),
+        ];
+        let response = client
+            .send(Method::POST, "/", &headers, Bytes::from(create_table))
+            .await
+            .unwrap();
+        assert!(response.status.is_success(), "{}", response.text());
+        let items = dir.path().join("users.jsonl");
+        tokio::fs::write(&items, "{\"pk\":{\"S\":\"alice\"}}\n")
+            .await
+            .unwrap();
+        let items_arg = items.to_string_lossy().into_owned();
+        let report = run_command(command(&["dynamodb", "load", "users", &items_arg])).await;
+        assert_eq!(report, "loaded 1 items into users\n");
+
+        // Without `--out`, the items go to `out` and the summary is returned.
+        let invocation = cli::parse_args(command(&["dynamodb", "dump", "users"])).unwrap();
+        let mut out = Vec::new();
+        let summary = run(invocation, &mut out).await.unwrap();
+        assert_eq!(summary.as_deref(), Some("dumped 1 items from users\n"));
+        assert_eq!(out, b"{\"pk\":{\"S\":\"alice\"}}\n");
+
+        let report = run_command(command(&["reset"])).await;
+        assert_eq!(report, "reset dynamodb, s3\n");
+        assert!(s3::list_buckets(&client).await.unwrap().is_empty());
+        server.shutdown().await.unwrap();
+    }
+}
@@ -0,0 +1,28 @@
+//! `rustack-admin`: inspect, seed, and reset a running Rustack server.
+//!
+//! See the `rustack_admin` library docs for the commands.
+
+use rustack_admin::cli::{self, BIN_NAME, Command};
+
+#[tokio::main]
+async fn main() {
+    let invocation = match cli::parse_args(std::env::args()) {
+        Ok(invocation) => invocation,
+        Err(e) => {
+            eprintln!("error: {e}\n");
+            eprint!("{}", cli::help_text());
+            std::process::exit(2);
+        }
+    };
+    match invocation.command {
+        Command::Help => print!("{}", cli::help_text()),
+        Command::Version => println!("{BIN_NAME} {}", env!("CARGO_PKG_VERSION")),
+        _ => match rustack_admin::run(invocation, &mut tokio::io::stdout()).await {
+            Ok(summary) => eprint!("{}", summary.unwrap_or_default()),
+            Err(e) => {
+                eprintln!("error: {e:#}");
+                std::process::exit(1);
+            }
+        },
+    }
+}
@@ -0,0 +1,418 @@
+//! S3 commands: list buckets and objects, upload local files, and scrub a
+//! bucket's stored data.
+//!
+//! Requests use path-style addressing (`/<bucket>/<key>`), which the gateway
+//! serves whatever `S3_DOMAIN` is set to. Scrubbing calls the gateway's
+//! `POST /_localstack/s3/scrub/<bucket>` endpoint.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use http::Method;
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use quick_xml::{Reader, escape::resolve_predefined_entity, events::Event};
+use serde::Deserialize;
+
+use crate::client::{AdminClient, Response};
+
+/// Characters escaped in a key path: everything but unreserved characters
+/// and `/`.
+const KEY: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~')
+    .remove(b'/');
+
+/// Characters escaped in a query value: everything but unreserved
+/// characters.
+const QUERY: &AsciiSet = &KEY.add(b'/');
+
+/// Path prefix of the gateway's scrub endpoint; the bucket name follows it.
+const SCRUB_PATH_PREFIX: &str = "/_localstack/s3/scrub/";
+
+/// A bucket and the current objects in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BucketSummary {
+    /// The bucket name.
+    pub name: String,
+    /// The number of current objects.
+    pub object_count: u64,
+    /// The total size of the current objects, in bytes.
+    pub size: u64,
+}
+
+/// An object listed in a bucket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectSummary {
+    /// The object key.
+    pub key: String,
+    /// The object size, in bytes.
+    pub size: u64,
+    /// When the object was last modified, as reported by the server.
+    pub last_modified: String,
+}
+
+/// The outcome of verifying the stored data of a bucket's objects.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrubReport {
+    /// The number of object versions verified.
+    pub objects_checked: u64,
+    /// The total size of the verified versions, in bytes.
+    pub bytes_checked: u64,
+    /// The versions whose data failed verification.
+    pub mismatches: Vec<ScrubMismatch>,
+}
+
+/// An object version whose stored data failed verification.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrubMismatch {
+    /// The object key.
+    pub key: String,
+    /// The version ID (`null` for un-versioned objects).
+    pub version_id: String,
+    /// Why verification failed.
+    pub reason: String,
+}
+
+/// Split an `s3://bucket/prefix` URL, or a bare `bucket/prefix`, into the
+/// bucket and the (possibly empty) prefix.
+///
+/// # Errors
+///
+/// Returns an error if the bucket name is empty.
+pub fn parse_location(location: &str) -> Result<(String, String)> {
+    let path = location.strip_prefix("s3://").unwrap_or(location);
+    let (bucket, prefix) = path.split_once('/').unwrap_or((path, ""));
+    if bucket.is_empty() {
+        anyhow::bail!("{location} names no bucket");
+    }
+    Ok((bucket.to_owned(), prefix.to_owned()))
+}
+
+/// List every bucket with the number and total size of its objects.
+///
+/// # Errors
+///
+/// Returns an error if a request fails.
+pub async fn list_buckets(client: &AdminClient) -> Result<Vec<BucketSummary>> {
+    let response = expect_success(client.send(Method::GET, "/", &[], Bytes::new()).await?)?;
+    let names: Vec<String> = records(&response.body, "Bucket")?
+        .into_iter()
+        .filter_map(|mut bucket| bucket.remove("Name"))
+        .collect();
+
+    let mut buckets = Vec::with_capacity(names.len());
+    for name in names {
+        let objects = list_objects(client, &name, "").await?;
+        buckets.push(BucketSummary {
+            object_count: objects.len() as u64,
+            size: objects.iter().map(|object| object.size).sum(),
+            name,
+        });
+    }
+    Ok(buckets)
+}
+
+/// List the objects in `bucket` whose keys start with `prefix`, following
+/// continuation tokens to the end.
+///
+/// # Errors
+///
+/// Returns an error if a request fails, for example because the bucket
+/// does not exist.
+pub async fn list_objects(
+    client: &AdminClient,
+    bucket: &str,
+    prefix: &str,
+) -> Result<Vec<ObjectSummary>> {
+    let mut objects = Vec::new();
+    let mut token: Option<String> = None;
+    loop {
+        let mut path = format!(
+            "/{}?list-type=2&prefix={}",
+            utf8_percent_encode(bucket, QUERY),
+            utf8_percent_encode(prefix, QUERY)
+        );
+        if let Some(token) = &token {
+            path.push_str("&continuation-token=");
+            path.extend(utf8_percent_encode(token, QUERY));
+        }
+        let response = expect_success(client.send(Method::GET, &path, &[], Bytes::new()).await?)
+            .with_context(|| format!("listing s3://{bucket}/{prefix} failed"))?;
+
+        for mut object in records(&response.body, "Contents")? {
+            objects.push(ObjectSummary {
+                key: object.remove("Key").unwrap_or_default(),
+                size: object
+                    .get("Size")
+                    .and_then(|size| size.parse().ok())
+                    .unwrap_or_default(),
+                last_modified: object.remove("LastModified").unwrap_or_default(),
+            });
+        }
+
+        let mut page = records(&response.body, "ListBucketResult")?
+            .pop()
+            .unwrap_or_default();
+        token = page.remove("NextContinuationToken");
+        if page.get("IsTruncated").map(String::as_str) != Some("true") || token.is_none() {
+            return Ok(objects);
+        }
+    }
+}
+
+/// Verify the stored data of every object version in `bucket`. Versions
+/// that fail are flagged by the server and listed in the report.
+///
+/// # Errors
+///
+/// Returns an error if the request fails, for example because the bucket
+/// does not exist.
+pub async fn scrub(client: &AdminClient, bucket: &str) -> Result<ScrubReport> {
+    let path = format!("{SCRUB_PATH_PREFIX}{}", utf8_percent_encode(bucket, QUERY));
+    let response = client.send(Method::POST, &path, &[], Bytes::new()).await?;
+    if !response.status.is_success() {
+        anyhow::bail!(
+            "scrub of {bucket} failed with HTTP {}: {}",
+            response.status,
+            response.text()
+        );
+    }
+    serde_json::from_slice(&response.body).context("unexpected scrub response")
+}
+
+/// Upload the file at `path` as `key` in `bucket`.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or the upload fails.
+pub async fn upload_file(client: &AdminClient, path: &Path, bucket: &str, key: &str) -> Result<()> {
+    let body = tokio::fs::read(path)
+        .await
+        .with_context(|| format!("cannot read {}", path.display()))?;
+    let object_path = format!(
+        "/{}/{}",
+        utf8_percent_encode(bucket, KEY),
+        utf8_percent_encode(key, KEY)
+    );
+    expect_success(
+        client
+            .send(Method::PUT, &object_path, &[], Bytes::from(body))
+            .await?,
+    )
+    .with_context(|| format!("uploading {} to s3://{bucket}/{key} failed", path.display()))?;
+    Ok(())
+}
+
+/// Upload every file under `dir` to `bucket`, keyed by `prefix` followed by
+/// the file's path relative to `dir`. Returns the uploaded keys, in order.
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be walked or an upload fails.
+pub async fn upload_dir(
+    client: &AdminClient,
+    dir: &Path,
+    bucket: &str,
+    prefix: &str,
+) -> Result<Vec<String>> {
+    let prefix = if prefix.is_empty() || prefix.ends_with('/') {
+        prefix.to_owned()
+    } else {
+        format!("{prefix}/")
+    };
+    let mut keys = Vec::new();
+    for path in walk_files(dir).await? {
+        let relative = path
+            .strip_prefix(dir)
+            .with_context(|| format!("{} is outside {}", path.display(), dir.display()))?;
+        let relative: Vec<_> = relative
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy())
+            .collect();
+        let key = format!("{prefix}{}", relative.join("/"));
+        upload_file(client, &path, bucket, &key).await?;
+        keys.push(key);
+    }
+    Ok(keys)
+}
+
+/// The files under `dir`, recursively, sorted by path.
+async fn walk_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let mut entries = tokio::fs::read_dir(&dir)
+            .await
+            .with_context(|| format!("cannot read directory {}", dir.display()))?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if entry.file_type().await?.is_dir() {
+                pending.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Pass a successful response through, or turn an S3 error response into an
+/// error naming its code.
+fn expect_success(response: Response) -> Result<Response> {
+    if response.status.is_success() {
+        return Ok(response);
+    }
+    let error = records(&response.body, "Error")
+        .ok()
+        .and_then(|mut errors| errors.pop())
+        .unwrap_or_default();
+    match (error.get("Code"), error.get("Message")) {
+        (Some(code), Some(message)) => anyhow::bail!("{code}: {message}"),
+        (Some(code), None) => anyhow::bail!("{code}"),
+        _ => anyhow::bail!("HTTP {}: {}", response.status, response.text()),
+    }
+}
+
+/// The text of the leaf child elements of every `name` element in `xml`,
+/// keyed by child name.
+fn records(xml: &[u8], name: &str) -> Result<Vec<HashMap<String, String>>> {
+    let mut reader = Reader::from_reader(xml);
+    let mut depth = 0usize;
+    let mut record: Option<(usize, HashMap<String, String>)> = None;
+    let mut records = Vec::new();
+    let mut text = String::new();
+    loop {
+        match reader.read_event().context("malformed XML response")? {
+            Event::Start(start) => {
+                depth += 1;
+                text.clear();
+                if record.is_none() && start.local_name().as_ref() == name.as_bytes() {
+                    record = Some((depth, HashMap::new()));
+                }
+            }
+            Event::Text(chunk) => text.push_str(&chunk.decode()?),
+            Event::GeneralRef(reference) => {
+                if let Some(ch) = reference.resolve_char_ref()? {
+                    text.push(ch);
+                } else {
+                    let entity = reference.decode()?;
+                    let resolved = resolve_predefined_entity(&entity)
+                        .with_context(|| format!("unknown XML entity &{entity};"))?;
+                    text.push_str(resolved);
+                }
+            }
+            Event::End(end) => {
+                if let Some((record_depth, fields)) = &mut record {
+                    if depth == *record_depth + 1 {
+                        let field = String::from_utf8_lossy(end.local_name().as_ref()).into_owned();
+                        fields.insert(field, std::mem::take(&mut text));
+                    } else if depth == *record_depth {
+                        records.extend(record.take().map(|(_, fields)| fields));
+                    }
+                }
+                depth = depth.saturating_sub(1);
+                text.clear();
+            }
+            Event::Eof => return Ok(records),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustack_server::ServerBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_should_parse_s3_locations() {
+        assert_eq!(
+            parse_location("s3://bucket/a/b").unwrap(),
+            ("bucket".to_owned(), "a/b".to_owned())
+        );
+        assert_eq!(
+            parse_location("bucket").unwrap(),
+            ("bucket".to_owned(), String::new())
+        );
+        assert!(parse_location("s3://").is_err());
+    }
+
+    #[test]
+    fn test_should_read_records_from_xml() {
+        let xml = br#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult>
+  <IsTruncated>false</IsTruncated>
+  <Contents><Key>a &amp; b</Key><Size>3</Size><Owner><ID>x</ID></Owner></Contents>
+  <Contents><Key>c&#47;d</Key><Size>4</Size></Contents>
+</ListBucketResult>"#;
+
+        let contents = records(xml, "Contents").unwrap();
+        assert_eq!(contents.len(), 2);
+        assert_eq!(contents[0]["Key"], "a & b");
+        assert_eq!(contents[0]["Size"], "3");
+        assert!(!contents[0].contains_key("ID"));
+        assert_eq!(contents[1]["Key"], "c/d");
+        let page = records(xml, "ListBucketResult").unwrap();
+        assert_eq!(page[0]["IsTruncated"], "false");
+    }
+
+    #[tokio::test]
+    async fn test_should_upload_a_directory_and_list_it() {
+        let server = ServerBuilder::new()
+            .services(&["s3"])
+            .start()
+            .await
+            .unwrap();
+        let client = AdminClient::new(&server.endpoint_url()).unwrap();
+        let response = client
+            .send(Method::PUT, "/seed", &[], Bytes::new())
+            .await
+            .unwrap();
+        assert!(response.status.is_success(), "{}", response.text());
+
+        let dir = tempfile::tempdir().unwrap();
+        tokio::fs::create_dir_all(dir.path().join("nested"))
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("a.txt"), "abc")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("nested/b c.json"), "{}")
+            .await
+            .unwrap();
+
+        let keys = upload_dir(&client, dir.path(), "seed", "fixtures")
+            .await
+            .unwrap();
+        assert_eq!(keys, ["fixtures/a.txt", "fixtures/nested/b c.json"]);
+
+        let objects = list_objects(&client, "seed", "fixtures/nested/")
+            .await
+            .unwrap();
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].key, "fixtures/nested/b c.json");
+        assert_eq!(objects[0].size, 2);
+        assert_eq!(
+            list_buckets(&client).await.unwrap(),
+            [BucketSummary {
+                name: "seed".to_owned(),
+                object_count: 2,
+                size: 5,
+            }]
+        );
+
+        let err = list_objects(&client, "missing", "").await.unwrap_err();
+        assert!(format!("{err:#}").contains("NoSuchBucket"), "{err:#}");
+        server.shutdown().await.unwrap();
+    }
+}
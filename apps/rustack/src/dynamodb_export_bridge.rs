@@ -0,0 +1,140 @@
+//! Bridge between DynamoDB table exports and S3.
+//!
+//! Implements the [`ExportWriter`] trait from `rustack-dynamodb-core` by
+//! writing objects through the S3 provider. This bridge lives in the server
+//! binary to avoid a direct dependency from `rustack-dynamodb-core` to
+//! `rustack-s3-core`.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use rustack_core::AccountId;
+use rustack_dynamodb_core::export::{ExportError, ExportWriter};
+use rustack_s3_core::RustackS3;
+use rustack_s3_model::{S3Operation, input::PutObjectInput, request::StreamingBlob};
+
+/// Production export writer that stores objects in RustStack S3.
+#[derive(Debug)]
+pub struct S3ExportWriter {
+    s3: Arc<RustackS3>,
+}
+
+impl S3ExportWriter {
+    /// Create a new writer wrapping the given S3 provider.
+    pub fn new(s3: Arc<RustackS3>) -> Self {
+        Self { s3 }
+    }
+}
+
+#[async_trait]
+impl ExportWriter for S3ExportWriter {
+    async fn put_object(
+        &self,
+        account: &AccountId,
+        bucket: &str,
+        key: &str,
+        body: Bytes,
+    ) -> Result<(), ExportError> {
+        // Write in the namespace that owns the bucket, provided its owner is
+        // the exporting account or its policy lets that account write.
+        let scope = self
+            .s3
+            .bucket_scope(bucket)
+            .ok_or_else(|| ExportError::NoSuchBucket(bucket.to_owned()))?;
+        let s3 = self.s3.scoped(&scope);
+        s3.authorize_account(account.as_str(), S3Operation::PutObject, bucket, key)
+            .map_err(|_| ExportError::AccessDenied(bucket.to_owned()))?;
+        let content_type = match key.rsplit('.').next() {
+            Some("gz") => "application/x-gzip",
+            Some("json") => "application/json",
+            _ => "text/plain",
+        };
+        let input = PutObjectInput {
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+            body: Some(StreamingBlob::new(body)),
+            content_type: Some(content_type.to_owned()),
+            ..PutObjectInput::default()
+        };
+        s3.handle_put_object(input)
+            .await
+            .map(|_| ())
+            .map_err(|e| ExportError::WriteFailed {
+                key: key.to_owned(),
+                reason: e.to_string(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustack_core::RequestScope;
+    use rustack_s3_core::S3Config;
+    use rustack_s3_model::input::CreateBucketInput;
+
+    use super::*;
+
+    const OWNER: &str = "111111111111";
+    const EXPORTER: &str = "222222222222";
+    const EXPORTER_POLICY: &str = r#"{
+        "Version": "2012-10-17",
+        "Statement": {
+            "Effect": "Allow",
+            "Principal": {"AWS": "arn:aws:iam::222222222222:root"},
+            "Action": "s3:PutObject",
+            "Resource": "arn:aws:s3:::exports/*"
+        }
+    }"#;
+
+    async fn writer_with_bucket() -> S3ExportWriter {
+        let s3 = Arc::new(RustackS3::new(S3Config::default()));
+        s3.scoped(&RequestScope::resolve(Some(OWNER), None, "us-east-1"))
+            .handle_create_bucket(CreateBucketInput {
+                bucket: "exports".to_owned(),
+                ..CreateBucketInput::default()
+            })
+            .await
+            .unwrap_or_else(|e| panic!("create failed: {e}"));
+        S3ExportWriter::new(s3)
+    }
+
+    async fn put(writer: &S3ExportWriter, account: &str) -> Result<(), ExportError> {
+        let account = AccountId::new(account).unwrap_or_else(|e| panic!("bad account: {e}"));
+        writer
+            .put_object(&account, "exports", "AWSDynamoDB/x/_started", Bytes::new())
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_should_reject_cross_account_export_unless_policy_allows() {
+        let writer = writer_with_bucket().await;
+        put(&writer, OWNER)
+            .await
+            .unwrap_or_else(|e| panic!("owner write failed: {e}"));
+
+        let err = put(&writer, EXPORTER).await.err();
+        assert!(matches!(err, Some(ExportError::AccessDenied(ref b)) if b == "exports"));
+
+        let owner = writer
+            .s3
+            .scoped(&RequestScope::resolve(Some(OWNER), None, "us-east-1"));
+        *owner
+            .state()
+            .get_bucket("exports")
+            .unwrap_or_else(|e| panic!("get failed: {e}"))
+            .policy
+            .write() = Some(EXPORTER_POLICY.to_owned());
+        put(&writer, EXPORTER)
+            .await
+            .unwrap_or_else(|e| panic!("allowed write failed: {e}"));
+        assert_eq!(
+            owner
+                .state()
+                .get_bucket("exports")
+                .map(|b| b.objects.read().len())
+                .ok(),
+            Some(1)
+        );
+    }
+}
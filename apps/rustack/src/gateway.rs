@@ -4,15 +4,77 @@
 //! dispatches each request to the first router whose [`matches`](ServiceRouter::matches)
 //! method returns `true`. If no router matches, a 404 response is returned.
 //!
-//! Health-check endpoints (`/_localstack/health`, `/_health`, `/health`) are
-//! intercepted at the gateway level and return a combined status for all
-//! registered services.
+//! Health-check endpoints (`/_localstack/health`, `/_health`, `/health`) and
+//! the readiness endpoint (`/_localstack/init/ready`) are intercepted at the
+//! gateway level and rendered per request from the gateway's [`HealthState`].
+//! When metrics are enabled, `GET /metrics` is likewise intercepted before
+//! any service router sees it, as are the state reset endpoint
+//! (`POST /_localstack/state/reset`), the S3 integrity scrub endpoint
+//! (`POST /_localstack/s3/scrub/<bucket>`), and the server information
+//! endpoint (`GET /_localstack/info`), as are the runtime rule endpoints
+//! such as `/_ruststack/s3/latency` and `/_ruststack/s3/faults` (see
+//! [`crate::rules`]) and the runtime settings endpoints such as
+//! `/_ruststack/dynamodb/config` (see [`crate::settings`]).
+//!
+//! # Namespaces
+//!
+//! With [`Namespaces`] configured, a request's `x-ruststack-namespace`
+//! header picks the stack of services it is routed among before any of the
+//! steps below, and the gateway serves the namespace management endpoints
+//! under `/_ruststack/namespaces`. See [`crate::namespace`].
+//!
+//! # Management paths
+//!
+//! Every other `/_localstack/` path is answered by the gateway with a JSON
+//! `404`. `/_aws/` paths belong to the services that serve them (SES,
+//! API Gateway execution, CloudFront); one that no service claims also gets
+//! a JSON `404` rather than falling through to the S3 catch-all as a bucket
+//! named `_aws`.
+//!
+//! # Routing precedence
+//!
+//! Each request goes to the first of these that picks a router:
+//!
+//! 1. **Host label.** The service label of the `Host` header, such as `dynamodb` in
+//!    `dynamodb.localhost.localstack.cloud:4566`. A host with an `s3` label after the first
+//!    (`<bucket>.s3.…`) is an S3 virtual-hosted bucket, whatever the bucket is called.
+//! 2. **Credential scope.** The service in the SigV4 credential scope, from the `Authorization`
+//!    header or a presigned URL's `X-Amz-Credential` query parameter.
+//! 3. **Heuristics.** The first router whose [`matches`](ServiceRouter::matches) accepts the
+//!    request, in registration order.
+//!
+//! A name from steps 1 and 2 selects the routers that claim it in
+//! [`ServiceRouter::endpoint_names`]. The first of those whose heuristics
+//! accept the request handles it, or else the first claimant. Names no
+//! router claims, such as a bucket in a virtual-hosted host, fall through to
+//! the next step. Every routing decision is logged at `debug` level with the
+//! chosen router and the reason.
+//!
+//! When the gateway is bound to a connection with [`GatewayService::with_peer_addr`],
+//! the client's [`SocketAddr`] is inserted into each request's extensions so services
+//! can evaluate source-IP conditions.
+//!
+//! A connection accepted over the gateway's connection limit is served by an
+//! [overloaded](GatewayService::overloaded) gateway, which answers everything
+//! but the health checks with `503 SlowDown`.
 
-use std::{convert::Infallible, future::Future, pin::Pin, sync::Arc};
+use std::{
+    convert::Infallible, future::Future, net::SocketAddr, pin::Pin, sync::Arc, time::Instant,
+};
 
 use hyper::{body::Incoming, service::Service};
+use tracing::debug;
 
-use crate::service::{GatewayBody, ServiceRouter, gateway_body_from_string};
+use crate::{
+    health::{HealthState, INFO_PATH, READY_PATH},
+    metrics::{GatewayMetrics, METRICS_PATH, declared_payload_len},
+    namespace::Namespaces,
+    reset::{RESET_PATH, StateReset},
+    rules::{self, RuleEndpoints},
+    scrub::BucketScrub,
+    service::{GatewayBody, ServiceRouter, gateway_body_from_string},
+    settings::{self, SettingsEndpoints},
+};
 
 /// Gateway that routes incoming HTTP requests to registered service routers.
 ///
@@ -21,16 +83,98 @@ use crate::service::{GatewayBody, ServiceRouter, gateway_body_from_string};
 /// specific services (DynamoDB, etc.) before catch-all services (S3).
 pub struct GatewayService {
     services: Arc<Vec<Box<dyn ServiceRouter>>>,
+    health: Arc<HealthState>,
+    metrics: Option<Arc<GatewayMetrics>>,
+    resets: Arc<StateReset>,
+    scrub: Arc<BucketScrub>,
+    rules: Arc<RuleEndpoints>,
+    settings: Arc<SettingsEndpoints>,
+    namespaces: Arc<Namespaces>,
+    peer_addr: Option<SocketAddr>,
+    overloaded: bool,
 }
 
 impl GatewayService {
-    /// Create a new gateway from a list of service routers.
+    /// Create a new gateway from a list of service routers, all reported as
+    /// starting until marked running in [`health`](Self::health).
     pub fn new(services: Vec<Box<dyn ServiceRouter>>) -> Self {
+        let health = HealthState::new(services.iter().map(|s| s.name()));
         Self {
             services: Arc::new(services),
+            health: Arc::new(health),
+            metrics: None,
+            resets: Arc::new(StateReset::default()),
+            scrub: Arc::new(BucketScrub::default()),
+            rules: Arc::new(RuleEndpoints::default()),
+            settings: Arc::new(SettingsEndpoints::default()),
+            namespaces: Arc::new(Namespaces::default()),
+            peer_addr: None,
+            overloaded: false,
         }
     }
 
+    /// Record per-request metrics and serve them on `GET /metrics`.
+    #[must_use]
+    pub fn with_metrics(mut self, metrics: Arc<GatewayMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Reset services' state on `POST /_localstack/state/reset`.
+    #[must_use]
+    pub fn with_state_reset(mut self, resets: StateReset) -> Self {
+        self.resets = Arc::new(resets);
+        self
+    }
+
+    /// Scrub S3 buckets on `POST /_localstack/s3/scrub/<bucket>`.
+    #[must_use]
+    pub fn with_bucket_scrub(mut self, scrub: BucketScrub) -> Self {
+        self.scrub = Arc::new(scrub);
+        self
+    }
+
+    /// Serve runtime-managed rules on their management paths.
+    #[must_use]
+    pub fn with_rule_endpoints(mut self, rules: RuleEndpoints) -> Self {
+        self.rules = Arc::new(rules);
+        self
+    }
+
+    /// Serve runtime-adjustable settings on their management paths.
+    #[must_use]
+    pub fn with_settings_endpoints(mut self, settings: SettingsEndpoints) -> Self {
+        self.settings = Arc::new(settings);
+        self
+    }
+
+    /// Route requests naming a namespace to that namespace's services.
+    #[must_use]
+    pub fn with_namespaces(mut self, namespaces: Namespaces) -> Self {
+        self.namespaces = Arc::new(namespaces);
+        self
+    }
+
+    /// Tag every request served by this instance with the client address.
+    #[must_use]
+    pub fn with_peer_addr(mut self, peer_addr: SocketAddr) -> Self {
+        self.peer_addr = Some(peer_addr);
+        self
+    }
+
+    /// Answer every request except health checks with `503 SlowDown`, for
+    /// connections accepted over the connection limit.
+    #[must_use]
+    pub fn overloaded(mut self) -> Self {
+        self.overloaded = true;
+        self
+    }
+
+    /// The health state rendered by the health and readiness endpoints.
+    pub fn health(&self) -> &Arc<HealthState> {
+        &self.health
+    }
+
     /// Return the names of all registered services.
     pub fn service_names(&self) -> Vec<&'static str> {
         self.services.iter().map(|s| s.name()).collect()
@@ -41,6 +185,15 @@ impl Clone for GatewayService {
     fn clone(&self) -> Self {
         Self {
             services: Arc::clone(&self.services),
+            health: Arc::clone(&self.health),
+            metrics: self.metrics.clone(),
+            resets: Arc::clone(&self.resets),
+            scrub: Arc::clone(&self.scrub),
+            rules: Arc::clone(&self.rules),
+            settings: Arc::clone(&self.settings),
+            namespaces: Arc::clone(&self.namespaces),
+            peer_addr: self.peer_addr,
+            overloaded: self.overloaded,
         }
     }
 }
@@ -50,24 +203,122 @@ impl Service<http::Request<Incoming>> for GatewayService {
     type Error = Infallible;
     type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
 
-    fn call(&self, req: http::Request<Incoming>) -> Self::Future {
+    #[allow(clippy::too_many_lines)]
+    fn call(&self, mut req: http::Request<Incoming>) -> Self::Future {
+        if let Some(peer_addr) = self.peer_addr {
+            req.extensions_mut().insert(peer_addr);
+        }
+
         // Intercept health checks at the gateway level.
         if is_health_check(req.method(), req.uri().path()) {
-            let services = Arc::clone(&self.services);
+            let resp = health_check_response(&self.health);
+            return Box::pin(async move { Ok(resp) });
+        }
+        if req.method() == http::Method::GET && req.uri().path() == READY_PATH {
+            let resp = readiness_response(&self.health);
+            return Box::pin(async move { Ok(resp) });
+        }
+        if self.overloaded {
+            return Box::pin(async { Ok(slow_down_response()) });
+        }
+
+        if req.method() == http::Method::POST && req.uri().path() == RESET_PATH {
+            let body = self.resets.reset_all();
+            return Box::pin(async move { Ok(json_response(body)) });
+        }
+        if let Some((status, body)) = self.namespaces.management(req.method(), req.uri().path()) {
+            let mut resp = json_response(body);
+            *resp.status_mut() = status;
+            return Box::pin(async move { Ok(resp) });
+        }
+        if let Some(rule_set) = self.rules.find(req.uri().path()) {
+            return Box::pin(async move {
+                let (status, body) = rules::handle(rule_set.as_ref(), req).await;
+                let mut resp = json_response(body);
+                *resp.status_mut() = status;
+                Ok(resp)
+            });
+        }
+        if let Some(settings) = self.settings.find(req.uri().path()) {
+            return Box::pin(async move {
+                let (status, body) = settings::handle(settings.as_ref(), req).await;
+                let mut resp = json_response(body);
+                *resp.status_mut() = status;
+                Ok(resp)
+            });
+        }
+        if let Some(bucket) = self.scrub.bucket(req.method(), req.uri().path()) {
+            let scrub = self.scrub.scrub(bucket);
             return Box::pin(async move {
-                let names: Vec<&str> = services.iter().map(|s| s.name()).collect();
-                Ok(health_check_response(&names))
+                let (status, body) = scrub.await;
+                let mut resp = json_response(body);
+                *resp.status_mut() = status;
+                Ok(resp)
             });
         }
 
-        // Route to the first matching service.
-        for svc in self.services.iter() {
-            if svc.matches(&req) {
-                return svc.call(req);
+        if let Some(metrics) = &self.metrics {
+            if req.method() == http::Method::GET && req.uri().path() == METRICS_PATH {
+                let body = metrics.render();
+                return Box::pin(async move { Ok(metrics_response(body)) });
             }
         }
 
+        if req.method() == http::Method::GET && req.uri().path() == INFO_PATH {
+            let body = self.health.info_json();
+            return Box::pin(async move { Ok(json_response(body)) });
+        }
+        if is_localstack_path(req.uri().path()) {
+            let resp = management_not_found_response(req.uri().path());
+            return Box::pin(async move { Ok(resp) });
+        }
+
+        let services = match self.namespaces.resolve(req.headers()) {
+            Ok(Some(services)) => services,
+            Ok(None) => Arc::clone(&self.services),
+            Err(invalid) => {
+                let mut resp = json_response(invalid.body());
+                *resp.status_mut() = http::StatusCode::BAD_REQUEST;
+                return Box::pin(async move { Ok(resp) });
+            }
+        };
+        let route = select_route(&services, &req).filter(|&(idx, reason)| {
+            reason != RouteReason::Heuristics
+                || !services[idx].is_catch_all()
+                || !is_aws_path(req.uri().path())
+        });
+        if let Some((idx, _)) = route {
+            let Some(metrics) = self.metrics.clone() else {
+                return services[idx].call(req);
+            };
+            let payload_len = declared_payload_len(req.headers());
+            let started = Instant::now();
+            let fut = services[idx].call(req);
+            return Box::pin(async move {
+                let resp = fut.await?;
+                let svc = &services[idx];
+                metrics.record_request(
+                    svc.name(),
+                    svc.operation_name(&resp).unwrap_or("unknown"),
+                    resp.status(),
+                    started.elapsed(),
+                    payload_len,
+                );
+                Ok(resp)
+            });
+        }
+
+        if is_aws_path(req.uri().path()) {
+            let resp = management_not_found_response(req.uri().path());
+            return Box::pin(async move { Ok(resp) });
+        }
+
         // No service matched — return a 404.
+        debug!(
+            method = %req.method(),
+            uri = %req.uri(),
+            "no service matched the request"
+        );
         Box::pin(async {
             Ok(http::Response::builder()
                 .status(http::StatusCode::NOT_FOUND)
@@ -80,6 +331,122 @@ impl Service<http::Request<Incoming>> for GatewayService {
     }
 }
 
+/// Why the gateway routed a request to a service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RouteReason {
+    /// The `Host` header's service label.
+    HostLabel,
+    /// The service in the SigV4 credential scope.
+    CredentialScope,
+    /// The router's own [`ServiceRouter::matches`] heuristics.
+    Heuristics,
+}
+
+impl RouteReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::HostLabel => "host label",
+            Self::CredentialScope => "credential scope",
+            Self::Heuristics => "heuristics",
+        }
+    }
+}
+
+/// Pick the router for `req`, logging the choice. See the module docs for
+/// the precedence.
+fn select_route(
+    services: &[Box<dyn ServiceRouter>],
+    req: &http::Request<Incoming>,
+) -> Option<(usize, RouteReason)> {
+    let host_label = host_service_label(req.uri(), req.headers());
+    let scope = credential_scope_service(req.uri(), req.headers());
+    let claims: Vec<_> = services.iter().map(|svc| svc.endpoint_names()).collect();
+    let (idx, reason) = choose_route(&claims, host_label, scope.as_deref(), |i| {
+        services[i].matches(req)
+    })?;
+    debug!(
+        service = services[idx].name(),
+        reason = reason.as_str(),
+        host_label,
+        credential_scope = scope.as_deref(),
+        method = %req.method(),
+        path = req.uri().path(),
+        "routed request"
+    );
+    Some((idx, reason))
+}
+
+/// Apply the routing precedence to routers described by the names they
+/// claim, using `matches` for their heuristics.
+fn choose_route(
+    claims: &[&[&str]],
+    host_label: Option<&str>,
+    scope: Option<&str>,
+    matches: impl Fn(usize) -> bool,
+) -> Option<(usize, RouteReason)> {
+    let signals = [
+        (host_label, RouteReason::HostLabel),
+        (scope, RouteReason::CredentialScope),
+    ];
+    for (name, reason) in signals {
+        let Some(name) = name else { continue };
+        let claimants: Vec<usize> = (0..claims.len())
+            .filter(|&i| claims[i].iter().any(|c| c.eq_ignore_ascii_case(name)))
+            .collect();
+        if let Some(&first) = claimants.first() {
+            let idx = claimants
+                .iter()
+                .copied()
+                .find(|&i| matches(i))
+                .unwrap_or(first);
+            return Some((idx, reason));
+        }
+    }
+    (0..claims.len())
+        .find(|&i| matches(i))
+        .map(|idx| (idx, RouteReason::Heuristics))
+}
+
+/// The service label of the request's host: `s3` for S3 virtual-hosted
+/// buckets (`<bucket>.s3.…`), otherwise the leftmost label.
+fn host_service_label<'a>(uri: &'a http::Uri, headers: &'a http::HeaderMap) -> Option<&'a str> {
+    let host = if let Some(host) = uri.host() {
+        host
+    } else {
+        let host = headers.get(http::header::HOST)?.to_str().ok()?;
+        host.rsplit_once(':')
+            .filter(|(_, port)| port.bytes().all(|b| b.is_ascii_digit()))
+            .map_or(host, |(name, _)| name)
+    };
+    let mut labels = host.split('.');
+    let first = labels.next()?;
+    if labels.any(|label| label.eq_ignore_ascii_case("s3")) {
+        return Some("s3");
+    }
+    Some(first)
+}
+
+/// The service in the request's SigV4 credential scope
+/// (`AKID/date/region/service/aws4_request`), taken from the
+/// `Authorization` header or the `X-Amz-Credential` query parameter.
+fn credential_scope_service(uri: &http::Uri, headers: &http::HeaderMap) -> Option<String> {
+    let credential = if let Some(auth) = headers
+        .get(http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+    {
+        let start = auth.find("Credential=")? + "Credential=".len();
+        let rest = &auth[start..];
+        rest[..rest.find(',').unwrap_or(rest.len())].to_owned()
+    } else {
+        let value = uri
+            .query()?
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("X-Amz-Credential="))?;
+        value.replace("%2F", "/").replace("%2f", "/")
+    };
+    credential.split('/').nth(3).map(str::to_owned)
+}
+
 /// Check if the request is a health check probe.
 fn is_health_check(method: &http::Method, path: &str) -> bool {
     *method == http::Method::GET
@@ -91,19 +458,85 @@ fn is_health_check(method: &http::Method, path: &str) -> bool {
             || path == "/minio/health/cluster")
 }
 
+/// Whether `path` is under the gateway's own `/_localstack/` prefix.
+fn is_localstack_path(path: &str) -> bool {
+    path == "/_localstack" || path.starts_with("/_localstack/")
+}
+
+/// Whether `path` is under the `/_aws/` prefix of service-specific
+/// management endpoints.
+fn is_aws_path(path: &str) -> bool {
+    path == "/_aws" || path.starts_with("/_aws/")
+}
+
+/// A JSON `404` for a management path nothing serves.
+fn management_not_found_response(path: &str) -> http::Response<GatewayBody> {
+    debug!(path, "unknown management endpoint");
+    http::Response::builder()
+        .status(http::StatusCode::NOT_FOUND)
+        .header("Content-Type", "application/json")
+        .body(gateway_body_from_string(
+            serde_json::json!({ "error": "not found", "path": path }).to_string(),
+        ))
+        .expect("static 404 response should be valid")
+}
+
 /// Produce a health check response listing all registered services.
-fn health_check_response(service_names: &[&str]) -> http::Response<GatewayBody> {
-    let entries: Vec<String> = service_names
-        .iter()
-        .map(|name| format!(r#""{name}":"running""#))
-        .collect();
-    let body = format!(r#"{{"services":{{{}}}}}"#, entries.join(","));
+///
+/// Liveness only: the status is `200` even while services are starting.
+fn health_check_response(health: &HealthState) -> http::Response<GatewayBody> {
+    http::Response::builder()
+        .status(http::StatusCode::OK)
+        .header("Content-Type", "application/json")
+        .body(gateway_body_from_string(health.health_json()))
+        .expect("static health response should be valid")
+}
+
+/// Produce a readiness response: `200` once every service is running, `503`
+/// before.
+fn readiness_response(health: &HealthState) -> http::Response<GatewayBody> {
+    let status = if health.is_ready() {
+        http::StatusCode::OK
+    } else {
+        http::StatusCode::SERVICE_UNAVAILABLE
+    };
+    http::Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(gateway_body_from_string(health.ready_json()))
+        .expect("static readiness response should be valid")
+}
 
+/// The S3 `SlowDown` error, returned on connections over the connection
+/// limit. AWS SDKs retry it with backoff.
+fn slow_down_response() -> http::Response<GatewayBody> {
+    http::Response::builder()
+        .status(http::StatusCode::SERVICE_UNAVAILABLE)
+        .header("Content-Type", "application/xml")
+        .body(gateway_body_from_string(
+            "<?xml version=\"1.0\" \
+             encoding=\"UTF-8\"?>\n<Error><Code>SlowDown</Code><Message>Please reduce your \
+             request rate.</Message></Error>",
+        ))
+        .expect("static SlowDown response should be valid")
+}
+
+/// Wrap a rendered JSON body in a `200` response.
+fn json_response(body: String) -> http::Response<GatewayBody> {
     http::Response::builder()
         .status(http::StatusCode::OK)
         .header("Content-Type", "application/json")
         .body(gateway_body_from_string(body))
-        .expect("static health response should be valid")
+        .expect("static JSON response should be valid")
+}
+
+/// Wrap rendered metrics in a Prometheus text-format response.
+fn metrics_response(body: String) -> http::Response<GatewayBody> {
+    http::Response::builder()
+        .status(http::StatusCode::OK)
+        .header("Content-Type", "text/plain; version=0.0.4; charset=utf-8")
+        .body(gateway_body_from_string(body))
+        .expect("static metrics response should be valid")
 }
 
 #[cfg(test)]
@@ -121,8 +554,8 @@ mod tests {
 
     #[test]
     fn test_should_produce_health_check_response_with_both_services() {
-        let names = vec!["s3", "dynamodb"];
-        let resp = health_check_response(&names);
+        let health = HealthState::new(["s3", "dynamodb"]);
+        let resp = health_check_response(&health);
         assert_eq!(resp.status(), http::StatusCode::OK);
         assert_eq!(
             resp.headers()
@@ -134,15 +567,205 @@ mod tests {
 
     #[test]
     fn test_should_produce_health_check_response_with_single_service() {
-        let names = vec!["dynamodb"];
-        let resp = health_check_response(&names);
+        let health = HealthState::new(["dynamodb"]);
+        let resp = health_check_response(&health);
         assert_eq!(resp.status(), http::StatusCode::OK);
     }
 
+    #[test]
+    fn test_should_report_readiness_only_when_all_services_run() {
+        let health = HealthState::new(["s3", "dynamodb"]);
+        assert_eq!(
+            readiness_response(&health).status(),
+            http::StatusCode::SERVICE_UNAVAILABLE
+        );
+        // Liveness holds while services are still starting.
+        assert_eq!(
+            health_check_response(&health).status(),
+            http::StatusCode::OK
+        );
+        health.mark_running("s3");
+        health.mark_running("dynamodb");
+        assert_eq!(readiness_response(&health).status(), http::StatusCode::OK);
+    }
+
+    #[test]
+    fn test_should_accept_health_reload_query() {
+        let uri: http::Uri = "/_localstack/health?reload".parse().unwrap();
+        assert!(is_health_check(&http::Method::GET, uri.path()));
+    }
+
+    #[test]
+    fn test_should_detect_management_paths() {
+        assert!(is_localstack_path("/_localstack"));
+        assert!(is_localstack_path("/_localstack/diagnose"));
+        assert!(!is_localstack_path("/_localstackbucket/key"));
+        assert!(is_aws_path("/_aws/sqs/messages"));
+        assert!(!is_aws_path("/_awsome/key"));
+
+        let resp = management_not_found_response("/_localstack/diagnose");
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+        assert_eq!(
+            resp.headers()
+                .get("Content-Type")
+                .and_then(|v| v.to_str().ok()),
+            Some("application/json"),
+        );
+    }
+
+    #[test]
+    fn test_should_produce_prometheus_metrics_response() {
+        let resp = metrics_response("rustack_s3_buckets 0\n".to_owned());
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert!(
+            resp.headers()
+                .get("Content-Type")
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| v.starts_with("text/plain"))
+        );
+    }
+
+    const S3: &[&str] = &["s3"];
+    const DYNAMODB: &[&str] = &["dynamodb"];
+    const STREAMS: &[&str] = &["streams", "dynamodb"];
+    const SQS: &[&str] = &["sqs"];
+
+    /// DynamoDB, Streams, SQS, then S3 as the catch-all.
+    const CLAIMS: &[&[&str]] = &[DYNAMODB, STREAMS, SQS, S3];
+
+    /// Route with heuristics that accept only the routers in `matching`.
+    fn route(
+        uri: &str,
+        headers: &[(&str, &str)],
+        matching: &[usize],
+    ) -> Option<(usize, RouteReason)> {
+        let uri: http::Uri = uri.parse().unwrap();
+        let mut map = http::HeaderMap::new();
+        for (name, value) in headers {
+            map.insert(
+                http::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                http::HeaderValue::from_str(value).unwrap(),
+            );
+        }
+        let host_label = host_service_label(&uri, &map);
+        let scope = credential_scope_service(&uri, &map);
+        choose_route(CLAIMS, host_label, scope.as_deref(), |i| {
+            matching.contains(&i)
+        })
+    }
+
+    fn auth(service: &str) -> String {
+        format!(
+            "AWS4-HMAC-SHA256 Credential=AKID/20240101/us-east-1/{service}/aws4_request, \
+             SignedHeaders=host, Signature=abc"
+        )
+    }
+
+    #[test]
+    fn test_should_route_s3_presigned_url_under_dynamodb_named_bucket() {
+        // Presigned GET for bucket "dynamodb": no Authorization header, and
+        // the leftmost host label is a service name.
+        let uri = "/photo.jpg?X-Amz-Algorithm=AWS4-HMAC-SHA256&X-Amz-Credential=AKID%2F20240101%\
+                   2Fus-east-1%2Fs3%2Faws4_request&X-Amz-Signature=abc";
+        assert_eq!(
+            route(
+                uri,
+                &[("host", "dynamodb.s3.localhost.localstack.cloud:4566")],
+                &[3],
+            ),
+            Some((3, RouteReason::HostLabel))
+        );
+    }
+
+    #[test]
+    fn test_should_prefer_host_label_over_credential_scope() {
+        let scope = auth("sqs");
+        assert_eq!(
+            route(
+                "/",
+                &[
+                    ("host", "dynamodb.localhost.localstack.cloud:4566"),
+                    ("authorization", &scope),
+                ],
+                &[2, 3],
+            ),
+            Some((0, RouteReason::HostLabel))
+        );
+    }
+
+    #[test]
+    fn test_should_route_by_credential_scope_when_host_names_no_service() {
+        let scope = auth("sqs");
+        assert_eq!(
+            route(
+                "/",
+                &[("host", "localhost:4566"), ("authorization", &scope)],
+                &[3]
+            ),
+            Some((2, RouteReason::CredentialScope))
+        );
+        // A bucket label is not a service, so the scope decides.
+        let scope = auth("s3");
+        assert_eq!(
+            route(
+                "/key",
+                &[
+                    ("host", "mybucket.localhost:4566"),
+                    ("authorization", &scope)
+                ],
+                &[3],
+            ),
+            Some((3, RouteReason::CredentialScope))
+        );
+    }
+
+    #[test]
+    fn test_should_split_shared_scope_by_heuristics() {
+        let scope = auth("dynamodb");
+        let headers = [
+            ("host", "localhost:4566"),
+            ("authorization", scope.as_str()),
+        ];
+        // A Streams request matches only the Streams router.
+        assert_eq!(
+            route("/", &headers, &[1, 3]),
+            Some((1, RouteReason::CredentialScope))
+        );
+        // Neither claimant matches: the first claimant wins over S3.
+        assert_eq!(
+            route("/", &headers, &[3]),
+            Some((0, RouteReason::CredentialScope))
+        );
+        assert_eq!(
+            route(
+                "/",
+                &[("host", "streams.dynamodb.us-east-1.amazonaws.com")],
+                &[3]
+            ),
+            Some((1, RouteReason::HostLabel))
+        );
+    }
+
+    #[test]
+    fn test_should_fall_back_to_heuristics_without_signals() {
+        assert_eq!(
+            route("/", &[("host", "127.0.0.1:4566")], &[2, 3]),
+            Some((2, RouteReason::Heuristics))
+        );
+        assert_eq!(
+            route(
+                "http://localhost:4566/",
+                &[("authorization", &auth("execute-api"))],
+                &[]
+            ),
+            None
+        );
+    }
+
     #[test]
     fn test_should_produce_health_check_response_with_no_services() {
-        let names: Vec<&str> = vec![];
-        let resp = health_check_response(&names);
+        let health = HealthState::new([]);
+        let resp = health_check_response(&health);
         assert_eq!(resp.status(), http::StatusCode::OK);
     }
 }
@@ -4,26 +4,56 @@
 //! (`rustack-s3-core`) by implementing the [`S3Handler`] trait. Each S3 operation is
 //! dispatched to the corresponding `handle_*` method on [`RustackS3`], with request
 //! deserialization via [`FromS3Request`] and response serialization via [`IntoS3Response`].
+//!
+//! Operations run against the provider scoped to the request's [`RequestScope`],
+//! so each (account, region) namespace sees only its own buckets. A signed
+//! request addressing a bucket in another namespace is redirected to the
+//! bucket's region, or denied if another account owns it.
 
-use std::{collections::HashMap, future::Future, pin::Pin};
+use std::{future::Future, net::SocketAddr, pin::Pin};
 
 use bytes::Bytes;
-use rustack_s3_core::RustackS3;
+use rustack_core::{AccessLogEntry, RequestScope};
+use rustack_s3_core::{RustackS3, policy::AccessRequest};
 use rustack_s3_http::{
-    body::S3ResponseBody, dispatch::S3Handler, multipart, request::FromS3Request,
-    response::IntoS3Response, router::RoutingContext,
+    body::S3ResponseBody,
+    cors::{CorsOutcome, CorsRequest},
+    dispatch::S3Handler,
+    post_object::PostObjectForm,
+    request::FromS3Request,
+    response::IntoS3Response,
+    router::{AccessPointRef, RoutingContext},
 };
 use rustack_s3_model::{
     S3Operation,
     error::{S3Error, S3ErrorCode},
-    input::PutObjectInput,
-    request::StreamingBlob,
 };
 
 /// Wrapper that implements [`S3Handler`] by delegating to [`RustackS3`] handler methods.
 #[derive(Debug, Clone)]
 pub struct RustackHandler(pub RustackS3);
 
+impl RustackHandler {
+    /// The provider a request operates on.
+    ///
+    /// Signed requests use the namespace resolved from their credentials.
+    /// Unsigned requests carry no namespace of their own, so a request
+    /// addressing an existing bucket runs in the namespace that owns it.
+    fn provider_for(&self, parts: &http::request::Parts, bucket: Option<&str>) -> RustackS3 {
+        let Some(scope) = parts.extensions.get::<RequestScope>() else {
+            return self.0.clone();
+        };
+        let anonymous = rustack_auth::requester_access_key(&parts.headers, &parts.uri).is_none();
+        match bucket
+            .filter(|_| anonymous)
+            .and_then(|name| self.0.bucket_scope(name))
+        {
+            Some(owner) => self.0.scoped(&owner),
+            None => self.0.scoped(scope),
+        }
+    }
+}
+
 impl S3Handler for RustackHandler {
     // This function dispatches all S3 operations via a match expression. Each arm
     // is a single-line delegation, so the overall line count is proportional to
@@ -36,8 +66,13 @@ impl S3Handler for RustackHandler {
         body: Bytes,
         ctx: RoutingContext,
     ) -> Pin<Box<dyn Future<Output = Result<http::Response<S3ResponseBody>, S3Error>> + Send>> {
-        let provider = self.0.clone();
+        let provider = self.provider_for(&parts, ctx.bucket.as_deref());
+        let in_scope = match ctx.bucket.as_deref() {
+            Some(bucket) if op != S3Operation::CreateBucket => provider.check_bucket_scope(bucket),
+            _ => Ok(()),
+        };
         Box::pin(async move {
+            in_scope?;
             let bucket = ctx.bucket.as_deref();
             let key = ctx.key.as_deref();
             let query_params = &ctx.query_params;
@@ -325,14 +360,16 @@ impl S3Handler for RustackHandler {
                     .await
                 }
                 S3Operation::GetObject => {
+                    let if_range = if_range_header(&parts);
                     dispatch_output(&parts, bucket, key, query_params, body, |input| {
-                        provider.handle_get_object(input)
+                        provider.handle_get_object_with_if_range(input, if_range)
                     })
                     .await
                 }
                 S3Operation::HeadObject => {
+                    let if_range = if_range_header(&parts);
                     dispatch_output(&parts, bucket, key, query_params, body, |input| {
-                        provider.handle_head_object(input)
+                        provider.handle_head_object_with_if_range(input, if_range)
                     })
                     .await
                 }
@@ -489,9 +526,88 @@ impl S3Handler for RustackHandler {
                     })
                     .await
                 }
+
+                // ---------------------------------------------------------------
+                // S3 Control Access Points
+                // ---------------------------------------------------------------
+                S3Operation::CreateAccessPoint => {
+                    dispatch_output(&parts, bucket, key, query_params, body, |input| {
+                        provider.handle_create_access_point(input)
+                    })
+                    .await
+                }
+                S3Operation::GetAccessPoint => {
+                    dispatch_output(&parts, bucket, key, query_params, body, |input| {
+                        provider.handle_get_access_point(input)
+                    })
+                    .await
+                }
+                S3Operation::DeleteAccessPoint => {
+                    dispatch_void(&parts, bucket, key, query_params, body, |input| {
+                        provider.handle_delete_access_point(input)
+                    })
+                    .await
+                }
+                S3Operation::ListAccessPoints => {
+                    dispatch_output(&parts, bucket, key, query_params, body, |input| {
+                        provider.handle_list_access_points(input)
+                    })
+                    .await
+                }
             }
         })
     }
+
+    fn resolve_access_point(
+        &self,
+        access_point: &AccessPointRef,
+        parts: &http::request::Parts,
+    ) -> Result<String, S3Error> {
+        self.provider_for(parts, None)
+            .access_point_bucket(&access_point.account, &access_point.name)
+    }
+
+    fn authorize(
+        &self,
+        ctx: &RoutingContext,
+        parts: &http::request::Parts,
+        principal: Option<&str>,
+    ) -> Result<(), S3Error> {
+        let provider = self.provider_for(parts, ctx.bucket.as_deref());
+        provider.authorize(&AccessRequest {
+            operation: ctx.operation,
+            bucket: ctx.bucket.as_deref(),
+            key: ctx.key.as_deref(),
+            principal,
+            headers: &parts.headers,
+            query_params: &ctx.query_params,
+            source_ip: parts.extensions.get::<SocketAddr>().map(SocketAddr::ip),
+        })
+    }
+
+    fn evaluate_cors(&self, request: &CorsRequest<'_>) -> CorsOutcome {
+        let index = self.0.cors_index();
+        if !index.has_rules(request.bucket) {
+            return CorsOutcome::NotConfigured;
+        }
+        let matched = match request.preflight_headers {
+            Some(headers) => {
+                index.match_preflight(request.bucket, request.origin, request.method, headers)
+            }
+            None => index.match_cors(request.bucket, request.origin, request.method),
+        };
+        matched.map_or(CorsOutcome::Denied, |m| {
+            CorsOutcome::Allowed(m.to_headers(request.is_preflight()))
+        })
+    }
+
+    fn bucket_region(&self, bucket: &str) -> Option<String> {
+        self.0.bucket_region(bucket)
+    }
+
+    fn record_access(&self, entry: &AccessLogEntry) {
+        self.0.record_access_log(entry);
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -522,6 +638,15 @@ where
     output.into_s3_response()
 }
 
+/// The `If-Range` request header, which the generated object inputs lack.
+fn if_range_header(parts: &http::request::Parts) -> Option<String> {
+    parts
+        .headers
+        .get(http::header::IF_RANGE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_owned)
+}
+
 /// Dispatch an operation that returns `Result<(), S3Error>` (void result).
 ///
 /// Returns a 204 No Content response on success.
@@ -549,7 +674,8 @@ where
 /// Dispatch an S3 POST Object (browser-based / presigned POST upload).
 ///
 /// This is a special case that doesn't use `FromS3Request` because the key and body
-/// come from multipart form data rather than URL path and raw body.
+/// come from multipart form data rather than URL path and raw body. The form's policy
+/// conditions are enforced here; its signature was verified by the service layer.
 async fn dispatch_post_object(
     parts: &http::request::Parts,
     bucket: Option<&str>,
@@ -559,90 +685,11 @@ async fn dispatch_post_object(
     let bucket_name = bucket
         .ok_or_else(|| S3Error::with_message(S3ErrorCode::InvalidRequest, "Missing bucket name"))?;
 
-    // Extract Content-Type header to get the boundary.
-    let content_type = parts
-        .headers
-        .get("content-type")
-        .and_then(|v| v.to_str().ok())
-        .ok_or_else(|| {
-            S3Error::with_message(
-                S3ErrorCode::InvalidRequest,
-                "POST Object requires Content-Type header",
-            )
-        })?;
-
-    let boundary = multipart::extract_boundary(content_type)?;
-    let form = multipart::parse_multipart(&body, &boundary)?;
-
-    // The "key" field is required.
-    let key = form.fields.get("key").ok_or_else(|| {
-        S3Error::with_message(
-            S3ErrorCode::InvalidRequest,
-            "Missing required 'key' field in POST form data",
-        )
-    })?;
-
-    // Determine success_action_status (default 204).
-    let success_status = form
-        .fields
-        .get("success_action_status")
-        .and_then(|s| s.parse::<u16>().ok())
-        .unwrap_or(204);
-
-    // Extract user metadata from form fields (x-amz-meta-* fields).
-    let metadata: HashMap<String, String> = form
-        .fields
-        .iter()
-        .filter(|(k, _)| k.starts_with("x-amz-meta-"))
-        .map(|(k, v)| {
-            (
-                k.strip_prefix("x-amz-meta-").unwrap_or(k).to_owned(),
-                v.clone(),
-            )
-        })
-        .collect();
-
-    // Build a PutObjectInput and delegate to the existing handler.
-    let input = PutObjectInput {
-        bucket: bucket_name.to_owned(),
-        key: key.clone(),
-        body: Some(StreamingBlob::new(form.file_data)),
-        content_type: form.file_content_type,
-        metadata,
-        ..PutObjectInput::default()
-    };
+    let form = PostObjectForm::parse(parts, &body)?;
+    form.check_policy(bucket_name, chrono::Utc::now())?;
 
-    let output = provider.handle_put_object(input).await?;
-
-    // Build response based on success_action_status.
-    match success_status {
-        200 | 201 => {
-            // Return XML response with bucket, key, etag, location.
-            let etag = output.e_tag.unwrap_or_default();
-            let location = format!("/{bucket_name}/{key}");
-            let xml = format!(
-                "<?xml version=\"1.0\" \
-                 encoding=\"UTF-8\"?>\n<PostResponse>\n<Location>{location}</Location>\\
-                 \
-                 n<Bucket>{bucket_name}</Bucket>\n<Key>{key}</Key>\n<ETag>{etag}</ETag>\n</\
-                 PostResponse>"
-            );
-            http::Response::builder()
-                .status(success_status)
-                .header("Content-Type", "application/xml")
-                .header("ETag", &etag)
-                .body(S3ResponseBody::from_string(xml))
-                .map_err(|e| S3Error::internal_error(e.to_string()))
-        }
-        _ => {
-            // 204 No Content (default).
-            let mut builder = http::Response::builder().status(204);
-            if let Some(etag) = &output.e_tag {
-                builder = builder.header("ETag", etag);
-            }
-            builder
-                .body(S3ResponseBody::empty())
-                .map_err(|e| S3Error::internal_error(e.to_string()))
-        }
-    }
+    let output = provider
+        .handle_put_object(form.to_put_object_input(bucket_name)?)
+        .await?;
+    form.success_response(bucket_name, output.e_tag.as_deref())
 }
@@ -0,0 +1,194 @@
+//! Health and readiness state for the gateway.
+//!
+//! [`HealthState`] tracks, per registered service router, whether it has
+//! finished initializing, along with the gateway's uptime and open
+//! connection count. The gateway renders it on every request to:
+//!
+//! - `GET /_localstack/health` (and its aliases): liveness. Always `200`, with each service
+//!   reported as `"starting"` or `"running"`. The `?reload` query the LocalStack CLI sends is
+//!   accepted and ignored.
+//! - `GET /_localstack/init/ready`: readiness. `200` once every service is running, `503` while any
+//!   is still starting.
+//! - `GET /_localstack/info`: version, edition, session id, and uptime, as the LocalStack CLI reads
+//!   them, plus the enabled services.
+//!
+//! Services start out `"starting"` and are marked running with
+//! [`HealthState::mark_running`]. The accept loop marks each registered
+//! service when it begins serving; a service with slow startup work (such as
+//! loading persisted state) would be marked once that work completes.
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::Instant,
+};
+
+use serde_json::{Map, Value, json};
+
+/// Path of the readiness endpoint.
+pub const READY_PATH: &str = "/_localstack/init/ready";
+
+/// Path of the server information endpoint.
+pub const INFO_PATH: &str = "/_localstack/info";
+
+/// Shared health state for every service behind the gateway.
+#[derive(Debug)]
+pub struct HealthState {
+    started_at: Instant,
+    session_id: String,
+    services: Vec<(&'static str, AtomicBool)>,
+    connections: AtomicUsize,
+}
+
+impl HealthState {
+    /// Create state for the named services, all still starting.
+    pub fn new(names: impl IntoIterator<Item = &'static str>) -> Self {
+        Self {
+            started_at: Instant::now(),
+            session_id: uuid::Uuid::new_v4().to_string(),
+            services: names
+                .into_iter()
+                .map(|name| (name, AtomicBool::new(false)))
+                .collect(),
+            connections: AtomicUsize::new(0),
+        }
+    }
+
+    /// Mark the service named `name` as running.
+    pub fn mark_running(&self, name: &str) {
+        for (service, running) in &self.services {
+            if *service == name {
+                running.store(true, Ordering::Release);
+            }
+        }
+    }
+
+    /// Whether every service has finished initializing.
+    pub fn is_ready(&self) -> bool {
+        self.services
+            .iter()
+            .all(|(_, running)| running.load(Ordering::Acquire))
+    }
+
+    /// Count a connection as open until the returned guard is dropped.
+    pub fn track_connection(self: &Arc<Self>) -> ConnectionGuard {
+        self.connections.fetch_add(1, Ordering::Relaxed);
+        ConnectionGuard(Arc::clone(self))
+    }
+
+    /// Number of connections currently open.
+    pub fn open_connections(&self) -> usize {
+        self.connections.load(Ordering::Relaxed)
+    }
+
+    /// Render the `/_localstack/health` body.
+    pub fn health_json(&self) -> String {
+        let services: Map<String, Value> = self
+            .services
+            .iter()
+            .map(|(name, running)| {
+                let status = if running.load(Ordering::Acquire) {
+                    "running"
+                } else {
+                    "starting"
+                };
+                ((*name).to_owned(), Value::from(status))
+            })
+            .collect();
+        json!({
+            "services": services,
+            "version": crate::VERSION,
+            "uptime": self.started_at.elapsed().as_secs(),
+            "connections": self.open_connections(),
+        })
+        .to_string()
+    }
+
+    /// Render the `/_localstack/init/ready` body.
+    pub fn ready_json(&self) -> String {
+        json!({ "completed": self.is_ready(), "scripts": [] }).to_string()
+    }
+
+    /// Render the `/_localstack/info` body. The session id is fixed for the
+    /// life of the process.
+    pub fn info_json(&self) -> String {
+        let services: Vec<&str> = self.services.iter().map(|(name, _)| *name).collect();
+        json!({
+            "version": crate::VERSION,
+            "edition": "community",
+            "is_license_activated": false,
+            "session_id": self.session_id,
+            "system": std::env::consts::OS,
+            "server_time_utc": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            "uptime": self.started_at.elapsed().as_secs(),
+            "services": services,
+        })
+        .to_string()
+    }
+}
+
+/// An open connection, counted by [`HealthState`] until dropped.
+#[derive(Debug)]
+pub struct ConnectionGuard(Arc<HealthState>);
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.0.connections.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_report_services_starting_until_marked_running() {
+        let health = HealthState::new(["s3", "dynamodb"]);
+        assert!(!health.is_ready());
+        let body: Value = serde_json::from_str(&health.health_json()).unwrap();
+        assert_eq!(body["services"]["s3"], "starting");
+        assert_eq!(body["version"], crate::VERSION);
+
+        health.mark_running("s3");
+        assert!(!health.is_ready());
+        let body: Value = serde_json::from_str(&health.health_json()).unwrap();
+        assert_eq!(body["services"]["s3"], "running");
+        assert_eq!(body["services"]["dynamodb"], "starting");
+        assert_eq!(health.ready_json(), r#"{"completed":false,"scripts":[]}"#);
+
+        health.mark_running("dynamodb");
+        assert!(health.is_ready());
+        assert_eq!(health.ready_json(), r#"{"completed":true,"scripts":[]}"#);
+    }
+
+    #[test]
+    fn test_should_render_info_with_a_stable_session_id() {
+        let health = HealthState::new(["s3", "dynamodb"]);
+        let first: Value = serde_json::from_str(&health.info_json()).unwrap();
+        assert_eq!(first["version"], crate::VERSION);
+        assert_eq!(first["edition"], "community");
+        assert_eq!(first["services"], json!(["s3", "dynamodb"]));
+        assert!(first["uptime"].is_u64());
+        let second: Value = serde_json::from_str(&health.info_json()).unwrap();
+        assert_eq!(first["session_id"], second["session_id"]);
+        assert_ne!(
+            first["session_id"],
+            serde_json::from_str::<Value>(&HealthState::new([]).info_json()).unwrap()["session_id"]
+        );
+    }
+
+    #[test]
+    fn test_should_count_open_connections() {
+        let health = Arc::new(HealthState::new(["s3"]));
+        let first = health.track_connection();
+        let second = health.track_connection();
+        assert_eq!(health.open_connections(), 2);
+        drop(first);
+        assert_eq!(health.open_connections(), 1);
+        drop(second);
+        let body: Value = serde_json::from_str(&health.health_json()).unwrap();
+        assert_eq!(body["connections"], 0);
+    }
+}
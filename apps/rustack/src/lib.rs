@@ -0,0 +1,53 @@
+//! Rustack server library.
+//!
+//! Wires the compiled-in AWS services behind a single gateway and serves
+//! them over HTTP. The `rustack` binary is a thin wrapper around
+//! [`ServerBuilder`]; tests can use the same builder to run the full HTTP
+//! stack in-process on an ephemeral port:
+//!
+//! ```no_run
+//! # async fn example() -> anyhow::Result<()> {
+//! let server = rustack_server::ServerBuilder::new()
+//!     .services(&["s3", "dynamodb"])
+//!     .bind("127.0.0.1:0")
+//!     .start()
+//!     .await?;
+//! let endpoint = server.endpoint_url();
+//! // ... point an AWS SDK client at `endpoint` ...
+//! server.shutdown().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(all(feature = "dynamodb", feature = "s3"))]
+mod dynamodb_export_bridge;
+#[cfg(feature = "events")]
+mod events_bridge;
+mod gateway;
+#[cfg(feature = "s3")]
+mod handler;
+mod health;
+mod limits;
+mod metrics;
+mod namespace;
+mod reset;
+mod rules;
+mod scrub;
+mod seed;
+mod server;
+mod service;
+mod settings;
+#[cfg(feature = "sns")]
+mod sns_bridge;
+pub mod telemetry;
+pub mod tls;
+
+pub use limits::ConnectionLimits;
+pub use seed::InitData;
+pub use server::{
+    ServerBuilder, ServerHandle, effective_config, gateway_listen_addr, init_file_credentials,
+    parse_services_value,
+};
+
+/// Server version reported in health check responses.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,299 @@
+//! Connection limits for the gateway's accept loop.
+//!
+//! [`ConnectionLimits`] bounds how many connections the gateway serves at
+//! once, how many HTTP/2 streams each may multiplex, how large request
+//! headers may grow, and how long a connection may stall on a read or a
+//! write. [`TimeoutIo`] enforces the stall timeouts on a connection's I/O.
+
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    str::FromStr,
+    task::{Context, Poll, ready},
+    time::Duration,
+};
+
+use hyper_util::{
+    rt::{TokioExecutor, TokioTimer},
+    server::conn::auto::Builder as HttpConnBuilder,
+};
+use rustack_core::config;
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    time::Sleep,
+};
+use tracing::warn;
+
+/// Smallest read buffer hyper accepts for HTTP/1.
+const MIN_HTTP1_BUF_SIZE: usize = 8192;
+
+/// Limits applied to every gateway connection.
+///
+/// A limit of `0` disables it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionLimits {
+    /// Connections served at once (`GATEWAY_MAX_CONNECTIONS`). Connections
+    /// over the limit are answered with `503 SlowDown` and closed.
+    pub max_connections: usize,
+    /// Concurrent streams per HTTP/2 connection
+    /// (`GATEWAY_H2_MAX_CONCURRENT_STREAMS`).
+    pub h2_max_concurrent_streams: u32,
+    /// Longest a connection may wait for the client to send data
+    /// (`GATEWAY_READ_TIMEOUT_SECS`). Idle keep-alive connections are closed
+    /// after this long.
+    pub read_timeout: Option<Duration>,
+    /// Longest a connection may wait for the client to accept data
+    /// (`GATEWAY_WRITE_TIMEOUT_SECS`).
+    pub write_timeout: Option<Duration>,
+    /// Largest request header block, in bytes (`GATEWAY_MAX_HEADER_BYTES`).
+    pub max_header_bytes: usize,
+}
+
+impl Default for ConnectionLimits {
+    fn default() -> Self {
+        Self {
+            max_connections: 1024,
+            h2_max_concurrent_streams: 100,
+            read_timeout: Some(Duration::from_secs(300)),
+            write_timeout: Some(Duration::from_secs(300)),
+            max_header_bytes: 64 * 1024,
+        }
+    }
+}
+
+impl ConnectionLimits {
+    /// Read the limits from the environment and the configuration file,
+    /// falling back to the defaults for unset or invalid values.
+    #[must_use]
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let secs = |name: &str, default: Option<Duration>| {
+            let default = default.map_or(0, |d| d.as_secs());
+            Some(Duration::from_secs(number(name, default))).filter(|d| !d.is_zero())
+        };
+        Self {
+            max_connections: number("GATEWAY_MAX_CONNECTIONS", defaults.max_connections),
+            h2_max_concurrent_streams: number(
+                "GATEWAY_H2_MAX_CONCURRENT_STREAMS",
+                defaults.h2_max_concurrent_streams,
+            ),
+            read_timeout: secs("GATEWAY_READ_TIMEOUT_SECS", defaults.read_timeout),
+            write_timeout: secs("GATEWAY_WRITE_TIMEOUT_SECS", defaults.write_timeout),
+            max_header_bytes: number("GATEWAY_MAX_HEADER_BYTES", defaults.max_header_bytes),
+        }
+    }
+
+    /// Apply the HTTP/1 and HTTP/2 protocol limits to `builder`.
+    pub(crate) fn configure(&self, builder: &mut HttpConnBuilder<TokioExecutor>) {
+        let mut http1 = builder.http1();
+        http1.timer(TokioTimer::new());
+        if self.max_header_bytes > 0 {
+            // hyper rejects a request head that does not fit the read buffer.
+            http1.max_buf_size(self.max_header_bytes.max(MIN_HTTP1_BUF_SIZE));
+        }
+
+        let mut http2 = builder.http2();
+        http2.timer(TokioTimer::new());
+        // Grow flow-control windows with the measured bandwidth, so large
+        // streamed uploads are not throttled to the 64 KiB default window.
+        http2.adaptive_window(true);
+        if self.h2_max_concurrent_streams > 0 {
+            http2.max_concurrent_streams(self.h2_max_concurrent_streams);
+        }
+        if let Ok(max) = u32::try_from(self.max_header_bytes)
+            && max > 0
+        {
+            http2.max_header_list_size(max);
+        }
+    }
+}
+
+/// Parse the numeric setting `name`, or return `default` when it is unset
+/// or invalid.
+fn number<T: FromStr + Copy + std::fmt::Display>(name: &str, default: T) -> T {
+    let Some(raw) = config::var(name) else {
+        return default;
+    };
+    raw.trim().parse().unwrap_or_else(|_| {
+        warn!(name, value = %raw, %default, "invalid number, using the default");
+        default
+    })
+}
+
+/// An I/O stream that fails reads and writes that make no progress within
+/// their timeouts.
+///
+/// The clock starts when an operation first has to wait and resets whenever
+/// it completes, so a slow but steady transfer never times out.
+#[derive(Debug)]
+pub struct TimeoutIo<S> {
+    inner: S,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    read_deadline: Option<Pin<Box<Sleep>>>,
+    write_deadline: Option<Pin<Box<Sleep>>>,
+}
+
+impl<S> TimeoutIo<S> {
+    /// Wrap `inner` with the timeouts of `limits`.
+    pub fn new(inner: S, limits: &ConnectionLimits) -> Self {
+        Self {
+            inner,
+            read_timeout: limits.read_timeout,
+            write_timeout: limits.write_timeout,
+            read_deadline: None,
+            write_deadline: None,
+        }
+    }
+}
+
+/// Poll the deadline of a pending operation, starting it if needed.
+fn poll_deadline(
+    deadline: &mut Option<Pin<Box<Sleep>>>,
+    timeout: Option<Duration>,
+    cx: &mut Context<'_>,
+    operation: &str,
+) -> Poll<io::Error> {
+    let Some(timeout) = timeout else {
+        return Poll::Pending;
+    };
+    let sleep = deadline.get_or_insert_with(|| Box::pin(tokio::time::sleep(timeout)));
+    ready!(sleep.as_mut().poll(cx));
+    *deadline = None;
+    Poll::Ready(io::Error::new(
+        io::ErrorKind::TimedOut,
+        format!("{operation} timed out after {timeout:?}"),
+    ))
+}
+
+/// Pass a completed write-side operation through, or wait on the write
+/// deadline while it is pending.
+fn write_result<T>(
+    this: &mut TimeoutIo<impl Unpin>,
+    poll: Poll<io::Result<T>>,
+    cx: &mut Context<'_>,
+) -> Poll<io::Result<T>> {
+    match poll {
+        Poll::Ready(result) => {
+            this.write_deadline = None;
+            Poll::Ready(result)
+        }
+        Poll::Pending => {
+            poll_deadline(&mut this.write_deadline, this.write_timeout, cx, "write").map(Err)
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for TimeoutIo<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.inner).poll_read(cx, buf) {
+            Poll::Ready(result) => {
+                this.read_deadline = None;
+                Poll::Ready(result)
+            }
+            Poll::Pending => {
+                poll_deadline(&mut this.read_deadline, this.read_timeout, cx, "read").map(Err)
+            }
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for TimeoutIo<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_write(cx, buf);
+        write_result(this, poll, cx)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_write_vectored(cx, bufs);
+        write_result(this, poll, cx)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_flush(cx);
+        write_result(this, poll, cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_shutdown(cx);
+        write_result(this, poll, cx)
+    }
+}
+
+/// Whether `error` was caused by a [`TimeoutIo`] timeout.
+pub fn is_timeout(error: &(dyn std::error::Error + 'static)) -> bool {
+    let mut source = Some(error);
+    while let Some(error) = source {
+        if error
+            .downcast_ref::<io::Error>()
+            .is_some_and(|e| e.kind() == io::ErrorKind::TimedOut)
+        {
+            return true;
+        }
+        source = error.source();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt, duplex};
+
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_should_time_out_stalled_reads_but_not_steady_ones() {
+        let limits = ConnectionLimits {
+            read_timeout: Some(Duration::from_secs(5)),
+            ..ConnectionLimits::default()
+        };
+        let (mut client, server) = duplex(64);
+        let mut server = TimeoutIo::new(server, &limits);
+
+        let mut buf = [0u8; 1];
+        for _ in 0..3 {
+            tokio::time::sleep(Duration::from_secs(4)).await;
+            client.write_all(b"x").await.unwrap();
+            server.read_exact(&mut buf).await.unwrap();
+        }
+
+        let err = server.read_exact(&mut buf).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert!(is_timeout(&err));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_should_time_out_writes_the_peer_does_not_read() {
+        let limits = ConnectionLimits {
+            write_timeout: Some(Duration::from_secs(5)),
+            ..ConnectionLimits::default()
+        };
+        let (_client, server) = duplex(4);
+        let mut server = TimeoutIo::new(server, &limits);
+
+        let err = server.write_all(&[0; 16]).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    }
+}
@@ -2,7 +2,8 @@
 //!
 //! This binary provides a LocalStack-compatible server that can host one or
 //! more AWS services. A gateway layer routes requests to the appropriate
-//! service based on request headers.
+//! service based on request headers. The server itself lives in the
+//! `rustack_server` library; this binary handles flags, logging, and Ctrl-C.
 //!
 //! # Usage
 //!
@@ -16,197 +17,60 @@
 //! |------|-------------|
 //! | `-h`, `--help` | Print help and exit |
 //! | `-v`, `--version` | Print version and exit |
-//! | `--health-check` | Probe the gateway health endpoint (exit 0 if healthy) |
+//! | `--health-check` | Probe the gateway readiness endpoint (exit 0 once all services are ready) |
+//! | `--config <path>` | Load settings from a TOML configuration file |
+//! | `--print-config` | Print the effective configuration as TOML and exit |
+//!
+//! # Configuration File
+//!
+//! A TOML file given by `--config` or `RUSTACK_CONFIG` can hold the settings
+//! below in `[gateway]`, `[s3]`, and `[dynamodb]` sections. Environment
+//! variables take precedence over the file.
 //!
 //! # Environment Variables
 //!
 //! | Variable | Default | Description |
 //! |----------|---------|-------------|
 //! | `GATEWAY_LISTEN` | `0.0.0.0:4566` | Bind address |
+//! | `GATEWAY_TLS_CERT` | *(unset)* | PEM certificate chain; enables HTTPS |
+//! | `GATEWAY_TLS_KEY` | *(unset)* | PEM private key for `GATEWAY_TLS_CERT` |
+//! | `GATEWAY_TLS_SELF_SIGNED` | `false` | Serve HTTPS with a generated self-signed certificate |
+//! | `GATEWAY_LISTEN_TLS` | *(unset)* | Serve HTTPS here, keeping plain HTTP on `GATEWAY_LISTEN` |
+//! | `GATEWAY_MAX_CONNECTIONS` | `1024` | Concurrent connections; more get `503 SlowDown` (`0` = unlimited) |
+//! | `GATEWAY_H2_MAX_CONCURRENT_STREAMS` | `100` | Concurrent streams per HTTP/2 connection |
+//! | `GATEWAY_READ_TIMEOUT_SECS` | `300` | Close connections that send nothing for this long (`0` = never) |
+//! | `GATEWAY_WRITE_TIMEOUT_SECS` | `300` | Close connections that accept no data for this long (`0` = never) |
+//! | `GATEWAY_MAX_HEADER_BYTES` | `65536` | Largest accepted request header block |
+//! | `SHUTDOWN_GRACE_PERIOD` | `5` | Seconds to drain in-flight requests on shutdown before aborting them |
 //! | `SERVICES` | *(empty = all)* | Comma-separated list of services to enable |
 //! | `<SERVICE>_SKIP_SIGNATURE_VALIDATION` | `true` | Skip SigV4 verification for `<SERVICE>` |
 //! | `S3_DOMAIN` | `s3.localhost.localstack.cloud` | Virtual hosting domain |
 //! | `LOG_LEVEL` | `info` | Log level filter |
 //! | `RUST_LOG` | *(unset)* | Fine-grained tracing filter (overrides `LOG_LEVEL`) |
-
-#[cfg(feature = "events")]
-mod events_bridge;
-mod gateway;
-#[cfg(feature = "s3")]
-mod handler;
-mod service;
-#[cfg(feature = "sns")]
-mod sns_bridge;
-
-use std::{net::SocketAddr, sync::Arc};
+//! | `METRICS_ENABLED` | `false` | Serve Prometheus metrics on `GET /metrics` |
+//! | `ACCESS_LOG_FORMAT` | *(unset = off)* | S3/DynamoDB access log format (`json` or `s3`) |
+//! | `ACCESS_LOG_FILE` | *(unset = tracing)* | Append access log lines to this file |
+//! | `OTEL_EXPORTER_OTLP_ENDPOINT` | *(unset = off)* | Export request spans over OTLP/HTTP (needs the `otel` feature) |
+//! | `CREDENTIALS_FILE` | *(unset)* | TOML/CSV file or directory of access keys, reloaded on change |
+//! | `INIT_DATA_DIR` | *(unset)* | Seed S3 buckets and DynamoDB tables from this directory at startup |
+//! | `INIT_DATA_CONTINUE_ON_ERROR` | `false` | Log and skip seed files that fail instead of aborting startup |
+//! | `RUSTACK_CONFIG` | *(unset)* | TOML configuration file (overridden by `--config`) |
 
 use anyhow::{Context, Result};
-use hyper_util::{
-    rt::{TokioExecutor, TokioIo},
-    server::conn::auto::Builder as HttpConnBuilder,
-};
-#[cfg(feature = "apigatewayv2")]
-use rustack_apigatewayv2_core::config::ApiGatewayV2Config;
-#[cfg(feature = "apigatewayv2")]
-use rustack_apigatewayv2_core::handler::RustackApiGatewayV2Handler;
-#[cfg(feature = "apigatewayv2")]
-use rustack_apigatewayv2_core::provider::RustackApiGatewayV2;
-#[cfg(feature = "apigatewayv2")]
-use rustack_apigatewayv2_http::service::{ApiGatewayV2HttpConfig, ApiGatewayV2HttpService};
-#[cfg(feature = "cloudfront")]
-use rustack_cloudfront_core::{CloudFrontConfig, RustackCloudFront};
-#[cfg(feature = "cloudfront-dataplane")]
-use rustack_cloudfront_dataplane::{DataPlane, DataPlaneConfig};
-#[cfg(feature = "cloudfront")]
-use rustack_cloudfront_http::service::{CloudFrontHttpConfig, CloudFrontHttpService};
-#[cfg(feature = "cloudwatch")]
-use rustack_cloudwatch_core::config::CloudWatchConfig;
-#[cfg(feature = "cloudwatch")]
-use rustack_cloudwatch_core::handler::RustackCloudWatchHandler;
-#[cfg(feature = "cloudwatch")]
-use rustack_cloudwatch_core::provider::RustackCloudWatch;
-#[cfg(feature = "cloudwatch")]
-use rustack_cloudwatch_http::service::{CloudWatchHttpConfig, CloudWatchHttpService};
-#[cfg(feature = "dynamodb")]
-use rustack_dynamodb_core::config::DynamoDBConfig;
-#[cfg(feature = "dynamodb")]
-use rustack_dynamodb_core::handler::RustackDynamoDBHandler;
-#[cfg(feature = "dynamodb")]
-use rustack_dynamodb_core::provider::RustackDynamoDB;
-#[cfg(feature = "dynamodb")]
-use rustack_dynamodb_http::service::{DynamoDBHttpConfig, DynamoDBHttpService};
-#[cfg(feature = "dynamodbstreams")]
-use rustack_dynamodbstreams_core::config::DynamoDBStreamsConfig;
-#[cfg(feature = "dynamodbstreams")]
-use rustack_dynamodbstreams_core::emitter::{
-    DynamoDBStreamEmitter, DynamoDBStreamLifecycleManager,
+use rustack_core::{ConfigFile, config};
+use rustack_server::{
+    ServerBuilder, VERSION, effective_config, gateway_listen_addr, init_file_credentials,
+    parse_services_value,
+    telemetry::{self, Telemetry},
+    tls,
 };
-#[cfg(feature = "dynamodbstreams")]
-use rustack_dynamodbstreams_core::handler::RustackDynamoDBStreamsHandler;
-#[cfg(feature = "dynamodbstreams")]
-use rustack_dynamodbstreams_core::provider::RustackDynamoDBStreams;
-#[cfg(feature = "dynamodbstreams")]
-use rustack_dynamodbstreams_core::storage::StreamStore;
-#[cfg(feature = "dynamodbstreams")]
-use rustack_dynamodbstreams_http::service::{
-    DynamoDBStreamsHttpConfig, DynamoDBStreamsHttpService,
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
 };
-#[cfg(feature = "events")]
-use rustack_events_core::config::EventsConfig;
-#[cfg(feature = "events")]
-use rustack_events_core::handler::RustackEventsHandler;
-#[cfg(feature = "events")]
-use rustack_events_core::provider::RustackEvents;
-#[cfg(feature = "events")]
-use rustack_events_http::service::{EventsHttpConfig, EventsHttpService};
-#[cfg(feature = "iam")]
-use rustack_iam_core::config::IamConfig;
-#[cfg(feature = "iam")]
-use rustack_iam_core::handler::RustackIamHandler;
-#[cfg(feature = "iam")]
-use rustack_iam_core::provider::RustackIam;
-#[cfg(feature = "iam")]
-use rustack_iam_core::store::IamStore;
-#[cfg(feature = "iam")]
-use rustack_iam_http::service::{IamHttpConfig, IamHttpService};
-#[cfg(feature = "kinesis")]
-use rustack_kinesis_core::config::KinesisConfig;
-#[cfg(feature = "kinesis")]
-use rustack_kinesis_core::handler::RustackKinesisHandler;
-#[cfg(feature = "kinesis")]
-use rustack_kinesis_core::provider::RustackKinesis;
-#[cfg(feature = "kinesis")]
-use rustack_kinesis_http::service::{KinesisHttpConfig, KinesisHttpService};
-#[cfg(feature = "kms")]
-use rustack_kms_core::config::KmsConfig;
-#[cfg(feature = "kms")]
-use rustack_kms_core::handler::RustackKmsHandler;
-#[cfg(feature = "kms")]
-use rustack_kms_core::provider::RustackKms;
-#[cfg(feature = "kms")]
-use rustack_kms_http::service::{KmsHttpConfig, KmsHttpService};
-#[cfg(feature = "lambda")]
-use rustack_lambda_core::config::LambdaConfig;
-#[cfg(feature = "lambda")]
-use rustack_lambda_core::handler::RustackLambdaHandler;
-#[cfg(feature = "lambda")]
-use rustack_lambda_core::provider::RustackLambda;
-#[cfg(feature = "lambda")]
-use rustack_lambda_http::service::{LambdaHttpConfig, LambdaHttpService};
-#[cfg(feature = "logs")]
-use rustack_logs_core::config::LogsConfig;
-#[cfg(feature = "logs")]
-use rustack_logs_core::handler::RustackLogsHandler;
-#[cfg(feature = "logs")]
-use rustack_logs_core::provider::RustackLogs;
-#[cfg(feature = "logs")]
-use rustack_logs_http::service::{LogsHttpConfig, LogsHttpService};
-#[cfg(feature = "s3")]
-use rustack_s3_core::{RustackS3, S3Config};
-#[cfg(feature = "s3")]
-use rustack_s3_http::service::{S3HttpConfig, S3HttpService};
-#[cfg(feature = "secretsmanager")]
-use rustack_secretsmanager_core::config::SecretsManagerConfig;
-#[cfg(feature = "secretsmanager")]
-use rustack_secretsmanager_core::handler::RustackSecretsManagerHandler;
-#[cfg(feature = "secretsmanager")]
-use rustack_secretsmanager_core::provider::RustackSecretsManager;
-#[cfg(feature = "secretsmanager")]
-use rustack_secretsmanager_http::service::{SecretsManagerHttpConfig, SecretsManagerHttpService};
-#[cfg(feature = "ses")]
-use rustack_ses_core::config::SesConfig;
-#[cfg(feature = "ses")]
-use rustack_ses_core::handler::RustackSesHandler;
-#[cfg(feature = "ses")]
-use rustack_ses_core::provider::RustackSes;
-#[cfg(feature = "ses")]
-use rustack_ses_http::service::{SesHttpConfig, SesHttpService};
-#[cfg(feature = "ses")]
-use rustack_ses_http::v2::SesV2HttpService;
-#[cfg(feature = "sns")]
-use rustack_sns_core::config::SnsConfig;
-#[cfg(feature = "sns")]
-use rustack_sns_core::handler::RustackSnsHandler;
-#[cfg(feature = "sns")]
-use rustack_sns_core::provider::RustackSns;
-#[cfg(feature = "sns")]
-use rustack_sns_http::service::{SnsHttpConfig, SnsHttpService};
-#[cfg(feature = "sqs")]
-use rustack_sqs_core::config::SqsConfig;
-#[cfg(feature = "sqs")]
-use rustack_sqs_core::handler::RustackSqsHandler;
-#[cfg(feature = "sqs")]
-use rustack_sqs_core::provider::RustackSqs;
-#[cfg(feature = "sqs")]
-use rustack_sqs_http::service::{SqsHttpConfig, SqsHttpService};
-#[cfg(feature = "ssm")]
-use rustack_ssm_core::config::SsmConfig;
-#[cfg(feature = "ssm")]
-use rustack_ssm_core::handler::RustackSsmHandler;
-#[cfg(feature = "ssm")]
-use rustack_ssm_core::provider::RustackSsm;
-#[cfg(feature = "ssm")]
-use rustack_ssm_http::service::{SsmHttpConfig, SsmHttpService};
-#[cfg(feature = "sts")]
-use rustack_sts_core::config::StsConfig;
-#[cfg(feature = "sts")]
-use rustack_sts_core::handler::RustackStsHandler;
-#[cfg(feature = "sts")]
-use rustack_sts_core::provider::RustackSts;
-#[cfg(feature = "sts")]
-use rustack_sts_http::service::{StsHttpConfig, StsHttpService};
-use tokio::net::TcpListener;
+use tokio_rustls::{TlsConnector, rustls::pki_types::ServerName};
 use tracing::{info, warn};
-use tracing_subscriber::EnvFilter;
-
-#[cfg(feature = "events")]
-use crate::events_bridge::LocalTargetDelivery;
-#[cfg(feature = "sns")]
-use crate::sns_bridge::RustackSqsPublisher;
-use crate::{gateway::GatewayService, service::ServiceRouter};
-
-/// Server version reported in health check responses.
-const VERSION: &str = env!("CARGO_PKG_VERSION");
+use tracing_subscriber::{EnvFilter, layer::SubscriberExt as _, util::SubscriberInitExt as _};
 
 /// Binary name reported in `--help` / `--version` output.
 const BIN_NAME: &str = "rustack";
@@ -214,6 +78,9 @@ const BIN_NAME: &str = "rustack";
 /// Short product description used by `--help`.
 const DESCRIPTION: &str = env!("CARGO_PKG_DESCRIPTION");
 
+/// Printed on stdout once the server accepts requests.
+const READY_MESSAGE: &str = "Ready.";
+
 /// Classification of command-line invocation.
 ///
 /// Parsed once up front by [`classify_args`] so `main` can dispatch without
@@ -228,15 +95,20 @@ enum CliAction {
     Version,
     /// Probe the gateway health endpoint (used by Docker `HEALTHCHECK`).
     HealthCheck,
+    /// Print the effective configuration to stdout and exit 0.
+    PrintConfig,
     /// An unrecognised flag was supplied.
     UnknownFlag(String),
+    /// A flag that takes a value was the last argument.
+    MissingValue(String),
 }
 
 /// Classify a sequence of CLI arguments (including argv\[0\]) into a [`CliAction`].
 ///
-/// Precedence: `--help` > `--version` > `--health-check`. Positional args are
-/// ignored (the binary takes no positional arguments today). Any other token
-/// that starts with `-` is treated as an unknown flag.
+/// Precedence: `--help` > `--version` > `--health-check` > `--print-config`.
+/// `--config` and its value are skipped; read them with [`config_flag`].
+/// Positional args are ignored (the binary takes no positional arguments
+/// today). Any other token that starts with `-` is treated as an unknown flag.
 fn classify_args<I, S>(args: I) -> CliAction
 where
     I: IntoIterator<Item = S>,
@@ -246,11 +118,17 @@ where
     let mut iter = args.into_iter();
     // Skip the program name (argv[0]).
     iter.next();
-    for arg in iter {
+    while let Some(arg) = iter.next() {
         match arg.as_ref() {
             "-h" | "--help" => return CliAction::Help,
             "-v" | "--version" => return CliAction::Version,
             "--health-check" => action = CliAction::HealthCheck,
+            "--print-config" if action == CliAction::Run => action = CliAction::PrintConfig,
+            "--config" if iter.next().is_none() => {
+                return CliAction::MissingValue("--config".to_owned());
+            }
+            "--print-config" | "--config" => {}
+            other if other.starts_with("--config=") => {}
             other if other.starts_with('-') => {
                 return CliAction::UnknownFlag(other.to_string());
             }
@@ -260,6 +138,41 @@ where
     action
 }
 
+/// The value of the `--config <path>` (or `--config=<path>`) flag, if given.
+fn config_flag<I, S>(args: I) -> Option<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut iter = args.into_iter().skip(1);
+    while let Some(arg) = iter.next() {
+        let arg = arg.as_ref();
+        if arg == "--config" {
+            return iter.next().map(|path| path.as_ref().to_owned());
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(path.to_owned());
+        }
+    }
+    None
+}
+
+/// Load the configuration file named by `--config` or `RUSTACK_CONFIG`, if
+/// any, and install it as the fallback for environment variables.
+///
+/// Returns the file's path and warnings, to be logged once tracing is up.
+fn load_config_file() -> Result<Option<(String, Vec<String>)>> {
+    let Some(path) =
+        config_flag(std::env::args()).or_else(|| std::env::var(config::CONFIG_FILE_ENV).ok())
+    else {
+        return Ok(None);
+    };
+    let file = ConfigFile::load(&path).with_context(|| format!("failed to load config {path}"))?;
+    let warnings = file.warnings().to_vec();
+    file.install();
+    Ok(Some((path, warnings)))
+}
+
 /// Render the `--help` text. Kept pure so it can be snapshot-tested.
 fn help_text() -> String {
     let services = parse_services_value("").join(", ");
@@ -273,14 +186,29 @@ fn help_text() -> String {
          FLAGS:\n    \
          -h, --help            Print this help message and exit\n    \
          -v, --version         Print version information and exit\n        \
-         --health-check    Probe the gateway health endpoint, exit 0 if healthy\n\
+         --health-check    Probe the gateway readiness endpoint, exit 0 if ready\n        \
+         --config <PATH>   Load settings from a TOML configuration file\n        \
+         --print-config    Print the effective configuration as TOML and exit\n\
          \n\
          ENVIRONMENT:\n    \
          GATEWAY_LISTEN        Bind address (default: 0.0.0.0:4566)\n    \
+         GATEWAY_TLS_CERT      PEM certificate chain; serves HTTPS (with GATEWAY_TLS_KEY)\n    \
+         GATEWAY_TLS_SELF_SIGNED  Serve HTTPS with a generated self-signed certificate\n    \
+         GATEWAY_LISTEN_TLS    Serve HTTPS here and plain HTTP on GATEWAY_LISTEN\n    \
+         GATEWAY_MAX_CONNECTIONS  Concurrent connections before 503 SlowDown (default: 1024)\n    \
+         GATEWAY_READ_TIMEOUT_SECS, GATEWAY_WRITE_TIMEOUT_SECS  Stalled connection timeouts (default: 300)\n    \
          SERVICES              Comma-separated list of services to enable (default: all compiled-in)\n    \
          LOG_LEVEL             Log level filter (default: info)\n    \
          RUST_LOG              Fine-grained tracing filter (overrides LOG_LEVEL)\n    \
-         ACCESS_KEY,SECRET_KEY Static credentials (also accepts AWS_ACCESS_KEY_ID / AWS_SECRET_ACCESS_KEY)\n\
+         SHUTDOWN_GRACE_PERIOD Seconds to drain requests on shutdown before aborting (default: 5)\n    \
+         METRICS_ENABLED       Serve Prometheus metrics on GET /metrics (default: false)\n    \
+         ACCESS_LOG_FORMAT     S3/DynamoDB access log format: json or s3 (default: disabled)\n    \
+         ACCESS_LOG_FILE       Write access log lines to this file (default: via tracing)\n    \
+         OTEL_EXPORTER_OTLP_ENDPOINT  Export request spans to this OTLP/HTTP collector (otel feature)\n    \
+         INIT_DATA_DIR         Seed S3 buckets and DynamoDB tables from this directory at startup\n    \
+         INIT_DATA_CONTINUE_ON_ERROR  Skip seed files that fail instead of aborting startup\n    \
+         ACCESS_KEY,SECRET_KEY Static credentials (also accepts AWS_ACCESS_KEY_ID / AWS_SECRET_ACCESS_KEY)\n    \
+         RUSTACK_CONFIG        TOML configuration file; environment variables take precedence\n\
          \n\
          COMPILED-IN SERVICES:\n    \
          {services}\n\
@@ -294,10 +222,11 @@ fn version_text() -> String {
     format!("{BIN_NAME} {VERSION}\n")
 }
 
-/// Initialize the tracing subscriber.
+/// Initialize the tracing subscriber, exporting request spans if an OTLP
+/// endpoint is configured.
 ///
 /// Uses `RUST_LOG` if set, otherwise falls back to the `LOG_LEVEL` config value.
-fn init_tracing(log_level: &str) -> Result<()> {
+fn init_tracing(log_level: &str) -> Result<Telemetry> {
     let filter = if std::env::var("RUST_LOG").is_ok() {
         EnvFilter::from_default_env()
     } else {
@@ -305,441 +234,57 @@ fn init_tracing(log_level: &str) -> Result<()> {
             .with_context(|| format!("invalid log level filter: {log_level}"))?
     };
 
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_target(true)
+    let (export, telemetry) = telemetry::layer()?;
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer().with_target(true))
+        .with(export)
         .init();
 
-    Ok(())
-}
-
-/// Build the [`S3HttpConfig`] from the application [`S3Config`].
-#[cfg(feature = "s3")]
-fn build_s3_http_config(config: &S3Config) -> S3HttpConfig {
-    let credential_provider = build_credential_provider();
-
-    S3HttpConfig {
-        domain: config.s3_domain.clone(),
-        virtual_hosting: config.s3_virtual_hosting,
-        skip_signature_validation: config.s3_skip_signature_validation,
-        region: config.default_region.clone(),
-        credential_provider: credential_provider.clone(),
-    }
-}
-
-/// Build the [`DynamoDBHttpConfig`] from the [`DynamoDBConfig`].
-#[cfg(feature = "dynamodb")]
-fn build_dynamodb_http_config(config: &DynamoDBConfig) -> DynamoDBHttpConfig {
-    let credential_provider = build_credential_provider();
-
-    DynamoDBHttpConfig {
-        skip_signature_validation: config.skip_signature_validation,
-        region: config.default_region.clone(),
-        credential_provider,
-    }
-}
-
-/// Build the [`SqsHttpConfig`] from the [`SqsConfig`].
-#[cfg(feature = "sqs")]
-fn build_sqs_http_config(config: &SqsConfig) -> SqsHttpConfig {
-    let credential_provider = build_credential_provider();
-
-    SqsHttpConfig {
-        skip_signature_validation: config.skip_signature_validation,
-        region: config.default_region.clone(),
-        credential_provider,
-    }
-}
-
-/// Build the [`SsmHttpConfig`] from the [`SsmConfig`].
-#[cfg(feature = "ssm")]
-fn build_ssm_http_config(config: &SsmConfig) -> SsmHttpConfig {
-    let credential_provider = build_credential_provider();
-
-    SsmHttpConfig {
-        skip_signature_validation: config.skip_signature_validation,
-        region: config.default_region.clone(),
-        credential_provider,
-    }
-}
-
-/// Build the [`SnsHttpConfig`] from the [`SnsConfig`].
-#[cfg(feature = "sns")]
-fn build_sns_http_config(config: &SnsConfig) -> SnsHttpConfig {
-    let credential_provider = build_credential_provider();
-
-    SnsHttpConfig {
-        skip_signature_validation: config.skip_signature_validation,
-        region: config.default_region.clone(),
-        credential_provider,
-    }
-}
-
-/// Build the [`LambdaHttpConfig`] from the [`LambdaConfig`].
-#[cfg(feature = "lambda")]
-fn build_lambda_http_config(config: &LambdaConfig) -> LambdaHttpConfig {
-    let credential_provider = build_credential_provider();
-
-    LambdaHttpConfig {
-        skip_signature_validation: config.skip_signature_validation,
-        region: config.default_region.clone(),
-        credential_provider,
-    }
-}
-
-/// Build the [`EventsHttpConfig`] from the [`EventsConfig`].
-#[cfg(feature = "events")]
-fn build_events_http_config(config: &EventsConfig) -> EventsHttpConfig {
-    let credential_provider = build_credential_provider();
-
-    EventsHttpConfig {
-        skip_signature_validation: config.skip_signature_validation,
-        region: config.default_region.clone(),
-        credential_provider,
-    }
-}
-
-/// Build the [`LogsHttpConfig`] from the [`LogsConfig`].
-#[cfg(feature = "logs")]
-fn build_logs_http_config(config: &LogsConfig) -> LogsHttpConfig {
-    let credential_provider = build_credential_provider();
-
-    LogsHttpConfig {
-        skip_signature_validation: config.skip_signature_validation,
-        region: config.default_region.clone(),
-        credential_provider,
-    }
-}
-
-/// Build the [`KmsHttpConfig`] from the [`KmsConfig`].
-#[cfg(feature = "kms")]
-fn build_kms_http_config(config: &KmsConfig) -> KmsHttpConfig {
-    let credential_provider = build_credential_provider();
-
-    KmsHttpConfig {
-        skip_signature_validation: config.skip_signature_validation,
-        region: config.default_region.clone(),
-        credential_provider,
-    }
-}
-
-/// Build the [`KinesisHttpConfig`] from the [`KinesisConfig`].
-#[cfg(feature = "kinesis")]
-fn build_kinesis_http_config(config: &KinesisConfig) -> KinesisHttpConfig {
-    let credential_provider = build_credential_provider();
-
-    KinesisHttpConfig {
-        skip_signature_validation: config.skip_signature_validation,
-        region: config.default_region.clone(),
-        credential_provider,
-    }
-}
-
-/// Build the [`SecretsManagerHttpConfig`] from the [`SecretsManagerConfig`].
-#[cfg(feature = "secretsmanager")]
-fn build_secretsmanager_http_config(config: &SecretsManagerConfig) -> SecretsManagerHttpConfig {
-    let credential_provider = build_credential_provider();
-
-    SecretsManagerHttpConfig {
-        skip_signature_validation: config.skip_signature_validation,
-        region: config.default_region.clone(),
-        credential_provider,
-    }
-}
-
-/// Build the [`SesHttpConfig`] from the [`SesConfig`].
-#[cfg(feature = "ses")]
-fn build_ses_http_config(config: &SesConfig) -> SesHttpConfig {
-    let credential_provider = build_credential_provider();
-
-    SesHttpConfig {
-        skip_signature_validation: config.skip_signature_validation,
-        region: config.default_region.clone(),
-        credential_provider,
-    }
-}
-
-/// Build the [`ApiGatewayV2HttpConfig`] from the [`ApiGatewayV2Config`].
-#[cfg(feature = "apigatewayv2")]
-fn build_apigatewayv2_http_config(config: &ApiGatewayV2Config) -> ApiGatewayV2HttpConfig {
-    let credential_provider = build_credential_provider();
-
-    ApiGatewayV2HttpConfig {
-        skip_signature_validation: config.skip_signature_validation,
-        region: config.default_region.clone(),
-        credential_provider,
-    }
+    Ok(telemetry)
 }
 
-/// Build the [`DynamoDBStreamsHttpConfig`] from the [`DynamoDBStreamsConfig`].
-#[cfg(feature = "dynamodbstreams")]
-fn build_dynamodbstreams_http_config(config: &DynamoDBStreamsConfig) -> DynamoDBStreamsHttpConfig {
-    let credential_provider = build_credential_provider();
-
-    DynamoDBStreamsHttpConfig {
-        skip_signature_validation: config.skip_signature_validation,
-        region: config.default_region.clone(),
-        credential_provider,
-    }
-}
-
-/// Build the [`CloudWatchHttpConfig`] from the [`CloudWatchConfig`].
-#[cfg(feature = "cloudwatch")]
-fn build_cloudwatch_http_config(config: &CloudWatchConfig) -> CloudWatchHttpConfig {
-    let credential_provider = build_credential_provider();
-
-    CloudWatchHttpConfig {
-        skip_signature_validation: config.skip_signature_validation,
-        region: config.default_region.clone(),
-        credential_provider,
-    }
-}
-
-/// Build the [`IamHttpConfig`] from the [`IamConfig`].
+/// Perform a health check by connecting to the gateway and requesting the
+/// readiness endpoint, over TLS when `tls` is set.
 ///
-/// IAM is a global service so we default the region to `us-east-1`.
-#[cfg(feature = "iam")]
-fn build_iam_http_config(config: &IamConfig) -> IamHttpConfig {
-    let credential_provider = build_credential_provider();
-
-    IamHttpConfig {
-        skip_signature_validation: config.skip_signature_validation,
-        region: "us-east-1".to_owned(),
-        credential_provider,
-    }
-}
-
-/// Build the [`CloudFrontHttpConfig`] from the [`CloudFrontConfig`].
-#[cfg(feature = "cloudfront")]
-fn build_cloudfront_http_config(config: &CloudFrontConfig) -> CloudFrontHttpConfig {
-    let credential_provider = build_credential_provider();
-    CloudFrontHttpConfig {
-        skip_signature_validation: config.skip_signature_validation,
-        region: config.default_region.clone(),
-        credential_provider,
-    }
-}
-
-/// Build the [`StsHttpConfig`] from the [`StsConfig`].
-#[cfg(feature = "sts")]
-fn build_sts_http_config(config: &StsConfig) -> StsHttpConfig {
-    let credential_provider = build_credential_provider();
-
-    StsHttpConfig {
-        skip_signature_validation: config.skip_signature_validation,
-        region: config.default_region.clone(),
-        credential_provider,
-    }
-}
-
-/// Build a credential provider from `ACCESS_KEY` / `SECRET_KEY` environment
-/// variables (used by MinIO Mint and other test harnesses).
-#[cfg(any(
-    feature = "s3",
-    feature = "dynamodb",
-    feature = "sqs",
-    feature = "ssm",
-    feature = "sns",
-    feature = "lambda",
-    feature = "events",
-    feature = "logs",
-    feature = "kms",
-    feature = "kinesis",
-    feature = "secretsmanager",
-    feature = "ses",
-    feature = "apigatewayv2",
-    feature = "cloudwatch",
-    feature = "dynamodbstreams",
-    feature = "iam",
-    feature = "sts",
-    feature = "cloudfront"
-))]
-fn build_credential_provider() -> Option<Arc<dyn rustack_auth::CredentialProvider>> {
-    use rustack_auth::StaticCredentialProvider;
-
-    let access_key = std::env::var("ACCESS_KEY")
-        .or_else(|_| std::env::var("AWS_ACCESS_KEY_ID"))
-        .ok()?;
-    let secret_key = std::env::var("SECRET_KEY")
-        .or_else(|_| std::env::var("AWS_SECRET_ACCESS_KEY"))
-        .ok()?;
-
-    info!(
-        access_key = %access_key,
-        "configured credential provider from environment"
-    );
-
-    Some(Arc::new(StaticCredentialProvider::new(vec![(
-        access_key, secret_key,
-    )])))
-}
-
-/// Run the accept loop, serving connections until a shutdown signal is received.
-async fn serve(listener: TcpListener, service: GatewayService) -> Result<()> {
-    let graceful = hyper_util::server::graceful::GracefulShutdown::new();
-    let http = HttpConnBuilder::new(TokioExecutor::new());
+/// Succeeds only when the gateway answers `200`, meaning every service has
+/// finished initializing. A gateway that is listening but still loading
+/// answers `503` and is reported as starting.
+async fn run_health_check(addr: &str, tls: bool) -> Result<()> {
+    let stream = TcpStream::connect(addr)
+        .await
+        .with_context(|| format!("cannot connect to {addr}"))?;
 
-    let shutdown = async {
-        tokio::signal::ctrl_c().await.ok();
-        info!("received shutdown signal, draining connections");
+    let response = if tls {
+        // The gateway's certificate is typically self-signed and not issued
+        // for the probed address, so it is not verified.
+        let connector = TlsConnector::from(tls::insecure_client_config()?);
+        let name = ServerName::try_from("localhost")?;
+        let stream = connector
+            .connect(name, stream)
+            .await
+            .with_context(|| format!("TLS handshake with {addr} failed"))?;
+        request_readiness(stream, addr).await?
+    } else {
+        request_readiness(stream, addr).await?
     };
 
-    tokio::pin!(shutdown);
-
-    loop {
-        tokio::select! {
-            result = listener.accept() => {
-                let (stream, peer_addr) = match result {
-                    Ok(conn) => conn,
-                    Err(e) => {
-                        warn!(error = %e, "failed to accept connection");
-                        continue;
-                    }
-                };
-
-                let svc = service.clone();
-                let conn = http.serve_connection(TokioIo::new(stream), svc);
-                let conn = graceful.watch(conn.into_owned());
-
-                tokio::spawn(async move {
-                    if let Err(e) = conn.await {
-                        warn!(peer_addr = %peer_addr, error = %e, "connection error");
-                    }
-                });
-            }
-
-            () = &mut shutdown => {
-                info!("shutting down gracefully");
-                break;
-            }
-        }
+    let status = response.split_whitespace().nth(1).unwrap_or_default();
+    match status {
+        "200" => Ok(()),
+        "503" => anyhow::bail!("{addr} is listening but services are still starting"),
+        _ => anyhow::bail!("unhealthy response from {addr}"),
     }
-
-    // Wait for in-flight requests to complete.
-    graceful.shutdown().await;
-    info!("all connections drained, exiting");
-
-    Ok(())
 }
 
-/// Check whether a service name was compiled into this binary.
-fn is_compiled_in(name: &str) -> bool {
-    (name == "s3" && cfg!(feature = "s3"))
-        || (name == "dynamodb" && cfg!(feature = "dynamodb"))
-        || (name == "sqs" && cfg!(feature = "sqs"))
-        || (name == "ssm" && cfg!(feature = "ssm"))
-        || (name == "sns" && cfg!(feature = "sns"))
-        || (name == "lambda" && cfg!(feature = "lambda"))
-        || (name == "events" && cfg!(feature = "events"))
-        || (name == "logs" && cfg!(feature = "logs"))
-        || (name == "kms" && cfg!(feature = "kms"))
-        || (name == "kinesis" && cfg!(feature = "kinesis"))
-        || (name == "secretsmanager" && cfg!(feature = "secretsmanager"))
-        || (name == "ses" && cfg!(feature = "ses"))
-        || (name == "apigatewayv2" && cfg!(feature = "apigatewayv2"))
-        || (name == "cloudwatch" && cfg!(feature = "cloudwatch"))
-        || (name == "dynamodbstreams" && cfg!(feature = "dynamodbstreams"))
-        || (name == "iam" && cfg!(feature = "iam"))
-        || (name == "sts" && cfg!(feature = "sts"))
-        || (name == "cloudfront" && cfg!(feature = "cloudfront"))
-}
-
-/// Parse the `SERVICES` environment variable into a list of service names.
-///
-/// If `SERVICES` is unset or empty, returns all compiled-in services.
-fn parse_enabled_services() -> Vec<String> {
-    let raw = std::env::var("SERVICES").unwrap_or_default();
-    parse_services_value(&raw)
-}
-
-/// Parse a comma-separated services string into a list of service names.
-///
-/// If the input is empty, returns all compiled-in services.
-fn parse_services_value(raw: &str) -> Vec<String> {
-    let trimmed = raw.trim();
-    if trimmed.is_empty() {
-        // All compiled-in services.
-        let mut all = Vec::new();
-        if cfg!(feature = "s3") {
-            all.push("s3".to_string());
-        }
-        if cfg!(feature = "dynamodb") {
-            all.push("dynamodb".to_string());
-        }
-        if cfg!(feature = "sqs") {
-            all.push("sqs".to_string());
-        }
-        if cfg!(feature = "ssm") {
-            all.push("ssm".to_string());
-        }
-        if cfg!(feature = "sns") {
-            all.push("sns".to_string());
-        }
-        if cfg!(feature = "lambda") {
-            all.push("lambda".to_string());
-        }
-        if cfg!(feature = "events") {
-            all.push("events".to_string());
-        }
-        if cfg!(feature = "logs") {
-            all.push("logs".to_string());
-        }
-        if cfg!(feature = "kms") {
-            all.push("kms".to_string());
-        }
-        if cfg!(feature = "kinesis") {
-            all.push("kinesis".to_string());
-        }
-        if cfg!(feature = "secretsmanager") {
-            all.push("secretsmanager".to_string());
-        }
-        if cfg!(feature = "ses") {
-            all.push("ses".to_string());
-        }
-        if cfg!(feature = "apigatewayv2") {
-            all.push("apigatewayv2".to_string());
-        }
-        if cfg!(feature = "cloudwatch") {
-            all.push("cloudwatch".to_string());
-        }
-        if cfg!(feature = "dynamodbstreams") {
-            all.push("dynamodbstreams".to_string());
-        }
-        if cfg!(feature = "iam") {
-            all.push("iam".to_string());
-        }
-        if cfg!(feature = "sts") {
-            all.push("sts".to_string());
-        }
-        if cfg!(feature = "cloudfront") {
-            all.push("cloudfront".to_string());
-        }
-        all
-    } else {
-        trimmed
-            .split(',')
-            .map(|s| s.trim().to_lowercase())
-            .filter(|s| !s.is_empty())
-            .collect()
-    }
-}
-
-/// Perform a health check by connecting to the gateway and requesting the health endpoint.
-///
-/// Exits with code 0 if the response is 200 OK and contains at least one
-/// running service, 1 otherwise.
-async fn run_health_check(addr: &str) -> Result<()> {
-    use tokio::{
-        io::{AsyncReadExt, AsyncWriteExt},
-        net::TcpStream,
-    };
-
-    let mut stream = TcpStream::connect(addr)
-        .await
-        .with_context(|| format!("cannot connect to {addr}"))?;
-
-    let request =
-        format!("GET /_localstack/health HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\n\r\n");
+/// Send the readiness request over `stream` and read the whole response.
+async fn request_readiness<S>(mut stream: S, addr: &str) -> Result<String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let request = format!(
+        "GET /_localstack/init/ready HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\n\r\n"
+    );
     stream.write_all(request.as_bytes()).await?;
     // Do not half-close the write side: the HTTP request is self-framing
     // (GET with no body) and the Connection: close header tells hyper not
@@ -748,410 +293,30 @@ async fn run_health_check(addr: &str) -> Result<()> {
 
     let mut response = String::new();
     stream.read_to_string(&mut response).await?;
-
-    // Accept any 200 response that reports at least one running service.
-    if response.contains("200 OK") && response.contains("\"running\"") {
-        Ok(())
-    } else {
-        anyhow::bail!("unhealthy response from {addr}")
-    }
+    Ok(response)
 }
 
-/// Read the gateway listen address from the environment.
+/// The address `--health-check` probes and whether it speaks TLS.
 ///
-/// Checks `GATEWAY_LISTEN` (the canonical var) and falls back to the
-/// S3Config default when S3 is compiled in.
-fn gateway_listen_addr() -> String {
-    std::env::var("GATEWAY_LISTEN").unwrap_or_else(|_| "0.0.0.0:4566".to_string())
+/// With a separate `GATEWAY_LISTEN_TLS` address, `GATEWAY_LISTEN` serves
+/// plain HTTP and is probed without TLS.
+fn health_check_target() -> (String, bool) {
+    let addr = gateway_listen_addr().replace("0.0.0.0", "127.0.0.1");
+    let tls = tls::tls_enabled() && config::var(tls::LISTEN_TLS_ENV).is_none();
+    (addr, tls)
 }
 
-/// Read the log level from the environment.
+/// Read the log level from the environment or the configuration file.
 fn log_level() -> String {
-    std::env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string())
-}
-
-/// Build all enabled service routers based on environment configuration.
-#[allow(clippy::too_many_lines)]
-fn build_services(is_enabled: impl Fn(&str) -> bool) -> Vec<Box<dyn ServiceRouter>> {
-    let mut services: Vec<Box<dyn ServiceRouter>> = Vec::new();
-
-    // ----- DynamoDB + DynamoDB Streams (register before S3: S3 is the catch-all) -----
-    #[cfg(feature = "dynamodb")]
-    if is_enabled("dynamodb") {
-        let dynamodb_config = DynamoDBConfig::from_env();
-        info!(
-            dynamodb_skip_signature_validation = dynamodb_config.skip_signature_validation,
-            "initializing DynamoDB service",
-        );
-        let mut dynamodb_provider = RustackDynamoDB::new(dynamodb_config.clone());
-
-        // Wire in DynamoDB Streams emitter and lifecycle manager if enabled.
-        #[cfg(feature = "dynamodbstreams")]
-        let stream_store = if is_enabled("dynamodbstreams") {
-            let streams_config = DynamoDBStreamsConfig::from_env();
-            let store = Arc::new(StreamStore::new());
-            let emitter = Arc::new(DynamoDBStreamEmitter::new(
-                Arc::clone(&store),
-                dynamodb_config.default_region.clone(),
-            ));
-            let lifecycle = Arc::new(DynamoDBStreamLifecycleManager::new(
-                Arc::clone(&store),
-                streams_config.default_region.clone(),
-                streams_config.default_account_id.clone(),
-            ));
-            dynamodb_provider.set_emitter(emitter);
-            dynamodb_provider.set_lifecycle(lifecycle);
-            Some((store, streams_config))
-        } else {
-            None
-        };
-
-        let dynamodb_handler = RustackDynamoDBHandler::new(Arc::new(dynamodb_provider));
-        let dynamodb_http_config = build_dynamodb_http_config(&dynamodb_config);
-        let dynamodb_service =
-            DynamoDBHttpService::new(Arc::new(dynamodb_handler), dynamodb_http_config);
-        services.push(Box::new(service::DynamoDBServiceRouter::new(
-            dynamodb_service,
-        )));
-
-        // Register DynamoDB Streams router.
-        #[cfg(feature = "dynamodbstreams")]
-        if let Some((store, streams_config)) = stream_store {
-            info!("initializing DynamoDB Streams service");
-            let streams_provider = RustackDynamoDBStreams::new(store, streams_config.clone());
-            let streams_handler = RustackDynamoDBStreamsHandler::new(Arc::new(streams_provider));
-            let streams_http_config = build_dynamodbstreams_http_config(&streams_config);
-            let streams_service =
-                DynamoDBStreamsHttpService::new(Arc::new(streams_handler), streams_http_config);
-            services.push(Box::new(service::DynamoDBStreamsServiceRouter::new(
-                streams_service,
-            )));
-        }
-    }
-
-    // ----- SQS (register before S3: S3 is the catch-all) -----
-    #[cfg(feature = "sqs")]
-    let sqs_provider_arc: Option<Arc<RustackSqs>> = if is_enabled("sqs") {
-        let sqs_config = SqsConfig::from_env();
-        info!(
-            sqs_skip_signature_validation = sqs_config.skip_signature_validation,
-            "initializing SQS service",
-        );
-        let sqs_provider = Arc::new(RustackSqs::new(sqs_config.clone()));
-        let sqs_handler = RustackSqsHandler::new(Arc::clone(&sqs_provider));
-        let sqs_http_config = build_sqs_http_config(&sqs_config);
-        let sqs_service = SqsHttpService::new(Arc::new(sqs_handler), sqs_http_config);
-        services.push(Box::new(service::SqsServiceRouter::new(sqs_service)));
-        Some(sqs_provider)
-    } else {
-        None
-    };
-
-    // ----- SSM (register before S3: S3 is the catch-all) -----
-    #[cfg(feature = "ssm")]
-    if is_enabled("ssm") {
-        let ssm_config = SsmConfig::from_env();
-        info!(
-            ssm_skip_signature_validation = ssm_config.skip_signature_validation,
-            "initializing SSM service",
-        );
-        let ssm_provider = RustackSsm::new(ssm_config.clone());
-        let ssm_handler = RustackSsmHandler::new(Arc::new(ssm_provider));
-        let ssm_http_config = build_ssm_http_config(&ssm_config);
-        let ssm_service = SsmHttpService::new(Arc::new(ssm_handler), ssm_http_config);
-        services.push(Box::new(service::SsmServiceRouter::new(ssm_service)));
-    }
-
-    // ----- IAM (register BEFORE CloudWatch/SES/SNS: all use form-urlencoded POST) -----
-    // IAM matches on SigV4 service=iam.
-    #[cfg(feature = "iam")]
-    if is_enabled("iam") {
-        let iam_config = IamConfig::from_env();
-        info!(
-            iam_skip_signature_validation = iam_config.skip_signature_validation,
-            "initializing IAM service",
-        );
-        let iam_store = Arc::new(IamStore::new());
-        let iam_provider = RustackIam::new(iam_store, Arc::new(iam_config.clone()));
-        let iam_handler = RustackIamHandler::new(Arc::new(iam_provider));
-        let iam_http_config = build_iam_http_config(&iam_config);
-        let iam_service = IamHttpService::new(Arc::new(iam_handler), iam_http_config);
-        services.push(Box::new(service::IamServiceRouter::new(iam_service)));
-    }
-
-    // ----- CloudWatch Metrics (register BEFORE SES/SNS: all use form-urlencoded POST) -----
-    // CloudWatch matches on SigV4 service=monitoring.
-    #[cfg(feature = "cloudwatch")]
-    if is_enabled("cloudwatch") {
-        let cw_config = CloudWatchConfig::from_env();
-        info!(
-            cloudwatch_skip_signature_validation = cw_config.skip_signature_validation,
-            "initializing CloudWatch Metrics service",
-        );
-        let cw_provider = RustackCloudWatch::new(Arc::new(cw_config.clone()));
-        let cw_handler = RustackCloudWatchHandler::new(Arc::new(cw_provider));
-        let cw_http_config = build_cloudwatch_http_config(&cw_config);
-        let cw_service = CloudWatchHttpService::new(Arc::new(cw_handler), cw_http_config);
-        services.push(Box::new(service::CloudWatchServiceRouter::new(cw_service)));
-    }
-
-    // ----- SES (register BEFORE SNS: both use form-urlencoded POST) -----
-    #[cfg(feature = "ses")]
-    if is_enabled("ses") {
-        let ses_config = SesConfig::from_env();
-        info!(
-            ses_skip_signature_validation = ses_config.skip_signature_validation,
-            ses_require_verified_identity = ses_config.require_verified_identity,
-            "initializing SES service",
-        );
-        let ses_provider = RustackSes::new(ses_config.clone());
-        let ses_handler = Arc::new(RustackSesHandler::new(Arc::new(ses_provider)));
-        let ses_http_config = build_ses_http_config(&ses_config);
-        let ses_v1_service = SesHttpService::new(Arc::clone(&ses_handler), ses_http_config);
-        let ses_v2_service = SesV2HttpService::new(Arc::clone(&ses_handler));
-        services.push(Box::new(service::SesServiceRouter::new(
-            ses_v1_service,
-            ses_v2_service,
-        )));
-    }
-
-    // ----- STS (register BEFORE SNS: both use form-urlencoded POST) -----
-    // STS matches on SigV4 service=sts.
-    #[cfg(feature = "sts")]
-    if is_enabled("sts") {
-        let sts_config = StsConfig::from_env();
-        info!(
-            sts_skip_signature_validation = sts_config.skip_signature_validation,
-            "initializing STS service",
-        );
-        let sts_provider = RustackSts::new(sts_config.clone());
-        let sts_handler = RustackStsHandler::new(Arc::new(sts_provider));
-        let sts_http_config = build_sts_http_config(&sts_config);
-        let sts_service = StsHttpService::new(Arc::new(sts_handler), sts_http_config);
-        services.push(Box::new(service::StsServiceRouter::new(sts_service)));
-    }
-
-    // ----- SNS (register before S3: S3 is the catch-all) -----
-    #[cfg(feature = "sns")]
-    if is_enabled("sns") {
-        let sns_config = SnsConfig::from_env();
-        info!(
-            sns_skip_signature_validation = sns_config.skip_signature_validation,
-            "initializing SNS service",
-        );
-        let sqs_publisher: Arc<dyn rustack_sns_core::publisher::SqsPublisher> =
-            if let Some(ref sqs) = sqs_provider_arc {
-                Arc::new(RustackSqsPublisher::new(
-                    Arc::clone(sqs),
-                    sns_config.clone(),
-                ))
-            } else {
-                Arc::new(rustack_sns_core::publisher::NoopSqsPublisher)
-            };
-        let sns_provider = RustackSns::new(sns_config.clone(), sqs_publisher);
-        let sns_handler = RustackSnsHandler::new(Arc::new(sns_provider));
-        let sns_http_config = build_sns_http_config(&sns_config);
-        let sns_service = SnsHttpService::new(Arc::new(sns_handler), sns_http_config);
-        services.push(Box::new(service::SnsServiceRouter::new(sns_service)));
-    }
-
-    // ----- EventBridge (register before S3: S3 is the catch-all) -----
-    #[cfg(feature = "events")]
-    if is_enabled("events") {
-        let events_config = EventsConfig::from_env();
-        info!(
-            events_skip_signature_validation = events_config.skip_signature_validation,
-            "initializing EventBridge service",
-        );
-        let delivery: Arc<dyn rustack_events_core::delivery::TargetDelivery> =
-            if let Some(ref sqs) = sqs_provider_arc {
-                Arc::new(LocalTargetDelivery::new(
-                    Arc::clone(sqs),
-                    events_config.account_id.clone(),
-                    events_config.host.clone(),
-                    events_config.port,
-                ))
-            } else {
-                Arc::new(rustack_events_core::delivery::NoopTargetDelivery)
-            };
-        let events_provider = RustackEvents::new(events_config.clone(), delivery);
-        let events_handler = RustackEventsHandler::new(Arc::new(events_provider));
-        let events_http_config = build_events_http_config(&events_config);
-        let events_service = EventsHttpService::new(Arc::new(events_handler), events_http_config);
-        services.push(Box::new(service::EventsServiceRouter::new(events_service)));
-    }
-
-    // ----- CloudWatch Logs (register before S3: S3 is the catch-all) -----
-    #[cfg(feature = "logs")]
-    if is_enabled("logs") {
-        let logs_config = LogsConfig::from_env();
-        info!(
-            logs_skip_signature_validation = logs_config.skip_signature_validation,
-            "initializing CloudWatch Logs service",
-        );
-        let logs_provider = RustackLogs::new(logs_config.clone());
-        let logs_handler = RustackLogsHandler::new(Arc::new(logs_provider));
-        let logs_http_config = build_logs_http_config(&logs_config);
-        let logs_service = LogsHttpService::new(Arc::new(logs_handler), logs_http_config);
-        services.push(Box::new(service::LogsServiceRouter::new(logs_service)));
-    }
-
-    // ----- KMS (register before S3: S3 is the catch-all) -----
-    #[cfg(feature = "kms")]
-    if is_enabled("kms") {
-        let kms_config = KmsConfig::from_env();
-        info!(
-            kms_skip_signature_validation = kms_config.skip_signature_validation,
-            "initializing KMS service",
-        );
-        let kms_provider = RustackKms::new(kms_config.clone());
-        let kms_handler = RustackKmsHandler::new(Arc::new(kms_provider));
-        let kms_http_config = build_kms_http_config(&kms_config);
-        let kms_service = KmsHttpService::new(Arc::new(kms_handler), kms_http_config);
-        services.push(Box::new(service::KmsServiceRouter::new(kms_service)));
-    }
-
-    // ----- Kinesis (register before S3: S3 is the catch-all) -----
-    #[cfg(feature = "kinesis")]
-    if is_enabled("kinesis") {
-        let kinesis_config = KinesisConfig::from_env();
-        info!(
-            kinesis_skip_signature_validation = kinesis_config.skip_signature_validation,
-            "initializing Kinesis service",
-        );
-        let kinesis_provider = RustackKinesis::new(kinesis_config.clone());
-        let kinesis_handler = RustackKinesisHandler::new(Arc::new(kinesis_provider));
-        let kinesis_http_config = build_kinesis_http_config(&kinesis_config);
-        let kinesis_service =
-            KinesisHttpService::new(Arc::new(kinesis_handler), kinesis_http_config);
-        services.push(Box::new(service::KinesisServiceRouter::new(
-            kinesis_service,
-        )));
-    }
-
-    // ----- Secrets Manager (register before S3: S3 is the catch-all) -----
-    #[cfg(feature = "secretsmanager")]
-    if is_enabled("secretsmanager") {
-        let sm_config = SecretsManagerConfig::from_env();
-        info!(
-            secretsmanager_skip_signature_validation = sm_config.skip_signature_validation,
-            "initializing Secrets Manager service",
-        );
-        let sm_provider = RustackSecretsManager::new(sm_config.clone());
-        let sm_handler = RustackSecretsManagerHandler::new(Arc::new(sm_provider));
-        let sm_http_config = build_secretsmanager_http_config(&sm_config);
-        let sm_service = SecretsManagerHttpService::new(Arc::new(sm_handler), sm_http_config);
-        services.push(Box::new(service::SecretsManagerServiceRouter::new(
-            sm_service,
-        )));
-    }
-
-    // ----- API Gateway v2 (register before Lambda and S3: S3 is the catch-all) -----
-    #[cfg(feature = "apigatewayv2")]
-    if is_enabled("apigatewayv2") {
-        let apigw_config = ApiGatewayV2Config::from_env();
-        info!(
-            apigatewayv2_skip_signature_validation = apigw_config.skip_signature_validation,
-            "initializing API Gateway v2 service",
-        );
-        let apigw_provider = Arc::new(RustackApiGatewayV2::new(apigw_config.clone()));
-        let apigw_handler = RustackApiGatewayV2Handler::new(Arc::clone(&apigw_provider));
-        let apigw_http_config = build_apigatewayv2_http_config(&apigw_config);
-        let apigw_service =
-            ApiGatewayV2HttpService::new(Arc::new(apigw_handler), apigw_http_config);
-        services.push(Box::new(service::ApiGatewayV2ManagementRouter::new(
-            apigw_service,
-        )));
-        services.push(Box::new(service::ApiGatewayV2ExecutionRouter::new(
-            Arc::clone(&apigw_provider),
-        )));
-    }
-
-    // ----- Lambda (register before S3: S3 is the catch-all) -----
-    #[cfg(feature = "lambda")]
-    if is_enabled("lambda") {
-        let lambda_config = LambdaConfig::from_env();
-        info!(
-            lambda_skip_signature_validation = lambda_config.skip_signature_validation,
-            lambda_docker_enabled = lambda_config.docker_enabled,
-            lambda_executor = ?lambda_config.executor,
-            "initializing Lambda service",
-        );
-        let lambda_provider = RustackLambda::new(lambda_config.clone());
-        let lambda_handler = RustackLambdaHandler::new(Arc::new(lambda_provider));
-        let lambda_http_config = build_lambda_http_config(&lambda_config);
-        let lambda_service = LambdaHttpService::new(Arc::new(lambda_handler), lambda_http_config);
-        services.push(Box::new(service::LambdaServiceRouter::new(lambda_service)));
-    }
-
-    // ----- S3 (catch-all, must be last) -----
-    // Build the S3 provider early so that the CloudFront data plane can share it.
-    #[cfg(feature = "s3")]
-    let s3_provider_arc: Option<Arc<RustackS3>> = if is_enabled("s3") {
-        let s3_config = S3Config::from_env();
-        info!(
-            s3_domain = %s3_config.s3_domain,
-            s3_virtual_hosting = s3_config.s3_virtual_hosting,
-            s3_skip_signature_validation = s3_config.s3_skip_signature_validation,
-            "initializing S3 service",
-        );
-        Some(Arc::new(RustackS3::new(s3_config)))
-    } else {
-        None
-    };
-
-    // ----- CloudFront (management + data plane, register before S3 catch-all) -----
-    #[cfg(feature = "cloudfront")]
-    if is_enabled("cloudfront") {
-        let cf_config = CloudFrontConfig::from_env();
-        info!(
-            cloudfront_skip_signature_validation = cf_config.skip_signature_validation,
-            cloudfront_domain_suffix = %cf_config.domain_suffix,
-            "initializing CloudFront service",
-        );
-        let cf_provider = Arc::new(RustackCloudFront::new(cf_config.clone()));
-        let cf_http_config = build_cloudfront_http_config(&cf_config);
-        let cf_service =
-            CloudFrontHttpService::new(Arc::new(Arc::clone(&cf_provider)), cf_http_config);
-
-        // Data plane (register first — it matches a narrower path prefix).
-        #[cfg(feature = "cloudfront-dataplane")]
-        {
-            let mut builder = DataPlane::builder()
-                .cloudfront(Arc::clone(&cf_provider))
-                .config(DataPlaneConfig::from_env());
-            if let Some(s3) = s3_provider_arc.as_ref() {
-                builder = builder.s3(Arc::clone(s3));
-            }
-            match builder.build() {
-                Ok(plane) => {
-                    services.push(Box::new(service::CloudFrontDataPlaneRouter::new(plane)));
-                }
-                Err(e) => warn!(error = %e, "failed to initialise CloudFront data plane"),
-            }
-        }
-
-        services.push(Box::new(service::CloudFrontServiceRouter::new(cf_service)));
-    }
-
-    // Register S3 last if enabled.
-    #[cfg(feature = "s3")]
-    if let Some(s3_provider) = s3_provider_arc {
-        let s3_config = S3Config::from_env();
-        let s3_handler = handler::RustackHandler((*s3_provider).clone());
-        let s3_http_config = build_s3_http_config(&s3_config);
-        let s3_service = S3HttpService::new(s3_handler, s3_http_config);
-        services.push(Box::new(service::S3ServiceRouter::new(s3_service)));
-    }
-
-    services
+    config::var("LOG_LEVEL").unwrap_or_else(|| "info".to_string())
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Parse CLI flags once and dispatch. Help / version are handled before
     // any tracing or config work so they're cheap and side-effect free.
-    match classify_args(std::env::args()) {
+    let action = classify_args(std::env::args());
+    match action {
         CliAction::Help => {
             print!("{}", help_text());
             return Ok(());
@@ -1165,137 +330,82 @@ async fn main() -> Result<()> {
             eprint!("{}", help_text());
             std::process::exit(2);
         }
-        CliAction::HealthCheck => {
-            let listen_addr = gateway_listen_addr();
-            let addr = listen_addr.replace("0.0.0.0", "127.0.0.1");
-            let healthy = run_health_check(&addr).await.is_ok();
-            std::process::exit(i32::from(!healthy));
+        CliAction::MissingValue(flag) => {
+            eprintln!("error: flag '{flag}' requires a value\n");
+            eprint!("{}", help_text());
+            std::process::exit(2);
         }
-        CliAction::Run => {}
+        _ => {}
     }
 
-    let listen_addr = gateway_listen_addr();
-    let log = log_level();
-    init_tracing(&log)?;
-
-    let enabled = parse_enabled_services();
-
-    // Warn about services that are requested but not compiled in.
-    for name in &enabled {
-        if !is_compiled_in(name) {
-            warn!(service = %name, "requested service is not compiled in, skipping");
+    let config_file = load_config_file()?;
+    match action {
+        CliAction::PrintConfig => {
+            for warning in config_file.iter().flat_map(|(_, warnings)| warnings) {
+                eprintln!("warning: {warning}");
+            }
+            print!("{}", effective_config()?);
+            return Ok(());
         }
+        CliAction::HealthCheck => {
+            let (addr, tls) = health_check_target();
+            if let Err(e) = run_health_check(&addr, tls).await {
+                eprintln!("unhealthy: {e:#}");
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        _ => {}
     }
 
-    let services = build_services(|name| enabled.iter().any(|s| s == name) && is_compiled_in(name));
-
-    if services.is_empty() {
-        anyhow::bail!(
-            "no services enabled. Check the SERVICES environment variable and compiled feature \
-             flags."
-        );
+    let telemetry = init_tracing(&log_level())?;
+    telemetry.log_status();
+    if let Some((path, warnings)) = &config_file {
+        info!(path = %path, "loaded configuration file");
+        for warning in warnings {
+            warn!(path = %path, "{warning}");
+        }
     }
-
-    let gateway = GatewayService::new(services);
-    let service_names = gateway.service_names();
-
-    let addr: SocketAddr = listen_addr
-        .parse()
-        .with_context(|| format!("invalid bind address: {listen_addr}"))?;
-
-    let listener = TcpListener::bind(addr)
-        .await
-        .with_context(|| format!("failed to bind to {addr}"))?;
-
-    info!(
-        %addr,
-        services = ?service_names,
-        version = VERSION,
-        "starting Rustack Server",
-    );
-
-    serve(listener, gateway).await
+    init_file_credentials()?;
+    let server = ServerBuilder::from_env()?.start().await?;
+    // LocalStack's readiness line, which testcontainers waits for on stdout.
+    println!("{READY_MESSAGE}");
+
+    // Serve until Ctrl-C or SIGTERM, then drain in-flight requests. A second
+    // signal aborts them.
+    shutdown_signal().await;
+    info!("received shutdown signal, draining connections (repeat to exit immediately)");
+    let result = server
+        .shutdown_or_abort(async {
+            shutdown_signal().await;
+            warn!("received second shutdown signal, aborting connections");
+        })
+        .await;
+    telemetry.shutdown();
+    result
+}
+
+/// Wait for Ctrl-C, or SIGTERM on Unix (as sent by `docker stop`).
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
+            }
+            return;
+        }
+    }
+    tokio::signal::ctrl_c().await.ok();
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_should_parse_services_value_default() {
-        // Empty string = all compiled-in services.
-        let services = parse_services_value("");
-        if cfg!(feature = "s3") {
-            assert!(services.contains(&"s3".to_string()));
-        }
-        if cfg!(feature = "dynamodb") {
-            assert!(services.contains(&"dynamodb".to_string()));
-        }
-    }
-
-    #[test]
-    fn test_should_parse_services_value_explicit() {
-        let services = parse_services_value("dynamodb");
-        assert_eq!(services, vec!["dynamodb"]);
-    }
-
-    #[test]
-    fn test_should_parse_services_value_multiple() {
-        let services = parse_services_value("s3, dynamodb");
-        assert_eq!(services, vec!["s3", "dynamodb"]);
-    }
-
-    #[test]
-    fn test_should_parse_services_value_whitespace() {
-        // Whitespace-only = all compiled-in services.
-        let services = parse_services_value("  ");
-        assert!(!services.is_empty());
-    }
-
-    #[test]
-    fn test_should_parse_services_value_case_insensitive() {
-        let services = parse_services_value("S3,DynamoDB");
-        assert_eq!(services, vec!["s3", "dynamodb"]);
-    }
-
-    #[test]
-    fn test_should_detect_compiled_services() {
-        assert_eq!(is_compiled_in("s3"), cfg!(feature = "s3"));
-        assert_eq!(is_compiled_in("dynamodb"), cfg!(feature = "dynamodb"));
-        assert_eq!(is_compiled_in("sqs"), cfg!(feature = "sqs"));
-        assert_eq!(is_compiled_in("ssm"), cfg!(feature = "ssm"));
-        assert_eq!(is_compiled_in("sns"), cfg!(feature = "sns"));
-        assert_eq!(is_compiled_in("lambda"), cfg!(feature = "lambda"));
-        assert_eq!(is_compiled_in("events"), cfg!(feature = "events"));
-        assert_eq!(is_compiled_in("logs"), cfg!(feature = "logs"));
-        assert_eq!(is_compiled_in("kms"), cfg!(feature = "kms"));
-        assert_eq!(is_compiled_in("kinesis"), cfg!(feature = "kinesis"));
-        assert_eq!(
-            is_compiled_in("secretsmanager"),
-            cfg!(feature = "secretsmanager")
-        );
-        assert_eq!(is_compiled_in("ses"), cfg!(feature = "ses"));
-        assert_eq!(
-            is_compiled_in("apigatewayv2"),
-            cfg!(feature = "apigatewayv2")
-        );
-    }
-
-    #[cfg(feature = "s3")]
-    #[test]
-    fn test_should_build_s3_http_config_from_s3_config() {
-        let config = S3Config::from_env();
-        let http_config = build_s3_http_config(&config);
-
-        assert_eq!(http_config.domain, config.s3_domain);
-        assert_eq!(http_config.virtual_hosting, config.s3_virtual_hosting);
-        assert_eq!(
-            http_config.skip_signature_validation,
-            config.s3_skip_signature_validation
-        );
-        assert_eq!(http_config.region, config.default_region);
-    }
-
     #[test]
     fn test_should_classify_no_args_as_run() {
         assert_eq!(classify_args(["rustack"]), CliAction::Run);
@@ -1346,6 +456,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_should_classify_print_config_flag() {
+        assert_eq!(
+            classify_args(["rustack", "--print-config"]),
+            CliAction::PrintConfig
+        );
+        assert_eq!(
+            classify_args(["rustack", "--print-config", "--health-check"]),
+            CliAction::HealthCheck
+        );
+    }
+
+    #[test]
+    fn test_should_read_config_flag_value() {
+        let args = ["rustack", "--config", "rustack.toml", "--print-config"];
+        assert_eq!(classify_args(args), CliAction::PrintConfig);
+        assert_eq!(config_flag(args).as_deref(), Some("rustack.toml"));
+        assert_eq!(
+            config_flag(["rustack", "--config=/etc/rustack.toml"]).as_deref(),
+            Some("/etc/rustack.toml")
+        );
+        assert_eq!(config_flag(["rustack"]), None);
+        assert_eq!(
+            classify_args(["rustack", "--config"]),
+            CliAction::MissingValue("--config".to_owned())
+        );
+    }
+
     #[test]
     fn test_help_text_includes_binary_name_and_version() {
         let text = help_text();
@@ -1354,6 +492,8 @@ mod tests {
         assert!(text.contains("--help"));
         assert!(text.contains("--version"));
         assert!(text.contains("--health-check"));
+        assert!(text.contains("--config"));
+        assert!(text.contains("--print-config"));
         assert!(text.contains("GATEWAY_LISTEN"));
     }
 
@@ -1361,17 +501,4 @@ mod tests {
     fn test_version_text_is_name_and_version() {
         assert_eq!(version_text(), format!("{BIN_NAME} {VERSION}\n"));
     }
-
-    #[cfg(feature = "dynamodb")]
-    #[test]
-    fn test_should_build_dynamodb_http_config_from_dynamodb_config() {
-        let config = DynamoDBConfig::from_env();
-        let http_config = build_dynamodb_http_config(&config);
-
-        assert_eq!(
-            http_config.skip_signature_validation,
-            config.skip_signature_validation
-        );
-        assert_eq!(http_config.region, config.default_region);
-    }
 }
@@ -0,0 +1,535 @@
+//! Prometheus metrics for the gateway.
+//!
+//! When `METRICS_ENABLED` is set, the gateway records every routed request in a
+//! [`GatewayMetrics`] registry and serves the result on `GET /metrics` in the
+//! Prometheus text exposition format (version 0.0.4).
+//!
+//! Recorded families:
+//!
+//! | Metric | Type | Labels |
+//! |--------|------|--------|
+//! | `rustack_requests_total` | counter | `service`, `operation`, `status_class` |
+//! | `rustack_request_duration_seconds` | histogram | `service`, `operation` |
+//! | `rustack_s3_object_size_bytes` | histogram | *(none)* |
+//!
+//! Point-in-time gauges (bucket count, object count, stored bytes) are pulled
+//! from registered [`GaugeSource`]s at scrape time rather than tracked
+//! incrementally, so they always reflect the current service state.
+
+use std::{
+    fmt::Write,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use dashmap::DashMap;
+
+/// Path on which the gateway serves metrics when enabled.
+pub const METRICS_PATH: &str = "/metrics";
+
+/// Latency histogram bucket bounds, in nanoseconds (1ms .. 10s).
+const LATENCY_BUCKETS_NANOS: [u64; 12] = [
+    1_000_000,
+    5_000_000,
+    10_000_000,
+    25_000_000,
+    50_000_000,
+    100_000_000,
+    250_000_000,
+    500_000_000,
+    1_000_000_000,
+    2_500_000_000,
+    5_000_000_000,
+    10_000_000_000,
+];
+
+/// Object size histogram bucket bounds, in bytes (1 KiB .. 1 GiB).
+const SIZE_BUCKETS_BYTES: [u64; 11] = [
+    1 << 10,
+    1 << 12,
+    1 << 14,
+    1 << 16,
+    1 << 18,
+    1 << 20,
+    1 << 22,
+    1 << 24,
+    1 << 26,
+    1 << 28,
+    1 << 30,
+];
+
+/// Nanoseconds per second, used to render latency sums and bounds in seconds.
+const NANOS_PER_SECOND: f64 = 1_000_000_000.0;
+
+/// A source of point-in-time gauge values rendered on every scrape.
+pub trait GaugeSource: Send + Sync {
+    /// Append gauge families in Prometheus text format to `out`.
+    fn write_gauges(&self, out: &mut String);
+}
+
+/// HTTP status class used as a low-cardinality request label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum StatusClass {
+    Informational,
+    Success,
+    Redirection,
+    ClientError,
+    ServerError,
+}
+
+impl StatusClass {
+    fn from_status(status: http::StatusCode) -> Self {
+        match status.as_u16() {
+            100..=199 => Self::Informational,
+            200..=299 => Self::Success,
+            300..=399 => Self::Redirection,
+            400..=499 => Self::ClientError,
+            _ => Self::ServerError,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Informational => "1xx",
+            Self::Success => "2xx",
+            Self::Redirection => "3xx",
+            Self::ClientError => "4xx",
+            Self::ServerError => "5xx",
+        }
+    }
+}
+
+/// A fixed-bucket histogram over integer observations.
+///
+/// Observations are recorded in raw integer units (nanoseconds, bytes) and
+/// scaled only when rendered, so recording stays lock-free.
+#[derive(Debug)]
+struct Histogram {
+    bounds: &'static [u64],
+    /// Non-cumulative count per bucket; the final slot is the `+Inf` overflow.
+    buckets: Vec<AtomicU64>,
+    sum: AtomicU64,
+    count: AtomicU64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [u64]) -> Self {
+        Self {
+            bounds,
+            buckets: (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect(),
+            sum: AtomicU64::new(0),
+            count: AtomicU64::new(0),
+        }
+    }
+
+    fn observe(&self, value: u64) {
+        let idx = self.bounds.partition_point(|&bound| bound < value);
+        self.buckets[idx].fetch_add(1, Ordering::Relaxed);
+        self.sum.fetch_add(value, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Render `_bucket`, `_sum`, and `_count` samples for this histogram.
+    ///
+    /// `labels` is a pre-rendered label list (without braces), possibly empty.
+    /// Values are divided by `scale` when rendered.
+    #[allow(clippy::cast_precision_loss)]
+    fn write_samples(&self, out: &mut String, name: &str, labels: &str, scale: f64) {
+        let sep = if labels.is_empty() { "" } else { "," };
+        let mut cumulative = 0u64;
+        for (idx, bucket) in self.buckets.iter().enumerate() {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let le = self
+                .bounds
+                .get(idx)
+                .map_or_else(|| "+Inf".to_owned(), |&b| format_float(b as f64 / scale));
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{{labels}{sep}le=\"{le}\"}} {cumulative}"
+            );
+        }
+        let braces = if labels.is_empty() {
+            String::new()
+        } else {
+            format!("{{{labels}}}")
+        };
+        let sum = self.sum.load(Ordering::Relaxed) as f64 / scale;
+        let _ = writeln!(out, "{name}_sum{braces} {}", format_float(sum));
+        let _ = writeln!(
+            out,
+            "{name}_count{braces} {}",
+            self.count.load(Ordering::Relaxed)
+        );
+    }
+}
+
+/// Request counters, latency histograms, and gauge sources for the gateway.
+pub struct GatewayMetrics {
+    requests: DashMap<(&'static str, &'static str, StatusClass), AtomicU64>,
+    latency: DashMap<(&'static str, &'static str), Histogram>,
+    object_sizes: Histogram,
+    gauge_sources: Vec<Box<dyn GaugeSource>>,
+}
+
+impl std::fmt::Debug for GatewayMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GatewayMetrics")
+            .field("request_series", &self.requests.len())
+            .field("gauge_sources", &self.gauge_sources.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl GatewayMetrics {
+    /// Create an empty registry that pulls gauges from `gauge_sources` on scrape.
+    pub fn new(gauge_sources: Vec<Box<dyn GaugeSource>>) -> Self {
+        Self {
+            requests: DashMap::new(),
+            latency: DashMap::new(),
+            object_sizes: Histogram::new(&SIZE_BUCKETS_BYTES),
+            gauge_sources,
+        }
+    }
+
+    /// Record a completed request.
+    ///
+    /// `payload_len` is the request body size declared by the client; it is
+    /// only observed for successful S3 object uploads.
+    pub fn record_request(
+        &self,
+        service: &'static str,
+        operation: &'static str,
+        status: http::StatusCode,
+        elapsed: Duration,
+        payload_len: Option<u64>,
+    ) {
+        let class = StatusClass::from_status(status);
+        self.requests
+            .entry((service, operation, class))
+            .or_insert_with(|| AtomicU64::new(0))
+            .fetch_add(1, Ordering::Relaxed);
+
+        let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+        self.latency
+            .entry((service, operation))
+            .or_insert_with(|| Histogram::new(&LATENCY_BUCKETS_NANOS))
+            .observe(nanos);
+
+        if class == StatusClass::Success && is_object_upload(service, operation) {
+            if let Some(len) = payload_len {
+                self.object_sizes.observe(len);
+            }
+        }
+    }
+
+    /// Render all metric families in Prometheus text format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        let mut requests: Vec<_> = self
+            .requests
+            .iter()
+            .map(|e| (*e.key(), e.value().load(Ordering::Relaxed)))
+            .collect();
+        requests.sort_unstable_by_key(|(key, _)| *key);
+        out.push_str("# HELP rustack_requests_total Total number of requests handled.\n");
+        out.push_str("# TYPE rustack_requests_total counter\n");
+        for ((service, operation, class), value) in requests {
+            let _ = writeln!(
+                out,
+                "rustack_requests_total{{service=\"{}\",operation=\"{}\",status_class=\"{}\"}} \
+                 {value}",
+                escape_label(service),
+                escape_label(operation),
+                class.as_str(),
+            );
+        }
+
+        let mut keys: Vec<_> = self.latency.iter().map(|e| *e.key()).collect();
+        keys.sort_unstable();
+        out.push_str("# HELP rustack_request_duration_seconds Request latency in seconds.\n");
+        out.push_str("# TYPE rustack_request_duration_seconds histogram\n");
+        for key in keys {
+            if let Some(hist) = self.latency.get(&key) {
+                let labels = format!(
+                    "service=\"{}\",operation=\"{}\"",
+                    escape_label(key.0),
+                    escape_label(key.1),
+                );
+                hist.write_samples(
+                    &mut out,
+                    "rustack_request_duration_seconds",
+                    &labels,
+                    NANOS_PER_SECOND,
+                );
+            }
+        }
+
+        out.push_str(
+            "# HELP rustack_s3_object_size_bytes Size of uploaded S3 objects and parts.\n",
+        );
+        out.push_str("# TYPE rustack_s3_object_size_bytes histogram\n");
+        self.object_sizes
+            .write_samples(&mut out, "rustack_s3_object_size_bytes", "", 1.0);
+
+        for source in &self.gauge_sources {
+            source.write_gauges(&mut out);
+        }
+
+        out
+    }
+}
+
+/// Whether a request carries an S3 object body worth recording in the size histogram.
+fn is_object_upload(service: &str, operation: &str) -> bool {
+    service == "s3" && matches!(operation, "PutObject" | "UploadPart")
+}
+
+/// Extract the declared payload size of a request.
+///
+/// Prefers `x-amz-decoded-content-length` (aws-chunked uploads) over
+/// `Content-Length`, which would include chunk signatures.
+pub fn declared_payload_len(headers: &http::HeaderMap) -> Option<u64> {
+    headers
+        .get("x-amz-decoded-content-length")
+        .or_else(|| headers.get(http::header::CONTENT_LENGTH))
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+}
+
+/// Write a single unlabelled gauge family.
+pub fn write_gauge(out: &mut String, name: &str, help: &str, value: u64) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} gauge");
+    let _ = writeln!(out, "{name} {value}");
+}
+
+/// Write a gauge family with one sample per `(label value, value)` pair.
+pub fn write_labelled_gauge<'a>(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    label: &str,
+    samples: impl IntoIterator<Item = (&'a str, u64)>,
+) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} gauge");
+    for (label_value, value) in samples {
+        let _ = writeln!(
+            out,
+            "{name}{{{label}=\"{}\"}} {value}",
+            escape_label(label_value)
+        );
+    }
+}
+
+/// Escape a label value per the Prometheus text format.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Format a float without a trailing `.0` for whole numbers.
+fn format_float(value: f64) -> String {
+    let formatted = format!("{value}");
+    formatted
+        .strip_suffix(".0")
+        .map_or(formatted.clone(), str::to_owned)
+}
+
+// ---------------------------------------------------------------------------
+// S3 gauges
+// ---------------------------------------------------------------------------
+
+/// Bucket, object, and storage gauges backed by the live S3 service state.
+#[cfg(feature = "s3")]
+#[derive(Debug)]
+pub struct S3StorageGauges(pub std::sync::Arc<rustack_s3_core::RustackS3>);
+
+#[cfg(feature = "s3")]
+impl GaugeSource for S3StorageGauges {
+    fn write_gauges(&self, out: &mut String) {
+        // Uploads a lifecycle rule has expired no longer count as in progress.
+        let _ = self.0.abort_expired_multipart_uploads();
+        let stats = self.0.all_bucket_stats();
+        let total_objects = stats.iter().map(|b| b.object_count).sum();
+        let total_bytes = stats.iter().map(|b| b.stored_bytes).sum();
+        let total_uploads = stats.iter().map(|b| b.multipart_upload_count).sum();
+
+        write_gauge(
+            out,
+            "rustack_s3_buckets",
+            "Number of S3 buckets.",
+            stats.len() as u64,
+        );
+        write_gauge(
+            out,
+            "rustack_s3_objects",
+            "Number of current S3 objects across all buckets.",
+            total_objects,
+        );
+        write_gauge(
+            out,
+            "rustack_s3_stored_bytes",
+            "Bytes stored across all S3 object versions.",
+            total_bytes,
+        );
+        write_gauge(
+            out,
+            "rustack_s3_multipart_uploads",
+            "Number of in-progress S3 multipart uploads.",
+            total_uploads,
+        );
+        write_gauge(
+            out,
+            "rustack_s3_multipart_part_bytes",
+            "Bytes stored for the parts of in-progress S3 multipart uploads.",
+            self.0.storage().part_bytes(),
+        );
+        let storage = self.0.storage().stats();
+        write_gauge(
+            out,
+            "rustack_s3_storage_memory_bytes",
+            "Bytes of S3 object and part data held in memory.",
+            storage.memory_bytes,
+        );
+        write_gauge(
+            out,
+            "rustack_s3_storage_disk_bytes",
+            "Bytes of S3 object and part data spilled to disk.",
+            storage.disk_bytes,
+        );
+        write_labelled_gauge(
+            out,
+            "rustack_s3_bucket_objects",
+            "Number of current objects per S3 bucket.",
+            "bucket",
+            stats.iter().map(|b| (b.name.as_str(), b.object_count)),
+        );
+        write_labelled_gauge(
+            out,
+            "rustack_s3_bucket_stored_bytes",
+            "Bytes stored per S3 bucket across all object versions.",
+            "bucket",
+            stats.iter().map(|b| (b.name.as_str(), b.stored_bytes)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_classify_status_codes() {
+        assert_eq!(
+            StatusClass::from_status(http::StatusCode::OK).as_str(),
+            "2xx"
+        );
+        assert_eq!(
+            StatusClass::from_status(http::StatusCode::NOT_FOUND).as_str(),
+            "4xx"
+        );
+        assert_eq!(
+            StatusClass::from_status(http::StatusCode::SERVICE_UNAVAILABLE).as_str(),
+            "5xx"
+        );
+    }
+
+    #[test]
+    fn test_should_render_request_counters_and_latency() {
+        let metrics = GatewayMetrics::new(Vec::new());
+        metrics.record_request(
+            "dynamodb",
+            "PutItem",
+            http::StatusCode::OK,
+            Duration::from_millis(3),
+            None,
+        );
+        metrics.record_request(
+            "dynamodb",
+            "PutItem",
+            http::StatusCode::BAD_REQUEST,
+            Duration::from_millis(3),
+            None,
+        );
+
+        let text = metrics.render();
+        let series = r#"service="dynamodb",operation="PutItem""#;
+        assert!(text.contains(&format!(
+            "rustack_requests_total{{{series},status_class=\"2xx\"}} 1"
+        )));
+        assert!(text.contains(&format!(
+            "rustack_requests_total{{{series},status_class=\"4xx\"}} 1"
+        )));
+        assert!(text.contains(&format!(
+            "rustack_request_duration_seconds_bucket{{{series},le=\"0.005\"}} 2"
+        )));
+        assert!(text.contains(&format!(
+            "rustack_request_duration_seconds_count{{{series}}} 2"
+        )));
+    }
+
+    #[test]
+    fn test_should_observe_object_sizes_only_for_successful_uploads() {
+        let metrics = GatewayMetrics::new(Vec::new());
+        let elapsed = Duration::from_millis(1);
+        metrics.record_request("s3", "PutObject", http::StatusCode::OK, elapsed, Some(2048));
+        metrics.record_request(
+            "s3",
+            "PutObject",
+            http::StatusCode::FORBIDDEN,
+            elapsed,
+            Some(2048),
+        );
+        metrics.record_request("s3", "GetObject", http::StatusCode::OK, elapsed, Some(10));
+
+        let text = metrics.render();
+        assert!(text.contains("rustack_s3_object_size_bytes_count 1"));
+        assert!(text.contains("rustack_s3_object_size_bytes_sum 2048"));
+        assert!(text.contains("rustack_s3_object_size_bytes_bucket{le=\"1024\"} 0"));
+        assert!(text.contains("rustack_s3_object_size_bytes_bucket{le=\"4096\"} 1"));
+        assert!(text.contains("rustack_s3_object_size_bytes_bucket{le=\"+Inf\"} 1"));
+    }
+
+    #[test]
+    fn test_should_prefer_decoded_content_length() {
+        let mut headers = http::HeaderMap::new();
+        headers.insert(http::header::CONTENT_LENGTH, "200".parse().unwrap());
+        assert_eq!(declared_payload_len(&headers), Some(200));
+        headers.insert("x-amz-decoded-content-length", "150".parse().unwrap());
+        assert_eq!(declared_payload_len(&headers), Some(150));
+    }
+
+    #[test]
+    fn test_should_escape_label_values() {
+        assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+
+    #[cfg(feature = "s3")]
+    #[test]
+    fn test_should_render_s3_storage_gauges() {
+        use rustack_s3_core::{S3Config, state::Owner};
+
+        let provider = std::sync::Arc::new(rustack_s3_core::RustackS3::new(S3Config::default()));
+        provider
+            .state()
+            .create_bucket(
+                "metrics-bucket".to_owned(),
+                "us-east-1".to_owned(),
+                Owner::default(),
+            )
+            .unwrap_or_else(|e| panic!("create failed: {e}"));
+
+        let metrics = GatewayMetrics::new(vec![Box::new(S3StorageGauges(provider))]);
+        let text = metrics.render();
+        assert!(text.contains("rustack_s3_buckets 1"));
+        assert!(text.contains("rustack_s3_objects 0"));
+        assert!(text.contains("rustack_s3_bucket_objects{bucket=\"metrics-bucket\"} 0"));
+        assert!(text.contains("rustack_s3_multipart_uploads 0"));
+        assert!(text.contains("rustack_s3_multipart_part_bytes 0"));
+        assert!(text.contains("rustack_s3_storage_disk_bytes 0"));
+    }
+}
@@ -0,0 +1,257 @@
+//! Isolated service stacks selected per request.
+//!
+//! A request carrying `x-ruststack-namespace: <name>` is served by the
+//! services of that namespace instead of the server's own. Each namespace is
+//! a complete set of the enabled services with its own state, built the
+//! first time a request names it, so parallel test workers can share one
+//! server without seeing each other's buckets, tables, or queues. Requests
+//! without the header, or naming `default`, go to the server's own services.
+//!
+//! `DELETE /_ruststack/namespaces/<name>` drops a namespace and its state;
+//! requests still in flight finish against it. `GET /_ruststack/namespaces`
+//! lists the live namespaces:
+//!
+//! ```json
+//! {"namespaces":["default","worker-3"]}
+//! ```
+//!
+//! Names are 1 to 64 ASCII letters, digits, `-`, or `_`; a request naming
+//! anything else is answered with a JSON `400`. The other management
+//! endpoints (state reset, S3 scrub) act on the server's own services.
+
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use serde_json::json;
+use tokio::task::JoinHandle;
+use tracing::info;
+
+use crate::service::ServiceRouter;
+
+/// Header naming the namespace a request belongs to.
+pub const NAMESPACE_HEADER: &str = "x-ruststack-namespace";
+
+/// The namespace of requests that name none: the server's own services.
+pub const DEFAULT_NAMESPACE: &str = "default";
+
+/// Path listing the live namespaces.
+pub const NAMESPACES_PATH: &str = "/_ruststack/namespaces";
+
+/// Longest accepted namespace name.
+const MAX_NAME_LEN: usize = 64;
+
+/// The routers of one stack of services, in routing order.
+pub type Routers = Arc<Vec<Box<dyn ServiceRouter>>>;
+
+/// Builds the services of a new namespace, with the background tasks they
+/// started.
+type BuildFn = Box<dyn Fn() -> (Vec<Box<dyn ServiceRouter>>, Vec<JoinHandle<()>>) + Send + Sync>;
+
+/// The services of a namespace other than the default one.
+struct Stack {
+    routers: Routers,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl Drop for Stack {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// The namespaces behind the gateway.
+#[derive(Default)]
+pub struct Namespaces {
+    stacks: DashMap<String, Stack>,
+    build: Option<BuildFn>,
+}
+
+impl std::fmt::Debug for Namespaces {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Namespaces")
+            .field("enabled", &self.build.is_some())
+            .field("live", &self.stacks.len())
+            .finish()
+    }
+}
+
+/// Why a request's namespace could not be resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidNamespace(String);
+
+impl Namespaces {
+    /// Serve namespaces with the services `build` creates for each.
+    pub fn new(
+        build: impl Fn() -> (Vec<Box<dyn ServiceRouter>>, Vec<JoinHandle<()>>) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            stacks: DashMap::new(),
+            build: Some(Box::new(build)),
+        }
+    }
+
+    /// The routers of the namespace `headers` name, creating it on first
+    /// use, or `None` for the default namespace.
+    ///
+    /// Without namespace support the header is ignored.
+    pub fn resolve(&self, headers: &http::HeaderMap) -> Result<Option<Routers>, InvalidNamespace> {
+        let Some(build) = &self.build else {
+            return Ok(None);
+        };
+        let Some(value) = headers.get(NAMESPACE_HEADER) else {
+            return Ok(None);
+        };
+        let name = value
+            .to_str()
+            .ok()
+            .filter(|name| is_valid_name(name))
+            .ok_or_else(|| InvalidNamespace(String::from_utf8_lossy(value.as_bytes()).into()))?;
+        if name == DEFAULT_NAMESPACE {
+            return Ok(None);
+        }
+
+        let stack = self.stacks.entry(name.to_owned()).or_insert_with(|| {
+            info!(namespace = name, "creating namespace");
+            let (routers, tasks) = build();
+            Stack {
+                routers: Arc::new(routers),
+                tasks,
+            }
+        });
+        Ok(Some(Arc::clone(&stack.routers)))
+    }
+
+    /// The response to a namespace management request for `path`, if it is
+    /// one.
+    pub fn management(
+        &self,
+        method: &http::Method,
+        path: &str,
+    ) -> Option<(http::StatusCode, String)> {
+        self.build.as_ref()?;
+        if *method == http::Method::GET && path == NAMESPACES_PATH {
+            let mut names: Vec<String> = self.stacks.iter().map(|s| s.key().clone()).collect();
+            names.push(DEFAULT_NAMESPACE.to_owned());
+            names.sort_unstable();
+            return Some((
+                http::StatusCode::OK,
+                json!({ "namespaces": names }).to_string(),
+            ));
+        }
+
+        let name = path.strip_prefix(NAMESPACES_PATH)?.strip_prefix('/')?;
+        if *method != http::Method::DELETE {
+            return None;
+        }
+        let response = if name == DEFAULT_NAMESPACE || !is_valid_name(name) {
+            (
+                http::StatusCode::BAD_REQUEST,
+                json!({ "error": "namespace cannot be deleted", "namespace": name }).to_string(),
+            )
+        } else if self.stacks.remove(name).is_some() {
+            info!(namespace = name, "deleted namespace");
+            (http::StatusCode::OK, json!({ "deleted": name }).to_string())
+        } else {
+            (
+                http::StatusCode::NOT_FOUND,
+                json!({ "error": "no such namespace", "namespace": name }).to_string(),
+            )
+        };
+        Some(response)
+    }
+}
+
+impl InvalidNamespace {
+    /// The JSON body of the `400` answering the request.
+    pub fn body(&self) -> String {
+        json!({
+            "error": format!(
+                "invalid {NAMESPACE_HEADER}: expected 1 to {MAX_NAME_LEN} letters, digits, '-' \
+                 or '_'"
+            ),
+            "namespace": self.0,
+        })
+        .to_string()
+    }
+}
+
+/// Whether `name` is an acceptable namespace name.
+fn is_valid_name(name: &str) -> bool {
+    (1..=MAX_NAME_LEN).contains(&name.len())
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn headers(namespace: &str) -> http::HeaderMap {
+        let mut headers = http::HeaderMap::new();
+        headers.insert(NAMESPACE_HEADER, namespace.parse().unwrap());
+        headers
+    }
+
+    #[tokio::test]
+    async fn test_should_build_each_namespace_once_until_deleted() {
+        let builds = Arc::new(AtomicUsize::new(0));
+        let namespaces = {
+            let builds = Arc::clone(&builds);
+            Namespaces::new(move || {
+                builds.fetch_add(1, Ordering::Relaxed);
+                (Vec::new(), vec![tokio::spawn(std::future::pending())])
+            })
+        };
+
+        assert!(
+            namespaces
+                .resolve(&http::HeaderMap::new())
+                .unwrap()
+                .is_none()
+        );
+        assert!(namespaces.resolve(&headers("default")).unwrap().is_none());
+        let first = namespaces.resolve(&headers("worker-3")).unwrap().unwrap();
+        let again = namespaces.resolve(&headers("worker-3")).unwrap().unwrap();
+        assert!(Arc::ptr_eq(&first, &again));
+        namespaces.resolve(&headers("worker_4")).unwrap();
+        assert_eq!(builds.load(Ordering::Relaxed), 2);
+        assert!(namespaces.resolve(&headers("a/b")).is_err());
+        assert!(namespaces.resolve(&headers(&"x".repeat(65))).is_err());
+
+        let list = namespaces.management(&http::Method::GET, NAMESPACES_PATH);
+        assert_eq!(
+            list,
+            Some((
+                http::StatusCode::OK,
+                r#"{"namespaces":["default","worker-3","worker_4"]}"#.to_owned()
+            ))
+        );
+        let delete = |name: &str| {
+            namespaces
+                .management(&http::Method::DELETE, &format!("{NAMESPACES_PATH}/{name}"))
+                .map(|(status, _)| status)
+        };
+        assert_eq!(delete("worker-3"), Some(http::StatusCode::OK));
+        assert_eq!(delete("worker-3"), Some(http::StatusCode::NOT_FOUND));
+        assert_eq!(delete("default"), Some(http::StatusCode::BAD_REQUEST));
+
+        namespaces.resolve(&headers("worker-3")).unwrap();
+        assert_eq!(builds.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn test_should_ignore_namespaces_when_disabled() {
+        let namespaces = Namespaces::default();
+        assert!(namespaces.resolve(&headers("worker-3")).unwrap().is_none());
+        assert!(namespaces.resolve(&headers("a/b")).unwrap().is_none());
+        assert_eq!(
+            namespaces.management(&http::Method::GET, NAMESPACES_PATH),
+            None
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! State reset endpoint for the gateway.
+//!
+//! `POST /_localstack/state/reset` clears the state of every service that
+//! registered a reset with [`StateReset`], leaving the server running. The
+//! response lists the services that were reset:
+//!
+//! ```json
+//! {"reset":["dynamodb","s3"]}
+//! ```
+
+use serde_json::json;
+use tracing::info;
+
+/// Path of the state reset endpoint.
+pub const RESET_PATH: &str = "/_localstack/state/reset";
+
+/// A service's state reset.
+type ResetFn = Box<dyn Fn() + Send + Sync>;
+
+/// The state resets of the services behind the gateway.
+#[derive(Default)]
+pub struct StateReset {
+    services: Vec<(&'static str, ResetFn)>,
+}
+
+impl std::fmt::Debug for StateReset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StateReset")
+            .field(
+                "services",
+                &self
+                    .services
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl StateReset {
+    /// Register `reset` as the state reset of the service `name`.
+    pub fn add(&mut self, name: &'static str, reset: impl Fn() + Send + Sync + 'static) {
+        self.services.push((name, Box::new(reset)));
+    }
+
+    /// Reset every registered service and render the response body.
+    pub fn reset_all(&self) -> String {
+        let mut names = Vec::with_capacity(self.services.len());
+        for (name, reset) in &self.services {
+            reset();
+            names.push(*name);
+        }
+        names.sort_unstable();
+        info!(services = ?names, "reset service state");
+        json!({ "reset": names }).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_should_reset_every_registered_service() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut resets = StateReset::default();
+        for name in ["s3", "dynamodb"] {
+            let calls = Arc::clone(&calls);
+            resets.add(name, move || {
+                calls.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+
+        assert_eq!(resets.reset_all(), r#"{"reset":["dynamodb","s3"]}"#);
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+    }
+}
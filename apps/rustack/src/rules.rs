@@ -0,0 +1,226 @@
+//! Runtime-managed rule sets for the gateway.
+//!
+//! Rules that tests change while the server runs, such as the S3 latency
+//! rules on [`S3_LATENCY_PATH`] and the S3 fault rules on
+//! [`S3_FAULTS_PATH`], are each served on a management path:
+//!
+//! - `GET <path>` answers with the active rules: `{"rules":[...]}`.
+//! - `PUT <path>` replaces them with the `rules` of a body of the same shape, and answers with the
+//!   new rules.
+//! - `DELETE <path>` removes every rule.
+//!
+//! A body that is not JSON of that shape, or that holds an invalid rule, is
+//! answered with a JSON `400` and leaves the rules unchanged. Rules act on
+//! the server's own services, not on other namespaces.
+
+use std::{collections::HashMap, sync::Arc};
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Limited};
+use serde_json::{Value, json};
+
+/// Path of the S3 latency injection rules.
+pub const S3_LATENCY_PATH: &str = "/_ruststack/s3/latency";
+
+/// Path of the S3 error injection rules.
+pub const S3_FAULTS_PATH: &str = "/_ruststack/s3/faults";
+
+/// Largest rules body accepted.
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// A set of rules readable and replaceable as JSON.
+pub trait RuleSet: Send + Sync {
+    /// The active rules, as a JSON array.
+    fn to_json(&self) -> Value;
+
+    /// Replace the active rules with `rules`, a JSON array.
+    ///
+    /// # Errors
+    ///
+    /// Returns why `rules` is invalid, leaving the active rules unchanged.
+    fn replace_from_json(&self, rules: Value) -> Result<(), String>;
+}
+
+#[cfg(feature = "s3")]
+impl RuleSet for rustack_s3_http::latency::LatencyInjector {
+    fn to_json(&self) -> Value {
+        serde_json::to_value(self.rules()).unwrap_or_default()
+    }
+
+    fn replace_from_json(&self, rules: Value) -> Result<(), String> {
+        let rules: Vec<rustack_s3_http::latency::LatencyRule> =
+            serde_json::from_value(rules).map_err(|e| e.to_string())?;
+        for rule in &rules {
+            rule.validate()?;
+        }
+        self.set_rules(rules);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "s3")]
+impl RuleSet for rustack_s3_http::fault::FaultInjector {
+    fn to_json(&self) -> Value {
+        serde_json::to_value(self.rules()).unwrap_or_default()
+    }
+
+    fn replace_from_json(&self, rules: Value) -> Result<(), String> {
+        let rules: Vec<rustack_s3_http::fault::FaultRule> =
+            serde_json::from_value(rules).map_err(|e| e.to_string())?;
+        for rule in &rules {
+            rule.validate()?;
+        }
+        self.set_rules(rules);
+        Ok(())
+    }
+}
+
+/// The rule sets behind the gateway, by management path.
+#[derive(Default)]
+pub struct RuleEndpoints {
+    endpoints: HashMap<&'static str, Arc<dyn RuleSet>>,
+}
+
+impl std::fmt::Debug for RuleEndpoints {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RuleEndpoints")
+            .field("paths", &self.endpoints.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl RuleEndpoints {
+    /// Serve `rules` on `path`.
+    pub fn add(&mut self, path: &'static str, rules: Arc<dyn RuleSet>) {
+        self.endpoints.insert(path, rules);
+    }
+
+    /// The rule set served on `path`, if any.
+    pub fn find(&self, path: &str) -> Option<Arc<dyn RuleSet>> {
+        self.endpoints.get(path).cloned()
+    }
+}
+
+/// Serve one request to a rule set's management path, answering with the
+/// response status and JSON body.
+pub async fn handle<B>(rules: &dyn RuleSet, req: http::Request<B>) -> (http::StatusCode, String)
+where
+    B: hyper::body::Body<Data = Bytes>,
+    B::Error: std::error::Error + Send + Sync + 'static,
+{
+    match *req.method() {
+        http::Method::GET => {}
+        http::Method::PUT => {
+            let body = match Limited::new(req.into_body(), MAX_BODY_BYTES)
+                .collect()
+                .await
+            {
+                Ok(body) => body.to_bytes(),
+                Err(e) => return bad_request(&format!("failed to read body: {e}")),
+            };
+            let new_rules = match serde_json::from_slice::<Value>(&body) {
+                Ok(Value::Object(mut body)) => body.remove("rules").unwrap_or(Value::Null),
+                Ok(_) => return bad_request("expected a JSON object with a 'rules' array"),
+                Err(e) => return bad_request(&e.to_string()),
+            };
+            if let Err(e) = rules.replace_from_json(new_rules) {
+                return bad_request(&e);
+            }
+        }
+        http::Method::DELETE => {
+            if let Err(e) = rules.replace_from_json(json!([])) {
+                return bad_request(&e);
+            }
+        }
+        _ => {
+            let body = json!({ "error": "method not allowed" }).to_string();
+            return (http::StatusCode::METHOD_NOT_ALLOWED, body);
+        }
+    }
+    let body = json!({ "rules": rules.to_json() }).to_string();
+    (http::StatusCode::OK, body)
+}
+
+/// A JSON `400` reporting `error`.
+fn bad_request(error: &str) -> (http::StatusCode, String) {
+    let body = json!({ "error": error }).to_string();
+    (http::StatusCode::BAD_REQUEST, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use http_body_util::Full;
+
+    use super::*;
+
+    /// Rules that must all be non-negative integers.
+    #[derive(Default)]
+    struct Numbers(Mutex<Vec<u64>>);
+
+    impl RuleSet for Numbers {
+        fn to_json(&self) -> Value {
+            json!(*self.0.lock().unwrap())
+        }
+
+        fn replace_from_json(&self, rules: Value) -> Result<(), String> {
+            *self.0.lock().unwrap() = serde_json::from_value(rules).map_err(|e| e.to_string())?;
+            Ok(())
+        }
+    }
+
+    async fn send(rules: &Numbers, method: http::Method, body: &str) -> (http::StatusCode, String) {
+        let req = http::Request::builder()
+            .method(method)
+            .uri(S3_LATENCY_PATH)
+            .body(Full::new(Bytes::from(body.to_owned())))
+            .unwrap();
+        handle(rules, req).await
+    }
+
+    #[tokio::test]
+    async fn test_should_get_replace_and_clear_rules() {
+        let rules = Numbers::default();
+
+        let ok = |body: &str| (http::StatusCode::OK, body.to_owned());
+        assert_eq!(
+            send(&rules, http::Method::GET, "").await,
+            ok(r#"{"rules":[]}"#)
+        );
+        assert_eq!(
+            send(&rules, http::Method::PUT, r#"{"rules":[1,2]}"#).await,
+            ok(r#"{"rules":[1,2]}"#)
+        );
+        assert_eq!(
+            send(&rules, http::Method::GET, "").await,
+            ok(r#"{"rules":[1,2]}"#)
+        );
+        assert_eq!(
+            send(&rules, http::Method::DELETE, "").await,
+            ok(r#"{"rules":[]}"#)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_should_reject_invalid_rules_without_changing_them() {
+        let rules = Numbers::default();
+        send(&rules, http::Method::PUT, r#"{"rules":[7]}"#).await;
+
+        for body in ["not json", "[7]", r#"{"rules":[-1]}"#, "{}"] {
+            let (status, _) = send(&rules, http::Method::PUT, body).await;
+            assert_eq!(status, http::StatusCode::BAD_REQUEST, "body {body}");
+        }
+        let (status, _) = send(&rules, http::Method::POST, "").await;
+        assert_eq!(status, http::StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(*rules.0.lock().unwrap(), [7]);
+    }
+
+    #[test]
+    fn test_should_find_rule_sets_by_path() {
+        let mut endpoints = RuleEndpoints::default();
+        endpoints.add(S3_LATENCY_PATH, Arc::new(Numbers::default()));
+        assert!(endpoints.find(S3_LATENCY_PATH).is_some());
+        assert!(endpoints.find("/_ruststack/s3/other").is_none());
+    }
+}
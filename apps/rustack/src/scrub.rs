@@ -0,0 +1,108 @@
+//! S3 integrity scrub endpoint for the gateway.
+//!
+//! `POST /_localstack/s3/scrub/<bucket>` re-hashes the stored data of every
+//! object version in the bucket and answers with the scrub report. Corrupt
+//! versions are flagged, so later `GetObject` calls on them fail unless
+//! `S3_SERVE_CORRUPT_OBJECTS` is set:
+//!
+//! ```json
+//! {"bucket":"data","objectsChecked":2,"bytesChecked":1048580,
+//!  "mismatches":[{"key":"big.bin","versionId":"null","reason":"MD5 mismatch: ..."}]}
+//! ```
+//!
+//! An unknown bucket is answered with a JSON `404`.
+
+use std::{future::Future, pin::Pin};
+
+use serde_json::json;
+
+/// Path prefix of the scrub endpoint; the bucket name follows it.
+pub const SCRUB_PATH_PREFIX: &str = "/_localstack/s3/scrub/";
+
+/// The outcome of a scrub: the response status and JSON body.
+pub type ScrubResponse = (http::StatusCode, String);
+
+/// Scrubs the named bucket.
+type ScrubFn =
+    Box<dyn Fn(String) -> Pin<Box<dyn Future<Output = ScrubResponse> + Send>> + Send + Sync>;
+
+/// The S3 scrub behind the gateway, if the S3 service is enabled.
+#[derive(Default)]
+pub struct BucketScrub {
+    scrub: Option<ScrubFn>,
+}
+
+impl std::fmt::Debug for BucketScrub {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BucketScrub")
+            .field("enabled", &self.scrub.is_some())
+            .finish()
+    }
+}
+
+impl BucketScrub {
+    /// Serve scrubs with `scrub`, which maps a bucket name to the response.
+    pub fn new<F, Fut>(scrub: F) -> Self
+    where
+        F: Fn(String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ScrubResponse> + Send + 'static,
+    {
+        Self {
+            scrub: Some(Box::new(move |bucket| Box::pin(scrub(bucket)))),
+        }
+    }
+
+    /// The bucket a `POST` to `path` asks to scrub, if this endpoint
+    /// serves it.
+    pub fn bucket<'p>(&self, method: &http::Method, path: &'p str) -> Option<&'p str> {
+        if self.scrub.is_none() || *method != http::Method::POST {
+            return None;
+        }
+        path.strip_prefix(SCRUB_PATH_PREFIX)
+            .filter(|bucket| !bucket.is_empty() && !bucket.contains('/'))
+    }
+
+    /// Scrub `bucket` and render the response.
+    pub fn scrub(&self, bucket: &str) -> Pin<Box<dyn Future<Output = ScrubResponse> + Send>> {
+        if let Some(scrub) = &self.scrub {
+            return scrub(bucket.to_owned());
+        }
+        let body = json!({ "error": "S3 is not enabled" }).to_string();
+        Box::pin(async move { (http::StatusCode::NOT_FOUND, body) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_should_route_scrub_requests_by_bucket() {
+        let scrub = BucketScrub::new(|bucket| async move {
+            (
+                http::StatusCode::OK,
+                json!({ "bucket": bucket }).to_string(),
+            )
+        });
+
+        let post = http::Method::POST;
+        assert_eq!(
+            scrub.bucket(&post, "/_localstack/s3/scrub/data"),
+            Some("data")
+        );
+        assert_eq!(scrub.bucket(&post, "/_localstack/s3/scrub/"), None);
+        assert_eq!(scrub.bucket(&post, "/_localstack/s3/scrub/a/b"), None);
+        assert_eq!(
+            scrub.bucket(&http::Method::GET, "/_localstack/s3/scrub/data"),
+            None
+        );
+        assert_eq!(
+            BucketScrub::default().bucket(&post, "/_localstack/s3/scrub/data"),
+            None
+        );
+
+        let (status, body) = scrub.scrub("data").await;
+        assert_eq!(status, http::StatusCode::OK);
+        assert_eq!(body, r#"{"bucket":"data"}"#);
+    }
+}
@@ -0,0 +1,436 @@
+//! Declarative seed data loaded at startup from `INIT_DATA_DIR`.
+//!
+//! The directory mirrors the state to create:
+//!
+//! ```text
+//! <INIT_DATA_DIR>/
+//!   buckets/<bucket>/<key...>   one object per file, keyed by its path under the bucket
+//!   dynamodb/<table>.json       {"schema": {...}, "items": [...]}
+//! ```
+//!
+//! A table's `schema` is a `CreateTableInput` whose `TableName` defaults to
+//! the file name, and its `items` are in attribute-value JSON, the shape
+//! `PutItem` accepts. Object content types are guessed from the file
+//! extension.
+//!
+//! Seeding runs before the gateway starts serving, so
+//! `/_localstack/init/ready` only reports ready once it has finished. A file
+//! that cannot be seeded aborts startup, unless
+//! `INIT_DATA_CONTINUE_ON_ERROR` is set, in which case it is logged and
+//! skipped.
+
+use std::path::{Path, PathBuf};
+#[cfg(feature = "dynamodb")]
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::{Context, Result};
+#[cfg(feature = "dynamodb")]
+use rustack_dynamodb_core::provider::RustackDynamoDB;
+#[cfg(feature = "dynamodb")]
+use rustack_dynamodb_model::{
+    AttributeValue, DynamoDBErrorCode,
+    input::{BatchWriteItemInput, CreateTableInput, DescribeTableInput},
+    types::{PutRequest, TableStatus, WriteRequest},
+};
+#[cfg(feature = "s3")]
+use rustack_s3_core::RustackS3;
+#[cfg(feature = "s3")]
+use rustack_s3_model::{
+    S3ErrorCode,
+    input::{CreateBucketInput, PutObjectInput},
+    request::StreamingBlob,
+};
+use tracing::{error, info};
+
+use crate::server::Providers;
+
+/// Environment variable naming the seed data directory.
+pub const INIT_DATA_DIR_ENV: &str = "INIT_DATA_DIR";
+
+/// Environment variable that keeps startup going past seed failures.
+pub const CONTINUE_ON_ERROR_ENV: &str = "INIT_DATA_CONTINUE_ON_ERROR";
+
+/// Most items `BatchWriteItem` accepts in one request.
+#[cfg(feature = "dynamodb")]
+const MAX_BATCH_WRITE_ITEMS: usize = 25;
+
+/// How often to check whether a seeded table has become active.
+#[cfg(feature = "dynamodb")]
+const TABLE_ACTIVE_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Seed data to load into the services before the gateway reports ready.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitData {
+    /// The directory holding `buckets/` and `dynamodb/`.
+    pub dir: PathBuf,
+    /// Log and skip files that cannot be seeded instead of failing startup.
+    pub continue_on_error: bool,
+}
+
+impl InitData {
+    /// Create seed data that aborts startup on the first failure.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            continue_on_error: false,
+        }
+    }
+
+    /// Load every bucket and table under [`dir`](Self::dir) into the
+    /// enabled services.
+    pub(crate) async fn load(&self, providers: &Providers) -> Result<()> {
+        let mut failed = 0;
+
+        let buckets_dir = self.dir.join("buckets");
+        for bucket_dir in entries(&buckets_dir, true).await? {
+            let result = load_bucket(providers, &bucket_dir).await;
+            self.check(&bucket_dir, result, &mut failed)?;
+        }
+
+        let tables_dir = self.dir.join("dynamodb");
+        for table_file in entries(&tables_dir, false).await? {
+            if table_file.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let result = load_table(providers, &table_file).await;
+            self.check(&table_file, result, &mut failed)?;
+        }
+
+        if failed > 0 {
+            error!(
+                dir = %self.dir.display(),
+                failed,
+                "seeded with failures, continuing because {CONTINUE_ON_ERROR_ENV} is set"
+            );
+        } else {
+            info!(dir = %self.dir.display(), "seeded initial data");
+        }
+        Ok(())
+    }
+
+    /// Log a failure to seed from `path`, and fail unless continuing on
+    /// error.
+    fn check(&self, path: &Path, result: Result<()>, failed: &mut usize) -> Result<()> {
+        let Err(e) = result else {
+            return Ok(());
+        };
+        error!(file = %path.display(), error = format!("{e:#}"), "failed to seed initial data");
+        if self.continue_on_error {
+            *failed += 1;
+            Ok(())
+        } else {
+            Err(e.context(format!("failed to seed from {}", path.display())))
+        }
+    }
+}
+
+/// Seed the bucket in `dir` into S3, if enabled.
+#[allow(clippy::unused_async, unused_variables)]
+async fn load_bucket(providers: &Providers, dir: &Path) -> Result<()> {
+    #[cfg(feature = "s3")]
+    if let Some(s3) = &providers.s3 {
+        return seed_bucket(s3, dir).await;
+    }
+    anyhow::bail!("seeding buckets requires the s3 service, which is not enabled")
+}
+
+/// Seed the table in `file` into DynamoDB, if enabled.
+#[allow(clippy::unused_async, unused_variables)]
+async fn load_table(providers: &Providers, file: &Path) -> Result<()> {
+    #[cfg(feature = "dynamodb")]
+    if let Some(dynamodb) = &providers.dynamodb {
+        return seed_table(dynamodb, file).await;
+    }
+    anyhow::bail!("seeding tables requires the dynamodb service, which is not enabled")
+}
+
+/// Create the bucket named after `dir` and upload every file under it,
+/// keyed by its path relative to `dir`.
+#[cfg(feature = "s3")]
+async fn seed_bucket(s3: &RustackS3, dir: &Path) -> Result<()> {
+    let bucket = file_name(dir)?;
+    let input = CreateBucketInput {
+        bucket: bucket.clone(),
+        ..CreateBucketInput::default()
+    };
+    match s3.handle_create_bucket(input).await {
+        // Persisted state may already hold the bucket.
+        Err(e) if e.code != S3ErrorCode::BucketAlreadyOwnedByYou => {
+            return Err(e).with_context(|| format!("cannot create bucket {bucket}"));
+        }
+        _ => {}
+    }
+
+    let files = walk_files(dir).await?;
+    for path in &files {
+        let key = path
+            .strip_prefix(dir)
+            .with_context(|| format!("{} is outside {}", path.display(), dir.display()))?
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let body = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("cannot read {}", path.display()))?;
+        let input = PutObjectInput {
+            bucket: bucket.clone(),
+            key: key.clone(),
+            body: Some(StreamingBlob::new(body)),
+            content_type: Some(content_type(path).to_owned()),
+            ..PutObjectInput::default()
+        };
+        s3.handle_put_object(input)
+            .await
+            .with_context(|| format!("cannot upload {} to s3://{bucket}/{key}", path.display()))?;
+    }
+    info!(bucket = %bucket, objects = files.len(), "seeded bucket");
+    Ok(())
+}
+
+/// A `dynamodb/<table>.json` seed file.
+#[cfg(feature = "dynamodb")]
+#[derive(Debug, serde::Deserialize)]
+struct TableSeed {
+    schema: serde_json::Map<String, serde_json::Value>,
+    #[serde(default)]
+    items: Vec<HashMap<String, AttributeValue>>,
+}
+
+/// Create the table described by `file`, wait for it to become active, and
+/// batch-write its items.
+#[cfg(feature = "dynamodb")]
+async fn seed_table(dynamodb: &RustackDynamoDB, file: &Path) -> Result<()> {
+    let table = file
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .with_context(|| format!("{} names no table", file.display()))?;
+    let contents = tokio::fs::read(file)
+        .await
+        .with_context(|| format!("cannot read {}", file.display()))?;
+    let mut seed: TableSeed = serde_json::from_slice(&contents)
+        .context("expected {\"schema\": ..., \"items\": [...]}")?;
+
+    let name = seed
+        .schema
+        .entry("TableName")
+        .or_insert_with(|| table.clone().into());
+    if name.as_str() != Some(table.as_str()) {
+        anyhow::bail!("schema names table {name}, but the file is named {table}");
+    }
+    let input: CreateTableInput =
+        serde_json::from_value(seed.schema.into()).context("invalid table schema")?;
+    match dynamodb.handle_create_table(input) {
+        // Persisted state may already hold the table.
+        Err(e) if e.code != DynamoDBErrorCode::ResourceInUseException => {
+            return Err(e).with_context(|| format!("cannot create table {table}"));
+        }
+        _ => {}
+    }
+    wait_until_active(dynamodb, &table).await?;
+
+    let count = seed.items.len();
+    let mut items = seed.items.into_iter().peekable();
+    while items.peek().is_some() {
+        let requests: Vec<WriteRequest> = items
+            .by_ref()
+            .take(MAX_BATCH_WRITE_ITEMS)
+            .map(|item| WriteRequest {
+                put_request: Some(PutRequest { item }),
+                delete_request: None,
+            })
+            .collect();
+        let input = BatchWriteItemInput {
+            request_items: HashMap::from([(table.clone(), requests)]),
+            ..BatchWriteItemInput::default()
+        };
+        let output = dynamodb
+            .handle_batch_write_item(input)
+            .with_context(|| format!("cannot write items to {table}"))?;
+        if !output.unprocessed_items.is_empty() {
+            anyhow::bail!("items left unprocessed writing to {table}");
+        }
+    }
+    info!(table = %table, items = count, "seeded table");
+    Ok(())
+}
+
+/// Wait for `table` to leave the `CREATING` state that
+/// `DYNAMODB_TABLE_CREATE_DELAY_MS` holds new tables in.
+#[cfg(feature = "dynamodb")]
+async fn wait_until_active(dynamodb: &RustackDynamoDB, table: &str) -> Result<()> {
+    loop {
+        let output = dynamodb.handle_describe_table(DescribeTableInput {
+            table_name: table.to_owned(),
+        })?;
+        let status = output.table.and_then(|table| table.table_status);
+        if status != Some(TableStatus::Creating) {
+            return Ok(());
+        }
+        tokio::time::sleep(TABLE_ACTIVE_POLL_INTERVAL).await;
+    }
+}
+
+/// The subdirectories (`dirs`) or files directly in `dir`, sorted; none
+/// when `dir` does not exist.
+async fn entries(dir: &Path, dirs: bool) -> Result<Vec<PathBuf>> {
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("cannot read {}", dir.display())),
+    };
+    let mut paths = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        if entry.file_type().await?.is_dir() == dirs {
+            paths.push(entry.path());
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// The files under `dir`, recursively, sorted by path.
+#[cfg(feature = "s3")]
+async fn walk_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        pending.extend(entries(&dir, true).await?);
+        files.extend(entries(&dir, false).await?);
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// The final component of `path`, as a name.
+#[cfg(feature = "s3")]
+fn file_name(path: &Path) -> Result<String> {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .with_context(|| format!("{} has no name", path.display()))
+}
+
+/// Guess a file's content type from its extension.
+#[cfg(feature = "s3")]
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("html" | "htm") => "text/html",
+        Some("css") => "text/css",
+        Some("js" | "mjs") => "text/javascript",
+        Some("txt" | "log") => "text/plain",
+        Some("md") => "text/markdown",
+        Some("csv") => "text/csv",
+        Some("xml") => "application/xml",
+        Some("json") => "application/json",
+        Some("yaml" | "yml") => "application/yaml",
+        Some("pdf") => "application/pdf",
+        Some("zip") => "application/zip",
+        Some("gz") => "application/gzip",
+        Some("wasm") => "application/wasm",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("webp") => "image/webp",
+        Some("ico") => "image/x-icon",
+        Some("mp3") => "audio/mpeg",
+        Some("mp4") => "video/mp4",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(all(test, feature = "s3", feature = "dynamodb"))]
+mod tests {
+    use rustack_dynamodb_model::input::ScanInput;
+    use rustack_s3_model::input::HeadObjectInput;
+    use serde_json::json;
+
+    use super::*;
+    use crate::ServerBuilder;
+
+    async fn write(path: PathBuf, contents: impl AsRef<[u8]>) {
+        tokio::fs::create_dir_all(path.parent().unwrap())
+            .await
+            .unwrap();
+        tokio::fs::write(path, contents).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_should_seed_buckets_and_tables_before_serving() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path().join("buckets/site/index.html"), "<h1>hi</h1>").await;
+        write(dir.path().join("buckets/site/assets/app.css"), "body {}").await;
+        let items: Vec<_> = (0..30)
+            .map(|i| json!({"pk": {"S": format!("user#{i}")}, "age": {"N": i.to_string()}}))
+            .collect();
+        let table = json!({
+            "schema": {
+                "KeySchema": [{"AttributeName": "pk", "KeyType": "HASH"}],
+                "AttributeDefinitions": [{"AttributeName": "pk", "AttributeType": "S"}],
+                "BillingMode": "PAY_PER_REQUEST",
+            },
+            "items": items,
+        });
+        write(dir.path().join("dynamodb/users.json"), table.to_string()).await;
+
+        let server = ServerBuilder::new()
+            .services(&["s3", "dynamodb"])
+            .init_data(InitData::new(dir.path()))
+            .start()
+            .await
+            .unwrap();
+
+        let s3 = server.s3().unwrap();
+        for (key, content_type) in [("index.html", "text/html"), ("assets/app.css", "text/css")] {
+            let head = s3
+                .handle_head_object(HeadObjectInput {
+                    bucket: "site".to_owned(),
+                    key: key.to_owned(),
+                    ..HeadObjectInput::default()
+                })
+                .await
+                .unwrap();
+            assert_eq!(head.content_type.as_deref(), Some(content_type), "{key}");
+        }
+        let scan = server
+            .dynamodb()
+            .unwrap()
+            .handle_scan(ScanInput {
+                table_name: "users".to_owned(),
+                ..ScanInput::default()
+            })
+            .unwrap();
+        assert_eq!(scan.count, 30);
+        server.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_should_abort_startup_on_bad_seed_data_unless_continuing() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path().join("buckets/fixtures/a.txt"), "a").await;
+        write(dir.path().join("dynamodb/broken.json"), "not json").await;
+
+        let err = ServerBuilder::new()
+            .services(&["s3", "dynamodb"])
+            .init_data(InitData::new(dir.path()))
+            .start()
+            .await
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("broken.json"), "{err:#}");
+
+        let server = ServerBuilder::new()
+            .services(&["s3", "dynamodb"])
+            .init_data(InitData {
+                dir: dir.path().to_path_buf(),
+                continue_on_error: true,
+            })
+            .start()
+            .await
+            .unwrap();
+        assert!(server.s3().unwrap().state().get_bucket("fixtures").is_ok());
+        server.shutdown().await.unwrap();
+    }
+}
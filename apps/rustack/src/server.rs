@@ -0,0 +1,2825 @@
+//! Server assembly: service wiring, the accept loop, and [`ServerBuilder`].
+//!
+//! [`ServerBuilder::start`] builds every enabled service, binds the gateway,
+//! and serves it on a background task. The returned [`ServerHandle`] reports
+//! the bound address, exposes the providers of the core services for direct
+//! state assertions, and shuts the server down through the same graceful
+//! drain the binary runs on Ctrl-C. The drain is bounded by a grace
+//! period (`SHUTDOWN_GRACE_PERIOD`), after which remaining connections are
+//! aborted.
+//!
+//! The gateway can serve HTTPS instead of, or next to, plain HTTP; see
+//! [`crate::tls`].
+
+use std::{
+    net::SocketAddr,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::{conn::auto::Builder as HttpConnBuilder, graceful::GracefulShutdown},
+};
+#[cfg(feature = "apigatewayv2")]
+use rustack_apigatewayv2_core::config::ApiGatewayV2Config;
+#[cfg(feature = "apigatewayv2")]
+use rustack_apigatewayv2_core::handler::RustackApiGatewayV2Handler;
+#[cfg(feature = "apigatewayv2")]
+use rustack_apigatewayv2_core::provider::RustackApiGatewayV2;
+#[cfg(feature = "apigatewayv2")]
+use rustack_apigatewayv2_http::service::{ApiGatewayV2HttpConfig, ApiGatewayV2HttpService};
+#[cfg(feature = "cloudfront")]
+use rustack_cloudfront_core::{CloudFrontConfig, RustackCloudFront};
+#[cfg(feature = "cloudfront-dataplane")]
+use rustack_cloudfront_dataplane::{DataPlane, DataPlaneConfig};
+#[cfg(feature = "cloudfront")]
+use rustack_cloudfront_http::service::{CloudFrontHttpConfig, CloudFrontHttpService};
+#[cfg(feature = "cloudwatch")]
+use rustack_cloudwatch_core::config::CloudWatchConfig;
+#[cfg(feature = "cloudwatch")]
+use rustack_cloudwatch_core::handler::RustackCloudWatchHandler;
+#[cfg(feature = "cloudwatch")]
+use rustack_cloudwatch_core::provider::RustackCloudWatch;
+#[cfg(feature = "cloudwatch")]
+use rustack_cloudwatch_http::service::{CloudWatchHttpConfig, CloudWatchHttpService};
+use rustack_core::{AccessLogFormat, AccessLogSink, AccessLogger, config};
+#[cfg(feature = "dynamodb")]
+use rustack_dynamodb_core::config::DynamoDBConfig;
+#[cfg(feature = "dynamodb")]
+use rustack_dynamodb_core::handler::RustackDynamoDBHandler;
+#[cfg(feature = "dynamodb")]
+use rustack_dynamodb_core::provider::RustackDynamoDB;
+#[cfg(feature = "dynamodb")]
+use rustack_dynamodb_http::service::{DynamoDBHttpConfig, DynamoDBHttpService};
+#[cfg(feature = "dynamodbstreams")]
+use rustack_dynamodbstreams_core::config::DynamoDBStreamsConfig;
+#[cfg(feature = "dynamodbstreams")]
+use rustack_dynamodbstreams_core::emitter::{
+    DynamoDBStreamEmitter, DynamoDBStreamLifecycleManager,
+};
+#[cfg(feature = "dynamodbstreams")]
+use rustack_dynamodbstreams_core::handler::RustackDynamoDBStreamsHandler;
+#[cfg(feature = "dynamodbstreams")]
+use rustack_dynamodbstreams_core::provider::RustackDynamoDBStreams;
+#[cfg(feature = "dynamodbstreams")]
+use rustack_dynamodbstreams_core::storage::StreamStore;
+#[cfg(feature = "dynamodbstreams")]
+use rustack_dynamodbstreams_http::service::{
+    DynamoDBStreamsHttpConfig, DynamoDBStreamsHttpService,
+};
+#[cfg(feature = "events")]
+use rustack_events_core::config::EventsConfig;
+#[cfg(feature = "events")]
+use rustack_events_core::handler::RustackEventsHandler;
+#[cfg(feature = "events")]
+use rustack_events_core::provider::RustackEvents;
+#[cfg(feature = "events")]
+use rustack_events_http::service::{EventsHttpConfig, EventsHttpService};
+#[cfg(feature = "iam")]
+use rustack_iam_core::config::IamConfig;
+#[cfg(feature = "iam")]
+use rustack_iam_core::handler::RustackIamHandler;
+#[cfg(feature = "iam")]
+use rustack_iam_core::provider::RustackIam;
+#[cfg(feature = "iam")]
+use rustack_iam_core::store::IamStore;
+#[cfg(feature = "iam")]
+use rustack_iam_http::service::{IamHttpConfig, IamHttpService};
+#[cfg(feature = "kinesis")]
+use rustack_kinesis_core::config::KinesisConfig;
+#[cfg(feature = "kinesis")]
+use rustack_kinesis_core::handler::RustackKinesisHandler;
+#[cfg(feature = "kinesis")]
+use rustack_kinesis_core::provider::RustackKinesis;
+#[cfg(feature = "kinesis")]
+use rustack_kinesis_http::service::{KinesisHttpConfig, KinesisHttpService};
+#[cfg(feature = "kms")]
+use rustack_kms_core::config::KmsConfig;
+#[cfg(feature = "kms")]
+use rustack_kms_core::handler::RustackKmsHandler;
+#[cfg(feature = "kms")]
+use rustack_kms_core::provider::RustackKms;
+#[cfg(feature = "kms")]
+use rustack_kms_http::service::{KmsHttpConfig, KmsHttpService};
+#[cfg(feature = "lambda")]
+use rustack_lambda_core::config::LambdaConfig;
+#[cfg(feature = "lambda")]
+use rustack_lambda_core::handler::RustackLambdaHandler;
+#[cfg(feature = "lambda")]
+use rustack_lambda_core::provider::RustackLambda;
+#[cfg(feature = "lambda")]
+use rustack_lambda_http::service::{LambdaHttpConfig, LambdaHttpService};
+#[cfg(feature = "logs")]
+use rustack_logs_core::config::LogsConfig;
+#[cfg(feature = "logs")]
+use rustack_logs_core::handler::RustackLogsHandler;
+#[cfg(feature = "logs")]
+use rustack_logs_core::provider::RustackLogs;
+#[cfg(feature = "logs")]
+use rustack_logs_http::service::{LogsHttpConfig, LogsHttpService};
+#[cfg(feature = "s3")]
+use rustack_s3_core::{RustackS3, S3Config, error::S3ServiceError};
+#[cfg(feature = "s3")]
+use rustack_s3_http::{
+    fault::FaultInjector,
+    fixture::{FixtureMode, HeaderMatch},
+    latency::{LatencyInjector, LatencyRule},
+    service::{S3HttpConfig, S3HttpService},
+};
+#[cfg(feature = "secretsmanager")]
+use rustack_secretsmanager_core::config::SecretsManagerConfig;
+#[cfg(feature = "secretsmanager")]
+use rustack_secretsmanager_core::handler::RustackSecretsManagerHandler;
+#[cfg(feature = "secretsmanager")]
+use rustack_secretsmanager_core::provider::RustackSecretsManager;
+#[cfg(feature = "secretsmanager")]
+use rustack_secretsmanager_http::service::{SecretsManagerHttpConfig, SecretsManagerHttpService};
+#[cfg(feature = "ses")]
+use rustack_ses_core::config::SesConfig;
+#[cfg(feature = "ses")]
+use rustack_ses_core::handler::RustackSesHandler;
+#[cfg(feature = "ses")]
+use rustack_ses_core::provider::RustackSes;
+#[cfg(feature = "ses")]
+use rustack_ses_http::service::{SesHttpConfig, SesHttpService};
+#[cfg(feature = "ses")]
+use rustack_ses_http::v2::SesV2HttpService;
+#[cfg(feature = "sns")]
+use rustack_sns_core::config::SnsConfig;
+#[cfg(feature = "sns")]
+use rustack_sns_core::handler::RustackSnsHandler;
+#[cfg(feature = "sns")]
+use rustack_sns_core::provider::RustackSns;
+#[cfg(feature = "sns")]
+use rustack_sns_http::service::{SnsHttpConfig, SnsHttpService};
+#[cfg(feature = "sqs")]
+use rustack_sqs_core::config::SqsConfig;
+#[cfg(feature = "sqs")]
+use rustack_sqs_core::handler::RustackSqsHandler;
+#[cfg(feature = "sqs")]
+use rustack_sqs_core::provider::RustackSqs;
+#[cfg(feature = "sqs")]
+use rustack_sqs_http::service::{SqsHttpConfig, SqsHttpService};
+#[cfg(feature = "ssm")]
+use rustack_ssm_core::config::SsmConfig;
+#[cfg(feature = "ssm")]
+use rustack_ssm_core::handler::RustackSsmHandler;
+#[cfg(feature = "ssm")]
+use rustack_ssm_core::provider::RustackSsm;
+#[cfg(feature = "ssm")]
+use rustack_ssm_http::service::{SsmHttpConfig, SsmHttpService};
+#[cfg(feature = "sts")]
+use rustack_sts_core::config::StsConfig;
+#[cfg(feature = "sts")]
+use rustack_sts_core::handler::RustackStsHandler;
+#[cfg(feature = "sts")]
+use rustack_sts_core::provider::RustackSts;
+#[cfg(feature = "sts")]
+use rustack_sts_http::service::{StsHttpConfig, StsHttpService};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::{Semaphore, oneshot},
+    task::{JoinHandle, JoinSet},
+};
+use tokio_rustls::{TlsAcceptor, rustls::ServerConfig};
+use tracing::{debug, info, warn};
+
+#[cfg(all(feature = "dynamodb", feature = "s3"))]
+use crate::dynamodb_export_bridge;
+#[cfg(feature = "events")]
+use crate::events_bridge::LocalTargetDelivery;
+#[cfg(feature = "s3")]
+use crate::handler;
+#[cfg(feature = "sns")]
+use crate::sns_bridge::RustackSqsPublisher;
+use crate::{
+    VERSION,
+    gateway::GatewayService,
+    limits::{self, ConnectionLimits, TimeoutIo},
+    metrics::{self, GatewayMetrics, GaugeSource},
+    namespace::Namespaces,
+    reset::StateReset,
+    rules::{self, RuleEndpoints},
+    scrub::BucketScrub,
+    seed::{self, INIT_DATA_DIR_ENV, InitData},
+    service::{self, ServiceRouter},
+    settings::{self, SettingsEndpoints},
+    telemetry, tls,
+};
+
+/// How long a client may take to complete the TLS handshake.
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long shutdown waits for in-flight requests by default.
+const DEFAULT_SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// Providers of the core services, kept so tests can inspect and seed state
+/// without going through HTTP.
+#[derive(Debug, Default)]
+pub(crate) struct Providers {
+    #[cfg(feature = "s3")]
+    pub(crate) s3: Option<Arc<RustackS3>>,
+    /// The S3 latency rules, adjustable at runtime.
+    #[cfg(feature = "s3")]
+    pub(crate) s3_latency: Option<Arc<LatencyInjector>>,
+    /// The S3 fault rules, adjustable at runtime.
+    #[cfg(feature = "s3")]
+    pub(crate) s3_faults: Option<Arc<FaultInjector>>,
+    #[cfg(feature = "dynamodb")]
+    pub(crate) dynamodb: Option<Arc<RustackDynamoDB>>,
+    #[cfg(feature = "sqs")]
+    pub(crate) sqs: Option<Arc<RustackSqs>>,
+    /// Background tasks the services started.
+    pub(crate) tasks: Vec<JoinHandle<()>>,
+}
+
+impl Providers {
+    /// The state resets of the services that support one.
+    fn state_reset(&self) -> StateReset {
+        #[allow(unused_mut)]
+        let mut resets = StateReset::default();
+        #[cfg(feature = "s3")]
+        if let Some(s3) = &self.s3 {
+            let s3 = Arc::clone(s3);
+            resets.add("s3", move || s3.reset());
+        }
+        #[cfg(feature = "dynamodb")]
+        if let Some(dynamodb) = &self.dynamodb {
+            let dynamodb = Arc::clone(dynamodb);
+            resets.add("dynamodb", move || dynamodb.reset());
+        }
+        resets
+    }
+
+    /// The runtime-managed rule sets of the enabled services.
+    fn rule_endpoints(&self) -> RuleEndpoints {
+        #[allow(unused_mut)]
+        let mut endpoints = RuleEndpoints::default();
+        #[cfg(feature = "s3")]
+        if let Some(latency) = &self.s3_latency {
+            endpoints.add(rules::S3_LATENCY_PATH, Arc::clone(latency) as _);
+        }
+        #[cfg(feature = "s3")]
+        if let Some(faults) = &self.s3_faults {
+            endpoints.add(rules::S3_FAULTS_PATH, Arc::clone(faults) as _);
+        }
+        endpoints
+    }
+
+    /// The runtime-adjustable settings of the enabled services.
+    fn settings_endpoints(&self) -> SettingsEndpoints {
+        #[allow(unused_mut)]
+        let mut endpoints = SettingsEndpoints::default();
+        #[cfg(feature = "dynamodb")]
+        if let Some(dynamodb) = &self.dynamodb {
+            endpoints.add(settings::DYNAMODB_CONFIG_PATH, Arc::clone(dynamodb) as _);
+        }
+        endpoints
+    }
+
+    /// The S3 integrity scrub, if S3 is enabled.
+    fn bucket_scrub(&self) -> BucketScrub {
+        #[cfg(feature = "s3")]
+        if let Some(s3) = &self.s3 {
+            let s3 = Arc::clone(s3);
+            return BucketScrub::new(move |bucket| {
+                let s3 = Arc::clone(&s3);
+                async move {
+                    match s3.scrub_bucket(&bucket).await {
+                        Ok(report) => (
+                            http::StatusCode::OK,
+                            serde_json::to_string(&report).unwrap_or_default(),
+                        ),
+                        Err(S3ServiceError::NoSuchBucket { .. }) => (
+                            http::StatusCode::NOT_FOUND,
+                            serde_json::json!({ "error": "no such bucket", "bucket": bucket })
+                                .to_string(),
+                        ),
+                        Err(e) => (
+                            http::StatusCode::INTERNAL_SERVER_ERROR,
+                            serde_json::json!({ "error": e.to_string() }).to_string(),
+                        ),
+                    }
+                }
+            });
+        }
+        BucketScrub::default()
+    }
+}
+
+/// Builder for a gateway server running on a background task.
+///
+/// Per-service settings still come from the `<SERVICE>_*` environment
+/// variables; the builder controls which services run and where the gateway
+/// listens. The default bind address is `127.0.0.1:0`, an ephemeral port.
+#[derive(Debug, Clone)]
+pub struct ServerBuilder {
+    services: Option<Vec<String>>,
+    bind: String,
+    tls: Option<Arc<ServerConfig>>,
+    tls_bind: Option<String>,
+    limits: ConnectionLimits,
+    grace_period: Duration,
+    metrics: bool,
+    access_log: Option<Arc<AccessLogger>>,
+    init_data: Option<InitData>,
+}
+
+impl Default for ServerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ServerBuilder {
+    /// Create a builder that enables every compiled-in service on an
+    /// ephemeral loopback port.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            services: None,
+            bind: "127.0.0.1:0".to_string(),
+            tls: None,
+            tls_bind: None,
+            limits: ConnectionLimits::default(),
+            grace_period: DEFAULT_SHUTDOWN_GRACE_PERIOD,
+            metrics: false,
+            access_log: None,
+            init_data: None,
+        }
+    }
+
+    /// Create a builder configured from `SERVICES`, `GATEWAY_LISTEN`, the
+    /// `GATEWAY_TLS_*` and `GATEWAY_LISTEN_TLS` settings, the connection
+    /// limits, `SHUTDOWN_GRACE_PERIOD`, `METRICS_ENABLED`,
+    /// `ACCESS_LOG_FORMAT` / `ACCESS_LOG_FILE`, and `INIT_DATA_DIR` /
+    /// `INIT_DATA_CONTINUE_ON_ERROR`, the way the `rustack` binary starts.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the TLS certificate or key cannot be loaded, the
+    /// access log settings are invalid, or the log file cannot be opened.
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            services: Some(parse_enabled_services()),
+            bind: gateway_listen_addr(),
+            tls: tls::server_config_from_env()?,
+            tls_bind: config::var(tls::LISTEN_TLS_ENV),
+            limits: ConnectionLimits::from_env(),
+            grace_period: shutdown_grace_period(),
+            metrics: metrics_enabled(),
+            access_log: build_access_logger()?,
+            init_data: init_data(),
+        })
+    }
+
+    /// Enable only the named services (case-insensitive).
+    #[must_use]
+    pub fn services(mut self, names: &[&str]) -> Self {
+        self.services = Some(names.iter().map(|name| name.to_lowercase()).collect());
+        self
+    }
+
+    /// Set the gateway bind address. Port `0` picks a free port; read it
+    /// back from [`ServerHandle::local_addr`].
+    #[must_use]
+    pub fn bind(mut self, addr: impl Into<String>) -> Self {
+        self.bind = addr.into();
+        self
+    }
+
+    /// Serve HTTPS with `config`.
+    ///
+    /// Without a [`tls_bind`](Self::tls_bind) address the gateway's bind
+    /// address serves HTTPS only.
+    #[must_use]
+    pub fn tls(mut self, config: Arc<ServerConfig>) -> Self {
+        self.tls = Some(config);
+        self
+    }
+
+    /// Serve HTTPS on a separate address, keeping plain HTTP on the bind
+    /// address. Requires [`tls`](Self::tls).
+    #[must_use]
+    pub fn tls_bind(mut self, addr: impl Into<String>) -> Self {
+        self.tls_bind = Some(addr.into());
+        self
+    }
+
+    /// Set the connection limits. Defaults to [`ConnectionLimits::default`].
+    #[must_use]
+    pub fn limits(mut self, limits: ConnectionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Set how long [`ServerHandle::shutdown`] waits for in-flight requests
+    /// before aborting their connections. Defaults to 5 seconds.
+    #[must_use]
+    pub fn shutdown_grace_period(mut self, grace_period: Duration) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+
+    /// Serve Prometheus metrics on `GET /metrics`.
+    #[must_use]
+    pub fn metrics(mut self, enabled: bool) -> Self {
+        self.metrics = enabled;
+        self
+    }
+
+    /// Write S3 and DynamoDB access log lines to `logger`.
+    #[must_use]
+    pub fn access_log(mut self, logger: Arc<AccessLogger>) -> Self {
+        self.access_log = Some(logger);
+        self
+    }
+
+    /// Seed S3 buckets and DynamoDB tables from `init_data` before the
+    /// gateway starts serving.
+    #[must_use]
+    pub fn init_data(mut self, init_data: InitData) -> Self {
+        self.init_data = Some(init_data);
+        self
+    }
+
+    /// Build the enabled services, bind the gateway, load any seed data, and
+    /// start serving on a background task.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no enabled service is compiled in, an enabled
+    /// service's configuration is malformed, a bind address is invalid or
+    /// cannot be bound, a TLS bind address is set without a
+    /// TLS configuration, or seed data fails to load without
+    /// `continue_on_error`.
+    pub async fn start(self) -> Result<ServerHandle> {
+        let enabled = self.services.unwrap_or_else(|| parse_services_value(""));
+
+        // Warn about services that are requested but not compiled in.
+        for name in &enabled {
+            if !is_compiled_in(name) {
+                warn!(service = %name, "requested service is not compiled in, skipping");
+            }
+        }
+        validate_service_configs(|name| enabled.iter().any(|s| s == name))?;
+
+        let mut gauge_sources: Vec<Box<dyn GaugeSource>> = Vec::new();
+        let mut providers = Providers::default();
+        let services = build_services(
+            |name| enabled.iter().any(|s| s == name) && is_compiled_in(name),
+            &mut gauge_sources,
+            self.access_log.as_ref(),
+            &mut providers,
+        );
+
+        if services.is_empty() {
+            anyhow::bail!(
+                "no services enabled. Check the SERVICES environment variable and compiled \
+                 feature flags."
+            );
+        }
+
+        let mut gateway = GatewayService::new(services)
+            .with_state_reset(providers.state_reset())
+            .with_bucket_scrub(providers.bucket_scrub())
+            .with_rule_endpoints(providers.rule_endpoints())
+            .with_settings_endpoints(providers.settings_endpoints())
+            .with_namespaces(build_namespaces(enabled, self.access_log.clone()));
+        if self.metrics {
+            info!(
+                "serving Prometheus metrics on GET {}",
+                metrics::METRICS_PATH
+            );
+            gateway = gateway.with_metrics(Arc::new(GatewayMetrics::new(gauge_sources)));
+        }
+        let service_names = gateway.service_names();
+
+        let acceptor = self.tls.map(TlsAcceptor::from);
+        let (primary, secondary) = match (self.tls_bind, acceptor) {
+            (None, acceptor) => (GatewayListener::bind(&self.bind, acceptor).await?, None),
+            (Some(tls_bind), Some(acceptor)) => (
+                GatewayListener::bind(&self.bind, None).await?,
+                Some(GatewayListener::bind(&tls_bind, Some(acceptor)).await?),
+            ),
+            (Some(_), None) => anyhow::bail!(
+                "{} requires a TLS certificate: set {} and {}, or {}",
+                tls::LISTEN_TLS_ENV,
+                tls::TLS_CERT_ENV,
+                tls::TLS_KEY_ENV,
+                tls::TLS_SELF_SIGNED_ENV,
+            ),
+        };
+        // Serving starts only after seeding, so readiness waits for it.
+        if let Some(init_data) = &self.init_data {
+            init_data.load(&providers).await?;
+        }
+
+        let local_addr = primary.local_addr;
+        let primary_is_tls = primary.tls.is_some();
+        let tls_local_addr = [Some(&primary), secondary.as_ref()]
+            .into_iter()
+            .flatten()
+            .find(|listener| listener.tls.is_some())
+            .map(|listener| listener.local_addr);
+
+        info!(
+            addr = %local_addr,
+            tls_addr = ?tls_local_addr,
+            services = ?service_names,
+            version = VERSION,
+            "starting Rustack Server",
+        );
+
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        let (abort, abort_rx) = oneshot::channel();
+        let task = tokio::spawn(serve(
+            primary,
+            secondary,
+            gateway,
+            self.limits,
+            self.grace_period,
+            shutdown_rx,
+            abort_rx,
+        ));
+
+        Ok(ServerHandle {
+            local_addr,
+            tls_local_addr,
+            primary_tls: primary_is_tls,
+            service_names,
+            providers,
+            shutdown,
+            abort,
+            task,
+        })
+    }
+}
+
+/// A running server started by [`ServerBuilder::start`].
+///
+/// Dropping the handle without calling [`shutdown`](Self::shutdown) also
+/// stops the server, aborting in-flight requests.
+#[derive(Debug)]
+pub struct ServerHandle {
+    local_addr: SocketAddr,
+    tls_local_addr: Option<SocketAddr>,
+    primary_tls: bool,
+    service_names: Vec<&'static str>,
+    providers: Providers,
+    shutdown: oneshot::Sender<()>,
+    abort: oneshot::Sender<()>,
+    task: JoinHandle<Result<()>>,
+}
+
+impl ServerHandle {
+    /// The address the gateway is listening on.
+    #[must_use]
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// The address serving HTTPS, if TLS is enabled: the bind address, or
+    /// the separate TLS bind address when one is set.
+    #[must_use]
+    pub fn tls_local_addr(&self) -> Option<SocketAddr> {
+        self.tls_local_addr
+    }
+
+    /// The gateway URL, for use as an AWS SDK endpoint override.
+    ///
+    /// The scheme is `https` when the bind address serves HTTPS.
+    #[must_use]
+    pub fn endpoint_url(&self) -> String {
+        let scheme = if self.primary_tls { "https" } else { "http" };
+        format!("{scheme}://{}", self.local_addr)
+    }
+
+    /// Names of the services the gateway routes to, in routing order.
+    #[must_use]
+    pub fn service_names(&self) -> &[&'static str] {
+        &self.service_names
+    }
+
+    /// The S3 provider, if S3 is enabled.
+    #[cfg(feature = "s3")]
+    #[must_use]
+    pub fn s3(&self) -> Option<&RustackS3> {
+        self.providers.s3.as_deref()
+    }
+
+    /// The DynamoDB provider, if DynamoDB is enabled.
+    #[cfg(feature = "dynamodb")]
+    #[must_use]
+    pub fn dynamodb(&self) -> Option<&RustackDynamoDB> {
+        self.providers.dynamodb.as_deref()
+    }
+
+    /// The SQS provider, if SQS is enabled.
+    #[cfg(feature = "sqs")]
+    #[must_use]
+    pub fn sqs(&self) -> Option<&RustackSqs> {
+        self.providers.sqs.as_deref()
+    }
+
+    /// Stop accepting connections and wait for in-flight requests to drain,
+    /// the same path the binary takes on Ctrl-C.
+    ///
+    /// Connections still open after the grace period are aborted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server task failed or panicked.
+    pub async fn shutdown(self) -> Result<()> {
+        self.shutdown_or_abort(std::future::pending()).await
+    }
+
+    /// Like [`shutdown`](Self::shutdown), but abort in-flight requests at
+    /// once if `abort` completes before they drain. The binary passes a
+    /// second Ctrl-C.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server task failed or panicked.
+    pub async fn shutdown_or_abort(self, abort: impl Future<Output = ()>) -> Result<()> {
+        // The receivers are gone only if the server task already exited, in
+        // which case awaiting it reports why.
+        let _ = self.shutdown.send(());
+        let mut task = self.task;
+        let result = tokio::select! {
+            result = &mut task => result,
+            () = abort => {
+                let _ = self.abort.send(());
+                task.await
+            }
+        };
+        result.context("server task panicked")?
+    }
+}
+
+/// Build the [`S3HttpConfig`] from the application [`S3Config`].
+#[cfg(feature = "s3")]
+fn build_s3_http_config(config: &S3Config, access_log: Option<Arc<AccessLogger>>) -> S3HttpConfig {
+    let credential_provider = build_credential_provider();
+    let fixtures = match (&config.s3_replay_dir, &config.s3_record_dir) {
+        (Some(dir), record_dir) => {
+            if record_dir.is_some() {
+                warn!("S3_REPLAY_DIR and S3_RECORD_DIR are both set; replaying fixtures only");
+            }
+            info!(dir = %dir, "replaying S3 fixtures");
+            let header_match = HeaderMatch::parse(&config.s3_replay_match_headers);
+            Some(Arc::new(FixtureMode::replay(dir, header_match)))
+        }
+        (None, Some(dir)) => {
+            info!(dir = %dir, "recording S3 fixtures");
+            Some(Arc::new(FixtureMode::record(dir)))
+        }
+        (None, None) => None,
+    };
+    let latency_rules = LatencyRule::parse_list(&config.s3_latency_rules).unwrap_or_else(|e| {
+        warn!(error = %e, "ignoring S3_LATENCY_RULES");
+        Vec::new()
+    });
+    if !latency_rules.is_empty() {
+        info!(rules = latency_rules.len(), "injecting S3 latency");
+    }
+
+    S3HttpConfig {
+        domain: config.s3_domain.clone(),
+        virtual_hosting: config.s3_virtual_hosting,
+        skip_signature_validation: config.s3_skip_signature_validation,
+        region: config.default_region.clone(),
+        credential_provider: credential_provider.clone(),
+        access_log,
+        fixtures,
+        max_body_bytes: config.s3_max_request_body_bytes,
+        latency: Some(Arc::new(LatencyInjector::new(latency_rules))),
+        faults: Some(Arc::new(FaultInjector::default())),
+    }
+}
+
+/// Build the [`DynamoDBHttpConfig`] from the [`DynamoDBConfig`].
+#[cfg(feature = "dynamodb")]
+fn build_dynamodb_http_config(
+    config: &DynamoDBConfig,
+    access_log: Option<Arc<AccessLogger>>,
+) -> DynamoDBHttpConfig {
+    let credential_provider = build_credential_provider();
+
+    DynamoDBHttpConfig {
+        skip_signature_validation: config.skip_signature_validation,
+        region: config.default_region.clone(),
+        credential_provider,
+        access_log,
+        max_body_bytes: config.max_request_body_bytes,
+    }
+}
+
+/// Build the [`SqsHttpConfig`] from the [`SqsConfig`].
+#[cfg(feature = "sqs")]
+fn build_sqs_http_config(config: &SqsConfig) -> SqsHttpConfig {
+    let credential_provider = build_credential_provider();
+
+    SqsHttpConfig {
+        skip_signature_validation: config.skip_signature_validation,
+        region: config.default_region.clone(),
+        credential_provider,
+    }
+}
+
+/// Build the [`SsmHttpConfig`] from the [`SsmConfig`].
+#[cfg(feature = "ssm")]
+fn build_ssm_http_config(config: &SsmConfig) -> SsmHttpConfig {
+    let credential_provider = build_credential_provider();
+
+    SsmHttpConfig {
+        skip_signature_validation: config.skip_signature_validation,
+        region: config.default_region.clone(),
+        credential_provider,
+    }
+}
+
+/// Build the [`SnsHttpConfig`] from the [`SnsConfig`].
+#[cfg(feature = "sns")]
+fn build_sns_http_config(config: &SnsConfig) -> SnsHttpConfig {
+    let credential_provider = build_credential_provider();
+
+    SnsHttpConfig {
+        skip_signature_validation: config.skip_signature_validation,
+        region: config.default_region.clone(),
+        credential_provider,
+    }
+}
+
+/// Build the [`LambdaHttpConfig`] from the [`LambdaConfig`].
+#[cfg(feature = "lambda")]
+fn build_lambda_http_config(config: &LambdaConfig) -> LambdaHttpConfig {
+    let credential_provider = build_credential_provider();
+
+    LambdaHttpConfig {
+        skip_signature_validation: config.skip_signature_validation,
+        region: config.default_region.clone(),
+        credential_provider,
+    }
+}
+
+/// Build the [`EventsHttpConfig`] from the [`EventsConfig`].
+#[cfg(feature = "events")]
+fn build_events_http_config(config: &EventsConfig) -> EventsHttpConfig {
+    let credential_provider = build_credential_provider();
+
+    EventsHttpConfig {
+        skip_signature_validation: config.skip_signature_validation,
+        region: config.default_region.clone(),
+        credential_provider,
+    }
+}
+
+/// Build the [`LogsHttpConfig`] from the [`LogsConfig`].
+#[cfg(feature = "logs")]
+fn build_logs_http_config(config: &LogsConfig) -> LogsHttpConfig {
+    let credential_provider = build_credential_provider();
+
+    LogsHttpConfig {
+        skip_signature_validation: config.skip_signature_validation,
+        region: config.default_region.clone(),
+        credential_provider,
+    }
+}
+
+/// Build the [`KmsHttpConfig`] from the [`KmsConfig`].
+#[cfg(feature = "kms")]
+fn build_kms_http_config(config: &KmsConfig) -> KmsHttpConfig {
+    let credential_provider = build_credential_provider();
+
+    KmsHttpConfig {
+        skip_signature_validation: config.skip_signature_validation,
+        region: config.default_region.clone(),
+        credential_provider,
+    }
+}
+
+/// Build the [`KinesisHttpConfig`] from the [`KinesisConfig`].
+#[cfg(feature = "kinesis")]
+fn build_kinesis_http_config(config: &KinesisConfig) -> KinesisHttpConfig {
+    let credential_provider = build_credential_provider();
+
+    KinesisHttpConfig {
+        skip_signature_validation: config.skip_signature_validation,
+        region: config.default_region.clone(),
+        credential_provider,
+    }
+}
+
+/// Build the [`SecretsManagerHttpConfig`] from the [`SecretsManagerConfig`].
+#[cfg(feature = "secretsmanager")]
+fn build_secretsmanager_http_config(config: &SecretsManagerConfig) -> SecretsManagerHttpConfig {
+    let credential_provider = build_credential_provider();
+
+    SecretsManagerHttpConfig {
+        skip_signature_validation: config.skip_signature_validation,
+        region: config.default_region.clone(),
+        credential_provider,
+    }
+}
+
+/// Build the [`SesHttpConfig`] from the [`SesConfig`].
+#[cfg(feature = "ses")]
+fn build_ses_http_config(config: &SesConfig) -> SesHttpConfig {
+    let credential_provider = build_credential_provider();
+
+    SesHttpConfig {
+        skip_signature_validation: config.skip_signature_validation,
+        region: config.default_region.clone(),
+        credential_provider,
+    }
+}
+
+/// Build the [`ApiGatewayV2HttpConfig`] from the [`ApiGatewayV2Config`].
+#[cfg(feature = "apigatewayv2")]
+fn build_apigatewayv2_http_config(config: &ApiGatewayV2Config) -> ApiGatewayV2HttpConfig {
+    let credential_provider = build_credential_provider();
+
+    ApiGatewayV2HttpConfig {
+        skip_signature_validation: config.skip_signature_validation,
+        region: config.default_region.clone(),
+        credential_provider,
+    }
+}
+
+/// Build the [`DynamoDBStreamsHttpConfig`] from the [`DynamoDBStreamsConfig`].
+#[cfg(feature = "dynamodbstreams")]
+fn build_dynamodbstreams_http_config(config: &DynamoDBStreamsConfig) -> DynamoDBStreamsHttpConfig {
+    let credential_provider = build_credential_provider();
+
+    DynamoDBStreamsHttpConfig {
+        skip_signature_validation: config.skip_signature_validation,
+        region: config.default_region.clone(),
+        credential_provider,
+    }
+}
+
+/// Build the [`CloudWatchHttpConfig`] from the [`CloudWatchConfig`].
+#[cfg(feature = "cloudwatch")]
+fn build_cloudwatch_http_config(config: &CloudWatchConfig) -> CloudWatchHttpConfig {
+    let credential_provider = build_credential_provider();
+
+    CloudWatchHttpConfig {
+        skip_signature_validation: config.skip_signature_validation,
+        region: config.default_region.clone(),
+        credential_provider,
+    }
+}
+
+/// Build the [`IamHttpConfig`] from the [`IamConfig`].
+///
+/// IAM is a global service so we default the region to `us-east-1`.
+#[cfg(feature = "iam")]
+fn build_iam_http_config(config: &IamConfig) -> IamHttpConfig {
+    let credential_provider = build_credential_provider();
+
+    IamHttpConfig {
+        skip_signature_validation: config.skip_signature_validation,
+        region: "us-east-1".to_owned(),
+        credential_provider,
+    }
+}
+
+/// Build the [`CloudFrontHttpConfig`] from the [`CloudFrontConfig`].
+#[cfg(feature = "cloudfront")]
+fn build_cloudfront_http_config(config: &CloudFrontConfig) -> CloudFrontHttpConfig {
+    let credential_provider = build_credential_provider();
+    CloudFrontHttpConfig {
+        skip_signature_validation: config.skip_signature_validation,
+        region: config.default_region.clone(),
+        credential_provider,
+    }
+}
+
+/// Build the [`StsHttpConfig`] from the [`StsConfig`].
+#[cfg(feature = "sts")]
+fn build_sts_http_config(config: &StsConfig) -> StsHttpConfig {
+    let credential_provider = build_credential_provider();
+
+    StsHttpConfig {
+        skip_signature_validation: config.skip_signature_validation,
+        region: config.default_region.clone(),
+        credential_provider,
+    }
+}
+
+/// How often the `CREDENTIALS_FILE` watcher checks for changes.
+const CREDENTIALS_RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The provider loaded from `CREDENTIALS_FILE`, shared by every service.
+static FILE_CREDENTIALS: OnceLock<Arc<rustack_auth::FileCredentialProvider>> = OnceLock::new();
+
+/// Load `CREDENTIALS_FILE`, if set, and start watching it for changes.
+///
+/// A file that cannot be loaded at startup is a hard error, so a typo never
+/// silently disables signature validation.
+pub fn init_file_credentials() -> Result<()> {
+    let Some(path) = config::var(rustack_auth::credentials::CREDENTIALS_FILE_ENV) else {
+        return Ok(());
+    };
+    let provider = rustack_auth::FileCredentialProvider::new(&path)
+        .with_context(|| format!("failed to load credentials from {path}"))?;
+    let provider = Arc::new(provider);
+    provider.watch(CREDENTIALS_RELOAD_INTERVAL);
+    info!(
+        path = %path,
+        keys = provider.len(),
+        "configured credential provider from file"
+    );
+    FILE_CREDENTIALS.get_or_init(|| provider);
+    Ok(())
+}
+
+/// Build the credential provider shared by the services.
+///
+/// Uses the `CREDENTIALS_FILE` provider when one was loaded, otherwise the
+/// `ACCESS_KEY` / `SECRET_KEY` environment variables (used by MinIO Mint and
+/// other test harnesses).
+#[cfg(any(
+    feature = "s3",
+    feature = "dynamodb",
+    feature = "sqs",
+    feature = "ssm",
+    feature = "sns",
+    feature = "lambda",
+    feature = "events",
+    feature = "logs",
+    feature = "kms",
+    feature = "kinesis",
+    feature = "secretsmanager",
+    feature = "ses",
+    feature = "apigatewayv2",
+    feature = "cloudwatch",
+    feature = "dynamodbstreams",
+    feature = "iam",
+    feature = "sts",
+    feature = "cloudfront"
+))]
+fn build_credential_provider() -> Option<Arc<dyn rustack_auth::CredentialProvider>> {
+    use rustack_auth::StaticCredentialProvider;
+
+    if let Some(provider) = FILE_CREDENTIALS.get() {
+        return Some(Arc::clone(provider) as Arc<dyn rustack_auth::CredentialProvider>);
+    }
+
+    let access_key = std::env::var("ACCESS_KEY")
+        .or_else(|_| std::env::var("AWS_ACCESS_KEY_ID"))
+        .ok()?;
+    let secret_key = std::env::var("SECRET_KEY")
+        .or_else(|_| std::env::var("AWS_SECRET_ACCESS_KEY"))
+        .ok()?;
+
+    info!(
+        access_key = %access_key,
+        "configured credential provider from environment"
+    );
+
+    Some(Arc::new(StaticCredentialProvider::new(vec![(
+        access_key, secret_key,
+    )])))
+}
+
+/// A bound gateway listener, serving HTTPS when it has a TLS acceptor.
+struct GatewayListener {
+    listener: TcpListener,
+    local_addr: SocketAddr,
+    tls: Option<TlsAcceptor>,
+}
+
+impl GatewayListener {
+    async fn bind(addr: &str, tls: Option<TlsAcceptor>) -> Result<Self> {
+        let addr: SocketAddr = addr
+            .parse()
+            .with_context(|| format!("invalid bind address: {addr}"))?;
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("failed to bind to {addr}"))?;
+        let local_addr = listener
+            .local_addr()
+            .context("failed to read the bound address")?;
+        Ok(Self {
+            listener,
+            local_addr,
+            tls,
+        })
+    }
+
+    async fn accept(&self) -> std::io::Result<(TcpStream, SocketAddr, Option<TlsAcceptor>)> {
+        let (stream, peer_addr) = self.listener.accept().await?;
+        Ok((stream, peer_addr, self.tls.clone()))
+    }
+}
+
+/// Accept from `listener`, or wait forever when there is none.
+async fn accept_optional(
+    listener: Option<&GatewayListener>,
+) -> std::io::Result<(TcpStream, SocketAddr, Option<TlsAcceptor>)> {
+    match listener {
+        Some(listener) => listener.accept().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Run the accept loop on `primary` and, if set, `secondary`, serving
+/// connections until `shutdown` fires or its sender is dropped, then drain
+/// in-flight requests.
+///
+/// Connections over `limits.max_connections` are still accepted, but served
+/// by an [overloaded](GatewayService::overloaded) gateway without
+/// keep-alive, so clients get a prompt `503 SlowDown` instead of waiting in
+/// the listen backlog.
+///
+/// Once stopped, in-flight requests are [drained](drain) for up to
+/// `grace_period`, or until `abort` fires or its sender is dropped.
+///
+/// Every service is reported running once the loop starts.
+async fn serve(
+    primary: GatewayListener,
+    secondary: Option<GatewayListener>,
+    service: GatewayService,
+    limits: ConnectionLimits,
+    grace_period: Duration,
+    mut shutdown: oneshot::Receiver<()>,
+    abort: oneshot::Receiver<()>,
+) -> Result<()> {
+    let graceful = GracefulShutdown::new();
+    let mut http = HttpConnBuilder::new(TokioExecutor::new());
+    limits.configure(&mut http);
+    let mut overloaded_http = http.clone();
+    overloaded_http.http1().keep_alive(false);
+    let permits =
+        (limits.max_connections > 0).then(|| Arc::new(Semaphore::new(limits.max_connections)));
+    let health = Arc::clone(service.health());
+    for name in service.service_names() {
+        health.mark_running(name);
+    }
+    let mut connections = JoinSet::new();
+
+    loop {
+        let result = tokio::select! {
+            result = primary.accept() => result,
+            result = accept_optional(secondary.as_ref()) => result,
+            Some(_) = connections.join_next(), if !connections.is_empty() => continue,
+            _ = &mut shutdown => {
+                info!("shutting down gracefully");
+                break;
+            }
+        };
+        let (stream, peer_addr, tls) = match result {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!(error = %e, "failed to accept connection");
+                continue;
+            }
+        };
+
+        let permit = permits
+            .as_ref()
+            .and_then(|permits| Arc::clone(permits).try_acquire_owned().ok());
+        let mut svc = service.clone().with_peer_addr(peer_addr);
+        let mut http = &http;
+        if permits.is_some() && permit.is_none() {
+            debug!(peer_addr = %peer_addr, "connection limit reached, answering 503 SlowDown");
+            svc = svc.overloaded();
+            http = &overloaded_http;
+        }
+        let http = http.clone();
+        // Held through the TLS handshake so shutdown waits for it too.
+        let watcher = graceful.watcher();
+        let open = health.track_connection();
+        let stream = TimeoutIo::new(stream, &limits);
+
+        connections.spawn(async move {
+            let _open = open;
+            let _permit = permit;
+            let result = match tls {
+                None => {
+                    let conn = http.serve_connection(TokioIo::new(stream), svc);
+                    watcher.watch(conn.into_owned()).await
+                }
+                Some(acceptor) => {
+                    let stream =
+                        match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream))
+                            .await
+                        {
+                            Ok(Ok(stream)) => stream,
+                            Ok(Err(e)) => {
+                                debug!(peer_addr = %peer_addr, error = %e, "TLS handshake failed");
+                                return;
+                            }
+                            Err(_) => {
+                                debug!(peer_addr = %peer_addr, "TLS handshake timed out");
+                                return;
+                            }
+                        };
+                    let conn = http.serve_connection(TokioIo::new(stream), svc);
+                    watcher.watch(conn.into_owned()).await
+                }
+            };
+            match result {
+                Err(e) if limits::is_timeout(&*e) => {
+                    debug!(peer_addr = %peer_addr, error = %e, "connection timed out");
+                }
+                Err(e) => warn!(peer_addr = %peer_addr, error = %e, "connection error"),
+                Ok(()) => {}
+            }
+        });
+    }
+
+    let abort = async {
+        let _ = abort.await;
+    };
+    let aborted = drain(graceful, &mut connections, grace_period, abort).await;
+    if aborted == 0 {
+        info!("all connections drained, exiting");
+    } else {
+        warn!(
+            aborted,
+            "aborted connections that did not drain in time, exiting"
+        );
+    }
+
+    Ok(())
+}
+
+/// Wait for in-flight requests to complete, for at most `grace_period` or
+/// until `abort` completes, then abort the connections that remain.
+///
+/// Returns how many connections were aborted.
+async fn drain(
+    graceful: GracefulShutdown,
+    connections: &mut JoinSet<()>,
+    grace_period: Duration,
+    abort: impl Future<Output = ()>,
+) -> usize {
+    let drained = tokio::select! {
+        () = graceful.shutdown() => true,
+        () = tokio::time::sleep(grace_period) => false,
+        () = abort => false,
+    };
+    if drained {
+        // Watched connections have finished; reap their tasks.
+        while connections.join_next().await.is_some() {}
+        return 0;
+    }
+    while connections.try_join_next().is_some() {}
+    let remaining = connections.len();
+    connections.shutdown().await;
+    remaining
+}
+
+/// Check whether a service name was compiled into this binary.
+fn is_compiled_in(name: &str) -> bool {
+    (name == "s3" && cfg!(feature = "s3"))
+        || (name == "dynamodb" && cfg!(feature = "dynamodb"))
+        || (name == "sqs" && cfg!(feature = "sqs"))
+        || (name == "ssm" && cfg!(feature = "ssm"))
+        || (name == "sns" && cfg!(feature = "sns"))
+        || (name == "lambda" && cfg!(feature = "lambda"))
+        || (name == "events" && cfg!(feature = "events"))
+        || (name == "logs" && cfg!(feature = "logs"))
+        || (name == "kms" && cfg!(feature = "kms"))
+        || (name == "kinesis" && cfg!(feature = "kinesis"))
+        || (name == "secretsmanager" && cfg!(feature = "secretsmanager"))
+        || (name == "ses" && cfg!(feature = "ses"))
+        || (name == "apigatewayv2" && cfg!(feature = "apigatewayv2"))
+        || (name == "cloudwatch" && cfg!(feature = "cloudwatch"))
+        || (name == "dynamodbstreams" && cfg!(feature = "dynamodbstreams"))
+        || (name == "iam" && cfg!(feature = "iam"))
+        || (name == "sts" && cfg!(feature = "sts"))
+        || (name == "cloudfront" && cfg!(feature = "cloudfront"))
+}
+
+/// Parse the `SERVICES` environment variable into a list of service names.
+///
+/// If `SERVICES` is unset or empty, returns all compiled-in services.
+fn parse_enabled_services() -> Vec<String> {
+    let raw = config::var("SERVICES").unwrap_or_default();
+    parse_services_value(&raw)
+}
+
+/// Parse a comma-separated services string into a list of service names.
+///
+/// If the input is empty, returns all compiled-in services.
+#[must_use]
+pub fn parse_services_value(raw: &str) -> Vec<String> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        // All compiled-in services.
+        let mut all = Vec::new();
+        if cfg!(feature = "s3") {
+            all.push("s3".to_string());
+        }
+        if cfg!(feature = "dynamodb") {
+            all.push("dynamodb".to_string());
+        }
+        if cfg!(feature = "sqs") {
+            all.push("sqs".to_string());
+        }
+        if cfg!(feature = "ssm") {
+            all.push("ssm".to_string());
+        }
+        if cfg!(feature = "sns") {
+            all.push("sns".to_string());
+        }
+        if cfg!(feature = "lambda") {
+            all.push("lambda".to_string());
+        }
+        if cfg!(feature = "events") {
+            all.push("events".to_string());
+        }
+        if cfg!(feature = "logs") {
+            all.push("logs".to_string());
+        }
+        if cfg!(feature = "kms") {
+            all.push("kms".to_string());
+        }
+        if cfg!(feature = "kinesis") {
+            all.push("kinesis".to_string());
+        }
+        if cfg!(feature = "secretsmanager") {
+            all.push("secretsmanager".to_string());
+        }
+        if cfg!(feature = "ses") {
+            all.push("ses".to_string());
+        }
+        if cfg!(feature = "apigatewayv2") {
+            all.push("apigatewayv2".to_string());
+        }
+        if cfg!(feature = "cloudwatch") {
+            all.push("cloudwatch".to_string());
+        }
+        if cfg!(feature = "dynamodbstreams") {
+            all.push("dynamodbstreams".to_string());
+        }
+        if cfg!(feature = "iam") {
+            all.push("iam".to_string());
+        }
+        if cfg!(feature = "sts") {
+            all.push("sts".to_string());
+        }
+        if cfg!(feature = "cloudfront") {
+            all.push("cloudfront".to_string());
+        }
+        all
+    } else {
+        trimmed
+            .split(',')
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+}
+
+/// Read the gateway listen address from the environment.
+///
+/// Checks `GATEWAY_LISTEN` (the canonical var), then the configuration file,
+/// and falls back to the S3Config default when S3 is compiled in.
+#[must_use]
+pub fn gateway_listen_addr() -> String {
+    config::var("GATEWAY_LISTEN").unwrap_or_else(|| "0.0.0.0:4566".to_string())
+}
+
+/// How long shutdown waits for in-flight requests (`SHUTDOWN_GRACE_PERIOD`,
+/// in seconds).
+fn shutdown_grace_period() -> Duration {
+    let Some(raw) = config::var("SHUTDOWN_GRACE_PERIOD") else {
+        return DEFAULT_SHUTDOWN_GRACE_PERIOD;
+    };
+    raw.trim().parse().map_or_else(
+        |_| {
+            warn!(value = %raw, "invalid SHUTDOWN_GRACE_PERIOD, using the default");
+            DEFAULT_SHUTDOWN_GRACE_PERIOD
+        },
+        Duration::from_secs,
+    )
+}
+
+/// The seed data to load at startup (`INIT_DATA_DIR`), if any.
+fn init_data() -> Option<InitData> {
+    config::var(INIT_DATA_DIR_ENV).map(|dir| InitData {
+        dir: dir.into(),
+        continue_on_error: config::var(seed::CONTINUE_ON_ERROR_ENV)
+            .is_some_and(|v| parse_bool_flag(&v)),
+    })
+}
+
+/// Whether the Prometheus `/metrics` endpoint is enabled (`METRICS_ENABLED`).
+fn metrics_enabled() -> bool {
+    config::var("METRICS_ENABLED").is_some_and(|v| parse_bool_flag(&v))
+}
+
+/// Build the access logger shared by the S3 and DynamoDB services.
+///
+/// Access logging is off unless `ACCESS_LOG_FORMAT` is set. Lines go to
+/// tracing, or are appended to `ACCESS_LOG_FILE` when that is set.
+fn build_access_logger() -> Result<Option<Arc<AccessLogger>>> {
+    let Some(format) = config::var("ACCESS_LOG_FORMAT") else {
+        return Ok(None);
+    };
+    let format: AccessLogFormat = format.parse()?;
+    let sink = config::var("ACCESS_LOG_FILE").map_or(AccessLogSink::Tracing, |path| {
+        AccessLogSink::File(path.into())
+    });
+    let logger = AccessLogger::new(format, &sink)
+        .with_context(|| format!("failed to open access log sink {sink:?}"))?;
+
+    info!(?format, ?sink, "access logging enabled");
+    Ok(Some(Arc::new(logger)))
+}
+
+/// Check the configuration of the services `is_enabled` accepts that
+/// validate theirs, failing on the first service with malformed settings.
+#[allow(unused_variables)]
+fn validate_service_configs(is_enabled: impl Fn(&str) -> bool) -> Result<()> {
+    #[cfg(feature = "dynamodb")]
+    if is_enabled("dynamodb") {
+        DynamoDBConfig::from_env()?;
+    }
+    Ok(())
+}
+
+/// Render the effective configuration, after environment variables, the
+/// configuration file, and defaults are applied, as a TOML configuration
+/// file.
+///
+/// # Errors
+///
+/// Returns an error if a service's configuration is malformed.
+pub fn effective_config() -> Result<String> {
+    let mut root = toml::Table::new();
+    root.insert("gateway".into(), effective_gateway_config().into());
+
+    #[cfg(feature = "s3")]
+    {
+        let s3_config = S3Config::from_env();
+        let mut s3 = toml::Table::new();
+        s3.insert("domain".into(), s3_config.s3_domain.into());
+        s3.insert(
+            "virtual_hosting".into(),
+            s3_config.s3_virtual_hosting.into(),
+        );
+        s3.insert(
+            "skip_signature_validation".into(),
+            s3_config.s3_skip_signature_validation.into(),
+        );
+        s3.insert(
+            "max_memory_object_size".into(),
+            toml_int(s3_config.s3_max_memory_object_size),
+        );
+        if let Some(dir) = s3_config.s3_spillover_dir {
+            s3.insert("spillover_dir".into(), dir.into());
+        }
+        s3.insert(
+            "max_disk_bytes".into(),
+            toml_int(s3_config.s3_max_disk_bytes.unwrap_or(0)),
+        );
+        s3.insert(
+            "access_log_flush_interval".into(),
+            toml_int(s3_config.s3_access_log_flush_interval_secs),
+        );
+        s3.insert(
+            "access_log_max_records".into(),
+            toml_int(s3_config.s3_access_log_max_records),
+        );
+        s3.insert(
+            "strict_validation".into(),
+            s3_config.s3_strict_validation.into(),
+        );
+        if let Some(dir) = s3_config.s3_record_dir {
+            s3.insert("record_dir".into(), dir.into());
+        }
+        if let Some(dir) = s3_config.s3_replay_dir {
+            s3.insert("replay_dir".into(), dir.into());
+        }
+        s3.insert(
+            "replay_match_headers".into(),
+            s3_config.s3_replay_match_headers.into(),
+        );
+        s3.insert(
+            "serve_corrupt_objects".into(),
+            s3_config.s3_serve_corrupt_objects.into(),
+        );
+        s3.insert(
+            "max_request_body_bytes".into(),
+            toml_int(s3_config.s3_max_request_body_bytes.unwrap_or(0)),
+        );
+        s3.insert(
+            "legacy_object_ownership".into(),
+            s3_config.s3_legacy_object_ownership.into(),
+        );
+        root.insert("s3".into(), s3.into());
+    }
+
+    #[cfg(feature = "dynamodb")]
+    root.insert("dynamodb".into(), effective_dynamodb_config()?.into());
+
+    Ok(root.to_string())
+}
+
+/// The `[dynamodb]` section of [`effective_config`].
+#[cfg(feature = "dynamodb")]
+fn effective_dynamodb_config() -> Result<toml::Table> {
+    let dynamodb_config = DynamoDBConfig::from_env()?;
+    let behavior = dynamodb_config.behavior;
+    let mut dynamodb = toml::Table::new();
+    dynamodb.insert(
+        "skip_signature_validation".into(),
+        dynamodb_config.skip_signature_validation.into(),
+    );
+    dynamodb.insert(
+        "table_create_delay_ms".into(),
+        toml_int(behavior.table_create_delay.as_millis()),
+    );
+    dynamodb.insert(
+        "table_delete_delay_ms".into(),
+        toml_int(behavior.table_delete_delay.as_millis()),
+    );
+    dynamodb.insert(
+        "strict_reserved_words".into(),
+        behavior.strict_reserved_words.into(),
+    );
+    dynamodb.insert(
+        "batch_write_unprocessed".into(),
+        toml_int(behavior.batch_write_unprocessed),
+    );
+    dynamodb.insert(
+        "response_size_limit".into(),
+        behavior.response_size_limit.into(),
+    );
+    dynamodb.insert(
+        "max_request_body_bytes".into(),
+        toml_int(dynamodb_config.max_request_body_bytes.unwrap_or(0)),
+    );
+    if let Some(dir) = dynamodb_config.persistence_dir {
+        dynamodb.insert("persistence_dir".into(), dir.display().to_string().into());
+    }
+    Ok(dynamodb)
+}
+
+/// The `[gateway]` section of [`effective_config`].
+fn effective_gateway_config() -> toml::Table {
+    let mut gateway = toml::Table::new();
+    gateway.insert("listen".into(), gateway_listen_addr().into());
+    gateway.insert("services".into(), parse_enabled_services().into());
+    gateway.insert(
+        "default_region".into(),
+        config::var("DEFAULT_REGION")
+            .unwrap_or_else(|| "us-east-1".to_owned())
+            .into(),
+    );
+    gateway.insert(
+        "log_level".into(),
+        config::var("LOG_LEVEL")
+            .unwrap_or_else(|| "info".to_owned())
+            .into(),
+    );
+    let limits = ConnectionLimits::from_env();
+    gateway.insert("max_connections".into(), toml_int(limits.max_connections));
+    gateway.insert(
+        "h2_max_concurrent_streams".into(),
+        toml_int(limits.h2_max_concurrent_streams),
+    );
+    gateway.insert(
+        "read_timeout_secs".into(),
+        toml_int(limits.read_timeout.map_or(0, |d| d.as_secs())),
+    );
+    gateway.insert(
+        "write_timeout_secs".into(),
+        toml_int(limits.write_timeout.map_or(0, |d| d.as_secs())),
+    );
+    gateway.insert("max_header_bytes".into(), toml_int(limits.max_header_bytes));
+    gateway.insert(
+        "shutdown_grace_period".into(),
+        toml_int(shutdown_grace_period().as_secs()),
+    );
+    gateway.insert("metrics_enabled".into(), metrics_enabled().into());
+    for (key, name) in [
+        ("listen_tls", tls::LISTEN_TLS_ENV),
+        ("tls_cert", tls::TLS_CERT_ENV),
+        ("tls_key", tls::TLS_KEY_ENV),
+        ("tls_self_signed", tls::TLS_SELF_SIGNED_ENV),
+        ("access_log_format", "ACCESS_LOG_FORMAT"),
+        ("access_log_file", "ACCESS_LOG_FILE"),
+        ("otlp_endpoint", telemetry::OTLP_ENDPOINT_ENV),
+        ("init_data_dir", INIT_DATA_DIR_ENV),
+        ("init_data_continue_on_error", seed::CONTINUE_ON_ERROR_ENV),
+        (
+            "credentials_file",
+            rustack_auth::credentials::CREDENTIALS_FILE_ENV,
+        ),
+    ] {
+        if let Some(value) = config::var(name) {
+            gateway.insert(key.into(), value.into());
+        }
+    }
+    gateway
+}
+
+/// A TOML integer, saturating at `i64::MAX`.
+fn toml_int(n: impl TryInto<i64>) -> toml::Value {
+    toml::Value::Integer(n.try_into().unwrap_or(i64::MAX))
+}
+
+/// Parse a boolean flag value, accepting `"1"` and `"true"` (case-insensitive).
+fn parse_bool_flag(value: &str) -> bool {
+    value == "1" || value.eq_ignore_ascii_case("true")
+}
+
+/// Namespaces serving the `enabled` services, each built like the server's
+/// own but without metrics gauges or seed data.
+fn build_namespaces(enabled: Vec<String>, access_log: Option<Arc<AccessLogger>>) -> Namespaces {
+    Namespaces::new(move || {
+        let mut providers = Providers::default();
+        let services = build_services(
+            |name| enabled.iter().any(|s| s == name) && is_compiled_in(name),
+            &mut Vec::new(),
+            access_log.as_ref(),
+            &mut providers,
+        );
+        (services, providers.tasks)
+    })
+}
+
+/// Build all enabled service routers based on environment configuration.
+///
+/// Services that expose state-derived metrics push a [`GaugeSource`] onto
+/// `gauge_sources`. Services that support access logging share `access_log`.
+/// The providers a [`ServerHandle`] exposes are recorded in `providers`.
+#[allow(clippy::too_many_lines)]
+fn build_services(
+    is_enabled: impl Fn(&str) -> bool,
+    gauge_sources: &mut Vec<Box<dyn GaugeSource>>,
+    access_log: Option<&Arc<AccessLogger>>,
+    providers: &mut Providers,
+) -> Vec<Box<dyn ServiceRouter>> {
+    let mut services: Vec<Box<dyn ServiceRouter>> = Vec::new();
+
+    // ----- S3 provider (its router is registered last, as the catch-all) -----
+    // Build the S3 provider early so that DynamoDB exports and the CloudFront
+    // data plane can share it.
+    #[cfg(feature = "s3")]
+    let s3_provider_arc: Option<Arc<RustackS3>> = if is_enabled("s3") {
+        let s3_config = S3Config::from_env();
+        info!(
+            s3_domain = %s3_config.s3_domain,
+            s3_virtual_hosting = s3_config.s3_virtual_hosting,
+            s3_skip_signature_validation = s3_config.s3_skip_signature_validation,
+            "initializing S3 service",
+        );
+        Some(Arc::new(RustackS3::new(s3_config)))
+    } else {
+        None
+    };
+
+    // ----- DynamoDB + DynamoDB Streams (register before S3: S3 is the catch-all) -----
+    #[cfg(feature = "dynamodb")]
+    if is_enabled("dynamodb") {
+        // Malformed settings fail `ServerBuilder::start` before this runs.
+        let dynamodb_config = DynamoDBConfig::from_env().unwrap_or_default();
+        info!(
+            dynamodb_skip_signature_validation = dynamodb_config.skip_signature_validation,
+            "initializing DynamoDB service",
+        );
+        let mut dynamodb_provider = RustackDynamoDB::new(dynamodb_config.clone());
+
+        // Wire in DynamoDB Streams emitter and lifecycle manager if enabled.
+        #[cfg(feature = "dynamodbstreams")]
+        let stream_store = if is_enabled("dynamodbstreams") {
+            let streams_config = DynamoDBStreamsConfig::from_env();
+            let store = Arc::new(StreamStore::new());
+            let emitter = Arc::new(DynamoDBStreamEmitter::new(
+                Arc::clone(&store),
+                dynamodb_config.default_region.clone(),
+            ));
+            let lifecycle = Arc::new(DynamoDBStreamLifecycleManager::new(
+                Arc::clone(&store),
+                streams_config.default_region.clone(),
+                streams_config.default_account_id.clone(),
+            ));
+            dynamodb_provider.set_emitter(emitter);
+            dynamodb_provider.set_lifecycle(lifecycle);
+            Some((store, streams_config))
+        } else {
+            None
+        };
+
+        // Write table exports to S3 if enabled.
+        #[cfg(feature = "s3")]
+        if let Some(s3) = s3_provider_arc.as_ref() {
+            dynamodb_provider.set_export_writer(Arc::new(
+                dynamodb_export_bridge::S3ExportWriter::new(Arc::clone(s3)),
+            ));
+        }
+
+        let dynamodb_provider = Arc::new(dynamodb_provider);
+        providers.dynamodb = Some(Arc::clone(&dynamodb_provider));
+        let dynamodb_handler = RustackDynamoDBHandler::new(dynamodb_provider);
+        let dynamodb_http_config =
+            build_dynamodb_http_config(&dynamodb_config, access_log.cloned());
+        let dynamodb_service =
+            DynamoDBHttpService::new(Arc::new(dynamodb_handler), dynamodb_http_config);
+        services.push(Box::new(service::DynamoDBServiceRouter::new(
+            dynamodb_service,
+        )));
+
+        // Register DynamoDB Streams router.
+        #[cfg(feature = "dynamodbstreams")]
+        if let Some((store, streams_config)) = stream_store {
+            info!("initializing DynamoDB Streams service");
+            let streams_provider = RustackDynamoDBStreams::new(store, streams_config.clone());
+            let streams_handler = RustackDynamoDBStreamsHandler::new(Arc::new(streams_provider));
+            let streams_http_config = build_dynamodbstreams_http_config(&streams_config);
+            let streams_service =
+                DynamoDBStreamsHttpService::new(Arc::new(streams_handler), streams_http_config);
+            services.push(Box::new(service::DynamoDBStreamsServiceRouter::new(
+                streams_service,
+            )));
+        }
+    }
+
+    // ----- SQS (register before S3: S3 is the catch-all) -----
+    #[cfg(feature = "sqs")]
+    let sqs_provider_arc: Option<Arc<RustackSqs>> = if is_enabled("sqs") {
+        let sqs_config = SqsConfig::from_env();
+        info!(
+            sqs_skip_signature_validation = sqs_config.skip_signature_validation,
+            "initializing SQS service",
+        );
+        let sqs_provider = Arc::new(RustackSqs::new(sqs_config.clone()));
+        providers.sqs = Some(Arc::clone(&sqs_provider));
+        let sqs_handler = RustackSqsHandler::new(Arc::clone(&sqs_provider));
+        let sqs_http_config = build_sqs_http_config(&sqs_config);
+        let sqs_service = SqsHttpService::new(Arc::new(sqs_handler), sqs_http_config);
+        services.push(Box::new(service::SqsServiceRouter::new(sqs_service)));
+        Some(sqs_provider)
+    } else {
+        None
+    };
+
+    // ----- SSM (register before S3: S3 is the catch-all) -----
+    #[cfg(feature = "ssm")]
+    if is_enabled("ssm") {
+        let ssm_config = SsmConfig::from_env();
+        info!(
+            ssm_skip_signature_validation = ssm_config.skip_signature_validation,
+            "initializing SSM service",
+        );
+        let ssm_provider = RustackSsm::new(ssm_config.clone());
+        let ssm_handler = RustackSsmHandler::new(Arc::new(ssm_provider));
+        let ssm_http_config = build_ssm_http_config(&ssm_config);
+        let ssm_service = SsmHttpService::new(Arc::new(ssm_handler), ssm_http_config);
+        services.push(Box::new(service::SsmServiceRouter::new(ssm_service)));
+    }
+
+    // ----- IAM (register BEFORE CloudWatch/SES/SNS: all use form-urlencoded POST) -----
+    // IAM matches on SigV4 service=iam.
+    #[cfg(feature = "iam")]
+    if is_enabled("iam") {
+        let iam_config = IamConfig::from_env();
+        info!(
+            iam_skip_signature_validation = iam_config.skip_signature_validation,
+            "initializing IAM service",
+        );
+        let iam_store = Arc::new(IamStore::new());
+        let iam_provider = RustackIam::new(iam_store, Arc::new(iam_config.clone()));
+        let iam_handler = RustackIamHandler::new(Arc::new(iam_provider));
+        let iam_http_config = build_iam_http_config(&iam_config);
+        let iam_service = IamHttpService::new(Arc::new(iam_handler), iam_http_config);
+        services.push(Box::new(service::IamServiceRouter::new(iam_service)));
+    }
+
+    // ----- CloudWatch Metrics (register BEFORE SES/SNS: all use form-urlencoded POST) -----
+    // CloudWatch matches on SigV4 service=monitoring.
+    #[cfg(feature = "cloudwatch")]
+    if is_enabled("cloudwatch") {
+        let cw_config = CloudWatchConfig::from_env();
+        info!(
+            cloudwatch_skip_signature_validation = cw_config.skip_signature_validation,
+            "initializing CloudWatch Metrics service",
+        );
+        let cw_provider = RustackCloudWatch::new(Arc::new(cw_config.clone()));
+        let cw_handler = RustackCloudWatchHandler::new(Arc::new(cw_provider));
+        let cw_http_config = build_cloudwatch_http_config(&cw_config);
+        let cw_service = CloudWatchHttpService::new(Arc::new(cw_handler), cw_http_config);
+        services.push(Box::new(service::CloudWatchServiceRouter::new(cw_service)));
+    }
+
+    // ----- SES (register BEFORE SNS: both use form-urlencoded POST) -----
+    #[cfg(feature = "ses")]
+    if is_enabled("ses") {
+        let ses_config = SesConfig::from_env();
+        info!(
+            ses_skip_signature_validation = ses_config.skip_signature_validation,
+            ses_require_verified_identity = ses_config.require_verified_identity,
+            "initializing SES service",
+        );
+        let ses_provider = RustackSes::new(ses_config.clone());
+        let ses_handler = Arc::new(RustackSesHandler::new(Arc::new(ses_provider)));
+        let ses_http_config = build_ses_http_config(&ses_config);
+        let ses_v1_service = SesHttpService::new(Arc::clone(&ses_handler), ses_http_config);
+        let ses_v2_service = SesV2HttpService::new(Arc::clone(&ses_handler));
+        services.push(Box::new(service::SesServiceRouter::new(
+            ses_v1_service,
+            ses_v2_service,
+        )));
+    }
+
+    // ----- STS (register BEFORE SNS: both use form-urlencoded POST) -----
+    // STS matches on SigV4 service=sts.
+    #[cfg(feature = "sts")]
+    if is_enabled("sts") {
+        let sts_config = StsConfig::from_env();
+        info!(
+            sts_skip_signature_validation = sts_config.skip_signature_validation,
+            "initializing STS service",
+        );
+        let sts_provider = RustackSts::new(sts_config.clone());
+        let sts_handler = RustackStsHandler::new(Arc::new(sts_provider));
+        let sts_http_config = build_sts_http_config(&sts_config);
+        let sts_service = StsHttpService::new(Arc::new(sts_handler), sts_http_config);
+        services.push(Box::new(service::StsServiceRouter::new(sts_service)));
+    }
+
+    // ----- SNS (register before S3: S3 is the catch-all) -----
+    #[cfg(feature = "sns")]
+    if is_enabled("sns") {
+        let sns_config = SnsConfig::from_env();
+        info!(
+            sns_skip_signature_validation = sns_config.skip_signature_validation,
+            "initializing SNS service",
+        );
+        let sqs_publisher: Arc<dyn rustack_sns_core::publisher::SqsPublisher> =
+            if let Some(ref sqs) = sqs_provider_arc {
+                Arc::new(RustackSqsPublisher::new(
+                    Arc::clone(sqs),
+                    sns_config.clone(),
+                ))
+            } else {
+                Arc::new(rustack_sns_core::publisher::NoopSqsPublisher)
+            };
+        let sns_provider = RustackSns::new(sns_config.clone(), sqs_publisher);
+        let sns_handler = RustackSnsHandler::new(Arc::new(sns_provider));
+        let sns_http_config = build_sns_http_config(&sns_config);
+        let sns_service = SnsHttpService::new(Arc::new(sns_handler), sns_http_config);
+        services.push(Box::new(service::SnsServiceRouter::new(sns_service)));
+    }
+
+    // ----- EventBridge (register before S3: S3 is the catch-all) -----
+    #[cfg(feature = "events")]
+    if is_enabled("events") {
+        let events_config = EventsConfig::from_env();
+        info!(
+            events_skip_signature_validation = events_config.skip_signature_validation,
+            "initializing EventBridge service",
+        );
+        let delivery: Arc<dyn rustack_events_core::delivery::TargetDelivery> =
+            if let Some(ref sqs) = sqs_provider_arc {
+                Arc::new(LocalTargetDelivery::new(
+                    Arc::clone(sqs),
+                    events_config.account_id.clone(),
+                    events_config.host.clone(),
+                    events_config.port,
+                ))
+            } else {
+                Arc::new(rustack_events_core::delivery::NoopTargetDelivery)
+            };
+        let events_provider = RustackEvents::new(events_config.clone(), delivery);
+        let events_handler = RustackEventsHandler::new(Arc::new(events_provider));
+        let events_http_config = build_events_http_config(&events_config);
+        let events_service = EventsHttpService::new(Arc::new(events_handler), events_http_config);
+        services.push(Box::new(service::EventsServiceRouter::new(events_service)));
+    }
+
+    // ----- CloudWatch Logs (register before S3: S3 is the catch-all) -----
+    #[cfg(feature = "logs")]
+    if is_enabled("logs") {
+        let logs_config = LogsConfig::from_env();
+        info!(
+            logs_skip_signature_validation = logs_config.skip_signature_validation,
+            "initializing CloudWatch Logs service",
+        );
+        let logs_provider = RustackLogs::new(logs_config.clone());
+        let logs_handler = RustackLogsHandler::new(Arc::new(logs_provider));
+        let logs_http_config = build_logs_http_config(&logs_config);
+        let logs_service = LogsHttpService::new(Arc::new(logs_handler), logs_http_config);
+        services.push(Box::new(service::LogsServiceRouter::new(logs_service)));
+    }
+
+    // ----- KMS (register before S3: S3 is the catch-all) -----
+    #[cfg(feature = "kms")]
+    if is_enabled("kms") {
+        let kms_config = KmsConfig::from_env();
+        info!(
+            kms_skip_signature_validation = kms_config.skip_signature_validation,
+            "initializing KMS service",
+        );
+        let kms_provider = RustackKms::new(kms_config.clone());
+        let kms_handler = RustackKmsHandler::new(Arc::new(kms_provider));
+        let kms_http_config = build_kms_http_config(&kms_config);
+        let kms_service = KmsHttpService::new(Arc::new(kms_handler), kms_http_config);
+        services.push(Box::new(service::KmsServiceRouter::new(kms_service)));
+    }
+
+    // ----- Kinesis (register before S3: S3 is the catch-all) -----
+    #[cfg(feature = "kinesis")]
+    if is_enabled("kinesis") {
+        let kinesis_config = KinesisConfig::from_env();
+        info!(
+            kinesis_skip_signature_validation = kinesis_config.skip_signature_validation,
+            "initializing Kinesis service",
+        );
+        let kinesis_provider = RustackKinesis::new(kinesis_config.clone());
+        let kinesis_handler = RustackKinesisHandler::new(Arc::new(kinesis_provider));
+        let kinesis_http_config = build_kinesis_http_config(&kinesis_config);
+        let kinesis_service =
+            KinesisHttpService::new(Arc::new(kinesis_handler), kinesis_http_config);
+        services.push(Box::new(service::KinesisServiceRouter::new(
+            kinesis_service,
+        )));
+    }
+
+    // ----- Secrets Manager (register before S3: S3 is the catch-all) -----
+    #[cfg(feature = "secretsmanager")]
+    if is_enabled("secretsmanager") {
+        let sm_config = SecretsManagerConfig::from_env();
+        info!(
+            secretsmanager_skip_signature_validation = sm_config.skip_signature_validation,
+            "initializing Secrets Manager service",
+        );
+        let sm_provider = RustackSecretsManager::new(sm_config.clone());
+        let sm_handler = RustackSecretsManagerHandler::new(Arc::new(sm_provider));
+        let sm_http_config = build_secretsmanager_http_config(&sm_config);
+        let sm_service = SecretsManagerHttpService::new(Arc::new(sm_handler), sm_http_config);
+        services.push(Box::new(service::SecretsManagerServiceRouter::new(
+            sm_service,
+        )));
+    }
+
+    // ----- API Gateway v2 (register before Lambda and S3: S3 is the catch-all) -----
+    #[cfg(feature = "apigatewayv2")]
+    if is_enabled("apigatewayv2") {
+        let apigw_config = ApiGatewayV2Config::from_env();
+        info!(
+            apigatewayv2_skip_signature_validation = apigw_config.skip_signature_validation,
+            "initializing API Gateway v2 service",
+        );
+        let apigw_provider = Arc::new(RustackApiGatewayV2::new(apigw_config.clone()));
+        let apigw_handler = RustackApiGatewayV2Handler::new(Arc::clone(&apigw_provider));
+        let apigw_http_config = build_apigatewayv2_http_config(&apigw_config);
+        let apigw_service =
+            ApiGatewayV2HttpService::new(Arc::new(apigw_handler), apigw_http_config);
+        services.push(Box::new(service::ApiGatewayV2ManagementRouter::new(
+            apigw_service,
+        )));
+        services.push(Box::new(service::ApiGatewayV2ExecutionRouter::new(
+            Arc::clone(&apigw_provider),
+        )));
+    }
+
+    // ----- Lambda (register before S3: S3 is the catch-all) -----
+    #[cfg(feature = "lambda")]
+    if is_enabled("lambda") {
+        let lambda_config = LambdaConfig::from_env();
+        info!(
+            lambda_skip_signature_validation = lambda_config.skip_signature_validation,
+            lambda_docker_enabled = lambda_config.docker_enabled,
+            lambda_executor = ?lambda_config.executor,
+            "initializing Lambda service",
+        );
+        let lambda_provider = RustackLambda::new(lambda_config.clone());
+        let lambda_handler = RustackLambdaHandler::new(Arc::new(lambda_provider));
+        let lambda_http_config = build_lambda_http_config(&lambda_config);
+        let lambda_service = LambdaHttpService::new(Arc::new(lambda_handler), lambda_http_config);
+        services.push(Box::new(service::LambdaServiceRouter::new(lambda_service)));
+    }
+
+    // ----- CloudFront (management + data plane, register before S3 catch-all) -----
+    #[cfg(feature = "cloudfront")]
+    if is_enabled("cloudfront") {
+        let cf_config = CloudFrontConfig::from_env();
+        info!(
+            cloudfront_skip_signature_validation = cf_config.skip_signature_validation,
+            cloudfront_domain_suffix = %cf_config.domain_suffix,
+            "initializing CloudFront service",
+        );
+        let cf_provider = Arc::new(RustackCloudFront::new(cf_config.clone()));
+        let cf_http_config = build_cloudfront_http_config(&cf_config);
+        let cf_service =
+            CloudFrontHttpService::new(Arc::new(Arc::clone(&cf_provider)), cf_http_config);
+
+        // Data plane (register first — it matches a narrower path prefix).
+        #[cfg(feature = "cloudfront-dataplane")]
+        {
+            let mut builder = DataPlane::builder()
+                .cloudfront(Arc::clone(&cf_provider))
+                .config(DataPlaneConfig::from_env());
+            if let Some(s3) = s3_provider_arc.as_ref() {
+                builder = builder.s3(Arc::clone(s3));
+            }
+            match builder.build() {
+                Ok(plane) => {
+                    services.push(Box::new(service::CloudFrontDataPlaneRouter::new(plane)));
+                }
+                Err(e) => warn!(error = %e, "failed to initialise CloudFront data plane"),
+            }
+        }
+
+        services.push(Box::new(service::CloudFrontServiceRouter::new(cf_service)));
+    }
+
+    // ----- S3 (catch-all, must be last) -----
+    #[cfg(feature = "s3")]
+    if let Some(s3_provider) = s3_provider_arc {
+        providers.s3 = Some(Arc::clone(&s3_provider));
+        gauge_sources.push(Box::new(metrics::S3StorageGauges(Arc::clone(&s3_provider))));
+        providers.tasks.push(s3_provider.spawn_access_log_flusher());
+        let s3_config = S3Config::from_env();
+        let s3_handler = handler::RustackHandler((*s3_provider).clone());
+        let s3_http_config = build_s3_http_config(&s3_config, access_log.cloned());
+        providers.s3_latency.clone_from(&s3_http_config.latency);
+        providers.s3_faults.clone_from(&s3_http_config.faults);
+        let s3_service = S3HttpService::new(s3_handler, s3_http_config);
+        services.push(Box::new(service::S3ServiceRouter::new(s3_service)));
+    }
+
+    services
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_parse_services_value_default() {
+        // Empty string = all compiled-in services.
+        let services = parse_services_value("");
+        if cfg!(feature = "s3") {
+            assert!(services.contains(&"s3".to_string()));
+        }
+        if cfg!(feature = "dynamodb") {
+            assert!(services.contains(&"dynamodb".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_should_parse_services_value_explicit() {
+        let services = parse_services_value("dynamodb");
+        assert_eq!(services, vec!["dynamodb"]);
+    }
+
+    #[test]
+    fn test_should_parse_services_value_multiple() {
+        let services = parse_services_value("s3, dynamodb");
+        assert_eq!(services, vec!["s3", "dynamodb"]);
+    }
+
+    #[test]
+    fn test_should_parse_services_value_whitespace() {
+        // Whitespace-only = all compiled-in services.
+        let services = parse_services_value("  ");
+        assert!(!services.is_empty());
+    }
+
+    #[test]
+    fn test_should_parse_services_value_case_insensitive() {
+        let services = parse_services_value("S3,DynamoDB");
+        assert_eq!(services, vec!["s3", "dynamodb"]);
+    }
+
+    #[test]
+    fn test_should_detect_compiled_services() {
+        assert_eq!(is_compiled_in("s3"), cfg!(feature = "s3"));
+        assert_eq!(is_compiled_in("dynamodb"), cfg!(feature = "dynamodb"));
+        assert_eq!(is_compiled_in("sqs"), cfg!(feature = "sqs"));
+        assert_eq!(is_compiled_in("ssm"), cfg!(feature = "ssm"));
+        assert_eq!(is_compiled_in("sns"), cfg!(feature = "sns"));
+        assert_eq!(is_compiled_in("lambda"), cfg!(feature = "lambda"));
+        assert_eq!(is_compiled_in("events"), cfg!(feature = "events"));
+        assert_eq!(is_compiled_in("logs"), cfg!(feature = "logs"));
+        assert_eq!(is_compiled_in("kms"), cfg!(feature = "kms"));
+        assert_eq!(is_compiled_in("kinesis"), cfg!(feature = "kinesis"));
+        assert_eq!(
+            is_compiled_in("secretsmanager"),
+            cfg!(feature = "secretsmanager")
+        );
+        assert_eq!(is_compiled_in("ses"), cfg!(feature = "ses"));
+        assert_eq!(
+            is_compiled_in("apigatewayv2"),
+            cfg!(feature = "apigatewayv2")
+        );
+    }
+
+    #[cfg(feature = "s3")]
+    #[test]
+    fn test_should_build_s3_http_config_from_s3_config() {
+        let config = S3Config::from_env();
+        let http_config = build_s3_http_config(&config, None);
+
+        assert_eq!(http_config.domain, config.s3_domain);
+        assert_eq!(http_config.virtual_hosting, config.s3_virtual_hosting);
+        assert_eq!(
+            http_config.skip_signature_validation,
+            config.s3_skip_signature_validation
+        );
+        assert_eq!(http_config.region, config.default_region);
+    }
+
+    #[test]
+    fn test_should_parse_bool_flag() {
+        assert!(parse_bool_flag("1"));
+        assert!(parse_bool_flag("TRUE"));
+        assert!(!parse_bool_flag("0"));
+        assert!(!parse_bool_flag("yes"));
+    }
+
+    #[cfg(feature = "dynamodb")]
+    #[test]
+    fn test_should_build_dynamodb_http_config_from_dynamodb_config() {
+        let config = DynamoDBConfig::from_env().unwrap();
+        let http_config = build_dynamodb_http_config(&config, None);
+
+        assert_eq!(
+            http_config.skip_signature_validation,
+            config.skip_signature_validation
+        );
+        assert_eq!(http_config.region, config.default_region);
+    }
+
+    #[cfg(any(feature = "dynamodb", feature = "s3"))]
+    async fn http_exchange(addr: SocketAddr, request: &str) -> std::io::Result<String> {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await?;
+        stream.write_all(request.as_bytes()).await?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await?;
+        Ok(response)
+    }
+
+    #[cfg(feature = "dynamodb")]
+    #[tokio::test]
+    async fn test_should_start_on_ephemeral_port_and_shut_down() {
+        let server = ServerBuilder::new()
+            .services(&["DynamoDB"])
+            .start()
+            .await
+            .unwrap();
+        let addr = server.local_addr();
+        assert_ne!(addr.port(), 0);
+        assert_eq!(server.endpoint_url(), format!("http://{addr}"));
+        assert_eq!(server.service_names(), ["dynamodb"]);
+        #[cfg(feature = "s3")]
+        assert!(server.s3().is_none());
+
+        // State seeded through the provider is visible over HTTP.
+        rustack_dynamodb_core::embedded::DynamoDBTestClient::with_provider(
+            server.dynamodb().unwrap().clone(),
+        )
+        .create_simple_table("seeded", "pk")
+        .unwrap();
+        let body = "{}";
+        let response = http_exchange(
+            addr,
+            &format!(
+                "POST / HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\nX-Amz-Target: \
+                 DynamoDB_20120810.ListTables\r\nContent-Type: \
+                 application/x-amz-json-1.0\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            ),
+        )
+        .await
+        .unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.contains("\"seeded\""), "{response}");
+
+        let response = http_exchange(
+            addr,
+            "GET /_localstack/init/ready HTTP/1.1\r\nConnection: close\r\n\r\n",
+        )
+        .await
+        .unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+        server.shutdown().await.unwrap();
+        assert!(
+            http_exchange(addr, "GET /_localstack/health HTTP/1.1\r\n\r\n")
+                .await
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "dynamodb")]
+    #[tokio::test]
+    async fn test_should_serve_https_next_to_plain_http() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio_rustls::{
+            TlsConnector,
+            rustls::{ClientConfig, RootCertStore, pki_types::ServerName},
+        };
+
+        let (cert, key) = tls::self_signed_certificate(tls::SELF_SIGNED_NAMES).unwrap();
+        let server = ServerBuilder::new()
+            .services(&["dynamodb"])
+            .tls(tls::server_config(vec![cert.clone()], key).unwrap())
+            .tls_bind("127.0.0.1:0")
+            .start()
+            .await
+            .unwrap();
+        let plain_addr = server.local_addr();
+        let tls_addr = server.tls_local_addr().unwrap();
+        assert_ne!(plain_addr, tls_addr);
+        assert_eq!(server.endpoint_url(), format!("http://{plain_addr}"));
+
+        let ready = "GET /_localstack/init/ready HTTP/1.1\r\nConnection: close\r\n\r\n";
+        let response = http_exchange(plain_addr, ready).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+        let mut roots = RootCertStore::empty();
+        roots.add(cert).unwrap();
+        let client = ClientConfig::builder_with_provider(Arc::new(
+            tokio_rustls::rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+        let stream = tokio::net::TcpStream::connect(tls_addr).await.unwrap();
+        let mut stream = TlsConnector::from(Arc::new(client))
+            .connect(ServerName::try_from("localhost").unwrap(), stream)
+            .await
+            .unwrap();
+        stream.write_all(ready.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+        server.shutdown().await.unwrap();
+        assert!(tokio::net::TcpStream::connect(tls_addr).await.is_err());
+    }
+
+    /// Send a keep-alive health check on `stream` and read its response.
+    async fn keep_alive_health_check(stream: &mut tokio::net::TcpStream) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        stream
+            .write_all(b"GET /_localstack/health HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = Vec::new();
+        let mut buf = [0u8; 1024];
+        loop {
+            let n = stream.read(&mut buf).await.unwrap();
+            assert_ne!(n, 0, "connection closed");
+            response.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&response);
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let len = head
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length: "))
+                    .and_then(|len| len.parse::<usize>().ok())
+                    .unwrap();
+                if body.len() >= len {
+                    return text.into_owned();
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "s3")]
+    #[tokio::test]
+    async fn test_should_answer_slow_down_over_the_connection_limit() {
+        let server = ServerBuilder::new()
+            .services(&["s3"])
+            .limits(ConnectionLimits {
+                max_connections: 3,
+                ..ConnectionLimits::default()
+            })
+            .start()
+            .await
+            .unwrap();
+        let addr = server.local_addr();
+
+        // Occupy every connection slot with an open keep-alive connection.
+        let mut held = Vec::new();
+        for _ in 0..3 {
+            let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+            let response = keep_alive_health_check(&mut stream).await;
+            assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+            held.push(stream);
+        }
+
+        // A burst of clients is answered promptly rather than left waiting,
+        // and the connections are closed without `Connection: close`.
+        let list_buckets = "GET / HTTP/1.1\r\nHost: localhost\r\n\r\n";
+        let burst: Vec<_> = (0..20)
+            .map(|_| {
+                tokio::spawn(tokio::time::timeout(
+                    Duration::from_secs(5),
+                    http_exchange(addr, list_buckets),
+                ))
+            })
+            .collect();
+        for request in burst {
+            let response = request.await.unwrap().unwrap().unwrap();
+            assert!(response.starts_with("HTTP/1.1 503"), "{response}");
+            assert!(response.contains("<Code>SlowDown</Code>"), "{response}");
+        }
+
+        // Held connections keep being served, and freed slots are reused.
+        let response = keep_alive_health_check(&mut held[0]).await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        drop(held);
+        let mut freed = false;
+        for _ in 0..50 {
+            let request = "GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+            let response = http_exchange(addr, request).await.unwrap();
+            if response.starts_with("HTTP/1.1 200") {
+                freed = true;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(freed, "no slot freed after closing connections");
+
+        server.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_should_drain_connections_within_the_grace_period() {
+        let graceful = GracefulShutdown::new();
+        let mut connections = JoinSet::new();
+        let watcher = graceful.watcher();
+        connections.spawn(async move {
+            tokio::time::sleep(Duration::from_secs(2)).await;
+            drop(watcher);
+        });
+
+        let started = tokio::time::Instant::now();
+        let grace = Duration::from_secs(5);
+        let aborted = drain(graceful, &mut connections, grace, std::future::pending()).await;
+        assert_eq!(aborted, 0);
+        assert_eq!(started.elapsed(), Duration::from_secs(2));
+        assert!(connections.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_should_abort_connections_after_the_grace_period() {
+        let graceful = GracefulShutdown::new();
+        let mut connections = JoinSet::new();
+        for _ in 0..2 {
+            let watcher = graceful.watcher();
+            connections.spawn(async move {
+                let _watcher = watcher;
+                std::future::pending::<()>().await;
+            });
+        }
+        connections.spawn(async {});
+        tokio::task::yield_now().await;
+
+        let started = tokio::time::Instant::now();
+        let grace = Duration::from_secs(5);
+        let aborted = drain(graceful, &mut connections, grace, std::future::pending()).await;
+        assert_eq!(aborted, 2);
+        assert_eq!(started.elapsed(), grace);
+        assert!(connections.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_should_abort_connections_on_request() {
+        let graceful = GracefulShutdown::new();
+        let mut connections = JoinSet::new();
+        let watcher = graceful.watcher();
+        connections.spawn(async move {
+            let _watcher = watcher;
+            std::future::pending::<()>().await;
+        });
+
+        let started = tokio::time::Instant::now();
+        let abort = tokio::time::sleep(Duration::from_secs(1));
+        let aborted = drain(graceful, &mut connections, Duration::from_secs(60), abort).await;
+        assert_eq!(aborted, 1);
+        assert_eq!(started.elapsed(), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_should_reject_tls_bind_without_certificate() {
+        let err = ServerBuilder::new()
+            .tls_bind("127.0.0.1:0")
+            .start()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("GATEWAY_LISTEN_TLS"), "{err}");
+    }
+
+    #[cfg(feature = "dynamodb")]
+    #[tokio::test]
+    async fn test_should_checksum_dynamodb_responses_on_the_wire() {
+        let server = ServerBuilder::new()
+            .services(&["dynamodb"])
+            .start()
+            .await
+            .unwrap();
+        let addr = server.local_addr();
+
+        for (target, body, status) in [
+            ("ListTables", "{}", "200"),
+            ("DescribeTable", r#"{"TableName":"missing"}"#, "400"),
+            ("NoSuchOperation", "{}", "400"),
+        ] {
+            let response = http_exchange(
+                addr,
+                &format!(
+                    "POST / HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\nX-Amz-Target: \
+                     DynamoDB_20120810.{target}\r\nContent-Type: \
+                     application/x-amz-json-1.0\r\nContent-Length: {}\r\n\r\n{body}",
+                    body.len()
+                ),
+            )
+            .await
+            .unwrap();
+            let (head, wire_body) = response.split_once("\r\n\r\n").unwrap();
+            let header = |name: &str| {
+                head.lines()
+                    .find_map(|line| line.strip_prefix(&format!("{name}: ")))
+                    .unwrap_or_else(|| panic!("{target}: no {name} header in {head}"))
+            };
+            assert_eq!(head.split_whitespace().nth(1), Some(status), "{target}");
+            assert_eq!(header("content-type"), "application/x-amz-json-1.0");
+            assert_eq!(
+                header("x-amz-crc32"),
+                crc32fast::hash(wire_body.as_bytes()).to_string(),
+                "{target}"
+            );
+            if status != "200" {
+                let error: serde_json::Value = serde_json::from_str(wire_body).unwrap();
+                let error_type = error["__type"].as_str().unwrap();
+                assert!(error_type.contains('#'), "{target}: {error_type}");
+            }
+        }
+
+        server.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_should_reject_start_without_compiled_services() {
+        let err = ServerBuilder::new()
+            .services(&["nonexistent"])
+            .start()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no services enabled"));
+    }
+
+    /// Send an unsigned S3 request and return the status and error code.
+    #[cfg(feature = "s3")]
+    async fn s3_exchange(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, String) {
+        let response = http_exchange(
+            addr,
+            &format!(
+                "{method} {path} HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\nContent-Length: \
+                 {}\r\n\r\n{body}",
+                body.len()
+            ),
+        )
+        .await
+        .unwrap();
+        let status = response
+            .split(' ')
+            .nth(1)
+            .and_then(|code| code.parse().ok())
+            .unwrap_or_default();
+        (
+            status,
+            xml_text(&response, "Code").unwrap_or_else(|| response.clone()),
+        )
+    }
+
+    #[cfg(feature = "s3")]
+    fn xml_text(xml: &str, element: &str) -> Option<String> {
+        let start = xml.find(&format!("<{element}>"))? + element.len() + 2;
+        let end = xml[start..].find(&format!("</{element}>"))? + start;
+        Some(xml[start..end].to_owned())
+    }
+
+    #[cfg(feature = "s3")]
+    #[tokio::test]
+    async fn test_should_reset_state_on_request() {
+        let server = ServerBuilder::new()
+            .services(&["S3"])
+            .start()
+            .await
+            .unwrap();
+        let addr = server.local_addr();
+        let (status, _) = s3_exchange(addr, "PUT", "/seeded", "").await;
+        assert_eq!(status, 200);
+
+        let response = http_exchange(
+            addr,
+            "POST /_localstack/state/reset HTTP/1.1\r\nConnection: close\r\nContent-Length: \
+             0\r\n\r\n",
+        )
+        .await
+        .unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.ends_with(r#"{"reset":["s3"]}"#), "{response}");
+
+        let (status, code) = s3_exchange(addr, "GET", "/seeded", "").await;
+        assert_eq!((status, code.as_str()), (404, "NoSuchBucket"));
+        server.shutdown().await.unwrap();
+    }
+
+    #[cfg(feature = "s3")]
+    #[tokio::test]
+    async fn test_should_isolate_namespaces() {
+        let server = ServerBuilder::new()
+            .services(&["S3"])
+            .start()
+            .await
+            .unwrap();
+        let addr = server.local_addr();
+        let send = |method: &str, path: &str, namespace: &str| {
+            let request = format!(
+                "{method} {path} HTTP/1.1\r\nHost: {addr}\r\nConnection: \
+                 close\r\nx-ruststack-namespace: {namespace}\r\nContent-Length: 0\r\n\r\n"
+            );
+            async move {
+                let response = http_exchange(addr, &request).await.unwrap();
+                let status = response.split(' ').nth(1).unwrap_or_default().to_owned();
+                (status, response)
+            }
+        };
+
+        assert_eq!(send("PUT", "/shared", "worker-1").await.0, "200");
+        assert_eq!(send("GET", "/shared", "worker-1").await.0, "200");
+        assert_eq!(send("GET", "/shared", "worker-2").await.0, "404");
+        assert_eq!(s3_exchange(addr, "GET", "/shared", "").await.0, 404);
+        assert_eq!(send("GET", "/shared", "default").await.0, "404");
+        assert_eq!(send("GET", "/shared", "bad.name").await.0, "400");
+
+        let (status, listing) = send("GET", "/_ruststack/namespaces", "default").await;
+        assert_eq!(status, "200");
+        assert!(
+            listing.ends_with(r#"{"namespaces":["default","worker-1","worker-2"]}"#),
+            "{listing}"
+        );
+
+        let deleted = send("DELETE", "/_ruststack/namespaces/worker-1", "default").await;
+        assert_eq!(deleted.0, "200", "{}", deleted.1);
+        assert_eq!(send("GET", "/shared", "worker-1").await.0, "404");
+        server.shutdown().await.unwrap();
+    }
+
+    #[cfg(feature = "s3")]
+    #[tokio::test]
+    async fn test_should_scrub_bucket_on_request() {
+        let server = ServerBuilder::new()
+            .services(&["S3"])
+            .start()
+            .await
+            .unwrap();
+        let addr = server.local_addr();
+        s3_exchange(addr, "PUT", "/scrubbed", "").await;
+        let (status, _) = s3_exchange(addr, "PUT", "/scrubbed/obj", "hello").await;
+        assert_eq!(status, 200);
+
+        let scrub = |bucket: &str| {
+            format!(
+                "POST /_localstack/s3/scrub/{bucket} HTTP/1.1\r\nConnection: \
+                 close\r\nContent-Length: 0\r\n\r\n"
+            )
+        };
+        let response = http_exchange(addr, &scrub("scrubbed")).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        let body: serde_json::Value =
+            serde_json::from_str(&response[response.find('{').unwrap()..]).unwrap();
+        assert_eq!(body["objectsChecked"], 1);
+        assert_eq!(body["bytesChecked"], 5);
+        assert_eq!(body["mismatches"], serde_json::json!([]));
+
+        let response = http_exchange(addr, &scrub("missing")).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 404"), "{response}");
+        server.shutdown().await.unwrap();
+    }
+
+    #[cfg(feature = "s3")]
+    #[tokio::test]
+    async fn test_should_inject_s3_latency_set_at_runtime() {
+        let server = ServerBuilder::new()
+            .services(&["S3"])
+            .start()
+            .await
+            .unwrap();
+        let addr = server.local_addr();
+        s3_exchange(addr, "PUT", "/slow", "").await;
+
+        let rules = r#"{"rules":[{"operation":"HeadBucket","bucket":"slow","delayMs":300}]}"#;
+        let response = http_exchange(
+            addr,
+            &format!(
+                "PUT {} HTTP/1.1\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{rules}",
+                rules::S3_LATENCY_PATH,
+                rules.len()
+            ),
+        )
+        .await
+        .unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+        let (_, active) = management_get(addr, rules::S3_LATENCY_PATH).await;
+        assert_eq!(active["rules"][0]["delayMs"], 300);
+        assert_eq!(active["rules"][0]["probability"], 1.0);
+
+        let started = std::time::Instant::now();
+        assert_eq!(s3_exchange(addr, "HEAD", "/slow", "").await.0, 200);
+        assert!(started.elapsed() >= Duration::from_millis(300));
+
+        let started = std::time::Instant::now();
+        assert_eq!(s3_exchange(addr, "GET", "/slow", "").await.0, 200);
+        assert!(started.elapsed() < Duration::from_millis(300));
+        server.shutdown().await.unwrap();
+    }
+
+    #[cfg(feature = "s3")]
+    #[tokio::test]
+    async fn test_should_inject_s3_errors_set_at_runtime() {
+        let server = ServerBuilder::new()
+            .services(&["S3"])
+            .start()
+            .await
+            .unwrap();
+        let addr = server.local_addr();
+        s3_exchange(addr, "PUT", "/flaky", "").await;
+        s3_exchange(addr, "PUT", "/flaky/obj", "data").await;
+
+        let rules = r#"{"rules":[{"operation":"GetObject","keyPrefix":"obj","errorCode":"SlowDown","count":2,"requestId":"injected"}]}"#;
+        let response = http_exchange(
+            addr,
+            &format!(
+                "PUT {} HTTP/1.1\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{rules}",
+                rules::S3_FAULTS_PATH,
+                rules.len()
+            ),
+        )
+        .await
+        .unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+        let response = http_exchange(
+            addr,
+            &format!("GET /flaky/obj HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\n\r\n"),
+        )
+        .await
+        .unwrap();
+        assert!(response.starts_with("HTTP/1.1 503"), "{response}");
+        assert!(
+            response.contains("x-amz-request-id: injected"),
+            "{response}"
+        );
+        assert_eq!(
+            xml_text(&response, "RequestId").as_deref(),
+            Some("injected")
+        );
+
+        let (_, active) = management_get(addr, rules::S3_FAULTS_PATH).await;
+        assert_eq!(active["rules"][0]["count"], 1);
+
+        assert_eq!(
+            s3_exchange(addr, "GET", "/flaky/obj", "").await,
+            (503, "SlowDown".to_owned())
+        );
+        assert_eq!(s3_exchange(addr, "GET", "/flaky/obj", "").await.0, 200);
+        let (_, active) = management_get(addr, rules::S3_FAULTS_PATH).await;
+        assert_eq!(active["rules"], serde_json::json!([]));
+        server.shutdown().await.unwrap();
+    }
+
+    /// The status line and JSON body of a management endpoint's response.
+    #[cfg(feature = "s3")]
+    async fn management_get(addr: SocketAddr, path: &str) -> (String, serde_json::Value) {
+        let response = http_exchange(
+            addr,
+            &format!("GET {path} HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\n\r\n"),
+        )
+        .await
+        .unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        assert!(head.contains("content-type: application/json"), "{head}");
+        let status = head.lines().next().unwrap().to_owned();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    /// The probes testcontainers and the LocalStack CLI send once the
+    /// container logs `Ready.`, answered by the gateway even with S3 as the
+    /// catch-all.
+    #[cfg(feature = "s3")]
+    #[tokio::test]
+    async fn test_should_answer_localstack_readiness_probes() {
+        let server = ServerBuilder::new()
+            .services(&["S3"])
+            .start()
+            .await
+            .unwrap();
+        let addr = server.local_addr();
+
+        let (status, body) = management_get(addr, "/_localstack/init/ready").await;
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(body["completed"], true);
+
+        let (status, body) = management_get(addr, "/_localstack/health").await;
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(body["services"]["s3"], "running");
+
+        let (status, info) = management_get(addr, "/_localstack/info").await;
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(info["version"], crate::VERSION);
+        assert_eq!(info["services"], serde_json::json!(["s3"]));
+        assert!(info["session_id"].is_string());
+        let (_, again) = management_get(addr, "/_localstack/info").await;
+        assert_eq!(info["session_id"], again["session_id"]);
+
+        for path in ["/_localstack/diagnose", "/_aws/sqs/messages"] {
+            let (status, body) = management_get(addr, path).await;
+            assert_eq!(status, "HTTP/1.1 404 Not Found", "{path}");
+            assert_eq!(body["path"], path);
+        }
+        server.shutdown().await.unwrap();
+    }
+
+    #[cfg(feature = "s3")]
+    #[tokio::test]
+    #[allow(clippy::too_many_lines)]
+    async fn test_should_map_s3_request_errors_to_documented_codes() {
+        let server = ServerBuilder::new()
+            .services(&["S3"])
+            .start()
+            .await
+            .unwrap();
+        let addr = server.local_addr();
+
+        let (status, _) = s3_exchange(addr, "PUT", "/conformance", "").await;
+        assert_eq!(status, 200);
+        let (status, _) = s3_exchange(addr, "PUT", "/conformance/part-1", "x").await;
+        assert_eq!(status, 200);
+        let created = http_exchange(
+            addr,
+            &format!(
+                "POST /conformance/mpu?uploads HTTP/1.1\r\nHost: {addr}\r\nConnection: \
+                 close\r\nContent-Length: 0\r\n\r\n"
+            ),
+        )
+        .await
+        .unwrap();
+        let upload_id = xml_text(&created, "UploadId").expect("upload id");
+        for part in [1, 2] {
+            let (status, _) = s3_exchange(
+                addr,
+                "PUT",
+                &format!("/conformance/mpu?partNumber={part}&uploadId={upload_id}"),
+                "part",
+            )
+            .await;
+            assert_eq!(status, 200);
+        }
+        let complete = format!("/conformance/mpu?uploadId={upload_id}");
+        let parts = |numbers: &[u32]| {
+            let parts = numbers
+                .iter()
+                .map(|n| format!("<Part><PartNumber>{n}</PartNumber></Part>"))
+                .collect::<Vec<_>>()
+                .concat();
+            format!("<CompleteMultipartUpload>{parts}</CompleteMultipartUpload>")
+        };
+        let duplicate_tags = "<Tagging><TagSet><Tag><Key>a</Key><Value>1</Value></\
+                              Tag><Tag><Key>a</Key><Value>2</Value></Tag></TagSet></Tagging>";
+
+        let cases = [
+            (
+                "PUT",
+                "/conformance?cors",
+                "not xml".to_owned(),
+                400,
+                "MalformedXML",
+            ),
+            (
+                "PUT",
+                "/conformance?cors",
+                "<CORSConfiguration><CORSRule>".to_owned(),
+                400,
+                "MalformedXML",
+            ),
+            (
+                "PUT",
+                "/conformance?cors",
+                "<CORSConfiguration/>".to_owned(),
+                400,
+                "MalformedXML",
+            ),
+            (
+                "PUT",
+                "/conformance?tagging",
+                duplicate_tags.to_owned(),
+                400,
+                "InvalidTag",
+            ),
+            (
+                "PUT",
+                "/conformance/part-1?tagging",
+                duplicate_tags.to_owned(),
+                400,
+                "InvalidTag",
+            ),
+            (
+                "PUT",
+                "/conformance?tagging",
+                "<Tagging><TagSet>".to_owned(),
+                400,
+                "MalformedXML",
+            ),
+            (
+                "PUT",
+                "/conformance?versioning",
+                "{}".to_owned(),
+                400,
+                "MalformedXML",
+            ),
+            (
+                "PUT",
+                "/conformance?lifecycle",
+                "<Lifecycle".to_owned(),
+                400,
+                "MalformedXML",
+            ),
+            (
+                "PUT",
+                "/conformance?encryption",
+                "<ServerSideEncryptionConfiguration/>".to_owned(),
+                400,
+                "MalformedXML",
+            ),
+            (
+                "POST",
+                "/conformance?delete",
+                "<Delete><Object>".to_owned(),
+                400,
+                "MalformedXML",
+            ),
+            ("POST", &complete, parts(&[2, 1]), 400, "InvalidPartOrder"),
+            ("POST", &complete, parts(&[1, 7]), 400, "InvalidPart"),
+            ("POST", &complete, parts(&[]), 400, "MalformedXML"),
+            (
+                "POST",
+                &complete,
+                "<CompleteMultipartUpload><Part>".to_owned(),
+                400,
+                "MalformedXML",
+            ),
+            (
+                "POST",
+                "/conformance/mpu?uploadId=missing",
+                parts(&[1]),
+                404,
+                "NoSuchUpload",
+            ),
+            (
+                "PUT",
+                &format!("/conformance/mpu?partNumber=0&uploadId={upload_id}"),
+                "x".to_owned(),
+                400,
+                "InvalidArgument",
+            ),
+            (
+                "PUT",
+                "/Bad_Bucket",
+                String::new(),
+                400,
+                "InvalidBucketName",
+            ),
+            (
+                "GET",
+                "/conformance/missing",
+                String::new(),
+                404,
+                "NoSuchKey",
+            ),
+            ("GET", "/missing-bucket", String::new(), 404, "NoSuchBucket"),
+        ];
+        let mut mismatches = Vec::new();
+        for (method, path, body, status, code) in cases {
+            let actual = s3_exchange(addr, method, path, &body).await;
+            if actual != (status, code.to_owned()) {
+                mismatches.push(format!("{method} {path} {body}: got {actual:?}"));
+            }
+        }
+        assert!(mismatches.is_empty(), "{mismatches:#?}");
+
+        server.shutdown().await.unwrap();
+    }
+}
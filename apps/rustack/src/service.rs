@@ -33,14 +33,41 @@ pub trait ServiceRouter: Send + Sync {
     /// Service name for health reporting (e.g., `"s3"`, `"dynamodb"`).
     fn name(&self) -> &'static str;
 
+    /// Service names this router answers to in a Host header's service label
+    /// (`dynamodb.localhost.localstack.cloud`) or a SigV4 credential scope
+    /// (`AKID/20240101/us-east-1/dynamodb/aws4_request`).
+    ///
+    /// The gateway uses these to pick a router before falling back to
+    /// [`matches`](ServiceRouter::matches) heuristics. Routers that are only
+    /// reachable by path or host pattern claim no names.
+    fn endpoint_names(&self) -> &'static [&'static str] {
+        &[]
+    }
+
     /// Returns `true` if this router should handle the given request.
     fn matches(&self, req: &http::Request<Incoming>) -> bool;
 
+    /// Whether [`matches`](ServiceRouter::matches) accepts every request.
+    ///
+    /// The gateway does not hand a catch-all router the `/_localstack/` and
+    /// `/_aws/` management paths that no other router claimed.
+    fn is_catch_all(&self) -> bool {
+        false
+    }
+
     /// Handle the request, producing a response with a type-erased body.
     fn call(
         &self,
         req: http::Request<Incoming>,
     ) -> Pin<Box<dyn Future<Output = Result<http::Response<GatewayBody>, Infallible>> + Send>>;
+
+    /// Name of the operation that produced `resp`, used as a metrics label.
+    ///
+    /// Services whose HTTP layer records the resolved operation in the
+    /// response extensions override this; others report no operation.
+    fn operation_name(&self, _resp: &http::Response<GatewayBody>) -> Option<&'static str> {
+        None
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -54,6 +81,7 @@ mod s3_router {
     use http_body_util::BodyExt;
     use hyper::{body::Incoming, service::Service};
     use rustack_s3_http::{dispatch::S3Handler, service::S3HttpService};
+    use rustack_s3_model::S3Operation;
 
     use super::{GatewayBody, ServiceRouter};
 
@@ -77,11 +105,19 @@ mod s3_router {
             "s3"
         }
 
+        fn endpoint_names(&self) -> &'static [&'static str] {
+            &["s3"]
+        }
+
         /// S3 is the default service — it matches every request.
         fn matches(&self, _req: &http::Request<Incoming>) -> bool {
             true
         }
 
+        fn is_catch_all(&self) -> bool {
+            true
+        }
+
         fn call(
             &self,
             req: http::Request<Incoming>,
@@ -93,6 +129,12 @@ mod s3_router {
                 Ok(resp.unwrap_or_else(|e| match e {}).map(BodyExt::boxed))
             })
         }
+
+        fn operation_name(&self, resp: &http::Response<GatewayBody>) -> Option<&'static str> {
+            resp.extensions()
+                .get::<S3Operation>()
+                .map(S3Operation::as_str)
+        }
     }
 }
 
@@ -110,6 +152,7 @@ mod dynamodb_router {
     use http_body_util::BodyExt;
     use hyper::{body::Incoming, service::Service};
     use rustack_dynamodb_http::{dispatch::DynamoDBHandler, service::DynamoDBHttpService};
+    use rustack_dynamodb_model::operations::DynamoDBOperation;
 
     use super::{GatewayBody, ServiceRouter};
 
@@ -132,6 +175,10 @@ mod dynamodb_router {
             "dynamodb"
         }
 
+        fn endpoint_names(&self) -> &'static [&'static str] {
+            &["dynamodb"]
+        }
+
         fn matches(&self, req: &http::Request<Incoming>) -> bool {
             req.headers()
                 .get("x-amz-target")
@@ -150,6 +197,12 @@ mod dynamodb_router {
                 Ok(resp.unwrap_or_else(|e| match e {}).map(BodyExt::boxed))
             })
         }
+
+        fn operation_name(&self, resp: &http::Response<GatewayBody>) -> Option<&'static str> {
+            resp.extensions()
+                .get::<DynamoDBOperation>()
+                .map(DynamoDBOperation::as_str)
+        }
     }
 }
 
@@ -191,6 +244,12 @@ mod dynamodbstreams_router {
             "dynamodbstreams"
         }
 
+        /// Streams requests are signed as `dynamodb`; among the routers
+        /// claiming that scope, `matches` tells them apart.
+        fn endpoint_names(&self) -> &'static [&'static str] {
+            &["streams", "dynamodb"]
+        }
+
         fn matches(&self, req: &http::Request<Incoming>) -> bool {
             req.headers()
                 .get("x-amz-target")
@@ -248,6 +307,10 @@ mod sqs_router {
             "sqs"
         }
 
+        fn endpoint_names(&self) -> &'static [&'static str] {
+            &["sqs"]
+        }
+
         fn matches(&self, req: &http::Request<Incoming>) -> bool {
             req.headers()
                 .get("x-amz-target")
@@ -305,6 +368,10 @@ mod ssm_router {
             "ssm"
         }
 
+        fn endpoint_names(&self) -> &'static [&'static str] {
+            &["ssm"]
+        }
+
         fn matches(&self, req: &http::Request<Incoming>) -> bool {
             req.headers()
                 .get("x-amz-target")
@@ -381,6 +448,10 @@ mod sns_router {
             "sns"
         }
 
+        fn endpoint_names(&self) -> &'static [&'static str] {
+            &["sns"]
+        }
+
         /// SNS matches form-urlencoded POST requests signed with `sns` SigV4
         /// service name. All SNS operations require SigV4 authentication.
         fn matches(&self, req: &http::Request<Incoming>) -> bool {
@@ -506,6 +577,10 @@ mod lambda_router {
             "lambda"
         }
 
+        fn endpoint_names(&self) -> &'static [&'static str] {
+            &["lambda"]
+        }
+
         fn matches(&self, req: &http::Request<Incoming>) -> bool {
             let path = req.uri().path();
             is_lambda_path(path)
@@ -561,6 +636,10 @@ mod events_router {
             "events"
         }
 
+        fn endpoint_names(&self) -> &'static [&'static str] {
+            &["events"]
+        }
+
         fn matches(&self, req: &http::Request<Incoming>) -> bool {
             req.headers()
                 .get("x-amz-target")
@@ -618,6 +697,10 @@ mod logs_router {
             "logs"
         }
 
+        fn endpoint_names(&self) -> &'static [&'static str] {
+            &["logs"]
+        }
+
         fn matches(&self, req: &http::Request<Incoming>) -> bool {
             req.headers()
                 .get("x-amz-target")
@@ -675,6 +758,10 @@ mod kms_router {
             "kms"
         }
 
+        fn endpoint_names(&self) -> &'static [&'static str] {
+            &["kms"]
+        }
+
         fn matches(&self, req: &http::Request<Incoming>) -> bool {
             req.headers()
                 .get("x-amz-target")
@@ -732,6 +819,10 @@ mod kinesis_router {
             "kinesis"
         }
 
+        fn endpoint_names(&self) -> &'static [&'static str] {
+            &["kinesis"]
+        }
+
         fn matches(&self, req: &http::Request<Incoming>) -> bool {
             req.headers()
                 .get("x-amz-target")
@@ -791,6 +882,10 @@ mod secretsmanager_router {
             "secretsmanager"
         }
 
+        fn endpoint_names(&self) -> &'static [&'static str] {
+            &["secretsmanager"]
+        }
+
         fn matches(&self, req: &http::Request<Incoming>) -> bool {
             req.headers()
                 .get("x-amz-target")
@@ -873,6 +968,10 @@ mod ses_router {
             "ses"
         }
 
+        fn endpoint_names(&self) -> &'static [&'static str] {
+            &["ses", "email"]
+        }
+
         /// SES matches in three ways:
         /// 1. SES v2: path starts with `/v2/email/`
         /// 2. SES retrospection: path starts with `/_aws/ses`
@@ -1041,6 +1140,10 @@ mod apigatewayv2_router {
             "apigatewayv2"
         }
 
+        fn endpoint_names(&self) -> &'static [&'static str] {
+            &["apigateway"]
+        }
+
         fn matches(&self, req: &http::Request<Incoming>) -> bool {
             let path = req.uri().path();
             path.starts_with("/v2/apis")
@@ -1239,6 +1342,10 @@ mod cloudwatch_router {
             "cloudwatch"
         }
 
+        fn endpoint_names(&self) -> &'static [&'static str] {
+            &["monitoring"]
+        }
+
         /// CloudWatch Metrics matches in three ways:
         /// 1. awsQuery: form-urlencoded POST signed with `monitoring` SigV4 service.
         /// 2. rpcv2Cbor path: POST to `/service/GraniteServiceVersion20100801/...`.
@@ -1358,6 +1465,10 @@ mod iam_router {
             "iam"
         }
 
+        fn endpoint_names(&self) -> &'static [&'static str] {
+            &["iam"]
+        }
+
         /// IAM matches form-urlencoded POST requests signed
         /// with the `iam` SigV4 service name.
         fn matches(&self, req: &http::Request<Incoming>) -> bool {
@@ -1444,6 +1555,10 @@ mod sts_router {
             "sts"
         }
 
+        fn endpoint_names(&self) -> &'static [&'static str] {
+            &["sts"]
+        }
+
         /// STS matches form-urlencoded POST requests signed with `sts` SigV4
         /// service name, or unsigned form-urlencoded requests (for federation
         /// operations like `AssumeRoleWithWebIdentity` and `AssumeRoleWithSAML`
@@ -1538,6 +1653,10 @@ mod cloudfront_router {
             "cloudfront"
         }
 
+        fn endpoint_names(&self) -> &'static [&'static str] {
+            &["cloudfront"]
+        }
+
         fn matches(&self, req: &http::Request<Incoming>) -> bool {
             if req.uri().path().starts_with("/2020-05-31/") {
                 return true;
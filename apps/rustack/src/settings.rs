@@ -0,0 +1,199 @@
+//! Runtime-adjustable service settings.
+//!
+//! Settings that tests change while the server runs, such as the DynamoDB
+//! behavior toggles on [`DYNAMODB_CONFIG_PATH`], are each served on a
+//! management path:
+//!
+//! - `GET <path>` answers with the service's configuration as a JSON object.
+//! - `PATCH <path>` changes the settings named in a JSON object body, and answers with the new
+//!   configuration.
+//!
+//! A body that is not a JSON object, or that names an unknown setting, a
+//! setting fixed at startup, or a value of the wrong type, is answered with
+//! a JSON `400` and leaves every setting unchanged. Settings act on the
+//! server's own services, not on other namespaces.
+
+use std::{collections::HashMap, sync::Arc};
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Limited};
+use serde_json::{Map, Value, json};
+
+/// Path of the DynamoDB configuration.
+pub const DYNAMODB_CONFIG_PATH: &str = "/_ruststack/dynamodb/config";
+
+/// Largest settings body accepted.
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// A service configuration readable as JSON and partly adjustable at runtime.
+pub trait Settings: Send + Sync {
+    /// The current configuration, as a JSON object.
+    fn to_json(&self) -> Value;
+
+    /// Change the settings named in `patch` to its values.
+    ///
+    /// # Errors
+    ///
+    /// Returns why `patch` is invalid, leaving every setting unchanged.
+    fn patch_from_json(&self, patch: &Map<String, Value>) -> Result<(), String>;
+}
+
+#[cfg(feature = "dynamodb")]
+impl Settings for rustack_dynamodb_core::provider::RustackDynamoDB {
+    fn to_json(&self) -> Value {
+        self.config().to_json()
+    }
+
+    fn patch_from_json(&self, patch: &Map<String, Value>) -> Result<(), String> {
+        self.update_config(patch).map(drop)
+    }
+}
+
+/// The settings behind the gateway, by management path.
+#[derive(Default)]
+pub struct SettingsEndpoints {
+    endpoints: HashMap<&'static str, Arc<dyn Settings>>,
+}
+
+impl std::fmt::Debug for SettingsEndpoints {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SettingsEndpoints")
+            .field("paths", &self.endpoints.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl SettingsEndpoints {
+    /// Serve `settings` on `path`.
+    pub fn add(&mut self, path: &'static str, settings: Arc<dyn Settings>) {
+        self.endpoints.insert(path, settings);
+    }
+
+    /// The settings served on `path`, if any.
+    pub fn find(&self, path: &str) -> Option<Arc<dyn Settings>> {
+        self.endpoints.get(path).cloned()
+    }
+}
+
+/// Serve one request to a settings management path, answering with the
+/// response status and JSON body.
+pub async fn handle<B>(settings: &dyn Settings, req: http::Request<B>) -> (http::StatusCode, String)
+where
+    B: hyper::body::Body<Data = Bytes>,
+    B::Error: std::error::Error + Send + Sync + 'static,
+{
+    match *req.method() {
+        http::Method::GET => {}
+        http::Method::PATCH => {
+            let body = match Limited::new(req.into_body(), MAX_BODY_BYTES)
+                .collect()
+                .await
+            {
+                Ok(body) => body.to_bytes(),
+                Err(e) => return bad_request(&format!("failed to read body: {e}")),
+            };
+            let patch = match serde_json::from_slice::<Value>(&body) {
+                Ok(Value::Object(patch)) => patch,
+                Ok(_) => return bad_request("expected a JSON object of settings"),
+                Err(e) => return bad_request(&e.to_string()),
+            };
+            if let Err(e) = settings.patch_from_json(&patch) {
+                return bad_request(&e);
+            }
+        }
+        _ => {
+            let body = json!({ "error": "method not allowed" }).to_string();
+            return (http::StatusCode::METHOD_NOT_ALLOWED, body);
+        }
+    }
+    (http::StatusCode::OK, settings.to_json().to_string())
+}
+
+/// A JSON `400` reporting `error`.
+fn bad_request(error: &str) -> (http::StatusCode, String) {
+    let body = json!({ "error": error }).to_string();
+    (http::StatusCode::BAD_REQUEST, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "dynamodb")]
+    async fn send(
+        settings: &dyn Settings,
+        method: http::Method,
+        body: &str,
+    ) -> (http::StatusCode, String) {
+        let req = http::Request::builder()
+            .method(method)
+            .uri(DYNAMODB_CONFIG_PATH)
+            .body(http_body_util::Full::new(Bytes::from(body.to_owned())))
+            .unwrap();
+        handle(settings, req).await
+    }
+
+    #[cfg(feature = "dynamodb")]
+    #[tokio::test]
+    async fn test_should_read_and_patch_dynamodb_config() {
+        use rustack_dynamodb_core::{config::DynamoDBConfig, provider::RustackDynamoDB};
+
+        let provider = RustackDynamoDB::new(DynamoDBConfig::default());
+
+        let (status, body) = send(&provider, http::Method::GET, "").await;
+        assert_eq!(status, http::StatusCode::OK);
+        let config: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(config["strict_reserved_words"], true);
+
+        let patch = r#"{"strict_reserved_words":false,"table_create_delay_ms":250}"#;
+        let (status, body) = send(&provider, http::Method::PATCH, patch).await;
+        assert_eq!(status, http::StatusCode::OK);
+        let config: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(config["strict_reserved_words"], false);
+        assert_eq!(config["table_create_delay_ms"], 250);
+        assert!(!provider.config().behavior.strict_reserved_words);
+    }
+
+    #[cfg(feature = "dynamodb")]
+    #[tokio::test]
+    async fn test_should_reject_invalid_patches_without_changing_settings() {
+        use rustack_dynamodb_core::{config::DynamoDBConfig, provider::RustackDynamoDB};
+
+        let provider = RustackDynamoDB::new(DynamoDBConfig::default());
+
+        for body in [
+            "not json",
+            "[]",
+            r#"{"persistence_dir":"/tmp/tables"}"#,
+            r#"{"strict_reserved_words":false,"default_region":"eu-west-1"}"#,
+            r#"{"strict_reserved_words":"no"}"#,
+            r#"{"no_such_setting":1}"#,
+        ] {
+            let (status, _) = send(&provider, http::Method::PATCH, body).await;
+            assert_eq!(status, http::StatusCode::BAD_REQUEST, "body {body}");
+        }
+        let (status, _) = send(&provider, http::Method::PUT, "{}").await;
+        assert_eq!(status, http::StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(provider.config(), DynamoDBConfig::default());
+    }
+
+    #[test]
+    fn test_should_find_settings_by_path() {
+        struct Empty;
+
+        impl Settings for Empty {
+            fn to_json(&self) -> Value {
+                json!({})
+            }
+
+            fn patch_from_json(&self, _: &Map<String, Value>) -> Result<(), String> {
+                Ok(())
+            }
+        }
+
+        let mut endpoints = SettingsEndpoints::default();
+        endpoints.add(DYNAMODB_CONFIG_PATH, Arc::new(Empty));
+        assert!(endpoints.find(DYNAMODB_CONFIG_PATH).is_some());
+        assert!(endpoints.find("/_ruststack/dynamodb/other").is_none());
+    }
+}
@@ -0,0 +1,193 @@
+//! OpenTelemetry export of request spans.
+//!
+//! With the `otel` feature compiled in and `OTEL_EXPORTER_OTLP_ENDPOINT` set,
+//! the S3 and DynamoDB request spans are exported over OTLP/HTTP to
+//! `<endpoint>/v1/traces`, named after their operation (`S3.PutObject`,
+//! `DynamoDB.Query`). A client `traceparent` header makes the request span a
+//! child of the caller's span, so RustStack shows up inside the caller's
+//! traces. Without an endpoint nothing is exported and request spans record
+//! only what they always have.
+
+use anyhow::Result;
+use rustack_core::config;
+use tracing::{Subscriber, info, warn};
+use tracing_subscriber::{Layer, registry::LookupSpan};
+
+/// Environment variable naming the OTLP collector base URL.
+pub const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// Service name reported on exported spans.
+#[cfg(feature = "otel")]
+const SERVICE_NAME: &str = "rustack";
+
+/// The configured OTLP collector base URL, if any.
+#[must_use]
+pub fn otlp_endpoint() -> Option<String> {
+    config::var(OTLP_ENDPOINT_ENV).filter(|endpoint| !endpoint.is_empty())
+}
+
+/// Span export set up by [`layer`]; flushes pending spans on
+/// [`shutdown`](Self::shutdown).
+#[derive(Debug, Default)]
+pub struct Telemetry {
+    /// The configured collector, if any.
+    endpoint: Option<String>,
+    /// The provider exporting spans, if export is on.
+    #[cfg(feature = "otel")]
+    provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+}
+
+impl Telemetry {
+    /// Log whether spans are exported. Call once tracing is initialized.
+    pub fn log_status(&self) {
+        let Some(endpoint) = &self.endpoint else {
+            return;
+        };
+        if cfg!(feature = "otel") {
+            info!(endpoint = %endpoint, "exporting request spans over OTLP");
+        } else {
+            warn!(
+                endpoint = %endpoint,
+                "{OTLP_ENDPOINT_ENV} is set but this build has no `otel` feature; spans are not \
+                 exported"
+            );
+        }
+    }
+
+    /// Flush pending spans and stop exporting.
+    pub fn shutdown(self) {
+        #[cfg(feature = "otel")]
+        if let Some(provider) = self.provider {
+            if let Err(e) = provider.shutdown() {
+                warn!(error = %e, "failed to flush exported spans");
+            }
+        }
+    }
+}
+
+/// The tracing layer exporting request spans to the configured collector,
+/// and the handle to flush it on shutdown.
+///
+/// # Errors
+///
+/// Returns an error if the OTLP exporter cannot be built.
+#[cfg(feature = "otel")]
+pub fn layer<S>() -> Result<(impl Layer<S>, Telemetry)>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::{Resource, trace::SdkTracerProvider};
+    use rustack_core::request_id::SpanExport;
+
+    let Some(endpoint) = otlp_endpoint() else {
+        return Ok((None, Telemetry::default()));
+    };
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+        .build()?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+        .build();
+    let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME));
+    rustack_core::request_id::enable_span_export(SpanExport {
+        link_parent,
+        rename,
+    });
+
+    Ok((
+        Some(layer),
+        Telemetry {
+            endpoint: Some(endpoint),
+            provider: Some(provider),
+        },
+    ))
+}
+
+/// The tracing layer exporting request spans: none, as this build has no
+/// `otel` feature.
+///
+/// # Errors
+///
+/// Never fails; the signature matches the `otel` build.
+#[cfg(not(feature = "otel"))]
+#[allow(clippy::unnecessary_wraps)]
+pub fn layer<S>() -> Result<(impl Layer<S>, Telemetry)>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    Ok((
+        tracing_subscriber::layer::Identity::new(),
+        Telemetry {
+            endpoint: otlp_endpoint(),
+        },
+    ))
+}
+
+/// Make `span` the child of the remote span named by a W3C `traceparent`
+/// header value. Malformed values leave the span a trace root.
+#[cfg(feature = "otel")]
+fn link_parent(span: &tracing::Span, traceparent: &str) {
+    use std::collections::HashMap;
+
+    use opentelemetry::propagation::TextMapPropagator as _;
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use tracing_opentelemetry::OpenTelemetrySpanExt as _;
+
+    let carrier = HashMap::from([(
+        rustack_core::TRACEPARENT_HEADER.to_owned(),
+        traceparent.to_owned(),
+    )]);
+    let parent = TraceContextPropagator::new().extract(&carrier);
+    // Fails only if the span is disabled, when there is nothing to link.
+    let _ = span.set_parent(parent);
+}
+
+/// Rename the exported span behind `span`.
+#[cfg(feature = "otel")]
+fn rename(span: &tracing::Span, name: String) {
+    use opentelemetry::trace::TraceContextExt as _;
+    use tracing_opentelemetry::OpenTelemetrySpanExt as _;
+
+    span.context().span().update_name(name);
+}
+
+#[cfg(all(test, feature = "otel"))]
+mod tests {
+    use opentelemetry::trace::{SpanId, TraceContextExt as _, TraceId};
+    use tracing_opentelemetry::OpenTelemetrySpanExt as _;
+    use tracing_subscriber::layer::SubscriberExt as _;
+
+    use super::*;
+
+    #[test]
+    fn test_should_continue_client_trace_from_traceparent() {
+        let provider = opentelemetry_sdk::trace::SdkTracerProvider::builder().build();
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(
+                opentelemetry::trace::TracerProvider::tracer(&provider, "test"),
+            ));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("request");
+            link_parent(
+                &span,
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            );
+            let context = span.context();
+            let remote = context.span();
+            let span_context = remote.span_context();
+            assert_eq!(
+                span_context.trace_id(),
+                TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").expect("trace id")
+            );
+            assert_ne!(
+                span_context.span_id(),
+                SpanId::from_hex("00f067aa0ba902b7").expect("span id")
+            );
+        });
+    }
+}
@@ -0,0 +1,408 @@
+//! TLS for the gateway listener.
+//!
+//! HTTPS is enabled by `GATEWAY_TLS_CERT` and `GATEWAY_TLS_KEY` (PEM files),
+//! or by `GATEWAY_TLS_SELF_SIGNED=true`, which generates a throwaway ECDSA
+//! P-256 certificate at startup for `localhost`, `127.0.0.1`, and the
+//! `*.localhost.localstack.cloud` names used for virtual-hosted-style S3.
+//!
+//! With TLS enabled the gateway serves HTTPS on `GATEWAY_LISTEN`, unless
+//! `GATEWAY_LISTEN_TLS` is set: then HTTPS is served there and plain HTTP
+//! stays on `GATEWAY_LISTEN`.
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{TimeDelta, Utc};
+use ring::{
+    rand::{SecureRandom, SystemRandom},
+    signature::{ECDSA_P256_SHA256_ASN1_SIGNING, EcdsaKeyPair, KeyPair},
+};
+use rustack_core::config;
+use rustls::{
+    ClientConfig, DigitallySignedStruct, ServerConfig, SignatureScheme,
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::{CryptoProvider, ring::default_provider},
+    pki_types::{
+        CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, ServerName, UnixTime, pem::PemObject,
+    },
+};
+
+/// Environment variable naming the PEM certificate chain.
+pub const TLS_CERT_ENV: &str = "GATEWAY_TLS_CERT";
+/// Environment variable naming the PEM private key.
+pub const TLS_KEY_ENV: &str = "GATEWAY_TLS_KEY";
+/// Environment variable enabling a generated self-signed certificate.
+pub const TLS_SELF_SIGNED_ENV: &str = "GATEWAY_TLS_SELF_SIGNED";
+/// Environment variable naming a separate HTTPS bind address.
+pub const LISTEN_TLS_ENV: &str = "GATEWAY_LISTEN_TLS";
+
+/// Host names a generated certificate is valid for.
+pub const SELF_SIGNED_NAMES: &[&str] = &[
+    "localhost",
+    "localhost.localstack.cloud",
+    "*.localhost.localstack.cloud",
+    "s3.localhost.localstack.cloud",
+    "*.s3.localhost.localstack.cloud",
+];
+
+/// How long a generated certificate stays valid.
+const SELF_SIGNED_VALIDITY_DAYS: i64 = 365;
+
+/// Whether the environment enables TLS for the gateway.
+#[must_use]
+pub fn tls_enabled() -> bool {
+    config::var(TLS_CERT_ENV).is_some()
+        || config::var(TLS_SELF_SIGNED_ENV)
+            .is_some_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+}
+
+/// Build the gateway's TLS configuration from the environment, or `None`
+/// when TLS is not enabled.
+///
+/// # Errors
+///
+/// Returns an error if only one of the certificate and key is set, or they
+/// cannot be loaded.
+pub fn server_config_from_env() -> Result<Option<Arc<ServerConfig>>> {
+    let (chain, key) = match (config::var(TLS_CERT_ENV), config::var(TLS_KEY_ENV)) {
+        (Some(cert), Some(key)) => {
+            let chain = CertificateDer::pem_file_iter(&cert)
+                .and_then(Iterator::collect::<Result<Vec<_>, _>>)
+                .map_err(|e| anyhow!("failed to read certificates from {cert}: {e}"))?;
+            let key = PrivateKeyDer::from_pem_file(&key)
+                .map_err(|e| anyhow!("failed to read private key from {key}: {e}"))?;
+            (chain, key)
+        }
+        (Some(_), None) | (None, Some(_)) => {
+            anyhow::bail!("{TLS_CERT_ENV} and {TLS_KEY_ENV} must be set together");
+        }
+        (None, None) if tls_enabled() => {
+            let (cert, key) = self_signed_certificate(SELF_SIGNED_NAMES)?;
+            (vec![cert], key)
+        }
+        (None, None) => return Ok(None),
+    };
+    server_config(chain, key).map(Some)
+}
+
+/// Build a TLS server configuration serving `chain`, offering HTTP/2 and
+/// HTTP/1.1 through ALPN.
+///
+/// # Errors
+///
+/// Returns an error if the key does not match the certificate or is of an
+/// unsupported type.
+pub fn server_config(
+    chain: Vec<CertificateDer<'static>>,
+    key: PrivateKeyDer<'static>,
+) -> Result<Arc<ServerConfig>> {
+    let mut config = ServerConfig::builder_with_provider(Arc::new(default_provider()))
+        .with_safe_default_protocol_versions()
+        .context("unsupported TLS protocol versions")?
+        .with_no_client_auth()
+        .with_single_cert(chain, key)
+        .context("invalid TLS certificate or key")?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(Arc::new(config))
+}
+
+/// Generate a self-signed ECDSA P-256 certificate for `names`, plus its
+/// PKCS#8 private key.
+///
+/// The certificate is valid from a day ago for a year and also covers the
+/// loopback address `127.0.0.1`.
+///
+/// # Errors
+///
+/// Returns an error if the system random number generator fails.
+pub fn self_signed_certificate(
+    names: &[&str],
+) -> Result<(CertificateDer<'static>, PrivateKeyDer<'static>)> {
+    let rng = SystemRandom::new();
+    let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng)
+        .map_err(|_| anyhow!("failed to generate a TLS key"))?;
+    let key_pair = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng)
+        .map_err(|e| anyhow!("failed to load the generated TLS key: {e}"))?;
+
+    let mut serial = [0u8; 16];
+    rng.fill(&mut serial)
+        .map_err(|_| anyhow!("failed to generate a certificate serial number"))?;
+    // A positive, minimally encoded INTEGER.
+    serial[0] = (serial[0] & 0x7f) | 0x40;
+
+    let now = Utc::now();
+    let not_before = now - TimeDelta::days(1);
+    let not_after = now + TimeDelta::days(SELF_SIGNED_VALIDITY_DAYS);
+    let name = der::seq(&[der::set(&[der::seq(&[
+        der::oid(der::OID_COMMON_NAME),
+        der::tlv(der::UTF8_STRING, b"Rustack"),
+    ])])]);
+    let signature_algorithm = der::seq(&[der::oid(der::OID_ECDSA_WITH_SHA256)]);
+
+    let mut alt_names: Vec<Vec<u8>> = names
+        .iter()
+        .map(|name| der::tlv(der::SAN_DNS_NAME, name.as_bytes()))
+        .collect();
+    alt_names.push(der::tlv(der::SAN_IP_ADDRESS, &[127, 0, 0, 1]));
+    let extensions = der::tlv(
+        der::EXTENSIONS,
+        &der::seq(&[der::seq(&[
+            der::oid(der::OID_SUBJECT_ALT_NAME),
+            der::tlv(der::OCTET_STRING, &der::seq(&alt_names)),
+        ])]),
+    );
+
+    let tbs = der::seq(&[
+        der::tlv(der::VERSION, &der::tlv(der::INTEGER, &[2])),
+        der::tlv(der::INTEGER, &serial),
+        signature_algorithm.clone(),
+        name.clone(),
+        der::seq(&[
+            der::tlv(
+                der::UTC_TIME,
+                not_before.format("%y%m%d%H%M%SZ").to_string().as_bytes(),
+            ),
+            der::tlv(
+                der::UTC_TIME,
+                not_after.format("%y%m%d%H%M%SZ").to_string().as_bytes(),
+            ),
+        ]),
+        name,
+        der::seq(&[
+            der::seq(&[
+                der::oid(der::OID_EC_PUBLIC_KEY),
+                der::oid(der::OID_PRIME256V1),
+            ]),
+            der::bit_string(key_pair.public_key().as_ref()),
+        ]),
+        extensions,
+    ]);
+    let signature = key_pair
+        .sign(&rng, &tbs)
+        .map_err(|_| anyhow!("failed to sign the certificate"))?;
+    let certificate = der::seq(&[
+        tbs,
+        signature_algorithm,
+        der::bit_string(signature.as_ref()),
+    ]);
+
+    Ok((
+        CertificateDer::from(certificate),
+        PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(pkcs8.as_ref().to_vec())),
+    ))
+}
+
+/// A TLS client configuration that accepts any server certificate.
+///
+/// Only for probing the local gateway (`--health-check`), whose certificate
+/// is typically self-signed.
+///
+/// # Errors
+///
+/// Returns an error if the crypto provider supports no TLS version.
+pub fn insecure_client_config() -> Result<Arc<ClientConfig>> {
+    let provider = Arc::new(default_provider());
+    let config = ClientConfig::builder_with_provider(Arc::clone(&provider))
+        .with_safe_default_protocol_versions()
+        .context("unsupported TLS protocol versions")?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(AcceptAnyCertificate(provider)))
+        .with_no_client_auth();
+    Ok(Arc::new(config))
+}
+
+/// Certificate verifier that trusts every server, checking only that
+/// handshake signatures are well formed.
+#[derive(Debug)]
+struct AcceptAnyCertificate(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// Minimal DER encoding for the generated certificate.
+mod der {
+    pub const INTEGER: u8 = 0x02;
+    pub const BIT_STRING: u8 = 0x03;
+    pub const OCTET_STRING: u8 = 0x04;
+    pub const OBJECT_IDENTIFIER: u8 = 0x06;
+    pub const UTF8_STRING: u8 = 0x0c;
+    pub const UTC_TIME: u8 = 0x17;
+    pub const SEQUENCE: u8 = 0x30;
+    pub const SET: u8 = 0x31;
+    /// `[0] EXPLICIT` certificate version.
+    pub const VERSION: u8 = 0xa0;
+    /// `[3] EXPLICIT` certificate extensions.
+    pub const EXTENSIONS: u8 = 0xa3;
+    /// `[2] IMPLICIT` `dNSName` general name.
+    pub const SAN_DNS_NAME: u8 = 0x82;
+    /// `[7] IMPLICIT` `iPAddress` general name.
+    pub const SAN_IP_ADDRESS: u8 = 0x87;
+
+    /// 1.2.840.10045.4.3.2
+    pub const OID_ECDSA_WITH_SHA256: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02];
+    /// 1.2.840.10045.2.1
+    pub const OID_EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+    /// 1.2.840.10045.3.1.7
+    pub const OID_PRIME256V1: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+    /// 2.5.4.3
+    pub const OID_COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+    /// 2.5.29.17
+    pub const OID_SUBJECT_ALT_NAME: &[u8] = &[0x55, 0x1d, 0x11];
+
+    /// Encode a tag-length-value element.
+    // Short-form lengths are below 0x80 and a `usize` has at most 8 bytes.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+        let mut out = vec![tag];
+        let len = content.len();
+        if len < 0x80 {
+            out.push(len as u8);
+        } else {
+            let bytes = len.to_be_bytes();
+            let skip = bytes.iter().take_while(|&&b| b == 0).count();
+            out.push(0x80 | (bytes.len() - skip) as u8);
+            out.extend_from_slice(&bytes[skip..]);
+        }
+        out.extend_from_slice(content);
+        out
+    }
+
+    pub fn seq(items: &[Vec<u8>]) -> Vec<u8> {
+        tlv(SEQUENCE, &items.concat())
+    }
+
+    pub fn set(items: &[Vec<u8>]) -> Vec<u8> {
+        tlv(SET, &items.concat())
+    }
+
+    pub fn oid(encoded: &[u8]) -> Vec<u8> {
+        tlv(OBJECT_IDENTIFIER, encoded)
+    }
+
+    /// A BIT STRING with no unused bits.
+    pub fn bit_string(bits: &[u8]) -> Vec<u8> {
+        let mut content = vec![0];
+        content.extend_from_slice(bits);
+        tlv(BIT_STRING, &content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustls::{RootCertStore, pki_types::ServerName};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt, duplex};
+    use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+    use super::*;
+
+    /// Complete a handshake between `server` and a client trusting `roots`.
+    async fn handshake(
+        server: Arc<ServerConfig>,
+        client: Arc<ClientConfig>,
+        name: &str,
+    ) -> std::io::Result<String> {
+        let (client_io, server_io) = duplex(16 * 1024);
+        let server_task = tokio::spawn(async move {
+            let mut stream = TlsAcceptor::from(server).accept(server_io).await?;
+            stream.write_all(b"hello").await?;
+            stream.shutdown().await
+        });
+        let name = ServerName::try_from(name.to_owned()).map_err(std::io::Error::other)?;
+        let mut stream = TlsConnector::from(client).connect(name, client_io).await?;
+        let mut received = String::new();
+        stream.read_to_string(&mut received).await?;
+        server_task.await.map_err(std::io::Error::other)??;
+        Ok(received)
+    }
+
+    #[tokio::test]
+    async fn test_should_serve_self_signed_certificate_to_verifying_clients() {
+        let (cert, key) = self_signed_certificate(SELF_SIGNED_NAMES).unwrap();
+        let server = server_config(vec![cert.clone()], key).unwrap();
+        let mut roots = RootCertStore::empty();
+        roots.add(cert).unwrap();
+        let client = Arc::new(
+            ClientConfig::builder_with_provider(Arc::new(default_provider()))
+                .with_safe_default_protocol_versions()
+                .unwrap()
+                .with_root_certificates(roots)
+                .with_no_client_auth(),
+        );
+
+        for name in [
+            "localhost",
+            "bucket.s3.localhost.localstack.cloud",
+            "127.0.0.1",
+        ] {
+            let received = handshake(Arc::clone(&server), Arc::clone(&client), name)
+                .await
+                .unwrap();
+            assert_eq!(received, "hello", "{name}");
+        }
+        assert!(
+            handshake(Arc::clone(&server), client, "example.com")
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_should_accept_any_certificate_for_health_checks() {
+        let (cert, key) = self_signed_certificate(&["example.test"]).unwrap();
+        let server = server_config(vec![cert], key).unwrap();
+        let received = handshake(server, insecure_client_config().unwrap(), "localhost")
+            .await
+            .unwrap();
+        assert_eq!(received, "hello");
+    }
+
+    #[test]
+    fn test_should_encode_long_der_lengths() {
+        assert_eq!(der::tlv(der::OCTET_STRING, &[1]), [0x04, 0x01, 0x01]);
+        let long = der::tlv(der::OCTET_STRING, &[0; 300]);
+        assert_eq!(long[..4], [0x04, 0x82, 0x01, 0x2c]);
+        assert_eq!(long.len(), 304);
+    }
+}
@@ -13,6 +13,10 @@
 //!
 //! Each component is normalized according to the AWS specification to ensure
 //! deterministic signature computation.
+//!
+//! S3 canonicalizes requests differently from every other service; the
+//! differences are captured by [`SigningProfile`], chosen from the service in
+//! the credential scope.
 
 use std::collections::BTreeMap;
 
@@ -23,12 +27,72 @@ use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, percent_decode_str, utf8_perc
 /// Per AWS SigV4 spec, all characters except unreserved characters
 /// (A-Z, a-z, 0-9, `-`, `_`, `.`, `~`) must be encoded.
 /// Forward slashes in the path are preserved (not encoded).
-const URI_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+pub(crate) const URI_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
     .remove(b'-')
     .remove(b'_')
     .remove(b'.')
     .remove(b'~');
 
+/// The payload hash S3 presigned URLs sign in place of a real hash.
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// SHA-256 hash of an empty payload.
+const EMPTY_PAYLOAD_HASH: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+/// The service-specific canonicalization rules a request was signed under.
+///
+/// # Examples
+///
+/// ```
+/// use rustack_auth::canonical::SigningProfile;
+///
+/// let lambda = SigningProfile::for_service("lambda");
+/// assert_eq!(
+///     lambda.canonical_uri("/2015-03-31/functions/arn%3Aaws/invocations"),
+///     "/2015-03-31/functions/arn%253Aaws/invocations"
+/// );
+/// assert_eq!(SigningProfile::for_service("s3").canonical_uri("/a/./b"), "/a/./b");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigningProfile {
+    /// Amazon S3. The path is signed as sent, without removing dot segments or
+    /// empty segments, and presigned URLs sign `UNSIGNED-PAYLOAD`.
+    S3,
+    /// Every other service. Dot segments and empty segments are removed from
+    /// the path, which is then URI-encoded a second time, and presigned URLs
+    /// sign the hash of the empty payload.
+    Standard,
+}
+
+impl SigningProfile {
+    /// The profile of `service`, the signing name from the credential scope.
+    #[must_use]
+    pub fn for_service(service: &str) -> Self {
+        match service {
+            "s3" | "s3-object-lambda" | "s3express" => Self::S3,
+            _ => Self::Standard,
+        }
+    }
+
+    /// Build the canonical URI for the request path `path`.
+    #[must_use]
+    pub fn canonical_uri(self, path: &str) -> String {
+        match self {
+            Self::S3 => build_canonical_uri(path),
+            Self::Standard => build_normalized_canonical_uri(path),
+        }
+    }
+
+    /// The payload hash a presigned URL's canonical request ends with.
+    #[must_use]
+    pub fn presigned_payload_hash(self) -> &'static str {
+        match self {
+            Self::S3 => UNSIGNED_PAYLOAD,
+            Self::Standard => EMPTY_PAYLOAD_HASH,
+        }
+    }
+}
+
 /// Build the full canonical request string from its components.
 ///
 /// The result is a newline-separated string of:
@@ -42,9 +106,10 @@ const URI_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
 /// # Examples
 ///
 /// ```
-/// use rustack_auth::canonical::build_canonical_request;
+/// use rustack_auth::canonical::{SigningProfile, build_canonical_request};
 ///
 /// let canonical = build_canonical_request(
+///     SigningProfile::S3,
 ///     "GET",
 ///     "/test.txt",
 ///     "",
@@ -56,6 +121,7 @@ const URI_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
 /// ```
 #[must_use]
 pub fn build_canonical_request(
+    profile: SigningProfile,
     method: &str,
     uri: &str,
     query_string: &str,
@@ -63,7 +129,7 @@ pub fn build_canonical_request(
     signed_headers: &[&str],
     payload_hash: &str,
 ) -> String {
-    let canonical_uri = build_canonical_uri(uri);
+    let canonical_uri = profile.canonical_uri(uri);
     let canonical_query = build_canonical_query_string(query_string);
     let canonical_headers = build_canonical_headers(headers, signed_headers);
     let signed_headers_str = build_signed_headers_string(signed_headers);
@@ -109,6 +175,36 @@ pub fn build_canonical_uri(path: &str) -> String {
     encoded_segments.join("/")
 }
 
+/// Build the canonical URI for services other than S3.
+///
+/// The path is taken as sent, already percent-encoded, and each segment is
+/// encoded again, as the AWS SDKs do when signing. Empty segments and `.` are
+/// dropped and `..` removes the preceding segment; a trailing slash is kept.
+fn build_normalized_canonical_uri(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    let mut uri = String::from("/");
+    uri.push_str(
+        &segments
+            .iter()
+            .map(|segment| uri_encode(segment))
+            .collect::<Vec<_>>()
+            .join("/"),
+    );
+    if path.ends_with('/') && !segments.is_empty() {
+        uri.push('/');
+    }
+    uri
+}
+
 /// Build the canonical query string by sorting parameters.
 ///
 /// Parameters are sorted by key name first, then by value for duplicate keys.
@@ -267,6 +363,23 @@ mod tests {
         assert_eq!(build_canonical_uri("/hello world"), "/hello%20world");
     }
 
+    #[test]
+    fn test_should_normalize_and_double_encode_standard_paths() {
+        let standard = SigningProfile::Standard;
+        assert_eq!(standard.canonical_uri(""), "/");
+        assert_eq!(standard.canonical_uri("/"), "/");
+        assert_eq!(standard.canonical_uri("/a/./b/../c//d/"), "/a/c/d/");
+        assert_eq!(standard.canonical_uri("/.."), "/");
+        assert_eq!(
+            standard.canonical_uri("/functions/my%20fn"),
+            "/functions/my%2520fn"
+        );
+        assert_eq!(
+            SigningProfile::S3.canonical_uri("/a//b/../my%20fn"),
+            "/a//b/../my%20fn"
+        );
+    }
+
     #[test]
     fn test_should_sort_query_parameters() {
         assert_eq!(build_canonical_query_string("b=2&a=1&c=3"), "a=1&b=2&c=3");
@@ -318,6 +431,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_should_join_repeated_headers_with_commas() {
+        let headers = [
+            ("Host", "example.com"),
+            ("X-Amz-Meta-Foo", "bar"),
+            ("x-amz-meta-foo", "baz"),
+        ];
+        let result = build_canonical_headers(&headers, &["host", "x-amz-meta-foo"]);
+        assert_eq!(result, "host:example.com\nx-amz-meta-foo:bar,baz");
+    }
+
     #[test]
     fn test_should_collapse_whitespace_in_header_values() {
         let headers = [("Host", "  example.com  "), ("X-Custom", "a   b   c")];
@@ -346,6 +470,7 @@ mod tests {
         let signed_headers = vec!["host", "range", "x-amz-content-sha256", "x-amz-date"];
 
         let canonical = build_canonical_request(
+            SigningProfile::S3,
             "GET",
             "/test.txt",
             "",
@@ -0,0 +1,75 @@
+//! Time source used when validating request timestamps.
+//!
+//! Signature verification compares `X-Amz-Date` against the current time. The
+//! [`Clock`] trait lets callers, mainly tests, substitute a fixed time for the
+//! system clock.
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+/// Maximum difference allowed between a signed request's timestamp and the
+/// server's clock, matching AWS (15 minutes).
+pub const MAX_CLOCK_SKEW: TimeDelta = TimeDelta::minutes(15);
+
+/// A source of the current time.
+pub trait Clock: Send + Sync {
+    /// Return the current time.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system wall clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock frozen at a fixed instant.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use rustack_auth::clock::{Clock, FixedClock};
+///
+/// let at = Utc.with_ymd_and_hms(2013, 5, 24, 0, 0, 0).unwrap();
+/// assert_eq!(FixedClock(at).now(), at);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// Parse an ISO 8601 basic timestamp (`YYYYMMDDTHHMMSSZ`) as used by SigV4.
+pub(crate) fn parse_amz_date(timestamp: &str) -> Option<DateTime<Utc>> {
+    chrono::NaiveDateTime::parse_from_str(timestamp, "%Y%m%dT%H%M%SZ")
+        .ok()
+        .map(|t| t.and_utc())
+}
+
+/// Format a time as an ISO 8601 basic timestamp (`YYYYMMDDTHHMMSSZ`).
+#[must_use]
+pub fn format_amz_date(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_should_round_trip_amz_date() {
+        let at = Utc.with_ymd_and_hms(2013, 5, 24, 1, 2, 3).unwrap();
+        assert_eq!(format_amz_date(at), "20130524T010203Z");
+        assert_eq!(parse_amz_date("20130524T010203Z"), Some(at));
+        assert!(parse_amz_date("2013-05-24").is_none());
+    }
+}
@@ -3,6 +3,8 @@
 //! All authentication failures are represented by [`AuthError`], which provides
 //! specific variants for each failure mode encountered during signature verification.
 
+use chrono::{DateTime, Utc};
+
 /// Errors that can occur during AWS Signature Version 4 authentication.
 #[derive(Debug, thiserror::Error)]
 pub enum AuthError {
@@ -14,7 +16,8 @@ pub enum AuthError {
     #[error("Invalid Authorization header format")]
     InvalidAuthHeader,
 
-    /// The signing algorithm is not supported (only AWS4-HMAC-SHA256 is supported).
+    /// The signing algorithm is not supported (only AWS4-HMAC-SHA256 and
+    /// AWS4-ECDSA-P256-SHA256 are).
     #[error("Unsupported algorithm: {0}")]
     UnsupportedAlgorithm(String),
 
@@ -31,6 +34,15 @@ pub enum AuthError {
     #[error("Access key not found: {0}")]
     AccessKeyNotFound(String),
 
+    /// The access key expects a session token and the request carried a
+    /// different one, or none.
+    #[error("The provided token is malformed or otherwise invalid.")]
+    InvalidToken,
+
+    /// The request's session token matched but has expired.
+    #[error("The provided token has expired.")]
+    ExpiredToken,
+
     /// The computed signature does not match the provided signature.
     #[error("Signature does not match")]
     SignatureDoesNotMatch,
@@ -39,7 +51,30 @@ pub enum AuthError {
     #[error("Request has expired")]
     RequestExpired,
 
+    /// The presigned URL's `X-Amz-Date` is further in the future than the
+    /// allowed clock skew.
+    #[error("Request is not valid yet")]
+    RequestNotYetValid,
+
+    /// The request's `X-Amz-Date` differs from the server clock by more than
+    /// [`MAX_CLOCK_SKEW`](crate::clock::MAX_CLOCK_SKEW).
+    #[error("The difference between the request time and the current time is too large.")]
+    RequestTimeTooSkewed {
+        /// The time the request claims to have been signed at.
+        request_time: DateTime<Utc>,
+        /// The server's time when the request was checked.
+        server_time: DateTime<Utc>,
+    },
+
     /// A required query parameter for presigned URL authentication is missing.
     #[error("Missing required query parameter: {0}")]
     MissingQueryParam(String),
+
+    /// A required field of a POST upload form is missing.
+    #[error("Missing required form field: {0}")]
+    MissingFormField(String),
+
+    /// A request passed to the presigned URL generator cannot be signed.
+    #[error("Cannot presign request: {0}")]
+    InvalidPresignRequest(String),
 }
@@ -1,9 +1,10 @@
-//! AWS Signature Version 2 and 4 request authentication for Rustack.
+//! AWS Signature Version 2, 4, and 4A request authentication for Rustack.
 //!
-//! This crate provides SigV4 and SigV2 signature verification for incoming HTTP
-//! requests to AWS-compatible services. It supports header-based authentication
-//! (via the `Authorization` header), presigned URL authentication (via query
-//! parameters), and legacy SigV2 authentication (HMAC-SHA1).
+//! This crate provides SigV4, SigV4A, and SigV2 signature verification for
+//! incoming HTTP requests to AWS-compatible services. It supports header-based
+//! authentication (via the `Authorization` header), presigned URL
+//! authentication (via query parameters), asymmetric multi-region SigV4A
+//! signatures (ECDSA P-256), and legacy SigV2 authentication (HMAC-SHA1).
 //!
 //! # Overview
 //!
@@ -29,22 +30,49 @@
 //!
 //! # Modules
 //!
-//! - [`canonical`] - Canonical request construction per the SigV4 specification
-//! - [`credentials`] - Credential provider trait and in-memory implementation
+//! - [`canonical`] - Canonical request construction per the SigV4 specification, with the
+//!   S3-specific rules selected by [`SigningProfile`]
+//! - [`clock`] - Injectable time source for request timestamp checks
+//! - [`credentials`] - Credential provider trait, in-memory and reloadable file-backed
+//!   implementations
 //! - [`error`] - Authentication error types
+//! - [`post_policy`] - Signature verification for S3 POST upload forms
+//! - [`presign`] - Presigned URL generation
 //! - [`presigned`] - Presigned URL verification
-//! - [`sigv2`] - Legacy SigV2 signature verification (HMAC-SHA1)
+//! - [`requester`] - Best-effort requester identification for logging
+//! - [`sigv2`] - Legacy SigV2 header and presigned URL verification (HMAC-SHA1)
 //! - [`sigv4`] - Main SigV4 signature verification logic
+//! - [`sigv4a`] - SigV4A key derivation and ECDSA signature verification
 
 pub mod canonical;
+pub mod clock;
 pub mod credentials;
 pub mod error;
+pub mod post_policy;
+pub mod presign;
 pub mod presigned;
+pub mod requester;
 pub mod sigv2;
 pub mod sigv4;
+pub mod sigv4a;
 
-pub use credentials::{CredentialProvider, StaticCredentialProvider};
+#[cfg(test)]
+mod conformance;
+
+pub use canonical::SigningProfile;
+pub use credentials::{
+    CredentialFileError, CredentialProvider, FileCredentialProvider, SessionToken,
+    StaticCredentialProvider,
+};
 pub use error::AuthError;
-pub use presigned::verify_presigned;
-pub use sigv2::{is_sigv2, verify_sigv2};
-pub use sigv4::{AuthResult, hash_payload, verify_sigv4};
+pub use post_policy::verify_post_policy;
+pub use presign::{PresignCredentials, generate_presigned_url};
+pub use presigned::{verify_presigned, verify_presigned_with_clock};
+pub use requester::{requester_access_key, requester_region};
+pub use sigv2::{
+    is_sigv2, is_sigv2_presigned, verify_sigv2, verify_sigv2_presigned,
+    verify_sigv2_presigned_with_clock,
+};
+pub use sigv4::{
+    AuthResult, SigningAlgorithm, hash_payload, verify_sigv4, verify_sigv4_with_clock,
+};
@@ -0,0 +1,169 @@
+//! Best-effort identification of the requester without verifying a signature.
+//!
+//! Used by logging and diagnostics, and to pick the account and region
+//! namespace a request operates in, where the credentials a client *claims*
+//! are useful even when signature validation is disabled. Nothing returned here
+//! should be trusted for authorization decisions unless the signature has been
+//! verified.
+
+/// Extract the access key ID a request claims to be signed with.
+///
+/// Looks at, in order:
+///
+/// - a SigV4 `Authorization` header (`Credential=AKID/...`)
+/// - a SigV2 `Authorization` header (`AWS AKID:signature`)
+/// - a SigV4 presigned URL (`X-Amz-Credential=AKID/...`)
+/// - a SigV2 presigned URL (`AWSAccessKeyId=AKID`)
+///
+/// Returns `None` for anonymous or unparseable requests. Only the access key ID
+/// is returned; signatures and other credential material are never exposed.
+#[must_use]
+pub fn requester_access_key(headers: &http::HeaderMap, uri: &http::Uri) -> Option<String> {
+    if let Some(header) = headers
+        .get(http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+    {
+        let credential = if crate::sigv2::is_sigv2(header) {
+            header.strip_prefix("AWS ")?.split_once(':')?.0
+        } else {
+            header
+                .split([' ', ','])
+                .find_map(|part| part.strip_prefix("Credential="))?
+        };
+        return access_key_from_credential(credential);
+    }
+
+    uri.query()?.split('&').find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        match name {
+            "X-Amz-Credential" | "AWSAccessKeyId" => {
+                let decoded = percent_encoding::percent_decode_str(value).decode_utf8_lossy();
+                access_key_from_credential(&decoded)
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Extract the region named in a request's SigV4 credential scope.
+///
+/// Checks the `Authorization` header and then the `X-Amz-Credential` query
+/// parameter of a presigned URL. Returns `None` for SigV2, anonymous, or
+/// unparseable requests, and for SigV4A requests that are not scoped to a
+/// single region.
+#[must_use]
+pub fn requester_region(headers: &http::HeaderMap, uri: &http::Uri) -> Option<String> {
+    if let Some(header) = headers
+        .get(http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+    {
+        let credential = header
+            .split([' ', ','])
+            .find_map(|part| part.strip_prefix("Credential="))?;
+        return region_from_credential(credential);
+    }
+
+    uri.query()?.split('&').find_map(|param| {
+        let value = param.strip_prefix("X-Amz-Credential=")?;
+        region_from_credential(&percent_encoding::percent_decode_str(value).decode_utf8_lossy())
+    })
+}
+
+/// Take the region from a credential scope (`AKID/date/region/service/aws4_request`).
+///
+/// SigV4A scopes that omit the region or name every region (`*`) yield `None`.
+fn region_from_credential(credential: &str) -> Option<String> {
+    let parts: Vec<&str> = credential.split('/').collect();
+    let [_, _, region, _, _] = parts.as_slice() else {
+        return None;
+    };
+    let region = region.trim();
+    (!region.is_empty() && region != "*").then(|| region.to_owned())
+}
+
+/// Take the access key ID from a credential scope (`AKID/date/region/...`).
+fn access_key_from_credential(credential: &str) -> Option<String> {
+    let access_key = credential.split('/').next()?.trim();
+    (!access_key.is_empty()).then(|| access_key.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn requester(uri: &str, authorization: Option<&str>) -> Option<String> {
+        let mut headers = http::HeaderMap::new();
+        if let Some(value) = authorization {
+            headers.insert(
+                http::header::AUTHORIZATION,
+                value.parse().expect("valid header"),
+            );
+        }
+        requester_access_key(&headers, &uri.parse().expect("valid uri"))
+    }
+
+    #[test]
+    fn test_should_extract_access_key_from_sigv4_header() {
+        let header = "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20130524/us-east-1/s3/aws4_request, \
+                      SignedHeaders=host, Signature=abc";
+        assert_eq!(
+            requester("/bucket", Some(header)).as_deref(),
+            Some("AKIDEXAMPLE")
+        );
+    }
+
+    #[test]
+    fn test_should_extract_access_key_from_sigv2_header() {
+        assert_eq!(
+            requester("/bucket", Some("AWS minioadmin:c2lnbmF0dXJl")).as_deref(),
+            Some("minioadmin")
+        );
+    }
+
+    #[test]
+    fn test_should_extract_access_key_from_presigned_query() {
+        let uri = "/bucket/key?X-Amz-Credential=AKIDEXAMPLE%2F20130524%2Fus-east-1%2Fs3%\
+                   2Faws4_request&X-Amz-Signature=abc";
+        assert_eq!(requester(uri, None).as_deref(), Some("AKIDEXAMPLE"));
+
+        let uri = "/bucket/key?AWSAccessKeyId=AKIDV2&Signature=abc";
+        assert_eq!(requester(uri, None).as_deref(), Some("AKIDV2"));
+    }
+
+    #[test]
+    fn test_should_extract_region_from_credential_scope() {
+        let header = "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20130524/eu-west-1/s3/aws4_request, \
+                      SignedHeaders=host, Signature=abc";
+        let mut headers = http::HeaderMap::new();
+        headers.insert(
+            http::header::AUTHORIZATION,
+            header.parse().expect("valid header"),
+        );
+        let uri: http::Uri = "/bucket".parse().expect("valid uri");
+        assert_eq!(
+            requester_region(&headers, &uri).as_deref(),
+            Some("eu-west-1")
+        );
+
+        let uri: http::Uri = "/b/k?X-Amz-Credential=AKID%2F20130524%2Fap-south-1%2Fs3%\
+                              2Faws4_request"
+            .parse()
+            .expect("valid uri");
+        assert_eq!(
+            requester_region(&http::HeaderMap::new(), &uri).as_deref(),
+            Some("ap-south-1")
+        );
+
+        headers.insert(
+            http::header::AUTHORIZATION,
+            "AWS AKID:sig".parse().expect("valid"),
+        );
+        assert!(requester_region(&headers, &uri).is_none());
+    }
+
+    #[test]
+    fn test_should_return_none_for_anonymous_request() {
+        assert!(requester("/bucket?list-type=2", None).is_none());
+        assert!(requester("/bucket", Some("Bearer token")).is_none());
+    }
+}
@@ -0,0 +1,415 @@
+//! Per-request access logging shared by the HTTP service layers.
+//!
+//! Each HTTP service builds an [`AccessLogEntry`] while it processes a request
+//! and hands it to an [`AccessLogger`] once the response is ready. The logger
+//! renders one line per request, either as JSON or in the space-separated
+//! S3 server access log layout, and writes it to tracing or to a file.
+//!
+//! Request headers are never copied into an entry wholesale, so credentials in
+//! `Authorization` cannot leak. Signature-bearing query parameters of presigned
+//! URLs are redacted from the logged URI.
+
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{LineWriter, Write as _},
+    path::PathBuf,
+    str::FromStr,
+    sync::Mutex,
+    time::Duration,
+};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::error::RustackError;
+
+/// Tracing target used when access log lines are emitted through tracing.
+pub const ACCESS_LOG_TARGET: &str = "rustack::access_log";
+
+/// Query parameters whose values are replaced before a URI is logged.
+const REDACTED_QUERY_PARAMS: &[&str] = &["X-Amz-Signature", "Signature", "X-Amz-Security-Token"];
+
+/// Line format of the access log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AccessLogFormat {
+    /// One JSON object per line.
+    #[default]
+    Json,
+    /// Space-separated text compatible with the S3 server access log layout.
+    S3,
+}
+
+impl FromStr for AccessLogFormat {
+    type Err = RustackError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "s3" | "text" => Ok(Self::S3),
+            other => Err(RustackError::Config(format!(
+                "unknown access log format '{other}' (expected 'json' or 's3')"
+            ))),
+        }
+    }
+}
+
+/// Destination for access log lines.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum AccessLogSink {
+    /// Emit each line as an `INFO` event on the [`ACCESS_LOG_TARGET`] target.
+    #[default]
+    Tracing,
+    /// Append each line to the file at this path.
+    File(PathBuf),
+}
+
+/// A single access log record, filled in as the request is processed.
+#[derive(Debug, Clone)]
+pub struct AccessLogEntry {
+    /// Time the request was received.
+    pub time: DateTime<Utc>,
+    /// Service that handled the request (e.g. `s3`, `dynamodb`).
+    pub service: &'static str,
+    /// Request ID returned to the client.
+    pub request_id: String,
+    /// HTTP method.
+    pub method: String,
+    /// Request path and query, with signatures redacted.
+    pub uri: String,
+    /// Resolved operation name, if routing succeeded.
+    pub operation: Option<&'static str>,
+    /// Canonical ID of the target bucket's owner, for S3 requests.
+    pub bucket_owner: Option<String>,
+    /// Target bucket, for S3 requests.
+    pub bucket: Option<String>,
+    /// Target object key, for S3 requests.
+    pub key: Option<String>,
+    /// Target table, for DynamoDB requests.
+    pub table: Option<String>,
+    /// HTTP status code of the response.
+    pub status: u16,
+    /// AWS error code of the response, if the request failed.
+    pub error_code: Option<&'static str>,
+    /// Time taken to produce the response.
+    pub latency: Duration,
+    /// Request payload size in bytes.
+    pub bytes_in: u64,
+    /// Response payload size in bytes.
+    pub bytes_out: u64,
+    /// Access key ID the request was signed with, if any.
+    pub requester: Option<String>,
+    /// Client `User-Agent`.
+    pub user_agent: Option<String>,
+}
+
+impl AccessLogEntry {
+    /// Start an entry for a request that has just been received.
+    #[must_use]
+    pub fn new(service: &'static str, request_id: &str, method: &str, uri: &str) -> Self {
+        Self {
+            time: Utc::now(),
+            service,
+            request_id: request_id.to_owned(),
+            method: method.to_owned(),
+            uri: redact_uri(uri),
+            operation: None,
+            bucket_owner: None,
+            bucket: None,
+            key: None,
+            table: None,
+            status: 0,
+            error_code: None,
+            latency: Duration::ZERO,
+            bytes_in: 0,
+            bytes_out: 0,
+            requester: None,
+            user_agent: None,
+        }
+    }
+
+    /// Render the entry as a single JSON object.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::json!({
+            "time": self.time.to_rfc3339_opts(SecondsFormat::Millis, true),
+            "service": self.service,
+            "requestId": self.request_id,
+            "method": self.method,
+            "uri": self.uri,
+            "operation": self.operation,
+            "bucketOwner": self.bucket_owner,
+            "bucket": self.bucket,
+            "key": self.key,
+            "table": self.table,
+            "status": self.status,
+            "errorCode": self.error_code,
+            "latencyMs": duration_millis(self.latency),
+            "bytesIn": self.bytes_in,
+            "bytesOut": self.bytes_out,
+            "requester": self.requester,
+            "userAgent": self.user_agent,
+        })
+        .to_string()
+    }
+
+    /// Render the entry in the S3 server access log layout.
+    ///
+    /// Fields without a local equivalent (remote IP, referrer, version ID,
+    /// signature and TLS details) are written as `-`. The operation is
+    /// rendered as `REST.<METHOD>.<Operation>`, `object_size` carries the
+    /// request payload size, and DynamoDB requests log the table in the
+    /// bucket column.
+    #[must_use]
+    pub fn to_s3_text(&self) -> String {
+        let resource = self.bucket.as_deref().or(self.table.as_deref());
+        let operation = format!(
+            "REST.{}.{}",
+            self.method,
+            self.operation.unwrap_or("Unknown")
+        );
+        format!(
+            "{bucket_owner} {bucket} [{time}] - {requester} {request_id} {operation} {key} \
+             \"{method} {uri} HTTP/1.1\" {status} {error_code} {bytes_out} {bytes_in} \
+             {total_time} - \"-\" \"{user_agent}\" - {request_id} - - - -",
+            bucket_owner = token(self.bucket_owner.as_deref()),
+            bucket = token(resource),
+            time = self.time.format("%d/%b/%Y:%H:%M:%S %z"),
+            requester = token(self.requester.as_deref()),
+            request_id = token(Some(&self.request_id)),
+            operation = token(Some(&operation)),
+            key = token(self.key.as_deref()),
+            method = self.method,
+            uri = token(Some(&self.uri)),
+            status = self.status,
+            error_code = token(self.error_code),
+            bytes_out = count(self.bytes_out),
+            bytes_in = count(self.bytes_in),
+            total_time = self.latency.as_millis(),
+            user_agent = quoted(self.user_agent.as_deref()),
+        )
+    }
+}
+
+/// Writes rendered access log entries to the configured sink.
+///
+/// File output uses a synchronous `std::fs::File`: each entry is a single
+/// short buffered line written from whichever task finished the request,
+/// which is cheaper than handing it to the async runtime. The
+/// disallowed-types lint is allowed locally for that reason.
+#[allow(clippy::disallowed_types)]
+pub struct AccessLogger {
+    format: AccessLogFormat,
+    file: Option<Mutex<LineWriter<fs::File>>>,
+}
+
+impl std::fmt::Debug for AccessLogger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AccessLogger")
+            .field("format", &self.format)
+            .field("file", &self.file.is_some())
+            .finish()
+    }
+}
+
+impl AccessLogger {
+    /// Create a logger, opening the sink file in append mode if one is given.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the log file cannot be opened.
+    #[allow(clippy::disallowed_types)]
+    pub fn new(format: AccessLogFormat, sink: &AccessLogSink) -> std::io::Result<Self> {
+        let file = match sink {
+            AccessLogSink::Tracing => None,
+            AccessLogSink::File(path) => {
+                let file = fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)?;
+                Some(Mutex::new(LineWriter::new(file)))
+            }
+        };
+        Ok(Self { format, file })
+    }
+
+    /// The line format this logger writes.
+    #[must_use]
+    pub fn format(&self) -> AccessLogFormat {
+        self.format
+    }
+
+    /// Render an entry in the configured format.
+    #[must_use]
+    pub fn render(&self, entry: &AccessLogEntry) -> String {
+        match self.format {
+            AccessLogFormat::Json => entry.to_json(),
+            AccessLogFormat::S3 => entry.to_s3_text(),
+        }
+    }
+
+    /// Write one line for the given entry.
+    ///
+    /// Failures to write to the sink are reported through tracing and
+    /// otherwise ignored, so logging never fails a request.
+    pub fn log(&self, entry: &AccessLogEntry) {
+        let line = self.render(entry);
+        let Some(file) = &self.file else {
+            tracing::info!(target: ACCESS_LOG_TARGET, "{line}");
+            return;
+        };
+        let result = match file.lock() {
+            Ok(mut writer) => writeln!(writer, "{line}"),
+            Err(poisoned) => writeln!(poisoned.into_inner(), "{line}"),
+        };
+        if let Err(err) = result {
+            tracing::warn!(error = %err, "failed to write access log entry");
+        }
+    }
+}
+
+/// Replace the values of signature-bearing query parameters.
+fn redact_uri(uri: &str) -> String {
+    let Some((path, query)) = uri.split_once('?') else {
+        return uri.to_owned();
+    };
+    let query = query
+        .split('&')
+        .map(|param| match param.split_once('=') {
+            Some((name, _)) if REDACTED_QUERY_PARAMS.contains(&name) => format!("{name}=REDACTED"),
+            _ => param.to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("{path}?{query}")
+}
+
+/// Format a space-delimited field, using `-` when absent and percent-escaping
+/// characters that would break the line layout.
+fn token(value: Option<&str>) -> String {
+    match value {
+        None | Some("") => "-".to_owned(),
+        Some(v) => {
+            let mut out = String::with_capacity(v.len());
+            for c in v.chars() {
+                if c.is_whitespace() || c.is_control() || c == '"' {
+                    let mut buf = [0u8; 4];
+                    for b in c.encode_utf8(&mut buf).bytes() {
+                        let _ = write!(out, "%{b:02X}");
+                    }
+                } else {
+                    out.push(c);
+                }
+            }
+            out
+        }
+    }
+}
+
+/// Format a quoted field, which may contain spaces but not quotes.
+fn quoted(value: Option<&str>) -> String {
+    match value {
+        None | Some("") => "-".to_owned(),
+        Some(v) => v.replace('"', "%22"),
+    }
+}
+
+/// Format a byte count, using `-` for zero as S3 does.
+fn count(value: u64) -> String {
+    if value == 0 {
+        "-".to_owned()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Express a duration in fractional milliseconds.
+fn duration_millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_entry() -> AccessLogEntry {
+        let mut entry = AccessLogEntry::new(
+            "s3",
+            "req-1",
+            "PUT",
+            "/bucket/my key?X-Amz-Credential=AKID&X-Amz-Signature=deadbeef",
+        );
+        entry.operation = Some("PutObject");
+        entry.bucket = Some("bucket".to_owned());
+        entry.key = Some("my key".to_owned());
+        entry.status = 403;
+        entry.error_code = Some("AccessDenied");
+        entry.latency = Duration::from_millis(12);
+        entry.bytes_in = 5;
+        entry.requester = Some("AKID".to_owned());
+        entry.user_agent = Some("aws-cli/2.0".to_owned());
+        entry
+    }
+
+    #[test]
+    fn test_should_parse_access_log_format() {
+        assert_eq!(
+            "json".parse::<AccessLogFormat>().ok(),
+            Some(AccessLogFormat::Json)
+        );
+        assert_eq!(
+            "S3".parse::<AccessLogFormat>().ok(),
+            Some(AccessLogFormat::S3)
+        );
+        assert_eq!(
+            "text".parse::<AccessLogFormat>().ok(),
+            Some(AccessLogFormat::S3)
+        );
+        assert!("xml".parse::<AccessLogFormat>().is_err());
+    }
+
+    #[test]
+    fn test_should_redact_presigned_signature() {
+        let entry = sample_entry();
+        assert!(!entry.uri.contains("deadbeef"));
+        assert!(entry.uri.contains("X-Amz-Signature=REDACTED"));
+        assert!(entry.uri.contains("X-Amz-Credential=AKID"));
+    }
+
+    #[test]
+    fn test_should_render_json_entry() {
+        let json: serde_json::Value =
+            serde_json::from_str(&sample_entry().to_json()).expect("valid json");
+        assert_eq!(json["operation"], "PutObject");
+        assert_eq!(json["status"], 403);
+        assert_eq!(json["errorCode"], "AccessDenied");
+        assert_eq!(json["bytesIn"], 5);
+        assert_eq!(json["requester"], "AKID");
+        assert!(json["table"].is_null());
+    }
+
+    #[test]
+    fn test_should_render_s3_text_entry() {
+        let line = sample_entry().to_s3_text();
+        assert!(line.starts_with("- bucket ["));
+        assert!(line.contains(" AKID req-1 REST.PUT.PutObject my%20key "));
+        assert!(line.contains(" 403 AccessDenied - 5 12 "));
+        assert!(line.contains("\"aws-cli/2.0\""));
+        assert!(!line.contains("deadbeef"));
+    }
+
+    #[test]
+    #[allow(clippy::disallowed_methods)]
+    fn test_should_append_lines_to_file_sink() {
+        let path =
+            std::env::temp_dir().join(format!("rustack-access-{}.log", uuid::Uuid::new_v4()));
+        let logger = AccessLogger::new(AccessLogFormat::Json, &AccessLogSink::File(path.clone()))
+            .expect("open log file");
+        logger.log(&sample_entry());
+        logger.log(&sample_entry());
+        drop(logger);
+
+        let contents = std::fs::read_to_string(&path).expect("read log file");
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(contents.lines().count(), 2);
+    }
+}
@@ -1,8 +1,287 @@
 //! Configuration management for Rustack services.
 //!
-//! All configuration is driven by environment variables, matching LocalStack conventions.
+//! All configuration is driven by environment variables, matching LocalStack
+//! conventions. A TOML [`ConfigFile`] can supply the same settings grouped in
+//! `[gateway]`, `[s3]`, and `[dynamodb]` sections:
+//!
+//! ```toml
+//! [gateway]
+//! listen = "0.0.0.0:4566"
+//! services = ["s3", "dynamodb"]
+//!
+//! [s3]
+//! max_memory_object_size = 1048576
+//!
+//! [dynamodb]
+//! table_create_delay_ms = 500
+//! ```
+//!
+//! Each key stands for one environment variable (see [`FILE_KEYS`]). Once a
+//! file is [installed](ConfigFile::install), [`var`] reads a setting from the
+//! environment first and falls back to the file, so environment variables
+//! always take precedence.
+
+use std::{collections::BTreeMap, path::Path, sync::OnceLock};
+
+use crate::{error::RustackError, types::AwsRegion};
+
+/// Environment variable naming the configuration file to load.
+pub const CONFIG_FILE_ENV: &str = "RUSTACK_CONFIG";
+
+/// The keys a configuration file accepts, as `(section, key, environment
+/// variable)`.
+pub const FILE_KEYS: &[(&str, &str, &str)] = &[
+    ("gateway", "listen", "GATEWAY_LISTEN"),
+    ("gateway", "listen_tls", "GATEWAY_LISTEN_TLS"),
+    ("gateway", "tls_cert", "GATEWAY_TLS_CERT"),
+    ("gateway", "tls_key", "GATEWAY_TLS_KEY"),
+    ("gateway", "tls_self_signed", "GATEWAY_TLS_SELF_SIGNED"),
+    ("gateway", "services", "SERVICES"),
+    ("gateway", "default_region", "DEFAULT_REGION"),
+    ("gateway", "log_level", "LOG_LEVEL"),
+    ("gateway", "max_connections", "GATEWAY_MAX_CONNECTIONS"),
+    (
+        "gateway",
+        "h2_max_concurrent_streams",
+        "GATEWAY_H2_MAX_CONCURRENT_STREAMS",
+    ),
+    ("gateway", "read_timeout_secs", "GATEWAY_READ_TIMEOUT_SECS"),
+    (
+        "gateway",
+        "write_timeout_secs",
+        "GATEWAY_WRITE_TIMEOUT_SECS",
+    ),
+    ("gateway", "max_header_bytes", "GATEWAY_MAX_HEADER_BYTES"),
+    ("gateway", "shutdown_grace_period", "SHUTDOWN_GRACE_PERIOD"),
+    ("gateway", "metrics_enabled", "METRICS_ENABLED"),
+    ("gateway", "access_log_format", "ACCESS_LOG_FORMAT"),
+    ("gateway", "access_log_file", "ACCESS_LOG_FILE"),
+    ("gateway", "otlp_endpoint", "OTEL_EXPORTER_OTLP_ENDPOINT"),
+    ("gateway", "credentials_file", "CREDENTIALS_FILE"),
+    ("gateway", "persistence", "PERSISTENCE"),
+    ("gateway", "data_dir", "DATA_DIR"),
+    ("gateway", "init_data_dir", "INIT_DATA_DIR"),
+    (
+        "gateway",
+        "init_data_continue_on_error",
+        "INIT_DATA_CONTINUE_ON_ERROR",
+    ),
+    ("s3", "domain", "S3_DOMAIN"),
+    ("s3", "virtual_hosting", "S3_VIRTUAL_HOSTING"),
+    (
+        "s3",
+        "skip_signature_validation",
+        "S3_SKIP_SIGNATURE_VALIDATION",
+    ),
+    ("s3", "max_memory_object_size", "S3_MAX_MEMORY_OBJECT_SIZE"),
+    ("s3", "spillover_dir", "S3_SPILLOVER_DIR"),
+    ("s3", "max_disk_bytes", "S3_MAX_DISK_BYTES"),
+    (
+        "s3",
+        "access_log_flush_interval",
+        "S3_ACCESS_LOG_FLUSH_INTERVAL",
+    ),
+    ("s3", "access_log_max_records", "S3_ACCESS_LOG_MAX_RECORDS"),
+    ("s3", "strict_validation", "S3_STRICT_VALIDATION"),
+    ("s3", "record_dir", "S3_RECORD_DIR"),
+    ("s3", "replay_dir", "S3_REPLAY_DIR"),
+    ("s3", "replay_match_headers", "S3_REPLAY_MATCH_HEADERS"),
+    ("s3", "serve_corrupt_objects", "S3_SERVE_CORRUPT_OBJECTS"),
+    ("s3", "max_request_body_bytes", "S3_MAX_REQUEST_BODY_BYTES"),
+    (
+        "s3",
+        "legacy_object_ownership",
+        "S3_LEGACY_OBJECT_OWNERSHIP",
+    ),
+    (
+        "dynamodb",
+        "skip_signature_validation",
+        "DYNAMODB_SKIP_SIGNATURE_VALIDATION",
+    ),
+    (
+        "dynamodb",
+        "table_create_delay_ms",
+        "DYNAMODB_TABLE_CREATE_DELAY_MS",
+    ),
+    (
+        "dynamodb",
+        "table_delete_delay_ms",
+        "DYNAMODB_TABLE_DELETE_DELAY_MS",
+    ),
+    (
+        "dynamodb",
+        "strict_reserved_words",
+        "DYNAMODB_STRICT_RESERVED_WORDS",
+    ),
+    (
+        "dynamodb",
+        "batch_write_unprocessed",
+        "DYNAMODB_BATCH_WRITE_UNPROCESSED",
+    ),
+    (
+        "dynamodb",
+        "max_request_body_bytes",
+        "DYNAMODB_MAX_REQUEST_BODY_BYTES",
+    ),
+    (
+        "dynamodb",
+        "response_size_limit",
+        "DYNAMODB_RESPONSE_SIZE_LIMIT",
+    ),
+    ("dynamodb", "persistence_dir", "DYNAMODB_PERSISTENCE_DIR"),
+];
+
+/// The configuration file installed for this process, if any.
+static INSTALLED: OnceLock<ConfigFile> = OnceLock::new();
+
+/// Read a setting: the environment variable `name` if set, otherwise the
+/// matching key of the installed configuration file.
+#[must_use]
+pub fn var(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .or_else(|| INSTALLED.get()?.get(name).map(str::to_owned))
+}
+
+/// Settings loaded from a TOML configuration file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigFile {
+    /// Values keyed by the environment variable they stand for.
+    settings: BTreeMap<&'static str, String>,
+    /// Problems with the file that did not stop it from loading.
+    warnings: Vec<String>,
+}
+
+impl ConfigFile {
+    /// Load and parse the configuration file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RustackError::Config`] if the file cannot be read or is not
+    /// valid TOML.
+    #[allow(clippy::disallowed_methods)] // Read once at startup, before the runtime serves.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RustackError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| RustackError::Config(format!("cannot read {}: {e}", path.display())))?;
+        Self::parse(&contents).map_err(|e| RustackError::Config(format!("{}: {e}", path.display())))
+    }
+
+    /// Parse a configuration file from its TOML text.
+    ///
+    /// Unknown sections and keys are skipped and reported by
+    /// [`warnings`](Self::warnings), along with the nearest valid name.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RustackError::Config`] if `contents` is not valid TOML.
+    pub fn parse(contents: &str) -> Result<Self, RustackError> {
+        let table: toml::Table = contents
+            .parse()
+            .map_err(|e: toml::de::Error| RustackError::Config(e.message().to_owned()))?;
+        let mut file = Self::default();
+        for (section, value) in table {
+            let toml::Value::Table(keys) = value else {
+                file.warnings.push(format!(
+                    "ignoring top-level key `{section}`: settings belong in a [gateway], [s3], or \
+                     [dynamodb] section"
+                ));
+                continue;
+            };
+            if !FILE_KEYS.iter().any(|(s, ..)| *s == section) {
+                let sections = FILE_KEYS.iter().map(|(s, ..)| *s);
+                file.warnings.push(unknown("section", &section, sections));
+                continue;
+            }
+            for (key, value) in keys {
+                let entry = FILE_KEYS
+                    .iter()
+                    .find(|(s, k, _)| *s == section && *k == key);
+                let Some((_, _, env)) = entry else {
+                    let keys = FILE_KEYS
+                        .iter()
+                        .filter(|(s, ..)| *s == section)
+                        .map(|(_, k, _)| *k);
+                    file.warnings
+                        .push(unknown(&format!("key in [{section}]"), &key, keys));
+                    continue;
+                };
+                match setting_value(&value) {
+                    Some(value) => {
+                        file.settings.insert(env, value);
+                    }
+                    None => file.warnings.push(format!(
+                        "ignoring `{section}.{key}`: expected a string, number, boolean, or list"
+                    )),
+                }
+            }
+        }
+        Ok(file)
+    }
+
+    /// The value the file gives the environment variable `name`.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.settings.get(name).map(String::as_str)
+    }
+
+    /// Problems found while parsing the file, such as unknown keys.
+    #[must_use]
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Make this file the fallback [`var`] reads settings from.
+    ///
+    /// Only the first file installed in a process takes effect; returns
+    /// `false` if one was already installed.
+    pub fn install(self) -> bool {
+        INSTALLED.set(self).is_ok()
+    }
+}
+
+/// Render a TOML value as the string its environment variable would hold.
+///
+/// Lists are joined with commas, as in `SERVICES=s3,dynamodb`.
+fn setting_value(value: &toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(s) => Some(s.clone()),
+        toml::Value::Integer(n) => Some(n.to_string()),
+        toml::Value::Float(n) => Some(n.to_string()),
+        toml::Value::Boolean(b) => Some(b.to_string()),
+        toml::Value::Array(items) => items
+            .iter()
+            .map(setting_value)
+            .collect::<Option<Vec<_>>>()
+            .map(|items| items.join(",")),
+        toml::Value::Datetime(_) | toml::Value::Table(_) => None,
+    }
+}
 
-use crate::types::AwsRegion;
+/// Warning for an unknown `what`, suggesting the nearest of `valid`.
+fn unknown<'a>(what: &str, name: &str, valid: impl Iterator<Item = &'a str>) -> String {
+    let nearest = valid.min_by_key(|candidate| edit_distance(name, candidate));
+    match nearest {
+        Some(nearest) => format!("ignoring unknown {what} `{name}`; did you mean `{nearest}`?"),
+        None => format!("ignoring unknown {what} `{name}`"),
+    }
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
 
 /// Global configuration for Rustack.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -33,29 +312,50 @@ impl Default for RustackConfig {
 }
 
 impl RustackConfig {
-    /// Load configuration from environment variables.
+    /// Load configuration from environment variables, falling back to the
+    /// installed configuration file.
     #[must_use]
     pub fn from_env() -> Self {
         let mut config = Self::default();
 
-        if let Ok(v) = std::env::var("GATEWAY_LISTEN") {
+        if let Some(v) = var("GATEWAY_LISTEN") {
             config.gateway_listen = v;
         }
-        if let Ok(v) = std::env::var("DEFAULT_REGION") {
+        if let Some(v) = var("DEFAULT_REGION") {
             config.default_region = AwsRegion::new(v);
         }
-        if let Ok(v) = std::env::var("LOG_LEVEL") {
+        if let Some(v) = var("LOG_LEVEL") {
             config.log_level = v;
         }
-        if let Ok(v) = std::env::var("PERSISTENCE") {
+        if let Some(v) = var("PERSISTENCE") {
             config.persistence = v == "1" || v.eq_ignore_ascii_case("true");
         }
-        if let Ok(v) = std::env::var("DATA_DIR") {
+        if let Some(v) = var("DATA_DIR") {
             config.data_dir = v;
         }
 
         config
     }
+
+    /// Install the configuration file at `path` and load configuration from
+    /// the environment, falling back to the file.
+    ///
+    /// Every service config read afterwards sees the file too. Warnings about
+    /// the file are logged.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RustackError::Config`] if the file cannot be read or parsed.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, RustackError> {
+        let file = ConfigFile::load(&path)?;
+        for warning in file.warnings() {
+            tracing::warn!(path = %path.as_ref().display(), "{warning}");
+        }
+        if !file.install() {
+            tracing::warn!("a configuration file was already loaded, ignoring this one");
+        }
+        Ok(Self::from_env())
+    }
 }
 
 #[cfg(test)]
@@ -69,4 +369,67 @@ mod tests {
         assert_eq!(config.default_region.as_str(), "us-east-1");
         assert!(!config.persistence);
     }
+
+    #[test]
+    fn test_should_map_file_keys_to_environment_variables() {
+        let file = ConfigFile::parse(
+            r#"
+            [gateway]
+            listen = "127.0.0.1:4567"
+            services = ["s3", "dynamodb"]
+            metrics_enabled = true
+
+            [s3]
+            max_memory_object_size = 1048576
+
+            [dynamodb]
+            table_create_delay_ms = 500
+            "#,
+        )
+        .unwrap();
+        assert!(file.warnings().is_empty());
+        assert_eq!(file.get("GATEWAY_LISTEN"), Some("127.0.0.1:4567"));
+        assert_eq!(file.get("SERVICES"), Some("s3,dynamodb"));
+        assert_eq!(file.get("METRICS_ENABLED"), Some("true"));
+        assert_eq!(file.get("S3_MAX_MEMORY_OBJECT_SIZE"), Some("1048576"));
+        assert_eq!(file.get("DYNAMODB_TABLE_CREATE_DELAY_MS"), Some("500"));
+        assert_eq!(file.get("S3_DOMAIN"), None);
+    }
+
+    #[test]
+    fn test_should_warn_about_unknown_keys_with_nearest_match() {
+        let file = ConfigFile::parse(
+            r#"
+            listen = "0.0.0.0:4566"
+
+            [s3]
+            virtual_hostng = false
+
+            [dynamdb]
+            skip_signature_validation = false
+            "#,
+        )
+        .unwrap();
+        assert_eq!(file.get("S3_VIRTUAL_HOSTING"), None);
+        let warned = |text: &str| file.warnings().iter().any(|w| w.contains(text));
+        assert_eq!(file.warnings().len(), 3);
+        assert!(warned("top-level key `listen`"));
+        assert!(warned("`virtual_hostng`; did you mean `virtual_hosting`?"));
+        assert!(warned("`dynamdb`; did you mean `dynamodb`?"));
+    }
+
+    #[test]
+    fn test_should_reject_invalid_toml() {
+        assert!(matches!(
+            ConfigFile::parse("[gateway\nlisten = 1"),
+            Err(RustackError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_should_compute_edit_distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("dynamodb", "dynamodb"), 0);
+    }
 }
@@ -4,12 +4,16 @@
 //! Rustack service implementations, including multi-account/multi-region
 //! state management, configuration, and common AWS type definitions.
 
-mod config;
+pub mod access_log;
+pub mod config;
 mod error;
+pub mod request_id;
 mod state;
 mod types;
 
-pub use config::RustackConfig;
+pub use access_log::{AccessLogEntry, AccessLogFormat, AccessLogSink, AccessLogger};
+pub use config::{ConfigFile, RustackConfig};
 pub use error::{RustackError, RustackResult};
+pub use request_id::{TRACE_ID_HEADER, TRACEPARENT_HEADER, new_request_id, request_span};
 pub use state::AccountRegionStore;
-pub use types::{AccountId, AwsRegion};
+pub use types::{AccountId, AwsRegion, RequestScope};
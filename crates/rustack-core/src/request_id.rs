@@ -0,0 +1,168 @@
+//! Per-request correlation for the HTTP services.
+//!
+//! Every request gets a fresh request id, returned to the client in the
+//! service's request-id header and recorded on a tracing span wrapping the
+//! whole request, so each log line for the request carries it. A client
+//! `X-Amzn-Trace-Id` is recorded on the same span and echoed back unchanged.
+//!
+//! Once span export is [enabled](enable_span_export), the request span also
+//! records what an OpenTelemetry collector needs: the span is named after the
+//! resolved operation (`S3.PutObject`, `DynamoDB.Query`), carries the bucket,
+//! key, or table, the response status and error code, and continues the
+//! trace of a client W3C `traceparent` header. Without span export none of
+//! this is recorded, so log lines are unchanged.
+
+use std::sync::OnceLock;
+
+use tracing::{Span, field::Empty};
+
+/// Header carrying an X-Ray style trace id, propagated from the request to
+/// the response.
+pub const TRACE_ID_HEADER: &str = "x-amzn-trace-id";
+
+/// Header carrying the caller's W3C trace context.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// The exporter-specific parts of span export.
+#[derive(Debug, Clone, Copy)]
+pub struct SpanExport {
+    /// Makes a request span the child of the remote span named by a
+    /// `traceparent` header value.
+    pub link_parent: fn(&Span, &str),
+    /// Renames an exported request span. Exporters fix a span's name when it
+    /// is first entered, before the operation is known, so a recorded
+    /// `otel.name` field would come too late.
+    pub rename: fn(&Span, String),
+}
+
+/// Set once span export is enabled.
+static SPAN_EXPORT: OnceLock<SpanExport> = OnceLock::new();
+
+/// Enable span export through `export`.
+///
+/// Called once at startup by the process exporting spans; later calls are
+/// ignored.
+pub fn enable_span_export(export: SpanExport) {
+    let _ = SPAN_EXPORT.set(export);
+}
+
+/// Whether span export is enabled.
+#[must_use]
+pub fn span_export_enabled() -> bool {
+    SPAN_EXPORT.get().is_some()
+}
+
+/// Generate a new request id.
+#[must_use]
+pub fn new_request_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// The span wrapping a single request to `service`.
+///
+/// `trace_id` is the client's `X-Amzn-Trace-Id` and `traceparent` its W3C
+/// `traceparent`, if it sent them.
+#[must_use]
+pub fn request_span(
+    service: &'static str,
+    request_id: &str,
+    trace_id: Option<&str>,
+    traceparent: Option<&str>,
+) -> Span {
+    let span = tracing::info_span!(
+        "request",
+        service,
+        request_id,
+        trace_id,
+        otel.kind = Empty,
+        otel.status_code = Empty,
+        rpc.service = Empty,
+        rpc.method = Empty,
+        bucket = Empty,
+        key = Empty,
+        table = Empty,
+        http.response.status_code = Empty,
+        error.code = Empty,
+    );
+    if let Some(export) = SPAN_EXPORT.get() {
+        span.record("otel.kind", "server");
+        if let Some(traceparent) = traceparent {
+            (export.link_parent)(&span, traceparent);
+        }
+    }
+    span
+}
+
+/// Name the current request span after the resolved operation, as
+/// `<service>.<operation>`, when span export is enabled.
+pub fn record_operation(service: &str, operation: &str) {
+    if let Some(export) = SPAN_EXPORT.get() {
+        let span = Span::current();
+        (export.rename)(&span, format!("{service}.{operation}"));
+        span.record("rpc.service", service);
+        span.record("rpc.method", operation);
+    }
+}
+
+/// Record the resource a request addresses (`bucket`, `key`, or `table`) on
+/// the current request span, when span export is enabled.
+pub fn record_resource(field: &'static str, value: Option<&str>) {
+    if let Some(value) = value.filter(|_| span_export_enabled()) {
+        Span::current().record(field, value);
+    }
+}
+
+/// Record the response status and error code on the current request span,
+/// when span export is enabled. Server errors mark the span as failed.
+pub fn record_outcome(status: u16, error_code: Option<&str>) {
+    if span_export_enabled() {
+        let span = Span::current();
+        span.record("http.response.status_code", status);
+        if let Some(code) = error_code {
+            span.record("error.code", code);
+        }
+        if status >= 500 {
+            span.record("otel.status_code", "ERROR");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[test]
+    fn test_should_generate_distinct_request_ids() {
+        let first = new_request_id();
+        assert_eq!(first.len(), 36);
+        assert_ne!(first, new_request_id());
+    }
+
+    #[test]
+    fn test_should_link_client_traces_once_span_export_is_enabled() {
+        static LINKED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+        fn link(_: &Span, traceparent: &str) {
+            LINKED.lock().expect("lock").push(traceparent.to_owned());
+        }
+        fn rename(_: &Span, name: String) {
+            LINKED.lock().expect("lock").push(name);
+        }
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+        drop(request_span("s3", "id", None, Some(traceparent)));
+        assert!(!span_export_enabled());
+        assert!(LINKED.lock().expect("lock").is_empty());
+
+        enable_span_export(SpanExport {
+            link_parent: link,
+            rename,
+        });
+        assert!(span_export_enabled());
+        drop(request_span("s3", "id", None, None));
+        let span = request_span("s3", "id", None, Some(traceparent));
+        span.in_scope(|| record_operation("S3", "GetObject"));
+        assert_eq!(*LINKED.lock().expect("lock"), [traceparent, "S3.GetObject"]);
+    }
+}
@@ -70,6 +70,15 @@ impl<T: Default + Send + Sync> AccountRegionStore<T> {
             .map(|(_, v)| v)
     }
 
+    /// Snapshot the state of every (account, region) entry.
+    #[must_use]
+    pub fn values(&self) -> Vec<Arc<T>> {
+        self.inner
+            .iter()
+            .map(|entry| Arc::clone(entry.value()))
+            .collect()
+    }
+
     /// Reset all state in the store.
     pub fn reset(&self) {
         self.inner.clear();
@@ -152,6 +161,7 @@ mod tests {
         let _ = store.get_or_create(&AccountId::default(), &AwsRegion::new("eu-west-1"));
 
         assert_eq!(store.len(), 2);
+        assert_eq!(store.values().len(), 2);
         store.reset();
         assert!(store.is_empty());
     }
@@ -22,6 +22,18 @@ impl AccountId {
         Ok(Self(id))
     }
 
+    /// Derive the account ID a request is attributed to from its access key ID.
+    ///
+    /// Following LocalStack, an access key ID that is itself a 12-digit number
+    /// is used as the account ID; any other key (including none, for unsigned
+    /// requests) maps to [`AccountId::DEFAULT`].
+    #[must_use]
+    pub fn from_access_key_id(access_key_id: Option<&str>) -> Self {
+        access_key_id
+            .and_then(|key| Self::new(key).ok())
+            .unwrap_or_default()
+    }
+
     /// Get the account ID as a string slice.
     #[must_use]
     pub fn as_str(&self) -> &str {
@@ -74,6 +86,44 @@ impl fmt::Display for AwsRegion {
     }
 }
 
+/// The (account, region) namespace a request operates in.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RequestScope {
+    /// Account the caller belongs to.
+    pub account: AccountId,
+    /// Region the request is addressed to.
+    pub region: AwsRegion,
+}
+
+impl RequestScope {
+    /// Create a scope for the given account and region.
+    #[must_use]
+    pub fn new(account: AccountId, region: AwsRegion) -> Self {
+        Self { account, region }
+    }
+
+    /// Resolve the scope of a request from the access key ID and the region of
+    /// its SigV4 credential scope, falling back to `default_region` when the
+    /// request does not name one.
+    #[must_use]
+    pub fn resolve(
+        access_key_id: Option<&str>,
+        region: Option<&str>,
+        default_region: &str,
+    ) -> Self {
+        Self {
+            account: AccountId::from_access_key_id(access_key_id),
+            region: AwsRegion::new(region.unwrap_or(default_region)),
+        }
+    }
+}
+
+impl fmt::Display for RequestScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.account, self.region)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -108,4 +158,33 @@ mod tests {
         let region = AwsRegion::default();
         assert_eq!(region.as_str(), "us-east-1");
     }
+
+    #[test]
+    fn test_should_derive_account_from_numeric_access_key() {
+        let id = AccountId::from_access_key_id(Some("111122223333"));
+        assert_eq!(id.as_str(), "111122223333");
+    }
+
+    #[test]
+    fn test_should_default_account_for_other_access_keys() {
+        assert_eq!(
+            AccountId::from_access_key_id(Some("test")),
+            AccountId::default()
+        );
+        assert_eq!(AccountId::from_access_key_id(None), AccountId::default());
+    }
+
+    #[test]
+    fn test_should_resolve_request_scope() {
+        let scope = RequestScope::resolve(Some("111122223333"), Some("eu-west-1"), "us-east-1");
+        assert_eq!(scope.account.as_str(), "111122223333");
+        assert_eq!(scope.region.as_str(), "eu-west-1");
+        assert_eq!(scope.to_string(), "111122223333/eu-west-1");
+
+        let scope = RequestScope::resolve(None, None, "ap-south-1");
+        assert_eq!(
+            scope,
+            RequestScope::new(AccountId::default(), AwsRegion::new("ap-south-1"))
+        );
+    }
 }
@@ -0,0 +1,147 @@
+//! Filtering a large table in place against cloning every item first.
+//!
+//! Scan and Query used to clone each item of a page before filtering it;
+//! [`TableStorage::scan_with`] and [`TableStorage::query_with`] visit items in
+//! place so only the kept ones are cloned. Each benchmark reads the whole table
+//! page by page with a filter keeping 1% of the items.
+//!
+//! Run with `cargo bench -p rustack-dynamodb-core --bench filter`.
+// `criterion_group!` defines an undocumented public function.
+#![allow(missing_docs)]
+
+use std::{collections::HashMap, convert::Infallible, hint::black_box};
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use rustack_dynamodb_core::storage::{
+    KeyAttribute, KeySchema, PageLimit, PrimaryKey, TableStorage,
+};
+use rustack_dynamodb_model::{AttributeValue, types::ScalarAttributeType};
+
+const ITEMS: usize = 200_000;
+
+type Item = HashMap<String, AttributeValue>;
+
+/// A table of [`ITEMS`] items over two partitions, 1% of them hot.
+fn table() -> TableStorage {
+    let key = |name: &str| KeyAttribute {
+        name: name.to_owned(),
+        attr_type: ScalarAttributeType::S,
+    };
+    let storage = TableStorage::new(KeySchema {
+        partition_key: key("pk"),
+        sort_key: Some(key("sk")),
+    });
+    let payload: Item = (0..8)
+        .map(|i| (format!("field{i}"), AttributeValue::S("x".repeat(24))))
+        .collect();
+    for i in 0..ITEMS {
+        let item = [
+            ("pk", AttributeValue::S(format!("p{}", i % 2))),
+            ("sk", AttributeValue::S(format!("{i:07}"))),
+            ("hot", AttributeValue::Bool(i % 100 == 0)),
+            ("payload", AttributeValue::M(payload.clone())),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value))
+        .collect();
+        storage.put_item(item).ok();
+    }
+    storage
+}
+
+/// Whether `item` passes the selective filter.
+fn is_hot(item: &Item) -> bool {
+    item.get("hot") == Some(&AttributeValue::Bool(true))
+}
+
+/// Read every page with `read_page`, which takes the previous page's last
+/// evaluated key and returns the items kept and the page's last evaluated
+/// key. Returns the items kept over all pages.
+fn read_all(
+    mut read_page: impl FnMut(Option<&PrimaryKey>) -> (usize, Option<PrimaryKey>),
+) -> usize {
+    let mut kept = 0;
+    let mut last_key = None;
+    loop {
+        let (page_kept, next) = read_page(last_key.as_ref());
+        kept += page_kept;
+        last_key = next;
+        if last_key.is_none() {
+            return kept;
+        }
+    }
+}
+
+fn bench_filter(c: &mut Criterion) {
+    let storage = table();
+    let partition = AttributeValue::S("p0".to_owned());
+    let sort_key = |key: Option<&PrimaryKey>| key.and_then(|k| k.sort_key.clone());
+
+    let mut group = c.benchmark_group("scan_filter");
+    group.sample_size(10);
+    group.bench_function("cloned", |b| {
+        b.iter(|| {
+            read_all(|start| {
+                let (items, last_key) = storage.scan(None, start, None, None);
+                (items.iter().filter(|item| is_hot(item)).count(), last_key)
+            })
+        });
+    });
+    group.bench_function("in_place", |b| {
+        b.iter(|| {
+            read_all(|start| {
+                let mut kept = Vec::new();
+                let last_key = storage
+                    .scan_with(PageLimit::items(None), start, None, None, |item| {
+                        if is_hot(item) {
+                            kept.push(item.clone());
+                        }
+                        Ok::<_, Infallible>(())
+                    })
+                    .unwrap_or_default();
+                (black_box(kept).len(), last_key)
+            })
+        });
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("query_filter");
+    group.sample_size(10);
+    group.bench_function("cloned", |b| {
+        b.iter(|| {
+            read_all(|start| {
+                let start = sort_key(start);
+                let (items, last_key) = storage.query(&partition, None, true, None, start.as_ref());
+                (items.iter().filter(|item| is_hot(item)).count(), last_key)
+            })
+        });
+    });
+    group.bench_function("in_place", |b| {
+        b.iter(|| {
+            read_all(|start| {
+                let start = sort_key(start);
+                let mut kept = Vec::new();
+                let last_key = storage
+                    .query_with(
+                        &partition,
+                        None,
+                        true,
+                        PageLimit::items(None),
+                        start.as_ref(),
+                        |item| {
+                            if is_hot(item) {
+                                kept.push(item.clone());
+                            }
+                            Ok::<_, Infallible>(())
+                        },
+                    )
+                    .unwrap_or_default();
+                (black_box(kept).len(), last_key)
+            })
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_filter);
+criterion_main!(benches);
@@ -0,0 +1,149 @@
+//! On-demand table backups (`CreateBackup` / `RestoreTableFromBackup`).
+//!
+//! A backup is an in-memory deep copy of a table's schema and items, taken
+//! when `CreateBackup` runs. Later writes to the table do not affect it, and
+//! it survives the table's deletion.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use rustack_dynamodb_model::{
+    AttributeValue,
+    types::{
+        AttributeDefinition, BackupDescription, BackupDetails, BackupStatus, BackupSummary,
+        BackupType, BillingMode, GlobalSecondaryIndex, KeySchemaElement, LocalSecondaryIndex,
+        ProvisionedThroughput, SSEDescription, SSESpecification, SourceTableDetails,
+        SourceTableFeatureDetails, SseStatus, SseType, StreamSpecification,
+    },
+};
+
+use crate::state::{DynamoDBTable, epoch_seconds};
+
+/// A snapshot of a table's schema and items.
+#[derive(Debug)]
+pub struct TableBackup {
+    /// The backup ARN.
+    pub arn: String,
+    /// The backup name.
+    pub name: String,
+    /// When the backup was created.
+    pub created_at: DateTime<Utc>,
+    /// Name of the backed-up table.
+    pub table_name: String,
+    /// ID of the backed-up table.
+    pub table_id: String,
+    /// ARN of the backed-up table.
+    pub table_arn: String,
+    /// When the backed-up table was created.
+    pub table_created_at: DateTime<Utc>,
+    /// Key schema elements.
+    pub key_schema_elements: Vec<KeySchemaElement>,
+    /// Attribute definitions.
+    pub attribute_definitions: Vec<AttributeDefinition>,
+    /// Billing mode.
+    pub billing_mode: BillingMode,
+    /// Provisioned throughput.
+    pub provisioned_throughput: Option<ProvisionedThroughput>,
+    /// Global secondary index definitions.
+    pub gsi_definitions: Vec<GlobalSecondaryIndex>,
+    /// Local secondary index definitions.
+    pub lsi_definitions: Vec<LocalSecondaryIndex>,
+    /// Stream specification.
+    pub stream_specification: Option<StreamSpecification>,
+    /// SSE specification.
+    pub sse_specification: Option<SSESpecification>,
+    /// Total size of the items in bytes.
+    pub size_bytes: u64,
+    /// The backed-up items.
+    pub items: Vec<HashMap<String, AttributeValue>>,
+}
+
+impl TableBackup {
+    /// Copy `table`'s schema and items into a new backup.
+    #[must_use]
+    pub fn capture(table: &DynamoDBTable, arn: String, name: String, now: DateTime<Utc>) -> Self {
+        Self {
+            arn,
+            name,
+            created_at: now,
+            table_name: table.name.clone(),
+            table_id: table.table_id.clone(),
+            table_arn: table.arn.clone(),
+            table_created_at: table.created_at,
+            key_schema_elements: table.key_schema_elements.clone(),
+            attribute_definitions: table.attribute_definitions.clone(),
+            billing_mode: table.billing_mode.clone(),
+            provisioned_throughput: table.provisioned_throughput.clone(),
+            gsi_definitions: table.gsi_definitions.clone(),
+            lsi_definitions: table.lsi_definitions.clone(),
+            stream_specification: table.stream_specification.clone(),
+            sse_specification: table.sse_specification.clone(),
+            size_bytes: table.storage.total_size_bytes(),
+            items: table.storage.snapshot(),
+        }
+    }
+
+    /// The backup's `BackupDetails`, reporting `status`.
+    #[must_use]
+    pub fn to_details(&self, status: BackupStatus) -> BackupDetails {
+        BackupDetails {
+            backup_arn: self.arn.clone(),
+            backup_name: self.name.clone(),
+            backup_size_bytes: Some(self.size_bytes_i64()),
+            backup_status: status,
+            backup_type: BackupType::User,
+            backup_creation_date_time: epoch_seconds(self.created_at),
+            backup_expiry_date_time: None,
+        }
+    }
+
+    /// The backup's `BackupDescription`, reporting `status`.
+    #[must_use]
+    pub fn to_description(&self, status: BackupStatus) -> BackupDescription {
+        BackupDescription {
+            backup_details: Some(self.to_details(status)),
+            source_table_details: Some(SourceTableDetails {
+                table_name: self.table_name.clone(),
+                table_id: self.table_id.clone(),
+                table_arn: Some(self.table_arn.clone()),
+                table_size_bytes: Some(self.size_bytes_i64()),
+                key_schema: self.key_schema_elements.clone(),
+                table_creation_date_time: epoch_seconds(self.table_created_at),
+                provisioned_throughput: self.provisioned_throughput.clone().unwrap_or_default(),
+                item_count: Some(i64::try_from(self.items.len()).unwrap_or(i64::MAX)),
+                billing_mode: Some(self.billing_mode.clone()),
+            }),
+            source_table_feature_details: Some(SourceTableFeatureDetails {
+                local_secondary_indexes: self.lsi_definitions.clone(),
+                global_secondary_indexes: self.gsi_definitions.clone(),
+                stream_description: self.stream_specification.clone(),
+                sse_description: self.sse_specification.as_ref().map(|_| SSEDescription {
+                    status: Some(SseStatus::Enabled),
+                    sse_type: Some(SseType::Aes256),
+                    ..Default::default()
+                }),
+            }),
+        }
+    }
+
+    /// The backup's `BackupSummary` for `ListBackups`.
+    #[must_use]
+    pub fn to_summary(&self) -> BackupSummary {
+        BackupSummary {
+            table_name: Some(self.table_name.clone()),
+            table_id: Some(self.table_id.clone()),
+            table_arn: Some(self.table_arn.clone()),
+            backup_arn: Some(self.arn.clone()),
+            backup_name: Some(self.name.clone()),
+            backup_creation_date_time: Some(epoch_seconds(self.created_at)),
+            backup_expiry_date_time: None,
+            backup_status: Some(BackupStatus::Available),
+            backup_type: Some(BackupType::User),
+            backup_size_bytes: Some(self.size_bytes_i64()),
+        }
+    }
+
+    fn size_bytes_i64(&self) -> i64 {
+        i64::try_from(self.size_bytes).unwrap_or(i64::MAX)
+    }
+}
@@ -1,23 +1,196 @@
 //! DynamoDB configuration.
+//!
+//! [`DynamoDBConfig::from_env`] validates every variable it reads
+//! and reports all malformed ones at once, so a typo fails startup instead of
+//! silently falling back to a default.
+//!
+//! The toggles that change how requests are answered are grouped in
+//! [`DynamoDBBehavior`]. They can be changed while the provider runs (see
+//! [`RustackDynamoDB::update_config`](crate::provider::RustackDynamoDB::update_config));
+//! the other settings are fixed at startup.
 
-use std::env;
+use std::{fmt, path::PathBuf, str::FromStr, time::Duration};
+
+use rustack_core::config;
+use serde_json::{Map, Value, json};
 
 /// DynamoDB service configuration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DynamoDBConfig {
     /// Skip signature validation (default: true for local dev).
     pub skip_signature_validation: bool,
-    /// Default AWS region.
+    /// Default AWS region (default: `us-east-1`).
     pub default_region: String,
+    /// Largest request body accepted, in bytes (default: 16 MiB, the
+    /// DynamoDB request size limit). Unlimited if unset.
+    pub max_request_body_bytes: Option<u64>,
+    /// Directory table data is persisted to (default: unset).
+    ///
+    /// Tables are currently kept in memory only; the directory is validated
+    /// and reserved so it cannot be changed under a running server.
+    pub persistence_dir: Option<PathBuf>,
+    /// Toggles adjustable at runtime.
+    pub behavior: DynamoDBBehavior,
+}
+
+/// DynamoDB behavior toggles, adjustable while the provider runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DynamoDBBehavior {
+    /// Reject expressions that use a reserved word as an unescaped attribute
+    /// name, as DynamoDB does (default: true).
+    ///
+    /// Turning this off restores the lax behaviour for code that relied on it.
+    pub strict_reserved_words: bool,
+    /// How long a new table reports `CREATING` before it becomes `ACTIVE`.
+    ///
+    /// Zero (the default) makes tables `ACTIVE` as soon as `CreateTable` returns.
+    pub table_create_delay: Duration,
+    /// How long a deleted table reports `DELETING` before it disappears.
+    ///
+    /// Zero (the default) removes tables as soon as `DeleteTable` returns.
+    pub table_delete_delay: Duration,
+    /// Number of write requests at the start of every `BatchWriteItem` call
+    /// to return in `UnprocessedItems` instead of writing (default: 0).
+    ///
+    /// Injects the throttling DynamoDB reports for batches, so client retry
+    /// loops can be exercised deterministically. At least one request per
+    /// batch is always written, so retries make progress.
+    pub batch_write_unprocessed: usize,
+    /// Cap each Query and Scan page at 1 MB, as DynamoDB does (default: true).
+    ///
+    /// Turning this off returns pages bounded by `Limit` only.
+    pub response_size_limit: bool,
 }
 
+/// Default cap on a request body: 16 MiB, the largest request DynamoDB
+/// accepts.
+const DEFAULT_MAX_REQUEST_BODY_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Settings fixed at startup, by their configuration file key.
+const IMMUTABLE_KEYS: &[&str] = &[
+    "skip_signature_validation",
+    "default_region",
+    "max_request_body_bytes",
+    "persistence_dir",
+];
+
 impl DynamoDBConfig {
-    /// Create configuration from environment variables.
+    /// Create configuration from environment variables, falling back to the
+    /// installed configuration file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ConfigError`] naming every variable whose value is
+    /// malformed.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_vars(config::var)
+    }
+
+    /// Create configuration from the variables `var` looks up.
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let mut env = EnvReader {
+            var,
+            invalid: Vec::new(),
+        };
+        let defaults = Self::default();
+        let config = Self {
+            skip_signature_validation: env.bool(
+                "DYNAMODB_SKIP_SIGNATURE_VALIDATION",
+                defaults.skip_signature_validation,
+            ),
+            default_region: env.region("DEFAULT_REGION", defaults.default_region),
+            max_request_body_bytes: env
+                .number("DYNAMODB_MAX_REQUEST_BODY_BYTES")
+                .map_or(defaults.max_request_body_bytes, |n| {
+                    Some(n).filter(|&n| n > 0)
+                }),
+            persistence_dir: env.directory("DYNAMODB_PERSISTENCE_DIR"),
+            behavior: DynamoDBBehavior {
+                strict_reserved_words: env.bool(
+                    "DYNAMODB_STRICT_RESERVED_WORDS",
+                    defaults.behavior.strict_reserved_words,
+                ),
+                table_create_delay: env
+                    .number("DYNAMODB_TABLE_CREATE_DELAY_MS")
+                    .map_or(defaults.behavior.table_create_delay, Duration::from_millis),
+                table_delete_delay: env
+                    .number("DYNAMODB_TABLE_DELETE_DELAY_MS")
+                    .map_or(defaults.behavior.table_delete_delay, Duration::from_millis),
+                batch_write_unprocessed: env
+                    .number("DYNAMODB_BATCH_WRITE_UNPROCESSED")
+                    .unwrap_or(defaults.behavior.batch_write_unprocessed),
+                response_size_limit: env.bool(
+                    "DYNAMODB_RESPONSE_SIZE_LIMIT",
+                    defaults.behavior.response_size_limit,
+                ),
+            },
+        };
+        if env.invalid.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError {
+                invalid: env.invalid,
+            })
+        }
+    }
+
+    /// The configuration as a JSON object keyed like the `[dynamodb]`
+    /// section of a configuration file.
     #[must_use]
-    pub fn from_env() -> Self {
-        Self {
-            skip_signature_validation: env_bool("DYNAMODB_SKIP_SIGNATURE_VALIDATION", true),
-            default_region: env::var("DEFAULT_REGION").unwrap_or_else(|_| "us-east-1".to_owned()),
+    pub fn to_json(&self) -> Value {
+        let behavior = &self.behavior;
+        json!({
+            "skip_signature_validation": self.skip_signature_validation,
+            "default_region": self.default_region,
+            "max_request_body_bytes": self.max_request_body_bytes.unwrap_or(0),
+            "persistence_dir": self.persistence_dir.as_ref().map(|dir| dir.display().to_string()),
+            "strict_reserved_words": behavior.strict_reserved_words,
+            "table_create_delay_ms": duration_millis(behavior.table_create_delay),
+            "table_delete_delay_ms": duration_millis(behavior.table_delete_delay),
+            "batch_write_unprocessed": behavior.batch_write_unprocessed,
+            "response_size_limit": behavior.response_size_limit,
+        })
+    }
+
+    /// Apply `patch`, a JSON object of behavior settings keyed as in
+    /// [`to_json`](Self::to_json), to the behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns why the patch is invalid, naming every unknown, immutable, or
+    /// mistyped setting; the configuration is then left unchanged.
+    pub fn apply_patch(&mut self, patch: &Map<String, Value>) -> Result<(), String> {
+        let mut behavior = self.behavior;
+        let mut errors = Vec::new();
+        for (key, value) in patch {
+            let applied = match key.as_str() {
+                "strict_reserved_words" => patch_bool(value, &mut behavior.strict_reserved_words),
+                "table_create_delay_ms" => patch_millis(value, &mut behavior.table_create_delay),
+                "table_delete_delay_ms" => patch_millis(value, &mut behavior.table_delete_delay),
+                "batch_write_unprocessed" => value
+                    .as_u64()
+                    .and_then(|v| usize::try_from(v).ok())
+                    .map(|v| behavior.batch_write_unprocessed = v)
+                    .ok_or("a non-negative integer"),
+                "response_size_limit" => patch_bool(value, &mut behavior.response_size_limit),
+                key if IMMUTABLE_KEYS.contains(&key) => {
+                    errors.push(format!("{key} cannot be changed while the server runs"));
+                    continue;
+                }
+                key => {
+                    errors.push(format!("unknown setting {key}"));
+                    continue;
+                }
+            };
+            if let Err(expected) = applied {
+                errors.push(format!("{key} must be {expected}, got {value}"));
+            }
+        }
+        if errors.is_empty() {
+            self.behavior = behavior;
+            Ok(())
+        } else {
+            Err(errors.join("; "))
         }
     }
 }
@@ -27,12 +200,290 @@ impl Default for DynamoDBConfig {
         Self {
             skip_signature_validation: true,
             default_region: "us-east-1".to_owned(),
+            max_request_body_bytes: Some(DEFAULT_MAX_REQUEST_BODY_BYTES),
+            persistence_dir: None,
+            behavior: DynamoDBBehavior::default(),
+        }
+    }
+}
+
+impl Default for DynamoDBBehavior {
+    fn default() -> Self {
+        Self {
+            strict_reserved_words: true,
+            table_create_delay: Duration::ZERO,
+            table_delete_delay: Duration::ZERO,
+            batch_write_unprocessed: 0,
+            response_size_limit: true,
         }
     }
 }
 
-fn env_bool(key: &str, default: bool) -> bool {
-    env::var(key).map_or(default, |v| {
-        matches!(v.as_str(), "1" | "true" | "yes" | "TRUE" | "YES")
-    })
+/// An environment variable whose value its setting cannot take.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidVar {
+    /// The variable name.
+    pub name: &'static str,
+    /// The value it holds.
+    pub value: String,
+    /// What the setting accepts.
+    pub expected: &'static str,
+}
+
+/// The malformed variables of a configuration, all reported together.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub struct ConfigError {
+    /// Every malformed variable, in the order they were read.
+    pub invalid: Vec<InvalidVar>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid DynamoDB configuration:")?;
+        for var in &self.invalid {
+            write!(
+                f,
+                "\n  {}={:?}: expected {}",
+                var.name, var.value, var.expected
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads variables, recording each malformed one instead of stopping.
+struct EnvReader<F> {
+    var: F,
+    invalid: Vec<InvalidVar>,
+}
+
+impl<F: Fn(&str) -> Option<String>> EnvReader<F> {
+    /// The value of `name`, if set and accepted by `parse`.
+    fn parse<T>(
+        &mut self,
+        name: &'static str,
+        expected: &'static str,
+        parse: impl FnOnce(&str) -> Option<T>,
+    ) -> Option<T> {
+        let value = (self.var)(name)?;
+        let parsed = parse(value.trim());
+        if parsed.is_none() {
+            self.invalid.push(InvalidVar {
+                name,
+                value,
+                expected,
+            });
+        }
+        parsed
+    }
+
+    fn bool(&mut self, name: &'static str, default: bool) -> bool {
+        self.parse(name, "true or false", |v| {
+            match v.to_ascii_lowercase().as_str() {
+                "1" | "true" | "yes" => Some(true),
+                "0" | "false" | "no" => Some(false),
+                _ => None,
+            }
+        })
+        .unwrap_or(default)
+    }
+
+    fn number<T: FromStr>(&mut self, name: &'static str) -> Option<T> {
+        self.parse(name, "a non-negative integer", |v| v.parse().ok())
+    }
+
+    fn region(&mut self, name: &'static str, default: String) -> String {
+        self.parse(name, "an AWS region such as us-east-1", |v| {
+            is_region(v).then(|| v.to_owned())
+        })
+        .unwrap_or(default)
+    }
+
+    fn directory(&mut self, name: &'static str) -> Option<PathBuf> {
+        self.parse(
+            name,
+            "a path that is a directory or does not exist yet",
+            |v| {
+                let path = PathBuf::from(v);
+                (!v.is_empty() && (path.is_dir() || !path.exists())).then_some(path)
+            },
+        )
+    }
+}
+
+/// Whether `name` is shaped like an AWS region: a two-letter area, one or
+/// more words, and a number, such as `us-east-1` or `us-gov-west-1`.
+fn is_region(name: &str) -> bool {
+    let parts: Vec<&str> = name.split('-').collect();
+    let [area, words @ .., number] = parts.as_slice() else {
+        return false;
+    };
+    area.len() == 2
+        && area.bytes().all(|b| b.is_ascii_lowercase())
+        && !words.is_empty()
+        && words
+            .iter()
+            .all(|w| !w.is_empty() && w.bytes().all(|b| b.is_ascii_lowercase()))
+        && !number.is_empty()
+        && number.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Set `setting` to `value` if it is a boolean.
+fn patch_bool(value: &Value, setting: &mut bool) -> Result<(), &'static str> {
+    *setting = value.as_bool().ok_or("a boolean")?;
+    Ok(())
+}
+
+/// Set `setting` to `value` milliseconds if it is a non-negative integer.
+fn patch_millis(value: &Value, setting: &mut Duration) -> Result<(), &'static str> {
+    *setting = Duration::from_millis(value.as_u64().ok_or("a non-negative integer")?);
+    Ok(())
+}
+
+/// Milliseconds of `duration`, saturating.
+fn duration_millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn from_vars(vars: &[(&str, &str)]) -> Result<DynamoDBConfig, ConfigError> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+            .collect();
+        DynamoDBConfig::from_vars(|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_should_read_documented_defaults() {
+        let config = from_vars(&[]).unwrap();
+        assert_eq!(config, DynamoDBConfig::default());
+        assert!(config.behavior.strict_reserved_words);
+        assert!(config.behavior.response_size_limit);
+        assert_eq!(config.max_request_body_bytes, Some(16 * 1024 * 1024));
+        assert_eq!(config.persistence_dir, None);
+    }
+
+    #[test]
+    fn test_should_read_valid_variables() {
+        let config = from_vars(&[
+            ("DEFAULT_REGION", "us-gov-west-1"),
+            ("DYNAMODB_SKIP_SIGNATURE_VALIDATION", "FALSE"),
+            ("DYNAMODB_MAX_REQUEST_BODY_BYTES", "0"),
+            ("DYNAMODB_STRICT_RESERVED_WORDS", "no"),
+            ("DYNAMODB_TABLE_CREATE_DELAY_MS", " 250 "),
+            ("DYNAMODB_BATCH_WRITE_UNPROCESSED", "2"),
+            ("DYNAMODB_RESPONSE_SIZE_LIMIT", "0"),
+        ])
+        .unwrap();
+        assert_eq!(config.default_region, "us-gov-west-1");
+        assert!(!config.skip_signature_validation);
+        assert_eq!(config.max_request_body_bytes, None);
+        assert_eq!(
+            config.behavior,
+            DynamoDBBehavior {
+                strict_reserved_words: false,
+                table_create_delay: Duration::from_millis(250),
+                table_delete_delay: Duration::ZERO,
+                batch_write_unprocessed: 2,
+                response_size_limit: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_should_report_every_invalid_variable() {
+        let err = from_vars(&[
+            ("DEFAULT_REGION", "us-east1"),
+            ("DYNAMODB_STRICT_RESERVED_WORDS", "ture"),
+            ("DYNAMODB_TABLE_CREATE_DELAY_MS", "5s"),
+            ("DYNAMODB_TABLE_DELETE_DELAY_MS", "100"),
+            ("DYNAMODB_BATCH_WRITE_UNPROCESSED", "-1"),
+            ("DYNAMODB_PERSISTENCE_DIR", "/dev/null"),
+        ])
+        .unwrap_err();
+        let names: Vec<&str> = err.invalid.iter().map(|var| var.name).collect();
+        assert_eq!(
+            names,
+            [
+                "DEFAULT_REGION",
+                "DYNAMODB_PERSISTENCE_DIR",
+                "DYNAMODB_STRICT_RESERVED_WORDS",
+                "DYNAMODB_TABLE_CREATE_DELAY_MS",
+                "DYNAMODB_BATCH_WRITE_UNPROCESSED",
+            ]
+        );
+        let message = err.to_string();
+        assert!(
+            message.contains(
+                r#"DYNAMODB_TABLE_CREATE_DELAY_MS="5s": expected a non-negative integer"#
+            )
+        );
+        assert!(message.contains("us-east1"));
+    }
+
+    #[test]
+    fn test_should_recognize_region_names() {
+        for region in [
+            "us-east-1",
+            "eu-central-2",
+            "us-gov-west-1",
+            "cn-northwest-1",
+        ] {
+            assert!(is_region(region), "{region}");
+        }
+        for region in [
+            "",
+            "us-east1",
+            "useast-1",
+            "us--1",
+            "US-EAST-1",
+            "us-east-x",
+        ] {
+            assert!(!is_region(region), "{region}");
+        }
+    }
+
+    #[test]
+    fn test_should_patch_behavior_and_reject_immutable_settings() {
+        let mut config = DynamoDBConfig::default();
+        let patch = |value: Value| value.as_object().cloned().unwrap();
+
+        config
+            .apply_patch(&patch(json!({
+                "strict_reserved_words": false,
+                "table_delete_delay_ms": 1500,
+                "response_size_limit": false,
+            })))
+            .unwrap();
+        assert!(!config.behavior.strict_reserved_words);
+        assert_eq!(
+            config.behavior.table_delete_delay,
+            Duration::from_millis(1500)
+        );
+        assert!(!config.behavior.response_size_limit);
+        assert_eq!(config.to_json()["table_delete_delay_ms"], 1500);
+
+        let before = config.clone();
+        let err = config
+            .apply_patch(&patch(json!({
+                "batch_write_unprocessed": 4,
+                "persistence_dir": "/tmp/tables",
+                "table_create_delay_ms": "soon",
+                "typo": true,
+            })))
+            .unwrap_err();
+        assert!(err.contains("persistence_dir cannot be changed"), "{err}");
+        assert!(
+            err.contains("table_create_delay_ms must be a non-negative integer"),
+            "{err}"
+        );
+        assert!(err.contains("unknown setting typo"), "{err}");
+        assert_eq!(config, before);
+    }
 }
@@ -0,0 +1,346 @@
+//! In-process DynamoDB client for Rust tests.
+//!
+//! [`DynamoDBTestClient`] drives a real [`RustackDynamoDB`] provider without
+//! an HTTP server, so tests get the same behavior as the server while working
+//! with plain `serde_json` values instead of `AttributeValue` maps. JSON
+//! converts to DynamoDB types the way the AWS document clients do: strings to
+//! `S`, numbers to `N`, booleans to `BOOL`, `null` to `NULL`, arrays to `L`,
+//! and objects to `M`.
+//!
+//! ```
+//! use rustack_dynamodb_core::embedded::DynamoDBTestClient;
+//! use serde_json::json;
+//!
+//! let client = DynamoDBTestClient::new();
+//! client.create_simple_table("users", "id").unwrap();
+//! client
+//!     .put("users", json!({ "id": "u1", "name": "Ada", "age": 36 }))
+//!     .unwrap();
+//!
+//! let user = client.get("users", json!({ "id": "u1" })).unwrap();
+//! assert_eq!(user, Some(json!({ "id": "u1", "name": "Ada", "age": 36 })));
+//!
+//! let found = client
+//!     .query("users", "id = :id", json!({ ":id": "u1" }))
+//!     .unwrap();
+//! assert_eq!(found.len(), 1);
+//! ```
+
+use std::collections::HashMap;
+
+use base64::Engine;
+use rustack_dynamodb_model::{
+    AttributeValue,
+    error::DynamoDBError,
+    input::{CreateTableInput, DeleteItemInput, GetItemInput, PutItemInput, QueryInput, ScanInput},
+    types::{AttributeDefinition, BillingMode, KeySchemaElement, KeyType, ScalarAttributeType},
+};
+use serde_json::{Map, Value};
+
+use crate::{config::DynamoDBConfig, provider::RustackDynamoDB};
+
+/// A DynamoDB client that calls a [`RustackDynamoDB`] provider in-process.
+#[derive(Debug, Clone)]
+pub struct DynamoDBTestClient {
+    provider: RustackDynamoDB,
+}
+
+impl Default for DynamoDBTestClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DynamoDBTestClient {
+    /// Create a client over a fresh provider with the default configuration.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_provider(RustackDynamoDB::new(DynamoDBConfig::default()))
+    }
+
+    /// Create a client over an existing provider, sharing its state.
+    #[must_use]
+    pub fn with_provider(provider: RustackDynamoDB) -> Self {
+        Self { provider }
+    }
+
+    /// The underlying provider, for operations the client has no helper for.
+    #[must_use]
+    pub fn provider(&self) -> &RustackDynamoDB {
+        &self.provider
+    }
+
+    /// Create an on-demand table with a string partition key.
+    pub fn create_simple_table(
+        &self,
+        table: &str,
+        partition_key: &str,
+    ) -> Result<(), DynamoDBError> {
+        self.create_table(table, partition_key, None)
+    }
+
+    /// Create an on-demand table with string partition and sort keys.
+    pub fn create_table_with_sort_key(
+        &self,
+        table: &str,
+        partition_key: &str,
+        sort_key: &str,
+    ) -> Result<(), DynamoDBError> {
+        self.create_table(table, partition_key, Some(sort_key))
+    }
+
+    fn create_table(
+        &self,
+        table: &str,
+        partition_key: &str,
+        sort_key: Option<&str>,
+    ) -> Result<(), DynamoDBError> {
+        let keys = std::iter::once((partition_key, KeyType::Hash))
+            .chain(sort_key.map(|sk| (sk, KeyType::Range)));
+        let (key_schema, attribute_definitions) = keys
+            .map(|(name, key_type)| {
+                (
+                    KeySchemaElement {
+                        attribute_name: name.to_owned(),
+                        key_type,
+                    },
+                    AttributeDefinition {
+                        attribute_name: name.to_owned(),
+                        attribute_type: ScalarAttributeType::S,
+                    },
+                )
+            })
+            .unzip();
+        self.provider.handle_create_table(CreateTableInput {
+            table_name: table.to_owned(),
+            key_schema,
+            attribute_definitions,
+            billing_mode: Some(BillingMode::PayPerRequest),
+            ..Default::default()
+        })?;
+        Ok(())
+    }
+
+    /// Write `item`, a JSON object, replacing any item with the same key.
+    pub fn put(&self, table: &str, item: Value) -> Result<(), DynamoDBError> {
+        self.provider.handle_put_item(PutItemInput {
+            table_name: table.to_owned(),
+            item: to_item(item)?,
+            ..Default::default()
+        })?;
+        Ok(())
+    }
+
+    /// Read the item with primary key `key`, a JSON object.
+    pub fn get(&self, table: &str, key: Value) -> Result<Option<Value>, DynamoDBError> {
+        let output = self.provider.handle_get_item(GetItemInput {
+            table_name: table.to_owned(),
+            key: to_item(key)?,
+            consistent_read: Some(true),
+            ..Default::default()
+        })?;
+        Ok(output.item.as_ref().map(from_item))
+    }
+
+    /// Delete the item with primary key `key`, a JSON object.
+    pub fn delete(&self, table: &str, key: Value) -> Result<(), DynamoDBError> {
+        self.provider.handle_delete_item(DeleteItemInput {
+            table_name: table.to_owned(),
+            key: to_item(key)?,
+            ..Default::default()
+        })?;
+        Ok(())
+    }
+
+    /// Run a `KeyConditionExpression` query and return every matching item,
+    /// following pagination.
+    ///
+    /// `values` is a JSON object of `ExpressionAttributeValues`.
+    pub fn query(
+        &self,
+        table: &str,
+        key_condition: &str,
+        values: Value,
+    ) -> Result<Vec<Value>, DynamoDBError> {
+        let expression_attribute_values = to_item(values)?;
+        let mut items = Vec::new();
+        let mut exclusive_start_key = HashMap::new();
+        loop {
+            let output = self.provider.handle_query(QueryInput {
+                table_name: table.to_owned(),
+                key_condition_expression: Some(key_condition.to_owned()),
+                expression_attribute_values: expression_attribute_values.clone(),
+                exclusive_start_key,
+                ..Default::default()
+            })?;
+            items.extend(output.items.iter().flatten().map(from_item));
+            let Some(key) = output.last_evaluated_key else {
+                return Ok(items);
+            };
+            exclusive_start_key = key;
+        }
+    }
+
+    /// Return every item in `table`, following pagination.
+    pub fn scan(&self, table: &str) -> Result<Vec<Value>, DynamoDBError> {
+        let mut items = Vec::new();
+        let mut exclusive_start_key = HashMap::new();
+        loop {
+            let output = self.provider.handle_scan(ScanInput {
+                table_name: table.to_owned(),
+                exclusive_start_key,
+                ..Default::default()
+            })?;
+            items.extend(output.items.iter().flatten().map(from_item));
+            let Some(key) = output.last_evaluated_key else {
+                return Ok(items);
+            };
+            exclusive_start_key = key;
+        }
+    }
+}
+
+/// Convert a JSON object into a DynamoDB item.
+///
+/// # Errors
+///
+/// Returns a `ValidationException` if `value` is not a JSON object.
+pub fn to_item(value: Value) -> Result<HashMap<String, AttributeValue>, DynamoDBError> {
+    match value {
+        Value::Object(map) => Ok(map
+            .into_iter()
+            .map(|(name, value)| (name, to_attribute_value(value)))
+            .collect()),
+        other => Err(DynamoDBError::validation(format!(
+            "Expected a JSON object for a DynamoDB item, got: {other}"
+        ))),
+    }
+}
+
+/// Convert a JSON value into an `AttributeValue`.
+#[must_use]
+pub fn to_attribute_value(value: Value) -> AttributeValue {
+    match value {
+        Value::Null => AttributeValue::Null(true),
+        Value::Bool(b) => AttributeValue::Bool(b),
+        Value::Number(n) => AttributeValue::N(n.to_string()),
+        Value::String(s) => AttributeValue::S(s),
+        Value::Array(list) => AttributeValue::L(list.into_iter().map(to_attribute_value).collect()),
+        Value::Object(map) => AttributeValue::M(
+            map.into_iter()
+                .map(|(name, value)| (name, to_attribute_value(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Convert a DynamoDB item into a JSON object.
+#[must_use]
+#[allow(clippy::implicit_hasher)]
+pub fn from_item(item: &HashMap<String, AttributeValue>) -> Value {
+    Value::Object(
+        item.iter()
+            .map(|(name, value)| (name.clone(), from_attribute_value(value)))
+            .collect::<Map<_, _>>(),
+    )
+}
+
+/// Convert an `AttributeValue` into a JSON value.
+///
+/// Numbers that JSON cannot represent exactly stay strings, binary values
+/// become base64 strings, and sets become arrays.
+#[must_use]
+pub fn from_attribute_value(value: &AttributeValue) -> Value {
+    let base64 = |b: &[u8]| Value::String(base64::engine::general_purpose::STANDARD.encode(b));
+    match value {
+        AttributeValue::S(s) => Value::String(s.clone()),
+        AttributeValue::N(n) => number_to_json(n),
+        AttributeValue::B(b) => base64(b),
+        AttributeValue::Ss(set) => set.iter().cloned().map(Value::String).collect(),
+        AttributeValue::Ns(set) => set.iter().map(|n| number_to_json(n)).collect(),
+        AttributeValue::Bs(set) => set.iter().map(|b| base64(b)).collect(),
+        AttributeValue::Bool(b) => Value::Bool(*b),
+        AttributeValue::Null(_) => Value::Null,
+        AttributeValue::L(list) => list.iter().map(from_attribute_value).collect(),
+        AttributeValue::M(map) => from_item(map),
+    }
+}
+
+/// A DynamoDB number as a JSON number, if it round-trips exactly.
+fn number_to_json(n: &str) -> Value {
+    serde_json::from_str::<serde_json::Number>(n)
+        .ok()
+        .filter(|parsed| parsed.to_string() == n)
+        .map_or_else(|| Value::String(n.to_owned()), Value::Number)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_should_round_trip_json_through_attribute_values() {
+        let value = json!({
+            "s": "text",
+            "n": 42,
+            "f": 1.5,
+            "b": true,
+            "z": null,
+            "l": [1, "two", [3]],
+            "m": { "nested": { "deep": false } },
+        });
+        let item = to_item(value.clone()).unwrap();
+        assert_eq!(item["n"], AttributeValue::N("42".to_owned()));
+        assert!(matches!(item["l"], AttributeValue::L(_)));
+        assert_eq!(from_item(&item), value);
+
+        assert_eq!(
+            from_attribute_value(&AttributeValue::N("12345678901234567890123".to_owned())),
+            json!("12345678901234567890123")
+        );
+        assert_eq!(
+            from_attribute_value(&AttributeValue::Ss(vec!["a".to_owned()])),
+            json!(["a"])
+        );
+        assert!(to_item(json!([1])).is_err());
+    }
+
+    #[test]
+    fn test_should_query_and_scan_across_pages() {
+        let client = DynamoDBTestClient::new();
+        client
+            .create_table_with_sort_key("events", "pk", "sk")
+            .unwrap();
+        let padding = "x".repeat(200_000);
+        for i in 0..12 {
+            client
+                .put(
+                    "events",
+                    json!({ "pk": "a", "sk": format!("{i:02}"), "pad": padding }),
+                )
+                .unwrap();
+        }
+        client
+            .put("events", json!({ "pk": "b", "sk": "00" }))
+            .unwrap();
+
+        let found = client
+            .query("events", "pk = :pk", json!({ ":pk": "a" }))
+            .unwrap();
+        assert_eq!(found.len(), 12);
+        assert_eq!(found[11]["sk"], "11");
+        assert_eq!(client.scan("events").unwrap().len(), 13);
+
+        client
+            .delete("events", json!({ "pk": "b", "sk": "00" }))
+            .unwrap();
+        assert_eq!(
+            client
+                .get("events", json!({ "pk": "b", "sk": "00" }))
+                .unwrap(),
+            None
+        );
+    }
+}
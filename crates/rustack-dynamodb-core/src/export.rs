@@ -0,0 +1,360 @@
+//! Table exports to S3 (`ExportTableToPointInTime`).
+//!
+//! An export writes a snapshot of a table to an S3 bucket using the layout
+//! DynamoDB produces:
+//!
+//! ```text
+//! <prefix>/AWSDynamoDB/<exportId>/_started
+//! <prefix>/AWSDynamoDB/<exportId>/data/<file>.json.gz
+//! <prefix>/AWSDynamoDB/<exportId>/manifest-files.json
+//! <prefix>/AWSDynamoDB/<exportId>/manifest-files.md5
+//! <prefix>/AWSDynamoDB/<exportId>/manifest-summary.json
+//! <prefix>/AWSDynamoDB/<exportId>/manifest-summary.md5
+//! ```
+//!
+//! Data files hold one `{"Item":{...}}` DynamoDB JSON object per line,
+//! gzip-compressed. The objects are written through an [`ExportWriter`], which
+//! is defined here and implemented by the server binary over the S3 provider
+//! (dependency inversion, as with [`crate::stream::StreamEmitter`]).
+
+use std::{collections::HashMap, io::Write};
+
+use async_trait::async_trait;
+use base64::Engine;
+use bytes::Bytes;
+use chrono::{DateTime, SecondsFormat, Utc};
+use flate2::{Compression, write::GzEncoder};
+use md5::{Digest, Md5};
+use rustack_core::AccountId;
+use rustack_dynamodb_model::{AttributeValue, types::ExportType};
+use serde_json::json;
+
+/// Error writing an export object to S3.
+#[derive(Debug, thiserror::Error)]
+pub enum ExportError {
+    /// The destination bucket does not exist.
+    #[error("The specified bucket does not exist: {0}")]
+    NoSuchBucket(String),
+    /// The exporting account may not write to the destination bucket.
+    #[error("Access Denied to bucket {0}")]
+    AccessDenied(String),
+    /// Writing an object failed.
+    #[error("Failed to write export object {key}: {reason}")]
+    WriteFailed {
+        /// The key of the object being written.
+        key: String,
+        /// The underlying error message.
+        reason: String,
+    },
+}
+
+impl ExportError {
+    /// The `FailureCode` reported by `DescribeExport` for this error.
+    #[must_use]
+    pub fn failure_code(&self) -> &'static str {
+        match self {
+            Self::NoSuchBucket(_) => "S3NoSuchBucket",
+            Self::AccessDenied(_) => "S3AccessDenied",
+            Self::WriteFailed { .. } => "UNKNOWN",
+        }
+    }
+}
+
+/// Trait for writing export objects to S3.
+///
+/// Implemented by the server binary over the S3 provider. Uses
+/// `async-trait` because it is used as `Arc<dyn ExportWriter>`.
+#[async_trait]
+pub trait ExportWriter: Send + Sync + 'static {
+    /// Store `body` as `key` in `bucket`, on behalf of `account`.
+    async fn put_object(
+        &self,
+        account: &AccountId,
+        bucket: &str,
+        key: &str,
+        body: Bytes,
+    ) -> Result<(), ExportError>;
+}
+
+/// A writer that discards every object.
+///
+/// Used when no S3 service is wired in.
+#[derive(Debug)]
+pub struct NoopExportWriter;
+
+#[async_trait]
+impl ExportWriter for NoopExportWriter {
+    async fn put_object(
+        &self,
+        _account: &AccountId,
+        _bucket: &str,
+        _key: &str,
+        _body: Bytes,
+    ) -> Result<(), ExportError> {
+        Ok(())
+    }
+}
+
+/// A point-in-time copy of a table, ready to be written to S3.
+#[derive(Debug)]
+pub struct ExportSnapshot {
+    /// The export ID (last segment of the export ARN).
+    pub export_id: String,
+    /// The export ARN.
+    pub export_arn: String,
+    /// The ARN of the exported table.
+    pub table_arn: String,
+    /// The ID of the exported table.
+    pub table_id: String,
+    /// The account that started the export.
+    pub account: AccountId,
+    /// The destination bucket.
+    pub bucket: String,
+    /// The key prefix, without a trailing slash.
+    pub prefix: Option<String>,
+    /// The server-side encryption algorithm reported in the manifest.
+    pub sse_algorithm: String,
+    /// The KMS key reported in the manifest.
+    pub sse_kms_key_id: Option<String>,
+    /// When the export started.
+    pub start_time: DateTime<Utc>,
+    /// The point in time the snapshot reflects.
+    pub export_time: DateTime<Utc>,
+    /// The billable size of the table, in bytes.
+    pub billed_size_bytes: u64,
+    /// The items to export.
+    pub items: Vec<HashMap<String, AttributeValue>>,
+}
+
+impl ExportSnapshot {
+    /// The key every object of this export lives under, ending in `/`.
+    #[must_use]
+    pub fn base_key(&self) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("{prefix}/AWSDynamoDB/{}/", self.export_id),
+            None => format!("AWSDynamoDB/{}/", self.export_id),
+        }
+    }
+
+    /// The key of the export's `manifest-summary.json`.
+    #[must_use]
+    pub fn manifest_summary_key(&self) -> String {
+        format!("{}manifest-summary.json", self.base_key())
+    }
+
+    /// Write the export to S3 and return its end time.
+    ///
+    /// The summary manifest is written last, so its presence marks a complete
+    /// export.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`ExportError`] reported by `writer`.
+    pub async fn write(&self, writer: &dyn ExportWriter) -> Result<DateTime<Utc>, ExportError> {
+        let base = self.base_key();
+        writer
+            .put_object(
+                &self.account,
+                &self.bucket,
+                &format!("{base}_started"),
+                Bytes::new(),
+            )
+            .await?;
+
+        let mut manifest_files = String::new();
+        if !self.items.is_empty() {
+            let key = format!("{base}data/{}.json.gz", uuid::Uuid::new_v4().simple());
+            let body = encode_data_file(&self.items).map_err(|e| ExportError::WriteFailed {
+                key: key.clone(),
+                reason: e.to_string(),
+            })?;
+            let digest = Md5::digest(&body);
+            let entry = json!({
+                "itemCount": self.items.len(),
+                "md5Checksum": base64::engine::general_purpose::STANDARD.encode(digest),
+                "etag": hex::encode(digest),
+                "dataFileS3Key": key,
+            });
+            manifest_files.push_str(&entry.to_string());
+            manifest_files.push('\n');
+            writer
+                .put_object(&self.account, &self.bucket, &key, body)
+                .await?;
+        }
+
+        let manifest_files_key = format!("{base}manifest-files.json");
+        self.put_with_md5(writer, &manifest_files_key, manifest_files.into_bytes())
+            .await?;
+
+        let end_time = Utc::now();
+        let summary = json!({
+            "version": "2020-06-30",
+            "exportArn": self.export_arn,
+            "startTime": format_time(self.start_time),
+            "endTime": format_time(end_time),
+            "tableArn": self.table_arn,
+            "tableId": self.table_id,
+            "exportTime": format_time(self.export_time),
+            "s3Bucket": self.bucket,
+            "s3Prefix": self.prefix,
+            "s3SseAlgorithm": self.sse_algorithm,
+            "s3SseKmsKeyId": self.sse_kms_key_id,
+            "manifestFilesS3Key": manifest_files_key,
+            "billedSizeBytes": self.billed_size_bytes,
+            "itemCount": self.items.len(),
+            "outputFormat": "DYNAMODB_JSON",
+            "exportType": ExportType::FullExport.as_str(),
+        });
+        self.put_with_md5(
+            writer,
+            &self.manifest_summary_key(),
+            summary.to_string().into_bytes(),
+        )
+        .await?;
+
+        Ok(end_time)
+    }
+
+    /// Write `body` as `key`, followed by its hex MD5 as the sibling `.md5` object.
+    async fn put_with_md5(
+        &self,
+        writer: &dyn ExportWriter,
+        key: &str,
+        body: Vec<u8>,
+    ) -> Result<(), ExportError> {
+        let md5 = hex::encode(Md5::digest(&body));
+        writer
+            .put_object(&self.account, &self.bucket, key, Bytes::from(body))
+            .await?;
+        let md5_key = format!("{}.md5", key.strip_suffix(".json").unwrap_or(key));
+        writer
+            .put_object(&self.account, &self.bucket, &md5_key, Bytes::from(md5))
+            .await
+    }
+}
+
+/// Gzip `items` as newline-delimited `{"Item":{...}}` DynamoDB JSON.
+fn encode_data_file(items: &[HashMap<String, AttributeValue>]) -> std::io::Result<Bytes> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    for item in items {
+        serde_json::to_writer(&mut encoder, &json!({ "Item": item }))?;
+        encoder.write_all(b"\n")?;
+    }
+    encoder.finish().map(Bytes::from)
+}
+
+/// Format a timestamp the way export manifests do (`2020-11-04T07:28:34.028Z`).
+fn format_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+    use parking_lot::Mutex;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordingWriter {
+        objects: Mutex<Vec<(String, Bytes)>>,
+    }
+
+    #[async_trait]
+    impl ExportWriter for RecordingWriter {
+        async fn put_object(
+            &self,
+            _account: &AccountId,
+            bucket: &str,
+            key: &str,
+            body: Bytes,
+        ) -> Result<(), ExportError> {
+            assert_eq!(bucket, "exports");
+            self.objects.lock().push((key.to_owned(), body));
+            Ok(())
+        }
+    }
+
+    fn snapshot(items: Vec<HashMap<String, AttributeValue>>) -> ExportSnapshot {
+        let now = Utc::now();
+        ExportSnapshot {
+            export_id: "01700000000000-0a1b2c3d".to_owned(),
+            export_arn: "arn:aws:dynamodb:us-east-1:000000000000:table/T/export/\
+                         01700000000000-0a1b2c3d"
+                .to_owned(),
+            table_arn: "arn:aws:dynamodb:us-east-1:000000000000:table/T".to_owned(),
+            table_id: "table-id".to_owned(),
+            account: AccountId::default(),
+            bucket: "exports".to_owned(),
+            prefix: Some("backups".to_owned()),
+            sse_algorithm: "AES256".to_owned(),
+            sse_kms_key_id: None,
+            start_time: now,
+            export_time: now,
+            billed_size_bytes: 42,
+            items,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_should_write_export_layout_with_manifests() {
+        let item = HashMap::from([("pk".to_owned(), AttributeValue::S("a".to_owned()))]);
+        let snapshot = snapshot(vec![item]);
+        let writer = RecordingWriter::default();
+        snapshot.write(&writer).await.expect("export written");
+
+        let objects = writer.objects.lock();
+        let keys: Vec<&str> = objects.iter().map(|(k, _)| k.as_str()).collect();
+        let base = "backups/AWSDynamoDB/01700000000000-0a1b2c3d/";
+        assert_eq!(keys[0], format!("{base}_started"));
+        assert!(keys[1].starts_with(&format!("{base}data/")));
+        assert!(keys[1].ends_with(".json.gz"));
+        assert_eq!(
+            &keys[2..],
+            [
+                format!("{base}manifest-files.json"),
+                format!("{base}manifest-files.md5"),
+                format!("{base}manifest-summary.json"),
+                format!("{base}manifest-summary.md5"),
+            ]
+        );
+
+        let mut data = String::new();
+        GzDecoder::new(objects[1].1.as_ref())
+            .read_to_string(&mut data)
+            .expect("valid gzip");
+        assert_eq!(data, "{\"Item\":{\"pk\":{\"S\":\"a\"}}}\n");
+
+        let files: serde_json::Value =
+            serde_json::from_slice(&objects[2].1).expect("manifest-files line");
+        assert_eq!(files["itemCount"], 1);
+        assert_eq!(files["dataFileS3Key"], keys[1]);
+        assert_eq!(files["etag"], hex::encode(Md5::digest(&objects[1].1)));
+
+        let summary: serde_json::Value =
+            serde_json::from_slice(&objects[4].1).expect("manifest-summary");
+        assert_eq!(summary["itemCount"], 1);
+        assert_eq!(summary["billedSizeBytes"], 42);
+        assert_eq!(summary["s3Prefix"], "backups");
+        assert_eq!(
+            summary["manifestFilesS3Key"],
+            format!("{base}manifest-files.json")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_should_skip_data_file_for_empty_table() {
+        let mut snapshot = snapshot(Vec::new());
+        snapshot.prefix = None;
+        let writer = RecordingWriter::default();
+        snapshot.write(&writer).await.expect("export written");
+
+        let objects = writer.objects.lock();
+        assert_eq!(objects.len(), 5);
+        assert!(objects[1].0.starts_with("AWSDynamoDB/"));
+        assert!(objects[1].0.ends_with("manifest-files.json"));
+        assert!(objects[1].1.is_empty());
+    }
+}
@@ -1,26 +1,55 @@
 //! DynamoDB handler implementation bridging HTTP to business logic.
 
-use std::{future::Future, pin::Pin, sync::Arc};
+use std::{
+    fmt::{self, Write as _},
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+};
 
 use bytes::Bytes;
+use rustack_core::RequestScope;
 use rustack_dynamodb_http::{
     body::DynamoDBResponseBody, dispatch::DynamoDBHandler, response::json_response,
 };
-use rustack_dynamodb_model::{error::DynamoDBError, operations::DynamoDBOperation};
+use rustack_dynamodb_model::{
+    attribute_value::NESTING_LIMIT_MESSAGE, error::DynamoDBError, operations::DynamoDBOperation,
+};
 
-use crate::provider::RustackDynamoDB;
+use crate::{interceptor::DynamoDBInterceptor, provider::RustackDynamoDB};
 
 /// Handler that bridges the HTTP layer to the DynamoDB provider.
-#[derive(Debug)]
 pub struct RustackDynamoDBHandler {
     provider: Arc<RustackDynamoDB>,
+    interceptors: Vec<Arc<dyn DynamoDBInterceptor>>,
+}
+
+impl fmt::Debug for RustackDynamoDBHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RustackDynamoDBHandler")
+            .field("provider", &self.provider)
+            .field("interceptors", &self.interceptors.len())
+            .finish()
+    }
 }
 
 impl RustackDynamoDBHandler {
     /// Create a new handler wrapping a provider.
     #[must_use]
     pub fn new(provider: Arc<RustackDynamoDB>) -> Self {
-        Self { provider }
+        Self {
+            provider,
+            interceptors: Vec::new(),
+        }
+    }
+
+    /// Append `interceptor` to the chain run around every operation.
+    ///
+    /// See [`DynamoDBInterceptor`] for the order hooks run in.
+    #[must_use]
+    pub fn with_interceptor(mut self, interceptor: Arc<dyn DynamoDBInterceptor>) -> Self {
+        self.interceptors.push(interceptor);
+        self
     }
 }
 
@@ -29,13 +58,103 @@ impl DynamoDBHandler for RustackDynamoDBHandler {
         &self,
         op: DynamoDBOperation,
         body: Bytes,
+        scope: RequestScope,
+        host: Option<String>,
     ) -> Pin<
         Box<
             dyn Future<Output = Result<http::Response<DynamoDBResponseBody>, DynamoDBError>> + Send,
         >,
     > {
-        let provider = Arc::clone(&self.provider);
-        Box::pin(async move { dispatch(provider.as_ref(), op, &body) })
+        let provider = self.provider.scoped(&scope);
+        let interceptors = self.interceptors.clone();
+        Box::pin(async move {
+            // Generate a request ID for responses.
+            let request_id = uuid::Uuid::new_v4().to_string();
+            let json = if interceptors.is_empty() {
+                execute(provider, op, body, host).await?
+            } else {
+                intercept(&interceptors, provider, op, body, host).await?
+            };
+            Ok(json_response(json, &request_id))
+        })
+    }
+}
+
+/// Run `op`, on the blocking thread pool if it may take a while, and return
+/// the serialized JSON response body.
+async fn execute(
+    provider: RustackDynamoDB,
+    op: DynamoDBOperation,
+    body: Bytes,
+    host: Option<String>,
+) -> Result<Vec<u8>, DynamoDBError> {
+    if !runs_blocking(op, body.len()) {
+        return dispatch(&provider, op, &body, host.as_deref());
+    }
+    tokio::task::spawn_blocking(move || dispatch(&provider, op, &body, host.as_deref()))
+        .await
+        .map_err(|e| {
+            DynamoDBError::internal_error(format!("DynamoDB operation task failed: {e}"))
+        })?
+}
+
+/// Run `op` through the interceptor chain.
+async fn intercept(
+    interceptors: &[Arc<dyn DynamoDBInterceptor>],
+    provider: RustackDynamoDB,
+    op: DynamoDBOperation,
+    body: Bytes,
+    host: Option<String>,
+) -> Result<Vec<u8>, DynamoDBError> {
+    let request: serde_json::Value = serde_json::from_slice(&body).unwrap_or_default();
+    let mut before = Ok(());
+    for interceptor in interceptors {
+        before = interceptor.before(&op, &request).await;
+        if before.is_err() {
+            break;
+        }
+    }
+    let result = match before {
+        Ok(()) => execute(provider, op, body, host).await,
+        Err(e) => Err(e),
+    };
+
+    let response: Result<serde_json::Value, &DynamoDBError> = match &result {
+        Ok(json) => Ok(serde_json::from_slice(json).unwrap_or_default()),
+        Err(e) => Err(e),
+    };
+    for interceptor in interceptors.iter().rev() {
+        interceptor
+            .after(&op, &request, response.as_ref().map_err(|e| *e))
+            .await;
+    }
+    result
+}
+
+/// Request body size above which a batch or transaction is processed on the
+/// blocking thread pool.
+const BLOCKING_BODY_BYTES: usize = 64 * 1024;
+
+/// Whether `op` may run long enough to stall the async worker it is called
+/// on, and so runs on the blocking thread pool instead.
+///
+/// Operations that walk a table always qualify; batches and transactions
+/// qualify once their request body is large enough for item validation and
+/// size accounting to matter. Everything else runs inline, avoiding the
+/// cost of a thread hand-off for single-item requests.
+fn runs_blocking(op: DynamoDBOperation, body_len: usize) -> bool {
+    match op {
+        DynamoDBOperation::Scan
+        | DynamoDBOperation::Query
+        | DynamoDBOperation::ExportTableToPointInTime
+        | DynamoDBOperation::CreateBackup
+        | DynamoDBOperation::RestoreTableFromBackup
+        | DynamoDBOperation::RestoreTableToPointInTime => true,
+        DynamoDBOperation::BatchGetItem
+        | DynamoDBOperation::BatchWriteItem
+        | DynamoDBOperation::TransactGetItems
+        | DynamoDBOperation::TransactWriteItems => body_len > BLOCKING_BODY_BYTES,
+        _ => false,
     }
 }
 
@@ -45,120 +164,173 @@ fn dispatch(
     provider: &RustackDynamoDB,
     op: DynamoDBOperation,
     body: &[u8],
-) -> Result<http::Response<DynamoDBResponseBody>, DynamoDBError> {
-    // Generate a request ID for responses.
-    let request_id = uuid::Uuid::new_v4().to_string();
-
+    host: Option<&str>,
+) -> Result<Vec<u8>, DynamoDBError> {
     match op {
         DynamoDBOperation::CreateTable => {
             let input = deserialize(body)?;
             let output = provider.handle_create_table(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::DeleteTable => {
             let input = deserialize(body)?;
             let output = provider.handle_delete_table(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::UpdateTable => {
             let input = deserialize(body)?;
             let output = provider.handle_update_table(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::DescribeTable => {
             let input = deserialize(body)?;
             let output = provider.handle_describe_table(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::ListTables => {
             let input = deserialize(body)?;
             let output = provider.handle_list_tables(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::PutItem => {
             let input = deserialize(body)?;
             let output = provider.handle_put_item(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::GetItem => {
             let input = deserialize(body)?;
             let output = provider.handle_get_item(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::UpdateItem => {
             let input = deserialize(body)?;
             let output = provider.handle_update_item(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::DeleteItem => {
             let input = deserialize(body)?;
             let output = provider.handle_delete_item(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::Query => {
             let input = deserialize(body)?;
             let output = provider.handle_query(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::Scan => {
             let input = deserialize(body)?;
             let output = provider.handle_scan(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::BatchGetItem => {
             let input = deserialize(body)?;
             let output = provider.handle_batch_get_item(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::BatchWriteItem => {
             let input = deserialize(body)?;
             let output = provider.handle_batch_write_item(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::TagResource => {
             let input = deserialize(body)?;
             let output = provider.handle_tag_resource(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::UntagResource => {
             let input = deserialize(body)?;
             let output = provider.handle_untag_resource(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::ListTagsOfResource => {
             let input = deserialize(body)?;
             let output = provider.handle_list_tags_of_resource(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::DescribeTimeToLive => {
             let input = deserialize(body)?;
             let output = provider.handle_describe_time_to_live(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::UpdateTimeToLive => {
             let input = deserialize(body)?;
             let output = provider.handle_update_time_to_live(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::TransactGetItems => {
             let input = deserialize(body)?;
             let output = provider.handle_transact_get_items(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::TransactWriteItems => {
             let input = deserialize(body)?;
             let output = provider.handle_transact_write_items(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::DescribeLimits => {
             let input = deserialize(body)?;
             let output = provider.handle_describe_limits(input)?;
-            serialize(&output, &request_id)
+            serialize(&output)
         }
         DynamoDBOperation::DescribeEndpoints => {
             let input = deserialize(body)?;
-            let output = provider.handle_describe_endpoints(input)?;
-            serialize(&output, &request_id)
+            let output = provider.handle_describe_endpoints(input, host)?;
+            serialize(&output)
+        }
+        DynamoDBOperation::ExportTableToPointInTime => {
+            let input = deserialize(body)?;
+            let output = provider.handle_export_table_to_point_in_time(input)?;
+            serialize(&output)
+        }
+        DynamoDBOperation::DescribeExport => {
+            let input = deserialize(body)?;
+            let output = provider.handle_describe_export(input)?;
+            serialize(&output)
+        }
+        DynamoDBOperation::ListExports => {
+            let input = deserialize(body)?;
+            let output = provider.handle_list_exports(input)?;
+            serialize(&output)
+        }
+        DynamoDBOperation::CreateBackup => {
+            let input = deserialize(body)?;
+            let output = provider.handle_create_backup(input)?;
+            serialize(&output)
+        }
+        DynamoDBOperation::DescribeBackup => {
+            let input = deserialize(body)?;
+            let output = provider.handle_describe_backup(input)?;
+            serialize(&output)
+        }
+        DynamoDBOperation::DeleteBackup => {
+            let input = deserialize(body)?;
+            let output = provider.handle_delete_backup(input)?;
+            serialize(&output)
+        }
+        DynamoDBOperation::ListBackups => {
+            let input = deserialize(body)?;
+            let output = provider.handle_list_backups(input)?;
+            serialize(&output)
+        }
+        DynamoDBOperation::RestoreTableFromBackup => {
+            let input = deserialize(body)?;
+            let output = provider.handle_restore_table_from_backup(input)?;
+            serialize(&output)
+        }
+        DynamoDBOperation::DescribeContinuousBackups => {
+            let input = deserialize(body)?;
+            let output = provider.handle_describe_continuous_backups(input)?;
+            serialize(&output)
+        }
+        DynamoDBOperation::UpdateContinuousBackups => {
+            let input = deserialize(body)?;
+            let output = provider.handle_update_continuous_backups(input)?;
+            serialize(&output)
+        }
+        DynamoDBOperation::RestoreTableToPointInTime => {
+            let input = deserialize(body)?;
+            let output = provider.handle_restore_table_to_point_in_time(input)?;
+            serialize(&output)
         }
     }
 }
@@ -166,27 +338,386 @@ fn dispatch(
 /// Deserialize a JSON request body into the input type.
 ///
 /// Serde errors for missing required fields (e.g., `missing field 'AttributeName'`)
-/// are mapped to `ValidationException` to match DynamoDB behaviour, while other
-/// deserialization errors remain `SerializationException`.
+/// and attribute values nested too deeply are mapped to `ValidationException`
+/// to match DynamoDB behaviour, while other deserialization errors remain
+/// `SerializationException`, naming where in the body they occurred.
 fn deserialize<T: serde::de::DeserializeOwned>(body: &[u8]) -> Result<T, DynamoDBError> {
     serde_json::from_slice(body).map_err(|e| {
         let msg = e.to_string();
-        if msg.contains("missing field") || msg.contains("unknown variant") {
+        if msg.contains(NESTING_LIMIT_MESSAGE) {
+            DynamoDBError::validation(NESTING_LIMIT_MESSAGE)
+        } else if msg.contains("missing field") || msg.contains("unknown variant") {
             DynamoDBError::validation(format!("1 validation error detected: {msg}"))
         } else {
-            DynamoDBError::serialization_exception(format!(
-                "Failed to deserialize request body: {e}"
-            ))
+            DynamoDBError::serialization_exception(serialization_message(body, &e))
         }
     })
 }
 
-/// Serialize an output type into a JSON HTTP response.
-fn serialize<T: serde::Serialize>(
-    output: &T,
-    request_id: &str,
-) -> Result<http::Response<DynamoDBResponseBody>, DynamoDBError> {
-    let json = serde_json::to_vec(output)
-        .map_err(|e| DynamoDBError::internal_error(format!("Failed to serialize response: {e}")))?;
-    Ok(json_response(json, request_id))
+/// Describe a body that failed to deserialize the way DynamoDB does, with
+/// the path and position of the offending value.
+fn serialization_message(body: &[u8], e: &serde_json::Error) -> String {
+    let msg = e.to_string();
+    let reason = if msg.starts_with("invalid type: map") {
+        "Start of structure or map found where not expected"
+    } else if msg.starts_with("invalid type: sequence") {
+        "Start of list found where not expected"
+    } else {
+        msg.rsplit_once(" at line ")
+            .map_or(msg.as_str(), |(reason, _)| reason)
+    };
+    let position = format!("line {}, column {}", e.line(), e.column());
+    match json_path_at(body, e.line(), e.column()) {
+        Some(path) => format!("{reason} at {path} ({position})"),
+        None => format!("{reason} ({position})"),
+    }
+}
+
+/// The path (`Item.tags.L[2].S`) of the member being parsed at a serde_json
+/// error position, or `None` at the top level.
+///
+/// Scans the body up to that position in a single pass without recursion,
+/// so it is cheap even for bodies that failed for being pathological.
+fn json_path_at(body: &[u8], line: usize, column: usize) -> Option<String> {
+    /// A container open at the position, with the member being parsed.
+    enum Frame {
+        Object { key: Option<String> },
+        Array { index: usize },
+    }
+
+    // serde_json lines are 1-based and columns count bytes from line start.
+    let line_start = if line <= 1 {
+        0
+    } else {
+        body.iter()
+            .enumerate()
+            .filter(|(_, b)| **b == b'\n')
+            .nth(line - 2)
+            .map_or(body.len(), |(i, _)| i + 1)
+    };
+    let end = (line_start + column).min(body.len());
+
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut expect_key = false;
+    let mut i = 0;
+    while i < end {
+        match body[i] {
+            b'"' => {
+                let start = i + 1;
+                i = start;
+                while i < body.len() && body[i] != b'"' {
+                    i += if body[i] == b'\\' { 2 } else { 1 };
+                }
+                if expect_key {
+                    if let Some(Frame::Object { key }) = stack.last_mut() {
+                        let raw = &body[start..i.min(body.len())];
+                        *key = Some(String::from_utf8_lossy(raw).into_owned());
+                    }
+                    expect_key = false;
+                }
+            }
+            b'{' => {
+                stack.push(Frame::Object { key: None });
+                expect_key = true;
+            }
+            b'[' => stack.push(Frame::Array { index: 0 }),
+            b'}' | b']' => {
+                // The member holding the container is complete.
+                stack.pop();
+                if let Some(Frame::Object { key }) = stack.last_mut() {
+                    *key = None;
+                }
+                expect_key = false;
+            }
+            b',' => match stack.last_mut() {
+                Some(Frame::Array { index }) => *index += 1,
+                Some(Frame::Object { .. }) => expect_key = true,
+                None => {}
+            },
+            _ => {}
+        }
+        i += 1;
+    }
+
+    let mut path = String::new();
+    for frame in &stack {
+        match frame {
+            Frame::Object { key: Some(key) } => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(key);
+            }
+            Frame::Object { key: None } => break,
+            Frame::Array { index } => {
+                let _ = write!(path, "[{index}]");
+            }
+        }
+    }
+    (!path.is_empty()).then_some(path)
+}
+
+/// Serialize an output type into a JSON response body.
+fn serialize<T: serde::Serialize>(output: &T) -> Result<Vec<u8>, DynamoDBError> {
+    serde_json::to_vec(output)
+        .map_err(|e| DynamoDBError::internal_error(format!("Failed to serialize response: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use parking_lot::Mutex;
+    use serde_json::json;
+
+    use super::*;
+    use crate::embedded::DynamoDBTestClient;
+
+    /// Start `requests` in order on the test runtime and return their
+    /// operations in the order they finished.
+    ///
+    /// `#[tokio::test]` runtimes have a single worker thread, so a request
+    /// executed inline finishes before a request started after it is even
+    /// polled, while one moved to the blocking thread pool lets it finish
+    /// first.
+    async fn completion_order(
+        client: &DynamoDBTestClient,
+        requests: Vec<(DynamoDBOperation, serde_json::Value)>,
+    ) -> Vec<DynamoDBOperation> {
+        let scope = client.provider().scope().clone();
+        let handler = Arc::new(RustackDynamoDBHandler::new(Arc::new(
+            client.provider().clone(),
+        )));
+        let finished = Arc::new(Mutex::new(Vec::new()));
+
+        let tasks: Vec<_> = requests
+            .into_iter()
+            .map(|(op, body)| {
+                let handler = Arc::clone(&handler);
+                let finished = Arc::clone(&finished);
+                let scope = scope.clone();
+                tokio::spawn(async move {
+                    handler
+                        .handle_operation(op, Bytes::from(body.to_string()), scope, None)
+                        .await
+                        .expect("operation");
+                    finished.lock().push(op);
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        Arc::try_unwrap(finished).unwrap().into_inner()
+    }
+
+    /// A `BatchWriteItem` body of exactly `len` bytes putting one item into
+    /// `table`.
+    fn batch_write_body(table: &str, len: usize) -> serde_json::Value {
+        let body = |padding: usize| {
+            json!({ "RequestItems": { table: [{ "PutRequest": { "Item": {
+                "id": { "S": "batch" },
+                "payload": { "S": "x".repeat(padding) },
+            } } }] } })
+        };
+        let body = body(len - body(0).to_string().len());
+        assert_eq!(body.to_string().len(), len);
+        body
+    }
+
+    #[tokio::test]
+    async fn test_should_serve_get_item_while_a_large_scan_runs() {
+        let client = DynamoDBTestClient::new();
+        client.create_simple_table("big", "id").unwrap();
+        for n in 0..5000 {
+            client
+                .put(
+                    "big",
+                    json!({ "id": format!("item-{n}"), "payload": "x".repeat(200) }),
+                )
+                .unwrap();
+        }
+
+        let order = completion_order(
+            &client,
+            vec![
+                (DynamoDBOperation::Scan, json!({ "TableName": "big" })),
+                (
+                    DynamoDBOperation::GetItem,
+                    json!({ "TableName": "big", "Key": { "id": { "S": "item-1" } } }),
+                ),
+            ],
+        )
+        .await;
+        assert_eq!(
+            order,
+            vec![DynamoDBOperation::GetItem, DynamoDBOperation::Scan]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_should_offload_batches_only_above_the_body_threshold() {
+        let client = DynamoDBTestClient::new();
+        client.create_simple_table("batches", "id").unwrap();
+        let get = (
+            DynamoDBOperation::GetItem,
+            json!({ "TableName": "batches", "Key": { "id": { "S": "probe" } } }),
+        );
+
+        for (len, offloaded) in [
+            (BLOCKING_BODY_BYTES - 1, false),
+            (BLOCKING_BODY_BYTES, false),
+            (BLOCKING_BODY_BYTES + 1, true),
+        ] {
+            let batch = (
+                DynamoDBOperation::BatchWriteItem,
+                batch_write_body("batches", len),
+            );
+            let order = completion_order(&client, vec![batch, get.clone()]).await;
+            let expected = if offloaded {
+                vec![
+                    DynamoDBOperation::GetItem,
+                    DynamoDBOperation::BatchWriteItem,
+                ]
+            } else {
+                vec![
+                    DynamoDBOperation::BatchWriteItem,
+                    DynamoDBOperation::GetItem,
+                ]
+            };
+            assert_eq!(order, expected, "body of {len} bytes");
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_should_serve_get_items_while_scans_keep_running() {
+        let client = DynamoDBTestClient::new();
+        client.create_simple_table("big", "id").unwrap();
+        for n in 0..2000 {
+            client
+                .put(
+                    "big",
+                    json!({ "id": format!("item-{n}"), "payload": "x".repeat(200) }),
+                )
+                .unwrap();
+        }
+        let scope = client.provider().scope().clone();
+        let handler = Arc::new(RustackDynamoDBHandler::new(Arc::new(
+            client.provider().clone(),
+        )));
+        let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let scan = Bytes::from(json!({ "TableName": "big" }).to_string());
+
+        // More scanners than async workers, each scanning until stopped.
+        let scanners: Vec<_> = (0..4)
+            .map(|_| {
+                let (handler, scope) = (Arc::clone(&handler), scope.clone());
+                let (stop, scan) = (Arc::clone(&stop), scan.clone());
+                tokio::spawn(async move {
+                    while !stop.load(std::sync::atomic::Ordering::Relaxed) {
+                        handler
+                            .handle_operation(
+                                DynamoDBOperation::Scan,
+                                scan.clone(),
+                                scope.clone(),
+                                None,
+                            )
+                            .await
+                            .expect("scan");
+                        tokio::task::yield_now().await;
+                    }
+                })
+            })
+            .collect();
+
+        let get = Bytes::from(
+            json!({ "TableName": "big", "Key": { "id": { "S": "item-1" } } }).to_string(),
+        );
+        for _ in 0..50 {
+            let (handler, scope, get) = (Arc::clone(&handler), scope.clone(), get.clone());
+            let response = tokio::spawn(async move {
+                handler
+                    .handle_operation(DynamoDBOperation::GetItem, get, scope, None)
+                    .await
+                    .expect("get")
+            })
+            .await
+            .unwrap();
+            assert_eq!(response.status(), http::StatusCode::OK);
+        }
+        stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        for scanner in scanners {
+            scanner.await.unwrap();
+        }
+    }
+
+    fn deserialize_error(body: &str) -> DynamoDBError {
+        deserialize::<rustack_dynamodb_model::input::PutItemInput>(body.as_bytes())
+            .err()
+            .unwrap_or_else(|| panic!("{body} should not deserialize"))
+    }
+
+    #[test]
+    fn test_should_locate_serialization_errors_in_the_body() {
+        let err = deserialize_error(r#"{"TableName":"t","Item":{"pk":{"S":{"x":1}}}}"#);
+        assert_eq!(
+            err.code,
+            rustack_dynamodb_model::error::DynamoDBErrorCode::SerializationException
+        );
+        assert_eq!(
+            err.message,
+            "Start of structure or map found where not expected at Item.pk.S (line 1, column 35)"
+        );
+
+        let err = deserialize_error(
+            "{\"TableName\": \"t\",\n \"Item\": {\"tags\": {\"L\": [{\"S\": \"a\"}, {\"N\": \
+             [1]}]}}}",
+        );
+        assert!(
+            err.message
+                .starts_with("Start of list found where not expected at Item.tags.L[1].N (line 2"),
+            "{}",
+            err.message
+        );
+
+        let err = deserialize_error(r#"{"TableName":"t","Item":{"pk":{"S":"a"}"#);
+        assert!(err.message.contains("EOF while parsing"), "{}", err.message);
+        assert!(err.message.contains("at Item (line 1"), "{}", err.message);
+    }
+
+    #[test]
+    fn test_should_reject_pathological_bodies_quickly() {
+        let started = std::time::Instant::now();
+
+        let mut item = r#"{"S":"leaf"}"#.to_owned();
+        for _ in 0..5000 {
+            item = format!(r#"{{"M":{{"a":{item}}}}}"#);
+        }
+        let err = deserialize_error(&format!(r#"{{"TableName":"t","Item":{{"pk":{item}}}}}"#));
+        assert_eq!(
+            err.code,
+            rustack_dynamodb_model::error::DynamoDBErrorCode::ValidationException
+        );
+        assert_eq!(err.message, NESTING_LIMIT_MESSAGE);
+
+        let arrays = format!(
+            r#"{{"TableName":"t","Extra":{}1{}}}"#,
+            "[".repeat(100_000),
+            "]".repeat(100_000)
+        );
+        deserialize_error(&arrays);
+        deserialize_error(&"{\"TableName\":".repeat(100_000));
+        deserialize_error(&"\"\\".repeat(100_000));
+
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_should_offload_only_table_walks_and_large_batches() {
+        assert!(runs_blocking(DynamoDBOperation::Scan, 0));
+        assert!(!runs_blocking(DynamoDBOperation::GetItem, 1 << 20));
+        assert!(!runs_blocking(DynamoDBOperation::BatchWriteItem, 1024));
+        assert!(runs_blocking(
+            DynamoDBOperation::BatchWriteItem,
+            BLOCKING_BODY_BYTES + 1
+        ));
+    }
 }
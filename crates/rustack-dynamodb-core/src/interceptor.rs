@@ -0,0 +1,385 @@
+//! Operation-level interceptors for the DynamoDB handler.
+//!
+//! Interceptors run around every operation the
+//! [`RustackDynamoDBHandler`](crate::handler::RustackDynamoDBHandler) serves.
+//! They see the operation, the parsed JSON request, and the JSON response or
+//! error, which lets tests inject faults, record fixtures, and assert
+//! invariants without forking the provider.
+//!
+//! Three interceptors are built in:
+//!
+//! - [`LatencyInjector`] delays operations before they run.
+//! - [`RecordingInterceptor`] appends each request and its outcome to a JSONL file.
+//! - [`ErrorInjector`] fails selected calls of one operation with a chosen error.
+
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use rustack_dynamodb_model::{
+    error::{DynamoDBError, DynamoDBErrorCode},
+    operations::DynamoDBOperation,
+};
+use serde_json::{Value, json};
+use tokio::{io::AsyncWriteExt, sync::Mutex};
+use tracing::warn;
+
+/// Hooks run around each DynamoDB operation.
+///
+/// `before` hooks run in registration order before the operation executes;
+/// the first error short-circuits the chain and becomes the operation's
+/// result. `after` hooks then run in reverse registration order and observe
+/// that result, whether it came from the provider or from a `before` hook.
+///
+/// Uses `async-trait` because interceptors are held as
+/// `Arc<dyn DynamoDBInterceptor>`.
+#[async_trait]
+pub trait DynamoDBInterceptor: Send + Sync + 'static {
+    /// Called before `op` runs with its parsed request body.
+    ///
+    /// A body that is not valid JSON is passed as [`Value::Null`].
+    async fn before(&self, _op: &DynamoDBOperation, _body: &Value) -> Result<(), DynamoDBError> {
+        Ok(())
+    }
+
+    /// Called after `op` completes with its request body and the JSON
+    /// response or error.
+    async fn after(
+        &self,
+        _op: &DynamoDBOperation,
+        _body: &Value,
+        _result: Result<&Value, &DynamoDBError>,
+    ) {
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Latency injection
+// ---------------------------------------------------------------------------
+
+/// Delays operations before they run.
+#[derive(Debug, Default)]
+pub struct LatencyInjector {
+    default: Duration,
+    per_operation: HashMap<DynamoDBOperation, Duration>,
+}
+
+impl LatencyInjector {
+    /// Delay every operation by `default`.
+    #[must_use]
+    pub fn new(default: Duration) -> Self {
+        Self {
+            default,
+            per_operation: HashMap::new(),
+        }
+    }
+
+    /// Delay `op` by `delay` instead of the default.
+    #[must_use]
+    pub fn with_operation(mut self, op: DynamoDBOperation, delay: Duration) -> Self {
+        self.per_operation.insert(op, delay);
+        self
+    }
+
+    /// The delay applied to `op`.
+    #[must_use]
+    pub fn delay_for(&self, op: DynamoDBOperation) -> Duration {
+        self.per_operation.get(&op).copied().unwrap_or(self.default)
+    }
+}
+
+#[async_trait]
+impl DynamoDBInterceptor for LatencyInjector {
+    async fn before(&self, op: &DynamoDBOperation, _body: &Value) -> Result<(), DynamoDBError> {
+        let delay = self.delay_for(*op);
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Request/response recording
+// ---------------------------------------------------------------------------
+
+/// Appends each operation to a JSONL file.
+///
+/// Every line is one JSON object with the `operation` name, the `request`
+/// body, and either the `response` body or an `error` holding the `__type`
+/// and `message` the client received. Write failures are logged and never
+/// fail the operation.
+#[derive(Debug)]
+pub struct RecordingInterceptor {
+    path: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl RecordingInterceptor {
+    /// Record operations to `path`, creating it if needed and appending
+    /// otherwise.
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    async fn append(&self, line: &[u8]) -> std::io::Result<()> {
+        let _guard = self.write_lock.lock().await;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(line).await?;
+        file.flush().await
+    }
+}
+
+#[async_trait]
+impl DynamoDBInterceptor for RecordingInterceptor {
+    async fn after(
+        &self,
+        op: &DynamoDBOperation,
+        body: &Value,
+        result: Result<&Value, &DynamoDBError>,
+    ) {
+        let record = match result {
+            Ok(response) => json!({
+                "operation": op.as_str(),
+                "request": body,
+                "response": response,
+            }),
+            Err(err) => json!({
+                "operation": op.as_str(),
+                "request": body,
+                "error": { "__type": err.error_type(), "message": err.message },
+            }),
+        };
+        let mut line = record.to_string().into_bytes();
+        line.push(b'\n');
+        if let Err(e) = self.append(&line).await {
+            warn!(path = %self.path.display(), error = %e, "failed to record DynamoDB operation");
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Error injection
+// ---------------------------------------------------------------------------
+
+/// Fails calls of one operation with a chosen error.
+///
+/// Calls of the operation are counted as they arrive. By default every call
+/// fails; [`skip`](Self::skip) lets the first calls through and
+/// [`times`](Self::times) limits how many fail after that.
+#[derive(Debug)]
+pub struct ErrorInjector {
+    operation: DynamoDBOperation,
+    code: DynamoDBErrorCode,
+    message: String,
+    skip: usize,
+    times: usize,
+    matched: AtomicUsize,
+}
+
+impl ErrorInjector {
+    /// Fail every call of `operation` with `code` and `message`.
+    #[must_use]
+    pub fn new(
+        operation: DynamoDBOperation,
+        code: DynamoDBErrorCode,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            operation,
+            code,
+            message: message.into(),
+            skip: 0,
+            times: usize::MAX,
+            matched: AtomicUsize::new(0),
+        }
+    }
+
+    /// Let the first `calls` calls of the operation through.
+    #[must_use]
+    pub fn skip(mut self, calls: usize) -> Self {
+        self.skip = calls;
+        self
+    }
+
+    /// Fail at most `calls` calls of the operation.
+    #[must_use]
+    pub fn times(mut self, calls: usize) -> Self {
+        self.times = calls;
+        self
+    }
+
+    /// How many calls of the operation have been seen so far.
+    #[must_use]
+    pub fn matched(&self) -> usize {
+        self.matched.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl DynamoDBInterceptor for ErrorInjector {
+    async fn before(&self, op: &DynamoDBOperation, _body: &Value) -> Result<(), DynamoDBError> {
+        if *op != self.operation {
+            return Ok(());
+        }
+        let call = self.matched.fetch_add(1, Ordering::Relaxed);
+        let injected = call
+            .checked_sub(self.skip)
+            .is_some_and(|failed| failed < self.times);
+        if injected {
+            return Err(DynamoDBError::with_message(self.code, self.message.clone()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use bytes::Bytes;
+    use rustack_dynamodb_http::dispatch::DynamoDBHandler;
+
+    use super::*;
+    use crate::{embedded::DynamoDBTestClient, handler::RustackDynamoDBHandler};
+
+    fn handler_with(
+        client: &DynamoDBTestClient,
+        interceptors: Vec<Arc<dyn DynamoDBInterceptor>>,
+    ) -> RustackDynamoDBHandler {
+        interceptors.into_iter().fold(
+            RustackDynamoDBHandler::new(Arc::new(client.provider().clone())),
+            RustackDynamoDBHandler::with_interceptor,
+        )
+    }
+
+    async fn call(
+        client: &DynamoDBTestClient,
+        handler: &RustackDynamoDBHandler,
+        op: DynamoDBOperation,
+        body: &Value,
+    ) -> Result<(), DynamoDBError> {
+        let scope = client.provider().scope().clone();
+        handler
+            .handle_operation(op, Bytes::from(body.to_string()), scope, None)
+            .await
+            .map(drop)
+    }
+
+    #[tokio::test]
+    async fn test_should_inject_errors_after_skipped_calls() {
+        let client = DynamoDBTestClient::new();
+        client.create_simple_table("tbl", "id").unwrap();
+        let injector = Arc::new(
+            ErrorInjector::new(
+                DynamoDBOperation::PutItem,
+                DynamoDBErrorCode::ProvisionedThroughputExceededException,
+                "injected",
+            )
+            .skip(1)
+            .times(2),
+        );
+        let handler = handler_with(&client, vec![injector.clone()]);
+        let put = json!({ "TableName": "tbl", "Item": { "id": { "S": "a" } } });
+        let get = json!({ "TableName": "tbl", "Key": { "id": { "S": "a" } } });
+
+        let mut outcomes = Vec::new();
+        for _ in 0..4 {
+            outcomes.push(
+                call(&client, &handler, DynamoDBOperation::PutItem, &put)
+                    .await
+                    .map_err(|e| e.code),
+            );
+        }
+        assert_eq!(
+            outcomes,
+            [
+                Ok(()),
+                Err(DynamoDBErrorCode::ProvisionedThroughputExceededException),
+                Err(DynamoDBErrorCode::ProvisionedThroughputExceededException),
+                Ok(()),
+            ]
+        );
+        call(&client, &handler, DynamoDBOperation::GetItem, &get)
+            .await
+            .expect("other operations pass through");
+        assert_eq!(injector.matched(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_should_record_requests_and_outcomes_as_jsonl() {
+        let client = DynamoDBTestClient::new();
+        client.create_simple_table("tbl", "id").unwrap();
+        let path =
+            std::env::temp_dir().join(format!("rustack-ddb-record-{}.jsonl", uuid::Uuid::new_v4()));
+        let handler = handler_with(
+            &client,
+            vec![
+                Arc::new(RecordingInterceptor::new(&path)),
+                Arc::new(ErrorInjector::new(
+                    DynamoDBOperation::DeleteItem,
+                    DynamoDBErrorCode::InternalServerError,
+                    "boom",
+                )),
+            ],
+        );
+        let put = json!({ "TableName": "tbl", "Item": { "id": { "S": "a" } } });
+        let delete = json!({ "TableName": "tbl", "Key": { "id": { "S": "a" } } });
+        call(&client, &handler, DynamoDBOperation::PutItem, &put)
+            .await
+            .unwrap();
+        call(&client, &handler, DynamoDBOperation::DeleteItem, &delete)
+            .await
+            .unwrap_err();
+
+        let contents = tokio::fs::read_to_string(&path).await.unwrap();
+        tokio::fs::remove_file(&path).await.unwrap();
+        let lines: Vec<Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["operation"], "PutItem");
+        assert_eq!(lines[0]["request"], put);
+        assert_eq!(lines[0]["response"], json!({}));
+        assert_eq!(lines[1]["operation"], "DeleteItem");
+        assert_eq!(lines[1]["error"]["message"], "boom");
+        assert!(lines[1].get("response").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_should_delay_operations_by_configured_latency() {
+        let client = DynamoDBTestClient::new();
+        client.create_simple_table("tbl", "id").unwrap();
+        let latency = LatencyInjector::new(Duration::ZERO)
+            .with_operation(DynamoDBOperation::DescribeTable, Duration::from_millis(50));
+        assert_eq!(
+            latency.delay_for(DynamoDBOperation::GetItem),
+            Duration::ZERO
+        );
+        let handler = handler_with(&client, vec![Arc::new(latency)]);
+
+        let started = std::time::Instant::now();
+        call(
+            &client,
+            &handler,
+            DynamoDBOperation::DescribeTable,
+            &json!({ "TableName": "tbl" }),
+        )
+        .await
+        .unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(50));
+    }
+}
@@ -1,11 +1,34 @@
 //! DynamoDB core business logic for Rustack.
+//!
+//! Everything but the [`expression`] re-export sits behind the default-on
+//! `engine` feature, so with `--no-default-features` the crate builds for
+//! `wasm32-unknown-unknown`.
 #![allow(missing_docs, clippy::doc_markdown, clippy::module_name_repetitions)]
 
+#[cfg(feature = "engine")]
+pub mod backup;
+#[cfg(feature = "engine")]
 pub mod config;
+#[cfg(feature = "engine")]
+pub mod embedded;
+#[cfg(feature = "engine")]
 pub mod error;
-pub mod expression;
+#[cfg(feature = "engine")]
+pub mod export;
+#[cfg(feature = "engine")]
 pub mod handler;
+#[cfg(feature = "engine")]
+pub mod interceptor;
+#[cfg(feature = "engine")]
 pub mod provider;
+#[cfg(feature = "engine")]
 pub mod state;
+#[cfg(feature = "engine")]
 pub mod storage;
+#[cfg(feature = "engine")]
 pub mod stream;
+
+/// DynamoDB expression parsing and evaluation, from
+/// `rustack-dynamodb-expression`.
+pub use rustack_dynamodb_expression as expression;
+pub use rustack_dynamodb_expression::number;
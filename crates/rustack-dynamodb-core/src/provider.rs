@@ -1,56 +1,80 @@
 //! DynamoDB provider implementing all MVP operations.
+//!
+//! Tables are partitioned by (account, region) namespace. A
+//! [`RustackDynamoDB`] handle operates in one namespace;
+//! [`RustackDynamoDB::scoped`] returns a handle for another one, sharing the
+//! same underlying stores.
 
 use std::{
     collections::{HashMap, HashSet},
     sync::Arc,
 };
 
+use rustack_core::{AccountId, AccountRegionStore, AwsRegion, RequestScope};
 use rustack_dynamodb_model::{
     AttributeValue,
-    error::DynamoDBError,
+    attribute_value::{MAX_NESTING_DEPTH, NESTING_LIMIT_MESSAGE},
+    error::{DynamoDBError, DynamoDBErrorCode},
     input::{
-        BatchGetItemInput, BatchWriteItemInput, CreateTableInput, DeleteItemInput,
-        DeleteTableInput, DescribeEndpointsInput, DescribeLimitsInput, DescribeTableInput,
-        DescribeTimeToLiveInput, GetItemInput, ListTablesInput, ListTagsOfResourceInput,
-        PutItemInput, QueryInput, ScanInput, TagResourceInput, TransactGetItemsInput,
-        TransactWriteItemsInput, UntagResourceInput, UpdateItemInput, UpdateTableInput,
-        UpdateTimeToLiveInput,
+        BatchGetItemInput, BatchWriteItemInput, CreateBackupInput, CreateTableInput,
+        DeleteBackupInput, DeleteItemInput, DeleteTableInput, DescribeBackupInput,
+        DescribeContinuousBackupsInput, DescribeEndpointsInput, DescribeExportInput,
+        DescribeLimitsInput, DescribeTableInput, DescribeTimeToLiveInput,
+        ExportTableToPointInTimeInput, GetItemInput, ListBackupsInput, ListExportsInput,
+        ListTablesInput, ListTagsOfResourceInput, PutItemInput, QueryInput,
+        RestoreTableFromBackupInput, RestoreTableToPointInTimeInput, ScanInput, TagResourceInput,
+        TransactGetItemsInput, TransactWriteItemsInput, UntagResourceInput,
+        UpdateContinuousBackupsInput, UpdateItemInput, UpdateTableInput, UpdateTimeToLiveInput,
     },
     output::{
-        BatchGetItemOutput, BatchWriteItemOutput, CreateTableOutput, DeleteItemOutput,
-        DeleteTableOutput, DescribeEndpointsOutput, DescribeLimitsOutput, DescribeTableOutput,
-        DescribeTimeToLiveOutput, Endpoint, GetItemOutput, ListTablesOutput,
-        ListTagsOfResourceOutput, PutItemOutput, QueryOutput, ScanOutput, TagResourceOutput,
-        TransactGetItemsOutput, TransactWriteItemsOutput, UntagResourceOutput, UpdateItemOutput,
-        UpdateTableOutput, UpdateTimeToLiveOutput,
+        BatchGetItemOutput, BatchWriteItemOutput, CreateBackupOutput, CreateTableOutput,
+        DeleteBackupOutput, DeleteItemOutput, DeleteTableOutput, DescribeBackupOutput,
+        DescribeContinuousBackupsOutput, DescribeEndpointsOutput, DescribeExportOutput,
+        DescribeLimitsOutput, DescribeTableOutput, DescribeTimeToLiveOutput, Endpoint,
+        ExportTableToPointInTimeOutput, GetItemOutput, ListBackupsOutput, ListExportsOutput,
+        ListTablesOutput, ListTagsOfResourceOutput, PutItemOutput, QueryOutput,
+        RestoreTableFromBackupOutput, RestoreTableToPointInTimeOutput, ScanOutput,
+        TagResourceOutput, TransactGetItemsOutput, TransactWriteItemsOutput, UntagResourceOutput,
+        UpdateContinuousBackupsOutput, UpdateItemOutput, UpdateTableOutput, UpdateTimeToLiveOutput,
     },
     types::{
-        AttributeAction, AttributeDefinition, AttributeValueUpdate, BillingMode,
-        CancellationReason, ComparisonOperator, Condition, ConditionalOperator,
-        ExpectedAttributeValue, ItemResponse, KeyType, ReturnValue, ScalarAttributeType, Select,
-        TableStatus, TimeToLiveDescription,
+        AttributeAction, AttributeDefinition, AttributeValueUpdate, BackupStatus, BackupType,
+        BillingMode, CancellationReason, ComparisonOperator, Condition, ConditionalOperator,
+        ExpectedAttributeValue, ExportDescription, ExportFormat, ExportStatus, ExportSummary,
+        ExportType, GlobalSecondaryIndex, ItemResponse, KeyType, LocalSecondaryIndex,
+        ProvisionedThroughput, ReturnConsumedCapacity, ReturnItemCollectionMetrics, ReturnValue,
+        SSESpecification, ScalarAttributeType, Select, TableStatus, TimeToLiveDescription,
+        WriteRequest,
     },
 };
 
 use crate::{
-    config::DynamoDBConfig,
+    backup::TableBackup,
+    config::{DynamoDBBehavior, DynamoDBConfig},
     error::{expression_error_to_dynamodb, storage_error_to_dynamodb},
+    export::{ExportSnapshot, ExportWriter, NoopExportWriter},
     expression::{
-        AttributePath, EvalContext, PathElement, UpdateExpr, collect_names_from_expr,
-        collect_names_from_projection, collect_names_from_update, collect_paths_from_expr,
-        collect_values_from_expr, collect_values_from_update, parse_condition, parse_projection,
-        parse_update,
+        AttributePath, EvalContext, Expr, PathElement, UpdateExpr,
+        ast::{PathClash, ResolvedPathElement, find_path_clash, resolve_path_elements},
+        check_projection_paths, collect_names_from_expr, collect_names_from_projection,
+        collect_names_from_update, collect_paths_from_expr, collect_values_from_expr,
+        collect_values_from_update, limits, parse_condition, parse_projection, parse_update,
+        reserved,
     },
-    state::{DynamoDBServiceState, DynamoDBTable},
+    state::{DynamoDBServiceState, DynamoDBTable, PointInTimeRecovery, epoch_seconds},
     storage::{
-        KeyAttribute, KeySchema, PrimaryKey, SortKeyCondition, SortableAttributeValue,
-        TableStorage, calculate_item_size, extract_primary_key, partition_key_segment,
+        ItemWrite, KeyAttribute, KeySchema, MAX_RESPONSE_BYTES, PageLimit, PrimaryKey,
+        SortKeyCondition, SortableAttributeValue, TableStorage, calculate_item_size,
+        extract_primary_key, partition_key_segment, partition_order,
     },
 };
 
 /// Maximum item size in bytes (400 KB).
 const MAX_ITEM_SIZE_BYTES: u64 = 400 * 1024;
 
+/// Maximum total size of the items in a `BatchWriteItem` request (16 MB).
+const MAX_BATCH_WRITE_BYTES: u64 = 16 * 1024 * 1024;
+
 /// Maximum number of significant digits allowed for DynamoDB numbers.
 const MAX_SIGNIFICANT_DIGITS: usize = 38;
 
@@ -219,6 +243,17 @@ fn validate_numbers_in_value(val: &AttributeValue) -> Result<(), DynamoDBError>
     }
 }
 
+/// Reject items holding `L` or `M` values nested deeper than DynamoDB allows.
+fn validate_item_nesting(item: &HashMap<String, AttributeValue>) -> Result<(), DynamoDBError> {
+    if item
+        .values()
+        .any(|val| val.nesting_depth() > MAX_NESTING_DEPTH)
+    {
+        return Err(DynamoDBError::validation(NESTING_LIMIT_MESSAGE));
+    }
+    Ok(())
+}
+
 /// Validate a table name against DynamoDB rules: 3-255 characters, `[a-zA-Z0-9._-]+`.
 fn validate_table_name(name: &str) -> Result<(), DynamoDBError> {
     if name.len() < 3 || name.len() > 255 {
@@ -331,10 +366,16 @@ fn validate_no_duplicate_attributes_to_get(attrs: &[String]) -> Result<(), Dynam
     Ok(())
 }
 
-/// Wrap an expression error with ProjectionExpression context.
+/// Wrap an expression error with ProjectionExpression context, unless the
+/// parser already phrased it as a ProjectionExpression error.
 #[allow(clippy::needless_pass_by_value)]
 fn projection_error_to_dynamodb(e: crate::expression::ExpressionError) -> DynamoDBError {
-    DynamoDBError::validation(format!("Invalid ProjectionExpression: {e}"))
+    let message = e.to_string();
+    if message.starts_with("Invalid ProjectionExpression:") {
+        DynamoDBError::validation(message)
+    } else {
+        DynamoDBError::validation(format!("Invalid ProjectionExpression: {message}"))
+    }
 }
 
 /// Format key attribute names from a key map for error messages.
@@ -389,6 +430,8 @@ fn validate_select(
                     )));
                 }
             }
+            // Already rejected by `validate_enum_value`.
+            Select::Unknown(_) => {}
         }
     }
     Ok(())
@@ -454,36 +497,119 @@ fn validate_parallel_scan(
     }
 }
 
+/// Reject a table-level change while the table is being created or deleted.
+fn require_not_in_transition(table: &DynamoDBTable) -> Result<(), DynamoDBError> {
+    match table.status() {
+        TableStatus::Creating | TableStatus::Deleting => Err(resource_in_transition(table)),
+        _ => Ok(()),
+    }
+}
+
+/// The `ResourceInUseException` DynamoDB returns for a change to a table that
+/// is being created or deleted.
+fn resource_in_transition(table: &DynamoDBTable) -> DynamoDBError {
+    let phase = if table.status() == TableStatus::Creating {
+        "created"
+    } else {
+        "deleted"
+    };
+    DynamoDBError::resource_in_use(format!(
+        "Attempt to change a resource which is still in use: Table is being {phase}: {}",
+        table.name
+    ))
+}
+
 /// Main DynamoDB provider implementing all operations.
+#[derive(Clone)]
 pub struct RustackDynamoDB {
-    /// Service state owning all tables.
+    /// Service state of every (account, region) namespace.
+    states: Arc<AccountRegionStore<DynamoDBServiceState>>,
+    /// The namespace this handle operates in.
+    scope: RequestScope,
+    /// Service state owning the tables of `scope`.
     pub state: Arc<DynamoDBServiceState>,
-    /// Configuration.
-    pub config: Arc<DynamoDBConfig>,
+    /// Configuration, whose behavior toggles can change while the provider
+    /// runs.
+    config: Arc<parking_lot::RwLock<DynamoDBConfig>>,
     /// Stream emitter for change data capture.
     emitter: Arc<dyn crate::stream::StreamEmitter>,
     /// Stream lifecycle manager.
     lifecycle: Arc<dyn crate::stream::StreamLifecycle>,
+    /// Writer that stores table exports in S3.
+    export_writer: Arc<dyn ExportWriter>,
 }
 
 impl std::fmt::Debug for RustackDynamoDB {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RustackDynamoDB")
+            .field("scope", &self.scope)
             .field("state", &self.state)
-            .field("config", &self.config)
+            .field("config", &*self.config.read())
             .finish_non_exhaustive()
     }
 }
 
 impl RustackDynamoDB {
-    /// Create a new DynamoDB provider.
+    /// Create a new DynamoDB provider operating in the default account and
+    /// the configured default region.
     #[must_use]
     pub fn new(config: DynamoDBConfig) -> Self {
+        let states = Arc::new(AccountRegionStore::new());
+        let scope = RequestScope::new(
+            AccountId::default(),
+            AwsRegion::new(config.default_region.clone()),
+        );
         Self {
-            state: Arc::new(DynamoDBServiceState::new()),
-            config: Arc::new(config),
+            state: states.get_or_create(&scope.account, &scope.region),
+            states,
+            scope,
+            config: Arc::new(parking_lot::RwLock::new(config)),
             emitter: Arc::new(crate::stream::NoopStreamEmitter),
             lifecycle: Arc::new(crate::stream::NoopStreamLifecycle),
+            export_writer: Arc::new(NoopExportWriter),
+        }
+    }
+
+    /// The current configuration.
+    #[must_use]
+    pub fn config(&self) -> DynamoDBConfig {
+        self.config.read().clone()
+    }
+
+    /// Change behavior toggles while the provider runs, returning the new
+    /// configuration. Requests already in progress may still see the old
+    /// values.
+    ///
+    /// `patch` maps toggles to new values, keyed as in
+    /// [`DynamoDBConfig::to_json`].
+    ///
+    /// # Errors
+    ///
+    /// Returns why `patch` is invalid, including when it names a setting
+    /// fixed at startup, leaving the configuration unchanged.
+    pub fn update_config(
+        &self,
+        patch: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<DynamoDBConfig, String> {
+        let mut config = self.config.write();
+        config.apply_patch(patch)?;
+        tracing::info!(behavior = ?config.behavior, "updated DynamoDB configuration");
+        Ok(config.clone())
+    }
+
+    /// The current behavior toggles.
+    fn behavior(&self) -> DynamoDBBehavior {
+        self.config.read().behavior
+    }
+
+    /// The page limit of a Query or Scan with `Limit` set to `limit`.
+    fn page_limit(&self, limit: Option<i32>) -> PageLimit {
+        PageLimit {
+            items: limit.map(|l| usize::try_from(l.max(0)).unwrap_or(usize::MAX)),
+            bytes: self
+                .behavior()
+                .response_size_limit
+                .then_some(MAX_RESPONSE_BYTES),
         }
     }
 
@@ -502,9 +628,74 @@ impl RustackDynamoDB {
         self.lifecycle = lifecycle;
     }
 
-    /// Reset all state (for testing).
+    /// Return a handle operating in `scope`, sharing all underlying state.
+    #[must_use]
+    pub fn scoped(&self, scope: &RequestScope) -> Self {
+        if *scope == self.scope {
+            return self.clone();
+        }
+        Self {
+            state: self.states.get_or_create(&scope.account, &scope.region),
+            scope: scope.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns the (account, region) namespace this handle operates in.
+    #[must_use]
+    pub fn scope(&self) -> &RequestScope {
+        &self.scope
+    }
+
+    /// Reset all state in every namespace (for testing).
     pub fn reset(&self) {
-        self.state.reset();
+        for state in self.states.values() {
+            state.reset();
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Expression parsing
+// ---------------------------------------------------------------------------
+
+impl RustackDynamoDB {
+    /// Parse a condition, filter, or key-condition expression, rejecting
+    /// unescaped reserved words unless `strict_reserved_words` is off.
+    ///
+    /// `kind` is the request parameter the expression came from.
+    fn parse_condition_expr(&self, kind: &str, input: &str) -> Result<Expr, DynamoDBError> {
+        let expr = parse_condition(input).map_err(expression_error_to_dynamodb)?;
+        if self.behavior().strict_reserved_words {
+            reserved::check_condition(kind, &expr).map_err(expression_error_to_dynamodb)?;
+        }
+        Ok(expr)
+    }
+
+    /// Parse an update expression, rejecting unescaped reserved words unless
+    /// `strict_reserved_words` is off.
+    fn parse_update_expr(&self, input: &str) -> Result<UpdateExpr, DynamoDBError> {
+        let update = parse_update(input).map_err(expression_error_to_dynamodb)?;
+        if self.behavior().strict_reserved_words {
+            reserved::check_update(&update).map_err(expression_error_to_dynamodb)?;
+        }
+        Ok(update)
+    }
+
+    /// Parse a projection expression, rejecting paths that overlap once
+    /// `names` are resolved, and unescaped reserved words unless
+    /// `strict_reserved_words` is off.
+    fn parse_projection_expr(
+        &self,
+        input: &str,
+        names: &HashMap<String, String>,
+    ) -> Result<Vec<AttributePath>, DynamoDBError> {
+        let paths = parse_projection(input).map_err(projection_error_to_dynamodb)?;
+        check_projection_paths(&paths, names).map_err(projection_error_to_dynamodb)?;
+        if self.behavior().strict_reserved_words {
+            reserved::check_projection(&paths).map_err(expression_error_to_dynamodb)?;
+        }
+        Ok(paths)
     }
 }
 
@@ -560,13 +751,13 @@ impl RustackDynamoDB {
 
         let table_name = input.table_name.clone();
         let arn = format!(
-            "arn:aws:dynamodb:{}:000000000000:table/{}",
-            self.config.default_region, table_name,
+            "arn:aws:dynamodb:{}:{}:table/{}",
+            self.scope.region, self.scope.account, table_name,
         );
 
+        let created_at = chrono::Utc::now();
         let table = DynamoDBTable {
             name: table_name,
-            status: TableStatus::Active,
             key_schema_elements: input.key_schema,
             key_schema,
             attribute_definitions: input.attribute_definitions,
@@ -580,7 +771,10 @@ impl RustackDynamoDB {
             ttl: parking_lot::RwLock::new(None),
             arn,
             table_id: uuid::Uuid::new_v4().to_string(),
-            created_at: chrono::Utc::now(),
+            created_at,
+            active_at: created_at + self.behavior().table_create_delay,
+            deleted_at: parking_lot::RwLock::new(None),
+            point_in_time_recovery: parking_lot::RwLock::new(None),
             storage,
         };
 
@@ -619,8 +813,21 @@ impl RustackDynamoDB {
         &self,
         input: DeleteTableInput,
     ) -> Result<DeleteTableOutput, DynamoDBError> {
-        let table = self.state.delete_table(&input.table_name)?;
-        self.lifecycle.on_table_deleted(&table.name);
+        let table = self.state.require_table(&input.table_name)?;
+        require_not_in_transition(&table)?;
+
+        // Requests already holding the table finish against it; later ones
+        // no longer find it.
+        let delay = self.behavior().table_delete_delay;
+        if delay.is_zero() {
+            self.state
+                .delete_table(&table, || self.lifecycle.on_table_deleted(&table.name))?;
+        } else {
+            if !table.begin_deletion(chrono::Utc::now() + delay) {
+                return Err(resource_in_transition(&table));
+            }
+            self.lifecycle.on_table_deleted(&table.name);
+        }
         Ok(DeleteTableOutput {
             table_description: Some(table.to_delete_description()),
         })
@@ -700,6 +907,7 @@ impl RustackDynamoDB {
         input: UpdateTableInput,
     ) -> Result<UpdateTableOutput, DynamoDBError> {
         let table = self.state.require_table(&input.table_name)?;
+        require_not_in_transition(&table)?;
 
         // For our in-memory emulator, UpdateTable is accepted but most changes
         // are not enforced (billing mode, provisioned throughput, attribute
@@ -721,8 +929,39 @@ impl RustackDynamoDB {
     /// Handle `PutItem`.
     #[allow(clippy::too_many_lines)]
     pub fn handle_put_item(&self, mut input: PutItemInput) -> Result<PutItemOutput, DynamoDBError> {
+        // Reject enum values outside the documented value sets.
+        validate_enum_value(
+            "returnValues",
+            input.return_values.as_ref().map(ReturnValue::as_str),
+            ReturnValue::VALUES,
+        )?;
+        validate_enum_value(
+            "returnConsumedCapacity",
+            input
+                .return_consumed_capacity
+                .as_ref()
+                .map(ReturnConsumedCapacity::as_str),
+            ReturnConsumedCapacity::VALUES,
+        )?;
+        validate_enum_value(
+            "returnItemCollectionMetrics",
+            input
+                .return_item_collection_metrics
+                .as_ref()
+                .map(ReturnItemCollectionMetrics::as_str),
+            ReturnItemCollectionMetrics::VALUES,
+        )?;
+        validate_enum_value(
+            "conditionalOperator",
+            input
+                .conditional_operator
+                .as_ref()
+                .map(ConditionalOperator::as_str),
+            ConditionalOperator::VALUES,
+        )?;
+
         validate_table_name(&input.table_name)?;
-        let table = self.state.require_table(&input.table_name)?;
+        let table = self.state.require_active_table(&input.table_name)?;
 
         // Validate return_values: PutItem only supports NONE and ALL_OLD.
         if let Some(ref rv) = input.return_values {
@@ -736,8 +975,8 @@ impl RustackDynamoDB {
         // Validate key attributes are not empty.
         validate_key_not_empty(&table.key_schema, &input.item)?;
 
-        // Validate item does not contain empty sets.
-        validate_item_no_empty_sets(&input.item)?;
+        // Validate item sets are not empty and have no empty members.
+        validate_item_sets(&input.item)?;
 
         // Reject mixing Expected with ConditionExpression.
         if !input.expected.is_empty() && input.condition_expression.is_some() {
@@ -763,8 +1002,9 @@ impl RustackDynamoDB {
             input.return_values_on_condition_check_failure.as_deref(),
         )?;
 
-        // Validate numbers in item.
+        // Validate numbers and nesting in item.
         validate_numbers_in_item(&input.item)?;
+        validate_item_nesting(&input.item)?;
 
         // Validate item size.
         let size = calculate_item_size(&input.item);
@@ -777,12 +1017,18 @@ impl RustackDynamoDB {
         // Reject empty condition expression.
         validate_condition_not_empty(input.condition_expression.as_deref())?;
 
-        // Validate unused expression attribute names/values.
+        // Validate expression limits and unused attribute names/values.
         {
+            limits::check_attribute_names(&input.expression_attribute_names)
+                .map_err(expression_error_to_dynamodb)?;
+            limits::check_attribute_values(&input.expression_attribute_values)
+                .map_err(expression_error_to_dynamodb)?;
             let mut used_names = HashSet::new();
             let mut used_values = HashSet::new();
             if let Some(ref condition) = input.condition_expression {
-                let expr = parse_condition(condition).map_err(expression_error_to_dynamodb)?;
+                let expr = self.parse_condition_expr("ConditionExpression", condition)?;
+                limits::check_condition("ConditionExpression", condition, &expr)
+                    .map_err(expression_error_to_dynamodb)?;
                 collect_names_from_expr(&expr, &mut used_names);
                 collect_values_from_expr(&expr, &mut used_values);
             }
@@ -790,39 +1036,26 @@ impl RustackDynamoDB {
             validate_no_unused_values(&input.expression_attribute_values, &used_values)?;
         }
 
-        // Evaluate condition expression if present.
-        if let Some(ref condition) = input.condition_expression {
-            let existing = {
-                let pk = extract_primary_key(&table.key_schema, &input.item)
-                    .map_err(storage_error_to_dynamodb)?;
-                table.storage.get_item(&pk)
-            };
-            let empty = HashMap::new();
-            let item_ref = existing.as_ref().unwrap_or(&empty);
-            let expr = parse_condition(condition).map_err(expression_error_to_dynamodb)?;
-            let ctx = EvalContext {
-                item: item_ref,
-                names: &input.expression_attribute_names,
-                values: &input.expression_attribute_values,
-            };
-            let result = ctx.evaluate(&expr).map_err(expression_error_to_dynamodb)?;
-            if !result {
-                let mut err =
-                    DynamoDBError::conditional_check_failed("The conditional request failed");
-                if input.return_values_on_condition_check_failure.as_deref() == Some("ALL_OLD") {
-                    if let Some(ref existing_item) = existing {
-                        err = err.with_item(existing_item.clone());
-                    }
-                }
-                return Err(err);
-            }
-        }
-
-        let new_item = input.item.clone();
-        let old = table
-            .storage
-            .put_item(input.item)
+        // Evaluate the condition and write the item atomically.
+        let pk = extract_primary_key(&table.key_schema, &input.item)
             .map_err(storage_error_to_dynamodb)?;
+        let condition = input
+            .condition_expression
+            .as_deref()
+            .map(parse_condition)
+            .transpose()
+            .map_err(expression_error_to_dynamodb)?;
+        let new_item = input.item.clone();
+        let (old, ()) = table.storage.modify_item(&pk, |existing| {
+            check_write_condition(
+                condition.as_ref(),
+                existing,
+                &input.expression_attribute_names,
+                &input.expression_attribute_values,
+                input.return_values_on_condition_check_failure.as_deref(),
+            )?;
+            Ok::<_, DynamoDBError>((ItemWrite::Put(input.item), ()))
+        })?;
 
         // Emit stream event if stream is enabled for this table.
         if table
@@ -849,8 +1082,8 @@ impl RustackDynamoDB {
 
         // ALL_OLD: return the old item if it existed, otherwise omit Attributes.
         let attributes = match input.return_values {
-            Some(ReturnValue::AllOld) => old.unwrap_or_default(),
-            _ => HashMap::new(),
+            Some(ReturnValue::AllOld) => old,
+            _ => None,
         };
 
         Ok(PutItemOutput {
@@ -863,8 +1096,18 @@ impl RustackDynamoDB {
     /// Handle `GetItem`.
     #[allow(clippy::needless_pass_by_value)]
     pub fn handle_get_item(&self, mut input: GetItemInput) -> Result<GetItemOutput, DynamoDBError> {
+        // Reject enum values outside the documented value sets.
+        validate_enum_value(
+            "returnConsumedCapacity",
+            input
+                .return_consumed_capacity
+                .as_ref()
+                .map(ReturnConsumedCapacity::as_str),
+            ReturnConsumedCapacity::VALUES,
+        )?;
+
         validate_table_name(&input.table_name)?;
-        let table = self.state.require_table(&input.table_name)?;
+        let table = self.state.require_active_table(&input.table_name)?;
 
         // Reject both ProjectionExpression and AttributesToGet.
         if input.projection_expression.is_some()
@@ -899,11 +1142,14 @@ impl RustackDynamoDB {
             }
         }
 
-        // Validate unused expression attribute names.
+        // Validate expression limits and unused attribute names.
         {
+            limits::check_attribute_names(&input.expression_attribute_names)
+                .map_err(expression_error_to_dynamodb)?;
             let mut used_names = HashSet::new();
             if let Some(ref proj) = input.projection_expression {
-                let paths = parse_projection(proj).map_err(projection_error_to_dynamodb)?;
+                let paths = self.parse_projection_expr(proj, &input.expression_attribute_names)?;
+                limits::check_projection(proj, &paths).map_err(expression_error_to_dynamodb)?;
                 collect_names_from_projection(&paths, &mut used_names);
             }
             validate_no_unused_names(&input.expression_attribute_names, &used_names)?;
@@ -914,7 +1160,8 @@ impl RustackDynamoDB {
         // Apply projection if specified.
         let projected = match (item, &input.projection_expression) {
             (Some(item), Some(proj_expr)) => {
-                let paths = parse_projection(proj_expr).map_err(projection_error_to_dynamodb)?;
+                let paths =
+                    self.parse_projection_expr(proj_expr, &input.expression_attribute_names)?;
                 let ctx = EvalContext {
                     item: &item,
                     names: &input.expression_attribute_names,
@@ -933,12 +1180,44 @@ impl RustackDynamoDB {
     }
 
     /// Handle `DeleteItem`.
+    #[allow(clippy::too_many_lines)]
     pub fn handle_delete_item(
         &self,
         mut input: DeleteItemInput,
     ) -> Result<DeleteItemOutput, DynamoDBError> {
+        // Reject enum values outside the documented value sets.
+        validate_enum_value(
+            "returnValues",
+            input.return_values.as_ref().map(ReturnValue::as_str),
+            ReturnValue::VALUES,
+        )?;
+        validate_enum_value(
+            "returnConsumedCapacity",
+            input
+                .return_consumed_capacity
+                .as_ref()
+                .map(ReturnConsumedCapacity::as_str),
+            ReturnConsumedCapacity::VALUES,
+        )?;
+        validate_enum_value(
+            "returnItemCollectionMetrics",
+            input
+                .return_item_collection_metrics
+                .as_ref()
+                .map(ReturnItemCollectionMetrics::as_str),
+            ReturnItemCollectionMetrics::VALUES,
+        )?;
+        validate_enum_value(
+            "conditionalOperator",
+            input
+                .conditional_operator
+                .as_ref()
+                .map(ConditionalOperator::as_str),
+            ConditionalOperator::VALUES,
+        )?;
+
         validate_table_name(&input.table_name)?;
-        let table = self.state.require_table(&input.table_name)?;
+        let table = self.state.require_active_table(&input.table_name)?;
 
         // Validate return_values: DeleteItem only supports NONE and ALL_OLD.
         if let Some(ref rv) = input.return_values {
@@ -985,12 +1264,18 @@ impl RustackDynamoDB {
         // Reject empty condition expression.
         validate_condition_not_empty(input.condition_expression.as_deref())?;
 
-        // Validate unused expression attribute names/values.
+        // Validate expression limits and unused attribute names/values.
         {
+            limits::check_attribute_names(&input.expression_attribute_names)
+                .map_err(expression_error_to_dynamodb)?;
+            limits::check_attribute_values(&input.expression_attribute_values)
+                .map_err(expression_error_to_dynamodb)?;
             let mut used_names = HashSet::new();
             let mut used_values = HashSet::new();
             if let Some(ref condition) = input.condition_expression {
-                let expr = parse_condition(condition).map_err(expression_error_to_dynamodb)?;
+                let expr = self.parse_condition_expr("ConditionExpression", condition)?;
+                limits::check_condition("ConditionExpression", condition, &expr)
+                    .map_err(expression_error_to_dynamodb)?;
                 collect_names_from_expr(&expr, &mut used_names);
                 collect_values_from_expr(&expr, &mut used_values);
             }
@@ -998,31 +1283,23 @@ impl RustackDynamoDB {
             validate_no_unused_values(&input.expression_attribute_values, &used_values)?;
         }
 
-        // Evaluate condition expression if present.
-        if let Some(ref condition) = input.condition_expression {
-            let existing = table.storage.get_item(&pk);
-            let empty = HashMap::new();
-            let item_ref = existing.as_ref().unwrap_or(&empty);
-            let expr = parse_condition(condition).map_err(expression_error_to_dynamodb)?;
-            let ctx = EvalContext {
-                item: item_ref,
-                names: &input.expression_attribute_names,
-                values: &input.expression_attribute_values,
-            };
-            let result = ctx.evaluate(&expr).map_err(expression_error_to_dynamodb)?;
-            if !result {
-                let mut err =
-                    DynamoDBError::conditional_check_failed("The conditional request failed");
-                if input.return_values_on_condition_check_failure.as_deref() == Some("ALL_OLD") {
-                    if let Some(ref existing_item) = existing {
-                        err = err.with_item(existing_item.clone());
-                    }
-                }
-                return Err(err);
-            }
-        }
-
-        let old = table.storage.delete_item(&pk);
+        // Evaluate the condition and delete the item atomically.
+        let condition = input
+            .condition_expression
+            .as_deref()
+            .map(parse_condition)
+            .transpose()
+            .map_err(expression_error_to_dynamodb)?;
+        let (old, ()) = table.storage.modify_item(&pk, |existing| {
+            check_write_condition(
+                condition.as_ref(),
+                existing,
+                &input.expression_attribute_names,
+                &input.expression_attribute_values,
+                input.return_values_on_condition_check_failure.as_deref(),
+            )?;
+            Ok::<_, DynamoDBError>((ItemWrite::Delete, ()))
+        })?;
 
         // Emit stream event if an item was actually deleted and stream is enabled.
         if let Some(ref old_item) = old {
@@ -1046,8 +1323,8 @@ impl RustackDynamoDB {
 
         // ALL_OLD: return the old item if it existed, otherwise omit Attributes.
         let attributes = match input.return_values {
-            Some(ReturnValue::AllOld) => old.unwrap_or_default(),
-            _ => HashMap::new(),
+            Some(ReturnValue::AllOld) => old,
+            _ => None,
         };
 
         Ok(DeleteItemOutput {
@@ -1063,8 +1340,39 @@ impl RustackDynamoDB {
         &self,
         mut input: UpdateItemInput,
     ) -> Result<UpdateItemOutput, DynamoDBError> {
+        // Reject enum values outside the documented value sets.
+        validate_enum_value(
+            "returnValues",
+            input.return_values.as_ref().map(ReturnValue::as_str),
+            ReturnValue::VALUES,
+        )?;
+        validate_enum_value(
+            "returnConsumedCapacity",
+            input
+                .return_consumed_capacity
+                .as_ref()
+                .map(ReturnConsumedCapacity::as_str),
+            ReturnConsumedCapacity::VALUES,
+        )?;
+        validate_enum_value(
+            "returnItemCollectionMetrics",
+            input
+                .return_item_collection_metrics
+                .as_ref()
+                .map(ReturnItemCollectionMetrics::as_str),
+            ReturnItemCollectionMetrics::VALUES,
+        )?;
+        validate_enum_value(
+            "conditionalOperator",
+            input
+                .conditional_operator
+                .as_ref()
+                .map(ConditionalOperator::as_str),
+            ConditionalOperator::VALUES,
+        )?;
+
         validate_table_name(&input.table_name)?;
-        let table = self.state.require_table(&input.table_name)?;
+        let table = self.state.require_active_table(&input.table_name)?;
 
         // Validate return_values.
         if let Some(ref rv) = input.return_values {
@@ -1126,9 +1434,6 @@ impl RustackDynamoDB {
         let pk = extract_primary_key(&table.key_schema, &input.key)
             .map_err(storage_error_to_dynamodb)?;
 
-        let existing = table.storage.get_item(&pk);
-        let mut item = existing.clone().unwrap_or_else(|| input.key.clone());
-
         // Legacy API: convert Expected to ConditionExpression.
         if !input.expected.is_empty() && input.condition_expression.is_none() {
             validate_expected(&input.expected)?;
@@ -1142,6 +1447,7 @@ impl RustackDynamoDB {
         // Legacy API: handle ADD-for-lists by applying list_append directly.
         // The modern UpdateExpression ADD does not support lists, but the
         // legacy AttributeUpdates ADD does (it appends to lists).
+        let mut list_appends: Vec<(String, Vec<AttributeValue>)> = Vec::new();
         if !input.attribute_updates.is_empty() && input.update_expression.is_none() {
             let list_add_attrs: Vec<String> = input
                 .attribute_updates
@@ -1156,26 +1462,13 @@ impl RustackDynamoDB {
                 })
                 .map(|(k, _)| k.clone())
                 .collect();
-            for attr in &list_add_attrs {
-                if let Some(update) = input.attribute_updates.remove(attr) {
-                    if let Some(AttributeValue::L(new_items)) = update.value {
-                        match item.get(attr) {
-                            Some(AttributeValue::L(existing)) => {
-                                let mut merged = existing.clone();
-                                merged.extend(new_items);
-                                item.insert(attr.clone(), AttributeValue::L(merged));
-                            }
-                            None => {
-                                item.insert(attr.clone(), AttributeValue::L(new_items));
-                            }
-                            Some(existing_val) => {
-                                return Err(DynamoDBError::validation(format!(
-                                    "Type mismatch for ADD; operator type: L, existing type: {}",
-                                    existing_val.type_descriptor(),
-                                )));
-                            }
-                        }
-                    }
+            for attr in list_add_attrs {
+                if let Some(AttributeValueUpdate {
+                    value: Some(AttributeValue::L(new_items)),
+                    ..
+                }) = input.attribute_updates.remove(&attr)
+                {
+                    list_appends.push((attr, new_items));
                 }
             }
         }
@@ -1192,23 +1485,31 @@ impl RustackDynamoDB {
         // Validate numbers in expression attribute values.
         validate_numbers_in_item(&input.expression_attribute_values)?;
 
-        // Validate expression attribute values contain no empty sets.
-        validate_no_empty_sets(&input.expression_attribute_values)?;
+        // Validate expression attribute values contain no empty sets or
+        // empty set members.
+        validate_expression_value_sets(&input.expression_attribute_values)?;
 
         // Reject empty condition expression.
         validate_condition_not_empty(input.condition_expression.as_deref())?;
 
-        // Validate unused expression attribute names/values.
+        // Validate expression limits and unused attribute names/values.
         {
+            limits::check_attribute_names(&input.expression_attribute_names)
+                .map_err(expression_error_to_dynamodb)?;
+            limits::check_attribute_values(&input.expression_attribute_values)
+                .map_err(expression_error_to_dynamodb)?;
             let mut used_names = HashSet::new();
             let mut used_values = HashSet::new();
             if let Some(ref condition) = input.condition_expression {
-                let expr = parse_condition(condition).map_err(expression_error_to_dynamodb)?;
+                let expr = self.parse_condition_expr("ConditionExpression", condition)?;
+                limits::check_condition("ConditionExpression", condition, &expr)
+                    .map_err(expression_error_to_dynamodb)?;
                 collect_names_from_expr(&expr, &mut used_names);
                 collect_values_from_expr(&expr, &mut used_values);
             }
             if let Some(ref update_expr) = input.update_expression {
-                let parsed = parse_update(update_expr).map_err(expression_error_to_dynamodb)?;
+                let parsed = self.parse_update_expr(update_expr)?;
+                limits::check_update(update_expr, &parsed).map_err(expression_error_to_dynamodb)?;
                 collect_names_from_update(&parsed, &mut used_names);
                 collect_values_from_update(&parsed, &mut used_values);
             }
@@ -1219,7 +1520,7 @@ impl RustackDynamoDB {
         // Validate update expression: key attributes cannot be modified,
         // and paths must not overlap.
         if let Some(ref update_expr) = input.update_expression {
-            let parsed = parse_update(update_expr).map_err(expression_error_to_dynamodb)?;
+            let parsed = self.parse_update_expr(update_expr)?;
             validate_update_paths(
                 &parsed,
                 &table.key_schema,
@@ -1227,86 +1528,102 @@ impl RustackDynamoDB {
             )?;
         }
 
-        // Evaluate condition expression.
-        if let Some(ref condition) = input.condition_expression {
-            let empty = HashMap::new();
-            let item_ref = existing.as_ref().unwrap_or(&empty);
-            let expr = parse_condition(condition).map_err(expression_error_to_dynamodb)?;
-            let ctx = EvalContext {
-                item: item_ref,
-                names: &input.expression_attribute_names,
-                values: &input.expression_attribute_values,
-            };
-            let result = ctx.evaluate(&expr).map_err(expression_error_to_dynamodb)?;
-            if !result {
-                let mut err =
-                    DynamoDBError::conditional_check_failed("The conditional request failed");
-                if input.return_values_on_condition_check_failure.as_deref() == Some("ALL_OLD") {
-                    let existing_ref = existing.as_ref();
-                    if let Some(existing_item) = existing_ref {
-                        err = err.with_item(existing_item.clone());
-                    }
-                }
-                return Err(err);
-            }
-        }
+        // Parse the update expression. Keep the parsed AST for computing
+        // UPDATED_OLD / UPDATED_NEW return values later.
+        let parsed_update = input
+            .update_expression
+            .as_deref()
+            .map(parse_update)
+            .transpose()
+            .map_err(expression_error_to_dynamodb)?;
 
         // Determine if the update contains only subtractive operations (REMOVE
         // and/or DELETE). When such an update targets a non-existent item,
         // DynamoDB does NOT create the item.
-        let is_subtractive_only = input.update_expression.as_ref().is_some_and(|expr| {
-            let upper = expr.trim().to_ascii_uppercase();
-            !upper.contains("SET ") && !upper.contains("ADD ")
-        });
+        let is_subtractive_only = list_appends.is_empty()
+            && parsed_update.as_ref().is_some_and(|update| {
+                update.set_actions.is_empty() && update.add_actions.is_empty()
+            });
+        let condition = input
+            .condition_expression
+            .as_deref()
+            .map(parse_condition)
+            .transpose()
+            .map_err(expression_error_to_dynamodb)?;
+
+        // Evaluate the condition and apply the update atomically, so
+        // concurrent updates of the item (such as atomic counters) are never
+        // lost.
+        let (old_item, updated) = table.storage.modify_item(&pk, |existing| {
+            let mut item = existing.cloned().unwrap_or_else(|| input.key.clone());
+            for (attr, new_items) in list_appends {
+                match item.get_mut(&attr) {
+                    Some(AttributeValue::L(current)) => current.extend(new_items),
+                    None => {
+                        item.insert(attr, AttributeValue::L(new_items));
+                    }
+                    Some(existing_val) => {
+                        return Err(DynamoDBError::validation(format!(
+                            "An operand in the update expression has an incorrect data type; \
+                             operator: ADD, operand type: L, existing type: {}",
+                            existing_val.type_descriptor(),
+                        )));
+                    }
+                }
+            }
 
-        // Parse and apply update expression. Keep the parsed AST for
-        // computing UPDATED_OLD / UPDATED_NEW return values later.
-        let parsed_update = if let Some(ref update_expr) = input.update_expression {
-            let parsed = parse_update(update_expr).map_err(expression_error_to_dynamodb)?;
-            let ctx = EvalContext {
-                item: &item,
-                names: &input.expression_attribute_names,
-                values: &input.expression_attribute_values,
-            };
-            item = ctx
-                .apply_update(&parsed)
-                .map_err(expression_error_to_dynamodb)?;
-            Some(parsed)
-        } else {
-            None
-        };
+            check_write_condition(
+                condition.as_ref(),
+                existing,
+                &input.expression_attribute_names,
+                &input.expression_attribute_values,
+                input.return_values_on_condition_check_failure.as_deref(),
+            )?;
+
+            if let Some(parsed) = &parsed_update {
+                let ctx = EvalContext {
+                    item: &item,
+                    names: &input.expression_attribute_names,
+                    values: &input.expression_attribute_values,
+                };
+                item = ctx
+                    .apply_update(parsed)
+                    .map_err(expression_error_to_dynamodb)?;
+            }
+
+            // If the original item didn't exist and the update only contains
+            // subtractive operations (REMOVE/DELETE), the resulting item would
+            // only have key attributes. In this case DynamoDB does NOT create
+            // the item.
+            if existing.is_none()
+                && is_subtractive_only
+                && item_has_only_key_attrs(&item, &table.key_schema)
+            {
+                return Ok((ItemWrite::Keep, None));
+            }
+
+            // Validate updated item nesting and size.
+            validate_item_nesting(&item)?;
+            let size = calculate_item_size(&item);
+            if size > MAX_ITEM_SIZE_BYTES {
+                return Err(DynamoDBError::validation(format!(
+                    "Item size has exceeded the maximum allowed size of {MAX_ITEM_SIZE_BYTES} \
+                     bytes"
+                )));
+            }
 
-        // If the original item didn't exist and the update only contains
-        // subtractive operations (REMOVE/DELETE), the resulting item would
-        // only have key attributes. In this case DynamoDB does NOT create
-        // the item.
-        let item_not_stored = existing.is_none()
-            && is_subtractive_only
-            && item_has_only_key_attrs(&item, &table.key_schema);
+            Ok((ItemWrite::Put(item.clone()), Some((item, size))))
+        })?;
 
-        if item_not_stored {
-            // Do not store the item. Return empty attributes for all ReturnValues
+        let Some((item, size)) = updated else {
+            // Do not store the item. Omit attributes for all ReturnValues
             // variants since there is no old item and no new item to return.
             return Ok(UpdateItemOutput {
-                attributes: HashMap::new(),
+                attributes: None,
                 consumed_capacity: None,
                 item_collection_metrics: None,
             });
-        }
-
-        // Validate updated item size.
-        let size = calculate_item_size(&item);
-        if size > MAX_ITEM_SIZE_BYTES {
-            return Err(DynamoDBError::validation(format!(
-                "Item size has exceeded the maximum allowed size of {MAX_ITEM_SIZE_BYTES} bytes"
-            )));
-        }
-
-        // Store updated item.
-        let old_item = table
-            .storage
-            .put_item(item.clone())
-            .map_err(storage_error_to_dynamodb)?;
+        };
 
         // Emit stream event if stream is enabled for this table.
         if table
@@ -1314,7 +1631,7 @@ impl RustackDynamoDB {
             .as_ref()
             .is_some_and(|s| s.stream_enabled)
         {
-            let event_name = if existing.is_some() || old_item.is_some() {
+            let event_name = if old_item.is_some() {
                 crate::stream::ChangeEventName::Modify
             } else {
                 crate::stream::ChangeEventName::Insert
@@ -1324,17 +1641,15 @@ impl RustackDynamoDB {
                 table_name: table.name.clone(),
                 event_name,
                 keys,
-                old_image: old_item.clone().or_else(|| existing.clone()),
+                old_image: old_item.clone(),
                 new_image: Some(item.clone()),
                 size_bytes: size,
             });
         }
 
-        let old_for_return = old_item.or(existing);
-
         let attributes = compute_update_return_values(
             input.return_values.as_ref(),
-            old_for_return.as_ref(),
+            old_item.as_ref(),
             &item,
             &table.key_schema,
             parsed_update.as_ref(),
@@ -1342,7 +1657,9 @@ impl RustackDynamoDB {
         );
 
         Ok(UpdateItemOutput {
-            attributes,
+            // Nothing to return (no old item for ALL_OLD, no changed attributes
+            // for UPDATED_*) omits `Attributes` entirely.
+            attributes: Some(attributes).filter(|attributes| !attributes.is_empty()),
             consumed_capacity: None,
             item_collection_metrics: None,
         })
@@ -1357,7 +1674,30 @@ impl RustackDynamoDB {
     /// Handle `Query`.
     #[allow(clippy::needless_pass_by_value, clippy::too_many_lines)]
     pub fn handle_query(&self, mut input: QueryInput) -> Result<QueryOutput, DynamoDBError> {
-        let table = self.state.require_table(&input.table_name)?;
+        // Reject enum values outside the documented value sets.
+        validate_enum_value(
+            "select",
+            input.select.as_ref().map(Select::as_str),
+            Select::VALUES,
+        )?;
+        validate_enum_value(
+            "returnConsumedCapacity",
+            input
+                .return_consumed_capacity
+                .as_ref()
+                .map(ReturnConsumedCapacity::as_str),
+            ReturnConsumedCapacity::VALUES,
+        )?;
+        validate_enum_value(
+            "conditionalOperator",
+            input
+                .conditional_operator
+                .as_ref()
+                .map(ConditionalOperator::as_str),
+            ConditionalOperator::VALUES,
+        )?;
+
+        let table = self.state.require_active_table(&input.table_name)?;
 
         // Validate Select parameter.
         let has_atg = input
@@ -1495,18 +1835,25 @@ impl RustackDynamoDB {
         };
         let effective_key_schema = gsi_key_schema.as_ref().unwrap_or(&table.key_schema);
 
-        // Validate unused expression attribute names/values.
+        // Validate expression limits and unused attribute names/values.
         {
+            limits::check_attribute_names(&input.expression_attribute_names)
+                .map_err(expression_error_to_dynamodb)?;
+            limits::check_attribute_values(&input.expression_attribute_values)
+                .map_err(expression_error_to_dynamodb)?;
             let mut used_names = HashSet::new();
             let mut used_values = HashSet::new();
             {
-                let parsed =
-                    parse_condition(key_condition).map_err(expression_error_to_dynamodb)?;
+                let parsed = self.parse_condition_expr("KeyConditionExpression", key_condition)?;
+                limits::check_condition("KeyConditionExpression", key_condition, &parsed)
+                    .map_err(expression_error_to_dynamodb)?;
                 collect_names_from_expr(&parsed, &mut used_names);
                 collect_values_from_expr(&parsed, &mut used_values);
             }
             if let Some(ref filter) = input.filter_expression {
-                let parsed = parse_condition(filter).map_err(expression_error_to_dynamodb)?;
+                let parsed = self.parse_condition_expr("FilterExpression", filter)?;
+                limits::check_condition("FilterExpression", filter, &parsed)
+                    .map_err(expression_error_to_dynamodb)?;
                 collect_names_from_expr(&parsed, &mut used_names);
                 collect_values_from_expr(&parsed, &mut used_values);
 
@@ -1519,7 +1866,8 @@ impl RustackDynamoDB {
                 )?;
             }
             if let Some(ref proj) = input.projection_expression {
-                let paths = parse_projection(proj).map_err(projection_error_to_dynamodb)?;
+                let paths = self.parse_projection_expr(proj, &input.expression_attribute_names)?;
+                limits::check_projection(proj, &paths).map_err(expression_error_to_dynamodb)?;
                 collect_names_from_projection(&paths, &mut used_names);
             }
             validate_no_unresolved_names(&input.expression_attribute_names, &used_names)?;
@@ -1527,7 +1875,7 @@ impl RustackDynamoDB {
             validate_no_unused_values(&input.expression_attribute_values, &used_values)?;
         }
 
-        let expr = parse_condition(key_condition).map_err(expression_error_to_dynamodb)?;
+        let expr = self.parse_condition_expr("KeyConditionExpression", key_condition)?;
 
         // Validate key condition against the effective key schema (table or GSI).
         validate_key_condition_expr(
@@ -1545,13 +1893,19 @@ impl RustackDynamoDB {
         )?;
 
         let scan_forward = input.scan_index_forward.unwrap_or(true);
-        let limit = input
-            .limit
-            .map(|l| usize::try_from(l.max(0)).unwrap_or(usize::MAX));
+        let limit = self.page_limit(input.limit);
+
+        let mut page = PageItems::new(
+            input.filter_expression.as_deref(),
+            input.projection_expression.as_deref(),
+            &input.expression_attribute_names,
+            &input.expression_attribute_values,
+            input.select == Some(Select::Count),
+        )?;
 
         // Branch: table query vs. GSI query.
-        let (mut items, last_evaluated_key) = if gsi_key_schema.is_some() {
-            query_gsi(
+        let last_evaluated_key = if gsi_key_schema.is_some() {
+            let (items, last_evaluated_key) = query_gsi(
                 &table,
                 effective_key_schema,
                 &partition_value,
@@ -1559,7 +1913,11 @@ impl RustackDynamoDB {
                 scan_forward,
                 limit,
                 &input.exclusive_start_key,
-            )
+            );
+            for item in &items {
+                page.visit(item)?;
+            }
+            last_evaluated_key
         } else {
             let exclusive_start_sort = if input.exclusive_start_key.is_empty() {
                 None
@@ -1569,83 +1927,29 @@ impl RustackDynamoDB {
                 start_pk.sort_key
             };
 
-            let (items, last_key_sort) = table.storage.query(
+            let last_key_sort = table.storage.query_with(
                 &partition_value,
                 sort_condition.as_ref(),
                 scan_forward,
                 limit,
                 exclusive_start_sort.as_ref(),
-            );
+                |item| page.visit(item),
+            )?;
 
-            let last_evaluated_key = last_key_sort.map(|pk| {
+            last_key_sort.map(|pk| {
                 let sort_av = pk
                     .sort_key
                     .as_ref()
                     .and_then(SortableAttributeValue::to_attribute_value);
                 build_last_evaluated_key(&table.key_schema, &pk.partition_key, sort_av.as_ref())
-            });
-
-            (items, last_evaluated_key)
+            })
         };
 
-        let scanned_count = i32::try_from(items.len()).unwrap_or(i32::MAX);
-
-        // Apply filter expression if present.
-        if let Some(ref filter) = input.filter_expression {
-            let filter_expr = parse_condition(filter).map_err(expression_error_to_dynamodb)?;
-            items = items
-                .into_iter()
-                .map(|item| {
-                    let ctx = EvalContext {
-                        item: &item,
-                        names: &input.expression_attribute_names,
-                        values: &input.expression_attribute_values,
-                    };
-                    let matched = ctx
-                        .evaluate(&filter_expr)
-                        .map_err(expression_error_to_dynamodb)?;
-                    Ok((item, matched))
-                })
-                .collect::<Result<Vec<_>, DynamoDBError>>()?
-                .into_iter()
-                .filter_map(|(item, matched)| matched.then_some(item))
-                .collect();
-        }
-
-        // Apply projection expression if present.
-        if let Some(ref proj) = input.projection_expression {
-            let paths = parse_projection(proj).map_err(projection_error_to_dynamodb)?;
-            items = items
-                .into_iter()
-                .map(|item| {
-                    let ctx = EvalContext {
-                        item: &item,
-                        names: &input.expression_attribute_names,
-                        values: &HashMap::new(),
-                    };
-                    ctx.apply_projection(&paths)
-                })
-                .collect();
-        }
-
-        let count = i32::try_from(items.len()).unwrap_or(i32::MAX);
-
-        // If Select=COUNT, return only the count (no items).
-        if input.select == Some(Select::Count) {
-            return Ok(QueryOutput {
-                items: None,
-                count,
-                scanned_count,
-                last_evaluated_key: last_evaluated_key.unwrap_or_default(),
-                consumed_capacity: None,
-            });
-        }
-
         Ok(QueryOutput {
-            items: Some(items),
-            count,
-            scanned_count,
-            last_evaluated_key: last_evaluated_key.unwrap_or_default(),
+            count: page.count(),
+            scanned_count: page.scanned_count(),
+            items: page.into_items(),
+            last_evaluated_key,
             consumed_capacity: None,
         })
     }
@@ -1653,7 +1957,30 @@ impl RustackDynamoDB {
     /// Handle `Scan`.
     #[allow(clippy::needless_pass_by_value, clippy::too_many_lines)]
     pub fn handle_scan(&self, mut input: ScanInput) -> Result<ScanOutput, DynamoDBError> {
-        let table = self.state.require_table(&input.table_name)?;
+        // Reject enum values outside the documented value sets.
+        validate_enum_value(
+            "select",
+            input.select.as_ref().map(Select::as_str),
+            Select::VALUES,
+        )?;
+        validate_enum_value(
+            "returnConsumedCapacity",
+            input
+                .return_consumed_capacity
+                .as_ref()
+                .map(ReturnConsumedCapacity::as_str),
+            ReturnConsumedCapacity::VALUES,
+        )?;
+        validate_enum_value(
+            "conditionalOperator",
+            input
+                .conditional_operator
+                .as_ref()
+                .map(ConditionalOperator::as_str),
+            ConditionalOperator::VALUES,
+        )?;
+
+        let table = self.state.require_active_table(&input.table_name)?;
 
         let has_atg = input
             .attributes_to_get
@@ -1706,26 +2033,31 @@ impl RustackDynamoDB {
         // Reject empty filter expression (before parsing attempt).
         validate_filter_not_empty(input.filter_expression.as_deref())?;
 
-        // Validate unused expression attribute names/values.
+        // Validate expression limits and unused attribute names/values.
         {
+            limits::check_attribute_names(&input.expression_attribute_names)
+                .map_err(expression_error_to_dynamodb)?;
+            limits::check_attribute_values(&input.expression_attribute_values)
+                .map_err(expression_error_to_dynamodb)?;
             let mut used_names = HashSet::new();
             let mut used_values = HashSet::new();
             if let Some(ref filter) = input.filter_expression {
-                let parsed = parse_condition(filter).map_err(expression_error_to_dynamodb)?;
+                let parsed = self.parse_condition_expr("FilterExpression", filter)?;
+                limits::check_condition("FilterExpression", filter, &parsed)
+                    .map_err(expression_error_to_dynamodb)?;
                 collect_names_from_expr(&parsed, &mut used_names);
                 collect_values_from_expr(&parsed, &mut used_values);
             }
             if let Some(ref proj) = input.projection_expression {
-                let paths = parse_projection(proj).map_err(projection_error_to_dynamodb)?;
+                let paths = self.parse_projection_expr(proj, &input.expression_attribute_names)?;
+                limits::check_projection(proj, &paths).map_err(expression_error_to_dynamodb)?;
                 collect_names_from_projection(&paths, &mut used_names);
             }
             validate_no_unused_names(&input.expression_attribute_names, &used_names)?;
             validate_no_unused_values(&input.expression_attribute_values, &used_values)?;
         }
 
-        let limit = input
-            .limit
-            .map(|l| usize::try_from(l.max(0)).unwrap_or(usize::MAX));
+        let limit = self.page_limit(input.limit);
         let exclusive_start = if input.exclusive_start_key.is_empty() {
             None
         } else {
@@ -1738,52 +2070,20 @@ impl RustackDynamoDB {
         // Validate and extract parallel scan parameters.
         let (segment, total_segments) = validate_parallel_scan(&input, exclusive_start.as_ref())?;
 
-        let (mut items, last_key) =
-            table
-                .storage
-                .scan(limit, exclusive_start.as_ref(), segment, total_segments);
-
-        let scanned_count = i32::try_from(items.len()).unwrap_or(i32::MAX);
-
-        // Apply filter expression if present.
-        if let Some(ref filter) = input.filter_expression {
-            let filter_expr = parse_condition(filter).map_err(expression_error_to_dynamodb)?;
-            items = items
-                .into_iter()
-                .map(|item| {
-                    let ctx = EvalContext {
-                        item: &item,
-                        names: &input.expression_attribute_names,
-                        values: &input.expression_attribute_values,
-                    };
-                    let matched = ctx
-                        .evaluate(&filter_expr)
-                        .map_err(expression_error_to_dynamodb)?;
-                    Ok((item, matched))
-                })
-                .collect::<Result<Vec<_>, DynamoDBError>>()?
-                .into_iter()
-                .filter_map(|(item, matched)| matched.then_some(item))
-                .collect();
-        }
-
-        // Apply projection expression if present.
-        if let Some(ref proj) = input.projection_expression {
-            let paths = parse_projection(proj).map_err(projection_error_to_dynamodb)?;
-            items = items
-                .into_iter()
-                .map(|item| {
-                    let ctx = EvalContext {
-                        item: &item,
-                        names: &input.expression_attribute_names,
-                        values: &HashMap::new(),
-                    };
-                    ctx.apply_projection(&paths)
-                })
-                .collect();
-        }
-
-        let count = i32::try_from(items.len()).unwrap_or(i32::MAX);
+        let mut page = PageItems::new(
+            input.filter_expression.as_deref(),
+            input.projection_expression.as_deref(),
+            &input.expression_attribute_names,
+            &input.expression_attribute_values,
+            input.select == Some(Select::Count),
+        )?;
+        let last_key = table.storage.scan_with(
+            limit,
+            exclusive_start.as_ref(),
+            segment,
+            total_segments,
+            |item| page.visit(item),
+        )?;
 
         let last_evaluated_key = last_key.map(|pk| {
             let sort_av = pk
@@ -1793,22 +2093,11 @@ impl RustackDynamoDB {
             build_last_evaluated_key(&table.key_schema, &pk.partition_key, sort_av.as_ref())
         });
 
-        // If Select=COUNT, return only the count (no items).
-        if input.select == Some(Select::Count) {
-            return Ok(ScanOutput {
-                items: None,
-                count,
-                scanned_count,
-                last_evaluated_key: last_evaluated_key.unwrap_or_default(),
-                consumed_capacity: None,
-            });
-        }
-
         Ok(ScanOutput {
-            items: Some(items),
-            count,
-            scanned_count,
-            last_evaluated_key: last_evaluated_key.unwrap_or_default(),
+            count: page.count(),
+            scanned_count: page.scanned_count(),
+            items: page.into_items(),
+            last_evaluated_key,
             consumed_capacity: None,
         })
     }
@@ -1825,6 +2114,16 @@ impl RustackDynamoDB {
         &self,
         input: BatchGetItemInput,
     ) -> Result<BatchGetItemOutput, DynamoDBError> {
+        // Reject enum values outside the documented value sets.
+        validate_enum_value(
+            "returnConsumedCapacity",
+            input
+                .return_consumed_capacity
+                .as_ref()
+                .map(ReturnConsumedCapacity::as_str),
+            ReturnConsumedCapacity::VALUES,
+        )?;
+
         // Enforce 100-item limit across all tables.
         let total_keys: usize = input.request_items.values().map(|ka| ka.keys.len()).sum();
         if total_keys > 100 {
@@ -1836,7 +2135,7 @@ impl RustackDynamoDB {
         let mut responses: HashMap<String, Vec<HashMap<String, AttributeValue>>> = HashMap::new();
 
         for (table_name, keys_and_attrs) in &input.request_items {
-            let table = self.state.require_table(table_name)?;
+            let table = self.state.require_active_table(table_name)?;
 
             // Detect duplicate keys within this table.
             detect_duplicate_keys(&table.key_schema, keys_and_attrs.keys.iter())?;
@@ -1851,15 +2150,21 @@ impl RustackDynamoDB {
                 None
             };
 
+            let names = keys_and_attrs
+                .expression_attribute_names
+                .clone()
+                .unwrap_or_default();
+            let paths = effective_projection
+                .as_deref()
+                .map(|proj| self.parse_projection_expr(proj, &names))
+                .transpose()?;
+
             // Validate unused expression attribute names if projection is set.
-            if let Some(ref proj) = effective_projection {
-                if let Some(ref ean) = keys_and_attrs.expression_attribute_names {
-                    if !ean.is_empty() {
-                        let paths = parse_projection(proj).map_err(projection_error_to_dynamodb)?;
-                        let mut used_names = HashSet::new();
-                        collect_names_from_projection(&paths, &mut used_names);
-                        validate_no_unused_names(ean, &used_names)?;
-                    }
+            if let Some(ref paths) = paths {
+                if !names.is_empty() {
+                    let mut used_names = HashSet::new();
+                    collect_names_from_projection(paths, &mut used_names);
+                    validate_no_unused_names(&names, &used_names)?;
                 }
             }
 
@@ -1870,18 +2175,13 @@ impl RustackDynamoDB {
                     .map_err(storage_error_to_dynamodb)?;
                 if let Some(item) = table.storage.get_item(&pk) {
                     // Apply projection if specified.
-                    let item = if let Some(ref proj) = effective_projection {
-                        let paths = parse_projection(proj).map_err(projection_error_to_dynamodb)?;
-                        let names = keys_and_attrs
-                            .expression_attribute_names
-                            .clone()
-                            .unwrap_or_default();
+                    let item = if let Some(ref paths) = paths {
                         let ctx = EvalContext {
                             item: &item,
                             names: &names,
                             values: &HashMap::new(),
                         };
-                        ctx.apply_projection(&paths)
+                        ctx.apply_projection(paths)
                     } else {
                         item
                     };
@@ -1901,11 +2201,29 @@ impl RustackDynamoDB {
     }
 
     /// Handle `BatchWriteItem`.
-    #[allow(clippy::needless_pass_by_value)]
+    #[allow(clippy::needless_pass_by_value, clippy::too_many_lines)]
     pub fn handle_batch_write_item(
         &self,
         input: BatchWriteItemInput,
     ) -> Result<BatchWriteItemOutput, DynamoDBError> {
+        // Reject enum values outside the documented value sets.
+        validate_enum_value(
+            "returnConsumedCapacity",
+            input
+                .return_consumed_capacity
+                .as_ref()
+                .map(ReturnConsumedCapacity::as_str),
+            ReturnConsumedCapacity::VALUES,
+        )?;
+        validate_enum_value(
+            "returnItemCollectionMetrics",
+            input
+                .return_item_collection_metrics
+                .as_ref()
+                .map(ReturnItemCollectionMetrics::as_str),
+            ReturnItemCollectionMetrics::VALUES,
+        )?;
+
         // Enforce 25-item limit across all tables.
         let total_writes: usize = input.request_items.values().map(Vec::len).sum();
         if total_writes > 25 {
@@ -1915,9 +2233,29 @@ impl RustackDynamoDB {
             )));
         }
 
-        // Validation pass: validate all items before writing any (atomic failure).
+        // The request as a whole is bounded before any item is looked at.
+        let request_size: u64 = input
+            .request_items
+            .values()
+            .flatten()
+            .filter_map(|wr| {
+                wr.put_request
+                    .as_ref()
+                    .map(|p| &p.item)
+                    .or(wr.delete_request.as_ref().map(|d| &d.key))
+            })
+            .map(calculate_item_size)
+            .sum();
+        if request_size > MAX_BATCH_WRITE_BYTES {
+            return Err(DynamoDBError::validation(format!(
+                "Request size has exceeded the maximum allowed size of {MAX_BATCH_WRITE_BYTES} \
+                 bytes"
+            )));
+        }
+
+        // Validation pass: a malformed request fails as a whole before any write.
         for (table_name, write_requests) in &input.request_items {
-            let table = self.state.require_table(table_name)?;
+            let table = self.state.require_active_table(table_name)?;
 
             // Detect duplicate keys within this table's write requests.
             let key_items: Vec<&HashMap<String, AttributeValue>> = write_requests
@@ -1934,6 +2272,8 @@ impl RustackDynamoDB {
             for wr in write_requests {
                 if let Some(ref put) = wr.put_request {
                     validate_key_not_empty(&table.key_schema, &put.item)?;
+                    validate_item_sets(&put.item)?;
+                    validate_item_nesting(&put.item)?;
                     let size = calculate_item_size(&put.item);
                     if size > MAX_ITEM_SIZE_BYTES {
                         return Err(DynamoDBError::validation(format!(
@@ -1950,16 +2290,32 @@ impl RustackDynamoDB {
                 }
             }
         }
-
-        // Execution pass: all validations passed, now execute writes.
-        for (table_name, write_requests) in &input.request_items {
-            let table = self.state.require_table(table_name)?;
+        // Execution pass: each write succeeds or is handed back on its own;
+        // the batch is not atomic. Tables are visited in name order so the
+        // forced unprocessed requests are the same on every run.
+        let mut unprocessed_items: HashMap<String, Vec<WriteRequest>> = HashMap::new();
+        let mut forced = self
+            .behavior()
+            .batch_write_unprocessed
+            .min(total_writes.saturating_sub(1));
+        let mut tables: Vec<_> = input.request_items.iter().collect();
+        tables.sort_by_key(|(table_name, _)| *table_name);
+        for (table_name, write_requests) in tables {
+            let table = self.state.require_active_table(table_name)?;
             let stream_enabled = table
                 .stream_specification
                 .as_ref()
                 .is_some_and(|s| s.stream_enabled);
 
             for wr in write_requests {
+                if forced > 0 {
+                    forced -= 1;
+                    unprocessed_items
+                        .entry(table_name.clone())
+                        .or_default()
+                        .push(wr.clone());
+                    continue;
+                }
                 if let Some(ref put) = wr.put_request {
                     let old = table
                         .storage
@@ -2007,7 +2363,7 @@ impl RustackDynamoDB {
         }
 
         Ok(BatchWriteItemOutput {
-            unprocessed_items: HashMap::new(),
+            unprocessed_items,
             item_collection_metrics: HashMap::new(),
             consumed_capacity: Vec::new(),
         })
@@ -2203,13 +2559,20 @@ impl RustackDynamoDB {
 
     /// Handle `DescribeEndpoints`.
     ///
-    /// Returns a single endpoint for the configured region with a 1440-minute
-    /// (24 hour) cache period, matching the real DynamoDB behaviour.
+    /// Returns a single endpoint with a 1440-minute (24 hour) cache period,
+    /// matching the real DynamoDB behaviour. The endpoint is `host`, the
+    /// address the client reached this server at, so SDKs with endpoint
+    /// discovery enabled keep talking to Rustack. Without a host the regional
+    /// AWS address is returned.
     pub fn handle_describe_endpoints(
         &self,
         _input: DescribeEndpointsInput,
+        host: Option<&str>,
     ) -> Result<DescribeEndpointsOutput, DynamoDBError> {
-        let address = format!("dynamodb.{}.amazonaws.com", self.config.default_region);
+        let address = host.map_or_else(
+            || format!("dynamodb.{}.amazonaws.com", self.scope.region),
+            str::to_owned,
+        );
         Ok(DescribeEndpointsOutput {
             endpoints: vec![Endpoint {
                 address,
@@ -2219,6 +2582,648 @@ impl RustackDynamoDB {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Exports
+// ---------------------------------------------------------------------------
+
+/// Maximum number of exports a single `ListExports` page returns.
+const MAX_LIST_EXPORTS_RESULTS: i32 = 25;
+
+impl RustackDynamoDB {
+    /// Set the writer that stores exports in S3.
+    ///
+    /// Called by the server binary to wire in the S3 provider.
+    pub fn set_export_writer(&mut self, writer: Arc<dyn ExportWriter>) {
+        self.export_writer = writer;
+    }
+
+    /// Handle `ExportTableToPointInTime`.
+    ///
+    /// The table's items are copied synchronously and written to S3 on a
+    /// background task; the export reports `IN_PROGRESS` until that task
+    /// finishes. The export always contains the table's current items.
+    /// `ExportTime` is validated against the table's lifetime and reported
+    /// as given.
+    #[allow(clippy::too_many_lines)]
+    pub fn handle_export_table_to_point_in_time(
+        &self,
+        input: ExportTableToPointInTimeInput,
+    ) -> Result<ExportTableToPointInTimeOutput, DynamoDBError> {
+        if input
+            .export_format
+            .as_ref()
+            .is_some_and(|format| *format != ExportFormat::DynamodbJson)
+        {
+            return Err(DynamoDBError::validation(
+                "Only the DYNAMODB_JSON export format is supported",
+            ));
+        }
+        if input.export_type == Some(ExportType::IncrementalExport) {
+            return Err(DynamoDBError::validation(
+                "Only FULL_EXPORT exports are supported",
+            ));
+        }
+        if input.s3_bucket.is_empty() {
+            return Err(DynamoDBError::validation(
+                "1 validation error detected: Value at 's3Bucket' failed to satisfy constraint: \
+                 Member must not be null",
+            ));
+        }
+
+        let table_not_found = || {
+            DynamoDBError::with_message(
+                DynamoDBErrorCode::TableNotFoundException,
+                format!("Table not found: {}", input.table_arn),
+            )
+        };
+        let table_name = Self::resolve_table_from_arn(&input.table_arn)?;
+        let table = self
+            .state
+            .require_active_table(table_name)
+            .ok()
+            .filter(|table| table.arn == input.table_arn)
+            .ok_or_else(table_not_found)?;
+
+        let prefix = input
+            .s3_prefix
+            .as_deref()
+            .map(|p| p.trim_end_matches('/'))
+            .filter(|p| !p.is_empty())
+            .map(ToOwned::to_owned);
+
+        if let Some(token) = input.client_token.as_deref() {
+            let existing = self
+                .state
+                .list_exports()
+                .into_iter()
+                .find(|e| e.client_token.as_deref() == Some(token));
+            if let Some(existing) = existing {
+                if existing.table_arn.as_deref() == Some(table.arn.as_str())
+                    && existing.s3_bucket.as_deref() == Some(input.s3_bucket.as_str())
+                    && existing.s3_prefix == prefix
+                {
+                    return Ok(ExportTableToPointInTimeOutput {
+                        export_description: Some(existing),
+                    });
+                }
+                return Err(DynamoDBError::with_message(
+                    DynamoDBErrorCode::ExportConflictException,
+                    format!(
+                        "There is already an export with client token {token} and different \
+                         parameters"
+                    ),
+                ));
+            }
+        }
+
+        let start_time = chrono::Utc::now();
+        let export_time = match input.export_time {
+            Some(seconds) => {
+                #[allow(clippy::cast_possible_truncation)]
+                let millis = (seconds * 1000.0).round() as i64;
+                chrono::DateTime::from_timestamp_millis(millis)
+                    .filter(|t| *t >= table.created_at && *t <= start_time)
+                    .ok_or_else(|| {
+                        DynamoDBError::with_message(
+                            DynamoDBErrorCode::InvalidExportTimeException,
+                            "Export time must be between the table's creation time and now",
+                        )
+                    })?
+            }
+            None => start_time,
+        };
+
+        let runtime = tokio::runtime::Handle::try_current().map_err(|_| {
+            DynamoDBError::internal_error("Exports require a running async runtime")
+        })?;
+
+        let export_id = format!(
+            "{:014}-{}",
+            start_time.timestamp_millis(),
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        );
+        let snapshot = ExportSnapshot {
+            export_arn: format!("{}/export/{export_id}", table.arn),
+            export_id,
+            table_arn: table.arn.clone(),
+            table_id: table.table_id.clone(),
+            account: self.scope.account.clone(),
+            bucket: input.s3_bucket.clone(),
+            prefix,
+            sse_algorithm: input
+                .s3_sse_algorithm
+                .clone()
+                .unwrap_or_else(|| "AES256".to_owned()),
+            sse_kms_key_id: input.s3_sse_kms_key_id.clone(),
+            start_time,
+            export_time,
+            billed_size_bytes: table.storage.total_size_bytes(),
+            items: table.storage.snapshot(),
+        };
+
+        let description = ExportDescription {
+            export_arn: Some(snapshot.export_arn.clone()),
+            export_status: Some(ExportStatus::InProgress),
+            start_time: Some(epoch_seconds(start_time)),
+            export_manifest: Some(snapshot.manifest_summary_key()),
+            table_arn: Some(snapshot.table_arn.clone()),
+            table_id: Some(snapshot.table_id.clone()),
+            export_time: Some(epoch_seconds(export_time)),
+            client_token: input.client_token,
+            s3_bucket: Some(snapshot.bucket.clone()),
+            s3_bucket_owner: input.s3_bucket_owner,
+            s3_prefix: snapshot.prefix.clone(),
+            s3_sse_algorithm: Some(snapshot.sse_algorithm.clone()),
+            s3_sse_kms_key_id: snapshot.sse_kms_key_id.clone(),
+            export_format: Some(ExportFormat::DynamodbJson),
+            export_type: Some(ExportType::FullExport),
+            ..Default::default()
+        };
+        self.state.put_export(description.clone());
+
+        let state = Arc::clone(&self.state);
+        let writer = Arc::clone(&self.export_writer);
+        runtime.spawn(async move {
+            let result = snapshot.write(writer.as_ref()).await;
+            state.update_export(&snapshot.export_arn, |export| match result {
+                Ok(end_time) => {
+                    export.export_status = Some(ExportStatus::Completed);
+                    export.end_time = Some(epoch_seconds(end_time));
+                    export.item_count = i64::try_from(snapshot.items.len()).ok();
+                    export.billed_size_bytes = i64::try_from(snapshot.billed_size_bytes).ok();
+                }
+                Err(e) => {
+                    tracing::warn!(export_arn = %snapshot.export_arn, error = %e, "table export failed");
+                    export.export_status = Some(ExportStatus::Failed);
+                    export.end_time = Some(epoch_seconds(chrono::Utc::now()));
+                    export.failure_code = Some(e.failure_code().to_owned());
+                    export.failure_message = Some(e.to_string());
+                }
+            });
+        });
+
+        Ok(ExportTableToPointInTimeOutput {
+            export_description: Some(description),
+        })
+    }
+
+    /// Handle `DescribeExport`.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn handle_describe_export(
+        &self,
+        input: DescribeExportInput,
+    ) -> Result<DescribeExportOutput, DynamoDBError> {
+        let export = self.state.get_export(&input.export_arn).ok_or_else(|| {
+            DynamoDBError::with_message(
+                DynamoDBErrorCode::ExportNotFoundException,
+                format!("Export not found: {}", input.export_arn),
+            )
+        })?;
+        Ok(DescribeExportOutput {
+            export_description: Some(export),
+        })
+    }
+
+    /// Handle `ListExports`.
+    ///
+    /// `NextToken` is the ARN of the last export on the previous page.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn handle_list_exports(
+        &self,
+        input: ListExportsInput,
+    ) -> Result<ListExportsOutput, DynamoDBError> {
+        let max_results = input.max_results.unwrap_or(MAX_LIST_EXPORTS_RESULTS);
+        if !(1..=MAX_LIST_EXPORTS_RESULTS).contains(&max_results) {
+            return Err(DynamoDBError::validation(format!(
+                "1 validation error detected: Value '{max_results}' at 'maxResults' failed to \
+                 satisfy constraint: Member must have value less than or equal to \
+                 {MAX_LIST_EXPORTS_RESULTS}"
+            )));
+        }
+        let max_results = usize::try_from(max_results).unwrap_or(1);
+
+        let exports: Vec<ExportDescription> = self
+            .state
+            .list_exports()
+            .into_iter()
+            .filter(|e| {
+                input
+                    .table_arn
+                    .as_deref()
+                    .is_none_or(|arn| e.table_arn.as_deref() == Some(arn))
+            })
+            .collect();
+
+        let start_idx = match input.next_token.as_deref() {
+            Some(token) => exports
+                .iter()
+                .position(|e| e.export_arn.as_deref() == Some(token))
+                .map(|idx| idx + 1)
+                .ok_or_else(|| DynamoDBError::validation("Invalid NextToken"))?,
+            None => 0,
+        };
+
+        let page: Vec<&ExportDescription> =
+            exports.iter().skip(start_idx).take(max_results).collect();
+        let next_token = (start_idx + page.len() < exports.len())
+            .then(|| page.last().and_then(|e| e.export_arn.clone()))
+            .flatten();
+
+        Ok(ListExportsOutput {
+            export_summaries: page
+                .into_iter()
+                .map(|e| ExportSummary {
+                    export_arn: e.export_arn.clone(),
+                    export_status: e.export_status.clone(),
+                    export_type: e.export_type.clone(),
+                })
+                .collect(),
+            next_token,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Backups
+// ---------------------------------------------------------------------------
+
+/// Maximum number of backups a single `ListBackups` page returns.
+const MAX_LIST_BACKUPS_LIMIT: i32 = 100;
+
+impl RustackDynamoDB {
+    /// Handle `CreateBackup`.
+    ///
+    /// Copies the table's schema and items into an in-memory backup, which is
+    /// `AVAILABLE` immediately.
+    pub fn handle_create_backup(
+        &self,
+        input: CreateBackupInput,
+    ) -> Result<CreateBackupOutput, DynamoDBError> {
+        if input.backup_name.len() < 3
+            || input.backup_name.len() > 255
+            || !input
+                .backup_name
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'.' || b == b'_' || b == b'-')
+        {
+            return Err(DynamoDBError::validation(format!(
+                "1 validation error detected: Value '{}' at 'backupName' failed to satisfy \
+                 constraint: Member must satisfy regular expression pattern: [a-zA-Z0-9_.-]+ and \
+                 have length between 3 and 255",
+                input.backup_name
+            )));
+        }
+
+        let table = self.state.get_table(&input.table_name).ok_or_else(|| {
+            DynamoDBError::with_message(
+                DynamoDBErrorCode::TableNotFoundException,
+                format!("Table not found: {}", input.table_name),
+            )
+        })?;
+        if table.status() != TableStatus::Active {
+            return Err(DynamoDBError::with_message(
+                DynamoDBErrorCode::TableInUseException,
+                format!("Table is not in ACTIVE state: {}", table.name),
+            ));
+        }
+
+        let now = chrono::Utc::now();
+        let arn = format!(
+            "{}/backup/{:014}-{}",
+            table.arn,
+            now.timestamp_millis(),
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        );
+        let backup =
+            self.state
+                .put_backup(TableBackup::capture(&table, arn, input.backup_name, now));
+
+        Ok(CreateBackupOutput {
+            backup_details: Some(backup.to_details(BackupStatus::Available)),
+        })
+    }
+
+    /// Handle `DescribeBackup`.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn handle_describe_backup(
+        &self,
+        input: DescribeBackupInput,
+    ) -> Result<DescribeBackupOutput, DynamoDBError> {
+        let backup = self.state.require_backup(&input.backup_arn)?;
+        Ok(DescribeBackupOutput {
+            backup_description: Some(backup.to_description(BackupStatus::Available)),
+        })
+    }
+
+    /// Handle `DeleteBackup`.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn handle_delete_backup(
+        &self,
+        input: DeleteBackupInput,
+    ) -> Result<DeleteBackupOutput, DynamoDBError> {
+        let backup = self.state.delete_backup(&input.backup_arn)?;
+        Ok(DeleteBackupOutput {
+            backup_description: Some(backup.to_description(BackupStatus::Deleted)),
+        })
+    }
+
+    /// Handle `ListBackups`.
+    ///
+    /// `TimeRangeLowerBound` is inclusive and `TimeRangeUpperBound` exclusive.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn handle_list_backups(
+        &self,
+        input: ListBackupsInput,
+    ) -> Result<ListBackupsOutput, DynamoDBError> {
+        let limit = input.limit.unwrap_or(MAX_LIST_BACKUPS_LIMIT);
+        if !(1..=MAX_LIST_BACKUPS_LIMIT).contains(&limit) {
+            return Err(DynamoDBError::validation(format!(
+                "1 validation error detected: Value '{limit}' at 'limit' failed to satisfy \
+                 constraint: Member must have value less than or equal to {MAX_LIST_BACKUPS_LIMIT}"
+            )));
+        }
+        let limit = usize::try_from(limit).unwrap_or(1);
+        let type_filter = input.backup_type.unwrap_or_default();
+
+        let backups: Vec<Arc<TableBackup>> = self
+            .state
+            .list_backups()
+            .into_iter()
+            .filter(|b| {
+                let created = epoch_seconds(b.created_at);
+                type_filter.matches(&BackupType::User)
+                    && input
+                        .table_name
+                        .as_deref()
+                        .is_none_or(|name| b.table_name == name)
+                    && input
+                        .time_range_lower_bound
+                        .is_none_or(|lower| created >= lower)
+                    && input
+                        .time_range_upper_bound
+                        .is_none_or(|upper| created < upper)
+            })
+            .collect();
+
+        let start_idx = match input.exclusive_start_backup_arn.as_deref() {
+            Some(start) => backups
+                .iter()
+                .position(|b| b.arn == start)
+                .map_or(0, |idx| idx + 1),
+            None => 0,
+        };
+
+        let page: Vec<&Arc<TableBackup>> = backups.iter().skip(start_idx).take(limit).collect();
+        let last_evaluated_backup_arn = (start_idx + page.len() < backups.len())
+            .then(|| page.last().map(|b| b.arn.clone()))
+            .flatten();
+
+        Ok(ListBackupsOutput {
+            backup_summaries: page.into_iter().map(|b| b.to_summary()).collect(),
+            last_evaluated_backup_arn,
+        })
+    }
+
+    /// Handle `RestoreTableFromBackup`.
+    ///
+    /// Creates `TargetTableName` with the backup's key schema, indexes, and
+    /// items, unless overridden. Like DynamoDB, stream settings, TTL, and tags
+    /// are not restored.
+    pub fn handle_restore_table_from_backup(
+        &self,
+        input: RestoreTableFromBackupInput,
+    ) -> Result<RestoreTableFromBackupOutput, DynamoDBError> {
+        validate_table_name(&input.target_table_name)?;
+        let backup = self.state.require_backup(&input.backup_arn)?;
+        let table = self.restore_table(
+            &input.target_table_name,
+            &backup,
+            RestoreOverrides {
+                billing_mode: input.billing_mode_override,
+                provisioned_throughput: input.provisioned_throughput_override,
+                gsi_definitions: input.global_secondary_index_override,
+                lsi_definitions: input.local_secondary_index_override,
+                sse_specification: input.sse_specification_override,
+            },
+        )?;
+        Ok(RestoreTableFromBackupOutput {
+            table_description: Some(table.to_description()),
+        })
+    }
+
+    /// Create `target_table_name` from `source`, applying `overrides`.
+    fn restore_table(
+        &self,
+        target_table_name: &str,
+        source: &TableBackup,
+        overrides: RestoreOverrides,
+    ) -> Result<Arc<DynamoDBTable>, DynamoDBError> {
+        let table_already_exists = || {
+            DynamoDBError::with_message(
+                DynamoDBErrorCode::TableAlreadyExistsException,
+                format!("Table already exists: {target_table_name}"),
+            )
+        };
+        if self.state.get_table(target_table_name).is_some() {
+            return Err(table_already_exists());
+        }
+
+        let billing_mode = overrides
+            .billing_mode
+            .unwrap_or_else(|| source.billing_mode.clone());
+        let provisioned_throughput = match (&billing_mode, overrides.provisioned_throughput) {
+            (BillingMode::PayPerRequest, _) => None,
+            (_, Some(throughput)) => Some(throughput),
+            (_, None) => source.provisioned_throughput.clone(),
+        };
+        let billing_mode =
+            validate_billing_mode(Some(&billing_mode), provisioned_throughput.as_ref())?;
+
+        let key_schema =
+            parse_key_schema(&source.key_schema_elements, &source.attribute_definitions)?;
+        let storage = TableStorage::new(key_schema.clone());
+        for item in &source.items {
+            storage
+                .put_item(item.clone())
+                .map_err(storage_error_to_dynamodb)?;
+        }
+
+        let created_at = chrono::Utc::now();
+        let table = DynamoDBTable {
+            name: target_table_name.to_owned(),
+            key_schema_elements: source.key_schema_elements.clone(),
+            key_schema,
+            attribute_definitions: source.attribute_definitions.clone(),
+            billing_mode,
+            provisioned_throughput,
+            gsi_definitions: overrides
+                .gsi_definitions
+                .unwrap_or_else(|| source.gsi_definitions.clone()),
+            lsi_definitions: overrides
+                .lsi_definitions
+                .unwrap_or_else(|| source.lsi_definitions.clone()),
+            stream_specification: None,
+            sse_specification: overrides
+                .sse_specification
+                .or_else(|| source.sse_specification.clone()),
+            tags: parking_lot::RwLock::new(Vec::new()),
+            ttl: parking_lot::RwLock::new(None),
+            arn: format!(
+                "arn:aws:dynamodb:{}:{}:table/{target_table_name}",
+                self.scope.region, self.scope.account,
+            ),
+            table_id: uuid::Uuid::new_v4().to_string(),
+            created_at,
+            active_at: created_at + self.behavior().table_create_delay,
+            deleted_at: parking_lot::RwLock::new(None),
+            point_in_time_recovery: parking_lot::RwLock::new(None),
+            storage,
+        };
+
+        self.state
+            .create_table(table)
+            .map_err(|_| table_already_exists())
+    }
+}
+
+/// Schema overrides shared by `RestoreTableFromBackup` and
+/// `RestoreTableToPointInTime`.
+struct RestoreOverrides {
+    billing_mode: Option<BillingMode>,
+    provisioned_throughput: Option<ProvisionedThroughput>,
+    gsi_definitions: Option<Vec<GlobalSecondaryIndex>>,
+    lsi_definitions: Option<Vec<LocalSecondaryIndex>>,
+    sse_specification: Option<SSESpecification>,
+}
+
+// ---------------------------------------------------------------------------
+// Continuous backups
+// ---------------------------------------------------------------------------
+
+impl RustackDynamoDB {
+    /// Handle `DescribeContinuousBackups`.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn handle_describe_continuous_backups(
+        &self,
+        input: DescribeContinuousBackupsInput,
+    ) -> Result<DescribeContinuousBackupsOutput, DynamoDBError> {
+        let table = self.state.require_table(&input.table_name)?;
+        Ok(DescribeContinuousBackupsOutput {
+            continuous_backups_description: Some(
+                table.continuous_backups_description(chrono::Utc::now()),
+            ),
+        })
+    }
+
+    /// Handle `UpdateContinuousBackups`.
+    ///
+    /// Only point-in-time recovery metadata is tracked; enabling it starts the
+    /// restorable window, and disabling it discards the window.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn handle_update_continuous_backups(
+        &self,
+        input: UpdateContinuousBackupsInput,
+    ) -> Result<UpdateContinuousBackupsOutput, DynamoDBError> {
+        let table = self.state.require_table(&input.table_name)?;
+        let spec = &input.point_in_time_recovery_specification;
+        let max_days = PointInTimeRecovery::MAX_RECOVERY_PERIOD_IN_DAYS;
+        let period = spec.recovery_period_in_days.unwrap_or(max_days);
+        if !(1..=max_days).contains(&period) {
+            return Err(DynamoDBError::validation(format!(
+                "1 validation error detected: Value '{period}' at \
+                 'pointInTimeRecoverySpecification.recoveryPeriodInDays' failed to satisfy \
+                 constraint: Member must have value between 1 and {max_days}"
+            )));
+        }
+
+        let now = chrono::Utc::now();
+        {
+            let mut pitr = table.point_in_time_recovery.write();
+            if spec.point_in_time_recovery_enabled {
+                let enabled_at = pitr.as_ref().map_or(now, |p| p.enabled_at);
+                *pitr = Some(PointInTimeRecovery {
+                    enabled_at,
+                    recovery_period_in_days: period,
+                });
+            } else {
+                *pitr = None;
+            }
+        }
+
+        Ok(UpdateContinuousBackupsOutput {
+            continuous_backups_description: Some(table.continuous_backups_description(now)),
+        })
+    }
+
+    /// Handle `RestoreTableToPointInTime`.
+    ///
+    /// Item history is not kept, so every restore clones the source table as
+    /// it is now; the requested time is only checked against the restorable
+    /// window.
+    pub fn handle_restore_table_to_point_in_time(
+        &self,
+        input: RestoreTableToPointInTimeInput,
+    ) -> Result<RestoreTableToPointInTimeOutput, DynamoDBError> {
+        validate_table_name(&input.target_table_name)?;
+        let source_name = match (&input.source_table_name, &input.source_table_arn) {
+            (Some(name), _) => name.as_str(),
+            (None, Some(arn)) => Self::resolve_table_from_arn(arn)?,
+            (None, None) => {
+                return Err(DynamoDBError::validation(
+                    "Either SourceTableName or SourceTableArn must be specified",
+                ));
+            }
+        };
+        let source = self.state.get_table(source_name).ok_or_else(|| {
+            DynamoDBError::with_message(
+                DynamoDBErrorCode::TableNotFoundException,
+                format!("Table not found: {source_name}"),
+            )
+        })?;
+
+        let now = chrono::Utc::now();
+        let Some((earliest, latest)) = source.restorable_window(now) else {
+            return Err(DynamoDBError::with_message(
+                DynamoDBErrorCode::PointInTimeRecoveryUnavailableException,
+                format!(
+                    "Point in time recovery is not enabled for table '{}'",
+                    source.name
+                ),
+            ));
+        };
+        if !input.use_latest_restorable_time.unwrap_or(false) {
+            let Some(restore_time) = input.restore_date_time else {
+                return Err(DynamoDBError::validation(
+                    "RestoreDateTime must be specified unless UseLatestRestorableTime is true",
+                ));
+            };
+            if restore_time < epoch_seconds(earliest) || restore_time > epoch_seconds(latest) {
+                return Err(DynamoDBError::with_message(
+                    DynamoDBErrorCode::InvalidRestoreTimeException,
+                    "RestoreDateTime must be between EarliestRestorableDateTime and \
+                     LatestRestorableDateTime",
+                ));
+            }
+        }
+
+        let snapshot = TableBackup::capture(&source, String::new(), String::new(), now);
+        let table = self.restore_table(
+            &input.target_table_name,
+            &snapshot,
+            RestoreOverrides {
+                billing_mode: input.billing_mode_override,
+                provisioned_throughput: input.provisioned_throughput_override,
+                gsi_definitions: input.global_secondary_index_override,
+                lsi_definitions: input.local_secondary_index_override,
+                sse_specification: input.sse_specification_override,
+            },
+        )?;
+        Ok(RestoreTableToPointInTimeOutput {
+            table_description: Some(table.to_description()),
+        })
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Transaction operations
 // ---------------------------------------------------------------------------
@@ -2233,6 +3238,16 @@ impl RustackDynamoDB {
         &self,
         input: TransactGetItemsInput,
     ) -> Result<TransactGetItemsOutput, DynamoDBError> {
+        // Reject enum values outside the documented value sets.
+        validate_enum_value(
+            "returnConsumedCapacity",
+            input
+                .return_consumed_capacity
+                .as_ref()
+                .map(ReturnConsumedCapacity::as_str),
+            ReturnConsumedCapacity::VALUES,
+        )?;
+
         if input.transact_items.is_empty() {
             return Err(DynamoDBError::validation(
                 "1 validation error detected: Value null at 'transactItems' failed to satisfy \
@@ -2251,7 +3266,7 @@ impl RustackDynamoDB {
 
         for transact_item in &input.transact_items {
             let get = &transact_item.get;
-            let table = self.state.require_table(&get.table_name)?;
+            let table = self.state.require_active_table(&get.table_name)?;
             let pk = extract_primary_key(&table.key_schema, &get.key)
                 .map_err(storage_error_to_dynamodb)?;
 
@@ -2263,8 +3278,7 @@ impl RustackDynamoDB {
                     let names = get.expression_attribute_names.as_ref();
                     let empty_names = HashMap::new();
                     let names_ref = names.unwrap_or(&empty_names);
-                    let paths =
-                        parse_projection(projection).map_err(projection_error_to_dynamodb)?;
+                    let paths = self.parse_projection_expr(projection, names_ref)?;
                     let empty_values = HashMap::new();
                     let ctx = EvalContext {
                         item: &found_item,
@@ -2297,6 +3311,24 @@ impl RustackDynamoDB {
         &self,
         input: TransactWriteItemsInput,
     ) -> Result<TransactWriteItemsOutput, DynamoDBError> {
+        // Reject enum values outside the documented value sets.
+        validate_enum_value(
+            "returnConsumedCapacity",
+            input
+                .return_consumed_capacity
+                .as_ref()
+                .map(ReturnConsumedCapacity::as_str),
+            ReturnConsumedCapacity::VALUES,
+        )?;
+        validate_enum_value(
+            "returnItemCollectionMetrics",
+            input
+                .return_item_collection_metrics
+                .as_ref()
+                .map(ReturnItemCollectionMetrics::as_str),
+            ReturnItemCollectionMetrics::VALUES,
+        )?;
+
         if input.transact_items.is_empty() {
             return Err(DynamoDBError::validation(
                 "1 validation error detected: Value null at 'transactItems' failed to satisfy \
@@ -2337,9 +3369,11 @@ impl RustackDynamoDB {
             let (table_name, key_map) = if let Some(ref cc) = item.condition_check {
                 (cc.table_name.as_str(), &cc.key)
             } else if let Some(ref put) = item.put {
-                let table = self.state.require_table(&put.table_name)?;
+                let table = self.state.require_active_table(&put.table_name)?;
                 let pk = extract_primary_key(&table.key_schema, &put.item)
                     .map_err(storage_error_to_dynamodb)?;
+                validate_item_sets(&put.item)?;
+                validate_item_nesting(&put.item)?;
                 if !seen_keys.insert((put.table_name.clone(), pk)) {
                     return Err(DynamoDBError::validation(
                         "Transaction request cannot include multiple operations on one item",
@@ -2354,7 +3388,7 @@ impl RustackDynamoDB {
                 continue;
             };
 
-            let table = self.state.require_table(table_name)?;
+            let table = self.state.require_active_table(table_name)?;
             let pk = extract_primary_key(&table.key_schema, key_map)
                 .map_err(storage_error_to_dynamodb)?;
             if !seen_keys.insert((table_name.to_owned(), pk)) {
@@ -2393,7 +3427,7 @@ impl RustackDynamoDB {
         // Phase 3: Apply all writes.
         for item in &input.transact_items {
             if let Some(ref put) = item.put {
-                let table = self.state.require_table(&put.table_name)?;
+                let table = self.state.require_active_table(&put.table_name)?;
                 let old = table
                     .storage
                     .put_item(put.item.clone())
@@ -2421,7 +3455,7 @@ impl RustackDynamoDB {
                     });
                 }
             } else if let Some(ref del) = item.delete {
-                let table = self.state.require_table(&del.table_name)?;
+                let table = self.state.require_active_table(&del.table_name)?;
                 let pk = extract_primary_key(&table.key_schema, &del.key)
                     .map_err(storage_error_to_dynamodb)?;
                 let old = table.storage.delete_item(&pk);
@@ -2445,7 +3479,7 @@ impl RustackDynamoDB {
                     }
                 }
             } else if let Some(ref upd) = item.update {
-                let table = self.state.require_table(&upd.table_name)?;
+                let table = self.state.require_active_table(&upd.table_name)?;
                 let pk = extract_primary_key(&table.key_schema, &upd.key)
                     .map_err(storage_error_to_dynamodb)?;
                 let existing = table.storage.get_item(&pk);
@@ -2458,8 +3492,7 @@ impl RustackDynamoDB {
                 let names_ref = names.unwrap_or(&empty_names);
                 let values_ref = values.unwrap_or(&empty_values);
 
-                let parsed =
-                    parse_update(&upd.update_expression).map_err(expression_error_to_dynamodb)?;
+                let parsed = self.parse_update_expr(&upd.update_expression)?;
                 let ctx = EvalContext {
                     item: &current,
                     names: names_ref,
@@ -2562,7 +3595,7 @@ impl RustackDynamoDB {
     ) -> Result<(), CancellationReason> {
         let table = self
             .state
-            .require_table(&put.table_name)
+            .require_active_table(&put.table_name)
             .map_err(|e| CancellationReason {
                 code: Some("ValidationException".to_owned()),
                 message: Some(e.message.clone()),
@@ -2590,13 +3623,15 @@ impl RustackDynamoDB {
             .as_ref()
             .unwrap_or(&empty_values);
 
-        let expr = parse_condition(condition).map_err(|e| CancellationReason {
-            code: Some("ValidationException".to_owned()),
-            message: Some(e.to_string()),
-            item: None,
-        })?;
-        let ctx = EvalContext {
-            item: item_ref,
+        let expr = self
+            .parse_condition_expr("ConditionExpression", condition)
+            .map_err(|e| CancellationReason {
+                code: Some("ValidationException".to_owned()),
+                message: Some(e.message),
+                item: None,
+            })?;
+        let ctx = EvalContext {
+            item: item_ref,
             names,
             values,
         };
@@ -2635,14 +3670,14 @@ impl RustackDynamoDB {
             return Ok(());
         };
 
-        let table = self
-            .state
-            .require_table(table_name)
-            .map_err(|e| CancellationReason {
-                code: Some("ValidationException".to_owned()),
-                message: Some(e.message.clone()),
-                item: None,
-            })?;
+        let table =
+            self.state
+                .require_active_table(table_name)
+                .map_err(|e| CancellationReason {
+                    code: Some("ValidationException".to_owned()),
+                    message: Some(e.message.clone()),
+                    item: None,
+                })?;
         let pk = extract_primary_key(&table.key_schema, key).map_err(|e| CancellationReason {
             code: Some("ValidationException".to_owned()),
             message: Some(e.to_string()),
@@ -2656,11 +3691,13 @@ impl RustackDynamoDB {
         let names = expression_names.unwrap_or(&empty_names);
         let values = expression_values.unwrap_or(&empty_values);
 
-        let expr = parse_condition(condition_str).map_err(|e| CancellationReason {
-            code: Some("ValidationException".to_owned()),
-            message: Some(e.to_string()),
-            item: None,
-        })?;
+        let expr = self
+            .parse_condition_expr("ConditionExpression", condition_str)
+            .map_err(|e| CancellationReason {
+                code: Some("ValidationException".to_owned()),
+                message: Some(e.message),
+                item: None,
+            })?;
         let ctx = EvalContext {
             item: item_ref,
             names,
@@ -3051,6 +4088,25 @@ fn merge_expression_values(
 ///
 /// DynamoDB does not allow empty sets (SS, NS, BS with zero elements).
 /// Validate an `Expected` map for correctness before converting to a condition expression.
+/// Validate that a request enum member holds one of its documented values.
+///
+/// The model keeps unrecognised values in each enum's `Unknown` variant;
+/// DynamoDB rejects them with a constraint violation naming the member.
+fn validate_enum_value(
+    member: &str,
+    value: Option<&str>,
+    allowed: &[&str],
+) -> Result<(), DynamoDBError> {
+    match value {
+        Some(value) if !allowed.contains(&value) => Err(DynamoDBError::validation(format!(
+            "1 validation error detected: Value '{value}' at '{member}' failed to satisfy \
+             constraint: Member must satisfy enum value set: [{}]",
+            allowed.join(", ")
+        ))),
+        _ => Ok(()),
+    }
+}
+
 /// Validate that `ConditionalOperator` is only used when `Expected` has
 /// conditions.  DynamoDB rejects `ConditionalOperator` when `Expected` is
 /// missing or empty.
@@ -3234,7 +4290,14 @@ fn is_scalar_attribute_value(val: &AttributeValue) -> bool {
     )
 }
 
-fn validate_no_empty_sets(values: &HashMap<String, AttributeValue>) -> Result<(), DynamoDBError> {
+/// Validate that expression attribute values hold no empty sets, and no
+/// empty strings or binaries inside a set.
+///
+/// Empty strings and binaries are valid values on their own, for example to
+/// compare an attribute against `""`.
+fn validate_expression_value_sets(
+    values: &HashMap<String, AttributeValue>,
+) -> Result<(), DynamoDBError> {
     for (key, val) in values {
         if is_empty_set(val) {
             return Err(DynamoDBError::validation(format!(
@@ -3242,10 +4305,51 @@ fn validate_no_empty_sets(values: &HashMap<String, AttributeValue>) -> Result<()
                  the ExpressionAttributeValues ({key}) contains an empty set"
             )));
         }
+        if let Some(kind) = set_with_empty_member(val) {
+            return Err(DynamoDBError::validation(format!(
+                "One or more parameter values are not valid. The AttributeValue for a member of \
+                 the ExpressionAttributeValues ({key}) contains an empty {kind} set member"
+            )));
+        }
     }
     Ok(())
 }
 
+/// Check a write's condition against the item it targets.
+///
+/// Returns `ConditionalCheckFailedException`, carrying the item when
+/// `return_on_failure` is `ALL_OLD`, if the condition does not hold.
+fn check_write_condition(
+    condition: Option<&Expr>,
+    existing: Option<&HashMap<String, AttributeValue>>,
+    names: &HashMap<String, String>,
+    values: &HashMap<String, AttributeValue>,
+    return_on_failure: Option<&str>,
+) -> Result<(), DynamoDBError> {
+    let Some(condition) = condition else {
+        return Ok(());
+    };
+    let empty = HashMap::new();
+    let ctx = EvalContext {
+        item: existing.unwrap_or(&empty),
+        names,
+        values,
+    };
+    if ctx
+        .evaluate(condition)
+        .map_err(expression_error_to_dynamodb)?
+    {
+        return Ok(());
+    }
+    let mut err = DynamoDBError::conditional_check_failed("The conditional request failed");
+    if return_on_failure == Some("ALL_OLD") {
+        if let Some(existing_item) = existing {
+            err = err.with_item(existing_item.clone());
+        }
+    }
+    Err(err)
+}
+
 /// Reject an empty condition expression.
 fn validate_condition_not_empty(condition: Option<&str>) -> Result<(), DynamoDBError> {
     if let Some(cond) = condition {
@@ -3298,10 +4402,11 @@ fn validate_filter_no_key_attrs(
     Ok(())
 }
 
-/// Validate that an item's attributes do not contain empty sets.
-fn validate_item_no_empty_sets(
-    item: &HashMap<String, AttributeValue>,
-) -> Result<(), DynamoDBError> {
+/// Validate that an item's sets, including nested ones, are not empty and
+/// have no empty string or binary members.
+///
+/// Empty strings and binaries are otherwise valid non-key attribute values.
+fn validate_item_sets(item: &HashMap<String, AttributeValue>) -> Result<(), DynamoDBError> {
     for val in item.values() {
         if contains_empty_set(val) {
             return Err(DynamoDBError::validation(
@@ -3309,10 +4414,28 @@ fn validate_item_no_empty_sets(
                  set is empty",
             ));
         }
+        if let Some(kind) = set_with_empty_member(val) {
+            return Err(DynamoDBError::validation(format!(
+                "One or more parameter values were invalid: An {kind} set may not have a empty \
+                 {kind} as a member"
+            )));
+        }
     }
     Ok(())
 }
 
+/// The kind of set (`"string"` or `"binary"`) in `val`, including nested
+/// sets, that has an empty member, if any.
+fn set_with_empty_member(val: &AttributeValue) -> Option<&'static str> {
+    match val {
+        AttributeValue::Ss(members) if members.iter().any(String::is_empty) => Some("string"),
+        AttributeValue::Bs(members) if members.iter().any(bytes::Bytes::is_empty) => Some("binary"),
+        AttributeValue::L(list) => list.iter().find_map(set_with_empty_member),
+        AttributeValue::M(map) => map.values().find_map(set_with_empty_member),
+        _ => None,
+    }
+}
+
 /// Check if an `AttributeValue` is an empty set.
 fn is_empty_set(val: &AttributeValue) -> bool {
     matches!(val, AttributeValue::Ss(v) if v.is_empty())
@@ -3408,77 +4531,6 @@ fn resolve_path_top_name(path: &AttributePath, names: &HashMap<String, String>)
     }
 }
 
-/// Resolve all path elements to concrete names/indices for comparison.
-fn resolve_path_elements(
-    path: &AttributePath,
-    names: &HashMap<String, String>,
-) -> Vec<ResolvedPathElement> {
-    path.elements
-        .iter()
-        .map(|elem| match elem {
-            PathElement::Attribute(name) => {
-                let resolved = if name.starts_with('#') {
-                    names
-                        .get(name.as_str())
-                        .cloned()
-                        .unwrap_or_else(|| name.clone())
-                } else {
-                    name.clone()
-                };
-                ResolvedPathElement::Attribute(resolved)
-            }
-            PathElement::Index(idx) => ResolvedPathElement::Index(*idx),
-        })
-        .collect()
-}
-
-/// A resolved path element (after `#name` substitution).
-#[derive(Debug, Clone, PartialEq, Eq)]
-enum ResolvedPathElement {
-    /// A resolved attribute name.
-    Attribute(String),
-    /// A list index.
-    Index(usize),
-}
-
-/// Check whether two resolved paths overlap (one is a prefix of the other or they are equal).
-fn paths_overlap(a: &[ResolvedPathElement], b: &[ResolvedPathElement]) -> bool {
-    let min_len = a.len().min(b.len());
-    for i in 0..min_len {
-        if a[i] != b[i] {
-            return false;
-        }
-    }
-    true
-}
-
-/// Check whether two resolved paths conflict (one uses dot access and the other uses
-/// index access at the same position).
-fn paths_conflict(a: &[ResolvedPathElement], b: &[ResolvedPathElement]) -> bool {
-    let min_len = a.len().min(b.len());
-    for i in 0..min_len {
-        match (&a[i], &b[i]) {
-            // Same attribute or same index: continue
-            (ResolvedPathElement::Attribute(na), ResolvedPathElement::Attribute(nb)) => {
-                if na != nb {
-                    return false;
-                }
-            }
-            (ResolvedPathElement::Index(ia), ResolvedPathElement::Index(ib)) => {
-                if ia != ib {
-                    return false;
-                }
-            }
-            // One uses dot, other uses index at same position: conflict
-            (ResolvedPathElement::Attribute(_), ResolvedPathElement::Index(_))
-            | (ResolvedPathElement::Index(_), ResolvedPathElement::Attribute(_)) => {
-                return true;
-            }
-        }
-    }
-    false
-}
-
 /// Validate update expression paths: reject key attribute modifications and overlapping paths.
 fn validate_update_paths(
     update: &UpdateExpr,
@@ -3486,19 +4538,7 @@ fn validate_update_paths(
     names: &HashMap<String, String>,
 ) -> Result<(), DynamoDBError> {
     // Collect all target paths from the update expression.
-    let mut all_paths: Vec<&AttributePath> = Vec::new();
-    for action in &update.set_actions {
-        all_paths.push(&action.path);
-    }
-    for path in &update.remove_paths {
-        all_paths.push(path);
-    }
-    for action in &update.add_actions {
-        all_paths.push(&action.path);
-    }
-    for action in &update.delete_actions {
-        all_paths.push(&action.path);
-    }
+    let all_paths = update.target_paths();
 
     // Check that no path targets a key attribute (top-level only).
     let key_names: Vec<&str> = {
@@ -3527,24 +4567,17 @@ fn validate_update_paths(
         .map(|p| resolve_path_elements(p, names))
         .collect();
 
-    for i in 0..resolved.len() {
-        for j in (i + 1)..resolved.len() {
-            if paths_overlap(&resolved[i], &resolved[j]) {
-                return Err(DynamoDBError::validation(
-                    "Invalid UpdateExpression: Two document paths overlap with each other; must \
-                     remove or rewrite one of these paths",
-                ));
-            }
-            if paths_conflict(&resolved[i], &resolved[j]) {
-                return Err(DynamoDBError::validation(
-                    "Invalid UpdateExpression: Two document paths conflict with each other; must \
-                     remove or rewrite one of these paths",
-                ));
-            }
-        }
+    match find_path_clash(&resolved) {
+        Some((_, _, PathClash::Overlap)) => Err(DynamoDBError::validation(
+            "Invalid UpdateExpression: Two document paths overlap with each other; must remove or \
+             rewrite one of these paths",
+        )),
+        Some((_, _, PathClash::Conflict)) => Err(DynamoDBError::validation(
+            "Invalid UpdateExpression: Two document paths conflict with each other; must remove \
+             or rewrite one of these paths",
+        )),
+        None => Ok(()),
     }
-
-    Ok(())
 }
 
 // ---------------------------------------------------------------------------
@@ -3584,20 +4617,11 @@ fn collect_update_target_paths(
     update: &UpdateExpr,
     names: &HashMap<String, String>,
 ) -> Vec<Vec<ResolvedPathElement>> {
-    let mut paths = Vec::new();
-    for action in &update.set_actions {
-        paths.push(resolve_path_elements(&action.path, names));
-    }
-    for path in &update.remove_paths {
-        paths.push(resolve_path_elements(path, names));
-    }
-    for action in &update.add_actions {
-        paths.push(resolve_path_elements(&action.path, names));
-    }
-    for action in &update.delete_actions {
-        paths.push(resolve_path_elements(&action.path, names));
-    }
-    paths
+    update
+        .target_paths()
+        .into_iter()
+        .map(|path| resolve_path_elements(path, names))
+        .collect()
 }
 
 /// Look up the value at a resolved path within an item. Returns `None` if
@@ -4481,6 +5505,84 @@ fn to_sortable(
     SortableAttributeValue::from_attribute_value(attr_name, val).map_err(storage_error_to_dynamodb)
 }
 
+/// The items of one Query or Scan page, gathered as storage visits them.
+///
+/// The filter and projection run on each stored item in place, so only the
+/// items the page returns are copied, and none are for `Select=COUNT`.
+struct PageItems<'a> {
+    filter: Option<Expr>,
+    projection: Option<Vec<AttributePath>>,
+    names: &'a HashMap<String, String>,
+    values: &'a HashMap<String, AttributeValue>,
+    count_only: bool,
+    items: Vec<HashMap<String, AttributeValue>>,
+    count: usize,
+    scanned_count: usize,
+}
+
+impl<'a> PageItems<'a> {
+    fn new(
+        filter: Option<&str>,
+        projection: Option<&str>,
+        names: &'a HashMap<String, String>,
+        values: &'a HashMap<String, AttributeValue>,
+        count_only: bool,
+    ) -> Result<Self, DynamoDBError> {
+        Ok(Self {
+            filter: filter
+                .map(parse_condition)
+                .transpose()
+                .map_err(expression_error_to_dynamodb)?,
+            projection: projection
+                .map(parse_projection)
+                .transpose()
+                .map_err(projection_error_to_dynamodb)?,
+            names,
+            values,
+            count_only,
+            items: Vec::new(),
+            count: 0,
+            scanned_count: 0,
+        })
+    }
+
+    /// Evaluate one item, keeping it if it passes the filter.
+    fn visit(&mut self, item: &HashMap<String, AttributeValue>) -> Result<(), DynamoDBError> {
+        self.scanned_count += 1;
+        let ctx = EvalContext {
+            item,
+            names: self.names,
+            values: self.values,
+        };
+        if let Some(ref filter) = self.filter {
+            if !ctx.evaluate(filter).map_err(expression_error_to_dynamodb)? {
+                return Ok(());
+            }
+        }
+        self.count += 1;
+        if !self.count_only {
+            self.items.push(match self.projection {
+                Some(ref paths) => ctx.apply_projection(paths),
+                None => item.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    fn count(&self) -> i32 {
+        i32::try_from(self.count).unwrap_or(i32::MAX)
+    }
+
+    fn scanned_count(&self) -> i32 {
+        i32::try_from(self.scanned_count).unwrap_or(i32::MAX)
+    }
+
+    /// The page's items, or `None` for `Select=COUNT`.
+    fn into_items(self) -> Option<Vec<HashMap<String, AttributeValue>>> {
+        (!self.count_only).then_some(self.items)
+    }
+}
+
 /// Build a `last_evaluated_key` map from partition and optional sort key.
 fn build_last_evaluated_key(
     key_schema: &KeySchema,
@@ -4495,9 +5597,6 @@ fn build_last_evaluated_key(
     key
 }
 
-/// Maximum response size for a single Query/Scan response (1 MB).
-const MAX_RESPONSE_BYTES: u64 = 1_048_576;
-
 /// Result type for GSI query operations.
 type GsiQueryResult = (
     Vec<HashMap<String, AttributeValue>>,
@@ -4520,7 +5619,7 @@ fn query_gsi(
     partition_value: &AttributeValue,
     sort_condition: Option<&SortKeyCondition>,
     scan_forward: bool,
-    limit: Option<usize>,
+    limit: PageLimit,
     exclusive_start_key: &HashMap<String, AttributeValue>,
 ) -> GsiQueryResult {
     let gsi_pk_name = &gsi_key_schema.partition_key.name;
@@ -4540,13 +5639,13 @@ fn query_gsi(
         gsi_apply_pagination(
             &mut matching,
             exclusive_start_key,
-            gsi_pk_name,
             gsi_key_schema,
             &table.key_schema,
+            scan_forward,
         );
     }
 
-    // Apply limit and 1 MB size cap.
+    // Apply the page limit.
     let (selected, has_more) = gsi_apply_limit(matching, limit);
 
     // Build last_evaluated_key containing both GSI keys and table primary keys.
@@ -4562,6 +5661,8 @@ fn query_gsi(
 }
 
 /// Scan, filter by GSI partition key, apply sort condition, and sort items.
+///
+/// Only items with every index key attribute are in the index.
 fn gsi_filter_and_sort(
     table: &DynamoDBTable,
     gsi_key_schema: &KeySchema,
@@ -4570,108 +5671,95 @@ fn gsi_filter_and_sort(
     sort_condition: Option<&SortKeyCondition>,
     scan_forward: bool,
 ) -> Vec<HashMap<String, AttributeValue>> {
-    let (all_items, _) = table.storage.scan(None, None, None, None);
+    let mut in_partition = Vec::new();
+    table.storage.for_each_item(|item| {
+        if item.get(gsi_pk_name).is_some_and(|v| v == partition_value) {
+            in_partition.push(item.clone());
+        }
+    });
 
-    let mut matching: Vec<HashMap<String, AttributeValue>> = all_items
+    let mut matching: Vec<(IndexPosition, HashMap<String, AttributeValue>)> = in_partition
         .into_iter()
-        .filter(|item| item.get(gsi_pk_name).is_some_and(|v| v == partition_value))
-        .collect();
-
-    // Apply GSI sort key condition if present.
-    if let Some(sk_def) = &gsi_key_schema.sort_key {
-        if let Some(condition) = sort_condition {
-            matching.retain(|item| {
-                item.get(&sk_def.name)
-                    .and_then(|v| {
-                        SortableAttributeValue::from_attribute_value(&sk_def.name, v).ok()
-                    })
-                    .is_some_and(|sv| condition.matches(&sv))
-            });
-        }
-        // Sort by the GSI sort key.
-        matching.sort_by(|a, b| {
-            let a_sk = a
-                .get(&sk_def.name)
-                .and_then(|v| SortableAttributeValue::from_attribute_value(&sk_def.name, v).ok());
-            let b_sk = b
-                .get(&sk_def.name)
-                .and_then(|v| SortableAttributeValue::from_attribute_value(&sk_def.name, v).ok());
-            match (a_sk, b_sk) {
-                (Some(av), Some(bv)) => av.cmp(&bv),
-                (Some(_), None) => std::cmp::Ordering::Less,
-                (None, Some(_)) => std::cmp::Ordering::Greater,
-                (None, None) => std::cmp::Ordering::Equal,
+        .map(|item| {
+            (
+                index_position(&item, gsi_key_schema, &table.key_schema),
+                item,
+            )
+        })
+        .filter(|((index_sort, ..), _)| {
+            // Without a sort key the index sorts by the table key alone.
+            if gsi_key_schema.sort_key.is_none() {
+                return true;
             }
-        });
-    }
+            index_sort
+                .as_ref()
+                .is_some_and(|sk| sort_condition.is_none_or(|condition| condition.matches(sk)))
+        })
+        .collect();
 
+    matching.sort_by(|(a, _), (b, _)| a.cmp(b));
     if !scan_forward {
         matching.reverse();
     }
 
-    matching
+    matching.into_iter().map(|(_, item)| item).collect()
+}
+
+/// Where an item sits in an index: by index sort key, then by table key, so
+/// items sharing an index key keep a stable order across pages.
+type IndexPosition = (
+    Option<SortableAttributeValue>,
+    Option<SortableAttributeValue>,
+    Option<SortableAttributeValue>,
+);
+
+/// The [`IndexPosition`] of an item, or of an exclusive start key.
+fn index_position(
+    item: &HashMap<String, AttributeValue>,
+    gsi_key_schema: &KeySchema,
+    table_key_schema: &KeySchema,
+) -> IndexPosition {
+    let sortable = |key: &KeyAttribute| {
+        item.get(&key.name)
+            .and_then(|v| SortableAttributeValue::from_attribute_value(&key.name, v).ok())
+    };
+    (
+        gsi_key_schema.sort_key.as_ref().and_then(sortable),
+        item.get(&table_key_schema.partition_key.name)
+            .map(partition_order),
+        table_key_schema.sort_key.as_ref().and_then(sortable),
+    )
 }
 
 /// Apply exclusive_start_key pagination to GSI query results.
 ///
-/// Removes all items up to and including the item that matches the start key.
+/// Removes all items up to and including the start key's position, which
+/// still exists in the ordering even if its item has since been deleted.
 fn gsi_apply_pagination(
     items: &mut Vec<HashMap<String, AttributeValue>>,
     start_key: &HashMap<String, AttributeValue>,
-    gsi_pk_name: &str,
     gsi_key_schema: &KeySchema,
     table_key_schema: &KeySchema,
+    scan_forward: bool,
 ) {
-    // An item matches the start key when every key component present in
-    // `start_key` has an equal value in the item.
-    let matches_start = |item: &HashMap<String, AttributeValue>| -> bool {
-        start_key.iter().all(|(key_name, expected)| {
-            // For sort key attributes, compare via SortableAttributeValue to
-            // get correct DynamoDB ordering semantics.
-            let is_sort_attr = gsi_key_schema
-                .sort_key
-                .as_ref()
-                .is_some_and(|sk| sk.name == *key_name)
-                || table_key_schema
-                    .sort_key
-                    .as_ref()
-                    .is_some_and(|sk| sk.name == *key_name);
-
-            if is_sort_attr {
-                item.get(key_name).is_some_and(|v| v == expected)
-            } else {
-                // Partition key: direct equality.
-                item.get(key_name).is_some_and(|v| v == expected)
-            }
-        })
-    };
-
-    // Find the position of the start key item, then keep only items after it.
-    let skip_pos = items.iter().position(|item| {
-        // Must match GSI PK at minimum.
-        item.get(gsi_pk_name).is_some_and(|v| {
-            start_key
-                .get(gsi_pk_name)
-                .is_some_and(|expected| v == expected)
-        }) && matches_start(item)
+    let start = index_position(start_key, gsi_key_schema, table_key_schema);
+    let skip = items.partition_point(|item| {
+        let position = index_position(item, gsi_key_schema, table_key_schema);
+        if scan_forward {
+            position <= start
+        } else {
+            position >= start
+        }
     });
-
-    if let Some(pos) = skip_pos {
-        // Remove everything up to and including the matched item.
-        items.drain(..=pos);
-    } else {
-        // Start key not found -- this shouldn't normally happen, but if it
-        // does, return no items (consistent with DynamoDB).
-        items.clear();
-    }
+    items.drain(..skip);
 }
 
-/// Apply count limit and 1 MB size cap, returning `(selected_items, has_more)`.
+/// Apply the page limit, returning `(selected_items, has_more)`.
 fn gsi_apply_limit(
     items: Vec<HashMap<String, AttributeValue>>,
-    limit: Option<usize>,
+    limit: PageLimit,
 ) -> (Vec<HashMap<String, AttributeValue>>, bool) {
-    let effective_limit = limit.unwrap_or(usize::MAX);
+    let effective_limit = limit.items.unwrap_or(usize::MAX);
     let mut selected: Vec<HashMap<String, AttributeValue>> = Vec::new();
     let mut cumulative_size: u64 = 0;
 
@@ -4680,7 +5768,7 @@ fn gsi_apply_limit(
             return (selected, true);
         }
         let item_size = calculate_item_size(&item);
-        if !selected.is_empty() && cumulative_size + item_size > MAX_RESPONSE_BYTES {
+        if !selected.is_empty() && limit.overflows(cumulative_size, item_size) {
             return (selected, true);
         }
         cumulative_size += item_size;
@@ -4735,8 +5823,8 @@ mod tests {
         error::DynamoDBErrorCode,
         input::{BatchWriteItemInput, CreateTableInput, UpdateItemInput},
         types::{
-            AttributeDefinition, KeySchemaElement, KeyType, PutRequest, ScalarAttributeType,
-            WriteRequest,
+            AttributeDefinition, Get, KeySchemaElement, KeyType, KeysAndAttributes, PutRequest,
+            ScalarAttributeType, TransactGetItem, WriteRequest,
         },
     };
 
@@ -4745,7 +5833,13 @@ mod tests {
     /// Create a provider with a pre-configured test table named "TestTable".
     fn setup_provider_with_table() -> RustackDynamoDB {
         let provider = RustackDynamoDB::new(DynamoDBConfig::default());
-        let input = CreateTableInput {
+        provider.handle_create_table(test_table_input()).unwrap();
+        provider
+    }
+
+    /// Input creating a table named "TestTable" with a string hash key "pk".
+    fn test_table_input() -> CreateTableInput {
+        CreateTableInput {
             table_name: "TestTable".to_owned(),
             key_schema: vec![KeySchemaElement {
                 attribute_name: "pk".to_owned(),
@@ -4757,9 +5851,52 @@ mod tests {
             }],
             billing_mode: Some(BillingMode::PayPerRequest),
             ..Default::default()
-        };
-        provider.handle_create_table(input).unwrap();
-        provider
+        }
+    }
+
+    #[test]
+    fn test_should_isolate_tables_by_account_and_region() {
+        let provider = setup_provider_with_table();
+        let other = provider.scoped(&RequestScope::resolve(
+            Some("111122223333"),
+            Some("eu-west-1"),
+            "us-east-1",
+        ));
+
+        let listed = other
+            .handle_list_tables(ListTablesInput::default())
+            .unwrap();
+        assert!(listed.table_names.is_empty());
+        assert!(
+            other
+                .handle_describe_table(DescribeTableInput {
+                    table_name: "TestTable".to_owned(),
+                })
+                .is_err()
+        );
+
+        // The same table name is free in another namespace, with its own ARN.
+        let created = other.handle_create_table(test_table_input()).unwrap();
+        assert_eq!(
+            created.table_description.unwrap().table_arn.as_deref(),
+            Some("arn:aws:dynamodb:eu-west-1:111122223333:table/TestTable")
+        );
+        let original = provider
+            .handle_describe_table(DescribeTableInput {
+                table_name: "TestTable".to_owned(),
+            })
+            .unwrap();
+        assert_eq!(
+            original.table.unwrap().table_arn.as_deref(),
+            Some("arn:aws:dynamodb:us-east-1:000000000000:table/TestTable")
+        );
+        assert_eq!(
+            provider
+                .handle_list_tables(ListTablesInput::default())
+                .unwrap()
+                .table_names,
+            vec!["TestTable".to_owned()]
+        );
     }
 
     #[test]
@@ -4776,6 +5913,173 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_should_describe_endpoint_at_request_host() {
+        let provider = setup_provider_with_table();
+
+        let output = provider
+            .handle_describe_endpoints(DescribeEndpointsInput {}, Some("localhost:4566"))
+            .unwrap();
+        assert_eq!(output.endpoints.len(), 1);
+        assert_eq!(output.endpoints[0].address, "localhost:4566");
+        assert_eq!(output.endpoints[0].cache_period_in_minutes, 1440);
+
+        let output = provider
+            .handle_describe_endpoints(DescribeEndpointsInput {}, None)
+            .unwrap();
+        assert_eq!(
+            output.endpoints[0].address,
+            "dynamodb.us-east-1.amazonaws.com"
+        );
+    }
+
+    #[test]
+    fn test_should_not_lose_concurrent_counter_updates() {
+        let provider = setup_provider_with_table();
+        let key = HashMap::from([("pk".to_owned(), AttributeValue::S("k1".to_owned()))]);
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..25 {
+                        provider
+                            .handle_update_item(UpdateItemInput {
+                                table_name: "TestTable".to_owned(),
+                                key: key.clone(),
+                                update_expression: Some(
+                                    "SET #c = if_not_exists(#c, :zero) + :one".to_owned(),
+                                ),
+                                expression_attribute_names: HashMap::from([(
+                                    "#c".to_owned(),
+                                    "counter".to_owned(),
+                                )]),
+                                expression_attribute_values: HashMap::from([
+                                    (":zero".to_owned(), AttributeValue::N("0".to_owned())),
+                                    (":one".to_owned(), AttributeValue::N("1".to_owned())),
+                                ]),
+                                ..Default::default()
+                            })
+                            .unwrap();
+                    }
+                });
+            }
+        });
+
+        let item = provider
+            .handle_get_item(GetItemInput {
+                table_name: "TestTable".to_owned(),
+                key,
+                ..Default::default()
+            })
+            .unwrap()
+            .item
+            .unwrap();
+        assert_eq!(item["counter"], AttributeValue::N("200".to_owned()));
+    }
+
+    #[test]
+    fn test_should_survive_tables_deleted_and_recreated_under_load() {
+        for config in [
+            DynamoDBConfig::default(),
+            DynamoDBConfig {
+                behavior: DynamoDBBehavior {
+                    table_create_delay: std::time::Duration::from_millis(1),
+                    table_delete_delay: std::time::Duration::from_millis(1),
+                    ..DynamoDBBehavior::default()
+                },
+                ..DynamoDBConfig::default()
+            },
+        ] {
+            let provider = &RustackDynamoDB::new(config);
+            provider.handle_create_table(test_table_input()).unwrap();
+            let deadline = std::time::Instant::now() + std::time::Duration::from_secs(1);
+            let key = &|i: usize| {
+                HashMap::from([("pk".to_owned(), AttributeValue::S(format!("k{}", i % 8)))])
+            };
+            let expect_gone_or_busy = &|result: Result<(), DynamoDBError>| {
+                if let Err(e) = result {
+                    assert!(
+                        matches!(
+                            e.code,
+                            DynamoDBErrorCode::ResourceNotFoundException
+                                | DynamoDBErrorCode::ResourceInUseException
+                        ),
+                        "unexpected error: {e:?}"
+                    );
+                }
+            };
+
+            std::thread::scope(|scope| {
+                for _ in 0..2 {
+                    scope.spawn(move || {
+                        while std::time::Instant::now() < deadline {
+                            expect_gone_or_busy(
+                                provider
+                                    .handle_delete_table(DeleteTableInput {
+                                        table_name: "TestTable".to_owned(),
+                                    })
+                                    .map(drop),
+                            );
+                            expect_gone_or_busy(
+                                provider.handle_create_table(test_table_input()).map(drop),
+                            );
+                        }
+                    });
+                }
+                for worker in 0..4 {
+                    scope.spawn(move || {
+                        let mut i = worker;
+                        while std::time::Instant::now() < deadline {
+                            i += 1;
+                            let mut item = key(i);
+                            item.insert("n".to_owned(), AttributeValue::N(i.to_string()));
+                            let result = match i % 5 {
+                                0 => provider
+                                    .handle_put_item(PutItemInput {
+                                        table_name: "TestTable".to_owned(),
+                                        item,
+                                        ..Default::default()
+                                    })
+                                    .map(drop),
+                                1 => provider
+                                    .handle_update_item(UpdateItemInput {
+                                        table_name: "TestTable".to_owned(),
+                                        key: key(i),
+                                        update_expression: Some("ADD n :one".to_owned()),
+                                        expression_attribute_values: HashMap::from([(
+                                            ":one".to_owned(),
+                                            AttributeValue::N("1".to_owned()),
+                                        )]),
+                                        ..Default::default()
+                                    })
+                                    .map(drop),
+                                2 => provider
+                                    .handle_get_item(GetItemInput {
+                                        table_name: "TestTable".to_owned(),
+                                        key: key(i),
+                                        ..Default::default()
+                                    })
+                                    .map(drop),
+                                3 => provider
+                                    .handle_scan(ScanInput {
+                                        table_name: "TestTable".to_owned(),
+                                        ..Default::default()
+                                    })
+                                    .map(drop),
+                                _ => provider
+                                    .handle_describe_table(DescribeTableInput {
+                                        table_name: "TestTable".to_owned(),
+                                    })
+                                    .map(drop),
+                            };
+                            expect_gone_or_busy(result);
+                        }
+                    });
+                }
+            });
+        }
+    }
+
     #[test]
     fn test_should_error_on_batch_write_item_exceeding_25_items() {
         let provider = setup_provider_with_table();
@@ -4803,21 +6107,98 @@ mod tests {
     }
 
     #[test]
-    fn test_should_allow_batch_write_item_at_exactly_25_items() {
-        let provider = setup_provider_with_table();
+    fn test_should_return_forced_unprocessed_items_until_retries_drain_them() {
+        let provider = RustackDynamoDB::new(DynamoDBConfig {
+            behavior: DynamoDBBehavior {
+                batch_write_unprocessed: 3,
+                ..DynamoDBBehavior::default()
+            },
+            ..DynamoDBConfig::default()
+        });
+        provider.handle_create_table(test_table_input()).unwrap();
+        let put = |i: usize| WriteRequest {
+            put_request: Some(PutRequest {
+                item: HashMap::from([("pk".to_owned(), AttributeValue::S(format!("key{i}")))]),
+            }),
+            delete_request: None,
+        };
+
+        let mut request_items =
+            HashMap::from([("TestTable".to_owned(), (0..5).map(put).collect())]);
+        let mut rounds = Vec::new();
+        while !request_items.is_empty() {
+            let output = provider
+                .handle_batch_write_item(BatchWriteItemInput {
+                    request_items,
+                    return_consumed_capacity: None,
+                    return_item_collection_metrics: None,
+                })
+                .unwrap();
+            request_items = output.unprocessed_items;
+            rounds.push(request_items.get("TestTable").map_or(0, Vec::len));
+        }
+        // 3 of 5 held back, then 2 of 3 (one always goes through), then 1 of 2.
+        assert_eq!(rounds, [3, 2, 1, 0]);
+
+        let first = provider
+            .handle_batch_write_item(BatchWriteItemInput {
+                request_items: HashMap::from([("TestTable".to_owned(), (5..7).map(put).collect())]),
+                return_consumed_capacity: None,
+                return_item_collection_metrics: None,
+            })
+            .unwrap();
+        let held = &first.unprocessed_items["TestTable"];
+        assert_eq!(held.len(), 1);
+        assert_eq!(
+            held[0].put_request.as_ref().unwrap().item["pk"],
+            AttributeValue::S("key5".to_owned())
+        );
+    }
 
+    #[test]
+    fn test_should_reject_batch_write_over_total_request_size() {
+        let provider = setup_provider_with_table();
         let writes: Vec<WriteRequest> = (0..25)
             .map(|i| WriteRequest {
                 put_request: Some(PutRequest {
-                    item: HashMap::from([("pk".to_owned(), AttributeValue::S(format!("key{i}")))]),
+                    item: HashMap::from([
+                        ("pk".to_owned(), AttributeValue::S(format!("key{i}"))),
+                        ("blob".to_owned(), AttributeValue::S("x".repeat(700 * 1024))),
+                    ]),
                 }),
                 delete_request: None,
             })
             .collect();
+        let err = provider
+            .handle_batch_write_item(BatchWriteItemInput {
+                request_items: HashMap::from([("TestTable".to_owned(), writes)]),
+                return_consumed_capacity: None,
+                return_item_collection_metrics: None,
+            })
+            .unwrap_err();
+        assert_eq!(err.code, DynamoDBErrorCode::ValidationException);
+        assert_eq!(
+            err.message,
+            "Request size has exceeded the maximum allowed size of 16777216 bytes"
+        );
+    }
 
-        let input = BatchWriteItemInput {
-            request_items: HashMap::from([("TestTable".to_owned(), writes)]),
-            return_consumed_capacity: None,
+    #[test]
+    fn test_should_allow_batch_write_item_at_exactly_25_items() {
+        let provider = setup_provider_with_table();
+
+        let writes: Vec<WriteRequest> = (0..25)
+            .map(|i| WriteRequest {
+                put_request: Some(PutRequest {
+                    item: HashMap::from([("pk".to_owned(), AttributeValue::S(format!("key{i}")))]),
+                }),
+                delete_request: None,
+            })
+            .collect();
+
+        let input = BatchWriteItemInput {
+            request_items: HashMap::from([("TestTable".to_owned(), writes)]),
+            return_consumed_capacity: None,
             return_item_collection_metrics: None,
         };
         let result = provider.handle_batch_write_item(input);
@@ -4993,6 +6374,107 @@ mod tests {
         assert_eq!(desc.table_status, Some(TableStatus::Deleting));
     }
 
+    #[test]
+    fn test_should_report_creating_until_create_delay_elapses() {
+        let provider = RustackDynamoDB::new(DynamoDBConfig {
+            behavior: DynamoDBBehavior {
+                table_create_delay: std::time::Duration::from_secs(60),
+                ..DynamoDBBehavior::default()
+            },
+            ..DynamoDBConfig::default()
+        });
+        let created = provider.handle_create_table(test_table_input()).unwrap();
+        assert_eq!(
+            created.table_description.unwrap().table_status,
+            Some(TableStatus::Creating)
+        );
+
+        let described = provider
+            .handle_describe_table(DescribeTableInput {
+                table_name: "TestTable".to_owned(),
+            })
+            .unwrap();
+        assert_eq!(
+            described.table.unwrap().table_status,
+            Some(TableStatus::Creating)
+        );
+
+        let err = provider
+            .handle_put_item(PutItemInput {
+                table_name: "TestTable".to_owned(),
+                item: HashMap::from([("pk".to_owned(), AttributeValue::S("k1".to_owned()))]),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert_eq!(err.code, DynamoDBErrorCode::ResourceNotFoundException);
+
+        let err = provider
+            .handle_delete_table(DeleteTableInput {
+                table_name: "TestTable".to_owned(),
+            })
+            .unwrap_err();
+        assert_eq!(err.code, DynamoDBErrorCode::ResourceInUseException);
+
+        let table = provider.state.require_table("TestTable").unwrap();
+        assert_eq!(table.status_at(table.active_at), TableStatus::Active);
+    }
+
+    #[test]
+    fn test_should_report_deleting_until_delete_delay_elapses() {
+        let provider = RustackDynamoDB::new(DynamoDBConfig {
+            behavior: DynamoDBBehavior {
+                table_delete_delay: std::time::Duration::from_secs(60),
+                ..DynamoDBBehavior::default()
+            },
+            ..DynamoDBConfig::default()
+        });
+        provider.handle_create_table(test_table_input()).unwrap();
+        let delete = || {
+            provider.handle_delete_table(DeleteTableInput {
+                table_name: "TestTable".to_owned(),
+            })
+        };
+        delete().unwrap();
+
+        let described = provider
+            .handle_describe_table(DescribeTableInput {
+                table_name: "TestTable".to_owned(),
+            })
+            .unwrap();
+        assert_eq!(
+            described.table.unwrap().table_status,
+            Some(TableStatus::Deleting)
+        );
+        assert_eq!(
+            delete().unwrap_err().code,
+            DynamoDBErrorCode::ResourceInUseException
+        );
+        let err = provider
+            .handle_get_item(GetItemInput {
+                table_name: "TestTable".to_owned(),
+                key: HashMap::from([("pk".to_owned(), AttributeValue::S("k1".to_owned()))]),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert_eq!(err.code, DynamoDBErrorCode::ResourceNotFoundException);
+
+        // Once the deletion time has passed, the table is gone and the name is free.
+        let table = provider.state.require_table("TestTable").unwrap();
+        *table.deleted_at.write() = Some(chrono::Utc::now() - chrono::TimeDelta::seconds(1));
+        assert_eq!(
+            delete().unwrap_err().code,
+            DynamoDBErrorCode::ResourceNotFoundException
+        );
+        assert!(
+            provider
+                .handle_list_tables(ListTablesInput::default())
+                .unwrap()
+                .table_names
+                .is_empty()
+        );
+        provider.handle_create_table(test_table_input()).unwrap();
+    }
+
     #[test]
     fn test_should_reject_invalid_return_values_for_put_item() {
         let provider = setup_provider_with_table();
@@ -5023,6 +6505,300 @@ mod tests {
         assert_eq!(err.code, DynamoDBErrorCode::ValidationException);
     }
 
+    #[test]
+    fn test_should_omit_attributes_on_the_wire_when_no_old_item_exists() {
+        use rustack_dynamodb_model::types::ReturnValue;
+
+        let provider = setup_provider_with_table();
+        let put = |value: &str| {
+            provider
+                .handle_put_item(PutItemInput {
+                    table_name: "TestTable".to_owned(),
+                    item: HashMap::from([
+                        ("pk".to_owned(), AttributeValue::S("k1".to_owned())),
+                        ("v".to_owned(), AttributeValue::S(value.to_owned())),
+                    ]),
+                    return_values: Some(ReturnValue::AllOld),
+                    ..Default::default()
+                })
+                .unwrap()
+        };
+        let delete = || {
+            provider
+                .handle_delete_item(DeleteItemInput {
+                    table_name: "TestTable".to_owned(),
+                    key: HashMap::from([("pk".to_owned(), AttributeValue::S("k1".to_owned()))]),
+                    return_values: Some(ReturnValue::AllOld),
+                    ..Default::default()
+                })
+                .unwrap()
+        };
+
+        let json = serde_json::to_value(put("a")).unwrap();
+        assert!(json.get("Attributes").is_none(), "{json}");
+        let json = serde_json::to_value(put("b")).unwrap();
+        assert_eq!(json["Attributes"]["v"], serde_json::json!({ "S": "a" }));
+
+        let json = serde_json::to_value(delete()).unwrap();
+        assert_eq!(json["Attributes"]["v"], serde_json::json!({ "S": "b" }));
+        let json = serde_json::to_value(delete()).unwrap();
+        assert!(json.get("Attributes").is_none(), "{json}");
+
+        let update = provider
+            .handle_update_item(UpdateItemInput {
+                table_name: "TestTable".to_owned(),
+                key: HashMap::from([("pk".to_owned(), AttributeValue::S("k1".to_owned()))]),
+                update_expression: Some("SET v = :v".to_owned()),
+                expression_attribute_values: HashMap::from([(
+                    ":v".to_owned(),
+                    AttributeValue::S("c".to_owned()),
+                )]),
+                return_values: Some(ReturnValue::AllOld),
+                ..Default::default()
+            })
+            .unwrap();
+        let json = serde_json::to_value(update).unwrap();
+        assert!(json.get("Attributes").is_none(), "{json}");
+    }
+
+    #[test]
+    fn test_should_omit_last_evaluated_key_on_the_wire_for_the_last_page() {
+        let provider = setup_provider_with_table();
+        for pk in ["k1", "k2"] {
+            provider
+                .handle_put_item(PutItemInput {
+                    table_name: "TestTable".to_owned(),
+                    item: HashMap::from([("pk".to_owned(), AttributeValue::S(pk.to_owned()))]),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        let scan = |limit| {
+            let page = provider
+                .handle_scan(ScanInput {
+                    table_name: "TestTable".to_owned(),
+                    limit,
+                    ..Default::default()
+                })
+                .unwrap();
+            serde_json::to_value(page).unwrap()
+        };
+
+        let json = scan(Some(1));
+        assert!(json["LastEvaluatedKey"]["pk"].is_object(), "{json}");
+        let json = scan(None);
+        assert!(json.get("LastEvaluatedKey").is_none(), "{json}");
+
+        let query = provider
+            .handle_query(QueryInput {
+                table_name: "TestTable".to_owned(),
+                key_condition_expression: Some("pk = :pk".to_owned()),
+                expression_attribute_values: HashMap::from([(
+                    ":pk".to_owned(),
+                    AttributeValue::S("k1".to_owned()),
+                )]),
+                ..Default::default()
+            })
+            .unwrap();
+        let json = serde_json::to_value(query).unwrap();
+        assert!(json.get("LastEvaluatedKey").is_none(), "{json}");
+    }
+
+    #[test]
+    fn test_should_reject_unknown_enum_values() {
+        let provider = setup_provider_with_table();
+        let input = PutItemInput {
+            table_name: "TestTable".to_owned(),
+            item: HashMap::from([("pk".to_owned(), AttributeValue::S("k1".to_owned()))]),
+            return_values: Some(ReturnValue::Unknown("all_old".to_owned())),
+            ..Default::default()
+        };
+        let err = provider.handle_put_item(input).unwrap_err();
+        assert_eq!(err.code, DynamoDBErrorCode::ValidationException);
+        assert_eq!(
+            err.message,
+            "1 validation error detected: Value 'all_old' at 'returnValues' failed to satisfy \
+             constraint: Member must satisfy enum value set: [NONE, ALL_OLD, UPDATED_OLD, \
+             ALL_NEW, UPDATED_NEW]"
+        );
+
+        let input = ScanInput {
+            table_name: "TestTable".to_owned(),
+            select: Some(Select::Unknown("EVERYTHING".to_owned())),
+            ..Default::default()
+        };
+        let err = provider.handle_scan(input).unwrap_err();
+        assert!(err.message.contains("at 'select'"), "{}", err.message);
+    }
+
+    #[test]
+    fn test_should_reject_unescaped_reserved_words_unless_relaxed() {
+        let update = |provider: &RustackDynamoDB, expression: &str, names: &[(&str, &str)]| {
+            provider.handle_update_item(UpdateItemInput {
+                table_name: "TestTable".to_owned(),
+                key: HashMap::from([("pk".to_owned(), AttributeValue::S("a".to_owned()))]),
+                update_expression: Some(expression.to_owned()),
+                expression_attribute_names: names
+                    .iter()
+                    .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+                    .collect(),
+                expression_attribute_values: HashMap::from([(
+                    ":v".to_owned(),
+                    AttributeValue::S("done".to_owned()),
+                )]),
+                ..Default::default()
+            })
+        };
+
+        let provider = setup_provider_with_table();
+        let err = update(&provider, "SET status = :v", &[]).unwrap_err();
+        assert_eq!(err.code, DynamoDBErrorCode::ValidationException);
+        assert_eq!(
+            err.message,
+            "Invalid UpdateExpression: Attribute name is a reserved keyword; reserved keyword: \
+             status; use an ExpressionAttributeNames placeholder such as #status instead"
+        );
+        update(&provider, "SET #s = :v", &[("#s", "status")]).unwrap();
+        let err = provider
+            .handle_scan(ScanInput {
+                table_name: "TestTable".to_owned(),
+                projection_expression: Some("pk, info.name".to_owned()),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert!(err.message.contains("reserved keyword: name"));
+
+        let lax = RustackDynamoDB::new(DynamoDBConfig {
+            behavior: DynamoDBBehavior {
+                strict_reserved_words: false,
+                ..DynamoDBBehavior::default()
+            },
+            ..DynamoDBConfig::default()
+        });
+        lax.handle_create_table(test_table_input()).unwrap();
+        update(&lax, "SET status = :v", &[]).unwrap();
+    }
+
+    #[test]
+    fn test_should_accept_empty_string_and_binary_non_key_values() {
+        let provider = setup_provider_with_table();
+        let empty_s = || AttributeValue::S(String::new());
+        let item = HashMap::from([
+            ("pk".to_owned(), AttributeValue::S("a".to_owned())),
+            ("s".to_owned(), empty_s()),
+            ("b".to_owned(), AttributeValue::B(bytes::Bytes::new())),
+            ("l".to_owned(), AttributeValue::L(vec![empty_s()])),
+            (
+                "m".to_owned(),
+                AttributeValue::M(HashMap::from([("nested".to_owned(), empty_s())])),
+            ),
+        ]);
+        provider
+            .handle_put_item(PutItemInput {
+                table_name: "TestTable".to_owned(),
+                item: item.clone(),
+                ..Default::default()
+            })
+            .unwrap();
+        let key = HashMap::from([("pk".to_owned(), AttributeValue::S("a".to_owned()))]);
+        let stored = provider
+            .handle_get_item(GetItemInput {
+                table_name: "TestTable".to_owned(),
+                key: key.clone(),
+                ..Default::default()
+            })
+            .unwrap()
+            .item;
+        assert_eq!(stored, Some(item));
+
+        // An empty string is a valid expression attribute value.
+        let empty_values = HashMap::from([(":empty".to_owned(), empty_s())]);
+        provider
+            .handle_update_item(UpdateItemInput {
+                table_name: "TestTable".to_owned(),
+                key: key.clone(),
+                update_expression: Some("SET t = :empty".to_owned()),
+                expression_attribute_values: empty_values.clone(),
+                ..Default::default()
+            })
+            .unwrap();
+        let scanned = provider
+            .handle_scan(ScanInput {
+                table_name: "TestTable".to_owned(),
+                filter_expression: Some(
+                    "s = :empty AND t = :empty AND m.nested = :empty".to_owned(),
+                ),
+                expression_attribute_values: empty_values.clone(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(scanned.count, 1);
+        let queried = provider
+            .handle_query(QueryInput {
+                table_name: "TestTable".to_owned(),
+                key_condition_expression: Some("pk = :pk".to_owned()),
+                filter_expression: Some("l[0] = :empty".to_owned()),
+                expression_attribute_values: HashMap::from([
+                    (":pk".to_owned(), AttributeValue::S("a".to_owned())),
+                    (":empty".to_owned(), empty_s()),
+                ]),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(queried.count, 1);
+    }
+
+    #[test]
+    fn test_should_reject_empty_set_members() {
+        let provider = setup_provider_with_table();
+        let put = |value: AttributeValue| {
+            provider
+                .handle_put_item(PutItemInput {
+                    table_name: "TestTable".to_owned(),
+                    item: HashMap::from([
+                        ("pk".to_owned(), AttributeValue::S("a".to_owned())),
+                        ("set".to_owned(), value),
+                    ]),
+                    ..Default::default()
+                })
+                .map(|_| ())
+                .map_err(|e| e.message)
+        };
+        let empty_member = AttributeValue::Ss(vec!["x".to_owned(), String::new()]);
+
+        let err = put(empty_member.clone()).unwrap_err();
+        assert!(
+            err.contains("An string set may not have a empty string"),
+            "{err}"
+        );
+        let err = put(AttributeValue::Bs(vec![bytes::Bytes::new()])).unwrap_err();
+        assert!(
+            err.contains("An binary set may not have a empty binary"),
+            "{err}"
+        );
+        let nested = AttributeValue::M(HashMap::from([(
+            "nested".to_owned(),
+            AttributeValue::L(vec![empty_member.clone()]),
+        )]));
+        assert!(put(nested).is_err());
+        put(AttributeValue::Ss(vec!["x".to_owned()])).unwrap();
+
+        let err = provider
+            .handle_update_item(UpdateItemInput {
+                table_name: "TestTable".to_owned(),
+                key: HashMap::from([("pk".to_owned(), AttributeValue::S("a".to_owned()))]),
+                update_expression: Some("ADD #s :members".to_owned()),
+                expression_attribute_names: HashMap::from([("#s".to_owned(), "set".to_owned())]),
+                expression_attribute_values: HashMap::from([(":members".to_owned(), empty_member)]),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert!(
+            err.message
+                .contains("(:members) contains an empty string set member")
+        );
+    }
+
     #[test]
     fn test_should_handle_update_table() {
         let provider = setup_provider_with_table();
@@ -5216,4 +6992,1662 @@ mod tests {
             )])
         );
     }
+
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn test_should_return_values_for_every_clause_mix_and_item_existence() {
+        type Attrs = Vec<(&'static str, AttributeValue)>;
+
+        let s = |v: &str| AttributeValue::S(v.to_owned());
+        let n = |v: &str| AttributeValue::N(v.to_owned());
+        let ss = |v: &[&str]| AttributeValue::Ss(v.iter().map(|m| (*m).to_owned()).collect());
+        let old_attrs = || {
+            HashMap::from([
+                ("hits".to_owned(), n("5")),
+                ("label".to_owned(), s("old")),
+                ("tags".to_owned(), ss(&["a", "b"])),
+                ("gone".to_owned(), s("x")),
+                ("asset".to_owned(), s("y")),
+            ])
+        };
+
+        // (update expression, attributes it targets, attributes it sets on
+        // the existing item, and the non-key attributes of a missing item
+        // after the update, where `None` means the item is not created).
+        let cases: Vec<(&str, &[&str], Attrs, Option<Attrs>)> = vec![
+            (
+                "SET label = :l",
+                &["label"],
+                vec![("label", s("new"))],
+                Some(vec![("label", s("new"))]),
+            ),
+            (
+                "ADD hits :one",
+                &["hits"],
+                vec![("hits", n("6"))],
+                Some(vec![("hits", n("1"))]),
+            ),
+            // UPDATED_NEW of a mixed expression reports the targets of
+            // every clause, not just the first.
+            (
+                "SET label = :l ADD hits :one",
+                &["label", "hits"],
+                vec![("label", s("new")), ("hits", n("6"))],
+                Some(vec![("label", s("new")), ("hits", n("1"))]),
+            ),
+            ("REMOVE gone", &["gone"], vec![], None),
+            (
+                "DELETE tags :t",
+                &["tags"],
+                vec![("tags", ss(&["b"]))],
+                None,
+            ),
+            (
+                "REMOVE gone DELETE tags :t",
+                &["gone", "tags"],
+                vec![("tags", ss(&["b"]))],
+                None,
+            ),
+            (
+                "SET label = :l REMOVE gone",
+                &["label", "gone"],
+                vec![("label", s("new"))],
+                Some(vec![("label", s("new"))]),
+            ),
+            (
+                "ADD hits :one DELETE tags :t",
+                &["hits", "tags"],
+                vec![("hits", n("6")), ("tags", ss(&["b"]))],
+                Some(vec![("hits", n("1"))]),
+            ),
+            // An attribute name containing "set " is still a REMOVE.
+            ("REMOVE asset , gone", &["asset", "gone"], vec![], None),
+        ];
+        let return_values = [
+            ReturnValue::None,
+            ReturnValue::AllOld,
+            ReturnValue::AllNew,
+            ReturnValue::UpdatedOld,
+            ReturnValue::UpdatedNew,
+        ];
+
+        let provider = setup_provider_with_table();
+        for (i, (expression, targets, existing_after, missing_after)) in cases.iter().enumerate() {
+            let removed: Vec<&str> = expression
+                .split_once("REMOVE ")
+                .map(|(_, rest)| {
+                    rest.split([',', ' '])
+                        .take_while(|word| *word != "DELETE")
+                        .filter(|word| !word.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default();
+            for existing in [true, false] {
+                for rv in &return_values {
+                    let case = format!("{expression} / existing: {existing} / {rv}");
+                    let key =
+                        HashMap::from([("pk".to_owned(), s(&format!("{i}-{existing}-{rv}")))]);
+                    let old = existing.then(|| {
+                        let mut item = old_attrs();
+                        item.extend(key.clone());
+                        item
+                    });
+                    if let Some(old) = &old {
+                        provider
+                            .handle_put_item(PutItemInput {
+                                table_name: "TestTable".to_owned(),
+                                item: old.clone(),
+                                ..Default::default()
+                            })
+                            .unwrap();
+                    }
+                    let new = if existing {
+                        let mut item = old.clone().unwrap();
+                        item.retain(|name, _| !removed.contains(&name.as_str()));
+                        item.extend(
+                            existing_after
+                                .iter()
+                                .map(|(k, v)| ((*k).to_owned(), v.clone())),
+                        );
+                        Some(item)
+                    } else {
+                        missing_after.as_ref().map(|attrs| {
+                            let mut item = key.clone();
+                            item.extend(attrs.iter().map(|(k, v)| ((*k).to_owned(), v.clone())));
+                            item
+                        })
+                    };
+
+                    let mut values = HashMap::new();
+                    for (placeholder, value) in
+                        [(":l", s("new")), (":one", n("1")), (":t", ss(&["a"]))]
+                    {
+                        if expression.contains(placeholder) {
+                            values.insert(placeholder.to_owned(), value);
+                        }
+                    }
+                    let output = provider
+                        .handle_update_item(UpdateItemInput {
+                            table_name: "TestTable".to_owned(),
+                            key: key.clone(),
+                            update_expression: Some((*expression).to_owned()),
+                            expression_attribute_values: values,
+                            return_values: Some(rv.clone()),
+                            ..Default::default()
+                        })
+                        .unwrap();
+
+                    let only_targets = |item: &HashMap<String, AttributeValue>| {
+                        item.iter()
+                            .filter(|(name, _)| targets.contains(&name.as_str()))
+                            .map(|(k, v)| (k.clone(), v.clone()))
+                            .collect::<HashMap<_, _>>()
+                    };
+                    let expected = match rv {
+                        ReturnValue::AllOld => old.clone().unwrap_or_default(),
+                        ReturnValue::AllNew => new.clone().unwrap_or_default(),
+                        ReturnValue::UpdatedOld => {
+                            old.as_ref().map(only_targets).unwrap_or_default()
+                        }
+                        ReturnValue::UpdatedNew => {
+                            new.as_ref().map(only_targets).unwrap_or_default()
+                        }
+                        _ => HashMap::new(),
+                    };
+                    assert_eq!(
+                        output.attributes,
+                        Some(expected).filter(|attrs| !attrs.is_empty()),
+                        "{case}"
+                    );
+
+                    let stored = provider
+                        .handle_get_item(GetItemInput {
+                            table_name: "TestTable".to_owned(),
+                            key,
+                            ..Default::default()
+                        })
+                        .unwrap();
+                    assert_eq!(stored.item, new, "{case}");
+                }
+            }
+        }
+    }
+
+    /// Export writer that records every object it is given.
+    #[derive(Default)]
+    struct RecordingExportWriter {
+        objects: parking_lot::Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ExportWriter for RecordingExportWriter {
+        async fn put_object(
+            &self,
+            _account: &AccountId,
+            bucket: &str,
+            key: &str,
+            _body: bytes::Bytes,
+        ) -> Result<(), crate::export::ExportError> {
+            if bucket == "missing" {
+                return Err(crate::export::ExportError::NoSuchBucket(bucket.to_owned()));
+            }
+            self.objects
+                .lock()
+                .push((bucket.to_owned(), key.to_owned()));
+            Ok(())
+        }
+    }
+
+    async fn wait_for_export(provider: &RustackDynamoDB, arn: &str) -> ExportDescription {
+        for _ in 0..100 {
+            let export = provider
+                .handle_describe_export(DescribeExportInput {
+                    export_arn: arn.to_owned(),
+                })
+                .unwrap()
+                .export_description
+                .unwrap();
+            if export.export_status != Some(ExportStatus::InProgress) {
+                return export;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("export {arn} did not finish");
+    }
+
+    #[tokio::test]
+    async fn test_should_export_table_to_s3_and_complete() {
+        let writer = Arc::new(RecordingExportWriter::default());
+        let mut provider = RustackDynamoDB::new(DynamoDBConfig::default());
+        provider.set_export_writer(Arc::clone(&writer) as Arc<dyn ExportWriter>);
+        let table = provider
+            .handle_create_table(test_table_input())
+            .unwrap()
+            .table_description
+            .unwrap();
+        let table_arn = table.table_arn.unwrap();
+        for pk in ["a", "b"] {
+            provider
+                .handle_put_item(PutItemInput {
+                    table_name: "TestTable".to_owned(),
+                    item: HashMap::from([("pk".to_owned(), AttributeValue::S(pk.to_owned()))]),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+
+        let started = provider
+            .handle_export_table_to_point_in_time(ExportTableToPointInTimeInput {
+                table_arn: table_arn.clone(),
+                s3_bucket: "exports".to_owned(),
+                s3_prefix: Some("snapshots/".to_owned()),
+                ..Default::default()
+            })
+            .unwrap()
+            .export_description
+            .unwrap();
+        assert_eq!(started.export_status, Some(ExportStatus::InProgress));
+        let arn = started.export_arn.unwrap();
+        assert!(arn.starts_with(&format!("{table_arn}/export/")));
+        let export_id = arn.rsplit('/').next().unwrap();
+
+        let done = wait_for_export(&provider, &arn).await;
+        assert_eq!(done.export_status, Some(ExportStatus::Completed));
+        assert_eq!(done.item_count, Some(2));
+        assert!(done.billed_size_bytes.unwrap() > 0);
+        assert_eq!(
+            done.export_manifest.as_deref(),
+            Some(format!("snapshots/AWSDynamoDB/{export_id}/manifest-summary.json").as_str())
+        );
+        assert!(
+            writer
+                .objects
+                .lock()
+                .iter()
+                .any(|(_, key)| key.ends_with("manifest-summary.json"))
+        );
+
+        let listed = provider
+            .handle_list_exports(ListExportsInput {
+                table_arn: Some(table_arn),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(listed.export_summaries.len(), 1);
+        assert_eq!(
+            listed.export_summaries[0].export_arn.as_deref(),
+            Some(arn.as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_should_fail_export_to_missing_bucket() {
+        let mut provider = RustackDynamoDB::new(DynamoDBConfig::default());
+        provider.set_export_writer(Arc::new(RecordingExportWriter::default()));
+        let table_arn = provider
+            .handle_create_table(test_table_input())
+            .unwrap()
+            .table_description
+            .unwrap()
+            .table_arn
+            .unwrap();
+
+        let arn = provider
+            .handle_export_table_to_point_in_time(ExportTableToPointInTimeInput {
+                table_arn,
+                s3_bucket: "missing".to_owned(),
+                ..Default::default()
+            })
+            .unwrap()
+            .export_description
+            .unwrap()
+            .export_arn
+            .unwrap();
+        let failed = wait_for_export(&provider, &arn).await;
+        assert_eq!(failed.export_status, Some(ExportStatus::Failed));
+        assert_eq!(failed.failure_code.as_deref(), Some("S3NoSuchBucket"));
+    }
+
+    #[tokio::test]
+    async fn test_should_reject_export_of_unknown_table_or_export() {
+        let provider = setup_provider_with_table();
+        let err = provider
+            .handle_export_table_to_point_in_time(ExportTableToPointInTimeInput {
+                table_arn: "arn:aws:dynamodb:us-east-1:000000000000:table/Missing".to_owned(),
+                s3_bucket: "exports".to_owned(),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert_eq!(err.code, DynamoDBErrorCode::TableNotFoundException);
+
+        let err = provider
+            .handle_describe_export(DescribeExportInput {
+                export_arn: "arn:aws:dynamodb:us-east-1:000000000000:table/TestTable/export/x"
+                    .to_owned(),
+            })
+            .unwrap_err();
+        assert_eq!(err.code, DynamoDBErrorCode::ExportNotFoundException);
+    }
+
+    /// Create a backup of "TestTable" named `name`, returning its ARN.
+    fn create_test_backup(provider: &RustackDynamoDB, name: &str) -> String {
+        provider
+            .handle_create_backup(CreateBackupInput {
+                table_name: "TestTable".to_owned(),
+                backup_name: name.to_owned(),
+            })
+            .unwrap()
+            .backup_details
+            .unwrap()
+            .backup_arn
+    }
+
+    #[test]
+    fn test_should_report_table_and_index_usage() {
+        let provider = RustackDynamoDB::new(DynamoDBConfig::default());
+        let mut input = test_table_input();
+        input.attribute_definitions.push(AttributeDefinition {
+            attribute_name: "status".to_owned(),
+            attribute_type: ScalarAttributeType::S,
+        });
+        input.global_secondary_indexes =
+            vec![rustack_dynamodb_model::types::GlobalSecondaryIndex {
+                index_name: "by-status".to_owned(),
+                key_schema: vec![KeySchemaElement {
+                    attribute_name: "status".to_owned(),
+                    key_type: KeyType::Hash,
+                }],
+                projection: rustack_dynamodb_model::types::Projection {
+                    projection_type: Some(rustack_dynamodb_model::types::ProjectionType::KeysOnly),
+                    ..Default::default()
+                },
+                provisioned_throughput: None,
+            }];
+        provider.handle_create_table(input).unwrap();
+        let put = |item: &HashMap<String, AttributeValue>| {
+            provider
+                .handle_put_item(PutItemInput {
+                    table_name: "TestTable".to_owned(),
+                    item: item.clone(),
+                    ..Default::default()
+                })
+                .unwrap();
+        };
+        let describe = || {
+            provider
+                .handle_describe_table(DescribeTableInput {
+                    table_name: "TestTable".to_owned(),
+                })
+                .unwrap()
+                .table
+                .unwrap()
+        };
+
+        let indexed_key = HashMap::from([
+            ("pk".to_owned(), AttributeValue::S("a".to_owned())),
+            ("status".to_owned(), AttributeValue::S("open".to_owned())),
+        ]);
+        let mut indexed = indexed_key.clone();
+        indexed.insert("body".to_owned(), AttributeValue::S("x".repeat(10)));
+        let unindexed = HashMap::from([("pk".to_owned(), AttributeValue::S("b".to_owned()))]);
+        put(&indexed);
+        put(&unindexed);
+
+        // Overwriting an item replaces its size rather than adding to it.
+        indexed.insert("body".to_owned(), AttributeValue::S("x".repeat(100)));
+        put(&indexed);
+        let table = describe();
+        let table_size = calculate_item_size(&indexed) + calculate_item_size(&unindexed);
+        assert_eq!(table.item_count, Some(2));
+        assert_eq!(table.table_size_bytes, i64::try_from(table_size).ok());
+        let gsi = &table.global_secondary_indexes[0];
+        assert_eq!(gsi.item_count, Some(1));
+        assert_eq!(
+            gsi.index_size_bytes,
+            i64::try_from(calculate_item_size(&indexed_key)).ok()
+        );
+
+        provider
+            .handle_delete_item(DeleteItemInput {
+                table_name: "TestTable".to_owned(),
+                key: HashMap::from([("pk".to_owned(), AttributeValue::S("a".to_owned()))]),
+                ..Default::default()
+            })
+            .unwrap();
+        let table = describe();
+        assert_eq!(table.item_count, Some(1));
+        assert_eq!(
+            table.table_size_bytes,
+            i64::try_from(calculate_item_size(&unindexed)).ok()
+        );
+        assert_eq!(table.global_secondary_indexes[0].item_count, Some(0));
+        assert_eq!(table.global_secondary_indexes[0].index_size_bytes, Some(0));
+    }
+
+    #[test]
+    fn test_should_restore_backup_into_renamed_table_with_gsi() {
+        let provider = RustackDynamoDB::new(DynamoDBConfig::default());
+        let mut input = test_table_input();
+        input.attribute_definitions.push(AttributeDefinition {
+            attribute_name: "status".to_owned(),
+            attribute_type: ScalarAttributeType::S,
+        });
+        input.global_secondary_indexes =
+            vec![rustack_dynamodb_model::types::GlobalSecondaryIndex {
+                index_name: "by-status".to_owned(),
+                key_schema: vec![KeySchemaElement {
+                    attribute_name: "status".to_owned(),
+                    key_type: KeyType::Hash,
+                }],
+                projection: rustack_dynamodb_model::types::Projection {
+                    projection_type: Some(rustack_dynamodb_model::types::ProjectionType::All),
+                    ..Default::default()
+                },
+                provisioned_throughput: None,
+            }];
+        provider.handle_create_table(input).unwrap();
+        let item = HashMap::from([
+            ("pk".to_owned(), AttributeValue::S("a".to_owned())),
+            ("status".to_owned(), AttributeValue::S("open".to_owned())),
+        ]);
+        provider
+            .handle_put_item(PutItemInput {
+                table_name: "TestTable".to_owned(),
+                item: item.clone(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let backup_arn = create_test_backup(&provider, "before-migration");
+        assert!(backup_arn.contains(":table/TestTable/backup/"));
+
+        // Writes after the backup are not part of it.
+        provider
+            .handle_delete_item(DeleteItemInput {
+                table_name: "TestTable".to_owned(),
+                key: HashMap::from([("pk".to_owned(), AttributeValue::S("a".to_owned()))]),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let restored = provider
+            .handle_restore_table_from_backup(RestoreTableFromBackupInput {
+                target_table_name: "Restored".to_owned(),
+                backup_arn: backup_arn.clone(),
+                ..Default::default()
+            })
+            .unwrap()
+            .table_description
+            .unwrap();
+        assert!(restored.table_arn.unwrap().ends_with(":table/Restored"));
+        assert_eq!(restored.item_count, Some(1));
+        assert_eq!(restored.global_secondary_indexes.len(), 1);
+        assert!(
+            restored.global_secondary_indexes[0]
+                .index_arn
+                .as_deref()
+                .unwrap()
+                .ends_with(":table/Restored/index/by-status")
+        );
+
+        let got = provider
+            .handle_get_item(GetItemInput {
+                table_name: "Restored".to_owned(),
+                key: HashMap::from([("pk".to_owned(), AttributeValue::S("a".to_owned()))]),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(got.item, Some(item));
+
+        let err = provider
+            .handle_restore_table_from_backup(RestoreTableFromBackupInput {
+                target_table_name: "TestTable".to_owned(),
+                backup_arn,
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert_eq!(err.code, DynamoDBErrorCode::TableAlreadyExistsException);
+    }
+
+    #[test]
+    fn test_should_list_backups_with_filters_and_pagination() {
+        let provider = setup_provider_with_table();
+        let first = create_test_backup(&provider, "first");
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        let second = create_test_backup(&provider, "second");
+
+        let page = provider
+            .handle_list_backups(ListBackupsInput {
+                table_name: Some("TestTable".to_owned()),
+                limit: Some(1),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(page.backup_summaries.len(), 1);
+        assert_eq!(
+            page.backup_summaries[0].backup_arn.as_deref(),
+            Some(first.as_str())
+        );
+        assert_eq!(
+            page.last_evaluated_backup_arn.as_deref(),
+            Some(first.as_str())
+        );
+
+        let page = provider
+            .handle_list_backups(ListBackupsInput {
+                exclusive_start_backup_arn: Some(first.clone()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(page.backup_summaries.len(), 1);
+        assert!(page.last_evaluated_backup_arn.is_none());
+
+        let second_created = provider
+            .handle_describe_backup(DescribeBackupInput {
+                backup_arn: second.clone(),
+            })
+            .unwrap()
+            .backup_description
+            .unwrap()
+            .backup_details
+            .unwrap()
+            .backup_creation_date_time;
+        let page = provider
+            .handle_list_backups(ListBackupsInput {
+                time_range_lower_bound: Some(second_created),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(page.backup_summaries.len(), 1);
+        assert_eq!(
+            page.backup_summaries[0].backup_arn.as_deref(),
+            Some(second.as_str())
+        );
+
+        let page = provider
+            .handle_list_backups(ListBackupsInput {
+                table_name: Some("OtherTable".to_owned()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(page.backup_summaries.is_empty());
+
+        let deleted = provider
+            .handle_delete_backup(DeleteBackupInput {
+                backup_arn: first.clone(),
+            })
+            .unwrap()
+            .backup_description
+            .unwrap()
+            .backup_details
+            .unwrap();
+        assert_eq!(deleted.backup_status, BackupStatus::Deleted);
+        let err = provider
+            .handle_describe_backup(DescribeBackupInput { backup_arn: first })
+            .unwrap_err();
+        assert_eq!(err.code, DynamoDBErrorCode::BackupNotFoundException);
+    }
+
+    fn enable_test_pitr(
+        provider: &RustackDynamoDB,
+        days: Option<i32>,
+    ) -> Result<(), DynamoDBError> {
+        provider
+            .handle_update_continuous_backups(UpdateContinuousBackupsInput {
+                table_name: "TestTable".to_owned(),
+                point_in_time_recovery_specification:
+                    rustack_dynamodb_model::types::PointInTimeRecoverySpecification {
+                        point_in_time_recovery_enabled: true,
+                        recovery_period_in_days: days,
+                    },
+            })
+            .map(|_| ())
+    }
+
+    #[test]
+    fn test_should_describe_and_update_continuous_backups() {
+        use rustack_dynamodb_model::types::PointInTimeRecoveryStatus;
+
+        let provider = setup_provider_with_table();
+        let describe = || {
+            provider
+                .handle_describe_continuous_backups(DescribeContinuousBackupsInput {
+                    table_name: "TestTable".to_owned(),
+                })
+                .unwrap()
+                .continuous_backups_description
+                .unwrap()
+                .point_in_time_recovery_description
+                .unwrap()
+        };
+        let pitr = describe();
+        assert_eq!(
+            pitr.point_in_time_recovery_status,
+            Some(PointInTimeRecoveryStatus::Disabled)
+        );
+        assert!(pitr.earliest_restorable_date_time.is_none());
+
+        enable_test_pitr(&provider, Some(7)).unwrap();
+        let pitr = describe();
+        assert_eq!(
+            pitr.point_in_time_recovery_status,
+            Some(PointInTimeRecoveryStatus::Enabled)
+        );
+        assert_eq!(pitr.recovery_period_in_days, Some(7));
+        assert!(
+            pitr.earliest_restorable_date_time.unwrap()
+                <= pitr.latest_restorable_date_time.unwrap()
+        );
+
+        let err = enable_test_pitr(&provider, Some(36)).unwrap_err();
+        assert_eq!(err.code, DynamoDBErrorCode::ValidationException);
+        assert_eq!(
+            err.message,
+            "1 validation error detected: Value '36' at \
+             'pointInTimeRecoverySpecification.recoveryPeriodInDays' failed to satisfy \
+             constraint: Member must have value between 1 and 35"
+        );
+
+        let err = provider
+            .handle_describe_continuous_backups(DescribeContinuousBackupsInput {
+                table_name: "Missing".to_owned(),
+            })
+            .unwrap_err();
+        assert_eq!(err.code, DynamoDBErrorCode::ResourceNotFoundException);
+    }
+
+    #[test]
+    fn test_should_restore_table_to_point_in_time() {
+        let provider = setup_provider_with_table();
+        provider
+            .handle_put_item(PutItemInput {
+                table_name: "TestTable".to_owned(),
+                item: HashMap::from([("pk".to_owned(), AttributeValue::S("a".to_owned()))]),
+                ..Default::default()
+            })
+            .unwrap();
+        let restore = |restore_date_time: Option<f64>| {
+            provider.handle_restore_table_to_point_in_time(RestoreTableToPointInTimeInput {
+                source_table_name: Some("TestTable".to_owned()),
+                target_table_name: "Restored".to_owned(),
+                use_latest_restorable_time: Some(restore_date_time.is_none()),
+                restore_date_time,
+                ..Default::default()
+            })
+        };
+
+        let err = restore(None).unwrap_err();
+        assert_eq!(
+            err.code,
+            DynamoDBErrorCode::PointInTimeRecoveryUnavailableException
+        );
+
+        enable_test_pitr(&provider, None).unwrap();
+        let err = restore(Some(0.0)).unwrap_err();
+        assert_eq!(err.code, DynamoDBErrorCode::InvalidRestoreTimeException);
+
+        let restored = restore(None).unwrap().table_description.unwrap();
+        assert_eq!(restored.table_name.as_deref(), Some("Restored"));
+        assert_eq!(restored.item_count, Some(1));
+
+        let err = restore(None).unwrap_err();
+        assert_eq!(err.code, DynamoDBErrorCode::TableAlreadyExistsException);
+    }
+
+    /// Page through a filtered Scan three items at a time, returning the
+    /// matched keys and the number of pages read.
+    fn scan_all_pages(provider: &RustackDynamoDB, filter: Option<&str>) -> (Vec<String>, usize) {
+        let mut keys = Vec::new();
+        let mut pages = 0;
+        let mut start = HashMap::new();
+        loop {
+            let page = provider
+                .handle_scan(ScanInput {
+                    table_name: "TestTable".to_owned(),
+                    limit: Some(3),
+                    filter_expression: filter.map(ToOwned::to_owned),
+                    expression_attribute_values: filter
+                        .map(|_| HashMap::from([(":t".to_owned(), AttributeValue::Bool(true))]))
+                        .unwrap_or_default(),
+                    exclusive_start_key: start,
+                    ..Default::default()
+                })
+                .unwrap();
+            pages += 1;
+            // Every page reads three items, whether or not any match.
+            assert_eq!(page.scanned_count, 3);
+            keys.extend(
+                page.items
+                    .unwrap()
+                    .into_iter()
+                    .map(|item| match &item["pk"] {
+                        AttributeValue::S(pk) => pk.clone(),
+                        other => panic!("unexpected key {other:?}"),
+                    }),
+            );
+            let Some(key) = page.last_evaluated_key else {
+                return (keys, pages);
+            };
+            start = key;
+        }
+    }
+
+    #[test]
+    fn test_should_paginate_filtered_scan_past_empty_pages() {
+        let provider = setup_provider_with_table();
+        for i in 0..9 {
+            provider
+                .handle_put_item(PutItemInput {
+                    table_name: "TestTable".to_owned(),
+                    item: HashMap::from([(
+                        "pk".to_owned(),
+                        AttributeValue::S(format!("item-{i}")),
+                    )]),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        let (order, _) = scan_all_pages(&provider, None);
+        assert_eq!(order.len(), 9);
+
+        // Only the items on the third page match the filter.
+        for pk in &order[6..] {
+            provider
+                .handle_put_item(PutItemInput {
+                    table_name: "TestTable".to_owned(),
+                    item: HashMap::from([
+                        ("pk".to_owned(), AttributeValue::S(pk.clone())),
+                        ("hit".to_owned(), AttributeValue::Bool(true)),
+                    ]),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+
+        let (matched, pages) = scan_all_pages(&provider, Some("hit = :t"));
+        assert_eq!(matched, order[6..]);
+        assert_eq!(pages, 3);
+    }
+
+    /// Keys of every item a scan of `segment` returns, following
+    /// `LastEvaluatedKey` across pages.
+    fn scan_segment_keys(
+        provider: &RustackDynamoDB,
+        segment: Option<(i32, i32)>,
+        limit: Option<i32>,
+    ) -> Vec<(String, String)> {
+        let mut keys = Vec::new();
+        let mut start = HashMap::new();
+        loop {
+            let page = provider
+                .handle_scan(ScanInput {
+                    table_name: "TestTable".to_owned(),
+                    segment: segment.map(|(segment, _)| segment),
+                    total_segments: segment.map(|(_, total)| total),
+                    limit,
+                    exclusive_start_key: start,
+                    ..Default::default()
+                })
+                .unwrap();
+            keys.extend(
+                page.items
+                    .unwrap()
+                    .iter()
+                    .map(|item| match (&item["pk"], &item["sk"]) {
+                        (AttributeValue::N(pk), AttributeValue::S(sk)) => (pk.clone(), sk.clone()),
+                        other => panic!("unexpected key {other:?}"),
+                    }),
+            );
+            let Some(key) = page.last_evaluated_key else {
+                return keys;
+            };
+            start = key;
+        }
+    }
+
+    #[test]
+    fn test_should_cover_table_exactly_once_with_parallel_scan_segments() {
+        let provider = RustackDynamoDB::new(DynamoDBConfig::default());
+        let mut input = test_table_input();
+        input.key_schema.push(KeySchemaElement {
+            attribute_name: "sk".to_owned(),
+            key_type: KeyType::Range,
+        });
+        input.attribute_definitions[0].attribute_type = ScalarAttributeType::N;
+        input.attribute_definitions.push(AttributeDefinition {
+            attribute_name: "sk".to_owned(),
+            attribute_type: ScalarAttributeType::S,
+        });
+        provider.handle_create_table(input).unwrap();
+
+        // A deterministic xorshift generator keeps failures reproducible.
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..10_000 {
+            let pk = next() % 1_500;
+            // Several spellings of the same numbers, as clients may send.
+            let pk = match next() % 4 {
+                0 => format!("{pk}.0"),
+                1 if pk > 0 => format!("{}e1", pk / 10).replace("0e1", "0"),
+                _ => pk.to_string(),
+            };
+            let payload = "x".repeat(usize::try_from(next() % 400).unwrap());
+            provider
+                .handle_put_item(PutItemInput {
+                    table_name: "TestTable".to_owned(),
+                    item: HashMap::from([
+                        ("pk".to_owned(), AttributeValue::N(pk)),
+                        (
+                            "sk".to_owned(),
+                            AttributeValue::S(format!("{:06}", next() % 50)),
+                        ),
+                        ("payload".to_owned(), AttributeValue::S(payload)),
+                    ]),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+
+        let mut full = scan_segment_keys(&provider, None, None);
+        full.sort();
+        assert!(full.len() > 5_000, "{}", full.len());
+
+        for total in [2, 5, 17] {
+            for limit in [None, Some(97)] {
+                let mut union: Vec<_> = (0..total)
+                    .flat_map(|segment| scan_segment_keys(&provider, Some((segment, total)), limit))
+                    .collect();
+                union.sort();
+                assert_eq!(
+                    union.len(),
+                    full.len(),
+                    "TotalSegments={total}, Limit={limit:?}"
+                );
+                assert!(union == full, "TotalSegments={total}, Limit={limit:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_should_paginate_filtered_query_past_empty_pages() {
+        let provider = RustackDynamoDB::new(DynamoDBConfig::default());
+        let mut input = test_table_input();
+        input.key_schema.push(KeySchemaElement {
+            attribute_name: "sk".to_owned(),
+            key_type: KeyType::Range,
+        });
+        input.attribute_definitions.push(AttributeDefinition {
+            attribute_name: "sk".to_owned(),
+            attribute_type: ScalarAttributeType::N,
+        });
+        provider.handle_create_table(input).unwrap();
+        for i in 0..9 {
+            let mut item = HashMap::from([
+                ("pk".to_owned(), AttributeValue::S("p".to_owned())),
+                ("sk".to_owned(), AttributeValue::N(i.to_string())),
+            ]);
+            if i >= 6 {
+                item.insert("hit".to_owned(), AttributeValue::Bool(true));
+            }
+            provider
+                .handle_put_item(PutItemInput {
+                    table_name: "TestTable".to_owned(),
+                    item,
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+
+        let mut matched = Vec::new();
+        let mut pages = Vec::new();
+        let mut start = HashMap::new();
+        loop {
+            let page = provider
+                .handle_query(QueryInput {
+                    table_name: "TestTable".to_owned(),
+                    key_condition_expression: Some("pk = :p".to_owned()),
+                    filter_expression: Some("hit = :t".to_owned()),
+                    expression_attribute_values: HashMap::from([
+                        (":p".to_owned(), AttributeValue::S("p".to_owned())),
+                        (":t".to_owned(), AttributeValue::Bool(true)),
+                    ]),
+                    limit: Some(3),
+                    exclusive_start_key: start,
+                    ..Default::default()
+                })
+                .unwrap();
+            pages.push((page.count, page.scanned_count));
+            matched.extend(
+                page.items
+                    .unwrap()
+                    .into_iter()
+                    .map(|item| item["sk"].clone()),
+            );
+            let Some(key) = page.last_evaluated_key else {
+                break;
+            };
+            start = key;
+        }
+
+        assert_eq!(pages, [(0, 3), (0, 3), (3, 3)]);
+        assert_eq!(
+            matched,
+            ["6", "7", "8"].map(|n| AttributeValue::N(n.to_owned()))
+        );
+    }
+
+    /// A table keyed `pk`/`sk` (N) with an index keyed `owner`/`rank` (N),
+    /// holding `sk` 0..50 in partition `p`, ranked `sk % 5`.
+    fn setup_paginated_partition() -> RustackDynamoDB {
+        let provider = RustackDynamoDB::new(DynamoDBConfig::default());
+        let mut input = test_table_input();
+        input.key_schema.push(KeySchemaElement {
+            attribute_name: "sk".to_owned(),
+            key_type: KeyType::Range,
+        });
+        for (name, attribute_type) in [
+            ("sk", ScalarAttributeType::N),
+            ("owner", ScalarAttributeType::S),
+            ("rank", ScalarAttributeType::N),
+        ] {
+            input.attribute_definitions.push(AttributeDefinition {
+                attribute_name: name.to_owned(),
+                attribute_type,
+            });
+        }
+        input.global_secondary_indexes =
+            vec![rustack_dynamodb_model::types::GlobalSecondaryIndex {
+                index_name: "by-owner".to_owned(),
+                key_schema: vec![
+                    KeySchemaElement {
+                        attribute_name: "owner".to_owned(),
+                        key_type: KeyType::Hash,
+                    },
+                    KeySchemaElement {
+                        attribute_name: "rank".to_owned(),
+                        key_type: KeyType::Range,
+                    },
+                ],
+                projection: rustack_dynamodb_model::types::Projection {
+                    projection_type: Some(rustack_dynamodb_model::types::ProjectionType::All),
+                    ..Default::default()
+                },
+                provisioned_throughput: None,
+            }];
+        provider.handle_create_table(input).unwrap();
+        for i in 0..50 {
+            provider
+                .handle_put_item(PutItemInput {
+                    table_name: "TestTable".to_owned(),
+                    item: HashMap::from([
+                        ("pk".to_owned(), AttributeValue::S("p".to_owned())),
+                        ("sk".to_owned(), AttributeValue::N(i.to_string())),
+                        ("owner".to_owned(), AttributeValue::S("o".to_owned())),
+                        ("rank".to_owned(), AttributeValue::N((i % 5).to_string())),
+                        ("odd".to_owned(), AttributeValue::Bool(i % 2 == 1)),
+                    ]),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        provider
+    }
+
+    /// Run `input` one item per page, returning the `sk` of every item and
+    /// checking each `LastEvaluatedKey` holds exactly `key_attrs`.
+    fn query_one_per_page(
+        provider: &RustackDynamoDB,
+        input: &QueryInput,
+        key_attrs: &[&str],
+    ) -> Vec<i64> {
+        let mut sort_keys = Vec::new();
+        let mut start = HashMap::new();
+        for _ in 0..100 {
+            let page = provider
+                .handle_query(QueryInput {
+                    limit: Some(1),
+                    exclusive_start_key: start,
+                    ..input.clone()
+                })
+                .unwrap();
+            sort_keys.extend(page.items.unwrap().iter().map(|item| {
+                let AttributeValue::N(n) = &item["sk"] else {
+                    panic!("sk is not a number");
+                };
+                n.parse::<i64>().unwrap()
+            }));
+            let Some(key) = page.last_evaluated_key else {
+                return sort_keys;
+            };
+            let mut names: Vec<_> = key.keys().map(String::as_str).collect();
+            names.sort_unstable();
+            assert_eq!(names, key_attrs);
+            start = key;
+        }
+        panic!("query did not finish in 100 pages");
+    }
+
+    fn partition_query(condition: &str, values: &[(&str, AttributeValue)]) -> QueryInput {
+        let mut expression_attribute_values =
+            HashMap::from([(":p".to_owned(), AttributeValue::S("p".to_owned()))]);
+        for (name, value) in values {
+            expression_attribute_values.insert((*name).to_owned(), value.clone());
+        }
+        QueryInput {
+            table_name: "TestTable".to_owned(),
+            key_condition_expression: Some(condition.to_owned()),
+            expression_attribute_values,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_should_paginate_filtered_count_like_the_items_path() {
+        let provider = setup_paginated_partition();
+        // Only every fifth item matches, so most two-item pages count zero.
+        let filter = || QueryInput {
+            filter_expression: Some("#rank = :r".to_owned()),
+            expression_attribute_names: HashMap::from([("#rank".to_owned(), "rank".to_owned())]),
+            ..partition_query("pk = :p", &[(":r", AttributeValue::N("0".to_owned()))])
+        };
+        let ground_truth = provider.handle_query(filter()).unwrap().count;
+        assert_eq!(ground_truth, 10);
+
+        let (mut count, mut scanned_count, mut pages) = (0, 0, 0);
+        let mut start = HashMap::new();
+        loop {
+            let page = provider
+                .handle_query(QueryInput {
+                    select: Some(Select::Count),
+                    limit: Some(2),
+                    exclusive_start_key: start,
+                    ..filter()
+                })
+                .unwrap();
+            assert!(page.items.is_none());
+            count += page.count;
+            scanned_count += page.scanned_count;
+            pages += 1;
+            let Some(key) = page.last_evaluated_key else {
+                break;
+            };
+            start = key;
+        }
+        assert_eq!((count, scanned_count, pages), (ground_truth, 50, 25));
+
+        let (mut count, mut scanned_count) = (0, 0);
+        let mut start = HashMap::new();
+        loop {
+            let page = provider
+                .handle_scan(ScanInput {
+                    table_name: "TestTable".to_owned(),
+                    select: Some(Select::Count),
+                    limit: Some(3),
+                    filter_expression: Some("#rank = :r".to_owned()),
+                    expression_attribute_names: HashMap::from([(
+                        "#rank".to_owned(),
+                        "rank".to_owned(),
+                    )]),
+                    expression_attribute_values: HashMap::from([(
+                        ":r".to_owned(),
+                        AttributeValue::N("0".to_owned()),
+                    )]),
+                    exclusive_start_key: start,
+                    ..Default::default()
+                })
+                .unwrap();
+            assert!(page.scanned_count <= 3);
+            count += page.count;
+            scanned_count += page.scanned_count;
+            let Some(key) = page.last_evaluated_key else {
+                break;
+            };
+            start = key;
+        }
+        assert_eq!((count, scanned_count), (ground_truth, 50));
+    }
+
+    #[test]
+    fn test_should_paginate_query_one_item_at_a_time() {
+        let provider = setup_paginated_partition();
+        let table_keys = ["pk", "sk"];
+        let all: Vec<i64> = (0..50).collect();
+
+        let forward = partition_query("pk = :p", &[]);
+        assert_eq!(query_one_per_page(&provider, &forward, &table_keys), all);
+
+        let backward = QueryInput {
+            scan_index_forward: Some(false),
+            ..forward.clone()
+        };
+        let reversed: Vec<i64> = all.iter().rev().copied().collect();
+        assert_eq!(
+            query_one_per_page(&provider, &backward, &table_keys),
+            reversed
+        );
+
+        let filtered = QueryInput {
+            filter_expression: Some("odd = :t".to_owned()),
+            ..partition_query("pk = :p", &[(":t", AttributeValue::Bool(true))])
+        };
+        let odd: Vec<i64> = all.iter().copied().filter(|i| i % 2 == 1).collect();
+        assert_eq!(query_one_per_page(&provider, &filtered, &table_keys), odd);
+
+        let between = partition_query(
+            "pk = :p AND sk BETWEEN :lo AND :hi",
+            &[
+                (":lo", AttributeValue::N("10".to_owned())),
+                (":hi", AttributeValue::N("20".to_owned())),
+            ],
+        );
+        let range: Vec<i64> = (10..=20).collect();
+        assert_eq!(query_one_per_page(&provider, &between, &table_keys), range);
+    }
+
+    #[test]
+    fn test_should_reject_items_nested_past_the_limit() {
+        let provider = setup_provider_with_table();
+        let nested = |levels: usize| {
+            (0..levels).fold(AttributeValue::S("leaf".to_owned()), |inner, _| {
+                AttributeValue::M(HashMap::from([("a".to_owned(), inner)]))
+            })
+        };
+        let key = HashMap::from([("pk".to_owned(), AttributeValue::S("k".to_owned()))]);
+        let put = |levels: usize| {
+            let mut item = key.clone();
+            item.insert("doc".to_owned(), nested(levels));
+            provider.handle_put_item(PutItemInput {
+                table_name: "TestTable".to_owned(),
+                item,
+                ..Default::default()
+            })
+        };
+
+        put(MAX_NESTING_DEPTH).unwrap();
+        let err = put(MAX_NESTING_DEPTH + 1).unwrap_err();
+        assert_eq!(err.code, DynamoDBErrorCode::ValidationException);
+        assert_eq!(err.message, NESTING_LIMIT_MESSAGE);
+
+        // Updates may not deepen a stored value past the limit either.
+        let err = provider
+            .handle_update_item(UpdateItemInput {
+                table_name: "TestTable".to_owned(),
+                key: key.clone(),
+                update_expression: Some("SET doc.a = :deeper".to_owned()),
+                expression_attribute_values: HashMap::from([(
+                    ":deeper".to_owned(),
+                    nested(MAX_NESTING_DEPTH),
+                )]),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert_eq!(err.message, NESTING_LIMIT_MESSAGE);
+        let stored = provider
+            .handle_get_item(GetItemInput {
+                table_name: "TestTable".to_owned(),
+                key,
+                ..Default::default()
+            })
+            .unwrap()
+            .item
+            .unwrap();
+        assert_eq!(stored["doc"].nesting_depth(), MAX_NESTING_DEPTH);
+    }
+
+    #[test]
+    fn test_should_reject_add_to_mismatched_type_and_keep_the_item() {
+        let provider = setup_provider_with_table();
+        let key = HashMap::from([("pk".to_owned(), AttributeValue::S("k".to_owned()))]);
+        let mut item = key.clone();
+        item.insert("label".to_owned(), AttributeValue::S("x".to_owned()));
+        item.insert("tags".to_owned(), AttributeValue::Ns(vec!["1".to_owned()]));
+        provider
+            .handle_put_item(PutItemInput {
+                table_name: "TestTable".to_owned(),
+                item: item.clone(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        for (attr, operand, types) in [
+            (
+                "label",
+                AttributeValue::N("1".to_owned()),
+                "N, existing type: S",
+            ),
+            (
+                "tags",
+                AttributeValue::Ss(vec!["a".to_owned()]),
+                "SS, existing type: NS",
+            ),
+        ] {
+            let err = provider
+                .handle_update_item(UpdateItemInput {
+                    table_name: "TestTable".to_owned(),
+                    key: key.clone(),
+                    update_expression: Some(format!("ADD {attr} :v")),
+                    expression_attribute_values: HashMap::from([(":v".to_owned(), operand)]),
+                    ..Default::default()
+                })
+                .unwrap_err();
+            assert_eq!(err.code, DynamoDBErrorCode::ValidationException);
+            assert_eq!(
+                err.message,
+                format!(
+                    "An operand in the update expression has an incorrect data type; operator: \
+                     ADD, operand type: {types}"
+                )
+            );
+        }
+
+        let stored = provider
+            .handle_get_item(GetItemInput {
+                table_name: "TestTable".to_owned(),
+                key,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(stored.item, Some(item));
+    }
+
+    #[test]
+    fn test_should_project_the_same_way_in_every_read() {
+        let provider = setup_provider_with_table();
+        let n = |v: &str| AttributeValue::N(v.to_owned());
+        let s = |v: &str| AttributeValue::S(v.to_owned());
+        provider
+            .handle_put_item(PutItemInput {
+                table_name: "TestTable".to_owned(),
+                item: HashMap::from([
+                    ("pk".to_owned(), s("k")),
+                    (
+                        "a".to_owned(),
+                        AttributeValue::M(HashMap::from([
+                            ("b".to_owned(), n("1")),
+                            ("c".to_owned(), n("2")),
+                            ("d".to_owned(), n("3")),
+                        ])),
+                    ),
+                    (
+                        "l".to_owned(),
+                        AttributeValue::L(vec![s("x"), s("y"), s("z")]),
+                    ),
+                ]),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let key = HashMap::from([("pk".to_owned(), s("k"))]);
+        let read_all = |projection: &str| {
+            let names = HashMap::from([("#a".to_owned(), "a".to_owned())]);
+            let get = provider.handle_get_item(GetItemInput {
+                table_name: "TestTable".to_owned(),
+                key: key.clone(),
+                projection_expression: Some(projection.to_owned()),
+                expression_attribute_names: names.clone(),
+                ..Default::default()
+            });
+            let query = provider.handle_query(QueryInput {
+                projection_expression: Some(projection.to_owned()),
+                expression_attribute_names: names.clone(),
+                ..partition_query("pk = :p", &[(":p", s("k"))])
+            });
+            let scan = provider.handle_scan(ScanInput {
+                table_name: "TestTable".to_owned(),
+                projection_expression: Some(projection.to_owned()),
+                expression_attribute_names: names.clone(),
+                ..Default::default()
+            });
+            let batch = provider.handle_batch_get_item(BatchGetItemInput {
+                request_items: HashMap::from([(
+                    "TestTable".to_owned(),
+                    KeysAndAttributes {
+                        keys: vec![key.clone()],
+                        projection_expression: Some(projection.to_owned()),
+                        expression_attribute_names: Some(names.clone()),
+                        consistent_read: None,
+                        attributes_to_get: Vec::new(),
+                    },
+                )]),
+                ..Default::default()
+            });
+            let transact = provider.handle_transact_get_items(TransactGetItemsInput {
+                transact_items: vec![TransactGetItem {
+                    get: Get {
+                        table_name: "TestTable".to_owned(),
+                        key: key.clone(),
+                        projection_expression: Some(projection.to_owned()),
+                        expression_attribute_names: Some(names),
+                    },
+                }],
+                ..Default::default()
+            });
+            [
+                get.map(|out| out.item.unwrap()),
+                query.map(|out| out.items.unwrap().remove(0)),
+                scan.map(|out| out.items.unwrap().remove(0)),
+                batch.map(|mut out| out.responses.remove("TestTable").unwrap().remove(0)),
+                transact.map(|out| out.responses.unwrap().remove(0).item.unwrap()),
+            ]
+        };
+
+        let expected = HashMap::from([
+            (
+                "a".to_owned(),
+                AttributeValue::M(HashMap::from([
+                    ("b".to_owned(), n("1")),
+                    ("c".to_owned(), n("2")),
+                ])),
+            ),
+            ("l".to_owned(), AttributeValue::L(vec![s("x"), s("z")])),
+        ]);
+        for projected in read_all("a.b, #a.c, l[2], l[0]") {
+            assert_eq!(projected.unwrap(), expected);
+        }
+
+        for result in read_all("#a, a.b") {
+            assert_eq!(
+                result.unwrap_err().message,
+                "Invalid ProjectionExpression: Two document paths overlap with each other; must \
+                 remove or rewrite one of these paths; path one: [#a], path two: [a.b]"
+            );
+        }
+    }
+
+    #[test]
+    fn test_should_limit_descending_queries_from_the_high_end() {
+        let provider = setup_paginated_partition();
+        let n = |v: &str| AttributeValue::N(v.to_owned());
+        let between = partition_query(
+            "pk = :p AND sk BETWEEN :lo AND :hi",
+            &[(":lo", n("10")), (":hi", n("30"))],
+        );
+        let at_least = partition_query("pk = :p AND sk >= :lo", &[(":lo", n("40"))]);
+        let by_rank = QueryInput {
+            index_name: Some("by-owner".to_owned()),
+            key_condition_expression: Some("#o = :o AND #r BETWEEN :lo AND :hi".to_owned()),
+            expression_attribute_names: HashMap::from([
+                ("#o".to_owned(), "owner".to_owned()),
+                ("#r".to_owned(), "rank".to_owned()),
+            ]),
+            expression_attribute_values: HashMap::from([
+                (":o".to_owned(), AttributeValue::S("o".to_owned())),
+                (":lo".to_owned(), n("1")),
+                (":hi".to_owned(), n("2")),
+            ]),
+            table_name: "TestTable".to_owned(),
+            ..Default::default()
+        };
+        let mut ranked: Vec<i64> = (0..50).filter(|i| (1..=2).contains(&(i % 5))).collect();
+        ranked.sort_by_key(|i| (i % 5, *i));
+
+        for (input, key_attrs, ascending) in [
+            (between, &["pk", "sk"][..], (10..=30).collect::<Vec<i64>>()),
+            (at_least, &["pk", "sk"], (40..50).collect()),
+            (by_rank, &["owner", "pk", "rank", "sk"], ranked),
+        ] {
+            let descending = QueryInput {
+                scan_index_forward: Some(false),
+                ..input.clone()
+            };
+            let first_page = provider
+                .handle_query(QueryInput {
+                    limit: Some(5),
+                    ..descending.clone()
+                })
+                .unwrap();
+            let first: Vec<_> = first_page
+                .items
+                .unwrap()
+                .into_iter()
+                .map(|item| item["sk"].clone())
+                .collect();
+            let expected: Vec<_> = ascending
+                .iter()
+                .rev()
+                .take(5)
+                .map(|i| n(&i.to_string()))
+                .collect();
+            assert_eq!(first, expected);
+
+            let reversed: Vec<i64> = ascending.iter().rev().copied().collect();
+            assert_eq!(
+                query_one_per_page(&provider, &descending, key_attrs),
+                reversed
+            );
+            assert_eq!(query_one_per_page(&provider, &input, key_attrs), ascending);
+        }
+    }
+
+    #[test]
+    fn test_should_paginate_index_query_with_index_and_table_keys() {
+        let provider = setup_paginated_partition();
+        let input = QueryInput {
+            table_name: "TestTable".to_owned(),
+            index_name: Some("by-owner".to_owned()),
+            key_condition_expression: Some("#o = :o".to_owned()),
+            expression_attribute_names: HashMap::from([("#o".to_owned(), "owner".to_owned())]),
+            expression_attribute_values: HashMap::from([(
+                ":o".to_owned(),
+                AttributeValue::S("o".to_owned()),
+            )]),
+            ..Default::default()
+        };
+        let mut expected: Vec<i64> = (0..50).collect();
+        expected.sort_by_key(|i| (i % 5, *i));
+        assert_eq!(
+            query_one_per_page(&provider, &input, &["owner", "pk", "rank", "sk"]),
+            expected
+        );
+
+        // Resuming after an item that has since been deleted continues with
+        // the item after it rather than returning nothing.
+        let first = provider
+            .handle_query(QueryInput {
+                limit: Some(1),
+                ..input.clone()
+            })
+            .unwrap();
+        provider
+            .handle_delete_item(DeleteItemInput {
+                table_name: "TestTable".to_owned(),
+                key: HashMap::from([
+                    ("pk".to_owned(), AttributeValue::S("p".to_owned())),
+                    ("sk".to_owned(), AttributeValue::N("0".to_owned())),
+                ]),
+                ..Default::default()
+            })
+            .unwrap();
+        let next = provider
+            .handle_query(QueryInput {
+                limit: Some(1),
+                exclusive_start_key: first.last_evaluated_key.unwrap(),
+                ..input
+            })
+            .unwrap();
+        assert_eq!(
+            next.items.unwrap()[0]["sk"],
+            AttributeValue::N("5".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_should_resume_query_from_start_key_outside_the_condition() {
+        let provider = setup_paginated_partition();
+        let start = HashMap::from([
+            ("pk".to_owned(), AttributeValue::S("p".to_owned())),
+            ("sk".to_owned(), AttributeValue::N("40".to_owned())),
+        ]);
+        let query = |condition: &str, values: &[(&str, &str)], forward: bool| {
+            let values: Vec<_> = values
+                .iter()
+                .map(|(name, n)| (*name, AttributeValue::N((*n).to_owned())))
+                .collect();
+            provider
+                .handle_query(QueryInput {
+                    scan_index_forward: Some(forward),
+                    exclusive_start_key: start.clone(),
+                    ..partition_query(condition, &values)
+                })
+                .unwrap()
+                .count
+        };
+        let range = [(":lo", "10"), (":hi", "20")];
+
+        // Past the end of the range in the query's direction: nothing left.
+        assert_eq!(query("pk = :p AND sk BETWEEN :lo AND :hi", &range, true), 0);
+        assert_eq!(query("pk = :p AND sk < :lo", &range[..1], true), 0);
+        assert_eq!(query("pk = :p AND sk = :lo", &range[..1], true), 0);
+        // Before the start of the range: all of it.
+        assert_eq!(
+            query("pk = :p AND sk BETWEEN :lo AND :hi", &range, false),
+            11
+        );
+        assert_eq!(query("pk = :p AND sk > :hi", &range[1..], false), 19);
+        assert_eq!(query("pk = :p AND sk = :hi", &range[1..], false), 1);
+    }
+
+    #[test]
+    fn test_should_round_trip_binary_last_evaluated_key() {
+        let provider = RustackDynamoDB::new(DynamoDBConfig::default());
+        let mut input = test_table_input();
+        input.key_schema.push(KeySchemaElement {
+            attribute_name: "sk".to_owned(),
+            key_type: KeyType::Range,
+        });
+        input.attribute_definitions.push(AttributeDefinition {
+            attribute_name: "sk".to_owned(),
+            attribute_type: ScalarAttributeType::B,
+        });
+        provider.handle_create_table(input).unwrap();
+        let sort_keys: Vec<bytes::Bytes> =
+            [&[0x00][..], &[0x7F, 0xFF], &[0x80], &[0xFF], &[0xFF, 0x00]]
+                .into_iter()
+                .map(bytes::Bytes::copy_from_slice)
+                .collect();
+        for sk in sort_keys.iter().rev() {
+            provider
+                .handle_put_item(PutItemInput {
+                    table_name: "TestTable".to_owned(),
+                    item: HashMap::from([
+                        ("pk".to_owned(), AttributeValue::S("p".to_owned())),
+                        ("sk".to_owned(), AttributeValue::B(sk.clone())),
+                    ]),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+
+        let mut seen = Vec::new();
+        let mut start = HashMap::new();
+        loop {
+            let page = provider
+                .handle_query(QueryInput {
+                    table_name: "TestTable".to_owned(),
+                    key_condition_expression: Some(
+                        "pk = :p AND sk BETWEEN :low AND :high".to_owned(),
+                    ),
+                    expression_attribute_values: HashMap::from([
+                        (":p".to_owned(), AttributeValue::S("p".to_owned())),
+                        (":low".to_owned(), AttributeValue::B(sort_keys[1].clone())),
+                        (":high".to_owned(), AttributeValue::B(sort_keys[4].clone())),
+                    ]),
+                    limit: Some(2),
+                    exclusive_start_key: start,
+                    ..Default::default()
+                })
+                .unwrap();
+            seen.extend(
+                page.items
+                    .unwrap()
+                    .into_iter()
+                    .map(|item| item["sk"].clone()),
+            );
+            let Some(key) = page.last_evaluated_key else {
+                break;
+            };
+            // Send the key back the way a client would: as base64 JSON.
+            let json = serde_json::to_string(&key).unwrap();
+            start = serde_json::from_str(&json).unwrap();
+            assert_eq!(start, key);
+        }
+
+        let expected: Vec<AttributeValue> = sort_keys[1..]
+            .iter()
+            .cloned()
+            .map(AttributeValue::B)
+            .collect();
+        assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn test_should_apply_config_updates_to_later_requests() {
+        let provider = setup_provider_with_table();
+        for i in 0..3 {
+            provider
+                .handle_put_item(PutItemInput {
+                    table_name: "TestTable".to_owned(),
+                    item: HashMap::from([
+                        ("pk".to_owned(), AttributeValue::S(format!("k{i}"))),
+                        ("blob".to_owned(), AttributeValue::S("x".repeat(390_000))),
+                    ]),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        let scan = |provider: &RustackDynamoDB| {
+            provider
+                .handle_scan(ScanInput {
+                    table_name: "TestTable".to_owned(),
+                    ..Default::default()
+                })
+                .unwrap()
+        };
+        let capped = scan(&provider);
+        assert_eq!(capped.count, 2);
+        assert!(capped.last_evaluated_key.is_some());
+
+        let patch = |value: serde_json::Value| value.as_object().cloned().unwrap();
+        let updated = provider
+            .update_config(&patch(serde_json::json!({
+                "response_size_limit": false,
+                "strict_reserved_words": false,
+            })))
+            .unwrap();
+        assert!(!updated.behavior.response_size_limit);
+        assert_eq!(provider.config(), updated);
+
+        // Handles to other namespaces share the configuration.
+        let other = provider.scoped(&RequestScope::resolve(
+            Some("111122223333"),
+            Some("eu-west-1"),
+            "us-east-1",
+        ));
+        assert!(!other.config().behavior.strict_reserved_words);
+
+        let uncapped = scan(&provider);
+        assert_eq!(uncapped.count, 3);
+        assert!(uncapped.last_evaluated_key.is_none());
+        provider
+            .handle_scan(ScanInput {
+                table_name: "TestTable".to_owned(),
+                projection_expression: Some("pk, name".to_owned()),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let err = provider
+            .update_config(&patch(serde_json::json!({
+                "response_size_limit": true,
+                "persistence_dir": "/var/lib/tables",
+            })))
+            .unwrap_err();
+        assert!(err.contains("persistence_dir cannot be changed"), "{err}");
+        assert_eq!(provider.config(), updated);
+    }
 }
@@ -1,26 +1,37 @@
 //! DynamoDB service state management.
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
+use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use rustack_dynamodb_model::{
-    error::DynamoDBError,
+    AttributeValue,
+    error::{DynamoDBError, DynamoDBErrorCode},
     types::{
-        AttributeDefinition, BillingMode, BillingModeSummary, GlobalSecondaryIndex,
+        AttributeDefinition, BillingMode, BillingModeSummary, ContinuousBackupsDescription,
+        ContinuousBackupsStatus, ExportDescription, GlobalSecondaryIndex,
         GlobalSecondaryIndexDescription, IndexStatus, KeySchemaElement, LocalSecondaryIndex,
-        LocalSecondaryIndexDescription, ProvisionedThroughput, ProvisionedThroughputDescription,
+        LocalSecondaryIndexDescription, PointInTimeRecoveryDescription, PointInTimeRecoveryStatus,
+        Projection, ProjectionType, ProvisionedThroughput, ProvisionedThroughputDescription,
         SSEDescription, SSESpecification, SseStatus, SseType, StreamSpecification,
         TableDescription, TableStatus, Tag, TimeToLiveSpecification,
     },
 };
 
-use crate::storage::{KeySchema, TableStorage};
+use crate::{
+    backup::TableBackup,
+    storage::{KeySchema, TableStorage, calculate_item_size},
+};
 
 /// Top-level DynamoDB service state.
 #[derive(Debug)]
 pub struct DynamoDBServiceState {
     /// All tables keyed by name.
     tables: DashMap<String, Arc<DynamoDBTable>>,
+    /// Table exports keyed by export ARN.
+    exports: DashMap<String, ExportDescription>,
+    /// On-demand backups keyed by backup ARN.
+    backups: DashMap<String, Arc<TableBackup>>,
 }
 
 impl DynamoDBServiceState {
@@ -29,22 +40,38 @@ impl DynamoDBServiceState {
     pub fn new() -> Self {
         Self {
             tables: DashMap::new(),
+            exports: DashMap::new(),
+            backups: DashMap::new(),
         }
     }
 
     /// Get a table by name.
+    ///
+    /// A table whose deletion delay has elapsed is removed here, on first
+    /// access, rather than by a background task.
     #[must_use]
     pub fn get_table(&self, name: &str) -> Option<Arc<DynamoDBTable>> {
-        self.tables.get(name).map(|r| Arc::clone(r.value()))
+        let table = self.tables.get(name).map(|r| Arc::clone(r.value()))?;
+        if table.is_gone_at(Utc::now()) {
+            self.tables.remove_if(name, |_, t| Arc::ptr_eq(t, &table));
+            return None;
+        }
+        Some(table)
     }
 
     /// Get a table or return `ResourceNotFoundException`.
     pub fn require_table(&self, name: &str) -> Result<Arc<DynamoDBTable>, DynamoDBError> {
-        self.get_table(name).ok_or_else(|| {
-            DynamoDBError::resource_not_found(format!(
-                "Requested resource not found: Table: {name} not found"
-            ))
-        })
+        self.get_table(name).ok_or_else(|| table_not_found(name))
+    }
+
+    /// Get a table that accepts item reads and writes.
+    ///
+    /// Like DynamoDB, a table that is still `CREATING` or already `DELETING`
+    /// is reported as not found.
+    pub fn require_active_table(&self, name: &str) -> Result<Arc<DynamoDBTable>, DynamoDBError> {
+        self.get_table(name)
+            .filter(|table| table.status() == TableStatus::Active)
+            .ok_or_else(|| table_not_found(name))
     }
 
     /// Insert a new table. Returns error if table already exists.
@@ -53,6 +80,11 @@ impl DynamoDBServiceState {
         let table = Arc::new(table);
         // Use entry API to atomically check + insert.
         match self.tables.entry(name) {
+            // A table whose deletion delay has elapsed no longer exists.
+            dashmap::mapref::entry::Entry::Occupied(mut e) if e.get().is_gone_at(Utc::now()) => {
+                e.insert(Arc::clone(&table));
+                Ok(table)
+            }
             dashmap::mapref::entry::Entry::Occupied(e) => Err(DynamoDBError::resource_in_use(
                 format!("Table already exists: {}", e.key()),
             )),
@@ -64,26 +96,117 @@ impl DynamoDBServiceState {
         }
     }
 
-    /// Remove a table by name. Returns the removed table.
-    pub fn delete_table(&self, name: &str) -> Result<Arc<DynamoDBTable>, DynamoDBError> {
-        self.tables.remove(name).map(|(_, t)| t).ok_or_else(|| {
-            DynamoDBError::resource_not_found(format!(
-                "Requested resource not found: Table: {name} not found"
-            ))
-        })
+    /// Remove `table`, if it is still the table registered under its name.
+    ///
+    /// A table deleted and re-created under the same name since `table` was
+    /// looked up is left alone. `on_removed` runs before another table can
+    /// take the name, so cleanup keyed by the name cannot reach a successor.
+    pub fn delete_table(
+        &self,
+        table: &Arc<DynamoDBTable>,
+        on_removed: impl FnOnce(),
+    ) -> Result<(), DynamoDBError> {
+        self.tables
+            .remove_if(&table.name, |_, current| {
+                let same = Arc::ptr_eq(current, table);
+                if same {
+                    on_removed();
+                }
+                same
+            })
+            .map(|_| ())
+            .ok_or_else(|| table_not_found(&table.name))
     }
 
-    /// List all table names (sorted).
+    /// List all table names (sorted), including tables still being deleted.
     #[must_use]
     pub fn list_table_names(&self) -> Vec<String> {
-        let mut names: Vec<String> = self.tables.iter().map(|r| r.key().clone()).collect();
+        let now = Utc::now();
+        let mut names: Vec<String> = self
+            .tables
+            .iter()
+            .filter(|r| !r.value().is_gone_at(now))
+            .map(|r| r.key().clone())
+            .collect();
         names.sort();
         names
     }
 
-    /// Reset all state (remove all tables).
+    /// Record a new export, or replace an existing one with the same ARN.
+    pub fn put_export(&self, export: ExportDescription) {
+        let arn = export.export_arn.clone().unwrap_or_default();
+        self.exports.insert(arn, export);
+    }
+
+    /// Get an export by ARN.
+    #[must_use]
+    pub fn get_export(&self, arn: &str) -> Option<ExportDescription> {
+        self.exports.get(arn).map(|r| r.value().clone())
+    }
+
+    /// Apply `update` to the export with the given ARN, if it exists.
+    pub fn update_export(&self, arn: &str, update: impl FnOnce(&mut ExportDescription)) {
+        if let Some(mut export) = self.exports.get_mut(arn) {
+            update(export.value_mut());
+        }
+    }
+
+    /// List all exports, oldest first.
+    #[must_use]
+    pub fn list_exports(&self) -> Vec<ExportDescription> {
+        let mut exports: Vec<ExportDescription> =
+            self.exports.iter().map(|r| r.value().clone()).collect();
+        exports.sort_by(|a, b| {
+            a.start_time
+                .unwrap_or_default()
+                .total_cmp(&b.start_time.unwrap_or_default())
+                .then_with(|| a.export_arn.cmp(&b.export_arn))
+        });
+        exports
+    }
+
+    /// Store a new backup.
+    #[must_use]
+    pub fn put_backup(&self, backup: TableBackup) -> Arc<TableBackup> {
+        let backup = Arc::new(backup);
+        self.backups.insert(backup.arn.clone(), Arc::clone(&backup));
+        backup
+    }
+
+    /// Get a backup or return `BackupNotFoundException`.
+    pub fn require_backup(&self, arn: &str) -> Result<Arc<TableBackup>, DynamoDBError> {
+        self.backups
+            .get(arn)
+            .map(|r| Arc::clone(r.value()))
+            .ok_or_else(|| backup_not_found(arn))
+    }
+
+    /// Remove a backup or return `BackupNotFoundException`.
+    pub fn delete_backup(&self, arn: &str) -> Result<Arc<TableBackup>, DynamoDBError> {
+        self.backups
+            .remove(arn)
+            .map(|(_, b)| b)
+            .ok_or_else(|| backup_not_found(arn))
+    }
+
+    /// List all backups, oldest first.
+    #[must_use]
+    pub fn list_backups(&self) -> Vec<Arc<TableBackup>> {
+        let mut backups: Vec<Arc<TableBackup>> =
+            self.backups.iter().map(|r| Arc::clone(r.value())).collect();
+        backups.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then_with(|| a.arn.cmp(&b.arn))
+        });
+        backups
+    }
+
+    /// Reset all state (remove all tables, exports, and backups).
     pub fn reset(&self) {
         self.tables.clear();
+        self.exports.clear();
+        self.backups.clear();
     }
 }
 
@@ -93,13 +216,24 @@ impl Default for DynamoDBServiceState {
     }
 }
 
+fn backup_not_found(arn: &str) -> DynamoDBError {
+    DynamoDBError::with_message(
+        DynamoDBErrorCode::BackupNotFoundException,
+        format!("Backup not found for arn {arn}"),
+    )
+}
+
+fn table_not_found(name: &str) -> DynamoDBError {
+    DynamoDBError::resource_not_found(format!(
+        "Requested resource not found: Table: {name} not found"
+    ))
+}
+
 /// A single DynamoDB table with metadata and storage.
 #[derive(Debug)]
 pub struct DynamoDBTable {
     /// Table name.
     pub name: String,
-    /// Table status.
-    pub status: TableStatus,
     /// Key schema elements.
     pub key_schema_elements: Vec<KeySchemaElement>,
     /// Parsed key schema for storage operations.
@@ -127,20 +261,187 @@ pub struct DynamoDBTable {
     /// Stable table ID (UUID v4), assigned at creation time.
     pub table_id: String,
     /// Creation timestamp.
-    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub created_at: DateTime<Utc>,
+    /// When the table becomes `ACTIVE`; later than `created_at` only if a
+    /// creation delay is configured.
+    pub active_at: DateTime<Utc>,
+    /// When the table disappears, once `DeleteTable` has started a delayed
+    /// deletion.
+    pub deleted_at: parking_lot::RwLock<Option<DateTime<Utc>>>,
+    /// Point-in-time recovery settings, if enabled.
+    pub point_in_time_recovery: parking_lot::RwLock<Option<PointInTimeRecovery>>,
     /// Item storage engine.
     pub storage: TableStorage,
 }
 
+/// Convert a timestamp to the epoch seconds DynamoDB reports.
+#[allow(clippy::cast_precision_loss)] // Acceptable: DynamoDB returns epoch seconds as f64
+pub(crate) fn epoch_seconds(time: DateTime<Utc>) -> f64 {
+    time.timestamp_millis() as f64 / 1000.0
+}
+
+/// A table's point-in-time recovery settings.
+#[derive(Debug, Clone)]
+pub struct PointInTimeRecovery {
+    /// When recovery was enabled; no earlier time can be restored.
+    pub enabled_at: DateTime<Utc>,
+    /// How many days of history are kept (1-35).
+    pub recovery_period_in_days: i32,
+}
+
+impl PointInTimeRecovery {
+    /// The default and maximum `RecoveryPeriodInDays`.
+    pub const MAX_RECOVERY_PERIOD_IN_DAYS: i32 = 35;
+
+    /// The restorable window at `now`.
+    fn window(&self, now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        let period_start = now - chrono::Duration::days(i64::from(self.recovery_period_in_days));
+        (self.enabled_at.max(period_start), now)
+    }
+}
+
 impl DynamoDBTable {
+    /// The table's status at `now`, derived from its lifecycle timestamps.
+    #[must_use]
+    pub fn status_at(&self, now: DateTime<Utc>) -> TableStatus {
+        if self.deleted_at.read().is_some() {
+            TableStatus::Deleting
+        } else if now < self.active_at {
+            TableStatus::Creating
+        } else {
+            TableStatus::Active
+        }
+    }
+
+    /// The table's current status.
+    #[must_use]
+    pub fn status(&self) -> TableStatus {
+        self.status_at(Utc::now())
+    }
+
+    /// Start a delayed deletion that completes at `deleted_at`.
+    ///
+    /// Returns `false` if the table is already being deleted.
+    pub fn begin_deletion(&self, deleted_at: DateTime<Utc>) -> bool {
+        let mut slot = self.deleted_at.write();
+        if slot.is_some() {
+            return false;
+        }
+        *slot = Some(deleted_at);
+        true
+    }
+
+    /// The window a point-in-time restore can target at `now`, or `None` if
+    /// point-in-time recovery is disabled.
+    ///
+    /// The window opens when recovery was enabled, or `RecoveryPeriodInDays`
+    /// ago if that is later, and closes at `now`.
+    #[must_use]
+    pub fn restorable_window(&self, now: DateTime<Utc>) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        self.point_in_time_recovery
+            .read()
+            .as_ref()
+            .map(|pitr| pitr.window(now))
+    }
+
+    /// The table's `ContinuousBackupsDescription` at `now`.
+    #[must_use]
+    pub fn continuous_backups_description(
+        &self,
+        now: DateTime<Utc>,
+    ) -> ContinuousBackupsDescription {
+        let pitr = self.point_in_time_recovery.read().clone();
+        let description = match pitr {
+            Some(pitr) => {
+                let (earliest, latest) = pitr.window(now);
+                PointInTimeRecoveryDescription {
+                    point_in_time_recovery_status: Some(PointInTimeRecoveryStatus::Enabled),
+                    recovery_period_in_days: Some(pitr.recovery_period_in_days),
+                    earliest_restorable_date_time: Some(epoch_seconds(earliest)),
+                    latest_restorable_date_time: Some(epoch_seconds(latest)),
+                }
+            }
+            None => PointInTimeRecoveryDescription {
+                point_in_time_recovery_status: Some(PointInTimeRecoveryStatus::Disabled),
+                ..PointInTimeRecoveryDescription::default()
+            },
+        };
+        ContinuousBackupsDescription {
+            continuous_backups_status: ContinuousBackupsStatus::Enabled,
+            point_in_time_recovery_description: Some(description),
+        }
+    }
+
+    /// Whether a delayed deletion has completed by `now`.
+    fn is_gone_at(&self, now: DateTime<Utc>) -> bool {
+        self.deleted_at.read().is_some_and(|at| now >= at)
+    }
+
+    /// The `(ItemCount, IndexSizeBytes)` of a secondary index.
+    ///
+    /// An index holds the items that have all of its key attributes, and its
+    /// size counts only the attributes it projects. Unlike DynamoDB, which
+    /// refreshes these figures about every six hours, they are exact at the
+    /// time of the call.
+    fn index_usage(&self, key_schema: &[KeySchemaElement], projection: &Projection) -> (i64, i64) {
+        let index_keys: Vec<&str> = key_schema
+            .iter()
+            .map(|k| k.attribute_name.as_str())
+            .collect();
+        let projected: Option<Vec<&str>> = match projection.projection_type {
+            None | Some(ProjectionType::All) => None,
+            Some(ProjectionType::KeysOnly) => Some(Vec::new()),
+            Some(ProjectionType::Include) => Some(
+                projection
+                    .non_key_attributes
+                    .iter()
+                    .map(String::as_str)
+                    .collect(),
+            ),
+        };
+        let (count, bytes) = self.storage.usage_where_present(&index_keys, |item| {
+            let Some(non_key) = &projected else {
+                return calculate_item_size(item);
+            };
+            let projected_item: HashMap<String, AttributeValue> = item
+                .iter()
+                .filter(|(name, _)| {
+                    let name = name.as_str();
+                    index_keys.contains(&name)
+                        || non_key.contains(&name)
+                        || self
+                            .key_schema_elements
+                            .iter()
+                            .any(|k| k.attribute_name == name)
+                })
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect();
+            calculate_item_size(&projected_item)
+        });
+        (
+            i64::try_from(count).unwrap_or(i64::MAX),
+            i64::try_from(bytes).unwrap_or(i64::MAX),
+        )
+    }
+
     /// Build a `TableDescription` from this table's metadata.
+    ///
+    /// `ItemCount`, `TableSizeBytes` and the per-index figures are exact at
+    /// the time of the call, where DynamoDB refreshes them about every six
+    /// hours.
     #[must_use]
     pub fn to_description(&self) -> TableDescription {
         #[allow(clippy::cast_precision_loss)] // Acceptable: DynamoDB returns epoch seconds as f64
         let creation_time = self.created_at.timestamp_millis() as f64 / 1000.0;
+        let status = self.status();
+        let index_status = match status {
+            TableStatus::Creating => IndexStatus::Creating,
+            TableStatus::Deleting => IndexStatus::Deleting,
+            _ => IndexStatus::Active,
+        };
         TableDescription {
             table_name: Some(self.name.clone()),
-            table_status: Some(self.status.clone()),
+            table_status: Some(status),
             key_schema: self.key_schema_elements.clone(),
             attribute_definitions: self.attribute_definitions.clone(),
             table_arn: Some(self.arn.clone()),
@@ -158,34 +459,42 @@ impl DynamoDBTable {
             global_secondary_indexes: self
                 .gsi_definitions
                 .iter()
-                .map(|gsi| GlobalSecondaryIndexDescription {
-                    index_name: Some(gsi.index_name.clone()),
-                    key_schema: gsi.key_schema.clone(),
-                    projection: Some(gsi.projection.clone()),
-                    index_status: Some(IndexStatus::Active),
-                    provisioned_throughput: gsi.provisioned_throughput.as_ref().map(|pt| {
-                        ProvisionedThroughputDescription {
-                            read_capacity_units: pt.read_capacity_units,
-                            write_capacity_units: pt.write_capacity_units,
-                            ..Default::default()
-                        }
-                    }),
-                    index_size_bytes: Some(0),
-                    item_count: Some(0),
-                    index_arn: Some(format!("{}/index/{}", self.arn, gsi.index_name)),
-                    ..Default::default()
+                .map(|gsi| {
+                    let (item_count, index_size_bytes) =
+                        self.index_usage(&gsi.key_schema, &gsi.projection);
+                    GlobalSecondaryIndexDescription {
+                        index_name: Some(gsi.index_name.clone()),
+                        key_schema: gsi.key_schema.clone(),
+                        projection: Some(gsi.projection.clone()),
+                        index_status: Some(index_status.clone()),
+                        provisioned_throughput: gsi.provisioned_throughput.as_ref().map(|pt| {
+                            ProvisionedThroughputDescription {
+                                read_capacity_units: pt.read_capacity_units,
+                                write_capacity_units: pt.write_capacity_units,
+                                ..Default::default()
+                            }
+                        }),
+                        index_size_bytes: Some(index_size_bytes),
+                        item_count: Some(item_count),
+                        index_arn: Some(format!("{}/index/{}", self.arn, gsi.index_name)),
+                        ..Default::default()
+                    }
                 })
                 .collect(),
             local_secondary_indexes: self
                 .lsi_definitions
                 .iter()
-                .map(|lsi| LocalSecondaryIndexDescription {
-                    index_name: Some(lsi.index_name.clone()),
-                    key_schema: lsi.key_schema.clone(),
-                    projection: Some(lsi.projection.clone()),
-                    index_size_bytes: Some(0),
-                    item_count: Some(0),
-                    index_arn: Some(format!("{}/index/{}", self.arn, lsi.index_name)),
+                .map(|lsi| {
+                    let (item_count, index_size_bytes) =
+                        self.index_usage(&lsi.key_schema, &lsi.projection);
+                    LocalSecondaryIndexDescription {
+                        index_name: Some(lsi.index_name.clone()),
+                        key_schema: lsi.key_schema.clone(),
+                        projection: Some(lsi.projection.clone()),
+                        index_size_bytes: Some(index_size_bytes),
+                        item_count: Some(item_count),
+                        index_arn: Some(format!("{}/index/{}", self.arn, lsi.index_name)),
+                    }
                 })
                 .collect(),
             stream_specification: self.stream_specification.clone(),
@@ -16,11 +16,13 @@
 //!   [`SortableAttributeValue`], which implements [`Ord`] following DynamoDB comparison rules.
 //! - For tables without a sort key, a sentinel value is used as the single BTreeMap key per
 //!   partition.
+//! - Conditional writes and read-modify-write updates go through [`TableStorage::modify_item`],
+//!   which holds the item's partition locked from the read to the write, so concurrent updates of
+//!   one item are never lost.
 
 use std::{
     cmp::Ordering,
     collections::{BTreeMap, HashMap},
-    hash::{DefaultHasher, Hash, Hasher},
     ops::Bound,
     sync::atomic::{AtomicU64, Ordering as AtomicOrdering},
 };
@@ -30,6 +32,8 @@ use rustack_dynamodb_model::{AttributeValue, types::ScalarAttributeType};
 use thiserror::Error;
 use tracing::debug;
 
+use crate::number::Number;
+
 // ---------------------------------------------------------------------------
 // Errors
 // ---------------------------------------------------------------------------
@@ -150,168 +154,13 @@ impl SortableAttributeValue {
     }
 }
 
-// ---------------------------------------------------------------------------
-// Arbitrary-precision decimal number comparison
-// ---------------------------------------------------------------------------
-
-/// A normalized representation of a decimal number for comparison.
-///
-/// The number is represented as `sign * 0.{digits} * 10^exponent`, where
-/// `digits` is a string of significant digits with no leading or trailing zeros.
-/// Zero is represented as `(false, "", 0)`.
-#[derive(Debug)]
-struct NormalizedNumber {
-    /// `true` if the number is negative.
-    negative: bool,
-    /// The significant digits (no leading/trailing zeros). Empty for zero.
-    digits: Vec<u8>,
-    /// The base-10 exponent such that the value is `0.{digits} * 10^exponent`.
-    exponent: i64,
-}
-
-/// Parse a DynamoDB number string into a normalized form for comparison.
-///
-/// Handles integers, decimals, and scientific notation (e.g., "1.5e10", "-3.14", "0.001").
-fn normalize_number(s: &str) -> NormalizedNumber {
-    let s = s.trim();
-    let (negative, s) = if let Some(rest) = s.strip_prefix('-') {
-        (true, rest)
-    } else if let Some(rest) = s.strip_prefix('+') {
-        (false, rest)
-    } else {
-        (false, s)
-    };
-
-    // Split on 'e' or 'E' for scientific notation.
-    let (mantissa_str, explicit_exp) = if let Some(pos) = s.find(['e', 'E']) {
-        let exp: i64 = s[pos + 1..].parse().unwrap_or(0);
-        (&s[..pos], exp)
-    } else {
-        (s, 0_i64)
-    };
-
-    // Split mantissa into integer and fractional parts.
-    let (int_part, frac_part) = if let Some(dot_pos) = mantissa_str.find('.') {
-        (&mantissa_str[..dot_pos], &mantissa_str[dot_pos + 1..])
-    } else {
-        (mantissa_str, "")
-    };
-
-    // Collect all digits and compute the exponent.
-    // The number is `int_part.frac_part * 10^explicit_exp`.
-    // We normalize to `0.{all_digits} * 10^exponent`.
-    let mut all_digits: Vec<u8> = Vec::new();
-    for c in int_part.chars() {
-        if c.is_ascii_digit() {
-            all_digits.push(c as u8 - b'0');
-        }
-    }
-    // The exponent starts at int_part length (since we treat all digits as
-    // fractional: `0.{int_part}{frac_part} * 10^(len(int_part) + explicit_exp)`).
-    // Safety: digit string length is bounded by input and always far below i64::MAX.
-    #[allow(clippy::cast_possible_wrap)]
-    let int_digits_len = all_digits.len() as i64;
-    for c in frac_part.chars() {
-        if c.is_ascii_digit() {
-            all_digits.push(c as u8 - b'0');
-        }
-    }
-
-    let exponent = int_digits_len + explicit_exp;
-
-    // Strip leading zeros from all_digits, adjusting exponent.
-    let leading_zeros = all_digits.iter().take_while(|&&d| d == 0).count();
-    let digits: Vec<u8> = all_digits[leading_zeros..].to_vec();
-    // Safety: leading_zeros count is bounded by digit string length.
-    #[allow(clippy::cast_possible_wrap)]
-    let exponent = exponent - leading_zeros as i64;
-
-    // Strip trailing zeros from digits.
-    let mut digits = digits;
-    while digits.last() == Some(&0) {
-        digits.pop();
-    }
-
-    // If no significant digits remain, the number is zero.
-    if digits.is_empty() {
-        return NormalizedNumber {
-            negative: false,
-            digits: Vec::new(),
-            exponent: 0,
-        };
-    }
-
-    NormalizedNumber {
-        negative,
-        digits,
-        exponent,
-    }
-}
-
-/// Compare two DynamoDB number strings with arbitrary precision.
+/// Compare two DynamoDB number strings exactly, falling back to string
+/// order for values that are not valid numbers.
 fn compare_number_strings(a: &str, b: &str) -> Ordering {
-    let na = normalize_number(a);
-    let nb = normalize_number(b);
-
-    let a_zero = na.digits.is_empty();
-    let b_zero = nb.digits.is_empty();
-
-    // Handle zeros.
-    if a_zero && b_zero {
-        return Ordering::Equal;
-    }
-    if a_zero {
-        return if nb.negative {
-            Ordering::Greater
-        } else {
-            Ordering::Less
-        };
-    }
-    if b_zero {
-        return if na.negative {
-            Ordering::Less
-        } else {
-            Ordering::Greater
-        };
+    match (Number::parse(a), Number::parse(b)) {
+        (Some(na), Some(nb)) => na.cmp(&nb),
+        _ => a.cmp(b),
     }
-
-    // Compare signs.
-    match (na.negative, nb.negative) {
-        (true, false) => return Ordering::Less,
-        (false, true) => return Ordering::Greater,
-        _ => {}
-    }
-
-    // Both same sign. Compare magnitude, then flip if negative.
-    let mag_cmp = compare_magnitude(&na, &nb);
-    if na.negative {
-        mag_cmp.reverse()
-    } else {
-        mag_cmp
-    }
-}
-
-/// Compare the magnitude (absolute value) of two normalized numbers.
-fn compare_magnitude(a: &NormalizedNumber, b: &NormalizedNumber) -> Ordering {
-    // First compare exponents (which represent the order of magnitude).
-    match a.exponent.cmp(&b.exponent) {
-        Ordering::Less => return Ordering::Less,
-        Ordering::Greater => return Ordering::Greater,
-        Ordering::Equal => {}
-    }
-
-    // Same exponent: compare digit-by-digit.
-    let max_len = a.digits.len().max(b.digits.len());
-    for i in 0..max_len {
-        let da = a.digits.get(i).copied().unwrap_or(0);
-        let db = b.digits.get(i).copied().unwrap_or(0);
-        match da.cmp(&db) {
-            Ordering::Equal => {}
-            other => return other,
-        }
-    }
-
-    Ordering::Equal
 }
 
 impl PartialEq for SortableAttributeValue {
@@ -355,10 +204,10 @@ impl std::hash::Hash for SortableAttributeValue {
             Self::N(n) => {
                 // Hash using the normalized form so that equivalent numbers
                 // (e.g., "1e2" and "100") produce the same hash.
-                let norm = normalize_number(n);
-                norm.negative.hash(state);
-                norm.digits.hash(state);
-                norm.exponent.hash(state);
+                match Number::parse(n) {
+                    Some(number) => number.hash(state),
+                    None => n.hash(state),
+                }
             }
             Self::B(b) => b.hash(state),
             Self::Sentinel => {}
@@ -437,6 +286,17 @@ struct StoredItem {
     attributes: HashMap<String, AttributeValue>,
 }
 
+/// The write [`TableStorage::modify_item`] applies to an item.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemWrite {
+    /// Leave the item as it is.
+    Keep,
+    /// Store this item, replacing the current one.
+    Put(HashMap<String, AttributeValue>),
+    /// Delete the item.
+    Delete,
+}
+
 // ---------------------------------------------------------------------------
 // TableStorage
 // ---------------------------------------------------------------------------
@@ -513,19 +373,8 @@ impl TableStorage {
             partition.insert(sort_key, stored)
         };
 
-        if let Some(old) = old_item {
-            let old_size = calculate_item_size(&old.attributes);
-            // Update total size: add new, subtract old.
-            self.total_size.fetch_add(new_size, AtomicOrdering::Relaxed);
-            self.total_size.fetch_sub(old_size, AtomicOrdering::Relaxed);
-            debug!(old_size, new_size, "replaced existing item");
-            Ok(Some(old.attributes))
-        } else {
-            self.item_count.fetch_add(1, AtomicOrdering::Relaxed);
-            self.total_size.fetch_add(new_size, AtomicOrdering::Relaxed);
-            debug!(new_size, "inserted new item");
-            Ok(None)
-        }
+        self.record_put(old_item.as_ref(), new_size);
+        Ok(old_item.map(|old| old.attributes))
     }
 
     /// Retrieves an item by its primary key.
@@ -560,12 +409,81 @@ impl TableStorage {
             partition.remove(sort_key)?
         };
 
+        self.record_delete(&removed);
+        Some(removed.attributes)
+    }
+
+    /// Atomically read, decide on, and write the item stored under `key`.
+    ///
+    /// `decide` sees the current item, if any, and returns the write to
+    /// apply along with a value handed back to the caller. The item's
+    /// partition stays locked from the read to the write, so concurrent
+    /// conditional writes and read-modify-write updates of the item are
+    /// serialized. `decide` must not access this table.
+    ///
+    /// Returns the item replaced or deleted by the write, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `decide`, in which case nothing is written.
+    pub fn modify_item<T, E>(
+        &self,
+        key: &PrimaryKey,
+        decide: impl FnOnce(Option<&HashMap<String, AttributeValue>>) -> Result<(ItemWrite, T), E>,
+    ) -> Result<(Option<HashMap<String, AttributeValue>>, T), E> {
+        let sort_key = key
+            .sort_key
+            .clone()
+            .unwrap_or(SortableAttributeValue::Sentinel);
+        let mut partition = self.data.entry(key.partition_key.clone()).or_default();
+
+        let result =
+            decide(partition.get(&sort_key).map(|item| &item.attributes)).map(|(write, value)| {
+                let old_item = match write {
+                    ItemWrite::Keep => None,
+                    ItemWrite::Put(attributes) => {
+                        let new_size = calculate_item_size(&attributes);
+                        let old_item = partition.insert(sort_key, StoredItem { attributes });
+                        self.record_put(old_item.as_ref(), new_size);
+                        old_item
+                    }
+                    ItemWrite::Delete => {
+                        let old_item = partition.remove(&sort_key);
+                        if let Some(removed) = &old_item {
+                            self.record_delete(removed);
+                        }
+                        old_item
+                    }
+                };
+                (old_item.map(|old| old.attributes), value)
+            });
+        drop(partition);
+        // Drop the partition if the write emptied it, or it was only created
+        // for the read.
+        self.data
+            .remove_if(&key.partition_key, |_, partition| partition.is_empty());
+        result
+    }
+
+    /// Update the item count and size for an item stored over `old_item`.
+    fn record_put(&self, old_item: Option<&StoredItem>, new_size: u64) {
+        self.total_size.fetch_add(new_size, AtomicOrdering::Relaxed);
+        if let Some(old) = old_item {
+            let old_size = calculate_item_size(&old.attributes);
+            self.total_size.fetch_sub(old_size, AtomicOrdering::Relaxed);
+            debug!(old_size, new_size, "replaced existing item");
+        } else {
+            self.item_count.fetch_add(1, AtomicOrdering::Relaxed);
+            debug!(new_size, "inserted new item");
+        }
+    }
+
+    /// Update the item count and size for a deleted item.
+    fn record_delete(&self, removed: &StoredItem) {
         let size = calculate_item_size(&removed.attributes);
         self.item_count.fetch_sub(1, AtomicOrdering::Relaxed);
         self.total_size.fetch_sub(size, AtomicOrdering::Relaxed);
         debug!(size, "deleted item");
-
-        Some(removed.attributes)
     }
 
     /// Queries items in a single partition with optional sort key conditions.
@@ -582,60 +500,62 @@ impl TableStorage {
         limit: Option<usize>,
         exclusive_start_key: Option<&SortableAttributeValue>,
     ) -> (Vec<HashMap<String, AttributeValue>>, Option<PrimaryKey>) {
-        /// DynamoDB caps a single Query response at 1 MB.
-        const MAX_RESPONSE_BYTES: u64 = 1_048_576;
+        let mut items = Vec::new();
+        let Ok(last_key) = self.query_with(
+            partition_key,
+            sort_condition,
+            scan_forward,
+            PageLimit::items(limit),
+            exclusive_start_key,
+            |item| {
+                items.push(item.clone());
+                Ok::<_, std::convert::Infallible>(())
+            },
+        );
+        (items, last_key)
+    }
 
-        let Some(partition) = self.data.get(partition_key) else {
-            return (Vec::new(), None);
+    /// Visits one page of [`query`](Self::query) results in order without
+    /// copying them.
+    ///
+    /// `visit` sees every item the page evaluates, and iteration stops at the
+    /// page `limit`, so a caller that filters or projects items only pays for
+    /// the ones it keeps. Returns the last evaluated key.
+    ///
+    /// # Errors
+    ///
+    /// Stops at and returns the first error from `visit`.
+    pub fn query_with<E>(
+        &self,
+        partition_key: &AttributeValue,
+        sort_condition: Option<&SortKeyCondition>,
+        scan_forward: bool,
+        limit: PageLimit,
+        exclusive_start_key: Option<&SortableAttributeValue>,
+        visit: impl FnMut(&HashMap<String, AttributeValue>) -> Result<(), E>,
+    ) -> Result<Option<PrimaryKey>, E> {
+        let Some(partition) = self.data.get(&canonical_partition_key(partition_key)) else {
+            return Ok(None);
         };
 
-        let all_items = collect_matching_items(
+        // One item past the limit tells whether the page was truncated.
+        let matching = collect_matching_items(
             &partition,
             sort_condition,
             scan_forward,
-            None, // collect all matching items first
+            limit.items.map(|n| n.saturating_add(1)),
             exclusive_start_key,
         );
 
-        // Apply the 1 MB size cap and optional count limit.
-        let effective_limit = limit.unwrap_or(usize::MAX);
-        let mut selected: Vec<&StoredItem> = Vec::new();
-        let mut cumulative_size: u64 = 0;
-        let mut size_limited = false;
-
-        for item in all_items.iter().take(effective_limit) {
-            let item_size = calculate_item_size(&item.attributes);
-            // Always include at least one item even if it exceeds 1 MB on its
-            // own, matching DynamoDB behaviour.
-            if !selected.is_empty() && cumulative_size + item_size > MAX_RESPONSE_BYTES {
-                size_limited = true;
-                break;
-            }
-            cumulative_size += item_size;
-            selected.push(item);
-        }
-
-        let count_limited =
-            !size_limited && selected.len() == effective_limit && effective_limit < all_items.len();
-        let has_more = size_limited || count_limited;
-
-        let last_key = if has_more {
-            build_last_evaluated_key(
-                &selected,
-                Some(selected.len()),
-                partition_key,
-                &self.key_schema,
-            )
-        } else {
-            None
-        };
-
-        let result: Vec<_> = selected
-            .into_iter()
-            .map(|stored| stored.attributes.clone())
-            .collect();
-
-        (result, last_key)
+        let last = visit_page(matching.into_iter().map(|item| (item, item)), limit, visit)?;
+        Ok(last.map(|item| PrimaryKey {
+            partition_key: partition_key.clone(),
+            sort_key: self.key_schema.sort_key.as_ref().and_then(|sk_def| {
+                item.attributes.get(&sk_def.name).and_then(|av| {
+                    SortableAttributeValue::from_attribute_value(&sk_def.name, av).ok()
+                })
+            }),
+        }))
     }
 
     /// Scans all items in the table.
@@ -654,119 +574,246 @@ impl TableStorage {
         segment: Option<u32>,
         total_segments: Option<u32>,
     ) -> (Vec<HashMap<String, AttributeValue>>, Option<PrimaryKey>) {
-        /// DynamoDB caps a single Scan/Query response at 1 MB.
-        const MAX_RESPONSE_BYTES: u64 = 1_048_576;
-
-        // Collect all items sorted deterministically by partition key then sort key.
-        let mut all_items: Vec<(AttributeValue, SortableAttributeValue, &StoredItem)> = Vec::new();
-
-        // We need to hold all partition refs alive while iterating.
-        // Collect partition keys first, then iterate in sorted order.
-        let mut partition_entries: Vec<_> = self.data.iter().collect();
-        // Sort partitions deterministically by partition key display string.
-        partition_entries.sort_by(|a, b| format!("{}", a.key()).cmp(&format!("{}", b.key())));
+        let mut items = Vec::new();
+        let Ok(last_key) = self.scan_with(
+            PageLimit::items(limit),
+            exclusive_start_key,
+            segment,
+            total_segments,
+            |item| {
+                items.push(item.clone());
+                Ok::<_, std::convert::Infallible>(())
+            },
+        );
+        (items, last_key)
+    }
 
-        // For parallel scan, filter partitions by segment assignment.
+    /// Visits one page of [`scan`](Self::scan) results in order without
+    /// copying them.
+    ///
+    /// Partitions are ordered by key, and iteration stops at the page
+    /// `limit`, as for [`query_with`](Self::query_with). Returns the last
+    /// evaluated key.
+    ///
+    /// # Errors
+    ///
+    /// Stops at and returns the first error from `visit`.
+    pub fn scan_with<E>(
+        &self,
+        limit: PageLimit,
+        exclusive_start_key: Option<&PrimaryKey>,
+        segment: Option<u32>,
+        total_segments: Option<u32>,
+        visit: impl FnMut(&HashMap<String, AttributeValue>) -> Result<(), E>,
+    ) -> Result<Option<PrimaryKey>, E> {
+        // Resume strictly after the start key's position, which still exists
+        // in the ordering even if the item itself has since been deleted.
+        let start = exclusive_start_key.map(|key| {
+            (
+                partition_order(&key.partition_key),
+                key.sort_key
+                    .as_ref()
+                    .unwrap_or(&SortableAttributeValue::Sentinel),
+            )
+        });
         let parallel_filter = segment.zip(total_segments);
 
-        for entry in &partition_entries {
-            // When parallel scan is active, skip partitions not assigned to this segment.
-            if let Some((seg, total)) = parallel_filter {
-                let pk_hash = partition_key_segment(entry.key(), total);
-                if pk_hash != seg {
-                    continue;
-                }
-            }
-            for (sk, item) in entry.value() {
-                all_items.push((entry.key().clone(), sk.clone(), item));
+        // Hold the partitions that can still contribute, in key order. Only
+        // partition keys are ordered up front; items are visited lazily.
+        let mut partitions: Vec<_> = self
+            .data
+            .iter()
+            .filter(|entry| {
+                parallel_filter
+                    .is_none_or(|(seg, total)| partition_key_segment(entry.key(), total) == seg)
+            })
+            .map(|entry| (partition_order(entry.key()), entry))
+            .filter(|(order, _)| start.as_ref().is_none_or(|(start_pk, _)| order >= start_pk))
+            .collect();
+        partitions.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let items = partitions.iter().flat_map(|(order, entry)| {
+            let lower = match &start {
+                Some((partition, sort_key)) if order == partition => Bound::Excluded(*sort_key),
+                _ => Bound::Unbounded,
+            };
+            entry
+                .value()
+                .range::<SortableAttributeValue, _>((lower, Bound::Unbounded))
+                .map(|(sk, item)| ((entry.key(), sk), item))
+        });
+
+        let last = visit_page(items, limit, visit)?;
+        Ok(last.map(|(pk, sk)| PrimaryKey {
+            partition_key: pk.clone(),
+            sort_key: sk.to_attribute_value().map(|_| sk.clone()),
+        }))
+    }
+
+    /// Visits every item in the table, in no particular order and without
+    /// the page limits of [`scan_with`](Self::scan_with).
+    pub fn for_each_item(&self, mut visit: impl FnMut(&HashMap<String, AttributeValue>)) {
+        for partition in &self.data {
+            for item in partition.value().values() {
+                visit(&item.attributes);
             }
         }
+    }
 
-        // Apply exclusive_start_key: skip items up to and including the start key.
-        let start_idx = if let Some(start_key) = exclusive_start_key {
-            let start_sk = start_key
-                .sort_key
-                .as_ref()
-                .map_or(&SortableAttributeValue::Sentinel, |sk| sk);
+    /// Returns the number of items that have every attribute in `attributes`,
+    /// and the sum of `size_of` over those items.
+    ///
+    /// This is what a sparse secondary index keyed on `attributes` holds.
+    pub fn usage_where_present(
+        &self,
+        attributes: &[&str],
+        size_of: impl Fn(&HashMap<String, AttributeValue>) -> u64,
+    ) -> (u64, u64) {
+        self.data
+            .iter()
+            .flat_map(|partition| {
+                partition
+                    .value()
+                    .values()
+                    .filter(|item| {
+                        attributes
+                            .iter()
+                            .all(|attr| item.attributes.contains_key(*attr))
+                    })
+                    .map(|item| size_of(&item.attributes))
+                    .collect::<Vec<_>>()
+            })
+            .fold((0, 0), |(count, bytes), size| (count + 1, bytes + size))
+    }
 
-            all_items
-                .iter()
-                .position(|(pk, sk, _)| *pk == start_key.partition_key && *sk == *start_sk)
-                .map_or(0, |idx| idx + 1)
-        } else {
-            0
-        };
+    /// Returns a copy of every item in the table, in no particular order.
+    #[must_use]
+    pub fn snapshot(&self) -> Vec<HashMap<String, AttributeValue>> {
+        self.data
+            .iter()
+            .flat_map(|partition| {
+                partition
+                    .value()
+                    .values()
+                    .map(|item| item.attributes.clone())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
 
-        let effective_limit = limit.unwrap_or(usize::MAX);
+/// DynamoDB caps a single Query or Scan response at 1 MB.
+pub const MAX_RESPONSE_BYTES: u64 = 1_048_576;
 
-        // Select items respecting both the count limit and the 1 MB size cap.
-        let mut selected: Vec<&(AttributeValue, SortableAttributeValue, &StoredItem)> = Vec::new();
-        let mut cumulative_size: u64 = 0;
-        let mut size_limited = false;
+/// How much one Query or Scan page may hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageLimit {
+    /// Most items in the page, if limited.
+    pub items: Option<usize>,
+    /// Most bytes of items in the page, if capped.
+    pub bytes: Option<u64>,
+}
 
-        for entry in all_items.iter().skip(start_idx).take(effective_limit) {
-            let item_size = calculate_item_size(&entry.2.attributes);
-            // Always include at least one item even if it exceeds 1 MB on its
-            // own, matching DynamoDB behaviour.
-            if !selected.is_empty() && cumulative_size + item_size > MAX_RESPONSE_BYTES {
-                size_limited = true;
-                break;
-            }
-            cumulative_size += item_size;
-            selected.push(entry);
+impl PageLimit {
+    /// At most `items` items, within the 1 MB response cap.
+    #[must_use]
+    pub fn items(items: Option<usize>) -> Self {
+        Self {
+            items,
+            bytes: Some(MAX_RESPONSE_BYTES),
         }
+    }
 
-        let count_limited = !size_limited
-            && selected.len() == effective_limit
-            && start_idx + effective_limit < all_items.len();
-        let has_more = size_limited || count_limited;
-
-        let last_key = if has_more {
-            selected.last().map(|(pk, sk, _)| {
-                PrimaryKey {
-                    partition_key: pk.clone(),
-                    sort_key: sk.to_attribute_value().map(|av| {
-                        // This conversion is safe because we know the sort key
-                        // came from a valid SortableAttributeValue.
-                        SortableAttributeValue::from_attribute_value(
-                            &self
-                                .key_schema
-                                .sort_key
-                                .as_ref()
-                                .map_or_else(String::new, |k| k.name.clone()),
-                            &av,
-                        )
-                        .unwrap_or(SortableAttributeValue::Sentinel)
-                    }),
-                }
-            })
-        } else {
-            None
-        };
-
-        let result: Vec<_> = selected
-            .into_iter()
-            .map(|(_, _, item)| item.attributes.clone())
-            .collect();
+    /// Whether an item of `item_size` bytes overflows a page already holding
+    /// `page_size` bytes.
+    pub(crate) fn overflows(self, page_size: u64, item_size: u64) -> bool {
+        self.bytes.is_some_and(|cap| page_size + item_size > cap)
+    }
+}
 
-        (result, last_key)
+/// Feed `items` to `visit` until the page `limit` is reached.
+///
+/// Each item comes with its position, and the position of the last item
+/// visited is returned when items remain past the cutoff. A page always
+/// includes at least one item even if it exceeds the size cap on its own,
+/// matching DynamoDB behaviour.
+fn visit_page<'a, P, E>(
+    items: impl Iterator<Item = (P, &'a StoredItem)>,
+    limit: PageLimit,
+    mut visit: impl FnMut(&HashMap<String, AttributeValue>) -> Result<(), E>,
+) -> Result<Option<P>, E> {
+    let max_items = limit.items.unwrap_or(usize::MAX);
+    let mut cumulative_size: u64 = 0;
+    let mut last = None;
+
+    for (visited, (position, item)) in items.enumerate() {
+        if visited == max_items {
+            return Ok(last);
+        }
+        let item_size = calculate_item_size(&item.attributes);
+        if visited > 0 && limit.overflows(cumulative_size, item_size) {
+            return Ok(last);
+        }
+        cumulative_size += item_size;
+        visit(&item.attributes)?;
+        last = Some(position);
     }
+    Ok(None)
 }
 
 // ---------------------------------------------------------------------------
 // Parallel scan helpers
 // ---------------------------------------------------------------------------
 
-/// Compute which segment a partition key belongs to by hashing the key's
-/// display representation and taking the modulus with the total segment count.
+/// The order `scan` visits partitions in.
+///
+/// Partition keys are S, N, or B, so they order like sort keys of the same
+/// type: binary keys by their raw unsigned bytes.
+pub(crate) fn partition_order(pk: &AttributeValue) -> SortableAttributeValue {
+    SortableAttributeValue::from_attribute_value("", pk).unwrap_or(SortableAttributeValue::Sentinel)
+}
+
+/// Compute which segment a partition key belongs to.
+///
+/// This is a pure function of the key's value, so the scan filter and the
+/// `ExclusiveStartKey` check always agree, across processes too: the key is
+/// hashed with 64-bit FNV-1a over its type and canonical bytes, and the
+/// hash is taken modulo the segment count. Equal numbers spelled differently
+/// ("10", "1e1", "10.0") land in the same segment.
 pub fn partition_key_segment(pk: &AttributeValue, total_segments: u32) -> u32 {
-    let mut hasher = DefaultHasher::new();
-    // Use the Display representation for a stable, deterministic hash.
-    format!("{pk}").hash(&mut hasher);
-    let h = hasher.finish();
-    #[allow(clippy::cast_possible_truncation)]
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let canonical = canonical_partition_key(pk);
+    let (tag, bytes): (u8, &[u8]) = match &canonical {
+        AttributeValue::S(s) => (b'S', s.as_bytes()),
+        AttributeValue::N(n) => (b'N', n.as_bytes()),
+        AttributeValue::B(b) => (b'B', b.as_ref()),
+        _ => (0, &[]),
+    };
+    let hash = std::iter::once(tag)
+        .chain(bytes.iter().copied())
+        .fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+        });
+    #[allow(clippy::cast_possible_truncation)] // The remainder is below `total_segments`.
     {
-        (h % u64::from(total_segments)) as u32
+        (hash % u64::from(total_segments.max(1))) as u32
+    }
+}
+
+/// The form a partition key is stored under.
+///
+/// Numbers compare by value, so a number key is stored in its canonical
+/// spelling: otherwise "10" and "1e1" would be separate partitions that
+/// scan ordering treats as one.
+fn canonical_partition_key(pk: &AttributeValue) -> AttributeValue {
+    match pk {
+        AttributeValue::N(n) => Number::parse(n).map_or_else(
+            || pk.clone(),
+            |number| AttributeValue::N(number.to_string()),
+        ),
+        other => other.clone(),
     }
 }
 
@@ -839,8 +886,21 @@ fn collect_with_condition<'a>(
 ) -> Vec<&'a StoredItem> {
     match condition {
         SortKeyCondition::Eq(val) => {
-            // Exact match - at most one item.
-            partition.get(val).into_iter().collect()
+            // Exact match - at most one item, unless pagination is already
+            // past it.
+            let after_start = exclusive_start_key.is_none_or(|start| {
+                if scan_forward {
+                    val > start
+                } else {
+                    val < start
+                }
+            });
+            partition
+                .get(val)
+                .filter(|_| after_start)
+                .into_iter()
+                .take(limit)
+                .collect()
         }
         SortKeyCondition::Lt(val) => collect_range(
             partition,
@@ -917,8 +977,7 @@ fn collect_range<'a>(
             }
             None => lower,
         };
-        partition
-            .range((effective_lower, upper))
+        partition_range(partition, effective_lower, upper)
             .take(limit)
             .map(|(_, item)| item)
             .collect()
@@ -936,8 +995,7 @@ fn collect_range<'a>(
             },
             None => upper,
         };
-        partition
-            .range((lower, effective_upper))
+        partition_range(partition, lower, effective_upper)
             .rev()
             .take(limit)
             .map(|(_, item)| item)
@@ -945,6 +1003,29 @@ fn collect_range<'a>(
     }
 }
 
+/// Iterates over the items of a partition between two bounds.
+///
+/// An exclusive start key can move one bound past the other; the range is
+/// then empty, where [`BTreeMap::range`] would panic.
+fn partition_range(
+    partition: &BTreeMap<SortableAttributeValue, StoredItem>,
+    lower: Bound<SortableAttributeValue>,
+    upper: Bound<SortableAttributeValue>,
+) -> impl DoubleEndedIterator<Item = (&SortableAttributeValue, &StoredItem)> {
+    let empty = match (&lower, &upper) {
+        (Bound::Included(low), Bound::Included(high)) => low > high,
+        (
+            Bound::Included(low) | Bound::Excluded(low),
+            Bound::Included(high) | Bound::Excluded(high),
+        ) => low >= high,
+        _ => false,
+    };
+    (!empty)
+        .then(|| partition.range((lower, upper)))
+        .into_iter()
+        .flatten()
+}
+
 /// Collects items whose string sort key begins with the given prefix.
 fn collect_begins_with_str<'a>(
     partition: &'a BTreeMap<SortableAttributeValue, StoredItem>,
@@ -976,15 +1057,13 @@ fn collect_begins_with_str<'a>(
     };
 
     if scan_forward {
-        partition
-            .range((lower_bound, upper_bound))
+        partition_range(partition, lower_bound, upper_bound)
             .filter(|(k, _)| matches!(k, SortableAttributeValue::S(s) if s.starts_with(prefix)))
             .take(limit)
             .map(|(_, item)| item)
             .collect()
     } else {
-        partition
-            .range((lower_bound, upper_bound))
+        partition_range(partition, lower_bound, upper_bound)
             .rev()
             .filter(|(k, _)| matches!(k, SortableAttributeValue::S(s) if s.starts_with(prefix)))
             .take(limit)
@@ -1023,15 +1102,13 @@ fn collect_begins_with_bytes<'a>(
     };
 
     if scan_forward {
-        partition
-            .range((lower_bound, upper_bound))
+        partition_range(partition, lower_bound, upper_bound)
             .filter(|(k, _)| matches!(k, SortableAttributeValue::B(b) if b.starts_with(prefix)))
             .take(limit)
             .map(|(_, item)| item)
             .collect()
     } else {
-        partition
-            .range((lower_bound, upper_bound))
+        partition_range(partition, lower_bound, upper_bound)
             .rev()
             .filter(|(k, _)| matches!(k, SortableAttributeValue::B(b) if b.starts_with(prefix)))
             .take(limit)
@@ -1084,33 +1161,6 @@ fn compute_bytes_prefix_upper_bound(prefix: &[u8]) -> Option<SortableAttributeVa
     Some(SortableAttributeValue::B(bytes::Bytes::from(bytes)))
 }
 
-/// Builds the last evaluated key for pagination when a limit was hit.
-fn build_last_evaluated_key(
-    items: &[&StoredItem],
-    limit: Option<usize>,
-    partition_key: &AttributeValue,
-    key_schema: &KeySchema,
-) -> Option<PrimaryKey> {
-    let effective_limit = limit?;
-
-    if items.len() < effective_limit {
-        return None;
-    }
-
-    let last_item = items.last()?;
-    let sort_key = key_schema.sort_key.as_ref().and_then(|sk_def| {
-        last_item
-            .attributes
-            .get(&sk_def.name)
-            .and_then(|av| SortableAttributeValue::from_attribute_value(&sk_def.name, av).ok())
-    });
-
-    Some(PrimaryKey {
-        partition_key: partition_key.clone(),
-        sort_key,
-    })
-}
-
 // ---------------------------------------------------------------------------
 // Key extraction
 // ---------------------------------------------------------------------------
@@ -1158,7 +1208,7 @@ pub fn extract_primary_key(
     };
 
     Ok(PrimaryKey {
-        partition_key: pk_value.clone(),
+        partition_key: canonical_partition_key(pk_value),
         sort_key,
     })
 }
@@ -1280,6 +1330,39 @@ mod tests {
         }
     }
 
+    fn binary_sort_key_schema() -> KeySchema {
+        KeySchema {
+            partition_key: KeyAttribute {
+                name: "pk".to_owned(),
+                attr_type: ScalarAttributeType::S,
+            },
+            sort_key: Some(KeyAttribute {
+                name: "sk".to_owned(),
+                attr_type: ScalarAttributeType::B,
+            }),
+        }
+    }
+
+    /// Binary keys in unsigned byte order, including prefixes of each other
+    /// and bytes with the high bit set.
+    const ORDERED_BINARY_KEYS: [&[u8]; 11] = [
+        &[0x00],
+        &[0x00, 0x00],
+        &[0x00, 0xFF],
+        &[0x01],
+        &[0x7F],
+        &[0x7F, 0xFF],
+        &[0x80],
+        &[0x80, 0x00],
+        &[0xFF],
+        &[0xFF, 0x00],
+        &[0xFF, 0xFF],
+    ];
+
+    fn binary(bytes: &[u8]) -> bytes::Bytes {
+        bytes::Bytes::copy_from_slice(bytes)
+    }
+
     fn make_item(pairs: &[(&str, AttributeValue)]) -> HashMap<String, AttributeValue> {
         pairs
             .iter()
@@ -1312,6 +1395,42 @@ mod tests {
         assert_eq!(storage.item_count(), 1);
     }
 
+    #[test]
+    fn test_should_modify_item_atomically() {
+        let storage = TableStorage::new(string_key_schema());
+        let key = PrimaryKey {
+            partition_key: AttributeValue::S("user1".to_owned()),
+            sort_key: None,
+        };
+        let kept: Result<_, ()> = storage.modify_item(&key, |existing| {
+            assert!(existing.is_none());
+            Ok((ItemWrite::Keep, 1))
+        });
+        assert_eq!(kept, Ok((None, 1)));
+        assert_eq!(storage.item_count(), 0);
+
+        let item = make_item(&[("pk", AttributeValue::S("user1".to_owned()))]);
+        let (old, ()) = storage
+            .modify_item::<_, ()>(&key, |_| Ok((ItemWrite::Put(item.clone()), ())))
+            .unwrap();
+        assert!(old.is_none());
+        assert_eq!(storage.item_count(), 1);
+
+        let failed = storage.modify_item(&key, |existing| {
+            assert_eq!(existing, Some(&item));
+            Err::<(ItemWrite, ()), _>("condition failed")
+        });
+        assert_eq!(failed, Err("condition failed"));
+        assert_eq!(storage.item_count(), 1);
+
+        let (old, ()) = storage
+            .modify_item::<_, ()>(&key, |_| Ok((ItemWrite::Delete, ())))
+            .unwrap();
+        assert_eq!(old, Some(item));
+        assert_eq!(storage.item_count(), 0);
+        assert_eq!(storage.total_size_bytes(), 0);
+    }
+
     #[test]
     fn test_should_delete_item() {
         let storage = TableStorage::new(string_key_schema());
@@ -1456,6 +1575,171 @@ mod tests {
         assert!(last_key2.is_none());
     }
 
+    #[test]
+    fn test_should_stop_visiting_at_the_page_cutoff() {
+        let storage = TableStorage::new(string_key_schema());
+        for i in 0..100 {
+            let item = make_item(&[("pk", AttributeValue::S(format!("user{i:03}")))]);
+            storage.put_item(item).ok();
+        }
+
+        let mut visited = Vec::new();
+        let last_key = storage
+            .scan_with(PageLimit::items(Some(10)), None, None, None, |item| {
+                visited.push(item["pk"].clone());
+                Ok::<_, ()>(())
+            })
+            .unwrap();
+        assert_eq!(visited.len(), 10);
+        assert_eq!(
+            last_key.map(|key| key.partition_key),
+            Some(AttributeValue::S("user009".to_owned()))
+        );
+
+        let err = storage.scan_with(PageLimit::items(None), None, None, None, |item| {
+            if item["pk"] == AttributeValue::S("user004".to_owned()) {
+                Err("stop")
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(err, Err("stop"));
+    }
+
+    #[test]
+    fn test_should_resume_scan_after_deleted_start_key() {
+        let storage = TableStorage::new(string_key_schema());
+
+        for i in 1..=5 {
+            let item = make_item(&[("pk", AttributeValue::S(format!("user{i}")))]);
+            storage.put_item(item).ok();
+        }
+
+        let (first, last_key) = storage.scan(Some(3), None, None, None);
+        let last_key = last_key.expect("page cut off by limit");
+        storage.delete_item(&last_key);
+
+        // The next page starts after the deleted item rather than over again.
+        let (rest, last_key) = storage.scan(Some(3), Some(&last_key), None, None);
+        assert_eq!(rest.len(), 2);
+        assert!(last_key.is_none());
+        assert!(rest.iter().all(|item| !first.contains(item)));
+    }
+
+    #[test]
+    fn test_should_order_binary_sort_keys_as_unsigned_bytes() {
+        let mut keys: Vec<SortableAttributeValue> = ORDERED_BINARY_KEYS
+            .iter()
+            .rev()
+            .map(|b| SortableAttributeValue::B(binary(b)))
+            .collect();
+        keys.sort();
+        let expected: Vec<SortableAttributeValue> = ORDERED_BINARY_KEYS
+            .iter()
+            .map(|b| SortableAttributeValue::B(binary(b)))
+            .collect();
+        assert_eq!(keys, expected);
+    }
+
+    #[test]
+    fn test_should_query_binary_sort_key_between_and_begins_with() {
+        let storage = TableStorage::new(binary_sort_key_schema());
+        for sk in ORDERED_BINARY_KEYS {
+            let item = make_item(&[
+                ("pk", AttributeValue::S("p".to_owned())),
+                ("sk", AttributeValue::B(binary(sk))),
+            ]);
+            storage.put_item(item).ok();
+        }
+        let pk = AttributeValue::S("p".to_owned());
+        let sort_keys = |items: Vec<HashMap<String, AttributeValue>>| -> Vec<bytes::Bytes> {
+            items
+                .into_iter()
+                .map(|item| match &item["sk"] {
+                    AttributeValue::B(b) => b.clone(),
+                    other => panic!("unexpected sort key {other:?}"),
+                })
+                .collect()
+        };
+
+        let condition = SortKeyCondition::Between(
+            SortableAttributeValue::B(binary(&[0x7F])),
+            SortableAttributeValue::B(binary(&[0xFF])),
+        );
+        let (items, _) = storage.query(&pk, Some(&condition), true, None, None);
+        assert_eq!(
+            sort_keys(items),
+            ORDERED_BINARY_KEYS[4..=8]
+                .iter()
+                .map(|b| binary(b))
+                .collect::<Vec<_>>()
+        );
+
+        let condition = SortKeyCondition::BeginsWithBytes(binary(&[0xFF]));
+        let (items, _) = storage.query(&pk, Some(&condition), true, None, None);
+        assert_eq!(
+            sort_keys(items),
+            [
+                binary(&[0xFF]),
+                binary(&[0xFF, 0x00]),
+                binary(&[0xFF, 0xFF])
+            ]
+        );
+
+        let condition = SortKeyCondition::BeginsWithBytes(binary(&[0x00]));
+        let (items, _) = storage.query(&pk, Some(&condition), false, None, None);
+        assert_eq!(
+            sort_keys(items),
+            [
+                binary(&[0x00, 0xFF]),
+                binary(&[0x00, 0x00]),
+                binary(&[0x00])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_should_scan_binary_partition_keys_in_byte_order() {
+        let storage = TableStorage::new(KeySchema {
+            partition_key: KeyAttribute {
+                name: "pk".to_owned(),
+                attr_type: ScalarAttributeType::B,
+            },
+            sort_key: None,
+        });
+        // Same-length keys that differ only in their high bits.
+        let keys: Vec<bytes::Bytes> = [0x00_u8, 0x7F, 0x80, 0xC0, 0xFF]
+            .iter()
+            .map(|b| binary(&[*b; 16]))
+            .collect();
+        for key in keys.iter().rev() {
+            storage
+                .put_item(make_item(&[("pk", AttributeValue::B(key.clone()))]))
+                .ok();
+        }
+
+        let mut scanned = Vec::new();
+        let mut start = None;
+        loop {
+            let (items, last_key) = storage.scan(Some(2), start.as_ref(), None, None);
+            scanned.extend(items.into_iter().map(|item| item["pk"].clone()));
+            if last_key.is_none() {
+                break;
+            }
+            start = last_key;
+        }
+        let expected: Vec<AttributeValue> = keys.iter().cloned().map(AttributeValue::B).collect();
+        assert_eq!(scanned, expected);
+
+        // Parallel scan segments partition the keys between them.
+        let mut segmented = 0;
+        for segment in 0..4 {
+            let (items, _) = storage.scan(None, None, Some(segment), Some(4));
+            segmented += items.len();
+        }
+        assert_eq!(segmented, keys.len());
+    }
+
     #[test]
     fn test_should_error_on_missing_key() {
         let storage = TableStorage::new(string_key_schema());
@@ -1588,6 +1872,96 @@ mod tests {
         }
     }
 
+    /// Pages through a query `limit` items at a time, returning the sort keys
+    /// of each page.
+    fn query_pages(
+        storage: &TableStorage,
+        condition: Option<&SortKeyCondition>,
+        scan_forward: bool,
+        limit: usize,
+    ) -> Vec<Vec<String>> {
+        let pk = AttributeValue::S("p1".to_owned());
+        let mut pages = Vec::new();
+        let mut start = None;
+        loop {
+            let (items, last_key) =
+                storage.query(&pk, condition, scan_forward, Some(limit), start.as_ref());
+            pages.push(
+                items
+                    .iter()
+                    .map(|item| match item.get("sk") {
+                        Some(AttributeValue::S(s) | AttributeValue::N(s)) => s.clone(),
+                        other => panic!("unexpected sort key {other:?}"),
+                    })
+                    .collect(),
+            );
+            match last_key.and_then(|key| key.sort_key) {
+                Some(sort_key) => start = Some(sort_key),
+                None => return pages,
+            }
+        }
+    }
+
+    #[test]
+    fn test_should_page_sort_conditions_in_both_directions() {
+        let numeric = TableStorage::new(numeric_sort_key_schema());
+        let strings = TableStorage::new(composite_key_schema());
+        for i in 1..=100 {
+            for (storage, sk) in [
+                (&numeric, AttributeValue::N(i.to_string())),
+                (&strings, AttributeValue::S(format!("item#{i:03}"))),
+                (&strings, AttributeValue::S(format!("other#{i:03}"))),
+            ] {
+                storage
+                    .put_item(make_item(&[
+                        ("pk", AttributeValue::S("p1".to_owned())),
+                        ("sk", sk),
+                    ]))
+                    .ok();
+            }
+        }
+        let n = |v: u32| SortableAttributeValue::N(v.to_string());
+
+        let between = SortKeyCondition::Between(n(20), n(80));
+        let at_least = SortKeyCondition::Ge(n(90));
+        let begins_with = SortKeyCondition::BeginsWithStr("item#".to_owned());
+        let cases: [(&TableStorage, &SortKeyCondition, Vec<String>); 3] = [
+            (
+                &numeric,
+                &between,
+                (20..=80).map(|i| i.to_string()).collect(),
+            ),
+            (
+                &numeric,
+                &at_least,
+                (90..=100).map(|i| i.to_string()).collect(),
+            ),
+            (
+                &strings,
+                &begins_with,
+                (1..=100).map(|i| format!("item#{i:03}")).collect(),
+            ),
+        ];
+
+        for (storage, condition, ascending) in cases {
+            let descending: Vec<_> = ascending.iter().rev().cloned().collect();
+            for (scan_forward, expected) in [(true, ascending), (false, descending)] {
+                let pages = query_pages(storage, Some(condition), scan_forward, 5);
+                assert_eq!(
+                    pages[0],
+                    expected[..5],
+                    "{condition:?} forward={scan_forward}"
+                );
+                assert!(pages.iter().all(|page| page.len() <= 5));
+                assert_eq!(
+                    pages.concat(),
+                    expected,
+                    "{condition:?} forward={scan_forward}"
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_should_handle_composite_key_put_get_delete() {
         let storage = TableStorage::new(composite_key_schema());
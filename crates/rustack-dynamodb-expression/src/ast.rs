@@ -8,7 +8,10 @@
 //! name and value references from parsed ASTs, used for validating that all
 //! provided names/values are actually used in expressions.
 
-use std::{collections::HashSet, fmt};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
 
 /// Expression AST node for condition, filter, and key-condition expressions.
 #[derive(Debug, Clone)]
@@ -137,7 +140,7 @@ impl fmt::Display for FunctionName {
 }
 
 /// An operand in an expression (a value producer).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Operand {
     /// A document path reference (e.g., `info.rating`, `#name`, `myList[0]`).
     Path(AttributePath),
@@ -149,8 +152,18 @@ pub enum Operand {
     Size(Box<Operand>),
 }
 
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Path(path) => write!(f, "{path}"),
+            Self::Value(name) => write!(f, ":{name}"),
+            Self::Size(inner) => write!(f, "size({inner})"),
+        }
+    }
+}
+
 /// A document path consisting of one or more elements.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AttributePath {
     /// The path elements in order.
     pub elements: Vec<PathElement>,
@@ -175,7 +188,7 @@ impl fmt::Display for AttributePath {
 }
 
 /// A single element in an attribute path.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PathElement {
     /// A named attribute (or `#placeholder` reference).
     Attribute(String),
@@ -196,6 +209,21 @@ pub struct UpdateExpr {
     pub delete_actions: Vec<DeleteAction>,
 }
 
+impl UpdateExpr {
+    /// All paths the update writes to: SET targets, REMOVE paths, then ADD
+    /// and DELETE targets.
+    #[must_use]
+    pub fn target_paths(&self) -> Vec<&AttributePath> {
+        self.set_actions
+            .iter()
+            .map(|action| &action.path)
+            .chain(&self.remove_paths)
+            .chain(self.add_actions.iter().map(|action| &action.path))
+            .chain(self.delete_actions.iter().map(|action| &action.path))
+            .collect()
+    }
+}
+
 /// A single SET action: `path = value`.
 #[derive(Debug, Clone)]
 pub struct SetAction {
@@ -459,3 +487,101 @@ fn collect_values_from_set_value(set_value: &SetValue, values: &mut HashSet<Stri
         }
     }
 }
+
+// ---------------------------------------------------------------------------
+// Resolving and comparing document paths
+// ---------------------------------------------------------------------------
+
+/// A path element after `#name` substitution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedPathElement {
+    /// A resolved attribute name.
+    Attribute(String),
+    /// A list index.
+    Index(usize),
+}
+
+/// Resolve all path elements to concrete names/indices for comparison.
+///
+/// Placeholders missing from `names` are kept verbatim.
+#[allow(clippy::implicit_hasher)]
+#[must_use]
+pub fn resolve_path_elements(
+    path: &AttributePath,
+    names: &HashMap<String, String>,
+) -> Vec<ResolvedPathElement> {
+    path.elements
+        .iter()
+        .map(|elem| match elem {
+            PathElement::Attribute(name) => {
+                let resolved = if name.starts_with('#') {
+                    names
+                        .get(name.as_str())
+                        .cloned()
+                        .unwrap_or_else(|| name.clone())
+                } else {
+                    name.clone()
+                };
+                ResolvedPathElement::Attribute(resolved)
+            }
+            PathElement::Index(idx) => ResolvedPathElement::Index(*idx),
+        })
+        .collect()
+}
+
+/// How two document paths in the same update expression collide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathClash {
+    /// One path is equal to or a prefix of the other.
+    Overlap,
+    /// One path uses dot access where the other uses index access.
+    Conflict,
+}
+
+/// Find the first pair of paths that overlap or conflict, returning their
+/// indices (`i < j`) and the kind of clash.
+#[must_use]
+pub fn find_path_clash(paths: &[Vec<ResolvedPathElement>]) -> Option<(usize, usize, PathClash)> {
+    for i in 0..paths.len() {
+        for j in (i + 1)..paths.len() {
+            if paths_overlap(&paths[i], &paths[j]) {
+                return Some((i, j, PathClash::Overlap));
+            }
+            if paths_conflict(&paths[i], &paths[j]) {
+                return Some((i, j, PathClash::Conflict));
+            }
+        }
+    }
+    None
+}
+
+/// Check whether two resolved paths overlap (one is a prefix of the other or they are equal).
+fn paths_overlap(a: &[ResolvedPathElement], b: &[ResolvedPathElement]) -> bool {
+    a.iter().zip(b).all(|(x, y)| x == y)
+}
+
+/// Check whether two resolved paths conflict (one uses dot access and the other uses
+/// index access at the same position).
+fn paths_conflict(a: &[ResolvedPathElement], b: &[ResolvedPathElement]) -> bool {
+    for (x, y) in a.iter().zip(b) {
+        match (x, y) {
+            // Same attribute or same index: continue
+            (ResolvedPathElement::Attribute(na), ResolvedPathElement::Attribute(nb)) => {
+                if na != nb {
+                    return false;
+                }
+            }
+            (ResolvedPathElement::Index(ia), ResolvedPathElement::Index(ib)) => {
+                if ia != ib {
+                    return false;
+                }
+            }
+            // One uses dot, other uses index at same position: conflict
+            (ResolvedPathElement::Attribute(_), ResolvedPathElement::Index(_))
+            | (ResolvedPathElement::Index(_), ResolvedPathElement::Attribute(_)) => {
+                return true;
+            }
+        }
+    }
+    false
+}
@@ -4,17 +4,18 @@
 //! then evaluates condition expressions to booleans, applies update mutations, or
 //! projects attributes.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use rustack_dynamodb_model::AttributeValue;
 
 use super::{
     ast::{
         AddAction, AttributePath, CompareOp, DeleteAction, Expr, FunctionName, LogicalOp, Operand,
-        PathElement, SetAction, SetValue, UpdateExpr,
+        PathElement, ResolvedPathElement, SetAction, SetValue, UpdateExpr, resolve_path_elements,
     },
     parser::ExpressionError,
 };
+use crate::number::Number;
 
 // ---------------------------------------------------------------------------
 // Evaluation context
@@ -403,7 +404,11 @@ impl EvalContext<'_> {
         for action in &sorted_set_actions {
             self.apply_set_action(&mut result, action)?;
         }
-        for path in &update.remove_paths {
+        // Remove later list elements first, so every index refers to the
+        // original list: `REMOVE l[0], l[1]` drops the first two elements.
+        let mut sorted_remove_paths: Vec<&AttributePath> = update.remove_paths.iter().collect();
+        sorted_remove_paths.sort_by(|a, b| compare_paths(b, a, self.names));
+        for path in sorted_remove_paths {
             apply_remove(&mut result, path, self.names)?;
         }
         for action in &update.add_actions {
@@ -614,24 +619,80 @@ impl EvalContext<'_> {
 
 impl EvalContext<'_> {
     /// Apply a projection expression, returning a new item containing only the
-    /// requested attributes. For nested paths, reconstructs the intermediate structure
-    /// so that only the projected leaf values are included.
+    /// requested attributes.
+    ///
+    /// Nested paths rebuild their enclosing maps and lists with only the
+    /// projected members: sibling paths (`a.b, a.c`) merge into one `a` map,
+    /// and projected list elements (`a[2], a[0]`) are compacted into a list in
+    /// index order. Paths that resolve to nothing are left out.
     #[must_use]
     pub fn apply_projection(&self, paths: &[AttributePath]) -> HashMap<String, AttributeValue> {
-        let mut result = HashMap::new();
+        let mut tree = ProjectionTree::default();
         for path in paths {
-            if let Some(val) = self.resolve_path(path) {
-                if let Some(top_name) = resolve_top_level_name_opt(path, self.names) {
-                    if path.elements.len() == 1 {
-                        result.insert(top_name, val.clone());
-                    } else {
-                        // For nested paths, reconstruct the nested structure.
-                        deep_insert(&mut result, path, val.clone(), self.names);
-                    }
-                }
+            tree.insert(&resolve_path_elements(path, self.names));
+        }
+        tree.project_members(self.item)
+    }
+}
+
+/// The projected paths below one node of an item, merged so paths sharing a
+/// prefix share the nodes along it.
+#[derive(Debug, Default)]
+struct ProjectionTree {
+    /// Whether the whole value at this node is projected.
+    whole: bool,
+    /// Projected map members, by name.
+    members: HashMap<String, ProjectionTree>,
+    /// Projected list elements, by index.
+    elements: BTreeMap<usize, ProjectionTree>,
+}
+
+impl ProjectionTree {
+    /// Add the path `path` below this node.
+    fn insert(&mut self, path: &[ResolvedPathElement]) {
+        match path.split_first() {
+            None => self.whole = true,
+            Some((ResolvedPathElement::Attribute(name), rest)) => {
+                self.members.entry(name.clone()).or_default().insert(rest);
+            }
+            Some((ResolvedPathElement::Index(index), rest)) => {
+                self.elements.entry(*index).or_default().insert(rest);
+            }
+        }
+    }
+
+    /// The projected members of `map`.
+    fn project_members(
+        &self,
+        map: &HashMap<String, AttributeValue>,
+    ) -> HashMap<String, AttributeValue> {
+        self.members
+            .iter()
+            .filter_map(|(name, tree)| Some((name.clone(), tree.project(map.get(name)?)?)))
+            .collect()
+    }
+
+    /// The projected part of `value`, or `None` if no projected path exists
+    /// in it.
+    fn project(&self, value: &AttributeValue) -> Option<AttributeValue> {
+        if self.whole {
+            return Some(value.clone());
+        }
+        match value {
+            AttributeValue::M(map) => {
+                let projected = self.project_members(map);
+                (!projected.is_empty()).then_some(AttributeValue::M(projected))
+            }
+            AttributeValue::L(list) => {
+                let projected: Vec<_> = self
+                    .elements
+                    .iter()
+                    .filter_map(|(index, tree)| tree.project(list.get(*index)?))
+                    .collect();
+                (!projected.is_empty()).then_some(AttributeValue::L(projected))
             }
+            _ => None,
         }
-        result
     }
 }
 
@@ -707,9 +768,7 @@ fn compare_values(
         // Ordered types: support all six comparison operators.
         (AttributeValue::S(a), AttributeValue::S(b)) => Ok(compare_ord(a, b, op)),
         (AttributeValue::N(a), AttributeValue::N(b)) => {
-            let fa = parse_number(a)?;
-            let fb = parse_number(b)?;
-            Ok(compare_f64(fa, fb, op))
+            Ok(compare_ord(&parse_number(a)?, &parse_number(b)?, op))
         }
         (AttributeValue::B(a), AttributeValue::B(b)) => Ok(compare_ord(a, b, op)),
 
@@ -803,13 +862,10 @@ fn deep_equal_values(a: &AttributeValue, b: &AttributeValue) -> bool {
         (AttributeValue::S(a), AttributeValue::S(b)) => a == b,
         (AttributeValue::N(a), AttributeValue::N(b)) => {
             // Compare numerically to handle "1" == "1.0" etc.
-            let Ok(fa) = a.parse::<f64>() else {
-                return a == b;
-            };
-            let Ok(fb) = b.parse::<f64>() else {
-                return a == b;
-            };
-            (fa - fb).abs() < f64::EPSILON
+            match (Number::parse(a), Number::parse(b)) {
+                (Some(na), Some(nb)) => na == nb,
+                _ => a == b,
+            }
         }
         (AttributeValue::B(a), AttributeValue::B(b)) => a == b,
         (AttributeValue::Bool(a), AttributeValue::Bool(b))
@@ -837,26 +893,14 @@ fn compare_ord<T: Ord>(a: &T, b: &T, op: CompareOp) -> bool {
     }
 }
 
-/// Floating-point comparison for DynamoDB number values.
-fn compare_f64(a: f64, b: f64, op: CompareOp) -> bool {
-    match op {
-        CompareOp::Eq => (a - b).abs() < f64::EPSILON,
-        CompareOp::Ne => (a - b).abs() >= f64::EPSILON,
-        CompareOp::Lt => a < b,
-        CompareOp::Le => a <= b,
-        CompareOp::Gt => a > b,
-        CompareOp::Ge => a >= b,
-    }
-}
-
-/// Parse a DynamoDB number string to f64.
-fn parse_number(s: &str) -> Result<f64, ExpressionError> {
-    s.parse::<f64>().map_err(|_| ExpressionError::TypeMismatch {
+/// Parse a DynamoDB number string exactly.
+fn parse_number(s: &str) -> Result<Number, ExpressionError> {
+    Number::parse(s).ok_or_else(|| ExpressionError::TypeMismatch {
         message: format!("'{s}' is not a valid number"),
     })
 }
 
-/// Perform numeric addition or subtraction.
+/// Perform exact numeric addition or subtraction.
 fn numeric_arithmetic(
     a: &AttributeValue,
     b: &AttributeValue,
@@ -867,233 +911,17 @@ fn numeric_arithmetic(
             message: "arithmetic requires number operands".to_owned(),
         });
     };
-    let result = precise_arithmetic(na, nb, is_add)?;
-    Ok(AttributeValue::N(result))
-}
-
-/// Parsed number representation: sign, significant digits, and exponent.
-/// The number represents: sign * digits * 10^exponent
-/// e.g. "123.45" → (false, "12345", -2), "-5e3" → (true, "5", 3)
-struct ParsedNumber {
-    negative: bool,
-    /// Significant digits as a string (no leading zeros, no trailing zeros).
-    digits: String,
-    /// Exponent after shifting all digits to integers.
-    exponent: i64,
-}
-
-/// Parse a number string into its components.
-fn parse_number_components(s: &str) -> Result<ParsedNumber, ExpressionError> {
-    let trimmed = s.trim();
-    let (negative, rest) = if let Some(r) = trimmed.strip_prefix('-') {
-        (true, r)
-    } else if let Some(r) = trimmed.strip_prefix('+') {
-        (false, r)
-    } else {
-        (false, trimmed)
-    };
-
-    let (mantissa, explicit_exp) = if let Some(pos) = rest.find(['e', 'E']) {
-        let exp: i64 = rest[pos + 1..]
-            .parse()
-            .map_err(|_| ExpressionError::TypeMismatch {
-                message: "invalid number format".to_owned(),
-            })?;
-        (&rest[..pos], exp)
-    } else {
-        (rest, 0i64)
-    };
-
-    // Remove the decimal point and adjust exponent.
-    let (all_digits, frac_len) = if let Some(dot_pos) = mantissa.find('.') {
-        let integer_part = &mantissa[..dot_pos];
-        let frac_part = &mantissa[dot_pos + 1..];
-        let all = format!("{integer_part}{frac_part}");
-        #[allow(clippy::cast_possible_wrap)]
-        let frac = frac_part.len() as i64;
-        (all, frac)
+    let (na, nb) = (parse_number(na)?, parse_number(nb)?);
+    let result = if is_add {
+        na.checked_add(&nb)
     } else {
-        (mantissa.to_owned(), 0i64)
+        na.checked_sub(&nb)
     };
-
-    // Remove leading zeros.
-    let trimmed_digits = all_digits.trim_start_matches('0').to_owned();
-    if trimmed_digits.is_empty() {
-        return Ok(ParsedNumber {
-            negative: false,
-            digits: "0".to_owned(),
-            exponent: 0,
-        });
-    }
-
-    // Remove trailing zeros and adjust exponent.
-    let trailing_zeros = trimmed_digits.len() - trimmed_digits.trim_end_matches('0').len();
-    let final_digits = trimmed_digits.trim_end_matches('0').to_owned();
-    #[allow(clippy::cast_possible_wrap)]
-    let exponent = explicit_exp - frac_len + trailing_zeros as i64;
-
-    Ok(ParsedNumber {
-        negative,
-        digits: final_digits,
-        exponent,
-    })
-}
-
-/// Perform precise arithmetic on two DynamoDB number strings.
-/// Returns the result as a canonical DynamoDB number string.
-fn precise_arithmetic(a: &str, b: &str, is_add: bool) -> Result<String, ExpressionError> {
-    let mut pa = parse_number_components(a)?;
-    let mut pb = parse_number_components(b)?;
-
-    // For subtraction, flip the sign of b.
-    if !is_add {
-        pb.negative = !pb.negative;
-    }
-
-    // If either is zero, return the other.
-    if pa.digits == "0" {
-        return Ok(format_parsed_number(&pb));
-    }
-    if pb.digits == "0" {
-        return Ok(format_parsed_number(&pa));
-    }
-
-    // Align exponents by padding the higher-exponent number with trailing zeros.
-    let min_exp = pa.exponent.min(pb.exponent);
-    // Shifts are always non-negative since min_exp <= both exponents.
-    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
-    let a_shift = (pa.exponent - min_exp) as usize;
-    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
-    let b_shift = (pb.exponent - min_exp) as usize;
-    pa.digits.push_str(&"0".repeat(a_shift));
-    pb.digits.push_str(&"0".repeat(b_shift));
-    pa.exponent = min_exp;
-    pb.exponent = min_exp;
-
-    // Check total digit count — if alignment requires too many digits, precision is lost.
-    let max_digits = pa.digits.len().max(pb.digits.len());
-    if max_digits > 38 + 2 {
-        // Precision would be lost in the result.
-        return Err(ExpressionError::Validation {
-            message: "Number overflow. Attempting to store a number with magnitude larger than \
-                      supported range"
-                .to_owned(),
-        });
-    }
-
-    // Convert to i128 for arithmetic (supports up to 38 digits).
-    let a_val: i128 = pa
-        .digits
-        .parse()
-        .map_err(|_| ExpressionError::TypeMismatch {
-            message: "number too large for arithmetic".to_owned(),
-        })?;
-    let b_val: i128 = pb
-        .digits
-        .parse()
-        .map_err(|_| ExpressionError::TypeMismatch {
-            message: "number too large for arithmetic".to_owned(),
-        })?;
-
-    let a_signed = if pa.negative { -a_val } else { a_val };
-    let b_signed = if pb.negative { -b_val } else { b_val };
-    let result = a_signed + b_signed;
-
-    let result_negative = result < 0;
-    let result_abs = result.unsigned_abs();
-    let result_str = result_abs.to_string();
-
-    // Remove trailing zeros and adjust exponent.
-    let trimmed = result_str.trim_end_matches('0');
-    let trailing = result_str.len() - trimmed.len();
-    #[allow(clippy::cast_possible_wrap)]
-    let final_exp = min_exp + trailing as i64;
-
-    if trimmed.is_empty() || trimmed == "0" {
-        return Ok("0".to_owned());
-    }
-
-    // Validate result precision and magnitude.
-    if trimmed.len() > 38 {
-        return Err(ExpressionError::Validation {
-            message: "Number overflow. Attempting to store a number with magnitude larger than \
-                      supported range"
-                .to_owned(),
-        });
-    }
-
-    #[allow(clippy::cast_possible_wrap)]
-    let magnitude = final_exp + trimmed.len() as i64 - 1;
-    if magnitude > 125 {
-        return Err(ExpressionError::Validation {
-            message: "Number overflow. Attempting to store a number with magnitude larger than \
-                      supported range"
-                .to_owned(),
-        });
-    }
-    if magnitude < -130 {
-        return Err(ExpressionError::Validation {
-            message: "Number underflow. Attempting to store a number with magnitude smaller than \
-                      supported range"
-                .to_owned(),
-        });
-    }
-
-    let parsed = ParsedNumber {
-        negative: result_negative,
-        digits: trimmed.to_owned(),
-        exponent: final_exp,
-    };
-    Ok(format_parsed_number(&parsed))
-}
-
-/// Format a `ParsedNumber` into a canonical DynamoDB number string.
-#[allow(
-    clippy::cast_possible_wrap,
-    clippy::cast_sign_loss,
-    clippy::cast_possible_truncation
-)]
-fn format_parsed_number(p: &ParsedNumber) -> String {
-    if p.digits == "0" {
-        return "0".to_owned();
-    }
-
-    let sign = if p.negative { "-" } else { "" };
-    let digits = &p.digits;
-    let exp = p.exponent;
-
-    // The number is: digits * 10^exp
-    // We want to produce the most natural representation.
-    let total_integer_digits = digits.len() as i64 + exp;
-
-    if exp >= 0 && total_integer_digits <= 20 {
-        // Pure integer: append zeros.
-        return format!("{sign}{digits}{}", "0".repeat(exp as usize));
-    }
-
-    if total_integer_digits > 0 && total_integer_digits <= 20 {
-        // Mixed: some integer digits, some fractional.
-        let int_part = &digits[..total_integer_digits as usize];
-        let frac_part = &digits[total_integer_digits as usize..];
-        return format!("{sign}{int_part}.{frac_part}");
-    }
-
-    if ((-6)..=0).contains(&total_integer_digits) {
-        // Small decimal: 0.000...digits
-        let leading_zeros = (-total_integer_digits) as usize;
-        return format!("{sign}0.{}{digits}", "0".repeat(leading_zeros));
-    }
-
-    // Scientific notation for very large or very small numbers.
-    if digits.len() == 1 {
-        let sci_exp = exp + digits.len() as i64 - 1;
-        format!("{sign}{digits}E{sci_exp}")
-    } else {
-        let int_part = &digits[..1];
-        let frac_part = &digits[1..];
-        let sci_exp = exp + digits.len() as i64 - 1;
-        format!("{sign}{int_part}.{frac_part}E{sci_exp}")
-    }
+    result
+        .map(|n| AttributeValue::N(n.to_string()))
+        .map_err(|e| ExpressionError::Validation {
+            message: e.to_string(),
+        })
 }
 
 /// Compute the size of an `AttributeValue`.
@@ -1119,131 +947,6 @@ fn is_valid_type_descriptor(s: &str) -> bool {
     )
 }
 
-/// Insert a value at a nested path within a result map, creating intermediate structures.
-/// Resolved path element for deep insertion.
-enum ResolvedElement {
-    /// A map key access.
-    MapKey(String),
-    /// A list index access (value not needed since projection appends densely).
-    ListIndex,
-}
-
-/// Insert a value at the given path into a result map, creating intermediate
-/// maps and lists as needed. Handles nested map and list structures, merging
-/// values with shared prefixes. For list indices, creates sparse lists (filled
-/// with `Null(true)` placeholders) large enough to hold the target index.
-fn deep_insert(
-    result: &mut HashMap<String, AttributeValue>,
-    path: &AttributePath,
-    value: AttributeValue,
-    names: &HashMap<String, String>,
-) {
-    if path.elements.is_empty() {
-        return;
-    }
-
-    // Build a resolved elements list (replacing `#name` references).
-    let resolved: Vec<ResolvedElement> = path
-        .elements
-        .iter()
-        .map(|e| match e {
-            PathElement::Attribute(name) => {
-                let resolved_name = if name.starts_with('#') {
-                    names
-                        .get(name.as_str())
-                        .cloned()
-                        .unwrap_or_else(|| name.clone())
-                } else {
-                    name.clone()
-                };
-                ResolvedElement::MapKey(resolved_name)
-            }
-            PathElement::Index(_) => ResolvedElement::ListIndex,
-        })
-        .collect();
-
-    // The first element must be a map key (top-level attribute name).
-    let ResolvedElement::MapKey(ref top_name) = resolved[0] else {
-        return;
-    };
-
-    if resolved.len() == 1 {
-        result.insert(top_name.clone(), value);
-        return;
-    }
-
-    // For deeper paths, navigate/create intermediate containers.
-    let entry = result
-        .entry(top_name.clone())
-        .or_insert_with(|| match &resolved[1] {
-            ResolvedElement::MapKey(_) => AttributeValue::M(HashMap::new()),
-            ResolvedElement::ListIndex => AttributeValue::L(Vec::new()),
-        });
-
-    deep_insert_into_av(entry, &resolved[1..], value);
-}
-
-/// Recursively insert a value into an `AttributeValue` at the given resolved path.
-fn deep_insert_into_av(
-    target: &mut AttributeValue,
-    resolved: &[ResolvedElement],
-    value: AttributeValue,
-) {
-    if resolved.is_empty() {
-        return;
-    }
-
-    if resolved.len() == 1 {
-        // Base case: set the value.
-        match &resolved[0] {
-            ResolvedElement::MapKey(key) => {
-                if let AttributeValue::M(map) = target {
-                    map.insert(key.clone(), value);
-                }
-            }
-            ResolvedElement::ListIndex => {
-                if let AttributeValue::L(list) = target {
-                    // For projection, we append values without null-padding.
-                    // Each projected index just gets appended to the result list.
-                    list.push(value);
-                }
-            }
-        }
-        return;
-    }
-
-    // Recursive case: navigate into the next level.
-    match &resolved[0] {
-        ResolvedElement::MapKey(key) => {
-            if let AttributeValue::M(map) = target {
-                // Determine what the next element expects.
-                let child = map
-                    .entry(key.clone())
-                    .or_insert_with(|| match &resolved[1] {
-                        ResolvedElement::MapKey(_) => AttributeValue::M(HashMap::new()),
-                        ResolvedElement::ListIndex => AttributeValue::L(Vec::new()),
-                    });
-                deep_insert_into_av(child, &resolved[1..], value);
-            }
-        }
-        ResolvedElement::ListIndex => {
-            if let AttributeValue::L(list) = target {
-                // For projection, find or create a child entry.
-                // We append a new entry if the list is empty or create one.
-                if list.is_empty() {
-                    let child = match &resolved[1] {
-                        ResolvedElement::MapKey(_) => AttributeValue::M(HashMap::new()),
-                        ResolvedElement::ListIndex => AttributeValue::L(Vec::new()),
-                    };
-                    list.push(child);
-                }
-                let child = list.last_mut().expect("just pushed");
-                deep_insert_into_av(child, &resolved[1..], value);
-            }
-        }
-    }
-}
-
 /// Extract the path argument from an operand, returning an error if it is not a path.
 fn operand_as_path<'o>(
     operand: &'o Operand,
@@ -1279,21 +982,6 @@ fn resolve_top_level_name(
     }
 }
 
-/// Same as `resolve_top_level_name` but returns `Option` instead of `Result`.
-fn resolve_top_level_name_opt(
-    path: &AttributePath,
-    names: &HashMap<String, String>,
-) -> Option<String> {
-    let PathElement::Attribute(name) = path.elements.first()? else {
-        return None;
-    };
-    if name.starts_with('#') {
-        names.get(name.as_str()).cloned()
-    } else {
-        Some(name.clone())
-    }
-}
-
 /// Resolve a name reference, handling `#name` substitution.
 fn resolve_name_ref(name: &str, names: &HashMap<String, String>) -> String {
     if name.starts_with('#') {
@@ -1314,6 +1002,28 @@ fn extract_last_index(path: &AttributePath) -> usize {
     }
 }
 
+/// Order two paths element by element, comparing list indices numerically.
+fn compare_paths(
+    a: &AttributePath,
+    b: &AttributePath,
+    names: &HashMap<String, String>,
+) -> std::cmp::Ordering {
+    for (ea, eb) in a.elements.iter().zip(&b.elements) {
+        let ordering = match (ea, eb) {
+            (PathElement::Attribute(na), PathElement::Attribute(nb)) => {
+                resolve_name_ref(na, names).cmp(&resolve_name_ref(nb, names))
+            }
+            (PathElement::Index(ia), PathElement::Index(ib)) => ia.cmp(ib),
+            (PathElement::Attribute(_), PathElement::Index(_)) => std::cmp::Ordering::Less,
+            (PathElement::Index(_), PathElement::Attribute(_)) => std::cmp::Ordering::Greater,
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+    a.elements.len().cmp(&b.elements.len())
+}
+
 /// Set a value at the given path in an item. For top-level paths, this inserts
 /// directly into the map. For nested paths, it traverses/creates intermediate maps.
 ///
@@ -1633,19 +1343,20 @@ fn compute_add_result(
         }
         // ADD to a string set
         (AttributeValue::Ss(new_items), Some(AttributeValue::Ss(existing_set))) => {
-            let mut merged = existing_set.clone();
-            for s in new_items {
-                if !merged.contains(s) {
-                    merged.push(s.clone());
-                }
-            }
-            Ok(AttributeValue::Ss(merged))
+            Ok(AttributeValue::Ss(set_union(existing_set, new_items)))
         }
-        // ADD to a number set
+        // ADD to a number set: members are compared by value, so `2.0` is
+        // already in a set holding `2`.
         (AttributeValue::Ns(new_items), Some(AttributeValue::Ns(existing_set))) => {
             let mut merged = existing_set.clone();
+            let mut present = existing_set
+                .iter()
+                .map(|n| parse_number(n))
+                .collect::<Result<Vec<_>, _>>()?;
             for n in new_items {
-                if !merged.contains(n) {
+                let value = parse_number(n)?;
+                if !present.contains(&value) {
+                    present.push(value);
                     merged.push(n.clone());
                 }
             }
@@ -1653,29 +1364,33 @@ fn compute_add_result(
         }
         // ADD to a binary set
         (AttributeValue::Bs(new_items), Some(AttributeValue::Bs(existing_set))) => {
-            let mut merged = existing_set.clone();
-            for b in new_items {
-                if !merged.contains(b) {
-                    merged.push(b.clone());
-                }
-            }
-            Ok(AttributeValue::Bs(merged))
+            Ok(AttributeValue::Bs(set_union(existing_set, new_items)))
         }
         // ADD a number or set to non-existing attribute: set it directly
         (_, None) => Ok(add_val.clone()),
-        // ADD a number/set to a mismatched existing attribute type
-        (_, Some(existing_val)) => {
-            let add_type = add_val.type_descriptor();
-            let existing_type = existing_val.type_descriptor();
-            Err(ExpressionError::InvalidOperand {
-                operation: "ADD".to_owned(),
-                message: format!(
-                    "Type mismatch for ADD; operator type: {add_type}, existing type: \
-                     {existing_type}"
-                ),
-            })
+        // ADD a number/set to a mismatched existing attribute type, including
+        // a set of another element type.
+        (_, Some(existing_val)) => Err(ExpressionError::Validation {
+            message: format!(
+                "An operand in the update expression has an incorrect data type; operator: ADD, \
+                 operand type: {}, existing type: {}",
+                add_val.type_descriptor(),
+                existing_val.type_descriptor()
+            ),
+        }),
+    }
+}
+
+/// The members of `existing` followed by the members of `new_items` not
+/// already in it.
+fn set_union<T: Clone + PartialEq>(existing: &[T], new_items: &[T]) -> Vec<T> {
+    let mut merged = existing.to_vec();
+    for item in new_items {
+        if !merged.contains(item) {
+            merged.push(item.clone());
         }
     }
+    merged
 }
 
 // ---------------------------------------------------------------------------
@@ -1685,7 +1400,7 @@ fn compute_add_result(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::expression::parser::{parse_condition, parse_projection, parse_update};
+    use crate::parser::{parse_condition, parse_projection, parse_update};
 
     /// Helper to build a simple item with string attributes.
     fn make_item(pairs: &[(&str, AttributeValue)]) -> HashMap<String, AttributeValue> {
@@ -1964,6 +1679,71 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_should_apply_set_arithmetic_exactly_on_large_decimals() {
+        let item = make_item(&[(
+            "counter",
+            AttributeValue::N("99999999999999999999999999.000000001".to_owned()),
+        )]);
+        let names = HashMap::new();
+        let values = make_values(&[
+            (":inc", AttributeValue::N("1".to_owned())),
+            (
+                ":big",
+                AttributeValue::N("9999999999999999999999999999".to_owned()),
+            ),
+            (":huge", AttributeValue::N("1e29".to_owned())),
+        ]);
+        let ctx = EvalContext {
+            item: &item,
+            names: &names,
+            values: &values,
+        };
+
+        let update = parse_update("SET counter = counter + :inc").unwrap();
+        let result = ctx.apply_update(&update).unwrap();
+        assert_eq!(
+            result.get("counter"),
+            Some(&AttributeValue::N(
+                "100000000000000000000000000.000000001".to_owned()
+            ))
+        );
+
+        // 29 integer digits plus 9 fractional ones is exactly 38 significant
+        // digits; one more integer digit is too many.
+        let update = parse_update("SET counter = counter + :big").unwrap();
+        let result = ctx.apply_update(&update).unwrap();
+        assert_eq!(
+            result.get("counter"),
+            Some(&AttributeValue::N(
+                "10099999999999999999999999998.000000001".to_owned()
+            ))
+        );
+
+        let err = ctx
+            .apply_update(&parse_update("SET counter = counter + :huge").unwrap())
+            .unwrap_err();
+        assert!(matches!(err, ExpressionError::Validation { .. }));
+    }
+
+    #[test]
+    fn test_should_compare_numbers_differing_in_late_digits() {
+        let item = make_item(&[("n", AttributeValue::N("12345678901234567890123".to_owned()))]);
+        let names = empty_names();
+        let values = make_values(&[(
+            ":v",
+            AttributeValue::N("12345678901234567890124".to_owned()),
+        )]);
+        let ctx = EvalContext {
+            item: &item,
+            names: &names,
+            values: &values,
+        };
+        assert!(ctx.evaluate(&parse_condition("n < :v").unwrap()).unwrap());
+        assert!(ctx.evaluate(&parse_condition("n <> :v").unwrap()).unwrap());
+        assert!(!ctx.evaluate(&parse_condition("n = :v").unwrap()).unwrap());
+    }
+
     #[test]
     fn test_should_apply_set_if_not_exists() {
         let item = HashMap::new();
@@ -2289,6 +2069,45 @@ mod tests {
         assert!(!ctx.evaluate(&expr).unwrap());
     }
 
+    #[test]
+    fn test_should_compare_binary_values_as_unsigned_bytes() {
+        let item = make_item(&[(
+            "data",
+            AttributeValue::B(bytes::Bytes::from_static(b"\x80\x00")),
+        )]);
+        let names = empty_names();
+        let values = make_values(&[
+            (
+                ":low",
+                AttributeValue::B(bytes::Bytes::from_static(b"\x7F\xFF")),
+            ),
+            (
+                ":high",
+                AttributeValue::B(bytes::Bytes::from_static(b"\xFF")),
+            ),
+            (
+                ":prefix",
+                AttributeValue::B(bytes::Bytes::from_static(b"\x80")),
+            ),
+        ]);
+        let evaluate = |condition: &str| {
+            let expr = parse_condition(condition).unwrap();
+            let ctx = EvalContext {
+                item: &item,
+                names: &names,
+                values: &values,
+            };
+            ctx.evaluate(&expr).unwrap()
+        };
+
+        assert!(evaluate("data BETWEEN :low AND :high"));
+        assert!(evaluate("data > :low"));
+        assert!(evaluate("data < :high"));
+        // A prefix sorts before the longer values that start with it.
+        assert!(evaluate("data > :prefix"));
+        assert!(!evaluate("data BETWEEN :high AND :high"));
+    }
+
     #[test]
     fn test_should_evaluate_begins_with_type_mismatch_returns_false() {
         // String attribute with binary prefix should return false.
@@ -2442,6 +2261,111 @@ mod tests {
         );
     }
 
+    /// Apply `ADD attr :v` to an item holding `existing` under `attr`.
+    fn add_to(
+        existing: Option<AttributeValue>,
+        operand: AttributeValue,
+    ) -> Result<Option<AttributeValue>, ExpressionError> {
+        let item: HashMap<_, _> = existing
+            .map(|v| ("attr".to_owned(), v))
+            .into_iter()
+            .collect();
+        let names = empty_names();
+        let values = make_values(&[(":v", operand)]);
+        let ctx = EvalContext {
+            item: &item,
+            names: &names,
+            values: &values,
+        };
+        let mut result = ctx.apply_update(&parse_update("ADD attr :v").unwrap())?;
+        Ok(result.remove("attr"))
+    }
+
+    #[test]
+    fn test_should_add_number_to_number() {
+        let n = |v: &str| AttributeValue::N(v.to_owned());
+        assert_eq!(add_to(Some(n("10")), n("-2.5")).unwrap(), Some(n("7.5")));
+    }
+
+    #[test]
+    fn test_should_add_set_to_set_of_the_same_type_as_a_union() {
+        let ss = |v: &[&str]| AttributeValue::Ss(v.iter().map(|s| (*s).to_owned()).collect());
+        let ns = |v: &[&str]| AttributeValue::Ns(v.iter().map(|s| (*s).to_owned()).collect());
+        let bs = |v: &[&[u8]]| {
+            AttributeValue::Bs(v.iter().map(|b| bytes::Bytes::copy_from_slice(b)).collect())
+        };
+        assert_eq!(
+            add_to(Some(ss(&["a", "b"])), ss(&["b", "c"])).unwrap(),
+            Some(ss(&["a", "b", "c"]))
+        );
+        // Numbers are compared by value, not by their text.
+        assert_eq!(
+            add_to(Some(ns(&["1", "2"])), ns(&["2.0", "3"])).unwrap(),
+            Some(ns(&["1", "2", "3"]))
+        );
+        assert_eq!(
+            add_to(Some(bs(&[b"a"])), bs(&[b"a", b"b"])).unwrap(),
+            Some(bs(&[b"a", b"b"]))
+        );
+    }
+
+    #[test]
+    fn test_should_reject_adding_set_to_set_of_another_type() {
+        let ss = AttributeValue::Ss(vec!["a".to_owned()]);
+        let ns = AttributeValue::Ns(vec!["1".to_owned()]);
+        let err = add_to(Some(ns), ss).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "An operand in the update expression has an incorrect data type; operator: ADD, \
+             operand type: SS, existing type: NS"
+        );
+    }
+
+    #[test]
+    fn test_should_initialize_missing_attribute_with_add_operand() {
+        let n = AttributeValue::N("5".to_owned());
+        assert_eq!(add_to(None, n.clone()).unwrap(), Some(n));
+        let ss = AttributeValue::Ss(vec!["a".to_owned()]);
+        assert_eq!(add_to(None, ss.clone()).unwrap(), Some(ss));
+    }
+
+    #[test]
+    fn test_should_reject_adding_to_attribute_of_wrong_type() {
+        let n = AttributeValue::N("1".to_owned());
+        let ss = AttributeValue::Ss(vec!["a".to_owned()]);
+        for (existing, operand, types) in [
+            (
+                AttributeValue::S("x".to_owned()),
+                n.clone(),
+                "operand type: N, existing type: S",
+            ),
+            (ss.clone(), n.clone(), "operand type: N, existing type: SS"),
+            (n.clone(), ss.clone(), "operand type: SS, existing type: N"),
+            (
+                AttributeValue::L(vec![]),
+                ss,
+                "operand type: SS, existing type: L",
+            ),
+        ] {
+            let err = add_to(Some(existing), operand).unwrap_err().to_string();
+            assert_eq!(
+                err,
+                format!(
+                    "An operand in the update expression has an incorrect data type; operator: \
+                     ADD, {types}"
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_should_reject_add_operand_that_is_not_a_number_or_set() {
+        let err = add_to(None, AttributeValue::S("x".to_owned()))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("operator: ADD, operand type: S"), "{err}");
+    }
+
     // -----------------------------------------------------------------------
     // 3A: Nested path SET improvements (list index beyond bounds)
     // -----------------------------------------------------------------------
@@ -2517,6 +2441,121 @@ mod tests {
         }
     }
 
+    fn string_list(values: &[&str]) -> AttributeValue {
+        AttributeValue::L(
+            values
+                .iter()
+                .map(|v| AttributeValue::S((*v).to_owned()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_should_remove_list_indices_against_original_list() {
+        let item = make_item(&[("l", string_list(&["a", "b", "c", "d"]))]);
+        let names = empty_names();
+        let values = HashMap::new();
+        let ctx = EvalContext {
+            item: &item,
+            names: &names,
+            values: &values,
+        };
+
+        let result = ctx
+            .apply_update(&parse_update("REMOVE l[0], l[1]").unwrap())
+            .unwrap();
+        assert_eq!(result.get("l"), Some(&string_list(&["c", "d"])));
+
+        let result = ctx
+            .apply_update(&parse_update("REMOVE l[2], l[0]").unwrap())
+            .unwrap();
+        assert_eq!(result.get("l"), Some(&string_list(&["b", "d"])));
+
+        // Removing a middle element shifts the rest left, with no placeholder.
+        let result = ctx
+            .apply_update(&parse_update("REMOVE l[1]").unwrap())
+            .unwrap();
+        assert_eq!(result.get("l"), Some(&string_list(&["a", "c", "d"])));
+    }
+
+    #[test]
+    fn test_should_update_nested_list_element_paths() {
+        let element = |c: &str| {
+            AttributeValue::M(HashMap::from([
+                ("c".to_owned(), AttributeValue::S(c.to_owned())),
+                ("d".to_owned(), AttributeValue::S(format!("{c}-d"))),
+            ]))
+        };
+        let item = make_item(&[(
+            "a",
+            AttributeValue::M(HashMap::from([(
+                "b".to_owned(),
+                AttributeValue::L(vec![element("x"), element("y"), element("z")]),
+            )])),
+        )]);
+        let names = empty_names();
+        let values = make_values(&[(":v", AttributeValue::S("new".to_owned()))]);
+        let ctx = EvalContext {
+            item: &item,
+            names: &names,
+            values: &values,
+        };
+        let nested_list = |result: &HashMap<String, AttributeValue>| match &result["a"] {
+            AttributeValue::M(a) => match &a["b"] {
+                AttributeValue::L(list) => list.clone(),
+                other => panic!("expected list, got {other:?}"),
+            },
+            other => panic!("expected map, got {other:?}"),
+        };
+
+        let result = ctx
+            .apply_update(&parse_update("SET a.b[2].c = :v").unwrap())
+            .unwrap();
+        let list = nested_list(&result);
+        assert_eq!(
+            list[2],
+            AttributeValue::M(HashMap::from([
+                ("c".to_owned(), AttributeValue::S("new".to_owned())),
+                ("d".to_owned(), AttributeValue::S("z-d".to_owned())),
+            ]))
+        );
+
+        // `a.b[2].c` still names the original third element after `a.b[0]`
+        // is removed.
+        let result = ctx
+            .apply_update(&parse_update("REMOVE a.b[0], a.b[2].c").unwrap())
+            .unwrap();
+        let list = nested_list(&result);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0], element("y"));
+        assert_eq!(
+            list[1],
+            AttributeValue::M(HashMap::from([(
+                "d".to_owned(),
+                AttributeValue::S("z-d".to_owned())
+            )]))
+        );
+    }
+
+    #[test]
+    fn test_should_apply_set_and_remove_on_same_list() {
+        let item = make_item(&[("l", string_list(&["a", "b", "c"]))]);
+        let names = empty_names();
+        let values = make_values(&[
+            (":x", AttributeValue::S("x".to_owned())),
+            (":y", AttributeValue::S("y".to_owned())),
+        ]);
+        let ctx = EvalContext {
+            item: &item,
+            names: &names,
+            values: &values,
+        };
+
+        let update = parse_update("SET l[1] = :x, l[100] = :y REMOVE l[0]").unwrap();
+        let result = ctx.apply_update(&update).unwrap();
+        assert_eq!(result.get("l"), Some(&string_list(&["x", "c", "y"])));
+    }
+
     // -----------------------------------------------------------------------
     // 3D: Projection expression for nested paths
     // -----------------------------------------------------------------------
@@ -2679,6 +2718,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_should_merge_sibling_paths_and_compact_list_indices() {
+        let s = |v: &str| AttributeValue::S(v.to_owned());
+        let map = |pairs: &[(&str, &str)]| {
+            AttributeValue::M(pairs.iter().map(|(k, v)| ((*k).to_owned(), s(v))).collect())
+        };
+        let item = make_item(&[
+            ("l", AttributeValue::L(vec![s("x"), s("y"), s("z")])),
+            (
+                "people",
+                AttributeValue::L(vec![
+                    map(&[("name", "Ann"), ("city", "Oslo"), ("age", "30")]),
+                    map(&[("name", "Bob"), ("city", "Rome")]),
+                ]),
+            ),
+        ]);
+        let names = HashMap::from([("#p".to_owned(), "people".to_owned())]);
+        let values = HashMap::new();
+        let ctx = EvalContext {
+            item: &item,
+            names: &names,
+            values: &values,
+        };
+
+        let paths =
+            parse_projection("l[2], l[9], l[0], #p[0].name, people[1].city, #p[0].city").unwrap();
+        let result = ctx.apply_projection(&paths);
+        assert_eq!(result["l"], AttributeValue::L(vec![s("x"), s("z")]));
+        assert_eq!(
+            result["people"],
+            AttributeValue::L(vec![
+                map(&[("name", "Ann"), ("city", "Oslo")]),
+                map(&[("city", "Rome")]),
+            ])
+        );
+    }
+
     #[test]
     fn test_should_return_empty_for_out_of_bounds_list_index() {
         // Projecting "a[10]" on {a: [1, 2]} should return {} (value not found).
@@ -2788,4 +2864,112 @@ mod tests {
             other => panic!("expected L, got {other:?}"),
         }
     }
+
+    #[test]
+    fn test_should_evaluate_size_in_between_and_in() {
+        let item = make_item(&[
+            ("a", AttributeValue::S("abcd".to_owned())),
+            ("b", AttributeValue::L(vec![AttributeValue::Null(true); 2])),
+            ("c", AttributeValue::S("xy".to_owned())),
+        ]);
+        let names = empty_names();
+        let values = make_values(&[
+            (":lo", AttributeValue::N("3".to_owned())),
+            (":hi", AttributeValue::N("5".to_owned())),
+            (":four", AttributeValue::N("4".to_owned())),
+        ]);
+        let ctx = EvalContext {
+            item: &item,
+            names: &names,
+            values: &values,
+        };
+
+        for (input, expected) in [
+            ("size(a) BETWEEN :lo AND :hi", true),
+            ("size(a) BETWEEN size(c) AND :hi", true),
+            ("size(c) BETWEEN :lo AND size(a)", false),
+            ("size(a) IN (size(b), :four)", true),
+            (":four IN (size(a))", true),
+            ("size(c) IN (size(b))", true),
+            ("size(a) < size(b)", false),
+            ("size(b) < size(a)", true),
+            ("size(missing) BETWEEN :lo AND :hi", false),
+        ] {
+            let expr = parse_condition(input).unwrap();
+            assert_eq!(ctx.evaluate(&expr).unwrap(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_should_evaluate_functions_with_nested_and_placeholder_paths() {
+        let item = make_item(&[(
+            "info",
+            AttributeValue::M(HashMap::from([
+                (
+                    "tags".to_owned(),
+                    AttributeValue::L(vec![
+                        AttributeValue::S("red".to_owned()),
+                        AttributeValue::Ss(vec!["x".to_owned(), "y".to_owned()]),
+                    ]),
+                ),
+                ("name".to_owned(), AttributeValue::S("Widget".to_owned())),
+                (
+                    "meta".to_owned(),
+                    AttributeValue::M(HashMap::from([(
+                        "count".to_owned(),
+                        AttributeValue::N("2".to_owned()),
+                    )])),
+                ),
+            ])),
+        )]);
+        let names = HashMap::from([
+            ("#i".to_owned(), "info".to_owned()),
+            ("#t".to_owned(), "tags".to_owned()),
+        ]);
+        let values = make_values(&[
+            (":s", AttributeValue::S("S".to_owned())),
+            (":ss", AttributeValue::S("SS".to_owned())),
+            (":m", AttributeValue::S("M".to_owned())),
+            (":n", AttributeValue::S("N".to_owned())),
+            (":re", AttributeValue::S("re".to_owned())),
+            (":wid", AttributeValue::S("Wid".to_owned())),
+            (":x", AttributeValue::S("x".to_owned())),
+            (":red", AttributeValue::S("red".to_owned())),
+            (":dg", AttributeValue::S("dg".to_owned())),
+            (":two", AttributeValue::N("2".to_owned())),
+            (":three", AttributeValue::N("3".to_owned())),
+        ]);
+        let ctx = EvalContext {
+            item: &item,
+            names: &names,
+            values: &values,
+        };
+
+        for (input, expected) in [
+            ("attribute_exists(info.tags[0])", true),
+            ("attribute_exists(#i.#t[1])", true),
+            ("attribute_exists(#i.meta.count)", true),
+            ("attribute_exists(info.tags[5])", false),
+            ("attribute_not_exists(#i.missing)", true),
+            ("attribute_not_exists(info.#t[1])", false),
+            ("attribute_type(info.tags[0], :s)", true),
+            ("attribute_type(#i.#t[1], :ss)", true),
+            ("attribute_type(#i.meta, :m)", true),
+            ("attribute_type(info.meta.count, :n)", true),
+            ("attribute_type(#i.#t[0], :n)", false),
+            ("begins_with(info.#t[0], :re)", true),
+            ("begins_with(#i.name, :wid)", true),
+            ("begins_with(#i.missing, :wid)", false),
+            ("contains(info.tags[1], :x)", true),
+            ("contains(#i.#t, :red)", true),
+            ("contains(#i.name, :dg)", true),
+            ("contains(#i.#t[0], :dg)", false),
+            ("size(#i.#t) = :two", true),
+            ("size(info.tags[0]) = :three", true),
+            ("size(#i.meta) = :two", false),
+        ] {
+            let expr = parse_condition(input).unwrap();
+            assert_eq!(ctx.evaluate(&expr).unwrap(), expected, "{input}");
+        }
+    }
 }
@@ -0,0 +1,42 @@
+//! DynamoDB expression parsing and evaluation.
+//!
+//! This crate provides a complete implementation of DynamoDB's expression language,
+//! supporting condition/filter/key-condition expressions, update expressions, and
+//! projection expressions. The pipeline is:
+//!
+//! 1. **Lexing**: Tokenize the expression string into a token stream.
+//! 2. **Parsing**: Build an AST from the token stream using recursive descent.
+//! 3. **Evaluation**: Walk the AST to evaluate conditions, apply updates, or project attributes.
+//!
+//! It depends only on `rustack-dynamodb-model` and needs no OS functionality
+//! (no clock, randomness, threads, or I/O), so it builds for
+//! `wasm32-unknown-unknown`; `make check-wasm` verifies that, without the
+//! default `http` feature that gives the model's errors HTTP status codes. A
+//! browser can validate an expression client-side with [`parse_condition`]
+//! and [`EvalContext::evaluate`], the same code the DynamoDB service runs.
+//!
+//! The [`validate`] module checks an expression against its attribute names
+//! and values ahead of time and reports located diagnostics (syntax errors,
+//! unresolved or unused placeholders, reserved words, overlapping paths),
+//! which makes it usable as a linter.
+//!
+//! `rustack-dynamodb-core` re-exports this crate as its `expression` module.
+#![allow(missing_docs, clippy::doc_markdown, clippy::module_name_repetitions)]
+
+pub mod ast;
+pub mod evaluator;
+pub mod limits;
+pub mod number;
+pub mod parser;
+pub mod reserved;
+pub mod validate;
+
+pub use ast::{
+    AttributePath, Expr, Operand, PathElement, UpdateExpr, collect_names_from_expr,
+    collect_names_from_projection, collect_names_from_update, collect_paths_from_expr,
+    collect_values_from_expr, collect_values_from_update,
+};
+pub use evaluator::EvalContext;
+pub use parser::{
+    ExpressionError, Span, check_projection_paths, parse_condition, parse_projection, parse_update,
+};
@@ -0,0 +1,333 @@
+//! DynamoDB expression limits.
+//!
+//! DynamoDB rejects oversized or overly complex expressions with a
+//! `ValidationException` before evaluating them. The checks here run on a
+//! parsed AST, together with the raw input, and report the same messages as
+//! DynamoDB. The caller passes the parameter name (`ConditionExpression`,
+//! `FilterExpression`, ...) since the same grammar serves several parameters.
+
+use std::collections::HashMap;
+
+use super::{
+    ast::{AttributePath, Expr, Operand, SetValue, UpdateExpr},
+    parser::ExpressionError,
+};
+
+/// Maximum length of an expression string, in bytes.
+pub const MAX_EXPRESSION_SIZE: usize = 4096;
+
+/// Maximum number of operators and functions in a single expression.
+pub const MAX_OPERATORS: usize = 300;
+
+/// Maximum number of operands on the right-hand side of an `IN` comparison.
+pub const MAX_IN_OPERANDS: usize = 100;
+
+/// Maximum number of nesting levels in a document path.
+pub const MAX_PATH_DEPTH: usize = 32;
+
+/// Maximum length of an `ExpressionAttributeNames` or
+/// `ExpressionAttributeValues` key, including its `#` or `:` prefix.
+pub const MAX_PLACEHOLDER_LENGTH: usize = 255;
+
+/// Check a condition, filter, or key-condition expression against the limits.
+///
+/// `kind` is the request parameter the expression came from.
+///
+/// # Errors
+///
+/// Returns `ExpressionError::Validation` for the first limit exceeded.
+pub fn check_condition(kind: &str, input: &str, expr: &Expr) -> Result<(), ExpressionError> {
+    let mut stats = ExpressionStats::default();
+    stats.visit_expr(expr);
+    stats.check(kind, input)
+}
+
+/// Check an update expression against the limits.
+///
+/// # Errors
+///
+/// Returns `ExpressionError::Validation` for the first limit exceeded.
+pub fn check_update(input: &str, update: &UpdateExpr) -> Result<(), ExpressionError> {
+    let mut stats = ExpressionStats::default();
+    for action in &update.set_actions {
+        stats.visit_path(&action.path);
+        stats.visit_set_value(&action.value);
+    }
+    for path in &update.remove_paths {
+        stats.visit_path(path);
+    }
+    for action in &update.add_actions {
+        stats.visit_path(&action.path);
+        stats.visit_operand(&action.value);
+    }
+    for action in &update.delete_actions {
+        stats.visit_path(&action.path);
+        stats.visit_operand(&action.value);
+    }
+    stats.check("UpdateExpression", input)
+}
+
+/// Check a projection expression against the limits.
+///
+/// # Errors
+///
+/// Returns `ExpressionError::Validation` for the first limit exceeded.
+pub fn check_projection(input: &str, paths: &[AttributePath]) -> Result<(), ExpressionError> {
+    let mut stats = ExpressionStats::default();
+    for path in paths {
+        stats.visit_path(path);
+    }
+    stats.check("ProjectionExpression", input)
+}
+
+/// Check that every `ExpressionAttributeNames` key is a well-formed `#name`
+/// placeholder.
+///
+/// # Errors
+///
+/// Returns `ExpressionError::Validation` naming the first invalid key.
+#[allow(clippy::implicit_hasher)]
+pub fn check_attribute_names(names: &HashMap<String, String>) -> Result<(), ExpressionError> {
+    check_placeholder_keys("ExpressionAttributeNames", '#', names.keys())
+}
+
+/// Check that every `ExpressionAttributeValues` key is a well-formed
+/// `:value` placeholder.
+///
+/// # Errors
+///
+/// Returns `ExpressionError::Validation` naming the first invalid key.
+#[allow(clippy::implicit_hasher)]
+pub fn check_attribute_values<V>(values: &HashMap<String, V>) -> Result<(), ExpressionError> {
+    check_placeholder_keys("ExpressionAttributeValues", ':', values.keys())
+}
+
+fn check_placeholder_keys<'a>(
+    parameter: &str,
+    prefix: char,
+    keys: impl Iterator<Item = &'a String>,
+) -> Result<(), ExpressionError> {
+    let mut keys: Vec<&String> = keys.collect();
+    keys.sort_unstable();
+    for key in keys {
+        let well_formed = key.strip_prefix(prefix).is_some_and(|name| {
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+        if !well_formed {
+            return Err(ExpressionError::Validation {
+                message: format!("{parameter} contains invalid key: Syntax error; key: \"{key}\""),
+            });
+        }
+        if key.len() > MAX_PLACEHOLDER_LENGTH {
+            return Err(ExpressionError::Validation {
+                message: format!(
+                    "{parameter} contains invalid key: The key length exceeds the maximum allowed \
+                     length of {MAX_PLACEHOLDER_LENGTH}; key: \"{key}\""
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Size measurements of a parsed expression.
+#[derive(Debug, Default)]
+struct ExpressionStats {
+    /// Operators and function calls.
+    operators: usize,
+    /// Largest `IN` operand list.
+    in_operands: usize,
+    /// Deepest document path.
+    path_depth: usize,
+}
+
+impl ExpressionStats {
+    fn check(&self, kind: &str, input: &str) -> Result<(), ExpressionError> {
+        let message = if input.len() > MAX_EXPRESSION_SIZE {
+            format!(
+                "Invalid {kind}: Expression size has exceeded the maximum allowed size; \
+                 expression size: {}",
+                input.len()
+            )
+        } else if self.in_operands > MAX_IN_OPERANDS {
+            format!(
+                "Invalid {kind}: The IN operator is provided with too many operands; number of \
+                 operands: {}",
+                self.in_operands
+            )
+        } else if self.operators > MAX_OPERATORS {
+            format!(
+                "Invalid {kind}: The expression contains too many operators; operator count: {}",
+                self.operators
+            )
+        } else if self.path_depth > MAX_PATH_DEPTH {
+            format!(
+                "Invalid {kind}: The document path has too many nesting levels; nesting levels: {}",
+                self.path_depth
+            )
+        } else {
+            return Ok(());
+        };
+        Err(ExpressionError::Validation { message })
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        self.operators += 1;
+        match expr {
+            Expr::Compare { left, right, .. } => {
+                self.visit_operand(left);
+                self.visit_operand(right);
+            }
+            Expr::Between { value, low, high } => {
+                self.visit_operand(value);
+                self.visit_operand(low);
+                self.visit_operand(high);
+            }
+            Expr::In { value, list } => {
+                self.in_operands = self.in_operands.max(list.len());
+                self.visit_operand(value);
+                for item in list {
+                    self.visit_operand(item);
+                }
+            }
+            Expr::Logical { left, right, .. } => {
+                self.visit_expr(left);
+                self.visit_expr(right);
+            }
+            Expr::Not(inner) => self.visit_expr(inner),
+            Expr::Function { args, .. } => {
+                for arg in args {
+                    self.visit_operand(arg);
+                }
+            }
+        }
+    }
+
+    fn visit_operand(&mut self, operand: &Operand) {
+        match operand {
+            Operand::Path(path) => self.visit_path(path),
+            Operand::Value(_) => {}
+            Operand::Size(inner) => {
+                self.operators += 1;
+                self.visit_operand(inner);
+            }
+        }
+    }
+
+    fn visit_set_value(&mut self, value: &SetValue) {
+        match value {
+            SetValue::Operand(operand) => self.visit_operand(operand),
+            SetValue::Plus(left, right) | SetValue::Minus(left, right) => {
+                self.operators += 1;
+                self.visit_set_value(left);
+                self.visit_set_value(right);
+            }
+            SetValue::IfNotExists(path, operand) => {
+                self.operators += 1;
+                self.visit_path(path);
+                self.visit_operand(operand);
+            }
+            SetValue::ListAppend(left, right) => {
+                self.operators += 1;
+                self.visit_operand(left);
+                self.visit_operand(right);
+            }
+        }
+    }
+
+    fn visit_path(&mut self, path: &AttributePath) {
+        self.path_depth = self.path_depth.max(path.elements.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_condition, parse_projection, parse_update};
+
+    fn condition_error(input: &str) -> String {
+        let expr = parse_condition(input).expect("valid syntax");
+        check_condition("ConditionExpression", input, &expr)
+            .expect_err("limit exceeded")
+            .to_string()
+    }
+
+    #[test]
+    fn test_should_reject_in_list_over_limit() {
+        let values = |n: usize| (0..n).map(|i| format!(":v{i}")).collect::<Vec<_>>();
+        let at_limit = format!("a IN ({})", values(MAX_IN_OPERANDS).join(", "));
+        let expr = parse_condition(&at_limit).expect("valid syntax");
+        assert!(check_condition("ConditionExpression", &at_limit, &expr).is_ok());
+
+        let over = format!("a IN ({})", values(MAX_IN_OPERANDS + 1).join(", "));
+        assert_eq!(
+            condition_error(&over),
+            "Invalid ConditionExpression: The IN operator is provided with too many operands; \
+             number of operands: 101"
+        );
+    }
+
+    #[test]
+    fn test_should_reject_expression_over_size_limit() {
+        let input = format!("a = :v AND {}", "b".repeat(MAX_EXPRESSION_SIZE));
+        let input = format!("{input} = :w");
+        assert_eq!(
+            condition_error(&input),
+            format!(
+                "Invalid ConditionExpression: Expression size has exceeded the maximum allowed \
+                 size; expression size: {}",
+                input.len()
+            )
+        );
+    }
+
+    #[test]
+    fn test_should_reject_too_many_operators() {
+        let input = vec!["a = :v"; MAX_OPERATORS / 2 + 1].join(" AND ");
+        assert_eq!(
+            condition_error(&input),
+            "Invalid ConditionExpression: The expression contains too many operators; operator \
+             count: 301"
+        );
+    }
+
+    #[test]
+    fn test_should_reject_deep_document_paths() {
+        let deep = vec!["a"; MAX_PATH_DEPTH + 1].join(".");
+        let update = format!("SET {deep} = :v");
+        let parsed = parse_update(&update).expect("valid syntax");
+        assert_eq!(
+            check_update(&update, &parsed)
+                .expect_err("too deep")
+                .to_string(),
+            "Invalid UpdateExpression: The document path has too many nesting levels; nesting \
+             levels: 33"
+        );
+
+        let shallow = vec!["a"; MAX_PATH_DEPTH].join(".");
+        let paths = parse_projection(&shallow).expect("valid syntax");
+        assert!(check_projection(&shallow, &paths).is_ok());
+    }
+
+    #[test]
+    fn test_should_reject_malformed_placeholder_keys() {
+        let names = HashMap::from([("name".to_owned(), "a".to_owned())]);
+        assert_eq!(
+            check_attribute_names(&names)
+                .expect_err("missing #")
+                .to_string(),
+            "ExpressionAttributeNames contains invalid key: Syntax error; key: \"name\""
+        );
+        let values = HashMap::from([(":".to_owned(), 0)]);
+        assert_eq!(
+            check_attribute_values(&values)
+                .expect_err("empty name")
+                .to_string(),
+            "ExpressionAttributeValues contains invalid key: Syntax error; key: \":\""
+        );
+        let long = HashMap::from([(format!(":{}", "v".repeat(MAX_PLACEHOLDER_LENGTH)), 0)]);
+        assert!(check_attribute_values(&long).is_err());
+        let valid = HashMap::from([("#n_1".to_owned(), "a".to_owned())]);
+        assert!(check_attribute_names(&valid).is_ok());
+    }
+}
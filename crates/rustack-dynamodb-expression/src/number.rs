@@ -0,0 +1,353 @@
+//! Exact decimal arithmetic for DynamoDB numbers.
+//!
+//! DynamoDB numbers are decimals with up to 38 significant digits and a
+//! magnitude between 10^-130 and 10^125. [`Number`] keeps every digit, so
+//! comparisons and `SET a = a + :b` / `ADD` never round through `f64`.
+
+use std::cmp::Ordering;
+
+/// Maximum number of significant digits in a DynamoDB number.
+pub const MAX_SIGNIFICANT_DIGITS: usize = 38;
+
+/// Largest allowed magnitude (power of ten of the leading digit).
+const MAX_MAGNITUDE: i64 = 125;
+
+/// Smallest allowed magnitude (power of ten of the leading digit).
+const MIN_MAGNITUDE: i64 = -130;
+
+/// Error producing a DynamoDB number from arithmetic.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum NumberError {
+    /// The result needs more than 38 significant digits.
+    #[error(
+        "Attempting to store more than {MAX_SIGNIFICANT_DIGITS} significant digits in a Number"
+    )]
+    Precision,
+    /// The result is larger than 10^125.
+    #[error(
+        "Number overflow. Attempting to store a number with magnitude larger than supported range"
+    )]
+    Overflow,
+    /// The result is non-zero and smaller than 10^-130.
+    #[error(
+        "Number underflow. Attempting to store a number with magnitude smaller than supported \
+         range"
+    )]
+    Underflow,
+}
+
+/// An exact decimal number.
+///
+/// The value is `sign * 0.{digits} * 10^exponent`, where `digits` has no
+/// leading or trailing zeros. Zero has no digits, exponent 0, and is never
+/// negative, so equal values have equal representations.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Number {
+    negative: bool,
+    digits: Vec<u8>,
+    exponent: i64,
+}
+
+impl Number {
+    /// Parse a DynamoDB number string (`-12.5`, `1e3`, `+.5`, ...).
+    ///
+    /// Returns `None` if `s` is not a valid number. Precision and magnitude
+    /// limits are not checked here.
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        let (negative, rest) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (mantissa, explicit_exp) = match rest.find(['e', 'E']) {
+            Some(pos) => (&rest[..pos], rest[pos + 1..].parse::<i64>().ok()?),
+            None => (rest, 0),
+        };
+        let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if int_part.is_empty() && frac_part.is_empty() {
+            return None;
+        }
+
+        let mut digits = Vec::with_capacity(int_part.len() + frac_part.len());
+        for c in int_part.bytes().chain(frac_part.bytes()) {
+            if !c.is_ascii_digit() {
+                return None;
+            }
+            digits.push(c - b'0');
+        }
+        // Safety: digit counts are bounded by the input length, far below i64::MAX.
+        #[allow(clippy::cast_possible_wrap)]
+        let exponent = explicit_exp.checked_add(int_part.len() as i64)?;
+        Some(Self::normalized(negative, digits, exponent))
+    }
+
+    /// Build a number from `0.{digits} * 10^exponent`, stripping zeros.
+    fn normalized(negative: bool, mut digits: Vec<u8>, exponent: i64) -> Self {
+        let leading = digits.iter().take_while(|&&d| d == 0).count();
+        digits.drain(..leading);
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        if digits.is_empty() {
+            return Self {
+                negative: false,
+                digits,
+                exponent: 0,
+            };
+        }
+        // Safety: `leading` is bounded by the digit count.
+        #[allow(clippy::cast_possible_wrap)]
+        let exponent = exponent - leading as i64;
+        Self {
+            negative,
+            digits,
+            exponent,
+        }
+    }
+
+    /// Whether this number is zero.
+    #[must_use]
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// `self + other`, checked against DynamoDB's precision and range limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`NumberError`] if the exact result cannot be stored.
+    pub fn checked_add(&self, other: &Self) -> Result<Self, NumberError> {
+        if self.is_zero() {
+            return Ok(other.clone());
+        }
+        if other.is_zero() {
+            return Ok(self.clone());
+        }
+
+        // Align both operands as integers scaled by 10^low.
+        let low = self.lowest_exponent().min(other.lowest_exponent());
+        let high = self.exponent.max(other.exponent);
+        let a = self.aligned_digits(low, high);
+        let b = other.aligned_digits(low, high);
+
+        let (negative, digits) = if self.negative == other.negative {
+            (self.negative, add_digits(&a, &b))
+        } else {
+            match a.cmp(&b) {
+                Ordering::Equal => return Ok(Self::normalized(false, Vec::new(), 0)),
+                Ordering::Greater => (self.negative, sub_digits(&a, &b)),
+                Ordering::Less => (other.negative, sub_digits(&b, &a)),
+            }
+        };
+        // Safety: the aligned width is bounded by the operands' exponent span.
+        #[allow(clippy::cast_possible_wrap)]
+        let exponent = low + digits.len() as i64;
+        let result = Self::normalized(negative, digits, exponent);
+        result.check_limits()?;
+        Ok(result)
+    }
+
+    /// `self - other`, checked against DynamoDB's precision and range limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`NumberError`] if the exact result cannot be stored.
+    pub fn checked_sub(&self, other: &Self) -> Result<Self, NumberError> {
+        self.checked_add(&other.negated())
+    }
+
+    fn negated(&self) -> Self {
+        Self {
+            negative: !self.negative && !self.is_zero(),
+            ..self.clone()
+        }
+    }
+
+    /// The power of ten of the last significant digit.
+    fn lowest_exponent(&self) -> i64 {
+        // Safety: the digit count is bounded by the input length.
+        #[allow(clippy::cast_possible_wrap)]
+        let len = self.digits.len() as i64;
+        self.exponent - len
+    }
+
+    /// The magnitude as big-endian digits of `value / 10^low`, padded to
+    /// `high - low` digits.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn aligned_digits(&self, low: i64, high: i64) -> Vec<u8> {
+        let width = (high - low) as usize;
+        let leading = (high - self.exponent) as usize;
+        let mut digits = vec![0; width];
+        digits[leading..leading + self.digits.len()].copy_from_slice(&self.digits);
+        digits
+    }
+
+    fn check_limits(&self) -> Result<(), NumberError> {
+        if self.is_zero() {
+            return Ok(());
+        }
+        if self.digits.len() > MAX_SIGNIFICANT_DIGITS {
+            return Err(NumberError::Precision);
+        }
+        let magnitude = self.exponent - 1;
+        if magnitude > MAX_MAGNITUDE {
+            return Err(NumberError::Overflow);
+        }
+        if magnitude < MIN_MAGNITUDE {
+            return Err(NumberError::Underflow);
+        }
+        Ok(())
+    }
+}
+
+impl Ord for Number {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let sign = |n: &Self| match (n.is_zero(), n.negative) {
+            (true, _) => 0,
+            (false, true) => -1,
+            (false, false) => 1,
+        };
+        match sign(self).cmp(&sign(other)) {
+            Ordering::Equal if !self.is_zero() => {}
+            ordering => return ordering,
+        }
+        let magnitude = self
+            .exponent
+            .cmp(&other.exponent)
+            .then_with(|| self.digits.cmp(&other.digits));
+        if self.negative {
+            magnitude.reverse()
+        } else {
+            magnitude
+        }
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Formats the number in plain notation with no trailing zeros, the way
+/// DynamoDB returns computed numbers (`100`, `0.001`, `-12.5`).
+impl std::fmt::Display for Number {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return f.write_str("0");
+        }
+        if self.negative {
+            f.write_str("-")?;
+        }
+        let digits: String = self.digits.iter().map(|d| char::from(b'0' + d)).collect();
+        let len = self.exponent - self.lowest_exponent();
+        if self.exponent <= 0 {
+            let zeros = "0".repeat((-self.exponent) as usize);
+            write!(f, "0.{zeros}{digits}")
+        } else if self.exponent >= len {
+            let zeros = "0".repeat((self.exponent - len) as usize);
+            write!(f, "{digits}{zeros}")
+        } else {
+            let (int_part, frac_part) = digits.split_at(self.exponent as usize);
+            write!(f, "{int_part}.{frac_part}")
+        }
+    }
+}
+
+/// Add two equal-width big-endian digit vectors.
+fn add_digits(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0;
+    for (x, y) in a.iter().rev().zip(b.iter().rev()) {
+        let sum = x + y + carry;
+        result.push(sum % 10);
+        carry = sum / 10;
+    }
+    result.push(carry);
+    result.reverse();
+    result
+}
+
+/// Subtract equal-width big-endian digit vectors, where `a >= b`.
+fn sub_digits(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+    for (&x, &y) in a.iter().rev().zip(b.iter().rev()) {
+        let (digit, next_borrow) = if x >= y + borrow {
+            (x - y - borrow, 0)
+        } else {
+            (x + 10 - y - borrow, 1)
+        };
+        result.push(digit);
+        borrow = next_borrow;
+    }
+    result.reverse();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn num(s: &str) -> Number {
+        Number::parse(s).expect("valid number")
+    }
+
+    fn add(a: &str, b: &str) -> Result<String, NumberError> {
+        num(a).checked_add(&num(b)).map(|n| n.to_string())
+    }
+
+    #[test]
+    fn test_should_add_large_decimals_exactly() {
+        assert_eq!(
+            add("99999999999999999999999999.000000001", "1").unwrap(),
+            "100000000000000000000000000.000000001"
+        );
+        assert_eq!(
+            add("12345678901234567890123456789012345678", "1").unwrap(),
+            "12345678901234567890123456789012345679"
+        );
+        assert_eq!(add("0.1", "0.2").unwrap(), "0.3");
+        assert_eq!(add("1.50", "-1.5").unwrap(), "0");
+        assert_eq!(add("-5", "3").unwrap(), "-2");
+        assert_eq!(add("1e3", "0").unwrap(), "1000");
+        assert_eq!(
+            num("0.0001").checked_sub(&num("1e-5")).unwrap().to_string(),
+            "0.00009"
+        );
+    }
+
+    #[test]
+    fn test_should_reject_results_beyond_dynamodb_limits() {
+        assert_eq!(
+            add("99999999999999999999999999999999999999", "1"),
+            Ok(format!("1{}", "0".repeat(38)))
+        );
+        assert_eq!(
+            add("99999999999999999999999999999999999999", "0.1"),
+            Err(NumberError::Precision)
+        );
+        assert_eq!(add("9.9e125", "1e125"), Err(NumberError::Overflow));
+        assert_eq!(add("1e-130", "-1.1e-130"), Err(NumberError::Underflow));
+    }
+
+    #[test]
+    fn test_should_order_numbers_differing_in_late_digits() {
+        assert!(num("12345678901234567890") < num("12345678901234567891"));
+        assert!(num("-12345678901234567891") < num("-12345678901234567890"));
+        assert!(num("0.00000000000000000001") > num("0"));
+        assert_eq!(num("1.0").cmp(&num("1e0")), Ordering::Equal);
+        assert_eq!(num("-0"), num("0"));
+        assert!(num("-1") < num("0.5"));
+    }
+
+    #[test]
+    fn test_should_reject_invalid_number_strings() {
+        for s in ["", "-", "abc", "1.2.3", "1e", "e5", ".", "1 "] {
+            assert!(Number::parse(s).is_none(), "{s:?} should not parse");
+        }
+        assert_eq!(num("+.5").to_string(), "0.5");
+        assert_eq!(num("5.").to_string(), "5");
+    }
+}
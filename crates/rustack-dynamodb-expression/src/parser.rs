@@ -4,11 +4,15 @@
 //! projection expressions. Keywords and function names are matched
 //! case-insensitively per DynamoDB specification.
 
-use std::{fmt, iter::Peekable, str::Chars};
+use std::{cell::Cell, collections::HashMap, fmt, iter::Peekable, str::CharIndices};
 
-use super::ast::{
-    AddAction, AttributePath, CompareOp, DeleteAction, Expr, FunctionName, LogicalOp, Operand,
-    PathElement, SetAction, SetValue, UpdateExpr,
+use super::{
+    ast::{
+        AddAction, AttributePath, CompareOp, DeleteAction, Expr, FunctionName, LogicalOp, Operand,
+        PathClash, PathElement, SetAction, SetValue, UpdateExpr, find_path_clash,
+        resolve_path_elements,
+    },
+    limits::MAX_PATH_DEPTH,
 };
 
 // ---------------------------------------------------------------------------
@@ -63,13 +67,73 @@ pub enum ExpressionError {
     },
 }
 
+/// A byte range in the source expression string.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    /// Byte offset of the first character.
+    pub start: usize,
+    /// Byte offset one past the last character.
+    pub end: usize,
+}
+
+impl Span {
+    /// Create a span covering `start..end`.
+    #[must_use]
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// A parse error together with the part of the source it refers to.
+#[derive(Debug)]
+pub(crate) struct LocatedError {
+    pub(crate) error: ExpressionError,
+    pub(crate) span: Span,
+    /// Whether the error reports overlapping or conflicting document paths
+    /// rather than malformed syntax.
+    pub(crate) overlap: bool,
+}
+
+impl LocatedError {
+    fn syntax(error: ExpressionError, span: Span) -> Self {
+        Self {
+            error,
+            span,
+            overlap: false,
+        }
+    }
+
+    fn overlap(error: ExpressionError, span: Span) -> Self {
+        Self {
+            error,
+            span,
+            overlap: true,
+        }
+    }
+}
+
+/// A successfully parsed expression along with its token stream and the
+/// location of every attribute path in it.
+#[derive(Debug)]
+pub(crate) struct Parsed<T> {
+    pub(crate) value: T,
+    pub(crate) tokens: Vec<(Token, Span)>,
+    pub(crate) paths: Vec<(AttributePath, Span)>,
+}
+
 // ---------------------------------------------------------------------------
 // Token type
 // ---------------------------------------------------------------------------
 
 /// Lexer token for DynamoDB expressions.
 #[derive(Debug, Clone, PartialEq)]
-enum Token {
+pub(crate) enum Token {
     /// A plain identifier (attribute name).
     Identifier(String),
     /// An expression attribute name reference (`#name`).
@@ -195,32 +259,48 @@ impl fmt::Display for Token {
 
 /// Tokenizer for DynamoDB expression strings.
 struct Lexer<'a> {
-    chars: Peekable<Chars<'a>>,
+    chars: Peekable<CharIndices<'a>>,
+    len: usize,
 }
 
 impl<'a> Lexer<'a> {
     fn new(input: &'a str) -> Self {
         Self {
-            chars: input.chars().peekable(),
+            chars: input.char_indices().peekable(),
+            len: input.len(),
         }
     }
 
-    /// Tokenize the entire input into a vector of tokens.
-    fn tokenize(&mut self) -> Result<Vec<Token>, ExpressionError> {
+    /// Tokenize the entire input into tokens paired with their source spans.
+    fn tokenize(&mut self) -> Result<Vec<(Token, Span)>, LocatedError> {
         let mut tokens = Vec::new();
         loop {
-            let tok = self.next_token()?;
+            self.skip_whitespace();
+            let start = self.offset();
+            let tok = self
+                .next_token()
+                .map_err(|e| LocatedError::syntax(e, Span::new(start, self.offset())))?;
+            let span = Span::new(start, self.offset());
             if tok == Token::Eof {
-                tokens.push(Token::Eof);
+                tokens.push((Token::Eof, span));
                 break;
             }
-            tokens.push(tok);
+            tokens.push((tok, span));
         }
         Ok(tokens)
     }
 
+    /// Byte offset of the next unread character.
+    fn offset(&mut self) -> usize {
+        self.chars.peek().map_or(self.len, |&(i, _)| i)
+    }
+
+    fn peek_char(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
     fn skip_whitespace(&mut self) {
-        while self.chars.peek().is_some_and(char::is_ascii_whitespace) {
+        while self.peek_char().is_some_and(|c| c.is_ascii_whitespace()) {
             self.chars.next();
         }
     }
@@ -228,7 +308,7 @@ impl<'a> Lexer<'a> {
     fn next_token(&mut self) -> Result<Token, ExpressionError> {
         self.skip_whitespace();
 
-        let Some(&ch) = self.chars.peek() else {
+        let Some(ch) = self.peek_char() else {
             return Ok(Token::Eof);
         };
 
@@ -275,10 +355,13 @@ impl<'a> Lexer<'a> {
             }
             c if c.is_ascii_digit() => self.read_number(),
             c if is_ident_start(c) => Ok(self.read_identifier_or_keyword()),
-            _ => Err(ExpressionError::UnexpectedToken {
-                expected: "valid token".to_owned(),
-                found: format!("'{ch}'; syntax error near '{ch}'"),
-            }),
+            _ => {
+                self.chars.next();
+                Err(ExpressionError::UnexpectedToken {
+                    expected: "valid token".to_owned(),
+                    found: format!("'{ch}'; syntax error near '{ch}'"),
+                })
+            }
         }
     }
 
@@ -312,10 +395,10 @@ impl<'a> Lexer<'a> {
 
     fn read_lt_family(&mut self) -> Token {
         self.chars.next(); // consume '<'
-        if self.chars.peek() == Some(&'=') {
+        if self.peek_char() == Some('=') {
             self.chars.next();
             Token::Le
-        } else if self.chars.peek() == Some(&'>') {
+        } else if self.peek_char() == Some('>') {
             self.chars.next();
             Token::Ne
         } else {
@@ -325,7 +408,7 @@ impl<'a> Lexer<'a> {
 
     fn read_gt_family(&mut self) -> Token {
         self.chars.next(); // consume '>'
-        if self.chars.peek() == Some(&'=') {
+        if self.peek_char() == Some('=') {
             self.chars.next();
             Token::Ge
         } else {
@@ -335,7 +418,7 @@ impl<'a> Lexer<'a> {
 
     fn read_number(&mut self) -> Result<Token, ExpressionError> {
         let mut s = String::new();
-        while let Some(&c) = self.chars.peek() {
+        while let Some(c) = self.peek_char() {
             if c.is_ascii_digit() {
                 s.push(c);
                 self.chars.next();
@@ -360,7 +443,7 @@ impl<'a> Lexer<'a> {
 
     fn read_ident_chars(&mut self) -> String {
         let mut s = String::new();
-        while let Some(&c) = self.chars.peek() {
+        while let Some(c) = self.peek_char() {
             if is_ident_continue(c) {
                 s.push(c);
                 self.chars.next();
@@ -377,7 +460,7 @@ impl<'a> Lexer<'a> {
     /// character in any position (including leading digits and underscores).
     fn read_ref_chars(&mut self) -> String {
         let mut s = String::new();
-        while let Some(&c) = self.chars.peek() {
+        while let Some(c) = self.peek_char() {
             if c.is_ascii_alphanumeric() || c == '_' {
                 s.push(c);
                 self.chars.next();
@@ -441,25 +524,65 @@ fn is_ident_continue(c: char) -> bool {
 
 /// Recursive-descent parser for DynamoDB expressions.
 struct Parser {
-    tokens: Vec<Token>,
+    tokens: Vec<(Token, Span)>,
     pos: usize,
+    /// Index of the token inspected most recently, which is where a parse
+    /// error is reported.
+    cursor: Cell<usize>,
+    /// Every attribute path parsed so far, in source order.
+    paths: Vec<(AttributePath, Span)>,
 }
 
 impl Parser {
-    fn new(tokens: Vec<Token>) -> Self {
-        Self { tokens, pos: 0 }
+    fn new(tokens: Vec<(Token, Span)>) -> Self {
+        Self {
+            tokens,
+            pos: 0,
+            cursor: Cell::new(0),
+            paths: Vec::new(),
+        }
     }
 
     fn peek(&self) -> &Token {
-        self.tokens.get(self.pos).unwrap_or(&Token::Eof)
+        self.cursor.set(self.pos);
+        self.tokens
+            .get(self.pos)
+            .map_or(&Token::Eof, |(tok, _)| tok)
     }
 
     fn advance(&mut self) -> Token {
-        let tok = self.tokens.get(self.pos).cloned().unwrap_or(Token::Eof);
+        self.cursor.set(self.pos);
+        let tok = self
+            .tokens
+            .get(self.pos)
+            .map_or(Token::Eof, |(tok, _)| tok.clone());
         self.pos += 1;
         tok
     }
 
+    /// Span of the token at `index`, clamped to the end-of-input token.
+    fn span_at(&self, index: usize) -> Span {
+        self.tokens
+            .get(index)
+            .or_else(|| self.tokens.last())
+            .map(|(_, span)| *span)
+            .unwrap_or_default()
+    }
+
+    /// Attach the location of the most recently inspected token to `error`.
+    fn locate(&self, error: ExpressionError) -> LocatedError {
+        LocatedError::syntax(error, self.span_at(self.cursor.get()))
+    }
+
+    /// Package the parsed value with the token stream and recorded paths.
+    fn finish<T>(self, value: T) -> Parsed<T> {
+        Parsed {
+            value,
+            tokens: self.tokens,
+            paths: self.paths,
+        }
+    }
+
     fn expect(&mut self, expected: &Token) -> Result<Token, ExpressionError> {
         let tok = self.advance();
         if std::mem::discriminant(&tok) == std::mem::discriminant(expected) {
@@ -479,7 +602,7 @@ impl Parser {
     /// Check if the current Identifier token is followed by `(`, indicating
     /// a function call.
     fn is_function_call_ahead(&self) -> bool {
-        matches!(self.tokens.get(self.pos + 1), Some(Token::LParen))
+        matches!(self.tokens.get(self.pos + 1), Some((Token::LParen, _)))
     }
 }
 
@@ -610,6 +733,7 @@ impl Parser {
             Token::Eq | Token::Ne | Token::Lt | Token::Le | Token::Gt | Token::Ge => {
                 let op = self.parse_compare_op()?;
                 let right = self.parse_operand()?;
+                check_distinct_operands(&op.to_string(), &left, [&right])?;
                 Ok(Expr::Compare {
                     left: Box::new(left),
                     op,
@@ -621,6 +745,7 @@ impl Parser {
                 let low = self.parse_operand()?;
                 self.expect(&Token::And)?;
                 let high = self.parse_operand()?;
+                check_distinct_operands("BETWEEN", &left, [&low, &high])?;
                 Ok(Expr::Between {
                     value: Box::new(left),
                     low: Box::new(low),
@@ -643,6 +768,7 @@ impl Parser {
                     list.push(self.parse_operand()?);
                 }
                 self.expect(&Token::RParen)?;
+                check_distinct_operands("IN", &left, &list)?;
                 Ok(Expr::In {
                     value: Box::new(left),
                     list,
@@ -692,6 +818,25 @@ impl Parser {
     }
 }
 
+/// Reject an operator whose first operand also appears among its other
+/// operands (`a = a`, `a BETWEEN a AND :hi`), as DynamoDB does.
+fn check_distinct_operands<'a>(
+    operator: &str,
+    first: &Operand,
+    rest: impl IntoIterator<Item = &'a Operand>,
+) -> Result<(), ExpressionError> {
+    if rest.into_iter().any(|operand| operand == first) {
+        return Err(ExpressionError::InvalidOperand {
+            operation: operator.to_owned(),
+            message: format!(
+                "The first operand must be distinct from the remaining operands for this operator \
+                 or function; operator: {operator}, first operand: [{first}]"
+            ),
+        });
+    }
+    Ok(())
+}
+
 // ---------------------------------------------------------------------------
 // Operand & path parsing
 // ---------------------------------------------------------------------------
@@ -740,6 +885,7 @@ impl Parser {
 
     /// Parse an attribute path like `info.rating`, `#name`, `myList[0].value`.
     fn parse_attribute_path(&mut self) -> Result<AttributePath, ExpressionError> {
+        let start = self.span_at(self.pos).start;
         let first = self.parse_path_head()?;
         let mut elements = vec![first];
 
@@ -773,7 +919,10 @@ impl Parser {
             }
         }
 
-        Ok(AttributePath { elements })
+        let path = AttributePath { elements };
+        let end = self.span_at(self.pos.saturating_sub(1)).end;
+        self.paths.push((path.clone(), Span::new(start, end)));
+        Ok(path)
     }
 
     /// Parse the first element of a path segment (identifier or `#name`).
@@ -1047,16 +1196,23 @@ impl Parser {
 ///
 /// Returns `ExpressionError` if the expression is syntactically invalid.
 pub fn parse_condition(input: &str) -> Result<Expr, ExpressionError> {
+    parse_condition_located(input)
+        .map(|parsed| parsed.value)
+        .map_err(|e| e.error)
+}
+
+/// Parse a condition expression, keeping source locations for diagnostics.
+pub(crate) fn parse_condition_located(input: &str) -> Result<Parsed<Expr>, LocatedError> {
     let tokens = Lexer::new(input).tokenize()?;
     let mut parser = Parser::new(tokens);
-    let expr = parser.parse_or_expr()?;
+    let expr = parser.parse_or_expr().map_err(|e| parser.locate(e))?;
     if !parser.at_end() {
-        return Err(ExpressionError::UnexpectedToken {
+        return Err(parser.locate(ExpressionError::UnexpectedToken {
             expected: "end of expression".to_owned(),
             found: parser.peek().to_string(),
-        });
+        }));
     }
-    Ok(expr)
+    Ok(parser.finish(expr))
 }
 
 /// Parse an update expression (SET / REMOVE / ADD / DELETE).
@@ -1065,22 +1221,32 @@ pub fn parse_condition(input: &str) -> Result<Expr, ExpressionError> {
 ///
 /// Returns `ExpressionError` if the expression is syntactically invalid.
 pub fn parse_update(input: &str) -> Result<UpdateExpr, ExpressionError> {
+    parse_update_located(input)
+        .map(|parsed| parsed.value)
+        .map_err(|e| e.error)
+}
+
+/// Parse an update expression, keeping source locations for diagnostics.
+pub(crate) fn parse_update_located(input: &str) -> Result<Parsed<UpdateExpr>, LocatedError> {
     let tokens = Lexer::new(input).tokenize()?;
     let mut parser = Parser::new(tokens);
-    let update = parser.parse_update_expr()?;
+    let update = parser.parse_update_expr().map_err(|e| parser.locate(e))?;
 
     if update.set_actions.is_empty()
         && update.remove_paths.is_empty()
         && update.add_actions.is_empty()
         && update.delete_actions.is_empty()
     {
-        return Err(ExpressionError::UnexpectedToken {
-            expected: "SET, REMOVE, ADD, or DELETE".to_owned(),
-            found: "empty update expression".to_owned(),
-        });
+        return Err(LocatedError::syntax(
+            ExpressionError::UnexpectedToken {
+                expected: "SET, REMOVE, ADD, or DELETE".to_owned(),
+                found: "empty update expression".to_owned(),
+            },
+            Span::new(0, input.len()),
+        ));
     }
 
-    Ok(update)
+    Ok(parser.finish(update))
 }
 
 /// Parse a projection expression (comma-separated attribute paths).
@@ -1091,43 +1257,113 @@ pub fn parse_update(input: &str) -> Result<UpdateExpr, ExpressionError> {
 /// contains overlapping or conflicting paths, exceeds nesting depth, or
 /// has duplicate top-level attributes.
 pub fn parse_projection(input: &str) -> Result<Vec<AttributePath>, ExpressionError> {
+    parse_projection_located(input)
+        .map(|parsed| parsed.value)
+        .map_err(|e| e.error)
+}
+
+/// Parse a projection expression, keeping source locations for diagnostics.
+pub(crate) fn parse_projection_located(
+    input: &str,
+) -> Result<Parsed<Vec<AttributePath>>, LocatedError> {
+    check_projection_separators(input)?;
+
+    let tokens = Lexer::new(input).tokenize()?;
+    let mut parser = Parser::new(tokens);
+    let paths = parser
+        .parse_projection_expr()
+        .map_err(|e| parser.locate(e))?;
+    if !parser.at_end() {
+        return Err(parser.locate(ExpressionError::UnexpectedToken {
+            expected: "end of expression".to_owned(),
+            found: parser.peek().to_string(),
+        }));
+    }
+    let parsed = parser.finish(paths);
+    let span_of = |index: usize| {
+        parsed
+            .paths
+            .get(index)
+            .map(|(_, span)| *span)
+            .unwrap_or_default()
+    };
+
+    // Validate nesting depth.
+    for (index, path) in parsed.value.iter().enumerate() {
+        if path.elements.len() > MAX_PATH_DEPTH {
+            return Err(LocatedError::syntax(
+                ExpressionError::Validation {
+                    message: format!(
+                        "Invalid ProjectionExpression: The document path has too many nesting \
+                         levels; nesting levels: {}",
+                        path.elements.len()
+                    ),
+                },
+                span_of(index),
+            ));
+        }
+    }
+
+    // Validate overlapping and conflicting paths as written; placeholders are
+    // compared by name until `check_projection_paths` resolves them.
+    if let Some((index, error)) = projection_clash(&parsed.value, &HashMap::new()) {
+        return Err(LocatedError::overlap(error, span_of(index)));
+    }
+
+    Ok(parsed)
+}
+
+/// Reject empty projections and misplaced commas before tokenizing.
+fn check_projection_separators(input: &str) -> Result<(), LocatedError> {
+    let syntax_error = |message: &str, at: usize| {
+        LocatedError::syntax(
+            ExpressionError::Validation {
+                message: format!("Invalid ProjectionExpression: {message}"),
+            },
+            Span::new(at, at + 1),
+        )
+    };
+
     // Reject empty projection expression.
     if input.trim().is_empty() {
-        return Err(ExpressionError::Validation {
-            message: "Invalid ProjectionExpression: The expression can not be empty;".to_owned(),
-        });
+        return Err(LocatedError::syntax(
+            ExpressionError::Validation {
+                message: "Invalid ProjectionExpression: The expression can not be empty;"
+                    .to_owned(),
+            },
+            Span::new(0, input.len()),
+        ));
     }
 
     // Reject leading commas.
+    let leading = input.len() - input.trim_start().len();
     if input.trim_start().starts_with(',') {
-        return Err(ExpressionError::Validation {
-            message: "Invalid ProjectionExpression: Syntax error; unexpected comma at start"
-                .to_owned(),
-        });
+        return Err(syntax_error(
+            "Syntax error; unexpected comma at start",
+            leading,
+        ));
     }
 
     // Reject trailing commas.
+    let trimmed_end = input.trim_end().len();
     if input.trim_end().ends_with(',') {
-        return Err(ExpressionError::Validation {
-            message: "Invalid ProjectionExpression: Syntax error; unexpected comma at end"
-                .to_owned(),
-        });
+        return Err(syntax_error(
+            "Syntax error; unexpected comma at end",
+            trimmed_end - 1,
+        ));
     }
 
     // Reject empty segments between commas (e.g., "a,,b").
     // Check for consecutive commas with optional whitespace between them.
     {
-        let trimmed = input.trim();
-        let chars: Vec<char> = trimmed.chars().collect();
         let mut saw_comma = false;
-        for &ch in &chars {
+        for (at, ch) in input.char_indices() {
             if ch == ',' {
                 if saw_comma {
-                    return Err(ExpressionError::Validation {
-                        message: "Invalid ProjectionExpression: Syntax error; empty segment \
-                                  between commas"
-                            .to_owned(),
-                    });
+                    return Err(syntax_error(
+                        "Syntax error; empty segment between commas",
+                        at,
+                    ));
                 }
                 saw_comma = true;
             } else if !ch.is_ascii_whitespace() {
@@ -1135,135 +1371,51 @@ pub fn parse_projection(input: &str) -> Result<Vec<AttributePath>, ExpressionErr
             }
         }
     }
-
-    let tokens = Lexer::new(input).tokenize()?;
-    let mut parser = Parser::new(tokens);
-    let paths = parser.parse_projection_expr()?;
-    if !parser.at_end() {
-        return Err(ExpressionError::UnexpectedToken {
-            expected: "end of expression".to_owned(),
-            found: parser.peek().to_string(),
-        });
-    }
-
-    // Validate nesting depth: DynamoDB limits to 32 levels.
-    for path in &paths {
-        if path.elements.len() > 32 {
-            return Err(ExpressionError::Validation {
-                message: format!(
-                    "Invalid ProjectionExpression: The document path has too many nesting levels; \
-                     nesting levels: {}",
-                    path.elements.len()
-                ),
-            });
-        }
-    }
-
-    // Validate duplicate top-level attributes.
-    {
-        let mut seen: Vec<String> = Vec::new();
-        for path in &paths {
-            let repr = format!("{path}");
-            if seen.contains(&repr) {
-                return Err(ExpressionError::Validation {
-                    message: format!(
-                        "Invalid ProjectionExpression: Two document paths overlap with each \
-                         other; must remove or rewrite one of these paths; path one: [{repr}], \
-                         path two: [{repr}]"
-                    ),
-                });
-            }
-            seen.push(repr);
-        }
-    }
-
-    // Validate overlapping and conflicting paths.
-    validate_projection_paths(&paths)?;
-
-    Ok(paths)
+    Ok(())
 }
 
-/// Represents a resolved element in a projection path for validation purposes.
-#[derive(Debug, Clone, PartialEq, Eq)]
-enum ResolvedPathElement {
-    /// A map attribute access (via dot notation).
-    MapKey(String),
-    /// A list index access (via bracket notation).
-    ListIndex(usize),
+/// Reject projection paths that overlap (`a, a.b`, or one path given twice)
+/// or conflict (`a.b, a[0]`) once `#name` placeholders are substituted from
+/// `names`, such as `#n, a` where `#n` names `a`.
+///
+/// [`parse_projection`] already rejects paths that clash as written.
+///
+/// # Errors
+///
+/// Returns `ExpressionError::Validation` naming the first clashing pair.
+#[allow(clippy::implicit_hasher)]
+pub fn check_projection_paths(
+    paths: &[AttributePath],
+    names: &HashMap<String, String>,
+) -> Result<(), ExpressionError> {
+    projection_clash(paths, names).map_or(Ok(()), |(_, error)| Err(error))
 }
 
-/// Convert an `AttributePath` to a vector of `ResolvedPathElement` for comparison.
-fn path_to_resolved(path: &AttributePath) -> Vec<ResolvedPathElement> {
-    path.elements
+/// The first pair of projection paths that overlap or conflict, as the index
+/// of the later path and the error reporting both.
+pub(crate) fn projection_clash(
+    paths: &[AttributePath],
+    names: &HashMap<String, String>,
+) -> Option<(usize, ExpressionError)> {
+    let resolved: Vec<_> = paths
         .iter()
-        .map(|e| match e {
-            PathElement::Attribute(name) => ResolvedPathElement::MapKey(name.clone()),
-            PathElement::Index(idx) => ResolvedPathElement::ListIndex(*idx),
-        })
-        .collect()
-}
-
-/// Validate that no two projection paths overlap or conflict.
-///
-/// - **Overlap**: One path is a prefix of another, or two paths are identical.
-/// - **Conflict**: At a shared prefix point, one path accesses via dot (map key) and the other via
-///   index (list), meaning the same node would need to be both a map and a list simultaneously.
-fn validate_projection_paths(paths: &[AttributePath]) -> Result<(), ExpressionError> {
-    let resolved: Vec<Vec<ResolvedPathElement>> = paths.iter().map(path_to_resolved).collect();
-
-    for i in 0..resolved.len() {
-        for j in (i + 1)..resolved.len() {
-            let a = &resolved[i];
-            let b = &resolved[j];
-            let min_len = a.len().min(b.len());
-
-            // Check the shared prefix for conflicts.
-            let mut prefix_matches = true;
-            for k in 0..min_len {
-                match (&a[k], &b[k]) {
-                    (ResolvedPathElement::MapKey(ka), ResolvedPathElement::MapKey(kb)) => {
-                        if ka != kb {
-                            prefix_matches = false;
-                            break;
-                        }
-                    }
-                    (ResolvedPathElement::ListIndex(ia), ResolvedPathElement::ListIndex(ib)) => {
-                        if ia != ib {
-                            prefix_matches = false;
-                            break;
-                        }
-                    }
-                    // One is a map key and the other is a list index at the same depth:
-                    // this is a conflict.
-                    _ => {
-                        return Err(ExpressionError::Validation {
-                            message: format!(
-                                "Invalid ProjectionExpression: Two document paths conflict with \
-                                 each other; must remove or rewrite one of these paths; path one: \
-                                 [{}], path two: [{}]",
-                                paths[i], paths[j]
-                            ),
-                        });
-                    }
-                }
-            }
-
-            // If the entire shorter path matches as a prefix of the longer one,
-            // they overlap.
-            if prefix_matches && (a.len() != b.len()) {
-                return Err(ExpressionError::Validation {
-                    message: format!(
-                        "Invalid ProjectionExpression: Two document paths overlap with each \
-                         other; must remove or rewrite one of these paths; path one: [{}], path \
-                         two: [{}]",
-                        paths[i], paths[j]
-                    ),
-                });
-            }
-        }
-    }
-
-    Ok(())
+        .map(|path| resolve_path_elements(path, names))
+        .collect();
+    let (i, j, clash) = find_path_clash(&resolved)?;
+    let verb = match clash {
+        PathClash::Overlap => "overlap",
+        PathClash::Conflict => "conflict",
+    };
+    Some((
+        j,
+        ExpressionError::Validation {
+            message: format!(
+                "Invalid ProjectionExpression: Two document paths {verb} with each other; must \
+                 remove or rewrite one of these paths; path one: [{}], path two: [{}]",
+                paths[i], paths[j]
+            ),
+        },
+    ))
 }
 
 // ---------------------------------------------------------------------------
@@ -1681,6 +1833,29 @@ mod tests {
         assert!(err.contains("conflict"), "expected 'conflict' in: {err}");
     }
 
+    #[test]
+    fn test_should_reject_paths_that_overlap_once_names_are_resolved() {
+        let names = HashMap::from([("#n".to_owned(), "a".to_owned())]);
+        let paths = parse_projection("#n, a.b").unwrap();
+        let err = check_projection_paths(&paths, &names)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "Invalid ProjectionExpression: Two document paths overlap with each other; must \
+             remove or rewrite one of these paths; path one: [#n], path two: [a.b]"
+        );
+
+        let paths = parse_projection("#n.b, a[0]").unwrap();
+        let err = check_projection_paths(&paths, &names)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("conflict"), "expected 'conflict' in: {err}");
+
+        let paths = parse_projection("#n.b, a.c").unwrap();
+        assert!(check_projection_paths(&paths, &names).is_ok());
+    }
+
     #[test]
     fn test_should_reject_nesting_depth_exceeds_32() {
         // Create a path with 33 elements.
@@ -1740,4 +1915,43 @@ mod tests {
         let err = result.unwrap_err().to_string();
         assert!(err.contains("overlap"), "expected 'overlap' in: {err}");
     }
+
+    #[test]
+    fn test_should_reject_operand_compared_with_itself() {
+        for input in [
+            "a = a",
+            "#n.b[0] <> #n.b[0]",
+            ":v < :v",
+            "size(a) >= size(a)",
+            "a BETWEEN :lo AND a",
+            "#n IN (:v1, #n)",
+        ] {
+            let err = parse_condition(input).expect_err(input).to_string();
+            assert!(
+                err.contains("The first operand must be distinct from the remaining operands"),
+                "{input}: {err}"
+            );
+        }
+        let err = parse_condition("a.b = a.b").unwrap_err().to_string();
+        assert!(err.ends_with("operator: =, first operand: [a.b]"), "{err}");
+
+        // Only the first operand must be distinct.
+        assert!(parse_condition("a BETWEEN :v AND :v").is_ok());
+        assert!(parse_condition("a IN (:v, :v)").is_ok());
+        assert!(parse_condition("a = a.b").is_ok());
+    }
+
+    #[test]
+    fn test_should_parse_size_in_every_operand_position() {
+        for input in [
+            "size(a) BETWEEN :lo AND :hi",
+            "a BETWEEN size(b) AND size(c)",
+            "size(a) IN (size(b), :v)",
+            ":v IN (size(a))",
+            "size(a) < size(b)",
+            "size(a.b[0]) = :v",
+        ] {
+            assert!(parse_condition(input).is_ok(), "{input}");
+        }
+    }
 }
@@ -0,0 +1,780 @@
+//! DynamoDB reserved words.
+//!
+//! DynamoDB rejects these words when they appear unescaped as attribute names
+//! in an expression; callers must use an `ExpressionAttributeNames`
+//! placeholder (`#name`) instead. Matching is case-insensitive.
+//!
+//! The `check_*` functions walk a parsed AST and mirror the API of
+//! [`limits`](super::limits): the caller passes the request parameter the
+//! expression came from and gets DynamoDB's `ValidationException` message.
+
+use super::{
+    ast::{AttributePath, Expr, Operand, PathElement, SetValue, UpdateExpr},
+    parser::ExpressionError,
+};
+
+/// All DynamoDB reserved words, upper-cased and sorted.
+pub const RESERVED_WORDS: &[&str] = &[
+    "ABORT",
+    "ABSOLUTE",
+    "ACTION",
+    "ADD",
+    "AFTER",
+    "AGENT",
+    "AGGREGATE",
+    "ALL",
+    "ALLOCATE",
+    "ALTER",
+    "ANALYZE",
+    "AND",
+    "ANY",
+    "ARCHIVE",
+    "ARE",
+    "ARRAY",
+    "AS",
+    "ASC",
+    "ASCII",
+    "ASENSITIVE",
+    "ASSERTION",
+    "ASYMMETRIC",
+    "AT",
+    "ATOMIC",
+    "ATTACH",
+    "ATTRIBUTE",
+    "AUTH",
+    "AUTHORIZATION",
+    "AUTHORIZE",
+    "AUTO",
+    "AVG",
+    "BACK",
+    "BACKUP",
+    "BASE",
+    "BATCH",
+    "BEFORE",
+    "BEGIN",
+    "BETWEEN",
+    "BIGINT",
+    "BINARY",
+    "BIT",
+    "BLOB",
+    "BLOCK",
+    "BOOLEAN",
+    "BOTH",
+    "BREADTH",
+    "BUCKET",
+    "BULK",
+    "BY",
+    "BYTE",
+    "CALL",
+    "CALLED",
+    "CALLING",
+    "CAPACITY",
+    "CASCADE",
+    "CASCADED",
+    "CASE",
+    "CAST",
+    "CATALOG",
+    "CHAR",
+    "CHARACTER",
+    "CHECK",
+    "CLASS",
+    "CLOB",
+    "CLOSE",
+    "CLUSTER",
+    "CLUSTERED",
+    "CLUSTERING",
+    "CLUSTERS",
+    "COALESCE",
+    "COLLATE",
+    "COLLATION",
+    "COLLECTION",
+    "COLUMN",
+    "COLUMNS",
+    "COMBINE",
+    "COMMENT",
+    "COMMIT",
+    "COMPACT",
+    "COMPILE",
+    "COMPRESS",
+    "CONDITION",
+    "CONFLICT",
+    "CONNECT",
+    "CONNECTION",
+    "CONSISTENCY",
+    "CONSISTENT",
+    "CONSTRAINT",
+    "CONSTRAINTS",
+    "CONSTRUCTOR",
+    "CONSUMED",
+    "CONTINUE",
+    "CONVERT",
+    "COPY",
+    "CORRESPONDING",
+    "COUNT",
+    "COUNTER",
+    "CREATE",
+    "CROSS",
+    "CUBE",
+    "CURRENT",
+    "CURSOR",
+    "CYCLE",
+    "DATA",
+    "DATABASE",
+    "DATE",
+    "DATETIME",
+    "DAY",
+    "DEALLOCATE",
+    "DEC",
+    "DECIMAL",
+    "DECLARE",
+    "DEFAULT",
+    "DEFERRABLE",
+    "DEFERRED",
+    "DEFINE",
+    "DEFINED",
+    "DEFINITION",
+    "DELETE",
+    "DELIMITED",
+    "DEPTH",
+    "DEREF",
+    "DESC",
+    "DESCRIBE",
+    "DESCRIPTOR",
+    "DETACH",
+    "DETERMINISTIC",
+    "DIAGNOSTICS",
+    "DIRECTORIES",
+    "DISABLE",
+    "DISCONNECT",
+    "DISTINCT",
+    "DISTRIBUTE",
+    "DO",
+    "DOMAIN",
+    "DOUBLE",
+    "DROP",
+    "DUMP",
+    "DURATION",
+    "DYNAMIC",
+    "EACH",
+    "ELEMENT",
+    "ELSE",
+    "ELSEIF",
+    "EMPTY",
+    "ENABLE",
+    "END",
+    "EQUAL",
+    "EQUALS",
+    "ERROR",
+    "ESCAPE",
+    "ESCAPED",
+    "EVAL",
+    "EVALUATE",
+    "EXCEEDED",
+    "EXCEPT",
+    "EXCEPTION",
+    "EXCEPTIONS",
+    "EXCLUSIVE",
+    "EXEC",
+    "EXECUTE",
+    "EXISTS",
+    "EXIT",
+    "EXPLAIN",
+    "EXPLODE",
+    "EXPORT",
+    "EXPRESSION",
+    "EXTENDED",
+    "EXTERNAL",
+    "EXTRACT",
+    "FAIL",
+    "FALSE",
+    "FAMILY",
+    "FETCH",
+    "FIELDS",
+    "FILE",
+    "FILTER",
+    "FILTERING",
+    "FINAL",
+    "FINISH",
+    "FIRST",
+    "FIXED",
+    "FLATTERN",
+    "FLOAT",
+    "FOR",
+    "FORCE",
+    "FOREIGN",
+    "FORMAT",
+    "FORWARD",
+    "FOUND",
+    "FREE",
+    "FROM",
+    "FULL",
+    "FUNCTION",
+    "FUNCTIONS",
+    "GENERAL",
+    "GENERATE",
+    "GET",
+    "GLOB",
+    "GLOBAL",
+    "GO",
+    "GOTO",
+    "GRANT",
+    "GREATER",
+    "GROUP",
+    "GROUPING",
+    "HANDLER",
+    "HASH",
+    "HAVE",
+    "HAVING",
+    "HEAP",
+    "HIDDEN",
+    "HOLD",
+    "HOUR",
+    "IDENTIFIED",
+    "IDENTITY",
+    "IF",
+    "IGNORE",
+    "IMMEDIATE",
+    "IMPORT",
+    "IN",
+    "INCLUDING",
+    "INCLUSIVE",
+    "INCREMENT",
+    "INCREMENTAL",
+    "INDEX",
+    "INDEXED",
+    "INDEXES",
+    "INDICATOR",
+    "INFINITE",
+    "INITIALLY",
+    "INLINE",
+    "INNER",
+    "INNTER",
+    "INOUT",
+    "INPUT",
+    "INSENSITIVE",
+    "INSERT",
+    "INSTEAD",
+    "INT",
+    "INTEGER",
+    "INTERSECT",
+    "INTERVAL",
+    "INTO",
+    "INVALIDATE",
+    "IS",
+    "ISOLATION",
+    "ITEM",
+    "ITEMS",
+    "ITERATE",
+    "JOIN",
+    "KEY",
+    "KEYS",
+    "LAG",
+    "LANGUAGE",
+    "LARGE",
+    "LAST",
+    "LATERAL",
+    "LEAD",
+    "LEADING",
+    "LEAVE",
+    "LEFT",
+    "LENGTH",
+    "LESS",
+    "LEVEL",
+    "LIKE",
+    "LIMIT",
+    "LIMITED",
+    "LINES",
+    "LIST",
+    "LOAD",
+    "LOCAL",
+    "LOCALTIME",
+    "LOCALTIMESTAMP",
+    "LOCATION",
+    "LOCATOR",
+    "LOCK",
+    "LOCKS",
+    "LOG",
+    "LOGED",
+    "LONG",
+    "LOOP",
+    "LOWER",
+    "MAP",
+    "MATCH",
+    "MATERIALIZED",
+    "MAX",
+    "MAXLEN",
+    "MEMBER",
+    "MERGE",
+    "METHOD",
+    "METRICS",
+    "MIN",
+    "MINUS",
+    "MINUTE",
+    "MISSING",
+    "MOD",
+    "MODE",
+    "MODIFIES",
+    "MODIFY",
+    "MODULE",
+    "MONTH",
+    "MULTI",
+    "MULTISET",
+    "NAME",
+    "NAMES",
+    "NATIONAL",
+    "NATURAL",
+    "NCHAR",
+    "NCLOB",
+    "NEW",
+    "NEXT",
+    "NO",
+    "NONE",
+    "NOT",
+    "NULL",
+    "NULLIF",
+    "NUMBER",
+    "NUMERIC",
+    "OBJECT",
+    "OF",
+    "OFFLINE",
+    "OFFSET",
+    "OLD",
+    "ON",
+    "ONLINE",
+    "ONLY",
+    "OPAQUE",
+    "OPEN",
+    "OPERATOR",
+    "OPTION",
+    "OR",
+    "ORDER",
+    "ORDINALITY",
+    "OTHER",
+    "OTHERS",
+    "OUT",
+    "OUTER",
+    "OUTPUT",
+    "OVER",
+    "OVERLAPS",
+    "OVERRIDE",
+    "OWNER",
+    "PAD",
+    "PARALLEL",
+    "PARAMETER",
+    "PARAMETERS",
+    "PARTIAL",
+    "PARTITION",
+    "PARTITIONED",
+    "PARTITIONS",
+    "PATH",
+    "PERCENT",
+    "PERCENTILE",
+    "PERMISSION",
+    "PERMISSIONS",
+    "PIPE",
+    "PIPELINED",
+    "PLAN",
+    "POOL",
+    "POSITION",
+    "PRECISION",
+    "PREPARE",
+    "PRESERVE",
+    "PRIMARY",
+    "PRIOR",
+    "PRIVATE",
+    "PRIVILEGES",
+    "PROCEDURE",
+    "PROCESSED",
+    "PROJECT",
+    "PROJECTION",
+    "PROPERTY",
+    "PROVISIONING",
+    "PUBLIC",
+    "PUT",
+    "QUERY",
+    "QUIT",
+    "QUORUM",
+    "RAISE",
+    "RANDOM",
+    "RANGE",
+    "RANK",
+    "RAW",
+    "READ",
+    "READS",
+    "REAL",
+    "REBUILD",
+    "RECORD",
+    "RECURSIVE",
+    "REDUCE",
+    "REF",
+    "REFERENCE",
+    "REFERENCES",
+    "REFERENCING",
+    "REGEXP",
+    "REGION",
+    "REINDEX",
+    "RELATIVE",
+    "RELEASE",
+    "REMAINDER",
+    "RENAME",
+    "REPEAT",
+    "REPLACE",
+    "REQUEST",
+    "RESET",
+    "RESIGNAL",
+    "RESOURCE",
+    "RESPONSE",
+    "RESTORE",
+    "RESTRICT",
+    "RESULT",
+    "RETURN",
+    "RETURNING",
+    "RETURNS",
+    "REVERSE",
+    "REVOKE",
+    "RIGHT",
+    "ROLE",
+    "ROLES",
+    "ROLLBACK",
+    "ROLLUP",
+    "ROUTINE",
+    "ROW",
+    "ROWS",
+    "RULE",
+    "RULES",
+    "SAMPLE",
+    "SATISFIES",
+    "SAVE",
+    "SAVEPOINT",
+    "SCAN",
+    "SCHEMA",
+    "SCOPE",
+    "SCROLL",
+    "SEARCH",
+    "SECOND",
+    "SECTION",
+    "SEGMENT",
+    "SEGMENTS",
+    "SELECT",
+    "SELF",
+    "SEMI",
+    "SENSITIVE",
+    "SEPARATE",
+    "SEQUENCE",
+    "SERIALIZABLE",
+    "SESSION",
+    "SET",
+    "SETS",
+    "SHARD",
+    "SHARE",
+    "SHARED",
+    "SHORT",
+    "SHOW",
+    "SIGNAL",
+    "SIMILAR",
+    "SIZE",
+    "SKEWED",
+    "SMALLINT",
+    "SNAPSHOT",
+    "SOME",
+    "SOURCE",
+    "SPACE",
+    "SPACES",
+    "SPARSE",
+    "SPECIFIC",
+    "SPECIFICTYPE",
+    "SPLIT",
+    "SQL",
+    "SQLCODE",
+    "SQLERROR",
+    "SQLEXCEPTION",
+    "SQLSTATE",
+    "SQLWARNING",
+    "START",
+    "STATE",
+    "STATIC",
+    "STATUS",
+    "STORAGE",
+    "STORE",
+    "STORED",
+    "STREAM",
+    "STRING",
+    "STRUCT",
+    "STYLE",
+    "SUB",
+    "SUBMULTISET",
+    "SUBPARTITION",
+    "SUBSTRING",
+    "SUBTYPE",
+    "SUM",
+    "SUPER",
+    "SYMMETRIC",
+    "SYNONYM",
+    "SYSTEM",
+    "TABLE",
+    "TABLESAMPLE",
+    "TEMP",
+    "TEMPORARY",
+    "TERMINATED",
+    "TEXT",
+    "THAN",
+    "THEN",
+    "THROUGHPUT",
+    "TIME",
+    "TIMESTAMP",
+    "TIMEZONE",
+    "TINYINT",
+    "TO",
+    "TOKEN",
+    "TOTAL",
+    "TOUCH",
+    "TRAILING",
+    "TRANSACTION",
+    "TRANSFORM",
+    "TRANSLATE",
+    "TRANSLATION",
+    "TREAT",
+    "TRIGGER",
+    "TRIM",
+    "TRUE",
+    "TRUNCATE",
+    "TTL",
+    "TUPLE",
+    "TYPE",
+    "UNDER",
+    "UNDO",
+    "UNION",
+    "UNIQUE",
+    "UNIT",
+    "UNKNOWN",
+    "UNLOGGED",
+    "UNNEST",
+    "UNPROCESSED",
+    "UNSIGNED",
+    "UNTIL",
+    "UPDATE",
+    "UPPER",
+    "URL",
+    "USAGE",
+    "USE",
+    "USER",
+    "USERS",
+    "USING",
+    "UUID",
+    "VACUUM",
+    "VALUE",
+    "VALUED",
+    "VALUES",
+    "VARCHAR",
+    "VARIABLE",
+    "VARIANCE",
+    "VARINT",
+    "VARYING",
+    "VIEW",
+    "VIEWS",
+    "VIRTUAL",
+    "VOID",
+    "WAIT",
+    "WHEN",
+    "WHENEVER",
+    "WHERE",
+    "WHILE",
+    "WINDOW",
+    "WITH",
+    "WITHIN",
+    "WITHOUT",
+    "WORK",
+    "WRAPPED",
+    "WRITE",
+    "YEAR",
+    "ZONE",
+];
+
+/// Returns `true` if `word` is a DynamoDB reserved word (case-insensitive).
+#[must_use]
+pub fn is_reserved_word(word: &str) -> bool {
+    RESERVED_WORDS
+        .binary_search(&word.to_ascii_uppercase().as_str())
+        .is_ok()
+}
+
+/// The message DynamoDB gives for a reserved word used as an attribute name,
+/// with a hint at the placeholder that would escape it.
+pub(crate) fn reserved_word_message(word: &str) -> String {
+    format!(
+        "Attribute name is a reserved keyword; reserved keyword: {word}; use an \
+         ExpressionAttributeNames placeholder such as #{word} instead"
+    )
+}
+
+/// Check a condition, filter, or key-condition expression for unescaped
+/// reserved words.
+///
+/// # Errors
+///
+/// Returns `ExpressionError::Validation` naming the first reserved word found.
+pub fn check_condition(kind: &str, expr: &Expr) -> Result<(), ExpressionError> {
+    let mut paths = Vec::new();
+    condition_paths(expr, &mut paths);
+    check_paths(kind, paths)
+}
+
+/// Check an update expression for unescaped reserved words.
+///
+/// # Errors
+///
+/// Returns `ExpressionError::Validation` naming the first reserved word found.
+pub fn check_update(update: &UpdateExpr) -> Result<(), ExpressionError> {
+    let mut paths = Vec::new();
+    for action in &update.set_actions {
+        paths.push(&action.path);
+        set_value_paths(&action.value, &mut paths);
+    }
+    paths.extend(&update.remove_paths);
+    for (path, value) in update
+        .add_actions
+        .iter()
+        .map(|action| (&action.path, &action.value))
+        .chain(
+            update
+                .delete_actions
+                .iter()
+                .map(|action| (&action.path, &action.value)),
+        )
+    {
+        paths.push(path);
+        operand_paths(value, &mut paths);
+    }
+    check_paths("UpdateExpression", paths)
+}
+
+/// Check a projection expression for unescaped reserved words.
+///
+/// # Errors
+///
+/// Returns `ExpressionError::Validation` naming the first reserved word found.
+pub fn check_projection(paths: &[AttributePath]) -> Result<(), ExpressionError> {
+    check_paths("ProjectionExpression", paths)
+}
+
+fn check_paths<'a>(
+    kind: &str,
+    paths: impl IntoIterator<Item = &'a AttributePath>,
+) -> Result<(), ExpressionError> {
+    let reserved = paths
+        .into_iter()
+        .flat_map(|path| &path.elements)
+        .find_map(|element| match element {
+            PathElement::Attribute(name) if !name.starts_with('#') && is_reserved_word(name) => {
+                Some(name)
+            }
+            _ => None,
+        });
+    match reserved {
+        Some(word) => Err(ExpressionError::Validation {
+            message: format!("Invalid {kind}: {}", reserved_word_message(word)),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn condition_paths<'a>(expr: &'a Expr, paths: &mut Vec<&'a AttributePath>) {
+    match expr {
+        Expr::Compare { left, right, .. } => {
+            operand_paths(left, paths);
+            operand_paths(right, paths);
+        }
+        Expr::Between { value, low, high } => {
+            operand_paths(value, paths);
+            operand_paths(low, paths);
+            operand_paths(high, paths);
+        }
+        Expr::In { value, list } => {
+            operand_paths(value, paths);
+            for item in list {
+                operand_paths(item, paths);
+            }
+        }
+        Expr::Logical { left, right, .. } => {
+            condition_paths(left, paths);
+            condition_paths(right, paths);
+        }
+        Expr::Not(inner) => condition_paths(inner, paths),
+        Expr::Function { args, .. } => {
+            for arg in args {
+                operand_paths(arg, paths);
+            }
+        }
+    }
+}
+
+fn operand_paths<'a>(operand: &'a Operand, paths: &mut Vec<&'a AttributePath>) {
+    match operand {
+        Operand::Path(path) => paths.push(path),
+        Operand::Value(_) => {}
+        Operand::Size(inner) => operand_paths(inner, paths),
+    }
+}
+
+fn set_value_paths<'a>(value: &'a SetValue, paths: &mut Vec<&'a AttributePath>) {
+    match value {
+        SetValue::Operand(op) => operand_paths(op, paths),
+        SetValue::Plus(a, b) | SetValue::Minus(a, b) => {
+            set_value_paths(a, paths);
+            set_value_paths(b, paths);
+        }
+        SetValue::IfNotExists(path, op) => {
+            paths.push(path);
+            operand_paths(op, paths);
+        }
+        SetValue::ListAppend(a, b) => {
+            operand_paths(a, paths);
+            operand_paths(b, paths);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{parse_condition, parse_projection, parse_update};
+
+    #[test]
+    fn test_should_keep_reserved_words_sorted_for_binary_search() {
+        assert!(RESERVED_WORDS.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(RESERVED_WORDS.len(), 573);
+    }
+
+    #[test]
+    fn test_should_match_reserved_words_case_insensitively() {
+        assert!(is_reserved_word("name"));
+        assert!(is_reserved_word("Status"));
+        assert!(is_reserved_word("TTL"));
+        assert!(!is_reserved_word("rating"));
+        assert!(!is_reserved_word("user_id"));
+    }
+
+    #[test]
+    fn test_should_reject_unescaped_reserved_words_in_any_path_position() {
+        let expr = parse_condition("attribute_exists(info.Status) AND rating > :r").unwrap();
+        let err = check_condition("ConditionExpression", &expr).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid ConditionExpression: Attribute name is a reserved keyword; reserved keyword: \
+             Status; use an ExpressionAttributeNames placeholder such as #Status instead"
+        );
+
+        let update = parse_update("SET a = if_not_exists(ttl, :z) REMOVE b").unwrap();
+        assert!(
+            check_update(&update)
+                .unwrap_err()
+                .to_string()
+                .contains("keyword: ttl")
+        );
+
+        let paths = parse_projection("a, b.#name, c[0].d").unwrap();
+        assert!(check_projection(&paths).is_ok());
+        let paths = parse_projection("a, b.comment").unwrap();
+        assert!(check_projection(&paths).is_err());
+    }
+}
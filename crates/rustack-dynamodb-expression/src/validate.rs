@@ -0,0 +1,488 @@
+//! Static validation of DynamoDB expressions.
+//!
+//! These entry points check an expression together with its
+//! `ExpressionAttributeNames` and `ExpressionAttributeValues` without touching
+//! any table, so they can lint expressions ahead of time (for example in CI).
+//! Each problem is reported as an [`ExpressionDiagnostic`] pointing into the
+//! source string where possible.
+//!
+//! A syntax error stops analysis, so it is always the only diagnostic. Unused
+//! names and values are judged against the single expression being
+//! validated; a request that shares one set of placeholders across several
+//! expressions should validate the union itself.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use rustack_dynamodb_model::AttributeValue;
+
+use crate::{
+    ast::{PathClash, find_path_clash, resolve_path_elements},
+    parser::{
+        LocatedError, Parsed, Span, Token, parse_condition_located, parse_projection_located,
+        parse_update_located, projection_clash,
+    },
+    reserved::{is_reserved_word, reserved_word_message},
+};
+
+/// The kind of problem an [`ExpressionDiagnostic`] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DiagnosticCategory {
+    /// The expression does not parse.
+    Syntax,
+    /// A `#name` placeholder is missing from `ExpressionAttributeNames`.
+    UnresolvedName,
+    /// A `:value` placeholder is missing from `ExpressionAttributeValues`.
+    UnresolvedValue,
+    /// An `ExpressionAttributeNames` entry is never referenced.
+    UnusedName,
+    /// An `ExpressionAttributeValues` entry is never referenced.
+    UnusedValue,
+    /// An unescaped attribute name is a DynamoDB reserved word.
+    ReservedWord,
+    /// Two document paths overlap or conflict with each other.
+    PathOverlap,
+    /// An operator or function is not allowed in this kind of expression.
+    UnsupportedOperator,
+}
+
+impl fmt::Display for DiagnosticCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Syntax => "syntax",
+            Self::UnresolvedName => "unresolved-name",
+            Self::UnresolvedValue => "unresolved-value",
+            Self::UnusedName => "unused-name",
+            Self::UnusedValue => "unused-value",
+            Self::ReservedWord => "reserved-word",
+            Self::PathOverlap => "path-overlap",
+            Self::UnsupportedOperator => "unsupported-operator",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A single problem found in an expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpressionDiagnostic {
+    /// What kind of problem this is.
+    pub category: DiagnosticCategory,
+    /// Human-readable explanation, worded like DynamoDB's own error.
+    pub message: String,
+    /// Byte range in the expression the problem refers to. `None` for
+    /// problems that have no location, such as an unused placeholder.
+    pub span: Option<Span>,
+}
+
+impl ExpressionDiagnostic {
+    fn new(category: DiagnosticCategory, message: String, span: Option<Span>) -> Self {
+        Self {
+            category,
+            message,
+            span,
+        }
+    }
+}
+
+impl fmt::Display for ExpressionDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.span {
+            Some(span) => write!(f, "[{}] {} (at {span})", self.category, self.message),
+            None => write!(f, "[{}] {}", self.category, self.message),
+        }
+    }
+}
+
+impl From<LocatedError> for ExpressionDiagnostic {
+    fn from(e: LocatedError) -> Self {
+        let category = if e.overlap {
+            DiagnosticCategory::PathOverlap
+        } else {
+            DiagnosticCategory::Syntax
+        };
+        Self::new(category, e.error.to_string(), Some(e.span))
+    }
+}
+
+/// Validate a condition or filter expression.
+///
+/// # Errors
+///
+/// Returns every diagnostic found, in source order where they have a location.
+#[allow(clippy::implicit_hasher)]
+pub fn validate_condition_expression(
+    expr: &str,
+    names: &HashMap<String, String>,
+    values: &HashMap<String, AttributeValue>,
+) -> Result<(), Vec<ExpressionDiagnostic>> {
+    let parsed = parse_condition_located(expr).map_err(|e| vec![e.into()])?;
+    finish(check_references(&parsed, names, Some(values)))
+}
+
+/// Validate a key-condition expression.
+///
+/// On top of the condition checks, rejects the operators and functions
+/// DynamoDB does not accept in a `KeyConditionExpression` (`OR`, `NOT`, `IN`,
+/// `<>`, and every function except `begins_with`). Whether the referenced
+/// attributes are actually key attributes depends on the table and is not
+/// checked here.
+///
+/// # Errors
+///
+/// Returns every diagnostic found, in source order where they have a location.
+#[allow(clippy::implicit_hasher)]
+pub fn validate_key_condition_expression(
+    expr: &str,
+    names: &HashMap<String, String>,
+    values: &HashMap<String, AttributeValue>,
+) -> Result<(), Vec<ExpressionDiagnostic>> {
+    let parsed = parse_condition_located(expr).map_err(|e| vec![e.into()])?;
+    let mut diagnostics: Vec<ExpressionDiagnostic> = parsed
+        .tokens
+        .iter()
+        .filter_map(|(token, span)| {
+            let message = match token {
+                Token::Or | Token::Not => {
+                    format!("Unsupported operator in KeyConditionExpression: {token}")
+                }
+                Token::In | Token::Ne => format!(
+                    "Unsupported operator on KeyConditionExpression: operator: {}",
+                    token.to_string().trim_matches('\'')
+                ),
+                Token::AttributeExists
+                | Token::AttributeNotExists
+                | Token::AttributeType
+                | Token::Contains
+                | Token::Size => {
+                    format!("Unsupported function in KeyConditionExpression: {token}")
+                }
+                _ => return None,
+            };
+            Some(ExpressionDiagnostic::new(
+                DiagnosticCategory::UnsupportedOperator,
+                message,
+                Some(*span),
+            ))
+        })
+        .collect();
+    diagnostics.extend(check_references(&parsed, names, Some(values)));
+    finish(diagnostics)
+}
+
+/// Validate an update expression, including overlapping or conflicting
+/// target paths (`SET a = :x REMOVE a.b`).
+///
+/// # Errors
+///
+/// Returns every diagnostic found, in source order where they have a location.
+#[allow(clippy::implicit_hasher)]
+pub fn validate_update_expression(
+    expr: &str,
+    names: &HashMap<String, String>,
+    values: &HashMap<String, AttributeValue>,
+) -> Result<(), Vec<ExpressionDiagnostic>> {
+    let parsed = parse_update_located(expr).map_err(|e| vec![e.into()])?;
+    let mut diagnostics = check_references(&parsed, names, Some(values));
+
+    let targets = parsed.value.target_paths();
+    let resolved: Vec<_> = targets
+        .iter()
+        .map(|path| resolve_path_elements(path, names))
+        .collect();
+    if let Some((i, j, clash)) = find_path_clash(&resolved) {
+        let verb = match clash {
+            PathClash::Overlap => "overlap",
+            PathClash::Conflict => "conflict",
+        };
+        // Point at the last occurrence of the later path; an identical path
+        // appears twice and the second one is the offending write.
+        let span = parsed
+            .paths
+            .iter()
+            .rev()
+            .find(|(path, _)| path == targets[j])
+            .map(|(_, span)| *span);
+        diagnostics.push(ExpressionDiagnostic::new(
+            DiagnosticCategory::PathOverlap,
+            format!(
+                "Invalid UpdateExpression: Two document paths {verb} with each other; must remove \
+                 or rewrite one of these paths; path one: [{}], path two: [{}]",
+                targets[i], targets[j]
+            ),
+            span,
+        ));
+    }
+    finish(diagnostics)
+}
+
+/// Validate a projection expression, including paths that overlap or
+/// conflict once names are resolved (`#n, a` with `#n` naming `a`).
+/// Projections take no attribute values.
+///
+/// # Errors
+///
+/// Returns every diagnostic found, in source order where they have a location.
+#[allow(clippy::implicit_hasher)]
+pub fn validate_projection_expression(
+    expr: &str,
+    names: &HashMap<String, String>,
+) -> Result<(), Vec<ExpressionDiagnostic>> {
+    let parsed = parse_projection_located(expr).map_err(|e| vec![e.into()])?;
+    let mut diagnostics = check_references(&parsed, names, None);
+    if let Some((index, error)) = projection_clash(&parsed.value, names) {
+        diagnostics.push(ExpressionDiagnostic::new(
+            DiagnosticCategory::PathOverlap,
+            error.to_string(),
+            parsed.paths.get(index).map(|(_, span)| *span),
+        ));
+    }
+    finish(diagnostics)
+}
+
+/// Check placeholders and reserved words against the token stream.
+///
+/// When `values` is `None` the expression kind takes no values, so value
+/// placeholders are neither resolved nor reported unused.
+fn check_references<T>(
+    parsed: &Parsed<T>,
+    names: &HashMap<String, String>,
+    values: Option<&HashMap<String, AttributeValue>>,
+) -> Vec<ExpressionDiagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut used_names = HashSet::new();
+    let mut used_values = HashSet::new();
+
+    for (token, span) in &parsed.tokens {
+        match token {
+            Token::Identifier(word) if is_reserved_word(word) => {
+                diagnostics.push(ExpressionDiagnostic::new(
+                    DiagnosticCategory::ReservedWord,
+                    reserved_word_message(word),
+                    Some(*span),
+                ));
+            }
+            Token::ExprAttrName(name) => {
+                let key = format!("#{name}");
+                if !names.contains_key(&key) {
+                    diagnostics.push(ExpressionDiagnostic::new(
+                        DiagnosticCategory::UnresolvedName,
+                        format!(
+                            "An expression attribute name used in the document path is not \
+                             defined; attribute name: {key}"
+                        ),
+                        Some(*span),
+                    ));
+                }
+                used_names.insert(key);
+            }
+            Token::ExprAttrValue(name) => {
+                let key = format!(":{name}");
+                if values.is_some_and(|values| !values.contains_key(&key)) {
+                    diagnostics.push(ExpressionDiagnostic::new(
+                        DiagnosticCategory::UnresolvedValue,
+                        format!(
+                            "An expression attribute value used in expression is not defined; \
+                             attribute value: {key}"
+                        ),
+                        Some(*span),
+                    ));
+                }
+                used_values.insert(key);
+            }
+            _ => {}
+        }
+    }
+
+    let mut unused_names: Vec<&String> = names
+        .keys()
+        .filter(|key| !used_names.contains(*key))
+        .collect();
+    unused_names.sort();
+    diagnostics.extend(unused_names.into_iter().map(|key| {
+        ExpressionDiagnostic::new(
+            DiagnosticCategory::UnusedName,
+            format!(
+                "Value provided in ExpressionAttributeNames unused in expressions: keys: {{{key}}}"
+            ),
+            None,
+        )
+    }));
+
+    if let Some(values) = values {
+        let mut unused_values: Vec<&String> = values
+            .keys()
+            .filter(|key| !used_values.contains(*key))
+            .collect();
+        unused_values.sort();
+        diagnostics.extend(unused_values.into_iter().map(|key| {
+            ExpressionDiagnostic::new(
+                DiagnosticCategory::UnusedValue,
+                format!(
+                    "Value provided in ExpressionAttributeValues unused in expressions: keys: \
+                     {{{key}}}"
+                ),
+                None,
+            )
+        }));
+    }
+
+    diagnostics
+}
+
+/// Order diagnostics by location, unlocated ones last, and wrap them up.
+fn finish(mut diagnostics: Vec<ExpressionDiagnostic>) -> Result<(), Vec<ExpressionDiagnostic>> {
+    diagnostics.sort_by_key(|d| d.span.map_or(usize::MAX, |span| span.start));
+    if diagnostics.is_empty() {
+        Ok(())
+    } else {
+        Err(diagnostics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+            .collect()
+    }
+
+    fn values(keys: &[&str]) -> HashMap<String, AttributeValue> {
+        keys.iter()
+            .map(|k| ((*k).to_owned(), AttributeValue::S("x".to_owned())))
+            .collect()
+    }
+
+    fn categories(diagnostics: &[ExpressionDiagnostic]) -> Vec<DiagnosticCategory> {
+        diagnostics.iter().map(|d| d.category).collect()
+    }
+
+    #[test]
+    fn test_should_accept_well_formed_expressions() {
+        let n = names(&[("#s", "status")]);
+        assert!(
+            validate_condition_expression("#s = :v AND rating > :r", &n, &values(&[":v", ":r"]))
+                .is_ok()
+        );
+        assert!(
+            validate_update_expression("SET #s = :v REMOVE tags", &n, &values(&[":v"])).is_ok()
+        );
+        assert!(validate_projection_expression("#s, info.rating, items2[0]", &n).is_ok());
+        assert!(
+            validate_key_condition_expression(
+                "pk = :p AND begins_with(sk, :s)",
+                &names(&[]),
+                &values(&[":p", ":s"])
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_should_locate_syntax_errors_in_source() {
+        let expr = "a = :v AND AND b = :w";
+        let diagnostics =
+            validate_condition_expression(expr, &names(&[]), &values(&[":v", ":w"])).unwrap_err();
+        assert_eq!(categories(&diagnostics), [DiagnosticCategory::Syntax]);
+        let span = diagnostics[0].span.unwrap();
+        assert_eq!(&expr[span.start..span.end], "AND");
+        assert_eq!(span.start, 11);
+
+        let diagnostics =
+            validate_condition_expression("a = :v $", &names(&[]), &values(&[":v"])).unwrap_err();
+        assert_eq!(diagnostics[0].span, Some(Span::new(7, 8)));
+    }
+
+    #[test]
+    fn test_should_report_unresolved_and_unused_placeholders() {
+        let expr = "#a = :v";
+        let diagnostics =
+            validate_condition_expression(expr, &names(&[("#b", "b")]), &values(&[":w"]))
+                .unwrap_err();
+        assert_eq!(
+            categories(&diagnostics),
+            [
+                DiagnosticCategory::UnresolvedName,
+                DiagnosticCategory::UnresolvedValue,
+                DiagnosticCategory::UnusedName,
+                DiagnosticCategory::UnusedValue,
+            ]
+        );
+        assert_eq!(diagnostics[0].span, Some(Span::new(0, 2)));
+        assert_eq!(diagnostics[1].span, Some(Span::new(5, 7)));
+        assert_eq!(diagnostics[2].span, None);
+        assert!(diagnostics[3].message.contains("{:w}"));
+    }
+
+    #[test]
+    fn test_should_flag_unescaped_reserved_words() {
+        let expr = "SET info.name = :v, rating = :r";
+        let diagnostics =
+            validate_update_expression(expr, &names(&[]), &values(&[":v", ":r"])).unwrap_err();
+        assert_eq!(categories(&diagnostics), [DiagnosticCategory::ReservedWord]);
+        let span = diagnostics[0].span.unwrap();
+        assert_eq!(&expr[span.start..span.end], "name");
+    }
+
+    #[test]
+    fn test_should_point_at_overlapping_update_paths() {
+        let expr = "SET a.b = :v REMOVE a";
+        let diagnostics =
+            validate_update_expression(expr, &names(&[]), &values(&[":v"])).unwrap_err();
+        assert_eq!(categories(&diagnostics), [DiagnosticCategory::PathOverlap]);
+        let span = diagnostics[0].span.unwrap();
+        assert_eq!(&expr[span.start..span.end], "a");
+        assert_eq!(span.start, 20);
+
+        let expr = "SET #x[0] = :v, #y.z = :w";
+        let diagnostics = validate_update_expression(
+            expr,
+            &names(&[("#x", "x"), ("#y", "x")]),
+            &values(&[":v", ":w"]),
+        )
+        .unwrap_err();
+        assert!(diagnostics[0].message.contains("conflict"));
+        let span = diagnostics[0].span.unwrap();
+        assert_eq!(&expr[span.start..span.end], "#y.z");
+    }
+
+    #[test]
+    fn test_should_point_at_overlapping_projection_paths() {
+        let expr = "a.b, c, a";
+        let diagnostics = validate_projection_expression(expr, &names(&[])).unwrap_err();
+        assert_eq!(categories(&diagnostics), [DiagnosticCategory::PathOverlap]);
+        assert_eq!(diagnostics[0].span, Some(Span::new(8, 9)));
+
+        let expr = "a.b, #n";
+        let diagnostics = validate_projection_expression(expr, &names(&[("#n", "a")])).unwrap_err();
+        assert_eq!(categories(&diagnostics), [DiagnosticCategory::PathOverlap]);
+        assert_eq!(diagnostics[0].span, Some(Span::new(5, 7)));
+
+        let diagnostics = validate_projection_expression("a,, b", &names(&[])).unwrap_err();
+        assert_eq!(categories(&diagnostics), [DiagnosticCategory::Syntax]);
+        assert_eq!(diagnostics[0].span, Some(Span::new(2, 3)));
+    }
+
+    #[test]
+    fn test_should_reject_unsupported_key_condition_operators() {
+        let expr = "pk = :p OR contains(sk, :s)";
+        let diagnostics =
+            validate_key_condition_expression(expr, &names(&[]), &values(&[":p", ":s"]))
+                .unwrap_err();
+        assert_eq!(
+            categories(&diagnostics),
+            [DiagnosticCategory::UnsupportedOperator; 2]
+        );
+        assert_eq!(
+            diagnostics[0].message,
+            "Unsupported operator in KeyConditionExpression: OR"
+        );
+        assert_eq!(diagnostics[1].span, Some(Span::new(11, 19)));
+    }
+}
@@ -3,14 +3,16 @@
 use std::{future::Future, pin::Pin};
 
 use bytes::Bytes;
+use rustack_core::RequestScope;
 use rustack_dynamodb_model::{error::DynamoDBError, operations::DynamoDBOperation};
 
 use crate::body::DynamoDBResponseBody;
 
 /// Trait that the DynamoDB business logic provider must implement.
 ///
-/// The handler receives a parsed operation enum and the raw JSON body bytes,
-/// and returns a complete HTTP response. This trait serves as the boundary
+/// The handler receives a parsed operation enum, the raw JSON body bytes, the
+/// (account, region) namespace the request operates in, and the host the
+/// client addressed, and returns a complete HTTP response. This trait serves as the boundary
 /// between the HTTP transport layer and the business logic layer.
 pub trait DynamoDBHandler: Send + Sync + 'static {
     /// Handle a DynamoDB operation and produce an HTTP response.
@@ -18,6 +20,8 @@ pub trait DynamoDBHandler: Send + Sync + 'static {
         &self,
         op: DynamoDBOperation,
         body: Bytes,
+        scope: RequestScope,
+        host: Option<String>,
     ) -> Pin<
         Box<
             dyn Future<Output = Result<http::Response<DynamoDBResponseBody>, DynamoDBError>> + Send,
@@ -30,9 +34,11 @@ pub async fn dispatch_operation<H: DynamoDBHandler>(
     handler: &H,
     op: DynamoDBOperation,
     body: Bytes,
+    scope: RequestScope,
+    host: Option<String>,
 ) -> Result<http::Response<DynamoDBResponseBody>, DynamoDBError> {
-    tracing::debug!(operation = %op, "dispatching DynamoDB operation");
-    handler.handle_operation(op, body).await
+    tracing::debug!(operation = %op, %scope, "dispatching DynamoDB operation");
+    handler.handle_operation(op, body, scope, host).await
 }
 
 /// Default handler that returns an error for all operations.
@@ -44,6 +50,8 @@ impl DynamoDBHandler for NotImplementedHandler {
         &self,
         op: DynamoDBOperation,
         _body: Bytes,
+        _scope: RequestScope,
+        _host: Option<String>,
     ) -> Pin<
         Box<
             dyn Future<Output = Result<http::Response<DynamoDBResponseBody>, DynamoDBError>> + Send,
@@ -7,6 +7,9 @@ use crate::body::DynamoDBResponseBody;
 /// Content type for DynamoDB JSON responses.
 pub const CONTENT_TYPE: &str = "application/x-amz-json-1.0";
 
+/// Header carrying the CRC32 of the response body, as a decimal number.
+pub const CRC32_HEADER: &str = "x-amz-crc32";
+
 /// Serialize a DynamoDB error into a JSON response body.
 ///
 /// The error format follows the AWS DynamoDB JSON protocol:
@@ -34,47 +37,42 @@ pub fn error_to_json(error: &DynamoDBError) -> Vec<u8> {
 }
 
 /// Convert a `DynamoDBError` into a complete HTTP error response.
+///
+/// The error code is attached to the response extensions for access logging.
 #[must_use]
 pub fn error_to_response(
     error: &DynamoDBError,
     request_id: &str,
 ) -> http::Response<DynamoDBResponseBody> {
-    let json = error_to_json(error);
-    let crc = crc32fast::hash(&json);
-    let body = DynamoDBResponseBody::from_json(json);
-
-    let mut response = http::Response::builder()
-        .status(error.status_code)
-        .header("content-type", CONTENT_TYPE)
-        .header("x-amzn-requestid", request_id)
-        .body(body)
-        .expect("valid error response");
-
-    if let Ok(hv) = http::HeaderValue::from_str(&crc.to_string()) {
-        response.headers_mut().insert("x-amz-crc32", hv);
-    }
-
+    let mut response = json_with_status(error.status_code, error_to_json(error), request_id);
+    response.extensions_mut().insert(error.code);
     response
 }
 
 /// Build a success response from JSON bytes.
 #[must_use]
 pub fn json_response(json: Vec<u8>, request_id: &str) -> http::Response<DynamoDBResponseBody> {
-    let crc = crc32fast::hash(&json);
-    let body = DynamoDBResponseBody::from_json(json);
+    json_with_status(http::StatusCode::OK, json, request_id)
+}
 
-    let mut response = http::Response::builder()
-        .status(http::StatusCode::OK)
-        .header("content-type", CONTENT_TYPE)
+/// Build a JSON response carrying the exact [`CONTENT_TYPE`] and the
+/// CRC32 of the body in [`CRC32_HEADER`].
+///
+/// Older SDKs (Java v1, .NET) check the CRC against the bytes they read and
+/// retry the request on a missing or mismatched value.
+fn json_with_status(
+    status: http::StatusCode,
+    json: Vec<u8>,
+    request_id: &str,
+) -> http::Response<DynamoDBResponseBody> {
+    let crc = crc32fast::hash(&json);
+    http::Response::builder()
+        .status(status)
+        .header(http::header::CONTENT_TYPE, CONTENT_TYPE)
+        .header(CRC32_HEADER, crc)
         .header("x-amzn-requestid", request_id)
-        .body(body)
-        .expect("valid JSON response");
-
-    if let Ok(hv) = http::HeaderValue::from_str(&crc.to_string()) {
-        response.headers_mut().insert("x-amz-crc32", hv);
-    }
-
-    response
+        .body(DynamoDBResponseBody::from_json(json))
+        .expect("valid JSON response")
 }
 
 #[cfg(test)]
@@ -122,4 +120,11 @@ mod tests {
         assert_eq!(resp.headers().get("content-type").unwrap(), CONTENT_TYPE,);
         assert!(resp.headers().get("x-amz-crc32").is_some());
     }
+
+    #[test]
+    fn test_should_checksum_response_body() {
+        // CRC32 of `{}`, as computed by the AWS SDKs.
+        let resp = json_response(b"{}".to_vec(), "req-789");
+        assert_eq!(resp.headers().get(CRC32_HEADER).unwrap(), "2745614147");
+    }
 }
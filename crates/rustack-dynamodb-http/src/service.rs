@@ -1,11 +1,40 @@
 //! DynamoDB HTTP service implementing the hyper `Service` trait.
+//!
+//! Responses produced by a dispatched operation carry the resolved
+//! [`DynamoDBOperation`](rustack_dynamodb_model::operations::DynamoDBOperation)
+//! in their extensions, and error responses carry their
+//! [`DynamoDBErrorCode`]. When an [`AccessLogger`] is configured, one line is
+//! written per request.
+//!
+//! Every response carries the request id in `x-amzn-RequestId`, and a client
+//! `X-Amzn-Trace-Id` is echoed back. Processing runs inside a span recording
+//! both, so all log lines for a request can be correlated. With span export
+//! enabled, the span is named after the operation (`DynamoDB.Query`) and
+//! records the table, status, and error code.
+//!
+//! Each request is dispatched with a [`RequestScope`] resolved from the access
+//! key and credential scope region it claims, so tables are namespaced by
+//! account and region.
 
-use std::{convert::Infallible, future::Future, pin::Pin, sync::Arc};
+use std::{convert::Infallible, future::Future, pin::Pin, sync::Arc, time::Instant};
 
 use bytes::Bytes;
-use http_body_util::BodyExt;
+use http_body::Body as _;
+use http_body_util::{BodyExt, LengthLimitError, Limited};
 use hyper::body::Incoming;
-use rustack_dynamodb_model::error::DynamoDBError;
+use rustack_auth::{
+    AuthError,
+    clock::{MAX_CLOCK_SKEW, format_amz_date},
+};
+use rustack_core::{
+    AccessLogEntry, AccessLogger, RequestScope, TRACE_ID_HEADER, TRACEPARENT_HEADER,
+    new_request_id,
+    request_id::{record_operation, record_outcome, record_resource, span_export_enabled},
+    request_span,
+};
+use rustack_dynamodb_model::error::{DynamoDBError, DynamoDBErrorCode};
+use serde::Deserialize;
+use tracing::{Instrument, debug};
 
 use crate::{
     body::DynamoDBResponseBody,
@@ -23,6 +52,11 @@ pub struct DynamoDBHttpConfig {
     pub region: String,
     /// Credential provider for signature validation.
     pub credential_provider: Option<Arc<dyn rustack_auth::CredentialProvider>>,
+    /// Optional access logger; when set, one line is written per request.
+    pub access_log: Option<Arc<AccessLogger>>,
+    /// Largest request body accepted, in bytes. Larger requests are rejected
+    /// with a `413` before the body is read. Unlimited if unset.
+    pub max_body_bytes: Option<u64>,
 }
 
 impl std::fmt::Debug for DynamoDBHttpConfig {
@@ -34,6 +68,8 @@ impl std::fmt::Debug for DynamoDBHttpConfig {
                 "credential_provider",
                 &self.credential_provider.as_ref().map(|_| "..."),
             )
+            .field("access_log", &self.access_log)
+            .field("max_body_bytes", &self.max_body_bytes)
             .finish()
     }
 }
@@ -44,6 +80,8 @@ impl Default for DynamoDBHttpConfig {
             skip_signature_validation: true,
             region: "us-east-1".to_owned(),
             credential_provider: None,
+            access_log: None,
+            max_body_bytes: None,
         }
     }
 }
@@ -87,13 +125,51 @@ impl<H: DynamoDBHandler> hyper::service::Service<http::Request<Incoming>>
     fn call(&self, req: http::Request<Incoming>) -> Self::Future {
         let handler = Arc::clone(&self.handler);
         let config = Arc::clone(&self.config);
-        let request_id = uuid::Uuid::new_v4().to_string();
+        let request_id = new_request_id();
+        let trace_id = req.headers().get(TRACE_ID_HEADER).cloned();
+        let span = request_span(
+            "dynamodb",
+            &request_id,
+            trace_id.as_ref().and_then(|v| v.to_str().ok()),
+            req.headers()
+                .get(TRACEPARENT_HEADER)
+                .and_then(|v| v.to_str().ok()),
+        );
 
-        Box::pin(async move {
-            let response = process_request(req, handler.as_ref(), &config, &request_id).await;
-            let response = add_common_headers(response, &request_id);
-            Ok(response)
-        })
+        Box::pin(
+            async move {
+                let started = Instant::now();
+                let mut access_log = config
+                    .access_log
+                    .as_ref()
+                    .map(|_| start_access_log_entry(&req, &request_id));
+
+                let response = process_request(
+                    req,
+                    handler.as_ref(),
+                    &config,
+                    &request_id,
+                    access_log.as_mut(),
+                )
+                .await;
+                let response = add_common_headers(response, &request_id, trace_id);
+                record_outcome(
+                    response.status().as_u16(),
+                    response
+                        .extensions()
+                        .get::<DynamoDBErrorCode>()
+                        .map(DynamoDBErrorCode::as_str),
+                );
+
+                if let (Some(logger), Some(mut entry)) = (&config.access_log, access_log) {
+                    finish_access_log_entry(&mut entry, &response, started);
+                    logger.log(&entry);
+                }
+
+                Ok(response)
+            }
+            .instrument(span),
+        )
     }
 }
 
@@ -103,6 +179,7 @@ async fn process_request<H: DynamoDBHandler>(
     handler: &H,
     config: &DynamoDBHttpConfig,
     request_id: &str,
+    access_log: Option<&mut AccessLogEntry>,
 ) -> http::Response<DynamoDBResponseBody> {
     let (parts, incoming) = req.into_parts();
 
@@ -120,59 +197,238 @@ async fn process_request<H: DynamoDBHandler>(
         Ok(op) => op,
         Err(err) => return error_to_response(&err, request_id),
     };
+    record_operation("DynamoDB", op.as_str());
 
-    // 3. Collect body.
-    let body = match collect_body(incoming).await {
+    // 3. Collect body, rejecting one over the limit before buffering it.
+    let body = match collect_body(
+        incoming,
+        content_length(&parts.headers),
+        config.max_body_bytes,
+    )
+    .await
+    {
         Ok(body) => body,
         Err(err) => return error_to_response(&err, request_id),
     };
 
+    let table = (access_log.is_some() || span_export_enabled())
+        .then(|| table_name(&body))
+        .flatten();
+    record_resource("table", table.as_deref());
+    if let Some(entry) = access_log {
+        entry.operation = Some(op.as_str());
+        entry.table = table;
+        entry.bytes_in = body.len() as u64;
+    }
+
     // 4. Authenticate (if enabled).
     if !config.skip_signature_validation {
         if let Some(ref cred_provider) = config.credential_provider {
             let body_hash = rustack_auth::hash_payload(&body);
-            if let Err(auth_err) =
-                rustack_auth::verify_sigv4(&parts, &body_hash, cred_provider.as_ref())
-            {
-                let err = DynamoDBError::with_message(
-                    rustack_dynamodb_model::error::DynamoDBErrorCode::AccessDeniedException,
-                    auth_err.to_string(),
-                );
-                return error_to_response(&err, request_id);
+            match rustack_auth::verify_sigv4(&parts, &body_hash, cred_provider.as_ref()) {
+                Ok(auth) => debug!(
+                    access_key_id = %auth.access_key_id,
+                    algorithm = %auth.algorithm,
+                    region = %auth.region,
+                    request_id,
+                    "request authenticated"
+                ),
+                Err(auth_err) => {
+                    return error_to_response(&auth_error_to_dynamodb(&auth_err), request_id);
+                }
             }
         }
     }
 
-    // 5. Dispatch to handler.
-    match dispatch_operation(handler, op, body).await {
+    // 5. Dispatch to handler, in the namespace of the claimed credentials.
+    let scope = RequestScope::resolve(
+        rustack_auth::requester_access_key(&parts.headers, &parts.uri).as_deref(),
+        rustack_auth::requester_region(&parts.headers, &parts.uri).as_deref(),
+        &config.region,
+    );
+    let host = request_host(&parts);
+    let mut response = match dispatch_operation(handler, op, body, scope, host).await {
         Ok(response) => response,
         Err(err) => error_to_response(&err, request_id),
+    };
+
+    // Expose the resolved operation to outer layers (e.g. gateway metrics).
+    response.extensions_mut().insert(op);
+    response
+}
+
+/// The host the client addressed, from the `Host` header or, for HTTP/2, the
+/// request URI's authority.
+fn request_host(parts: &http::request::Parts) -> Option<String> {
+    parts
+        .headers
+        .get(http::header::HOST)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned)
+        .or_else(|| parts.uri.authority().map(ToString::to_string))
+}
+
+/// Start an access log entry from the parts of the request available up front.
+///
+/// Only the `User-Agent` header is copied; `Authorization` is reduced to the
+/// claimed access key ID.
+fn start_access_log_entry<B>(req: &http::Request<B>, request_id: &str) -> AccessLogEntry {
+    let uri = req
+        .uri()
+        .path_and_query()
+        .map_or_else(|| req.uri().path(), http::uri::PathAndQuery::as_str);
+    let mut entry = AccessLogEntry::new("dynamodb", request_id, req.method().as_str(), uri);
+    entry.user_agent = req
+        .headers()
+        .get(http::header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(ToOwned::to_owned);
+    entry.requester = rustack_auth::requester_access_key(req.headers(), req.uri());
+    entry
+}
+
+/// Complete an access log entry from the final response.
+fn finish_access_log_entry(
+    entry: &mut AccessLogEntry,
+    response: &http::Response<DynamoDBResponseBody>,
+    started: Instant,
+) {
+    entry.status = response.status().as_u16();
+    entry.error_code = response
+        .extensions()
+        .get::<DynamoDBErrorCode>()
+        .map(DynamoDBErrorCode::as_str);
+    entry.bytes_out = response.body().size_hint().lower();
+    entry.latency = started.elapsed();
+}
+
+/// Extract the top-level `TableName` from a request body, if present.
+///
+/// Multi-table operations (batch and transactional requests) have no
+/// top-level table and yield `None`.
+fn table_name(body: &[u8]) -> Option<String> {
+    #[derive(Deserialize)]
+    struct TableNameProbe {
+        #[serde(rename = "TableName")]
+        table_name: Option<String>,
     }
+
+    serde_json::from_slice::<TableNameProbe>(body)
+        .ok()
+        .and_then(|probe| probe.table_name)
 }
 
-/// Collect the incoming body into a single `Bytes` buffer.
-async fn collect_body(incoming: Incoming) -> Result<Bytes, DynamoDBError> {
-    incoming
+/// Map an authentication failure to the DynamoDB error a client would see from
+/// AWS.
+///
+/// A skewed `X-Amz-Date` becomes `InvalidSignatureException` with the message
+/// AWS uses, and a wrong or expired session token becomes
+/// `UnrecognizedClientException` or `ExpiredTokenException`; every other
+/// failure is reported as `AccessDeniedException`.
+fn auth_error_to_dynamodb(err: &AuthError) -> DynamoDBError {
+    match err {
+        AuthError::InvalidToken => DynamoDBError::with_message(
+            DynamoDBErrorCode::UnrecognizedClientException,
+            "The security token included in the request is invalid.",
+        ),
+        AuthError::ExpiredToken => DynamoDBError::with_message(
+            DynamoDBErrorCode::ExpiredTokenException,
+            "The security token included in the request is expired",
+        ),
+        AuthError::RequestTimeTooSkewed {
+            request_time,
+            server_time,
+        } => {
+            let message = if request_time < server_time {
+                format!(
+                    "Signature expired: {} is now earlier than {} ({} - 15 min.)",
+                    format_amz_date(*request_time),
+                    format_amz_date(*server_time - MAX_CLOCK_SKEW),
+                    format_amz_date(*server_time),
+                )
+            } else {
+                format!(
+                    "Signature not yet current: {} is still later than {} ({} + 15 min.)",
+                    format_amz_date(*request_time),
+                    format_amz_date(*server_time + MAX_CLOCK_SKEW),
+                    format_amz_date(*server_time),
+                )
+            };
+            DynamoDBError::with_message(DynamoDBErrorCode::InvalidSignatureException, message)
+        }
+        _ => DynamoDBError::with_message(DynamoDBErrorCode::AccessDeniedException, err.to_string()),
+    }
+}
+
+/// Collect the incoming body into a single `Bytes` buffer, failing as soon
+/// as it is declared or grows larger than `limit` bytes.
+async fn collect_body(
+    incoming: Incoming,
+    declared_len: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Bytes, DynamoDBError> {
+    let Some(limit) = limit else {
+        return incoming
+            .collect()
+            .await
+            .map(http_body_util::Collected::to_bytes)
+            .map_err(|e| {
+                DynamoDBError::internal_error(format!("Failed to read request body: {e}"))
+            });
+    };
+    if declared_len.is_some_and(|len| len > limit) {
+        return Err(request_too_large(limit));
+    }
+    Limited::new(incoming, usize::try_from(limit).unwrap_or(usize::MAX))
         .collect()
         .await
         .map(http_body_util::Collected::to_bytes)
-        .map_err(|e| DynamoDBError::internal_error(format!("Failed to read request body: {e}")))
+        .map_err(|e| {
+            if e.is::<LengthLimitError>() {
+                request_too_large(limit)
+            } else {
+                DynamoDBError::internal_error(format!("Failed to read request body: {e}"))
+            }
+        })
+}
+
+/// The declared `Content-Length` of the request, if any.
+fn content_length(headers: &http::HeaderMap) -> Option<u64> {
+    headers
+        .get(http::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
 }
 
-/// Add common response headers to every DynamoDB response.
+/// The `413` error for a request body over `limit` bytes.
+fn request_too_large(limit: u64) -> DynamoDBError {
+    let mut err =
+        DynamoDBError::validation(format!("Request size exceeds the maximum of {limit} bytes"));
+    err.status_code = http::StatusCode::PAYLOAD_TOO_LARGE;
+    err
+}
+
+/// Add common response headers to every DynamoDB response, echoing the
+/// client's `X-Amzn-Trace-Id` when it sent one.
 fn add_common_headers(
     mut response: http::Response<DynamoDBResponseBody>,
     request_id: &str,
+    trace_id: Option<http::HeaderValue>,
 ) -> http::Response<DynamoDBResponseBody> {
     let headers = response.headers_mut();
 
     if let Ok(hv) = http::HeaderValue::from_str(request_id) {
         headers.entry("x-amzn-requestid").or_insert(hv);
     }
+    if let Some(trace_id) = trace_id {
+        headers.insert(TRACE_ID_HEADER, trace_id);
+    }
 
-    headers
-        .entry("content-type")
-        .or_insert(http::HeaderValue::from_static(CONTENT_TYPE));
+    // Older SDKs reject any other content type, even on errors.
+    headers.insert(
+        http::header::CONTENT_TYPE,
+        http::HeaderValue::from_static(CONTENT_TYPE),
+    );
 
     headers.insert("server", http::HeaderValue::from_static("Rustack"));
 
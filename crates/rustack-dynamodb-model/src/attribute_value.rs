@@ -2,15 +2,26 @@
 //!
 //! `AttributeValue` is a tagged union where exactly one variant is present.
 //! The JSON wire format uses single-key objects like `{"S": "hello"}`.
+//!
+//! Deserialization rejects `L` and `M` values nested more than
+//! [`MAX_NESTING_DEPTH`] levels deep as soon as the extra level starts, so a
+//! pathologically nested document costs no more than a legal one.
 
-use std::{collections::HashMap, fmt};
+use std::{collections::HashMap, fmt, marker::PhantomData};
 
 use serde::{
     Deserialize, Deserializer, Serialize, Serializer,
-    de::{self, MapAccess, Visitor},
+    de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor},
     ser::SerializeMap,
 };
 
+/// Deepest nesting of `L` and `M` values DynamoDB accepts.
+pub const MAX_NESTING_DEPTH: usize = 32;
+
+/// The error DynamoDB reports for values nested deeper than
+/// [`MAX_NESTING_DEPTH`].
+pub const NESTING_LIMIT_MESSAGE: &str = "Nesting Levels have exceeded supported limits";
+
 /// DynamoDB attribute value.
 ///
 /// Represented as a tagged union where exactly one variant is present.
@@ -127,6 +138,17 @@ impl AttributeValue {
         }
     }
 
+    /// Returns how many `L` and `M` levels this value spans: 0 for scalars
+    /// and sets, 1 for a list or map of them, and so on.
+    #[must_use]
+    pub fn nesting_depth(&self) -> usize {
+        match self {
+            Self::L(list) => 1 + list.iter().map(Self::nesting_depth).max().unwrap_or(0),
+            Self::M(map) => 1 + map.values().map(Self::nesting_depth).max().unwrap_or(0),
+            _ => 0,
+        }
+    }
+
     /// Returns the DynamoDB type descriptor string (e.g., "S", "N", "BOOL").
     #[must_use]
     pub fn type_descriptor(&self) -> &'static str {
@@ -224,11 +246,34 @@ impl Serialize for AttributeValue {
 
 impl<'de> Deserialize<'de> for AttributeValue {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        deserializer.deserialize_map(AttributeValueVisitor)
+        AttributeValueVisitor { depth: 0 }.deserialize(deserializer)
+    }
+}
+
+/// Deserializes an attribute value enclosed in `depth` levels of `L` and `M`.
+#[derive(Clone, Copy)]
+struct AttributeValueVisitor {
+    depth: usize,
+}
+
+impl AttributeValueVisitor {
+    /// The visitor for the members of an `L` or `M` value at this depth.
+    fn nested<E: de::Error>(self) -> Result<Self, E> {
+        let depth = self.depth + 1;
+        if depth > MAX_NESTING_DEPTH {
+            return Err(E::custom(NESTING_LIMIT_MESSAGE));
+        }
+        Ok(Self { depth })
     }
 }
 
-struct AttributeValueVisitor;
+impl<'de> DeserializeSeed<'de> for AttributeValueVisitor {
+    type Value = AttributeValue;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
 
 impl<'de> Visitor<'de> for AttributeValueVisitor {
     type Value = AttributeValue;
@@ -272,8 +317,8 @@ impl<'de> Visitor<'de> for AttributeValueVisitor {
             }
             "BOOL" => AttributeValue::Bool(map.next_value()?),
             "NULL" => AttributeValue::Null(map.next_value()?),
-            "L" => AttributeValue::L(map.next_value()?),
-            "M" => AttributeValue::M(map.next_value()?),
+            "L" => AttributeValue::L(map.next_value_seed(ListVisitor(self.nested()?))?),
+            "M" => AttributeValue::M(map.next_value_seed(MapVisitor(self.nested()?))?),
             other => {
                 return Err(de::Error::unknown_field(
                     other,
@@ -286,6 +331,60 @@ impl<'de> Visitor<'de> for AttributeValueVisitor {
     }
 }
 
+/// Deserializes the members of an `L` value.
+struct ListVisitor(AttributeValueVisitor);
+
+impl<'de> DeserializeSeed<'de> for ListVisitor {
+    type Value = Vec<AttributeValue>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ListVisitor {
+    type Value = Vec<AttributeValue>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a list of DynamoDB AttributeValues")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut list = Vec::new();
+        while let Some(value) = seq.next_element_seed(self.0)? {
+            list.push(value);
+        }
+        Ok(list)
+    }
+}
+
+/// Deserializes the members of an `M` value.
+struct MapVisitor(AttributeValueVisitor);
+
+impl<'de> DeserializeSeed<'de> for MapVisitor {
+    type Value = HashMap<String, AttributeValue>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for MapVisitor {
+    type Value = HashMap<String, AttributeValue>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a map of DynamoDB AttributeValues")
+    }
+
+    fn visit_map<M: MapAccess<'de>>(self, mut map: M) -> Result<Self::Value, M::Error> {
+        let mut members = HashMap::new();
+        while let Some((name, value)) = map.next_entry_seed(PhantomData::<String>, self.0)? {
+            members.insert(name, value);
+        }
+        Ok(members)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -353,6 +452,39 @@ mod tests {
         assert!(matches!(val, AttributeValue::Ns(ref v) if v.len() == 3));
     }
 
+    fn nested_maps(levels: usize) -> String {
+        let mut json = r#"{"S":"leaf"}"#.to_owned();
+        for _ in 0..levels {
+            json = format!(r#"{{"M":{{"a":{json}}}}}"#);
+        }
+        json
+    }
+
+    #[test]
+    fn test_should_accept_values_nested_to_the_limit() {
+        let json = nested_maps(MAX_NESTING_DEPTH);
+        let val: AttributeValue = serde_json::from_str(&json).unwrap();
+        assert_eq!(val.nesting_depth(), MAX_NESTING_DEPTH);
+
+        let lists = format!(
+            "{}{{\"N\":\"1\"}}{}",
+            r#"{"L":["#.repeat(MAX_NESTING_DEPTH),
+            "]}".repeat(MAX_NESTING_DEPTH)
+        );
+        let val: AttributeValue = serde_json::from_str(&lists).unwrap();
+        assert_eq!(val.nesting_depth(), MAX_NESTING_DEPTH);
+    }
+
+    #[test]
+    fn test_should_reject_values_nested_past_the_limit() {
+        for levels in [MAX_NESTING_DEPTH + 1, 5000] {
+            let err = serde_json::from_str::<AttributeValue>(&nested_maps(levels))
+                .err()
+                .unwrap_or_else(|| panic!("{levels} levels should be rejected"));
+            assert!(err.to_string().contains(NESTING_LIMIT_MESSAGE), "{err}");
+        }
+    }
+
     #[test]
     fn test_should_deserialize_string_set() {
         let json = r#"{"SS":["a","b"]}"#;
@@ -44,6 +44,29 @@ pub enum DynamoDBErrorCode {
     AccessDeniedException,
     /// Unknown operation.
     UnrecognizedClientException,
+    /// The request signature is invalid or its timestamp is outside the
+    /// allowed clock skew.
+    InvalidSignatureException,
+    /// The request's session token has expired.
+    ExpiredTokenException,
+    /// Table not found (export operations).
+    TableNotFoundException,
+    /// Export not found.
+    ExportNotFoundException,
+    /// An export with the same client token but different parameters exists.
+    ExportConflictException,
+    /// The requested export time is outside the exportable window.
+    InvalidExportTimeException,
+    /// The target table of a restore already exists.
+    TableAlreadyExistsException,
+    /// The table is being created, updated, or deleted.
+    TableInUseException,
+    /// Backup not found.
+    BackupNotFoundException,
+    /// Point-in-time recovery is not enabled for the table.
+    PointInTimeRecoveryUnavailableException,
+    /// The restore time is outside the table's recovery window.
+    InvalidRestoreTimeException,
 }
 
 impl DynamoDBErrorCode {
@@ -89,6 +112,33 @@ impl DynamoDBErrorCode {
             Self::UnrecognizedClientException => {
                 "com.amazonaws.dynamodb.v20120810#UnrecognizedClientException"
             }
+            Self::InvalidSignatureException => "com.amazon.coral.service#InvalidSignatureException",
+            Self::ExpiredTokenException => "com.amazon.coral.service#ExpiredTokenException",
+            Self::TableNotFoundException => {
+                "com.amazonaws.dynamodb.v20120810#TableNotFoundException"
+            }
+            Self::ExportNotFoundException => {
+                "com.amazonaws.dynamodb.v20120810#ExportNotFoundException"
+            }
+            Self::ExportConflictException => {
+                "com.amazonaws.dynamodb.v20120810#ExportConflictException"
+            }
+            Self::InvalidExportTimeException => {
+                "com.amazonaws.dynamodb.v20120810#InvalidExportTimeException"
+            }
+            Self::TableAlreadyExistsException => {
+                "com.amazonaws.dynamodb.v20120810#TableAlreadyExistsException"
+            }
+            Self::TableInUseException => "com.amazonaws.dynamodb.v20120810#TableInUseException",
+            Self::BackupNotFoundException => {
+                "com.amazonaws.dynamodb.v20120810#BackupNotFoundException"
+            }
+            Self::PointInTimeRecoveryUnavailableException => {
+                "com.amazonaws.dynamodb.v20120810#PointInTimeRecoveryUnavailableException"
+            }
+            Self::InvalidRestoreTimeException => {
+                "com.amazonaws.dynamodb.v20120810#InvalidRestoreTimeException"
+            }
         }
     }
 
@@ -116,10 +166,24 @@ impl DynamoDBErrorCode {
             Self::MissingAction => "MissingAction",
             Self::AccessDeniedException => "AccessDeniedException",
             Self::UnrecognizedClientException => "UnrecognizedClientException",
+            Self::InvalidSignatureException => "InvalidSignatureException",
+            Self::ExpiredTokenException => "ExpiredTokenException",
+            Self::TableNotFoundException => "TableNotFoundException",
+            Self::ExportNotFoundException => "ExportNotFoundException",
+            Self::ExportConflictException => "ExportConflictException",
+            Self::InvalidExportTimeException => "InvalidExportTimeException",
+            Self::TableAlreadyExistsException => "TableAlreadyExistsException",
+            Self::TableInUseException => "TableInUseException",
+            Self::BackupNotFoundException => "BackupNotFoundException",
+            Self::PointInTimeRecoveryUnavailableException => {
+                "PointInTimeRecoveryUnavailableException"
+            }
+            Self::InvalidRestoreTimeException => "InvalidRestoreTimeException",
         }
     }
 
     /// Returns the default HTTP status code for this error.
+    #[cfg(feature = "http")]
     #[must_use]
     pub fn default_status_code(&self) -> http::StatusCode {
         match self {
@@ -143,6 +207,7 @@ pub struct DynamoDBError {
     /// A human-readable error message.
     pub message: String,
     /// The HTTP status code.
+    #[cfg(feature = "http")]
     pub status_code: http::StatusCode,
     /// The underlying source error, if any.
     pub source: Option<Box<dyn std::error::Error + Send + Sync>>,
@@ -172,6 +237,7 @@ impl DynamoDBError {
     #[must_use]
     pub fn new(code: DynamoDBErrorCode) -> Self {
         Self {
+            #[cfg(feature = "http")]
             status_code: code.default_status_code(),
             message: code.as_str().to_owned(),
             code,
@@ -185,6 +251,7 @@ impl DynamoDBError {
     #[must_use]
     pub fn with_message(code: DynamoDBErrorCode, message: impl Into<String>) -> Self {
         Self {
+            #[cfg(feature = "http")]
             status_code: code.default_status_code(),
             message: message.into(),
             code,
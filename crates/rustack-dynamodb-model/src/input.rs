@@ -3,6 +3,12 @@
 //! All input structs use `PascalCase` JSON field naming to match the DynamoDB
 //! wire protocol (`awsJson1_0`). Optional fields are omitted when `None`,
 //! empty `HashMap`s and `Vec`s are omitted to produce minimal JSON payloads.
+//!
+//! Deserialization is as lenient as DynamoDB itself: unknown members (such as
+//! fields added by newer SDKs) are ignored, integer members accept integral
+//! floats like `10.0`, and unrecognised enum values are kept in the enum's
+//! `Unknown` variant so the provider can reject them with a
+//! `ValidationException`.
 
 use std::collections::HashMap;
 
@@ -11,14 +17,64 @@ use serde::{Deserialize, Serialize};
 use crate::{
     attribute_value::AttributeValue,
     types::{
-        AttributeDefinition, AttributeValueUpdate, BillingMode, Condition, ConditionalOperator,
-        ExpectedAttributeValue, GlobalSecondaryIndex, KeySchemaElement, KeysAndAttributes,
-        LocalSecondaryIndex, ProvisionedThroughput, ReturnConsumedCapacity,
+        AttributeDefinition, AttributeValueUpdate, BackupTypeFilter, BillingMode, Condition,
+        ConditionalOperator, ExpectedAttributeValue, ExportFormat, ExportType,
+        GlobalSecondaryIndex, KeySchemaElement, KeysAndAttributes, LocalSecondaryIndex,
+        PointInTimeRecoverySpecification, ProvisionedThroughput, ReturnConsumedCapacity,
         ReturnItemCollectionMetrics, ReturnValue, SSESpecification, Select, StreamSpecification,
         Tag, TimeToLiveSpecification, TransactGetItem, TransactWriteItem, WriteRequest,
     },
 };
 
+/// Deserializers for integer members that also accept integral floats.
+///
+/// Hand-rolled clients sometimes send `"Limit": 10.0`, which DynamoDB accepts
+/// as `10`. Fractional or out-of-range numbers are still rejected.
+pub(crate) mod integer {
+    use serde::{
+        Deserialize, Deserializer,
+        de::{Error, Unexpected},
+    };
+    use serde_json::Number;
+
+    /// Deserialize a required integer member.
+    pub(crate) fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<i64>,
+    {
+        from_number(&Number::deserialize(deserializer)?)
+    }
+
+    /// Deserialize an optional integer member.
+    pub(crate) fn option<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<i64>,
+    {
+        Option::<Number>::deserialize(deserializer)?
+            .map(|number| from_number(&number))
+            .transpose()
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_number<T: TryFrom<i64>, E: Error>(number: &Number) -> Result<T, E> {
+        let value = if let Some(value) = number.as_i64() {
+            value
+        } else {
+            let float = number.as_f64().unwrap_or(f64::NAN);
+            if float.fract() != 0.0
+                || !(-9.223_372_036_854_776e18..9.223_372_036_854_776e18).contains(&float)
+            {
+                return Err(E::invalid_value(Unexpected::Float(float), &"an integer"));
+            }
+            float as i64
+        };
+        T::try_from(value)
+            .map_err(|_| E::invalid_value(Unexpected::Signed(value), &"an integer in range"))
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Table management
 // ---------------------------------------------------------------------------
@@ -112,7 +168,11 @@ pub struct ListTablesInput {
     pub exclusive_start_table_name: Option<String>,
 
     /// The maximum number of table names to return (1--100).
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        deserialize_with = "integer::option",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub limit: Option<i32>,
 }
 
@@ -348,7 +408,11 @@ pub struct QueryInput {
 
     /// The maximum number of items to evaluate (not necessarily the number of
     /// matching items).
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        deserialize_with = "integer::option",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub limit: Option<i32>,
 
     /// The primary key of the first item that this operation will evaluate.
@@ -413,7 +477,11 @@ pub struct ScanInput {
     pub expression_attribute_values: HashMap<String, AttributeValue>,
 
     /// The maximum number of items to evaluate.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        deserialize_with = "integer::option",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub limit: Option<i32>,
 
     /// The primary key of the first item that this operation will evaluate.
@@ -423,12 +491,20 @@ pub struct ScanInput {
 
     /// For a parallel `Scan` request, identifies an individual segment to be
     /// scanned by an application worker.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        deserialize_with = "integer::option",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub segment: Option<i32>,
 
     /// For a parallel `Scan` request, the total number of segments into which
     /// the table is divided.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        deserialize_with = "integer::option",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub total_segments: Option<i32>,
 
     /// The attributes to be returned in the result.
@@ -592,3 +668,313 @@ pub struct DescribeLimitsInput {}
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct DescribeEndpointsInput {}
+
+// ---------------------------------------------------------------------------
+// Exports
+// ---------------------------------------------------------------------------
+
+/// Input for the `ExportTableToPointInTime` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ExportTableToPointInTimeInput {
+    /// The ARN of the table to export.
+    pub table_arn: String,
+    /// The destination S3 bucket.
+    pub s3_bucket: String,
+    /// The account ID that owns the destination bucket.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_bucket_owner: Option<String>,
+    /// The key prefix to write the export under.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_prefix: Option<String>,
+    /// The server-side encryption algorithm for the exported objects.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_sse_algorithm: Option<String>,
+    /// The KMS key used to encrypt the exported objects.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_sse_kms_key_id: Option<String>,
+    /// The point in time (epoch seconds) to export.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub export_time: Option<f64>,
+    /// The data format of the export.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub export_format: Option<ExportFormat>,
+    /// Whether to export a full snapshot or an incremental change set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub export_type: Option<ExportType>,
+    /// An idempotency token for the export.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_token: Option<String>,
+}
+
+/// Input for the `DescribeExport` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DescribeExportInput {
+    /// The ARN of the export.
+    pub export_arn: String,
+}
+
+/// Input for the `ListExports` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ListExportsInput {
+    /// Only list exports of this table.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_arn: Option<String>,
+    /// The maximum number of exports to return.
+    #[serde(
+        default,
+        deserialize_with = "integer::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_results: Option<i32>,
+    /// The pagination token from a previous `ListExports` response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_token: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Backups
+// ---------------------------------------------------------------------------
+
+/// Input for the `CreateBackup` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CreateBackupInput {
+    /// The name of the table to back up.
+    pub table_name: String,
+    /// The name of the backup.
+    pub backup_name: String,
+}
+
+/// Input for the `DescribeBackup` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DescribeBackupInput {
+    /// The ARN of the backup.
+    pub backup_arn: String,
+}
+
+/// Input for the `DeleteBackup` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DeleteBackupInput {
+    /// The ARN of the backup.
+    pub backup_arn: String,
+}
+
+/// Input for the `ListBackups` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ListBackupsInput {
+    /// Only list backups of this table.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_name: Option<String>,
+    /// The maximum number of backups to return.
+    #[serde(
+        default,
+        deserialize_with = "integer::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub limit: Option<i32>,
+    /// Only list backups created at or after this time (epoch seconds).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_range_lower_bound: Option<f64>,
+    /// Only list backups created before this time (epoch seconds).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_range_upper_bound: Option<f64>,
+    /// The `LastEvaluatedBackupArn` from a previous `ListBackups` response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclusive_start_backup_arn: Option<String>,
+    /// Only list backups of this type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_type: Option<BackupTypeFilter>,
+}
+
+/// Input for the `RestoreTableFromBackup` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RestoreTableFromBackupInput {
+    /// The name of the table to create.
+    pub target_table_name: String,
+    /// The ARN of the backup to restore.
+    pub backup_arn: String,
+    /// Billing mode for the restored table, instead of the backup's.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub billing_mode_override: Option<BillingMode>,
+    /// Global secondary indexes for the restored table, instead of the backup's.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub global_secondary_index_override: Option<Vec<GlobalSecondaryIndex>>,
+    /// Local secondary indexes for the restored table, instead of the backup's.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_secondary_index_override: Option<Vec<LocalSecondaryIndex>>,
+    /// Provisioned throughput for the restored table, instead of the backup's.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provisioned_throughput_override: Option<ProvisionedThroughput>,
+    /// Encryption settings for the restored table, instead of the backup's.
+    #[serde(
+        rename = "SSESpecificationOverride",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sse_specification_override: Option<SSESpecification>,
+}
+
+// ---------------------------------------------------------------------------
+// Continuous backups
+// ---------------------------------------------------------------------------
+
+/// Input for the `DescribeContinuousBackups` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DescribeContinuousBackupsInput {
+    /// The name of the table.
+    pub table_name: String,
+}
+
+/// Input for the `UpdateContinuousBackups` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct UpdateContinuousBackupsInput {
+    /// The name of the table.
+    pub table_name: String,
+    /// The point-in-time recovery settings to apply.
+    pub point_in_time_recovery_specification: PointInTimeRecoverySpecification,
+}
+
+/// Input for the `RestoreTableToPointInTime` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RestoreTableToPointInTimeInput {
+    /// The ARN of the table to restore from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_table_arn: Option<String>,
+    /// The name of the table to restore from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_table_name: Option<String>,
+    /// The name of the table to create.
+    pub target_table_name: String,
+    /// Restore to the latest restorable time instead of `RestoreDateTime`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_latest_restorable_time: Option<bool>,
+    /// The time (epoch seconds) to restore to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restore_date_time: Option<f64>,
+    /// Billing mode for the restored table, instead of the source's.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub billing_mode_override: Option<BillingMode>,
+    /// Global secondary indexes for the restored table, instead of the source's.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub global_secondary_index_override: Option<Vec<GlobalSecondaryIndex>>,
+    /// Local secondary indexes for the restored table, instead of the source's.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_secondary_index_override: Option<Vec<LocalSecondaryIndex>>,
+    /// Provisioned throughput for the restored table, instead of the source's.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provisioned_throughput_override: Option<ProvisionedThroughput>,
+    /// Encryption settings for the restored table, instead of the source's.
+    #[serde(
+        rename = "SSESpecificationOverride",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sse_specification_override: Option<SSESpecification>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TransactWriteItem;
+
+    /// Request bodies in the shapes the AWS SDKs and hand-rolled clients
+    /// serialize, each of which DynamoDB accepts.
+    #[test]
+    fn test_should_deserialize_sdk_request_corpus() {
+        // boto3: integer `Limit`, JSON boolean `ScanIndexForward`.
+        let query: QueryInput = serde_json::from_str(
+            r#"{"TableName":"Music","KeyConditionExpression":"Artist = :a",
+                "ExpressionAttributeValues":{":a":{"S":"Acme"}},"Limit":10,
+                "ScanIndexForward":false,"ReturnConsumedCapacity":"TOTAL"}"#,
+        )
+        .unwrap();
+        assert_eq!(query.limit, Some(10));
+        assert_eq!(query.scan_index_forward, Some(false));
+        assert_eq!(
+            query.return_consumed_capacity,
+            Some(ReturnConsumedCapacity::Total)
+        );
+
+        // Go SDK v2: members unknown to this model are ignored.
+        let put: PutItemInput = serde_json::from_str(
+            r#"{"TableName":"Music","Item":{"Artist":{"S":"Acme"}},"ReturnValues":"ALL_OLD",
+                "ReturnValuesOnConditionCheckFailure":"ALL_OLD","SomeFutureMember":{"A":1}}"#,
+        )
+        .unwrap();
+        assert_eq!(put.return_values, Some(ReturnValue::AllOld));
+
+        // Java SDK: parallel scan segments, `ScanIndexForward` omitted.
+        let scan: ScanInput = serde_json::from_str(
+            r#"{"TableName":"Music","Segment":1,"TotalSegments":4,"Select":"COUNT"}"#,
+        )
+        .unwrap();
+        assert_eq!((scan.segment, scan.total_segments), (Some(1), Some(4)));
+        assert_eq!(scan.select, Some(Select::Count));
+
+        // Hand-rolled JS clients: integral floats for integer members.
+        let query: QueryInput = serde_json::from_str(
+            r#"{"TableName":"Music","KeyConditionExpression":"Artist = :a","Limit":10.0}"#,
+        )
+        .unwrap();
+        assert_eq!(query.limit, Some(10));
+        let create: CreateTableInput = serde_json::from_str(
+            r#"{"TableName":"Music",
+                "AttributeDefinitions":[{"AttributeName":"Artist","AttributeType":"S"}],
+                "KeySchema":[{"AttributeName":"Artist","KeyType":"HASH"}],
+                "ProvisionedThroughput":{"ReadCapacityUnits":5.0,"WriteCapacityUnits":5}}"#,
+        )
+        .unwrap();
+        let throughput = create.provisioned_throughput.unwrap();
+        assert_eq!(throughput.read_capacity_units, 5);
+
+        // Newer SDK members nested inside transaction items are ignored too.
+        let items: Vec<TransactWriteItem> = serde_json::from_str(
+            r#"[{"Put":{"TableName":"Music","Item":{"Artist":{"S":"Acme"}},
+                 "ReturnValuesOnConditionCheckFailure":"ALL_OLD","FutureNested":true}}]"#,
+        )
+        .unwrap();
+        assert_eq!(items.len(), 1);
+    }
+
+    #[test]
+    fn test_should_keep_unknown_enum_values() {
+        let put: PutItemInput = serde_json::from_str(
+            r#"{"TableName":"Music","Item":{},"ReturnValues":"all_old",
+                "ReturnItemCollectionMetrics":"FULL","ConditionalOperator":"XOR"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            put.return_values,
+            Some(ReturnValue::Unknown("all_old".to_owned()))
+        );
+        assert_eq!(
+            put.return_item_collection_metrics
+                .as_ref()
+                .map(ReturnItemCollectionMetrics::as_str),
+            Some("FULL")
+        );
+        assert_eq!(
+            serde_json::to_value(&put).unwrap()["ConditionalOperator"],
+            "XOR"
+        );
+    }
+
+    #[test]
+    fn test_should_reject_fractional_integers() {
+        for limit in ["10.5", "\"10\"", "1e300", "3000000000"] {
+            let body = format!(r#"{{"TableName":"Music","Limit":{limit}}}"#);
+            assert!(
+                serde_json::from_str::<ScanInput>(&body).is_err(),
+                "Limit {limit} should be rejected"
+            );
+        }
+    }
+}
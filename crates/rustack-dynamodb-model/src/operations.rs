@@ -64,6 +64,34 @@ pub enum DynamoDBOperation {
     DescribeLimits,
     /// Describe regional endpoints for DynamoDB.
     DescribeEndpoints,
+
+    // Exports
+    /// Export a table snapshot to S3.
+    ExportTableToPointInTime,
+    /// Describe an export.
+    DescribeExport,
+    /// List exports.
+    ListExports,
+
+    // Backups
+    /// Create an on-demand backup of a table.
+    CreateBackup,
+    /// Describe a backup.
+    DescribeBackup,
+    /// Delete a backup.
+    DeleteBackup,
+    /// List backups.
+    ListBackups,
+    /// Create a new table from a backup.
+    RestoreTableFromBackup,
+
+    // Continuous backups
+    /// Describe the continuous backup and point-in-time recovery settings of a table.
+    DescribeContinuousBackups,
+    /// Enable or disable point-in-time recovery for a table.
+    UpdateContinuousBackups,
+    /// Create a new table from a table's point-in-time recovery window.
+    RestoreTableToPointInTime,
 }
 
 impl DynamoDBOperation {
@@ -93,6 +121,17 @@ impl DynamoDBOperation {
             Self::TransactWriteItems => "TransactWriteItems",
             Self::DescribeLimits => "DescribeLimits",
             Self::DescribeEndpoints => "DescribeEndpoints",
+            Self::ExportTableToPointInTime => "ExportTableToPointInTime",
+            Self::DescribeExport => "DescribeExport",
+            Self::ListExports => "ListExports",
+            Self::CreateBackup => "CreateBackup",
+            Self::DescribeBackup => "DescribeBackup",
+            Self::DeleteBackup => "DeleteBackup",
+            Self::ListBackups => "ListBackups",
+            Self::RestoreTableFromBackup => "RestoreTableFromBackup",
+            Self::DescribeContinuousBackups => "DescribeContinuousBackups",
+            Self::UpdateContinuousBackups => "UpdateContinuousBackups",
+            Self::RestoreTableToPointInTime => "RestoreTableToPointInTime",
         }
     }
 
@@ -122,6 +161,17 @@ impl DynamoDBOperation {
             "TransactWriteItems" => Some(Self::TransactWriteItems),
             "DescribeLimits" => Some(Self::DescribeLimits),
             "DescribeEndpoints" => Some(Self::DescribeEndpoints),
+            "ExportTableToPointInTime" => Some(Self::ExportTableToPointInTime),
+            "DescribeExport" => Some(Self::DescribeExport),
+            "ListExports" => Some(Self::ListExports),
+            "CreateBackup" => Some(Self::CreateBackup),
+            "DescribeBackup" => Some(Self::DescribeBackup),
+            "DeleteBackup" => Some(Self::DeleteBackup),
+            "ListBackups" => Some(Self::ListBackups),
+            "RestoreTableFromBackup" => Some(Self::RestoreTableFromBackup),
+            "DescribeContinuousBackups" => Some(Self::DescribeContinuousBackups),
+            "UpdateContinuousBackups" => Some(Self::UpdateContinuousBackups),
+            "RestoreTableToPointInTime" => Some(Self::RestoreTableToPointInTime),
             _ => None,
         }
     }
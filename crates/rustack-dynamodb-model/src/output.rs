@@ -11,8 +11,10 @@ use serde::{Deserialize, Serialize};
 use crate::{
     attribute_value::AttributeValue,
     types::{
-        ConsumedCapacity, ItemCollectionMetrics, ItemResponse, KeysAndAttributes, TableDescription,
-        Tag, TimeToLiveDescription, TimeToLiveSpecification, WriteRequest,
+        BackupDescription, BackupDetails, BackupSummary, ConsumedCapacity,
+        ContinuousBackupsDescription, ExportDescription, ExportSummary, ItemCollectionMetrics,
+        ItemResponse, KeysAndAttributes, TableDescription, Tag, TimeToLiveDescription,
+        TimeToLiveSpecification, WriteRequest,
     },
 };
 
@@ -79,9 +81,10 @@ pub struct ListTablesOutput {
 #[serde(rename_all = "PascalCase")]
 pub struct PutItemOutput {
     /// The attribute values as they appeared before the `PutItem` operation
-    /// (only returned when `ReturnValues` is specified).
-    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
-    pub attributes: HashMap<String, AttributeValue>,
+    /// (only returned when `ReturnValues` is specified). `None` (omitted from
+    /// JSON) when there was no item to return.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<HashMap<String, AttributeValue>>,
 
     /// The capacity units consumed by the operation.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -111,9 +114,10 @@ pub struct GetItemOutput {
 #[serde(rename_all = "PascalCase")]
 pub struct UpdateItemOutput {
     /// The attribute values as they appeared before or after the update
-    /// (depending on the `ReturnValues` setting).
-    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
-    pub attributes: HashMap<String, AttributeValue>,
+    /// (depending on the `ReturnValues` setting). `None` (omitted from JSON)
+    /// when there is nothing to return.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<HashMap<String, AttributeValue>>,
 
     /// The capacity units consumed by the operation.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -129,9 +133,10 @@ pub struct UpdateItemOutput {
 #[serde(rename_all = "PascalCase")]
 pub struct DeleteItemOutput {
     /// The attribute values as they appeared before the deletion (only
-    /// returned when `ReturnValues` is `ALL_OLD`).
-    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
-    pub attributes: HashMap<String, AttributeValue>,
+    /// returned when `ReturnValues` is `ALL_OLD`). `None` (omitted from JSON)
+    /// when no item was deleted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<HashMap<String, AttributeValue>>,
 
     /// The capacity units consumed by the operation.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -163,9 +168,10 @@ pub struct QueryOutput {
     pub scanned_count: i32,
 
     /// The primary key of the item where the query operation stopped. Use this
-    /// value as `ExclusiveStartKey` in a subsequent query to continue.
-    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
-    pub last_evaluated_key: HashMap<String, AttributeValue>,
+    /// value as `ExclusiveStartKey` in a subsequent query to continue. `None`
+    /// (omitted from JSON) on the last page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_evaluated_key: Option<HashMap<String, AttributeValue>>,
 
     /// The capacity units consumed by the operation.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -189,9 +195,10 @@ pub struct ScanOutput {
     pub scanned_count: i32,
 
     /// The primary key of the item where the scan operation stopped. Use this
-    /// value as `ExclusiveStartKey` in a subsequent scan to continue.
-    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
-    pub last_evaluated_key: HashMap<String, AttributeValue>,
+    /// value as `ExclusiveStartKey` in a subsequent scan to continue. `None`
+    /// (omitted from JSON) on the last page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_evaluated_key: Option<HashMap<String, AttributeValue>>,
 
     /// The capacity units consumed by the operation.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -355,3 +362,120 @@ pub struct Endpoint {
     /// The cache period in minutes.
     pub cache_period_in_minutes: i64,
 }
+
+// ---------------------------------------------------------------------------
+// Exports
+// ---------------------------------------------------------------------------
+
+/// Output for the `ExportTableToPointInTime` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ExportTableToPointInTimeOutput {
+    /// The description of the started export.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub export_description: Option<ExportDescription>,
+}
+
+/// Output for the `DescribeExport` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DescribeExportOutput {
+    /// The description of the export.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub export_description: Option<ExportDescription>,
+}
+
+/// Output for the `ListExports` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ListExportsOutput {
+    /// The exports, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub export_summaries: Vec<ExportSummary>,
+    /// The token for the next page, if more exports remain.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_token: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Backups
+// ---------------------------------------------------------------------------
+
+/// Output for the `CreateBackup` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CreateBackupOutput {
+    /// Details of the new backup.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_details: Option<BackupDetails>,
+}
+
+/// Output for the `DescribeBackup` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DescribeBackupOutput {
+    /// The description of the backup.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_description: Option<BackupDescription>,
+}
+
+/// Output for the `DeleteBackup` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DeleteBackupOutput {
+    /// The description of the deleted backup.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_description: Option<BackupDescription>,
+}
+
+/// Output for the `ListBackups` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ListBackupsOutput {
+    /// The backups, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub backup_summaries: Vec<BackupSummary>,
+    /// The ARN of the last backup returned, if more backups remain.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_evaluated_backup_arn: Option<String>,
+}
+
+/// Output for the `RestoreTableFromBackup` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RestoreTableFromBackupOutput {
+    /// The description of the restored table.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_description: Option<TableDescription>,
+}
+
+// ---------------------------------------------------------------------------
+// Continuous backups
+// ---------------------------------------------------------------------------
+
+/// Output for the `DescribeContinuousBackups` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DescribeContinuousBackupsOutput {
+    /// The table's continuous backup settings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuous_backups_description: Option<ContinuousBackupsDescription>,
+}
+
+/// Output for the `UpdateContinuousBackups` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct UpdateContinuousBackupsOutput {
+    /// The table's updated continuous backup settings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuous_backups_description: Option<ContinuousBackupsDescription>,
+}
+
+/// Output for the `RestoreTableToPointInTime` operation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RestoreTableToPointInTimeOutput {
+    /// The description of the restored table.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_description: Option<TableDescription>,
+}
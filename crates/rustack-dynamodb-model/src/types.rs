@@ -383,36 +383,58 @@ impl std::fmt::Display for IndexStatus {
 }
 
 /// Determines what values are returned by write operations.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub enum ReturnValue {
     /// Nothing is returned.
     #[default]
-    #[serde(rename = "NONE")]
     None,
     /// Returns all attributes of the item as they appeared before the operation.
-    #[serde(rename = "ALL_OLD")]
     AllOld,
     /// Returns only the updated attributes as they appeared before the operation.
-    #[serde(rename = "UPDATED_OLD")]
     UpdatedOld,
     /// Returns all attributes of the item as they appear after the operation.
-    #[serde(rename = "ALL_NEW")]
     AllNew,
     /// Returns only the updated attributes as they appear after the operation.
-    #[serde(rename = "UPDATED_NEW")]
     UpdatedNew,
+    /// An unknown value received from the client.
+    Unknown(String),
 }
 
 impl ReturnValue {
+    /// The values DynamoDB accepts.
+    pub const VALUES: &'static [&'static str] =
+        &["NONE", "ALL_OLD", "UPDATED_OLD", "ALL_NEW", "UPDATED_NEW"];
+
     /// Returns the DynamoDB wire-format string representation.
     #[must_use]
-    pub fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             Self::None => "NONE",
             Self::AllOld => "ALL_OLD",
             Self::UpdatedOld => "UPDATED_OLD",
             Self::AllNew => "ALL_NEW",
             Self::UpdatedNew => "UPDATED_NEW",
+            Self::Unknown(s) => s.as_str(),
+        }
+    }
+}
+
+impl Serialize for ReturnValue {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ReturnValue {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        match s.as_str() {
+            "NONE" => Ok(Self::None),
+            "ALL_OLD" => Ok(Self::AllOld),
+            "UPDATED_OLD" => Ok(Self::UpdatedOld),
+            "ALL_NEW" => Ok(Self::AllNew),
+            "UPDATED_NEW" => Ok(Self::UpdatedNew),
+            _ => Ok(Self::Unknown(s)),
         }
     }
 }
@@ -424,28 +446,31 @@ impl std::fmt::Display for ReturnValue {
 }
 
 /// Controls whether consumed capacity information is returned.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub enum ReturnConsumedCapacity {
     /// Return consumed capacity for the table and any indexes involved.
-    #[serde(rename = "INDEXES")]
     Indexes,
     /// Return only the total consumed capacity.
-    #[serde(rename = "TOTAL")]
     Total,
     /// Do not return consumed capacity (default).
     #[default]
-    #[serde(rename = "NONE")]
     None,
+    /// An unknown value received from the client.
+    Unknown(String),
 }
 
 impl ReturnConsumedCapacity {
+    /// The values DynamoDB accepts.
+    pub const VALUES: &'static [&'static str] = &["INDEXES", "TOTAL", "NONE"];
+
     /// Returns the DynamoDB wire-format string representation.
     #[must_use]
-    pub fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             Self::Indexes => "INDEXES",
             Self::Total => "TOTAL",
             Self::None => "NONE",
+            Self::Unknown(s) => s.as_str(),
         }
     }
 
@@ -462,6 +487,24 @@ impl ReturnConsumedCapacity {
     }
 }
 
+impl Serialize for ReturnConsumedCapacity {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ReturnConsumedCapacity {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        match s.as_str() {
+            "INDEXES" => Ok(Self::Indexes),
+            "TOTAL" => Ok(Self::Total),
+            "NONE" => Ok(Self::None),
+            _ => Ok(Self::Unknown(s)),
+        }
+    }
+}
+
 impl std::fmt::Display for ReturnConsumedCapacity {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(self.as_str())
@@ -469,24 +512,28 @@ impl std::fmt::Display for ReturnConsumedCapacity {
 }
 
 /// Controls whether item collection metrics are returned for writes.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub enum ReturnItemCollectionMetrics {
     /// Return item collection size estimates.
-    #[serde(rename = "SIZE")]
     Size,
     /// Do not return item collection metrics (default).
     #[default]
-    #[serde(rename = "NONE")]
     None,
+    /// An unknown value received from the client.
+    Unknown(String),
 }
 
 impl ReturnItemCollectionMetrics {
+    /// The values DynamoDB accepts.
+    pub const VALUES: &'static [&'static str] = &["SIZE", "NONE"];
+
     /// Returns the DynamoDB wire-format string representation.
     #[must_use]
-    pub fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             Self::Size => "SIZE",
             Self::None => "NONE",
+            Self::Unknown(s) => s.as_str(),
         }
     }
 
@@ -497,6 +544,23 @@ impl ReturnItemCollectionMetrics {
     }
 }
 
+impl Serialize for ReturnItemCollectionMetrics {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ReturnItemCollectionMetrics {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        match s.as_str() {
+            "SIZE" => Ok(Self::Size),
+            "NONE" => Ok(Self::None),
+            _ => Ok(Self::Unknown(s)),
+        }
+    }
+}
+
 impl std::fmt::Display for ReturnItemCollectionMetrics {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(self.as_str())
@@ -504,32 +568,58 @@ impl std::fmt::Display for ReturnItemCollectionMetrics {
 }
 
 /// Attributes to retrieve in a `Query` or `Scan` operation.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub enum Select {
     /// All attributes of the item.
     #[default]
-    #[serde(rename = "ALL_ATTRIBUTES")]
     AllAttributes,
     /// All projected attributes (for index queries).
-    #[serde(rename = "ALL_PROJECTED_ATTRIBUTES")]
     AllProjectedAttributes,
     /// Only the attributes specified in `ProjectionExpression`.
-    #[serde(rename = "SPECIFIC_ATTRIBUTES")]
     SpecificAttributes,
     /// Only the count of matching items (no item data).
-    #[serde(rename = "COUNT")]
     Count,
+    /// An unknown value received from the client.
+    Unknown(String),
 }
 
 impl Select {
+    /// The values DynamoDB accepts.
+    pub const VALUES: &'static [&'static str] = &[
+        "ALL_ATTRIBUTES",
+        "ALL_PROJECTED_ATTRIBUTES",
+        "SPECIFIC_ATTRIBUTES",
+        "COUNT",
+    ];
+
     /// Returns the DynamoDB wire-format string representation.
     #[must_use]
-    pub fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             Self::AllAttributes => "ALL_ATTRIBUTES",
             Self::AllProjectedAttributes => "ALL_PROJECTED_ATTRIBUTES",
             Self::SpecificAttributes => "SPECIFIC_ATTRIBUTES",
             Self::Count => "COUNT",
+            Self::Unknown(s) => s.as_str(),
+        }
+    }
+}
+
+impl Serialize for Select {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Select {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        match s.as_str() {
+            "ALL_ATTRIBUTES" => Ok(Self::AllAttributes),
+            "ALL_PROJECTED_ATTRIBUTES" => Ok(Self::AllProjectedAttributes),
+            "SPECIFIC_ATTRIBUTES" => Ok(Self::SpecificAttributes),
+            "COUNT" => Ok(Self::Count),
+            _ => Ok(Self::Unknown(s)),
         }
     }
 }
@@ -541,24 +631,45 @@ impl std::fmt::Display for Select {
 }
 
 /// Logical operator for combining multiple conditions (legacy API).
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub enum ConditionalOperator {
     /// All conditions must be true.
     #[default]
-    #[serde(rename = "AND")]
     And,
     /// At least one condition must be true.
-    #[serde(rename = "OR")]
     Or,
+    /// An unknown value received from the client.
+    Unknown(String),
 }
 
 impl ConditionalOperator {
+    /// The values DynamoDB accepts.
+    pub const VALUES: &'static [&'static str] = &["AND", "OR"];
+
     /// Returns the DynamoDB wire-format string representation.
     #[must_use]
-    pub fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             Self::And => "AND",
             Self::Or => "OR",
+            Self::Unknown(s) => s.as_str(),
+        }
+    }
+}
+
+impl Serialize for ConditionalOperator {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ConditionalOperator {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        match s.as_str() {
+            "AND" => Ok(Self::And),
+            "OR" => Ok(Self::Or),
+            _ => Ok(Self::Unknown(s)),
         }
     }
 }
@@ -699,8 +810,10 @@ pub struct BillingModeSummary {
 #[serde(rename_all = "PascalCase")]
 pub struct ProvisionedThroughput {
     /// The maximum number of strongly consistent reads per second.
+    #[serde(deserialize_with = "crate::input::integer::deserialize")]
     pub read_capacity_units: i64,
     /// The maximum number of writes per second.
+    #[serde(deserialize_with = "crate::input::integer::deserialize")]
     pub write_capacity_units: i64,
 }
 
@@ -1396,6 +1509,524 @@ pub type ExpressionAttributeNames = HashMap<String, String>;
 /// Expression attribute values mapping (`:value` placeholders to attribute values).
 pub type ExpressionAttributeValues = HashMap<String, AttributeValue>;
 
+// ---------------------------------------------------------------------------
+// Structs - Exports
+// ---------------------------------------------------------------------------
+
+/// Status of a table export.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ExportStatus {
+    /// The export is still writing data to S3.
+    #[serde(rename = "IN_PROGRESS")]
+    InProgress,
+    /// The export finished successfully.
+    #[serde(rename = "COMPLETED")]
+    Completed,
+    /// The export failed.
+    #[serde(rename = "FAILED")]
+    Failed,
+}
+
+impl ExportStatus {
+    /// Returns the DynamoDB wire-format string representation.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::InProgress => "IN_PROGRESS",
+            Self::Completed => "COMPLETED",
+            Self::Failed => "FAILED",
+        }
+    }
+}
+
+impl std::fmt::Display for ExportStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Data format of an export.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ExportFormat {
+    /// DynamoDB JSON, one item per line.
+    #[default]
+    #[serde(rename = "DYNAMODB_JSON")]
+    DynamodbJson,
+    /// Amazon Ion text.
+    #[serde(rename = "ION")]
+    Ion,
+}
+
+impl ExportFormat {
+    /// Returns the DynamoDB wire-format string representation.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DynamodbJson => "DYNAMODB_JSON",
+            Self::Ion => "ION",
+        }
+    }
+}
+
+impl std::fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Whether an export is a full snapshot or an incremental change set.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ExportType {
+    /// A full snapshot of the table.
+    #[default]
+    #[serde(rename = "FULL_EXPORT")]
+    FullExport,
+    /// The changes made during a time window.
+    #[serde(rename = "INCREMENTAL_EXPORT")]
+    IncrementalExport,
+}
+
+impl ExportType {
+    /// Returns the DynamoDB wire-format string representation.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::FullExport => "FULL_EXPORT",
+            Self::IncrementalExport => "INCREMENTAL_EXPORT",
+        }
+    }
+}
+
+impl std::fmt::Display for ExportType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Full description of a table export (output from `DescribeExport`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ExportDescription {
+    /// The ARN of the export.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub export_arn: Option<String>,
+    /// The current status of the export.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub export_status: Option<ExportStatus>,
+    /// The date and time (epoch seconds) when the export started.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<f64>,
+    /// The date and time (epoch seconds) when the export finished.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<f64>,
+    /// The S3 key of the manifest listing the export's data files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub export_manifest: Option<String>,
+    /// The ARN of the exported table.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_arn: Option<String>,
+    /// The unique ID of the exported table.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_id: Option<String>,
+    /// The point in time (epoch seconds) the export reflects.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub export_time: Option<f64>,
+    /// The client token supplied with the export request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_token: Option<String>,
+    /// The destination S3 bucket.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_bucket: Option<String>,
+    /// The account ID that owns the destination bucket.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_bucket_owner: Option<String>,
+    /// The key prefix the export was written under.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_prefix: Option<String>,
+    /// The server-side encryption algorithm for the exported objects.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_sse_algorithm: Option<String>,
+    /// The KMS key used to encrypt the exported objects.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_sse_kms_key_id: Option<String>,
+    /// The error code of a failed export.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_code: Option<String>,
+    /// The error message of a failed export.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_message: Option<String>,
+    /// The data format of the export.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub export_format: Option<ExportFormat>,
+    /// The billable size of the table at export time, in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub billed_size_bytes: Option<i64>,
+    /// The number of items exported.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item_count: Option<i64>,
+    /// Whether this is a full or incremental export.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub export_type: Option<ExportType>,
+}
+
+/// Summary of a table export (element of `ListExports` output).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ExportSummary {
+    /// The ARN of the export.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub export_arn: Option<String>,
+    /// The current status of the export.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub export_status: Option<ExportStatus>,
+    /// Whether this is a full or incremental export.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub export_type: Option<ExportType>,
+}
+
+// ---------------------------------------------------------------------------
+// Structs - Backups
+// ---------------------------------------------------------------------------
+
+/// Status of an on-demand backup.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum BackupStatus {
+    /// The backup is being created.
+    #[serde(rename = "CREATING")]
+    Creating,
+    /// The backup has been deleted.
+    #[serde(rename = "DELETED")]
+    Deleted,
+    /// The backup can be restored.
+    #[default]
+    #[serde(rename = "AVAILABLE")]
+    Available,
+}
+
+impl BackupStatus {
+    /// Returns the DynamoDB wire-format string representation.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Creating => "CREATING",
+            Self::Deleted => "DELETED",
+            Self::Available => "AVAILABLE",
+        }
+    }
+}
+
+impl std::fmt::Display for BackupStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Who created a backup.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum BackupType {
+    /// An on-demand backup created with `CreateBackup`.
+    #[default]
+    #[serde(rename = "USER")]
+    User,
+    /// A backup DynamoDB created automatically.
+    #[serde(rename = "SYSTEM")]
+    System,
+    /// A backup created through AWS Backup.
+    #[serde(rename = "AWS_BACKUP")]
+    AwsBackup,
+}
+
+impl BackupType {
+    /// Returns the DynamoDB wire-format string representation.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::User => "USER",
+            Self::System => "SYSTEM",
+            Self::AwsBackup => "AWS_BACKUP",
+        }
+    }
+}
+
+impl std::fmt::Display for BackupType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Backup type filter for `ListBackups`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum BackupTypeFilter {
+    /// Only on-demand backups.
+    #[default]
+    #[serde(rename = "USER")]
+    User,
+    /// Only system backups.
+    #[serde(rename = "SYSTEM")]
+    System,
+    /// Only AWS Backup backups.
+    #[serde(rename = "AWS_BACKUP")]
+    AwsBackup,
+    /// Every backup.
+    #[serde(rename = "ALL")]
+    All,
+}
+
+impl BackupTypeFilter {
+    /// Returns the DynamoDB wire-format string representation.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::User => "USER",
+            Self::System => "SYSTEM",
+            Self::AwsBackup => "AWS_BACKUP",
+            Self::All => "ALL",
+        }
+    }
+
+    /// Whether a backup of type `backup_type` passes this filter.
+    #[must_use]
+    pub fn matches(&self, backup_type: &BackupType) -> bool {
+        match self {
+            Self::All => true,
+            Self::User => *backup_type == BackupType::User,
+            Self::System => *backup_type == BackupType::System,
+            Self::AwsBackup => *backup_type == BackupType::AwsBackup,
+        }
+    }
+}
+
+impl std::fmt::Display for BackupTypeFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Details of a backup (output from `CreateBackup`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct BackupDetails {
+    /// The ARN of the backup.
+    pub backup_arn: String,
+    /// The name of the backup.
+    pub backup_name: String,
+    /// The size of the backup in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_size_bytes: Option<i64>,
+    /// The current status of the backup.
+    pub backup_status: BackupStatus,
+    /// Who created the backup.
+    pub backup_type: BackupType,
+    /// The date and time (epoch seconds) the backup was created.
+    pub backup_creation_date_time: f64,
+    /// The date and time (epoch seconds) a system backup expires.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_expiry_date_time: Option<f64>,
+}
+
+/// The source table of a backup.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SourceTableDetails {
+    /// The name of the table.
+    pub table_name: String,
+    /// The unique ID of the table.
+    pub table_id: String,
+    /// The ARN of the table.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_arn: Option<String>,
+    /// The size of the table in bytes when it was backed up.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_size_bytes: Option<i64>,
+    /// The table's primary key schema.
+    pub key_schema: Vec<KeySchemaElement>,
+    /// The date and time (epoch seconds) the table was created.
+    pub table_creation_date_time: f64,
+    /// The table's provisioned throughput.
+    pub provisioned_throughput: ProvisionedThroughput,
+    /// The number of items in the table when it was backed up.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item_count: Option<i64>,
+    /// The table's billing mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub billing_mode: Option<BillingMode>,
+}
+
+/// Features of the source table that a backup preserves.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SourceTableFeatureDetails {
+    /// The table's local secondary indexes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub local_secondary_indexes: Vec<LocalSecondaryIndex>,
+    /// The table's global secondary indexes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub global_secondary_indexes: Vec<GlobalSecondaryIndex>,
+    /// The table's stream settings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_description: Option<StreamSpecification>,
+    /// The table's server-side encryption settings.
+    #[serde(rename = "SSEDescription", skip_serializing_if = "Option::is_none")]
+    pub sse_description: Option<SSEDescription>,
+}
+
+/// Full description of a backup (output from `DescribeBackup`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct BackupDescription {
+    /// Details of the backup.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_details: Option<BackupDetails>,
+    /// Details of the table that was backed up.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_table_details: Option<SourceTableDetails>,
+    /// Features of the table that was backed up.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_table_feature_details: Option<SourceTableFeatureDetails>,
+}
+
+/// Summary of a backup (element of `ListBackups` output).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct BackupSummary {
+    /// The name of the backed-up table.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_name: Option<String>,
+    /// The unique ID of the backed-up table.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_id: Option<String>,
+    /// The ARN of the backed-up table.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_arn: Option<String>,
+    /// The ARN of the backup.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_arn: Option<String>,
+    /// The name of the backup.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_name: Option<String>,
+    /// The date and time (epoch seconds) the backup was created.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_creation_date_time: Option<f64>,
+    /// The date and time (epoch seconds) a system backup expires.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_expiry_date_time: Option<f64>,
+    /// The current status of the backup.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_status: Option<BackupStatus>,
+    /// Who created the backup.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_type: Option<BackupType>,
+    /// The size of the backup in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_size_bytes: Option<i64>,
+}
+
+// ---------------------------------------------------------------------------
+// Structs - Continuous Backups
+// ---------------------------------------------------------------------------
+
+/// Whether continuous backups are enabled for a table.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ContinuousBackupsStatus {
+    /// Continuous backups are enabled.
+    #[default]
+    #[serde(rename = "ENABLED")]
+    Enabled,
+    /// Continuous backups are disabled.
+    #[serde(rename = "DISABLED")]
+    Disabled,
+}
+
+impl ContinuousBackupsStatus {
+    /// Returns the DynamoDB wire-format string representation.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Enabled => "ENABLED",
+            Self::Disabled => "DISABLED",
+        }
+    }
+}
+
+impl std::fmt::Display for ContinuousBackupsStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Whether point-in-time recovery is enabled for a table.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum PointInTimeRecoveryStatus {
+    /// Point-in-time recovery is enabled.
+    #[serde(rename = "ENABLED")]
+    Enabled,
+    /// Point-in-time recovery is disabled.
+    #[default]
+    #[serde(rename = "DISABLED")]
+    Disabled,
+}
+
+impl PointInTimeRecoveryStatus {
+    /// Returns the DynamoDB wire-format string representation.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Enabled => "ENABLED",
+            Self::Disabled => "DISABLED",
+        }
+    }
+}
+
+impl std::fmt::Display for PointInTimeRecoveryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Point-in-time recovery settings (input to `UpdateContinuousBackups`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PointInTimeRecoverySpecification {
+    /// Whether point-in-time recovery should be enabled.
+    pub point_in_time_recovery_enabled: bool,
+    /// How many days of history to keep (1-35).
+    #[serde(
+        default,
+        deserialize_with = "crate::input::integer::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub recovery_period_in_days: Option<i32>,
+}
+
+/// Point-in-time recovery state of a table.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PointInTimeRecoveryDescription {
+    /// Whether point-in-time recovery is enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub point_in_time_recovery_status: Option<PointInTimeRecoveryStatus>,
+    /// How many days of history are kept.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_period_in_days: Option<i32>,
+    /// The earliest time (epoch seconds) the table can be restored to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub earliest_restorable_date_time: Option<f64>,
+    /// The latest time (epoch seconds) the table can be restored to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_restorable_date_time: Option<f64>,
+}
+
+/// Continuous backup state of a table (output from `DescribeContinuousBackups`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContinuousBackupsDescription {
+    /// Whether continuous backups are enabled.
+    pub continuous_backups_status: ContinuousBackupsStatus,
+    /// The table's point-in-time recovery state.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub point_in_time_recovery_description: Option<PointInTimeRecoveryDescription>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1786,4 +2417,22 @@ mod tests {
         assert_eq!(desc.index_status, parsed.index_status);
         assert_eq!(desc.item_count, parsed.item_count);
     }
+
+    #[test]
+    fn test_should_serialize_export_description_wire_names() {
+        let desc = ExportDescription {
+            export_arn: Some("arn:aws:dynamodb:us-east-1:000000000000:table/T/export/1".to_owned()),
+            export_status: Some(ExportStatus::InProgress),
+            s3_sse_algorithm: Some("AES256".to_owned()),
+            export_format: Some(ExportFormat::DynamodbJson),
+            export_type: Some(ExportType::FullExport),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&desc).expect("serialize ExportDescription");
+        assert!(json.contains(r#""ExportStatus":"IN_PROGRESS""#));
+        assert!(json.contains(r#""S3SseAlgorithm":"AES256""#));
+        assert!(json.contains(r#""ExportFormat":"DYNAMODB_JSON""#));
+        assert!(json.contains(r#""ExportType":"FULL_EXPORT""#));
+        assert!(!json.contains("ItemCount"));
+    }
 }
@@ -0,0 +1,429 @@
+//! S3 server access log delivery.
+//!
+//! When a bucket has a logging target configured (`PutBucketLogging`), each
+//! request against it is rendered as an S3 server access log line and buffered
+//! per target. A buffer is written as a single object into the target bucket
+//! when it reaches [`S3Config::s3_access_log_max_records`], on each tick of the
+//! flusher started by [`RustackS3::spawn_access_log_flusher`], or on demand via
+//! [`RustackS3::flush_access_logs`].
+//!
+//! Log objects are written directly through the provider rather than over
+//! HTTP, so they never produce access log records of their own. Delivering
+//! into a bucket that also has logging enabled, including a bucket that logs
+//! to itself, therefore cannot recurse.
+//!
+//! [`S3Config::s3_access_log_max_records`]: crate::config::S3Config::s3_access_log_max_records
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use rustack_core::{AccessLogEntry, RequestScope};
+use rustack_s3_model::{
+    input::PutObjectInput,
+    request::StreamingBlob,
+    types::{LoggingEnabled, PartitionDateSource},
+};
+use tracing::{debug, warn};
+
+use crate::{provider::RustackS3, state::bucket::S3Bucket};
+
+/// Where a batch of log records is delivered.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct LogTarget {
+    /// Namespace of the source bucket; the log object is written from it.
+    scope: RequestScope,
+    source_bucket: String,
+    source_region: String,
+    target_bucket: String,
+    target_prefix: String,
+    /// `None` for the simple key format, otherwise the partition date source.
+    partition: Option<PartitionDateSource>,
+}
+
+impl LogTarget {
+    fn new(scope: RequestScope, source: &S3Bucket, logging: &LoggingEnabled) -> Self {
+        let partition = logging
+            .target_object_key_format
+            .as_ref()
+            .and_then(|format| format.partitioned_prefix.as_ref())
+            .map(|prefix| prefix.partition_date_source.clone().unwrap_or_default());
+        Self {
+            scope,
+            source_bucket: source.name.clone(),
+            source_region: source.region.clone(),
+            target_bucket: logging.target_bucket.clone(),
+            target_prefix: logging.target_prefix.clone(),
+            partition,
+        }
+    }
+
+    /// Build the log object key for a batch.
+    ///
+    /// Simple: `[prefix]YYYY-MM-DD-hh-mm-ss-UniqueString`. Partitioned:
+    /// `[prefix]account/region/bucket/YYYY/MM/DD/YYYY-MM-DD-hh-mm-ss-UniqueString`.
+    fn object_key(&self, records: &[LogRecord], delivered_at: DateTime<Utc>) -> String {
+        let unique = unique_string();
+        let stamp = delivered_at.format("%Y-%m-%d-%H-%M-%S");
+        match &self.partition {
+            None => format!("{}{stamp}-{unique}", self.target_prefix),
+            Some(source) => {
+                let partition_time = match source {
+                    PartitionDateSource::EventTime => {
+                        records.iter().map(|r| r.time).min().unwrap_or(delivered_at)
+                    }
+                    PartitionDateSource::DeliveryTime => delivered_at,
+                };
+                format!(
+                    "{}{}/{}/{}/{}/{stamp}-{unique}",
+                    self.target_prefix,
+                    self.scope.account,
+                    self.source_region,
+                    self.source_bucket,
+                    partition_time.format("%Y/%m/%d"),
+                )
+            }
+        }
+    }
+}
+
+/// A single rendered log line.
+#[derive(Debug, Clone)]
+struct LogRecord {
+    time: DateTime<Utc>,
+    line: String,
+}
+
+/// Pending server access log records, grouped by delivery target.
+#[derive(Debug, Default)]
+pub struct AccessLogBuffer {
+    pending: DashMap<LogTarget, Vec<LogRecord>>,
+}
+
+impl AccessLogBuffer {
+    /// Create an empty buffer.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Total number of records waiting to be delivered.
+    #[must_use]
+    pub fn pending_records(&self) -> usize {
+        self.pending.iter().map(|batch| batch.len()).sum()
+    }
+
+    /// Drop all pending records.
+    pub fn clear(&self) {
+        self.pending.clear();
+    }
+
+    /// Buffer a record, returning `true` once the target holds `max_records`.
+    fn push(&self, target: LogTarget, record: LogRecord, max_records: usize) -> bool {
+        let mut batch = self.pending.entry(target).or_default();
+        batch.push(record);
+        batch.len() >= max_records
+    }
+
+    /// Remove and return the pending records for one target.
+    fn take(&self, target: &LogTarget) -> Option<Vec<LogRecord>> {
+        self.pending.remove(target).map(|(_, records)| records)
+    }
+
+    /// Remove and return all pending records.
+    fn take_all(&self) -> Vec<(LogTarget, Vec<LogRecord>)> {
+        let targets: Vec<LogTarget> = self.pending.iter().map(|e| e.key().clone()).collect();
+        targets
+            .into_iter()
+            .filter_map(|target| self.pending.remove(&target))
+            .collect()
+    }
+}
+
+impl RustackS3 {
+    /// Buffer a server access log record for a completed request.
+    ///
+    /// Requests against buckets without a logging configuration are ignored.
+    /// When the target's buffer is full, delivery is spawned on the current
+    /// Tokio runtime.
+    pub fn record_access_log(&self, entry: &AccessLogEntry) {
+        let Some(bucket_name) = entry.bucket.as_deref() else {
+            return;
+        };
+        let source = self
+            .bucket_scope(bucket_name)
+            .map_or_else(|| self.clone(), |scope| self.scoped(&scope));
+        let (target, owner_id) = {
+            let Ok(bucket) = source.state.get_bucket(bucket_name) else {
+                return;
+            };
+            let Some(logging) = bucket.logging.read().clone() else {
+                return;
+            };
+            (
+                LogTarget::new(source.scope.clone(), &bucket, &logging),
+                bucket.owner.id.clone(),
+            )
+        };
+
+        let mut entry = entry.clone();
+        entry.bucket_owner = Some(owner_id);
+        let record = LogRecord {
+            time: entry.time,
+            line: entry.to_s3_text(),
+        };
+
+        let full = self.access_logs.push(
+            target.clone(),
+            record,
+            self.config.s3_access_log_max_records,
+        );
+        if full {
+            if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+                let provider = self.clone();
+                runtime.spawn(async move {
+                    if let Some(records) = provider.access_logs.take(&target) {
+                        provider.deliver_access_logs(&target, records).await;
+                    }
+                });
+            }
+        }
+    }
+
+    /// Deliver all buffered server access logs to their target buckets.
+    ///
+    /// Returns the number of log objects written. Records whose target bucket
+    /// no longer exists are dropped.
+    pub async fn flush_access_logs(&self) -> usize {
+        let mut written = 0;
+        for (target, records) in self.access_logs.take_all() {
+            if self.deliver_access_logs(&target, records).await {
+                written += 1;
+            }
+        }
+        written
+    }
+
+    /// Spawn a task that flushes buffered server access logs periodically,
+    /// every [`S3Config::s3_access_log_flush_interval_secs`] seconds.
+    ///
+    /// The task runs until aborted through the returned handle, or for the
+    /// lifetime of the Tokio runtime if the handle is dropped.
+    ///
+    /// [`S3Config::s3_access_log_flush_interval_secs`]: crate::config::S3Config::s3_access_log_flush_interval_secs
+    #[must_use]
+    pub fn spawn_access_log_flusher(&self) -> tokio::task::JoinHandle<()> {
+        let provider = self.clone();
+        let period = Duration::from_secs(self.config.s3_access_log_flush_interval_secs.max(1));
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(period);
+            // The first tick completes immediately; skip it.
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                provider.flush_access_logs().await;
+            }
+        })
+    }
+
+    /// Write one batch of records as a log object in the target bucket.
+    async fn deliver_access_logs(&self, target: &LogTarget, records: Vec<LogRecord>) -> bool {
+        let key = target.object_key(&records, Utc::now());
+        let mut body = String::new();
+        for record in &records {
+            body.push_str(&record.line);
+            body.push('\n');
+        }
+
+        let input = PutObjectInput {
+            bucket: target.target_bucket.clone(),
+            key: key.clone(),
+            body: Some(StreamingBlob::new(Bytes::from(body))),
+            content_type: Some("text/plain".to_owned()),
+            ..PutObjectInput::default()
+        };
+        match self.scoped(&target.scope).handle_put_object(input).await {
+            Ok(_) => {
+                debug!(
+                    source_bucket = %target.source_bucket,
+                    target_bucket = %target.target_bucket,
+                    %key,
+                    records = records.len(),
+                    "delivered server access logs"
+                );
+                true
+            }
+            Err(err) => {
+                warn!(
+                    source_bucket = %target.source_bucket,
+                    target_bucket = %target.target_bucket,
+                    error = %err,
+                    records = records.len(),
+                    "failed to deliver server access logs"
+                );
+                false
+            }
+        }
+    }
+}
+
+/// Generate the 16-character uppercase hex suffix of a log object key.
+fn unique_string() -> String {
+    let id = uuid::Uuid::new_v4()
+        .simple()
+        .to_string()
+        .to_ascii_uppercase();
+    id[..16].to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use rustack_s3_model::types::{PartitionedPrefix, TargetObjectKeyFormat};
+
+    use super::*;
+    use crate::{config::S3Config, state::object::Owner};
+
+    fn provider_with_buckets(names: &[&str]) -> RustackS3 {
+        let provider = RustackS3::new(S3Config::default());
+        for name in names {
+            provider
+                .state()
+                .create_bucket((*name).to_owned(), "us-east-1".to_owned(), Owner::default())
+                .unwrap_or_else(|e| panic!("create failed: {e}"));
+        }
+        provider
+    }
+
+    fn enable_logging(provider: &RustackS3, source: &str, logging: LoggingEnabled) {
+        let bucket = provider
+            .state()
+            .get_bucket(source)
+            .unwrap_or_else(|e| panic!("get failed: {e}"));
+        *bucket.logging.write() = Some(logging);
+    }
+
+    fn logging_to(target: &str, prefix: &str) -> LoggingEnabled {
+        LoggingEnabled {
+            target_bucket: target.to_owned(),
+            target_prefix: prefix.to_owned(),
+            ..LoggingEnabled::default()
+        }
+    }
+
+    fn request(bucket: &str, key: &str) -> AccessLogEntry {
+        let mut entry = AccessLogEntry::new("s3", "req-1", "GET", &format!("/{bucket}/{key}"));
+        entry.operation = Some("GetObject");
+        entry.bucket = Some(bucket.to_owned());
+        entry.key = Some(key.to_owned());
+        entry.status = 200;
+        entry
+    }
+
+    fn log_keys(provider: &RustackS3, bucket: &str) -> Vec<String> {
+        let bucket = provider
+            .state()
+            .get_bucket(bucket)
+            .unwrap_or_else(|e| panic!("get failed: {e}"));
+        let listing = bucket.objects.read().list_objects("", "", "", 1000);
+        listing.objects.into_iter().map(|o| o.key).collect()
+    }
+
+    #[tokio::test]
+    async fn test_should_deliver_buffered_records_on_flush() {
+        let provider = provider_with_buckets(&["source", "logs"]);
+        enable_logging(&provider, "source", logging_to("logs", "access/"));
+
+        provider.record_access_log(&request("source", "a.txt"));
+        provider.record_access_log(&request("source", "b.txt"));
+        assert_eq!(provider.access_logs.pending_records(), 2);
+
+        assert_eq!(provider.flush_access_logs().await, 1);
+        assert_eq!(provider.access_logs.pending_records(), 0);
+
+        let keys = log_keys(&provider, "logs");
+        assert_eq!(keys.len(), 1);
+        let name = keys[0].strip_prefix("access/").expect("prefixed key");
+        // YYYY-MM-DD-hh-mm-ss-UniqueString
+        assert_eq!(name.len(), 19 + 1 + 16);
+        assert_eq!(name.matches('-').count(), 6);
+
+        let body = provider
+            .storage()
+            .read_object("logs", &keys[0], "null", None)
+            .await
+            .expect("log object body");
+        let text = String::from_utf8(body.to_vec()).expect("utf-8 log");
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.contains(" source ["));
+        assert!(text.contains("REST.GET.GetObject a.txt"));
+        assert!(text.starts_with(&Owner::default().id));
+    }
+
+    #[tokio::test]
+    async fn test_should_ignore_buckets_without_logging() {
+        let provider = provider_with_buckets(&["source"]);
+        provider.record_access_log(&request("source", "a.txt"));
+        provider.record_access_log(&AccessLogEntry::new("s3", "req-2", "GET", "/"));
+        assert_eq!(provider.access_logs.pending_records(), 0);
+        assert_eq!(provider.flush_access_logs().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_should_not_recurse_when_logging_to_self() {
+        let provider = provider_with_buckets(&["source"]);
+        enable_logging(&provider, "source", logging_to("source", "logs/"));
+
+        provider.record_access_log(&request("source", "a.txt"));
+        assert_eq!(provider.flush_access_logs().await, 1);
+        // Writing the log object must not have buffered any new records.
+        assert_eq!(provider.access_logs.pending_records(), 0);
+        assert_eq!(provider.flush_access_logs().await, 0);
+        assert_eq!(log_keys(&provider, "source").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_should_use_partitioned_key_format() {
+        let provider = provider_with_buckets(&["source", "logs"]);
+        let mut logging = logging_to("logs", "p/");
+        logging.target_object_key_format = Some(TargetObjectKeyFormat {
+            partitioned_prefix: Some(PartitionedPrefix {
+                partition_date_source: Some(PartitionDateSource::EventTime),
+            }),
+            simple_prefix: None,
+        });
+        enable_logging(&provider, "source", logging);
+
+        provider.record_access_log(&request("source", "a.txt"));
+        provider.flush_access_logs().await;
+
+        let keys = log_keys(&provider, "logs");
+        assert_eq!(keys.len(), 1);
+        assert!(keys[0].starts_with("p/000000000000/us-east-1/source/"));
+    }
+
+    #[tokio::test]
+    async fn test_should_deliver_when_buffer_is_full() {
+        let config = S3Config::builder().s3_access_log_max_records(2).build();
+        let provider = RustackS3::new(config);
+        for name in ["source", "logs"] {
+            provider
+                .state()
+                .create_bucket(name.to_owned(), "us-east-1".to_owned(), Owner::default())
+                .unwrap_or_else(|e| panic!("create failed: {e}"));
+        }
+        enable_logging(&provider, "source", logging_to("logs", ""));
+
+        provider.record_access_log(&request("source", "a.txt"));
+        provider.record_access_log(&request("source", "b.txt"));
+
+        for _ in 0..100 {
+            if !log_keys(&provider, "logs").is_empty() {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(log_keys(&provider, "logs").len(), 1);
+        assert_eq!(provider.access_logs.pending_records(), 0);
+    }
+}
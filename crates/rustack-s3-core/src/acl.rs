@@ -0,0 +1,778 @@
+//! Access control lists of buckets and objects.
+//!
+//! A request sets an ACL in one of three mutually exclusive ways: a canned
+//! ACL (`x-amz-acl`), explicit grants in the `x-amz-grant-*` headers, or an
+//! `AccessControlPolicy` document in the body of `PutBucketAcl` and
+//! `PutObjectAcl`. [`AclRequest::resolve`] validates them into an
+//! [`AclUpdate`]. A canned ACL is stored as such and expanded into the grants
+//! it stands for when the ACL is read, so `GetBucketAcl` and `GetObjectAcl`
+//! report the same grant set S3 does.
+
+use rustack_s3_model::types::{
+    AccessControlPolicy, Grant as ModelGrant, Grantee as ModelGrantee,
+    Permission as ModelPermission, Type as GranteeType,
+};
+
+use crate::{
+    error::S3ServiceError,
+    state::{
+        bucket::S3Bucket,
+        object::{CannedAcl, Grant, Grantee, Owner, Permission},
+    },
+};
+
+/// URI of the group of all users, authenticated or not.
+pub const ALL_USERS_URI: &str = "http://acs.amazonaws.com/groups/global/AllUsers";
+
+/// URI of the group of all authenticated AWS users.
+pub const AUTHENTICATED_USERS_URI: &str =
+    "http://acs.amazonaws.com/groups/global/AuthenticatedUsers";
+
+/// URI of the group S3 server access logs are delivered as.
+pub const LOG_DELIVERY_URI: &str = "http://acs.amazonaws.com/groups/s3/LogDelivery";
+
+/// Canonical user ID of Amazon EC2, granted `READ` by `aws-exec-read`.
+const EC2_CANONICAL_ID: &str = "6aa5a366c34c1cbe25dc49211496e913e0351eb0e8c37aa3477e40942ec6b97c";
+
+/// The ACL parameters of a request.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AclRequest<'a> {
+    /// The canned ACL (`x-amz-acl`).
+    pub canned: Option<&'a str>,
+    /// Grantees of `FULL_CONTROL` (`x-amz-grant-full-control`).
+    pub grant_full_control: Option<&'a str>,
+    /// Grantees of `READ` (`x-amz-grant-read`).
+    pub grant_read: Option<&'a str>,
+    /// Grantees of `READ_ACP` (`x-amz-grant-read-acp`).
+    pub grant_read_acp: Option<&'a str>,
+    /// Grantees of `WRITE` (`x-amz-grant-write`).
+    pub grant_write: Option<&'a str>,
+    /// Grantees of `WRITE_ACP` (`x-amz-grant-write-acp`).
+    pub grant_write_acp: Option<&'a str>,
+    /// The `AccessControlPolicy` body.
+    pub policy: Option<&'a AccessControlPolicy>,
+}
+
+/// The ACL a request sets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AclUpdate {
+    /// A canned ACL.
+    Canned(CannedAcl),
+    /// Explicit grants, replacing any canned ACL.
+    Grants(Vec<Grant>),
+}
+
+impl AclUpdate {
+    /// Store the ACL into the `acl` and `grants` fields of a bucket or
+    /// object.
+    pub fn apply(self, acl: &mut CannedAcl, grants: &mut Option<Vec<Grant>>) {
+        match self {
+            Self::Canned(canned) => {
+                *acl = canned;
+                *grants = None;
+            }
+            Self::Grants(explicit) => {
+                *acl = CannedAcl::Private;
+                *grants = Some(explicit);
+            }
+        }
+    }
+
+    /// Whether the ACL grants nothing but `FULL_CONTROL` to `owner`, the
+    /// only ACL a bucket with ACLs disabled accepts.
+    #[must_use]
+    pub fn is_owner_full_control(&self, owner: &Owner) -> bool {
+        match self {
+            Self::Canned(acl) => {
+                matches!(acl, CannedAcl::Private | CannedAcl::BucketOwnerFullControl)
+            }
+            Self::Grants(grants) => {
+                !grants.is_empty()
+                    && grants.iter().all(|grant| {
+                        grant.permission == Permission::FullControl
+                            && matches!(&grant.grantee, Grantee::CanonicalUser { id, .. } if *id == owner.id)
+                    })
+            }
+        }
+    }
+}
+
+/// Check that `bucket` accepts the ACL a request sets.
+///
+/// # Errors
+///
+/// Returns [`S3ServiceError::AccessControlListNotSupported`] if the bucket
+/// has ACLs disabled and the ACL grants more than `FULL_CONTROL` to the
+/// bucket owner.
+pub fn check_acl_supported(
+    bucket: &S3Bucket,
+    update: Option<&AclUpdate>,
+) -> Result<(), S3ServiceError> {
+    match update {
+        Some(update) if bucket.acls_disabled() && !update.is_owner_full_control(&bucket.owner) => {
+            Err(S3ServiceError::AccessControlListNotSupported)
+        }
+        _ => Ok(()),
+    }
+}
+
+impl AclRequest<'_> {
+    /// Validate the request's ACL parameters into the ACL it sets.
+    ///
+    /// Returns `Ok(None)` if the request sets no ACL. `owner` names the
+    /// canonical user whose display name is reported for grants to its ID.
+    ///
+    /// # Errors
+    ///
+    /// - [`S3ServiceError::InvalidRequest`] if more than one way of setting the ACL is used.
+    /// - [`S3ServiceError::InvalidArgument`] for an unknown canned ACL, a malformed grant header,
+    ///   or an unknown group URI.
+    /// - [`S3ServiceError::MalformedAcl`] for a grant in the body without a grantee, permission, or
+    ///   grantee identifier.
+    /// - [`S3ServiceError::UnresolvableGrantByEmailAddress`] for a grantee identified by email
+    ///   address.
+    pub fn resolve(&self, owner: &Owner) -> Result<Option<AclUpdate>, S3ServiceError> {
+        let headers = [
+            (self.grant_full_control, Permission::FullControl),
+            (self.grant_read, Permission::Read),
+            (self.grant_read_acp, Permission::ReadAcp),
+            (self.grant_write, Permission::Write),
+            (self.grant_write_acp, Permission::WriteAcp),
+        ];
+        let has_headers = headers.iter().any(|(value, _)| value.is_some());
+
+        if self.canned.is_some() && (has_headers || self.policy.is_some()) {
+            return Err(S3ServiceError::InvalidRequest {
+                message: "Specifying both Canned ACLs and Header Grants is not allowed".to_owned(),
+            });
+        }
+        if has_headers && self.policy.is_some() {
+            return Err(S3ServiceError::InvalidRequest {
+                message: "This request does not support content".to_owned(),
+            });
+        }
+
+        if let Some(canned) = self.canned {
+            let canned = canned
+                .parse()
+                .map_err(|_| S3ServiceError::InvalidArgument {
+                    message: "Invalid canned ACL".to_owned(),
+                })?;
+            return Ok(Some(AclUpdate::Canned(canned)));
+        }
+        if let Some(policy) = self.policy {
+            let grants = policy
+                .grants
+                .iter()
+                .map(|grant| grant_from_model(grant, owner))
+                .collect::<Result<_, _>>()?;
+            return Ok(Some(AclUpdate::Grants(grants)));
+        }
+        if !has_headers {
+            return Ok(None);
+        }
+
+        let mut grants = Vec::new();
+        for (value, permission) in headers {
+            let Some(value) = value else {
+                continue;
+            };
+            for grantee in parse_grant_header(value, owner)? {
+                grants.push(Grant {
+                    grantee,
+                    permission,
+                });
+            }
+        }
+        Ok(Some(AclUpdate::Grants(grants)))
+    }
+}
+
+/// The grants an ACL stands for: the explicit grants if set, else the
+/// expansion of the canned ACL.
+///
+/// `owner` owns the resource, and `bucket_owner` the bucket holding it,
+/// which the `bucket-owner-*` canned ACLs grant access to.
+#[must_use]
+pub fn effective_grants(
+    acl: CannedAcl,
+    grants: Option<&[Grant]>,
+    owner: &Owner,
+    bucket_owner: &Owner,
+) -> Vec<Grant> {
+    if let Some(grants) = grants {
+        return grants.to_vec();
+    }
+
+    let user = |owner: &Owner| Grantee::CanonicalUser {
+        id: owner.id.clone(),
+        display_name: Some(owner.display_name.clone()),
+    };
+    let group = |uri: &str| Grantee::Group {
+        uri: uri.to_owned(),
+    };
+    let mut expanded = vec![(user(owner), Permission::FullControl)];
+    let other_bucket_owner = bucket_owner.id != owner.id;
+    match acl {
+        CannedAcl::PublicRead => expanded.push((group(ALL_USERS_URI), Permission::Read)),
+        CannedAcl::PublicReadWrite => {
+            expanded.push((group(ALL_USERS_URI), Permission::Read));
+            expanded.push((group(ALL_USERS_URI), Permission::Write));
+        }
+        CannedAcl::AuthenticatedRead => {
+            expanded.push((group(AUTHENTICATED_USERS_URI), Permission::Read));
+        }
+        CannedAcl::AwsExecRead => expanded.push((
+            Grantee::CanonicalUser {
+                id: EC2_CANONICAL_ID.to_owned(),
+                display_name: None,
+            },
+            Permission::Read,
+        )),
+        CannedAcl::BucketOwnerRead if other_bucket_owner => {
+            expanded.push((user(bucket_owner), Permission::Read));
+        }
+        CannedAcl::BucketOwnerFullControl if other_bucket_owner => {
+            expanded.push((user(bucket_owner), Permission::FullControl));
+        }
+        CannedAcl::Private | CannedAcl::BucketOwnerRead | CannedAcl::BucketOwnerFullControl => {}
+        CannedAcl::LogDeliveryWrite => {
+            expanded.push((group(LOG_DELIVERY_URI), Permission::Write));
+            expanded.push((group(LOG_DELIVERY_URI), Permission::ReadAcp));
+        }
+    }
+    expanded
+        .into_iter()
+        .map(|(grantee, permission)| Grant {
+            grantee,
+            permission,
+        })
+        .collect()
+}
+
+/// Whether `grants` give the `AllUsers` group `permission`, directly or
+/// through `FULL_CONTROL`.
+#[must_use]
+pub fn grants_all_users(grants: &[Grant], permission: Permission) -> bool {
+    grants.iter().any(|grant| {
+        matches!(&grant.grantee, Grantee::Group { uri } if uri == ALL_USERS_URI)
+            && (grant.permission == permission || grant.permission == Permission::FullControl)
+    })
+}
+
+/// Whether `grants` give the `AllUsers` or `AuthenticatedUsers` group any
+/// permission, which makes the resource public.
+#[must_use]
+pub fn grants_public_access(grants: &[Grant]) -> bool {
+    grants.iter().any(|grant| {
+        matches!(&grant.grantee, Grantee::Group { uri }
+            if uri == ALL_USERS_URI || uri == AUTHENTICATED_USERS_URI)
+    })
+}
+
+/// Convert grants to their model DTOs.
+#[must_use]
+pub fn to_model_grants(grants: &[Grant]) -> Vec<ModelGrant> {
+    grants
+        .iter()
+        .map(|grant| {
+            let (r#type, id, display_name, uri, email_address) = match &grant.grantee {
+                Grantee::CanonicalUser { id, display_name } => (
+                    GranteeType::CanonicalUser,
+                    Some(id.clone()),
+                    display_name.clone(),
+                    None,
+                    None,
+                ),
+                Grantee::Group { uri } => (GranteeType::Group, None, None, Some(uri.clone()), None),
+                Grantee::Email { email } => (
+                    GranteeType::AmazonCustomerByEmail,
+                    None,
+                    None,
+                    None,
+                    Some(email.clone()),
+                ),
+            };
+            ModelGrant {
+                grantee: Some(ModelGrantee {
+                    display_name,
+                    email_address,
+                    id,
+                    r#type,
+                    uri,
+                }),
+                permission: Some(ModelPermission::from(grant.permission.as_str())),
+            }
+        })
+        .collect()
+}
+
+/// Convert a grant of an `AccessControlPolicy` body.
+fn grant_from_model(grant: &ModelGrant, owner: &Owner) -> Result<Grant, S3ServiceError> {
+    let (Some(grantee), Some(permission)) = (&grant.grantee, &grant.permission) else {
+        return Err(S3ServiceError::MalformedAcl);
+    };
+    let permission = permission
+        .as_str()
+        .parse()
+        .map_err(|_| S3ServiceError::MalformedAcl)?;
+    let grantee = match grantee.r#type {
+        GranteeType::CanonicalUser => {
+            let id = grantee.id.as_deref().ok_or(S3ServiceError::MalformedAcl)?;
+            canonical_user(id, grantee.display_name.as_deref(), owner)
+        }
+        GranteeType::Group => group(grantee.uri.as_deref().ok_or(S3ServiceError::MalformedAcl)?)?,
+        GranteeType::AmazonCustomerByEmail => {
+            return Err(S3ServiceError::UnresolvableGrantByEmailAddress);
+        }
+    };
+    Ok(Grant {
+        grantee,
+        permission,
+    })
+}
+
+/// Parse the grantees of an `x-amz-grant-*` header, a comma-separated list
+/// of `id="..."`, `uri="..."` and `emailAddress="..."` entries.
+fn parse_grant_header(value: &str, owner: &Owner) -> Result<Vec<Grantee>, S3ServiceError> {
+    value
+        .split(',')
+        .map(|entry| {
+            let (kind, grantee) =
+                entry
+                    .trim()
+                    .split_once('=')
+                    .ok_or_else(|| S3ServiceError::InvalidArgument {
+                        message: "Argument format not recognized".to_owned(),
+                    })?;
+            let grantee = grantee.trim().trim_matches('"');
+            match kind.trim() {
+                "id" => Ok(canonical_user(grantee, None, owner)),
+                "uri" => group(grantee),
+                "emailAddress" => Err(S3ServiceError::UnresolvableGrantByEmailAddress),
+                _ => Err(S3ServiceError::InvalidArgument {
+                    message: "Argument format not recognized".to_owned(),
+                }),
+            }
+        })
+        .collect()
+}
+
+/// A canonical user grantee, named after `owner` if it has the same ID.
+fn canonical_user(id: &str, display_name: Option<&str>, owner: &Owner) -> Grantee {
+    let display_name = display_name
+        .map(str::to_owned)
+        .or_else(|| (id == owner.id).then(|| owner.display_name.clone()));
+    Grantee::CanonicalUser {
+        id: id.to_owned(),
+        display_name,
+    }
+}
+
+/// A group grantee, which must be one of the predefined S3 groups.
+fn group(uri: &str) -> Result<Grantee, S3ServiceError> {
+    if ![ALL_USERS_URI, AUTHENTICATED_USERS_URI, LOG_DELIVERY_URI].contains(&uri) {
+        return Err(S3ServiceError::InvalidArgument {
+            message: "Invalid group uri".to_owned(),
+        });
+    }
+    Ok(Grantee::Group {
+        uri: uri.to_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rustack_s3_model::types::Owner as ModelOwner;
+
+    use super::*;
+
+    fn headers<'a>(read: &'a str, full_control: Option<&'a str>) -> AclRequest<'a> {
+        AclRequest {
+            grant_read: Some(read),
+            grant_full_control: full_control,
+            ..AclRequest::default()
+        }
+    }
+
+    #[test]
+    fn test_should_parse_grant_headers() {
+        let owner = Owner::default();
+        let update = headers(
+            &format!(r#"uri="{ALL_USERS_URI}", id="1234""#),
+            Some(&format!("id={}", owner.id)),
+        )
+        .resolve(&owner)
+        .expect("valid grants");
+        assert_eq!(
+            update,
+            Some(AclUpdate::Grants(vec![
+                Grant {
+                    grantee: Grantee::CanonicalUser {
+                        id: owner.id.clone(),
+                        display_name: Some(owner.display_name.clone()),
+                    },
+                    permission: Permission::FullControl,
+                },
+                Grant {
+                    grantee: Grantee::Group {
+                        uri: ALL_USERS_URI.to_owned(),
+                    },
+                    permission: Permission::Read,
+                },
+                Grant {
+                    grantee: Grantee::CanonicalUser {
+                        id: "1234".to_owned(),
+                        display_name: None,
+                    },
+                    permission: Permission::Read,
+                },
+            ]))
+        );
+        assert_eq!(AclRequest::default().resolve(&owner).expect("no ACL"), None);
+    }
+
+    #[test]
+    fn test_should_reject_invalid_acl_requests() {
+        let owner = Owner::default();
+        let cases = [
+            (
+                AclRequest {
+                    canned: Some("public-read"),
+                    ..headers("id=1", None)
+                },
+                "InvalidRequest",
+            ),
+            (
+                headers(r#"emailAddress="a@example.com""#, None),
+                "UnresolvableGrantByEmailAddress",
+            ),
+            (
+                headers(r#"uri="http://example.com/group""#, None),
+                "InvalidArgument",
+            ),
+            (headers("1234", None), "InvalidArgument"),
+            (
+                AclRequest {
+                    canned: Some("everyone"),
+                    ..AclRequest::default()
+                },
+                "InvalidArgument",
+            ),
+        ];
+        for (request, code) in cases {
+            let err = request.resolve(&owner).expect_err("invalid ACL request");
+            assert_eq!(err.into_s3_error().code.as_str(), code);
+        }
+    }
+
+    #[test]
+    fn test_should_convert_access_control_policy() {
+        let owner = Owner::default();
+        let grant = |r#type: GranteeType, id: Option<&str>, email: Option<&str>| ModelGrant {
+            grantee: Some(ModelGrantee {
+                display_name: Some("someone".to_owned()),
+                email_address: email.map(str::to_owned),
+                id: id.map(str::to_owned),
+                r#type,
+                uri: None,
+            }),
+            permission: Some(ModelPermission::WriteAcp),
+        };
+        let policy = |grants| AccessControlPolicy {
+            grants,
+            owner: Some(ModelOwner::default()),
+        };
+
+        let accepted = policy(vec![grant(GranteeType::CanonicalUser, Some("1234"), None)]);
+        let update = AclRequest {
+            policy: Some(&accepted),
+            ..AclRequest::default()
+        }
+        .resolve(&owner)
+        .expect("valid policy");
+        assert_eq!(
+            update,
+            Some(AclUpdate::Grants(vec![Grant {
+                grantee: Grantee::CanonicalUser {
+                    id: "1234".to_owned(),
+                    display_name: Some("someone".to_owned()),
+                },
+                permission: Permission::WriteAcp,
+            }]))
+        );
+
+        for (rejected, code) in [
+            (
+                policy(vec![grant(GranteeType::CanonicalUser, None, None)]),
+                "MalformedACLError",
+            ),
+            (
+                policy(vec![grant(
+                    GranteeType::AmazonCustomerByEmail,
+                    None,
+                    Some("a@example.com"),
+                )]),
+                "UnresolvableGrantByEmailAddress",
+            ),
+        ] {
+            let err = AclRequest {
+                policy: Some(&rejected),
+                ..AclRequest::default()
+            }
+            .resolve(&owner)
+            .expect_err("invalid policy");
+            assert_eq!(err.into_s3_error().code.as_str(), code);
+        }
+    }
+
+    #[test]
+    fn test_should_expand_canned_acls() {
+        let owner = Owner::default();
+        let bucket_owner = Owner {
+            id: "bucket-owner".to_owned(),
+            display_name: "bucket".to_owned(),
+        };
+        let expand = |acl, bucket_owner: &Owner| {
+            effective_grants(acl, None, &owner, bucket_owner)
+                .into_iter()
+                .map(|grant| grant.permission.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(expand(CannedAcl::Private, &owner), ["FULL_CONTROL"]);
+        assert_eq!(
+            expand(CannedAcl::PublicReadWrite, &owner),
+            ["FULL_CONTROL", "READ", "WRITE"]
+        );
+        assert_eq!(
+            expand(CannedAcl::LogDeliveryWrite, &owner),
+            ["FULL_CONTROL", "WRITE", "READ_ACP"]
+        );
+        assert_eq!(expand(CannedAcl::BucketOwnerRead, &owner), ["FULL_CONTROL"]);
+        assert_eq!(
+            expand(CannedAcl::BucketOwnerFullControl, &bucket_owner),
+            ["FULL_CONTROL", "FULL_CONTROL"]
+        );
+
+        let public = effective_grants(CannedAcl::PublicRead, None, &owner, &owner);
+        assert!(grants_all_users(&public, Permission::Read));
+        assert!(!grants_all_users(&public, Permission::Write));
+        let explicit = [Grant {
+            grantee: Grantee::Group {
+                uri: ALL_USERS_URI.to_owned(),
+            },
+            permission: Permission::FullControl,
+        }];
+        let grants = effective_grants(CannedAcl::Private, Some(&explicit), &owner, &owner);
+        assert!(grants_all_users(&grants, Permission::Write));
+    }
+
+    fn legacy_provider() -> crate::RustackS3 {
+        crate::RustackS3::new(crate::config::S3Config {
+            s3_legacy_object_ownership: true,
+            ..crate::config::S3Config::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_should_round_trip_grants_through_acl_operations() {
+        use rustack_s3_model::{
+            input::{GetBucketAclInput, GetObjectAclInput, PutBucketAclInput, PutObjectInput},
+            request::StreamingBlob,
+            types::BucketCannedACL,
+        };
+
+        let client = crate::embedded::S3TestClient::with_provider(legacy_provider());
+        let provider = client.provider();
+        client.create_bucket("acl").await.expect("create bucket");
+        provider
+            .handle_put_object(PutObjectInput {
+                bucket: "acl".to_owned(),
+                key: "k".to_owned(),
+                body: Some(StreamingBlob::new("data")),
+                grant_read: Some(format!(r#"uri="{AUTHENTICATED_USERS_URI}""#)),
+                grant_read_acp: Some(r#"id="1234""#.to_owned()),
+                ..Default::default()
+            })
+            .await
+            .expect("put with grants");
+        let acl = provider
+            .handle_get_object_acl(GetObjectAclInput {
+                bucket: "acl".to_owned(),
+                key: "k".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .expect("get object acl");
+        let grants: Vec<_> = acl
+            .grants
+            .iter()
+            .map(|g| {
+                let grantee = g.grantee.as_ref().expect("grantee");
+                (
+                    grantee.r#type.as_str(),
+                    grantee.id.as_deref().or(grantee.uri.as_deref()),
+                    g.permission.as_ref().map(ModelPermission::as_str),
+                )
+            })
+            .collect();
+        assert_eq!(
+            grants,
+            [
+                ("Group", Some(AUTHENTICATED_USERS_URI), Some("READ")),
+                ("CanonicalUser", Some("1234"), Some("READ_ACP")),
+            ]
+        );
+
+        provider
+            .handle_put_bucket_acl(PutBucketAclInput {
+                bucket: "acl".to_owned(),
+                acl: Some(BucketCannedACL::PublicReadWrite),
+                ..Default::default()
+            })
+            .await
+            .expect("put bucket acl");
+        let acl = provider
+            .handle_get_bucket_acl(GetBucketAclInput {
+                bucket: "acl".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .expect("get bucket acl");
+        let uris: Vec<_> = acl
+            .grants
+            .iter()
+            .filter_map(|g| {
+                g.grantee
+                    .as_ref()
+                    .and_then(|grantee| grantee.uri.as_deref())
+            })
+            .collect();
+        assert_eq!(uris, [ALL_USERS_URI, ALL_USERS_URI]);
+    }
+
+    #[tokio::test]
+    async fn test_should_reject_acls_when_bucket_owner_enforced() {
+        use rustack_s3_model::{
+            error::S3ErrorCode,
+            input::{CreateBucketInput, PutBucketAclInput, PutObjectAclInput, PutObjectInput},
+            types::{BucketCannedACL, ObjectCannedACL},
+        };
+
+        let client = crate::embedded::S3TestClient::new();
+        let provider = client.provider();
+        client.create_bucket("owned").await.expect("create bucket");
+        let put = |acl: &str| PutObjectInput {
+            bucket: "owned".to_owned(),
+            key: "k".to_owned(),
+            acl: Some(ObjectCannedACL::from(acl)),
+            ..Default::default()
+        };
+
+        let err = provider
+            .handle_put_object(put("public-read"))
+            .await
+            .expect_err("public object ACL");
+        assert_eq!(err.code, S3ErrorCode::AccessControlListNotSupported);
+        provider
+            .handle_put_object(put("bucket-owner-full-control"))
+            .await
+            .expect("owner-only object ACL");
+        let err = provider
+            .handle_put_object_acl(PutObjectAclInput {
+                bucket: "owned".to_owned(),
+                key: "k".to_owned(),
+                grant_read: Some(format!("uri={ALL_USERS_URI}")),
+                ..Default::default()
+            })
+            .await
+            .expect_err("object grants");
+        assert_eq!(err.code, S3ErrorCode::AccessControlListNotSupported);
+        let err = provider
+            .handle_put_bucket_acl(PutBucketAclInput {
+                bucket: "owned".to_owned(),
+                acl: Some(BucketCannedACL::PublicRead),
+                ..Default::default()
+            })
+            .await
+            .expect_err("public bucket ACL");
+        assert_eq!(err.code, S3ErrorCode::AccessControlListNotSupported);
+
+        let err = provider
+            .handle_create_bucket(CreateBucketInput {
+                bucket: "public".to_owned(),
+                acl: Some(BucketCannedACL::PublicRead),
+                ..Default::default()
+            })
+            .await
+            .expect_err("public bucket");
+        assert_eq!(err.code, S3ErrorCode::InvalidBucketAclWithObjectOwnership);
+    }
+
+    #[tokio::test]
+    async fn test_should_report_owner_only_acl_once_acls_are_disabled() {
+        use rustack_s3_model::{
+            error::S3ErrorCode,
+            input::{
+                GetBucketOwnershipControlsInput, GetObjectAclInput,
+                PutBucketOwnershipControlsInput, PutObjectInput,
+            },
+            types::{ObjectCannedACL, ObjectOwnership, OwnershipControls, OwnershipControlsRule},
+        };
+
+        let provider = legacy_provider();
+        let client = crate::embedded::S3TestClient::with_provider(provider.clone());
+        client.create_bucket("legacy").await.expect("create bucket");
+        let err = provider
+            .handle_get_bucket_ownership_controls(GetBucketOwnershipControlsInput {
+                bucket: "legacy".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .expect_err("no ownership controls");
+        assert_eq!(err.code, S3ErrorCode::OwnershipControlsNotFoundError);
+        provider
+            .handle_put_object(PutObjectInput {
+                bucket: "legacy".to_owned(),
+                key: "k".to_owned(),
+                acl: Some(ObjectCannedACL::PublicRead),
+                ..Default::default()
+            })
+            .await
+            .expect("public object ACL");
+
+        provider
+            .handle_put_bucket_ownership_controls(PutBucketOwnershipControlsInput {
+                bucket: "legacy".to_owned(),
+                ownership_controls: OwnershipControls {
+                    rules: vec![OwnershipControlsRule {
+                        object_ownership: ObjectOwnership::BucketOwnerEnforced,
+                    }],
+                },
+                ..Default::default()
+            })
+            .await
+            .expect("enforce bucket owner");
+        let acl = provider
+            .handle_get_object_acl(GetObjectAclInput {
+                bucket: "legacy".to_owned(),
+                key: "k".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .expect("get object acl");
+        assert_eq!(acl.grants.len(), 1);
+        assert_eq!(
+            acl.grants[0]
+                .permission
+                .as_ref()
+                .map(ModelPermission::as_str),
+            Some("FULL_CONTROL")
+        );
+        assert_eq!(
+            acl.grants[0].grantee.as_ref().and_then(|g| g.id.as_deref()),
+            Some(Owner::default().id.as_str())
+        );
+    }
+}
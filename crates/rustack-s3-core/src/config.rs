@@ -4,9 +4,14 @@
 //! Configuration values are loaded from environment variables, matching
 //! LocalStack conventions for S3-specific settings.
 
+use rustack_core::config;
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
+/// Default cap on a request body: 5 GiB, the largest single-request upload
+/// S3 accepts.
+const DEFAULT_MAX_REQUEST_BODY_BYTES: u64 = 5 * 1024 * 1024 * 1024;
+
 /// S3 service configuration.
 ///
 /// All fields have sensible defaults matching LocalStack behavior. Configuration
@@ -22,6 +27,7 @@ use typed_builder::TypedBuilder;
 /// assert!(config.s3_virtual_hosting);
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, TypedBuilder)]
+#[allow(clippy::struct_excessive_bools)]
 #[serde(rename_all = "camelCase")]
 pub struct S3Config {
     /// Bind address for the gateway (e.g. `"0.0.0.0:4566"`).
@@ -44,6 +50,70 @@ pub struct S3Config {
     #[builder(default = 524_288)]
     pub s3_max_memory_object_size: usize,
 
+    /// Directory for objects spilled to disk. The OS temp directory if unset.
+    #[builder(default)]
+    pub s3_spillover_dir: Option<String>,
+
+    /// Cap on the total bytes of objects spilled to disk. Writes past the cap
+    /// fail with `InsufficientStorage`. Unlimited if unset.
+    #[builder(default)]
+    pub s3_max_disk_bytes: Option<u64>,
+
+    /// Seconds between periodic deliveries of buffered server access logs.
+    #[builder(default = 60)]
+    pub s3_access_log_flush_interval_secs: u64,
+
+    /// Buffered server access log records per target that trigger an
+    /// immediate delivery.
+    #[builder(default = 1000)]
+    pub s3_access_log_max_records: usize,
+
+    /// Whether bucket names and user metadata are validated against the
+    /// current AWS rules. When `false`, legacy bucket names (uppercase
+    /// letters, underscores, up to 255 characters) are accepted and the 2 KB
+    /// user metadata limit is not enforced.
+    #[builder(default = true)]
+    pub s3_strict_validation: bool,
+
+    /// Directory to record S3 request/response fixtures into. Recording is
+    /// off if unset.
+    #[builder(default)]
+    pub s3_record_dir: Option<String>,
+
+    /// Directory to replay S3 fixtures from instead of calling the handler.
+    /// Takes precedence over `s3_record_dir`. Replay is off if unset.
+    #[builder(default)]
+    pub s3_replay_dir: Option<String>,
+
+    /// Request headers compared when replaying: empty for none, `*` for all
+    /// recorded headers, or a comma-separated list of header names.
+    #[builder(default)]
+    pub s3_replay_match_headers: String,
+
+    /// Whether objects that failed a scrub are still served. When `false`,
+    /// `GetObject` on such an object fails with `InternalError`.
+    #[builder(default = false)]
+    pub s3_serve_corrupt_objects: bool,
+
+    /// Largest request body accepted, in bytes on the wire. Larger uploads
+    /// are rejected with `EntityTooLarge` before they are buffered. Defaults
+    /// to 5 GiB, the S3 single-upload limit; unlimited if unset.
+    #[builder(default = Some(DEFAULT_MAX_REQUEST_BODY_BYTES))]
+    pub s3_max_request_body_bytes: Option<u64>,
+
+    /// Whether new buckets get the legacy `ObjectWriter` object ownership,
+    /// with ACLs enabled. When `false`, buckets created without an
+    /// `x-amz-object-ownership` header default to `BucketOwnerEnforced`,
+    /// which disables ACLs, as new AWS buckets do.
+    #[builder(default = false)]
+    pub s3_legacy_object_ownership: bool,
+
+    /// Latency injected into matching requests, as a comma-separated rule
+    /// list such as `GetObject:200ms±50,PutObject:p(0.01)=5s`. Empty for
+    /// none. See `rustack_s3_http::latency` for the syntax.
+    #[builder(default)]
+    pub s3_latency_rules: String,
+
     /// Default AWS region for this S3 service instance.
     #[builder(default = String::from("us-east-1"))]
     pub default_region: String,
@@ -69,6 +139,18 @@ impl Default for S3Config {
             s3_domain: String::from("s3.localhost.localstack.cloud"),
             s3_skip_signature_validation: true,
             s3_max_memory_object_size: 524_288,
+            s3_spillover_dir: None,
+            s3_max_disk_bytes: None,
+            s3_access_log_flush_interval_secs: 60,
+            s3_access_log_max_records: 1000,
+            s3_strict_validation: true,
+            s3_record_dir: None,
+            s3_replay_dir: None,
+            s3_replay_match_headers: String::new(),
+            s3_serve_corrupt_objects: false,
+            s3_max_request_body_bytes: Some(DEFAULT_MAX_REQUEST_BODY_BYTES),
+            s3_legacy_object_ownership: false,
+            s3_latency_rules: String::new(),
             default_region: String::from("us-east-1"),
             log_level: String::from("info"),
             persistence: false,
@@ -80,7 +162,9 @@ impl Default for S3Config {
 impl S3Config {
     /// Load configuration from environment variables.
     ///
-    /// Reads the following environment variables (falling back to defaults):
+    /// Reads the following environment variables, falling back to the
+    /// `[s3]` and `[gateway]` keys of the installed configuration file and
+    /// then to defaults:
     ///
     /// | Variable | Default |
     /// |----------|---------|
@@ -89,6 +173,19 @@ impl S3Config {
     /// | `S3_DOMAIN` | `s3.localhost.localstack.cloud` |
     /// | `S3_SKIP_SIGNATURE_VALIDATION` | `true` |
     /// | `S3_MAX_MEMORY_OBJECT_SIZE` | `524288` |
+    /// | `S3_SPILLOVER_THRESHOLD_BYTES` | alias of `S3_MAX_MEMORY_OBJECT_SIZE`, taking precedence |
+    /// | `S3_SPILLOVER_DIR` | OS temp directory |
+    /// | `S3_MAX_DISK_BYTES` | unlimited (`0`) |
+    /// | `S3_ACCESS_LOG_FLUSH_INTERVAL` | `60` |
+    /// | `S3_ACCESS_LOG_MAX_RECORDS` | `1000` |
+    /// | `S3_STRICT_VALIDATION` | `true` |
+    /// | `S3_RECORD_DIR` | unset (no recording) |
+    /// | `S3_REPLAY_DIR` | unset (no replay) |
+    /// | `S3_REPLAY_MATCH_HEADERS` | empty (headers not compared) |
+    /// | `S3_SERVE_CORRUPT_OBJECTS` | `false` |
+    /// | `S3_MAX_REQUEST_BODY_BYTES` | `5368709120` (`0` for unlimited) |
+    /// | `S3_LEGACY_OBJECT_OWNERSHIP` | `false` |
+    /// | `S3_LATENCY_RULES` | empty (no injected latency) |
     /// | `DEFAULT_REGION` | `us-east-1` |
     /// | `LOG_LEVEL` | `info` |
     /// | `PERSISTENCE` | `false` |
@@ -106,33 +203,82 @@ impl S3Config {
     pub fn from_env() -> Self {
         let mut config = Self::default();
 
-        if let Ok(v) = std::env::var("GATEWAY_LISTEN") {
+        if let Some(v) = config::var("GATEWAY_LISTEN") {
             config.gateway_listen = v;
         }
-        if let Ok(v) = std::env::var("S3_VIRTUAL_HOSTING") {
+        if let Some(v) = config::var("S3_VIRTUAL_HOSTING") {
             config.s3_virtual_hosting = parse_bool(&v);
         }
-        if let Ok(v) = std::env::var("S3_DOMAIN") {
+        if let Some(v) = config::var("S3_DOMAIN") {
             config.s3_domain = v;
         }
-        if let Ok(v) = std::env::var("S3_SKIP_SIGNATURE_VALIDATION") {
+        if let Some(v) = config::var("S3_SKIP_SIGNATURE_VALIDATION") {
             config.s3_skip_signature_validation = parse_bool(&v);
         }
-        if let Ok(v) = std::env::var("S3_MAX_MEMORY_OBJECT_SIZE") {
+        if let Some(v) = config::var("S3_MAX_MEMORY_OBJECT_SIZE") {
             if let Ok(n) = v.parse::<usize>() {
                 config.s3_max_memory_object_size = n;
             }
         }
-        if let Ok(v) = std::env::var("DEFAULT_REGION") {
+        if let Some(v) = config::var("S3_SPILLOVER_THRESHOLD_BYTES") {
+            if let Ok(n) = v.parse::<usize>() {
+                config.s3_max_memory_object_size = n;
+            }
+        }
+        if let Some(v) = config::var("S3_SPILLOVER_DIR") {
+            config.s3_spillover_dir = Some(v).filter(|dir| !dir.is_empty());
+        }
+        if let Some(v) = config::var("S3_MAX_DISK_BYTES") {
+            if let Ok(n) = v.parse::<u64>() {
+                config.s3_max_disk_bytes = Some(n).filter(|&n| n > 0);
+            }
+        }
+        if let Some(v) = config::var("S3_ACCESS_LOG_FLUSH_INTERVAL") {
+            if let Ok(n) = v.parse::<u64>() {
+                config.s3_access_log_flush_interval_secs = n;
+            }
+        }
+        if let Some(v) = config::var("S3_ACCESS_LOG_MAX_RECORDS") {
+            if let Ok(n) = v.parse::<usize>() {
+                config.s3_access_log_max_records = n;
+            }
+        }
+        if let Some(v) = config::var("S3_STRICT_VALIDATION") {
+            config.s3_strict_validation = parse_bool(&v);
+        }
+        if let Some(v) = config::var("S3_RECORD_DIR") {
+            config.s3_record_dir = Some(v).filter(|dir| !dir.is_empty());
+        }
+        if let Some(v) = config::var("S3_REPLAY_DIR") {
+            config.s3_replay_dir = Some(v).filter(|dir| !dir.is_empty());
+        }
+        if let Some(v) = config::var("S3_REPLAY_MATCH_HEADERS") {
+            config.s3_replay_match_headers = v;
+        }
+        if let Some(v) = config::var("S3_SERVE_CORRUPT_OBJECTS") {
+            config.s3_serve_corrupt_objects = parse_bool(&v);
+        }
+        if let Some(v) = config::var("S3_MAX_REQUEST_BODY_BYTES") {
+            if let Ok(n) = v.parse::<u64>() {
+                config.s3_max_request_body_bytes = Some(n).filter(|&n| n > 0);
+            }
+        }
+        if let Some(v) = config::var("S3_LEGACY_OBJECT_OWNERSHIP") {
+            config.s3_legacy_object_ownership = parse_bool(&v);
+        }
+        if let Some(v) = config::var("S3_LATENCY_RULES") {
+            config.s3_latency_rules = v;
+        }
+        if let Some(v) = config::var("DEFAULT_REGION") {
             config.default_region = v;
         }
-        if let Ok(v) = std::env::var("LOG_LEVEL") {
+        if let Some(v) = config::var("LOG_LEVEL") {
             config.log_level = v;
         }
-        if let Ok(v) = std::env::var("PERSISTENCE") {
+        if let Some(v) = config::var("PERSISTENCE") {
             config.persistence = parse_bool(&v);
         }
-        if let Ok(v) = std::env::var("DATA_DIR") {
+        if let Some(v) = config::var("DATA_DIR") {
             config.data_dir = v;
         }
 
@@ -157,6 +303,21 @@ mod tests {
         assert_eq!(config.s3_domain, "s3.localhost.localstack.cloud");
         assert!(config.s3_skip_signature_validation);
         assert_eq!(config.s3_max_memory_object_size, 524_288);
+        assert_eq!(config.s3_spillover_dir, None);
+        assert_eq!(config.s3_max_disk_bytes, None);
+        assert_eq!(config.s3_access_log_flush_interval_secs, 60);
+        assert_eq!(config.s3_access_log_max_records, 1000);
+        assert!(config.s3_strict_validation);
+        assert_eq!(config.s3_record_dir, None);
+        assert_eq!(config.s3_replay_dir, None);
+        assert!(config.s3_replay_match_headers.is_empty());
+        assert!(!config.s3_serve_corrupt_objects);
+        assert_eq!(
+            config.s3_max_request_body_bytes,
+            Some(5 * 1024 * 1024 * 1024)
+        );
+        assert!(!config.s3_legacy_object_ownership);
+        assert!(config.s3_latency_rules.is_empty());
         assert_eq!(config.default_region, "us-east-1");
         assert_eq!(config.log_level, "info");
         assert!(!config.persistence);
@@ -2,10 +2,11 @@
 //!
 //! Provides [`CorsIndex`] for storing per-bucket CORS configurations and
 //! matching incoming requests against those rules. The matching logic follows
-//! the S3 CORS specification, including wildcard origin support and preflight
-//! request handling.
+//! the S3 CORS specification, including wildcard origin and header support and
+//! preflight request handling.
 
 use dashmap::DashMap;
+use http::{HeaderMap, HeaderValue, header};
 
 // ---------------------------------------------------------------------------
 // CorsRule
@@ -18,11 +19,11 @@ use dashmap::DashMap;
 /// preflight result.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CorsRule {
-    /// Origins that are allowed (supports `"*"` wildcard).
+    /// Origins that are allowed (each may contain one `"*"` wildcard).
     pub allowed_origins: Vec<String>,
     /// HTTP methods that are allowed (e.g. `"GET"`, `"PUT"`).
     pub allowed_methods: Vec<String>,
-    /// Request headers that are allowed (supports `"*"` wildcard).
+    /// Request headers that are allowed (each may contain one `"*"` wildcard).
     pub allowed_headers: Vec<String>,
     /// Response headers that the browser is allowed to access.
     pub expose_headers: Vec<String>,
@@ -44,6 +45,8 @@ pub struct CorsMatch {
     /// The allowed methods for `Access-Control-Allow-Methods`.
     pub allowed_methods: Vec<String>,
     /// The allowed headers for `Access-Control-Allow-Headers`.
+    ///
+    /// For a preflight match these are the requested headers, lowercased.
     pub allowed_headers: Vec<String>,
     /// The headers to expose via `Access-Control-Expose-Headers`.
     pub expose_headers: Vec<String>,
@@ -51,6 +54,65 @@ pub struct CorsMatch {
     pub max_age_seconds: Option<i32>,
 }
 
+impl CorsMatch {
+    /// Build the CORS response headers for this match.
+    ///
+    /// `Access-Control-Allow-Headers` is only emitted for preflight responses.
+    /// Credentials are allowed whenever the origin is echoed rather than `*`.
+    /// Values that are not valid header values are skipped.
+    #[must_use]
+    pub fn to_headers(&self, preflight: bool) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        insert_header(
+            &mut headers,
+            header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            &self.allowed_origin,
+        );
+        insert_header(
+            &mut headers,
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            &self.allowed_methods.join(", "),
+        );
+        if preflight && !self.allowed_headers.is_empty() {
+            insert_header(
+                &mut headers,
+                header::ACCESS_CONTROL_ALLOW_HEADERS,
+                &self.allowed_headers.join(", "),
+            );
+        }
+        if !self.expose_headers.is_empty() {
+            insert_header(
+                &mut headers,
+                header::ACCESS_CONTROL_EXPOSE_HEADERS,
+                &self.expose_headers.join(", "),
+            );
+        }
+        if let Some(max_age) = self.max_age_seconds {
+            headers.insert(header::ACCESS_CONTROL_MAX_AGE, HeaderValue::from(max_age));
+        }
+        if self.allowed_origin != "*" {
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                HeaderValue::from_static("true"),
+            );
+        }
+        headers.insert(
+            header::VARY,
+            HeaderValue::from_static(
+                "Origin, Access-Control-Request-Headers, Access-Control-Request-Method",
+            ),
+        );
+        headers
+    }
+}
+
+/// Insert a header, skipping values that are not valid header values.
+fn insert_header(headers: &mut HeaderMap, name: header::HeaderName, value: &str) {
+    if let Ok(value) = HeaderValue::from_str(value) {
+        headers.insert(name, value);
+    }
+}
+
 // ---------------------------------------------------------------------------
 // CorsIndex
 // ---------------------------------------------------------------------------
@@ -102,6 +164,12 @@ impl CorsIndex {
         self.rules.remove(bucket);
     }
 
+    /// Whether a bucket has a CORS configuration.
+    #[must_use]
+    pub fn has_rules(&self, bucket: &str) -> bool {
+        self.rules.contains_key(bucket)
+    }
+
     /// Get a clone of the CORS rules for a bucket.
     #[must_use]
     pub fn get_rules(&self, bucket: &str) -> Option<Vec<CorsRule>> {
@@ -140,7 +208,8 @@ impl CorsIndex {
     ///
     /// Returns the first matching [`CorsMatch`] or `None` if no rule matches.
     /// Unlike [`match_cors`](Self::match_cors), this also validates the
-    /// `Access-Control-Request-Headers` against `allowed_headers`.
+    /// `Access-Control-Request-Headers` against `allowed_headers`, and the
+    /// match echoes the requested headers back.
     #[must_use]
     pub fn match_preflight(
         &self,
@@ -167,7 +236,10 @@ impl CorsIndex {
             return Some(CorsMatch {
                 allowed_origin: resolve_origin(&rule.allowed_origins, origin),
                 allowed_methods: rule.allowed_methods.clone(),
-                allowed_headers: rule.allowed_headers.clone(),
+                allowed_headers: request_headers
+                    .iter()
+                    .map(|h| h.to_ascii_lowercase())
+                    .collect(),
                 expose_headers: rule.expose_headers.clone(),
                 max_age_seconds: rule.max_age_seconds,
             });
@@ -188,14 +260,34 @@ impl Default for CorsIndex {
 
 /// Match an origin pattern against an actual origin.
 ///
-/// A pattern of `"*"` matches any origin. Otherwise the comparison is
-/// case-sensitive and exact.
+/// A pattern of `"*"` matches any origin, and a pattern with an embedded
+/// wildcard such as `"https://*.example.com"` matches any origin with that
+/// prefix and suffix. The comparison is case-sensitive.
 #[must_use]
 pub fn match_origin(pattern: &str, origin: &str) -> bool {
-    if pattern == "*" {
-        return true;
+    wildcard_match(pattern, origin, false)
+}
+
+/// Match a pattern containing at most one `*` wildcard against a value.
+///
+/// The wildcard matches any run of characters, including an empty one.
+fn wildcard_match(pattern: &str, value: &str, ignore_case: bool) -> bool {
+    let eq = |a: &str, b: &str| {
+        if ignore_case {
+            a.eq_ignore_ascii_case(b)
+        } else {
+            a == b
+        }
+    };
+    let Some((prefix, suffix)) = pattern.split_once('*') else {
+        return eq(pattern, value);
+    };
+    if value.len() < prefix.len() + suffix.len() {
+        return false;
     }
-    pattern == origin
+    let head = value.get(..prefix.len());
+    let tail = value.get(value.len() - suffix.len()..);
+    matches!((head, tail), (Some(head), Some(tail)) if eq(head, prefix) && eq(tail, suffix))
 }
 
 /// Determine the effective `Access-Control-Allow-Origin` value.
@@ -211,14 +303,12 @@ fn resolve_origin(allowed_origins: &[String], origin: &str) -> String {
 }
 
 /// Check whether all requested headers are permitted by the rule's
-/// `allowed_headers`.
+/// `allowed_headers`, which may contain wildcards such as `"*"` or
+/// `"x-amz-*"`. Header names are compared case-insensitively.
 fn headers_allowed(allowed: &[String], requested: &[String]) -> bool {
-    if allowed.iter().any(|h| h == "*") {
-        return true;
-    }
     requested
         .iter()
-        .all(|req| allowed.iter().any(|a| a.eq_ignore_ascii_case(req)))
+        .all(|req| allowed.iter().any(|a| wildcard_match(a, req, true)))
 }
 
 #[cfg(test)]
@@ -420,4 +510,88 @@ mod tests {
     fn test_should_not_match_different_pattern() {
         assert!(!match_origin("https://example.com", "https://other.com"));
     }
+
+    #[test]
+    fn test_should_match_embedded_wildcard_origin() {
+        assert!(match_origin(
+            "https://*.example.com",
+            "https://app.example.com"
+        ));
+        assert!(match_origin("http://localhost:*", "http://localhost:3000"));
+        assert!(!match_origin(
+            "https://*.example.com",
+            "https://example.org"
+        ));
+        assert!(!match_origin(
+            "https://*.example.com",
+            "http://app.example.com"
+        ));
+    }
+
+    // -----------------------------------------------------------------------
+    // Header matching and response headers
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_should_match_wildcard_headers_case_insensitively() {
+        let wildcard = vec!["*".to_owned()];
+        let requested = vec!["content-type".to_owned(), "X-Amz-Meta-Foo".to_owned()];
+        assert!(headers_allowed(&wildcard, &requested));
+
+        let prefixed = vec!["Content-Type".to_owned(), "x-amz-*".to_owned()];
+        assert!(headers_allowed(&prefixed, &requested));
+        assert!(!headers_allowed(&prefixed, &["authorization".to_owned()]));
+    }
+
+    #[test]
+    fn test_should_build_preflight_headers() {
+        let index = CorsIndex::new();
+        let mut rule = make_strict_rule();
+        rule.allowed_origins = vec!["https://*.example.com".to_owned()];
+        rule.allowed_headers = vec!["*".to_owned()];
+        rule.expose_headers = vec!["ETag".to_owned()];
+        rule.max_age_seconds = Some(600);
+        index.set_rules("bucket", vec![rule]);
+
+        let headers = index
+            .match_preflight(
+                "bucket",
+                "https://app.example.com",
+                "GET",
+                &["Content-Type".to_owned(), "x-amz-meta-foo".to_owned()],
+            )
+            .expect("test match")
+            .to_headers(true);
+        let get = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        assert_eq!(
+            get("access-control-allow-origin"),
+            Some("https://app.example.com")
+        );
+        assert_eq!(
+            get("access-control-allow-headers"),
+            Some("content-type, x-amz-meta-foo")
+        );
+        assert_eq!(get("access-control-expose-headers"), Some("ETag"));
+        assert_eq!(get("access-control-max-age"), Some("600"));
+        assert_eq!(get("access-control-allow-credentials"), Some("true"));
+    }
+
+    #[test]
+    fn test_should_omit_allow_headers_on_actual_response() {
+        let index = CorsIndex::new();
+        index.set_rules("bucket", vec![make_permissive_rule()]);
+
+        let headers = index
+            .match_cors("bucket", "https://example.com", "GET")
+            .expect("test match")
+            .to_headers(false);
+        assert!(!headers.contains_key("access-control-allow-headers"));
+        assert!(!headers.contains_key("access-control-allow-credentials"));
+        assert_eq!(
+            headers
+                .get("access-control-expose-headers")
+                .and_then(|v| v.to_str().ok()),
+            Some("x-amz-request-id")
+        );
+    }
 }
@@ -0,0 +1,168 @@
+//! In-process S3 client for Rust tests.
+//!
+//! [`S3TestClient`] drives a real [`RustackS3`] provider without an HTTP
+//! server, so tests see the same behavior as the server while passing plain
+//! bucket names, keys, and bytes.
+//!
+//! ```
+//! use rustack_s3_core::embedded::S3TestClient;
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let client = S3TestClient::new();
+//! client.create_bucket("assets").await.unwrap();
+//! client
+//!     .put_object("assets", "logo.txt", "hello")
+//!     .await
+//!     .unwrap();
+//!
+//! let body = client.get_object("assets", "logo.txt").await.unwrap();
+//! assert_eq!(body.as_ref(), b"hello");
+//! assert_eq!(client.list_keys("assets").await.unwrap(), ["logo.txt"]);
+//! # }
+//! ```
+
+use bytes::Bytes;
+use rustack_s3_model::{
+    error::S3Error,
+    input::{
+        CreateBucketInput, DeleteObjectInput, GetObjectInput, ListObjectsV2Input, PutObjectInput,
+    },
+    request::StreamingBlob,
+};
+
+use crate::{RustackS3, config::S3Config};
+
+/// An S3 client that calls a [`RustackS3`] provider in-process.
+#[derive(Debug, Clone)]
+pub struct S3TestClient {
+    provider: RustackS3,
+}
+
+impl Default for S3TestClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl S3TestClient {
+    /// Create a client over a fresh provider with the default configuration.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_provider(RustackS3::new(S3Config::default()))
+    }
+
+    /// Create a client over an existing provider, sharing its state.
+    #[must_use]
+    pub fn with_provider(provider: RustackS3) -> Self {
+        Self { provider }
+    }
+
+    /// The underlying provider, for operations the client has no helper for.
+    #[must_use]
+    pub fn provider(&self) -> &RustackS3 {
+        &self.provider
+    }
+
+    /// Create a bucket in the provider's default region.
+    pub async fn create_bucket(&self, bucket: &str) -> Result<(), S3Error> {
+        self.provider
+            .handle_create_bucket(CreateBucketInput {
+                bucket: bucket.to_owned(),
+                ..Default::default()
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Store `body` as `key` and return the object's ETag.
+    pub async fn put_object(
+        &self,
+        bucket: &str,
+        key: &str,
+        body: impl Into<Bytes>,
+    ) -> Result<Option<String>, S3Error> {
+        let output = self
+            .provider
+            .handle_put_object(PutObjectInput {
+                bucket: bucket.to_owned(),
+                key: key.to_owned(),
+                body: Some(StreamingBlob::new(body)),
+                ..Default::default()
+            })
+            .await?;
+        Ok(output.e_tag)
+    }
+
+    /// Read the body of `key`.
+    pub async fn get_object(&self, bucket: &str, key: &str) -> Result<Bytes, S3Error> {
+        let output = self
+            .provider
+            .handle_get_object(GetObjectInput {
+                bucket: bucket.to_owned(),
+                key: key.to_owned(),
+                ..Default::default()
+            })
+            .await?;
+        Ok(output.body.map(|body| body.data).unwrap_or_default())
+    }
+
+    /// Delete `key`.
+    pub async fn delete_object(&self, bucket: &str, key: &str) -> Result<(), S3Error> {
+        self.provider
+            .handle_delete_object(DeleteObjectInput {
+                bucket: bucket.to_owned(),
+                key: key.to_owned(),
+                ..Default::default()
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// List every key in `bucket` in lexicographic order, following
+    /// pagination.
+    pub async fn list_keys(&self, bucket: &str) -> Result<Vec<String>, S3Error> {
+        let mut keys = Vec::new();
+        let mut continuation_token = None;
+        loop {
+            let output = self
+                .provider
+                .handle_list_objects_v2(ListObjectsV2Input {
+                    bucket: bucket.to_owned(),
+                    continuation_token,
+                    ..Default::default()
+                })
+                .await?;
+            keys.extend(output.contents.into_iter().filter_map(|object| object.key));
+            if output.is_truncated != Some(true) {
+                return Ok(keys);
+            }
+            continuation_token = output.next_continuation_token;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_should_list_keys_across_pages() {
+        let client = S3TestClient::new();
+        client.create_bucket("bucket").await.unwrap();
+        for i in 0..1005 {
+            client
+                .put_object("bucket", &format!("k{i:04}"), Bytes::new())
+                .await
+                .unwrap();
+        }
+
+        let keys = client.list_keys("bucket").await.unwrap();
+        assert_eq!(keys.len(), 1005);
+        assert_eq!(keys[1004], "k1004");
+
+        client.delete_object("bucket", "k0000").await.unwrap();
+        assert!(client.get_object("bucket", "k0000").await.is_err());
+        assert_eq!(client.list_keys("bucket").await.unwrap()[0], "k0001");
+    }
+}
@@ -108,6 +108,17 @@ pub enum S3ServiceError {
     #[error("Your proposed upload exceeds the maximum allowed object size")]
     EntityTooLarge,
 
+    /// Storing the data would exceed the disk cap of the storage backend.
+    #[error("There is not enough storage space to complete the request")]
+    InsufficientStorage,
+
+    /// The object's stored data failed an integrity check.
+    #[error("The stored data of object {key} failed an integrity check")]
+    ObjectCorrupted {
+        /// The object key.
+        key: String,
+    },
+
     // -----------------------------------------------------------------------
     // Validation errors
     // -----------------------------------------------------------------------
@@ -120,6 +131,33 @@ pub enum S3ServiceError {
         reason: String,
     },
 
+    /// The location constraint does not match the region the request was
+    /// sent to.
+    #[error(
+        "The {constraint} location constraint is incompatible for the region specific endpoint \
+         this request was sent to."
+    )]
+    IllegalLocationConstraint {
+        /// The requested constraint, or `unspecified` if none was given.
+        constraint: String,
+    },
+
+    /// The specified access point does not exist.
+    #[error("The specified accesspoint does not exist")]
+    NoSuchAccessPoint {
+        /// The access point name that was not found.
+        name: String,
+    },
+
+    /// An access point with the same name already exists in the account.
+    #[error(
+        "Your previous request to create the named accesspoint succeeded and you already own it"
+    )]
+    AccessPointAlreadyOwnedByYou {
+        /// The access point name that already exists.
+        name: String,
+    },
+
     /// An argument provided is invalid.
     #[error("Invalid argument: {message}")]
     InvalidArgument {
@@ -127,21 +165,57 @@ pub enum S3ServiceError {
         message: String,
     },
 
+    /// The request is invalid as a whole, e.g. missing the encryption
+    /// parameters an object was stored with.
+    #[error("Invalid request: {message}")]
+    InvalidRequest {
+        /// Description of why the request is invalid.
+        message: String,
+    },
+
     /// The requested range is not satisfiable.
     #[error("The requested range is not satisfiable")]
     InvalidRange,
 
-    /// A tag key or value is invalid.
+    /// A tag key or value is invalid, or a tag key is repeated.
     #[error("Invalid tag: {message}")]
     InvalidTag {
         /// Description of the tag error.
         message: String,
     },
 
+    /// The request exceeds a limit S3 reports as a generic bad request,
+    /// such as too many tags on an object.
+    #[error("Bad request: {message}")]
+    BadRequest {
+        /// Description of the violated limit.
+        message: String,
+    },
+
     /// The XML body is malformed.
     #[error("The XML you provided was not well-formed")]
     MalformedXml,
 
+    /// An `AccessControlPolicy` body has a grant missing its grantee,
+    /// permission, or grantee identifier.
+    #[error("The ACL you provided was not well-formed")]
+    MalformedAcl,
+
+    /// A grant names its grantee by email address, which cannot be resolved
+    /// to an account.
+    #[error("The email address you provided does not match any account on record")]
+    UnresolvableGrantByEmailAddress,
+
+    /// A request sets an ACL on a bucket whose object ownership is
+    /// `BucketOwnerEnforced`, which disables ACLs.
+    #[error("The bucket does not allow ACLs")]
+    AccessControlListNotSupported,
+
+    /// A `CreateBucket` request with object ownership `BucketOwnerEnforced`
+    /// also sets an ACL.
+    #[error("Bucket cannot have ACLs set with ObjectOwnership's BucketOwnerEnforced setting")]
+    InvalidBucketAclWithObjectOwnership,
+
     // -----------------------------------------------------------------------
     // Authorization / access errors
     // -----------------------------------------------------------------------
@@ -205,6 +279,10 @@ pub enum S3ServiceError {
     #[error("Your key is too long")]
     KeyTooLong,
 
+    /// The user-defined metadata is too large.
+    #[error("Your metadata headers exceed the maximum allowed metadata size")]
+    MetadataTooLarge,
+
     /// The message body exceeds the maximum length.
     #[error("Your request was too big")]
     MaxMessageLengthExceeded,
@@ -280,14 +358,40 @@ impl From<S3ServiceError> for S3Error {
         // For validation errors, preserve the specific message.
         let message = match &err {
             S3ServiceError::InvalidArgument { message }
-            | S3ServiceError::InvalidTag { message } => message.clone(),
+            | S3ServiceError::InvalidRequest { message }
+            | S3ServiceError::InvalidTag { message }
+            | S3ServiceError::BadRequest { message } => message.clone(),
             S3ServiceError::InvalidBucketName { name, reason } => {
                 format!("Invalid bucket name: {name}: {reason}")
             }
+            S3ServiceError::IllegalLocationConstraint { .. }
+            | S3ServiceError::NoSuchAccessPoint { .. }
+            | S3ServiceError::AccessPointAlreadyOwnedByYou { .. }
+            | S3ServiceError::InsufficientStorage
+            | S3ServiceError::ObjectCorrupted { .. } => err.to_string(),
             S3ServiceError::Internal(e) => e.to_string(),
             _ => code.default_message().to_owned(),
         };
-        S3Error::with_message(code, message)
+        let mut s3_err = S3Error::with_message(code, message);
+        if matches!(
+            err,
+            S3ServiceError::IllegalLocationConstraint { .. }
+                | S3ServiceError::InvalidTag { .. }
+                | S3ServiceError::BadRequest { .. }
+        ) {
+            // Not a modeled S3 error code, so it has no default status.
+            s3_err.status_code = http::StatusCode::BAD_REQUEST;
+        }
+        if matches!(err, S3ServiceError::NoSuchAccessPoint { .. }) {
+            s3_err.status_code = http::StatusCode::NOT_FOUND;
+        }
+        if matches!(err, S3ServiceError::AccessPointAlreadyOwnedByYou { .. }) {
+            s3_err.status_code = http::StatusCode::CONFLICT;
+        }
+        if matches!(err, S3ServiceError::InsufficientStorage) {
+            s3_err.status_code = http::StatusCode::INSUFFICIENT_STORAGE;
+        }
+        s3_err
     }
 }
 
@@ -305,12 +409,29 @@ fn error_code(err: &S3ServiceError) -> S3ErrorCode {
         S3ServiceError::InvalidPart => S3ErrorCode::InvalidPart,
         S3ServiceError::EntityTooSmall => S3ErrorCode::EntityTooSmall,
         S3ServiceError::EntityTooLarge => S3ErrorCode::EntityTooLarge,
+        S3ServiceError::InsufficientStorage => S3ErrorCode::Custom("InsufficientStorage"),
         S3ServiceError::InvalidBucketName { .. } => S3ErrorCode::InvalidBucketName,
-        S3ServiceError::InvalidArgument { .. } | S3ServiceError::InvalidTag { .. } => {
-            S3ErrorCode::InvalidArgument
+        S3ServiceError::IllegalLocationConstraint { .. } => {
+            S3ErrorCode::Custom("IllegalLocationConstraintException")
+        }
+        S3ServiceError::NoSuchAccessPoint { .. } => S3ErrorCode::Custom("NoSuchAccessPoint"),
+        S3ServiceError::AccessPointAlreadyOwnedByYou { .. } => {
+            S3ErrorCode::Custom("AccessPointAlreadyOwnedByYou")
         }
+        S3ServiceError::InvalidArgument { .. } => S3ErrorCode::InvalidArgument,
+        S3ServiceError::InvalidTag { .. } => S3ErrorCode::Custom("InvalidTag"),
+        S3ServiceError::BadRequest { .. } => S3ErrorCode::Custom("BadRequest"),
+        S3ServiceError::InvalidRequest { .. } => S3ErrorCode::InvalidRequest,
         S3ServiceError::InvalidRange => S3ErrorCode::InvalidRange,
         S3ServiceError::MalformedXml => S3ErrorCode::MalformedXML,
+        S3ServiceError::MalformedAcl => S3ErrorCode::MalformedACLError,
+        S3ServiceError::UnresolvableGrantByEmailAddress => {
+            S3ErrorCode::UnresolvableGrantByEmailAddress
+        }
+        S3ServiceError::AccessControlListNotSupported => S3ErrorCode::AccessControlListNotSupported,
+        S3ServiceError::InvalidBucketAclWithObjectOwnership => {
+            S3ErrorCode::InvalidBucketAclWithObjectOwnership
+        }
         S3ServiceError::AccessDenied => S3ErrorCode::AccessDenied,
         S3ServiceError::MethodNotAllowed => S3ErrorCode::MethodNotAllowed,
         S3ServiceError::NotImplemented => S3ErrorCode::NotImplemented,
@@ -323,6 +444,7 @@ fn error_code(err: &S3ServiceError) -> S3ErrorCode {
         S3ServiceError::BadDigest => S3ErrorCode::BadDigest,
         S3ServiceError::MissingContentLength => S3ErrorCode::MissingContentLength,
         S3ServiceError::KeyTooLong => S3ErrorCode::KeyTooLongError,
+        S3ServiceError::MetadataTooLarge => S3ErrorCode::MetadataTooLarge,
         S3ServiceError::MaxMessageLengthExceeded => S3ErrorCode::MaxMessageLengthExceeded,
         S3ServiceError::NoSuchCorsConfiguration => S3ErrorCode::NoSuchCORSConfiguration,
         S3ServiceError::NoSuchTagSet => S3ErrorCode::NoSuchTagSet,
@@ -344,7 +466,9 @@ fn error_code(err: &S3ServiceError) -> S3ErrorCode {
         S3ServiceError::ReplicationConfigurationNotFoundError => {
             S3ErrorCode::ReplicationConfigurationNotFoundError
         }
-        S3ServiceError::Internal(_) => S3ErrorCode::InternalError,
+        S3ServiceError::ObjectCorrupted { .. } | S3ServiceError::Internal(_) => {
+            S3ErrorCode::InternalError
+        }
     }
 }
 
@@ -412,6 +536,31 @@ mod tests {
         assert_eq!(s3_err.code, S3ErrorCode::InvalidBucketName);
     }
 
+    #[test]
+    fn test_should_convert_invalid_tag_to_s3_error() {
+        let err = S3ServiceError::InvalidTag {
+            message: "Cannot provide multiple Tags with the same key".to_owned(),
+        };
+        let s3_err: S3Error = err.into();
+        assert_eq!(s3_err.code.as_str(), "InvalidTag");
+        assert_eq!(s3_err.status_code, http::StatusCode::BAD_REQUEST);
+        assert_eq!(
+            s3_err.message,
+            "Cannot provide multiple Tags with the same key"
+        );
+    }
+
+    #[test]
+    fn test_should_convert_bad_request_to_s3_error() {
+        let err = S3ServiceError::BadRequest {
+            message: "Object tags cannot be greater than 10".to_owned(),
+        };
+        let s3_err: S3Error = err.into();
+        assert_eq!(s3_err.code.as_str(), "BadRequest");
+        assert_eq!(s3_err.status_code, http::StatusCode::BAD_REQUEST);
+        assert_eq!(s3_err.message, "Object tags cannot be greater than 10");
+    }
+
     #[test]
     fn test_should_convert_entity_too_small_to_s3_error() {
         let err = S3ServiceError::EntityTooSmall;
@@ -535,4 +684,11 @@ mod tests {
         assert_eq!(s3_err.code, S3ErrorCode::MaxMessageLengthExceeded);
         assert_eq!(s3_err.status_code, http::StatusCode::BAD_REQUEST);
     }
+
+    #[test]
+    fn test_should_convert_insufficient_storage_to_507() {
+        let s3_err: S3Error = S3ServiceError::InsufficientStorage.into();
+        assert_eq!(s3_err.code.as_str(), "InsufficientStorage");
+        assert_eq!(s3_err.status_code, http::StatusCode::INSUFFICIENT_STORAGE);
+    }
 }
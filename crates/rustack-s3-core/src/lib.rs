@@ -3,7 +3,8 @@
 //! This crate implements the S3 business logic provider (`RustackS3`) that can
 //! be plugged into the `rustack-s3-http` service layer via the `S3Handler` trait.
 //! It supports bucket CRUD, object CRUD, multipart uploads, versioning, CORS,
-//! tagging, ACLs, encryption metadata, checksums, object lock, and more.
+//! tagging, ACLs, bucket policy authorization, encryption metadata, checksums,
+//! object lock, server access log delivery, and more.
 //!
 //! # Architecture
 //!
@@ -20,13 +21,19 @@
 //!   StorageBackend (in-memory + spillover)
 //! ```
 
+pub mod access_log;
+pub mod acl;
 pub mod auth;
 pub mod checksums;
 pub mod config;
 pub mod cors;
+pub mod embedded;
 pub mod error;
 pub mod ops;
+pub mod policy;
 pub mod provider;
+pub mod scrub;
+pub mod sse;
 pub mod state;
 pub mod storage;
 pub mod utils;
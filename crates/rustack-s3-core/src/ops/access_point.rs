@@ -0,0 +1,164 @@
+//! Access point operation handlers.
+//!
+//! Implements the S3 Control `create_access_point`, `get_access_point`,
+//! `delete_access_point`, and `list_access_points` operations, plus the
+//! lookup that routes requests addressed to an access point to its bucket.
+
+use rustack_s3_model::{
+    error::S3Error,
+    input::{
+        CreateAccessPointInput, DeleteAccessPointInput, GetAccessPointInput, ListAccessPointsInput,
+    },
+    output::{CreateAccessPointOutput, GetAccessPointOutput, ListAccessPointsOutput},
+    types::AccessPoint as ModelAccessPoint,
+};
+use tracing::debug;
+
+use crate::{error::S3ServiceError, provider::RustackS3, state::AccessPoint};
+
+/// Network origin reported for every access point; VPC-restricted access
+/// points are not modeled.
+const NETWORK_ORIGIN: &str = "Internet";
+
+/// Page size of `ListAccessPoints` when the request does not set one.
+const DEFAULT_MAX_RESULTS: usize = 1000;
+
+// These handler methods must remain async for consistency.
+#[allow(clippy::unused_async)]
+impl RustackS3 {
+    /// Create an access point for an existing bucket.
+    pub async fn handle_create_access_point(
+        &self,
+        input: CreateAccessPointInput,
+    ) -> Result<CreateAccessPointOutput, S3Error> {
+        let access_point = self
+            .state
+            .create_access_point(input.name, input.bucket, input.account_id)
+            .map_err(S3ServiceError::into_s3_error)?;
+
+        debug!(
+            access_point = %access_point.name,
+            bucket = %access_point.bucket,
+            "create_access_point completed"
+        );
+        Ok(CreateAccessPointOutput {
+            access_point_arn: Some(self.access_point_arn(&access_point)),
+            alias: None,
+        })
+    }
+
+    /// Get an access point's configuration.
+    pub async fn handle_get_access_point(
+        &self,
+        input: GetAccessPointInput,
+    ) -> Result<GetAccessPointOutput, S3Error> {
+        let access_point = self
+            .owned_access_point(&input.account_id, &input.name)
+            .map_err(S3ServiceError::into_s3_error)?;
+
+        Ok(GetAccessPointOutput {
+            access_point_arn: Some(self.access_point_arn(&access_point)),
+            alias: None,
+            bucket_account_id: Some(access_point.account_id),
+            creation_date: Some(access_point.creation_date),
+            name: access_point.name,
+            bucket: access_point.bucket,
+            network_origin: NETWORK_ORIGIN.to_owned(),
+        })
+    }
+
+    /// Delete an access point, leaving its bucket untouched.
+    pub async fn handle_delete_access_point(
+        &self,
+        input: DeleteAccessPointInput,
+    ) -> Result<(), S3Error> {
+        self.owned_access_point(&input.account_id, &input.name)
+            .and_then(|_| self.state.delete_access_point(&input.name))
+            .map_err(S3ServiceError::into_s3_error)
+    }
+
+    /// List the account's access points, optionally only those for a bucket.
+    ///
+    /// Pages are ordered by name; the continuation token is the last name
+    /// returned.
+    pub async fn handle_list_access_points(
+        &self,
+        input: ListAccessPointsInput,
+    ) -> Result<ListAccessPointsOutput, S3Error> {
+        let max_results = input
+            .max_results
+            .and_then(|n| usize::try_from(n).ok())
+            .filter(|&n| n > 0)
+            .map_or(DEFAULT_MAX_RESULTS, |n| n.min(DEFAULT_MAX_RESULTS));
+
+        let mut remaining = self
+            .state
+            .list_access_points(input.bucket.as_deref())
+            .into_iter()
+            .filter(|ap| ap.account_id == input.account_id)
+            .filter(|ap| {
+                input
+                    .next_token
+                    .as_ref()
+                    .is_none_or(|token| ap.name > *token)
+            })
+            .peekable();
+
+        let mut access_point_list = Vec::new();
+        while access_point_list.len() < max_results {
+            let Some(access_point) = remaining.next() else {
+                break;
+            };
+            access_point_list.push(ModelAccessPoint {
+                access_point_arn: Some(self.access_point_arn(&access_point)),
+                alias: None,
+                bucket_account_id: Some(access_point.account_id),
+                name: access_point.name,
+                bucket: access_point.bucket,
+                network_origin: NETWORK_ORIGIN.to_owned(),
+            });
+        }
+        let next_token = remaining
+            .peek()
+            .and_then(|_| access_point_list.last())
+            .map(|ap| ap.name.clone());
+
+        Ok(ListAccessPointsOutput {
+            access_point_list,
+            next_token,
+        })
+    }
+
+    /// Resolve the bucket behind the access point `name` of `account`, for a
+    /// request that addresses the access point in place of a bucket.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoSuchAccessPoint` if the account has no such access point.
+    #[allow(clippy::result_large_err)]
+    pub fn access_point_bucket(&self, account: &str, name: &str) -> Result<String, S3Error> {
+        self.owned_access_point(account, name)
+            .map(|access_point| access_point.bucket)
+            .map_err(S3ServiceError::into_s3_error)
+    }
+
+    /// Look up an access point, treating one created for another account as
+    /// missing.
+    fn owned_access_point(&self, account: &str, name: &str) -> Result<AccessPoint, S3ServiceError> {
+        let access_point = self.state.access_point(name)?;
+        if access_point.account_id != account {
+            return Err(S3ServiceError::NoSuchAccessPoint {
+                name: name.to_owned(),
+            });
+        }
+        Ok(access_point)
+    }
+
+    /// The ARN of an access point in this handle's region.
+    fn access_point_arn(&self, access_point: &AccessPoint) -> String {
+        format!(
+            "arn:aws:s3:{}:{}:accesspoint/{}",
+            self.scope.region, access_point.account_id, access_point.name
+        )
+    }
+}
@@ -3,6 +3,7 @@
 //! Implements `create_bucket`, `delete_bucket`, `head_bucket`, `list_buckets`,
 //! and `get_bucket_location`.
 
+use dashmap::mapref::entry::Entry;
 use rustack_s3_model::{
     error::S3Error,
     input::{
@@ -10,15 +11,27 @@ use rustack_s3_model::{
         ListBucketsInput,
     },
     output::{CreateBucketOutput, GetBucketLocationOutput, HeadBucketOutput, ListBucketsOutput},
-    types::{Bucket, BucketLocationConstraint, LocationType, Owner},
+    types::{
+        Bucket, BucketCannedACL, BucketLocationConstraint, LocationType, ObjectOwnership, Owner,
+    },
 };
 use tracing::debug;
 
 use crate::{
-    error::S3ServiceError, provider::RustackS3, state::object::Owner as InternalOwner,
-    validation::validate_bucket_name,
+    acl::AclRequest,
+    error::S3ServiceError,
+    provider::RustackS3,
+    state::{
+        bucket::{BUCKET_OWNER_ENFORCED, OwnershipControlsConfig},
+        object::Owner as InternalOwner,
+    },
+    utils::{decode_continuation_token, encode_continuation_token},
+    validation::{validate_bucket_name, validate_legacy_bucket_name},
 };
 
+/// Default and largest `max-buckets` of a ListBuckets page.
+const MAX_BUCKETS_PER_PAGE: usize = 10_000;
+
 /// Convert our internal [`InternalOwner`] to the model [`Owner`] type.
 pub(crate) fn to_model_owner(owner: &InternalOwner) -> Owner {
     Owner {
@@ -39,32 +52,87 @@ impl RustackS3 {
     ) -> Result<CreateBucketOutput, S3Error> {
         let bucket_name = input.bucket;
 
-        validate_bucket_name(&bucket_name).map_err(S3ServiceError::into_s3_error)?;
+        if self.config.s3_strict_validation {
+            validate_bucket_name(&bucket_name)
+        } else {
+            validate_legacy_bucket_name(&bucket_name)
+        }
+        .map_err(S3ServiceError::into_s3_error)?;
 
-        let region = input
+        let constraint = input
             .create_bucket_configuration
-            .and_then(|c| c.location_constraint)
-            .map_or_else(
-                || self.config.default_region.clone(),
-                |lc: BucketLocationConstraint| lc.as_str().to_owned(),
-            );
+            .and_then(|c| c.location_constraint);
+        let region = resolve_bucket_region(self.scope.region.as_str(), constraint.as_ref())
+            .map_err(S3ServiceError::into_s3_error)?;
 
         let owner = InternalOwner::default();
+        let acl = AclRequest {
+            canned: input.acl.as_ref().map(BucketCannedACL::as_str),
+            grant_full_control: input.grant_full_control.as_deref(),
+            grant_read: input.grant_read.as_deref(),
+            grant_read_acp: input.grant_read_acp.as_deref(),
+            grant_write: input.grant_write.as_deref(),
+            grant_write_acp: input.grant_write_acp.as_deref(),
+            policy: None,
+        }
+        .resolve(&owner)
+        .map_err(S3ServiceError::into_s3_error)?;
+
+        // New buckets have ACLs disabled unless the request or the legacy
+        // default says otherwise, and then accept no ACL but the owner's.
+        let object_ownership = input
+            .object_ownership
+            .as_ref()
+            .map(ObjectOwnership::as_str)
+            .or((!self.config.s3_legacy_object_ownership).then_some(BUCKET_OWNER_ENFORCED));
+        if object_ownership == Some(BUCKET_OWNER_ENFORCED)
+            && acl
+                .as_ref()
+                .is_some_and(|update| !update.is_owner_full_control(&owner))
+        {
+            return Err(S3ServiceError::InvalidBucketAclWithObjectOwnership.into_s3_error());
+        }
 
         // Check if object lock is requested.
         let object_lock_enabled = input.object_lock_enabled_for_bucket.unwrap_or(false);
 
-        self.state
-            .create_bucket(bucket_name.clone(), region, owner)
-            .map_err(S3ServiceError::into_s3_error)?;
+        // Bucket names are global: claim the name for this namespace first.
+        match self.bucket_scopes.entry(bucket_name.clone()) {
+            Entry::Occupied(existing) => {
+                let err = if existing.get().account == self.scope.account {
+                    S3ServiceError::BucketAlreadyOwnedByYou {
+                        bucket: bucket_name,
+                    }
+                } else {
+                    S3ServiceError::BucketAlreadyExists {
+                        bucket: bucket_name,
+                    }
+                };
+                return Err(err.into_s3_error());
+            }
+            Entry::Vacant(slot) => {
+                self.state
+                    .create_bucket(bucket_name.clone(), region, owner)
+                    .map_err(S3ServiceError::into_s3_error)?;
+                slot.insert(self.scope.clone());
+            }
+        }
 
-        // If object lock was requested, enable it on the bucket.
-        if object_lock_enabled {
-            if let Ok(bucket) = self.state.get_bucket(&bucket_name) {
+        if let Ok(bucket) = self.state.get_bucket(&bucket_name) {
+            // If object lock was requested, enable it on the bucket.
+            if object_lock_enabled {
                 *bucket.object_lock_enabled.write() = true;
                 // Object lock requires versioning.
                 bucket.enable_versioning();
             }
+            if let Some(update) = acl {
+                update.apply(&mut bucket.acl.write(), &mut bucket.grants.write());
+            }
+            if let Some(ownership) = object_ownership {
+                *bucket.ownership_controls.write() = Some(OwnershipControlsConfig {
+                    object_ownership: ownership.to_owned(),
+                });
+            }
         }
 
         debug!(bucket = %bucket_name, "create_bucket completed");
@@ -79,16 +147,26 @@ impl RustackS3 {
     pub async fn handle_delete_bucket(&self, input: DeleteBucketInput) -> Result<(), S3Error> {
         let bucket_name = input.bucket;
 
-        // Clean up CORS rules for this bucket.
-        self.cors_index.delete_rules(&bucket_name);
-
-        // Delete storage data for this bucket.
-        self.storage.delete_bucket_data(&bucket_name);
+        // Uploads a lifecycle rule has already aborted do not keep the
+        // bucket alive.
+        if let Ok(bucket) = self.state.get_bucket(&bucket_name) {
+            self.abort_expired_uploads(&bucket_name, &bucket);
+        }
 
-        // Delete the bucket from state.
+        // Delete the bucket from state first, so a missing or non-empty
+        // bucket leaves its CORS rules and data untouched.
         self.state
             .delete_bucket(&bucket_name)
             .map_err(S3ServiceError::into_s3_error)?;
+        self.bucket_scopes.remove(&bucket_name);
+
+        // Clean up CORS rules for this bucket.
+        self.cors_index.delete_rules(&bucket_name);
+
+        // Delete storage data for this bucket, including the parts and
+        // spillover files of uploads aborted above, so a bucket later
+        // created with the same name starts clean.
+        self.storage.delete_bucket_data(&bucket_name);
 
         debug!(bucket = %bucket_name, "delete_bucket completed");
 
@@ -116,33 +194,95 @@ impl RustackS3 {
         })
     }
 
-    /// List all buckets.
+    /// List the buckets of the requesting account, across all regions.
+    ///
+    /// Buckets are returned in name order, filtered by `prefix` and
+    /// `bucket-region`, in pages of at most `max-buckets`. A truncated page
+    /// carries an opaque continuation token resuming after its last bucket.
     pub async fn handle_list_buckets(
         &self,
-        _input: ListBucketsInput,
+        input: ListBucketsInput,
     ) -> Result<ListBucketsOutput, S3Error> {
-        let bucket_list = self.state.list_buckets();
+        let max_buckets = match input.max_buckets {
+            None => MAX_BUCKETS_PER_PAGE,
+            Some(n) => usize::try_from(n)
+                .ok()
+                .filter(|n| (1..=MAX_BUCKETS_PER_PAGE).contains(n))
+                .ok_or_else(|| {
+                    S3ServiceError::InvalidArgument {
+                        message: format!(
+                            "Argument max-buckets must be an integer between 1 and \
+                             {MAX_BUCKETS_PER_PAGE}"
+                        ),
+                    }
+                    .into_s3_error()
+                })?,
+        };
+        let after = input
+            .continuation_token
+            .as_deref()
+            .map(decode_continuation_token)
+            .transpose()
+            .map_err(S3ServiceError::into_s3_error)?;
 
-        let buckets: Vec<Bucket> = bucket_list
+        let mut buckets: Vec<Bucket> = self
+            .account_buckets()
             .into_iter()
-            .map(|(name, creation_date)| Bucket {
-                bucket_arn: None,
-                name: Some(name),
-                creation_date: Some(creation_date),
-                bucket_region: None,
+            .filter(|bucket| {
+                let name = bucket.name.as_deref().unwrap_or_default();
+                after.as_deref().is_none_or(|after| name > after)
+                    && input
+                        .prefix
+                        .as_deref()
+                        .is_none_or(|prefix| name.starts_with(prefix))
+                    && input
+                        .bucket_region
+                        .as_deref()
+                        .is_none_or(|region| bucket.bucket_region.as_deref() == Some(region))
             })
             .collect();
+        buckets.sort_by(|a, b| a.name.cmp(&b.name));
 
+        let continuation_token = if buckets.len() > max_buckets {
+            buckets.truncate(max_buckets);
+            buckets
+                .last()
+                .and_then(|bucket| bucket.name.as_deref())
+                .map(encode_continuation_token)
+        } else {
+            None
+        };
+
+        // Buckets are created with the default owner, which their ACLs report.
         let owner = to_model_owner(&InternalOwner::default());
 
         Ok(ListBucketsOutput {
             buckets,
-            continuation_token: None,
+            continuation_token,
             owner: Some(owner),
-            prefix: None,
+            prefix: input.prefix,
         })
     }
 
+    /// Every bucket owned by this handle's account, in any region.
+    fn account_buckets(&self) -> Vec<Bucket> {
+        self.bucket_scopes
+            .iter()
+            .filter(|entry| entry.value().account == self.scope.account)
+            .filter_map(|entry| {
+                let scope = entry.value();
+                let state = self.states.get(&scope.account, &scope.region)?;
+                let bucket = state.get_bucket(entry.key()).ok()?;
+                Some(Bucket {
+                    bucket_arn: None,
+                    name: Some(bucket.name.clone()),
+                    creation_date: Some(bucket.creation_date),
+                    bucket_region: Some(bucket.region.clone()),
+                })
+            })
+            .collect()
+    }
+
     /// Get the location (region) of a bucket.
     pub async fn handle_get_bucket_location(
         &self,
@@ -167,3 +307,481 @@ impl RustackS3 {
         })
     }
 }
+
+/// Determine the region of a new bucket from the region the CreateBucket
+/// request was sent to and its `LocationConstraint`.
+///
+/// As in AWS, a bucket outside `us-east-1` must name its region as the
+/// constraint, a `us-east-1` bucket must omit it, and the constraint must
+/// match the request region. An explicit `us-east-1` constraint is not a
+/// [`BucketLocationConstraint`] at all; it is rejected while parsing the
+/// request.
+fn resolve_bucket_region(
+    request_region: &str,
+    constraint: Option<&BucketLocationConstraint>,
+) -> Result<String, S3ServiceError> {
+    match constraint.map(BucketLocationConstraint::as_str) {
+        None if request_region == "us-east-1" => Ok(request_region.to_owned()),
+        None => Err(S3ServiceError::IllegalLocationConstraint {
+            constraint: "unspecified".to_owned(),
+        }),
+        Some(constraint) if constraint != request_region => {
+            Err(S3ServiceError::IllegalLocationConstraint {
+                constraint: constraint.to_owned(),
+            })
+        }
+        Some(constraint) => Ok(constraint.to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_resolve_bucket_region_from_location_constraint() {
+        let eu = BucketLocationConstraint::from("eu-west-1");
+
+        assert_eq!(
+            resolve_bucket_region("us-east-1", None).expect("us-east-1"),
+            "us-east-1"
+        );
+        assert_eq!(
+            resolve_bucket_region("eu-west-1", Some(&eu)).expect("eu-west-1"),
+            "eu-west-1"
+        );
+        assert!(matches!(
+            resolve_bucket_region("us-east-1", Some(&eu)),
+            Err(S3ServiceError::IllegalLocationConstraint { constraint }) if constraint == "eu-west-1"
+        ));
+        assert!(matches!(
+            resolve_bucket_region("eu-west-1", None),
+            Err(S3ServiceError::IllegalLocationConstraint { constraint }) if constraint == "unspecified"
+        ));
+
+        let err = S3ServiceError::IllegalLocationConstraint {
+            constraint: "eu-west-1".to_owned(),
+        }
+        .into_s3_error();
+        assert_eq!(err.code.as_str(), "IllegalLocationConstraintException");
+        assert_eq!(err.status_code, http::StatusCode::BAD_REQUEST);
+    }
+
+    async fn list(provider: &RustackS3, input: ListBucketsInput) -> ListBucketsOutput {
+        provider
+            .handle_list_buckets(input)
+            .await
+            .unwrap_or_else(|e| panic!("list failed: {e}"))
+    }
+
+    fn names(output: &ListBucketsOutput) -> Vec<&str> {
+        output
+            .buckets
+            .iter()
+            .filter_map(|b| b.name.as_deref())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_should_page_and_filter_account_buckets() {
+        let root = RustackS3::new(crate::config::S3Config::default());
+        let scope = |region: &str| {
+            rustack_core::RequestScope::resolve(Some("111111111111"), Some(region), "us-east-1")
+        };
+        let east = root.scoped(&scope("us-east-1"));
+        let west = root.scoped(&scope("eu-west-1"));
+        for name in ["logs-b", "data-a", "logs-a"] {
+            east.handle_create_bucket(CreateBucketInput {
+                bucket: name.to_owned(),
+                ..Default::default()
+            })
+            .await
+            .unwrap_or_else(|e| panic!("create failed: {e}"));
+        }
+        west.handle_create_bucket(CreateBucketInput {
+            bucket: "logs-eu".to_owned(),
+            create_bucket_configuration: Some(rustack_s3_model::types::CreateBucketConfiguration {
+                location_constraint: Some("eu-west-1".into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .await
+        .unwrap_or_else(|e| panic!("create failed: {e}"));
+        root.handle_create_bucket(CreateBucketInput {
+            bucket: "other-account".to_owned(),
+            ..Default::default()
+        })
+        .await
+        .unwrap_or_else(|e| panic!("create failed: {e}"));
+
+        let all = list(&west, ListBucketsInput::default()).await;
+        assert_eq!(names(&all), ["data-a", "logs-a", "logs-b", "logs-eu"]);
+        assert_eq!(all.buckets[3].bucket_region.as_deref(), Some("eu-west-1"));
+        assert_eq!(all.continuation_token, None);
+        let owner = all.owner.unwrap_or_else(|| panic!("owner missing"));
+        assert_eq!(owner.id, Some(InternalOwner::default().id));
+
+        let first = list(
+            &east,
+            ListBucketsInput {
+                max_buckets: Some(2),
+                prefix: Some("logs-".to_owned()),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(names(&first), ["logs-a", "logs-b"]);
+        assert_eq!(first.prefix.as_deref(), Some("logs-"));
+        let second = list(
+            &east,
+            ListBucketsInput {
+                max_buckets: Some(2),
+                prefix: Some("logs-".to_owned()),
+                continuation_token: first.continuation_token,
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(names(&second), ["logs-eu"]);
+        assert_eq!(second.continuation_token, None);
+
+        let regional = list(
+            &east,
+            ListBucketsInput {
+                bucket_region: Some("us-east-1".to_owned()),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(names(&regional), ["data-a", "logs-a", "logs-b"]);
+
+        for max_buckets in [0, 10_001] {
+            let err = east
+                .handle_list_buckets(ListBucketsInput {
+                    max_buckets: Some(max_buckets),
+                    ..Default::default()
+                })
+                .await
+                .err()
+                .unwrap_or_else(|| panic!("max-buckets {max_buckets} accepted"));
+            assert_eq!(err.code.as_str(), "InvalidArgument");
+        }
+    }
+
+    fn create_input(name: &str) -> CreateBucketInput {
+        CreateBucketInput {
+            bucket: name.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_should_create_racing_bucket_exactly_once() {
+        let provider = RustackS3::new(crate::config::S3Config::default());
+        for round in 0..20 {
+            let name = format!("race-{round}");
+            let tasks: Vec<_> = (0..8)
+                .map(|_| {
+                    let provider = provider.clone();
+                    let input = create_input(&name);
+                    tokio::spawn(async move { provider.handle_create_bucket(input).await })
+                })
+                .collect();
+
+            let mut created = 0;
+            for task in tasks {
+                match task.await.expect("join") {
+                    Ok(_) => created += 1,
+                    Err(err) => assert_eq!(err.code.as_str(), "BucketAlreadyOwnedByYou"),
+                }
+            }
+            assert_eq!(created, 1, "{name} created {created} times");
+        }
+        assert_eq!(
+            list(&provider, ListBucketsInput::default())
+                .await
+                .buckets
+                .len(),
+            20
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_should_not_store_objects_in_deleted_bucket() {
+        let provider = RustackS3::new(crate::config::S3Config::default());
+        for _ in 0..50 {
+            provider
+                .handle_create_bucket(create_input("contested"))
+                .await
+                .unwrap_or_else(|e| panic!("create failed: {e}"));
+
+            let put = tokio::spawn({
+                let provider = provider.clone();
+                async move {
+                    provider
+                        .handle_put_object(rustack_s3_model::input::PutObjectInput {
+                            bucket: "contested".to_owned(),
+                            key: "key".to_owned(),
+                            body: Some(rustack_s3_model::request::StreamingBlob::new(
+                                bytes::Bytes::from_static(b"data"),
+                            )),
+                            ..Default::default()
+                        })
+                        .await
+                }
+            });
+            let delete = tokio::spawn({
+                let provider = provider.clone();
+                async move {
+                    provider
+                        .handle_delete_bucket(DeleteBucketInput {
+                            bucket: "contested".to_owned(),
+                            ..Default::default()
+                        })
+                        .await
+                }
+            });
+            let put = put.await.expect("join");
+            let delete = delete.await.expect("join");
+
+            // Exactly one wins: the object keeps the bucket alive, or the
+            // deleted bucket leaves no object data behind.
+            match (put, delete) {
+                (Ok(_), Err(err)) => assert_eq!(err.code.as_str(), "BucketNotEmpty"),
+                (Err(err), Ok(())) => {
+                    assert_eq!(err.code.as_str(), "NoSuchBucket");
+                    assert_eq!(provider.storage().stats().object_count, 0);
+                    assert!(
+                        list(&provider, ListBucketsInput::default())
+                            .await
+                            .buckets
+                            .is_empty()
+                    );
+                    continue;
+                }
+                (put, delete) => panic!("put {put:?} and delete {delete:?} both decided"),
+            }
+            provider.reset();
+        }
+    }
+
+    async fn delete_bucket(provider: &RustackS3, name: &str) -> Result<(), S3Error> {
+        provider
+            .handle_delete_bucket(DeleteBucketInput {
+                bucket: name.to_owned(),
+                ..Default::default()
+            })
+            .await
+    }
+
+    async fn start_upload(provider: &RustackS3, bucket: &str, key: &str) -> String {
+        let upload_id = provider
+            .handle_create_multipart_upload(rustack_s3_model::input::CreateMultipartUploadInput {
+                bucket: bucket.to_owned(),
+                key: key.to_owned(),
+                ..Default::default()
+            })
+            .await
+            .unwrap_or_else(|e| panic!("create upload failed: {e}"))
+            .upload_id
+            .unwrap_or_else(|| panic!("no upload id"));
+        provider
+            .handle_upload_part(rustack_s3_model::input::UploadPartInput {
+                bucket: bucket.to_owned(),
+                key: key.to_owned(),
+                upload_id: upload_id.clone(),
+                part_number: 1,
+                body: Some(rustack_s3_model::request::StreamingBlob::new(vec![
+                    b'p';
+                    256
+                ])),
+                ..Default::default()
+            })
+            .await
+            .unwrap_or_else(|e| panic!("upload part failed: {e}"));
+        upload_id
+    }
+
+    #[tokio::test]
+    async fn test_should_reject_deleting_bucket_with_in_progress_upload() {
+        let provider = RustackS3::new(crate::config::S3Config::default());
+        provider
+            .handle_create_bucket(create_input("uploads"))
+            .await
+            .unwrap_or_else(|e| panic!("create failed: {e}"));
+        let upload_id = start_upload(&provider, "uploads", "big.bin").await;
+
+        let err = delete_bucket(&provider, "uploads")
+            .await
+            .err()
+            .unwrap_or_else(|| panic!("bucket with an upload deleted"));
+        assert_eq!(err.code.as_str(), "BucketNotEmpty");
+        assert_eq!(provider.storage().stats().part_count, 1);
+
+        provider
+            .handle_abort_multipart_upload(rustack_s3_model::input::AbortMultipartUploadInput {
+                bucket: "uploads".to_owned(),
+                key: "big.bin".to_owned(),
+                upload_id,
+                ..Default::default()
+            })
+            .await
+            .unwrap_or_else(|e| panic!("abort failed: {e}"));
+        delete_bucket(&provider, "uploads")
+            .await
+            .unwrap_or_else(|e| panic!("delete failed: {e}"));
+    }
+
+    #[tokio::test]
+    async fn test_should_reject_deleting_bucket_with_only_versions_or_delete_markers() {
+        use rustack_s3_model::{
+            input::{DeleteObjectInput, PutBucketVersioningInput, PutObjectInput},
+            types::{BucketVersioningStatus, VersioningConfiguration},
+        };
+
+        let provider = RustackS3::new(crate::config::S3Config::default());
+        provider
+            .handle_create_bucket(create_input("versioned"))
+            .await
+            .unwrap_or_else(|e| panic!("create failed: {e}"));
+        provider
+            .handle_put_bucket_versioning(PutBucketVersioningInput {
+                bucket: "versioned".to_owned(),
+                versioning_configuration: VersioningConfiguration {
+                    status: Some(BucketVersioningStatus::from("Enabled")),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .await
+            .unwrap_or_else(|e| panic!("put versioning failed: {e}"));
+        let object_version = provider
+            .handle_put_object(PutObjectInput {
+                bucket: "versioned".to_owned(),
+                key: "key".to_owned(),
+                body: Some(rustack_s3_model::request::StreamingBlob::new(
+                    bytes::Bytes::from_static(b"data"),
+                )),
+                ..Default::default()
+            })
+            .await
+            .unwrap_or_else(|e| panic!("put failed: {e}"))
+            .version_id
+            .unwrap_or_else(|| panic!("no version id"));
+        let delete_version = |version_id: Option<String>| DeleteObjectInput {
+            bucket: "versioned".to_owned(),
+            key: "key".to_owned(),
+            version_id,
+            ..Default::default()
+        };
+        let marker_version = provider
+            .handle_delete_object(delete_version(None))
+            .await
+            .unwrap_or_else(|e| panic!("delete object failed: {e}"))
+            .version_id
+            .unwrap_or_else(|| panic!("no delete marker version"));
+
+        // A noncurrent version behind a delete marker, then the marker alone.
+        for removed in [None, Some(object_version)] {
+            if let Some(version_id) = removed {
+                provider
+                    .handle_delete_object(delete_version(Some(version_id)))
+                    .await
+                    .unwrap_or_else(|e| panic!("delete version failed: {e}"));
+            }
+            let err = delete_bucket(&provider, "versioned")
+                .await
+                .err()
+                .unwrap_or_else(|| panic!("bucket with versions deleted"));
+            assert_eq!(err.code.as_str(), "BucketNotEmpty");
+        }
+
+        provider
+            .handle_delete_object(delete_version(Some(marker_version)))
+            .await
+            .unwrap_or_else(|e| panic!("delete marker failed: {e}"));
+        delete_bucket(&provider, "versioned")
+            .await
+            .unwrap_or_else(|e| panic!("delete failed: {e}"));
+    }
+
+    #[tokio::test]
+    async fn test_should_recreate_deleted_bucket_without_stale_uploads() {
+        use rustack_s3_model::{
+            input::{ListMultipartUploadsInput, ListPartsInput},
+            types::{
+                AbortIncompleteMultipartUpload, BucketLifecycleConfiguration, ExpirationStatus,
+                LifecycleRule,
+            },
+        };
+
+        // Parts above 64 bytes spill to disk.
+        let provider = RustackS3::new(crate::config::S3Config {
+            s3_max_memory_object_size: 64,
+            ..Default::default()
+        });
+        provider
+            .handle_create_bucket(create_input("reused"))
+            .await
+            .unwrap_or_else(|e| panic!("create failed: {e}"));
+        let upload_id = start_upload(&provider, "reused", "stale.bin").await;
+        assert!(provider.storage().stats().disk_bytes > 0);
+
+        // The upload has outlived a lifecycle rule, so it no longer counts.
+        {
+            let bucket = provider
+                .state()
+                .get_bucket("reused")
+                .unwrap_or_else(|e| panic!("get failed: {e}"));
+            bucket
+                .multipart_uploads
+                .get_mut(&upload_id)
+                .unwrap_or_else(|| panic!("upload missing"))
+                .initiated -= chrono::TimeDelta::days(2);
+            *bucket.lifecycle.write() = Some(BucketLifecycleConfiguration {
+                rules: vec![LifecycleRule {
+                    abort_incomplete_multipart_upload: Some(AbortIncompleteMultipartUpload {
+                        days_after_initiation: Some(1),
+                    }),
+                    status: ExpirationStatus::Enabled,
+                    ..Default::default()
+                }],
+            });
+        }
+        delete_bucket(&provider, "reused")
+            .await
+            .unwrap_or_else(|e| panic!("delete failed: {e}"));
+
+        let stats = provider.storage().stats();
+        assert_eq!((stats.part_count, stats.disk_bytes), (0, 0));
+
+        provider
+            .handle_create_bucket(create_input("reused"))
+            .await
+            .unwrap_or_else(|e| panic!("recreate failed: {e}"));
+        let uploads = provider
+            .handle_list_multipart_uploads(ListMultipartUploadsInput {
+                bucket: "reused".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .unwrap_or_else(|e| panic!("list uploads failed: {e}"));
+        assert!(uploads.uploads.is_empty());
+        let err = provider
+            .handle_list_parts(ListPartsInput {
+                bucket: "reused".to_owned(),
+                key: "stale.bin".to_owned(),
+                upload_id,
+                ..Default::default()
+            })
+            .await
+            .err()
+            .unwrap_or_else(|| panic!("stale upload listed"));
+        assert_eq!(err.code.as_str(), "NoSuchUpload");
+
+        // An upload in the new bucket starts from its own parts only.
+        start_upload(&provider, "reused", "fresh.bin").await;
+        assert_eq!(provider.storage().stats().part_count, 1);
+    }
+}
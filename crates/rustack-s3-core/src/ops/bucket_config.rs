@@ -6,7 +6,7 @@
 //! policy status operations.
 
 use rustack_s3_model::{
-    error::S3Error,
+    error::{S3Error, S3ErrorCode},
     input::{
         DeleteBucketCorsInput, DeleteBucketEncryptionInput, DeleteBucketLifecycleInput,
         DeleteBucketOwnershipControlsInput, DeleteBucketPolicyInput, DeleteBucketTaggingInput,
@@ -34,11 +34,11 @@ use rustack_s3_model::{
         PutBucketLifecycleConfigurationOutput, PutObjectLockConfigurationOutput,
     },
     types::{
-        BucketAccelerateStatus, BucketVersioningStatus, CORSRule,
-        DefaultRetention as ModelDefaultRetention, ErrorDocument, Grant, Grantee, IndexDocument,
+        BucketAccelerateStatus, BucketCannedACL, BucketVersioningStatus, CORSRule,
+        DefaultRetention as ModelDefaultRetention, ErrorDocument, IndexDocument,
         ObjectLockConfiguration as ModelObjectLockConfiguration, ObjectLockEnabled,
         ObjectLockRetentionMode, ObjectLockRule as ModelObjectLockRule, ObjectOwnership,
-        OwnershipControls, OwnershipControlsRule, Payer, Permission, PolicyStatus, Protocol,
+        OwnershipControls, OwnershipControlsRule, Payer, PolicyStatus, Protocol,
         PublicAccessBlockConfiguration, RedirectAllRequestsTo, ServerSideEncryption,
         ServerSideEncryptionByDefault, ServerSideEncryptionConfiguration, ServerSideEncryptionRule,
         Tag,
@@ -48,15 +48,17 @@ use tracing::debug;
 
 use super::bucket::to_model_owner;
 use crate::{
+    acl::{AclRequest, check_acl_supported, effective_grants, to_model_grants},
     cors::CorsRule,
     error::S3ServiceError,
+    policy::{BucketPolicy, bucket_is_public},
     provider::RustackS3,
     state::{
         bucket::{
             BucketEncryption, CorsRuleConfig, ObjectLockConfiguration, ObjectLockRule,
             OwnershipControlsConfig, PublicAccessBlockConfig, VersioningStatus, WebsiteConfig,
         },
-        object::{CannedAcl, Owner as InternalOwner},
+        object::CannedAcl,
     },
 };
 
@@ -400,6 +402,16 @@ impl RustackS3 {
             .get_bucket(&bucket_name)
             .map_err(S3ServiceError::into_s3_error)?;
 
+        let policy = BucketPolicy::parse(&input.policy)?;
+        let blocks_public_policy = bucket
+            .public_access_block
+            .read()
+            .as_ref()
+            .is_some_and(|c| c.block_public_policy);
+        if blocks_public_policy && policy.is_public() {
+            return Err(S3Error::access_denied(format!("/{bucket_name}")));
+        }
+
         *bucket.policy.write() = Some(input.policy);
 
         debug!(bucket = %bucket_name, "put_bucket_policy completed");
@@ -476,7 +488,7 @@ impl RustackS3 {
             .map(|t| (t.key, t.value))
             .collect();
 
-        crate::validation::validate_tags(&tags).map_err(S3ServiceError::into_s3_error)?;
+        crate::validation::validate_bucket_tags(&tags).map_err(S3ServiceError::into_s3_error)?;
 
         *bucket.tags.write() = tags;
 
@@ -554,29 +566,44 @@ impl RustackS3 {
     ) -> Result<GetBucketLoggingOutput, S3Error> {
         let bucket_name = input.bucket;
 
-        let _bucket = self
+        let bucket = self
             .state
             .get_bucket(&bucket_name)
             .map_err(S3ServiceError::into_s3_error)?;
 
         Ok(GetBucketLoggingOutput {
-            logging_enabled: None,
+            logging_enabled: bucket.logging.read().clone(),
         })
     }
 
     /// Set logging configuration for a bucket.
+    ///
+    /// An empty `BucketLoggingStatus` disables logging. The target bucket must
+    /// exist; records are delivered by [`RustackS3::flush_access_logs`].
     pub async fn handle_put_bucket_logging(
         &self,
         input: PutBucketLoggingInput,
     ) -> Result<(), S3Error> {
         let bucket_name = input.bucket;
+        let logging = input.bucket_logging_status.logging_enabled;
+
+        if let Some(ref enabled) = logging {
+            if !self.state.bucket_exists(&enabled.target_bucket) {
+                let mut err = S3Error::with_message(
+                    S3ErrorCode::Custom("InvalidTargetBucketForLogging"),
+                    "The target bucket for logging does not exist",
+                );
+                err.status_code = http::StatusCode::BAD_REQUEST;
+                return Err(err);
+            }
+        }
 
         let bucket = self
             .state
             .get_bucket(&bucket_name)
             .map_err(S3ServiceError::into_s3_error)?;
 
-        *bucket.logging.write() = Some(serde_json::json!({"status": "configured"}));
+        *bucket.logging.write() = logging;
 
         debug!(bucket = %bucket_name, "put_bucket_logging completed");
         Ok(())
@@ -791,10 +818,15 @@ impl RustackS3 {
             .get_bucket(&bucket_name)
             .map_err(S3ServiceError::into_s3_error)?;
 
-        if let Some(config) = input.object_lock_configuration {
-            *bucket.object_lock_enabled.write() = true;
-            bucket.enable_versioning();
+        // Object Lock can only be enabled when the bucket is created.
+        if !*bucket.object_lock_enabled.read() {
+            return Err(S3Error::with_message(
+                S3ErrorCode::InvalidBucketState,
+                "Object Lock configuration cannot be enabled on existing buckets",
+            ));
+        }
 
+        if let Some(config) = input.object_lock_configuration {
             let internal_config = ObjectLockConfiguration {
                 object_lock_enabled: config
                     .object_lock_enabled
@@ -1026,11 +1058,20 @@ impl RustackS3 {
             .map_err(S3ServiceError::into_s3_error)?;
 
         let owner = to_model_owner(&bucket.owner);
-        let acl = *bucket.acl.read();
-        let grants = canned_acl_to_grants(&bucket.owner, acl);
+        // With ACLs disabled, the stored ACL no longer applies.
+        let grants = if bucket.acls_disabled() {
+            effective_grants(CannedAcl::Private, None, &bucket.owner, &bucket.owner)
+        } else {
+            effective_grants(
+                *bucket.acl.read(),
+                bucket.grants.read().as_deref(),
+                &bucket.owner,
+                &bucket.owner,
+            )
+        };
 
         Ok(GetBucketAclOutput {
-            grants,
+            grants: to_model_grants(&grants),
             owner: Some(owner),
         })
     }
@@ -1044,12 +1085,20 @@ impl RustackS3 {
             .get_bucket(&bucket_name)
             .map_err(S3ServiceError::into_s3_error)?;
 
-        if let Some(acl_val) = input.acl {
-            let acl: CannedAcl = acl_val
-                .as_str()
-                .parse()
-                .map_err(|_| S3Error::invalid_argument("Invalid canned ACL"))?;
-            *bucket.acl.write() = acl;
+        let update = AclRequest {
+            canned: input.acl.as_ref().map(BucketCannedACL::as_str),
+            grant_full_control: input.grant_full_control.as_deref(),
+            grant_read: input.grant_read.as_deref(),
+            grant_read_acp: input.grant_read_acp.as_deref(),
+            grant_write: input.grant_write.as_deref(),
+            grant_write_acp: input.grant_write_acp.as_deref(),
+            policy: input.access_control_policy.as_ref(),
+        }
+        .resolve(&bucket.owner)
+        .map_err(S3ServiceError::into_s3_error)?;
+        check_acl_supported(&bucket, update.as_ref()).map_err(S3ServiceError::into_s3_error)?;
+        if let Some(update) = update {
+            update.apply(&mut bucket.acl.write(), &mut bucket.grants.write());
         }
 
         debug!(bucket = %bucket_name, "put_bucket_acl completed");
@@ -1061,20 +1110,25 @@ impl RustackS3 {
     // -----------------------------------------------------------------------
 
     /// Get the policy status for a bucket.
+    ///
+    /// `IsPublic` reflects both the bucket policy and the bucket ACL, as
+    /// filtered by the bucket's public access block.
     pub async fn handle_get_bucket_policy_status(
         &self,
         input: GetBucketPolicyStatusInput,
     ) -> Result<GetBucketPolicyStatusOutput, S3Error> {
         let bucket_name = input.bucket;
 
-        let _bucket = self
+        let bucket = self
             .state
             .get_bucket(&bucket_name)
             .map_err(S3ServiceError::into_s3_error)?;
 
+        let is_public = bucket_is_public(&bucket);
+
         Ok(GetBucketPolicyStatusOutput {
             policy_status: Some(PolicyStatus {
-                is_public: Some(false),
+                is_public: Some(is_public),
             }),
         })
     }
@@ -1107,34 +1161,3 @@ fn dto_to_cors_config(rule: &CORSRule) -> CorsRuleConfig {
         max_age_seconds: rule.max_age_seconds,
     }
 }
-
-/// Convert a canned ACL to a list of model [`Grant`] DTOs.
-fn canned_acl_to_grants(owner: &InternalOwner, acl: CannedAcl) -> Vec<Grant> {
-    let owner_grant = Grant {
-        grantee: Some(Grantee {
-            display_name: Some(owner.display_name.clone()),
-            email_address: None,
-            id: Some(owner.id.clone()),
-            r#type: rustack_s3_model::types::Type::from("CanonicalUser"),
-            uri: None,
-        }),
-        permission: Some(Permission::from("FULL_CONTROL")),
-    };
-
-    match acl {
-        CannedAcl::PublicRead => {
-            let public_read = Grant {
-                grantee: Some(Grantee {
-                    display_name: None,
-                    email_address: None,
-                    id: None,
-                    r#type: rustack_s3_model::types::Type::from("Group"),
-                    uri: Some("http://acs.amazonaws.com/groups/global/AllUsers".to_owned()),
-                }),
-                permission: Some(Permission::from("READ")),
-            };
-            vec![owner_grant, public_read]
-        }
-        _ => vec![owner_grant],
-    }
-}
@@ -1,13 +1,16 @@
 //! List operation handlers.
 //!
 //! Implements `list_objects` (v1), `list_objects_v2`, and `list_object_versions`.
+//!
+//! With `EncodingType=url`, keys, prefixes, markers, and the delimiter are
+//! URL-encoded in the response, as S3 does.
 
 use rustack_s3_model::{
     error::S3Error,
     input::{ListObjectVersionsInput, ListObjectsInput, ListObjectsV2Input},
     output::{ListObjectVersionsOutput, ListObjectsOutput, ListObjectsV2Output},
     types::{
-        CommonPrefix, DeleteMarkerEntry, Object, ObjectStorageClass, ObjectVersion,
+        CommonPrefix, DeleteMarkerEntry, EncodingType, Object, ObjectStorageClass, ObjectVersion,
         ObjectVersionStorageClass, Owner,
     },
 };
@@ -17,7 +20,7 @@ use crate::{
     error::S3ServiceError,
     provider::RustackS3,
     state::{keystore::VersionListEntry, object::Owner as InternalOwner},
-    utils::{decode_continuation_token, encode_continuation_token},
+    utils::{decode_continuation_token, encode_continuation_token, url_encode_listing_key},
 };
 
 /// Default maximum number of keys returned in a single listing response.
@@ -42,9 +45,39 @@ fn validate_max_keys(max_keys: Option<i32>) -> Result<i32, S3Error> {
     Ok(value)
 }
 
+/// How a listing writes keys into its response, per the request's
+/// `EncodingType`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct KeyEncoding {
+    url: bool,
+}
+
+impl KeyEncoding {
+    /// The encoding requested by `encoding_type`.
+    pub(crate) fn new(encoding_type: Option<&EncodingType>) -> Self {
+        Self {
+            url: encoding_type == Some(&EncodingType::Url),
+        }
+    }
+
+    /// Encode a key, prefix, marker, or delimiter.
+    pub(crate) fn encode(self, key: String) -> String {
+        if self.url {
+            url_encode_listing_key(&key)
+        } else {
+            key
+        }
+    }
+
+    /// Encode an optional key, prefix, marker, or delimiter.
+    pub(crate) fn encode_opt(self, key: Option<String>) -> Option<String> {
+        key.map(|key| self.encode(key))
+    }
+}
+
 /// Convert an internal [`crate::state::object::S3Object`] to a model [`Object`].
 #[allow(clippy::cast_possible_wrap)]
-fn to_model_object(obj: &crate::state::object::S3Object) -> Object {
+fn to_model_object(obj: &crate::state::object::S3Object, encoding: KeyEncoding) -> Object {
     let owner = Owner {
         display_name: Some(obj.owner.display_name.clone()),
         id: Some(obj.owner.id.clone()),
@@ -53,7 +86,7 @@ fn to_model_object(obj: &crate::state::object::S3Object) -> Object {
         checksum_algorithm: Vec::new(),
         checksum_type: None,
         e_tag: Some(obj.etag.clone()),
-        key: Some(obj.key.clone()),
+        key: Some(encoding.encode(obj.key.clone())),
         last_modified: Some(obj.last_modified),
         owner: Some(owner),
         restore_status: None,
@@ -71,11 +104,11 @@ fn to_model_owner(owner: &InternalOwner) -> Owner {
 }
 
 /// Convert common prefix strings to model [`CommonPrefix`] values.
-fn to_common_prefixes(prefixes: &[String]) -> Vec<CommonPrefix> {
+fn to_common_prefixes(prefixes: &[String], encoding: KeyEncoding) -> Vec<CommonPrefix> {
     prefixes
         .iter()
         .map(|p| CommonPrefix {
-            prefix: Some(p.clone()),
+            prefix: Some(encoding.encode(p.clone())),
         })
         .collect()
 }
@@ -106,16 +139,24 @@ impl RustackS3 {
         let marker = input.marker.as_deref().unwrap_or("");
         let max_keys = validate_max_keys(input.max_keys)?;
         let max_keys_usize = usize::try_from(max_keys).unwrap_or(1000);
+        let encoding = KeyEncoding::new(input.encoding_type.as_ref());
 
         let store = bucket.objects.read();
         let result = store.list_objects(prefix, delimiter, marker, max_keys_usize);
         drop(store);
         drop(bucket);
 
-        let contents: Vec<Object> = result.objects.iter().map(to_model_object).collect();
-        let common_prefixes = to_common_prefixes(&result.common_prefixes);
+        let contents: Vec<Object> = result
+            .objects
+            .iter()
+            .map(|obj| to_model_object(obj, encoding))
+            .collect();
+        let common_prefixes = to_common_prefixes(&result.common_prefixes, encoding);
 
-        let next_marker = if result.is_truncated {
+        // S3 returns NextMarker only for delimited listings: the last key or
+        // common prefix of the page. Without a delimiter, clients continue
+        // from the last key.
+        let next_marker = if result.is_truncated && !delimiter.is_empty() {
             result.next_marker.clone()
         } else {
             None
@@ -132,14 +173,14 @@ impl RustackS3 {
         Ok(ListObjectsOutput {
             common_prefixes,
             contents,
-            delimiter: input.delimiter,
+            delimiter: encoding.encode_opt(input.delimiter),
             encoding_type: input.encoding_type,
             is_truncated: Some(result.is_truncated),
-            marker: input.marker,
+            marker: encoding.encode_opt(input.marker),
             max_keys: Some(max_keys),
             name: Some(bucket_name),
-            next_marker,
-            prefix: input.prefix,
+            next_marker: encoding.encode_opt(next_marker),
+            prefix: encoding.encode_opt(input.prefix),
             request_charged: None,
         })
     }
@@ -161,6 +202,7 @@ impl RustackS3 {
         let max_keys = validate_max_keys(input.max_keys)?;
         let max_keys_usize = usize::try_from(max_keys).unwrap_or(1000);
         let fetch_owner = input.fetch_owner.unwrap_or(false);
+        let encoding = KeyEncoding::new(input.encoding_type.as_ref());
 
         // Determine start_after: either from continuation token or start_after param.
         let decoded_token = if let Some(token) = &input.continuation_token {
@@ -182,14 +224,14 @@ impl RustackS3 {
             .objects
             .iter()
             .map(|obj| {
-                let mut s3_obj = to_model_object(obj);
+                let mut s3_obj = to_model_object(obj, encoding);
                 if !fetch_owner {
                     s3_obj.owner = None;
                 }
                 s3_obj
             })
             .collect();
-        let common_prefixes = to_common_prefixes(&result.common_prefixes);
+        let common_prefixes = to_common_prefixes(&result.common_prefixes, encoding);
 
         let next_continuation_token = if result.is_truncated {
             result
@@ -214,16 +256,16 @@ impl RustackS3 {
             common_prefixes,
             contents,
             continuation_token: input.continuation_token,
-            delimiter: input.delimiter,
+            delimiter: encoding.encode_opt(input.delimiter),
             encoding_type: input.encoding_type,
             is_truncated: Some(result.is_truncated),
             key_count: Some(key_count),
             max_keys: Some(max_keys),
             name: Some(bucket_name),
             next_continuation_token,
-            prefix: input.prefix,
+            prefix: encoding.encode_opt(input.prefix),
             request_charged: None,
-            start_after: input.start_after,
+            start_after: encoding.encode_opt(input.start_after),
         })
     }
 
@@ -252,6 +294,7 @@ impl RustackS3 {
         let version_id_marker = input.version_id_marker.as_deref().unwrap_or("");
         let max_keys = validate_max_keys(input.max_keys)?;
         let max_keys_usize = usize::try_from(max_keys).unwrap_or(1000);
+        let encoding = KeyEncoding::new(input.encoding_type.as_ref());
 
         let store = bucket.objects.read();
         let result = store.list_object_versions(
@@ -265,9 +308,9 @@ impl RustackS3 {
         drop(bucket);
 
         // Separate versions and delete markers.
-        let (versions, delete_markers) = partition_version_list_entries(&result.versions);
+        let (versions, delete_markers) = partition_version_list_entries(&result.versions, encoding);
 
-        let common_prefixes = to_common_prefixes(&result.common_prefixes);
+        let common_prefixes = to_common_prefixes(&result.common_prefixes, encoding);
 
         debug!(
             bucket = %bucket_name,
@@ -281,15 +324,15 @@ impl RustackS3 {
         Ok(ListObjectVersionsOutput {
             common_prefixes,
             delete_markers,
-            delimiter: input.delimiter,
+            delimiter: encoding.encode_opt(input.delimiter),
             encoding_type: input.encoding_type,
             is_truncated: Some(result.is_truncated),
-            key_marker: input.key_marker,
+            key_marker: encoding.encode_opt(input.key_marker),
             max_keys: Some(max_keys),
             name: Some(bucket_name),
-            next_key_marker: result.next_key_marker,
+            next_key_marker: encoding.encode_opt(result.next_key_marker),
             next_version_id_marker: result.next_version_id_marker,
-            prefix: input.prefix,
+            prefix: encoding.encode_opt(input.prefix),
             request_charged: None,
             version_id_marker: input.version_id_marker,
             versions,
@@ -302,6 +345,7 @@ impl RustackS3 {
 #[allow(clippy::cast_possible_wrap)]
 fn partition_version_list_entries(
     entries: &[VersionListEntry],
+    encoding: KeyEncoding,
 ) -> (Vec<ObjectVersion>, Vec<DeleteMarkerEntry>) {
     let mut versions = Vec::new();
     let mut delete_markers = Vec::new();
@@ -315,7 +359,7 @@ fn partition_version_list_entries(
                     checksum_type: None,
                     e_tag: Some(obj.etag.clone()),
                     is_latest: Some(entry.is_latest),
-                    key: Some(obj.key.clone()),
+                    key: Some(encoding.encode(obj.key.clone())),
                     last_modified: Some(obj.last_modified),
                     owner: Some(owner),
                     restore_status: None,
@@ -330,7 +374,7 @@ fn partition_version_list_entries(
                 let owner = to_model_owner(&dm.owner);
                 delete_markers.push(DeleteMarkerEntry {
                     is_latest: Some(entry.is_latest),
-                    key: Some(dm.key.clone()),
+                    key: Some(encoding.encode(dm.key.clone())),
                     last_modified: Some(dm.last_modified),
                     owner: Some(owner),
                     version_id: Some(dm.version_id.clone()),
@@ -341,3 +385,174 @@ fn partition_version_list_entries(
 
     (versions, delete_markers)
 }
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use rustack_s3_model::{
+        input::{CreateBucketInput, PutObjectInput},
+        request::StreamingBlob,
+    };
+
+    use super::*;
+    use crate::config::S3Config;
+
+    const BUCKET: &str = "listed";
+
+    async fn provider_with_keys(keys: &[&str]) -> RustackS3 {
+        let provider = RustackS3::new(S3Config::default());
+        provider
+            .handle_create_bucket(CreateBucketInput {
+                bucket: BUCKET.to_owned(),
+                ..Default::default()
+            })
+            .await
+            .unwrap_or_else(|e| panic!("create failed: {e}"));
+        for key in keys {
+            provider
+                .handle_put_object(PutObjectInput {
+                    bucket: BUCKET.to_owned(),
+                    key: (*key).to_owned(),
+                    body: Some(StreamingBlob::new(Bytes::from_static(b"x"))),
+                    ..Default::default()
+                })
+                .await
+                .unwrap_or_else(|e| panic!("put failed: {e}"));
+        }
+        provider
+    }
+
+    async fn list_v1(provider: &RustackS3, input: ListObjectsInput) -> ListObjectsOutput {
+        provider
+            .handle_list_objects(ListObjectsInput {
+                bucket: BUCKET.to_owned(),
+                ..input
+            })
+            .await
+            .unwrap_or_else(|e| panic!("list failed: {e}"))
+    }
+
+    fn keys(contents: &[Object]) -> Vec<&str> {
+        contents.iter().filter_map(|o| o.key.as_deref()).collect()
+    }
+
+    fn prefixes(common_prefixes: &[CommonPrefix]) -> Vec<&str> {
+        common_prefixes
+            .iter()
+            .filter_map(|p| p.prefix.as_deref())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_should_page_v1_listing_by_next_marker_with_delimiter() {
+        let provider =
+            provider_with_keys(&["a.txt", "logs/1", "logs/2", "m.txt", "tmp/1", "tmp/2"]).await;
+
+        let mut seen = Vec::new();
+        let mut marker = None;
+        loop {
+            let page = list_v1(
+                &provider,
+                ListObjectsInput {
+                    delimiter: Some("/".to_owned()),
+                    marker: marker.clone(),
+                    max_keys: Some(1),
+                    ..Default::default()
+                },
+            )
+            .await;
+            assert_eq!(page.marker, marker);
+            seen.extend(keys(&page.contents).into_iter().map(str::to_owned));
+            seen.extend(
+                prefixes(&page.common_prefixes)
+                    .into_iter()
+                    .map(str::to_owned),
+            );
+            if page.is_truncated != Some(true) {
+                assert_eq!(page.next_marker, None);
+                break;
+            }
+            // The page's only element, whether a key or a prefix.
+            assert_eq!(page.next_marker.as_ref(), seen.last());
+            marker = page.next_marker;
+        }
+        assert_eq!(seen, ["a.txt", "logs/", "m.txt", "tmp/"]);
+    }
+
+    #[tokio::test]
+    async fn test_should_omit_next_marker_without_delimiter() {
+        let provider = provider_with_keys(&["a", "b", "c"]).await;
+        let page = list_v1(
+            &provider,
+            ListObjectsInput {
+                marker: Some("a".to_owned()),
+                max_keys: Some(1),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(keys(&page.contents), ["b"]);
+        assert_eq!(page.is_truncated, Some(true));
+        assert_eq!(page.marker.as_deref(), Some("a"));
+        assert_eq!(page.next_marker, None);
+    }
+
+    #[tokio::test]
+    async fn test_should_url_encode_listings_when_requested() {
+        let provider =
+            provider_with_keys(&["my docs/a b.txt", "my docs/x+y", "rocket 🚀/z", "top+1"]).await;
+
+        let plain = list_v1(&provider, ListObjectsInput::default()).await;
+        assert_eq!(
+            keys(&plain.contents),
+            ["my docs/a b.txt", "my docs/x+y", "rocket 🚀/z", "top+1"]
+        );
+
+        let v1 = list_v1(
+            &provider,
+            ListObjectsInput {
+                delimiter: Some(" ".to_owned()),
+                encoding_type: Some(EncodingType::Url),
+                marker: Some("my docs/a b.txt".to_owned()),
+                max_keys: Some(1),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(prefixes(&v1.common_prefixes), ["rocket+"]);
+        assert_eq!(v1.delimiter.as_deref(), Some("+"));
+        assert_eq!(v1.marker.as_deref(), Some("my+docs/a+b.txt"));
+        assert_eq!(v1.next_marker.as_deref(), Some("rocket+"));
+        assert_eq!(v1.encoding_type, Some(EncodingType::Url));
+
+        let v2 = provider
+            .handle_list_objects_v2(ListObjectsV2Input {
+                bucket: BUCKET.to_owned(),
+                encoding_type: Some(EncodingType::Url),
+                prefix: Some("my docs/".to_owned()),
+                start_after: Some("my docs/a b.txt".to_owned()),
+                ..Default::default()
+            })
+            .await
+            .unwrap_or_else(|e| panic!("list failed: {e}"));
+        assert_eq!(keys(&v2.contents), ["my+docs/x%2By"]);
+        assert_eq!(v2.prefix.as_deref(), Some("my+docs/"));
+        assert_eq!(v2.start_after.as_deref(), Some("my+docs/a+b.txt"));
+
+        let versions = provider
+            .handle_list_object_versions(ListObjectVersionsInput {
+                bucket: BUCKET.to_owned(),
+                encoding_type: Some(EncodingType::Url),
+                prefix: Some("r".to_owned()),
+                ..Default::default()
+            })
+            .await
+            .unwrap_or_else(|e| panic!("list failed: {e}"));
+        let version_keys: Vec<_> = versions
+            .versions
+            .iter()
+            .filter_map(|v| v.key.as_deref())
+            .collect();
+        assert_eq!(version_keys, ["rocket+%F0%9F%9A%80/z"]);
+    }
+}
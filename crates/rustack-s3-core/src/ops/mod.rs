@@ -7,6 +7,7 @@
 //! The server binary bridges these handlers to the HTTP layer by implementing
 //! the `S3Handler` trait from `rustack-s3-http`.
 
+pub mod access_point;
 pub mod bucket;
 pub mod bucket_config;
 pub mod list;
@@ -18,31 +18,40 @@ use rustack_s3_model::{
         ListMultipartUploadsOutput, ListPartsOutput, UploadPartCopyOutput, UploadPartOutput,
     },
     types::{
-        ChecksumAlgorithm, ChecksumType, CopyPartResult, Initiator,
-        MultipartUpload as ModelMultipartUpload, Part, StorageClass,
+        ChecksumAlgorithm, ChecksumType, CommonPrefix, CopyPartResult, Initiator,
+        MultipartUpload as ModelMultipartUpload, ObjectCannedACL, Part, StorageClass,
     },
 };
 use tracing::debug;
 
 use crate::{
+    acl::AclRequest,
     checksums::{
         ChecksumAlgorithm as CoreChecksumAlgorithm, compute_checksum, compute_composite_checksum,
     },
     error::S3ServiceError,
     provider::RustackS3,
+    sse::{CustomerKey, EncryptionFields, apply_default_encryption},
     state::{
+        bucket::S3Bucket,
         multipart::{MultipartUpload, UploadPart},
-        object::{ChecksumData, ObjectMetadata, Owner as InternalOwner, S3Object},
+        object::{ChecksumData, ObjectMetadata, ObjectPart, Owner as InternalOwner, S3Object},
     },
     utils::{generate_upload_id, parse_copy_source},
-    validation::{validate_content_md5, validate_object_key},
+    validation::{validate_content_md5, validate_metadata, validate_object_key},
 };
 
 /// Minimum part size for multipart uploads (5 MB). All parts except the last
 /// must be at least this size per the S3 specification.
 const MIN_PART_SIZE: u64 = 5 * 1024 * 1024;
 
-use super::bucket::to_model_owner;
+/// Maximum number of parts returned by one `ListParts` page.
+pub(crate) const MAX_LIST_PARTS: i32 = 1000;
+
+/// Maximum number of uploads returned by one `ListMultipartUploads` page.
+const MAX_LIST_UPLOADS: i32 = 1000;
+
+use super::{bucket::to_model_owner, list::KeyEncoding};
 
 // AWS S3 DTOs use signed integers (i32/i64) for inherently non-negative values.
 // These handler methods must remain async for consistency.
@@ -63,6 +72,16 @@ impl RustackS3 {
         let key = input.key;
 
         validate_object_key(&key).map_err(S3ServiceError::into_s3_error)?;
+        super::object::validate_tagging_header(input.tagging.as_deref())?;
+        if self.config.s3_strict_validation {
+            validate_metadata(&input.metadata).map_err(S3ServiceError::into_s3_error)?;
+        }
+        CustomerKey::from_headers(
+            input.sse_customer_algorithm.as_deref(),
+            input.sse_customer_key.as_deref(),
+            input.sse_customer_key_md5.as_deref(),
+        )
+        .map_err(S3ServiceError::into_s3_error)?;
 
         let bucket = self
             .state
@@ -70,15 +89,27 @@ impl RustackS3 {
             .map_err(S3ServiceError::into_s3_error)?;
 
         let upload_id = generate_upload_id();
+        let (acl, grants) = super::object::object_acl(
+            &AclRequest {
+                canned: input.acl.as_ref().map(ObjectCannedACL::as_str),
+                grant_full_control: input.grant_full_control.as_deref(),
+                grant_read: input.grant_read.as_deref(),
+                grant_read_acp: input.grant_read_acp.as_deref(),
+                grant_write_acp: input.grant_write_acp.as_deref(),
+                ..AclRequest::default()
+            },
+            &bucket,
+        )
+        .map_err(S3ServiceError::into_s3_error)?;
 
         // Build metadata from the request.
-        let metadata = ObjectMetadata {
+        let mut metadata = ObjectMetadata {
             content_type: input.content_type.clone(),
             content_encoding: input.content_encoding.clone(),
             content_disposition: input.content_disposition.clone(),
             content_language: input.content_language.clone(),
             cache_control: input.cache_control.clone(),
-            expires: None,
+            expires: input.expires.clone(),
             user_metadata: input.metadata.clone(),
             sse_algorithm: input
                 .server_side_encryption
@@ -91,13 +122,10 @@ impl RustackS3 {
             tagging: input
                 .tagging
                 .as_ref()
-                .map(|t| super::object::parse_tagging_header(t))
-                .unwrap_or_default(),
-            acl: input
-                .acl
-                .as_ref()
-                .and_then(|a| a.as_str().parse().ok())
+                .map(|t| super::object::parse_tagging_header(t).into())
                 .unwrap_or_default(),
+            acl,
+            grants,
             object_lock_mode: input
                 .object_lock_mode
                 .as_ref()
@@ -107,7 +135,10 @@ impl RustackS3 {
                 .object_lock_legal_hold_status
                 .as_ref()
                 .map(|s| s.as_str() == "ON"),
+            integrity_failure: None,
         };
+        apply_default_encryption(&mut metadata, bucket.encryption.read().as_ref());
+        let encryption = EncryptionFields::from_metadata(&metadata);
 
         let mut upload = MultipartUpload::new(
             upload_id.clone(),
@@ -140,12 +171,12 @@ impl RustackS3 {
             });
         }
 
-        upload.sse_algorithm = input
-            .server_side_encryption
-            .as_ref()
-            .map(|s| s.as_str().to_owned());
-
-        upload.sse_kms_key_id.clone_from(&input.ssekms_key_id);
+        upload
+            .sse_algorithm
+            .clone_from(&upload.metadata.sse_algorithm);
+        upload
+            .sse_kms_key_id
+            .clone_from(&upload.metadata.sse_kms_key_id);
 
         let output_checksum_type = upload.checksum_type.as_ref().map(|ct| match ct.as_str() {
             "FULL_OBJECT" => ChecksumType::FullObject,
@@ -165,7 +196,7 @@ impl RustackS3 {
             abort_date: None,
             abort_rule_id: None,
             bucket: Some(bucket_name),
-            bucket_key_enabled: None,
+            bucket_key_enabled: encryption.bucket_key_enabled,
             checksum_algorithm: input.checksum_algorithm,
             checksum_type: output_checksum_type,
             key: Some(key),
@@ -173,8 +204,8 @@ impl RustackS3 {
             sse_customer_algorithm: None,
             sse_customer_key_md5: None,
             ssekms_encryption_context: None,
-            ssekms_key_id: None,
-            server_side_encryption: None,
+            ssekms_key_id: encryption.ssekms_key_id,
+            server_side_encryption: encryption.server_side_encryption,
             upload_id: Some(upload_id),
         })
     }
@@ -203,16 +234,15 @@ impl RustackS3 {
             .state
             .get_bucket(&bucket_name)
             .map_err(S3ServiceError::into_s3_error)?;
+        self.abort_expired_uploads(&bucket_name, &bucket);
 
         // Verify the upload exists and get its checksum algorithm.
-        let upload_checksum_algorithm = {
-            let upload_ref = bucket.multipart_uploads.get(&upload_id).ok_or_else(|| {
-                S3ServiceError::NoSuchUpload {
-                    upload_id: upload_id.clone(),
-                }
-                .into_s3_error()
-            })?;
-            upload_ref.checksum_algorithm.clone()
+        let (upload_checksum_algorithm, encryption) = {
+            let upload_ref = get_upload(&bucket, &upload_id, &key)?;
+            (
+                upload_ref.checksum_algorithm.clone(),
+                EncryptionFields::from_metadata(&upload_ref.metadata),
+            )
         };
 
         // Collect body data.
@@ -274,10 +304,7 @@ impl RustackS3 {
             last_modified: Utc::now(),
             checksum,
         };
-
-        if let Some(mut upload) = bucket.multipart_uploads.get_mut(&upload_id) {
-            upload.put_part(part);
-        }
+        self.record_part(&bucket, &bucket_name, &upload_id, part)?;
 
         debug!(
             bucket = %bucket_name,
@@ -288,7 +315,7 @@ impl RustackS3 {
         );
 
         Ok(UploadPartOutput {
-            bucket_key_enabled: None,
+            bucket_key_enabled: encryption.bucket_key_enabled,
             checksum_crc32: out_crc32,
             checksum_crc32c: out_crc32c,
             checksum_crc64nvme: out_crc64nvme,
@@ -298,8 +325,8 @@ impl RustackS3 {
             request_charged: None,
             sse_customer_algorithm: None,
             sse_customer_key_md5: None,
-            ssekms_key_id: None,
-            server_side_encryption: None,
+            ssekms_key_id: encryption.ssekms_key_id,
+            server_side_encryption: encryption.server_side_encryption,
         })
     }
 
@@ -315,27 +342,28 @@ impl RustackS3 {
         let (src_bucket, src_key, src_version_id) =
             parse_copy_source(&input.copy_source).map_err(S3ServiceError::into_s3_error)?;
 
-        // Read source object data.
-        let src_vid = src_version_id.as_deref().unwrap_or("null");
-        let data = self
-            .storage
-            .read_object(&src_bucket, &src_key, src_vid, None)
-            .await
-            .map_err(|e| S3ServiceError::Internal(anyhow::anyhow!("{e}")).into_s3_error())?;
+        let bucket = self
+            .state
+            .get_bucket(&bucket_name)
+            .map_err(S3ServiceError::into_s3_error)?;
+        self.abort_expired_uploads(&bucket_name, &bucket);
+        get_upload(&bucket, &upload_id, &input.key)?;
 
-        // Write as part.
+        // Share the source object's data as the part.
+        let src_vid = src_version_id.as_deref().unwrap_or("null");
         let write_result = self
             .storage
-            .write_part(&bucket_name, &upload_id, part_number as u32, data)
-            .await
+            .copy_part(
+                &src_bucket,
+                &src_key,
+                src_vid,
+                &bucket_name,
+                &upload_id,
+                part_number as u32,
+            )
             .map_err(|e| S3ServiceError::Internal(anyhow::anyhow!("{e}")).into_s3_error())?;
 
         // Record the part metadata.
-        let bucket = self
-            .state
-            .get_bucket(&bucket_name)
-            .map_err(S3ServiceError::into_s3_error)?;
-
         let part = UploadPart {
             part_number: part_number as u32,
             etag: write_result.etag.clone(),
@@ -343,10 +371,7 @@ impl RustackS3 {
             last_modified: Utc::now(),
             checksum: None,
         };
-
-        if let Some(mut upload) = bucket.multipart_uploads.get_mut(&upload_id) {
-            upload.put_part(part);
-        }
+        self.record_part(&bucket, &bucket_name, &upload_id, part)?;
 
         let copy_result = CopyPartResult {
             checksum_crc32: None,
@@ -384,24 +409,19 @@ impl RustackS3 {
             .state
             .get_bucket(&bucket_name)
             .map_err(S3ServiceError::into_s3_error)?;
+        self.abort_expired_uploads(&bucket_name, &bucket);
 
-        // Get the upload.
-        let upload = bucket
-            .multipart_uploads
-            .get(&upload_id)
-            .ok_or_else(|| {
-                S3ServiceError::NoSuchUpload {
-                    upload_id: upload_id.clone(),
-                }
-                .into_s3_error()
-            })?
-            .clone();
+        // Snapshot the upload; parts may still be uploaded concurrently.
+        let upload = get_upload(&bucket, &upload_id, &key)?.clone();
 
         // Extract the requested part list.
         let requested_parts = input
             .multipart_upload
             .map(|mu| mu.parts)
             .unwrap_or_default();
+        if requested_parts.is_empty() {
+            return Err(S3ServiceError::MalformedXml.into_s3_error());
+        }
 
         // Validate parts are in order and exist.
         let mut part_numbers: Vec<u32> = Vec::with_capacity(requested_parts.len());
@@ -421,10 +441,18 @@ impl RustackS3 {
                 S3Error::with_message(S3ErrorCode::InvalidArgument, "Invalid part number")
             })?;
 
-            // Verify the part exists in our upload record.
-            upload
+            // Verify the part exists in this upload and, when the client
+            // names an ETag, that it is the part's current ETag.
+            let part = upload
                 .get_part(part_num_u32)
                 .ok_or_else(|| S3ServiceError::InvalidPart.into_s3_error())?;
+            if cp
+                .e_tag
+                .as_deref()
+                .is_some_and(|etag| etag.trim_matches('"') != part.etag.trim_matches('"'))
+            {
+                return Err(S3ServiceError::InvalidPart.into_s3_error());
+            }
 
             part_numbers.push(part_num_u32);
         }
@@ -455,12 +483,25 @@ impl RustackS3 {
             "null".to_owned()
         };
 
-        // Assemble parts in storage.
-        let (write_result, _part_md5s) = self
+        // Assemble the parts. A part re-uploaded since the snapshot no longer
+        // has the ETag that was validated, so the upload is rejected.
+        let assembled = self
             .storage
-            .complete_multipart(&bucket_name, &upload_id, &key, &version_id, &part_numbers)
+            .assemble_multipart(&bucket_name, &upload_id, &part_numbers)
             .await
-            .map_err(|e| S3ServiceError::Internal(anyhow::anyhow!("{e}")).into_s3_error())?;
+            .map_err(S3ServiceError::into_s3_error)?;
+        let parts_unchanged = part_numbers
+            .iter()
+            .zip(&assembled.part_md5s)
+            .all(|(&num, md5)| {
+                upload
+                    .get_part(num)
+                    .is_some_and(|part| part.etag.trim_matches('"') == md5)
+            });
+        if !parts_unchanged {
+            return Err(S3ServiceError::InvalidPart.into_s3_error());
+        }
+        let write_result = assembled.write_result.clone();
 
         // Compute the combined checksum for the final object if the multipart
         // upload was created with a checksum algorithm.
@@ -476,14 +517,7 @@ impl RustackS3 {
                 let checksum_type_str = upload.checksum_type.as_deref().unwrap_or("COMPOSITE");
 
                 let value = if checksum_type_str == "FULL_OBJECT" {
-                    let assembled = self
-                        .storage
-                        .read_object(&bucket_name, &key, &version_id, None)
-                        .await
-                        .map_err(|e| {
-                            S3ServiceError::Internal(anyhow::anyhow!("{e}")).into_s3_error()
-                        })?;
-                    compute_checksum(algo, &assembled)
+                    compute_checksum(algo, &assembled.data)
                 } else {
                     // Collect part checksums in order for composite calculation.
                     let part_checksums: Vec<String> = part_numbers
@@ -520,15 +554,24 @@ impl RustackS3 {
                 _ => ChecksumType::Composite,
             });
 
+        let encryption = EncryptionFields::from_metadata(&upload.metadata);
+        let mut metadata = upload.metadata.clone();
+        let now = Utc::now();
+        super::object::apply_default_retention(
+            &mut metadata,
+            bucket.default_retention().as_ref(),
+            now,
+        );
+
         // Build the final object.
         let obj = S3Object {
             key: key.clone(),
             version_id: version_id.clone(),
             etag: write_result.etag.clone(),
             size: write_result.size,
-            last_modified: Utc::now(),
+            last_modified: now,
             storage_class: upload.storage_class.clone(),
-            metadata: upload.metadata.clone(),
+            metadata,
             owner: upload.owner.clone(),
             checksum: final_checksum,
             parts_count: Some(part_numbers.len() as u32),
@@ -536,16 +579,34 @@ impl RustackS3 {
                 .iter()
                 .filter_map(|p| p.e_tag.clone())
                 .collect(),
+            parts: part_numbers
+                .iter()
+                .filter_map(|&num| upload.get_part(num))
+                .map(|part| ObjectPart {
+                    part_number: part.part_number,
+                    size: part.size,
+                    checksum: part.checksum.clone(),
+                })
+                .collect(),
         };
 
+        // Claim the upload, then store the data and the object metadata
+        // under the object lock so a concurrent completion of the same key
+        // cannot interleave its data with this object's metadata.
         {
             let mut store = bucket.objects.write();
+            if bucket.multipart_uploads.remove(&upload_id).is_none() {
+                // Completed or aborted concurrently.
+                return Err(S3ServiceError::NoSuchUpload {
+                    upload_id: upload_id.clone(),
+                }
+                .into_s3_error());
+            }
+            self.storage
+                .commit_multipart(&bucket_name, &upload_id, &key, &version_id, assembled);
             store.put(obj);
         }
 
-        // Remove the completed upload.
-        bucket.multipart_uploads.remove(&upload_id);
-
         debug!(
             bucket = %bucket_name,
             key = %key,
@@ -562,7 +623,7 @@ impl RustackS3 {
 
         Ok(CompleteMultipartUploadOutput {
             bucket: Some(bucket_name.clone()),
-            bucket_key_enabled: None,
+            bucket_key_enabled: encryption.bucket_key_enabled,
             checksum_crc32: out_crc32,
             checksum_crc32c: out_crc32c,
             checksum_crc64nvme: out_crc64nvme,
@@ -574,8 +635,8 @@ impl RustackS3 {
             key: Some(key),
             location: Some(format!("http://s3.amazonaws.com/{bucket_name}")),
             request_charged: None,
-            ssekms_key_id: None,
-            server_side_encryption: None,
+            ssekms_key_id: encryption.ssekms_key_id,
+            server_side_encryption: encryption.server_side_encryption,
             version_id: real_version_id,
         })
     }
@@ -586,18 +647,24 @@ impl RustackS3 {
         input: AbortMultipartUploadInput,
     ) -> Result<AbortMultipartUploadOutput, S3Error> {
         let bucket_name = input.bucket;
+        let key = input.key;
         let upload_id = input.upload_id;
 
         let bucket = self
             .state
             .get_bucket(&bucket_name)
             .map_err(S3ServiceError::into_s3_error)?;
+        self.abort_expired_uploads(&bucket_name, &bucket);
 
-        // Remove the upload metadata (idempotent: no error if already gone).
-        bucket.multipart_uploads.remove(&upload_id);
-
-        // Clean up storage parts.
-        self.storage.abort_multipart(&bucket_name, &upload_id);
+        // Remove the upload metadata and its parts (idempotent: no error if
+        // already gone). An upload of another key is left alone.
+        if bucket
+            .multipart_uploads
+            .remove_if(&upload_id, |_, upload| upload.key == key)
+            .is_some()
+        {
+            self.storage.abort_multipart(&bucket_name, &upload_id);
+        }
 
         debug!(
             bucket = %bucket_name,
@@ -623,15 +690,14 @@ impl RustackS3 {
             .state
             .get_bucket(&bucket_name)
             .map_err(S3ServiceError::into_s3_error)?;
+        self.abort_expired_uploads(&bucket_name, &bucket);
 
-        let upload = bucket.multipart_uploads.get(&upload_id).ok_or_else(|| {
-            S3ServiceError::NoSuchUpload {
-                upload_id: upload_id.clone(),
-            }
-            .into_s3_error()
-        })?;
+        let upload = get_upload(&bucket, &upload_id, &key)?;
 
-        let max_parts = input.max_parts.unwrap_or(1000) as usize;
+        let max_parts = input
+            .max_parts
+            .map_or(MAX_LIST_PARTS, |n| n.clamp(0, MAX_LIST_PARTS))
+            as usize;
         let part_number_marker: u32 = input
             .part_number_marker
             .as_deref()
@@ -704,6 +770,11 @@ impl RustackS3 {
     }
 
     /// List in-progress multipart uploads for a bucket.
+    ///
+    /// Uploads are ordered by key, then by initiation time. With a
+    /// delimiter, keys sharing a prefix up to the delimiter are rolled up
+    /// into one common prefix, which counts towards `max-uploads` like an
+    /// upload.
     pub async fn handle_list_multipart_uploads(
         &self,
         input: ListMultipartUploadsInput,
@@ -714,72 +785,175 @@ impl RustackS3 {
             .state
             .get_bucket(&bucket_name)
             .map_err(S3ServiceError::into_s3_error)?;
-
-        let prefix = input.prefix.unwrap_or_default();
-        let max_uploads = input.max_uploads.unwrap_or(1000) as usize;
+        self.abort_expired_uploads(&bucket_name, &bucket);
+
+        let max_uploads = match input.max_uploads {
+            None => MAX_LIST_UPLOADS,
+            Some(n) if n < 0 => {
+                return Err(S3Error::invalid_argument(format!(
+                    "Argument max-uploads must be an integer between 0 and {MAX_LIST_UPLOADS}"
+                )));
+            }
+            Some(n) => n.min(MAX_LIST_UPLOADS),
+        };
 
         let mut uploads: Vec<MultipartUpload> = bucket
             .multipart_uploads
             .iter()
-            .filter(|entry| entry.key.starts_with(&prefix))
             .map(|entry| entry.value().clone())
             .collect();
+        drop(bucket);
+        uploads.sort_by(|a, b| {
+            (&a.key, a.initiated, &a.upload_id).cmp(&(&b.key, b.initiated, &b.upload_id))
+        });
 
-        // Sort by key then by initiated time.
-        uploads.sort_by(|a, b| a.key.cmp(&b.key).then(a.initiated.cmp(&b.initiated)));
-
-        let is_truncated = uploads.len() > max_uploads;
-        let uploads_to_return = &uploads[..uploads.len().min(max_uploads)];
+        let page = page_uploads(
+            &uploads,
+            input.prefix.as_deref().unwrap_or_default(),
+            input.delimiter.as_deref().unwrap_or_default(),
+            input.key_marker.as_deref().unwrap_or_default(),
+            input.upload_id_marker.as_deref(),
+            max_uploads as usize,
+        );
+        let encoding = KeyEncoding::new(input.encoding_type.as_ref());
 
-        let s3_uploads: Vec<ModelMultipartUpload> = uploads_to_return
+        let s3_uploads: Vec<ModelMultipartUpload> = page
+            .uploads
             .iter()
             .map(|u| ModelMultipartUpload {
                 checksum_algorithm: u
                     .checksum_algorithm
                     .as_ref()
                     .map(|a| ChecksumAlgorithm::from(a.as_str())),
-                checksum_type: None,
+                checksum_type: u.checksum_type.as_deref().map(ChecksumType::from),
                 initiated: Some(u.initiated),
                 initiator: Some(Initiator {
                     display_name: Some(u.owner.display_name.clone()),
                     id: Some(u.owner.id.clone()),
                 }),
-                key: Some(u.key.clone()),
+                key: Some(encoding.encode(u.key.clone())),
                 owner: Some(to_model_owner(&u.owner)),
                 storage_class: Some(StorageClass::from(u.storage_class.as_str())),
                 upload_id: Some(u.upload_id.clone()),
             })
             .collect();
+        let common_prefixes = page
+            .common_prefixes
+            .into_iter()
+            .map(|prefix| CommonPrefix {
+                prefix: Some(encoding.encode(prefix)),
+            })
+            .collect();
 
-        let next_key_marker = if is_truncated {
-            s3_uploads.last().and_then(|u| u.key.clone())
-        } else {
-            None
-        };
-        let next_upload_id_marker = if is_truncated {
-            s3_uploads.last().and_then(|u| u.upload_id.clone())
-        } else {
-            None
-        };
+        debug!(
+            bucket = %bucket_name,
+            count = s3_uploads.len(),
+            is_truncated = page.is_truncated,
+            "list_multipart_uploads completed"
+        );
 
         Ok(ListMultipartUploadsOutput {
             bucket: Some(bucket_name),
-            common_prefixes: Vec::new(),
-            delimiter: input.delimiter,
-            encoding_type: None,
-            is_truncated: Some(is_truncated),
-            key_marker: input.key_marker,
-            max_uploads: Some(max_uploads as i32),
-            next_key_marker,
-            next_upload_id_marker,
-            prefix: Some(prefix),
+            common_prefixes,
+            delimiter: encoding.encode_opt(input.delimiter),
+            encoding_type: input.encoding_type,
+            is_truncated: Some(page.is_truncated),
+            key_marker: Some(encoding.encode(input.key_marker.unwrap_or_default())),
+            max_uploads: Some(max_uploads),
+            next_key_marker: encoding.encode_opt(page.next_key_marker),
+            next_upload_id_marker: page.next_upload_id_marker,
+            prefix: Some(encoding.encode(input.prefix.unwrap_or_default())),
             request_charged: None,
-            upload_id_marker: input.upload_id_marker,
+            upload_id_marker: Some(input.upload_id_marker.unwrap_or_default()),
             uploads: s3_uploads,
         })
     }
 }
 
+/// One page of a `ListMultipartUploads` listing.
+#[derive(Debug, Default)]
+struct UploadPage<'a> {
+    uploads: Vec<&'a MultipartUpload>,
+    common_prefixes: Vec<String>,
+    is_truncated: bool,
+    next_key_marker: Option<String>,
+    next_upload_id_marker: Option<String>,
+}
+
+/// Select the page of `uploads` (sorted by key, then initiation) after the
+/// markers.
+///
+/// Without `upload_id_marker`, listing resumes at the first key after
+/// `key_marker`; with it, at the upload of `key_marker` that follows the
+/// marker upload.
+fn page_uploads<'a>(
+    uploads: &'a [MultipartUpload],
+    prefix: &str,
+    delimiter: &str,
+    key_marker: &str,
+    upload_id_marker: Option<&str>,
+    max_uploads: usize,
+) -> UploadPage<'a> {
+    let resume_after = upload_id_marker.and_then(|marker| {
+        uploads
+            .iter()
+            .find(|u| u.key == key_marker && u.upload_id == marker)
+            .map(|u| (u.initiated, u.upload_id.as_str()))
+    });
+
+    let mut page = UploadPage::default();
+    let mut count = 0usize;
+    for upload in uploads {
+        if !upload.key.starts_with(prefix) {
+            continue;
+        }
+        let key = upload.key.as_str();
+        if !key_marker.is_empty() && key <= key_marker {
+            let resumes = key == key_marker
+                && resume_after.is_some_and(|(initiated, upload_id)| {
+                    (upload.initiated, upload.upload_id.as_str()) > (initiated, upload_id)
+                });
+            if !resumes {
+                continue;
+            }
+        }
+
+        let common_prefix = (!delimiter.is_empty())
+            .then(|| key[prefix.len()..].find(delimiter))
+            .flatten()
+            .map(|pos| &key[..prefix.len() + pos + delimiter.len()]);
+        if let Some(common_prefix) = common_prefix {
+            // A prefix at or before the marker was returned on an earlier page.
+            if page.common_prefixes.last().map(String::as_str) == Some(common_prefix)
+                || (!key_marker.is_empty() && common_prefix <= key_marker)
+            {
+                continue;
+            }
+        }
+
+        if count == max_uploads {
+            page.is_truncated = true;
+            break;
+        }
+        count += 1;
+        if let Some(common_prefix) = common_prefix {
+            page.next_key_marker = Some(common_prefix.to_owned());
+            page.next_upload_id_marker = None;
+            page.common_prefixes.push(common_prefix.to_owned());
+        } else {
+            page.next_key_marker = Some(upload.key.clone());
+            page.next_upload_id_marker = Some(upload.upload_id.clone());
+            page.uploads.push(upload);
+        }
+    }
+
+    if !page.is_truncated {
+        page.next_key_marker = None;
+        page.next_upload_id_marker = None;
+    }
+    page
+}
+
 /// Per-algorithm checksum output fields.
 type ChecksumOutputFields = (
     Option<String>,
@@ -818,7 +992,7 @@ fn extract_checksum_from_part(input: &UploadPartInput) -> Result<Option<Checksum
 
 /// Map an optional [`ChecksumData`] to individual output fields for the five
 /// supported algorithms.
-fn checksum_to_output_fields(checksum: Option<&ChecksumData>) -> ChecksumOutputFields {
+pub(crate) fn checksum_to_output_fields(checksum: Option<&ChecksumData>) -> ChecksumOutputFields {
     let Some(c) = checksum else {
         return (None, None, None, None, None);
     };
@@ -832,8 +1006,87 @@ fn checksum_to_output_fields(checksum: Option<&ChecksumData>) -> ChecksumOutputF
     }
 }
 
+/// Look up an in-progress upload of `key` in `bucket`.
+///
+/// An upload ID issued for a different key is reported as missing, as S3
+/// does.
+#[allow(clippy::result_large_err)]
+fn get_upload<'a>(
+    bucket: &'a S3Bucket,
+    upload_id: &str,
+    key: &str,
+) -> Result<dashmap::mapref::one::Ref<'a, String, MultipartUpload>, S3Error> {
+    bucket
+        .multipart_uploads
+        .get(upload_id)
+        .filter(|upload| upload.key == key)
+        .ok_or_else(|| {
+            S3ServiceError::NoSuchUpload {
+                upload_id: upload_id.to_owned(),
+            }
+            .into_s3_error()
+        })
+}
+
+impl RustackS3 {
+    /// Abort the multipart uploads of every bucket, in every namespace, that
+    /// have outlived a lifecycle `AbortIncompleteMultipartUpload` rule,
+    /// freeing their part data. Returns the number of uploads aborted.
+    ///
+    /// Expired uploads of a bucket are also aborted whenever its multipart
+    /// uploads are accessed.
+    #[must_use]
+    pub fn abort_expired_multipart_uploads(&self) -> usize {
+        let now = Utc::now();
+        let mut aborted = 0;
+        for state in self.states.values() {
+            for (bucket_name, upload_id) in state.remove_expired_uploads(now) {
+                self.storage.abort_multipart(&bucket_name, &upload_id);
+                aborted += 1;
+            }
+        }
+        aborted
+    }
+
+    /// Abort the uploads of `bucket` that have outlived a lifecycle rule.
+    pub(crate) fn abort_expired_uploads(&self, bucket_name: &str, bucket: &S3Bucket) {
+        for upload_id in bucket.remove_expired_uploads(Utc::now()) {
+            self.storage.abort_multipart(bucket_name, &upload_id);
+        }
+    }
+
+    /// Record a part whose data has been written to storage.
+    ///
+    /// If the upload was completed or aborted while the data was being
+    /// written, the orphaned part data is removed and `NoSuchUpload` returned.
+    #[allow(clippy::result_large_err)]
+    fn record_part(
+        &self,
+        bucket: &S3Bucket,
+        bucket_name: &str,
+        upload_id: &str,
+        part: UploadPart,
+    ) -> Result<(), S3Error> {
+        if let Some(mut upload) = bucket.multipart_uploads.get_mut(upload_id) {
+            upload.put_part(part);
+            return Ok(());
+        }
+        self.storage.abort_multipart(bucket_name, upload_id);
+        Err(S3ServiceError::NoSuchUpload {
+            upload_id: upload_id.to_owned(),
+        }
+        .into_s3_error())
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use rustack_s3_model::{
+        input::GetObjectAttributesInput,
+        request::StreamingBlob,
+        types::{CompletedMultipartUpload, CompletedPart, ObjectAttributes},
+    };
+
     use super::*;
 
     #[test]
@@ -881,4 +1134,440 @@ mod tests {
         let result = parse_copy_source("/");
         assert!(result.is_err());
     }
+
+    const BUCKET: &str = "mpu-bucket";
+
+    async fn create_upload(provider: &RustackS3, key: &str) -> String {
+        provider
+            .handle_create_multipart_upload(CreateMultipartUploadInput {
+                bucket: BUCKET.to_owned(),
+                key: key.to_owned(),
+                ..Default::default()
+            })
+            .await
+            .expect("create upload")
+            .upload_id
+            .expect("upload id")
+    }
+
+    async fn upload_part(
+        provider: &RustackS3,
+        key: &str,
+        upload_id: &str,
+        part_number: i32,
+        data: Vec<u8>,
+    ) -> Result<String, S3Error> {
+        let output = provider
+            .handle_upload_part(UploadPartInput {
+                bucket: BUCKET.to_owned(),
+                key: key.to_owned(),
+                upload_id: upload_id.to_owned(),
+                part_number,
+                body: Some(StreamingBlob::new(data)),
+                ..Default::default()
+            })
+            .await?;
+        Ok(output.e_tag.expect("part etag"))
+    }
+
+    async fn complete(
+        provider: &RustackS3,
+        key: &str,
+        upload_id: &str,
+        parts: &[(i32, &str)],
+    ) -> Result<CompleteMultipartUploadOutput, S3Error> {
+        provider
+            .handle_complete_multipart_upload(CompleteMultipartUploadInput {
+                bucket: BUCKET.to_owned(),
+                key: key.to_owned(),
+                upload_id: upload_id.to_owned(),
+                multipart_upload: Some(CompletedMultipartUpload {
+                    parts: parts
+                        .iter()
+                        .map(|&(part_number, etag)| CompletedPart {
+                            part_number: Some(part_number),
+                            e_tag: Some(etag.to_owned()),
+                            ..Default::default()
+                        })
+                        .collect(),
+                }),
+                ..Default::default()
+            })
+            .await
+    }
+
+    async fn setup() -> crate::embedded::S3TestClient {
+        let client = crate::embedded::S3TestClient::new();
+        client.create_bucket(BUCKET).await.expect("create bucket");
+        client
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_should_isolate_concurrent_uploads_to_one_key() {
+        const UPLOADS: u8 = 6;
+        let client = setup().await;
+        let provider = client.provider().clone();
+
+        let tasks: Vec<_> = (0..UPLOADS)
+            .map(|n| {
+                let provider = provider.clone();
+                tokio::spawn(async move {
+                    let upload_id = create_upload(&provider, "shared").await;
+                    let first = vec![n; usize::try_from(MIN_PART_SIZE).expect("part size")];
+                    let last = vec![n.wrapping_add(100); 1024];
+                    let first_etag = upload_part(&provider, "shared", &upload_id, 1, first.clone())
+                        .await
+                        .expect("upload part 1");
+                    let last_etag = upload_part(&provider, "shared", &upload_id, 2, last.clone())
+                        .await
+                        .expect("upload part 2");
+                    let output = complete(
+                        &provider,
+                        "shared",
+                        &upload_id,
+                        &[(1, &first_etag), (2, &last_etag)],
+                    )
+                    .await
+                    .expect("complete upload");
+                    (output.e_tag.expect("etag"), [first, last].concat())
+                })
+            })
+            .collect();
+        let mut completed = Vec::new();
+        for task in tasks {
+            completed.push(task.await.expect("upload task"));
+        }
+
+        // The stored object is exactly one upload's bytes, with that
+        // upload's ETag.
+        let body = client.get_object(BUCKET, "shared").await.expect("get");
+        let head = provider
+            .handle_head_object(rustack_s3_model::input::HeadObjectInput {
+                bucket: BUCKET.to_owned(),
+                key: "shared".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .expect("head");
+        let (etag, _) = completed
+            .iter()
+            .find(|(_, bytes)| bytes.as_slice() == body.as_ref())
+            .expect("object matches one upload byte for byte");
+        assert_eq!(head.e_tag.as_ref(), Some(etag));
+        assert_eq!(head.content_length, i64::try_from(body.len()).ok());
+    }
+
+    #[tokio::test]
+    async fn test_should_keep_other_upload_abortable_after_complete() {
+        let client = setup().await;
+        let provider = client.provider();
+        let first = create_upload(provider, "key").await;
+        let second = create_upload(provider, "key").await;
+        let first_etag = upload_part(provider, "key", &first, 1, b"first".to_vec())
+            .await
+            .expect("upload first");
+        let second_etag = upload_part(provider, "key", &second, 1, b"second".to_vec())
+            .await
+            .expect("upload second");
+
+        // A part of another upload (or a stale ETag) is not this upload's part.
+        let err = complete(provider, "key", &first, &[(1, &second_etag)])
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, S3ErrorCode::InvalidPart);
+        // An upload ID is only valid for the key it was created for.
+        let err = complete(provider, "other", &first, &[(1, &first_etag)])
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, S3ErrorCode::NoSuchUpload);
+
+        complete(provider, "key", &first, &[(1, &first_etag)])
+            .await
+            .expect("complete first");
+        assert_eq!(
+            client
+                .get_object(BUCKET, "key")
+                .await
+                .expect("get")
+                .as_ref(),
+            b"first"
+        );
+
+        provider
+            .handle_abort_multipart_upload(AbortMultipartUploadInput {
+                bucket: BUCKET.to_owned(),
+                key: "key".to_owned(),
+                upload_id: second.clone(),
+                ..Default::default()
+            })
+            .await
+            .expect("abort second");
+        let err = upload_part(provider, "key", &second, 2, b"late".to_vec())
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, S3ErrorCode::NoSuchUpload);
+        assert_eq!(
+            client
+                .get_object(BUCKET, "key")
+                .await
+                .expect("get")
+                .as_ref(),
+            b"first"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_should_paginate_list_parts() {
+        let client = setup().await;
+        let provider = client.provider();
+        let upload_id = create_upload(provider, "paged").await;
+        for part_number in 1..=5 {
+            upload_part(provider, "paged", &upload_id, part_number, vec![0; 8])
+                .await
+                .expect("upload part");
+        }
+
+        let list = |marker: Option<&str>, max_parts: Option<i32>| ListPartsInput {
+            bucket: BUCKET.to_owned(),
+            key: "paged".to_owned(),
+            upload_id: upload_id.clone(),
+            part_number_marker: marker.map(ToOwned::to_owned),
+            max_parts,
+            ..Default::default()
+        };
+        let mut marker = None;
+        let mut seen = Vec::new();
+        loop {
+            let page = provider
+                .handle_list_parts(list(marker.as_deref(), Some(2)))
+                .await
+                .expect("list parts");
+            assert_eq!(page.max_parts, Some(2));
+            seen.extend(page.parts.iter().filter_map(|p| p.part_number));
+            if page.is_truncated != Some(true) {
+                assert_eq!(page.next_part_number_marker, None);
+                break;
+            }
+            marker = page.next_part_number_marker;
+        }
+        assert_eq!(seen, vec![1, 2, 3, 4, 5]);
+
+        let page = provider
+            .handle_list_parts(list(None, Some(5000)))
+            .await
+            .expect("list parts");
+        assert_eq!(page.max_parts, Some(MAX_LIST_PARTS));
+        assert_eq!(page.parts.len(), 5);
+        assert_eq!(page.is_truncated, Some(false));
+    }
+
+    #[tokio::test]
+    async fn test_should_page_object_parts_of_completed_upload() {
+        let client = setup().await;
+        let provider = client.provider();
+        let upload_id = create_upload(provider, "parts").await;
+        let part_size = usize::try_from(MIN_PART_SIZE).expect("part size");
+        let mut etags = Vec::new();
+        for (part_number, size) in [(1, part_size), (2, part_size), (3, 10)] {
+            let etag = upload_part(provider, "parts", &upload_id, part_number, vec![1; size])
+                .await
+                .expect("upload part");
+            etags.push((part_number, etag));
+        }
+        let completed: Vec<(i32, &str)> = etags.iter().map(|(n, e)| (*n, e.as_str())).collect();
+        complete(provider, "parts", &upload_id, &completed)
+            .await
+            .expect("complete");
+
+        let attributes = |object_attributes: Vec<ObjectAttributes>, marker: Option<&str>| {
+            provider.handle_get_object_attributes(GetObjectAttributesInput {
+                bucket: BUCKET.to_owned(),
+                key: "parts".to_owned(),
+                object_attributes,
+                max_parts: Some(2),
+                part_number_marker: marker.map(ToOwned::to_owned),
+                ..Default::default()
+            })
+        };
+
+        let first = attributes(vec![ObjectAttributes::ObjectParts], None)
+            .await
+            .expect("attributes");
+        assert_eq!(first.e_tag, None);
+        assert_eq!(first.object_size, None);
+        let parts = first.object_parts.expect("object parts");
+        assert_eq!(parts.total_parts_count, Some(3));
+        assert_eq!(parts.is_truncated, Some(true));
+        assert_eq!(parts.next_part_number_marker.as_deref(), Some("2"));
+        let sizes: Vec<_> = parts
+            .parts
+            .iter()
+            .map(|p| (p.part_number, p.size))
+            .collect();
+        assert_eq!(
+            sizes,
+            vec![(Some(1), Some(5_242_880)), (Some(2), Some(5_242_880))]
+        );
+
+        let rest = attributes(
+            vec![ObjectAttributes::ObjectParts, ObjectAttributes::ObjectSize],
+            Some("2"),
+        )
+        .await
+        .expect("attributes");
+        assert_eq!(rest.object_size, Some(2 * 5_242_880 + 10));
+        let parts = rest.object_parts.expect("object parts");
+        assert_eq!(parts.is_truncated, Some(false));
+        assert_eq!(parts.next_part_number_marker, None);
+        assert_eq!(parts.parts.len(), 1);
+        assert_eq!(parts.parts[0].size, Some(10));
+    }
+
+    async fn list_uploads(
+        provider: &RustackS3,
+        input: ListMultipartUploadsInput,
+    ) -> ListMultipartUploadsOutput {
+        provider
+            .handle_list_multipart_uploads(ListMultipartUploadsInput {
+                bucket: BUCKET.to_owned(),
+                ..input
+            })
+            .await
+            .expect("list uploads")
+    }
+
+    fn listed_keys(output: &ListMultipartUploadsOutput) -> Vec<&str> {
+        output
+            .uploads
+            .iter()
+            .filter_map(|u| u.key.as_deref())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_should_filter_and_paginate_multipart_uploads() {
+        let client = setup().await;
+        let provider = client.provider();
+        let first = create_upload(provider, "logs/a").await;
+        let second = create_upload(provider, "logs/a").await;
+        create_upload(provider, "logs/b").await;
+        create_upload(provider, "logs/nested/c").await;
+        create_upload(provider, "other").await;
+
+        let output = list_uploads(
+            provider,
+            ListMultipartUploadsInput {
+                prefix: Some("logs/".to_owned()),
+                delimiter: Some("/".to_owned()),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(listed_keys(&output), ["logs/a", "logs/a", "logs/b"]);
+        assert_eq!(
+            output.common_prefixes[0].prefix.as_deref(),
+            Some("logs/nested/")
+        );
+        assert!(output.uploads.iter().all(|u| u.initiated.is_some()));
+        assert_eq!(output.is_truncated, Some(false));
+
+        let page = list_uploads(
+            provider,
+            ListMultipartUploadsInput {
+                max_uploads: Some(1),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(page.is_truncated, Some(true));
+        assert_eq!(page.next_key_marker.as_deref(), Some("logs/a"));
+        let first_listed = page
+            .next_upload_id_marker
+            .clone()
+            .expect("upload id marker");
+
+        let page = list_uploads(
+            provider,
+            ListMultipartUploadsInput {
+                key_marker: page.next_key_marker,
+                upload_id_marker: page.next_upload_id_marker,
+                max_uploads: Some(1),
+                ..Default::default()
+            },
+        )
+        .await;
+        let second_listed = page.uploads[0].upload_id.clone().expect("upload id");
+        let mut listed = [first_listed, second_listed];
+        listed.sort();
+        let mut created = [first, second];
+        created.sort();
+        assert_eq!(listed, created);
+
+        let rest = list_uploads(
+            provider,
+            ListMultipartUploadsInput {
+                key_marker: Some("logs/a".to_owned()),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(listed_keys(&rest), ["logs/b", "logs/nested/c", "other"]);
+    }
+
+    #[tokio::test]
+    async fn test_should_abort_uploads_expired_by_lifecycle_rule() {
+        use rustack_s3_model::types::{
+            AbortIncompleteMultipartUpload, BucketLifecycleConfiguration, ExpirationStatus,
+            LifecycleRule, LifecycleRuleFilter,
+        };
+
+        let client = setup().await;
+        let provider = client.provider();
+        let stale = create_upload(provider, "tmp/stale").await;
+        let fresh = create_upload(provider, "tmp/fresh").await;
+        let kept = create_upload(provider, "data/stale").await;
+        for (key, upload_id) in [
+            ("tmp/stale", &stale),
+            ("tmp/fresh", &fresh),
+            ("data/stale", &kept),
+        ] {
+            upload_part(provider, key, upload_id, 1, vec![b'x'; 100])
+                .await
+                .expect("upload part");
+        }
+        assert_eq!(provider.storage().part_bytes(), 300);
+
+        {
+            let bucket = provider.state().get_bucket(BUCKET).expect("bucket");
+            for upload_id in [&stale, &kept] {
+                bucket
+                    .multipart_uploads
+                    .get_mut(upload_id)
+                    .expect("upload")
+                    .initiated -= chrono::TimeDelta::days(8);
+            }
+            *bucket.lifecycle.write() = Some(BucketLifecycleConfiguration {
+                rules: vec![LifecycleRule {
+                    abort_incomplete_multipart_upload: Some(AbortIncompleteMultipartUpload {
+                        days_after_initiation: Some(7),
+                    }),
+                    filter: Some(LifecycleRuleFilter {
+                        prefix: Some("tmp/".to_owned()),
+                        ..Default::default()
+                    }),
+                    status: ExpirationStatus::Enabled,
+                    ..Default::default()
+                }],
+            });
+        }
+
+        let output = list_uploads(provider, ListMultipartUploadsInput::default()).await;
+        assert_eq!(listed_keys(&output), ["data/stale", "tmp/fresh"]);
+        assert_eq!(provider.storage().part_bytes(), 200);
+        assert!(matches!(
+            upload_part(provider, "tmp/stale", &stale, 2, vec![b'y'; 10]).await,
+            Err(e) if e.code == S3ErrorCode::NoSuchUpload
+        ));
+        assert_eq!(provider.abort_expired_multipart_uploads(), 0);
+    }
 }
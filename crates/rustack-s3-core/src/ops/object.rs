@@ -3,10 +3,10 @@
 //! Implements `put_object`, `get_object`, `head_object`, `delete_object`,
 //! `delete_objects`, and `copy_object`.
 
-use std::{collections::HashMap, str::FromStr};
+use std::{borrow::Cow, collections::HashMap, str::FromStr};
 
 use bytes::Bytes;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use rustack_s3_model::{
     error::{S3Error, S3ErrorCode},
     input::{
@@ -20,23 +20,35 @@ use rustack_s3_model::{
     request::StreamingBlob,
     types::{
         ChecksumType, CopyObjectResult, DeletedObject, MetadataDirective, ObjectCannedACL,
-        ObjectLockLegalHoldStatus, ObjectLockMode, ServerSideEncryption, StorageClass,
+        ObjectIdentifier, ObjectLockLegalHoldStatus, ObjectLockMode, ServerSideEncryption,
+        StorageClass,
     },
 };
-use tracing::debug;
+use tracing::{debug, warn};
 
 use crate::{
+    acl::{AclRequest, check_acl_supported},
     checksums::{ChecksumAlgorithm, compute_checksum},
     error::S3ServiceError,
     provider::RustackS3,
+    sse::{CustomerKey, EncryptionFields, apply_default_encryption, check_customer_key},
     state::{
+        bucket::{DefaultRetention, S3Bucket},
         keystore::ObjectStore,
-        object::{CannedAcl, ChecksumData, ObjectMetadata, Owner as InternalOwner, S3Object},
+        object::{
+            CannedAcl, ChecksumData, Grant, ObjectMetadata, Owner as InternalOwner, S3Object,
+        },
     },
-    utils::{is_valid_if_match, is_valid_if_none_match, parse_copy_source, parse_range_header},
-    validation::{validate_content_md5, validate_metadata, validate_object_key},
+    utils::{
+        Preconditions, format_http_date, is_valid_if_match, is_valid_if_range, parse_copy_source,
+        parse_range_header,
+    },
+    validation::{validate_content_md5, validate_metadata, validate_object_key, validate_tags},
 };
 
+/// Maximum number of keys a single DeleteObjects request may name.
+const MAX_DELETE_OBJECTS: usize = 1000;
+
 /// Check whether Object Lock (legal hold or retention) prevents deletion of a
 /// specific object version.
 ///
@@ -89,6 +101,93 @@ fn check_object_lock_for_delete(
     Ok(())
 }
 
+/// Apply a bucket's default Object Lock retention to a new object version.
+///
+/// A version written with its own `x-amz-object-lock-mode` or
+/// `x-amz-object-lock-retain-until-date` keeps them.
+pub(super) fn apply_default_retention(
+    metadata: &mut ObjectMetadata,
+    default: Option<&DefaultRetention>,
+    created: DateTime<Utc>,
+) {
+    let Some(default) = default else {
+        return;
+    };
+    if metadata.object_lock_mode.is_some() || metadata.object_lock_retain_until.is_some() {
+        return;
+    }
+    if let Some(retain_until) = default.retain_until(created) {
+        metadata.object_lock_mode = Some(default.mode.clone());
+        metadata.object_lock_retain_until = Some(retain_until);
+    }
+}
+
+/// The Object Lock response fields of an object version.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct LockFields {
+    /// `x-amz-object-lock-legal-hold`.
+    legal_hold_status: Option<ObjectLockLegalHoldStatus>,
+    /// `x-amz-object-lock-mode`.
+    mode: Option<ObjectLockMode>,
+    /// `x-amz-object-lock-retain-until-date`.
+    retain_until_date: Option<DateTime<Utc>>,
+}
+
+impl LockFields {
+    fn from_metadata(metadata: &ObjectMetadata) -> Self {
+        Self {
+            legal_hold_status: metadata
+                .object_lock_legal_hold
+                .filter(|&v| v)
+                .map(|_| ObjectLockLegalHoldStatus::from("ON")),
+            mode: metadata
+                .object_lock_mode
+                .as_deref()
+                .map(ObjectLockMode::from),
+            retain_until_date: metadata.object_lock_retain_until,
+        }
+    }
+}
+
+/// Check the conditions a DeleteObjects entry places on the object it deletes.
+///
+/// The `ETag`, `LastModifiedTime`, and `Size` of the entry, when given, must
+/// match the targeted version (the current one if no version is named).
+#[allow(clippy::result_large_err)]
+fn check_delete_preconditions(
+    store: &ObjectStore,
+    obj_id: &ObjectIdentifier,
+) -> Result<(), S3Error> {
+    if obj_id.e_tag.is_none() && obj_id.last_modified_time.is_none() && obj_id.size.is_none() {
+        return Ok(());
+    }
+    let target = match &obj_id.version_id {
+        Some(vid) => store.get_version(&obj_id.key, vid),
+        None => store.get(&obj_id.key),
+    };
+    let Some(obj) = target else {
+        return Err(S3ServiceError::NoSuchKey {
+            key: obj_id.key.clone(),
+        }
+        .into_s3_error());
+    };
+    let etag_matches = obj_id
+        .e_tag
+        .as_deref()
+        .is_none_or(|etag| is_valid_if_match(&obj.etag, etag));
+    let modified_matches = obj_id
+        .last_modified_time
+        .is_none_or(|at| at.timestamp() == obj.last_modified.timestamp());
+    let size_matches = obj_id
+        .size
+        .is_none_or(|size| u64::try_from(size).is_ok_and(|size| size == obj.size));
+    if etag_matches && modified_matches && size_matches {
+        Ok(())
+    } else {
+        Err(S3ServiceError::PreconditionFailed.into_s3_error())
+    }
+}
+
 // AWS S3 DTOs use signed integers (i32/i64) for inherently non-negative values
 // (sizes, part counts). Casting from u64/u32/usize is safe in practice.
 // These handler methods must remain async because some operations involve
@@ -101,6 +200,7 @@ fn check_object_lock_for_delete(
 )]
 impl RustackS3 {
     /// Put (upload) a new object.
+    #[allow(clippy::too_many_lines)]
     pub async fn handle_put_object(
         &self,
         mut input: PutObjectInput,
@@ -109,12 +209,23 @@ impl RustackS3 {
         let key = input.key.clone();
 
         validate_object_key(&key).map_err(S3ServiceError::into_s3_error)?;
+        validate_tagging_header(input.tagging.as_deref())?;
 
-        // Verify bucket exists.
-        let bucket = self
-            .state
-            .get_bucket(&bucket_name)
-            .map_err(S3ServiceError::into_s3_error)?;
+        // Verify bucket exists, and build the object's metadata with the
+        // bucket's defaults. The bucket is not held across the storage write,
+        // which would block a concurrent DeleteBucket.
+        let (metadata, versioning_enabled) = {
+            let bucket = self
+                .state
+                .get_bucket(&bucket_name)
+                .map_err(S3ServiceError::into_s3_error)?;
+            let mut metadata =
+                build_metadata(&input, &bucket).map_err(S3ServiceError::into_s3_error)?;
+            apply_default_encryption(&mut metadata, bucket.encryption.read().as_ref());
+            let retention = bucket.default_retention();
+            apply_default_retention(&mut metadata, retention.as_ref(), Utc::now());
+            (metadata, bucket.is_versioning_enabled())
+        };
 
         // Take the body out before borrowing other fields from input.
         let body_data = input.body.take().map_or_else(Bytes::new, |b| b.data);
@@ -122,13 +233,23 @@ impl RustackS3 {
         // Validate Content-MD5 if provided.
         validate_content_md5(input.content_md5.as_deref(), &body_data)
             .map_err(S3ServiceError::into_s3_error)?;
-
-        // Extract metadata from the request.
-        let metadata = build_metadata(&input);
-        validate_metadata(&metadata.user_metadata).map_err(S3ServiceError::into_s3_error)?;
+        CustomerKey::from_headers(
+            input.sse_customer_algorithm.as_deref(),
+            input.sse_customer_key.as_deref(),
+            input.sse_customer_key_md5.as_deref(),
+        )
+        .map_err(S3ServiceError::into_s3_error)?;
+
+        let encryption = EncryptionFields::from_metadata(&metadata);
+        let lock = LockFields::from_metadata(&metadata);
+        let sse_customer_algorithm = metadata.sse_customer_algorithm.clone();
+        let sse_customer_key_md5 = metadata.sse_customer_key_md5.clone();
+        if self.config.s3_strict_validation {
+            validate_metadata(&metadata.user_metadata).map_err(S3ServiceError::into_s3_error)?;
+        }
 
         // Determine version ID based on versioning status.
-        let version_id = if bucket.is_versioning_enabled() {
+        let version_id = if versioning_enabled {
             crate::utils::generate_version_id()
         } else {
             "null".to_owned()
@@ -179,13 +300,10 @@ impl RustackS3 {
             checksum: Some(checksum.clone()),
             parts_count: None,
             part_etags: Vec::new(),
+            parts: Vec::new(),
         };
 
-        // Store the object metadata.
-        {
-            let mut store = bucket.objects.write();
-            store.put(obj);
-        }
+        self.store_written_object(&bucket_name, obj)?;
 
         debug!(bucket = %bucket_name, key = %key, version_id = %version_id, "put_object completed");
 
@@ -205,23 +323,70 @@ impl RustackS3 {
             checksum_sha1: cksum.sha1,
             checksum_sha256: cksum.sha256,
             checksum_type: cksum.checksum_type,
+            bucket_key_enabled: encryption.bucket_key_enabled,
+            object_lock_legal_hold_status: lock.legal_hold_status,
+            object_lock_mode: lock.mode,
+            object_lock_retain_until_date: lock.retain_until_date,
+            sse_customer_algorithm,
+            sse_customer_key_md5,
+            ssekms_key_id: encryption.ssekms_key_id,
+            server_side_encryption: encryption.server_side_encryption,
             ..PutObjectOutput::default()
         })
     }
 
+    /// Store the metadata of an object whose data was just written, unless
+    /// the bucket was deleted during the write, in which case the data must
+    /// not outlive it.
+    #[allow(clippy::result_large_err)]
+    fn store_written_object(&self, bucket_name: &str, obj: S3Object) -> Result<(), S3Error> {
+        if let Ok(bucket) = self.state.get_bucket(bucket_name) {
+            bucket.objects.write().put(obj);
+            return Ok(());
+        }
+        self.storage
+            .delete_object(bucket_name, &obj.key, &obj.version_id);
+        Err(S3ServiceError::NoSuchBucket {
+            bucket: bucket_name.to_owned(),
+        }
+        .into_s3_error())
+    }
+
     /// Get (download) an object.
-    #[allow(clippy::too_many_lines)]
     pub async fn handle_get_object(
         &self,
         input: GetObjectInput,
+    ) -> Result<GetObjectOutput, S3Error> {
+        self.handle_get_object_with_if_range(input, None).await
+    }
+
+    /// Get an object, honouring an `If-Range` request header.
+    ///
+    /// The `Range` is served only while `if_range` still matches the object;
+    /// otherwise the whole object is returned.
+    #[allow(clippy::too_many_lines)]
+    pub async fn handle_get_object_with_if_range(
+        &self,
+        input: GetObjectInput,
+        if_range: Option<String>,
     ) -> Result<GetObjectOutput, S3Error> {
         let bucket_name = input.bucket;
         let key = input.key;
         let version_id_param = input.version_id;
-        let if_match_param = input.if_match;
-        let if_none_match_param = input.if_none_match;
+        let preconditions = Preconditions {
+            if_match: input.if_match.as_deref(),
+            if_unmodified_since: input.if_unmodified_since,
+            if_none_match: input.if_none_match.as_deref(),
+            if_modified_since: input.if_modified_since,
+        };
         let range_param = input.range;
         let checksum_mode = input.checksum_mode;
+        let customer_key = CustomerKey::from_headers(
+            input.sse_customer_algorithm.as_deref(),
+            input.sse_customer_key.as_deref(),
+            input.sse_customer_key_md5.as_deref(),
+        )
+        .map_err(S3ServiceError::into_s3_error)?;
 
         // S3 response header overrides (from query parameters in presigned URLs).
         let override_cache_control = input.response_cache_control;
@@ -272,17 +437,20 @@ impl RustackS3 {
                     .get(&key)
                     .ok_or_else(|| S3ServiceError::NoSuchKey { key: key.clone() }.into_s3_error())?
             };
+            check_customer_key(&obj.metadata, customer_key.as_ref())
+                .map_err(S3ServiceError::into_s3_error)?;
 
-            // Conditional request checks.
-            if let Some(ref if_match) = if_match_param {
-                if !is_valid_if_match(&obj.etag, if_match) {
-                    return Err(S3ServiceError::PreconditionFailed.into_s3_error());
-                }
-            }
-            if let Some(ref if_none_match) = if_none_match_param {
-                if !is_valid_if_none_match(&obj.etag, if_none_match) {
-                    return Err(S3ServiceError::NotModified.into_s3_error());
+            preconditions
+                .check(&obj.etag, obj.last_modified, true)
+                .map_err(S3ServiceError::into_s3_error)?;
+
+            if let Some(failure) = &obj.metadata.integrity_failure {
+                if !self.config.s3_serve_corrupt_objects {
+                    return Err(
+                        S3ServiceError::ObjectCorrupted { key: key.clone() }.into_s3_error()
+                    );
                 }
+                warn!(bucket = %bucket_name, key = %key, %failure, "serving corrupt object");
             }
 
             let version_id_opt = if obj.version_id == "null" {
@@ -304,14 +472,13 @@ impl RustackS3 {
             )
         };
 
-        // Parse range header if provided.
-        let range = if let Some(ref range_value) = range_param {
-            let (start, end) =
-                parse_range_header(range_value, obj_size).map_err(S3ServiceError::into_s3_error)?;
-            Some((start, end))
-        } else {
-            None
-        };
+        let range = resolve_range(
+            range_param.as_deref(),
+            if_range.as_deref(),
+            obj_size,
+            &obj_etag,
+            obj_last_modified,
+        )?;
 
         // Read data from storage.
         let data = self
@@ -352,9 +519,12 @@ impl RustackS3 {
         } else {
             None
         };
+        let encryption = EncryptionFields::from_metadata(&obj_meta);
+        let lock = LockFields::from_metadata(&obj_meta);
         let output = GetObjectOutput {
             accept_ranges: Some("bytes".to_owned()),
             body: Some(body),
+            bucket_key_enabled: encryption.bucket_key_enabled,
             cache_control: override_cache_control.or(obj_meta.cache_control),
             checksum_crc32: cksum.as_ref().and_then(|c| c.crc32.clone()),
             checksum_crc32c: cksum.as_ref().and_then(|c| c.crc32c.clone()),
@@ -368,27 +538,18 @@ impl RustackS3 {
             content_length: Some(content_length),
             content_range,
             content_type: override_content_type.or(content_type),
-            expires: override_expires.map(|dt| dt.to_rfc2822()),
+            expires: override_expires.map(format_http_date).or(obj_meta.expires),
             e_tag: Some(obj_etag),
             last_modified: Some(obj_last_modified),
             metadata,
-            object_lock_legal_hold_status: obj_meta
-                .object_lock_legal_hold
-                .filter(|&v| v)
-                .map(|_| ObjectLockLegalHoldStatus::from("ON")),
-            object_lock_mode: obj_meta
-                .object_lock_mode
-                .as_deref()
-                .map(ObjectLockMode::from),
-            object_lock_retain_until_date: obj_meta.object_lock_retain_until,
+            object_lock_legal_hold_status: lock.legal_hold_status,
+            object_lock_mode: lock.mode,
+            object_lock_retain_until_date: lock.retain_until_date,
             parts_count: obj_parts_count.map(|n| n as i32),
             sse_customer_algorithm: obj_meta.sse_customer_algorithm,
             sse_customer_key_md5: obj_meta.sse_customer_key_md5,
-            ssekms_key_id: obj_meta.sse_kms_key_id,
-            server_side_encryption: obj_meta
-                .sse_algorithm
-                .as_deref()
-                .map(ServerSideEncryption::from),
+            ssekms_key_id: encryption.ssekms_key_id,
+            server_side_encryption: encryption.server_side_encryption,
             storage_class: Some(StorageClass::from(obj_storage_class.as_str())),
             version_id: obj_version_id,
             ..GetObjectOutput::default()
@@ -397,15 +558,34 @@ impl RustackS3 {
     }
 
     /// Head object (get metadata without body).
-    #[allow(clippy::too_many_lines)]
     pub async fn handle_head_object(
         &self,
         input: HeadObjectInput,
+    ) -> Result<HeadObjectOutput, S3Error> {
+        self.handle_head_object_with_if_range(input, None).await
+    }
+
+    /// Head an object, honouring an `If-Range` request header.
+    ///
+    /// A served `Range` is reported through `Content-Range` and the ranged
+    /// `Content-Length`, as for the matching GET.
+    #[allow(clippy::too_many_lines)]
+    pub async fn handle_head_object_with_if_range(
+        &self,
+        input: HeadObjectInput,
+        if_range: Option<String>,
     ) -> Result<HeadObjectOutput, S3Error> {
         let bucket_name = input.bucket;
         let key = input.key;
         let version_id_param = input.version_id;
+        let range_param = input.range;
         let checksum_mode = input.checksum_mode;
+        let customer_key = CustomerKey::from_headers(
+            input.sse_customer_algorithm.as_deref(),
+            input.sse_customer_key.as_deref(),
+            input.sse_customer_key_md5.as_deref(),
+        )
+        .map_err(S3ServiceError::into_s3_error)?;
 
         // S3 response header overrides (from query parameters in presigned URLs).
         let override_cache_control = input.response_cache_control;
@@ -441,6 +621,16 @@ impl RustackS3 {
                 .get(&key)
                 .ok_or_else(|| S3ServiceError::NoSuchKey { key: key.clone() }.into_s3_error())?
         };
+        check_customer_key(&obj.metadata, customer_key.as_ref())
+            .map_err(S3ServiceError::into_s3_error)?;
+        Preconditions {
+            if_match: input.if_match.as_deref(),
+            if_unmodified_since: input.if_unmodified_since,
+            if_none_match: input.if_none_match.as_deref(),
+            if_modified_since: input.if_modified_since,
+        }
+        .check(&obj.etag, obj.last_modified, true)
+        .map_err(S3ServiceError::into_s3_error)?;
 
         let obj_version_id = if obj.version_id == "null" {
             None
@@ -448,6 +638,16 @@ impl RustackS3 {
             Some(obj.version_id.clone())
         };
 
+        let range = resolve_range(
+            range_param.as_deref(),
+            if_range.as_deref(),
+            obj.size,
+            &obj.etag,
+            obj.last_modified,
+        )?;
+        let content_length = range.map_or(obj.size, |(start, end)| end - start + 1);
+        let content_range = range.map(|(start, end)| format!("bytes {start}-{end}/{}", obj.size));
+
         let content_type = Some(
             obj.metadata
                 .content_type
@@ -465,13 +665,16 @@ impl RustackS3 {
         let checksum_enabled = checksum_mode
             .as_ref()
             .is_some_and(|m| m.as_str() == "ENABLED");
-        let cksum = if checksum_enabled {
+        let cksum = if checksum_enabled && range.is_none() {
             obj.checksum.as_ref().map(checksum_to_fields)
         } else {
             None
         };
+        let encryption = EncryptionFields::from_metadata(&obj.metadata);
+        let lock = LockFields::from_metadata(&obj.metadata);
         let output = HeadObjectOutput {
             accept_ranges: Some("bytes".to_owned()),
+            bucket_key_enabled: encryption.bucket_key_enabled,
             cache_control: override_cache_control.or(obj.metadata.cache_control.clone()),
             checksum_crc32: cksum.as_ref().and_then(|c| c.crc32.clone()),
             checksum_crc32c: cksum.as_ref().and_then(|c| c.crc32c.clone()),
@@ -483,32 +686,23 @@ impl RustackS3 {
                 .or(obj.metadata.content_disposition.clone()),
             content_encoding: override_content_encoding.or(obj.metadata.content_encoding.clone()),
             content_language: override_content_language.or(obj.metadata.content_language.clone()),
-            content_length: Some(obj.size as i64),
+            content_length: Some(content_length as i64),
+            content_range,
             content_type: override_content_type.or(content_type),
-            expires: override_expires.map(|dt| dt.to_rfc2822()),
+            expires: override_expires
+                .map(format_http_date)
+                .or(obj.metadata.expires.clone()),
             e_tag: Some(obj.etag.clone()),
             last_modified: Some(obj.last_modified),
             metadata,
-            object_lock_legal_hold_status: obj
-                .metadata
-                .object_lock_legal_hold
-                .filter(|&v| v)
-                .map(|_| ObjectLockLegalHoldStatus::from("ON")),
-            object_lock_mode: obj
-                .metadata
-                .object_lock_mode
-                .as_deref()
-                .map(ObjectLockMode::from),
-            object_lock_retain_until_date: obj.metadata.object_lock_retain_until,
+            object_lock_legal_hold_status: lock.legal_hold_status,
+            object_lock_mode: lock.mode,
+            object_lock_retain_until_date: lock.retain_until_date,
             parts_count: obj.parts_count.map(|n| n as i32),
             sse_customer_algorithm: obj.metadata.sse_customer_algorithm.clone(),
             sse_customer_key_md5: obj.metadata.sse_customer_key_md5.clone(),
-            ssekms_key_id: obj.metadata.sse_kms_key_id.clone(),
-            server_side_encryption: obj
-                .metadata
-                .sse_algorithm
-                .as_deref()
-                .map(ServerSideEncryption::from),
+            ssekms_key_id: encryption.ssekms_key_id,
+            server_side_encryption: encryption.server_side_encryption,
             storage_class: Some(StorageClass::from(obj.storage_class.as_str())),
             version_id: obj_version_id,
             ..HeadObjectOutput::default()
@@ -529,6 +723,7 @@ impl RustackS3 {
             .get_bucket(&bucket_name)
             .map_err(S3ServiceError::into_s3_error)?;
 
+        let suspended = bucket.is_versioning_suspended();
         let (delete_marker_version_id, version_id_to_remove) =
             if let Some(version_id) = &input.version_id {
                 // Delete a specific version.
@@ -547,9 +742,11 @@ impl RustackS3 {
             } else {
                 // Delete without version: in versioned bucket, create delete marker.
                 let mut store = bucket.objects.write();
-                let (dm_id, _had) = store.delete_versioned(&key, &InternalOwner::default());
-                if dm_id.is_none() {
-                    // Un-versioned bucket: remove the storage data.
+                let owner = InternalOwner::default();
+                let (dm_id, _had) = store.delete_versioned(&key, &owner, suspended);
+                if dm_id.as_deref().is_none_or(|id| id == "null") {
+                    // Un-versioned bucket, or a null delete marker that
+                    // replaced the null version: remove its storage data.
                     self.storage.delete_object(&bucket_name, &key, "null");
                 }
                 (dm_id.is_some(), dm_id)
@@ -585,53 +782,26 @@ impl RustackS3 {
 
         let objects = delete_request.objects;
         let quiet = delete_request.quiet.unwrap_or(false);
+        if objects.is_empty() || objects.len() > MAX_DELETE_OBJECTS {
+            return Err(S3ServiceError::MalformedXml.into_s3_error());
+        }
 
         let mut deleted: Vec<DeletedObject> = Vec::with_capacity(objects.len());
         let mut errors: Vec<rustack_s3_model::types::Error> = Vec::new();
 
+        let suspended = bucket.is_versioning_suspended();
+        // Each key succeeds or fails on its own; failures are reported in the
+        // result instead of failing the request.
         for obj_id in objects {
-            let key = obj_id.key;
-            let version_id = obj_id.version_id;
-
-            if let Some(ref vid) = version_id {
-                // Delete a specific version.
-                let mut store = bucket.objects.write();
-                if let Err(lock_err) = check_object_lock_for_delete(&store, &key, vid, bypass) {
-                    errors.push(rustack_s3_model::types::Error {
-                        code: Some(lock_err.code.as_str().to_owned()),
-                        key: Some(key),
-                        message: Some(lock_err.message),
-                        version_id: Some(vid.clone()),
-                    });
-                    continue;
-                }
-                let removed = store.delete_version(&key, vid);
-                if let Some(ref version) = removed {
-                    self.storage
-                        .delete_object(&bucket_name, &key, version.version_id());
-                }
-                let is_dm = removed
-                    .as_ref()
-                    .is_some_and(crate::state::object::ObjectVersion::is_delete_marker);
-                deleted.push(DeletedObject {
-                    delete_marker: if is_dm { Some(true) } else { None },
-                    delete_marker_version_id: if is_dm { Some(vid.clone()) } else { None },
-                    key: Some(key),
-                    version_id: Some(vid.clone()),
-                });
-            } else {
-                // Delete without version.
-                let mut store = bucket.objects.write();
-                let (dm_id, _had) = store.delete_versioned(&key, &InternalOwner::default());
-                if dm_id.is_none() {
-                    self.storage.delete_object(&bucket_name, &key, "null");
-                }
-                deleted.push(DeletedObject {
-                    delete_marker: dm_id.as_ref().map(|_| true),
-                    delete_marker_version_id: dm_id.clone(),
-                    key: Some(key),
-                    version_id: dm_id,
-                });
+            let mut store = bucket.objects.write();
+            match self.delete_object_entry(&bucket_name, &mut store, &obj_id, bypass, suspended) {
+                Ok(entry) => deleted.push(entry),
+                Err(err) => errors.push(rustack_s3_model::types::Error {
+                    code: Some(err.code.as_str().to_owned()),
+                    key: Some(obj_id.key),
+                    message: Some(err.message),
+                    version_id: obj_id.version_id,
+                }),
             }
         }
 
@@ -649,6 +819,51 @@ impl RustackS3 {
         })
     }
 
+    /// Delete one key of a DeleteObjects request.
+    #[allow(clippy::result_large_err)]
+    fn delete_object_entry(
+        &self,
+        bucket_name: &str,
+        store: &mut ObjectStore,
+        obj_id: &ObjectIdentifier,
+        bypass: bool,
+        suspended: bool,
+    ) -> Result<DeletedObject, S3Error> {
+        let key = &obj_id.key;
+        check_delete_preconditions(store, obj_id)?;
+
+        if let Some(vid) = &obj_id.version_id {
+            // Delete a specific version.
+            check_object_lock_for_delete(store, key, vid, bypass)?;
+            let removed = store.delete_version(key, vid);
+            if let Some(ref version) = removed {
+                self.storage
+                    .delete_object(bucket_name, key, version.version_id());
+            }
+            let is_dm = removed
+                .as_ref()
+                .is_some_and(crate::state::object::ObjectVersion::is_delete_marker);
+            Ok(DeletedObject {
+                delete_marker: if is_dm { Some(true) } else { None },
+                delete_marker_version_id: if is_dm { Some(vid.clone()) } else { None },
+                key: Some(key.clone()),
+                version_id: Some(vid.clone()),
+            })
+        } else {
+            // Delete without version.
+            let (dm_id, _had) = store.delete_versioned(key, &InternalOwner::default(), suspended);
+            if dm_id.as_deref().is_none_or(|id| id == "null") {
+                self.storage.delete_object(bucket_name, key, "null");
+            }
+            Ok(DeletedObject {
+                delete_marker: dm_id.as_ref().map(|_| true),
+                delete_marker_version_id: dm_id.clone(),
+                key: Some(key.clone()),
+                version_id: dm_id,
+            })
+        }
+    }
+
     /// Copy an object from a source to a destination.
     #[allow(clippy::too_many_lines)]
     pub async fn handle_copy_object(
@@ -659,9 +874,30 @@ impl RustackS3 {
         let dst_key = input.key.clone();
 
         validate_object_key(&dst_key).map_err(S3ServiceError::into_s3_error)?;
+        validate_tagging_header(input.tagging.as_deref())?;
 
         let (src_bucket, src_key, src_version_id) =
             parse_copy_source(&input.copy_source).map_err(S3ServiceError::into_s3_error)?;
+        let replace_metadata = input
+            .metadata_directive
+            .as_ref()
+            .is_some_and(|d| *d == MetadataDirective::Replace);
+        if replace_metadata && self.config.s3_strict_validation {
+            validate_metadata(&input.metadata).map_err(S3ServiceError::into_s3_error)?;
+        }
+        // The source and destination may use different SSE-C keys.
+        let src_customer_key = CustomerKey::from_headers(
+            input.copy_source_sse_customer_algorithm.as_deref(),
+            input.copy_source_sse_customer_key.as_deref(),
+            input.copy_source_sse_customer_key_md5.as_deref(),
+        )
+        .map_err(S3ServiceError::into_s3_error)?;
+        let dst_customer_key = CustomerKey::from_headers(
+            input.sse_customer_algorithm.as_deref(),
+            input.sse_customer_key.as_deref(),
+            input.sse_customer_key_md5.as_deref(),
+        )
+        .map_err(S3ServiceError::into_s3_error)?;
 
         // Look up source object to get its metadata.
         // Keep this entire block synchronous -- no awaits while the lock is held.
@@ -688,6 +924,16 @@ impl RustackS3 {
                     .into_s3_error()
                 })?
             };
+            check_customer_key(&src_obj.metadata, src_customer_key.as_ref())
+                .map_err(S3ServiceError::into_s3_error)?;
+            Preconditions {
+                if_match: input.copy_source_if_match.as_deref(),
+                if_unmodified_since: input.copy_source_if_unmodified_since,
+                if_none_match: input.copy_source_if_none_match.as_deref(),
+                if_modified_since: input.copy_source_if_modified_since,
+            }
+            .check(&src_obj.etag, src_obj.last_modified, false)
+            .map_err(S3ServiceError::into_s3_error)?;
 
             (
                 src_obj.metadata.clone(),
@@ -702,6 +948,20 @@ impl RustackS3 {
             .get_bucket(&dst_bucket)
             .map_err(S3ServiceError::into_s3_error)?;
 
+        let acl = object_acl(
+            &AclRequest {
+                canned: input.acl.as_ref().map(ObjectCannedACL::as_str),
+                grant_full_control: input.grant_full_control.as_deref(),
+                grant_read: input.grant_read.as_deref(),
+                grant_read_acp: input.grant_read_acp.as_deref(),
+                grant_write_acp: input.grant_write_acp.as_deref(),
+                ..AclRequest::default()
+            },
+            &dst_bucket_ref,
+        )
+        .map_err(S3ServiceError::into_s3_error)?;
+        let dst_encryption = dst_bucket_ref.encryption.read().clone();
+        let dst_retention = dst_bucket_ref.default_retention();
         let dst_version_id = if dst_bucket_ref.is_versioning_enabled() {
             crate::utils::generate_version_id()
         } else {
@@ -722,19 +982,46 @@ impl RustackS3 {
                 &dst_key,
                 &dst_version_id,
             )
-            .await
             .map_err(|e| S3ServiceError::Internal(anyhow::anyhow!("{e}")).into_s3_error())?;
 
         // Determine metadata: use source metadata unless MetadataDirective is REPLACE.
-        let metadata = if input
-            .metadata_directive
-            .as_ref()
-            .is_some_and(|d| *d == MetadataDirective::Replace)
-        {
-            build_metadata_for_copy(&input)
+        let mut metadata = if replace_metadata {
+            // The copy shares the source's data, and so any integrity failure.
+            ObjectMetadata {
+                integrity_failure: src_metadata.integrity_failure,
+                ..build_metadata_for_copy(&input)
+            }
         } else {
             src_metadata
         };
+        // Encryption is never copied from the source: the copy is encrypted
+        // as the request asks, or with the destination bucket's default.
+        metadata.sse_algorithm = input
+            .server_side_encryption
+            .as_ref()
+            .map(|sse| sse.as_str().to_owned());
+        metadata.sse_kms_key_id.clone_from(&input.ssekms_key_id);
+        metadata.sse_bucket_key_enabled = input.bucket_key_enabled;
+        metadata.sse_customer_algorithm = dst_customer_key.as_ref().map(|k| k.algorithm.clone());
+        metadata.sse_customer_key_md5 = dst_customer_key.map(|k| k.key_md5);
+        apply_default_encryption(&mut metadata, dst_encryption.as_ref());
+        // Nor is the ACL: the copy gets the ACL the request sets, or none.
+        (metadata.acl, metadata.grants) = acl;
+        // Nor is Object Lock: the copy is locked as the request asks, or with
+        // the destination bucket's default retention.
+        metadata.object_lock_mode = input
+            .object_lock_mode
+            .as_ref()
+            .map(|m| m.as_str().to_owned());
+        metadata.object_lock_retain_until = input.object_lock_retain_until_date;
+        metadata.object_lock_legal_hold = input
+            .object_lock_legal_hold_status
+            .as_ref()
+            .map(|s| s.as_str() == "ON");
+        apply_default_retention(&mut metadata, dst_retention.as_ref(), Utc::now());
+        let encryption = EncryptionFields::from_metadata(&metadata);
+        let sse_customer_algorithm = metadata.sse_customer_algorithm.clone();
+        let sse_customer_key_md5 = metadata.sse_customer_key_md5.clone();
 
         let storage_class = input
             .storage_class
@@ -754,6 +1041,7 @@ impl RustackS3 {
             checksum: src_checksum,
             parts_count: None,
             part_etags: Vec::new(),
+            parts: Vec::new(),
         };
 
         // Re-acquire the bucket ref to store the object.
@@ -787,8 +1075,13 @@ impl RustackS3 {
         };
 
         Ok(CopyObjectOutput {
+            bucket_key_enabled: encryption.bucket_key_enabled,
             copy_object_result: Some(copy_result),
             copy_source_version_id: src_version_id,
+            sse_customer_algorithm,
+            sse_customer_key_md5,
+            ssekms_key_id: encryption.ssekms_key_id,
+            server_side_encryption: encryption.server_side_encryption,
             version_id: real_version_id,
             ..CopyObjectOutput::default()
         })
@@ -815,19 +1108,32 @@ impl AsStrOwned for StorageClass {
 }
 
 /// Build [`ObjectMetadata`] from a [`PutObjectInput`].
-fn build_metadata(input: &PutObjectInput) -> ObjectMetadata {
+fn build_metadata(
+    input: &PutObjectInput,
+    bucket: &S3Bucket,
+) -> Result<ObjectMetadata, S3ServiceError> {
     let user_metadata = input.metadata.clone();
 
     // Parse tagging from the x-amz-tagging header.
     let tagging = input
         .tagging
         .as_deref()
-        .map(parse_tagging_header)
+        .map(|t| parse_tagging_header(t).into())
         .unwrap_or_default();
 
-    let acl = parse_acl(input.acl.as_ref());
-
-    ObjectMetadata {
+    let (acl, grants) = object_acl(
+        &AclRequest {
+            canned: input.acl.as_ref().map(ObjectCannedACL::as_str),
+            grant_full_control: input.grant_full_control.as_deref(),
+            grant_read: input.grant_read.as_deref(),
+            grant_read_acp: input.grant_read_acp.as_deref(),
+            grant_write_acp: input.grant_write_acp.as_deref(),
+            ..AclRequest::default()
+        },
+        bucket,
+    )?;
+
+    Ok(ObjectMetadata {
         content_type: input.content_type.clone(),
         content_encoding: input.content_encoding.clone(),
         content_disposition: input.content_disposition.clone(),
@@ -845,6 +1151,7 @@ fn build_metadata(input: &PutObjectInput) -> ObjectMetadata {
         sse_customer_key_md5: input.sse_customer_key_md5.clone(),
         tagging,
         acl,
+        grants,
         object_lock_mode: input
             .object_lock_mode
             .as_ref()
@@ -854,7 +1161,8 @@ fn build_metadata(input: &PutObjectInput) -> ObjectMetadata {
             .object_lock_legal_hold_status
             .as_ref()
             .map(|s: &ObjectLockLegalHoldStatus| s.as_str() == "ON"),
-    }
+        integrity_failure: None,
+    })
 }
 
 /// Build [`ObjectMetadata`] for a copy operation with REPLACE directive.
@@ -864,18 +1172,16 @@ fn build_metadata_for_copy(input: &CopyObjectInput) -> ObjectMetadata {
     let tagging = input
         .tagging
         .as_deref()
-        .map(parse_tagging_header)
+        .map(|t| parse_tagging_header(t).into())
         .unwrap_or_default();
 
-    let acl = parse_acl(input.acl.as_ref());
-
     ObjectMetadata {
         content_type: input.content_type.clone(),
         content_encoding: input.content_encoding.clone(),
         content_disposition: input.content_disposition.clone(),
         content_language: input.content_language.clone(),
         cache_control: input.cache_control.clone(),
-        expires: None,
+        expires: input.expires.clone(),
         user_metadata,
         sse_algorithm: input
             .server_side_encryption
@@ -886,7 +1192,9 @@ fn build_metadata_for_copy(input: &CopyObjectInput) -> ObjectMetadata {
         sse_customer_algorithm: input.sse_customer_algorithm.clone(),
         sse_customer_key_md5: input.sse_customer_key_md5.clone(),
         tagging,
-        acl,
+        // Set from the request by the caller, whatever the directive.
+        acl: CannedAcl::default(),
+        grants: None,
         object_lock_mode: input
             .object_lock_mode
             .as_ref()
@@ -896,35 +1204,58 @@ fn build_metadata_for_copy(input: &CopyObjectInput) -> ObjectMetadata {
             .object_lock_legal_hold_status
             .as_ref()
             .map(|s: &ObjectLockLegalHoldStatus| s.as_str() == "ON"),
+        integrity_failure: None,
     }
 }
 
-/// Parse an optional [`ObjectCannedACL`] into our internal [`CannedAcl`].
-fn parse_acl(acl: Option<&ObjectCannedACL>) -> CannedAcl {
-    acl.and_then(|a| a.as_str().parse::<CannedAcl>().ok())
-        .unwrap_or_default()
+/// Resolve the ACL a write into `bucket` sets on the new object into its
+/// `acl` and `grants` metadata, private if the request sets none.
+pub(super) fn object_acl(
+    request: &AclRequest<'_>,
+    bucket: &S3Bucket,
+) -> Result<(CannedAcl, Option<Vec<Grant>>), S3ServiceError> {
+    let (mut acl, mut grants) = (CannedAcl::default(), None);
+    let update = request.resolve(&bucket.owner)?;
+    check_acl_supported(bucket, update.as_ref())?;
+    if let Some(update) = update {
+        update.apply(&mut acl, &mut grants);
+    }
+    Ok((acl, grants))
 }
 
 /// Parse the `x-amz-tagging` URL-encoded query string into tag pairs.
+///
+/// The header is form-encoded like a query string, so `+` decodes to a space
+/// just as `%20` does.
 pub(super) fn parse_tagging_header(tagging: &str) -> Vec<(String, String)> {
+    let decode = |component: &str| {
+        percent_encoding::percent_decode_str(&component.replace('+', " "))
+            .decode_utf8()
+            .ok()
+            .map(Cow::into_owned)
+    };
     tagging
         .split('&')
         .filter(|s| !s.is_empty())
         .filter_map(|pair| {
             let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
-            let key = percent_encoding::percent_decode_str(k)
-                .decode_utf8()
-                .ok()?
-                .to_string();
-            let value = percent_encoding::percent_decode_str(v)
-                .decode_utf8()
-                .ok()?
-                .to_string();
-            Some((key, value))
+            Some((decode(k)?, decode(v)?))
         })
         .collect()
 }
 
+/// Validate the tags of an `x-amz-tagging` header against the same limits as
+/// `PutObjectTagging`.
+#[allow(clippy::result_large_err)]
+pub(super) fn validate_tagging_header(tagging: Option<&str>) -> Result<(), S3Error> {
+    match tagging {
+        Some(tagging) => {
+            validate_tags(&parse_tagging_header(tagging)).map_err(S3ServiceError::into_s3_error)
+        }
+        None => Ok(()),
+    }
+}
+
 /// Holds the individual checksum fields for populating output structs.
 struct ChecksumFields {
     crc32: Option<String>,
@@ -960,6 +1291,34 @@ fn checksum_to_fields(checksum: &ChecksumData) -> ChecksumFields {
     fields
 }
 
+/// Resolve the byte range to serve for a GET or HEAD of an object.
+///
+/// Returns `None` when the whole object should be served: no `Range` was
+/// sent, S3 ignores its form, or `If-Range` no longer matches the object. An
+/// unsatisfiable range fails with `InvalidRange`, carrying the
+/// `Content-Range: bytes */<size>` header.
+#[allow(clippy::result_large_err)]
+fn resolve_range(
+    range: Option<&str>,
+    if_range: Option<&str>,
+    size: u64,
+    etag: &str,
+    last_modified: DateTime<Utc>,
+) -> Result<Option<(u64, u64)>, S3Error> {
+    let Some(range) = range else {
+        return Ok(None);
+    };
+    if if_range.is_some_and(|v| !is_valid_if_range(etag, last_modified, v)) {
+        return Ok(None);
+    }
+    parse_range_header(range, size).map_err(|e| {
+        e.into_s3_error()
+            .with_header("Content-Range", format!("bytes */{size}"))
+            .with_detail("RangeRequested", range)
+            .with_detail("ActualObjectSize", size.to_string())
+    })
+}
+
 /// Extract checksum data from a [`PutObjectInput`] if any checksum fields are
 /// set.
 ///
@@ -1062,6 +1421,13 @@ mod tests {
         assert_eq!(tags[0], ("key 1".to_owned(), "value 1".to_owned()));
     }
 
+    #[test]
+    fn test_should_parse_tagging_header_plus_as_space() {
+        let tags = parse_tagging_header("project+name=blue+team&a%2Bb=c");
+        assert_eq!(tags[0], ("project name".to_owned(), "blue team".to_owned()));
+        assert_eq!(tags[1], ("a+b".to_owned(), "c".to_owned()));
+    }
+
     #[test]
     fn test_should_parse_tagging_header_empty() {
         let tags = parse_tagging_header("");
@@ -1074,4 +1440,867 @@ mod tests {
         assert_eq!(tags.len(), 1);
         assert_eq!(tags[0], ("key1".to_owned(), String::new()));
     }
+
+    fn get_input(range: &str) -> GetObjectInput {
+        GetObjectInput {
+            bucket: "range-bucket".to_owned(),
+            key: "k".to_owned(),
+            range: Some(range.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_should_serve_ranges_and_honour_if_range() {
+        let client = crate::embedded::S3TestClient::new();
+        client
+            .create_bucket("range-bucket")
+            .await
+            .expect("create bucket");
+        let etag = client
+            .put_object("range-bucket", "k", "0123456789")
+            .await
+            .expect("put object")
+            .expect("etag");
+        let provider = client.provider();
+
+        let suffix = provider
+            .handle_get_object(get_input("bytes=-3"))
+            .await
+            .expect("suffix range");
+        assert_eq!(suffix.content_range.as_deref(), Some("bytes 7-9/10"));
+        assert_eq!(suffix.body.expect("body").data, Bytes::from_static(b"789"));
+
+        let open = provider
+            .handle_get_object(get_input("bytes=4-"))
+            .await
+            .expect("open-ended range");
+        assert_eq!(open.content_range.as_deref(), Some("bytes 4-9/10"));
+        assert_eq!(open.content_length, Some(6));
+
+        let multi = provider
+            .handle_get_object(get_input("bytes=0-1,4-5"))
+            .await
+            .expect("multi-range");
+        assert_eq!(multi.content_range, None);
+        assert_eq!(multi.content_length, Some(10));
+
+        let err = provider
+            .handle_get_object(get_input("bytes=10-"))
+            .await
+            .expect_err("unsatisfiable range");
+        assert_eq!(err.status_code, http::StatusCode::RANGE_NOT_SATISFIABLE);
+        assert!(
+            err.headers
+                .as_deref()
+                .is_some_and(|h| h.contains(&("Content-Range".to_owned(), "bytes */10".to_owned())))
+        );
+
+        let stale = provider
+            .handle_get_object_with_if_range(get_input("bytes=0-1"), Some("\"stale\"".to_owned()))
+            .await
+            .expect("stale if-range");
+        assert_eq!(stale.content_range, None);
+        let fresh = provider
+            .handle_get_object_with_if_range(get_input("bytes=0-1"), Some(etag))
+            .await
+            .expect("fresh if-range");
+        assert_eq!(fresh.content_range.as_deref(), Some("bytes 0-1/10"));
+
+        let head = provider
+            .handle_head_object(HeadObjectInput {
+                bucket: "range-bucket".to_owned(),
+                key: "k".to_owned(),
+                range: Some("bytes=-4".to_owned()),
+                ..Default::default()
+            })
+            .await
+            .expect("ranged head");
+        assert_eq!(head.content_length, Some(4));
+        assert_eq!(head.content_range.as_deref(), Some("bytes 6-9/10"));
+    }
+
+    #[tokio::test]
+    async fn test_should_validate_and_return_object_tags_in_order() {
+        use rustack_s3_model::{
+            input::{GetObjectTaggingInput, PutObjectTaggingInput},
+            types::{Tag, Tagging},
+        };
+
+        let client = crate::embedded::S3TestClient::new();
+        client
+            .create_bucket("tag-bucket")
+            .await
+            .expect("create bucket");
+        client
+            .put_object("tag-bucket", "k", "data")
+            .await
+            .expect("put object");
+        let provider = client.provider();
+        let get_tags = || {
+            provider.handle_get_object_tagging(GetObjectTaggingInput {
+                bucket: "tag-bucket".to_owned(),
+                key: "k".to_owned(),
+                ..Default::default()
+            })
+        };
+        let put_tags = |pairs: &[(&str, &str)]| {
+            provider.handle_put_object_tagging(PutObjectTaggingInput {
+                bucket: "tag-bucket".to_owned(),
+                key: "k".to_owned(),
+                tagging: Tagging {
+                    tag_set: pairs
+                        .iter()
+                        .map(|(k, v)| Tag {
+                            key: (*k).to_owned(),
+                            value: (*v).to_owned(),
+                        })
+                        .collect(),
+                },
+                ..Default::default()
+            })
+        };
+
+        let untagged = get_tags().await.expect("untagged object has a tag set");
+        assert!(untagged.tag_set.is_empty());
+
+        put_tags(&[("zone", "b"), ("app", "web"), ("env", "")])
+            .await
+            .expect("put tags");
+        let keys: Vec<String> = get_tags()
+            .await
+            .expect("get tags")
+            .tag_set
+            .into_iter()
+            .map(|t| t.key)
+            .collect();
+        assert_eq!(keys, ["zone", "app", "env"]);
+
+        let too_many: Vec<(String, String)> =
+            (0..11).map(|i| (format!("k{i}"), String::new())).collect();
+        let too_many: Vec<(&str, &str)> = too_many
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        let err = put_tags(&too_many).await.expect_err("11 tags");
+        assert_eq!(err.code.as_str(), "BadRequest");
+        for bad in [("aws:owner", "x"), ("a#b", "x"), ("ok", "a|b")] {
+            let err = put_tags(&[bad]).await.expect_err("invalid tag");
+            assert_eq!(err.code.as_str(), "InvalidTag", "{bad:?}");
+        }
+
+        assert!(validate_tagging_header(Some("a=1&b=2")).is_ok());
+        let err = validate_tagging_header(Some("aws%3Akey=1")).expect_err("reserved prefix");
+        assert_eq!(err.code.as_str(), "InvalidTag");
+    }
+
+    fn delete_entry(key: &str, e_tag: Option<&str>) -> ObjectIdentifier {
+        ObjectIdentifier {
+            key: key.to_owned(),
+            e_tag: e_tag.map(str::to_owned),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_should_report_delete_objects_results_per_key() {
+        let client = crate::embedded::S3TestClient::new();
+        client
+            .create_bucket("batch-bucket")
+            .await
+            .expect("create bucket");
+        let etag = client
+            .put_object("batch-bucket", "kept", "data")
+            .await
+            .expect("put object")
+            .expect("etag");
+        client
+            .put_object("batch-bucket", "gone", "data")
+            .await
+            .expect("put object");
+        let provider = client.provider();
+        let input = |objects: Vec<ObjectIdentifier>, quiet: bool| DeleteObjectsInput {
+            bucket: "batch-bucket".to_owned(),
+            delete: rustack_s3_model::types::Delete {
+                objects,
+                quiet: Some(quiet),
+            },
+            ..Default::default()
+        };
+
+        let output = provider
+            .handle_delete_objects(input(
+                vec![
+                    delete_entry("gone", None),
+                    delete_entry("kept", Some("\"stale\"")),
+                    delete_entry("missing", Some(&etag)),
+                ],
+                false,
+            ))
+            .await
+            .expect("delete objects");
+        assert_eq!(output.deleted.len(), 1);
+        assert_eq!(output.deleted[0].key.as_deref(), Some("gone"));
+        let codes: Vec<_> = output
+            .errors
+            .iter()
+            .map(|e| (e.key.as_deref(), e.code.as_deref()))
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                (Some("kept"), Some("PreconditionFailed")),
+                (Some("missing"), Some("NoSuchKey")),
+            ]
+        );
+
+        let output = provider
+            .handle_delete_objects(input(vec![delete_entry("kept", Some(&etag))], true))
+            .await
+            .expect("quiet delete objects");
+        assert!(output.deleted.is_empty());
+        assert!(output.errors.is_empty());
+        assert!(
+            client
+                .list_keys("batch-bucket")
+                .await
+                .expect("list")
+                .is_empty()
+        );
+
+        let too_many = (0..=MAX_DELETE_OBJECTS)
+            .map(|i| delete_entry(&format!("k{i}"), None))
+            .collect();
+        let err = provider
+            .handle_delete_objects(input(too_many, false))
+            .await
+            .expect_err("too many keys");
+        assert_eq!(err.code, S3ErrorCode::MalformedXML);
+    }
+
+    const SOURCE_KEY: (&str, &str) = (
+        "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=",
+        "hRasmdxgYDKV3nvbahU1MA==",
+    );
+    const DEST_KEY: (&str, &str) = (
+        "YWJjZGVmZ2hpamtsbW5vcHFyc3R1dnd4eXowMTIzNDU=",
+        "NX6C25NPxF9KJbS4Pci9GQ==",
+    );
+
+    fn sse_get_input(key: &str, customer_key: Option<(&str, &str)>) -> GetObjectInput {
+        GetObjectInput {
+            bucket: "sse-bucket".to_owned(),
+            key: key.to_owned(),
+            sse_customer_algorithm: customer_key.map(|_| "AES256".to_owned()),
+            sse_customer_key: customer_key.map(|(k, _)| k.to_owned()),
+            sse_customer_key_md5: customer_key.map(|(_, md5)| md5.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_should_require_customer_key_to_read_and_copy() {
+        let client = crate::embedded::S3TestClient::new();
+        client
+            .create_bucket("sse-bucket")
+            .await
+            .expect("create bucket");
+        let provider = client.provider();
+
+        let put = provider
+            .handle_put_object(PutObjectInput {
+                bucket: "sse-bucket".to_owned(),
+                key: "src".to_owned(),
+                body: Some(StreamingBlob::new(Bytes::from_static(b"secret"))),
+                sse_customer_algorithm: Some("AES256".to_owned()),
+                sse_customer_key: Some(SOURCE_KEY.0.to_owned()),
+                sse_customer_key_md5: Some(SOURCE_KEY.1.to_owned()),
+                ..Default::default()
+            })
+            .await
+            .expect("put with customer key");
+        assert_eq!(put.sse_customer_key_md5.as_deref(), Some(SOURCE_KEY.1));
+
+        let err = provider
+            .handle_get_object(sse_get_input("src", None))
+            .await
+            .expect_err("missing key");
+        assert_eq!(err.code, S3ErrorCode::InvalidRequest);
+        let err = provider
+            .handle_get_object(sse_get_input("src", Some(DEST_KEY)))
+            .await
+            .expect_err("wrong key");
+        assert_eq!(err.code, S3ErrorCode::AccessDenied);
+        let got = provider
+            .handle_get_object(sse_get_input("src", Some(SOURCE_KEY)))
+            .await
+            .expect("matching key");
+        assert_eq!(got.sse_customer_algorithm.as_deref(), Some("AES256"));
+        assert_eq!(got.sse_customer_key_md5.as_deref(), Some(SOURCE_KEY.1));
+
+        let copy_input = |source_key: Option<(&str, &str)>| CopyObjectInput {
+            bucket: "sse-bucket".to_owned(),
+            key: "dst".to_owned(),
+            copy_source: "sse-bucket/src".to_owned(),
+            copy_source_sse_customer_algorithm: source_key.map(|_| "AES256".to_owned()),
+            copy_source_sse_customer_key: source_key.map(|(k, _)| k.to_owned()),
+            copy_source_sse_customer_key_md5: source_key.map(|(_, md5)| md5.to_owned()),
+            sse_customer_algorithm: Some("AES256".to_owned()),
+            sse_customer_key: Some(DEST_KEY.0.to_owned()),
+            sse_customer_key_md5: Some(DEST_KEY.1.to_owned()),
+            ..Default::default()
+        };
+        let err = provider
+            .handle_copy_object(copy_input(None))
+            .await
+            .expect_err("missing source key");
+        assert_eq!(err.code, S3ErrorCode::InvalidRequest);
+        let copied = provider
+            .handle_copy_object(copy_input(Some(SOURCE_KEY)))
+            .await
+            .expect("copy with distinct keys");
+        assert_eq!(copied.sse_customer_key_md5.as_deref(), Some(DEST_KEY.1));
+
+        let head = provider
+            .handle_head_object(HeadObjectInput {
+                bucket: "sse-bucket".to_owned(),
+                key: "dst".to_owned(),
+                sse_customer_algorithm: Some("AES256".to_owned()),
+                sse_customer_key: Some(DEST_KEY.0.to_owned()),
+                sse_customer_key_md5: Some(DEST_KEY.1.to_owned()),
+                ..Default::default()
+            })
+            .await
+            .expect("head copy with destination key");
+        assert_eq!(head.sse_customer_key_md5.as_deref(), Some(DEST_KEY.1));
+        let err = provider
+            .handle_get_object(sse_get_input("dst", Some(SOURCE_KEY)))
+            .await
+            .expect_err("source key no longer opens the copy");
+        assert_eq!(err.code, S3ErrorCode::AccessDenied);
+    }
+
+    #[tokio::test]
+    async fn test_should_apply_bucket_default_encryption() {
+        use rustack_s3_model::{
+            input::PutBucketEncryptionInput,
+            types::{
+                ServerSideEncryptionByDefault, ServerSideEncryptionConfiguration,
+                ServerSideEncryptionRule,
+            },
+        };
+
+        let client = crate::embedded::S3TestClient::new();
+        client
+            .create_bucket("enc-bucket")
+            .await
+            .expect("create bucket");
+        let provider = client.provider();
+        provider
+            .handle_put_bucket_encryption(PutBucketEncryptionInput {
+                bucket: "enc-bucket".to_owned(),
+                server_side_encryption_configuration: ServerSideEncryptionConfiguration {
+                    rules: vec![ServerSideEncryptionRule {
+                        apply_server_side_encryption_by_default: Some(
+                            ServerSideEncryptionByDefault {
+                                kms_master_key_id: Some("alias/app".to_owned()),
+                                sse_algorithm: ServerSideEncryption::AwsKms,
+                            },
+                        ),
+                        bucket_key_enabled: Some(true),
+                        ..Default::default()
+                    }],
+                },
+                ..Default::default()
+            })
+            .await
+            .expect("put bucket encryption");
+
+        let put = provider
+            .handle_put_object(PutObjectInput {
+                bucket: "enc-bucket".to_owned(),
+                key: "defaulted".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .expect("put without encryption header");
+        assert_eq!(
+            put.server_side_encryption,
+            Some(ServerSideEncryption::AwsKms)
+        );
+        assert_eq!(put.ssekms_key_id.as_deref(), Some("alias/app"));
+        assert_eq!(put.bucket_key_enabled, Some(true));
+
+        provider
+            .handle_put_object(PutObjectInput {
+                bucket: "enc-bucket".to_owned(),
+                key: "explicit".to_owned(),
+                server_side_encryption: Some(ServerSideEncryption::Aes256),
+                ..Default::default()
+            })
+            .await
+            .expect("put with explicit encryption");
+        let head = |key: &str| HeadObjectInput {
+            bucket: "enc-bucket".to_owned(),
+            key: key.to_owned(),
+            ..Default::default()
+        };
+        let explicit = provider
+            .handle_head_object(head("explicit"))
+            .await
+            .expect("head explicit");
+        assert_eq!(
+            explicit.server_side_encryption,
+            Some(ServerSideEncryption::Aes256)
+        );
+        assert_eq!(explicit.ssekms_key_id, None);
+
+        let copied = provider
+            .handle_copy_object(CopyObjectInput {
+                bucket: "enc-bucket".to_owned(),
+                key: "copied".to_owned(),
+                copy_source: "enc-bucket/explicit".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .expect("copy");
+        assert_eq!(
+            copied.server_side_encryption,
+            Some(ServerSideEncryption::AwsKms)
+        );
+        let head = provider
+            .handle_head_object(head("copied"))
+            .await
+            .expect("head copy");
+        assert_eq!(head.bucket_key_enabled, Some(true));
+    }
+
+    async fn set_versioning(provider: &RustackS3, status: &str) -> Result<(), S3Error> {
+        use rustack_s3_model::{
+            input::PutBucketVersioningInput,
+            types::{BucketVersioningStatus, VersioningConfiguration},
+        };
+
+        provider
+            .handle_put_bucket_versioning(PutBucketVersioningInput {
+                bucket: "susp".to_owned(),
+                versioning_configuration: VersioningConfiguration {
+                    status: Some(BucketVersioningStatus::from(status)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .await
+    }
+
+    /// The `(version_id, is_latest)` pairs of the versions and delete
+    /// markers of `susp`.
+    async fn list_versions(provider: &RustackS3) -> (Vec<(String, bool)>, Vec<(String, bool)>) {
+        use rustack_s3_model::input::ListObjectVersionsInput;
+
+        let output = provider
+            .handle_list_object_versions(ListObjectVersionsInput {
+                bucket: "susp".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .expect("list versions");
+        let versions = output
+            .versions
+            .into_iter()
+            .map(|v| (v.version_id.unwrap_or_default(), v.is_latest == Some(true)))
+            .collect();
+        let markers = output
+            .delete_markers
+            .into_iter()
+            .map(|m| (m.version_id.unwrap_or_default(), m.is_latest == Some(true)))
+            .collect();
+        (versions, markers)
+    }
+
+    #[tokio::test]
+    async fn test_should_keep_null_version_while_versioning_suspended() {
+        use rustack_s3_model::input::PutObjectTaggingInput;
+
+        let client = crate::embedded::S3TestClient::new();
+        client.create_bucket("susp").await.expect("create bucket");
+        let provider = client.provider();
+        let put = |body: &'static str| {
+            provider.handle_put_object(PutObjectInput {
+                bucket: "susp".to_owned(),
+                key: "k".to_owned(),
+                body: Some(StreamingBlob::new(body)),
+                ..Default::default()
+            })
+        };
+        let get = |version_id: &str| {
+            provider.handle_get_object(GetObjectInput {
+                bucket: "susp".to_owned(),
+                key: "k".to_owned(),
+                version_id: Some(version_id.to_owned()),
+                ..Default::default()
+            })
+        };
+        set_versioning(provider, "Enabled").await.expect("enable");
+        let v1 = put("v1").await.expect("put v1").version_id.expect("v1 id");
+
+        set_versioning(provider, "Suspended")
+            .await
+            .expect("suspend");
+        let null_put = put("null-1").await.expect("put null-1");
+        assert!(null_put.version_id.is_none());
+        put("null-2").await.expect("put null-2");
+
+        let body = |output: GetObjectOutput| output.body.map(|b| b.data).unwrap_or_default();
+        assert_eq!(body(get("null").await.expect("get null")), "null-2");
+        assert_eq!(body(get(&v1).await.expect("get v1")), "v1");
+        assert_eq!(
+            list_versions(provider).await,
+            (vec![("null".to_owned(), true), (v1.clone(), false)], vec![])
+        );
+
+        // Tagging a version updates it in place rather than adding one.
+        provider
+            .handle_put_object_tagging(PutObjectTaggingInput {
+                bucket: "susp".to_owned(),
+                key: "k".to_owned(),
+                version_id: Some(v1.clone()),
+                ..Default::default()
+            })
+            .await
+            .expect("tag v1");
+        assert_eq!(list_versions(provider).await.0.len(), 2);
+
+        for _ in 0..2 {
+            let deleted = provider
+                .handle_delete_object(DeleteObjectInput {
+                    bucket: "susp".to_owned(),
+                    key: "k".to_owned(),
+                    ..Default::default()
+                })
+                .await
+                .expect("delete");
+            assert_eq!(deleted.delete_marker, Some(true));
+            assert_eq!(deleted.version_id.as_deref(), Some("null"));
+            assert_eq!(
+                list_versions(provider).await,
+                (vec![(v1.clone(), false)], vec![("null".to_owned(), true)])
+            );
+        }
+        let err = get("null").await.expect_err("null is a delete marker");
+        assert_eq!(err.code, S3ErrorCode::MethodNotAllowed);
+        assert_eq!(
+            client
+                .get_object("susp", "k")
+                .await
+                .expect_err("deleted")
+                .code,
+            S3ErrorCode::NoSuchKey
+        );
+
+        put("null-3").await.expect("put null-3");
+        assert_eq!(
+            list_versions(provider).await,
+            (vec![("null".to_owned(), true), (v1.clone(), false)], vec![])
+        );
+        assert_eq!(body(get("null").await.expect("get null")), "null-3");
+    }
+
+    #[tokio::test]
+    async fn test_should_apply_bucket_default_retention() {
+        use rustack_s3_model::{
+            input::{CreateBucketInput, PutObjectLockConfigurationInput},
+            types::{
+                DefaultRetention, ObjectLockConfiguration, ObjectLockRetentionMode, ObjectLockRule,
+            },
+        };
+
+        let client = crate::embedded::S3TestClient::new();
+        let provider = client.provider();
+        let lock_config = |bucket: &str| PutObjectLockConfigurationInput {
+            bucket: bucket.to_owned(),
+            object_lock_configuration: Some(ObjectLockConfiguration {
+                rule: Some(ObjectLockRule {
+                    default_retention: Some(DefaultRetention {
+                        days: Some(1),
+                        mode: Some(ObjectLockRetentionMode::from("GOVERNANCE")),
+                        years: None,
+                    }),
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        client.create_bucket("plain").await.expect("create plain");
+        let err = provider
+            .handle_put_object_lock_configuration(lock_config("plain"))
+            .await
+            .expect_err("lock on existing bucket");
+        assert_eq!(err.code, S3ErrorCode::InvalidBucketState);
+
+        provider
+            .handle_create_bucket(CreateBucketInput {
+                bucket: "locked".to_owned(),
+                object_lock_enabled_for_bucket: Some(true),
+                ..Default::default()
+            })
+            .await
+            .expect("create locked");
+        provider
+            .handle_put_object_lock_configuration(lock_config("locked"))
+            .await
+            .expect("put lock configuration");
+
+        let before = Utc::now();
+        let put = provider
+            .handle_put_object(PutObjectInput {
+                bucket: "locked".to_owned(),
+                key: "k".to_owned(),
+                body: Some(StreamingBlob::new("data")),
+                ..Default::default()
+            })
+            .await
+            .expect("put");
+        assert!(put.version_id.is_some(), "lock enables versioning");
+        assert_eq!(
+            put.object_lock_mode,
+            Some(ObjectLockMode::from("GOVERNANCE"))
+        );
+        let retain_until = put.object_lock_retain_until_date.expect("retain until");
+        assert!(retain_until >= before + chrono::TimeDelta::days(1));
+
+        let explicit_until = before + chrono::TimeDelta::days(7);
+        let put = provider
+            .handle_put_object(PutObjectInput {
+                bucket: "locked".to_owned(),
+                key: "k".to_owned(),
+                body: Some(StreamingBlob::new("data")),
+                object_lock_mode: Some(ObjectLockMode::from("COMPLIANCE")),
+                object_lock_retain_until_date: Some(explicit_until),
+                ..Default::default()
+            })
+            .await
+            .expect("put with retention");
+        assert_eq!(
+            put.object_lock_mode,
+            Some(ObjectLockMode::from("COMPLIANCE"))
+        );
+
+        let head = provider
+            .handle_head_object(HeadObjectInput {
+                bucket: "locked".to_owned(),
+                key: "k".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .expect("head");
+        assert_eq!(
+            head.object_lock_mode,
+            Some(ObjectLockMode::from("COMPLIANCE"))
+        );
+        assert_eq!(head.object_lock_retain_until_date, Some(explicit_until));
+
+        let put = client.put_object("plain", "k", "data").await;
+        assert!(put.is_ok());
+        let head = provider
+            .handle_head_object(HeadObjectInput {
+                bucket: "plain".to_owned(),
+                key: "k".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .expect("head plain");
+        assert_eq!(head.object_lock_mode, None);
+    }
+
+    /// `Cache-Control`, `Content-Disposition`, `Content-Encoding`,
+    /// `Content-Language`, `Content-Type` and `Expires`, in that order.
+    type StandardHeaders = [Option<String>; 6];
+
+    /// The standard headers HeadObject and GetObject return for `key`.
+    async fn standard_headers(
+        provider: &RustackS3,
+        key: &str,
+    ) -> (StandardHeaders, StandardHeaders) {
+        let head = provider
+            .handle_head_object(HeadObjectInput {
+                bucket: "headers".to_owned(),
+                key: key.to_owned(),
+                ..Default::default()
+            })
+            .await
+            .expect("head");
+        let get = provider
+            .handle_get_object(GetObjectInput {
+                bucket: "headers".to_owned(),
+                key: key.to_owned(),
+                ..Default::default()
+            })
+            .await
+            .expect("get");
+        (
+            [
+                head.cache_control,
+                head.content_disposition,
+                head.content_encoding,
+                head.content_language,
+                head.content_type,
+                head.expires,
+            ],
+            [
+                get.cache_control,
+                get.content_disposition,
+                get.content_encoding,
+                get.content_language,
+                get.content_type,
+                get.expires,
+            ],
+        )
+    }
+
+    /// Upload `key` in one part, setting the standard headers to `values` in
+    /// [`StandardHeaders`] order.
+    async fn complete_upload_with_headers(provider: &RustackS3, key: &str, values: [&str; 6]) {
+        use rustack_s3_model::{
+            input::{CompleteMultipartUploadInput, CreateMultipartUploadInput, UploadPartInput},
+            types::{CompletedMultipartUpload, CompletedPart},
+        };
+
+        let some = |v: &str| Some(v.to_owned());
+        let upload_id = provider
+            .handle_create_multipart_upload(CreateMultipartUploadInput {
+                bucket: "headers".to_owned(),
+                key: key.to_owned(),
+                cache_control: some(values[0]),
+                content_disposition: some(values[1]),
+                content_encoding: some(values[2]),
+                content_language: some(values[3]),
+                content_type: some(values[4]),
+                expires: some(values[5]),
+                ..Default::default()
+            })
+            .await
+            .expect("create upload")
+            .upload_id
+            .expect("upload id");
+        let part = provider
+            .handle_upload_part(UploadPartInput {
+                bucket: "headers".to_owned(),
+                key: key.to_owned(),
+                upload_id: upload_id.clone(),
+                part_number: 1,
+                body: Some(StreamingBlob::new("data")),
+                ..Default::default()
+            })
+            .await
+            .expect("upload part");
+        provider
+            .handle_complete_multipart_upload(CompleteMultipartUploadInput {
+                bucket: "headers".to_owned(),
+                key: key.to_owned(),
+                upload_id,
+                multipart_upload: Some(CompletedMultipartUpload {
+                    parts: vec![CompletedPart {
+                        part_number: Some(1),
+                        e_tag: part.e_tag,
+                        ..Default::default()
+                    }],
+                }),
+                ..Default::default()
+            })
+            .await
+            .expect("complete");
+    }
+
+    #[tokio::test]
+    async fn test_should_round_trip_standard_headers() {
+        use chrono::TimeZone;
+        use rustack_s3_model::types::MetadataDirective;
+
+        let client = crate::embedded::S3TestClient::new();
+        let provider = client.provider();
+        client
+            .create_bucket("headers")
+            .await
+            .expect("create bucket");
+        let original = [
+            "max-age=3600, must-revalidate",
+            "attachment; filename=\"report 1.txt\"",
+            "gzip",
+            "en-US",
+            "text/plain; charset=utf-8",
+            "Thu, 01 Dec 1994 16:00:00 GMT",
+        ];
+        let replaced = [
+            "no-cache",
+            "inline",
+            "identity",
+            "fr-CA",
+            "text/html",
+            "Sun, 06 Nov 1994 08:49:37 GMT",
+        ];
+        let some = |v: &str| Some(v.to_owned());
+
+        provider
+            .handle_put_object(PutObjectInput {
+                bucket: "headers".to_owned(),
+                key: "put".to_owned(),
+                body: Some(StreamingBlob::new("data")),
+                cache_control: some(original[0]),
+                content_disposition: some(original[1]),
+                content_encoding: some(original[2]),
+                content_language: some(original[3]),
+                content_type: some(original[4]),
+                expires: some(original[5]),
+                ..Default::default()
+            })
+            .await
+            .expect("put");
+        for (key, directive) in [("copied", "COPY"), ("replaced", "REPLACE")] {
+            provider
+                .handle_copy_object(CopyObjectInput {
+                    bucket: "headers".to_owned(),
+                    key: key.to_owned(),
+                    copy_source: "headers/put".to_owned(),
+                    metadata_directive: Some(MetadataDirective::from(directive)),
+                    cache_control: some(replaced[0]),
+                    content_disposition: some(replaced[1]),
+                    content_encoding: some(replaced[2]),
+                    content_language: some(replaced[3]),
+                    content_type: some(replaced[4]),
+                    expires: some(replaced[5]),
+                    ..Default::default()
+                })
+                .await
+                .expect("copy");
+        }
+
+        complete_upload_with_headers(provider, "multipart", original).await;
+
+        for (key, expected) in [
+            ("put", original),
+            ("copied", original),
+            ("replaced", replaced),
+            ("multipart", original),
+        ] {
+            let (head, get) = standard_headers(provider, key).await;
+            assert_eq!(head, expected.map(some), "HeadObject {key}");
+            assert_eq!(get, expected.map(some), "GetObject {key}");
+        }
+
+        let get = provider
+            .handle_get_object(GetObjectInput {
+                bucket: "headers".to_owned(),
+                key: "put".to_owned(),
+                response_expires: Utc.with_ymd_and_hms(2030, 1, 2, 3, 4, 5).single(),
+                ..Default::default()
+            })
+            .await
+            .expect("get with override");
+        assert_eq!(
+            get.expires.as_deref(),
+            Some("Wed, 02 Jan 2030 03:04:05 GMT")
+        );
+    }
 }
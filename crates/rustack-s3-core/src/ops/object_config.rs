@@ -19,15 +19,24 @@ use rustack_s3_model::{
         PutObjectTaggingOutput,
     },
     types::{
-        Checksum, ChecksumType, GetObjectAttributesParts, Grant, Grantee, ObjectLockLegalHold,
-        ObjectLockLegalHoldStatus, ObjectLockRetention, ObjectLockRetentionMode, Permission,
-        StorageClass, Tag, Type,
+        Checksum, ChecksumType, GetObjectAttributesParts, ObjectAttributes, ObjectCannedACL,
+        ObjectLockLegalHold, ObjectLockLegalHoldStatus, ObjectLockRetention,
+        ObjectLockRetentionMode, ObjectPart as ModelObjectPart, StorageClass, Tag,
     },
 };
 use tracing::debug;
 
-use super::bucket::to_model_owner;
-use crate::{error::S3ServiceError, provider::RustackS3, state::object::CannedAcl};
+use super::{
+    bucket::to_model_owner,
+    multipart::{MAX_LIST_PARTS, checksum_to_output_fields},
+};
+use crate::{
+    acl::{AclRequest, check_acl_supported, effective_grants, to_model_grants},
+    error::S3ServiceError,
+    provider::RustackS3,
+    sse::{CustomerKey, check_customer_key},
+    state::object::{CannedAcl, ObjectPart, TagSet},
+};
 
 // AWS S3 DTOs use signed integers (i32/i64) for inherently non-negative values.
 // These handler methods must remain async for consistency.
@@ -75,8 +84,8 @@ impl RustackS3 {
             .tagging
             .iter()
             .map(|(k, v)| Tag {
-                key: k.clone(),
-                value: v.clone(),
+                key: k.to_owned(),
+                value: v.to_owned(),
             })
             .collect();
 
@@ -115,11 +124,9 @@ impl RustackS3 {
 
         crate::validation::validate_tags(&tags).map_err(S3ServiceError::into_s3_error)?;
 
-        // We need mutable access to the object. Since ObjectStore wraps objects
-        // immutably, we re-insert a modified copy.
         let mut store = bucket.objects.write();
         let obj = if let Some(version_id) = &input.version_id {
-            store.get_version(&key, version_id).ok_or_else(|| {
+            store.get_version_mut(&key, version_id).ok_or_else(|| {
                 S3ServiceError::NoSuchVersion {
                     key: key.clone(),
                     version_id: version_id.clone(),
@@ -128,13 +135,11 @@ impl RustackS3 {
             })?
         } else {
             store
-                .get(&key)
+                .get_mut(&key)
                 .ok_or_else(|| S3ServiceError::NoSuchKey { key: key.clone() }.into_s3_error())?
         };
 
-        let mut updated = obj.clone();
-        updated.metadata.tagging = tags;
-        store.put(updated);
+        obj.metadata.tagging = tags.into();
 
         debug!(bucket = %bucket_name, key = %key, "put_object_tagging completed");
 
@@ -159,7 +164,7 @@ impl RustackS3 {
 
         let mut store = bucket.objects.write();
         let obj = if let Some(version_id) = &input.version_id {
-            store.get_version(&key, version_id).ok_or_else(|| {
+            store.get_version_mut(&key, version_id).ok_or_else(|| {
                 S3ServiceError::NoSuchVersion {
                     key: key.clone(),
                     version_id: version_id.clone(),
@@ -168,13 +173,11 @@ impl RustackS3 {
             })?
         } else {
             store
-                .get(&key)
+                .get_mut(&key)
                 .ok_or_else(|| S3ServiceError::NoSuchKey { key: key.clone() }.into_s3_error())?
         };
 
-        let mut updated = obj.clone();
-        updated.metadata.tagging = Vec::new();
-        store.put(updated);
+        obj.metadata.tagging = TagSet::default();
 
         debug!(bucket = %bucket_name, key = %key, "delete_object_tagging completed");
 
@@ -216,22 +219,24 @@ impl RustackS3 {
                 .ok_or_else(|| S3ServiceError::NoSuchKey { key: key.clone() }.into_s3_error())?
         };
 
-        let owner = to_model_owner(&obj.owner);
-
-        let grant = Grant {
-            grantee: Some(Grantee {
-                display_name: Some(obj.owner.display_name.clone()),
-                email_address: None,
-                id: Some(obj.owner.id.clone()),
-                r#type: Type::CanonicalUser,
-                uri: None,
-            }),
-            permission: Some(Permission::FullControl),
+        // With ACLs disabled, the bucket owner owns the object and the
+        // stored ACL no longer applies.
+        let (owner, grants) = if bucket.acls_disabled() {
+            let grants = effective_grants(CannedAcl::Private, None, &bucket.owner, &bucket.owner);
+            (&bucket.owner, grants)
+        } else {
+            let grants = effective_grants(
+                obj.metadata.acl,
+                obj.metadata.grants.as_deref(),
+                &obj.owner,
+                &bucket.owner,
+            );
+            (&obj.owner, grants)
         };
 
         Ok(GetObjectAclOutput {
-            grants: vec![grant],
-            owner: Some(owner),
+            grants: to_model_grants(&grants),
+            owner: Some(to_model_owner(owner)),
             request_charged: None,
         })
     }
@@ -249,15 +254,23 @@ impl RustackS3 {
             .get_bucket(&bucket_name)
             .map_err(S3ServiceError::into_s3_error)?;
 
-        if let Some(acl_enum) = input.acl {
-            let acl: CannedAcl = acl_enum
-                .as_str()
-                .parse()
-                .map_err(|_| S3Error::invalid_argument("Invalid canned ACL"))?;
+        let update = AclRequest {
+            canned: input.acl.as_ref().map(ObjectCannedACL::as_str),
+            grant_full_control: input.grant_full_control.as_deref(),
+            grant_read: input.grant_read.as_deref(),
+            grant_read_acp: input.grant_read_acp.as_deref(),
+            grant_write: input.grant_write.as_deref(),
+            grant_write_acp: input.grant_write_acp.as_deref(),
+            policy: input.access_control_policy.as_ref(),
+        }
+        .resolve(&bucket.owner)
+        .map_err(S3ServiceError::into_s3_error)?;
+        check_acl_supported(&bucket, update.as_ref()).map_err(S3ServiceError::into_s3_error)?;
 
+        if let Some(update) = update {
             let mut store = bucket.objects.write();
             let obj = if let Some(version_id) = &input.version_id {
-                store.get_version(&key, version_id).ok_or_else(|| {
+                store.get_version_mut(&key, version_id).ok_or_else(|| {
                     S3ServiceError::NoSuchVersion {
                         key: key.clone(),
                         version_id: version_id.clone(),
@@ -266,13 +279,11 @@ impl RustackS3 {
                 })?
             } else {
                 store
-                    .get(&key)
+                    .get_mut(&key)
                     .ok_or_else(|| S3ServiceError::NoSuchKey { key: key.clone() }.into_s3_error())?
             };
 
-            let mut updated = obj.clone();
-            updated.metadata.acl = acl;
-            store.put(updated);
+            update.apply(&mut obj.metadata.acl, &mut obj.metadata.grants);
         }
 
         debug!(bucket = %bucket_name, key = %key, "put_object_acl completed");
@@ -549,13 +560,23 @@ impl RustackS3 {
     // Get Object Attributes
     // -----------------------------------------------------------------------
 
-    /// Get attributes for an object.
+    /// Get the requested attributes of an object.
+    ///
+    /// Only the attributes named in `x-amz-object-attributes` are returned.
+    /// `ObjectParts` is returned for multipart objects and pages through the
+    /// parts with `max-parts` and `part-number-marker`, as `ListParts` does.
     pub async fn handle_get_object_attributes(
         &self,
         input: GetObjectAttributesInput,
     ) -> Result<GetObjectAttributesOutput, S3Error> {
         let bucket_name = input.bucket;
         let key = input.key;
+        let customer_key = CustomerKey::from_headers(
+            input.sse_customer_algorithm.as_deref(),
+            input.sse_customer_key.as_deref(),
+            input.sse_customer_key_md5.as_deref(),
+        )
+        .map_err(S3ServiceError::into_s3_error)?;
 
         let bucket = self
             .state
@@ -565,58 +586,129 @@ impl RustackS3 {
         let store = bucket.objects.read();
         let obj = if let Some(version_id) = &input.version_id {
             store.get_version(&key, version_id).ok_or_else(|| {
-                S3ServiceError::NoSuchVersion {
-                    key: key.clone(),
-                    version_id: version_id.clone(),
+                if store.is_delete_marker(&key, version_id) {
+                    S3ServiceError::MethodNotAllowed
+                        .into_s3_error()
+                        .with_header("x-amz-delete-marker", "true")
+                        .with_header("x-amz-version-id", version_id.clone())
+                } else {
+                    S3ServiceError::NoSuchVersion {
+                        key: key.clone(),
+                        version_id: version_id.clone(),
+                    }
+                    .into_s3_error()
                 }
-                .into_s3_error()
             })?
         } else {
             store
                 .get(&key)
                 .ok_or_else(|| S3ServiceError::NoSuchKey { key: key.clone() }.into_s3_error())?
         };
+        check_customer_key(&obj.metadata, customer_key.as_ref())
+            .map_err(S3ServiceError::into_s3_error)?;
 
         let version_id = if obj.version_id == "null" {
             None
         } else {
             Some(obj.version_id.clone())
         };
+        let wants = |attribute: ObjectAttributes| input.object_attributes.contains(&attribute);
+
+        let checksum = obj
+            .checksum
+            .as_ref()
+            .filter(|_| wants(ObjectAttributes::Checksum))
+            .map(|c| {
+                let (crc32, crc32c, crc64nvme, sha1, sha256) = checksum_to_output_fields(Some(c));
+                Checksum {
+                    checksum_crc32: crc32,
+                    checksum_crc32c: crc32c,
+                    checksum_crc64nvme: crc64nvme,
+                    checksum_sha1: sha1,
+                    checksum_sha256: sha256,
+                    checksum_type: Some(match c.checksum_type.as_str() {
+                        "COMPOSITE" => ChecksumType::Composite,
+                        _ => ChecksumType::FullObject,
+                    }),
+                }
+            });
 
-        let checksum = obj.checksum.as_ref().map(|c| {
-            let mut cksum = Checksum::default();
-            match c.algorithm.as_str() {
-                "CRC32" => cksum.checksum_crc32 = Some(c.value.clone()),
-                "CRC32C" => cksum.checksum_crc32c = Some(c.value.clone()),
-                "CRC64NVME" => cksum.checksum_crc64nvme = Some(c.value.clone()),
-                "SHA1" => cksum.checksum_sha1 = Some(c.value.clone()),
-                "SHA256" => cksum.checksum_sha256 = Some(c.value.clone()),
-                _ => {}
-            }
-            cksum.checksum_type = Some(match c.checksum_type.as_str() {
-                "COMPOSITE" => ChecksumType::Composite,
-                _ => ChecksumType::FullObject,
+        let object_parts = obj
+            .parts_count
+            .filter(|_| wants(ObjectAttributes::ObjectParts))
+            .map(|total| {
+                object_parts_page(
+                    &obj.parts,
+                    total,
+                    input.max_parts,
+                    input.part_number_marker.as_deref(),
+                )
             });
-            cksum
-        });
+
+        debug!(bucket = %bucket_name, key = %key, "get_object_attributes completed");
 
         Ok(GetObjectAttributesOutput {
             checksum,
             delete_marker: None,
-            e_tag: Some(obj.etag.clone()),
+            e_tag: wants(ObjectAttributes::Etag).then(|| obj.etag.trim_matches('"').to_owned()),
             last_modified: Some(obj.last_modified),
-            object_parts: obj.parts_count.map(|n| GetObjectAttributesParts {
-                is_truncated: None,
-                max_parts: None,
-                next_part_number_marker: None,
-                part_number_marker: None,
-                parts: Vec::new(),
-                total_parts_count: Some(n as i32),
-            }),
-            object_size: Some(obj.size as i64),
+            object_parts,
+            object_size: wants(ObjectAttributes::ObjectSize).then_some(obj.size as i64),
             request_charged: None,
-            storage_class: Some(StorageClass::from(obj.storage_class.as_str())),
+            storage_class: wants(ObjectAttributes::StorageClass)
+                .then(|| StorageClass::from(obj.storage_class.as_str())),
             version_id,
         })
     }
 }
+
+/// Build one page of the `ObjectParts` attribute of a multipart object with
+/// `total` parts.
+#[allow(
+    clippy::cast_possible_wrap,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn object_parts_page(
+    parts: &[ObjectPart],
+    total: u32,
+    max_parts: Option<i32>,
+    part_number_marker: Option<&str>,
+) -> GetObjectAttributesParts {
+    let max_parts = max_parts.map_or(MAX_LIST_PARTS, |n| n.clamp(0, MAX_LIST_PARTS));
+    let marker: u32 = part_number_marker.and_then(|s| s.parse().ok()).unwrap_or(0);
+
+    let mut remaining = parts.iter().filter(|p| p.part_number > marker).peekable();
+    let mut page = Vec::new();
+    while page.len() < max_parts as usize {
+        let Some(part) = remaining.next() else {
+            break;
+        };
+        let (crc32, crc32c, crc64nvme, sha1, sha256) =
+            checksum_to_output_fields(part.checksum.as_ref());
+        page.push(ModelObjectPart {
+            checksum_crc32: crc32,
+            checksum_crc32c: crc32c,
+            checksum_crc64nvme: crc64nvme,
+            checksum_sha1: sha1,
+            checksum_sha256: sha256,
+            part_number: Some(part.part_number as i32),
+            size: Some(part.size as i64),
+        });
+    }
+    let is_truncated = remaining.peek().is_some();
+    let next_part_number_marker = page
+        .last()
+        .and_then(|p| p.part_number)
+        .filter(|_| is_truncated)
+        .map(|n| n.to_string());
+
+    GetObjectAttributesParts {
+        is_truncated: Some(is_truncated),
+        max_parts: Some(max_parts),
+        next_part_number_marker,
+        part_number_marker: Some(marker.to_string()),
+        parts: page,
+        total_parts_count: Some(total as i32),
+    }
+}
@@ -0,0 +1,1246 @@
+//! Bucket policy parsing and request authorization.
+//!
+//! Bucket policies are stored verbatim by `PutBucketPolicy` (after being
+//! validated with [`BucketPolicy::parse`]) and parsed again whenever a request
+//! needs authorizing. [`RustackS3::authorize`] combines the bucket policy with
+//! canned ACLs and the bucket's public access block to decide whether a
+//! request may proceed.
+//!
+//! The supported subset of the policy language is `Effect`, `Principal` (`"*"`
+//! or `{"AWS": ...}`), `Action`, `Resource` (with `*` and `?` wildcards) and
+//! `Condition`. Condition operators are the `String*`, `IpAddress`, `Bool` and
+//! `Null` families, optionally suffixed with `IfExists`. Condition keys are
+//! resolved from the request:
+//!
+//! | Key | Source |
+//! |-----|--------|
+//! | `aws:Referer`, `aws:UserAgent` | request header |
+//! | `aws:SourceIp` | client address |
+//! | `aws:userid`, `aws:username` | authenticated access key ID |
+//! | `s3:prefix`, `s3:delimiter`, `s3:max-keys` | query parameter |
+//! | `s3:x-amz-*` (e.g. `s3:x-amz-server-side-encryption`) | request header |
+//!
+//! A statement using an unsupported operator never matches.
+
+use std::{collections::HashMap, net::IpAddr};
+
+use rustack_s3_model::{
+    S3Operation,
+    error::{S3Error, S3ErrorCode},
+};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use tracing::debug;
+
+use crate::{
+    acl::{effective_grants, grants_all_users, grants_public_access},
+    provider::RustackS3,
+    state::{bucket::S3Bucket, object::Permission},
+};
+
+/// Outcome of evaluating a bucket policy against a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyDecision {
+    /// A matching `Allow` statement grants the request.
+    Allow,
+    /// A matching `Deny` statement rejects the request.
+    Deny,
+    /// No statement matched; the decision falls back to ACLs.
+    Implicit,
+}
+
+/// The attributes of a request that authorization is evaluated against.
+#[derive(Debug, Clone, Copy)]
+pub struct AccessRequest<'a> {
+    /// The resolved S3 operation.
+    pub operation: S3Operation,
+    /// Target bucket, if any.
+    pub bucket: Option<&'a str>,
+    /// Target object key, if any.
+    pub key: Option<&'a str>,
+    /// Access key ID of an authenticated caller; `None` for anonymous requests.
+    pub principal: Option<&'a str>,
+    /// Request headers, used for condition keys such as `aws:Referer`.
+    pub headers: &'a http::HeaderMap,
+    /// Query parameters, used for condition keys such as `s3:prefix`.
+    pub query_params: &'a [(String, String)],
+    /// Address of the client that sent the request, if known.
+    pub source_ip: Option<IpAddr>,
+}
+
+impl AccessRequest<'_> {
+    /// The IAM action this request is authorized as (e.g. `s3:GetObject`).
+    #[must_use]
+    pub fn action(&self) -> &'static str {
+        iam_action(self.operation)
+    }
+
+    /// The ARN of the bucket or object this request targets.
+    #[must_use]
+    pub fn resource_arn(&self) -> String {
+        match (self.bucket, self.key) {
+            (Some(bucket), Some(key)) => format!("arn:aws:s3:::{bucket}/{key}"),
+            (Some(bucket), None) => format!("arn:aws:s3:::{bucket}"),
+            (None, _) => "*".to_owned(),
+        }
+    }
+
+    /// Look up a global condition key in the request context.
+    fn context_value(&self, key: &str) -> Option<String> {
+        let header = |name: &str| {
+            self.headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(ToOwned::to_owned)
+        };
+        let query = |name: &str| {
+            self.query_params
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.clone())
+        };
+        let key = key.to_ascii_lowercase();
+        match key.as_str() {
+            "aws:referer" => header("referer"),
+            "aws:useragent" => header("user-agent"),
+            "aws:sourceip" => self.source_ip.map(|ip| ip.to_string()),
+            "aws:userid" | "aws:username" => self.principal.map(ToOwned::to_owned),
+            "s3:prefix" | "s3:delimiter" | "s3:max-keys" => query(&key[3..]),
+            _ => key
+                .strip_prefix("s3:")
+                .filter(|h| h.starts_with("x-amz-"))
+                .and_then(header),
+        }
+    }
+}
+
+/// A parsed bucket policy document.
+#[derive(Debug, Clone)]
+pub struct BucketPolicy {
+    statements: Vec<Statement>,
+}
+
+impl BucketPolicy {
+    /// Parse a bucket policy JSON document.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MalformedPolicy` if the document is not valid JSON, has no
+    /// statements, or a statement has an unknown `Effect`.
+    #[allow(clippy::result_large_err)]
+    pub fn parse(document: &str) -> Result<Self, S3Error> {
+        let parsed: PolicyDocument = serde_json::from_str(document)
+            .map_err(|e| malformed_policy(format!("Policy has invalid JSON: {e}")))?;
+        if parsed.statement.is_empty() {
+            return Err(malformed_policy("Missing required field Statement"));
+        }
+        Ok(Self {
+            statements: parsed.statement,
+        })
+    }
+
+    /// Evaluate the policy; an explicit `Deny` always wins over an `Allow`.
+    #[must_use]
+    pub fn evaluate(&self, request: &AccessRequest<'_>) -> PolicyDecision {
+        let action = request.action().to_ascii_lowercase();
+        let resource = request.resource_arn();
+        let mut decision = PolicyDecision::Implicit;
+        for statement in &self.statements {
+            if !statement.applies(request, &action, &resource) {
+                continue;
+            }
+            match statement.effect {
+                Effect::Deny => return PolicyDecision::Deny,
+                Effect::Allow => decision = PolicyDecision::Allow,
+            }
+        }
+        decision
+    }
+
+    /// Whether the policy grants access to everyone.
+    ///
+    /// As in AWS, an `Allow` statement with a wildcard principal is public
+    /// unless one of its conditions pins a [restricting key](RESTRICTING_KEYS)
+    /// to fixed values. Conditions on keys any caller controls, such as
+    /// `aws:Referer`, do not make a statement private.
+    #[must_use]
+    pub fn is_public(&self) -> bool {
+        self.statements.iter().any(|s| {
+            s.effect == Effect::Allow
+                && s.principal.as_ref().is_some_and(Principal::is_wildcard)
+                && !s.condition.iter().any(|(operator, entries)| {
+                    entries
+                        .iter()
+                        .any(|(key, values)| restricts_access(operator, key, values))
+                })
+        })
+    }
+}
+
+/// Condition keys that, pinned to fixed values, limit a statement to known
+/// callers or networks.
+const RESTRICTING_KEYS: &[&str] = &[
+    "aws:principalaccount",
+    "aws:principalarn",
+    "aws:principalorgid",
+    "aws:principalorgpaths",
+    "aws:principalservicename",
+    "aws:sourcearn",
+    "aws:sourceaccount",
+    "aws:sourceip",
+    "aws:sourceowner",
+    "aws:sourcevpc",
+    "aws:sourcevpce",
+    "aws:userid",
+    "s3:dataaccesspointaccount",
+    "s3:dataaccesspointarn",
+    "s3:x-amz-server-side-encryption-aws-kms-key-id",
+];
+
+/// Whether a condition pins a restricting key to fixed values: a positive
+/// operator whose values contain no wildcard, and for `aws:SourceIp` no
+/// range covering every address.
+fn restricts_access(operator: &str, key: &str, values: &Value) -> bool {
+    let key = key.to_ascii_lowercase();
+    if !RESTRICTING_KEYS.contains(&key.as_str()) {
+        return false;
+    }
+    let operator = operator.strip_suffix("IfExists").unwrap_or(operator);
+    let fixed: fn(&str) -> bool = match operator {
+        "StringEquals" | "StringEqualsIgnoreCase" | "ArnEquals" | "StringLike" | "ArnLike" => {
+            |value: &str| !value.contains(['*', '?'])
+        }
+        "IpAddress" => |value: &str| !value.ends_with("/0"),
+        _ => return false,
+    };
+    let values = scalar_values(values);
+    !values.is_empty() && values.iter().all(|value| fixed(value))
+}
+
+/// Top-level shape of a policy document; other fields are ignored.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PolicyDocument {
+    #[serde(default, deserialize_with = "one_or_many")]
+    statement: Vec<Statement>,
+}
+
+/// A single policy statement.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Statement {
+    effect: Effect,
+    #[serde(default)]
+    principal: Option<Principal>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    action: Vec<String>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    resource: Vec<String>,
+    /// Operator -> condition key -> expected value(s).
+    #[serde(default)]
+    condition: HashMap<String, HashMap<String, Value>>,
+}
+
+impl Statement {
+    /// Whether the statement covers the request; `action` must be lowercase.
+    fn applies(&self, request: &AccessRequest<'_>, action: &str, resource: &str) -> bool {
+        self.principal
+            .as_ref()
+            .is_some_and(|p| p.matches(request.principal))
+            && self
+                .action
+                .iter()
+                .any(|pattern| wildcard_match(&pattern.to_ascii_lowercase(), action))
+            && self
+                .resource
+                .iter()
+                .any(|pattern| wildcard_match(pattern, resource))
+            && self.condition.iter().all(|(operator, entries)| {
+                entries.iter().all(|(key, expected)| {
+                    condition_matches(
+                        operator,
+                        &scalar_values(expected),
+                        request.context_value(key).as_deref(),
+                    )
+                })
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+enum Effect {
+    Allow,
+    Deny,
+}
+
+/// A statement principal: `"*"` or a map such as `{"AWS": [...]}`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum Principal {
+    Wildcard(String),
+    Mapped(HashMap<String, Value>),
+}
+
+impl Principal {
+    /// Whether the principal covers the caller (`None` for anonymous callers).
+    fn matches(&self, caller: Option<&str>) -> bool {
+        match self {
+            Self::Wildcard(value) => value == "*",
+            Self::Mapped(map) => map.get("AWS").is_some_and(|aws| {
+                scalar_values(aws)
+                    .iter()
+                    .any(|p| p == "*" || caller.is_some_and(|c| p == c))
+            }),
+        }
+    }
+
+    fn is_wildcard(&self) -> bool {
+        self.matches(None)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+/// Accept either a single value or a list, as the policy grammar allows.
+fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
+/// Flatten a JSON scalar or array of scalars into strings.
+fn scalar_values(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(items) => items.iter().flat_map(scalar_values).collect(),
+        Value::Bool(b) => vec![b.to_string()],
+        Value::Number(n) => vec![n.to_string()],
+        Value::Null | Value::Object(_) => Vec::new(),
+    }
+}
+
+/// Evaluate one condition operator for one key.
+///
+/// A missing context value fails positive operators and satisfies negated ones,
+/// unless the operator ends in `IfExists`, in which case it always matches.
+/// `Null` tests for presence only. Unsupported operators never match.
+fn condition_matches(operator: &str, expected: &[String], actual: Option<&str>) -> bool {
+    if operator == "Null" {
+        return expected
+            .iter()
+            .any(|e| e.eq_ignore_ascii_case("true") == actual.is_none());
+    }
+    let (operator, if_exists) = match operator.strip_suffix("IfExists") {
+        Some(base) => (base, true),
+        None => (operator, false),
+    };
+    let (negated, test): (bool, fn(&str, &str) -> bool) = match operator {
+        "StringEquals" => (false, |e, a| e == a),
+        "StringNotEquals" => (true, |e, a| e == a),
+        "StringEqualsIgnoreCase" | "Bool" => (false, str::eq_ignore_ascii_case),
+        "StringNotEqualsIgnoreCase" => (true, str::eq_ignore_ascii_case),
+        "StringLike" => (false, wildcard_match),
+        "StringNotLike" => (true, wildcard_match),
+        "IpAddress" => (false, ip_in_cidr),
+        "NotIpAddress" => (true, ip_in_cidr),
+        _ => return false,
+    };
+    let Some(actual) = actual else {
+        return negated || if_exists;
+    };
+    expected.iter().any(|e| test(e, actual)) != negated
+}
+
+/// Match `value` against a pattern where `*` matches any run of characters and
+/// `?` matches exactly one.
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    let (mut p, mut v) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while v < value.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == value[v]) {
+            p += 1;
+            v += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, v));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            v = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Whether `ip` lies within `cidr` (`192.0.2.0/24`, `2001:db8::/32`, or a bare address).
+fn ip_in_cidr(cidr: &str, ip: &str) -> bool {
+    let Ok(ip) = ip.parse::<IpAddr>() else {
+        return false;
+    };
+    let (network, prefix) = match cidr.split_once('/') {
+        Some((network, prefix)) => match prefix.parse::<u32>() {
+            Ok(prefix) => (network, Some(prefix)),
+            Err(_) => return false,
+        },
+        None => (cidr, None),
+    };
+    let Ok(network) = network.trim().parse::<IpAddr>() else {
+        return false;
+    };
+    match (network, ip.to_canonical()) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) => {
+            let mask = u32::MAX
+                .checked_shl(32 - prefix.unwrap_or(32).min(32))
+                .unwrap_or(0);
+            u32::from(network) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) => {
+            let mask = u128::MAX
+                .checked_shl(128 - prefix.unwrap_or(128).min(128))
+                .unwrap_or(0);
+            u128::from(network) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
+
+fn malformed_policy(message: impl Into<String>) -> S3Error {
+    let mut err = S3Error::with_message(S3ErrorCode::Custom("MalformedPolicy"), message);
+    err.status_code = http::StatusCode::BAD_REQUEST;
+    err
+}
+
+/// Map an S3 operation to the IAM action used in policies.
+#[must_use]
+pub fn iam_action(operation: S3Operation) -> &'static str {
+    match operation {
+        S3Operation::CreateBucket => "s3:CreateBucket",
+        S3Operation::DeleteBucket => "s3:DeleteBucket",
+        S3Operation::HeadBucket | S3Operation::ListObjects | S3Operation::ListObjectsV2 => {
+            "s3:ListBucket"
+        }
+        S3Operation::ListBuckets => "s3:ListAllMyBuckets",
+        S3Operation::GetBucketLocation => "s3:GetBucketLocation",
+        S3Operation::GetBucketVersioning => "s3:GetBucketVersioning",
+        S3Operation::PutBucketVersioning => "s3:PutBucketVersioning",
+        S3Operation::GetBucketEncryption => "s3:GetEncryptionConfiguration",
+        S3Operation::PutBucketEncryption | S3Operation::DeleteBucketEncryption => {
+            "s3:PutEncryptionConfiguration"
+        }
+        S3Operation::GetBucketCors => "s3:GetBucketCORS",
+        S3Operation::PutBucketCors | S3Operation::DeleteBucketCors => "s3:PutBucketCORS",
+        S3Operation::GetBucketLifecycleConfiguration => "s3:GetLifecycleConfiguration",
+        S3Operation::PutBucketLifecycleConfiguration | S3Operation::DeleteBucketLifecycle => {
+            "s3:PutLifecycleConfiguration"
+        }
+        S3Operation::GetBucketPolicy => "s3:GetBucketPolicy",
+        S3Operation::PutBucketPolicy => "s3:PutBucketPolicy",
+        S3Operation::DeleteBucketPolicy => "s3:DeleteBucketPolicy",
+        S3Operation::GetBucketTagging => "s3:GetBucketTagging",
+        S3Operation::PutBucketTagging | S3Operation::DeleteBucketTagging => "s3:PutBucketTagging",
+        S3Operation::GetBucketNotificationConfiguration => "s3:GetBucketNotification",
+        S3Operation::PutBucketNotificationConfiguration => "s3:PutBucketNotification",
+        S3Operation::GetBucketLogging => "s3:GetBucketLogging",
+        S3Operation::PutBucketLogging => "s3:PutBucketLogging",
+        S3Operation::GetPublicAccessBlock => "s3:GetBucketPublicAccessBlock",
+        S3Operation::PutPublicAccessBlock | S3Operation::DeletePublicAccessBlock => {
+            "s3:PutBucketPublicAccessBlock"
+        }
+        S3Operation::GetBucketOwnershipControls => "s3:GetBucketOwnershipControls",
+        S3Operation::PutBucketOwnershipControls | S3Operation::DeleteBucketOwnershipControls => {
+            "s3:PutBucketOwnershipControls"
+        }
+        S3Operation::GetObjectLockConfiguration => "s3:GetBucketObjectLockConfiguration",
+        S3Operation::PutObjectLockConfiguration => "s3:PutBucketObjectLockConfiguration",
+        S3Operation::GetBucketAccelerateConfiguration => "s3:GetAccelerateConfiguration",
+        S3Operation::PutBucketAccelerateConfiguration => "s3:PutAccelerateConfiguration",
+        S3Operation::GetBucketRequestPayment => "s3:GetBucketRequestPayment",
+        S3Operation::PutBucketRequestPayment => "s3:PutBucketRequestPayment",
+        S3Operation::GetBucketWebsite => "s3:GetBucketWebsite",
+        S3Operation::PutBucketWebsite => "s3:PutBucketWebsite",
+        S3Operation::DeleteBucketWebsite => "s3:DeleteBucketWebsite",
+        S3Operation::GetBucketAcl => "s3:GetBucketAcl",
+        S3Operation::PutBucketAcl => "s3:PutBucketAcl",
+        S3Operation::GetBucketPolicyStatus => "s3:GetBucketPolicyStatus",
+        S3Operation::PutObject
+        | S3Operation::CopyObject
+        | S3Operation::PostObject
+        | S3Operation::CreateMultipartUpload
+        | S3Operation::UploadPart
+        | S3Operation::UploadPartCopy
+        | S3Operation::CompleteMultipartUpload => "s3:PutObject",
+        S3Operation::GetObject | S3Operation::HeadObject => "s3:GetObject",
+        S3Operation::DeleteObject | S3Operation::DeleteObjects => "s3:DeleteObject",
+        S3Operation::GetObjectTagging => "s3:GetObjectTagging",
+        S3Operation::PutObjectTagging => "s3:PutObjectTagging",
+        S3Operation::DeleteObjectTagging => "s3:DeleteObjectTagging",
+        S3Operation::GetObjectAcl => "s3:GetObjectAcl",
+        S3Operation::PutObjectAcl => "s3:PutObjectAcl",
+        S3Operation::GetObjectRetention => "s3:GetObjectRetention",
+        S3Operation::PutObjectRetention => "s3:PutObjectRetention",
+        S3Operation::GetObjectLegalHold => "s3:GetObjectLegalHold",
+        S3Operation::PutObjectLegalHold => "s3:PutObjectLegalHold",
+        S3Operation::GetObjectAttributes => "s3:GetObjectAttributes",
+        S3Operation::AbortMultipartUpload => "s3:AbortMultipartUpload",
+        S3Operation::ListParts => "s3:ListMultipartUploadParts",
+        S3Operation::ListMultipartUploads => "s3:ListBucketMultipartUploads",
+        S3Operation::ListObjectVersions => "s3:ListBucketVersions",
+        S3Operation::CreateAccessPoint => "s3:CreateAccessPoint",
+        S3Operation::GetAccessPoint => "s3:GetAccessPoint",
+        S3Operation::DeleteAccessPoint => "s3:DeleteAccessPoint",
+        S3Operation::ListAccessPoints => "s3:ListAccessPoints",
+    }
+}
+
+/// The permission a canned ACL must grant to `AllUsers` for an operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AclPermission {
+    /// `READ` on the object itself.
+    ReadObject,
+    /// `READ` on the bucket (listing).
+    ReadBucket,
+    /// `WRITE` on the bucket (creating or deleting objects).
+    WriteBucket,
+}
+
+impl AclPermission {
+    fn required_for(operation: S3Operation) -> Option<Self> {
+        match operation {
+            S3Operation::GetObject | S3Operation::HeadObject | S3Operation::GetObjectAttributes => {
+                Some(Self::ReadObject)
+            }
+            S3Operation::HeadBucket
+            | S3Operation::ListObjects
+            | S3Operation::ListObjectsV2
+            | S3Operation::ListObjectVersions
+            | S3Operation::ListMultipartUploads => Some(Self::ReadBucket),
+            S3Operation::PutObject
+            | S3Operation::CopyObject
+            | S3Operation::DeleteObject
+            | S3Operation::DeleteObjects
+            | S3Operation::CreateMultipartUpload
+            | S3Operation::UploadPart
+            | S3Operation::UploadPartCopy
+            | S3Operation::CompleteMultipartUpload
+            | S3Operation::AbortMultipartUpload
+            | S3Operation::ListParts => Some(Self::WriteBucket),
+            _ => None,
+        }
+    }
+}
+
+impl RustackS3 {
+    /// Decide whether a request may proceed, after authentication.
+    ///
+    /// The bucket policy is evaluated first, and an explicit `Deny` rejects any
+    /// caller. Otherwise authenticated callers are treated as the account that
+    /// owns every bucket and are allowed. Anonymous callers additionally need
+    /// the policy or a public canned ACL to grant them the action. The bucket's
+    /// public access block is honoured: `IgnorePublicAcls` disregards public
+    /// ACLs and `RestrictPublicBuckets` disregards a public policy.
+    ///
+    /// Requests for buckets that do not exist are allowed through so the
+    /// operation itself can report `NoSuchBucket`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AccessDenied`, naming the denied action, if the request is not
+    /// authorized.
+    #[allow(clippy::result_large_err)]
+    pub fn authorize(&self, request: &AccessRequest<'_>) -> Result<(), S3Error> {
+        let Some(bucket_name) = request.bucket else {
+            return if request.principal.is_some() {
+                Ok(())
+            } else {
+                Err(access_denied(
+                    request,
+                    "because no identity-based policy allows this action",
+                ))
+            };
+        };
+        let Ok(bucket) = self.state.get_bucket(bucket_name) else {
+            return Ok(());
+        };
+
+        let (ignore_public_acls, restrict_public_buckets) = bucket
+            .public_access_block
+            .read()
+            .as_ref()
+            .map_or((false, false), |c| {
+                (c.ignore_public_acls, c.restrict_public_buckets)
+            });
+
+        let policy = bucket
+            .policy
+            .read()
+            .as_deref()
+            .and_then(|document| BucketPolicy::parse(document).ok());
+        let decision = policy
+            .as_ref()
+            .map_or(PolicyDecision::Implicit, |p| p.evaluate(request));
+        match decision {
+            PolicyDecision::Deny => {
+                debug!(
+                    action = request.action(),
+                    bucket = bucket_name,
+                    "denied by bucket policy"
+                );
+                return Err(access_denied(
+                    request,
+                    "with an explicit deny in a resource-based policy",
+                ));
+            }
+            _ if request.principal.is_some() => return Ok(()),
+            PolicyDecision::Allow
+                if !(restrict_public_buckets
+                    && policy.as_ref().is_some_and(BucketPolicy::is_public)) =>
+            {
+                return Ok(());
+            }
+            PolicyDecision::Allow | PolicyDecision::Implicit => {}
+        }
+
+        if !ignore_public_acls && acl_grants_anonymous(&bucket, request) {
+            return Ok(());
+        }
+
+        debug!(
+            action = request.action(),
+            bucket = bucket_name,
+            "anonymous request denied"
+        );
+        Err(access_denied(
+            request,
+            "because no resource-based policy allows this action",
+        ))
+    }
+
+    /// Decide whether `account` may perform `operation` on `key` in a bucket
+    /// of this handle's namespace, for writes another service makes on the
+    /// account's behalf (such as a DynamoDB table export).
+    ///
+    /// The account owning the namespace is always allowed. Any other account
+    /// needs the bucket policy to allow it, named by account ID or as
+    /// `arn:aws:iam::<account>:root`, without an explicit `Deny`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AccessDenied` if `account` is not authorized.
+    #[allow(clippy::result_large_err)]
+    pub fn authorize_account(
+        &self,
+        account: &str,
+        operation: S3Operation,
+        bucket: &str,
+        key: &str,
+    ) -> Result<(), S3Error> {
+        if account == self.scope().account.as_str() {
+            return Ok(());
+        }
+        let root = format!("arn:aws:iam::{account}:root");
+        let headers = http::HeaderMap::new();
+        let request = |principal| AccessRequest {
+            operation,
+            bucket: Some(bucket),
+            key: Some(key),
+            principal: Some(principal),
+            headers: &headers,
+            query_params: &[],
+            source_ip: None,
+        };
+        let policy = self.state.get_bucket(bucket).ok().and_then(|bucket| {
+            bucket
+                .policy
+                .read()
+                .as_deref()
+                .and_then(|document| BucketPolicy::parse(document).ok())
+        });
+        let decisions = [account, root.as_str()]
+            .map(|principal| policy.as_ref().map(|p| p.evaluate(&request(principal))));
+        if !decisions.contains(&Some(PolicyDecision::Deny))
+            && decisions.contains(&Some(PolicyDecision::Allow))
+        {
+            return Ok(());
+        }
+        Err(access_denied(
+            &request(&root),
+            "because no resource-based policy allows this action",
+        ))
+    }
+}
+
+/// Whether a bucket is public, as `GetBucketPolicyStatus` reports it.
+///
+/// A bucket is public if its policy [is public](BucketPolicy::is_public) or
+/// its ACL grants the `AllUsers` or `AuthenticatedUsers` group access. The
+/// bucket's public access block disregards the policy under
+/// `RestrictPublicBuckets` and the ACL under `IgnorePublicAcls`.
+pub(crate) fn bucket_is_public(bucket: &S3Bucket) -> bool {
+    let (ignore_public_acls, restrict_public_buckets) = bucket
+        .public_access_block
+        .read()
+        .as_ref()
+        .map_or((false, false), |c| {
+            (c.ignore_public_acls, c.restrict_public_buckets)
+        });
+
+    let public_policy = !restrict_public_buckets
+        && bucket
+            .policy
+            .read()
+            .as_deref()
+            .and_then(|document| BucketPolicy::parse(document).ok())
+            .is_some_and(|policy| policy.is_public());
+    let public_acl = !ignore_public_acls
+        && !bucket.acls_disabled()
+        && grants_public_access(&effective_grants(
+            *bucket.acl.read(),
+            bucket.grants.read().as_deref(),
+            &bucket.owner,
+            &bucket.owner,
+        ));
+    public_policy || public_acl
+}
+
+/// Build an `AccessDenied` error naming the caller, action and resource.
+fn access_denied(request: &AccessRequest<'_>, reason: &str) -> S3Error {
+    let resource = match (request.bucket, request.key) {
+        (Some(bucket), Some(key)) => format!("/{bucket}/{key}"),
+        (Some(bucket), None) => format!("/{bucket}"),
+        (None, _) => "/".to_owned(),
+    };
+    let message = format!(
+        "User: {} is not authorized to perform: {} on resource: \"{}\" {reason}",
+        request.principal.unwrap_or("anonymous"),
+        request.action(),
+        request.resource_arn(),
+    );
+    S3Error::with_message(S3ErrorCode::AccessDenied, message).with_resource(resource)
+}
+
+/// Whether the bucket or object ACL grants `AllUsers` the operation. ACLs
+/// grant nothing on a bucket that has them disabled.
+fn acl_grants_anonymous(bucket: &S3Bucket, request: &AccessRequest<'_>) -> bool {
+    if bucket.acls_disabled() {
+        return false;
+    }
+    let Some(permission) = AclPermission::required_for(request.operation) else {
+        return false;
+    };
+    let grants = if permission == AclPermission::ReadObject {
+        let Some(key) = request.key else {
+            return false;
+        };
+        match bucket.objects.read().get(key) {
+            Some(object) => effective_grants(
+                object.metadata.acl,
+                object.metadata.grants.as_deref(),
+                &object.owner,
+                &bucket.owner,
+            ),
+            None => return false,
+        }
+    } else {
+        effective_grants(
+            *bucket.acl.read(),
+            bucket.grants.read().as_deref(),
+            &bucket.owner,
+            &bucket.owner,
+        )
+    };
+    let needed = match permission {
+        AclPermission::ReadObject | AclPermission::ReadBucket => Permission::Read,
+        AclPermission::WriteBucket => Permission::Write,
+    };
+    grants_all_users(&grants, needed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::S3Config,
+        state::{
+            bucket::PublicAccessBlockConfig,
+            object::{CannedAcl, Owner},
+        },
+    };
+
+    const PUBLIC_READ_POLICY: &str = r#"{
+        "Version": "2012-10-17",
+        "Statement": {
+            "Effect": "Allow",
+            "Principal": "*",
+            "Action": "s3:GetObject",
+            "Resource": "arn:aws:s3:::site/*"
+        }
+    }"#;
+
+    fn request<'a>(
+        operation: S3Operation,
+        key: Option<&'a str>,
+        headers: &'a http::HeaderMap,
+    ) -> AccessRequest<'a> {
+        AccessRequest {
+            operation,
+            bucket: Some("site"),
+            key,
+            principal: None,
+            headers,
+            query_params: &[],
+            source_ip: None,
+        }
+    }
+
+    fn provider_with_bucket() -> RustackS3 {
+        let provider = RustackS3::new(S3Config::default());
+        provider
+            .state()
+            .create_bucket("site".to_owned(), "us-east-1".to_owned(), Owner::default())
+            .unwrap_or_else(|e| panic!("create failed: {e}"));
+        provider
+    }
+
+    fn bucket(provider: &RustackS3) -> dashmap::mapref::one::Ref<'_, String, S3Bucket> {
+        provider
+            .state()
+            .get_bucket("site")
+            .unwrap_or_else(|e| panic!("get failed: {e}"))
+    }
+
+    #[test]
+    fn test_should_map_operations_to_iam_actions() {
+        assert_eq!(iam_action(S3Operation::HeadObject), "s3:GetObject");
+        assert_eq!(iam_action(S3Operation::ListObjectsV2), "s3:ListBucket");
+        assert_eq!(iam_action(S3Operation::UploadPart), "s3:PutObject");
+        assert_eq!(
+            iam_action(S3Operation::DeleteBucketCors),
+            "s3:PutBucketCORS"
+        );
+    }
+
+    #[test]
+    fn test_should_reject_malformed_policy() {
+        let err = BucketPolicy::parse("not json").unwrap_err();
+        assert_eq!(err.code, S3ErrorCode::Custom("MalformedPolicy"));
+        assert_eq!(err.status_code, http::StatusCode::BAD_REQUEST);
+
+        assert!(BucketPolicy::parse(r#"{"Version": "2012-10-17"}"#).is_err());
+        assert!(BucketPolicy::parse(r#"{"Statement": [{"Effect": "Maybe"}]}"#).is_err());
+    }
+
+    #[test]
+    fn test_should_allow_anonymous_get_with_wildcard_principal() {
+        let policy = BucketPolicy::parse(PUBLIC_READ_POLICY).unwrap();
+        let headers = http::HeaderMap::new();
+
+        let get = request(S3Operation::GetObject, Some("index.html"), &headers);
+        assert_eq!(policy.evaluate(&get), PolicyDecision::Allow);
+
+        let put = request(S3Operation::PutObject, Some("index.html"), &headers);
+        assert_eq!(policy.evaluate(&put), PolicyDecision::Implicit);
+
+        let list = request(S3Operation::ListObjectsV2, None, &headers);
+        assert_eq!(policy.evaluate(&list), PolicyDecision::Implicit);
+        assert!(policy.is_public());
+    }
+
+    #[test]
+    fn test_should_let_deny_override_allow() {
+        let policy = BucketPolicy::parse(
+            r#"{"Statement": [
+                {"Effect": "Allow", "Principal": "*", "Action": "s3:*", "Resource": "*"},
+                {"Effect": "Deny", "Principal": {"AWS": "*"}, "Action": "s3:GetObject",
+                 "Resource": ["arn:aws:s3:::site/private/*"]}
+            ]}"#,
+        )
+        .unwrap();
+        let headers = http::HeaderMap::new();
+
+        let public = request(S3Operation::GetObject, Some("public/a"), &headers);
+        assert_eq!(policy.evaluate(&public), PolicyDecision::Allow);
+
+        let private = request(S3Operation::GetObject, Some("private/a"), &headers);
+        assert_eq!(policy.evaluate(&private), PolicyDecision::Deny);
+    }
+
+    #[test]
+    fn test_should_evaluate_referer_condition() {
+        let policy = BucketPolicy::parse(
+            r#"{"Statement": [{"Effect": "Allow", "Principal": "*", "Action": "s3:GetObject",
+                "Resource": "arn:aws:s3:::site/*",
+                "Condition": {"StringLike": {"aws:Referer": "https://example.com/*"}}}]}"#,
+        )
+        .unwrap();
+        let mut headers = http::HeaderMap::new();
+        let no_referer = request(S3Operation::GetObject, Some("a"), &headers);
+        assert_eq!(policy.evaluate(&no_referer), PolicyDecision::Implicit);
+
+        headers.insert(
+            http::header::REFERER,
+            http::HeaderValue::from_static("https://example.com/page"),
+        );
+        let referred = request(S3Operation::GetObject, Some("a"), &headers);
+        assert_eq!(policy.evaluate(&referred), PolicyDecision::Allow);
+        // Any caller can send the referer, so the policy is still public.
+        assert!(policy.is_public());
+    }
+
+    #[test]
+    fn test_should_evaluate_source_ip_condition() {
+        let policy = BucketPolicy::parse(
+            r#"{"Statement": [{"Effect": "Deny", "Principal": "*", "Action": "s3:*",
+                "Resource": ["arn:aws:s3:::site", "arn:aws:s3:::site/*"],
+                "Condition": {"NotIpAddress": {"aws:SourceIp": ["10.0.0.0/8", "::1"]}}}]}"#,
+        )
+        .unwrap();
+        let headers = http::HeaderMap::new();
+        let mut req = request(S3Operation::ListObjects, None, &headers);
+
+        req.source_ip = Some("10.1.2.3".parse().unwrap());
+        assert_eq!(policy.evaluate(&req), PolicyDecision::Implicit);
+        req.source_ip = Some("::1".parse().unwrap());
+        assert_eq!(policy.evaluate(&req), PolicyDecision::Implicit);
+        req.source_ip = Some("192.168.1.1".parse().unwrap());
+        assert_eq!(policy.evaluate(&req), PolicyDecision::Deny);
+    }
+
+    #[test]
+    fn test_should_match_wildcards_and_cidrs() {
+        assert!(wildcard_match(
+            "arn:aws:s3:::site/*",
+            "arn:aws:s3:::site/a/b"
+        ));
+        assert!(wildcard_match("s3:Get*", "s3:GetObject"));
+        assert!(wildcard_match("a?c*", "abcdef"));
+        assert!(!wildcard_match("arn:aws:s3:::site/*", "arn:aws:s3:::site"));
+
+        assert!(ip_in_cidr("192.0.2.0/24", "192.0.2.200"));
+        assert!(!ip_in_cidr("192.0.2.0/24", "192.0.3.1"));
+        assert!(ip_in_cidr("0.0.0.0/0", "8.8.8.8"));
+        assert!(ip_in_cidr("2001:db8::/32", "2001:db8::1"));
+        assert!(ip_in_cidr("127.0.0.1", "::ffff:127.0.0.1"));
+        assert!(!ip_in_cidr("10.0.0.0/abc", "10.0.0.1"));
+    }
+
+    #[tokio::test]
+    async fn test_should_authorize_anonymous_requests_by_object_acl() {
+        let provider = provider_with_bucket();
+        for (key, acl) in [("public", "public-read"), ("private", "private")] {
+            let input = rustack_s3_model::input::PutObjectInput {
+                bucket: "site".to_owned(),
+                key: key.to_owned(),
+                acl: Some(acl.into()),
+                ..Default::default()
+            };
+            provider
+                .handle_put_object(input)
+                .await
+                .unwrap_or_else(|e| panic!("put failed: {e}"));
+        }
+        let headers = http::HeaderMap::new();
+
+        let public = request(S3Operation::GetObject, Some("public"), &headers);
+        assert!(provider.authorize(&public).is_ok());
+
+        let private = request(S3Operation::GetObject, Some("private"), &headers);
+        let err = provider.authorize(&private).unwrap_err();
+        assert_eq!(err.code, S3ErrorCode::AccessDenied);
+
+        let upload = request(S3Operation::PutObject, Some("new"), &headers);
+        assert!(provider.authorize(&upload).is_err());
+
+        let mut signed = private;
+        signed.principal = Some("AKIDEXAMPLE");
+        assert!(provider.authorize(&signed).is_ok());
+
+        *bucket(&provider).public_access_block.write() = Some(PublicAccessBlockConfig {
+            block_public_acls: false,
+            ignore_public_acls: true,
+            block_public_policy: false,
+            restrict_public_buckets: false,
+        });
+        assert!(provider.authorize(&public).is_err());
+    }
+
+    #[test]
+    fn test_should_authorize_anonymous_requests_by_bucket_policy() {
+        let provider = provider_with_bucket();
+        let headers = http::HeaderMap::new();
+        let get = request(S3Operation::GetObject, Some("missing"), &headers);
+        assert!(provider.authorize(&get).is_err());
+
+        *bucket(&provider).policy.write() = Some(PUBLIC_READ_POLICY.to_owned());
+        assert!(provider.authorize(&get).is_ok());
+
+        let list = request(S3Operation::ListObjectsV2, None, &headers);
+        assert!(provider.authorize(&list).is_err());
+        *bucket(&provider).acl.write() = CannedAcl::PublicRead;
+        assert!(provider.authorize(&list).is_ok());
+
+        *bucket(&provider).public_access_block.write() = Some(PublicAccessBlockConfig {
+            block_public_acls: false,
+            ignore_public_acls: false,
+            block_public_policy: false,
+            restrict_public_buckets: true,
+        });
+        assert!(provider.authorize(&get).is_err());
+    }
+
+    #[test]
+    fn test_should_allow_anonymous_requests_for_missing_buckets_through() {
+        let provider = RustackS3::new(S3Config::default());
+        let headers = http::HeaderMap::new();
+        assert!(
+            provider
+                .authorize(&request(S3Operation::GetObject, Some("a"), &headers))
+                .is_ok()
+        );
+
+        let list_buckets = AccessRequest {
+            bucket: None,
+            ..request(S3Operation::ListBuckets, None, &headers)
+        };
+        assert!(provider.authorize(&list_buckets).is_err());
+    }
+
+    #[test]
+    fn test_should_evaluate_header_conditions_for_signed_requests() {
+        let provider = provider_with_bucket();
+        *bucket(&provider).policy.write() = Some(
+            r#"{"Statement": [{"Effect": "Deny", "Principal": "*", "Action": "s3:PutObject",
+                "Resource": "arn:aws:s3:::site/*",
+                "Condition": {"StringNotEquals": {"s3:x-amz-server-side-encryption": "aws:kms"}}}]}"#
+                .to_owned(),
+        );
+        let headers = http::HeaderMap::new();
+        let mut encrypted = http::HeaderMap::new();
+        encrypted.insert(
+            "x-amz-server-side-encryption",
+            http::HeaderValue::from_static("aws:kms"),
+        );
+        let mut put = request(S3Operation::PutObject, Some("doc"), &headers);
+        put.principal = Some("AKIDEXAMPLE");
+
+        let err = provider.authorize(&put).unwrap_err();
+        assert_eq!(err.code, S3ErrorCode::AccessDenied);
+        assert!(err.message.contains("s3:PutObject"));
+        assert!(err.message.contains("AKIDEXAMPLE"));
+
+        let get = AccessRequest {
+            operation: S3Operation::GetObject,
+            ..put
+        };
+        assert!(provider.authorize(&get).is_ok());
+
+        put.headers = &encrypted;
+        assert!(provider.authorize(&put).is_ok());
+    }
+
+    #[test]
+    fn test_should_evaluate_null_bool_and_if_exists_operators() {
+        let present = Some("true");
+        assert!(condition_matches("Null", &["true".to_owned()], None));
+        assert!(!condition_matches("Null", &["true".to_owned()], present));
+        assert!(condition_matches("Null", &["false".to_owned()], present));
+
+        assert!(condition_matches("Bool", &["True".to_owned()], present));
+        assert!(!condition_matches("Bool", &["false".to_owned()], present));
+        assert!(!condition_matches("Bool", &["true".to_owned()], None));
+
+        let expected = ["AES256".to_owned()];
+        assert!(condition_matches("StringEqualsIfExists", &expected, None));
+        assert!(!condition_matches(
+            "StringEqualsIfExists",
+            &expected,
+            Some("aws:kms")
+        ));
+        assert!(!condition_matches("NumericLessThan", &expected, Some("1")));
+    }
+
+    #[test]
+    fn test_should_resolve_prefix_and_principal_condition_keys() {
+        let policy = BucketPolicy::parse(
+            r#"{"Statement": [{"Effect": "Deny", "Principal": {"AWS": ["AKIDREADER"]},
+                "Action": "s3:ListBucket", "Resource": "arn:aws:s3:::site",
+                "Condition": {"StringNotLike": {"s3:prefix": "public/*"}}}]}"#,
+        )
+        .unwrap();
+        let headers = http::HeaderMap::new();
+        let query = [("prefix".to_owned(), "public/img".to_owned())];
+        let mut list = request(S3Operation::ListObjectsV2, None, &headers);
+        list.principal = Some("AKIDREADER");
+        assert_eq!(policy.evaluate(&list), PolicyDecision::Deny);
+
+        list.query_params = &query;
+        assert_eq!(policy.evaluate(&list), PolicyDecision::Implicit);
+        assert_eq!(
+            list.context_value("aws:userid").as_deref(),
+            Some("AKIDREADER")
+        );
+
+        list.query_params = &[];
+        list.principal = Some("AKIDOTHER");
+        assert_eq!(policy.evaluate(&list), PolicyDecision::Implicit);
+    }
+
+    #[test]
+    fn test_should_judge_policy_public_by_restricting_conditions() {
+        let policy = |principal: &str, condition: &str| {
+            BucketPolicy::parse(&format!(
+                r#"{{"Statement": [{{"Effect": "Allow", "Principal": {principal},
+                    "Action": "s3:GetObject", "Resource": "arn:aws:s3:::site/*",
+                    "Condition": {condition}}}]}}"#
+            ))
+            .unwrap()
+        };
+        for (principal, condition, public) in [
+            (r#""*""#, "{}", true),
+            (r#"{"AWS": ["*"]}"#, "{}", true),
+            (r#"{"AWS": "AKIDEXAMPLE"}"#, "{}", false),
+            (
+                r#""*""#,
+                r#"{"IpAddress": {"aws:SourceIp": "10.0.0.0/8"}}"#,
+                false,
+            ),
+            (
+                r#""*""#,
+                r#"{"IpAddress": {"aws:SourceIp": ["10.0.0.0/8", "0.0.0.0/0"]}}"#,
+                true,
+            ),
+            (
+                r#""*""#,
+                r#"{"StringEquals": {"aws:SourceVpce": "vpce-1a2b3c4d"}}"#,
+                false,
+            ),
+            (
+                r#""*""#,
+                r#"{"StringLike": {"aws:SourceVpce": "vpce-*"}}"#,
+                true,
+            ),
+            (
+                r#""*""#,
+                r#"{"StringNotEquals": {"aws:SourceAccount": "111111111111"}}"#,
+                true,
+            ),
+            (
+                r#""*""#,
+                r#"{"StringEqualsIfExists": {"aws:PrincipalOrgID": "o-abc"}}"#,
+                false,
+            ),
+        ] {
+            assert_eq!(
+                policy(principal, condition).is_public(),
+                public,
+                "{principal} with {condition}"
+            );
+        }
+
+        let deny_only = BucketPolicy::parse(
+            r#"{"Statement": {"Effect": "Deny", "Principal": "*", "Action": "s3:*",
+                "Resource": "*"}}"#,
+        )
+        .unwrap();
+        assert!(!deny_only.is_public());
+    }
+
+    async fn policy_status(provider: &RustackS3) -> bool {
+        provider
+            .handle_get_bucket_policy_status(rustack_s3_model::input::GetBucketPolicyStatusInput {
+                bucket: "site".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .unwrap_or_else(|e| panic!("get policy status failed: {e}"))
+            .policy_status
+            .and_then(|status| status.is_public)
+            .unwrap_or_else(|| panic!("no IsPublic"))
+    }
+
+    fn block(ignore_public_acls: bool, restrict_public_buckets: bool) -> PublicAccessBlockConfig {
+        PublicAccessBlockConfig {
+            block_public_acls: false,
+            ignore_public_acls,
+            block_public_policy: false,
+            restrict_public_buckets,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_should_report_bucket_public_through_policy() {
+        let provider = provider_with_bucket();
+        assert!(!policy_status(&provider).await);
+
+        *bucket(&provider).policy.write() = Some(PUBLIC_READ_POLICY.to_owned());
+        assert!(policy_status(&provider).await);
+
+        // Ignoring public ACLs leaves a public policy in effect.
+        *bucket(&provider).public_access_block.write() = Some(block(true, false));
+        assert!(policy_status(&provider).await);
+        *bucket(&provider).public_access_block.write() = Some(block(false, true));
+        assert!(!policy_status(&provider).await);
+    }
+
+    #[tokio::test]
+    async fn test_should_report_bucket_public_through_acl() {
+        let provider = provider_with_bucket();
+        for (acl, public) in [
+            (CannedAcl::PublicRead, true),
+            (CannedAcl::PublicReadWrite, true),
+            (CannedAcl::AuthenticatedRead, true),
+            (CannedAcl::LogDeliveryWrite, false),
+            (CannedAcl::Private, false),
+        ] {
+            *bucket(&provider).acl.write() = acl;
+            assert_eq!(policy_status(&provider).await, public, "{acl:?}");
+        }
+
+        *bucket(&provider).acl.write() = CannedAcl::PublicRead;
+        *bucket(&provider).public_access_block.write() = Some(block(false, true));
+        assert!(policy_status(&provider).await);
+        *bucket(&provider).public_access_block.write() = Some(block(true, false));
+        assert!(!policy_status(&provider).await);
+    }
+
+    #[tokio::test]
+    async fn test_should_reject_public_policy_when_blocking_public_policies() {
+        let provider = provider_with_bucket();
+        *bucket(&provider).public_access_block.write() = Some(PublicAccessBlockConfig {
+            block_public_policy: true,
+            ..block(false, false)
+        });
+        let put = |policy: &str| {
+            provider.handle_put_bucket_policy(rustack_s3_model::input::PutBucketPolicyInput {
+                bucket: "site".to_owned(),
+                policy: policy.to_owned(),
+                ..Default::default()
+            })
+        };
+
+        let err = put(PUBLIC_READ_POLICY).await.unwrap_err();
+        assert_eq!(err.code, S3ErrorCode::AccessDenied);
+        assert!(bucket(&provider).policy.read().is_none());
+
+        let restricted = r#"{"Statement": {"Effect": "Allow", "Principal": "*",
+            "Action": "s3:GetObject", "Resource": "arn:aws:s3:::site/*",
+            "Condition": {"IpAddress": {"aws:SourceIp": "192.0.2.0/24"}}}}"#;
+        put(restricted)
+            .await
+            .unwrap_or_else(|e| panic!("put restricted policy failed: {e}"));
+        assert!(!policy_status(&provider).await);
+    }
+}
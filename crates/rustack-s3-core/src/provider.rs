@@ -5,13 +5,26 @@
 //! Individual S3 operations are implemented in the [`crate::ops`] submodules
 //! as `handle_*` methods on `RustackS3`.
 //!
+//! Bucket state is partitioned by (account, region) namespace. A `RustackS3`
+//! handle operates in one namespace; [`RustackS3::scoped`] returns a cheap
+//! handle for another one, sharing the same underlying stores. Bucket names
+//! stay globally unique across namespaces, as in AWS.
+//!
 //! The server binary implements the `S3Handler` trait (from `rustack-s3-http`)
 //! for `RustackS3`, bridging the HTTP layer to these handler methods.
 
 use std::sync::Arc;
 
+use dashmap::DashMap;
+use rustack_core::{AccountId, AccountRegionStore, AwsRegion, RequestScope};
+use rustack_s3_model::error::{S3Error, S3ErrorCode};
+
 use crate::{
-    config::S3Config, cors::CorsIndex, state::service::S3ServiceState, storage::InMemoryStorage,
+    access_log::AccessLogBuffer,
+    config::S3Config,
+    cors::CorsIndex,
+    state::service::{BucketStats, S3ServiceState},
+    storage::InMemoryStorage,
 };
 
 /// The main S3 provider.
@@ -30,12 +43,20 @@ use crate::{
 /// ```
 #[derive(Debug, Clone)]
 pub struct RustackS3 {
-    /// Bucket and object metadata state.
+    /// Bucket and object metadata state of every (account, region) namespace.
+    pub(crate) states: Arc<AccountRegionStore<S3ServiceState>>,
+    /// The namespace that owns each bucket name.
+    pub(crate) bucket_scopes: Arc<DashMap<String, RequestScope>>,
+    /// The namespace this handle operates in.
+    pub(crate) scope: RequestScope,
+    /// Bucket and object metadata state of `scope`.
     pub(crate) state: Arc<S3ServiceState>,
     /// Object body storage (in-memory with disk spillover).
     pub(crate) storage: Arc<InMemoryStorage>,
     /// Per-bucket CORS rule index for request-time matching.
     pub(crate) cors_index: Arc<CorsIndex>,
+    /// Server access log records awaiting delivery to target buckets.
+    pub(crate) access_logs: Arc<AccessLogBuffer>,
     /// Provider configuration.
     pub(crate) config: Arc<S3Config>,
 }
@@ -44,24 +65,112 @@ impl RustackS3 {
     /// Create a new S3 provider with the given configuration.
     ///
     /// Initializes an empty service state, a storage backend configured with
-    /// the memory threshold from `config`, and an empty CORS index.
+    /// the spillover settings from `config`, an empty CORS index, and an empty
+    /// server access log buffer. The returned handle operates in the default
+    /// account and `config.default_region`.
     #[must_use]
     pub fn new(config: S3Config) -> Self {
-        let storage = InMemoryStorage::new(config.s3_max_memory_object_size);
+        let mut storage = InMemoryStorage::new(config.s3_max_memory_object_size);
+        if let Some(dir) = &config.s3_spillover_dir {
+            storage = storage.with_spillover_dir(dir);
+        }
+        if let Some(max_disk_bytes) = config.s3_max_disk_bytes {
+            storage = storage.with_max_disk_bytes(max_disk_bytes);
+        }
+        let states = Arc::new(AccountRegionStore::new());
+        let scope = RequestScope::new(
+            AccountId::default(),
+            AwsRegion::new(config.default_region.clone()),
+        );
         Self {
-            state: Arc::new(S3ServiceState::new()),
+            state: states.get_or_create(&scope.account, &scope.region),
+            states,
+            bucket_scopes: Arc::new(DashMap::new()),
+            scope,
             storage: Arc::new(storage),
             cors_index: Arc::new(CorsIndex::new()),
+            access_logs: Arc::new(AccessLogBuffer::new()),
             config: Arc::new(config),
         }
     }
 
-    /// Returns a reference to the service state.
+    /// Return a handle operating in `scope`, sharing all underlying state.
+    #[must_use]
+    pub fn scoped(&self, scope: &RequestScope) -> Self {
+        if *scope == self.scope {
+            return self.clone();
+        }
+        Self {
+            state: self.states.get_or_create(&scope.account, &scope.region),
+            scope: scope.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns the (account, region) namespace this handle operates in.
+    #[must_use]
+    pub fn scope(&self) -> &RequestScope {
+        &self.scope
+    }
+
+    /// Returns the namespace that owns a bucket, if the bucket exists.
+    #[must_use]
+    pub fn bucket_scope(&self, bucket: &str) -> Option<RequestScope> {
+        self.bucket_scopes.get(bucket).map(|scope| scope.clone())
+    }
+
+    /// Returns the region of a bucket in any namespace, if the bucket exists.
+    #[must_use]
+    pub fn bucket_region(&self, bucket: &str) -> Option<String> {
+        self.bucket_scopes
+            .get(bucket)
+            .map(|scope| scope.region.as_str().to_owned())
+    }
+
+    /// Check that a request from this handle's namespace may address
+    /// `bucket`.
+    ///
+    /// A bucket of the same account in another region answers
+    /// `PermanentRedirect` with its region in `x-amz-bucket-region`, which
+    /// SDKs use to discover the region. A bucket of another account answers
+    /// `AccessDenied`. A missing bucket passes, and is reported by the
+    /// operation itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PermanentRedirect` or `AccessDenied` as above.
+    #[allow(clippy::result_large_err)]
+    pub fn check_bucket_scope(&self, bucket: &str) -> Result<(), S3Error> {
+        let Some(owner) = self.bucket_scope(bucket) else {
+            return Ok(());
+        };
+        if owner.account != self.scope.account {
+            return Err(S3Error::new(S3ErrorCode::AccessDenied).with_resource(bucket));
+        }
+        if owner.region != self.scope.region {
+            return Err(S3Error::new(S3ErrorCode::PermanentRedirect)
+                .with_detail("Bucket", bucket)
+                .with_header("x-amz-bucket-region", owner.region.as_str()));
+        }
+        Ok(())
+    }
+
+    /// Returns a reference to the service state of this handle's namespace.
     #[must_use]
     pub fn state(&self) -> &S3ServiceState {
         &self.state
     }
 
+    /// Storage usage of every bucket, across all namespaces.
+    #[must_use]
+    pub fn all_bucket_stats(&self) -> Vec<BucketStats> {
+        self.states
+            .values()
+            .iter()
+            .flat_map(|state| state.bucket_stats())
+            .collect()
+    }
+
     /// Returns a reference to the storage backend.
     #[must_use]
     pub fn storage(&self) -> &InMemoryStorage {
@@ -74,18 +183,29 @@ impl RustackS3 {
         &self.cors_index
     }
 
+    /// Returns a reference to the server access log buffer.
+    #[must_use]
+    pub fn access_logs(&self) -> &AccessLogBuffer {
+        &self.access_logs
+    }
+
     /// Returns a reference to the provider configuration.
     #[must_use]
     pub fn config(&self) -> &S3Config {
         &self.config
     }
 
-    /// Reset all state (buckets, objects, multipart uploads, CORS rules).
+    /// Reset all state (buckets, objects, multipart uploads, CORS rules,
+    /// pending server access logs) in every namespace.
     ///
     /// Primarily useful for testing and the `/_localstack/health` reset endpoint.
     pub fn reset(&self) {
-        self.state.reset();
+        for state in self.states.values() {
+            state.reset();
+        }
+        self.bucket_scopes.clear();
         self.storage.reset();
+        self.access_logs.clear();
     }
 }
 
@@ -143,4 +263,205 @@ mod tests {
         provider.reset();
         assert!(!provider.state().bucket_exists("test"));
     }
+
+    fn create_bucket_input(name: &str) -> rustack_s3_model::input::CreateBucketInput {
+        rustack_s3_model::input::CreateBucketInput {
+            bucket: name.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    fn create_bucket_input_in(
+        name: &str,
+        region: &str,
+    ) -> rustack_s3_model::input::CreateBucketInput {
+        rustack_s3_model::input::CreateBucketInput {
+            create_bucket_configuration: Some(rustack_s3_model::types::CreateBucketConfiguration {
+                location_constraint: Some(region.into()),
+                ..Default::default()
+            }),
+            ..create_bucket_input(name)
+        }
+    }
+
+    async fn bucket_names(provider: &RustackS3) -> Vec<String> {
+        provider
+            .handle_list_buckets(rustack_s3_model::input::ListBucketsInput::default())
+            .await
+            .unwrap_or_else(|e| panic!("list failed: {e}"))
+            .buckets
+            .into_iter()
+            .filter_map(|b| b.name)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_should_isolate_buckets_by_account_and_region() {
+        let root = RustackS3::new(S3Config::default());
+        let alice = root.scoped(&RequestScope::resolve(
+            Some("111111111111"),
+            Some("us-east-1"),
+            "us-east-1",
+        ));
+        let bob = root.scoped(&RequestScope::resolve(
+            Some("222222222222"),
+            Some("eu-west-1"),
+            "us-east-1",
+        ));
+
+        alice
+            .handle_create_bucket(create_bucket_input("alice-bucket"))
+            .await
+            .unwrap_or_else(|e| panic!("create failed: {e}"));
+        bob.handle_create_bucket(create_bucket_input_in("bob-bucket", "eu-west-1"))
+            .await
+            .unwrap_or_else(|e| panic!("create failed: {e}"));
+
+        assert_eq!(bucket_names(&alice).await, vec!["alice-bucket"]);
+        assert_eq!(bucket_names(&bob).await, vec!["bob-bucket"]);
+        assert!(bucket_names(&root).await.is_empty());
+        assert!(!bob.state().bucket_exists("alice-bucket"));
+        assert_eq!(
+            bob.state()
+                .get_bucket("bob-bucket")
+                .map(|b| b.region.clone())
+                .ok()
+                .as_deref(),
+            Some("eu-west-1")
+        );
+        assert_eq!(root.all_bucket_stats().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_should_keep_bucket_names_globally_unique() {
+        let root = RustackS3::new(S3Config::default());
+        let alice = root.scoped(&RequestScope::resolve(
+            Some("111111111111"),
+            None,
+            "us-east-1",
+        ));
+        let alice_west = root.scoped(&RequestScope::resolve(
+            Some("111111111111"),
+            Some("us-west-2"),
+            "us-east-1",
+        ));
+        let bob = root.scoped(&RequestScope::resolve(
+            Some("222222222222"),
+            None,
+            "us-east-1",
+        ));
+
+        alice
+            .handle_create_bucket(create_bucket_input("shared"))
+            .await
+            .unwrap_or_else(|e| panic!("create failed: {e}"));
+
+        let err = bob
+            .handle_create_bucket(create_bucket_input("shared"))
+            .await
+            .err()
+            .unwrap_or_else(|| panic!("expected conflict"));
+        assert_eq!(
+            err.code,
+            rustack_s3_model::error::S3ErrorCode::BucketAlreadyExists
+        );
+        let err = alice_west
+            .handle_create_bucket(create_bucket_input_in("shared", "us-west-2"))
+            .await
+            .err()
+            .unwrap_or_else(|| panic!("expected conflict"));
+        assert_eq!(
+            err.code,
+            rustack_s3_model::error::S3ErrorCode::BucketAlreadyOwnedByYou
+        );
+
+        alice
+            .handle_delete_bucket(rustack_s3_model::input::DeleteBucketInput {
+                bucket: "shared".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .unwrap_or_else(|e| panic!("delete failed: {e}"));
+        bob.handle_create_bucket(create_bucket_input("shared"))
+            .await
+            .unwrap_or_else(|e| panic!("create failed: {e}"));
+        assert_eq!(
+            root.bucket_scope("shared")
+                .map(|s| s.account.as_str().to_owned())
+                .as_deref(),
+            Some("222222222222")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_should_redirect_requests_to_buckets_in_other_namespaces() {
+        let root = RustackS3::new(S3Config::default());
+        let scope = |account, region| {
+            root.scoped(&RequestScope::resolve(
+                Some(account),
+                Some(region),
+                "us-east-1",
+            ))
+        };
+        let alice = scope("111111111111", "us-east-1");
+        let alice_west = scope("111111111111", "us-west-2");
+        let bob = scope("222222222222", "us-east-1");
+        alice
+            .handle_create_bucket(create_bucket_input("east"))
+            .await
+            .unwrap_or_else(|e| panic!("create failed: {e}"));
+
+        assert!(alice.check_bucket_scope("east").is_ok());
+        assert!(alice_west.check_bucket_scope("missing").is_ok());
+        assert_eq!(root.bucket_region("east").as_deref(), Some("us-east-1"));
+        assert_eq!(root.bucket_region("missing"), None);
+
+        let err = alice_west
+            .check_bucket_scope("east")
+            .err()
+            .unwrap_or_else(|| panic!("expected redirect"));
+        assert_eq!(err.code, S3ErrorCode::PermanentRedirect);
+        assert_eq!(err.status_code, http::StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(
+            err.headers.as_deref().map(Vec::as_slice),
+            Some(&[("x-amz-bucket-region".to_owned(), "us-east-1".to_owned())][..])
+        );
+
+        let err = bob
+            .check_bucket_scope("east")
+            .err()
+            .unwrap_or_else(|| panic!("expected access denied"));
+        assert_eq!(err.code, S3ErrorCode::AccessDenied);
+    }
+
+    #[tokio::test]
+    async fn test_should_relax_bucket_name_and_metadata_rules_when_configured() {
+        let strict = RustackS3::new(S3Config::default());
+        let err = strict
+            .handle_create_bucket(create_bucket_input("MyBucket_2024"))
+            .await
+            .expect_err("strict validation rejects legacy names");
+        assert_eq!(
+            err.code,
+            rustack_s3_model::error::S3ErrorCode::InvalidBucketName
+        );
+
+        let relaxed = RustackS3::new(S3Config {
+            s3_strict_validation: false,
+            ..S3Config::default()
+        });
+        relaxed
+            .handle_create_bucket(create_bucket_input("MyBucket_2024"))
+            .await
+            .unwrap_or_else(|e| panic!("create failed: {e}"));
+        relaxed
+            .handle_put_object(rustack_s3_model::input::PutObjectInput {
+                bucket: "MyBucket_2024".to_owned(),
+                key: "k".to_owned(),
+                metadata: [("big".to_owned(), "v".repeat(4096))].into(),
+                ..Default::default()
+            })
+            .await
+            .unwrap_or_else(|e| panic!("put failed: {e}"));
+    }
 }
@@ -0,0 +1,275 @@
+//! Integrity scrubbing of stored object data.
+//!
+//! [`RustackS3::scrub_bucket`] re-hashes the stored bytes of every object
+//! version in a bucket with [`InMemoryStorage::verify_object`] and reports
+//! the ones that no longer match their recorded digests. Each mismatched
+//! version is flagged in its metadata, after which `GetObject` fails with
+//! `InternalError` instead of serving the data, unless
+//! [`S3Config::s3_serve_corrupt_objects`] is set.
+//!
+//! [`InMemoryStorage::verify_object`]: crate::storage::InMemoryStorage::verify_object
+//! [`S3Config::s3_serve_corrupt_objects`]: crate::config::S3Config::s3_serve_corrupt_objects
+
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::{
+    checksums::ChecksumAlgorithm, error::S3ServiceError, provider::RustackS3, state::ObjectRecord,
+    storage::VerifyResult,
+};
+
+/// The result of scrubbing one bucket.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrubReport {
+    /// The scrubbed bucket.
+    pub bucket: String,
+    /// Number of object versions verified.
+    pub objects_checked: u64,
+    /// Total bytes of the verified versions.
+    pub bytes_checked: u64,
+    /// The versions whose data failed verification, in key order.
+    pub mismatches: Vec<ScrubMismatch>,
+}
+
+/// An object version whose stored data failed verification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrubMismatch {
+    /// The object key.
+    pub key: String,
+    /// The version ID (`"null"` for un-versioned objects).
+    pub version_id: String,
+    /// Why verification failed.
+    pub reason: String,
+}
+
+impl RustackS3 {
+    /// Verify the stored data of every object version in `bucket` and flag
+    /// the versions that fail.
+    ///
+    /// Data is checked against the MD5 digest recorded at write time and,
+    /// for objects with a full-object checksum, against that checksum.
+    /// Versions deleted or overwritten while the scrub runs are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`S3ServiceError::NoSuchBucket`] if the bucket does not exist.
+    pub async fn scrub_bucket(&self, bucket: &str) -> Result<ScrubReport, S3ServiceError> {
+        let owner = self
+            .bucket_scope(bucket)
+            .map_or_else(|| self.clone(), |scope| self.scoped(&scope));
+        let mut report = ScrubReport {
+            bucket: bucket.to_owned(),
+            ..ScrubReport::default()
+        };
+
+        for record in owner.state.export_manifest(bucket, true)? {
+            if record.is_delete_marker {
+                continue;
+            }
+            let checksum = owner.full_object_checksum(bucket, &record.key, &record.version_id);
+            let result = match self
+                .storage
+                .verify_object(
+                    bucket,
+                    &record.key,
+                    &record.version_id,
+                    checksum.as_ref().map(|(alg, value)| (*alg, value.as_str())),
+                )
+                .await
+            {
+                Ok(result) => result,
+                Err(S3ServiceError::NoSuchKey { .. }) => continue,
+                Err(e) => return Err(e),
+            };
+            report.objects_checked += 1;
+            report.bytes_checked += record.size;
+            if result.is_intact() {
+                continue;
+            }
+
+            if !owner.flag_corrupt(bucket, &record, &result) {
+                continue;
+            }
+            warn!(
+                bucket,
+                key = %record.key,
+                version_id = %record.version_id,
+                %result,
+                "object data failed integrity check"
+            );
+            report.mismatches.push(ScrubMismatch {
+                key: record.key,
+                version_id: record.version_id,
+                reason: result.to_string(),
+            });
+        }
+
+        info!(
+            bucket,
+            objects_checked = report.objects_checked,
+            mismatches = report.mismatches.len(),
+            "scrubbed bucket"
+        );
+        Ok(report)
+    }
+
+    /// The full-object checksum recorded for a version, if any.
+    fn full_object_checksum(
+        &self,
+        bucket: &str,
+        key: &str,
+        version_id: &str,
+    ) -> Option<(ChecksumAlgorithm, String)> {
+        let bucket = self.state.get_bucket(bucket).ok()?;
+        let store = bucket.objects.read();
+        let checksum = store.get_version(key, version_id)?.checksum.as_ref()?;
+        if checksum.checksum_type != "FULL_OBJECT" {
+            return None;
+        }
+        let algorithm = checksum.algorithm.parse().ok()?;
+        Some((algorithm, checksum.value.clone()))
+    }
+
+    /// Record `result` as the integrity failure of the scrubbed version.
+    ///
+    /// Returns `false` if the version was deleted or replaced since it was
+    /// listed, in which case the verified data is no longer its data.
+    fn flag_corrupt(&self, bucket: &str, record: &ObjectRecord, result: &VerifyResult) -> bool {
+        let Ok(bucket) = self.state.get_bucket(bucket) else {
+            return false;
+        };
+        let mut store = bucket.objects.write();
+        match store.get_version_mut(&record.key, &record.version_id) {
+            Some(obj) if obj.etag == record.etag && obj.last_modified == record.last_modified => {
+                obj.metadata.integrity_failure = Some(result.to_string());
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use rustack_s3_model::{
+        error::S3ErrorCode,
+        input::{CreateBucketInput, GetObjectInput, PutObjectInput},
+        request::StreamingBlob,
+    };
+
+    use super::*;
+    use crate::config::S3Config;
+
+    async fn provider_with_objects(dir: &std::path::Path, serve_corrupt: bool) -> RustackS3 {
+        let provider = RustackS3::new(S3Config {
+            s3_max_memory_object_size: 16,
+            s3_spillover_dir: Some(dir.display().to_string()),
+            s3_serve_corrupt_objects: serve_corrupt,
+            ..S3Config::default()
+        });
+        provider
+            .handle_create_bucket(CreateBucketInput {
+                bucket: "scrubbed".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .unwrap_or_else(|e| panic!("create failed: {e}"));
+        for (key, body) in [("big", "spilled to disk, then damaged"), ("small", "tiny")] {
+            provider
+                .handle_put_object(PutObjectInput {
+                    bucket: "scrubbed".to_owned(),
+                    key: key.to_owned(),
+                    body: Some(StreamingBlob::new(Bytes::from(body))),
+                    ..Default::default()
+                })
+                .await
+                .unwrap_or_else(|e| panic!("put failed: {e}"));
+        }
+        provider
+    }
+
+    async fn corrupt_spilled_files(dir: &std::path::Path) {
+        let mut entries = tokio::fs::read_dir(dir)
+            .await
+            .unwrap_or_else(|e| panic!("read_dir failed: {e}"));
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .unwrap_or_else(|e| panic!("dir entry failed: {e}"))
+        {
+            let path = entry.path();
+            let mut data = tokio::fs::read(&path)
+                .await
+                .unwrap_or_else(|e| panic!("read failed: {e}"));
+            data[0] ^= 0xff;
+            tokio::fs::write(&path, data)
+                .await
+                .unwrap_or_else(|e| panic!("write failed: {e}"));
+        }
+    }
+
+    fn get(key: &str) -> GetObjectInput {
+        GetObjectInput {
+            bucket: "scrubbed".to_owned(),
+            key: key.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_should_report_and_flag_corrupt_objects() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("tempdir failed: {e}"));
+        let provider = provider_with_objects(dir.path(), false).await;
+
+        let clean = provider
+            .scrub_bucket("scrubbed")
+            .await
+            .unwrap_or_else(|e| panic!("scrub failed: {e}"));
+        assert_eq!(clean.objects_checked, 2);
+        assert!(clean.mismatches.is_empty());
+
+        corrupt_spilled_files(dir.path()).await;
+        let report = provider
+            .scrub_bucket("scrubbed")
+            .await
+            .unwrap_or_else(|e| panic!("scrub failed: {e}"));
+        assert_eq!(report.mismatches.len(), 1);
+        assert_eq!(report.mismatches[0].key, "big");
+        assert!(report.mismatches[0].reason.starts_with("MD5 mismatch"));
+
+        let err = provider
+            .handle_get_object(get("big"))
+            .await
+            .err()
+            .unwrap_or_else(|| panic!("expected corrupt object to fail"));
+        assert_eq!(err.code, S3ErrorCode::InternalError);
+        provider
+            .handle_get_object(get("small"))
+            .await
+            .unwrap_or_else(|e| panic!("get failed: {e}"));
+
+        assert!(matches!(
+            provider.scrub_bucket("missing").await,
+            Err(S3ServiceError::NoSuchBucket { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_should_serve_corrupt_objects_when_configured() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("tempdir failed: {e}"));
+        let provider = provider_with_objects(dir.path(), true).await;
+        corrupt_spilled_files(dir.path()).await;
+        provider
+            .scrub_bucket("scrubbed")
+            .await
+            .unwrap_or_else(|e| panic!("scrub failed: {e}"));
+
+        provider
+            .handle_get_object(get("big"))
+            .await
+            .unwrap_or_else(|e| panic!("get failed: {e}"));
+    }
+}
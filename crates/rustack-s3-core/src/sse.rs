@@ -0,0 +1,288 @@
+//! Server-side encryption settings of objects.
+//!
+//! Covers a bucket's default encryption, stamped onto objects written
+//! without an explicit encryption header, and customer-provided keys
+//! (SSE-C). S3 never keeps an SSE-C key: it stores the key's MD5 with the
+//! object and requires every read of the object to present the same key.
+//! Rustack enforces these protocols; the stored bytes are not encrypted.
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64_STANDARD};
+use md5::{Digest, Md5};
+use rustack_s3_model::types::ServerSideEncryption;
+
+use crate::{
+    error::S3ServiceError,
+    state::{bucket::BucketEncryption, object::ObjectMetadata},
+};
+
+/// The only SSE-C algorithm S3 supports.
+pub const SSE_C_ALGORITHM: &str = "AES256";
+
+/// Length in bytes of an SSE-C key (AES-256).
+const SSE_C_KEY_LEN: usize = 32;
+
+/// A customer-provided key presented with a request, checked against its
+/// MD5.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomerKey {
+    /// The encryption algorithm (always [`SSE_C_ALGORITHM`]).
+    pub algorithm: String,
+    /// Base64 MD5 of the key, as stored with the object.
+    pub key_md5: String,
+}
+
+impl CustomerKey {
+    /// Parse the three SSE-C headers of a request.
+    ///
+    /// Returns `Ok(None)` when none of them is present.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`S3ServiceError::InvalidArgument`] if only some of the
+    /// headers are present, the algorithm is not `AES256`, the key is not a
+    /// Base64 256-bit key, or the key does not match its MD5.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustack_s3_core::sse::CustomerKey;
+    ///
+    /// let key = "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=";
+    /// let md5 = "hRasmdxgYDKV3nvbahU1MA==";
+    /// let parsed = CustomerKey::from_headers(Some("AES256"), Some(key), Some(md5)).unwrap();
+    /// assert_eq!(parsed.unwrap().key_md5, md5);
+    /// assert!(CustomerKey::from_headers(None, None, None).unwrap().is_none());
+    /// assert!(CustomerKey::from_headers(Some("AES256"), Some(key), None).is_err());
+    /// ```
+    pub fn from_headers(
+        algorithm: Option<&str>,
+        key: Option<&str>,
+        key_md5: Option<&str>,
+    ) -> Result<Option<Self>, S3ServiceError> {
+        let (algorithm, key, key_md5) = match (algorithm, key, key_md5) {
+            (None, None, None) => return Ok(None),
+            (Some(algorithm), Some(key), Some(key_md5)) => (algorithm, key, key_md5),
+            (None, ..) => {
+                return Err(invalid(
+                    "Requests specifying Server Side Encryption with Customer provided keys must \
+                     provide a valid encryption algorithm.",
+                ));
+            }
+            (_, None, _) => {
+                return Err(invalid(
+                    "Requests specifying Server Side Encryption with Customer provided keys must \
+                     provide an appropriate secret key.",
+                ));
+            }
+            (_, _, None) => {
+                return Err(invalid(
+                    "Requests specifying Server Side Encryption with Customer provided keys must \
+                     provide the client calculated MD5 of the secret key.",
+                ));
+            }
+        };
+
+        if algorithm != SSE_C_ALGORITHM {
+            return Err(invalid(
+                "The encryption algorithm specified is not valid. The valid value is AES256.",
+            ));
+        }
+        let key_bytes = BASE64_STANDARD
+            .decode(key)
+            .ok()
+            .filter(|bytes| bytes.len() == SSE_C_KEY_LEN)
+            .ok_or_else(|| invalid("The secret key was invalid for the specified algorithm."))?;
+        let computed = BASE64_STANDARD.encode(Md5::digest(&key_bytes));
+        if computed != key_md5 {
+            return Err(invalid(
+                "The calculated MD5 hash of the key did not match the hash that was provided.",
+            ));
+        }
+
+        Ok(Some(Self {
+            algorithm: algorithm.to_owned(),
+            key_md5: computed,
+        }))
+    }
+}
+
+/// Check the key presented to read an object against the key it was
+/// stored with.
+///
+/// # Errors
+///
+/// - `InvalidRequest` if the object is SSE-C encrypted and no key was presented, or a key was
+///   presented for an object that is not SSE-C encrypted.
+/// - `AccessDenied` if the presented key is not the object's key.
+pub fn check_customer_key(
+    metadata: &ObjectMetadata,
+    presented: Option<&CustomerKey>,
+) -> Result<(), S3ServiceError> {
+    match (metadata.sse_customer_key_md5.as_deref(), presented) {
+        (None, None) => Ok(()),
+        (Some(_), None) => Err(S3ServiceError::InvalidRequest {
+            message: "The object was stored using a form of Server Side Encryption. The correct \
+                      parameters must be provided to retrieve the object."
+                .to_owned(),
+        }),
+        (None, Some(_)) => Err(S3ServiceError::InvalidRequest {
+            message: "The encryption parameters are not applicable to this object.".to_owned(),
+        }),
+        (Some(stored), Some(key)) if stored == key.key_md5 => Ok(()),
+        (Some(_), Some(_)) => Err(S3ServiceError::AccessDenied),
+    }
+}
+
+/// Apply a bucket's default encryption to an object's metadata.
+///
+/// An explicit `x-amz-server-side-encryption` header or an SSE-C key on the
+/// request overrides the default, so the metadata is left as is.
+pub fn apply_default_encryption(metadata: &mut ObjectMetadata, default: Option<&BucketEncryption>) {
+    let Some(default) = default else {
+        return;
+    };
+    if metadata.sse_algorithm.is_some() || metadata.sse_customer_algorithm.is_some() {
+        return;
+    }
+    let is_kms = default.sse_algorithm.starts_with("aws:kms");
+    metadata.sse_algorithm = Some(default.sse_algorithm.clone());
+    if is_kms {
+        metadata
+            .sse_kms_key_id
+            .clone_from(&default.kms_master_key_id);
+        metadata.sse_bucket_key_enabled = default.bucket_key_enabled.then_some(true);
+    }
+}
+
+/// The server-side encryption response fields for an object.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncryptionFields {
+    /// `x-amz-server-side-encryption`.
+    pub server_side_encryption: Option<ServerSideEncryption>,
+    /// `x-amz-server-side-encryption-aws-kms-key-id`.
+    pub ssekms_key_id: Option<String>,
+    /// `x-amz-server-side-encryption-bucket-key-enabled`, only reported when
+    /// enabled.
+    pub bucket_key_enabled: Option<bool>,
+}
+
+impl EncryptionFields {
+    /// Collect the fields reported for an object with `metadata`.
+    #[must_use]
+    pub fn from_metadata(metadata: &ObjectMetadata) -> Self {
+        Self {
+            server_side_encryption: metadata
+                .sse_algorithm
+                .as_deref()
+                .map(ServerSideEncryption::from),
+            ssekms_key_id: metadata.sse_kms_key_id.clone(),
+            bucket_key_enabled: metadata.sse_bucket_key_enabled.filter(|&enabled| enabled),
+        }
+    }
+}
+
+fn invalid(message: &str) -> S3ServiceError {
+    S3ServiceError::InvalidArgument {
+        message: message.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=";
+    const KEY_MD5: &str = "hRasmdxgYDKV3nvbahU1MA==";
+
+    #[test]
+    fn test_should_validate_customer_key_headers() {
+        let key = CustomerKey::from_headers(Some("AES256"), Some(KEY), Some(KEY_MD5))
+            .expect("valid key")
+            .expect("key present");
+        assert_eq!(key.algorithm, "AES256");
+
+        for (algorithm, key, md5) in [
+            (Some("aws:kms"), Some(KEY), Some(KEY_MD5)),
+            (Some("AES256"), Some("c2hvcnQ="), Some(KEY_MD5)),
+            (Some("AES256"), Some(KEY), Some("AAAAAAAAAAAAAAAAAAAAAA==")),
+            (None, Some(KEY), Some(KEY_MD5)),
+        ] {
+            assert!(matches!(
+                CustomerKey::from_headers(algorithm, key, md5),
+                Err(S3ServiceError::InvalidArgument { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_should_require_matching_key_for_encrypted_objects() {
+        let key = CustomerKey {
+            algorithm: SSE_C_ALGORITHM.to_owned(),
+            key_md5: KEY_MD5.to_owned(),
+        };
+        let other = CustomerKey {
+            key_md5: "AAAAAAAAAAAAAAAAAAAAAA==".to_owned(),
+            ..key.clone()
+        };
+        let encrypted = ObjectMetadata {
+            sse_customer_algorithm: Some(SSE_C_ALGORITHM.to_owned()),
+            sse_customer_key_md5: Some(KEY_MD5.to_owned()),
+            ..ObjectMetadata::default()
+        };
+        let plain = ObjectMetadata::default();
+
+        assert!(check_customer_key(&encrypted, Some(&key)).is_ok());
+        assert!(check_customer_key(&plain, None).is_ok());
+        assert!(matches!(
+            check_customer_key(&encrypted, None),
+            Err(S3ServiceError::InvalidRequest { .. })
+        ));
+        assert!(matches!(
+            check_customer_key(&encrypted, Some(&other)),
+            Err(S3ServiceError::AccessDenied)
+        ));
+        assert!(matches!(
+            check_customer_key(&plain, Some(&key)),
+            Err(S3ServiceError::InvalidRequest { .. })
+        ));
+    }
+
+    #[test]
+    fn test_should_apply_default_encryption_unless_overridden() {
+        let kms = BucketEncryption {
+            sse_algorithm: "aws:kms".to_owned(),
+            kms_master_key_id: Some("alias/app".to_owned()),
+            bucket_key_enabled: true,
+        };
+
+        let mut defaulted = ObjectMetadata::default();
+        apply_default_encryption(&mut defaulted, Some(&kms));
+        let fields = EncryptionFields::from_metadata(&defaulted);
+        assert_eq!(
+            fields.server_side_encryption,
+            Some(ServerSideEncryption::AwsKms)
+        );
+        assert_eq!(fields.ssekms_key_id.as_deref(), Some("alias/app"));
+        assert_eq!(fields.bucket_key_enabled, Some(true));
+
+        let mut explicit = ObjectMetadata {
+            sse_algorithm: Some("AES256".to_owned()),
+            ..ObjectMetadata::default()
+        };
+        apply_default_encryption(&mut explicit, Some(&kms));
+        let fields = EncryptionFields::from_metadata(&explicit);
+        assert_eq!(
+            fields.server_side_encryption,
+            Some(ServerSideEncryption::Aes256)
+        );
+        assert_eq!(fields.ssekms_key_id, None);
+        assert_eq!(fields.bucket_key_enabled, None);
+
+        let mut unconfigured = ObjectMetadata::default();
+        apply_default_encryption(&mut unconfigured, None);
+        assert_eq!(
+            EncryptionFields::from_metadata(&unconfigured),
+            EncryptionFields::default()
+        );
+    }
+}
@@ -10,16 +10,17 @@
 //! single-valued configuration fields and for the object store, and
 //! `DashMap` for the multipart upload table.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Months, TimeDelta, Utc};
 use dashmap::DashMap;
 use parking_lot::RwLock;
+use rustack_s3_model::types::{ExpirationStatus, LifecycleRule};
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
 use super::{
     keystore::ObjectStore,
     multipart::MultipartUpload,
-    object::{CannedAcl, Owner},
+    object::{CannedAcl, Grant, Owner, TagSet},
 };
 
 // ---------------------------------------------------------------------------
@@ -98,6 +99,10 @@ pub struct PublicAccessBlockConfig {
     pub restrict_public_buckets: bool,
 }
 
+/// The object ownership under which the bucket owner owns every object and
+/// ACLs are disabled.
+pub const BUCKET_OWNER_ENFORCED: &str = "BucketOwnerEnforced";
+
 /// Bucket ownership controls configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -141,6 +146,23 @@ pub struct DefaultRetention {
     pub years: Option<i32>,
 }
 
+impl DefaultRetention {
+    /// The retain-until date of an object version created at `created`.
+    ///
+    /// Returns `None` if neither a positive `days` nor `years` is set, or
+    /// the date is out of range.
+    #[must_use]
+    pub fn retain_until(&self, created: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match (self.days, self.years) {
+            (Some(days), _) if days > 0 => created.checked_add_signed(TimeDelta::days(days.into())),
+            (_, Some(years)) if years > 0 => {
+                created.checked_add_months(Months::new(u32::try_from(years).ok()? * 12))
+            }
+            _ => None,
+        }
+    }
+}
+
 /// Static website hosting configuration for a bucket.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -200,11 +222,13 @@ pub struct S3Bucket {
     pub tags: RwLock<Vec<(String, String)>>,
     /// Canned ACL for the bucket.
     pub acl: RwLock<CannedAcl>,
+    /// Explicit ACL grants, which replace the canned ACL when set.
+    pub grants: RwLock<Option<Vec<Grant>>>,
     /// Notification configuration for the bucket.
     pub notification_configuration:
         RwLock<Option<rustack_s3_model::types::NotificationConfiguration>>,
-    /// Logging configuration (stored as opaque JSON).
-    pub logging: RwLock<Option<serde_json::Value>>,
+    /// Server access logging target, if enabled.
+    pub logging: RwLock<Option<rustack_s3_model::types::LoggingEnabled>>,
     /// Public access block settings.
     pub public_access_block: RwLock<Option<PublicAccessBlockConfig>>,
     /// Ownership controls.
@@ -263,6 +287,7 @@ impl S3Bucket {
             policy: RwLock::new(None),
             tags: RwLock::new(Vec::new()),
             acl: RwLock::new(CannedAcl::default()),
+            grants: RwLock::new(None),
             notification_configuration: RwLock::new(None),
             logging: RwLock::new(None),
             public_access_block: RwLock::new(None),
@@ -292,6 +317,39 @@ impl S3Bucket {
         *self.versioning.read() == VersioningStatus::Enabled
     }
 
+    /// Whether ACLs are disabled, i.e. the bucket's object ownership is
+    /// `BucketOwnerEnforced`.
+    #[must_use]
+    pub fn acls_disabled(&self) -> bool {
+        self.ownership_controls
+            .read()
+            .as_ref()
+            .is_some_and(|c| c.object_ownership == BUCKET_OWNER_ENFORCED)
+    }
+
+    /// The default retention of the bucket's Object Lock configuration,
+    /// applied to new object versions that set no retention of their own.
+    #[must_use]
+    pub fn default_retention(&self) -> Option<DefaultRetention> {
+        if !*self.object_lock_enabled.read() {
+            return None;
+        }
+        self.object_lock_configuration
+            .read()
+            .as_ref()?
+            .rule
+            .as_ref()?
+            .default_retention
+            .clone()
+    }
+
+    /// Whether versioning is suspended on this bucket, so that new writes
+    /// and delete markers take the `"null"` version ID.
+    #[must_use]
+    pub fn is_versioning_suspended(&self) -> bool {
+        *self.versioning.read() == VersioningStatus::Suspended
+    }
+
     /// Enable versioning on this bucket.
     ///
     /// If the bucket is currently un-versioned, the object store is
@@ -320,6 +378,110 @@ impl S3Bucket {
             *status = VersioningStatus::Suspended;
         }
     }
+
+    /// Remove the in-progress multipart uploads that the bucket's lifecycle
+    /// configuration aborts as of `now`, returning their upload IDs.
+    ///
+    /// An enabled rule with `AbortIncompleteMultipartUpload` aborts uploads
+    /// whose key matches the rule's prefix once `DaysAfterInitiation` days
+    /// have passed since they were initiated.
+    pub fn remove_expired_uploads(&self, now: DateTime<Utc>) -> Vec<String> {
+        let lifecycle = self.lifecycle.read();
+        let rules: Vec<(&str, TimeDelta)> = lifecycle
+            .iter()
+            .flat_map(|config| &config.rules)
+            .filter_map(abort_incomplete_upload_rule)
+            .collect();
+        if rules.is_empty() {
+            return Vec::new();
+        }
+
+        let mut expired = Vec::new();
+        self.multipart_uploads.retain(|upload_id, upload| {
+            let abort = rules.iter().any(|(prefix, age)| {
+                upload.key.starts_with(prefix) && now - upload.initiated >= *age
+            });
+            if abort {
+                debug!(bucket = %self.name, upload_id, key = %upload.key, "lifecycle aborted multipart upload");
+                expired.push(upload_id.clone());
+            }
+            !abort
+        });
+        expired
+    }
+}
+
+/// The key prefix and upload age at which a lifecycle rule aborts
+/// incomplete multipart uploads, if it does.
+///
+/// Rules filtering on tags or object size never match an upload, whose
+/// final object does not exist yet.
+fn abort_incomplete_upload_rule(rule: &LifecycleRule) -> Option<(&str, TimeDelta)> {
+    if rule.status != ExpirationStatus::Enabled {
+        return None;
+    }
+    let days = rule
+        .abort_incomplete_multipart_upload
+        .as_ref()?
+        .days_after_initiation?;
+    let prefix = match &rule.filter {
+        Some(filter) => {
+            let and = filter.and.as_ref();
+            let has_conditions = filter.tag.is_some()
+                || filter.object_size_greater_than.is_some()
+                || filter.object_size_less_than.is_some()
+                || and.is_some_and(|and| {
+                    !and.tags.is_empty()
+                        || and.object_size_greater_than.is_some()
+                        || and.object_size_less_than.is_some()
+                });
+            if has_conditions {
+                return None;
+            }
+            filter
+                .prefix
+                .as_deref()
+                .or_else(|| and.and_then(|and| and.prefix.as_deref()))
+        }
+        None => rule.prefix.as_deref(),
+    };
+    Some((prefix.unwrap_or_default(), TimeDelta::days(i64::from(days))))
+}
+
+/// Whether an enabled lifecycle rule's filter selects an object with the
+/// given key, size, and tags.
+///
+/// Every condition present in the filter (prefix, tags, and size bounds,
+/// whether given directly or under `And`) must hold. Disabled rules match
+/// nothing.
+#[must_use]
+pub fn lifecycle_rule_matches(rule: &LifecycleRule, key: &str, size: u64, tags: &TagSet) -> bool {
+    if rule.status != ExpirationStatus::Enabled {
+        return false;
+    }
+    let Some(filter) = &rule.filter else {
+        return key.starts_with(rule.prefix.as_deref().unwrap_or_default());
+    };
+    let and = filter.and.as_ref();
+    let size = i64::try_from(size).unwrap_or(i64::MAX);
+    let mut prefixes = filter
+        .prefix
+        .iter()
+        .chain(and.and_then(|a| a.prefix.as_ref()));
+    let filter_tags = filter.tag.iter().chain(and.iter().flat_map(|a| &a.tags));
+    let mut greater_than = filter
+        .object_size_greater_than
+        .into_iter()
+        .chain(and.and_then(|a| a.object_size_greater_than));
+    let mut less_than = filter
+        .object_size_less_than
+        .into_iter()
+        .chain(and.and_then(|a| a.object_size_less_than));
+
+    prefixes.all(|prefix| key.starts_with(prefix.as_str()))
+        && tags.contains_all(filter_tags.map(|t| (t.key.as_str(), t.value.as_str())))
+        && greater_than.all(|bound| size > bound)
+        && less_than.all(|bound| size < bound)
 }
 
 // ---------------------------------------------------------------------------
@@ -466,6 +628,30 @@ mod tests {
         assert_eq!(retention.map(|r| r.days), Some(Some(30)));
     }
 
+    #[test]
+    fn test_should_compute_default_retain_until_date() {
+        let created = DateTime::parse_from_rfc3339("2024-02-29T12:00:00Z")
+            .map_or_else(|e| panic!("parse failed: {e}"), |d| d.with_timezone(&Utc));
+        let retention = |days, years| DefaultRetention {
+            mode: "COMPLIANCE".to_owned(),
+            days,
+            years,
+        };
+
+        assert_eq!(
+            retention(Some(30), None).retain_until(created),
+            Some(created + TimeDelta::days(30))
+        );
+        assert_eq!(
+            retention(None, Some(1))
+                .retain_until(created)
+                .map(|d| d.to_rfc3339()),
+            Some("2025-02-28T12:00:00+00:00".to_owned())
+        );
+        assert_eq!(retention(None, None).retain_until(created), None);
+        assert_eq!(retention(Some(0), None).retain_until(created), None);
+    }
+
     #[test]
     fn test_should_create_bucket_encryption() {
         let enc = BucketEncryption {
@@ -476,4 +662,65 @@ mod tests {
         assert_eq!(enc.sse_algorithm, "aws:kms");
         assert!(enc.bucket_key_enabled);
     }
+
+    #[test]
+    fn test_should_match_lifecycle_filter_on_prefix_tags_and_size() {
+        use rustack_s3_model::types::{LifecycleRuleAndOperator, LifecycleRuleFilter, Tag};
+
+        let tag = |k: &str, v: &str| Tag {
+            key: k.to_owned(),
+            value: v.to_owned(),
+        };
+        let rule = LifecycleRule {
+            status: ExpirationStatus::Enabled,
+            filter: Some(LifecycleRuleFilter {
+                and: Some(LifecycleRuleAndOperator {
+                    prefix: Some("logs/".to_owned()),
+                    tags: vec![tag("env", "prod"), tag("tier", "cold")],
+                    object_size_greater_than: Some(100),
+                    ..LifecycleRuleAndOperator::default()
+                }),
+                ..LifecycleRuleFilter::default()
+            }),
+            ..LifecycleRule::default()
+        };
+        let tags: TagSet = vec![
+            ("tier".to_owned(), "cold".to_owned()),
+            ("env".to_owned(), "prod".to_owned()),
+            ("team".to_owned(), "infra".to_owned()),
+        ]
+        .into();
+
+        assert!(lifecycle_rule_matches(&rule, "logs/a", 101, &tags));
+        assert!(!lifecycle_rule_matches(&rule, "data/a", 101, &tags));
+        assert!(!lifecycle_rule_matches(&rule, "logs/a", 100, &tags));
+        assert!(!lifecycle_rule_matches(
+            &rule,
+            "logs/a",
+            101,
+            &TagSet::from(vec![("env".to_owned(), "prod".to_owned())])
+        ));
+
+        let single_tag = LifecycleRule {
+            status: ExpirationStatus::Enabled,
+            filter: Some(LifecycleRuleFilter {
+                tag: Some(tag("team", "infra")),
+                ..LifecycleRuleFilter::default()
+            }),
+            ..LifecycleRule::default()
+        };
+        assert!(lifecycle_rule_matches(&single_tag, "any", 0, &tags));
+        assert!(!lifecycle_rule_matches(
+            &single_tag,
+            "any",
+            0,
+            &TagSet::default()
+        ));
+
+        let disabled = LifecycleRule {
+            status: ExpirationStatus::Disabled,
+            ..single_tag
+        };
+        assert!(!lifecycle_rule_matches(&disabled, "any", 0, &tags));
+    }
 }
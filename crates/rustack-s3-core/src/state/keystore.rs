@@ -5,9 +5,10 @@
 //! internally so keys are always sorted, which is required for correct
 //! `ListObjects` / `ListObjectVersions` pagination.
 
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, ops::Bound};
 
-use chrono::Utc;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
 use tracing::debug;
 use uuid::Uuid;
 
@@ -54,6 +55,105 @@ pub struct VersionListEntry {
     pub is_latest: bool,
 }
 
+/// One row of a bucket manifest export: the listing fields of a single
+/// object version, without its metadata or body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectRecord {
+    /// The object key.
+    pub key: String,
+    /// The version ID (`"null"` for un-versioned objects).
+    pub version_id: String,
+    /// Whether this is the latest version for its key.
+    pub is_latest: bool,
+    /// Whether this version is a delete marker.
+    pub is_delete_marker: bool,
+    /// Object size in bytes (0 for delete markers).
+    pub size: u64,
+    /// The quoted ETag (empty for delete markers).
+    pub etag: String,
+    /// The storage class (empty for delete markers).
+    pub storage_class: String,
+    /// When this version was written.
+    pub last_modified: DateTime<Utc>,
+}
+
+impl ObjectRecord {
+    /// Header row matching [`to_csv_row`](Self::to_csv_row).
+    pub const CSV_HEADER: &'static str =
+        "Key,VersionId,IsLatest,IsDeleteMarker,Size,ETag,StorageClass,LastModified";
+
+    fn from_object(obj: &S3Object, is_latest: bool) -> Self {
+        Self {
+            key: obj.key.clone(),
+            version_id: obj.version_id.clone(),
+            is_latest,
+            is_delete_marker: false,
+            size: obj.size,
+            etag: obj.etag.clone(),
+            storage_class: obj.storage_class.clone(),
+            last_modified: obj.last_modified,
+        }
+    }
+
+    fn from_version(version: &ObjectVersion, is_latest: bool) -> Self {
+        match version {
+            ObjectVersion::Object(obj) => Self::from_object(obj, is_latest),
+            ObjectVersion::DeleteMarker(dm) => Self {
+                key: dm.key.clone(),
+                version_id: dm.version_id.clone(),
+                is_latest,
+                is_delete_marker: true,
+                size: 0,
+                etag: String::new(),
+                storage_class: String::new(),
+                last_modified: dm.last_modified,
+            },
+        }
+    }
+
+    /// Render the record as one RFC 4180 CSV row, without a line ending.
+    #[must_use]
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{}",
+            csv_field(&self.key),
+            csv_field(&self.version_id),
+            self.is_latest,
+            self.is_delete_marker,
+            self.size,
+            csv_field(&self.etag),
+            csv_field(&self.storage_class),
+            self.last_modified
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+        )
+    }
+
+    /// Render the record as one JSON object, without a line ending.
+    #[must_use]
+    pub fn to_json_line(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote, or line break.
+fn csv_field(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
+    }
+}
+
+/// One batch of a manifest export. See [`ObjectStore::export_page`].
+#[derive(Debug, Clone, Default)]
+pub struct ExportPage {
+    /// Records for the keys visited, in key order.
+    pub records: Vec<ObjectRecord>,
+    /// The last key visited, when more keys may follow it.
+    pub next_key: Option<String>,
+}
+
 // ---------------------------------------------------------------------------
 // ObjectStore (enum dispatch)
 // ---------------------------------------------------------------------------
@@ -158,15 +258,23 @@ impl ObjectStore {
     /// an existing object was logically hidden, or `(Some(version_id), false)`
     /// if a delete marker was created but no real object existed for that key.
     ///
+    /// When versioning is `suspended`, the delete marker gets the `"null"`
+    /// version ID and replaces the key's existing `"null"` version.
+    ///
     /// For un-versioned stores, removes the object directly and returns
     /// `(None, had_object)`.
-    pub fn delete_versioned(&mut self, key: &str, owner: &Owner) -> (Option<String>, bool) {
+    pub fn delete_versioned(
+        &mut self,
+        key: &str,
+        owner: &Owner,
+        suspended: bool,
+    ) -> (Option<String>, bool) {
         match self {
             Self::Unversioned(ks) => {
                 let had = ks.delete(key).is_some();
                 (None, had)
             }
-            Self::Versioned(vs) => vs.delete(key, owner),
+            Self::Versioned(vs) => vs.delete(key, owner, suspended),
         }
     }
 
@@ -228,6 +336,15 @@ impl ObjectStore {
         }
     }
 
+    /// Total size in bytes of every stored object version (delete markers excluded).
+    #[must_use]
+    pub fn stored_bytes(&self) -> u64 {
+        match self {
+            Self::Unversioned(ks) => ks.stored_bytes(),
+            Self::Versioned(vs) => vs.stored_bytes(),
+        }
+    }
+
     /// Whether the store contains zero entries (objects, versions, or delete markers).
     ///
     /// This checks for truly empty storage (no entries at all), which is the
@@ -263,6 +380,43 @@ impl ObjectStore {
     pub fn is_versioned(&self) -> bool {
         matches!(self, Self::Versioned(_))
     }
+
+    /// Export manifest records for up to `max_keys` keys after `after`.
+    ///
+    /// With `all_versions`, every version and delete marker of a key is
+    /// exported; otherwise only its current object, if any. A key is never
+    /// split across pages, so `next_key` is a safe resume point even if the
+    /// store changes between calls.
+    #[must_use]
+    pub fn export_page(
+        &self,
+        after: Option<&str>,
+        all_versions: bool,
+        max_keys: usize,
+    ) -> ExportPage {
+        let lower = after.map_or(Bound::Unbounded, Bound::Excluded);
+        match self {
+            Self::Unversioned(ks) => {
+                export_from_btree(&ks.objects, lower, max_keys, |obj, records| {
+                    records.push(ObjectRecord::from_object(obj, true));
+                })
+            }
+            Self::Versioned(vs) => {
+                export_from_btree(&vs.objects, lower, max_keys, |versions, records| {
+                    if all_versions {
+                        records.extend(
+                            versions
+                                .iter()
+                                .enumerate()
+                                .map(|(idx, v)| ObjectRecord::from_version(v, idx == 0)),
+                        );
+                    } else if let Some(obj) = versions.first().and_then(ObjectVersion::as_object) {
+                        records.push(ObjectRecord::from_object(obj, true));
+                    }
+                })
+            }
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -304,6 +458,12 @@ impl KeyStore {
         self.objects.len()
     }
 
+    /// Total size in bytes of all stored objects.
+    #[must_use]
+    pub fn stored_bytes(&self) -> u64 {
+        self.objects.values().map(|obj| obj.size).sum()
+    }
+
     /// Whether the store is empty.
     #[must_use]
     pub fn is_empty(&self) -> bool {
@@ -320,7 +480,8 @@ impl KeyStore {
         max_keys: usize,
     ) -> ListResult {
         list_from_btree(
-            self.objects.values(),
+            &self.objects,
+            Some,
             prefix,
             delimiter,
             start_after,
@@ -369,14 +530,17 @@ pub struct VersionedKeyStore {
 }
 
 impl VersionedKeyStore {
-    /// Insert an object, generating a new version ID and prepending to the
-    /// version list.
-    pub fn put(&mut self, mut object: S3Object) {
-        if object.version_id == "null" {
-            object.version_id = generate_version_id();
-        }
+    /// Insert an object at the front of its key's version list.
+    ///
+    /// An object with the `"null"` version ID (written while versioning is
+    /// suspended) replaces the key's existing `"null"` version, whether that
+    /// is an object or a delete marker.
+    pub fn put(&mut self, object: S3Object) {
         debug!(key = %object.key, version = %object.version_id, "storing versioned object");
         let versions = self.objects.entry(object.key.clone()).or_default();
+        if object.version_id == "null" {
+            versions.retain(|v| v.version_id() != "null");
+        }
         versions.insert(0, ObjectVersion::Object(Box::new(object)));
     }
 
@@ -438,9 +602,16 @@ impl VersionedKeyStore {
 
     /// Delete an object by inserting a delete marker at the front.
     ///
+    /// When versioning is `suspended` the marker's version ID is `"null"`
+    /// and it replaces the key's existing `"null"` version.
+    ///
     /// Returns `(version_id_of_marker, had_real_object)`.
-    pub fn delete(&mut self, key: &str, owner: &Owner) -> (Option<String>, bool) {
-        let version_id = generate_version_id();
+    pub fn delete(&mut self, key: &str, owner: &Owner, suspended: bool) -> (Option<String>, bool) {
+        let version_id = if suspended {
+            "null".to_owned()
+        } else {
+            generate_version_id()
+        };
         let dm = S3DeleteMarker {
             key: key.to_owned(),
             version_id: version_id.clone(),
@@ -450,6 +621,9 @@ impl VersionedKeyStore {
 
         let versions = self.objects.entry(key.to_owned()).or_default();
         let had_object = versions.iter().any(|v| v.as_object().is_some());
+        if suspended {
+            versions.retain(|v| v.version_id() != "null");
+        }
         versions.insert(0, ObjectVersion::DeleteMarker(dm));
         debug!(key, version_id = %version_id, "inserted delete marker");
 
@@ -478,6 +652,17 @@ impl VersionedKeyStore {
             .count()
     }
 
+    /// Total size in bytes of every object version, including noncurrent ones.
+    #[must_use]
+    pub fn stored_bytes(&self) -> u64 {
+        self.objects
+            .values()
+            .flatten()
+            .filter_map(ObjectVersion::as_object)
+            .map(|obj| obj.size)
+            .sum()
+    }
+
     /// Whether the store contains zero entries (no versions or delete markers).
     ///
     /// This checks if the underlying BTreeMap is completely empty, which is
@@ -497,17 +682,15 @@ impl VersionedKeyStore {
         start_after: &str,
         max_keys: usize,
     ) -> ListResult {
-        // Build an iterator over the "current" (latest non-DM) object per key.
-        let current_objects = self.objects.values().filter_map(|versions| {
-            // Only consider keys whose latest entry is NOT a delete marker.
-            let latest = versions.first()?;
-            if latest.is_delete_marker() {
-                return None;
-            }
-            latest.as_object()
-        });
-
-        list_from_btree(current_objects, prefix, delimiter, start_after, max_keys)
+        // Keys whose latest entry is a delete marker are not listed.
+        list_from_btree(
+            &self.objects,
+            |versions| versions.first().and_then(ObjectVersion::as_object),
+            prefix,
+            delimiter,
+            start_after,
+            max_keys,
+        )
     }
 
     /// List all versions (objects and delete markers).
@@ -520,92 +703,81 @@ impl VersionedKeyStore {
         version_id_marker: &str,
         max_keys: usize,
     ) -> VersionListResult {
-        let use_delim = !delimiter.is_empty();
-        let mut result_versions: Vec<VersionListEntry> = Vec::new();
-        let mut common_prefixes: Vec<String> = Vec::new();
-        let mut seen_prefixes = std::collections::HashSet::new();
+        let mut result = VersionListResult {
+            versions: Vec::new(),
+            common_prefixes: Vec::new(),
+            is_truncated: false,
+            next_key_marker: None,
+            next_version_id_marker: None,
+        };
         let mut count = 0usize;
-        let mut is_truncated = false;
-        let mut last_key: Option<String> = None;
-        let mut last_version_id: Option<String> = None;
-
-        // Determine where to start iteration.
-        let iter: Box<dyn Iterator<Item = (&String, &Vec<ObjectVersion>)>> =
-            if key_marker.is_empty() {
-                Box::new(self.objects.iter())
-            } else {
-                // Start from the key_marker (exclusive unless version_id_marker applies).
-                let marker = key_marker.to_owned();
-                Box::new(self.objects.range(marker..))
-            };
-
-        'outer: for (key, versions) in iter {
-            // Skip keys before the marker.
-            if !key_marker.is_empty() && key.as_str() < key_marker {
-                continue;
-            }
 
-            // Filter by prefix.
-            if !prefix.is_empty() && !key.starts_with(prefix) {
-                // BTreeMap is sorted, so if the key is past the prefix range, stop.
-                if key.as_str() > prefix {
-                    // Check if we are still in prefix-adjacent territory.
-                    // Once key is beyond the prefix lexicographically and doesn't
-                    // start with prefix, there can be no more matches.
-                    let beyond = !key.starts_with(&prefix[..prefix.len().saturating_sub(1).max(1)]);
-                    if beyond {
-                        break;
-                    }
+        // The marker key itself is revisited, to resume after
+        // `version_id_marker` among its versions.
+        let mut lower = Bound::Included(key_marker.max(prefix).to_owned());
+        'seek: loop {
+            for (key, versions) in self
+                .objects
+                .range::<str, _>((as_str(&lower), Bound::Unbounded))
+            {
+                if !key.starts_with(prefix) {
+                    break 'seek;
                 }
-                continue;
-            }
 
-            // Delimiter-based common prefix grouping.
-            if use_delim {
-                let after_prefix = &key[prefix.len()..];
-                if let Some(pos) = after_prefix.find(delimiter) {
-                    let cp = format!("{}{}{}", prefix, &after_prefix[..pos], delimiter);
-                    if seen_prefixes.insert(cp.clone()) {
-                        common_prefixes.push(cp);
+                if let Some(cp) = common_prefix(key, prefix, delimiter) {
+                    // A prefix at or before the marker was returned on an
+                    // earlier page.
+                    if result.common_prefixes.last().map(String::as_str) != Some(cp)
+                        && (key_marker.is_empty() || cp > key_marker)
+                    {
+                        if count == max_keys {
+                            result.is_truncated = true;
+                            break 'seek;
+                        }
+                        count += 1;
+                        result.common_prefixes.push(cp.to_owned());
+                        result.next_key_marker = Some(cp.to_owned());
+                        result.next_version_id_marker = None;
+                    }
+                    if let Some(next) = prefix_successor(cp) {
+                        lower = Bound::Included(next);
+                        continue 'seek;
                     }
                     continue;
                 }
-            }
 
-            // For the key_marker key, skip versions until we pass version_id_marker.
-            let mut skip_versions = key.as_str() == key_marker && !version_id_marker.is_empty();
+                // For the key_marker key, skip versions until we pass version_id_marker.
+                let mut skip_versions = key.as_str() == key_marker && !version_id_marker.is_empty();
 
-            for (idx, version) in versions.iter().enumerate() {
-                if skip_versions {
-                    if version.version_id() == version_id_marker {
-                        skip_versions = false;
+                for (idx, version) in versions.iter().enumerate() {
+                    if skip_versions {
+                        if version.version_id() == version_id_marker {
+                            skip_versions = false;
+                        }
+                        continue;
                     }
-                    continue;
-                }
 
-                if count >= max_keys {
-                    is_truncated = true;
-                    break 'outer;
+                    if count == max_keys {
+                        result.is_truncated = true;
+                        break 'seek;
+                    }
+                    count += 1;
+                    result.next_key_marker = Some(key.clone());
+                    result.next_version_id_marker = Some(version.version_id().to_owned());
+                    result.versions.push(VersionListEntry {
+                        version: version.clone(),
+                        is_latest: idx == 0,
+                    });
                 }
-
-                let entry = VersionListEntry {
-                    version: version.clone(),
-                    is_latest: idx == 0,
-                };
-                last_key = Some(key.clone());
-                last_version_id = Some(version.version_id().to_owned());
-                result_versions.push(entry);
-                count += 1;
             }
+            break;
         }
 
-        VersionListResult {
-            versions: result_versions,
-            common_prefixes,
-            is_truncated,
-            next_key_marker: if is_truncated { last_key } else { None },
-            next_version_id_marker: if is_truncated { last_version_id } else { None },
+        if !result.is_truncated {
+            result.next_key_marker = None;
+            result.next_version_id_marker = None;
         }
+        result
     }
 }
 
@@ -613,66 +785,131 @@ impl VersionedKeyStore {
 // Shared listing helper
 // ---------------------------------------------------------------------------
 
-/// Build a [`ListResult`] from an iterator of `S3Object` references, applying
-/// prefix, delimiter, start-after, and max-keys filtering.
-fn list_from_btree<'a>(
-    objects: impl Iterator<Item = &'a S3Object>,
+/// Build one [`ListResult`] page from a sorted key map, applying prefix,
+/// delimiter, start-after, and max-keys.
+///
+/// `current` picks the object a key lists as, if any. Listing seeks straight
+/// to the first candidate key, skips past every key under a common prefix
+/// at once, and stops as soon as the page is full, so its cost follows the
+/// page size rather than the size of the bucket. Common prefixes count
+/// toward `max_keys`, as in S3.
+fn list_from_btree<'a, V>(
+    objects: &'a BTreeMap<String, V>,
+    current: impl Fn(&'a V) -> Option<&'a S3Object>,
     prefix: &str,
     delimiter: &str,
     start_after: &str,
     max_keys: usize,
 ) -> ListResult {
-    let use_delim = !delimiter.is_empty();
-    let mut result_objects: Vec<S3Object> = Vec::new();
-    let mut common_prefixes: Vec<String> = Vec::new();
-    let mut seen_prefixes = std::collections::HashSet::new();
+    let mut result = ListResult {
+        objects: Vec::new(),
+        common_prefixes: Vec::new(),
+        is_truncated: false,
+        next_marker: None,
+    };
     let mut count = 0usize;
-    let mut is_truncated = false;
 
-    for obj in objects {
-        // Skip keys at or before start_after.
-        if !start_after.is_empty() && obj.key.as_str() <= start_after {
-            continue;
-        }
-
-        // Filter by prefix.
-        if !prefix.is_empty() && !obj.key.starts_with(prefix) {
-            continue;
-        }
+    let mut lower = if start_after >= prefix {
+        Bound::Excluded(start_after.to_owned())
+    } else {
+        Bound::Included(prefix.to_owned())
+    };
+    'seek: loop {
+        for (key, value) in objects.range::<str, _>((as_str(&lower), Bound::Unbounded)) {
+            // Keys with the prefix are contiguous, and iteration starts at
+            // or after the prefix.
+            if !key.starts_with(prefix) {
+                break 'seek;
+            }
+            let Some(obj) = current(value) else {
+                continue;
+            };
 
-        // Delimiter-based grouping.
-        if use_delim {
-            let after_prefix = &obj.key[prefix.len()..];
-            if let Some(pos) = after_prefix.find(delimiter) {
-                let cp = format!("{}{}{}", prefix, &after_prefix[..pos], delimiter);
-                if seen_prefixes.insert(cp.clone()) {
-                    common_prefixes.push(cp);
+            if let Some(cp) = common_prefix(key, prefix, delimiter) {
+                // A prefix at or before the marker was returned on an earlier
+                // page.
+                if result.common_prefixes.last().map(String::as_str) != Some(cp)
+                    && (start_after.is_empty() || cp > start_after)
+                {
+                    if count == max_keys {
+                        result.is_truncated = true;
+                        break 'seek;
+                    }
+                    count += 1;
+                    result.common_prefixes.push(cp.to_owned());
+                    result.next_marker = Some(cp.to_owned());
+                }
+                if let Some(next) = prefix_successor(cp) {
+                    lower = Bound::Included(next);
+                    continue 'seek;
                 }
                 continue;
             }
-        }
 
-        if count >= max_keys {
-            is_truncated = true;
-            break;
+            if count == max_keys {
+                result.is_truncated = true;
+                break 'seek;
+            }
+            count += 1;
+            result.objects.push(obj.clone());
+            result.next_marker = Some(key.clone());
         }
+        break;
+    }
 
-        result_objects.push(obj.clone());
-        count += 1;
+    if !result.is_truncated {
+        result.next_marker = None;
     }
+    result
+}
 
-    let next_marker = if is_truncated {
-        result_objects.last().map(|o| o.key.clone())
-    } else {
-        None
-    };
+/// Build one [`ExportPage`] from the first `max_keys` keys within `lower`,
+/// letting `export` turn each key's value into records.
+fn export_from_btree<V>(
+    objects: &BTreeMap<String, V>,
+    lower: Bound<&str>,
+    max_keys: usize,
+    export: impl Fn(&V, &mut Vec<ObjectRecord>),
+) -> ExportPage {
+    let mut page = ExportPage::default();
+    for (visited, (key, value)) in objects
+        .range::<str, _>((lower, Bound::Unbounded))
+        .enumerate()
+    {
+        if visited == max_keys {
+            return page;
+        }
+        export(value, &mut page.records);
+        page.next_key = Some(key.clone());
+    }
+    page.next_key = None;
+    page
+}
 
-    ListResult {
-        objects: result_objects,
-        common_prefixes,
-        is_truncated,
-        next_marker,
+/// The common prefix `key` rolls up into: the key through the first
+/// `delimiter` after `prefix`.
+fn common_prefix<'k>(key: &'k str, prefix: &str, delimiter: &str) -> Option<&'k str> {
+    if delimiter.is_empty() {
+        return None;
     }
+    let pos = key[prefix.len()..].find(delimiter)?;
+    Some(&key[..prefix.len() + pos + delimiter.len()])
+}
+
+/// The smallest string greater than every string starting with `prefix`,
+/// or `None` if there is none.
+fn prefix_successor(prefix: &str) -> Option<String> {
+    let last = prefix.chars().next_back()?;
+    let next = (u32::from(last) + 1..=u32::from(char::MAX)).find_map(char::from_u32)?;
+    Some(format!(
+        "{}{next}",
+        &prefix[..prefix.len() - last.len_utf8()]
+    ))
+}
+
+/// Borrow an owned range bound as a `str` bound.
+fn as_str(bound: &Bound<String>) -> Bound<&str> {
+    bound.as_ref().map(String::as_str)
 }
 
 /// Generate a unique version ID for versioned objects / delete markers.
@@ -704,6 +941,14 @@ mod tests {
             checksum: None,
             parts_count: None,
             part_etags: Vec::new(),
+            parts: Vec::new(),
+        }
+    }
+
+    fn make_version(key: &str) -> S3Object {
+        S3Object {
+            version_id: generate_version_id(),
+            ..make_object(key)
         }
     }
 
@@ -790,12 +1035,99 @@ mod tests {
         assert!(result.common_prefixes.is_empty());
     }
 
+    #[test]
+    fn test_should_page_through_common_prefixes_and_keys() {
+        let mut ks = KeyStore::default();
+        for key in ["a/1", "a/2", "b", "c/1", "c/2", "c/3", "d"] {
+            ks.put(make_object(key));
+        }
+
+        let mut start_after = String::new();
+        let mut listed = Vec::new();
+        loop {
+            let page = ks.list_objects("", "/", &start_after, 2);
+            assert!(page.objects.len() + page.common_prefixes.len() <= 2);
+            listed.extend(page.common_prefixes);
+            listed.extend(page.objects.into_iter().map(|obj| obj.key));
+            let Some(marker) = page.next_marker else {
+                break;
+            };
+            start_after = marker;
+        }
+        listed.sort();
+        assert_eq!(listed, vec!["a/", "b", "c/", "d"]);
+    }
+
+    #[test]
+    fn test_should_seek_to_start_after_within_prefix() {
+        let mut ks = KeyStore::default();
+        for i in 0..1000 {
+            ks.put(make_object(&format!("logs/{i:04}")));
+        }
+        ks.put(make_object("other"));
+
+        let page = ks.list_objects("logs/", "", "logs/0997", 10);
+        let keys: Vec<_> = page.objects.iter().map(|obj| obj.key.as_str()).collect();
+        assert_eq!(keys, vec!["logs/0998", "logs/0999"]);
+        assert!(!page.is_truncated);
+
+        let page = ks.list_objects("logs/", "", "a", 1);
+        assert_eq!(page.objects[0].key, "logs/0000");
+        assert_eq!(page.next_marker.as_deref(), Some("logs/0000"));
+    }
+
+    #[test]
+    fn test_should_compute_prefix_successor() {
+        assert_eq!(prefix_successor("photos/").as_deref(), Some("photos0"));
+        assert_eq!(prefix_successor("a\u{D7FF}").as_deref(), Some("a\u{E000}"));
+        assert_eq!(prefix_successor(""), None);
+    }
+
+    /// A bucket of `keys` keys spread over 500 "directories".
+    fn synthetic_keystore(keys: usize) -> KeyStore {
+        let per_dir = keys / 500;
+        let mut ks = KeyStore::default();
+        for dir in 0..500 {
+            for key in 0..per_dir {
+                ks.put(make_object(&format!("dir-{dir:03}/key-{key:07}")));
+            }
+        }
+        ks
+    }
+
+    #[test]
+    fn test_should_list_full_pages_from_a_large_bucket() {
+        let ks = synthetic_keystore(100_000);
+
+        // Each listing stops at its page, wherever in the keyspace it
+        // starts; with a delimiter the 500 directories fit in one page.
+        let listings: [(&str, &str, &str, usize); 3] = [
+            ("first page", "", "", 1000),
+            ("mid-keyspace continuation", "", "dir-250/", 1000),
+            ("heavy delimiter", "/", "", 1000),
+        ];
+        for (name, delimiter, start_after, page) in listings {
+            let listed = ks.list_objects("", delimiter, start_after, page);
+            assert_eq!(
+                listed.objects.len() + listed.common_prefixes.len(),
+                if delimiter.is_empty() { page } else { 500 },
+                "{name}"
+            );
+        }
+        let listed = ks.list_objects("", "", "dir-250/", 1000);
+        assert_eq!(
+            listed.objects.first().map(|o| o.key.as_str()),
+            Some("dir-250/key-0000000")
+        );
+        assert!(listed.is_truncated);
+    }
+
     // ---- VersionedKeyStore tests ----
 
     #[test]
     fn test_should_put_and_get_in_versioned_store() {
         let mut vs = VersionedKeyStore::default();
-        vs.put(make_object("key1"));
+        vs.put(make_version("key1"));
 
         let obj = vs.get("key1");
         assert!(obj.is_some());
@@ -806,11 +1138,11 @@ mod tests {
     fn test_should_stack_versions_newest_first() {
         let mut vs = VersionedKeyStore::default();
 
-        let mut obj1 = make_object("key1");
+        let mut obj1 = make_version("key1");
         obj1.size = 100;
         vs.put(obj1);
 
-        let mut obj2 = make_object("key1");
+        let mut obj2 = make_version("key1");
         obj2.size = 200;
         vs.put(obj2);
 
@@ -826,9 +1158,9 @@ mod tests {
     #[test]
     fn test_should_insert_delete_marker() {
         let mut vs = VersionedKeyStore::default();
-        vs.put(make_object("key1"));
+        vs.put(make_version("key1"));
 
-        let (dm_version, had_object) = vs.delete("key1", &Owner::default());
+        let (dm_version, had_object) = vs.delete("key1", &Owner::default(), false);
         assert!(dm_version.is_some());
         assert!(had_object);
 
@@ -849,7 +1181,7 @@ mod tests {
     #[test]
     fn test_should_delete_specific_version() {
         let mut vs = VersionedKeyStore::default();
-        vs.put(make_object("key1"));
+        vs.put(make_version("key1"));
         let version_id = vs.get("key1").map(|o| o.version_id.clone());
         assert!(version_id.is_some());
 
@@ -862,7 +1194,7 @@ mod tests {
     #[test]
     fn test_should_get_version_by_id() {
         let mut vs = VersionedKeyStore::default();
-        let mut obj1 = make_object("key1");
+        let mut obj1 = make_version("key1");
         obj1.size = 111;
         vs.put(obj1);
         let v1_id = vs
@@ -872,7 +1204,7 @@ mod tests {
             .map(|v| v.version_id().to_owned())
             .unwrap_or_default();
 
-        let mut obj2 = make_object("key1");
+        let mut obj2 = make_version("key1");
         obj2.size = 222;
         vs.put(obj2);
 
@@ -885,9 +1217,9 @@ mod tests {
     #[test]
     fn test_should_list_versioned_objects() {
         let mut vs = VersionedKeyStore::default();
-        vs.put(make_object("a"));
-        vs.put(make_object("b"));
-        vs.put(make_object("c"));
+        vs.put(make_version("a"));
+        vs.put(make_version("b"));
+        vs.put(make_version("c"));
 
         let result = vs.list_objects("", "", "", 10);
         assert_eq!(result.objects.len(), 3);
@@ -897,9 +1229,9 @@ mod tests {
     #[test]
     fn test_should_list_object_versions() {
         let mut vs = VersionedKeyStore::default();
-        vs.put(make_object("key1"));
-        vs.put(make_object("key1")); // second version
-        vs.put(make_object("key2"));
+        vs.put(make_version("key1"));
+        vs.put(make_version("key1")); // second version
+        vs.put(make_version("key2"));
 
         let result = vs.list_object_versions("", "", "", "", 100);
         // key1 has 2 versions, key2 has 1.
@@ -914,8 +1246,49 @@ mod tests {
         assert!(first_key1.is_some());
     }
 
+    #[test]
+    fn test_should_replace_null_version_in_versioned_store() {
+        let mut vs = VersionedKeyStore::default();
+        vs.put(make_object("key1"));
+        vs.put(make_version("key1"));
+
+        let mut null_obj = make_object("key1");
+        null_obj.size = 7;
+        vs.put(null_obj);
+        assert_eq!(vs.objects.get("key1").map(Vec::len), Some(2));
+        assert_eq!(vs.get("key1").map(|o| o.size), Some(7));
+
+        let (dm_version, had_object) = vs.delete("key1", &Owner::default(), true);
+        assert_eq!(dm_version.as_deref(), Some("null"));
+        assert!(had_object);
+        assert!(vs.is_delete_marker("key1", "null"));
+        assert_eq!(vs.objects.get("key1").map(Vec::len), Some(2));
+
+        vs.put(make_object("key1"));
+        assert!(!vs.is_delete_marker("key1", "null"));
+        assert_eq!(vs.get_version("key1", "null").map(|o| o.size), Some(100));
+        assert_eq!(vs.objects.get("key1").map(Vec::len), Some(2));
+    }
+
     // ---- ObjectStore tests ----
 
+    #[test]
+    fn test_should_not_roll_up_keys_hidden_by_delete_markers() {
+        let mut vs = VersionedKeyStore::default();
+        vs.put(make_version("gone/1"));
+        vs.put(make_version("kept/1"));
+        vs.delete("gone/1", &Owner::default(), false);
+
+        let result = vs.list_objects("", "/", "", 100);
+        assert_eq!(result.common_prefixes, vec!["kept/".to_owned()]);
+
+        let result = vs.list_object_versions("", "/", "", "", 100);
+        assert_eq!(
+            result.common_prefixes,
+            vec!["gone/".to_owned(), "kept/".to_owned()]
+        );
+    }
+
     #[test]
     fn test_should_default_to_unversioned() {
         let store = ObjectStore::default();
@@ -951,19 +1324,19 @@ mod tests {
     #[test]
     fn test_should_not_return_previous_on_versioned_put() {
         let mut store = ObjectStore::Versioned(VersionedKeyStore::default());
-        let prev = store.put(make_object("k"));
+        let prev = store.put(make_version("k"));
         assert!(prev.is_none());
 
-        let prev = store.put(make_object("k"));
+        let prev = store.put(make_version("k"));
         assert!(prev.is_none());
     }
 
     #[test]
     fn test_should_delete_versioned_via_object_store() {
         let mut store = ObjectStore::Versioned(VersionedKeyStore::default());
-        store.put(make_object("k"));
+        store.put(make_version("k"));
 
-        let (dm_id, had) = store.delete_versioned("k", &Owner::default());
+        let (dm_id, had) = store.delete_versioned("k", &Owner::default(), false);
         assert!(dm_id.is_some());
         assert!(had);
         // After delete marker, len() should be 0 (key is logically deleted).
@@ -972,6 +1345,20 @@ mod tests {
         assert!(!store.is_empty());
     }
 
+    #[test]
+    fn test_should_count_stored_bytes_across_versions() {
+        let mut store = ObjectStore::default();
+        store.put(make_object("a"));
+        store.put(make_object("b"));
+        assert_eq!(store.stored_bytes(), 200);
+
+        store.transition_to_versioned();
+        store.put(make_version("a"));
+        store.delete_versioned("b", &Owner::default(), false);
+        // Noncurrent versions still occupy storage; delete markers do not.
+        assert_eq!(store.stored_bytes(), 300);
+    }
+
     #[test]
     fn test_should_get_version_in_unversioned_store() {
         let mut store = ObjectStore::default();
@@ -1035,8 +1422,65 @@ mod tests {
     #[test]
     fn test_should_handle_delete_marker_on_nonexistent_key() {
         let mut vs = VersionedKeyStore::default();
-        let (dm_version, had_object) = vs.delete("nonexistent", &Owner::default());
+        let (dm_version, had_object) = vs.delete("nonexistent", &Owner::default(), false);
         assert!(dm_version.is_some());
         assert!(!had_object);
     }
+
+    #[test]
+    fn test_should_export_pages_of_whole_keys() {
+        let mut store = ObjectStore::default();
+        for key in ["a", "b", "c"] {
+            store.put(make_object(key));
+        }
+        store.transition_to_versioned();
+        store.put(make_version("a"));
+        store.delete_versioned("b", &Owner::default(), false);
+
+        let current = store.export_page(None, false, 10);
+        let keys: Vec<&str> = current.records.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(keys, vec!["a", "c"]);
+        assert!(current.next_key.is_none());
+
+        let first = store.export_page(None, true, 2);
+        assert_eq!(first.next_key.as_deref(), Some("b"));
+        let flags: Vec<(&str, bool, bool)> = first
+            .records
+            .iter()
+            .map(|r| (r.key.as_str(), r.is_latest, r.is_delete_marker))
+            .collect();
+        assert_eq!(
+            flags,
+            vec![
+                ("a", true, false),
+                ("a", false, false),
+                ("b", true, true),
+                ("b", false, false),
+            ]
+        );
+        let rest = store.export_page(first.next_key.as_deref(), true, 2);
+        assert_eq!(rest.records.len(), 1);
+        assert_eq!(rest.records[0].key, "c");
+        assert!(rest.next_key.is_none());
+    }
+
+    #[test]
+    fn test_should_render_records_as_csv_and_json() {
+        let mut record = ObjectRecord::from_object(&make_object("plain"), true);
+        record.last_modified = DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            record.to_csv_row(),
+            "plain,null,true,false,100,\"\"\"etag-plain\"\"\",STANDARD,2024-01-02T03:04:05.000Z"
+        );
+        record.key = "a,b".to_owned();
+        assert!(record.to_csv_row().starts_with("\"a,b\",null,"));
+
+        let json: serde_json::Value = serde_json::from_str(&record.to_json_line()).unwrap();
+        assert_eq!(json["key"], "a,b");
+        assert_eq!(json["versionId"], "null");
+        assert_eq!(json["size"], 100);
+        assert_eq!(json["isDeleteMarker"], false);
+    }
 }
@@ -2,10 +2,12 @@
 //!
 //! This module provides the in-memory state for the S3 service:
 //!
-//! - [`S3ServiceState`] -- top-level service owning all buckets
+//! - [`S3ServiceState`] -- top-level service owning all buckets and access points
+//! - [`ManifestExport`] / [`ObjectRecord`] -- batched bucket manifest export
 //! - [`S3Bucket`] -- per-bucket state (objects, versioning, configs)
 //! - [`ObjectStore`] / [`KeyStore`] / [`VersionedKeyStore`] -- key-level storage
 //! - [`S3Object`] / [`S3DeleteMarker`] / [`ObjectMetadata`] -- object types
+//! - [`TagSet`] / [`lifecycle_rule_matches`] -- object tags and tag-based rule filters
 //! - [`MultipartUpload`] / [`UploadPart`] -- multipart upload tracking
 //!
 //! # Thread Safety
@@ -24,13 +26,15 @@ pub(crate) mod service;
 pub use bucket::{
     BucketEncryption, CorsRuleConfig, DefaultRetention, ObjectLockConfiguration, ObjectLockRule,
     OwnershipControlsConfig, PublicAccessBlockConfig, S3Bucket, VersioningStatus,
+    lifecycle_rule_matches,
 };
 pub use keystore::{
-    KeyStore, ListResult, ObjectStore, VersionListEntry, VersionListResult, VersionedKeyStore,
+    ExportPage, KeyStore, ListResult, ObjectRecord, ObjectStore, VersionListEntry,
+    VersionListResult, VersionedKeyStore,
 };
 pub use multipart::{MultipartUpload, UploadPart};
 pub use object::{
     CannedAcl, ChecksumData, Grant, Grantee, ObjectMetadata, ObjectVersion, Owner, Permission,
-    S3DeleteMarker, S3Object,
+    S3DeleteMarker, S3Object, TagSet,
 };
-pub use service::S3ServiceState;
+pub use service::{AccessPoint, BucketStats, ManifestExport, S3ServiceState};
@@ -132,8 +132,9 @@ pub enum Grantee {
     CanonicalUser {
         /// The canonical user ID.
         id: String,
-        /// The display name for the user.
-        display_name: String,
+        /// The display name for the user, if known.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        display_name: Option<String>,
     },
     /// A predefined Amazon S3 group.
     Group {
@@ -163,16 +164,43 @@ pub enum Permission {
     WriteAcp,
 }
 
-impl fmt::Display for Permission {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let s = match self {
+impl Permission {
+    /// Return the S3 name of the permission (e.g. `FULL_CONTROL`).
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
             Self::FullControl => "FULL_CONTROL",
             Self::Read => "READ",
             Self::Write => "WRITE",
             Self::ReadAcp => "READ_ACP",
             Self::WriteAcp => "WRITE_ACP",
-        };
-        f.write_str(s)
+        }
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error returned when parsing a [`Permission`] from a string fails.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("unknown permission: {0}")]
+pub struct ParsePermissionError(String);
+
+impl FromStr for Permission {
+    type Err = ParsePermissionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "FULL_CONTROL" => Ok(Self::FullControl),
+            "READ" => Ok(Self::Read),
+            "WRITE" => Ok(Self::Write),
+            "READ_ACP" => Ok(Self::ReadAcp),
+            "WRITE_ACP" => Ok(Self::WriteAcp),
+            _ => Err(ParsePermissionError(s.to_owned())),
+        }
     }
 }
 
@@ -198,6 +226,73 @@ fn default_checksum_type() -> String {
     "FULL_OBJECT".to_owned()
 }
 
+// ---------------------------------------------------------------------------
+// TagSet
+// ---------------------------------------------------------------------------
+
+/// The tags of an object, kept in the order they were supplied.
+///
+/// Tag sets are small (at most 10 entries once validated), so lookups scan
+/// the list rather than maintaining an index. Serializes as a list of
+/// `[key, value]` pairs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TagSet(Vec<(String, String)>);
+
+impl TagSet {
+    /// Return the value of the tag with the given key.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Whether every `(key, value)` pair in `filter` is present in this set.
+    ///
+    /// This is how lifecycle and replication rule filters match on tags; an
+    /// empty filter matches every object.
+    #[must_use]
+    pub fn contains_all<'a, I>(&self, filter: I) -> bool
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        filter
+            .into_iter()
+            .all(|(key, value)| self.get(key) == Some(value))
+    }
+
+    /// Iterate over the tags in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Number of tags in the set.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether the set has no tags.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<Vec<(String, String)>> for TagSet {
+    fn from(tags: Vec<(String, String)>) -> Self {
+        Self(tags)
+    }
+}
+
+impl FromIterator<(String, String)> for TagSet {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
 // ---------------------------------------------------------------------------
 // ObjectMetadata
 // ---------------------------------------------------------------------------
@@ -245,12 +340,15 @@ pub struct ObjectMetadata {
     /// Base64-encoded MD5 of the customer-provided encryption key.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sse_customer_key_md5: Option<String>,
-    /// Object tags as key-value pairs.
+    /// Object tags in insertion order.
     #[serde(default)]
-    pub tagging: Vec<(String, String)>,
+    pub tagging: TagSet,
     /// Canned ACL applied to this object.
     #[serde(default)]
     pub acl: CannedAcl,
+    /// Explicit ACL grants, which replace the canned ACL when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grants: Option<Vec<Grant>>,
     /// Object lock retention mode (`GOVERNANCE` or `COMPLIANCE`).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub object_lock_mode: Option<String>,
@@ -260,6 +358,27 @@ pub struct ObjectMetadata {
     /// Whether a legal hold is in effect for this object.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub object_lock_legal_hold: Option<bool>,
+    /// Why the object's stored data failed its last scrub, if it did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub integrity_failure: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// ObjectPart
+// ---------------------------------------------------------------------------
+
+/// A part of an object created via multipart upload, as recorded when the
+/// upload completed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectPart {
+    /// The part number given at upload time.
+    pub part_number: u32,
+    /// Size of the part in bytes.
+    pub size: u64,
+    /// Checksum data of the part, if it was uploaded with one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<ChecksumData>,
 }
 
 // ---------------------------------------------------------------------------
@@ -295,6 +414,10 @@ pub struct S3Object {
     /// Individual part ETags (used for composite ETag generation in multipart uploads).
     #[serde(default)]
     pub part_etags: Vec<String>,
+    /// The parts the object was assembled from, in part-number order, if it
+    /// was created via multipart upload.
+    #[serde(default)]
+    pub parts: Vec<ObjectPart>,
 }
 
 impl S3Object {
@@ -533,6 +656,36 @@ mod tests {
         assert!(meta.object_lock_mode.is_none());
     }
 
+    #[test]
+    fn test_should_match_tag_filters_against_tag_set() {
+        let tags: TagSet = vec![
+            ("env".to_owned(), "prod".to_owned()),
+            ("team".to_owned(), "data".to_owned()),
+        ]
+        .into();
+        assert_eq!(tags.get("team"), Some("data"));
+        assert!(tags.contains_all([]));
+        assert!(tags.contains_all([("env", "prod")]));
+        assert!(tags.contains_all([("team", "data"), ("env", "prod")]));
+        assert!(!tags.contains_all([("env", "dev")]));
+        assert!(!tags.contains_all([("env", "prod"), ("owner", "me")]));
+        let keys: Vec<&str> = tags.iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, ["env", "team"]);
+    }
+
+    #[test]
+    fn test_should_serialize_tag_set_as_pair_list() {
+        let tags: TagSet = vec![
+            ("b".to_owned(), "1".to_owned()),
+            ("a".to_owned(), "2".to_owned()),
+        ]
+        .into();
+        let json = serde_json::to_string(&tags).unwrap();
+        assert_eq!(json, r#"[["b","1"],["a","2"]]"#);
+        let back: TagSet = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, tags);
+    }
+
     #[test]
     fn test_should_display_permission() {
         assert_eq!(format!("{}", Permission::FullControl), "FULL_CONTROL");
@@ -557,6 +710,7 @@ mod tests {
             checksum: None,
             parts_count: None,
             part_etags: Vec::new(),
+            parts: Vec::new(),
         }
     }
 }
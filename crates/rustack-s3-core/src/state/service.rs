@@ -1,18 +1,99 @@
 //! Top-level S3 service state.
 //!
 //! [`S3ServiceState`] manages the collection of buckets and enforces global
-//! bucket-name uniqueness. All operations are thread-safe via `DashMap`.
+//! bucket-name uniqueness. It also keeps the account's access points, each
+//! naming the bucket it fronts. All operations are thread-safe via `DashMap`.
 
 use chrono::{DateTime, Utc};
 use dashmap::{
     DashMap,
-    mapref::one::{Ref, RefMut},
+    mapref::{
+        entry::Entry,
+        one::{Ref, RefMut},
+    },
 };
 use tracing::{debug, info};
 
-use super::{bucket::S3Bucket, object::Owner};
+use super::{
+    bucket::S3Bucket,
+    keystore::{ExportPage, ObjectRecord},
+    object::Owner,
+};
 use crate::error::S3ServiceError;
 
+/// Point-in-time storage usage of a single bucket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BucketStats {
+    /// Bucket name.
+    pub name: String,
+    /// Number of current (non-deleted) objects.
+    pub object_count: u64,
+    /// Total bytes held by all object versions in the bucket.
+    pub stored_bytes: u64,
+    /// Number of in-progress multipart uploads.
+    pub multipart_upload_count: u64,
+}
+
+/// Number of keys a [`ManifestExport`] reads per object-store lock.
+const EXPORT_PAGE_KEYS: usize = 1000;
+
+/// Iterator over a bucket's manifest, returned by
+/// [`S3ServiceState::export_manifest`].
+///
+/// Records are read in batches of [`EXPORT_PAGE_KEYS`] keys, taking the
+/// bucket's object-store read lock only while a batch is copied out, so
+/// writers are never blocked for the whole export. Objects written during
+/// the export appear if their key sorts after the batch being read. If the
+/// bucket is deleted mid-export, iteration ends.
+#[derive(Debug)]
+pub struct ManifestExport<'a> {
+    state: &'a S3ServiceState,
+    bucket: String,
+    all_versions: bool,
+    next_key: Option<String>,
+    exhausted: bool,
+    buffered: std::vec::IntoIter<ObjectRecord>,
+}
+
+impl Iterator for ManifestExport<'_> {
+    type Item = ObjectRecord;
+
+    fn next(&mut self) -> Option<ObjectRecord> {
+        loop {
+            if let Some(record) = self.buffered.next() {
+                return Some(record);
+            }
+            if self.exhausted {
+                return None;
+            }
+            let page = match self.state.buckets.get(&self.bucket) {
+                Some(bucket) => bucket.objects.read().export_page(
+                    self.next_key.as_deref(),
+                    self.all_versions,
+                    EXPORT_PAGE_KEYS,
+                ),
+                None => ExportPage::default(),
+            };
+            self.exhausted = page.next_key.is_none();
+            self.next_key = page.next_key;
+            self.buffered = page.records.into_iter();
+        }
+    }
+}
+
+/// An S3 access point: a named alias for a bucket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessPoint {
+    /// Access point name, unique within the account and region.
+    pub name: String,
+    /// Name of the bucket the access point fronts.
+    pub bucket: String,
+    /// Account ID the access point was created for, as named in its ARN.
+    pub account_id: String,
+    /// When the access point was created.
+    pub creation_date: DateTime<Utc>,
+}
+
 /// Top-level S3 service state holding all buckets.
 ///
 /// Bucket names are globally unique across accounts, enforced by
@@ -25,6 +106,8 @@ pub struct S3ServiceState {
     buckets: DashMap<String, S3Bucket>,
     /// Bucket name to account-ID mapping (enforces global uniqueness).
     global_bucket_owner: DashMap<String, String>,
+    /// Access point name to `AccessPoint` mapping.
+    access_points: DashMap<String, AccessPoint>,
 }
 
 impl std::fmt::Debug for S3ServiceState {
@@ -48,6 +131,7 @@ impl S3ServiceState {
         Self {
             buckets: DashMap::new(),
             global_bucket_owner: DashMap::new(),
+            access_points: DashMap::new(),
         }
     }
 
@@ -67,21 +151,23 @@ impl S3ServiceState {
     ) -> Result<(), S3ServiceError> {
         let account_id = owner.id.clone();
 
-        // Check global uniqueness.
-        if let Some(existing_owner) = self.global_bucket_owner.get(&name) {
-            if *existing_owner == account_id {
-                return Err(S3ServiceError::BucketAlreadyOwnedByYou { bucket: name });
+        // Claim the name and insert the bucket under the same entry lock, so
+        // exactly one of several concurrent creates succeeds.
+        match self.global_bucket_owner.entry(name.clone()) {
+            Entry::Occupied(existing_owner) => {
+                if *existing_owner.get() == account_id {
+                    return Err(S3ServiceError::BucketAlreadyOwnedByYou { bucket: name });
+                }
+                Err(S3ServiceError::BucketAlreadyExists { bucket: name })
+            }
+            Entry::Vacant(slot) => {
+                let bucket = S3Bucket::new(name.clone(), region, owner);
+                self.buckets.insert(name.clone(), bucket);
+                slot.insert(account_id);
+                info!(bucket = %name, "bucket created");
+                Ok(())
             }
-            return Err(S3ServiceError::BucketAlreadyExists { bucket: name });
         }
-
-        // Insert into both maps.
-        let bucket = S3Bucket::new(name.clone(), region, owner);
-        self.buckets.insert(name.clone(), bucket);
-        self.global_bucket_owner.insert(name.clone(), account_id);
-
-        info!(bucket = %name, "bucket created");
-        Ok(())
     }
 
     /// Delete a bucket.
@@ -89,27 +175,33 @@ impl S3ServiceState {
     /// # Errors
     ///
     /// - [`S3ServiceError::NoSuchBucket`] if the bucket does not exist.
-    /// - [`S3ServiceError::BucketNotEmpty`] if the bucket still contains objects or in-progress
-    ///   multipart uploads.
+    /// - [`S3ServiceError::BucketNotEmpty`] if the bucket still contains objects, noncurrent
+    ///   versions, delete markers, or in-progress multipart uploads.
     pub fn delete_bucket(&self, name: &str) -> Result<(), S3ServiceError> {
-        let bucket_ref = self
-            .buckets
-            .get(name)
-            .ok_or_else(|| S3ServiceError::NoSuchBucket {
+        let Entry::Occupied(owner) = self.global_bucket_owner.entry(name.to_owned()) else {
+            return Err(S3ServiceError::NoSuchBucket {
                 bucket: name.to_owned(),
-            })?;
+            });
+        };
 
-        if !bucket_ref.is_empty() {
-            return Err(S3ServiceError::BucketNotEmpty {
-                bucket: name.to_owned(),
+        // Check emptiness under the bucket's write lock: an object stored
+        // concurrently either lands before the check or finds the bucket gone.
+        if self
+            .buckets
+            .remove_if(name, |_, bucket| bucket.is_empty())
+            .is_none()
+        {
+            return Err(if self.buckets.contains_key(name) {
+                S3ServiceError::BucketNotEmpty {
+                    bucket: name.to_owned(),
+                }
+            } else {
+                S3ServiceError::NoSuchBucket {
+                    bucket: name.to_owned(),
+                }
             });
         }
-
-        // Drop the read reference before removing.
-        drop(bucket_ref);
-
-        self.buckets.remove(name);
-        self.global_bucket_owner.remove(name);
+        owner.remove();
 
         info!(bucket = %name, "bucket deleted");
         Ok(())
@@ -156,17 +248,162 @@ impl S3ServiceState {
         buckets
     }
 
+    /// Collect per-bucket storage usage, sorted by bucket name.
+    #[must_use]
+    pub fn bucket_stats(&self) -> Vec<BucketStats> {
+        let mut stats: Vec<BucketStats> = self
+            .buckets
+            .iter()
+            .map(|entry| {
+                let objects = entry.value().objects.read();
+                BucketStats {
+                    name: entry.key().clone(),
+                    object_count: objects.len() as u64,
+                    stored_bytes: objects.stored_bytes(),
+                    multipart_upload_count: entry.value().multipart_uploads.len() as u64,
+                }
+            })
+            .collect();
+        stats.sort_by(|a, b| a.name.cmp(&b.name));
+        stats
+    }
+
+    /// Export a manifest of `bucket`: one [`ObjectRecord`] per current object
+    /// in key order, or per version and delete marker with `all_versions`.
+    ///
+    /// This bypasses `ListObjectsV2` pagination and XML serialization for
+    /// verifying large buckets in tests. Only listing fields are copied;
+    /// object bodies and metadata are never cloned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`S3ServiceError::NoSuchBucket`] if the bucket does not exist.
+    pub fn export_manifest(
+        &self,
+        bucket: &str,
+        all_versions: bool,
+    ) -> Result<ManifestExport<'_>, S3ServiceError> {
+        if !self.bucket_exists(bucket) {
+            return Err(S3ServiceError::NoSuchBucket {
+                bucket: bucket.to_owned(),
+            });
+        }
+        Ok(ManifestExport {
+            state: self,
+            bucket: bucket.to_owned(),
+            all_versions,
+            next_key: None,
+            exhausted: false,
+            buffered: Vec::new().into_iter(),
+        })
+    }
+
+    /// Remove the multipart uploads that bucket lifecycle rules abort as of
+    /// `now`, returning `(bucket, upload_id)` for each.
+    ///
+    /// See [`S3Bucket::remove_expired_uploads`].
+    #[must_use]
+    pub fn remove_expired_uploads(&self, now: DateTime<Utc>) -> Vec<(String, String)> {
+        self.buckets
+            .iter()
+            .flat_map(|entry| {
+                entry
+                    .value()
+                    .remove_expired_uploads(now)
+                    .into_iter()
+                    .map(|upload_id| (entry.key().clone(), upload_id))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
     /// Check whether a bucket exists.
     #[must_use]
     pub fn bucket_exists(&self, name: &str) -> bool {
         self.buckets.contains_key(name)
     }
 
-    /// Reset all state, removing all buckets.
+    /// Create an access point named `name` for `bucket`, owned by
+    /// `account_id`.
+    ///
+    /// # Errors
+    ///
+    /// - [`S3ServiceError::NoSuchBucket`] if the bucket does not exist.
+    /// - [`S3ServiceError::AccessPointAlreadyOwnedByYou`] if an access point with the same name
+    ///   already exists.
+    pub fn create_access_point(
+        &self,
+        name: String,
+        bucket: String,
+        account_id: String,
+    ) -> Result<AccessPoint, S3ServiceError> {
+        if !self.bucket_exists(&bucket) {
+            return Err(S3ServiceError::NoSuchBucket { bucket });
+        }
+        match self.access_points.entry(name.clone()) {
+            Entry::Occupied(_) => Err(S3ServiceError::AccessPointAlreadyOwnedByYou { name }),
+            Entry::Vacant(entry) => {
+                let access_point = AccessPoint {
+                    name: name.clone(),
+                    bucket,
+                    account_id,
+                    creation_date: Utc::now(),
+                };
+                entry.insert(access_point.clone());
+                info!(access_point = %name, bucket = %access_point.bucket, "access point created");
+                Ok(access_point)
+            }
+        }
+    }
+
+    /// Look up an access point by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`S3ServiceError::NoSuchAccessPoint`] if the access point does not exist.
+    pub fn access_point(&self, name: &str) -> Result<AccessPoint, S3ServiceError> {
+        self.access_points
+            .get(name)
+            .map(|entry| entry.value().clone())
+            .ok_or_else(|| S3ServiceError::NoSuchAccessPoint {
+                name: name.to_owned(),
+            })
+    }
+
+    /// Delete an access point. The bucket it fronts is left untouched.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`S3ServiceError::NoSuchAccessPoint`] if the access point does not exist.
+    pub fn delete_access_point(&self, name: &str) -> Result<(), S3ServiceError> {
+        self.access_points
+            .remove(name)
+            .ok_or_else(|| S3ServiceError::NoSuchAccessPoint {
+                name: name.to_owned(),
+            })?;
+        info!(access_point = %name, "access point deleted");
+        Ok(())
+    }
+
+    /// List access points sorted by name, optionally only those for `bucket`.
+    #[must_use]
+    pub fn list_access_points(&self, bucket: Option<&str>) -> Vec<AccessPoint> {
+        let mut access_points: Vec<AccessPoint> = self
+            .access_points
+            .iter()
+            .filter(|entry| bucket.is_none_or(|b| entry.value().bucket == b))
+            .map(|entry| entry.value().clone())
+            .collect();
+        access_points.sort_by(|a, b| a.name.cmp(&b.name));
+        access_points
+    }
+
+    /// Reset all state, removing all buckets and access points.
     pub fn reset(&self) {
         debug!("resetting all S3 service state");
         self.buckets.clear();
         self.global_bucket_owner.clear();
+        self.access_points.clear();
     }
 }
 
@@ -302,6 +539,7 @@ mod tests {
                 checksum: None,
                 parts_count: None,
                 part_etags: Vec::new(),
+                parts: Vec::new(),
             };
             bucket.objects.write().put(obj);
         }
@@ -391,6 +629,57 @@ mod tests {
         assert!(!state.bucket_exists("b"));
     }
 
+    #[test]
+    fn test_should_manage_access_points() {
+        const ACCOUNT: &str = "123456789012";
+        let state = S3ServiceState::new();
+        for name in ["data", "logs"] {
+            state
+                .create_bucket(name.to_owned(), "us-east-1".to_owned(), default_owner())
+                .unwrap_or_else(|e| panic!("create {name} failed: {e}"));
+        }
+        for (name, bucket) in [("logs-ap", "logs"), ("data-b", "data"), ("data-a", "data")] {
+            state
+                .create_access_point(name.to_owned(), bucket.to_owned(), ACCOUNT.to_owned())
+                .unwrap_or_else(|e| panic!("create {name} failed: {e}"));
+        }
+
+        assert!(matches!(
+            state.create_access_point("data-a".to_owned(), "logs".to_owned(), ACCOUNT.to_owned()),
+            Err(S3ServiceError::AccessPointAlreadyOwnedByYou { .. })
+        ));
+        assert!(matches!(
+            state.create_access_point("other".to_owned(), "missing".to_owned(), ACCOUNT.to_owned()),
+            Err(S3ServiceError::NoSuchBucket { .. })
+        ));
+        assert_eq!(
+            state.access_point("data-a").map(|ap| ap.bucket).ok(),
+            Some("data".to_owned())
+        );
+
+        let names = |bucket| -> Vec<String> {
+            state
+                .list_access_points(bucket)
+                .into_iter()
+                .map(|ap| ap.name)
+                .collect()
+        };
+        assert_eq!(names(None), vec!["data-a", "data-b", "logs-ap"]);
+        assert_eq!(names(Some("data")), vec!["data-a", "data-b"]);
+
+        state
+            .delete_access_point("data-a")
+            .unwrap_or_else(|e| panic!("delete failed: {e}"));
+        assert!(matches!(
+            state.access_point("data-a"),
+            Err(S3ServiceError::NoSuchAccessPoint { .. })
+        ));
+        assert!(state.bucket_exists("data"));
+
+        state.reset();
+        assert!(state.list_access_points(None).is_empty());
+    }
+
     #[test]
     fn test_should_recreate_bucket_after_delete() {
         let state = S3ServiceState::new();
@@ -412,6 +701,101 @@ mod tests {
         assert_eq!(bucket.region, "eu-west-1");
     }
 
+    #[test]
+    fn test_should_report_bucket_stats() {
+        use crate::state::object::{ObjectMetadata, S3Object};
+
+        let state = S3ServiceState::new();
+        for name in ["stats-b", "stats-a"] {
+            state
+                .create_bucket(name.to_owned(), "us-east-1".to_owned(), default_owner())
+                .unwrap_or_else(|e| panic!("create {name} failed: {e}"));
+        }
+        {
+            let bucket = state
+                .get_bucket("stats-a")
+                .unwrap_or_else(|e| panic!("get failed: {e}"));
+            bucket.objects.write().put(S3Object {
+                key: "file.txt".to_owned(),
+                version_id: "null".to_owned(),
+                etag: "\"abc\"".to_owned(),
+                size: 42,
+                last_modified: chrono::Utc::now(),
+                storage_class: "STANDARD".to_owned(),
+                metadata: ObjectMetadata::default(),
+                owner: default_owner(),
+                checksum: None,
+                parts_count: None,
+                part_etags: Vec::new(),
+                parts: Vec::new(),
+            });
+        }
+
+        let usage = state.bucket_stats();
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0].name, "stats-a");
+        assert_eq!(usage[0].object_count, 1);
+        assert_eq!(usage[0].stored_bytes, 42);
+        assert_eq!(usage[1].object_count, 0);
+        assert_eq!(usage[1].multipart_upload_count, 0);
+    }
+
+    #[test]
+    fn test_should_export_manifest_across_pages() {
+        use crate::state::object::{ObjectMetadata, S3Object};
+
+        let state = S3ServiceState::new();
+        state
+            .create_bucket("big".to_owned(), "us-east-1".to_owned(), default_owner())
+            .unwrap_or_else(|e| panic!("create failed: {e}"));
+        let total = EXPORT_PAGE_KEYS * 2 + 5;
+        {
+            let bucket = state
+                .get_bucket("big")
+                .unwrap_or_else(|e| panic!("get failed: {e}"));
+            let mut objects = bucket.objects.write();
+            for i in 0..total {
+                objects.put(S3Object {
+                    key: format!("key-{i:05}"),
+                    version_id: "null".to_owned(),
+                    etag: "\"abc\"".to_owned(),
+                    size: i as u64,
+                    last_modified: chrono::Utc::now(),
+                    storage_class: "STANDARD".to_owned(),
+                    metadata: ObjectMetadata::default(),
+                    owner: default_owner(),
+                    checksum: None,
+                    parts_count: None,
+                    part_etags: Vec::new(),
+                    parts: Vec::new(),
+                });
+            }
+        }
+
+        let mut export = state
+            .export_manifest("big", false)
+            .unwrap_or_else(|e| panic!("export failed: {e}"));
+        let first = export.next().map(|r| r.key);
+        assert_eq!(first.as_deref(), Some("key-00000"));
+        // The object store is not locked between batches.
+        drop(
+            state
+                .get_bucket("big")
+                .unwrap_or_else(|e| panic!("get failed: {e}"))
+                .objects
+                .write(),
+        );
+        let records: Vec<ObjectRecord> = export.collect();
+        assert_eq!(records.len(), total - 1);
+        assert!(records.windows(2).all(|w| w[0].key < w[1].key));
+        assert_eq!(records.last().map(|r| r.size), Some(total as u64 - 1));
+
+        assert!(matches!(
+            state.export_manifest("missing", false),
+            Err(S3ServiceError::NoSuchBucket { .. })
+        ));
+    }
+
     #[test]
     fn test_should_use_default_trait() {
         let state = S3ServiceState::default();
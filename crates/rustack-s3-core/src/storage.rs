@@ -9,18 +9,49 @@
 //! # Spillover to Disk
 //!
 //! When object data exceeds [`InMemoryStorage::max_memory_size`], the bytes
-//! are written to a temporary file via the [`tempfile`] crate. On-disk data
-//! is automatically cleaned up when the entry is removed from the map (via
-//! the [`Drop`] implementation on [`StoredData`]).
-
-use std::path::PathBuf;
+//! are written to a temporary file via the [`tempfile`] crate, in the OS temp
+//! directory or the one set with [`InMemoryStorage::with_spillover_dir`].
+//! On-disk data is removed as soon as the entry is removed from the map and
+//! no read of it is in flight (via the [`Drop`] implementation on
+//! [`StoredData`]). The total size of spilled files can be capped with
+//! [`InMemoryStorage::with_max_disk_bytes`]; writes that would exceed the cap
+//! fail with [`S3ServiceError::InsufficientStorage`].
+//!
+//! # Shared Blobs
+//!
+//! Stored bytes are content-addressed by their MD5 digest and size: writing
+//! data identical to a live blob reuses it, and copying an object or a part
+//! only shares the source's blob. Every object version and part holds an
+//! `Arc<Blob>`, so the bytes (and their spillover file) are freed once the
+//! last entry referencing them is removed. Reads clone the handle and
+//! release the map's shard lock before awaiting any disk I/O.
+//!
+//! # Verification
+//!
+//! Every blob records the MD5 digest of the bytes it was written with.
+//! [`InMemoryStorage::verify_object`] reads the bytes back and re-hashes
+//! them, so data changed underneath the storage, such as a damaged
+//! spillover file, is detected rather than served.
+
+use std::{
+    collections::HashSet,
+    fmt,
+    path::PathBuf,
+    sync::{
+        Arc, Weak,
+        atomic::{AtomicU64, Ordering},
+    },
+};
 
 use bytes::{Bytes, BytesMut};
 use dashmap::DashMap;
 use tokio::io::AsyncReadExt as _;
 use tracing::{debug, trace, warn};
 
-use crate::{checksums, error::S3ServiceError};
+use crate::{
+    checksums::{self, ChecksumAlgorithm},
+    error::S3ServiceError,
+};
 
 /// Composite key identifying a stored object: `(bucket, key, version_id)`.
 type StorageKey = (String, String, String);
@@ -28,12 +59,22 @@ type StorageKey = (String, String, String);
 /// Composite key identifying a multipart part: `(bucket, upload_id, part_number)`.
 type PartKey = (String, String, u32);
 
+/// Content address of a blob: `(md5_hex, size)`.
+type BlobKey = (String, u64);
+
+/// Live blobs by content address. Holds weak handles, so the index never
+/// keeps a blob alive.
+type BlobIndex = DashMap<BlobKey, Weak<Blob>>;
+
 /// Default maximum object size (in bytes) kept in memory before spilling to
 /// disk. Objects larger than this threshold are written to temporary files.
 ///
 /// The default is 512 KiB.
 const DEFAULT_MAX_MEMORY_SIZE: usize = 524_288;
 
+/// File name prefix of spillover files.
+const SPILL_FILE_PREFIX: &str = "rustack-s3-";
+
 // ---------------------------------------------------------------------------
 // WriteResult
 // ---------------------------------------------------------------------------
@@ -52,6 +93,76 @@ pub struct WriteResult {
     pub md5_hex: String,
 }
 
+/// The parts of a multipart upload concatenated into an object, not yet
+/// stored under its key.
+pub struct AssembledObject {
+    /// The composite ETag and total size of the object.
+    pub write_result: WriteResult,
+    /// The (unquoted) MD5 hex digest of each part, in order.
+    pub part_md5s: Vec<String>,
+    /// The assembled object bytes.
+    pub data: Bytes,
+    /// The object data as it will be stored.
+    stored: Arc<Blob>,
+}
+
+impl std::fmt::Debug for AssembledObject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AssembledObject")
+            .field("write_result", &self.write_result)
+            .field("part_md5s", &self.part_md5s)
+            .finish_non_exhaustive()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// VerifyResult
+// ---------------------------------------------------------------------------
+
+/// Outcome of re-hashing stored object data with
+/// [`InMemoryStorage::verify_object`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyResult {
+    /// The bytes match every recorded digest.
+    Intact,
+    /// The bytes no longer match a recorded digest.
+    Mismatch {
+        /// The digest that did not match (`MD5` or a checksum algorithm).
+        algorithm: String,
+        /// The digest recorded when the data was written.
+        expected: String,
+        /// The digest of the bytes read back.
+        actual: String,
+    },
+    /// The bytes could not be read back.
+    Unreadable {
+        /// Why reading failed.
+        reason: String,
+    },
+}
+
+impl VerifyResult {
+    /// Whether the data passed verification.
+    #[must_use]
+    pub fn is_intact(&self) -> bool {
+        matches!(self, Self::Intact)
+    }
+}
+
+impl fmt::Display for VerifyResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Intact => f.write_str("intact"),
+            Self::Mismatch {
+                algorithm,
+                expected,
+                actual,
+            } => write!(f, "{algorithm} mismatch: expected {expected}, got {actual}"),
+            Self::Unreadable { reason } => write!(f, "unreadable: {reason}"),
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // StoredData
 // ---------------------------------------------------------------------------
@@ -73,6 +184,8 @@ enum StoredData {
         path: PathBuf,
         /// Size of the stored data in bytes.
         size: u64,
+        /// The storage's count of bytes on disk, released on drop.
+        disk_bytes: Arc<AtomicU64>,
     },
 }
 
@@ -83,7 +196,7 @@ impl std::fmt::Debug for StoredData {
                 .debug_struct("InMemory")
                 .field("size", &data.len())
                 .finish(),
-            Self::OnDisk { path, size } => f
+            Self::OnDisk { path, size, .. } => f
                 .debug_struct("OnDisk")
                 .field("path", path)
                 .field("size", size)
@@ -93,33 +206,45 @@ impl std::fmt::Debug for StoredData {
 }
 
 impl Drop for StoredData {
+    #[allow(clippy::disallowed_methods)]
     fn drop(&mut self) {
-        if let Self::OnDisk { path, .. } = self {
-            let path = path.clone();
-            // Spawn async file removal on the tokio runtime. If no runtime is
-            // active (e.g. during test teardown), the file will be cleaned up
-            // by the OS temp-dir reaper.
-            if let Ok(handle) = tokio::runtime::Handle::try_current() {
-                handle.spawn(async move {
-                    if let Err(e) = tokio::fs::remove_file(&path).await {
-                        if e.kind() != std::io::ErrorKind::NotFound {
-                            warn!(path = %path.display(), error = %e, "failed to remove temp file");
-                        }
-                    } else {
-                        trace!(path = %path.display(), "removed temp file");
-                    }
-                });
+        if let Self::OnDisk {
+            path,
+            size,
+            disk_bytes,
+        } = self
+        {
+            // Removed synchronously: a file left to a spawned task would leak
+            // whenever no runtime is running or it is shutting down.
+            match std::fs::remove_file(&*path) {
+                Ok(()) => trace!(path = %path.display(), "removed temp file"),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => warn!(path = %path.display(), error = %e, "failed to remove temp file"),
             }
+            disk_bytes.fetch_sub(*size, Ordering::Relaxed);
         }
     }
 }
 
 impl StoredData {
+    /// Size of the stored data in bytes.
+    fn size(&self) -> u64 {
+        match self {
+            Self::InMemory { data } => data.len() as u64,
+            Self::OnDisk { size, .. } => *size,
+        }
+    }
+
+    /// Whether the data was spilled to disk.
+    fn is_on_disk(&self) -> bool {
+        matches!(self, Self::OnDisk { .. })
+    }
+
     /// Read the full data from this stored entry.
     async fn read_all(&self) -> Result<Bytes, S3ServiceError> {
         match self {
             Self::InMemory { data } => Ok(data.clone()),
-            Self::OnDisk { path, size } => {
+            Self::OnDisk { path, size, .. } => {
                 let mut file = tokio::fs::File::open(path).await.map_err(|e| {
                     S3ServiceError::Internal(anyhow::anyhow!(
                         "failed to open temp file {}: {e}",
@@ -140,6 +265,86 @@ impl StoredData {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Blob
+// ---------------------------------------------------------------------------
+
+/// Stored bytes shared by every object version and part with the same
+/// content.
+///
+/// Dropping the last handle releases the data and removes the blob from the
+/// index.
+struct Blob {
+    /// The stored bytes.
+    data: StoredData,
+    /// The MD5 hex digest of the bytes.
+    md5_hex: String,
+    /// The index the blob is registered in.
+    index: Arc<BlobIndex>,
+}
+
+impl std::fmt::Debug for Blob {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Blob")
+            .field("data", &self.data)
+            .field("md5_hex", &self.md5_hex)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for Blob {
+    fn drop(&mut self) {
+        let key = (std::mem::take(&mut self.md5_hex), self.data.size());
+        // A blob stored concurrently with the same content may have replaced
+        // this one in the index; that entry is still live.
+        self.index
+            .remove_if(&key, |_, blob| blob.strong_count() == 0);
+    }
+}
+
+impl Blob {
+    /// The [`WriteResult`] of storing this blob's bytes.
+    fn write_result(&self) -> WriteResult {
+        WriteResult {
+            etag: format!("\"{}\"", self.md5_hex),
+            size: self.data.size(),
+            md5_hex: self.md5_hex.clone(),
+        }
+    }
+}
+
+/// Disk bytes reserved for a spillover file not yet created, released if
+/// creating it fails.
+struct DiskReservation<'a> {
+    disk_bytes: &'a AtomicU64,
+    size: u64,
+}
+
+impl Drop for DiskReservation<'_> {
+    fn drop(&mut self) {
+        self.disk_bytes.fetch_sub(self.size, Ordering::Relaxed);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// StorageStats
+// ---------------------------------------------------------------------------
+
+/// Point-in-time usage of the storage backend.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StorageStats {
+    /// Number of stored object versions.
+    pub object_count: u64,
+    /// Number of stored multipart parts.
+    pub part_count: u64,
+    /// Bytes of object and part data kept in memory, counting data shared
+    /// by several entries once.
+    pub memory_bytes: u64,
+    /// Bytes of spillover files on disk, including those of removed entries
+    /// still being read.
+    pub disk_bytes: u64,
+}
+
 // ---------------------------------------------------------------------------
 // InMemoryStorage
 // ---------------------------------------------------------------------------
@@ -173,11 +378,19 @@ impl StoredData {
 /// ```
 pub struct InMemoryStorage {
     /// Object data keyed by `(bucket, key, version_id)`.
-    objects: DashMap<StorageKey, StoredData>,
+    objects: DashMap<StorageKey, Arc<Blob>>,
     /// Multipart part data keyed by `(bucket, upload_id, part_number)`.
-    parts: DashMap<PartKey, StoredData>,
+    parts: DashMap<PartKey, Arc<Blob>>,
+    /// Live blobs by content, for sharing identical data.
+    blobs: Arc<BlobIndex>,
     /// Max size in bytes for in-memory storage before spilling to disk.
     max_memory_size: usize,
+    /// Directory of spillover files; the OS temp directory if unset.
+    spillover_dir: Option<PathBuf>,
+    /// Cap on the total size of spillover files, if any.
+    max_disk_bytes: Option<u64>,
+    /// Total size of spillover files currently on disk.
+    disk_bytes: Arc<AtomicU64>,
 }
 
 impl std::fmt::Debug for InMemoryStorage {
@@ -186,7 +399,9 @@ impl std::fmt::Debug for InMemoryStorage {
             .field("objects_count", &self.objects.len())
             .field("parts_count", &self.parts.len())
             .field("max_memory_size", &self.max_memory_size)
-            .finish()
+            .field("spillover_dir", &self.spillover_dir)
+            .field("max_disk_bytes", &self.max_disk_bytes)
+            .finish_non_exhaustive()
     }
 }
 
@@ -207,10 +422,30 @@ impl InMemoryStorage {
         Self {
             objects: DashMap::new(),
             parts: DashMap::new(),
+            blobs: Arc::new(DashMap::new()),
             max_memory_size,
+            spillover_dir: None,
+            max_disk_bytes: None,
+            disk_bytes: Arc::new(AtomicU64::new(0)),
         }
     }
 
+    /// Write spillover files to `dir` instead of the OS temp directory.
+    ///
+    /// The directory is created on first use.
+    #[must_use]
+    pub fn with_spillover_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.spillover_dir = Some(dir.into());
+        self
+    }
+
+    /// Cap the total size of spillover files at `max_disk_bytes`.
+    #[must_use]
+    pub fn with_max_disk_bytes(mut self, max_disk_bytes: u64) -> Self {
+        self.max_disk_bytes = Some(max_disk_bytes);
+        self
+    }
+
     /// Return the default maximum in-memory object size (512 KiB).
     #[must_use]
     pub fn default_max_memory_size() -> usize {
@@ -224,8 +459,9 @@ impl InMemoryStorage {
     ///
     /// # Errors
     ///
-    /// Returns [`S3ServiceError::Internal`] if the temporary file cannot be
-    /// created or written.
+    /// - [`S3ServiceError::InsufficientStorage`] if spilling the data to disk would exceed the disk
+    ///   cap.
+    /// - [`S3ServiceError::Internal`] if the temporary file cannot be created or written.
     pub async fn write_object(
         &self,
         bucket: &str,
@@ -234,22 +470,22 @@ impl InMemoryStorage {
         data: Bytes,
     ) -> Result<WriteResult, S3ServiceError> {
         let md5_hex = checksums::compute_md5(&data);
-        let etag = format!("\"{md5_hex}\"");
-        let size = data.len() as u64;
-
-        let stored = self.store_data(data).await?;
+        let stored = self.store_data(data, md5_hex).await?;
+        let result = stored.write_result();
 
-        trace!(bucket, key, version_id, size, "stored object data");
+        trace!(
+            bucket,
+            key,
+            version_id,
+            size = result.size,
+            "stored object data"
+        );
         self.objects.insert(
             (bucket.to_owned(), key.to_owned(), version_id.to_owned()),
             stored,
         );
 
-        Ok(WriteResult {
-            etag,
-            size,
-            md5_hex,
-        })
+        Ok(result)
     }
 
     /// Read object data. Returns the full [`Bytes`] for the object.
@@ -273,11 +509,12 @@ impl InMemoryStorage {
         let entry = self
             .objects
             .get(&storage_key)
+            .map(|entry| Arc::clone(entry.value()))
             .ok_or_else(|| S3ServiceError::NoSuchKey {
                 key: key.to_owned(),
             })?;
 
-        let all_data = entry.value().read_all().await?;
+        let all_data = entry.data.read_all().await?;
 
         match range {
             Some((start, end)) => {
@@ -293,16 +530,60 @@ impl InMemoryStorage {
         }
     }
 
+    /// Read an object's data back and check it against the MD5 digest
+    /// recorded when it was written and, if given, its additional
+    /// `checksum` as `(algorithm, base64 value)`.
+    ///
+    /// # Errors
+    ///
+    /// - [`S3ServiceError::NoSuchKey`] if the object is not found.
+    pub async fn verify_object(
+        &self,
+        bucket: &str,
+        key: &str,
+        version_id: &str,
+        checksum: Option<(ChecksumAlgorithm, &str)>,
+    ) -> Result<VerifyResult, S3ServiceError> {
+        let stored = self.object_blob(bucket, key, version_id)?;
+        let data = match stored.data.read_all().await {
+            Ok(data) => data,
+            Err(e) => {
+                return Ok(VerifyResult::Unreadable {
+                    reason: e.to_string(),
+                });
+            }
+        };
+
+        let md5_hex = checksums::compute_md5(&data);
+        if md5_hex != stored.md5_hex {
+            return Ok(VerifyResult::Mismatch {
+                algorithm: "MD5".to_owned(),
+                expected: stored.md5_hex.clone(),
+                actual: md5_hex,
+            });
+        }
+        if let Some((algorithm, expected)) = checksum {
+            let actual = checksums::compute_checksum(algorithm, &data);
+            if actual != expected {
+                return Ok(VerifyResult::Mismatch {
+                    algorithm: algorithm.as_str().to_owned(),
+                    expected: expected.to_owned(),
+                    actual,
+                });
+            }
+        }
+        Ok(VerifyResult::Intact)
+    }
+
     /// Copy object data from one location to another.
     ///
-    /// Reads the source object data, then writes it to the destination
-    /// location. Returns a [`WriteResult`] for the destination object.
+    /// The destination shares the source's data, so no bytes are read or
+    /// written. Returns a [`WriteResult`] for the destination object.
     ///
     /// # Errors
     ///
     /// - [`S3ServiceError::NoSuchKey`] if the source object is not found.
-    /// - [`S3ServiceError::Internal`] if disk I/O fails.
-    pub async fn copy_object(
+    pub fn copy_object(
         &self,
         src_bucket: &str,
         src_key: &str,
@@ -311,9 +592,8 @@ impl InMemoryStorage {
         dst_key: &str,
         dst_version_id: &str,
     ) -> Result<WriteResult, S3ServiceError> {
-        let data = self
-            .read_object(src_bucket, src_key, src_version_id, None)
-            .await?;
+        let stored = self.object_blob(src_bucket, src_key, src_version_id)?;
+        let result = stored.write_result();
 
         debug!(
             src_bucket,
@@ -322,12 +602,19 @@ impl InMemoryStorage {
             dst_bucket,
             dst_key,
             dst_version_id,
-            size = data.len(),
+            size = result.size,
             "copying object data"
         );
 
-        self.write_object(dst_bucket, dst_key, dst_version_id, data)
-            .await
+        self.objects.insert(
+            (
+                dst_bucket.to_owned(),
+                dst_key.to_owned(),
+                dst_version_id.to_owned(),
+            ),
+            stored,
+        );
+        Ok(result)
     }
 
     /// Delete object data.
@@ -349,8 +636,9 @@ impl InMemoryStorage {
     ///
     /// # Errors
     ///
-    /// Returns [`S3ServiceError::Internal`] if the temporary file cannot be
-    /// created or written.
+    /// - [`S3ServiceError::InsufficientStorage`] if spilling the data to disk would exceed the disk
+    ///   cap.
+    /// - [`S3ServiceError::Internal`] if the temporary file cannot be created or written.
     pub async fn write_part(
         &self,
         bucket: &str,
@@ -359,22 +647,58 @@ impl InMemoryStorage {
         data: Bytes,
     ) -> Result<WriteResult, S3ServiceError> {
         let md5_hex = checksums::compute_md5(&data);
-        let etag = format!("\"{md5_hex}\"");
-        let size = data.len() as u64;
+        let stored = self.store_data(data, md5_hex).await?;
+        let result = stored.write_result();
 
-        let stored = self.store_data(data).await?;
-
-        trace!(bucket, upload_id, part_number, size, "stored part data");
+        trace!(
+            bucket,
+            upload_id,
+            part_number,
+            size = result.size,
+            "stored part data"
+        );
         self.parts.insert(
             (bucket.to_owned(), upload_id.to_owned(), part_number),
             stored,
         );
 
-        Ok(WriteResult {
-            etag,
-            size,
-            md5_hex,
-        })
+        Ok(result)
+    }
+
+    /// Store a whole object as a multipart part.
+    ///
+    /// The part shares the source's data, so no bytes are read or written.
+    ///
+    /// # Errors
+    ///
+    /// - [`S3ServiceError::NoSuchKey`] if the source object is not found.
+    pub fn copy_part(
+        &self,
+        src_bucket: &str,
+        src_key: &str,
+        src_version_id: &str,
+        bucket: &str,
+        upload_id: &str,
+        part_number: u32,
+    ) -> Result<WriteResult, S3ServiceError> {
+        let stored = self.object_blob(src_bucket, src_key, src_version_id)?;
+        let result = stored.write_result();
+
+        trace!(
+            src_bucket,
+            src_key,
+            src_version_id,
+            bucket,
+            upload_id,
+            part_number,
+            size = result.size,
+            "copied object data to part"
+        );
+        self.parts.insert(
+            (bucket.to_owned(), upload_id.to_owned(), part_number),
+            stored,
+        );
+        Ok(result)
     }
 
     /// Read a multipart part's data.
@@ -393,9 +717,10 @@ impl InMemoryStorage {
         let entry = self
             .parts
             .get(&part_key)
+            .map(|entry| Arc::clone(entry.value()))
             .ok_or(S3ServiceError::InvalidPart)?;
 
-        entry.value().read_all().await
+        entry.data.read_all().await
     }
 
     /// Assemble parts into a final object. Concatenates part data in order.
@@ -417,27 +742,86 @@ impl InMemoryStorage {
         version_id: &str,
         part_numbers: &[u32],
     ) -> Result<(WriteResult, Vec<String>), S3ServiceError> {
+        let assembled = self
+            .assemble_multipart(bucket, upload_id, part_numbers)
+            .await?;
+        let result = (assembled.write_result.clone(), assembled.part_md5s.clone());
+        self.commit_multipart(bucket, upload_id, key, version_id, assembled);
+        Ok(result)
+    }
+
+    /// Concatenate the parts of an upload, in order, without storing the
+    /// result.
+    ///
+    /// The returned [`AssembledObject`] is stored under its final key by
+    /// [`commit_multipart`](Self::commit_multipart). Splitting the two lets a
+    /// caller commit the data and the object's metadata under one lock, so
+    /// concurrent completions of the same key cannot pair one upload's data
+    /// with another's metadata.
+    ///
+    /// # Errors
+    ///
+    /// - [`S3ServiceError::InvalidPart`] if any requested part does not exist.
+    /// - [`S3ServiceError::Internal`] if disk I/O fails.
+    pub async fn assemble_multipart(
+        &self,
+        bucket: &str,
+        upload_id: &str,
+        part_numbers: &[u32],
+    ) -> Result<AssembledObject, S3ServiceError> {
         let mut combined = BytesMut::new();
-        let mut part_md5_hexes = Vec::with_capacity(part_numbers.len());
+        let mut part_md5s = Vec::with_capacity(part_numbers.len());
 
         for &part_number in part_numbers {
             let part_data = self.read_part(bucket, upload_id, part_number).await?;
-            let md5_hex = checksums::compute_md5(&part_data);
-            part_md5_hexes.push(md5_hex);
+            part_md5s.push(checksums::compute_md5(&part_data));
             combined.extend_from_slice(&part_data);
         }
 
-        let combined_bytes = combined.freeze();
-        let size = combined_bytes.len() as u64;
+        let data = combined.freeze();
+        let size = data.len() as u64;
 
         // Compute composite ETag: MD5-of-concatenated-MD5s with part count suffix.
-        let etag = checksums::compute_multipart_etag(&part_md5_hexes, part_numbers.len());
+        let etag = checksums::compute_multipart_etag(&part_md5s, part_numbers.len());
 
-        // Store the assembled object.
-        let stored = self.store_data(combined_bytes).await?;
+        // The md5_hex for the composite result is the hash portion of the ETag
+        // (without quotes and without the -N suffix).
+        let composite_md5 = etag
+            .trim_matches('"')
+            .split('-')
+            .next()
+            .unwrap_or_default()
+            .to_owned();
+
+        let stored = self
+            .store_data(data.clone(), checksums::compute_md5(&data))
+            .await?;
+
+        Ok(AssembledObject {
+            write_result: WriteResult {
+                etag,
+                size,
+                md5_hex: composite_md5,
+            },
+            part_md5s,
+            data,
+            stored,
+        })
+    }
+
+    /// Store an assembled upload under its final key and remove the
+    /// upload's parts.
+    pub fn commit_multipart(
+        &self,
+        bucket: &str,
+        upload_id: &str,
+        key: &str,
+        version_id: &str,
+        assembled: AssembledObject,
+    ) {
         self.objects.insert(
             (bucket.to_owned(), key.to_owned(), version_id.to_owned()),
-            stored,
+            assembled.stored,
         );
 
         // Clean up the parts for this upload.
@@ -448,28 +832,10 @@ impl InMemoryStorage {
             upload_id,
             key,
             version_id,
-            size,
-            parts = part_numbers.len(),
+            size = assembled.write_result.size,
+            parts = assembled.part_md5s.len(),
             "completed multipart upload"
         );
-
-        // The md5_hex for the composite result is the hash portion of the ETag
-        // (without quotes and without the -N suffix).
-        let composite_md5 = etag
-            .trim_matches('"')
-            .split('-')
-            .next()
-            .unwrap_or_default()
-            .to_owned();
-
-        Ok((
-            WriteResult {
-                etag,
-                size,
-                md5_hex: composite_md5,
-            },
-            part_md5_hexes,
-        ))
     }
 
     /// Delete all parts for a multipart upload.
@@ -486,6 +852,49 @@ impl InMemoryStorage {
         });
     }
 
+    /// Collect the current usage of the storage.
+    #[must_use]
+    pub fn stats(&self) -> StorageStats {
+        // Blobs are compared by address rather than upgraded from the index:
+        // dropping an upgraded handle inside an index shard lock could free
+        // the blob and deadlock on its removal from that shard.
+        let mut seen = HashSet::new();
+        let mut in_memory = |stored: &Arc<Blob>| {
+            if stored.data.is_on_disk() || !seen.insert(Arc::as_ptr(stored)) {
+                0
+            } else {
+                stored.data.size()
+            }
+        };
+        let object_bytes: u64 = self
+            .objects
+            .iter()
+            .map(|entry| in_memory(entry.value()))
+            .sum();
+        let part_bytes: u64 = self
+            .parts
+            .iter()
+            .map(|entry| in_memory(entry.value()))
+            .sum();
+        let memory_bytes = object_bytes + part_bytes;
+        StorageStats {
+            object_count: self.objects.len() as u64,
+            part_count: self.parts.len() as u64,
+            memory_bytes,
+            disk_bytes: self.disk_bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Total bytes of multipart part data currently stored, in memory or
+    /// spilled to disk.
+    #[must_use]
+    pub fn part_bytes(&self) -> u64 {
+        self.parts
+            .iter()
+            .map(|entry| entry.value().data.size())
+            .sum()
+    }
+
     /// Delete all data (objects and parts) for a bucket.
     ///
     /// This removes both object data and any in-progress multipart part data
@@ -516,36 +925,98 @@ impl InMemoryStorage {
     // Private helpers
     // -----------------------------------------------------------------------
 
-    /// Store data either in memory or on disk, depending on size.
-    async fn store_data(&self, data: Bytes) -> Result<StoredData, S3ServiceError> {
-        if data.len() > self.max_memory_size {
-            self.spill_to_disk(&data).await
-        } else {
-            Ok(StoredData::InMemory { data })
+    /// The blob of an object version.
+    fn object_blob(
+        &self,
+        bucket: &str,
+        key: &str,
+        version_id: &str,
+    ) -> Result<Arc<Blob>, S3ServiceError> {
+        let storage_key = (bucket.to_owned(), key.to_owned(), version_id.to_owned());
+        self.objects
+            .get(&storage_key)
+            .map(|entry| Arc::clone(entry.value()))
+            .ok_or_else(|| S3ServiceError::NoSuchKey {
+                key: key.to_owned(),
+            })
+    }
+
+    /// Store data either in memory or on disk, depending on size, sharing
+    /// the blob of identical data already stored.
+    async fn store_data(&self, data: Bytes, md5_hex: String) -> Result<Arc<Blob>, S3ServiceError> {
+        let key = (md5_hex, data.len() as u64);
+        // The upgraded handle is only dropped after the shard lock is
+        // released; see `Blob::drop`.
+        let existing = self.blobs.get(&key).and_then(|blob| blob.upgrade());
+        if let Some(existing) = existing {
+            // Compare the bytes as well, so colliding digests are never
+            // shared.
+            if existing.data.read_all().await? == data {
+                trace!(md5 = %key.0, size = key.1, "sharing stored data");
+                return Ok(existing);
+            }
+            warn!(md5 = %key.0, size = key.1, "stored data with the same digest differs");
+            return self.new_blob(data, key.0).await;
         }
+
+        let blob = self.new_blob(data, key.0.clone()).await?;
+        self.blobs.insert(key, Arc::downgrade(&blob));
+        Ok(blob)
+    }
+
+    /// Store data in a new blob, not yet registered in the index.
+    async fn new_blob(&self, data: Bytes, md5_hex: String) -> Result<Arc<Blob>, S3ServiceError> {
+        let data = if data.len() > self.max_memory_size {
+            self.spill_to_disk(&data).await?
+        } else {
+            StoredData::InMemory { data }
+        };
+        Ok(Arc::new(Blob {
+            data,
+            md5_hex,
+            index: Arc::clone(&self.blobs),
+        }))
     }
 
     /// Write data to a temporary file and return an [`StoredData::OnDisk`].
     async fn spill_to_disk(&self, data: &[u8]) -> Result<StoredData, S3ServiceError> {
         let size = data.len() as u64;
+        self.reserve_disk_bytes(size)?;
+        let reservation = DiskReservation {
+            disk_bytes: &self.disk_bytes,
+            size,
+        };
 
-        // Create the temp file synchronously (tempfile::NamedTempFile uses
-        // the OS temp directory) then persist it so it is not deleted when
-        // the NamedTempFile handle is dropped -- we manage cleanup in Drop.
-        let temp = tempfile::NamedTempFile::new().map_err(|e| {
+        let mut builder = tempfile::Builder::new();
+        builder.prefix(SPILL_FILE_PREFIX);
+        let temp = match &self.spillover_dir {
+            Some(dir) => {
+                tokio::fs::create_dir_all(dir).await.map_err(|e| {
+                    S3ServiceError::Internal(anyhow::anyhow!(
+                        "failed to create spillover directory {}: {e}",
+                        dir.display()
+                    ))
+                })?;
+                builder.tempfile_in(dir)
+            }
+            None => builder.tempfile(),
+        }
+        .map_err(|e| {
             S3ServiceError::Internal(anyhow::anyhow!("failed to create temp file: {e}"))
         })?;
-        let path = temp.path().to_path_buf();
 
-        // Persist the named temp file so it is not auto-deleted.
-        temp.persist(&path).map_err(|e| {
-            S3ServiceError::Internal(anyhow::anyhow!(
-                "failed to persist temp file {}: {e}",
-                path.display()
-            ))
+        // Keep the file past the handle; the returned entry removes it on
+        // drop, including when the write below fails.
+        let path = temp.into_temp_path().keep().map_err(|e| {
+            S3ServiceError::Internal(anyhow::anyhow!("failed to keep temp file: {e}"))
         })?;
+        std::mem::forget(reservation);
+        let stored = StoredData::OnDisk {
+            path: path.clone(),
+            size,
+            disk_bytes: Arc::clone(&self.disk_bytes),
+        };
 
-        // Write data asynchronously.
         tokio::fs::write(&path, data).await.map_err(|e| {
             S3ServiceError::Internal(anyhow::anyhow!(
                 "failed to write temp file {}: {e}",
@@ -554,7 +1025,28 @@ impl InMemoryStorage {
         })?;
 
         trace!(path = %path.display(), size, "spilled data to disk");
-        Ok(StoredData::OnDisk { path, size })
+        Ok(stored)
+    }
+
+    /// Count `size` more bytes as on disk, failing if that exceeds the cap.
+    fn reserve_disk_bytes(&self, size: u64) -> Result<(), S3ServiceError> {
+        let reserved =
+            self.disk_bytes
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                    let total = current.checked_add(size)?;
+                    self.max_disk_bytes
+                        .is_none_or(|max| total <= max)
+                        .then_some(total)
+                });
+        if reserved.is_err() {
+            warn!(
+                size,
+                max_disk_bytes = self.max_disk_bytes,
+                "spillover disk cap reached"
+            );
+            return Err(S3ServiceError::InsufficientStorage);
+        }
+        Ok(())
     }
 
     /// Remove all part entries whose bucket matches `bucket`.
@@ -582,7 +1074,12 @@ mod tests {
     }
 
     fn large_data() -> Bytes {
-        Bytes::from(vec![0xAB_u8; TEST_THRESHOLD + 1])
+        large_data_filled(0xAB)
+    }
+
+    /// Data just over the threshold, distinct for each `fill`.
+    fn large_data_filled(fill: u8) -> Bytes {
+        Bytes::from(vec![fill; TEST_THRESHOLD + 1])
     }
 
     // -----------------------------------------------------------------------
@@ -704,7 +1201,6 @@ mod tests {
                 "dst-key",
                 "v1",
             )
-            .await
             .unwrap_or_else(|e| panic!("copy failed: {e}"));
         assert_eq!(wr.size, data.len() as u64);
 
@@ -725,9 +1221,7 @@ mod tests {
     #[tokio::test]
     async fn test_should_return_error_on_copy_nonexistent_source() {
         let storage = InMemoryStorage::new(TEST_THRESHOLD);
-        let result = storage
-            .copy_object("bucket", "missing", "null", "dst", "key", "null")
-            .await;
+        let result = storage.copy_object("bucket", "missing", "null", "dst", "key", "null");
         assert!(matches!(result, Err(S3ServiceError::NoSuchKey { .. })));
     }
 
@@ -868,8 +1362,10 @@ mod tests {
             .write_part("bucket", "upload-2", 1, Bytes::from("c"))
             .await
             .unwrap_or_else(|e| panic!("write part for upload-2 failed: {e}"));
+        assert_eq!(storage.part_bytes(), 3);
 
         storage.abort_multipart("bucket", "upload-1");
+        assert_eq!(storage.part_bytes(), 1);
 
         // upload-1 parts should be gone.
         assert!(matches!(
@@ -994,23 +1490,101 @@ mod tests {
     // On-disk cleanup on overwrite
     // -----------------------------------------------------------------------
 
+    #[allow(clippy::disallowed_methods)]
+    fn spilled_files(dir: &std::path::Path) -> usize {
+        std::fs::read_dir(dir).map_or(0, Iterator::count)
+    }
+
+    /// Overwrite the first byte of every spillover file in `dir`.
+    async fn corrupt_spilled_files(dir: &std::path::Path) {
+        let mut entries = tokio::fs::read_dir(dir)
+            .await
+            .unwrap_or_else(|e| panic!("read_dir failed: {e}"));
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .unwrap_or_else(|e| panic!("dir entry failed: {e}"))
+        {
+            let path = entry.path();
+            let mut data = tokio::fs::read(&path)
+                .await
+                .unwrap_or_else(|e| panic!("read failed: {e}"));
+            data[0] ^= 0xff;
+            tokio::fs::write(&path, data)
+                .await
+                .unwrap_or_else(|e| panic!("write failed: {e}"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_should_verify_stored_data_against_digests() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("tempdir failed: {e}"));
+        let storage = InMemoryStorage::new(TEST_THRESHOLD).with_spillover_dir(dir.path());
+        let data = large_data();
+        storage
+            .write_object("bucket", "big", "null", data.clone())
+            .await
+            .unwrap_or_else(|e| panic!("write failed: {e}"));
+        storage
+            .write_object("bucket", "small", "null", small_data())
+            .await
+            .unwrap_or_else(|e| panic!("write failed: {e}"));
+        let crc = checksums::compute_checksum(ChecksumAlgorithm::Crc32, &data);
+
+        let verify = |key: &'static str, checksum| {
+            let storage = &storage;
+            async move {
+                storage
+                    .verify_object("bucket", key, "null", checksum)
+                    .await
+                    .unwrap_or_else(|e| panic!("verify failed: {e}"))
+            }
+        };
+        assert!(
+            verify("big", Some((ChecksumAlgorithm::Crc32, crc.as_str())))
+                .await
+                .is_intact()
+        );
+        assert!(matches!(
+            verify("big", Some((ChecksumAlgorithm::Crc32, "AAAAAA=="))).await,
+            VerifyResult::Mismatch { ref algorithm, .. } if algorithm == "CRC32"
+        ));
+
+        corrupt_spilled_files(dir.path()).await;
+        let result = verify("big", None).await;
+        assert!(
+            matches!(result, VerifyResult::Mismatch { ref algorithm, .. } if algorithm == "MD5"),
+            "{result}"
+        );
+        assert!(verify("small", None).await.is_intact());
+        assert!(matches!(
+            storage
+                .verify_object("bucket", "missing", "null", None)
+                .await,
+            Err(S3ServiceError::NoSuchKey { .. })
+        ));
+    }
+
     #[tokio::test]
     async fn test_should_clean_up_on_overwrite() {
-        let storage = InMemoryStorage::new(TEST_THRESHOLD);
-        let data1 = large_data();
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("tempdir failed: {e}"));
+        let storage = InMemoryStorage::new(TEST_THRESHOLD).with_spillover_dir(dir.path());
 
         storage
-            .write_object("bucket", "key", "null", data1)
+            .write_object("bucket", "key", "null", large_data())
             .await
             .unwrap_or_else(|e| panic!("write1 failed: {e}"));
+        assert_eq!(spilled_files(dir.path()), 1);
 
-        // Overwrite with new data. The old temp file should be cleaned up
-        // via Drop when the DashMap entry is replaced.
+        // Overwrite with new data. The old temp file should be removed when
+        // the DashMap entry is replaced.
         let data2 = Bytes::from("small");
         storage
             .write_object("bucket", "key", "null", data2.clone())
             .await
             .unwrap_or_else(|e| panic!("write2 failed: {e}"));
+        assert_eq!(spilled_files(dir.path()), 0);
+        assert_eq!(storage.stats().disk_bytes, 0);
 
         let read = storage
             .read_object("bucket", "key", "null", None)
@@ -1019,6 +1593,226 @@ mod tests {
         assert_eq!(read, data2);
     }
 
+    #[tokio::test]
+    async fn test_should_clean_up_spilled_files_on_delete() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("tempdir failed: {e}"));
+        let storage = InMemoryStorage::new(TEST_THRESHOLD).with_spillover_dir(dir.path());
+        for (fill, bucket, key, version_id) in [
+            (1, "bucket", "obj", "null"),
+            (2, "bucket", "obj", "v2"),
+            (3, "other", "obj", "null"),
+        ] {
+            storage
+                .write_object(bucket, key, version_id, large_data_filled(fill))
+                .await
+                .unwrap_or_else(|e| panic!("write failed: {e}"));
+        }
+        storage
+            .write_part("bucket", "upload", 1, large_data_filled(0xFF))
+            .await
+            .unwrap_or_else(|e| panic!("write part failed: {e}"));
+        assert_eq!(spilled_files(dir.path()), 4);
+
+        storage.delete_object("bucket", "obj", "v2");
+        assert_eq!(spilled_files(dir.path()), 3);
+
+        storage.delete_bucket_data("bucket");
+        assert_eq!(spilled_files(dir.path()), 1);
+
+        storage.reset();
+        assert_eq!(spilled_files(dir.path()), 0);
+        assert_eq!(storage.stats(), StorageStats::default());
+    }
+
+    #[tokio::test]
+    async fn test_should_report_storage_stats() {
+        let storage = InMemoryStorage::new(TEST_THRESHOLD);
+        let large = large_data();
+        storage
+            .write_object("bucket", "small", "null", small_data())
+            .await
+            .unwrap_or_else(|e| panic!("write small failed: {e}"));
+        storage
+            .write_object("bucket", "large", "null", large.clone())
+            .await
+            .unwrap_or_else(|e| panic!("write large failed: {e}"));
+        storage
+            .write_part("bucket", "upload", 1, Bytes::from("part"))
+            .await
+            .unwrap_or_else(|e| panic!("write part failed: {e}"));
+
+        assert_eq!(
+            storage.stats(),
+            StorageStats {
+                object_count: 2,
+                part_count: 1,
+                memory_bytes: (small_data().len() + 4) as u64,
+                disk_bytes: large.len() as u64,
+            }
+        );
+    }
+
+    // -----------------------------------------------------------------------
+    // Shared blobs
+    // -----------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn test_should_not_grow_storage_when_copying_an_object() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("tempdir failed: {e}"));
+        let storage = InMemoryStorage::new(TEST_THRESHOLD).with_spillover_dir(dir.path());
+        for (key, data) in [("small", small_data()), ("large", large_data())] {
+            storage
+                .write_object("bucket", key, "null", data)
+                .await
+                .unwrap_or_else(|e| panic!("write {key} failed: {e}"));
+        }
+        let before = storage.stats();
+
+        for i in 0..50 {
+            for key in ["small", "large"] {
+                storage
+                    .copy_object(
+                        "bucket",
+                        key,
+                        "null",
+                        "bucket",
+                        &format!("{key}-{i}"),
+                        "null",
+                    )
+                    .unwrap_or_else(|e| panic!("copy {key} failed: {e}"));
+            }
+            storage
+                .copy_part("bucket", "large", "null", "bucket", "upload", i + 1)
+                .unwrap_or_else(|e| panic!("copy part failed: {e}"));
+        }
+
+        let after = storage.stats();
+        assert_eq!(after.object_count, before.object_count + 100);
+        assert_eq!(after.part_count, 50);
+        assert_eq!(after.memory_bytes, before.memory_bytes);
+        assert_eq!(after.disk_bytes, before.disk_bytes);
+        assert_eq!(spilled_files(dir.path()), 1);
+    }
+
+    #[tokio::test]
+    async fn test_should_share_identical_writes_until_the_last_reference_drops() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("tempdir failed: {e}"));
+        let storage = InMemoryStorage::new(TEST_THRESHOLD).with_spillover_dir(dir.path());
+        for version_id in ["v1", "v2"] {
+            storage
+                .write_object("bucket", "key", version_id, large_data())
+                .await
+                .unwrap_or_else(|e| panic!("write {version_id} failed: {e}"));
+        }
+        storage
+            .copy_object("bucket", "key", "v1", "bucket", "copy", "null")
+            .unwrap_or_else(|e| panic!("copy failed: {e}"));
+        assert_eq!(spilled_files(dir.path()), 1);
+        assert_eq!(storage.stats().disk_bytes, large_data().len() as u64);
+
+        storage.delete_object("bucket", "key", "v1");
+        storage.delete_object("bucket", "key", "v2");
+        assert_eq!(spilled_files(dir.path()), 1);
+        let read = storage
+            .read_object("bucket", "copy", "null", None)
+            .await
+            .unwrap_or_else(|e| panic!("read failed: {e}"));
+        assert_eq!(read, large_data());
+
+        storage.delete_object("bucket", "copy", "null");
+        assert_eq!(spilled_files(dir.path()), 0);
+        assert_eq!(storage.stats(), StorageStats::default());
+        assert!(storage.blobs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_should_not_change_shared_data_through_another_reference() {
+        let storage = InMemoryStorage::new(TEST_THRESHOLD);
+        let original = small_data();
+        storage
+            .write_object("bucket", "src", "null", original.clone())
+            .await
+            .unwrap_or_else(|e| panic!("write failed: {e}"));
+        storage
+            .copy_object("bucket", "src", "null", "bucket", "copy", "null")
+            .unwrap_or_else(|e| panic!("copy failed: {e}"));
+        storage
+            .copy_part("bucket", "src", "null", "bucket", "upload", 1)
+            .unwrap_or_else(|e| panic!("copy part failed: {e}"));
+
+        // Overwrite the source, write the part again, and assemble and
+        // abort an upload of the shared data.
+        storage
+            .write_object("bucket", "src", "null", Bytes::from("changed"))
+            .await
+            .unwrap_or_else(|e| panic!("overwrite failed: {e}"));
+        storage
+            .write_part("bucket", "upload", 1, Bytes::from("new part"))
+            .await
+            .unwrap_or_else(|e| panic!("write part failed: {e}"));
+        storage
+            .copy_part("bucket", "copy", "null", "bucket", "other", 1)
+            .unwrap_or_else(|e| panic!("copy part failed: {e}"));
+        storage
+            .complete_multipart("bucket", "other", "assembled", "null", &[1])
+            .await
+            .unwrap_or_else(|e| panic!("complete failed: {e}"));
+        storage.delete_object("bucket", "assembled", "null");
+
+        let read = storage
+            .read_object("bucket", "copy", "null", None)
+            .await
+            .unwrap_or_else(|e| panic!("read failed: {e}"));
+        assert_eq!(read, original);
+        let read = storage
+            .read_object("bucket", "src", "null", None)
+            .await
+            .unwrap_or_else(|e| panic!("read failed: {e}"));
+        assert_eq!(read, Bytes::from("changed"));
+        let read = storage
+            .read_part("bucket", "upload", 1)
+            .await
+            .unwrap_or_else(|e| panic!("read part failed: {e}"));
+        assert_eq!(read, Bytes::from("new part"));
+    }
+
+    #[tokio::test]
+    async fn test_should_reject_writes_past_disk_cap() {
+        let cap = (TEST_THRESHOLD as u64 + 1) * 2;
+        let storage = InMemoryStorage::new(TEST_THRESHOLD).with_max_disk_bytes(cap);
+        for (fill, key) in [(1, "a"), (2, "b")] {
+            storage
+                .write_object("bucket", key, "null", large_data_filled(fill))
+                .await
+                .unwrap_or_else(|e| panic!("write {key} failed: {e}"));
+        }
+
+        assert!(matches!(
+            storage
+                .write_object("bucket", "c", "null", large_data_filled(3))
+                .await,
+            Err(S3ServiceError::InsufficientStorage)
+        ));
+        assert!(matches!(
+            storage
+                .write_part("bucket", "upload", 1, large_data_filled(3))
+                .await,
+            Err(S3ServiceError::InsufficientStorage)
+        ));
+        assert_eq!(storage.stats().disk_bytes, cap);
+
+        // Data kept in memory is not capped, and deleting frees room on disk.
+        storage
+            .write_object("bucket", "c", "null", small_data())
+            .await
+            .unwrap_or_else(|e| panic!("write small failed: {e}"));
+        storage.delete_object("bucket", "a", "null");
+        storage
+            .write_object("bucket", "d", "null", large_data_filled(3))
+            .await
+            .unwrap_or_else(|e| panic!("write after delete failed: {e}"));
+    }
+
     // -----------------------------------------------------------------------
     // Large part spillover
     // -----------------------------------------------------------------------
@@ -1,11 +1,12 @@
 //! Shared utilities for the S3 service.
 //!
 //! Provides ID generation, timestamp helpers, range-header parsing,
-//! conditional-request matching, continuation-token encoding, and XML
-//! escaping functions.
+//! conditional-request matching, continuation-token and listing-key encoding,
+//! and XML escaping functions.
 
 use base64::{Engine, engine::general_purpose::STANDARD as BASE64_STANDARD};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
 use rand::RngExt;
 use uuid::Uuid;
 
@@ -107,6 +108,22 @@ pub fn timestamp_rfc3339() -> String {
     Utc::now().to_rfc3339()
 }
 
+/// Format a timestamp as an HTTP date (RFC 7231 IMF-fixdate).
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use rustack_s3_core::utils::format_http_date;
+///
+/// let date = Utc.with_ymd_and_hms(2030, 1, 2, 3, 4, 5).unwrap();
+/// assert_eq!(format_http_date(date), "Wed, 02 Jan 2030 03:04:05 GMT");
+/// ```
+#[must_use]
+pub fn format_http_date(date: DateTime<Utc>) -> String {
+    date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
 // ---------------------------------------------------------------------------
 // Range header parsing
 // ---------------------------------------------------------------------------
@@ -114,67 +131,119 @@ pub fn timestamp_rfc3339() -> String {
 /// Parse an HTTP `Range` header value and return the inclusive byte range.
 ///
 /// Supported formats:
-/// - `bytes=0-499` -- first 500 bytes
-/// - `bytes=-500` -- last 500 bytes
+/// - `bytes=0-499` -- first 500 bytes (an end past the object is clamped)
+/// - `bytes=-500` -- last 500 bytes (the whole object if it is shorter)
 /// - `bytes=500-` -- from byte 500 to the end
 /// - `bytes=0-` -- the entire content
 ///
-/// Returns an inclusive `(start, end)` tuple.
+/// Like S3, a header that is not a single well-formed byte range -- a
+/// multi-range such as `bytes=0-99,200-299`, another unit, or a range whose
+/// end precedes its start -- is ignored, and `Ok(None)` is returned so the
+/// whole object is served.
 ///
 /// # Errors
 ///
-/// Returns [`S3ServiceError::InvalidRange`] if the range header is malformed
-/// or specifies an unsatisfiable range.
+/// Returns [`S3ServiceError::InvalidRange`] if the range is well-formed but
+/// unsatisfiable: it starts at or past the end of the content, is the empty
+/// suffix `bytes=-0`, or the content is empty.
 ///
 /// # Examples
 ///
 /// ```
 /// use rustack_s3_core::utils::parse_range_header;
 ///
-/// let (start, end) = parse_range_header("bytes=0-499", 1000).unwrap();
-/// assert_eq!((start, end), (0, 499));
+/// assert_eq!(parse_range_header("bytes=0-499", 1000).unwrap(), Some((0, 499)));
+/// assert_eq!(parse_range_header("bytes=-2000", 1000).unwrap(), Some((0, 999)));
+/// assert_eq!(parse_range_header("bytes=0-1,5-9", 1000).unwrap(), None);
+/// assert!(parse_range_header("bytes=1000-", 1000).is_err());
 /// ```
-pub fn parse_range_header(range: &str, content_length: u64) -> Result<(u64, u64), S3ServiceError> {
-    let range = range
-        .strip_prefix("bytes=")
-        .ok_or(S3ServiceError::InvalidRange)?;
-
-    if content_length == 0 {
-        return Err(S3ServiceError::InvalidRange);
-    }
+pub fn parse_range_header(
+    range: &str,
+    content_length: u64,
+) -> Result<Option<(u64, u64)>, S3ServiceError> {
+    let Some(spec) = range.trim().strip_prefix("bytes=") else {
+        return Ok(None);
+    };
+    let Some((first, last)) = spec.trim().split_once('-') else {
+        return Ok(None);
+    };
+    let parse = |n: &str| -> Option<Option<u64>> {
+        if n.is_empty() {
+            Some(None)
+        } else if n.bytes().all(|b| b.is_ascii_digit()) {
+            n.parse().ok().map(Some)
+        } else {
+            None
+        }
+    };
+    let (Some(first), Some(last)) = (parse(first), parse(last)) else {
+        return Ok(None);
+    };
 
-    if let Some(suffix) = range.strip_prefix('-') {
+    match (first, last) {
         // bytes=-N  (last N bytes)
-        let n: u64 = suffix.parse().map_err(|_| S3ServiceError::InvalidRange)?;
-        if n == 0 || n > content_length {
-            return Err(S3ServiceError::InvalidRange);
+        (None, Some(suffix)) => {
+            if suffix == 0 || content_length == 0 {
+                return Err(S3ServiceError::InvalidRange);
+            }
+            Ok(Some((
+                content_length.saturating_sub(suffix),
+                content_length - 1,
+            )))
         }
-        let start = content_length - n;
-        Ok((start, content_length - 1))
-    } else if let Some(prefix) = range.strip_suffix('-') {
         // bytes=N-  (from N to end)
-        let start: u64 = prefix.parse().map_err(|_| S3ServiceError::InvalidRange)?;
-        if start >= content_length {
-            return Err(S3ServiceError::InvalidRange);
+        (Some(start), None) => {
+            if start >= content_length {
+                return Err(S3ServiceError::InvalidRange);
+            }
+            Ok(Some((start, content_length - 1)))
         }
-        Ok((start, content_length - 1))
-    } else {
         // bytes=N-M
-        let parts: Vec<&str> = range.splitn(2, '-').collect();
-        if parts.len() != 2 {
-            return Err(S3ServiceError::InvalidRange);
+        (Some(start), Some(end)) => {
+            if start > end {
+                return Ok(None);
+            }
+            if start >= content_length {
+                return Err(S3ServiceError::InvalidRange);
+            }
+            Ok(Some((start, end.min(content_length - 1))))
         }
-        let start: u64 = parts[0].parse().map_err(|_| S3ServiceError::InvalidRange)?;
-        let end: u64 = parts[1].parse().map_err(|_| S3ServiceError::InvalidRange)?;
-        if start > end || start >= content_length {
-            return Err(S3ServiceError::InvalidRange);
-        }
-        // Clamp end to content_length - 1
-        let end = end.min(content_length - 1);
-        Ok((start, end))
+        (None, None) => Ok(None),
     }
 }
 
+/// Check whether an `If-Range` validator still matches the object, so a
+/// `Range` request may be served partially.
+///
+/// The validator is either an ETag, compared strongly (a weak `W/` ETag never
+/// matches), or an HTTP date, which matches if the object was last modified
+/// at exactly that second.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use rustack_s3_core::utils::is_valid_if_range;
+///
+/// let modified = Utc.with_ymd_and_hms(2024, 3, 15, 12, 30, 45).unwrap();
+/// assert!(is_valid_if_range("\"abc\"", modified, "\"abc\""));
+/// assert!(!is_valid_if_range("\"abc\"", modified, "W/\"abc\""));
+/// assert!(is_valid_if_range("\"abc\"", modified, "Fri, 15 Mar 2024 12:30:45 GMT"));
+/// assert!(!is_valid_if_range("\"abc\"", modified, "Fri, 15 Mar 2024 12:30:44 GMT"));
+/// ```
+#[must_use]
+pub fn is_valid_if_range(etag: &str, last_modified: DateTime<Utc>, if_range: &str) -> bool {
+    let if_range = if_range.trim();
+    if if_range.starts_with("W/") {
+        return false;
+    }
+    if if_range.starts_with('"') {
+        return normalize_etag(etag) == normalize_etag(if_range);
+    }
+    DateTime::parse_from_rfc2822(if_range)
+        .is_ok_and(|date| date.timestamp() == last_modified.timestamp())
+}
+
 // ---------------------------------------------------------------------------
 // Conditional request helpers
 // ---------------------------------------------------------------------------
@@ -224,6 +293,79 @@ pub fn is_valid_if_none_match(etag: &str, if_none_match: &str) -> bool {
     normalize_etag(etag) != normalize_etag(if_none_match)
 }
 
+/// The conditional request headers of a read or copy, evaluated together by
+/// [`Preconditions::check`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Preconditions<'a> {
+    /// `If-Match` (or `x-amz-copy-source-if-match`).
+    pub if_match: Option<&'a str>,
+    /// `If-Unmodified-Since` (or `x-amz-copy-source-if-unmodified-since`).
+    pub if_unmodified_since: Option<DateTime<Utc>>,
+    /// `If-None-Match` (or `x-amz-copy-source-if-none-match`).
+    pub if_none_match: Option<&'a str>,
+    /// `If-Modified-Since` (or `x-amz-copy-source-if-modified-since`).
+    pub if_modified_since: Option<DateTime<Utc>>,
+}
+
+impl Preconditions<'_> {
+    /// Evaluate the conditions against an object in RFC 7232 section 6 order.
+    ///
+    /// `If-Match` takes priority over `If-Unmodified-Since`, which is only
+    /// consulted when `If-Match` is absent; likewise `If-None-Match` over
+    /// `If-Modified-Since`. Dates compare at whole-second granularity, as
+    /// HTTP dates carry no fractions. A failed `If-None-Match` or
+    /// `If-Modified-Since` is `NotModified` (304) for a GET or HEAD (`read`)
+    /// and `PreconditionFailed` (412) otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PreconditionFailed` or `NotModified` for the first condition,
+    /// in evaluation order, that does not hold.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use rustack_s3_core::utils::Preconditions;
+    ///
+    /// let modified = Utc.with_ymd_and_hms(2024, 3, 15, 12, 30, 45).unwrap();
+    /// let conditions = Preconditions {
+    ///     if_none_match: Some("\"xyz\""),
+    ///     if_modified_since: Some(modified),
+    ///     ..Preconditions::default()
+    /// };
+    /// // A differing ETag wins over an unchanged date.
+    /// assert!(conditions.check("\"abc\"", modified, true).is_ok());
+    /// ```
+    pub fn check(
+        &self,
+        etag: &str,
+        last_modified: DateTime<Utc>,
+        read: bool,
+    ) -> Result<(), S3ServiceError> {
+        let modified = last_modified.timestamp();
+        match (self.if_match, self.if_unmodified_since) {
+            (Some(if_match), _) if !is_valid_if_match(etag, if_match) => {
+                return Err(S3ServiceError::PreconditionFailed);
+            }
+            (None, Some(since)) if modified > since.timestamp() => {
+                return Err(S3ServiceError::PreconditionFailed);
+            }
+            _ => {}
+        }
+        let not_modified = match (self.if_none_match, self.if_modified_since) {
+            (Some(if_none_match), _) => !is_valid_if_none_match(etag, if_none_match),
+            (None, Some(since)) => modified <= since.timestamp(),
+            (None, None) => false,
+        };
+        match (not_modified, read) {
+            (false, _) => Ok(()),
+            (true, true) => Err(S3ServiceError::NotModified),
+            (true, false) => Err(S3ServiceError::PreconditionFailed),
+        }
+    }
+}
+
 /// Normalize an ETag by stripping surrounding double quotes.
 fn normalize_etag(etag: &str) -> &str {
     etag.strip_prefix('"')
@@ -268,6 +410,40 @@ pub fn decode_continuation_token(token: &str) -> Result<String, S3ServiceError>
     })
 }
 
+// ---------------------------------------------------------------------------
+// Listing key encoding
+// ---------------------------------------------------------------------------
+
+/// Characters left as-is when URL-encoding listed keys: unreserved
+/// characters and `/`.
+const LISTING_KEY_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~')
+    .remove(b'/');
+
+/// URL-encode a key, prefix, marker, or delimiter for a listing requested
+/// with `EncodingType=url`.
+///
+/// Matches S3: spaces become `+`, `/` and unreserved characters are kept, and
+/// everything else is percent-encoded as UTF-8.
+///
+/// # Examples
+///
+/// ```
+/// use rustack_s3_core::utils::url_encode_listing_key;
+///
+/// assert_eq!(url_encode_listing_key("a b/c+d"), "a+b/c%2Bd");
+/// ```
+#[must_use]
+pub fn url_encode_listing_key(key: &str) -> String {
+    // A literal `%` is encoded as `%25`, so `%20` can only come from a space.
+    utf8_percent_encode(key, LISTING_KEY_SET)
+        .to_string()
+        .replace("%20", "+")
+}
+
 // ---------------------------------------------------------------------------
 // Copy source parsing
 // ---------------------------------------------------------------------------
@@ -368,6 +544,8 @@ pub fn xml_escape(s: &str) -> String {
 
 #[cfg(test)]
 mod tests {
+    use chrono::TimeZone;
+
     use super::*;
 
     // -----------------------------------------------------------------------
@@ -421,52 +599,76 @@ mod tests {
 
     #[test]
     fn test_should_parse_range_start_end() {
-        let (s, e) = parse_range_header("bytes=0-499", 1000).expect("test parse");
-        assert_eq!((s, e), (0, 499));
+        let range = parse_range_header("bytes=0-499", 1000).expect("test parse");
+        assert_eq!(range, Some((0, 499)));
     }
 
     #[test]
     fn test_should_parse_range_suffix() {
-        let (s, e) = parse_range_header("bytes=-500", 1000).expect("test parse");
-        assert_eq!((s, e), (500, 999));
+        let range = parse_range_header("bytes=-500", 1000).expect("test parse");
+        assert_eq!(range, Some((500, 999)));
     }
 
     #[test]
     fn test_should_parse_range_from_offset() {
-        let (s, e) = parse_range_header("bytes=500-", 1000).expect("test parse");
-        assert_eq!((s, e), (500, 999));
+        let range = parse_range_header("bytes=500-", 1000).expect("test parse");
+        assert_eq!(range, Some((500, 999)));
     }
 
     #[test]
     fn test_should_parse_range_from_zero() {
-        let (s, e) = parse_range_header("bytes=0-", 1000).expect("test parse");
-        assert_eq!((s, e), (0, 999));
+        let range = parse_range_header("bytes=0-", 1000).expect("test parse");
+        assert_eq!(range, Some((0, 999)));
     }
 
     #[test]
     fn test_should_clamp_range_end_to_content_length() {
-        let (s, e) = parse_range_header("bytes=0-9999", 100).expect("test parse");
-        assert_eq!((s, e), (0, 99));
+        let range = parse_range_header("bytes=0-9999", 100).expect("test parse");
+        assert_eq!(range, Some((0, 99)));
     }
 
     #[test]
-    fn test_should_reject_invalid_range_no_prefix() {
-        assert!(parse_range_header("0-499", 1000).is_err());
+    fn test_should_ignore_range_without_bytes_unit() {
+        assert_eq!(parse_range_header("0-499", 1000).expect("test parse"), None);
+        assert_eq!(
+            parse_range_header("items=0-499", 1000).expect("test parse"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_should_ignore_multi_range() {
+        let range = parse_range_header("bytes=0-99,200-299", 1000).expect("test parse");
+        assert_eq!(range, None);
+    }
+
+    #[test]
+    fn test_should_ignore_malformed_range() {
+        for range in ["bytes=", "bytes=-", "bytes=abc-", "bytes=1-x", "bytes=+1-2"] {
+            assert_eq!(
+                parse_range_header(range, 1000).expect("test parse"),
+                None,
+                "{range}"
+            );
+        }
     }
 
     #[test]
     fn test_should_reject_range_start_beyond_length() {
         assert!(parse_range_header("bytes=1000-", 1000).is_err());
+        assert!(parse_range_header("bytes=1000-1200", 1000).is_err());
     }
 
     #[test]
-    fn test_should_reject_range_start_greater_than_end() {
-        assert!(parse_range_header("bytes=500-100", 1000).is_err());
+    fn test_should_ignore_range_start_greater_than_end() {
+        let range = parse_range_header("bytes=500-100", 1000).expect("test parse");
+        assert_eq!(range, None);
     }
 
     #[test]
     fn test_should_reject_range_on_empty_content() {
         assert!(parse_range_header("bytes=0-0", 0).is_err());
+        assert!(parse_range_header("bytes=-10", 0).is_err());
     }
 
     #[test]
@@ -475,8 +677,23 @@ mod tests {
     }
 
     #[test]
-    fn test_should_reject_suffix_range_exceeding_length() {
-        assert!(parse_range_header("bytes=-2000", 1000).is_err());
+    fn test_should_serve_whole_object_for_suffix_range_exceeding_length() {
+        let range = parse_range_header("bytes=-2000", 1000).expect("test parse");
+        assert_eq!(range, Some((0, 999)));
+    }
+
+    #[test]
+    fn test_should_match_if_range_validators() {
+        let modified = Utc::now();
+        assert!(is_valid_if_range("\"abc\"", modified, "\"abc\""));
+        assert!(!is_valid_if_range("\"abc\"", modified, "\"xyz\""));
+        assert!(!is_valid_if_range("\"abc\"", modified, "W/\"abc\""));
+        assert!(is_valid_if_range(
+            "\"abc\"",
+            modified,
+            &modified.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+        ));
+        assert!(!is_valid_if_range("\"abc\"", modified, "not a date"));
     }
 
     // -----------------------------------------------------------------------
@@ -518,6 +735,83 @@ mod tests {
         assert!(is_valid_if_none_match("\"abc\"", "\"xyz\""));
     }
 
+    #[test]
+    fn test_should_evaluate_preconditions_in_rfc_7232_order() {
+        let modified = Utc.with_ymd_and_hms(2024, 3, 15, 12, 30, 45).unwrap();
+        let before = modified - chrono::TimeDelta::seconds(1);
+        let check = |conditions: Preconditions<'_>, read| {
+            conditions
+                .check("\"abc\"", modified, read)
+                .map_err(|e| e.to_string())
+        };
+        let failed = Err(S3ServiceError::PreconditionFailed.to_string());
+        let not_modified = Err(S3ServiceError::NotModified.to_string());
+
+        // If-Match wins over a failing If-Unmodified-Since.
+        let conditions = Preconditions {
+            if_match: Some("\"abc\""),
+            if_unmodified_since: Some(before),
+            ..Preconditions::default()
+        };
+        assert_eq!(check(conditions, true), Ok(()));
+        let conditions = Preconditions {
+            if_unmodified_since: Some(before),
+            ..Preconditions::default()
+        };
+        assert_eq!(check(conditions, true), failed);
+
+        // If-None-Match wins over If-Modified-Since, both ways.
+        let conditions = Preconditions {
+            if_none_match: Some("\"xyz\""),
+            if_modified_since: Some(modified),
+            ..Preconditions::default()
+        };
+        assert_eq!(check(conditions, true), Ok(()));
+        let conditions = Preconditions {
+            if_none_match: Some("\"abc\""),
+            if_modified_since: Some(before),
+            ..Preconditions::default()
+        };
+        assert_eq!(check(conditions, true), not_modified);
+        assert_eq!(check(conditions, false), failed);
+
+        // A failed If-Match is reported before a failed If-None-Match.
+        let conditions = Preconditions {
+            if_match: Some("\"xyz\""),
+            if_none_match: Some("\"abc\""),
+            ..Preconditions::default()
+        };
+        assert_eq!(check(conditions, true), failed);
+    }
+
+    #[test]
+    fn test_should_compare_precondition_dates_to_the_second() {
+        let modified = Utc.with_ymd_and_hms(2024, 3, 15, 12, 30, 45).unwrap()
+            + chrono::TimeDelta::milliseconds(900);
+        let header = Utc.with_ymd_and_hms(2024, 3, 15, 12, 30, 45).unwrap();
+        let since = |if_modified_since, if_unmodified_since| Preconditions {
+            if_unmodified_since,
+            if_modified_since,
+            ..Preconditions::default()
+        };
+        // Written later in the same second as the header: not modified since.
+        assert!(matches!(
+            since(Some(header), None).check("\"abc\"", modified, true),
+            Err(S3ServiceError::NotModified)
+        ));
+        assert!(
+            since(None, Some(header))
+                .check("\"abc\"", modified, true)
+                .is_ok()
+        );
+        let earlier = header - chrono::TimeDelta::seconds(1);
+        assert!(
+            since(Some(earlier), None)
+                .check("\"abc\"", modified, true)
+                .is_ok()
+        );
+    }
+
     // -----------------------------------------------------------------------
     // Continuation tokens
     // -----------------------------------------------------------------------
@@ -542,6 +836,16 @@ mod tests {
         assert!(decode_continuation_token("!!!not-base64!!!").is_err());
     }
 
+    #[test]
+    fn test_should_url_encode_listing_keys() {
+        assert_eq!(
+            url_encode_listing_key("photos/2024/img.jpg"),
+            "photos/2024/img.jpg"
+        );
+        assert_eq!(url_encode_listing_key("my file+v2.txt"), "my+file%2Bv2.txt");
+        assert_eq!(url_encode_listing_key("🚀/a%20b"), "%F0%9F%9A%80/a%2520b");
+    }
+
     // -----------------------------------------------------------------------
     // Copy source parsing
     // -----------------------------------------------------------------------
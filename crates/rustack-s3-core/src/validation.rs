@@ -11,9 +11,15 @@ use md5::{Digest, Md5};
 
 use crate::error::S3ServiceError;
 
-/// Maximum number of tags allowed on a single S3 object or bucket.
+/// Maximum number of tags allowed on a single S3 object.
 const MAX_TAGS: usize = 10;
 
+/// Maximum number of tags allowed on a single S3 bucket.
+const MAX_BUCKET_TAGS: usize = 50;
+
+/// Tag key prefix reserved for AWS-generated tags.
+const RESERVED_TAG_PREFIX: &str = "aws:";
+
 /// Maximum length of a tag key in characters.
 const MAX_TAG_KEY_LEN: usize = 128;
 
@@ -32,6 +38,12 @@ const MIN_BUCKET_NAME_LEN: usize = 3;
 /// Maximum bucket name length.
 const MAX_BUCKET_NAME_LEN: usize = 63;
 
+/// Maximum length of a legacy (pre-2018 `us-east-1`) bucket name.
+const MAX_LEGACY_BUCKET_NAME_LEN: usize = 255;
+
+/// Bucket name suffix reserved for S3 Express One Zone directory buckets.
+const DIRECTORY_BUCKET_SUFFIX: &str = "--x-s3";
+
 /// Validate an S3 bucket name.
 ///
 /// Rules (per AWS documentation):
@@ -42,6 +54,7 @@ const MAX_BUCKET_NAME_LEN: usize = 63;
 /// - Not formatted as an IPv4 address (e.g. `192.168.0.1`)
 /// - Must not start with `xn--`
 /// - Must not end with `-s3alias`
+/// - Must not end with `--x-s3` (reserved for directory buckets, which are not supported)
 /// - Must not start with `sthree-`
 ///
 /// # Errors
@@ -126,9 +139,68 @@ pub fn validate_bucket_name(name: &str) -> Result<(), S3ServiceError> {
         });
     }
 
+    reject_directory_bucket_name(name)
+}
+
+/// Reject the `--x-s3` suffix S3 reserves for directory buckets.
+fn reject_directory_bucket_name(name: &str) -> Result<(), S3ServiceError> {
+    if name.ends_with(DIRECTORY_BUCKET_SUFFIX) {
+        return Err(S3ServiceError::InvalidBucketName {
+            name: name.to_owned(),
+            reason: format!(
+                "Bucket name must not end with '{DIRECTORY_BUCKET_SUFFIX}': directory buckets (S3 \
+                 Express One Zone) are not supported"
+            ),
+        });
+    }
     Ok(())
 }
 
+/// Validate a bucket name against the legacy rules S3 once allowed in
+/// `us-east-1`.
+///
+/// Rules:
+/// - 1-255 characters long
+/// - Only letters (either case), numbers, hyphens, dots, and underscores
+/// - Must not end with `--x-s3`
+///
+/// # Errors
+///
+/// Returns [`S3ServiceError::InvalidBucketName`] if any rule is violated.
+///
+/// # Examples
+///
+/// ```
+/// use rustack_s3_core::validation::validate_legacy_bucket_name;
+///
+/// assert!(validate_legacy_bucket_name("MyBucket_2024").is_ok());
+/// assert!(validate_legacy_bucket_name("bad/name").is_err());
+/// ```
+pub fn validate_legacy_bucket_name(name: &str) -> Result<(), S3ServiceError> {
+    if name.is_empty() || name.len() > MAX_LEGACY_BUCKET_NAME_LEN {
+        return Err(S3ServiceError::InvalidBucketName {
+            name: name.to_owned(),
+            reason: format!(
+                "Bucket name must be between 1 and {MAX_LEGACY_BUCKET_NAME_LEN} characters long"
+            ),
+        });
+    }
+
+    if !name
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_'))
+    {
+        return Err(S3ServiceError::InvalidBucketName {
+            name: name.to_owned(),
+            reason: "Bucket name must only contain letters, numbers, hyphens, dots, and \
+                     underscores"
+                .to_owned(),
+        });
+    }
+
+    reject_directory_bucket_name(name)
+}
+
 /// Validate an S3 object key.
 ///
 /// Rules:
@@ -162,14 +234,25 @@ pub fn validate_object_key(key: &str) -> Result<(), S3ServiceError> {
     Ok(())
 }
 
+/// Whether `c` may appear in a tag key or value.
+///
+/// S3 accepts Unicode letters, digits, whitespace, and `_ . : / = + - @`.
+fn is_allowed_tag_char(c: char) -> bool {
+    c.is_alphanumeric()
+        || c.is_whitespace()
+        || matches!(c, '_' | '.' | ':' | '/' | '=' | '+' | '-' | '@')
+}
+
 /// Validate a tag key.
 ///
 /// Rules:
 /// - 1-128 characters in length
+/// - Only letters, digits, whitespace, and `_ . : / = + - @`
+/// - Must not start with the reserved `aws:` prefix (case-insensitive)
 ///
 /// # Errors
 ///
-/// Returns [`S3ServiceError::InvalidTag`] if the key is empty or too long.
+/// Returns [`S3ServiceError::InvalidTag`] if any rule is violated.
 ///
 /// # Examples
 ///
@@ -178,19 +261,21 @@ pub fn validate_object_key(key: &str) -> Result<(), S3ServiceError> {
 ///
 /// assert!(validate_tag_key("environment").is_ok());
 /// assert!(validate_tag_key("").is_err());
+/// assert!(validate_tag_key("aws:createdBy").is_err());
 /// ```
 pub fn validate_tag_key(key: &str) -> Result<(), S3ServiceError> {
-    if key.is_empty() {
+    let len = key.chars().count();
+    if len == 0 || len > MAX_TAG_KEY_LEN || !key.chars().all(is_allowed_tag_char) {
         return Err(S3ServiceError::InvalidTag {
-            message: "Tag key must not be empty".to_owned(),
+            message: "The TagKey you have provided is invalid".to_owned(),
         });
     }
-    if key.chars().count() > MAX_TAG_KEY_LEN {
+    let reserved = key
+        .get(..RESERVED_TAG_PREFIX.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(RESERVED_TAG_PREFIX));
+    if reserved {
         return Err(S3ServiceError::InvalidTag {
-            message: format!(
-                "Tag key must not exceed {MAX_TAG_KEY_LEN} characters, got {}",
-                key.chars().count()
-            ),
+            message: "Your TagKey cannot be prefixed with aws:".to_owned(),
         });
     }
     Ok(())
@@ -200,41 +285,45 @@ pub fn validate_tag_key(key: &str) -> Result<(), S3ServiceError> {
 ///
 /// Rules:
 /// - 0-256 characters in length (empty values are allowed)
+/// - Only letters, digits, whitespace, and `_ . : / = + - @`
 ///
 /// # Errors
 ///
-/// Returns [`S3ServiceError::InvalidTag`] if the value exceeds 256 characters.
+/// Returns [`S3ServiceError::InvalidTag`] if the value is too long or
+/// contains a disallowed character.
 ///
 /// # Examples
 ///
 /// ```
 /// use rustack_s3_core::validation::validate_tag_value;
 ///
-/// assert!(validate_tag_value("production").is_ok());
 /// assert!(validate_tag_value("").is_ok());
+/// assert!(validate_tag_value("prod").is_ok());
+/// assert!(validate_tag_value("a#b").is_err());
 /// ```
 pub fn validate_tag_value(value: &str) -> Result<(), S3ServiceError> {
-    if value.chars().count() > MAX_TAG_VALUE_LEN {
+    if value.chars().count() > MAX_TAG_VALUE_LEN || !value.chars().all(is_allowed_tag_char) {
         return Err(S3ServiceError::InvalidTag {
-            message: format!(
-                "Tag value must not exceed {MAX_TAG_VALUE_LEN} characters, got {}",
-                value.chars().count()
-            ),
+            message: "The TagValue you have provided is invalid".to_owned(),
         });
     }
     Ok(())
 }
 
-/// Validate a set of tags.
+/// Validate the tag set of an object.
+///
+/// This is shared by PutObjectTagging and the `x-amz-tagging` header on
+/// PutObject, CopyObject, and CreateMultipartUpload.
 ///
 /// Rules:
 /// - Maximum of 10 tags
-/// - Each key must be 1-128 characters
-/// - Each value must be 0-256 characters
+/// - Each key and value must pass [`validate_tag_key`] and [`validate_tag_value`]
+/// - Keys must be unique
 ///
 /// # Errors
 ///
-/// Returns [`S3ServiceError::InvalidTag`] if any rule is violated.
+/// Returns [`S3ServiceError::BadRequest`] if there are too many tags and
+/// [`S3ServiceError::InvalidTag`] for any other violation.
 ///
 /// # Examples
 ///
@@ -249,19 +338,41 @@ pub fn validate_tag_value(value: &str) -> Result<(), S3ServiceError> {
 /// ```
 pub fn validate_tags(tags: &[(String, String)]) -> Result<(), S3ServiceError> {
     if tags.len() > MAX_TAGS {
+        return Err(S3ServiceError::BadRequest {
+            message: format!("Object tags cannot be greater than {MAX_TAGS}"),
+        });
+    }
+    validate_tag_entries(tags)
+}
+
+/// Validate the tag set of a bucket.
+///
+/// Applies the same per-tag rules as [`validate_tags`] with the bucket
+/// limit of 50 tags.
+///
+/// # Errors
+///
+/// Returns [`S3ServiceError::InvalidTag`] if any rule is violated.
+pub fn validate_bucket_tags(tags: &[(String, String)]) -> Result<(), S3ServiceError> {
+    if tags.len() > MAX_BUCKET_TAGS {
         return Err(S3ServiceError::InvalidTag {
-            message: format!(
-                "Object tags cannot be greater than {MAX_TAGS}, got {}",
-                tags.len()
-            ),
+            message: format!("Bucket tag count cannot be greater than {MAX_BUCKET_TAGS}"),
         });
     }
+    validate_tag_entries(tags)
+}
 
-    for (key, value) in tags {
+/// Validate each key and value and reject repeated keys.
+fn validate_tag_entries(tags: &[(String, String)]) -> Result<(), S3ServiceError> {
+    for (i, (key, value)) in tags.iter().enumerate() {
         validate_tag_key(key)?;
         validate_tag_value(value)?;
+        if tags[..i].iter().any(|(earlier, _)| earlier == key) {
+            return Err(S3ServiceError::InvalidTag {
+                message: "Cannot provide multiple Tags with the same key".to_owned(),
+            });
+        }
     }
-
     Ok(())
 }
 
@@ -272,7 +383,7 @@ pub fn validate_tags(tags: &[(String, String)]) -> Result<(), S3ServiceError> {
 ///
 /// # Errors
 ///
-/// Returns [`S3ServiceError::InvalidArgument`] if the total metadata size
+/// Returns [`S3ServiceError::MetadataTooLarge`] if the total metadata size
 /// exceeds the limit.
 ///
 /// # Examples
@@ -291,11 +402,7 @@ pub fn validate_metadata<S: BuildHasher>(
     let total_size: usize = metadata.iter().map(|(k, v)| k.len() + v.len()).sum();
 
     if total_size > MAX_METADATA_SIZE {
-        return Err(S3ServiceError::InvalidArgument {
-            message: format!(
-                "User-defined metadata must not exceed {MAX_METADATA_SIZE} bytes, got {total_size}"
-            ),
-        });
+        return Err(S3ServiceError::MetadataTooLarge);
     }
 
     Ok(())
@@ -411,11 +518,27 @@ mod tests {
         assert!(validate_bucket_name("mybucket-s3alias").is_err());
     }
 
+    #[test]
+    fn test_should_reject_directory_bucket_suffix() {
+        assert!(validate_bucket_name("mybucket--usw2-az1--x-s3").is_err());
+        assert!(validate_legacy_bucket_name("mybucket--usw2-az1--x-s3").is_err());
+    }
+
     #[test]
     fn test_should_reject_sthree_prefix_bucket_name() {
         assert!(validate_bucket_name("sthree-bucket").is_err());
     }
 
+    #[test]
+    fn test_should_accept_legacy_bucket_names() {
+        assert!(validate_legacy_bucket_name("MyBucket_2024").is_ok());
+        assert!(validate_legacy_bucket_name("ab").is_ok());
+        assert!(validate_legacy_bucket_name(&"a".repeat(255)).is_ok());
+        assert!(validate_legacy_bucket_name("").is_err());
+        assert!(validate_legacy_bucket_name(&"a".repeat(256)).is_err());
+        assert!(validate_legacy_bucket_name("has space").is_err());
+    }
+
     // -----------------------------------------------------------------------
     // Object key validation
     // -----------------------------------------------------------------------
@@ -483,7 +606,46 @@ mod tests {
         let tags: Vec<(String, String)> = (0..11)
             .map(|i| (format!("key{i}"), format!("val{i}")))
             .collect();
-        assert!(validate_tags(&tags).is_err());
+        assert!(matches!(
+            validate_tags(&tags),
+            Err(S3ServiceError::BadRequest { .. })
+        ));
+    }
+
+    #[test]
+    fn test_should_allow_up_to_fifty_bucket_tags() {
+        let tags: Vec<(String, String)> = (0..51)
+            .map(|i| (format!("key{i}"), format!("val{i}")))
+            .collect();
+        assert!(validate_bucket_tags(&tags[..50]).is_ok());
+        assert!(matches!(
+            validate_bucket_tags(&tags),
+            Err(S3ServiceError::InvalidTag { .. })
+        ));
+    }
+
+    #[test]
+    fn test_should_reject_tag_keys_with_disallowed_characters() {
+        assert!(validate_tag_key("cost-center/team_a.b:c=d+e@f g").is_ok());
+        assert!(validate_tag_key("caf\u{e9}").is_ok());
+        for key in ["a#b", "a?b", "a*b", "a,b", "a&b"] {
+            assert!(
+                matches!(
+                    validate_tag_key(key),
+                    Err(S3ServiceError::InvalidTag { .. })
+                ),
+                "{key} should be rejected"
+            );
+        }
+        assert!(validate_tag_value("x<y").is_err());
+    }
+
+    #[test]
+    fn test_should_reject_reserved_aws_tag_prefix() {
+        assert!(validate_tag_key("aws:cloudformation:stack-name").is_err());
+        assert!(validate_tag_key("AWS:Owner").is_err());
+        assert!(validate_tag_key("awsome").is_ok());
+        assert!(validate_tag_key("my:aws:key").is_ok());
     }
 
     #[test]
@@ -498,6 +660,18 @@ mod tests {
         assert!(validate_tags(&tags).is_err());
     }
 
+    #[test]
+    fn test_should_reject_duplicate_tag_keys() {
+        let tags = vec![
+            ("env".to_owned(), "prod".to_owned()),
+            ("env".to_owned(), "dev".to_owned()),
+        ];
+        assert!(matches!(
+            validate_tags(&tags),
+            Err(S3ServiceError::InvalidTag { .. })
+        ));
+    }
+
     // -----------------------------------------------------------------------
     // Metadata validation
     // -----------------------------------------------------------------------
@@ -520,7 +694,10 @@ mod tests {
         let mut meta = HashMap::new();
         // Single entry that exceeds 2 KB
         meta.insert("key".to_owned(), "v".repeat(2048));
-        assert!(validate_metadata(&meta).is_err());
+        assert!(matches!(
+            validate_metadata(&meta),
+            Err(S3ServiceError::MetadataTooLarge)
+        ));
     }
 
     #[test]
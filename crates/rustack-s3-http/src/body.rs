@@ -1,21 +1,32 @@
 //! S3 response body types supporting buffered and empty modes.
 //!
 //! This module provides [`S3ResponseBody`], the HTTP response body type used throughout
-//! the S3 HTTP service. It supports two modes:
+//! the S3 HTTP service. It supports three modes:
 //!
 //! - **Buffered**: For small responses such as XML payloads, error bodies, and raw bytes.
 //! - **Empty**: For responses with no body content (e.g., 204 No Content, HEAD responses).
+//! - **Dripped**: Another body delivered at a fixed rate, for injected network latency (see
+//!   [`crate::latency`]).
 //!
 //! Streaming support for large objects (e.g., `GetObject`) can be added in the future
 //! by extending this enum with a streaming variant.
 
 use std::{
+    future::Future,
     pin::Pin,
-    task::{Context, Poll},
+    task::{Context, Poll, ready},
+    time::Duration,
 };
 
 use bytes::Bytes;
 use http_body_util::Full;
+use tokio::time::Sleep;
+
+/// How often a dripped body releases a chunk, in milliseconds.
+const DRIP_INTERVAL_MS: u64 = 100;
+
+/// How often a dripped body releases a chunk.
+const DRIP_INTERVAL: Duration = Duration::from_millis(DRIP_INTERVAL_MS);
 
 /// S3 response body supporting buffered and empty modes.
 ///
@@ -27,6 +38,8 @@ pub enum S3ResponseBody {
     /// Empty body for 204 responses, DELETE confirmations, HEAD responses, etc.
     #[default]
     Empty,
+    /// Another body delivered at a fixed rate.
+    Dripped(DripBody),
 }
 
 impl S3ResponseBody {
@@ -53,6 +66,72 @@ impl S3ResponseBody {
     pub fn from_xml(xml: Vec<u8>) -> Self {
         Self::Buffered(Full::new(Bytes::from(xml)))
     }
+
+    /// Deliver this body at `bytes_per_sec`, in chunks released every
+    /// 100 ms.
+    #[must_use]
+    pub fn drip(self, bytes_per_sec: u64) -> Self {
+        let per_interval = bytes_per_sec.saturating_mul(DRIP_INTERVAL_MS) / 1000;
+        Self::Dripped(DripBody {
+            inner: Box::new(self),
+            pending: Bytes::new(),
+            chunk_len: usize::try_from(per_interval.max(1)).unwrap_or(usize::MAX),
+            sleep: None,
+        })
+    }
+}
+
+/// A body whose data frames are split into chunks released one per
+/// [`DRIP_INTERVAL`].
+#[derive(Debug)]
+pub struct DripBody {
+    inner: Box<S3ResponseBody>,
+    pending: Bytes,
+    chunk_len: usize,
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl http_body::Body for DripBody {
+    type Data = Bytes;
+    type Error = std::io::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+        while this.pending.is_empty() {
+            match ready!(Pin::new(this.inner.as_mut()).poll_frame(cx)) {
+                Some(Ok(frame)) => match frame.into_data() {
+                    Ok(data) => this.pending = data,
+                    Err(frame) => return Poll::Ready(Some(Ok(frame))),
+                },
+                other => return Poll::Ready(other),
+            }
+        }
+        if let Some(sleep) = &mut this.sleep {
+            ready!(sleep.as_mut().poll(cx));
+        }
+        let len = this.chunk_len.min(this.pending.len());
+        let chunk = this.pending.split_to(len);
+        this.sleep = Some(Box::pin(tokio::time::sleep(DRIP_INTERVAL)));
+        Poll::Ready(Some(Ok(http_body::Frame::data(chunk))))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.pending.is_empty() && self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        let pending = self.pending.len() as u64;
+        let inner = self.inner.size_hint();
+        let mut hint = http_body::SizeHint::new();
+        hint.set_lower(inner.lower() + pending);
+        if let Some(upper) = inner.upper() {
+            hint.set_upper(upper + pending);
+        }
+        hint
+    }
 }
 
 impl http_body::Body for S3ResponseBody {
@@ -68,6 +147,7 @@ impl http_body::Body for S3ResponseBody {
                 .poll_frame(cx)
                 .map_err(|never| match never {}),
             Self::Empty => Poll::Ready(None),
+            Self::Dripped(drip) => Pin::new(drip).poll_frame(cx),
         }
     }
 
@@ -75,6 +155,7 @@ impl http_body::Body for S3ResponseBody {
         match self {
             Self::Buffered(full) => full.is_end_stream(),
             Self::Empty => true,
+            Self::Dripped(drip) => drip.is_end_stream(),
         }
     }
 
@@ -82,6 +163,7 @@ impl http_body::Body for S3ResponseBody {
         match self {
             Self::Buffered(full) => full.size_hint(),
             Self::Empty => http_body::SizeHint::with_exact(0),
+            Self::Dripped(drip) => drip.size_hint(),
         }
     }
 }
@@ -128,6 +210,23 @@ mod tests {
         assert!(!body.is_end_stream());
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn test_should_drip_body_in_chunks() {
+        use http_body_util::BodyExt;
+
+        let mut body = S3ResponseBody::from_bytes(Bytes::from_static(b"0123456789")).drip(30);
+        assert_eq!(body.size_hint().exact(), Some(10));
+
+        let started = tokio::time::Instant::now();
+        let mut chunks = Vec::new();
+        while let Some(frame) = body.frame().await {
+            chunks.push(frame.unwrap().into_data().unwrap());
+        }
+        assert_eq!(chunks, ["012", "345", "678", "9"]);
+        assert_eq!(started.elapsed(), DRIP_INTERVAL * 3);
+        assert!(body.is_end_stream());
+    }
+
     #[test]
     fn test_should_default_to_empty() {
         let body = S3ResponseBody::default();
@@ -0,0 +1,164 @@
+//! Bucket CORS evaluation for preflight and actual requests.
+//!
+//! The service asks the [`S3Handler`] to evaluate cross-origin requests against
+//! the addressed bucket's CORS configuration:
+//!
+//! - A preflight (`OPTIONS`) request that matches a rule gets that rule's headers. One that matches
+//!   no rule is rejected with `403 AccessForbidden`.
+//! - An actual request from a matching origin gets the rule's headers, including
+//!   `Access-Control-Expose-Headers`, added to its response.
+//!
+//! Buckets without a CORS configuration, and requests that carry no `Origin`
+//! or address no bucket, keep the service's permissive defaults.
+
+use http::{HeaderMap, HeaderValue, header};
+use rustack_s3_model::error::{S3Error, S3ErrorCode};
+
+use crate::{body::S3ResponseBody, dispatch::S3Handler};
+
+/// A cross-origin request to evaluate against a bucket's CORS rules.
+#[derive(Debug, Clone, Copy)]
+pub struct CorsRequest<'a> {
+    /// The bucket the request addresses.
+    pub bucket: &'a str,
+    /// The `Origin` request header.
+    pub origin: &'a str,
+    /// The request method, or `Access-Control-Request-Method` for a preflight.
+    pub method: &'a str,
+    /// The headers named in `Access-Control-Request-Headers` for a preflight,
+    /// or `None` for an actual request.
+    pub preflight_headers: Option<&'a [String]>,
+}
+
+impl CorsRequest<'_> {
+    /// Whether this is a preflight request.
+    #[must_use]
+    pub fn is_preflight(&self) -> bool {
+        self.preflight_headers.is_some()
+    }
+}
+
+/// The outcome of evaluating a [`CorsRequest`].
+#[derive(Debug, Clone, Default)]
+pub enum CorsOutcome {
+    /// The bucket has no CORS configuration.
+    #[default]
+    NotConfigured,
+    /// The bucket has CORS rules but none matches the request.
+    Denied,
+    /// A rule matches; these headers go on the response.
+    Allowed(HeaderMap),
+}
+
+/// Evaluate a request against the CORS rules of the bucket it addresses.
+///
+/// `OPTIONS` requests are evaluated as preflights, using
+/// `Access-Control-Request-Method` and `Access-Control-Request-Headers`.
+/// Requests without an `Origin`, a bucket, or (for a preflight) a requested
+/// method are reported as [`CorsOutcome::NotConfigured`].
+pub(crate) fn evaluate<H: S3Handler, B>(
+    handler: &H,
+    bucket: Option<&str>,
+    req: &http::Request<B>,
+) -> CorsOutcome {
+    let headers = req.headers();
+    let (Some(bucket), Some(origin)) = (bucket, header_str(headers, &header::ORIGIN)) else {
+        return CorsOutcome::NotConfigured;
+    };
+
+    if req.method() == http::Method::OPTIONS {
+        let Some(method) = header_str(headers, &header::ACCESS_CONTROL_REQUEST_METHOD) else {
+            return CorsOutcome::NotConfigured;
+        };
+        let requested = requested_headers(headers);
+        handler.evaluate_cors(&CorsRequest {
+            bucket,
+            origin,
+            method,
+            preflight_headers: Some(&requested),
+        })
+    } else {
+        handler.evaluate_cors(&CorsRequest {
+            bucket,
+            origin,
+            method: req.method().as_str(),
+            preflight_headers: None,
+        })
+    }
+}
+
+/// Add the CORS headers for `outcome` to a response.
+///
+/// A matched rule contributes its own headers and a denied request gets none.
+/// Otherwise the permissive defaults apply: any origin, and the S3 response
+/// headers clients commonly read.
+pub(crate) fn apply_headers(response: &mut http::Response<S3ResponseBody>, outcome: CorsOutcome) {
+    let headers = response.headers_mut();
+    match outcome {
+        CorsOutcome::Allowed(cors_headers) => headers.extend(cors_headers),
+        CorsOutcome::Denied => {}
+        CorsOutcome::NotConfigured => {
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_ORIGIN,
+                HeaderValue::from_static("*"),
+            );
+            headers.insert(
+                header::ACCESS_CONTROL_EXPOSE_HEADERS,
+                HeaderValue::from_static(
+                    "x-amz-request-id, x-amz-id-2, x-amz-version-id, ETag, x-amz-delete-marker",
+                ),
+            );
+        }
+    }
+}
+
+/// Split `Access-Control-Request-Headers` into header names.
+fn requested_headers(headers: &HeaderMap) -> Vec<String> {
+    headers
+        .get_all(header::ACCESS_CONTROL_REQUEST_HEADERS)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .filter(|h| !h.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &header::HeaderName) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+/// The error S3 returns for a preflight that matches no CORS rule.
+pub(crate) fn forbidden() -> S3Error {
+    let mut err = S3Error::with_message(
+        S3ErrorCode::Custom("AccessForbidden"),
+        "CORSResponse: This CORS request is not allowed. This is usually because the evalution of \
+         Origin, request method / Access-Control-Request-Method or Access-Control-Request-Headers \
+         are not whitelisted by the resource's CORS spec.",
+    );
+    err.status_code = http::StatusCode::FORBIDDEN;
+    err
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_split_requested_headers() {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            header::ACCESS_CONTROL_REQUEST_HEADERS,
+            "content-type, x-amz-meta-foo".parse().expect("valid"),
+        );
+        headers.append(
+            header::ACCESS_CONTROL_REQUEST_HEADERS,
+            "authorization,".parse().expect("valid"),
+        );
+        assert_eq!(
+            requested_headers(&headers),
+            vec!["content-type", "x-amz-meta-foo", "authorization"]
+        );
+    }
+}
@@ -14,9 +14,14 @@
 use std::{future::Future, pin::Pin};
 
 use bytes::Bytes;
+use rustack_core::AccessLogEntry;
 use rustack_s3_model::{S3Operation, error::S3Error};
 
-use crate::{body::S3ResponseBody, router::RoutingContext};
+use crate::{
+    body::S3ResponseBody,
+    cors::{CorsOutcome, CorsRequest},
+    router::{AccessPointRef, RoutingContext},
+};
 
 /// Trait that the business logic provider must implement.
 ///
@@ -40,6 +45,68 @@ pub trait S3Handler: Send + Sync + 'static {
         body: Bytes,
         ctx: RoutingContext,
     ) -> Pin<Box<dyn Future<Output = Result<http::Response<S3ResponseBody>, S3Error>> + Send>>;
+
+    /// Authorize an authenticated or anonymous request before dispatch.
+    ///
+    /// Called only when signature validation is enabled, after the signature
+    /// has been verified. `principal` is the caller's access key ID, or `None`
+    /// for an unsigned request. The default implementation allows everything.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, typically `AccessDenied`, to reject the request.
+    fn authorize(
+        &self,
+        _ctx: &RoutingContext,
+        _parts: &http::request::Parts,
+        _principal: Option<&str>,
+    ) -> Result<(), S3Error> {
+        Ok(())
+    }
+
+    /// Resolve the bucket behind an access point addressed in place of a bucket.
+    ///
+    /// Called after routing and before authorization. The default
+    /// implementation does not support access points.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, typically `NoSuchAccessPoint`, if the access point
+    /// cannot be resolved.
+    fn resolve_access_point(
+        &self,
+        access_point: &AccessPointRef,
+        _parts: &http::request::Parts,
+    ) -> Result<String, S3Error> {
+        Err(S3Error::not_implemented(format!(
+            "access point {}",
+            access_point.name
+        )))
+    }
+
+    /// Evaluate a cross-origin request against the bucket's CORS configuration.
+    ///
+    /// Called for preflight (`OPTIONS`) requests and for actual requests that
+    /// carry an `Origin` header, whether or not they are signed. The default
+    /// implementation reports that no bucket has a CORS configuration.
+    fn evaluate_cors(&self, _request: &CorsRequest<'_>) -> CorsOutcome {
+        CorsOutcome::NotConfigured
+    }
+
+    /// The region of the named bucket, reported in `x-amz-bucket-region`.
+    ///
+    /// Called for every request addressed to a bucket. The default
+    /// implementation reports no region.
+    fn bucket_region(&self, _bucket: &str) -> Option<String> {
+        None
+    }
+
+    /// Observe a completed request, e.g. to deliver S3 server access logs.
+    ///
+    /// Called once per request after the response has been produced, including
+    /// requests that failed before dispatch. The default implementation does
+    /// nothing.
+    fn record_access(&self, _entry: &AccessLogEntry) {}
 }
 
 /// Dispatch a routed S3 request to the handler.
@@ -94,6 +161,7 @@ mod tests {
             key: None,
             operation: S3Operation::ListObjects,
             query_params: vec![],
+            access_point: None,
         };
 
         let err = dispatch_operation(&handler, parts, Bytes::new(), ctx)
@@ -0,0 +1,358 @@
+//! Error injection for retry testing.
+//!
+//! A [`FaultInjector`] holds rules that answer matching requests with an S3
+//! error instead of dispatching them, so SDK retry behavior and backoff
+//! wrappers can be verified. A rule matches by operation name, bucket, and
+//! key prefix like a [latency rule](crate::latency), and fires with a
+//! configured probability, optionally only a fixed number of times: each
+//! firing decrements the rule's `count`, and the rule is removed once it
+//! reaches zero. The first matching rule that fires applies.
+//!
+//! Injected errors are rendered through the usual error serialization, so
+//! SDKs classify them by code and status as they would a real S3 error.
+//!
+//! # JSON form
+//!
+//! The management endpoint reads and writes rules as JSON:
+//!
+//! ```json
+//! {"operation":"GetObject","bucket":"data","keyPrefix":"flaky/",
+//!  "errorCode":"SlowDown","status":503,"count":2,"probability":1.0,
+//!  "requestId":"injected-1"}
+//! ```
+//!
+//! Every field may be omitted. `errorCode` defaults to `InternalError` and
+//! is one of [`FAULT_CODES`]; `status` defaults to the code's usual status;
+//! without `count` the rule fires indefinitely. With `requestId` set, the
+//! error carries that request id in its body and `x-amz-request-id` header
+//! instead of a fresh one, so tests can tell injected failures apart.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use parking_lot::Mutex;
+use rand::RngExt;
+use rustack_s3_model::error::{S3Error, S3ErrorCode};
+use serde::{Deserialize, Serialize};
+
+/// The error codes a fault rule may inject.
+pub const FAULT_CODES: &[&str] = &[
+    "InternalError",
+    "SlowDown",
+    "ServiceUnavailable",
+    "RequestTimeout",
+];
+
+/// One fault rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FaultRule {
+    /// Operation name, such as `GetObject`. Any operation if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation: Option<String>,
+    /// Bucket name. Any bucket if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<String>,
+    /// Prefix the object key must start with. Any key if unset; a set
+    /// prefix never matches requests without a key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_prefix: Option<String>,
+    /// The S3 error code returned, one of [`FAULT_CODES`].
+    #[serde(default = "default_error_code")]
+    pub error_code: String,
+    /// HTTP status of the error. The code's usual status if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// Times the rule still fires before it is removed. Unlimited if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+    /// Chance that a matching request fails, from 0 to 1.
+    #[serde(default = "default_probability")]
+    pub probability: f64,
+    /// Request id reported by the injected error. A fresh one if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+fn default_error_code() -> String {
+    "InternalError".to_owned()
+}
+
+fn default_probability() -> f64 {
+    1.0
+}
+
+impl FaultRule {
+    /// Check the rule's values are in range.
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason the rule is invalid.
+    pub fn validate(&self) -> Result<(), String> {
+        if fault_code(&self.error_code).is_none() {
+            return Err(format!(
+                "unsupported error code '{}', expected one of {}",
+                self.error_code,
+                FAULT_CODES.join(", ")
+            ));
+        }
+        if let Some(status) = self.status {
+            if !(400..=599).contains(&status) {
+                return Err(format!("status {status} is not an HTTP error status"));
+            }
+        }
+        if self.count == Some(0) {
+            return Err("count must be positive".to_owned());
+        }
+        if !(0.0..=1.0).contains(&self.probability) {
+            return Err(format!(
+                "probability {} is not between 0 and 1",
+                self.probability
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether the rule applies to a request for `operation` on `bucket`
+    /// and `key`.
+    fn matches(&self, operation: &str, bucket: Option<&str>, key: Option<&str>) -> bool {
+        self.operation.as_deref().is_none_or(|op| op == operation)
+            && self.bucket.as_deref().is_none_or(|b| bucket == Some(b))
+            && self
+                .key_prefix
+                .as_deref()
+                .is_none_or(|prefix| key.is_some_and(|key| key.starts_with(prefix)))
+    }
+
+    /// The error the rule injects.
+    fn error(&self) -> S3Error {
+        let (code, status) = fault_code(&self.error_code).unwrap_or((
+            S3ErrorCode::InternalError,
+            http::StatusCode::INTERNAL_SERVER_ERROR,
+        ));
+        let mut err = S3Error::new(code);
+        err.status_code = self
+            .status
+            .and_then(|status| http::StatusCode::from_u16(status).ok())
+            .unwrap_or(status);
+        err
+    }
+}
+
+/// The S3 error code and usual status of a [`FAULT_CODES`] entry.
+fn fault_code(code: &str) -> Option<(S3ErrorCode, http::StatusCode)> {
+    match code {
+        "InternalError" => Some((
+            S3ErrorCode::InternalError,
+            http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+        "SlowDown" => Some((
+            S3ErrorCode::Custom("SlowDown"),
+            http::StatusCode::SERVICE_UNAVAILABLE,
+        )),
+        "ServiceUnavailable" => Some((
+            S3ErrorCode::Custom("ServiceUnavailable"),
+            http::StatusCode::SERVICE_UNAVAILABLE,
+        )),
+        "RequestTimeout" => Some((
+            S3ErrorCode::Custom("RequestTimeout"),
+            http::StatusCode::BAD_REQUEST,
+        )),
+        _ => None,
+    }
+}
+
+/// The error injected into one request.
+#[derive(Debug)]
+pub struct Fault {
+    /// The error to answer with.
+    pub error: S3Error,
+    /// The request id to report instead of the request's own, if any.
+    pub request_id: Option<String>,
+}
+
+/// The active fault rules, replaceable at runtime.
+///
+/// With no rules, [`select`](Self::select) is a single atomic load.
+#[derive(Debug, Default)]
+pub struct FaultInjector {
+    rules: Mutex<Vec<FaultRule>>,
+    active: AtomicBool,
+}
+
+impl FaultInjector {
+    /// Create an injector applying `rules`.
+    #[must_use]
+    pub fn new(rules: Vec<FaultRule>) -> Self {
+        let injector = Self::default();
+        injector.set_rules(rules);
+        injector
+    }
+
+    /// The active rules, in match order, with their remaining counts.
+    #[must_use]
+    pub fn rules(&self) -> Vec<FaultRule> {
+        self.rules.lock().clone()
+    }
+
+    /// Replace the active rules.
+    pub fn set_rules(&self, rules: Vec<FaultRule>) {
+        let mut current = self.rules.lock();
+        self.active.store(!rules.is_empty(), Ordering::Relaxed);
+        *current = rules;
+    }
+
+    /// The error to inject into a request for `operation` on `bucket` and
+    /// `key`, if a rule matches and fires. A counted rule that fires is
+    /// decremented, and removed once exhausted.
+    #[must_use]
+    pub fn select(
+        &self,
+        operation: &str,
+        bucket: Option<&str>,
+        key: Option<&str>,
+    ) -> Option<Fault> {
+        if !self.active.load(Ordering::Relaxed) {
+            return None;
+        }
+        let mut rules = self.rules.lock();
+        let idx = rules.iter().position(|rule| {
+            rule.matches(operation, bucket, key)
+                && (rule.probability >= 1.0 || rand::rng().random_bool(rule.probability))
+        })?;
+        let rule = &mut rules[idx];
+        let fault = Fault {
+            error: rule.error(),
+            request_id: rule.request_id.clone(),
+        };
+        if let Some(count) = &mut rule.count {
+            *count -= 1;
+        }
+        if rule.count == Some(0) {
+            rules.remove(idx);
+            self.active.store(!rules.is_empty(), Ordering::Relaxed);
+        }
+        Some(fault)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule() -> FaultRule {
+        serde_json::from_str("{}").unwrap()
+    }
+
+    #[test]
+    fn test_should_default_omitted_fields() {
+        assert_eq!(
+            rule(),
+            FaultRule {
+                operation: None,
+                bucket: None,
+                key_prefix: None,
+                error_code: "InternalError".to_owned(),
+                status: None,
+                count: None,
+                probability: 1.0,
+                request_id: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_should_reject_invalid_rules() {
+        let invalid = [
+            FaultRule {
+                error_code: "NoSuchKey".to_owned(),
+                ..rule()
+            },
+            FaultRule {
+                status: Some(200),
+                ..rule()
+            },
+            FaultRule {
+                count: Some(0),
+                ..rule()
+            },
+            FaultRule {
+                probability: 1.5,
+                ..rule()
+            },
+        ];
+        for fault in invalid {
+            assert!(fault.validate().is_err(), "{fault:?}");
+        }
+        assert_eq!(rule().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_should_fire_counted_rule_then_remove_it() {
+        let injector = FaultInjector::new(vec![FaultRule {
+            operation: Some("GetObject".to_owned()),
+            key_prefix: Some("flaky/".to_owned()),
+            error_code: "SlowDown".to_owned(),
+            count: Some(2),
+            request_id: Some("injected".to_owned()),
+            ..rule()
+        }]);
+
+        assert!(
+            injector
+                .select("GetObject", Some("b"), Some("ok"))
+                .is_none()
+        );
+        assert!(
+            injector
+                .select("PutObject", Some("b"), Some("flaky/a"))
+                .is_none()
+        );
+
+        let fault = injector
+            .select("GetObject", Some("b"), Some("flaky/a"))
+            .unwrap();
+        assert_eq!(fault.error.code.as_str(), "SlowDown");
+        assert_eq!(
+            fault.error.status_code,
+            http::StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(fault.request_id.as_deref(), Some("injected"));
+        assert_eq!(injector.rules()[0].count, Some(1));
+
+        assert!(
+            injector
+                .select("GetObject", Some("b"), Some("flaky/a"))
+                .is_some()
+        );
+        assert_eq!(injector.rules(), []);
+        assert!(
+            injector
+                .select("GetObject", Some("b"), Some("flaky/a"))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_should_override_status_and_skip_unfired_rules() {
+        let injector = FaultInjector::new(vec![
+            FaultRule {
+                probability: 0.0,
+                count: Some(1),
+                ..rule()
+            },
+            FaultRule {
+                bucket: Some("data".to_owned()),
+                status: Some(502),
+                ..rule()
+            },
+        ]);
+
+        let fault = injector.select("HeadObject", Some("data"), None).unwrap();
+        assert_eq!(fault.error.code, S3ErrorCode::InternalError);
+        assert_eq!(fault.error.status_code, http::StatusCode::BAD_GATEWAY);
+        assert_eq!(fault.request_id, None);
+        // The rule that did not fire keeps its count.
+        assert_eq!(injector.rules()[0].count, Some(1));
+        assert!(injector.select("HeadObject", Some("other"), None).is_none());
+    }
+}
@@ -0,0 +1,466 @@
+//! Latency injection for resilience testing.
+//!
+//! A [`LatencyInjector`] holds rules that delay matching requests before
+//! they are dispatched to the handler, so client timeouts and retry
+//! policies can be exercised against a slow S3. A rule matches by operation
+//! name, bucket, and key prefix, and fires with a configured probability.
+//! It can also drip-feed the response body at a fixed rate to simulate a
+//! slow network. The first matching rule that fires applies; later rules
+//! are not considered.
+//!
+//! # Rule syntax
+//!
+//! Rules are written as a comma-separated list, as in `S3_LATENCY_RULES`:
+//!
+//! ```text
+//! GetObject:200ms±50,PutObject@uploads/tmp/:p(0.01)=5s,*@slow:100ms~65536
+//! ```
+//!
+//! Each rule is `<operation>[@<bucket>[/<key-prefix>]]:<delay>`, where the
+//! operation may be `*` for any, and the delay is
+//! `[p(<probability>)=]<duration>[±<jitter>][~<bytes-per-second>]`.
+//! Durations take an `ms` or `s` unit; a jitter without one uses the
+//! delay's unit, and `+-` may be written for `±`. A jittered delay is drawn
+//! uniformly from `duration - jitter` to `duration + jitter`.
+//!
+//! # JSON form
+//!
+//! The management endpoint reads and writes rules as JSON:
+//!
+//! ```json
+//! {"operation":"GetObject","bucket":"data","keyPrefix":"logs/",
+//!  "delayMs":200,"jitterMs":50,"probability":1.0,"dripBytesPerSec":65536}
+//! ```
+//!
+//! Every field but `delayMs` may be omitted.
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use parking_lot::RwLock;
+use rand::RngExt;
+use serde::{Deserialize, Serialize};
+
+/// One latency rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyRule {
+    /// Operation name, such as `GetObject`. Any operation if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation: Option<String>,
+    /// Bucket name. Any bucket if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<String>,
+    /// Prefix the object key must start with. Any key if unset; a set
+    /// prefix never matches requests without a key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_prefix: Option<String>,
+    /// Delay before the request is dispatched, in milliseconds.
+    pub delay_ms: u64,
+    /// Largest deviation from `delay_ms`, in milliseconds.
+    #[serde(default)]
+    pub jitter_ms: u64,
+    /// Chance that a matching request is delayed, from 0 to 1.
+    #[serde(default = "default_probability")]
+    pub probability: f64,
+    /// Rate at which the response body is delivered, in bytes per second.
+    /// The body is sent at once if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drip_bytes_per_sec: Option<u64>,
+}
+
+fn default_probability() -> f64 {
+    1.0
+}
+
+/// A latency rule that could not be parsed or is out of range.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid latency rule '{rule}': {reason}")]
+pub struct LatencyRuleError {
+    /// The offending rule, as written.
+    pub rule: String,
+    /// What is wrong with it.
+    pub reason: String,
+}
+
+impl LatencyRuleError {
+    fn new(rule: &str, reason: impl Into<String>) -> Self {
+        Self {
+            rule: rule.to_owned(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl LatencyRule {
+    /// Parse a comma-separated list of rules; see the [module docs](self)
+    /// for the syntax. An empty or blank `spec` holds no rules.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first rule that is malformed or out of
+    /// range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustack_s3_http::latency::LatencyRule;
+    ///
+    /// let rules = LatencyRule::parse_list("GetObject:200ms±50,PutObject:p(0.01)=5s").unwrap();
+    /// assert_eq!(rules[0].delay_ms, 200);
+    /// assert_eq!(rules[0].jitter_ms, 50);
+    /// assert_eq!(rules[1].delay_ms, 5000);
+    /// assert!((rules[1].probability - 0.01).abs() < f64::EPSILON);
+    /// ```
+    pub fn parse_list(spec: &str) -> Result<Vec<Self>, LatencyRuleError> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty())
+            .map(Self::parse)
+            .collect()
+    }
+
+    /// Parse one rule.
+    fn parse(rule: &str) -> Result<Self, LatencyRuleError> {
+        let (target, delay) = rule
+            .split_once(':')
+            .ok_or_else(|| LatencyRuleError::new(rule, "expected '<operation>:<delay>'"))?;
+
+        let (operation, location) = match target.split_once('@') {
+            Some((operation, location)) => (operation, Some(location)),
+            None => (target, None),
+        };
+        let (bucket, key_prefix) = match location {
+            Some(location) => match location.split_once('/') {
+                Some((bucket, prefix)) => (Some(bucket), Some(prefix)),
+                None => (Some(location), None),
+            },
+            None => (None, None),
+        };
+
+        let (probability, delay) = match delay.strip_prefix("p(") {
+            Some(rest) => {
+                let (probability, delay) = rest
+                    .split_once(")=")
+                    .ok_or_else(|| LatencyRuleError::new(rule, "expected 'p(<probability>)='"))?;
+                let probability = probability.parse::<f64>().map_err(|_| {
+                    LatencyRuleError::new(rule, format!("invalid probability '{probability}'"))
+                })?;
+                (probability, delay)
+            }
+            None => (1.0, delay),
+        };
+        let (delay, drip) = match delay.split_once('~') {
+            Some((delay, drip)) => {
+                let drip = drip.parse::<u64>().map_err(|_| {
+                    LatencyRuleError::new(rule, format!("invalid drip rate '{drip}'"))
+                })?;
+                (delay, Some(drip))
+            }
+            None => (delay, None),
+        };
+        let (delay, jitter) = match delay.split_once('±').or_else(|| delay.split_once("+-")) {
+            Some((delay, jitter)) => (delay, Some(jitter)),
+            None => (delay, None),
+        };
+        let (delay_ms, unit) = parse_duration(delay, None)
+            .ok_or_else(|| LatencyRuleError::new(rule, format!("invalid delay '{delay}'")))?;
+        let jitter_ms = match jitter {
+            Some(jitter) => {
+                parse_duration(jitter, Some(unit))
+                    .ok_or_else(|| {
+                        LatencyRuleError::new(rule, format!("invalid jitter '{jitter}'"))
+                    })?
+                    .0
+            }
+            None => 0,
+        };
+
+        let parsed = Self {
+            operation: Some(operation)
+                .filter(|op| !op.is_empty() && *op != "*")
+                .map(str::to_owned),
+            bucket: bucket.filter(|b| !b.is_empty()).map(str::to_owned),
+            key_prefix: key_prefix.filter(|p| !p.is_empty()).map(str::to_owned),
+            delay_ms,
+            jitter_ms,
+            probability,
+            drip_bytes_per_sec: drip,
+        };
+        parsed
+            .validate()
+            .map_err(|reason| LatencyRuleError::new(rule, reason))?;
+        Ok(parsed)
+    }
+
+    /// Check the rule's values are in range.
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason the rule is invalid.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.probability) {
+            return Err(format!(
+                "probability {} is not between 0 and 1",
+                self.probability
+            ));
+        }
+        if self.drip_bytes_per_sec == Some(0) {
+            return Err("drip rate must be positive".to_owned());
+        }
+        Ok(())
+    }
+
+    /// Whether the rule applies to a request for `operation` on `bucket`
+    /// and `key`.
+    fn matches(&self, operation: &str, bucket: Option<&str>, key: Option<&str>) -> bool {
+        self.operation.as_deref().is_none_or(|op| op == operation)
+            && self.bucket.as_deref().is_none_or(|b| bucket == Some(b))
+            && self
+                .key_prefix
+                .as_deref()
+                .is_none_or(|prefix| key.is_some_and(|key| key.starts_with(prefix)))
+    }
+
+    /// The delay for one request, with jitter applied.
+    fn draw_delay(&self) -> Duration {
+        let millis = if self.jitter_ms == 0 {
+            self.delay_ms
+        } else {
+            let low = self.delay_ms.saturating_sub(self.jitter_ms);
+            let high = self.delay_ms.saturating_add(self.jitter_ms);
+            rand::rng().random_range(low..=high)
+        };
+        Duration::from_millis(millis)
+    }
+}
+
+/// Parse a duration in milliseconds, returning it with its unit's scale.
+/// A bare number takes `default_unit`, and is rejected without one.
+fn parse_duration(value: &str, default_unit: Option<u64>) -> Option<(u64, u64)> {
+    let value = value.trim();
+    let (number, scale) = if let Some(number) = value.strip_suffix("ms") {
+        (number, 1)
+    } else if let Some(number) = value.strip_suffix('s') {
+        (number, 1000)
+    } else {
+        (value, default_unit?)
+    };
+    let number = number.trim().parse::<u64>().ok()?;
+    Some((number.checked_mul(scale)?, scale))
+}
+
+/// The latency chosen for one request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Injection {
+    /// How long to wait before dispatching the request.
+    pub delay: Duration,
+    /// Rate at which to deliver the response body, if throttled.
+    pub drip_bytes_per_sec: Option<u64>,
+}
+
+/// The active latency rules, replaceable at runtime.
+///
+/// With no rules, [`select`](Self::select) is a single atomic load.
+#[derive(Debug, Default)]
+pub struct LatencyInjector {
+    rules: RwLock<Arc<Vec<LatencyRule>>>,
+    active: AtomicBool,
+}
+
+impl LatencyInjector {
+    /// Create an injector applying `rules`.
+    #[must_use]
+    pub fn new(rules: Vec<LatencyRule>) -> Self {
+        let injector = Self::default();
+        injector.set_rules(rules);
+        injector
+    }
+
+    /// The active rules, in match order.
+    #[must_use]
+    pub fn rules(&self) -> Vec<LatencyRule> {
+        self.rules.read().as_ref().clone()
+    }
+
+    /// Replace the active rules.
+    pub fn set_rules(&self, rules: Vec<LatencyRule>) {
+        let mut current = self.rules.write();
+        self.active.store(!rules.is_empty(), Ordering::Relaxed);
+        *current = Arc::new(rules);
+    }
+
+    /// The latency to inject into a request for `operation` on `bucket` and
+    /// `key`, if a rule matches and fires.
+    #[must_use]
+    pub fn select(
+        &self,
+        operation: &str,
+        bucket: Option<&str>,
+        key: Option<&str>,
+    ) -> Option<Injection> {
+        if !self.active.load(Ordering::Relaxed) {
+            return None;
+        }
+        let rules = Arc::clone(&self.rules.read());
+        rules
+            .iter()
+            .filter(|rule| rule.matches(operation, bucket, key))
+            .find(|rule| rule.probability >= 1.0 || rand::rng().random_bool(rule.probability))
+            .map(|rule| Injection {
+                delay: rule.draw_delay(),
+                drip_bytes_per_sec: rule.drip_bytes_per_sec,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(delay_ms: u64) -> LatencyRule {
+        LatencyRule {
+            operation: None,
+            bucket: None,
+            key_prefix: None,
+            delay_ms,
+            jitter_ms: 0,
+            probability: 1.0,
+            drip_bytes_per_sec: None,
+        }
+    }
+
+    #[test]
+    fn test_should_parse_rule_list() {
+        let rules = LatencyRule::parse_list(
+            "GetObject:200ms±50, PutObject@uploads/tmp/:p(0.25)=5s, *@slow:100ms+-1s~65536",
+        )
+        .unwrap();
+        assert_eq!(
+            rules,
+            [
+                LatencyRule {
+                    operation: Some("GetObject".to_owned()),
+                    jitter_ms: 50,
+                    ..rule(200)
+                },
+                LatencyRule {
+                    operation: Some("PutObject".to_owned()),
+                    bucket: Some("uploads".to_owned()),
+                    key_prefix: Some("tmp/".to_owned()),
+                    probability: 0.25,
+                    ..rule(5000)
+                },
+                LatencyRule {
+                    bucket: Some("slow".to_owned()),
+                    jitter_ms: 1000,
+                    drip_bytes_per_sec: Some(65536),
+                    ..rule(100)
+                },
+            ]
+        );
+        assert_eq!(LatencyRule::parse_list(" ").unwrap(), []);
+    }
+
+    #[test]
+    fn test_should_reject_malformed_rules() {
+        for spec in [
+            "GetObject",
+            "GetObject:200",
+            "GetObject:fast",
+            "GetObject:p(2)=1s",
+            "GetObject:p(x)=1s",
+            "GetObject:1s~0",
+            "GetObject:1s±x",
+        ] {
+            let err = LatencyRule::parse_list(spec).unwrap_err();
+            assert_eq!(err.rule, spec);
+        }
+    }
+
+    #[test]
+    fn test_should_select_first_matching_rule() {
+        let injector = LatencyInjector::new(vec![
+            LatencyRule {
+                operation: Some("GetObject".to_owned()),
+                key_prefix: Some("logs/".to_owned()),
+                ..rule(10)
+            },
+            LatencyRule {
+                bucket: Some("data".to_owned()),
+                drip_bytes_per_sec: Some(100),
+                ..rule(20)
+            },
+        ]);
+
+        let delay = |op, bucket, key| {
+            injector
+                .select(op, bucket, key)
+                .map(|i| i.delay.as_millis())
+        };
+        assert_eq!(delay("GetObject", Some("data"), Some("logs/a")), Some(10));
+        assert_eq!(delay("GetObject", Some("data"), Some("img/a")), Some(20));
+        assert_eq!(delay("ListObjectsV2", Some("data"), None), Some(20));
+        assert_eq!(delay("GetObject", Some("other"), None), None);
+        assert_eq!(
+            injector
+                .select("PutObject", Some("data"), Some("k"))
+                .and_then(|i| i.drip_bytes_per_sec),
+            Some(100)
+        );
+    }
+
+    #[test]
+    fn test_should_apply_probability_and_jitter() {
+        let injector = LatencyInjector::new(vec![LatencyRule {
+            probability: 0.0,
+            ..rule(10)
+        }]);
+        assert_eq!(injector.select("GetObject", None, None), None);
+
+        let jittered = LatencyRule {
+            jitter_ms: 5,
+            ..rule(10)
+        };
+        for _ in 0..100 {
+            let delay = jittered.draw_delay().as_millis();
+            assert!((5..=15).contains(&delay), "delay {delay}ms out of range");
+        }
+    }
+
+    #[test]
+    fn test_should_replace_and_clear_rules() {
+        let injector = LatencyInjector::default();
+        assert_eq!(injector.select("GetObject", None, None), None);
+
+        injector.set_rules(vec![rule(10)]);
+        assert_eq!(injector.rules(), [rule(10)]);
+        assert!(injector.select("GetObject", None, None).is_some());
+
+        injector.set_rules(Vec::new());
+        assert_eq!(injector.rules(), []);
+        assert_eq!(injector.select("GetObject", None, None), None);
+    }
+
+    #[test]
+    fn test_should_round_trip_rules_as_json() {
+        let rules: Vec<LatencyRule> =
+            serde_json::from_str(r#"[{"operation":"GetObject","delayMs":200}]"#).unwrap();
+        assert_eq!(
+            rules,
+            [LatencyRule {
+                operation: Some("GetObject".to_owned()),
+                ..rule(200)
+            }]
+        );
+        assert_eq!(
+            serde_json::to_string(&rules).unwrap(),
+            r#"[{"operation":"GetObject","delayMs":200,"jitterMs":0,"probability":1.0}]"#
+        );
+    }
+}
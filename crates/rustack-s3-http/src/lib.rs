@@ -17,6 +17,20 @@
 //! - **Service** ([`service`]): The main [`S3HttpService`](service::S3HttpService) that implements
 //!   hyper's `Service` trait, tying routing, auth, dispatch, and middleware together.
 //!
+//! - **CORS** ([`cors`]): Evaluates preflight and actual cross-origin requests against bucket CORS
+//!   configurations.
+//!
+//! - **POST Object** ([`post_object`]): Parses browser-based form uploads and enforces their policy
+//!   documents.
+//!
+//! - **Fixtures** ([`fixture`]): Records request/response pairs to disk and replays them without
+//!   calling the handler, for hermetic tests.
+//!
+//! - **Latency** ([`latency`]): Injects configurable delays before dispatch, and drip-feeds
+//!   response bodies, for resilience testing.
+//!
+//! - **Faults** ([`fault`]): Answers matching requests with injected S3 errors, for retry testing.
+//!
 //! - **Body** ([`body`]): The [`S3ResponseBody`](body::S3ResponseBody) type supporting buffered and
 //!   empty response modes.
 //!
@@ -54,8 +68,13 @@
 
 pub mod body;
 pub mod codec;
+pub mod cors;
 pub mod dispatch;
+pub mod fault;
+pub mod fixture;
+pub mod latency;
 pub mod multipart;
+pub mod post_object;
 pub mod request;
 pub mod response;
 pub mod router;
@@ -17,6 +17,8 @@ pub struct MultipartForm {
     pub file_data: Bytes,
     /// The Content-Type of the file field, if specified.
     pub file_content_type: Option<String>,
+    /// The `filename` of the file field's Content-Disposition, if specified.
+    pub file_name: Option<String>,
 }
 
 /// Extract the boundary string from a `Content-Type: multipart/form-data; boundary=...` header.
@@ -72,6 +74,7 @@ pub fn parse_multipart(body: &[u8], boundary: &str) -> Result<MultipartForm, S3E
     let mut fields: HashMap<String, String> = HashMap::new();
     let mut file_data: Option<Bytes> = None;
     let mut file_content_type: Option<String> = None;
+    let mut file_name: Option<String> = None;
 
     // Split body by boundary delimiters.
     let parts = split_multipart_parts(body, delimiter.as_bytes(), end_delimiter.as_bytes());
@@ -91,6 +94,7 @@ pub fn parse_multipart(body: &[u8], boundary: &str) -> Result<MultipartForm, S3E
         if field_name == "file" || disposition.filename.is_some() {
             // This is the file field — extract content type and data.
             file_content_type = parse_part_content_type(headers_section);
+            file_name = disposition.filename;
             file_data = Some(Bytes::copy_from_slice(part_body));
         } else {
             // Regular form field — store as string.
@@ -110,6 +114,7 @@ pub fn parse_multipart(body: &[u8], boundary: &str) -> Result<MultipartForm, S3E
         fields,
         file_data,
         file_content_type,
+        file_name,
     })
 }
 
@@ -303,6 +308,7 @@ mod tests {
         );
         assert_eq!(result.file_data.as_ref(), b"hello world");
         assert_eq!(result.file_content_type.as_deref(), Some("text/plain"));
+        assert_eq!(result.file_name.as_deref(), Some("test.txt"));
     }
 
     #[test]
@@ -0,0 +1,763 @@
+//! S3 POST Object (browser-based form upload).
+//!
+//! A POST upload sends the object and its settings as `multipart/form-data`
+//! fields instead of a path, headers, and raw body. [`PostObjectForm`] parses
+//! such a request, checks the optional base64 [`PostPolicy`] document against
+//! the form, converts the form into a [`PutObjectInput`], and builds the
+//! response selected by `success_action_redirect` or `success_action_status`.
+//!
+//! The policy's signature is verified separately by
+//! [`rustack_auth::verify_post_policy`] in the service layer, and only when
+//! signature validation is enabled. Policy conditions are always enforced.
+
+use std::{collections::HashMap, fmt};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use rustack_s3_model::{
+    error::{S3Error, S3ErrorCode},
+    input::PutObjectInput,
+    types::Tagging,
+};
+use serde_json::Value;
+
+use crate::{
+    body::S3ResponseBody,
+    multipart,
+    request::{FromS3Request, parse_xml_body},
+};
+
+/// Characters escaped in redirect query values.
+const QUERY_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// Characters escaped in the object path of the `Location` header.
+const PATH_ENCODE_SET: &AsciiSet = &QUERY_ENCODE_SET.remove(b'/');
+
+/// Form fields carrying credentials, which are never stored on the object.
+const CREDENTIAL_FIELDS: &[&str] = &[
+    "x-amz-algorithm",
+    "x-amz-credential",
+    "x-amz-date",
+    "x-amz-security-token",
+    "x-amz-signature",
+];
+
+/// A parsed POST Object form.
+#[derive(Debug, Clone)]
+pub struct PostObjectForm {
+    /// Form fields keyed by lowercase name, excluding the file.
+    pub fields: HashMap<String, String>,
+    /// The object key, with `${filename}` replaced by the uploaded file's name.
+    pub key: String,
+    /// The uploaded object data.
+    pub file_data: Bytes,
+    /// The Content-Type of the file part, used when the form has no
+    /// `Content-Type` field.
+    pub file_content_type: Option<String>,
+}
+
+impl PostObjectForm {
+    /// Parse a POST Object request from its `multipart/form-data` body.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidRequest` if the body is not well-formed form data or the
+    /// `key` field is missing.
+    pub fn parse(parts: &http::request::Parts, body: &[u8]) -> Result<Self, S3Error> {
+        let content_type = parts
+            .headers
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| {
+                S3Error::with_message(
+                    S3ErrorCode::InvalidRequest,
+                    "POST Object requires Content-Type header",
+                )
+            })?;
+        let boundary = multipart::extract_boundary(content_type)?;
+        let form = multipart::parse_multipart(body, &boundary)?;
+
+        // Form field names are case-insensitive.
+        let fields: HashMap<String, String> = form
+            .fields
+            .into_iter()
+            .map(|(name, value)| (name.to_ascii_lowercase(), value))
+            .collect();
+
+        let key = fields
+            .get("key")
+            .ok_or_else(|| {
+                S3Error::with_message(
+                    S3ErrorCode::InvalidRequest,
+                    "Missing required 'key' field in POST form data",
+                )
+            })?
+            .replace("${filename}", form.file_name.as_deref().unwrap_or_default());
+
+        Ok(Self {
+            fields,
+            key,
+            file_data: form.file_data,
+            file_content_type: form.file_content_type,
+        })
+    }
+
+    /// Decode the form's policy document, if it has one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidPolicyDocument` if the policy is not valid base64 JSON.
+    pub fn policy(&self) -> Result<Option<PostPolicy>, S3Error> {
+        self.fields
+            .get("policy")
+            .map(|encoded| PostPolicy::decode(encoded))
+            .transpose()
+    }
+
+    /// Check the form against its policy document, if it has one.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from [`PostPolicy::check`], or `InvalidPolicyDocument`
+    /// if the policy cannot be decoded.
+    pub fn check_policy(&self, bucket: &str, now: DateTime<Utc>) -> Result<(), S3Error> {
+        match self.policy()? {
+            Some(policy) => policy.check(self, bucket, now),
+            None => Ok(()),
+        }
+    }
+
+    /// Convert the form into the equivalent `PutObject` input.
+    ///
+    /// Object settings such as `Content-Type`, `Cache-Control`, `acl`,
+    /// `tagging`, and `x-amz-meta-*` are taken from the form fields.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a field value is not a valid header value, the
+    /// `tagging` document is malformed, or an object setting is invalid.
+    pub fn to_put_object_input(&self, bucket: &str) -> Result<PutObjectInput, S3Error> {
+        let mut headers = http::HeaderMap::new();
+        for (name, value) in &self.fields {
+            let header = match name.as_str() {
+                "cache-control"
+                | "content-disposition"
+                | "content-encoding"
+                | "content-language"
+                | "content-type"
+                | "expires" => name.as_str(),
+                "acl" => "x-amz-acl",
+                "tagging" => "x-amz-tagging",
+                other if other.starts_with("x-amz-") && !CREDENTIAL_FIELDS.contains(&other) => {
+                    other
+                }
+                _ => continue,
+            };
+            let value = if name == "tagging" {
+                tagging_to_query(value)?
+            } else {
+                value.clone()
+            };
+            headers.insert(
+                http::HeaderName::from_bytes(header.as_bytes()).map_err(|_| {
+                    S3Error::invalid_argument(format!("Invalid form field name: {name}"))
+                })?,
+                http::HeaderValue::try_from(value).map_err(|_| {
+                    S3Error::invalid_argument(format!("Invalid value for form field: {name}"))
+                })?,
+            );
+        }
+        if !headers.contains_key(http::header::CONTENT_TYPE) {
+            if let Some(value) = self
+                .file_content_type
+                .as_deref()
+                .and_then(|ct| http::HeaderValue::from_str(ct).ok())
+            {
+                headers.insert(http::header::CONTENT_TYPE, value);
+            }
+        }
+
+        let mut request = http::Request::new(());
+        *request.headers_mut() = headers;
+        let (parts, ()) = request.into_parts();
+        PutObjectInput::from_s3_request(
+            &parts,
+            Some(bucket),
+            Some(&self.key),
+            &[],
+            self.file_data.clone(),
+        )
+    }
+
+    /// Build the response for a successful upload.
+    ///
+    /// A valid `success_action_redirect` (or legacy `redirect`) URL yields a
+    /// `303 See Other` to that URL with `bucket`, `key`, and `etag` appended.
+    /// Otherwise `success_action_status` selects `200` (empty), `201` (a
+    /// `PostResponse` XML document), or the default `204`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InternalError` if the response cannot be built.
+    pub fn success_response(
+        &self,
+        bucket: &str,
+        etag: Option<&str>,
+    ) -> Result<http::Response<S3ResponseBody>, S3Error> {
+        let build_err = |e: http::Error| S3Error::internal_error(e.to_string());
+
+        let redirect = self
+            .fields
+            .get("success_action_redirect")
+            .or_else(|| self.fields.get("redirect"))
+            .filter(|url| {
+                url.parse::<http::Uri>()
+                    .is_ok_and(|uri| uri.scheme().is_some())
+            });
+        if let Some(url) = redirect {
+            let separator = if url.contains('?') { '&' } else { '?' };
+            let location = format!(
+                "{url}{separator}bucket={}&key={}&etag={}",
+                utf8_percent_encode(bucket, QUERY_ENCODE_SET),
+                utf8_percent_encode(&self.key, QUERY_ENCODE_SET),
+                utf8_percent_encode(etag.unwrap_or_default(), QUERY_ENCODE_SET),
+            );
+            let mut builder = http::Response::builder()
+                .status(http::StatusCode::SEE_OTHER)
+                .header(http::header::LOCATION, location);
+            if let Some(etag) = etag {
+                builder = builder.header(http::header::ETAG, etag);
+            }
+            return builder.body(S3ResponseBody::empty()).map_err(build_err);
+        }
+
+        let status = match self.fields.get("success_action_status").map(String::as_str) {
+            Some("200") => http::StatusCode::OK,
+            Some("201") => http::StatusCode::CREATED,
+            _ => http::StatusCode::NO_CONTENT,
+        };
+        let location = format!(
+            "/{bucket}/{}",
+            utf8_percent_encode(&self.key, PATH_ENCODE_SET)
+        );
+        let mut builder = http::Response::builder()
+            .status(status)
+            .header(http::header::LOCATION, &location);
+        if let Some(etag) = etag {
+            builder = builder.header(http::header::ETAG, etag);
+        }
+
+        if status != http::StatusCode::CREATED {
+            return builder.body(S3ResponseBody::empty()).map_err(build_err);
+        }
+        let xml = format!(
+            "<?xml version=\"1.0\" \
+             encoding=\"UTF-8\"?>\n<PostResponse><Location>{}</Location><Bucket>{}</\
+             Bucket><Key>{}</Key><ETag>{}</ETag></PostResponse>",
+            xml_escape(&location),
+            xml_escape(bucket),
+            xml_escape(&self.key),
+            xml_escape(etag.unwrap_or_default()),
+        );
+        builder
+            .header(http::header::CONTENT_TYPE, "application/xml")
+            .body(S3ResponseBody::from_string(xml))
+            .map_err(build_err)
+    }
+}
+
+/// A decoded POST policy document.
+///
+/// ```json
+/// {
+///   "expiration": "2030-01-01T00:00:00.000Z",
+///   "conditions": [
+///     {"bucket": "uploads"},
+///     ["starts-with", "$key", "user/"],
+///     ["content-length-range", 1, 10485760]
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PostPolicy {
+    /// The time after which the policy is no longer valid.
+    pub expiration: DateTime<Utc>,
+    /// The conditions every upload using this policy must satisfy.
+    pub conditions: Vec<PolicyCondition>,
+}
+
+/// A single POST policy condition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyCondition {
+    /// The field must equal `value` (`{"field": "value"}` or `["eq", "$field", "value"]`).
+    Eq {
+        /// The lowercase form field name, or `bucket`.
+        field: String,
+        /// The required value.
+        value: String,
+    },
+    /// The field must start with `prefix`; an empty prefix allows any value.
+    StartsWith {
+        /// The lowercase form field name, or `bucket`.
+        field: String,
+        /// The required prefix.
+        prefix: String,
+    },
+    /// The uploaded file's size in bytes must be within `min..=max`.
+    ContentLengthRange {
+        /// The minimum size.
+        min: u64,
+        /// The maximum size.
+        max: u64,
+    },
+}
+
+impl fmt::Display for PolicyCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Eq { field, value } => write!(f, "[\"eq\", \"${field}\", \"{value}\"]"),
+            Self::StartsWith { field, prefix } => {
+                write!(f, "[\"starts-with\", \"${field}\", \"{prefix}\"]")
+            }
+            Self::ContentLengthRange { min, max } => {
+                write!(f, "[\"content-length-range\", {min}, {max}]")
+            }
+        }
+    }
+}
+
+impl PostPolicy {
+    /// Decode a base64-encoded JSON policy document.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidPolicyDocument` if the document is not valid base64 or
+    /// JSON, or its `expiration` or `conditions` are malformed.
+    pub fn decode(encoded: &str) -> Result<Self, S3Error> {
+        let json = BASE64
+            .decode(encoded.trim())
+            .map_err(|_| invalid_policy("Invalid Policy: Invalid Simple-Base64 value."))?;
+        let document: Value = serde_json::from_slice(&json)
+            .map_err(|_| invalid_policy("Invalid Policy: Invalid JSON."))?;
+
+        let expiration = document
+            .get("expiration")
+            .and_then(Value::as_str)
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .ok_or_else(|| invalid_policy("Invalid Policy: Invalid 'expiration' value."))?
+            .with_timezone(&Utc);
+
+        let mut conditions = Vec::new();
+        for condition in document
+            .get("conditions")
+            .and_then(Value::as_array)
+            .ok_or_else(|| invalid_policy("Invalid Policy: Missing 'conditions' field."))?
+        {
+            parse_condition(condition, &mut conditions)?;
+        }
+
+        Ok(Self {
+            expiration,
+            conditions,
+        })
+    }
+
+    /// Check a form against this policy.
+    ///
+    /// Every form field other than the policy, signature, access key, file,
+    /// and `x-ignore-*` fields must be covered by a condition.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AccessDenied` if the policy has expired, a condition fails, or
+    /// the form has fields the policy does not cover, and `EntityTooSmall` or
+    /// `EntityTooLarge` if the file is outside the `content-length-range`.
+    pub fn check(
+        &self,
+        form: &PostObjectForm,
+        bucket: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(), S3Error> {
+        if now > self.expiration {
+            return Err(policy_denied("Policy expired."));
+        }
+
+        let field_value = |field: &str| {
+            if field == "bucket" {
+                bucket
+            } else {
+                form.fields.get(field).map_or("", String::as_str)
+            }
+        };
+
+        for condition in &self.conditions {
+            let satisfied = match condition {
+                PolicyCondition::Eq { field, value } => field_value(field) == value,
+                PolicyCondition::StartsWith { field, prefix } => {
+                    field_value(field).starts_with(prefix.as_str())
+                }
+                PolicyCondition::ContentLengthRange { min, max } => {
+                    let len = form.file_data.len() as u64;
+                    if len < *min {
+                        return Err(S3Error::with_message(
+                            S3ErrorCode::EntityTooSmall,
+                            "Your proposed upload is smaller than the minimum allowed size",
+                        ));
+                    }
+                    if len > *max {
+                        return Err(S3Error::with_message(
+                            S3ErrorCode::EntityTooLarge,
+                            "Your proposed upload exceeds the maximum allowed size",
+                        ));
+                    }
+                    true
+                }
+            };
+            if !satisfied {
+                return Err(policy_denied(format!(
+                    "Policy Condition failed: {condition}"
+                )));
+            }
+        }
+
+        let mut extra: Vec<&str> = form
+            .fields
+            .keys()
+            .map(String::as_str)
+            .filter(|name| !is_exempt_field(name) && !self.covers(name))
+            .collect();
+        if !extra.is_empty() {
+            extra.sort_unstable();
+            return Err(policy_denied(format!(
+                "Extra input fields: {}",
+                extra.join(", ")
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether a condition names the given field.
+    fn covers(&self, name: &str) -> bool {
+        self.conditions.iter().any(|condition| match condition {
+            PolicyCondition::Eq { field, .. } | PolicyCondition::StartsWith { field, .. } => {
+                field == name
+            }
+            PolicyCondition::ContentLengthRange { .. } => false,
+        })
+    }
+}
+
+/// Parse one entry of a policy's `conditions` array.
+fn parse_condition(value: &Value, out: &mut Vec<PolicyCondition>) -> Result<(), S3Error> {
+    let invalid = || invalid_policy(format!("Invalid Policy: Invalid condition: {value}"));
+
+    match value {
+        Value::Object(entries) => {
+            for (field, value) in entries {
+                out.push(PolicyCondition::Eq {
+                    field: field.trim_start_matches('$').to_ascii_lowercase(),
+                    value: value.as_str().ok_or_else(invalid)?.to_owned(),
+                });
+            }
+        }
+        Value::Array(items) => {
+            let [op, first, second] = items.as_slice() else {
+                return Err(invalid());
+            };
+            let op = op.as_str().ok_or_else(invalid)?.to_ascii_lowercase();
+            if op == "content-length-range" {
+                out.push(PolicyCondition::ContentLengthRange {
+                    min: json_u64(first).ok_or_else(invalid)?,
+                    max: json_u64(second).ok_or_else(invalid)?,
+                });
+                return Ok(());
+            }
+
+            let field = first
+                .as_str()
+                .and_then(|f| f.strip_prefix('$'))
+                .ok_or_else(invalid)?
+                .to_ascii_lowercase();
+            let operand = second.as_str().ok_or_else(invalid)?.to_owned();
+            out.push(match op.as_str() {
+                "eq" => PolicyCondition::Eq {
+                    field,
+                    value: operand,
+                },
+                "starts-with" => PolicyCondition::StartsWith {
+                    field,
+                    prefix: operand,
+                },
+                _ => return Err(invalid()),
+            });
+        }
+        _ => return Err(invalid()),
+    }
+    Ok(())
+}
+
+/// Read a non-negative integer given as a JSON number or numeric string.
+fn json_u64(value: &Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+/// Whether a form field may be omitted from the policy conditions.
+fn is_exempt_field(name: &str) -> bool {
+    matches!(
+        name,
+        "awsaccesskeyid" | "file" | "policy" | "signature" | "x-amz-signature"
+    ) || name.starts_with("x-ignore-")
+}
+
+/// Convert a `tagging` form field (a `<Tagging>` XML document) to the
+/// URL-encoded form used by the `x-amz-tagging` header.
+fn tagging_to_query(xml: &str) -> Result<String, S3Error> {
+    let tagging: Tagging = parse_xml_body(&Bytes::copy_from_slice(xml.as_bytes()))?;
+    Ok(tagging
+        .tag_set
+        .iter()
+        .map(|tag| {
+            format!(
+                "{}={}",
+                utf8_percent_encode(&tag.key, QUERY_ENCODE_SET),
+                utf8_percent_encode(&tag.value, QUERY_ENCODE_SET)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("&"))
+}
+
+/// Escape text for inclusion in an XML element.
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// An `AccessDenied` error for a form that violates its policy.
+fn policy_denied(reason: impl fmt::Display) -> S3Error {
+    S3Error::with_message(
+        S3ErrorCode::AccessDenied,
+        format!("Invalid according to Policy: {reason}"),
+    )
+}
+
+/// An `InvalidPolicyDocument` error for a policy that cannot be decoded.
+fn invalid_policy(message: impl Into<String>) -> S3Error {
+    let mut err = S3Error::with_message(S3ErrorCode::Custom("InvalidPolicyDocument"), message);
+    err.status_code = http::StatusCode::BAD_REQUEST;
+    err
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use http_body_util::BodyExt;
+
+    use super::*;
+
+    const BOUNDARY: &str = "xyzzy";
+
+    fn encode_policy(json: &str) -> String {
+        BASE64.encode(json)
+    }
+
+    fn form(fields: &[(&str, &str)], file: &[u8]) -> PostObjectForm {
+        let mut body = Vec::new();
+        for (name, value) in fields {
+            body.extend_from_slice(
+                format!(
+                    "--{BOUNDARY}\r\nContent-Disposition: form-data; \
+                     name=\"{name}\"\r\n\r\n{value}\r\n"
+                )
+                .as_bytes(),
+            );
+        }
+        body.extend_from_slice(
+            format!(
+                "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; \
+                 filename=\"photo.png\"\r\nContent-Type: image/png\r\n\r\n"
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(file);
+        body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+
+        let (parts, ()) = http::Request::post("/uploads")
+            .header(
+                http::header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={BOUNDARY}"),
+            )
+            .body(())
+            .unwrap()
+            .into_parts();
+        PostObjectForm::parse(&parts, &body).unwrap()
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_should_substitute_filename_in_key() {
+        let form = form(&[("Key", "user/${filename}")], b"data");
+        assert_eq!(form.key, "user/photo.png");
+        assert_eq!(
+            form.fields.get("key").map(String::as_str),
+            Some("user/${filename}")
+        );
+    }
+
+    #[test]
+    fn test_should_check_policy_conditions() {
+        let policy = encode_policy(
+            r#"{"expiration":"2030-01-01T00:00:00.000Z","conditions":[
+                {"bucket":"uploads"},["starts-with","$key","user/"],
+                ["eq","$success_action_status","201"],["starts-with","$Content-Type","image/"],
+                ["content-length-range",1,10]]}"#,
+        );
+        let fields = [
+            ("key", "user/${filename}"),
+            ("policy", policy.as_str()),
+            ("success_action_status", "201"),
+            ("content-type", "image/png"),
+        ];
+
+        assert!(
+            form(&fields, b"data")
+                .check_policy("uploads", now())
+                .is_ok()
+        );
+
+        let err = form(&fields, b"data")
+            .check_policy("other", now())
+            .unwrap_err();
+        assert_eq!(err.code, S3ErrorCode::AccessDenied);
+        assert!(err.message.contains(r#"["eq", "$bucket", "uploads"]"#));
+
+        let err = form(&fields, b"more than ten bytes")
+            .check_policy("uploads", now())
+            .unwrap_err();
+        assert_eq!(err.code, S3ErrorCode::EntityTooLarge);
+
+        let err = form(&fields, b"")
+            .check_policy("uploads", now())
+            .unwrap_err();
+        assert_eq!(err.code, S3ErrorCode::EntityTooSmall);
+
+        let mut extra = fields.to_vec();
+        extra.push(("x-amz-meta-owner", "eve"));
+        let err = form(&extra, b"data")
+            .check_policy("uploads", now())
+            .unwrap_err();
+        assert_eq!(
+            err.message,
+            "Invalid according to Policy: Extra input fields: x-amz-meta-owner"
+        );
+
+        let late = Utc.with_ymd_and_hms(2031, 1, 1, 0, 0, 0).unwrap();
+        let err = form(&fields, b"data")
+            .check_policy("uploads", late)
+            .unwrap_err();
+        assert_eq!(err.message, "Invalid according to Policy: Policy expired.");
+    }
+
+    #[test]
+    fn test_should_reject_malformed_policy() {
+        let form = form(&[("key", "k"), ("policy", "not base64!")], b"data");
+        let err = form.check_policy("uploads", now()).unwrap_err();
+        assert_eq!(err.code, S3ErrorCode::Custom("InvalidPolicyDocument"));
+        assert_eq!(err.status_code, http::StatusCode::BAD_REQUEST);
+
+        let policy = encode_policy(
+            r#"{"expiration":"2030-01-01T00:00:00Z","conditions":[["in","$key","a"]]}"#,
+        );
+        assert!(PostPolicy::decode(&policy).is_err());
+    }
+
+    #[test]
+    fn test_should_convert_form_to_put_object_input() {
+        let tagging =
+            "<Tagging><TagSet><Tag><Key>team</Key><Value>web app</Value></Tag></TagSet></Tagging>";
+        let form = form(
+            &[
+                ("key", "k"),
+                ("Cache-Control", "max-age=60"),
+                ("x-amz-meta-owner", "alice"),
+                (
+                    "x-amz-credential",
+                    "AKID/20250101/us-east-1/s3/aws4_request",
+                ),
+                ("tagging", tagging),
+            ],
+            b"data",
+        );
+
+        let input = form.to_put_object_input("uploads").unwrap();
+        assert_eq!(input.bucket, "uploads");
+        assert_eq!(input.key, "k");
+        assert_eq!(input.content_type.as_deref(), Some("image/png"));
+        assert_eq!(input.cache_control.as_deref(), Some("max-age=60"));
+        assert_eq!(
+            input.metadata.get("owner").map(String::as_str),
+            Some("alice")
+        );
+        assert_eq!(input.tagging.as_deref(), Some("team=web%20app"));
+        assert_eq!(input.metadata.len(), 1);
+    }
+
+    #[test]
+    fn test_should_honor_success_action_status() {
+        let status = |value: &str| {
+            form(&[("key", "a b"), ("success_action_status", value)], b"data")
+                .success_response("uploads", Some("\"etag\""))
+                .unwrap()
+        };
+
+        assert_eq!(status("200").status(), http::StatusCode::OK);
+        assert_eq!(status("204").status(), http::StatusCode::NO_CONTENT);
+        assert_eq!(status("bogus").status(), http::StatusCode::NO_CONTENT);
+
+        let created = status("201");
+        assert_eq!(created.status(), http::StatusCode::CREATED);
+        assert_eq!(
+            created.headers().get(http::header::LOCATION).unwrap(),
+            "/uploads/a%20b"
+        );
+        let xml = tokio_test::block_on(created.into_body().collect())
+            .unwrap()
+            .to_bytes();
+        let xml = std::str::from_utf8(&xml).unwrap();
+        assert!(xml.contains("<Key>a b</Key><ETag>&quot;etag&quot;</ETag>"));
+    }
+
+    #[test]
+    fn test_should_redirect_on_success() {
+        let response = form(
+            &[
+                ("key", "k"),
+                ("success_action_redirect", "https://example.com/done?x=1"),
+                ("success_action_status", "201"),
+            ],
+            b"data",
+        )
+        .success_response("uploads", Some("\"abc\""))
+        .unwrap();
+
+        assert_eq!(response.status(), http::StatusCode::SEE_OTHER);
+        assert_eq!(
+            response.headers().get(http::header::LOCATION).unwrap(),
+            "https://example.com/done?x=1&bucket=uploads&key=k&etag=%22abc%22"
+        );
+    }
+}
@@ -21,13 +21,14 @@ use rustack_s3_model::{
     types::{
         AccelerateConfiguration, AccessControlPolicy, BucketLifecycleConfiguration,
         BucketLoggingStatus, CORSConfiguration, CompletedMultipartUpload,
-        CreateBucketConfiguration, Delete, NotificationConfiguration, ObjectLockConfiguration,
-        ObjectLockLegalHold, ObjectLockRetention, OwnershipControls,
-        PublicAccessBlockConfiguration, RequestPaymentConfiguration,
-        ServerSideEncryptionConfiguration, Tagging, VersioningConfiguration, WebsiteConfiguration,
+        CreateAccessPointConfiguration, CreateBucketConfiguration, Delete,
+        NotificationConfiguration, ObjectAttributes, ObjectLockConfiguration, ObjectLockLegalHold,
+        ObjectLockRetention, OwnershipControls, PublicAccessBlockConfiguration,
+        RequestPaymentConfiguration, ServerSideEncryptionConfiguration, Tagging,
+        VersioningConfiguration, WebsiteConfiguration,
     },
 };
-use rustack_s3_xml::from_xml;
+use rustack_s3_xml::{XmlError, from_xml};
 
 /// Trait for extracting an S3 input struct from HTTP request components.
 ///
@@ -139,22 +140,28 @@ pub fn query_param_parse<T: FromStr>(params: &[(String, String)], name: &str) ->
 /// Collect all `x-amz-meta-*` headers into a metadata `HashMap`.
 ///
 /// The key in the returned map is the portion of the header name after `x-amz-meta-`,
-/// preserving the original case.
+/// lowercased as S3 stores it. Repeated headers with the same name are joined
+/// with commas (RFC 7230 section 3.2.2), the same way the SigV4 canonical
+/// request combines them.
 pub fn collect_metadata(parts: &http::request::Parts) -> HashMap<String, String> {
     let prefix = "x-amz-meta-";
-    parts
-        .headers
-        .iter()
-        .filter_map(|(name, value)| {
-            let name_str = name.as_str();
-            if let Some(meta_key) = name_str.strip_prefix(prefix) {
-                let meta_value = value.to_str().ok()?;
-                Some((meta_key.to_owned(), meta_value.to_owned()))
-            } else {
-                None
-            }
-        })
-        .collect()
+    let mut metadata: HashMap<String, String> = HashMap::new();
+    for (name, value) in &parts.headers {
+        let Some(meta_key) = name.as_str().strip_prefix(prefix) else {
+            continue;
+        };
+        let Ok(meta_value) = value.to_str() else {
+            continue;
+        };
+        metadata
+            .entry(meta_key.to_ascii_lowercase())
+            .and_modify(|existing| {
+                existing.push(',');
+                existing.push_str(meta_value);
+            })
+            .or_insert_with(|| meta_value.to_owned());
+    }
+    metadata
 }
 
 /// Require a bucket name from the routing context, returning an error if absent.
@@ -170,6 +177,23 @@ fn require_key(key: Option<&str>) -> Result<String, S3Error> {
         .ok_or_else(|| S3Error::with_message(S3ErrorCode::InvalidRequest, "Object key is required"))
 }
 
+/// The account ID an S3 Control request is made for (`x-amz-account-id`).
+fn require_account_id(parts: &http::request::Parts) -> Result<String, S3Error> {
+    header_str(parts, "x-amz-account-id").ok_or_else(|| {
+        S3Error::with_message(
+            S3ErrorCode::InvalidRequest,
+            "Missing required header for this request: x-amz-account-id",
+        )
+    })
+}
+
+/// The access point name of an S3 Control request, routed as the key.
+fn require_access_point_name(key: Option<&str>) -> Result<String, S3Error> {
+    key.map(str::to_owned).ok_or_else(|| {
+        S3Error::with_message(S3ErrorCode::InvalidRequest, "Missing access point name")
+    })
+}
+
 /// Known valid S3 storage class values.
 const VALID_STORAGE_CLASSES: &[&str] = &[
     "DEEP_ARCHIVE",
@@ -233,11 +257,69 @@ where
         .unwrap_or_default()
 }
 
+/// Parse the `x-amz-object-attributes` header of `GetObjectAttributes`.
+///
+/// S3 requires at least one attribute and rejects unknown names rather than
+/// ignoring them.
+fn object_attributes(parts: &http::request::Parts) -> Result<Vec<ObjectAttributes>, S3Error> {
+    let names: Vec<&str> = parts
+        .headers
+        .get_all("x-amz-object-attributes")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|s| s.split(','))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect();
+    if names.is_empty() {
+        return Err(S3Error::invalid_argument(
+            "The x-amz-object-attributes header specifying the attributes to be retrieved is \
+             either missing or empty",
+        ));
+    }
+    names
+        .into_iter()
+        .map(|name| {
+            let attribute = ObjectAttributes::from(name);
+            if attribute.as_str() == name {
+                Ok(attribute)
+            } else {
+                Err(S3Error::invalid_argument(
+                    "Invalid attribute name specified.",
+                ))
+            }
+        })
+        .collect()
+}
+
+/// Extract an integer header, rejecting a value that does not parse.
+fn header_integer(parts: &http::request::Parts, name: &str) -> Result<Option<i32>, S3Error> {
+    let Some(value) = header_str(parts, name) else {
+        return Ok(None);
+    };
+    value.trim().parse().map(Some).map_err(|_| {
+        S3Error::invalid_argument(format!(
+            "Provided {name} not an integer or within integer range"
+        ))
+    })
+}
+
 /// Parse an XML body into a typed value, returning an `S3Error` on failure.
-fn parse_xml_body<T: rustack_s3_xml::S3Deserialize>(body: &Bytes) -> Result<T, S3Error> {
+pub(crate) fn parse_xml_body<T: rustack_s3_xml::S3Deserialize>(body: &Bytes) -> Result<T, S3Error> {
     from_xml(body).map_err(|e| S3Error::malformed_xml(format!("Failed to parse XML body: {e}")))
 }
 
+/// Parse a CreateBucket body, reporting an unknown `LocationConstraint` as
+/// `InvalidLocationConstraint` rather than malformed XML.
+fn parse_create_bucket_configuration(body: &Bytes) -> Result<CreateBucketConfiguration, S3Error> {
+    from_xml(body).map_err(|e| match e {
+        XmlError::InvalidValue { element, .. } if element == "LocationConstraint" => {
+            S3Error::new(S3ErrorCode::InvalidLocationConstraint)
+        }
+        e => S3Error::malformed_xml(format!("Failed to parse XML body: {e}")),
+    })
+}
+
 // ---------------------------------------------------------------------------
 // Macro to reduce boilerplate for simple bucket-only inputs
 // ---------------------------------------------------------------------------
@@ -359,7 +441,7 @@ impl FromS3Request for CreateBucketInput {
             create_bucket_configuration: if body.is_empty() {
                 None
             } else {
-                Some(parse_xml_body::<CreateBucketConfiguration>(&body)?)
+                Some(parse_create_bucket_configuration(&body)?)
             },
             grant_full_control: header_str(parts, "x-amz-grant-full-control"),
             grant_read: header_str(parts, "x-amz-grant-read"),
@@ -463,9 +545,10 @@ impl FromS3Request for GetObjectAttributesInput {
             bucket: require_bucket(bucket)?,
             key: require_key(key)?,
             expected_bucket_owner: header_str(parts, "x-amz-expected-bucket-owner"),
-            max_parts: header_parse(parts, "x-amz-max-parts"),
-            object_attributes: header_enum_list(parts, "x-amz-object-attributes"),
-            part_number_marker: header_str(parts, "x-amz-part-number-marker"),
+            max_parts: header_integer(parts, "x-amz-max-parts")?,
+            object_attributes: object_attributes(parts)?,
+            part_number_marker: header_integer(parts, "x-amz-part-number-marker")?
+                .map(|marker| marker.to_string()),
             request_payer: header_enum(parts, "x-amz-request-payer"),
             sse_customer_algorithm: header_str(
                 parts,
@@ -1583,6 +1666,73 @@ impl FromS3Request for PutObjectRetentionInput {
     }
 }
 
+// --- S3 Control access point operations ---
+
+impl FromS3Request for CreateAccessPointInput {
+    fn from_s3_request(
+        parts: &http::request::Parts,
+        _bucket: Option<&str>,
+        key: Option<&str>,
+        _query_params: &[(String, String)],
+        body: Bytes,
+    ) -> Result<Self, S3Error> {
+        let configuration = parse_xml_body::<CreateAccessPointConfiguration>(&body)?;
+        Ok(Self {
+            account_id: require_account_id(parts)?,
+            name: require_access_point_name(key)?,
+            bucket: configuration.bucket,
+            bucket_account_id: configuration.bucket_account_id,
+        })
+    }
+}
+
+impl FromS3Request for GetAccessPointInput {
+    fn from_s3_request(
+        parts: &http::request::Parts,
+        _bucket: Option<&str>,
+        key: Option<&str>,
+        _query_params: &[(String, String)],
+        _body: Bytes,
+    ) -> Result<Self, S3Error> {
+        Ok(Self {
+            account_id: require_account_id(parts)?,
+            name: require_access_point_name(key)?,
+        })
+    }
+}
+
+impl FromS3Request for DeleteAccessPointInput {
+    fn from_s3_request(
+        parts: &http::request::Parts,
+        _bucket: Option<&str>,
+        key: Option<&str>,
+        _query_params: &[(String, String)],
+        _body: Bytes,
+    ) -> Result<Self, S3Error> {
+        Ok(Self {
+            account_id: require_account_id(parts)?,
+            name: require_access_point_name(key)?,
+        })
+    }
+}
+
+impl FromS3Request for ListAccessPointsInput {
+    fn from_s3_request(
+        parts: &http::request::Parts,
+        _bucket: Option<&str>,
+        _key: Option<&str>,
+        query_params: &[(String, String)],
+        _body: Bytes,
+    ) -> Result<Self, S3Error> {
+        Ok(Self {
+            account_id: require_account_id(parts)?,
+            bucket: query_param(query_params, "bucket"),
+            max_results: query_param_parse(query_params, "maxResults"),
+            next_token: query_param(query_params, "nextToken"),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use http::Request;
@@ -1680,6 +1830,40 @@ mod tests {
         assert_eq!(metadata.get("baz"), Some(&"qux".to_owned()));
     }
 
+    #[test]
+    fn test_should_lowercase_and_join_duplicate_metadata_headers() {
+        let req = Request::builder()
+            .method(http::Method::PUT)
+            .uri("/mybucket/mykey")
+            .header("X-Amz-Meta-FOO", "bar")
+            .header("x-amz-meta-foo", "baz")
+            .body(())
+            .expect("valid request");
+        let (parts, ()) = req.into_parts();
+
+        let metadata = collect_metadata(&parts);
+        assert_eq!(metadata.len(), 1);
+        assert_eq!(metadata.get("foo").map(String::as_str), Some("bar,baz"));
+    }
+
+    #[test]
+    fn test_should_reject_explicit_us_east_1_location_constraint() {
+        let req = Request::builder()
+            .method(http::Method::PUT)
+            .uri("/mybucket")
+            .body(())
+            .expect("valid request");
+        let (parts, ()) = req.into_parts();
+        let body = Bytes::from(
+            "<CreateBucketConfiguration><LocationConstraint>us-east-1</LocationConstraint></\
+             CreateBucketConfiguration>",
+        );
+
+        let err = CreateBucketInput::from_s3_request(&parts, Some("mybucket"), None, &[], body)
+            .unwrap_err();
+        assert_eq!(err.code, S3ErrorCode::InvalidLocationConstraint);
+    }
+
     #[test]
     fn test_should_require_bucket_when_needed() {
         let req = Request::builder()
@@ -1739,6 +1923,36 @@ mod tests {
         assert!(input.body.is_some());
     }
 
+    #[test]
+    fn test_should_validate_object_attributes_header() {
+        let parse = |attributes: Option<&str>| {
+            let mut builder = Request::builder()
+                .method(http::Method::GET)
+                .uri("/mybucket/mykey?attributes");
+            if let Some(attributes) = attributes {
+                builder = builder.header("x-amz-object-attributes", attributes);
+            }
+            let (parts, ()) = builder.body(()).expect("valid request").into_parts();
+            GetObjectAttributesInput::from_s3_request(
+                &parts,
+                Some("mybucket"),
+                Some("mykey"),
+                &[],
+                Bytes::new(),
+            )
+        };
+
+        let input = parse(Some("ETag, ObjectParts")).expect("should parse");
+        assert_eq!(
+            input.object_attributes,
+            vec![ObjectAttributes::Etag, ObjectAttributes::ObjectParts]
+        );
+        for invalid in [None, Some(""), Some("ETag,Size")] {
+            let err = parse(invalid).unwrap_err();
+            assert_eq!(err.code, S3ErrorCode::InvalidArgument);
+        }
+    }
+
     #[test]
     fn test_should_parse_http_date_rfc3339() {
         let dt = parse_http_date("2024-01-15T10:30:00Z");
@@ -350,6 +350,16 @@ fn set_object_metadata_headers(
     builder
 }
 
+/// Status of a GetObject or HeadObject response: `206 Partial Content` when
+/// a byte range was served, `200 OK` otherwise.
+fn object_status(content_range: Option<&str>) -> http::StatusCode {
+    if content_range.is_some() {
+        http::StatusCode::PARTIAL_CONTENT
+    } else {
+        http::StatusCode::OK
+    }
+}
+
 impl IntoS3Response for GetObjectOutput {
     fn into_s3_response(self) -> Result<http::Response<S3ResponseBody>, S3Error> {
         let headers = ObjectMetadataHeaders {
@@ -392,7 +402,7 @@ impl IntoS3Response for GetObjectOutput {
             website_redirect_location: self.website_redirect_location.as_deref(),
         };
 
-        let builder = http::Response::builder().status(http::StatusCode::OK);
+        let builder = http::Response::builder().status(object_status(headers.content_range));
         let builder = set_object_metadata_headers(builder, &headers);
 
         let body = if let Some(blob) = self.body {
@@ -447,7 +457,7 @@ impl IntoS3Response for HeadObjectOutput {
             website_redirect_location: self.website_redirect_location.as_deref(),
         };
 
-        let mut builder = http::Response::builder().status(http::StatusCode::OK);
+        let mut builder = http::Response::builder().status(object_status(headers.content_range));
         // HeadObject has an additional archive_status header not present in GetObject.
         builder = set_optional_display_header(
             builder,
@@ -499,6 +509,21 @@ impl IntoS3Response for PutObjectOutput {
         );
         builder = set_optional_header(builder, "ETag", self.e_tag.as_deref());
         builder = set_optional_header(builder, "x-amz-expiration", self.expiration.as_deref());
+        builder = set_optional_display_header(
+            builder,
+            "x-amz-object-lock-legal-hold",
+            self.object_lock_legal_hold_status.as_ref(),
+        );
+        builder = set_optional_display_header(
+            builder,
+            "x-amz-object-lock-mode",
+            self.object_lock_mode.as_ref(),
+        );
+        builder = set_optional_timestamp_header(
+            builder,
+            "x-amz-object-lock-retain-until-date",
+            self.object_lock_retain_until_date.as_ref(),
+        );
         builder = set_optional_display_header(
             builder,
             "x-amz-request-charged",
@@ -667,6 +692,32 @@ impl_xml_body_response!(ListObjectVersionsOutput, "ListVersionsResult");
 impl_xml_body_response!(ListMultipartUploadsOutput, "ListMultipartUploadsResult");
 impl_xml_body_response!(ListPartsOutput, "ListPartsResult");
 
+// --- S3 Control access point operations (return XML) ---
+
+/// Macro for S3 Control outputs, whose XML body uses the S3 Control namespace.
+macro_rules! impl_control_xml_body_response {
+    ($ty:ty, $root:expr) => {
+        impl IntoS3Response for $ty {
+            fn into_s3_response(self) -> Result<http::Response<S3ResponseBody>, S3Error> {
+                let xml_bytes = rustack_s3_xml::to_xml_with_namespace(
+                    $root,
+                    rustack_s3_xml::S3_CONTROL_NAMESPACE,
+                    &self,
+                )
+                .map_err(|e| S3Error::internal_error(format!("XML serialization failed: {e}")))?;
+                let builder = http::Response::builder()
+                    .status(http::StatusCode::OK)
+                    .header("Content-Type", "application/xml");
+                build_response(builder, S3ResponseBody::from_bytes(Bytes::from(xml_bytes)))
+            }
+        }
+    };
+}
+
+impl_control_xml_body_response!(CreateAccessPointOutput, "CreateAccessPointResult");
+impl_control_xml_body_response!(GetAccessPointOutput, "GetAccessPointResult");
+impl_control_xml_body_response!(ListAccessPointsOutput, "ListAccessPointsResult");
+
 impl IntoS3Response for ListObjectsOutput {
     fn into_s3_response(self) -> Result<http::Response<S3ResponseBody>, S3Error> {
         let body = serialize_xml_body("ListBucketResult", &self)?;
@@ -729,7 +780,7 @@ impl IntoS3Response for GetObjectTaggingOutput {
 
 impl IntoS3Response for GetObjectAttributesOutput {
     fn into_s3_response(self) -> Result<http::Response<S3ResponseBody>, S3Error> {
-        let body = serialize_xml_body("GetObjectAttributesOutput", &self)?;
+        let body = serialize_xml_body("GetObjectAttributesResponse", &self)?;
         let mut builder = http::Response::builder()
             .status(http::StatusCode::OK)
             .header("Content-Type", "application/xml");
@@ -1013,12 +1064,15 @@ impl IntoS3Response for UploadPartCopyOutput {
 // --- S3Error to HTTP response ---
 
 /// Convert an S3Error into an HTTP error response with an XML body.
+///
+/// The error code is attached to the response extensions for access logging.
 #[must_use]
 pub fn error_to_response(err: &S3Error, request_id: &str) -> http::Response<S3ResponseBody> {
-    let xml_bytes = rustack_s3_xml::error::error_to_xml(
+    let xml_bytes = rustack_s3_xml::error::error_to_xml_with_details(
         err.code.as_str(),
         &err.message,
         err.resource.as_deref(),
+        err.details.as_deref().map_or(&[], Vec::as_slice),
         request_id,
     );
 
@@ -1037,12 +1091,14 @@ pub fn error_to_response(err: &S3Error, request_id: &str) -> http::Response<S3Re
         }
     }
 
-    builder.body(body).unwrap_or_else(|_| {
+    let mut response = builder.body(body).unwrap_or_else(|_| {
         http::Response::builder()
             .status(http::StatusCode::INTERNAL_SERVER_ERROR)
             .body(S3ResponseBody::empty())
             .expect("static response should be valid")
-    })
+    });
+    response.extensions_mut().insert(err.code);
+    response
 }
 
 #[cfg(test)]
@@ -1072,6 +1128,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_should_set_object_lock_headers_on_put_object_response() {
+        let retain_until = chrono::DateTime::parse_from_rfc3339("2030-01-02T03:04:05Z")
+            .expect("timestamp")
+            .with_timezone(&chrono::Utc);
+        let output = PutObjectOutput {
+            object_lock_mode: Some(rustack_s3_model::types::ObjectLockMode::from("GOVERNANCE")),
+            object_lock_retain_until_date: Some(retain_until),
+            ..Default::default()
+        };
+        let resp = output.into_s3_response().expect("should build response");
+        let header = |name: &str| {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_owned)
+        };
+        assert_eq!(
+            header("x-amz-object-lock-mode").as_deref(),
+            Some("GOVERNANCE")
+        );
+        assert_eq!(
+            header("x-amz-object-lock-retain-until-date").as_deref(),
+            Some("Wed, 02 Jan 2030 03:04:05 GMT")
+        );
+        assert_eq!(header("x-amz-object-lock-legal-hold"), None);
+    }
+
     #[test]
     fn test_should_create_delete_object_response() {
         let output = DeleteObjectOutput {
@@ -1136,6 +1220,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_should_create_partial_content_response_for_range() {
+        let output = GetObjectOutput {
+            body: Some(StreamingBlob::new(Bytes::from("content"))),
+            content_length: Some(7),
+            content_range: Some("bytes 5-11/100".to_owned()),
+            ..Default::default()
+        };
+        let resp = output.into_s3_response().expect("should build response");
+        assert_eq!(resp.status(), http::StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            resp.headers()
+                .get("Content-Range")
+                .and_then(|v| v.to_str().ok()),
+            Some("bytes 5-11/100"),
+        );
+
+        let output = HeadObjectOutput {
+            content_length: Some(7),
+            content_range: Some("bytes 5-11/100".to_owned()),
+            ..Default::default()
+        };
+        let resp = output.into_s3_response().expect("should build response");
+        assert_eq!(resp.status(), http::StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            resp.headers()
+                .get("Content-Length")
+                .and_then(|v| v.to_str().ok()),
+            Some("7"),
+        );
+    }
+
     #[test]
     fn test_should_create_get_bucket_location_response() {
         let output = GetBucketLocationOutput {
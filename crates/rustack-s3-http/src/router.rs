@@ -10,6 +10,19 @@
 //!
 //! Virtual hosting is supported: the bucket name can come from either the `Host` header
 //! (e.g., `mybucket.s3.localhost`) or from the first path segment (path-style).
+//!
+//! An access point can stand in for the bucket, either as an ARN in the first path
+//! segment (`arn:aws:s3:us-east-1:123456789012:accesspoint/my-ap`) or as the host
+//! (`my-ap-123456789012.s3-accesspoint.us-east-1.amazonaws.com`). Object Lambda
+//! access points are passed through the same way. The router records the access
+//! point in [`RoutingContext::access_point`]; the service swaps in the bucket behind
+//! it before dispatch. The S3 Control access point operations themselves
+//! (`/v20180820/accesspoint/...`) are routed here too.
+//!
+//! S3 Express One Zone (directory bucket) APIs are not supported. Requests for
+//! `CreateSession`, `ListDirectoryBuckets`, or addressed to a zonal or control
+//! `s3express` endpoint are rejected with `NotImplemented` up front rather than
+//! being misrouted to a general-purpose bucket operation.
 
 use http::Method;
 use percent_encoding::percent_decode_str;
@@ -38,8 +51,23 @@ pub struct RoutingContext {
     pub operation: S3Operation,
     /// Parsed query parameters from the request URI.
     pub query_params: Vec<(String, String)>,
+    /// The access point addressed in place of the bucket, if any. `bucket`
+    /// holds the access point name until it is resolved.
+    pub access_point: Option<AccessPointRef>,
+}
+
+/// An access point addressed by ARN or host in place of a bucket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessPointRef {
+    /// The access point name.
+    pub name: String,
+    /// The account ID the access point belongs to.
+    pub account: String,
 }
 
+/// Path prefix of the S3 Control API, which manages access points.
+const CONTROL_PATH_PREFIX: &str = "/v20180820/";
+
 impl S3Router {
     /// Create a new router with the given domain and virtual hosting setting.
     #[must_use]
@@ -70,36 +98,23 @@ impl S3Router {
         // Parse query parameters.
         let query_params = parse_query_params(uri.query().unwrap_or(""));
 
-        // Extract bucket from virtual hosting (Host header).
-        let virtual_bucket = if self.virtual_hosting {
-            extract_virtual_host_bucket(headers, &self.domain)
-        } else {
-            None
-        };
-
-        // Parse path to extract bucket and key.
-        let path = uri.path();
-        let (path_bucket, path_key) = parse_path(path);
+        if let Some(resource) = uri.path().strip_prefix(CONTROL_PATH_PREFIX) {
+            return resolve_control_request(method, resource, query_params);
+        }
 
-        // Combine virtual host bucket with path-based bucket/key.
-        let (bucket, key) = if let Some(vhost_bucket) = virtual_bucket {
-            // Virtual hosting: bucket comes from Host, entire path is the key.
-            let key = if path == "/" || path.is_empty() {
-                None
-            } else {
-                // Strip leading "/" and decode.
-                let raw_key = &path[1..];
-                if raw_key.is_empty() {
-                    None
-                } else {
-                    Some(decode_uri_component(raw_key))
-                }
-            };
-            (Some(vhost_bucket), key)
-        } else {
-            // Path-style: bucket is first path segment, rest is key.
-            (path_bucket, path_key)
-        };
+        let (bucket, key, access_point) = self.locate(uri, headers);
+
+        if let Some(operation) =
+            identify_directory_bucket_operation(method, bucket.is_some(), &query_params, headers)
+        {
+            return Err(S3Error::with_message(
+                S3ErrorCode::NotImplemented,
+                format!(
+                    "{operation} is not implemented: S3 Express One Zone directory buckets are \
+                     not supported"
+                ),
+            ));
+        }
 
         // Identify the operation.
         let operation = identify_operation(
@@ -115,8 +130,191 @@ impl S3Router {
             key,
             operation,
             query_params,
+            access_point,
         })
     }
+
+    /// Resolve only the bucket a request addresses, without identifying an
+    /// operation.
+    ///
+    /// Useful for requests such as CORS preflights whose method does not map
+    /// to an S3 operation.
+    #[must_use]
+    pub fn resolve_bucket<B>(&self, req: &http::Request<B>) -> Option<String> {
+        self.locate(req.uri(), req.headers()).0
+    }
+
+    /// Extract the bucket, key, and addressed access point from the Host
+    /// header and URI path.
+    fn locate(
+        &self,
+        uri: &http::Uri,
+        headers: &http::HeaderMap,
+    ) -> (Option<String>, Option<String>, Option<AccessPointRef>) {
+        let path = uri.path();
+
+        // Access point host: the access point stands in for the bucket and
+        // the entire path is the key.
+        if let Some(access_point) = extract_access_point_host(headers) {
+            return (
+                Some(access_point.name.clone()),
+                host_style_key(path),
+                Some(access_point),
+            );
+        }
+
+        // Extract bucket from virtual hosting (Host header).
+        let virtual_bucket = if self.virtual_hosting {
+            extract_virtual_host_bucket(headers, &self.domain)
+        } else {
+            None
+        };
+
+        // Parse path to extract bucket and key.
+        let (path_bucket, path_key) = parse_path(path);
+
+        // Combine virtual host bucket with path-based bucket/key.
+        if let Some(vhost_bucket) = virtual_bucket {
+            // Virtual hosting: bucket comes from Host, entire path is the key.
+            (Some(vhost_bucket), host_style_key(path), None)
+        } else if let Some((access_point, key)) = path_bucket
+            .as_deref()
+            .and_then(|bucket| parse_access_point_arn(bucket, path_key.as_deref()))
+        {
+            (Some(access_point.name.clone()), key, Some(access_point))
+        } else {
+            // Path-style: bucket is first path segment, rest is key.
+            (path_bucket, path_key, None)
+        }
+    }
+}
+
+/// The key of a request whose bucket comes from the Host header: the entire
+/// path, without its leading `/`.
+fn host_style_key(path: &str) -> Option<String> {
+    let raw_key = path.strip_prefix('/').unwrap_or(path);
+    if raw_key.is_empty() {
+        None
+    } else {
+        Some(decode_uri_component(raw_key))
+    }
+}
+
+/// Extract the access point from an access point Host header.
+///
+/// The host looks like `my-ap-123456789012.s3-accesspoint.us-east-1.amazonaws.com`
+/// (or `s3-object-lambda` in place of `s3-accesspoint`): the first label is the
+/// access point name followed by the 12-digit account ID.
+fn extract_access_point_host(headers: &http::HeaderMap) -> Option<AccessPointRef> {
+    let host = headers
+        .get(http::header::HOST)
+        .and_then(|v| v.to_str().ok())?;
+    let host_without_port = host.split(':').next().unwrap_or(host);
+
+    let mut labels = host_without_port.split('.');
+    let (name, account) = labels.next()?.rsplit_once('-')?;
+    if !matches!(labels.next()?, "s3-accesspoint" | "s3-object-lambda") {
+        return None;
+    }
+    access_point_ref(name, account)
+}
+
+/// Parse an access point ARN in the bucket position of a path-style request,
+/// returning the access point and the key that follows it.
+///
+/// Accepts `arn:{partition}:{s3|s3-object-lambda}:{region}:{account}:accesspoint/{name}`
+/// and the `accesspoint:{name}` form. When the ARN is not percent-encoded, the
+/// `/` before the name splits it across the bucket and key, so the name is
+/// taken from the first key segment.
+fn parse_access_point_arn(
+    bucket: &str,
+    key: Option<&str>,
+) -> Option<(AccessPointRef, Option<String>)> {
+    let mut parts = bucket.splitn(6, ':');
+    if parts.next()? != "arn" {
+        return None;
+    }
+    let _partition = parts.next()?;
+    if !matches!(parts.next()?, "s3" | "s3-object-lambda") {
+        return None;
+    }
+    let _region = parts.next()?;
+    let account = parts.next()?;
+    let resource = parts.next()?;
+
+    let (name, key) = match resource {
+        "accesspoint" => {
+            // Unencoded `accesspoint/{name}/{key}`.
+            let key = key?;
+            match key.split_once('/') {
+                Some((name, rest)) => (name, Some(rest.to_owned()).filter(|k| !k.is_empty())),
+                None => (key, None),
+            }
+        }
+        resource => {
+            let name = resource
+                .strip_prefix("accesspoint/")
+                .or_else(|| resource.strip_prefix("accesspoint:"))?;
+            (name, key.map(str::to_owned))
+        }
+    };
+    Some((access_point_ref(name, account)?, key))
+}
+
+/// Build an [`AccessPointRef`] from a non-empty name and a 12-digit account ID.
+fn access_point_ref(name: &str, account: &str) -> Option<AccessPointRef> {
+    let valid_account = account.len() == 12 && account.bytes().all(|b| b.is_ascii_digit());
+    if name.is_empty() || name.contains('/') || !valid_account {
+        return None;
+    }
+    Some(AccessPointRef {
+        name: name.to_owned(),
+        account: account.to_owned(),
+    })
+}
+
+/// Route an S3 Control request, given its path after `/v20180820/`.
+///
+/// Only the access point operations are served: the access point name is
+/// returned as the key, with no bucket.
+fn resolve_control_request(
+    method: &Method,
+    resource: &str,
+    query_params: Vec<(String, String)>,
+) -> Result<RoutingContext, S3Error> {
+    let mut segments = resource.split('/');
+    let operation = match (segments.next(), segments.next(), segments.next()) {
+        (Some("accesspoint"), None, None) if *method == Method::GET => {
+            Some(S3Operation::ListAccessPoints)
+        }
+        (Some("accesspoint"), Some(name), None) if !name.is_empty() => match *method {
+            Method::PUT => Some(S3Operation::CreateAccessPoint),
+            Method::GET => Some(S3Operation::GetAccessPoint),
+            Method::DELETE => Some(S3Operation::DeleteAccessPoint),
+            _ => None,
+        },
+        _ => None,
+    };
+    let Some(operation) = operation else {
+        return Err(S3Error::with_message(
+            S3ErrorCode::NotImplemented,
+            format!("S3 Control {method} {CONTROL_PATH_PREFIX}{resource} is not implemented"),
+        ));
+    };
+
+    let key = match operation {
+        S3Operation::ListAccessPoints => None,
+        _ => resource
+            .strip_prefix("accesspoint/")
+            .map(decode_uri_component),
+    };
+    Ok(RoutingContext {
+        bucket: None,
+        key,
+        operation,
+        query_params,
+        access_point: None,
+    })
 }
 
 /// Extract the bucket name from a virtual-hosted-style Host header.
@@ -205,6 +403,42 @@ fn query_value<'a>(params: &'a [(String, String)], key: &str) -> Option<&'a str>
         .map(|(_, v)| v.as_str())
 }
 
+/// Identify a request for an S3 Express One Zone API, which the router does
+/// not serve.
+///
+/// Returns the name of the operation (or endpoint) requested.
+fn identify_directory_bucket_operation(
+    method: &Method,
+    has_bucket: bool,
+    query_params: &[(String, String)],
+    headers: &http::HeaderMap,
+) -> Option<&'static str> {
+    let host = headers
+        .get(http::header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    // Zonal endpoints look like `s3express-usw2-az1.us-west-2.amazonaws.com`
+    // and the control endpoint like `s3express-control.us-west-2.amazonaws.com`,
+    // optionally prefixed with a bucket label.
+    if host
+        .split('.')
+        .any(|label| label.starts_with("s3express-control"))
+    {
+        return Some("S3 Express One Zone control endpoint");
+    }
+    if host.split('.').any(|label| label.starts_with("s3express-")) {
+        return Some("S3 Express One Zone zonal endpoint");
+    }
+
+    match (method, has_bucket) {
+        (&Method::GET, true) if query_has_key(query_params, "session") => Some("CreateSession"),
+        (&Method::GET, false) if query_has_key(query_params, "max-directory-buckets") => {
+            Some("ListDirectoryBuckets")
+        }
+        _ => None,
+    }
+}
+
 /// Identify the S3 operation from the HTTP method, path structure, query params, and headers.
 ///
 /// This is the core routing logic that maps the HTTP request characteristics to a
@@ -587,6 +821,19 @@ mod tests {
         assert_eq!(ctx.operation, S3Operation::ListBuckets);
     }
 
+    #[test]
+    fn test_should_resolve_bucket_for_unrouted_method() {
+        let req = vhost_request(&Method::OPTIONS, "mybucket.s3.localhost:4566", "/key");
+        assert!(router().resolve(&req).is_err());
+        assert_eq!(router().resolve_bucket(&req).as_deref(), Some("mybucket"));
+
+        let req = vhost_request(&Method::OPTIONS, "localhost:4566", "/other/key");
+        assert_eq!(
+            path_style_router().resolve_bucket(&req).as_deref(),
+            Some("other")
+        );
+    }
+
     // --- Path-style routing tests ---
 
     #[test]
@@ -982,4 +1229,138 @@ mod tests {
         let err = path_style_router().resolve(&req).unwrap_err();
         assert_eq!(err.code, S3ErrorCode::MethodNotAllowed);
     }
+
+    #[test]
+    fn test_should_reject_directory_bucket_operations() {
+        let requests = [
+            (
+                get_request("/mybucket--usw2-az1--x-s3?session"),
+                "CreateSession",
+            ),
+            (
+                get_request("/?max-directory-buckets=10"),
+                "ListDirectoryBuckets",
+            ),
+            (
+                vhost_request(
+                    &Method::PUT,
+                    "mybucket--usw2-az1--x-s3.s3express-usw2-az1.us-west-2.amazonaws.com",
+                    "/key",
+                ),
+                "zonal endpoint",
+            ),
+            (
+                vhost_request(
+                    &Method::GET,
+                    "s3express-control.us-west-2.amazonaws.com",
+                    "/",
+                ),
+                "control endpoint",
+            ),
+        ];
+        for (req, operation) in requests {
+            let err = path_style_router().resolve(&req).unwrap_err();
+            assert_eq!(err.code, S3ErrorCode::NotImplemented);
+            assert_eq!(err.status_code, http::StatusCode::NOT_IMPLEMENTED);
+            assert!(err.message.contains(operation), "{}", err.message);
+        }
+    }
+
+    #[test]
+    fn test_should_route_access_point_arns_to_the_access_point() {
+        let expected = AccessPointRef {
+            name: "my-ap".to_owned(),
+            account: "123456789012".to_owned(),
+        };
+        let requests = [
+            get_request("/arn%3Aaws%3As3%3Aus-east-1%3A123456789012%3Aaccesspoint%2Fmy-ap/a/b.txt"),
+            get_request("/arn:aws:s3:us-east-1:123456789012:accesspoint/my-ap/a/b.txt"),
+            get_request("/arn:aws:s3:us-east-1:123456789012:accesspoint:my-ap/a/b.txt"),
+            get_request(
+                "/arn%3Aaws%3As3-object-lambda%3Aus-east-1%3A123456789012%3Aaccesspoint%2Fmy-ap/a/\
+                 b.txt",
+            ),
+            vhost_request(
+                &Method::GET,
+                "my-ap-123456789012.s3-accesspoint.us-east-1.amazonaws.com",
+                "/a/b.txt",
+            ),
+            vhost_request(
+                &Method::GET,
+                "my-ap-123456789012.s3-object-lambda.us-east-1.amazonaws.com",
+                "/a/b.txt",
+            ),
+        ];
+        for req in requests {
+            let ctx = path_style_router().resolve(&req).expect("should resolve");
+            assert_eq!(ctx.access_point.as_ref(), Some(&expected), "{}", req.uri());
+            assert_eq!(ctx.bucket.as_deref(), Some("my-ap"));
+            assert_eq!(ctx.key.as_deref(), Some("a/b.txt"));
+            assert_eq!(ctx.operation, S3Operation::GetObject);
+        }
+
+        let ctx = path_style_router()
+            .resolve(&get_request(
+                "/arn:aws:s3:us-east-1:123456789012:accesspoint/my-ap?list-type=2",
+            ))
+            .expect("should resolve");
+        assert_eq!(ctx.access_point, Some(expected));
+        assert!(ctx.key.is_none());
+        assert_eq!(ctx.operation, S3Operation::ListObjectsV2);
+
+        // Not an access point: a malformed account ID or another service.
+        for uri in [
+            "/arn:aws:s3:us-east-1:1234:accesspoint/my-ap/key",
+            "/arn:aws:sqs:us-east-1:123456789012:accesspoint/my-ap/key",
+        ] {
+            let ctx = path_style_router()
+                .resolve(&get_request(uri))
+                .expect("should resolve");
+            assert!(ctx.access_point.is_none(), "{uri}");
+        }
+    }
+
+    #[test]
+    fn test_should_route_access_point_control_operations() {
+        let cases = [
+            (
+                Method::PUT,
+                "/v20180820/accesspoint/my-ap",
+                S3Operation::CreateAccessPoint,
+            ),
+            (
+                Method::GET,
+                "/v20180820/accesspoint/my-ap",
+                S3Operation::GetAccessPoint,
+            ),
+            (
+                Method::DELETE,
+                "/v20180820/accesspoint/my-ap",
+                S3Operation::DeleteAccessPoint,
+            ),
+            (
+                Method::GET,
+                "/v20180820/accesspoint?bucket=data",
+                S3Operation::ListAccessPoints,
+            ),
+        ];
+        for (method, uri, operation) in cases {
+            let req = vhost_request(&method, "123456789012.s3-control.localhost:4566", uri);
+            let ctx = router().resolve(&req).expect("should resolve");
+            assert_eq!(ctx.operation, operation);
+            assert!(ctx.bucket.is_none());
+            let expected_key = (operation != S3Operation::ListAccessPoints).then_some("my-ap");
+            assert_eq!(ctx.key.as_deref(), expected_key);
+        }
+
+        for (method, uri) in [
+            (Method::POST, "/v20180820/accesspoint/my-ap"),
+            (Method::GET, "/v20180820/accesspoint/my-ap/policy"),
+            (Method::GET, "/v20180820/jobs"),
+        ] {
+            let req = vhost_request(&method, "s3.localhost:4566", uri);
+            let err = router().resolve(&req).unwrap_err();
+            assert_eq!(err.code, S3ErrorCode::NotImplemented, "{method} {uri}");
+        }
+    }
 }
@@ -4,30 +4,75 @@
 //! serialization into a single hyper-compatible service. It handles:
 //!
 //! 1. Health check interception (`GET /_localstack/health`)
-//! 2. CORS preflight requests (`OPTIONS`)
-//! 3. Request body collection
-//! 4. S3 request routing via [`S3Router`]
-//! 5. Optional SigV4 authentication
-//! 6. Operation dispatch to the [`S3Handler`]
-//! 7. Common response headers (`x-amz-request-id`, `Server`, `Date`)
-//! 8. Error response formatting
+//! 2. CORS preflight requests (`OPTIONS`), evaluated against the bucket's CORS rules via
+//!    [`S3Handler::evaluate_cors`]
+//! 3. S3 request routing via [`S3Router`], and resolution of the request's [`RequestScope`]
+//!    (account and region namespace), which is inserted into the request extensions
+//! 4. Request body collection, rejecting bodies over [`S3HttpConfig::max_body_bytes`] with
+//!    `EntityTooLarge` before they are buffered
+//! 5. Optional SigV4 authentication (from the POST form for browser uploads), followed by
+//!    [`S3Handler::authorize`]. Anonymous GetObject requests may not override response headers, and
+//!    DeleteObjects requests must carry `Content-MD5` (or a flexible checksum header)
+//! 6. Injected latency: a request matching a [`LatencyInjector`] rule waits before dispatch, and
+//!    its response body may be drip-fed (see [`crate::latency`]). A request then matching a
+//!    [`FaultInjector`] rule is answered with the injected error instead of being dispatched (see
+//!    [`crate::fault`])
+//! 7. Operation dispatch to the [`S3Handler`]
+//! 8. Common response headers (`x-amz-request-id`, `x-amz-id-2`, an echoed `X-Amzn-Trace-Id`,
+//!    `Server`, and `x-amz-bucket-region` from [`S3Handler::bucket_region`]), plus the matching
+//!    CORS rule's headers for cross-origin requests
+//! 9. Error response formatting. Responses to `HEAD` requests never carry a body, so a failed
+//!    `HEAD` reports its error through the status code and headers only
+//! 10. Fixture record and replay ([`crate::fixture`]): when replaying, routed requests are answered
+//!     from recorded responses after body collection, before authentication and dispatch; when
+//!     recording, dispatched operations are appended to the fixture file
+//! 11. Access logging: every completed request is reported to [`S3Handler::record_access`] and, if
+//!     configured, to an [`AccessLogger`]
+//!
+//! A request sent with `Expect: 100-continue` is routed, scoped, and
+//! authenticated before its body is read, since hyper sends the interim
+//! `100 Continue` when the body is first polled. A request failing those
+//! checks is answered with its error instead, and the client never sends the
+//! body. Only signatures that cover the body itself (SigV4 without
+//! `X-Amz-Content-Sha256`, POST forms) wait for it.
+//!
+//! Responses produced by a dispatched operation carry the resolved
+//! [`S3Operation`](rustack_s3_model::S3Operation) in their extensions, and
+//! error responses carry their [`S3ErrorCode`]. Each request is processed
+//! inside a span recording its request id and any client trace id; with span
+//! export enabled, the span is named after the operation (`S3.PutObject`) and
+//! records the bucket, key, status, and error code.
 
-use std::{convert::Infallible, future::Future, pin::Pin, sync::Arc};
+use std::{convert::Infallible, future::Future, pin::Pin, sync::Arc, time::Instant};
 
 use bytes::Bytes;
-use http_body_util::BodyExt;
+use http_body::Body as _;
+use http_body_util::{BodyExt, LengthLimitError, Limited};
 use hyper::{body::Incoming, service::Service};
-use rustack_auth::CredentialProvider;
-use rustack_s3_model::error::{S3Error, S3ErrorCode};
+use rustack_auth::{AuthError, AuthResult, CredentialProvider, clock::MAX_CLOCK_SKEW};
+use rustack_core::{
+    AccessLogEntry, AccessLogger, RequestScope, TRACE_ID_HEADER, TRACEPARENT_HEADER,
+    new_request_id,
+    request_id::{record_operation, record_outcome, record_resource},
+    request_span,
+};
+use rustack_s3_model::{
+    S3Operation,
+    error::{S3Error, S3ErrorCode},
+};
 use sha2::{Digest, Sha256};
-use tracing::{debug, error, info, warn};
-use uuid::Uuid;
+use tracing::{Instrument, debug, error, info, warn};
 
 use crate::{
     body::S3ResponseBody,
+    cors::{self, CorsOutcome},
     dispatch::{S3Handler, dispatch_operation},
+    fault::FaultInjector,
+    fixture::{FixtureMode, FixtureRequest},
+    latency::LatencyInjector,
+    post_object::PostObjectForm,
     response::error_to_response,
-    router::S3Router,
+    router::{RoutingContext, S3Router},
 };
 
 /// Configuration for the S3 HTTP service.
@@ -43,6 +88,17 @@ pub struct S3HttpConfig {
     pub region: String,
     /// Optional credential provider for SigV4 and presigned URL verification.
     pub credential_provider: Option<Arc<dyn CredentialProvider>>,
+    /// Optional access logger; when set, one line is written per request.
+    pub access_log: Option<Arc<AccessLogger>>,
+    /// Optional fixture recording or replay; see [`crate::fixture`].
+    pub fixtures: Option<Arc<FixtureMode>>,
+    /// Largest request body accepted, in bytes on the wire. Larger bodies are
+    /// rejected with `EntityTooLarge`. Unlimited if unset.
+    pub max_body_bytes: Option<u64>,
+    /// Optional latency injection; see [`crate::latency`].
+    pub latency: Option<Arc<LatencyInjector>>,
+    /// Optional error injection; see [`crate::fault`].
+    pub faults: Option<Arc<FaultInjector>>,
 }
 
 impl std::fmt::Debug for S3HttpConfig {
@@ -56,6 +112,11 @@ impl std::fmt::Debug for S3HttpConfig {
                 "credential_provider",
                 &self.credential_provider.as_ref().map(|_| "..."),
             )
+            .field("access_log", &self.access_log)
+            .field("fixtures", &self.fixtures)
+            .field("max_body_bytes", &self.max_body_bytes)
+            .field("latency", &self.latency)
+            .field("faults", &self.faults)
             .finish()
     }
 }
@@ -68,6 +129,11 @@ impl Default for S3HttpConfig {
             skip_signature_validation: true,
             region: "us-east-1".to_owned(),
             credential_provider: None,
+            access_log: None,
+            fixtures: None,
+            max_body_bytes: None,
+            latency: None,
+            faults: None,
         }
     }
 }
@@ -131,21 +197,66 @@ impl<H: S3Handler> Service<http::Request<Incoming>> for S3HttpService<H> {
         let router = self.router.clone();
         let config = Arc::clone(&self.config);
 
-        Box::pin(async move {
-            let request_id = Uuid::new_v4().to_string();
+        let request_id = new_request_id();
+        let trace_id = req.headers().get(TRACE_ID_HEADER).cloned();
+        let span = request_span(
+            "s3",
+            &request_id,
+            trace_id.as_ref().and_then(|v| v.to_str().ok()),
+            req.headers()
+                .get(TRACEPARENT_HEADER)
+                .and_then(|v| v.to_str().ok()),
+        );
 
-            // Process the request through the S3 pipeline.
-            let response =
-                process_request(req, handler.as_ref(), &router, &config, &request_id).await;
+        Box::pin(
+            async move {
+                let started = Instant::now();
+                let mut access_log = start_access_log_entry(&req, &request_id);
+                let is_head = req.method() == http::Method::HEAD;
+                let bucket = router.resolve_bucket(&req);
+                let cors_outcome = cors::evaluate(handler.as_ref(), bucket.as_deref(), &req);
+
+                // Process the request through the S3 pipeline.
+                let response = process_request(
+                    req,
+                    handler.as_ref(),
+                    &router,
+                    &config,
+                    &cors_outcome,
+                    &request_id,
+                    &mut access_log,
+                )
+                .await;
+
+                // Add common response headers.
+                let mut response = add_common_headers(response, &request_id, trace_id);
+                cors::apply_headers(&mut response, cors_outcome);
+                if let Some(region) = bucket.as_deref().and_then(|b| handler.bucket_region(b)) {
+                    add_bucket_region_header(&mut response, &region);
+                }
+                if is_head {
+                    strip_head_body(&mut response);
+                }
 
-            // Add common response headers.
-            let response = add_common_headers(response, &request_id);
+                finish_access_log_entry(&mut access_log, &response, started);
+                record_outcome(access_log.status, access_log.error_code);
+                handler.record_access(&access_log);
+                if let Some(ref logger) = config.access_log {
+                    logger.log(&access_log);
+                }
 
-            Ok(response)
-        })
+                Ok(response)
+            }
+            .instrument(span),
+        )
     }
 }
 
+/// Request id of an injected error response, reported in place of the
+/// request's own.
+#[derive(Debug, Clone)]
+struct InjectedRequestId(String);
+
 /// Process an incoming HTTP request through the S3 pipeline.
 #[allow(clippy::too_many_lines)]
 async fn process_request<H: S3Handler>(
@@ -153,7 +264,9 @@ async fn process_request<H: S3Handler>(
     handler: &H,
     router: &S3Router,
     config: &S3HttpConfig,
+    cors_outcome: &CorsOutcome,
     request_id: &str,
+    access_log: &mut AccessLogEntry,
 ) -> http::Response<S3ResponseBody> {
     let method = req.method().clone();
     let uri = req.uri().clone();
@@ -171,12 +284,20 @@ async fn process_request<H: S3Handler>(
 
     // 2. CORS preflight.
     if method == http::Method::OPTIONS {
-        return cors_preflight_response();
+        return match cors_outcome {
+            // The matched rule's headers are added with the common headers.
+            CorsOutcome::Allowed(_) => http::Response::new(S3ResponseBody::empty()),
+            CorsOutcome::NotConfigured => cors_preflight_response(),
+            CorsOutcome::Denied => {
+                debug!(%uri, request_id, "CORS preflight not allowed");
+                error_to_response(&cors::forbidden(), request_id)
+            }
+        };
     }
 
     // 3. Route the request (needs the original request for headers).
     let routing_result = router.resolve(&req);
-    let ctx = match routing_result {
+    let mut ctx = match routing_result {
         Ok(ctx) => ctx,
         Err(err) => {
             warn!(
@@ -195,16 +316,48 @@ async fn process_request<H: S3Handler>(
         "routed S3 request"
     );
 
-    // 4. Collect body.
+    access_log.operation = Some(ctx.operation.as_str());
+    access_log.bucket.clone_from(&ctx.bucket);
+    access_log.key.clone_from(&ctx.key);
+    record_operation("S3", ctx.operation.as_str());
+    record_resource("bucket", ctx.bucket.as_deref());
+    record_resource("key", ctx.key.as_deref());
+
+    // 3b. Reject a declared body over the limit without reading it.
+    if let Some(limit) = config.max_body_bytes {
+        if content_length(req.headers()).is_some_and(|len| len > limit) {
+            warn!(limit, request_id, "request body too large");
+            return error_to_response(&entity_too_large(limit), request_id);
+        }
+    }
+
+    // 3c. Check a request waiting for `100 Continue` before reading its body,
+    // so a rejected request is answered with its error instead.
     let (mut parts, incoming) = req.into_parts();
-    let mut body = match collect_body(incoming).await {
-        Ok(body) => body,
-        Err(err) => {
-            error!(error = %err, request_id, "failed to collect request body");
-            let s3_err =
-                rustack_s3_model::error::S3Error::internal_error("Failed to read request body");
-            return error_to_response(&s3_err, request_id);
+    let replaying = config.fixtures.as_ref().is_some_and(|f| f.is_replay());
+    let mut resolved = false;
+    let mut authenticated = false;
+    if expects_continue(&parts.headers) && !replaying {
+        if let Err(err) = resolve_target(
+            handler, config, &mut ctx, &mut parts, access_log, request_id,
+        ) {
+            return error_to_response(&err, request_id);
         }
+        resolved = true;
+        authenticated = match authenticate(handler, config, &ctx, &parts, None, request_id) {
+            Ok(authenticated) => authenticated,
+            Err(err) => return error_to_response(&err, request_id),
+        };
+        debug!(
+            authenticated,
+            request_id, "checked request before 100 Continue"
+        );
+    }
+
+    // 4. Collect body.
+    let mut body = match collect_body(incoming, config.max_body_bytes).await {
+        Ok(body) => body,
+        Err(err) => return error_to_response(&err, request_id),
     };
 
     // 4b. Validate X-Amz-Content-Sha256 header (independent of auth).
@@ -245,47 +398,78 @@ async fn process_request<H: S3Handler>(
         }
     }
 
-    // 5. Authentication.
-    if !config.skip_signature_validation {
-        if let Some(ref cred_provider) = config.credential_provider {
-            let has_presigned = parts
-                .uri
-                .query()
-                .is_some_and(|q| q.contains("X-Amz-Signature"));
-
-            let auth_result = if has_presigned {
-                rustack_auth::verify_presigned(&parts, cred_provider.as_ref())
-            } else if let Some(auth_header) = parts
-                .headers
-                .get("authorization")
-                .and_then(|v| v.to_str().ok())
-            {
-                if rustack_auth::is_sigv2(auth_header) {
-                    rustack_auth::verify_sigv2(&parts, cred_provider.as_ref())
-                } else {
-                    let body_hash = rustack_auth::hash_payload(&body);
-                    rustack_auth::verify_sigv4(&parts, &body_hash, cred_provider.as_ref())
+    access_log.bytes_in = body.len() as u64;
+
+    // 4d. Answer from fixtures when replaying, before any state is touched.
+    let fixture_request = config
+        .fixtures
+        .as_ref()
+        .map(|_| FixtureRequest::capture(&parts, &ctx, &body));
+    if let (Some(fixtures), Some(fixture_request)) = (&config.fixtures, &fixture_request) {
+        if fixtures.is_replay() {
+            return match fixtures.replay_response(fixture_request).await {
+                Ok(mut response) => {
+                    response.extensions_mut().insert(ctx.operation);
+                    response
                 }
-            } else {
-                // Anonymous request — allow through.
-                Ok(rustack_auth::AuthResult {
-                    access_key_id: String::new(),
-                    region: String::new(),
-                    service: String::new(),
-                    signed_headers: Vec::new(),
-                })
+                Err(err) => error_to_response(&err, request_id),
             };
+        }
+    }
 
-            if let Err(auth_err) = auth_result {
-                warn!(error = %auth_err, request_id, "authentication failed");
-                let s3_err = S3Error::with_message(S3ErrorCode::AccessDenied, auth_err.to_string());
-                return error_to_response(&s3_err, request_id);
-            }
+    // 4e. Resolve the namespace and access point, unless done before the body.
+    if !resolved {
+        if let Err(err) = resolve_target(
+            handler, config, &mut ctx, &mut parts, access_log, request_id,
+        ) {
+            return error_to_response(&err, request_id);
+        }
+    }
+
+    // 5. Authentication, unless done before the body.
+    if !authenticated {
+        if let Err(err) = authenticate(handler, config, &ctx, &parts, Some(&body), request_id) {
+            return error_to_response(&err, request_id);
         }
     }
 
+    // 5b. Injected latency and errors.
+    let injection = config.latency.as_ref().and_then(|latency| {
+        latency.select(
+            ctx.operation.as_str(),
+            ctx.bucket.as_deref(),
+            ctx.key.as_deref(),
+        )
+    });
+    if let Some(injection) = injection {
+        debug!(delay = ?injection.delay, request_id, "injecting latency");
+        tokio::time::sleep(injection.delay).await;
+    }
+    let fault = config.faults.as_ref().and_then(|faults| {
+        faults.select(
+            ctx.operation.as_str(),
+            ctx.bucket.as_deref(),
+            ctx.key.as_deref(),
+        )
+    });
+    if let Some(fault) = fault {
+        info!(code = %fault.error.code, request_id, "injecting error");
+        let mut response = error_to_response(
+            &fault.error,
+            fault.request_id.as_deref().unwrap_or(request_id),
+        );
+        if let Some(injected_id) = fault.request_id {
+            response
+                .extensions_mut()
+                .insert(InjectedRequestId(injected_id));
+        }
+        response.extensions_mut().insert(ctx.operation);
+        return response;
+    }
+
     // 6. Dispatch to handler.
-    match dispatch_operation(handler, parts, body, ctx).await {
+    let operation = ctx.operation;
+    let mut response = match dispatch_operation(handler, parts, body, ctx).await {
         Ok(response) => response,
         Err(err) => {
             debug!(
@@ -295,13 +479,341 @@ async fn process_request<H: S3Handler>(
             );
             error_to_response(&err, request_id)
         }
+    };
+
+    // Expose the resolved operation to outer layers (e.g. gateway metrics).
+    response.extensions_mut().insert(operation);
+    if let Some(rate) = injection.and_then(|injection| injection.drip_bytes_per_sec) {
+        response = response.map(|body| body.drip(rate));
+    }
+
+    // 7. Record the exchange when recording fixtures.
+    match (&config.fixtures, fixture_request) {
+        (Some(fixtures), Some(fixture_request)) => {
+            fixtures.record_exchange(fixture_request, response).await
+        }
+        _ => response,
+    }
+}
+
+/// Start an access log entry from the parts of the request available up front.
+///
+/// Only the `User-Agent` header is copied; `Authorization` is reduced to the
+/// claimed access key ID.
+fn start_access_log_entry<B>(req: &http::Request<B>, request_id: &str) -> AccessLogEntry {
+    let uri = req
+        .uri()
+        .path_and_query()
+        .map_or_else(|| req.uri().path(), http::uri::PathAndQuery::as_str);
+    let mut entry = AccessLogEntry::new("s3", request_id, req.method().as_str(), uri);
+    entry.user_agent = req
+        .headers()
+        .get(http::header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(ToOwned::to_owned);
+    entry.requester = rustack_auth::requester_access_key(req.headers(), req.uri());
+    entry
+}
+
+/// Complete an access log entry from the final response.
+fn finish_access_log_entry(
+    entry: &mut AccessLogEntry,
+    response: &http::Response<S3ResponseBody>,
+    started: Instant,
+) {
+    entry.status = response.status().as_u16();
+    entry.error_code = response
+        .extensions()
+        .get::<S3ErrorCode>()
+        .map(S3ErrorCode::as_str);
+    entry.bytes_out = response.body().size_hint().lower();
+    entry.latency = started.elapsed();
+}
+
+/// Resolve the request's (account, region) namespace into its extensions, and
+/// swap an access point addressed in place of the bucket for the bucket
+/// behind it.
+fn resolve_target<H: S3Handler>(
+    handler: &H,
+    config: &S3HttpConfig,
+    ctx: &mut RoutingContext,
+    parts: &mut http::request::Parts,
+    access_log: &mut AccessLogEntry,
+    request_id: &str,
+) -> Result<(), S3Error> {
+    let scope = RequestScope::resolve(
+        rustack_auth::requester_access_key(&parts.headers, &parts.uri).as_deref(),
+        rustack_auth::requester_region(&parts.headers, &parts.uri).as_deref(),
+        &config.region,
+    );
+    debug!(%scope, request_id, "resolved request scope");
+    parts.extensions.insert(scope);
+
+    if let Some(access_point) = &ctx.access_point {
+        match handler.resolve_access_point(access_point, parts) {
+            Ok(bucket) => {
+                debug!(access_point = %access_point.name, %bucket, request_id, "resolved access point");
+                access_log.bucket = Some(bucket.clone());
+                ctx.bucket = Some(bucket);
+            }
+            Err(err) => {
+                warn!(access_point = %access_point.name, error = %err, request_id, "failed to resolve access point");
+                return Err(err);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Authenticate and authorize a routed request, if signature validation is
+/// enabled.
+///
+/// With `body` unset, returns `Ok(false)` without deciding if the checks
+/// depend on the body: a SigV4 signature over the body hash, a signed POST
+/// form, or a `DeleteObjects` integrity header that may arrive as an
+/// `aws-chunked` trailer.
+#[allow(clippy::too_many_lines)]
+fn authenticate<H: S3Handler>(
+    handler: &H,
+    config: &S3HttpConfig,
+    ctx: &RoutingContext,
+    parts: &http::request::Parts,
+    body: Option<&[u8]>,
+    request_id: &str,
+) -> Result<bool, S3Error> {
+    if config.skip_signature_validation {
+        return Ok(true);
+    }
+    let Some(ref cred_provider) = config.credential_provider else {
+        return Ok(true);
+    };
+
+    let has_presigned = parts
+        .uri
+        .query()
+        .is_some_and(|q| q.contains("X-Amz-Signature"));
+
+    let auth_result = if has_presigned {
+        rustack_auth::verify_presigned(parts, cred_provider.as_ref()).map(Some)
+    } else if parts
+        .uri
+        .query()
+        .is_some_and(rustack_auth::is_sigv2_presigned)
+    {
+        // Legacy clients presign with SigV2 query parameters.
+        rustack_auth::verify_sigv2_presigned(parts, cred_provider.as_ref()).map(Some)
+    } else if let Some(auth_header) = parts
+        .headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+    {
+        if rustack_auth::is_sigv2(auth_header) {
+            rustack_auth::verify_sigv2(parts, cred_provider.as_ref()).map(Some)
+        } else if let Some(body) = body {
+            // Covers both SigV4 and SigV4A, told apart by the algorithm.
+            let body_hash = rustack_auth::hash_payload(body);
+            rustack_auth::verify_sigv4(parts, &body_hash, cred_provider.as_ref()).map(Some)
+        } else if parts.headers.contains_key("x-amz-content-sha256") {
+            // The signed payload hash is the header value, not the body's.
+            rustack_auth::verify_sigv4(parts, "", cred_provider.as_ref()).map(Some)
+        } else {
+            return Ok(false);
+        }
+    } else if ctx.operation == S3Operation::PostObject {
+        // POST uploads sign their policy document inside the form body.
+        let Some(body) = body else {
+            return Ok(false);
+        };
+        verify_post_form(parts, body, cred_provider.as_ref())
+    } else {
+        // Anonymous request — allow through.
+        Ok(None)
+    };
+
+    let auth = match auth_result {
+        Ok(auth) => auth,
+        Err(auth_err) => {
+            warn!(error = %auth_err, request_id, "authentication failed");
+            return Err(auth_error_to_s3(&auth_err));
+        }
+    };
+    if let Some(auth) = &auth {
+        debug!(
+            access_key_id = %auth.access_key_id,
+            algorithm = %auth.algorithm,
+            region = %auth.region,
+            request_id,
+            "request authenticated"
+        );
+    }
+
+    if auth.is_none()
+        && ctx.operation == S3Operation::GetObject
+        && has_response_overrides(&ctx.query_params)
+    {
+        warn!(request_id, "response header overrides on anonymous request");
+        return Err(S3Error::with_message(
+            S3ErrorCode::InvalidRequest,
+            "Request specific response headers cannot be used for anonymous GET requests.",
+        ));
+    }
+
+    if ctx.operation == S3Operation::DeleteObjects && !has_integrity_header(parts) {
+        if body.is_none() {
+            return Ok(false);
+        }
+        warn!(request_id, "DeleteObjects request without Content-MD5");
+        return Err(S3Error::with_message(
+            S3ErrorCode::InvalidRequest,
+            "Missing required header for this request: Content-Md5.",
+        ));
+    }
+
+    // Authorization.
+    let principal = auth.as_ref().map(|auth| auth.access_key_id.as_str());
+    if let Err(err) = handler.authorize(ctx, parts, principal) {
+        warn!(
+            operation = %ctx.operation,
+            principal = principal.unwrap_or("anonymous"),
+            request_id,
+            "request not authorized"
+        );
+        return Err(err);
+    }
+    Ok(true)
+}
+
+/// Whether the client waits for `100 Continue` before sending the body.
+fn expects_continue(headers: &http::HeaderMap) -> bool {
+    headers
+        .get(http::header::EXPECT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("100-continue"))
+}
+
+/// The declared `Content-Length` of the request, if any.
+fn content_length(headers: &http::HeaderMap) -> Option<u64> {
+    headers
+        .get(http::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// The `EntityTooLarge` error for a body over `limit` bytes.
+fn entity_too_large(limit: u64) -> S3Error {
+    S3Error::new(S3ErrorCode::EntityTooLarge).with_detail("MaxSizeAllowed", limit.to_string())
+}
+
+/// Collect the full body from a hyper `Incoming` stream into `Bytes`, failing
+/// with `EntityTooLarge` as soon as it grows past `limit` bytes.
+async fn collect_body(incoming: Incoming, limit: Option<u64>) -> Result<Bytes, S3Error> {
+    let Some(limit) = limit else {
+        return match incoming.collect().await {
+            Ok(collected) => Ok(collected.to_bytes()),
+            Err(err) => Err(body_read_error(&err)),
+        };
+    };
+    let limited = Limited::new(incoming, usize::try_from(limit).unwrap_or(usize::MAX));
+    match limited.collect().await {
+        Ok(collected) => Ok(collected.to_bytes()),
+        Err(err) if err.is::<LengthLimitError>() => {
+            warn!(limit, "request body too large");
+            Err(entity_too_large(limit))
+        }
+        Err(err) => Err(body_read_error(err.as_ref())),
+    }
+}
+
+/// The error for a request body that could not be read.
+fn body_read_error(err: &dyn std::error::Error) -> S3Error {
+    error!(error = %err, "failed to collect request body");
+    S3Error::internal_error("Failed to read request body")
+}
+
+/// Verify the signature of a POST upload form, if it carries one.
+///
+/// A form without a policy or signature is an anonymous upload. A body that is
+/// not valid form data is left for the handler to reject.
+fn verify_post_form(
+    parts: &http::request::Parts,
+    body: &[u8],
+    credential_provider: &dyn CredentialProvider,
+) -> Result<Option<AuthResult>, AuthError> {
+    let Ok(form) = PostObjectForm::parse(parts, body) else {
+        return Ok(None);
+    };
+    let signed = ["policy", "x-amz-signature", "signature"]
+        .iter()
+        .any(|field| form.fields.contains_key(*field));
+    if !signed {
+        return Ok(None);
+    }
+    rustack_auth::verify_post_policy(&form.fields, credential_provider).map(Some)
+}
+
+/// Query parameters of a GetObject request that override response headers.
+const RESPONSE_OVERRIDE_PARAMS: [&str; 6] = [
+    "response-cache-control",
+    "response-content-disposition",
+    "response-content-encoding",
+    "response-content-language",
+    "response-content-type",
+    "response-expires",
+];
+
+/// Whether the query string overrides any response header.
+///
+/// S3 accepts the overrides only on signed requests, where they are covered
+/// by the signature.
+fn has_response_overrides(query_params: &[(String, String)]) -> bool {
+    query_params
+        .iter()
+        .any(|(name, _)| RESPONSE_OVERRIDE_PARAMS.contains(&name.as_str()))
+}
+
+/// Whether the request carries a body integrity header: `Content-MD5` or one
+/// of the flexible `x-amz-checksum-*` headers newer SDKs send instead.
+fn has_integrity_header(parts: &http::request::Parts) -> bool {
+    parts.headers.keys().any(|name| {
+        name == "content-md5"
+            || (name.as_str().starts_with("x-amz-checksum-") && name != "x-amz-checksum-mode")
+    })
+}
+
+/// Map an authentication failure to the S3 error a client would see from AWS.
+///
+/// A skewed `X-Amz-Date` becomes `RequestTimeTooSkewed` carrying the request and
+/// server times, and a wrong or expired session token becomes `InvalidToken` or
+/// `ExpiredToken`; every other failure is reported as `AccessDenied`.
+fn auth_error_to_s3(err: &AuthError) -> S3Error {
+    match err {
+        AuthError::InvalidToken => token_error("InvalidToken", err),
+        AuthError::ExpiredToken => token_error("ExpiredToken", err),
+        AuthError::RequestTimeTooSkewed {
+            request_time,
+            server_time,
+        } => S3Error::with_message(S3ErrorCode::RequestTimeTooSkewed, err.to_string())
+            .with_detail(
+                "RequestTime",
+                rustack_auth::clock::format_amz_date(*request_time),
+            )
+            .with_detail(
+                "ServerTime",
+                server_time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            )
+            .with_detail(
+                "MaxAllowedSkewMilliseconds",
+                MAX_CLOCK_SKEW.num_milliseconds().to_string(),
+            ),
+        _ => S3Error::with_message(S3ErrorCode::AccessDenied, err.to_string()),
     }
 }
 
-/// Collect the full body from a hyper `Incoming` stream into `Bytes`.
-async fn collect_body(incoming: Incoming) -> Result<Bytes, hyper::Error> {
-    let collected = incoming.collect().await?;
-    Ok(collected.to_bytes())
+/// A `400` session token error with the given S3 error code.
+fn token_error(code: &'static str, err: &AuthError) -> S3Error {
+    let mut s3_err = S3Error::with_message(S3ErrorCode::Custom(code), err.to_string());
+    s3_err.status_code = http::StatusCode::BAD_REQUEST;
+    s3_err
 }
 
 /// Validate the `X-Amz-Content-Sha256` header against the request body.
@@ -399,7 +911,8 @@ fn prometheus_metrics_response() -> http::Response<S3ResponseBody> {
         .expect("static metrics response should be valid")
 }
 
-/// Produce a CORS preflight response.
+/// Produce the permissive default CORS preflight response, used when the
+/// bucket has no CORS configuration.
 fn cors_preflight_response() -> http::Response<S3ResponseBody> {
     http::Response::builder()
         .status(http::StatusCode::OK)
@@ -421,7 +934,15 @@ fn cors_preflight_response() -> http::Response<S3ResponseBody> {
 fn add_common_headers(
     mut response: http::Response<S3ResponseBody>,
     request_id: &str,
+    trace_id: Option<http::header::HeaderValue>,
 ) -> http::Response<S3ResponseBody> {
+    // An injected error reports the request id its rule names.
+    let request_id = response
+        .extensions()
+        .get::<InjectedRequestId>()
+        .map_or(request_id, |id| id.0.as_str())
+        .to_owned();
+    let request_id = request_id.as_str();
     let headers = response.headers_mut();
 
     // x-amz-request-id
@@ -434,30 +955,79 @@ fn add_common_headers(
         headers.insert("x-amz-id-2", hv);
     }
 
+    // Echo the client's X-Amzn-Trace-Id.
+    if let Some(trace_id) = trace_id {
+        headers.insert(TRACE_ID_HEADER, trace_id);
+    }
+
     // Server header
     headers.insert(
         "Server",
         http::header::HeaderValue::from_static("RustackS3"),
     );
 
-    // CORS headers for all responses
-    headers.insert(
-        "Access-Control-Allow-Origin",
-        http::header::HeaderValue::from_static("*"),
-    );
-    headers.insert(
-        "Access-Control-Expose-Headers",
-        http::header::HeaderValue::from_static(
-            "x-amz-request-id, x-amz-id-2, x-amz-version-id, ETag, x-amz-delete-marker",
-        ),
-    );
-
     response
 }
 
+/// Report the addressed bucket's region, unless the response already does.
+fn add_bucket_region_header(response: &mut http::Response<S3ResponseBody>, region: &str) {
+    if let Ok(hv) = http::header::HeaderValue::from_str(region) {
+        response
+            .headers_mut()
+            .entry("x-amz-bucket-region")
+            .or_insert(hv);
+    }
+}
+
+/// Drop the body of a response to a `HEAD` request.
+///
+/// A successful response keeps the `Content-Length` of the resource it
+/// describes; an error loses its XML document and reports an empty body.
+fn strip_head_body(response: &mut http::Response<S3ResponseBody>) {
+    let is_error = response.status().as_u16() >= 300;
+    if is_error {
+        response.headers_mut().insert(
+            http::header::CONTENT_LENGTH,
+            http::header::HeaderValue::from_static("0"),
+        );
+    }
+    *response.body_mut() = S3ResponseBody::empty();
+}
+
 #[cfg(test)]
 mod tests {
+    use chrono::{TimeZone, Utc};
+
     use super::*;
+    use crate::router::RoutingContext;
+
+    #[test]
+    fn test_should_map_skewed_request_to_request_time_too_skewed() {
+        let err = auth_error_to_s3(&AuthError::RequestTimeTooSkewed {
+            request_time: Utc.with_ymd_and_hms(2013, 5, 24, 0, 0, 0).unwrap(),
+            server_time: Utc.with_ymd_and_hms(2013, 5, 24, 1, 0, 0).unwrap(),
+        });
+        assert_eq!(err.code, S3ErrorCode::RequestTimeTooSkewed);
+        assert_eq!(err.status_code, http::StatusCode::FORBIDDEN);
+        let details = err.details.expect("details");
+        assert!(details.contains(&("RequestTime".to_owned(), "20130524T000000Z".to_owned())));
+        assert!(details.contains(&("ServerTime".to_owned(), "2013-05-24T01:00:00Z".to_owned())));
+
+        let err = auth_error_to_s3(&AuthError::RequestExpired);
+        assert_eq!(err.code, S3ErrorCode::AccessDenied);
+        assert_eq!(err.message, "Request has expired");
+    }
+
+    #[test]
+    fn test_should_map_session_token_errors() {
+        let err = auth_error_to_s3(&AuthError::InvalidToken);
+        assert_eq!(err.code.as_str(), "InvalidToken");
+        assert_eq!(err.status_code, http::StatusCode::BAD_REQUEST);
+
+        let err = auth_error_to_s3(&AuthError::ExpiredToken);
+        assert_eq!(err.code.as_str(), "ExpiredToken");
+        assert_eq!(err.message, "The provided token has expired.");
+    }
 
     #[test]
     fn test_should_detect_health_check_paths() {
@@ -521,13 +1091,308 @@ mod tests {
         assert!(resp.headers().contains_key("Access-Control-Allow-Methods"));
     }
 
+    /// A handler whose only bucket with CORS rules is `cors-bucket`, which
+    /// allows `https://app.example.com` and exposes `ETag`.
+    struct CorsHandler;
+
+    impl S3Handler for CorsHandler {
+        fn handle_operation(
+            &self,
+            _op: S3Operation,
+            _parts: http::request::Parts,
+            _body: Bytes,
+            _ctx: RoutingContext,
+        ) -> Pin<Box<dyn Future<Output = Result<http::Response<S3ResponseBody>, S3Error>> + Send>>
+        {
+            Box::pin(async { Ok(http::Response::new(S3ResponseBody::empty())) })
+        }
+
+        fn evaluate_cors(&self, request: &cors::CorsRequest<'_>) -> cors::CorsOutcome {
+            if request.bucket != "cors-bucket" {
+                return cors::CorsOutcome::NotConfigured;
+            }
+            if request.origin != "https://app.example.com" {
+                return cors::CorsOutcome::Denied;
+            }
+            let mut headers = http::HeaderMap::new();
+            headers.insert(
+                http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
+                request.origin.parse().expect("valid"),
+            );
+            headers.insert(
+                http::header::ACCESS_CONTROL_EXPOSE_HEADERS,
+                http::HeaderValue::from_static("ETag"),
+            );
+            if let Some(requested) = request.preflight_headers {
+                headers.insert(
+                    http::header::ACCESS_CONTROL_ALLOW_HEADERS,
+                    requested.join(", ").parse().expect("valid"),
+                );
+            }
+            cors::CorsOutcome::Allowed(headers)
+        }
+
+        fn bucket_region(&self, bucket: &str) -> Option<String> {
+            (bucket == "cors-bucket").then(|| "eu-west-1".to_owned())
+        }
+    }
+
+    /// A signature-validating configuration with path-style addressing.
+    fn validating_config() -> S3HttpConfig {
+        S3HttpConfig {
+            virtual_hosting: false,
+            skip_signature_validation: false,
+            credential_provider: Some(Arc::new(rustack_auth::StaticCredentialProvider::new(vec![
+                ("AKID".to_owned(), "secret".to_owned()),
+            ]))),
+            ..S3HttpConfig::default()
+        }
+    }
+
+    /// Send a raw HTTP/1.1 request through a signature-validating
+    /// [`S3HttpService`] and return the status and lowercased headers.
+    async fn send_raw(request: &str) -> (u16, Vec<(String, String)>) {
+        let raw = exchange_raw(validating_config(), request).await;
+        let head = raw.split("\r\n\r\n").next().unwrap_or_default();
+        let mut lines = head.lines();
+        let status = lines
+            .next()
+            .and_then(|line| line.split(' ').nth(1))
+            .and_then(|code| code.parse().ok())
+            .expect("status line");
+        let headers = lines
+            .filter_map(|line| line.split_once(": "))
+            .map(|(name, value)| (name.to_ascii_lowercase(), value.to_owned()))
+            .collect();
+        (status, headers)
+    }
+
+    /// Send a raw HTTP/1.1 request through an [`S3HttpService`] with `config`
+    /// and return everything written back, interim responses included.
+    async fn exchange_raw(config: S3HttpConfig, request: &str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let service = S3HttpService::new(CorsHandler, config);
+        let (mut client, server) = tokio::io::duplex(64 * 1024);
+        let connection = tokio::spawn(
+            hyper::server::conn::http1::Builder::new()
+                .serve_connection(hyper_util::rt::TokioIo::new(server), service),
+        );
+
+        client
+            .write_all(request.as_bytes())
+            .await
+            .expect("write request");
+        let mut raw = String::new();
+        client
+            .read_to_string(&mut raw)
+            .await
+            .expect("read response");
+        connection
+            .await
+            .expect("join")
+            .expect("connection succeeds");
+        raw
+    }
+
+    fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    #[tokio::test]
+    async fn test_should_reject_response_overrides_on_anonymous_get() {
+        let (status, _) = send_raw(
+            "GET /cors-bucket/photo.jpg?response-content-disposition=attachment HTTP/1.1\r\nHost: \
+             localhost\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert_eq!(status, 400);
+
+        let (status, _) = send_raw(
+            "GET /cors-bucket/photo.jpg HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert_eq!(status, 200);
+    }
+
+    #[tokio::test]
+    async fn test_should_require_content_md5_for_delete_objects() {
+        let body = "<Delete><Object><Key>a</Key></Object></Delete>";
+        let request = |integrity: &str| {
+            format!(
+                "POST /cors-bucket?delete HTTP/1.1\r\nHost: \
+                 localhost\r\n{integrity}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+        };
+
+        let (status, _) = send_raw(&request("")).await;
+        assert_eq!(status, 400);
+        let (status, _) = send_raw(&request("Content-MD5: 1B2M2Y8AsgTpgAmY7PhCfg==\r\n")).await;
+        assert_eq!(status, 200);
+        let (status, _) = send_raw(&request("x-amz-checksum-crc32: AAAAAA==\r\n")).await;
+        assert_eq!(status, 200);
+    }
+
+    #[tokio::test]
+    async fn test_should_answer_expect_continue_with_auth_error() {
+        let raw = exchange_raw(
+            validating_config(),
+            "PUT /cors-bucket/key HTTP/1.1\r\nHost: localhost\r\nAuthorization: AWS4-HMAC-SHA256 \
+             Credential=UNKNOWN/20250101/us-east-1/s3/aws4_request, SignedHeaders=host, \
+             Signature=00\r\nx-amz-content-sha256: UNSIGNED-PAYLOAD\r\nx-amz-date: \
+             20250101T000000Z\r\nExpect: 100-continue\r\nContent-Length: 5\r\nConnection: \
+             close\r\n\r\n",
+        )
+        .await;
+        assert!(raw.starts_with("HTTP/1.1 403"), "{raw}");
+        assert!(!raw.contains("100 Continue"));
+    }
+
+    #[tokio::test]
+    async fn test_should_continue_accepted_request_before_reading_body() {
+        let raw = exchange_raw(
+            validating_config(),
+            "PUT /cors-bucket/key HTTP/1.1\r\nHost: localhost\r\nExpect: \
+             100-continue\r\nContent-Length: 5\r\nConnection: close\r\n\r\nhello",
+        )
+        .await;
+        assert!(raw.starts_with("HTTP/1.1 100 Continue\r\n\r\n"), "{raw}");
+        assert!(raw.contains("HTTP/1.1 200 OK"));
+    }
+
+    #[tokio::test]
+    async fn test_should_drop_error_body_from_head_response() {
+        let raw = exchange_raw(
+            validating_config(),
+            "HEAD /cors-bucket/key HTTP/1.1\r\nHost: localhost\r\nAuthorization: AWS4-HMAC-SHA256 \
+             Credential=UNKNOWN/20250101/us-east-1/s3/aws4_request, SignedHeaders=host, \
+             Signature=00\r\nx-amz-content-sha256: UNSIGNED-PAYLOAD\r\nx-amz-date: \
+             20250101T000000Z\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(raw.starts_with("HTTP/1.1 403"), "{raw}");
+        assert!(raw.contains("content-length: 0\r\n"), "{raw}");
+        assert!(raw.ends_with("\r\n\r\n"), "{raw}");
+    }
+
+    #[tokio::test]
+    async fn test_should_report_bucket_region() {
+        let (_, headers) = send_raw(
+            "GET /cors-bucket/photo.jpg HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert_eq!(header(&headers, "x-amz-bucket-region"), Some("eu-west-1"));
+
+        let (_, headers) = send_raw(
+            "GET /other-bucket/photo.jpg HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert_eq!(header(&headers, "x-amz-bucket-region"), None);
+    }
+
+    #[tokio::test]
+    async fn test_should_reject_oversized_body_with_entity_too_large() {
+        let config = S3HttpConfig {
+            max_body_bytes: Some(4),
+            ..validating_config()
+        };
+        let declared = exchange_raw(
+            config.clone(),
+            "PUT /cors-bucket/key HTTP/1.1\r\nHost: localhost\r\nExpect: \
+             100-continue\r\nContent-Length: 5\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(declared.starts_with("HTTP/1.1 400"), "{declared}");
+        assert!(declared.contains("<Code>EntityTooLarge</Code>"));
+        assert!(declared.contains("<MaxSizeAllowed>4</MaxSizeAllowed>"));
+
+        let chunked = exchange_raw(
+            config,
+            "PUT /cors-bucket/key HTTP/1.1\r\nHost: localhost\r\nTransfer-Encoding: \
+             chunked\r\nConnection: close\r\n\r\n3\r\nabc\r\n3\r\ndef\r\n0\r\n\r\n",
+        )
+        .await;
+        assert!(chunked.contains("<Code>EntityTooLarge</Code>"), "{chunked}");
+    }
+
+    #[tokio::test]
+    async fn test_should_allow_unauthenticated_preflight_with_wildcard_headers() {
+        let (status, headers) = send_raw(
+            "OPTIONS /cors-bucket/photo.jpg HTTP/1.1\r\nHost: localhost\r\n\
+             Origin: https://app.example.com\r\nAccess-Control-Request-Method: PUT\r\n\
+             Access-Control-Request-Headers: content-type, x-amz-meta-foo\r\n\
+             Connection: close\r\n\r\n",
+        )
+        .await;
+        assert_eq!(status, 200);
+        assert_eq!(
+            header(&headers, "access-control-allow-origin"),
+            Some("https://app.example.com")
+        );
+        assert_eq!(
+            header(&headers, "access-control-allow-headers"),
+            Some("content-type, x-amz-meta-foo")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_should_reject_preflight_matching_no_rule() {
+        let (status, headers) = send_raw(
+            "OPTIONS /cors-bucket/photo.jpg HTTP/1.1\r\nHost: localhost\r\n\
+             Origin: https://evil.example.org\r\nAccess-Control-Request-Method: PUT\r\n\
+             Connection: close\r\n\r\n",
+        )
+        .await;
+        assert_eq!(status, 403);
+        assert!(header(&headers, "access-control-allow-origin").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_should_use_default_preflight_for_bucket_without_cors() {
+        let (status, headers) = send_raw(
+            "OPTIONS /plain-bucket HTTP/1.1\r\nHost: localhost\r\n\
+             Origin: https://app.example.com\r\nAccess-Control-Request-Method: GET\r\n\
+             Connection: close\r\n\r\n",
+        )
+        .await;
+        assert_eq!(status, 200);
+        assert_eq!(header(&headers, "access-control-allow-origin"), Some("*"));
+    }
+
+    #[tokio::test]
+    async fn test_should_expose_headers_on_actual_cors_response() {
+        let (status, headers) = send_raw(
+            "GET /cors-bucket/photo.jpg HTTP/1.1\r\nHost: localhost\r\n\
+             Origin: https://app.example.com\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert_eq!(status, 200);
+        assert_eq!(
+            header(&headers, "access-control-expose-headers"),
+            Some("ETag")
+        );
+        assert!(header(&headers, "access-control-allow-headers").is_none());
+
+        let (_, headers) = send_raw(
+            "GET /cors-bucket/photo.jpg HTTP/1.1\r\nHost: localhost\r\n\
+             Origin: https://evil.example.org\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(header(&headers, "access-control-allow-origin").is_none());
+        assert!(header(&headers, "access-control-expose-headers").is_none());
+    }
+
     #[test]
     fn test_should_add_common_headers() {
         let resp = http::Response::builder()
             .status(http::StatusCode::OK)
             .body(S3ResponseBody::empty())
             .expect("valid response");
-        let resp = add_common_headers(resp, "test-request-id");
+        let resp = add_common_headers(resp, "test-request-id", None);
         assert_eq!(
             resp.headers()
                 .get("x-amz-request-id")
@@ -538,6 +1403,29 @@ mod tests {
             resp.headers().get("Server").and_then(|v| v.to_str().ok()),
             Some("RustackS3"),
         );
+        assert!(resp.headers().get(TRACE_ID_HEADER).is_none());
+    }
+
+    #[test]
+    fn test_should_echo_trace_id_on_error_responses() {
+        let err = S3Error::no_such_bucket("missing");
+        let resp = add_common_headers(
+            error_to_response(&err, "req-7"),
+            "req-7",
+            Some(http::header::HeaderValue::from_static("Root=1-abc-def")),
+        );
+        assert_eq!(
+            resp.headers()
+                .get(TRACE_ID_HEADER)
+                .and_then(|v| v.to_str().ok()),
+            Some("Root=1-abc-def"),
+        );
+        assert_eq!(
+            resp.headers()
+                .get("x-amz-id-2")
+                .and_then(|v| v.to_str().ok()),
+            Some("req-7"),
+        );
     }
 
     #[test]
@@ -548,6 +1436,7 @@ mod tests {
         assert!(config.skip_signature_validation);
         assert_eq!(config.region, "us-east-1");
         assert!(config.credential_provider.is_none());
+        assert_eq!(config.max_body_bytes, None);
     }
 
     #[test]
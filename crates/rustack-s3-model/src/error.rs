@@ -10,6 +10,8 @@ pub enum S3ErrorCode {
     #[default]
     /// AccessDenied error.
     AccessDenied,
+    /// AccessControlListNotSupported error.
+    AccessControlListNotSupported,
     /// AccountProblem error.
     AccountProblem,
     /// BucketAlreadyExists error.
@@ -26,6 +28,8 @@ pub enum S3ErrorCode {
     InternalError,
     /// InvalidArgument error.
     InvalidArgument,
+    /// InvalidBucketAclWithObjectOwnership error.
+    InvalidBucketAclWithObjectOwnership,
     /// InvalidBucketName error.
     InvalidBucketName,
     /// InvalidBucketState error.
@@ -48,6 +52,8 @@ pub enum S3ErrorCode {
     InvalidStorageClass,
     /// KeyTooLongError error.
     KeyTooLongError,
+    /// MalformedACLError error.
+    MalformedACLError,
     /// MalformedXML error.
     MalformedXML,
     /// MetadataTooLarge error.
@@ -80,10 +86,14 @@ pub enum S3ErrorCode {
     ObjectNotInActiveTierError,
     /// PreconditionFailed error.
     PreconditionFailed,
+    /// RequestTimeTooSkewed error.
+    RequestTimeTooSkewed,
     /// SignatureDoesNotMatch error.
     SignatureDoesNotMatch,
     /// TooManyBuckets error.
     TooManyBuckets,
+    /// UnresolvableGrantByEmailAddress error.
+    UnresolvableGrantByEmailAddress,
     /// XAmzContentSHA256Mismatch error.
     XAmzContentSHA256Mismatch,
     /// BadDigest error.
@@ -98,6 +108,8 @@ pub enum S3ErrorCode {
     NoSuchPublicAccessBlockConfiguration,
     /// NotModified error (HTTP 304).
     NotModified,
+    /// PermanentRedirect error (HTTP 301).
+    PermanentRedirect,
     /// OwnershipControlsNotFoundError error.
     OwnershipControlsNotFoundError,
     /// ReplicationConfigurationNotFoundError error.
@@ -114,6 +126,7 @@ impl S3ErrorCode {
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::AccessDenied => "AccessDenied",
+            Self::AccessControlListNotSupported => "AccessControlListNotSupported",
             Self::AccountProblem => "AccountProblem",
             Self::BucketAlreadyExists => "BucketAlreadyExists",
             Self::BucketAlreadyOwnedByYou => "BucketAlreadyOwnedByYou",
@@ -122,6 +135,7 @@ impl S3ErrorCode {
             Self::EntityTooSmall => "EntityTooSmall",
             Self::InternalError => "InternalError",
             Self::InvalidArgument => "InvalidArgument",
+            Self::InvalidBucketAclWithObjectOwnership => "InvalidBucketAclWithObjectOwnership",
             Self::InvalidBucketName => "InvalidBucketName",
             Self::InvalidBucketState => "InvalidBucketState",
             Self::InvalidDigest => "InvalidDigest",
@@ -133,6 +147,7 @@ impl S3ErrorCode {
             Self::InvalidRequest => "InvalidRequest",
             Self::InvalidStorageClass => "InvalidStorageClass",
             Self::KeyTooLongError => "KeyTooLongError",
+            Self::MalformedACLError => "MalformedACLError",
             Self::MalformedXML => "MalformedXML",
             Self::MetadataTooLarge => "MetadataTooLarge",
             Self::MethodNotAllowed => "MethodNotAllowed",
@@ -149,8 +164,10 @@ impl S3ErrorCode {
             Self::NotImplemented => "NotImplemented",
             Self::ObjectNotInActiveTierError => "ObjectNotInActiveTierError",
             Self::PreconditionFailed => "PreconditionFailed",
+            Self::RequestTimeTooSkewed => "RequestTimeTooSkewed",
             Self::SignatureDoesNotMatch => "SignatureDoesNotMatch",
             Self::TooManyBuckets => "TooManyBuckets",
+            Self::UnresolvableGrantByEmailAddress => "UnresolvableGrantByEmailAddress",
             Self::XAmzContentSHA256Mismatch => "XAmzContentSHA256Mismatch",
             Self::BadDigest => "BadDigest",
             Self::ConditionalRequestConflict => "ConditionalRequestConflict",
@@ -158,6 +175,7 @@ impl S3ErrorCode {
             Self::NoSuchObjectLockConfiguration => "NoSuchObjectLockConfiguration",
             Self::NoSuchPublicAccessBlockConfiguration => "NoSuchPublicAccessBlockConfiguration",
             Self::NotModified => "NotModified",
+            Self::PermanentRedirect => "PermanentRedirect",
             Self::OwnershipControlsNotFoundError => "OwnershipControlsNotFoundError",
             Self::ReplicationConfigurationNotFoundError => "ReplicationConfigurationNotFoundError",
             Self::ServerSideEncryptionConfigurationNotFoundError => {
@@ -173,10 +191,13 @@ impl S3ErrorCode {
     pub fn default_status_code(&self) -> http::StatusCode {
         match self {
             Self::NotModified => http::StatusCode::NOT_MODIFIED,
-            Self::BadDigest
+            Self::PermanentRedirect => http::StatusCode::MOVED_PERMANENTLY,
+            Self::AccessControlListNotSupported
+            | Self::BadDigest
             | Self::EntityTooLarge
             | Self::EntityTooSmall
             | Self::InvalidArgument
+            | Self::InvalidBucketAclWithObjectOwnership
             | Self::InvalidBucketName
             | Self::InvalidDigest
             | Self::InvalidLocationConstraint
@@ -185,16 +206,19 @@ impl S3ErrorCode {
             | Self::InvalidRequest
             | Self::InvalidStorageClass
             | Self::KeyTooLongError
+            | Self::MalformedACLError
             | Self::MalformedXML
             | Self::MaxMessageLengthExceeded
             | Self::MetadataTooLarge
             | Self::ServerSideEncryptionConfigurationNotFoundError
             | Self::TooManyBuckets
+            | Self::UnresolvableGrantByEmailAddress
             | Self::XAmzContentSHA256Mismatch => http::StatusCode::BAD_REQUEST,
             Self::AccessDenied
             | Self::AccountProblem
             | Self::InvalidObjectState
             | Self::ObjectNotInActiveTierError
+            | Self::RequestTimeTooSkewed
             | Self::SignatureDoesNotMatch => http::StatusCode::FORBIDDEN,
             Self::NoSuchBucket
             | Self::NoSuchBucketPolicy
@@ -229,6 +253,7 @@ impl S3ErrorCode {
     pub fn default_message(&self) -> &'static str {
         match self {
             Self::AccessDenied => "Access Denied",
+            Self::AccessControlListNotSupported => "The bucket does not allow ACLs",
             Self::AccountProblem => "There is a problem with the account",
             Self::BucketAlreadyExists => "The requested bucket name is not available",
             Self::BucketAlreadyOwnedByYou => "The bucket is already owned by you",
@@ -237,6 +262,9 @@ impl S3ErrorCode {
             Self::EntityTooSmall => "Your proposed upload is smaller than the minimum allowed size",
             Self::InternalError => "Internal server error",
             Self::InvalidArgument => "Invalid Argument",
+            Self::InvalidBucketAclWithObjectOwnership => {
+                "Bucket cannot have ACLs set with ObjectOwnership's BucketOwnerEnforced setting"
+            }
             Self::InvalidBucketName => "The specified bucket is not valid",
             Self::InvalidBucketState => {
                 "The request is not valid with the current state of the bucket"
@@ -252,6 +280,10 @@ impl S3ErrorCode {
             Self::InvalidRequest => "Invalid Request",
             Self::InvalidStorageClass => "The storage class you specified is not valid",
             Self::KeyTooLongError => "Your key is too long",
+            Self::MalformedACLError => {
+                "The XML you provided was not well-formed or did not validate against our \
+                 published schema"
+            }
             Self::MalformedXML => "The XML you provided was not well-formed",
             Self::MetadataTooLarge => {
                 "Your metadata headers exceed the maximum allowed metadata size"
@@ -274,8 +306,14 @@ impl S3ErrorCode {
             Self::PreconditionFailed => {
                 "At least one of the preconditions you specified did not hold"
             }
+            Self::RequestTimeTooSkewed => {
+                "The difference between the request time and the current time is too large."
+            }
             Self::SignatureDoesNotMatch => "The request signature does not match",
             Self::TooManyBuckets => "You have attempted to create more buckets than allowed",
+            Self::UnresolvableGrantByEmailAddress => {
+                "The email address you provided does not match any account on record"
+            }
             Self::XAmzContentSHA256Mismatch => {
                 "The provided x-amz-content-sha256 header does not match"
             }
@@ -289,6 +327,10 @@ impl S3ErrorCode {
                 "The public access block configuration was not found"
             }
             Self::NotModified => "Not Modified",
+            Self::PermanentRedirect => {
+                "The bucket you are attempting to access must be addressed using the specified \
+                 endpoint. Please send all future requests to this endpoint."
+            }
             Self::OwnershipControlsNotFoundError => "The bucket ownership controls were not found",
             Self::ReplicationConfigurationNotFoundError => {
                 "The replication configuration was not found"
@@ -326,6 +368,9 @@ pub struct S3Error {
     ///
     /// Boxed to minimize the size of `S3Error` on the common (header-free) path.
     pub headers: Option<Box<Vec<(String, String)>>>,
+    /// Extra child elements of the `<Error>` XML body, such as `ServerTime`
+    /// for `RequestTimeTooSkewed`.
+    pub details: Option<Box<Vec<(String, String)>>>,
 }
 
 impl fmt::Display for S3Error {
@@ -356,6 +401,7 @@ impl S3Error {
             status_code,
             source: None,
             headers: None,
+            details: None,
         }
     }
 
@@ -370,6 +416,7 @@ impl S3Error {
             request_id: None,
             source: None,
             headers: None,
+            details: None,
         }
     }
 
@@ -403,6 +450,15 @@ impl S3Error {
         self
     }
 
+    /// Add an extra child element to the error's XML body.
+    #[must_use]
+    pub fn with_detail(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.details
+            .get_or_insert_with(|| Box::new(Vec::new()))
+            .push((name.into(), value.into()));
+        self
+    }
+
     /// Create a NoSuchBucket error.
     #[must_use]
     pub fn no_such_bucket(bucket_name: impl Into<String>) -> Self {
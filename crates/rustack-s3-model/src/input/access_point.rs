@@ -0,0 +1,43 @@
+//! S3 Control access point inputs.
+
+/// S3 Control CreateAccessPointInput.
+#[derive(Debug, Clone, Default)]
+pub struct CreateAccessPointInput {
+    /// HTTP header: `x-amz-account-id`.
+    pub account_id: String,
+    /// HTTP label (URI path).
+    pub name: String,
+    pub bucket: String,
+    pub bucket_account_id: Option<String>,
+}
+
+/// S3 Control DeleteAccessPointInput.
+#[derive(Debug, Clone, Default)]
+pub struct DeleteAccessPointInput {
+    /// HTTP header: `x-amz-account-id`.
+    pub account_id: String,
+    /// HTTP label (URI path).
+    pub name: String,
+}
+
+/// S3 Control GetAccessPointInput.
+#[derive(Debug, Clone, Default)]
+pub struct GetAccessPointInput {
+    /// HTTP header: `x-amz-account-id`.
+    pub account_id: String,
+    /// HTTP label (URI path).
+    pub name: String,
+}
+
+/// S3 Control ListAccessPointsInput.
+#[derive(Debug, Clone, Default)]
+pub struct ListAccessPointsInput {
+    /// HTTP header: `x-amz-account-id`.
+    pub account_id: String,
+    /// HTTP query: `bucket`.
+    pub bucket: Option<String>,
+    /// HTTP query: `maxResults`.
+    pub max_results: Option<i32>,
+    /// HTTP query: `nextToken`.
+    pub next_token: Option<String>,
+}
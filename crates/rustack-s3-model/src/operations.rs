@@ -145,6 +145,14 @@ pub enum S3Operation {
     ListObjectVersions,
     /// The PostObject (browser-based upload) operation.
     PostObject,
+    /// The CreateAccessPoint (S3 Control) operation.
+    CreateAccessPoint,
+    /// The GetAccessPoint (S3 Control) operation.
+    GetAccessPoint,
+    /// The DeleteAccessPoint (S3 Control) operation.
+    DeleteAccessPoint,
+    /// The ListAccessPoints (S3 Control) operation.
+    ListAccessPoints,
 }
 
 impl S3Operation {
@@ -223,6 +231,10 @@ impl S3Operation {
             Self::ListObjectsV2 => "ListObjectsV2",
             Self::ListObjectVersions => "ListObjectVersions",
             Self::PostObject => "PostObject",
+            Self::CreateAccessPoint => "CreateAccessPoint",
+            Self::GetAccessPoint => "GetAccessPoint",
+            Self::DeleteAccessPoint => "DeleteAccessPoint",
+            Self::ListAccessPoints => "ListAccessPoints",
         }
     }
 
@@ -301,6 +313,10 @@ impl S3Operation {
             "ListObjectsV2" => Some(Self::ListObjectsV2),
             "ListObjectVersions" => Some(Self::ListObjectVersions),
             "PostObject" => Some(Self::PostObject),
+            "CreateAccessPoint" => Some(Self::CreateAccessPoint),
+            "GetAccessPoint" => Some(Self::GetAccessPoint),
+            "DeleteAccessPoint" => Some(Self::DeleteAccessPoint),
+            "ListAccessPoints" => Some(Self::ListAccessPoints),
             _ => None,
         }
     }
@@ -0,0 +1,29 @@
+//! S3 Control access point outputs.
+
+use crate::types::AccessPoint;
+
+/// S3 Control CreateAccessPointOutput.
+#[derive(Debug, Clone, Default)]
+pub struct CreateAccessPointOutput {
+    pub access_point_arn: Option<String>,
+    pub alias: Option<String>,
+}
+
+/// S3 Control GetAccessPointOutput.
+#[derive(Debug, Clone, Default)]
+pub struct GetAccessPointOutput {
+    pub access_point_arn: Option<String>,
+    pub alias: Option<String>,
+    pub bucket: String,
+    pub bucket_account_id: Option<String>,
+    pub creation_date: Option<chrono::DateTime<chrono::Utc>>,
+    pub name: String,
+    pub network_origin: String,
+}
+
+/// S3 Control ListAccessPointsOutput.
+#[derive(Debug, Clone, Default)]
+pub struct ListAccessPointsOutput {
+    pub access_point_list: Vec<AccessPoint>,
+    pub next_token: Option<String>,
+}
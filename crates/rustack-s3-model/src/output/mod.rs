@@ -1,11 +1,13 @@
 //! Auto-generated from AWS S3 Smithy model. DO NOT EDIT.
 
+mod access_point;
 mod bucket;
 mod config;
 mod list;
 mod multipart;
 mod object;
 
+pub use access_point::*;
 pub use bucket::*;
 pub use config::*;
 pub use list::*;
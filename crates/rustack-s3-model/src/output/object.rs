@@ -241,6 +241,12 @@ pub struct PutObjectOutput {
     pub e_tag: Option<String>,
     /// HTTP header: `x-amz-expiration`.
     pub expiration: Option<String>,
+    /// HTTP header: `x-amz-object-lock-legal-hold`.
+    pub object_lock_legal_hold_status: Option<ObjectLockLegalHoldStatus>,
+    /// HTTP header: `x-amz-object-lock-mode`.
+    pub object_lock_mode: Option<ObjectLockMode>,
+    /// HTTP header: `x-amz-object-lock-retain-until-date`.
+    pub object_lock_retain_until_date: Option<chrono::DateTime<chrono::Utc>>,
     /// HTTP header: `x-amz-request-charged`.
     pub request_charged: Option<RequestCharged>,
     /// HTTP header: `x-amz-server-side-encryption-customer-algorithm`.
@@ -2029,6 +2029,17 @@ pub struct AccelerateConfiguration {
     pub status: Option<BucketAccelerateStatus>,
 }
 
+/// S3 Control AccessPoint.
+#[derive(Debug, Clone, Default)]
+pub struct AccessPoint {
+    pub access_point_arn: Option<String>,
+    pub alias: Option<String>,
+    pub bucket: String,
+    pub bucket_account_id: Option<String>,
+    pub name: String,
+    pub network_origin: String,
+}
+
 /// S3 AccessControlPolicy.
 #[derive(Debug, Clone, Default)]
 pub struct AccessControlPolicy {
@@ -2154,6 +2165,13 @@ pub struct CopyPartResult {
     pub last_modified: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+/// S3 Control CreateAccessPointRequest body.
+#[derive(Debug, Clone, Default)]
+pub struct CreateAccessPointConfiguration {
+    pub bucket: String,
+    pub bucket_account_id: Option<String>,
+}
+
 /// S3 CreateBucketConfiguration.
 #[derive(Debug, Clone, Default)]
 pub struct CreateBucketConfiguration {
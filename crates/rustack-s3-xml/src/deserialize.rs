@@ -4,7 +4,10 @@
 //! that need to be deserialized from XML request bodies. The deserialization follows the
 //! AWS S3 RestXml protocol conventions.
 
-use quick_xml::{Reader, events::Event};
+use quick_xml::{
+    Reader,
+    events::{BytesStart, Event},
+};
 
 use crate::error::XmlError;
 
@@ -149,20 +152,21 @@ use rustack_s3_model::types::{
     BucketAccelerateStatus, BucketInfo, BucketLifecycleConfiguration, BucketLocationConstraint,
     BucketLoggingStatus, BucketLogsPermission, BucketType, BucketVersioningStatus,
     CORSConfiguration, CORSRule, CompletedMultipartUpload, CompletedPart, Condition,
-    CreateBucketConfiguration, DataRedundancy, DefaultRetention, Delete, ErrorDocument,
-    Event as S3Event, EventBridgeConfiguration, ExpirationStatus, FilterRule, FilterRuleName,
-    Grant, Grantee, IndexDocument, LambdaFunctionConfiguration, LifecycleExpiration, LifecycleRule,
-    LifecycleRuleAndOperator, LifecycleRuleFilter, LocationInfo, LocationType, LoggingEnabled,
-    MFADelete, NoncurrentVersionExpiration, NoncurrentVersionTransition, NotificationConfiguration,
-    NotificationConfigurationFilter, ObjectIdentifier, ObjectLockConfiguration, ObjectLockEnabled,
-    ObjectLockLegalHold, ObjectLockLegalHoldStatus, ObjectLockRetention, ObjectLockRetentionMode,
-    ObjectLockRule, ObjectOwnership, Owner, OwnershipControls, OwnershipControlsRule,
-    PartitionedPrefix, Payer, Permission, Protocol, PublicAccessBlockConfiguration,
-    QueueConfiguration, Redirect, RedirectAllRequestsTo, RequestPaymentConfiguration, RoutingRule,
-    S3KeyFilter, ServerSideEncryption, ServerSideEncryptionByDefault,
-    ServerSideEncryptionConfiguration, ServerSideEncryptionRule, SimplePrefix, Tag, Tagging,
-    TargetGrant, TargetObjectKeyFormat, TopicConfiguration, Transition, TransitionStorageClass,
-    VersioningConfiguration, WebsiteConfiguration,
+    CreateAccessPointConfiguration, CreateBucketConfiguration, DataRedundancy, DefaultRetention,
+    Delete, ErrorDocument, Event as S3Event, EventBridgeConfiguration, ExpirationStatus,
+    FilterRule, FilterRuleName, Grant, Grantee, IndexDocument, LambdaFunctionConfiguration,
+    LifecycleExpiration, LifecycleRule, LifecycleRuleAndOperator, LifecycleRuleFilter,
+    LocationInfo, LocationType, LoggingEnabled, MFADelete, NoncurrentVersionExpiration,
+    NoncurrentVersionTransition, NotificationConfiguration, NotificationConfigurationFilter,
+    ObjectIdentifier, ObjectLockConfiguration, ObjectLockEnabled, ObjectLockLegalHold,
+    ObjectLockLegalHoldStatus, ObjectLockRetention, ObjectLockRetentionMode, ObjectLockRule,
+    ObjectOwnership, Owner, OwnershipControls, OwnershipControlsRule, PartitionedPrefix, Payer,
+    Permission, Protocol, PublicAccessBlockConfiguration, QueueConfiguration, Redirect,
+    RedirectAllRequestsTo, RequestPaymentConfiguration, RoutingRule, S3KeyFilter,
+    ServerSideEncryption, ServerSideEncryptionByDefault, ServerSideEncryptionConfiguration,
+    ServerSideEncryptionRule, SimplePrefix, Tag, Tagging, TargetGrant, TargetObjectKeyFormat,
+    TopicConfiguration, Transition, TransitionStorageClass, VersioningConfiguration,
+    WebsiteConfiguration,
 };
 
 impl S3Deserialize for Tag {
@@ -298,13 +302,13 @@ impl S3Deserialize for Grantee {
         let mut display_name = None;
         let mut email_address = None;
         let mut id = None;
-        let mut grantee_type = rustack_s3_model::types::Type::default();
+        let mut grantee_type = None;
         let mut uri = None;
 
-        // Note: the xsi:type attribute is on the <Grantee> element which has already
-        // been consumed. We'd need to parse it from the Start event, but for deserialization
-        // from client input, the type is typically inferred from which sub-fields are present.
-        // For robustness, we also accept the <Type> child element.
+        // The xsi:type attribute is on the <Grantee> start tag, which the
+        // caller has already consumed and applies via `deserialize_grantee`.
+        // A <Type> child element is accepted too; without either, the type is
+        // inferred from the identifying field present.
 
         loop {
             match reader.read_event()? {
@@ -319,7 +323,7 @@ impl S3Deserialize for Grantee {
                         "URI" => uri = Some(read_text_content(reader)?),
                         "Type" => {
                             let text = read_text_content(reader)?;
-                            grantee_type = rustack_s3_model::types::Type::from(text.as_str());
+                            grantee_type = Some(rustack_s3_model::types::Type::from(text.as_str()));
                         }
                         _ => skip_element(reader)?,
                     }
@@ -334,6 +338,13 @@ impl S3Deserialize for Grantee {
             }
         }
 
+        let grantee_type = grantee_type.unwrap_or(if uri.is_some() {
+            rustack_s3_model::types::Type::Group
+        } else if email_address.is_some() {
+            rustack_s3_model::types::Type::AmazonCustomerByEmail
+        } else {
+            rustack_s3_model::types::Type::CanonicalUser
+        });
         Ok(Grantee {
             display_name,
             email_address,
@@ -344,6 +355,28 @@ impl S3Deserialize for Grantee {
     }
 }
 
+/// Deserialize a `<Grantee>` element whose start tag is `start`, taking its
+/// type from the `xsi:type` attribute if present.
+fn deserialize_grantee(
+    start: &BytesStart<'_>,
+    reader: &mut Reader<&[u8]>,
+) -> Result<Grantee, XmlError> {
+    let mut xsi_type = None;
+    for attribute in start.attributes() {
+        let attribute = attribute?;
+        if attribute.key.local_name().as_ref() == b"type" {
+            let value = std::str::from_utf8(&attribute.value)
+                .map_err(|e| XmlError::ParseError(e.to_string()))?;
+            xsi_type = Some(rustack_s3_model::types::Type::from(value));
+        }
+    }
+    let mut grantee = Grantee::deserialize_xml(reader)?;
+    if let Some(xsi_type) = xsi_type {
+        grantee.r#type = xsi_type;
+    }
+    Ok(grantee)
+}
+
 impl S3Deserialize for Grant {
     fn deserialize_xml(reader: &mut Reader<&[u8]>) -> Result<Self, XmlError> {
         let mut grantee = None;
@@ -356,7 +389,7 @@ impl S3Deserialize for Grant {
                     let tag_name = std::str::from_utf8(name.as_ref())
                         .map_err(|e| XmlError::ParseError(e.to_string()))?;
                     match tag_name {
-                        "Grantee" => grantee = Some(Grantee::deserialize_xml(reader)?),
+                        "Grantee" => grantee = Some(deserialize_grantee(&e, reader)?),
                         "Permission" => {
                             let text = read_text_content(reader)?;
                             permission = Some(Permission::from(text.as_str()));
@@ -1240,7 +1273,7 @@ impl S3Deserialize for TargetGrant {
                     let tag_name = std::str::from_utf8(name.as_ref())
                         .map_err(|e| XmlError::ParseError(e.to_string()))?;
                     match tag_name {
-                        "Grantee" => grantee = Some(Grantee::deserialize_xml(reader)?),
+                        "Grantee" => grantee = Some(deserialize_grantee(&e, reader)?),
                         "Permission" => {
                             let text = read_text_content(reader)?;
                             permission = Some(BucketLogsPermission::from(text.as_str()));
@@ -2076,6 +2109,40 @@ impl S3Deserialize for VersioningConfiguration {
     }
 }
 
+impl S3Deserialize for CreateAccessPointConfiguration {
+    fn deserialize_xml(reader: &mut Reader<&[u8]>) -> Result<Self, XmlError> {
+        let mut bucket = None;
+        let mut bucket_account_id = None;
+
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) => {
+                    let name = e.name();
+                    let tag_name = std::str::from_utf8(name.as_ref())
+                        .map_err(|e| XmlError::ParseError(e.to_string()))?;
+                    match tag_name {
+                        "Bucket" => bucket = Some(read_text_content(reader)?),
+                        "BucketAccountId" => bucket_account_id = Some(read_text_content(reader)?),
+                        _ => skip_element(reader)?,
+                    }
+                }
+                Event::End(_) => break,
+                Event::Eof => {
+                    return Err(XmlError::UnexpectedElement(
+                        "unexpected EOF in CreateAccessPointRequest".to_string(),
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(CreateAccessPointConfiguration {
+            bucket: bucket.ok_or_else(|| XmlError::MissingElement("Bucket".to_string()))?,
+            bucket_account_id,
+        })
+    }
+}
+
 impl S3Deserialize for CreateBucketConfiguration {
     fn deserialize_xml(reader: &mut Reader<&[u8]>) -> Result<Self, XmlError> {
         let mut location_constraint = None;
@@ -2092,8 +2159,20 @@ impl S3Deserialize for CreateBucketConfiguration {
                     match tag_name {
                         "LocationConstraint" => {
                             let text = read_text_content(reader)?;
-                            location_constraint =
-                                Some(BucketLocationConstraint::from(text.as_str()));
+                            // An empty constraint means the default region. Any
+                            // value that is not a known constraint (including an
+                            // explicit `us-east-1`) is rejected rather than
+                            // falling back to the enum default.
+                            if !text.is_empty() {
+                                let constraint = BucketLocationConstraint::from(text.as_str());
+                                if constraint.as_str() != text {
+                                    return Err(XmlError::InvalidValue {
+                                        element: "LocationConstraint".to_owned(),
+                                        value: text,
+                                    });
+                                }
+                                location_constraint = Some(constraint);
+                            }
                         }
                         "Bucket" => {
                             bucket = Some(BucketInfo::deserialize_xml(reader)?);
@@ -2451,6 +2530,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_should_reject_unknown_location_constraint() {
+        let xml = br"<CreateBucketConfiguration>
+            <LocationConstraint>us-east-1</LocationConstraint>
+        </CreateBucketConfiguration>";
+        let err = from_xml::<CreateBucketConfiguration>(xml).expect_err("us-east-1 is not valid");
+        assert!(
+            matches!(err, XmlError::InvalidValue { ref element, .. } if element == "LocationConstraint")
+        );
+
+        let xml = br"<CreateBucketConfiguration><LocationConstraint></LocationConstraint></CreateBucketConfiguration>";
+        let config: CreateBucketConfiguration = from_xml(xml).expect("empty constraint");
+        assert_eq!(config.location_constraint, None);
+    }
+
     #[test]
     fn test_should_deserialize_access_control_policy() {
         let xml = br#"<?xml version="1.0" encoding="UTF-8"?>
@@ -2480,6 +2574,32 @@ mod tests {
         assert_eq!(acp.grants[0].permission, Some(Permission::FullControl));
     }
 
+    #[test]
+    fn test_should_deserialize_grantee_type_from_xsi_attribute() {
+        let xml = br#"<AccessControlPolicy>
+            <AccessControlList>
+                <Grant>
+                    <Grantee xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="Group">
+                        <URI>http://acs.amazonaws.com/groups/global/AllUsers</URI>
+                    </Grantee>
+                    <Permission>READ</Permission>
+                </Grant>
+                <Grant>
+                    <Grantee><ID>grantee-id</ID></Grantee>
+                    <Permission>WRITE_ACP</Permission>
+                </Grant>
+            </AccessControlList>
+        </AccessControlPolicy>"#;
+
+        let acp: AccessControlPolicy = from_xml(xml).expect("deserialization should succeed");
+        let types: Vec<_> = acp
+            .grants
+            .iter()
+            .map(|g| g.grantee.as_ref().map(|grantee| grantee.r#type.as_str()))
+            .collect();
+        assert_eq!(types, [Some("Group"), Some("CanonicalUser")]);
+    }
+
     #[test]
     fn test_should_deserialize_cors_configuration() {
         let xml = br#"<CORSConfiguration xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
@@ -36,6 +36,15 @@ pub enum XmlError {
     /// An error parsing a value from XML text content.
     #[error("failed to parse value: {0}")]
     ParseError(String),
+
+    /// An element held a value outside its allowed set.
+    #[error("invalid value for {element}: {value}")]
+    InvalidValue {
+        /// The element name.
+        element: String,
+        /// The rejected value.
+        value: String,
+    },
 }
 
 /// Format an S3 error as XML.
@@ -54,15 +63,32 @@ pub enum XmlError {
 ///   <RequestId>tx00000...</RequestId>
 /// </Error>
 /// ```
+#[must_use]
 pub fn error_to_xml(
     code: &str,
     message: &str,
     resource: Option<&str>,
     request_id: &str,
+) -> Vec<u8> {
+    error_to_xml_with_details(code, message, resource, &[], request_id)
+}
+
+/// Format an S3 error as XML with extra child elements.
+///
+/// `details` are written as `<Name>value</Name>` elements after `<Resource>`,
+/// for errors that carry more context, such as `RequestTime` and `ServerTime`
+/// on `RequestTimeTooSkewed`.
+#[must_use]
+pub fn error_to_xml_with_details(
+    code: &str,
+    message: &str,
+    resource: Option<&str>,
+    details: &[(String, String)],
+    request_id: &str,
 ) -> Vec<u8> {
     let mut buf = Vec::with_capacity(256);
     // Writing to Vec<u8> is infallible; if this fails it means a logic error.
-    if let Err(e) = write_error_xml(&mut buf, code, message, resource, request_id) {
+    if let Err(e) = write_error_xml(&mut buf, code, message, resource, details, request_id) {
         tracing::error!(error = %e, "failed to serialize S3 error XML");
         buf.clear();
     }
@@ -74,6 +100,7 @@ fn write_error_xml(
     code: &str,
     message: &str,
     resource: Option<&str>,
+    details: &[(String, String)],
     request_id: &str,
 ) -> io::Result<()> {
     let mut writer = Writer::new(buf);
@@ -89,6 +116,10 @@ fn write_error_xml(
             w.create_element("Resource")
                 .write_text_content(BytesText::new(res))?;
         }
+        for (name, value) in details {
+            w.create_element(name.as_str())
+                .write_text_content(BytesText::new(value))?;
+        }
         w.create_element("RequestId")
             .write_text_content(BytesText::new(request_id))?;
         Ok(())
@@ -118,6 +149,27 @@ mod tests {
         assert!(xml_str.contains("<RequestId>tx000001</RequestId>"));
     }
 
+    #[test]
+    fn test_should_format_error_with_details() {
+        let details = vec![
+            ("RequestTime".to_owned(), "20130524T000000Z".to_owned()),
+            ("ServerTime".to_owned(), "2013-05-24T01:00:00Z".to_owned()),
+        ];
+        let xml = error_to_xml_with_details(
+            "RequestTimeTooSkewed",
+            "The difference between the request time and the current time is too large.",
+            None,
+            &details,
+            "tx000004",
+        );
+        let xml_str = std::str::from_utf8(&xml).expect("valid UTF-8");
+
+        assert!(xml_str.contains(
+            "<RequestTime>20130524T000000Z</RequestTime><ServerTime>2013-05-24T01:00:00Z</\
+             ServerTime><RequestId>tx000004</RequestId>"
+        ));
+    }
+
     #[test]
     fn test_should_format_error_without_resource() {
         let xml = error_to_xml("InternalError", "Internal server error", None, "tx000002");
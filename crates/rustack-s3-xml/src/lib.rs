@@ -23,4 +23,6 @@ pub mod serialize;
 
 pub use deserialize::{S3Deserialize, from_xml};
 pub use error::{XmlError, error_to_xml};
-pub use serialize::{S3_NAMESPACE, S3Serialize, to_xml};
+pub use serialize::{
+    S3_CONTROL_NAMESPACE, S3_NAMESPACE, S3Serialize, to_xml, to_xml_with_namespace,
+};
@@ -21,6 +21,9 @@ use crate::error::XmlError;
 /// The S3 XML namespace.
 pub const S3_NAMESPACE: &str = "http://s3.amazonaws.com/doc/2006-03-01/";
 
+/// The S3 Control XML namespace, used by access point responses.
+pub const S3_CONTROL_NAMESPACE: &str = "http://awss3control.amazonaws.com/doc/2018-08-20/";
+
 /// Trait for serializing S3 types to XML.
 ///
 /// Implementors write their content as child elements inside the current XML context.
@@ -47,6 +50,19 @@ pub trait S3Serialize {
 ///
 /// Returns `XmlError` if serialization fails.
 pub fn to_xml<T: S3Serialize>(root_element: &str, value: &T) -> Result<Vec<u8>, XmlError> {
+    to_xml_with_namespace(root_element, S3_NAMESPACE, value)
+}
+
+/// Serialize a value like [`to_xml`], with `namespace` on the root element.
+///
+/// # Errors
+///
+/// Returns `XmlError` if serialization fails.
+pub fn to_xml_with_namespace<T: S3Serialize>(
+    root_element: &str,
+    namespace: &str,
+    value: &T,
+) -> Result<Vec<u8>, XmlError> {
     let mut buf = Vec::with_capacity(512);
     let mut writer = Writer::new(&mut buf);
 
@@ -58,7 +74,7 @@ pub fn to_xml<T: S3Serialize>(root_element: &str, value: &T) -> Result<Vec<u8>,
 
     writer
         .create_element(root_element)
-        .with_attribute(("xmlns", S3_NAMESPACE))
+        .with_attribute(("xmlns", namespace))
         .write_inner_content(|w| value.serialize_xml(w))?;
 
     Ok(buf)
@@ -212,22 +228,23 @@ impl_as_str!(
 
 use rustack_s3_model::{
     output::{
-        CompleteMultipartUploadOutput, CopyObjectOutput, CreateMultipartUploadOutput,
-        DeleteObjectsOutput, GetBucketAccelerateConfigurationOutput, GetBucketAclOutput,
-        GetBucketCorsOutput, GetBucketEncryptionOutput, GetBucketLifecycleConfigurationOutput,
-        GetBucketLoggingOutput, GetBucketNotificationConfigurationOutput,
-        GetBucketOwnershipControlsOutput, GetBucketPolicyStatusOutput,
-        GetBucketRequestPaymentOutput, GetBucketTaggingOutput, GetBucketVersioningOutput,
-        GetBucketWebsiteOutput, GetObjectAclOutput, GetObjectAttributesOutput,
-        GetObjectLegalHoldOutput, GetObjectLockConfigurationOutput, GetObjectRetentionOutput,
-        GetObjectTaggingOutput, GetPublicAccessBlockOutput, ListBucketsOutput,
-        ListMultipartUploadsOutput, ListObjectVersionsOutput, ListObjectsOutput,
-        ListObjectsV2Output, ListPartsOutput, UploadPartCopyOutput,
+        CompleteMultipartUploadOutput, CopyObjectOutput, CreateAccessPointOutput,
+        CreateMultipartUploadOutput, DeleteObjectsOutput, GetAccessPointOutput,
+        GetBucketAccelerateConfigurationOutput, GetBucketAclOutput, GetBucketCorsOutput,
+        GetBucketEncryptionOutput, GetBucketLifecycleConfigurationOutput, GetBucketLoggingOutput,
+        GetBucketNotificationConfigurationOutput, GetBucketOwnershipControlsOutput,
+        GetBucketPolicyStatusOutput, GetBucketRequestPaymentOutput, GetBucketTaggingOutput,
+        GetBucketVersioningOutput, GetBucketWebsiteOutput, GetObjectAclOutput,
+        GetObjectAttributesOutput, GetObjectLegalHoldOutput, GetObjectLockConfigurationOutput,
+        GetObjectRetentionOutput, GetObjectTaggingOutput, GetPublicAccessBlockOutput,
+        ListAccessPointsOutput, ListBucketsOutput, ListMultipartUploadsOutput,
+        ListObjectVersionsOutput, ListObjectsOutput, ListObjectsV2Output, ListPartsOutput,
+        UploadPartCopyOutput,
     },
     types::{
-        AbortIncompleteMultipartUpload, AccelerateConfiguration, AccessControlPolicy, Bucket,
-        BucketInfo, BucketLifecycleConfiguration, BucketLoggingStatus, CORSConfiguration, CORSRule,
-        Checksum, CommonPrefix, CompletedMultipartUpload, CompletedPart, Condition,
+        AbortIncompleteMultipartUpload, AccelerateConfiguration, AccessControlPolicy, AccessPoint,
+        Bucket, BucketInfo, BucketLifecycleConfiguration, BucketLoggingStatus, CORSConfiguration,
+        CORSRule, Checksum, CommonPrefix, CompletedMultipartUpload, CompletedPart, Condition,
         CopyObjectResult, CopyPartResult, CreateBucketConfiguration, DefaultRetention, Delete,
         DeleteMarkerEntry, DeletedObject, Error, ErrorDocument, EventBridgeConfiguration,
         FilterRule, GetObjectAttributesParts, Grant, Grantee, IndexDocument, Initiator,
@@ -1070,6 +1087,8 @@ impl S3Serialize for Bucket {
         writer.create_element("Bucket").write_inner_content(|w| {
             write_optional_text(w, "Name", self.name.as_deref())?;
             write_optional_timestamp(w, "CreationDate", self.creation_date.as_ref())?;
+            write_optional_text(w, "BucketRegion", self.bucket_region.as_deref())?;
+            write_optional_text(w, "BucketArn", self.bucket_arn.as_deref())?;
             Ok(())
         })?;
         Ok(())
@@ -1773,6 +1792,63 @@ impl S3Serialize for CopyPartResult {
     }
 }
 
+// ---------------------------------------------------------------------------
+// S3 Control access points
+// ---------------------------------------------------------------------------
+
+impl S3Serialize for AccessPoint {
+    fn serialize_xml<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<()> {
+        writer
+            .create_element("AccessPoint")
+            .write_inner_content(|w| {
+                write_text_element(w, "Name", &self.name)?;
+                write_text_element(w, "NetworkOrigin", &self.network_origin)?;
+                write_text_element(w, "Bucket", &self.bucket)?;
+                write_optional_text(w, "AccessPointArn", self.access_point_arn.as_deref())?;
+                write_optional_text(w, "Alias", self.alias.as_deref())?;
+                write_optional_text(w, "BucketAccountId", self.bucket_account_id.as_deref())?;
+                Ok(())
+            })?;
+        Ok(())
+    }
+}
+
+impl S3Serialize for CreateAccessPointOutput {
+    fn serialize_xml<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<()> {
+        write_optional_text(writer, "AccessPointArn", self.access_point_arn.as_deref())?;
+        write_optional_text(writer, "Alias", self.alias.as_deref())?;
+        Ok(())
+    }
+}
+
+impl S3Serialize for GetAccessPointOutput {
+    fn serialize_xml<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<()> {
+        write_text_element(writer, "Name", &self.name)?;
+        write_text_element(writer, "Bucket", &self.bucket)?;
+        write_text_element(writer, "NetworkOrigin", &self.network_origin)?;
+        write_optional_timestamp(writer, "CreationDate", self.creation_date.as_ref())?;
+        write_optional_text(writer, "Alias", self.alias.as_deref())?;
+        write_optional_text(writer, "AccessPointArn", self.access_point_arn.as_deref())?;
+        write_optional_text(writer, "BucketAccountId", self.bucket_account_id.as_deref())?;
+        Ok(())
+    }
+}
+
+impl S3Serialize for ListAccessPointsOutput {
+    fn serialize_xml<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<()> {
+        writer
+            .create_element("AccessPointList")
+            .write_inner_content(|w| {
+                for access_point in &self.access_point_list {
+                    access_point.serialize_xml(w)?;
+                }
+                Ok(())
+            })?;
+        write_optional_text(writer, "NextToken", self.next_token.as_deref())?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1801,6 +1877,35 @@ mod tests {
         assert!(xml_str.contains("</Tagging>"));
     }
 
+    #[test]
+    fn test_should_serialize_access_points_in_the_control_namespace() {
+        let output = ListAccessPointsOutput {
+            access_point_list: vec![AccessPoint {
+                name: "my-ap".to_owned(),
+                network_origin: "Internet".to_owned(),
+                bucket: "data".to_owned(),
+                access_point_arn: Some(
+                    "arn:aws:s3:us-east-1:123456789012:accesspoint/my-ap".to_owned(),
+                ),
+                ..AccessPoint::default()
+            }],
+            next_token: None,
+        };
+
+        let xml = to_xml_with_namespace("ListAccessPointsResult", S3_CONTROL_NAMESPACE, &output)
+            .expect("serialization should succeed");
+        let xml_str = std::str::from_utf8(&xml).expect("valid UTF-8");
+
+        assert!(xml_str.contains(
+            "<ListAccessPointsResult xmlns=\"http://awss3control.amazonaws.com/doc/2018-08-20/\">"
+        ));
+        assert!(xml_str.contains(
+            "<AccessPointList><AccessPoint><Name>my-ap</Name><NetworkOrigin>Internet</\
+             NetworkOrigin><Bucket>data</Bucket>"
+        ));
+        assert!(!xml_str.contains("NextToken"));
+    }
+
     #[test]
     fn test_should_serialize_versioning_configuration() {
         let vc = VersioningConfiguration {
@@ -26,7 +26,7 @@ publish_crate() {
 }
 
 # Layer 1: Model crates (no internal deps, or deps already published)
-# dynamodbstreams-model depends on dynamodb-model (already published)
+# dynamodbstreams-model and dynamodb-expression depend on dynamodb-model (already published)
 MODEL_CRATES=(
     rustack-events-model
     rustack-iam-model
@@ -42,6 +42,7 @@ MODEL_CRATES=(
     rustack-ssm-model
     rustack-sts-model
     rustack-dynamodbstreams-model
+    rustack-dynamodb-expression
 )
 
 # Layer 2: S3 XML (depends on s3-model)
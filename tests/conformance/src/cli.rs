@@ -0,0 +1,179 @@
+//! Command-line parsing for `rustack-conformance`.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+/// Binary name reported in `--help` / `--version` output.
+pub const BIN_NAME: &str = "rustack-conformance";
+
+/// What the command line asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Print help text and exit.
+    Help,
+    /// Print the version and exit.
+    Version,
+    /// Print the scenario names and exit.
+    List,
+    /// Run scenarios against Rustack and compare them with the goldens.
+    Check,
+    /// Run scenarios against AWS and rewrite their goldens.
+    Record,
+}
+
+/// A parsed command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    /// What to do.
+    pub mode: Mode,
+    /// The `--endpoint-url` flag, if given.
+    pub endpoint: Option<String>,
+    /// The `--region` flag, if given.
+    pub region: Option<String>,
+    /// The `--goldens` flag, if given.
+    pub goldens: Option<PathBuf>,
+    /// Only run scenarios whose name contains this.
+    pub filter: Option<String>,
+    /// The `--allow-missing` flag: skip scenarios without a golden file
+    /// instead of failing them.
+    pub allow_missing: bool,
+}
+
+/// Parse a command line, including argv\[0\].
+///
+/// # Errors
+///
+/// Returns an error describing an unknown flag, a flag missing its value,
+/// or a flag that does not apply to the mode.
+pub fn parse_args<I, S>(args: I) -> Result<Invocation>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut invocation = Invocation {
+        mode: Mode::Check,
+        endpoint: None,
+        region: None,
+        goldens: None,
+        filter: None,
+        allow_missing: false,
+    };
+    let mut iter = args.into_iter().skip(1).map(|arg| arg.as_ref().to_owned());
+    while let Some(arg) = iter.next() {
+        let mut value = |flag: &str| {
+            iter.next()
+                .ok_or_else(|| anyhow::anyhow!("flag '{flag}' requires a value"))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => {
+                return Ok(Invocation {
+                    mode: Mode::Help,
+                    ..invocation
+                });
+            }
+            "-V" | "--version" => {
+                return Ok(Invocation {
+                    mode: Mode::Version,
+                    ..invocation
+                });
+            }
+            "--list" => invocation.mode = Mode::List,
+            "--record" => invocation.mode = Mode::Record,
+            "--allow-missing" => invocation.allow_missing = true,
+            "--endpoint-url" => invocation.endpoint = Some(value("--endpoint-url")?),
+            "--region" => invocation.region = Some(value("--region")?),
+            "--goldens" => invocation.goldens = Some(PathBuf::from(value("--goldens")?)),
+            other if other.starts_with('-') && other.len() > 1 => {
+                anyhow::bail!("unrecognised flag '{other}'");
+            }
+            _ if invocation.filter.is_some() => anyhow::bail!("unexpected argument '{arg}'"),
+            _ => invocation.filter = Some(arg),
+        }
+    }
+    if invocation.mode == Mode::Record && invocation.endpoint.is_some() {
+        anyhow::bail!("'--record' always targets AWS; '--endpoint-url' only applies to checks");
+    }
+    if invocation.mode == Mode::Record && invocation.allow_missing {
+        anyhow::bail!("'--allow-missing' only applies to checks");
+    }
+    if invocation.mode == Mode::Check && invocation.region.is_some() {
+        anyhow::bail!("'--region' only applies to '--record'");
+    }
+    Ok(invocation)
+}
+
+/// Render the `--help` text.
+#[must_use]
+pub fn help_text() -> String {
+    format!(
+        "{BIN_NAME} {version}\n\
+         Compare Rustack's responses with golden files recorded from AWS.\n\
+         \n\
+         USAGE:\n    \
+         {BIN_NAME} [--endpoint-url <URL>] [--allow-missing] [FILTER]  Check scenarios against Rustack\n    \
+         {BIN_NAME} --record [--region <REGION>] [FILTER]              Record goldens from AWS\n    \
+         {BIN_NAME} --list                                              List the scenarios\n\
+         \n\
+         FILTER runs only the scenarios whose name contains it.\n\
+         \n\
+         FLAGS:\n    \
+         --endpoint-url <URL>  Gateway endpoint (default: RUSTACK_ENDPOINT, AWS_ENDPOINT_URL, or http://localhost:4566)\n    \
+         --record              Run against AWS with the AWS_ACCESS_KEY_ID / AWS_SECRET_ACCESS_KEY /\n                          \
+         AWS_SESSION_TOKEN credentials and rewrite the goldens; creates and deletes real resources\n    \
+         --allow-missing       Skip scenarios without a golden file instead of failing them\n    \
+         --region <REGION>     Region to record in (default: AWS_REGION or us-east-1)\n    \
+         --goldens <DIR>       Golden file directory (default: the crate's goldens/)\n    \
+         --list                Print the scenario names and exit\n    \
+         -h, --help            Print this help message and exit\n    \
+         -V, --version         Print version information and exit\n",
+        version = env!("CARGO_PKG_VERSION"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Invocation> {
+        parse_args(std::iter::once(BIN_NAME).chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn test_should_parse_modes_and_flags() {
+        let check = parse(&["--endpoint-url", "http://rustack:4566", "versioning"]).unwrap();
+        assert_eq!(
+            check,
+            Invocation {
+                mode: Mode::Check,
+                endpoint: Some("http://rustack:4566".to_owned()),
+                region: None,
+                goldens: None,
+                filter: Some("versioning".to_owned()),
+                allow_missing: false,
+            }
+        );
+        assert!(parse(&["--allow-missing"]).unwrap().allow_missing);
+
+        let record = parse(&["--record", "--region", "eu-west-1", "--goldens", "g"]).unwrap();
+        assert_eq!(record.mode, Mode::Record);
+        assert_eq!(record.region.as_deref(), Some("eu-west-1"));
+        assert_eq!(record.goldens, Some(PathBuf::from("g")));
+
+        assert_eq!(parse(&["--list"]).unwrap().mode, Mode::List);
+        assert_eq!(
+            parse(&["--bogus", "--help"]).unwrap_err().to_string(),
+            "unrecognised flag '--bogus'"
+        );
+        assert_eq!(parse(&["--help", "--bogus"]).unwrap().mode, Mode::Help);
+    }
+
+    #[test]
+    fn test_should_reject_flags_of_other_modes() {
+        assert!(parse(&["--record", "--endpoint-url", "http://x"]).is_err());
+        assert!(parse(&["--record", "--allow-missing"]).is_err());
+        assert!(parse(&["--region", "us-east-1"]).is_err());
+        assert!(parse(&["--region"]).is_err());
+        assert!(parse(&["a", "b"]).is_err());
+    }
+}
@@ -0,0 +1,230 @@
+//! Golden files.
+//!
+//! A golden file holds the normalized exchanges of one scenario as recorded
+//! from AWS, at `<dir>/<service>/<scenario>.json`:
+//!
+//! ```json
+//! {
+//!   "scenario": "s3_get_missing_key",
+//!   "region": "us-east-1",
+//!   "exchanges": [
+//!     {
+//!       "request": "GET /{bucket}/missing",
+//!       "status": 404,
+//!       "headers": { "content-type": "application/xml" },
+//!       "body": "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Error>...</Error>"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Only the [checked](crate::scenario::Step::checked) steps appear, in order.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::scenario::{Scenario, Service};
+
+/// One normalized request and response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Exchange {
+    /// The step's method, path, and query, before substitution.
+    pub request: String,
+    /// Response status code.
+    pub status: u16,
+    /// The compared response headers.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// The normalized response body.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub body: String,
+}
+
+/// The recorded exchanges of one scenario.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Golden {
+    /// The scenario's name.
+    pub scenario: String,
+    /// The AWS region the exchanges were recorded in.
+    pub region: String,
+    /// The checked exchanges, in order.
+    pub exchanges: Vec<Exchange>,
+}
+
+/// The golden file of `scenario` under `dir`.
+#[must_use]
+pub fn path(dir: &Path, scenario: &Scenario) -> PathBuf {
+    let service = match scenario.service {
+        Service::S3 => "s3",
+        Service::DynamoDb => "dynamodb",
+    };
+    dir.join(service).join(format!("{}.json", scenario.name))
+}
+
+/// Load the golden file of `scenario`, if one was recorded.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be read or parsed.
+pub async fn load(dir: &Path, scenario: &Scenario) -> Result<Option<Golden>> {
+    let path = path(dir, scenario);
+    let text = match tokio::fs::read_to_string(&path).await {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    };
+    let golden = serde_json::from_str(&text)
+        .with_context(|| format!("invalid golden file {}", path.display()))?;
+    Ok(Some(golden))
+}
+
+/// Write the golden file of `scenario`, replacing any previous one.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written.
+pub async fn save(dir: &Path, scenario: &Scenario, golden: &Golden) -> Result<()> {
+    let path = path(dir, scenario);
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let mut text = serde_json::to_string_pretty(golden)?;
+    text.push('\n');
+    tokio::fs::write(&path, text)
+        .await
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Compare `actual` exchanges against the `expected` golden ones.
+///
+/// # Errors
+///
+/// Returns a description of every differing exchange.
+pub fn compare(expected: &[Exchange], actual: &[Exchange]) -> Result<(), String> {
+    let mut report = String::new();
+    if expected.len() != actual.len() {
+        let _ = writeln!(
+            report,
+            "expected {} exchanges, got {}; re-record the golden file if the scenario changed",
+            expected.len(),
+            actual.len()
+        );
+    }
+    for (i, (want, got)) in expected.iter().zip(actual).enumerate() {
+        let mut diffs = Vec::new();
+        if want.request != got.request {
+            diffs.push(format!(
+                "request: golden has '{}'; re-record the golden file if the scenario changed",
+                want.request
+            ));
+        }
+        if want.status != got.status {
+            diffs.push(format!(
+                "status: expected {}, got {}",
+                want.status, got.status
+            ));
+        }
+        let names: std::collections::BTreeSet<&String> =
+            want.headers.keys().chain(got.headers.keys()).collect();
+        for name in names {
+            let (w, g) = (want.headers.get(name), got.headers.get(name));
+            if w != g {
+                diffs.push(format!("header {name}: expected {w:?}, got {g:?}"));
+            }
+        }
+        if want.body != got.body {
+            diffs.push(body_diff(&want.body, &got.body));
+        }
+        if !diffs.is_empty() {
+            let _ = writeln!(report, "exchange {} ({}):", i + 1, got.request);
+            for diff in diffs {
+                let _ = writeln!(report, "  {diff}");
+            }
+        }
+    }
+    if report.is_empty() {
+        Ok(())
+    } else {
+        Err(report)
+    }
+}
+
+/// Describe where two bodies first differ, with some context.
+fn body_diff(expected: &str, actual: &str) -> String {
+    const CONTEXT: usize = 40;
+    let at = expected
+        .char_indices()
+        .zip(actual.chars())
+        .find(|((_, w), g)| w != g)
+        .map_or_else(|| expected.len().min(actual.len()), |((i, _), _)| i);
+    let excerpt = |body: &str| {
+        let start = body.floor_char_boundary(at.saturating_sub(CONTEXT));
+        let end = body.ceil_char_boundary((at + CONTEXT).min(body.len()));
+        body[start..end].to_owned()
+    };
+    format!(
+        "body differs at byte {at}:\n    expected: {:?}\n    got:      {:?}",
+        excerpt(expected),
+        excerpt(actual)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(status: u16, body: &str) -> Exchange {
+        Exchange {
+            request: "GET /{bucket}/k".to_owned(),
+            status,
+            headers: BTreeMap::from([("content-type".to_owned(), "application/xml".to_owned())]),
+            body: body.to_owned(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_should_round_trip_golden_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let scenario = Scenario::s3("s3_example");
+        assert_eq!(load(dir.path(), &scenario).await.unwrap(), None);
+
+        let golden = Golden {
+            scenario: "s3_example".to_owned(),
+            region: "us-east-1".to_owned(),
+            exchanges: vec![exchange(200, "<a/>")],
+        };
+        save(dir.path(), &scenario, &golden).await.unwrap();
+        assert!(dir.path().join("s3/s3_example.json").exists());
+        assert_eq!(load(dir.path(), &scenario).await.unwrap(), Some(golden));
+    }
+
+    #[test]
+    fn test_should_report_differences() {
+        let expected = [exchange(404, "<Code>NoSuchKey</Code>")];
+        assert_eq!(compare(&expected, &expected), Ok(()));
+
+        let mut actual = exchange(404, "<Code>NoSuchBucket</Code>");
+        actual
+            .headers
+            .insert("etag".to_owned(), "<etag>".to_owned());
+        let report = compare(&expected, &[actual]).unwrap_err();
+        assert!(report.contains("header etag: expected None"), "{report}");
+        assert!(report.contains("body differs at byte 12"), "{report}");
+
+        let report = compare(&expected, &[exchange(200, "<Code>NoSuchKey</Code>")]).unwrap_err();
+        assert!(report.contains("status: expected 404, got 200"), "{report}");
+        assert!(
+            compare(&expected, &[])
+                .unwrap_err()
+                .contains("expected 1 exchanges, got 0")
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! Conformance checks of Rustack against responses recorded from AWS.
+//!
+//! Each [scenario](scenario::Scenario) in the [catalog](scenarios::catalog)
+//! is a sequence of raw, SigV4-signed HTTP requests. Its responses are
+//! [normalized](normalize) — request ids, dates, ETags, and generated ids
+//! replaced by shape-checked tokens — and compared byte for byte against
+//! the scenario's [golden file](golden), so differences SDKs would paper
+//! over, such as element order or a missing header, are caught.
+//!
+//! Goldens are recorded from AWS itself into the crate's `goldens/`
+//! directory. None are committed yet, so they must be recorded before
+//! anything is checked:
+//!
+//! ```text
+//! # Record (creates and deletes real buckets and tables in the account):
+//! AWS_ACCESS_KEY_ID=... AWS_SECRET_ACCESS_KEY=... \
+//!     cargo run -p rustack-conformance -- --record
+//!
+//! # Check a running server:
+//! cargo run -p rustack-conformance
+//! cargo test -p rustack-conformance -- --ignored
+//! ```
+//!
+//! A scenario without a golden file fails the binary's check, unless
+//! `--allow-missing` is given to skip it, as `make conformance` does. The
+//! ignored test skips such scenarios too.
+//! The endpoint checked comes from `--endpoint-url`, `RUSTACK_ENDPOINT`, or
+//! `AWS_ENDPOINT_URL`, in that order, and defaults to
+//! `http://localhost:4566`.
+
+pub mod cli;
+pub mod golden;
+pub mod normalize;
+pub mod runner;
+pub mod scenario;
+pub mod scenarios;
+pub mod sign;
+
+use std::path::{Path, PathBuf};
+
+use crate::{golden::Golden, runner::Runner, scenario::Scenario};
+
+/// The outcome of one scenario.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The responses match the golden file.
+    Matched,
+    /// The responses differ from the golden file, as described.
+    Mismatched(String),
+    /// No golden file was recorded for the scenario.
+    NoGolden,
+    /// The golden file was (re)written.
+    Recorded,
+    /// The scenario could not be run, as described.
+    Failed(String),
+}
+
+/// The golden files shipped with this crate.
+#[must_use]
+pub fn default_goldens_dir() -> PathBuf {
+    PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/goldens"))
+}
+
+/// The Rustack endpoint from `RUSTACK_ENDPOINT` or `AWS_ENDPOINT_URL`,
+/// defaulting to `http://localhost:4566`.
+#[must_use]
+pub fn endpoint_from_env() -> String {
+    ["RUSTACK_ENDPOINT", "AWS_ENDPOINT_URL"]
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+        .unwrap_or_else(|| "http://localhost:4566".to_owned())
+}
+
+/// Run `scenario` and compare it with its golden file under `goldens`.
+pub async fn check(runner: &Runner, goldens: &Path, scenario: &Scenario) -> Outcome {
+    let golden = match golden::load(goldens, scenario).await {
+        Ok(Some(golden)) => golden,
+        Ok(None) => return Outcome::NoGolden,
+        Err(e) => return Outcome::Failed(format!("{e:#}")),
+    };
+    match runner.run(scenario).await {
+        Ok(exchanges) => match golden::compare(&golden.exchanges, &exchanges) {
+            Ok(()) => Outcome::Matched,
+            Err(report) => Outcome::Mismatched(report),
+        },
+        Err(e) => Outcome::Failed(format!("{e:#}")),
+    }
+}
+
+/// Run `scenario` and write its responses as its golden file under
+/// `goldens`.
+pub async fn record(runner: &Runner, goldens: &Path, scenario: &Scenario) -> Outcome {
+    let exchanges = match runner.run(scenario).await {
+        Ok(exchanges) => exchanges,
+        Err(e) => return Outcome::Failed(format!("{e:#}")),
+    };
+    let golden = Golden {
+        scenario: scenario.name.to_owned(),
+        region: runner.target().region.clone(),
+        exchanges,
+    };
+    match golden::save(goldens, scenario, &golden).await {
+        Ok(()) => Outcome::Recorded,
+        Err(e) => Outcome::Failed(format!("{e:#}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::Target;
+
+    #[tokio::test]
+    #[ignore = "requires running server"]
+    async fn test_should_match_aws_goldens() {
+        let runner = Runner::new(Target::gateway(endpoint_from_env())).unwrap();
+        let goldens = default_goldens_dir();
+
+        let mut failures = Vec::new();
+        for scenario in scenarios::catalog() {
+            match check(&runner, &goldens, &scenario).await {
+                Outcome::Mismatched(report) | Outcome::Failed(report) => {
+                    failures.push(format!("{}:\n{report}", scenario.name));
+                }
+                // Goldens are recorded from AWS, so a checkout may lack them.
+                Outcome::Matched | Outcome::NoGolden | Outcome::Recorded => {}
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
@@ -0,0 +1,111 @@
+//! `rustack-conformance`: compare Rustack with goldens recorded from AWS.
+//!
+//! See the `rustack_conformance` library docs.
+
+use rustack_conformance::{
+    Outcome,
+    cli::{self, BIN_NAME, Mode},
+    runner::{Runner, Target},
+    scenarios,
+    sign::Credentials,
+};
+
+#[tokio::main]
+async fn main() {
+    let invocation = match cli::parse_args(std::env::args()) {
+        Ok(invocation) => invocation,
+        Err(e) => {
+            eprintln!("error: {e}\n");
+            eprint!("{}", cli::help_text());
+            std::process::exit(2);
+        }
+    };
+    let scenarios: Vec<_> = scenarios::catalog()
+        .into_iter()
+        .filter(|s| {
+            invocation
+                .filter
+                .as_deref()
+                .is_none_or(|f| s.name.contains(f))
+        })
+        .collect();
+
+    let target = match invocation.mode {
+        Mode::Help => return print!("{}", cli::help_text()),
+        Mode::Version => return println!("{BIN_NAME} {}", env!("CARGO_PKG_VERSION")),
+        Mode::List => {
+            for scenario in &scenarios {
+                println!("{}", scenario.name);
+            }
+            return;
+        }
+        Mode::Check => Target::gateway(
+            invocation
+                .endpoint
+                .unwrap_or_else(rustack_conformance::endpoint_from_env),
+        ),
+        Mode::Record => {
+            let credentials = match Credentials::from_env() {
+                Ok(credentials) => credentials,
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    std::process::exit(2);
+                }
+            };
+            let region = invocation
+                .region
+                .or_else(|| std::env::var("AWS_REGION").ok())
+                .unwrap_or_else(|| "us-east-1".to_owned());
+            Target::aws(region, credentials)
+        }
+    };
+    let runner = match Runner::new(target) {
+        Ok(runner) => runner,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            std::process::exit(1);
+        }
+    };
+    let goldens = invocation
+        .goldens
+        .unwrap_or_else(rustack_conformance::default_goldens_dir);
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for scenario in &scenarios {
+        let outcome = if invocation.mode == Mode::Record {
+            rustack_conformance::record(&runner, &goldens, scenario).await
+        } else {
+            rustack_conformance::check(&runner, &goldens, scenario).await
+        };
+        match outcome {
+            Outcome::Matched => {
+                passed += 1;
+                println!("ok        {}", scenario.name);
+            }
+            Outcome::Recorded => {
+                passed += 1;
+                println!("recorded  {}", scenario.name);
+            }
+            Outcome::NoGolden if invocation.allow_missing => {
+                missing += 1;
+                println!("no golden {}", scenario.name);
+            }
+            Outcome::NoGolden => {
+                failed += 1;
+                println!("NO GOLDEN {}: record it with --record", scenario.name);
+            }
+            Outcome::Mismatched(report) => {
+                failed += 1;
+                println!("MISMATCH  {}\n{report}", scenario.name);
+            }
+            Outcome::Failed(error) => {
+                failed += 1;
+                println!("FAILED    {}: {error}", scenario.name);
+            }
+        }
+    }
+    println!("\n{passed} passed, {failed} failed, {missing} skipped without golden");
+    if failed > 0 {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,359 @@
+//! Response normalization.
+//!
+//! Responses are compared byte for byte, so values that legitimately differ
+//! between runs or between AWS and Rustack — request ids, dates, ETags,
+//! generated ids — are replaced before comparison. Each volatile field has a
+//! [`Matcher`] that checks the value's shape and, only if it fits, replaces
+//! it with the matcher's token, such as `<http-date>`. A value of the wrong
+//! shape is kept, so the comparison still reports it.
+//!
+//! Values of scenario variables, such as the run's bucket name or a
+//! captured version id, are first replaced by their `{name}` placeholder.
+//!
+//! Only the headers listed by the [`Normalizer`] are compared. XML and text
+//! bodies are compared as they are; JSON bodies are re-serialized with
+//! sorted keys, since member order carries no meaning in JSON.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::scenario::Service;
+
+/// The expected shape of a volatile value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Matcher {
+    /// Any value.
+    Any,
+    /// An HTTP date, such as `Wed, 21 Oct 2015 07:28:00 GMT`.
+    HttpDate,
+    /// An ISO 8601 timestamp, such as `2015-10-21T07:28:00.000Z`.
+    Timestamp,
+    /// Seconds since the epoch, such as `1445412480.123`.
+    EpochSeconds,
+    /// A quoted MD5 ETag, possibly with a multipart `-N` suffix.
+    ETag,
+    /// An opaque id: non-empty, without whitespace or markup.
+    Identifier,
+}
+
+impl Matcher {
+    /// The placeholder a matching value is replaced with.
+    #[must_use]
+    pub fn token(self) -> &'static str {
+        match self {
+            Self::Any => "<any>",
+            Self::HttpDate => "<http-date>",
+            Self::Timestamp => "<timestamp>",
+            Self::EpochSeconds => "<epoch-seconds>",
+            Self::ETag => "<etag>",
+            Self::Identifier => "<id>",
+        }
+    }
+
+    /// Whether `value` has the expected shape.
+    #[must_use]
+    pub fn matches(self, value: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::HttpDate => chrono::DateTime::parse_from_rfc2822(value).is_ok(),
+            Self::Timestamp => chrono::DateTime::parse_from_rfc3339(value).is_ok(),
+            Self::EpochSeconds => value.parse::<f64>().is_ok_and(|secs| secs > 1e9),
+            Self::ETag => {
+                let unquoted = value
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .or_else(|| {
+                        value
+                            .strip_prefix("&quot;")
+                            .and_then(|v| v.strip_suffix("&quot;"))
+                    });
+                unquoted.is_some_and(|etag| {
+                    let (hash, parts) = etag.split_once('-').unwrap_or((etag, "1"));
+                    hash.len() == 32
+                        && hash.bytes().all(|b| b.is_ascii_hexdigit())
+                        && parts.parse::<u32>().is_ok()
+                })
+            }
+            Self::Identifier => {
+                !value.is_empty()
+                    && value
+                        .bytes()
+                        .all(|b| b.is_ascii_alphanumeric() || b"._-+/=:".contains(&b))
+            }
+        }
+    }
+}
+
+/// A volatile field and the shape its values must have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldRule {
+    /// Lowercase header name, or XML element / JSON member name.
+    pub field: &'static str,
+    /// The expected shape.
+    pub matcher: Matcher,
+}
+
+const fn rule(field: &'static str, matcher: Matcher) -> FieldRule {
+    FieldRule { field, matcher }
+}
+
+/// A response reduced to what is compared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Normalized {
+    /// The compared headers, by lowercase name.
+    pub headers: BTreeMap<String, String>,
+    /// The normalized body.
+    pub body: String,
+}
+
+/// How the responses of one service are normalized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Normalizer {
+    /// Lowercase names of the compared headers.
+    pub headers: &'static [&'static str],
+    /// Volatile headers.
+    pub header_rules: Vec<FieldRule>,
+    /// Volatile XML elements or JSON members.
+    pub body_rules: Vec<FieldRule>,
+}
+
+impl Normalizer {
+    /// The normalizer for `service`'s responses.
+    #[must_use]
+    pub fn for_service(service: Service) -> Self {
+        match service {
+            Service::S3 => Self {
+                headers: &[
+                    "content-range",
+                    "content-type",
+                    "etag",
+                    "last-modified",
+                    "location",
+                    "x-amz-delete-marker",
+                    "x-amz-server-side-encryption",
+                    "x-amz-version-id",
+                ],
+                header_rules: vec![
+                    rule("etag", Matcher::ETag),
+                    rule("last-modified", Matcher::HttpDate),
+                    rule("x-amz-version-id", Matcher::Identifier),
+                ],
+                body_rules: vec![
+                    rule("RequestId", Matcher::Any),
+                    rule("HostId", Matcher::Any),
+                    rule("ID", Matcher::Any),
+                    rule("DisplayName", Matcher::Any),
+                    rule("CreationDate", Matcher::Timestamp),
+                    rule("LastModified", Matcher::Timestamp),
+                    rule("Initiated", Matcher::Timestamp),
+                    rule("ETag", Matcher::ETag),
+                    rule("UploadId", Matcher::Identifier),
+                    rule("VersionId", Matcher::Identifier),
+                    rule("NextVersionIdMarker", Matcher::Identifier),
+                    rule("NextUploadIdMarker", Matcher::Identifier),
+                ],
+            },
+            Service::DynamoDb => Self {
+                headers: &["content-type"],
+                header_rules: Vec::new(),
+                body_rules: vec![
+                    rule("CreationDateTime", Matcher::EpochSeconds),
+                    rule("LastUpdateToPayPerRequestDateTime", Matcher::EpochSeconds),
+                    rule("TableArn", Matcher::Any),
+                    rule("TableId", Matcher::Identifier),
+                ],
+            },
+        }
+    }
+
+    /// Normalize a response with `headers` and `body`, given the scenario's
+    /// variables.
+    #[must_use]
+    pub fn normalize(
+        &self,
+        headers: &[(String, String)],
+        body: &[u8],
+        vars: &[(String, String)],
+    ) -> Normalized {
+        let mut vars: Vec<&(String, String)> =
+            vars.iter().filter(|(_, value)| !value.is_empty()).collect();
+        // Longer values first, so a value containing another is replaced whole.
+        vars.sort_by_key(|(_, value)| std::cmp::Reverse(value.len()));
+        let unsubstitute = |text: &str| {
+            vars.iter().fold(text.to_owned(), |text, (name, value)| {
+                text.replace(value.as_str(), &format!("{{{name}}}"))
+            })
+        };
+
+        let mut compared = BTreeMap::new();
+        for (name, value) in headers {
+            let name = name.to_ascii_lowercase();
+            if !self.headers.contains(&name.as_str()) {
+                continue;
+            }
+            let value = unsubstitute(value);
+            let value = match self.header_rules.iter().find(|r| r.field == name) {
+                Some(rule) if rule.matcher.matches(&value) => rule.matcher.token().to_owned(),
+                _ => value,
+            };
+            compared.insert(name, value);
+        }
+
+        let text = unsubstitute(&String::from_utf8_lossy(body));
+        let is_json = compared
+            .get("content-type")
+            .is_some_and(|ct| ct.contains("json"));
+        let body = match serde_json::from_str::<Value>(&text) {
+            Ok(mut json) if is_json => {
+                self.normalize_json(&mut json);
+                sorted(json).to_string()
+            }
+            _ => self.normalize_xml(text),
+        };
+        Normalized {
+            headers: compared,
+            body,
+        }
+    }
+
+    /// Replace the text of volatile elements in an XML body.
+    fn normalize_xml(&self, mut xml: String) -> String {
+        for rule in &self.body_rules {
+            let open = format!("<{}>", rule.field);
+            let close = format!("</{}>", rule.field);
+            let mut from = 0;
+            while let Some(start) = xml[from..].find(&open).map(|i| from + i + open.len()) {
+                let Some(end) = xml[start..].find(&close).map(|i| start + i) else {
+                    break;
+                };
+                if rule.matcher.matches(&xml[start..end]) {
+                    xml.replace_range(start..end, rule.matcher.token());
+                    from = start + rule.matcher.token().len() + close.len();
+                } else {
+                    from = end + close.len();
+                }
+            }
+        }
+        xml
+    }
+
+    /// Replace volatile members of a JSON body, at any depth.
+    fn normalize_json(&self, json: &mut Value) {
+        match json {
+            Value::Object(members) => {
+                for (name, value) in members.iter_mut() {
+                    let text = match value {
+                        Value::String(s) => Some(s.clone()),
+                        Value::Number(n) => Some(n.to_string()),
+                        _ => None,
+                    };
+                    let rule = self.body_rules.iter().find(|r| r.field == name);
+                    match (rule, text) {
+                        (Some(rule), Some(text)) if rule.matcher.matches(&text) => {
+                            *value = Value::String(rule.matcher.token().to_owned());
+                        }
+                        _ => self.normalize_json(value),
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.normalize_json(item)),
+            _ => {}
+        }
+    }
+}
+
+/// `json` with the members of every object in sorted order.
+fn sorted(json: Value) -> Value {
+    match json {
+        Value::Object(members) => {
+            let members: BTreeMap<String, Value> =
+                members.into_iter().map(|(k, v)| (k, sorted(v))).collect();
+            Value::Object(members.into_iter().collect())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sorted).collect()),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn test_should_match_value_shapes() {
+        assert!(Matcher::HttpDate.matches("Wed, 21 Oct 2015 07:28:00 GMT"));
+        assert!(!Matcher::HttpDate.matches("2015-10-21T07:28:00Z"));
+        assert!(Matcher::Timestamp.matches("2015-10-21T07:28:00.000Z"));
+        assert!(Matcher::EpochSeconds.matches("1445412480.123"));
+        assert!(!Matcher::EpochSeconds.matches("12"));
+        assert!(Matcher::ETag.matches("\"5d41402abc4b2a76b9719d911017c592\""));
+        assert!(Matcher::ETag.matches("&quot;5d41402abc4b2a76b9719d911017c592-2&quot;"));
+        assert!(!Matcher::ETag.matches("5d41402abc4b2a76b9719d911017c592"));
+        assert!(!Matcher::ETag.matches("\"not-an-md5\""));
+        assert!(Matcher::Identifier.matches("3HL4kqtJlcpXroDTDmJ+rmSpXd3dIbrHY"));
+        assert!(!Matcher::Identifier.matches(""));
+        assert!(!Matcher::Identifier.matches("{v1}"));
+    }
+
+    #[test]
+    fn test_should_normalize_s3_response() {
+        let normalizer = Normalizer::for_service(Service::S3);
+        let vars = [
+            ("bucket".to_owned(), "rsconf-1-b".to_owned()),
+            ("v1".to_owned(), "Xyz.123".to_owned()),
+        ];
+        let response = normalizer.normalize(
+            &headers(&[
+                ("Date", "Wed, 21 Oct 2015 07:28:00 GMT"),
+                ("x-amz-request-id", "ABC"),
+                ("ETag", "\"5d41402abc4b2a76b9719d911017c592\""),
+                ("x-amz-version-id", "Xyz.123"),
+                ("Last-Modified", "yesterday"),
+                ("Content-Type", "application/xml"),
+            ]),
+            b"<Error><Code>NoSuchKey</Code><Resource>/rsconf-1-b/k</Resource>\
+              <RequestId>ABC</RequestId><LastModified>2015-10-21T07:28:00.000Z</LastModified>\
+              <LastModified>bad</LastModified></Error>",
+            &vars,
+        );
+        assert_eq!(
+            response.headers,
+            BTreeMap::from([
+                ("content-type".to_owned(), "application/xml".to_owned()),
+                ("etag".to_owned(), "<etag>".to_owned()),
+                ("last-modified".to_owned(), "yesterday".to_owned()),
+                ("x-amz-version-id".to_owned(), "{v1}".to_owned()),
+            ])
+        );
+        assert_eq!(
+            response.body,
+            "<Error><Code>NoSuchKey</Code><Resource>/{bucket}/k</Resource><RequestId><any></\
+             RequestId><LastModified><timestamp></LastModified><LastModified>bad</LastModified></\
+             Error>"
+        );
+    }
+
+    #[test]
+    fn test_should_sort_and_normalize_json_response() {
+        let normalizer = Normalizer::for_service(Service::DynamoDb);
+        let vars = [("table".to_owned(), "rsconf-1-t".to_owned())];
+        let response = normalizer.normalize(
+            &headers(&[("content-type", "application/x-amz-json-1.0")]),
+            br#"{"TableDescription":{"TableName":"rsconf-1-t","CreationDateTime":1.7e9,
+                "TableArn":"arn:aws:dynamodb:us-east-1:000000000000:table/rsconf-1-t"},"A":[1]}"#,
+            &vars,
+        );
+        assert_eq!(
+            response.body,
+            r#"{"A":[1],"TableDescription":{"CreationDateTime":"<epoch-seconds>","TableArn":"<any>","TableName":"{table}"}}"#
+        );
+    }
+}
@@ -0,0 +1,323 @@
+//! Scenario execution.
+//!
+//! A [`Runner`] sends a scenario's steps as raw HTTP to a [`Target`] — a
+//! Rustack gateway or AWS itself — and returns the normalized exchanges of
+//! its checked steps. Resource names embed a random run id, so runs against
+//! AWS do not collide with existing buckets or with each other.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use base64::Engine as _;
+use md5::Digest as _;
+
+use crate::{
+    golden::Exchange,
+    normalize::Normalizer,
+    scenario::{Capture, Scenario, Service, Step, substitute},
+    sign::{Credentials, SignableRequest, sign},
+};
+
+/// Times a [waiting](Step::wait_until) step is sent before giving up.
+const WAIT_ATTEMPTS: u32 = 60;
+
+/// Pause between attempts of a waiting step.
+const WAIT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Where scenarios are sent.
+#[derive(Debug, Clone)]
+pub struct Target {
+    /// A single endpoint serving every service, or `None` for AWS.
+    endpoint: Option<String>,
+    /// Region requests are signed for.
+    pub region: String,
+    /// Credentials requests are signed with.
+    pub credentials: Credentials,
+}
+
+impl Target {
+    /// A Rustack gateway at `endpoint`, signed with test credentials.
+    #[must_use]
+    pub fn gateway(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: Some(endpoint.into().trim_end_matches('/').to_owned()),
+            region: "us-east-1".to_owned(),
+            credentials: Credentials::test(),
+        }
+    }
+
+    /// The public AWS endpoints of `region`.
+    #[must_use]
+    pub fn aws(region: impl Into<String>, credentials: Credentials) -> Self {
+        Self {
+            endpoint: None,
+            region: region.into(),
+            credentials,
+        }
+    }
+
+    /// Whether the target is AWS itself.
+    #[must_use]
+    pub fn is_aws(&self) -> bool {
+        self.endpoint.is_none()
+    }
+
+    /// The base URL of `service`.
+    fn base_url(&self, service: Service) -> String {
+        match &self.endpoint {
+            Some(endpoint) => endpoint.clone(),
+            None => format!(
+                "https://{}.{}.amazonaws.com",
+                service.signing_name(),
+                self.region
+            ),
+        }
+    }
+}
+
+/// Runs scenarios against one target.
+#[derive(Debug)]
+pub struct Runner {
+    client: reqwest::Client,
+    target: Target,
+    run_id: String,
+}
+
+impl Runner {
+    /// A runner for `target`, with a fresh run id.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new(target: Target) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+        let run_id = uuid::Uuid::new_v4().simple().to_string()[..8].to_owned();
+        Ok(Self {
+            client,
+            target,
+            run_id,
+        })
+    }
+
+    /// The target scenarios are sent to.
+    #[must_use]
+    pub fn target(&self) -> &Target {
+        &self.target
+    }
+
+    /// Send every step of `scenario` and return the normalized exchanges of
+    /// its checked steps.
+    ///
+    /// A value a step fails to capture stays unset, so the steps using it
+    /// fail visibly in the comparison instead of aborting the scenario and
+    /// its cleanup.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a request cannot be sent at all.
+    pub async fn run(&self, scenario: &Scenario) -> Result<Vec<Exchange>> {
+        let resource = format!("rsconf-{}-{}", self.run_id, scenario.name.replace('_', "-"));
+        let mut vars = vec![
+            ("bucket".to_owned(), resource.clone()),
+            ("table".to_owned(), resource),
+        ];
+        let normalizer = Normalizer::for_service(scenario.service);
+
+        let mut exchanges = Vec::new();
+        for (i, step) in scenario.steps.iter().enumerate() {
+            let (status, headers, body) = self
+                .send_until(scenario.service, step, &vars)
+                .await
+                .with_context(|| format!("step {} ({}) failed", i + 1, step.label()))?;
+            for (name, from) in &step.captures {
+                if let Some(value) = extract(*from, &headers, &body) {
+                    vars.retain(|(n, _)| n != name);
+                    vars.push(((*name).to_owned(), value));
+                }
+            }
+            if step.checked {
+                let response = normalizer.normalize(&headers, &body, &vars);
+                exchanges.push(Exchange {
+                    request: step.label(),
+                    status,
+                    headers: response.headers,
+                    body: response.body,
+                });
+            }
+        }
+        Ok(exchanges)
+    }
+
+    /// Send `step`, repeating it while its wait condition does not hold.
+    async fn send_until(
+        &self,
+        service: Service,
+        step: &Step,
+        vars: &[(String, String)],
+    ) -> Result<(u16, Vec<(String, String)>, Vec<u8>)> {
+        let mut attempt = 1;
+        loop {
+            let response = self.send(service, step, vars).await?;
+            let Some(wait) = &step.wait else {
+                return Ok(response);
+            };
+            let value = extract(wait.value, &response.1, &response.2);
+            if value.as_deref() == Some(wait.expected) || attempt >= WAIT_ATTEMPTS {
+                return Ok(response);
+            }
+            attempt += 1;
+            tokio::time::sleep(WAIT_INTERVAL).await;
+        }
+    }
+
+    /// Send `step` once, with `vars` substituted.
+    async fn send(
+        &self,
+        service: Service,
+        step: &Step,
+        vars: &[(String, String)],
+    ) -> Result<(u16, Vec<(String, String)>, Vec<u8>)> {
+        let path = substitute(&step.path, vars);
+        let query = substitute(&step.query, vars);
+        let body = match std::str::from_utf8(&step.body) {
+            Ok(text) => substitute(text, vars).into_bytes(),
+            Err(_) => step.body.clone(),
+        };
+
+        let mut url = format!("{}{path}", self.target.base_url(service));
+        if !query.is_empty() {
+            url = format!("{url}?{query}");
+        }
+        let parsed = reqwest::Url::parse(&url).with_context(|| format!("invalid URL {url}"))?;
+        let host = match (parsed.host_str(), parsed.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_owned(),
+            (None, _) => anyhow::bail!("URL {url} has no host"),
+        };
+
+        let mut headers = vec![("host".to_owned(), host)];
+        headers.extend(
+            step.headers
+                .iter()
+                .map(|(name, value)| (name.clone(), substitute(value, vars))),
+        );
+        if step.content_md5 {
+            let md5 = md5::Md5::digest(&body);
+            headers.push((
+                "content-md5".to_owned(),
+                base64::engine::general_purpose::STANDARD.encode(md5),
+            ));
+        }
+        let signable = SignableRequest {
+            method: step.method,
+            path: &path,
+            query: &query,
+            headers: &headers,
+            body: &body,
+        };
+        let auth = sign(
+            &signable,
+            &self.target.credentials,
+            service.signing_name(),
+            &self.target.region,
+            chrono::Utc::now(),
+        );
+
+        let method = reqwest::Method::from_bytes(step.method.as_bytes())?;
+        let mut request = self.client.request(method, parsed);
+        // reqwest sets `host` itself, from the URL it was signed with.
+        for (name, value) in headers.iter().skip(1).chain(&auth) {
+            request = request.header(name, value);
+        }
+        let response = request.body(body).send().await?;
+
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.as_str().to_owned(),
+                    String::from_utf8_lossy(value.as_bytes()).into_owned(),
+                )
+            })
+            .collect();
+        let body = response.bytes().await?.to_vec();
+        Ok((status, headers, body))
+    }
+}
+
+/// Read a captured value from a response.
+fn extract(from: Capture, headers: &[(String, String)], body: &[u8]) -> Option<String> {
+    match from {
+        Capture::Header(name) => headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.clone()),
+        Capture::XmlElement(name) => {
+            let body = std::str::from_utf8(body).ok()?;
+            let (open, close) = (format!("<{name}>"), format!("</{name}>"));
+            let start = body.find(&open)? + open.len();
+            let end = start + body[start..].find(&close)?;
+            Some(body[start..end].replace("&quot;", "\""))
+        }
+        Capture::JsonPointer(pointer) => {
+            let json: serde_json::Value = serde_json::from_slice(body).ok()?;
+            match json.pointer(pointer)? {
+                serde_json::Value::String(s) => Some(s.clone()),
+                serde_json::Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_extract_captured_values() {
+        let headers = vec![("ETag".to_owned(), "\"abc\"".to_owned())];
+        let xml = b"<R><UploadId>u-1</UploadId><ETag>&quot;e&quot;</ETag></R>";
+        let json = br#"{"Table":{"TableStatus":"ACTIVE","ItemCount":3}}"#;
+
+        assert_eq!(
+            extract(Capture::Header("etag"), &headers, b""),
+            Some("\"abc\"".to_owned())
+        );
+        assert_eq!(
+            extract(Capture::XmlElement("UploadId"), &[], xml),
+            Some("u-1".to_owned())
+        );
+        assert_eq!(
+            extract(Capture::XmlElement("ETag"), &[], xml),
+            Some("\"e\"".to_owned())
+        );
+        assert_eq!(extract(Capture::XmlElement("Missing"), &[], xml), None);
+        assert_eq!(
+            extract(Capture::JsonPointer("/Table/TableStatus"), &[], json),
+            Some("ACTIVE".to_owned())
+        );
+        assert_eq!(
+            extract(Capture::JsonPointer("/Table/ItemCount"), &[], json),
+            Some("3".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_should_resolve_service_urls() {
+        let gateway = Target::gateway("http://localhost:4566/");
+        assert_eq!(gateway.base_url(Service::S3), "http://localhost:4566");
+        assert!(!gateway.is_aws());
+
+        let aws = Target::aws("eu-west-1", Credentials::test());
+        assert_eq!(
+            aws.base_url(Service::DynamoDb),
+            "https://dynamodb.eu-west-1.amazonaws.com"
+        );
+        assert!(aws.is_aws());
+    }
+}
@@ -0,0 +1,249 @@
+//! Scenario definitions.
+//!
+//! A [`Scenario`] is an ordered list of raw HTTP [`Step`]s against one
+//! service. Paths, queries, header values, and bodies may reference
+//! variables as `{name}`: `{bucket}` and `{table}` name resources unique to
+//! the run, and a step can [capture](Step::capture) a value from its
+//! response, such as a version id or upload id, for later steps to use.
+//!
+//! Steps marked [`setup`](Step::setup) prepare or clean up state and are
+//! sent but not compared; every other step's response is part of the
+//! scenario's golden file.
+
+/// The service a scenario talks to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Service {
+    /// Amazon S3, spoken to with path-style requests.
+    S3,
+    /// Amazon DynamoDB, spoken to with the JSON 1.0 protocol.
+    DynamoDb,
+}
+
+impl Service {
+    /// The service's SigV4 signing name.
+    #[must_use]
+    pub fn signing_name(self) -> &'static str {
+        match self {
+            Self::S3 => "s3",
+            Self::DynamoDb => "dynamodb",
+        }
+    }
+}
+
+/// Where a captured value is read from in a response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capture {
+    /// A response header, by lowercase name.
+    Header(&'static str),
+    /// The text of the first XML element with this name.
+    XmlElement(&'static str),
+    /// A string or number in a JSON body, by JSON pointer.
+    JsonPointer(&'static str),
+}
+
+/// A step repeated until a value of its response has the expected value,
+/// such as a DynamoDB table becoming `ACTIVE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wait {
+    /// The value to inspect.
+    pub value: Capture,
+    /// The value to wait for.
+    pub expected: &'static str,
+}
+
+/// One HTTP request of a scenario.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// HTTP method.
+    pub method: &'static str,
+    /// Request path, starting with `/`, already percent-encoded.
+    pub path: String,
+    /// Query string without the leading `?`, already percent-encoded.
+    pub query: String,
+    /// Request headers, with lowercase names.
+    pub headers: Vec<(String, String)>,
+    /// Request body.
+    pub body: Vec<u8>,
+    /// Whether the request carries a `Content-MD5` of its body.
+    pub content_md5: bool,
+    /// Whether the response is compared against the golden file.
+    pub checked: bool,
+    /// Variables set from the response.
+    pub captures: Vec<(&'static str, Capture)>,
+    /// Repeat the step until this holds.
+    pub wait: Option<Wait>,
+}
+
+impl Step {
+    /// A request with no query, headers, or body.
+    #[must_use]
+    pub fn new(method: &'static str, path: impl Into<String>) -> Self {
+        Self {
+            method,
+            path: path.into(),
+            query: String::new(),
+            headers: Vec::new(),
+            body: Vec::new(),
+            content_md5: false,
+            checked: true,
+            captures: Vec::new(),
+            wait: None,
+        }
+    }
+
+    /// A DynamoDB JSON 1.0 request for `operation`.
+    #[must_use]
+    pub fn dynamodb(operation: &str, body: &serde_json::Value) -> Self {
+        Self::new("POST", "/")
+            .header("x-amz-target", format!("DynamoDB_20120810.{operation}"))
+            .header("content-type", "application/x-amz-json-1.0")
+            .body(body.to_string())
+    }
+
+    /// Set the query string.
+    #[must_use]
+    pub fn query(mut self, query: impl Into<String>) -> Self {
+        self.query = query.into();
+        self
+    }
+
+    /// Add a header. The name is lowercased.
+    #[must_use]
+    pub fn header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.push((name.to_ascii_lowercase(), value.into()));
+        self
+    }
+
+    /// Set the body.
+    #[must_use]
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Send a `Content-MD5` of the body, as S3 requires for some operations.
+    #[must_use]
+    pub fn content_md5(mut self) -> Self {
+        self.content_md5 = true;
+        self
+    }
+
+    /// Send the step without comparing its response.
+    #[must_use]
+    pub fn setup(mut self) -> Self {
+        self.checked = false;
+        self
+    }
+
+    /// Set the variable `name` from the response.
+    #[must_use]
+    pub fn capture(mut self, name: &'static str, from: Capture) -> Self {
+        self.captures.push((name, from));
+        self
+    }
+
+    /// Repeat the step until `value` of its response is `expected`.
+    #[must_use]
+    pub fn wait_until(mut self, value: Capture, expected: &'static str) -> Self {
+        self.wait = Some(Wait { value, expected });
+        self
+    }
+
+    /// The method and unsubstituted path and query, as golden files label
+    /// the exchange.
+    #[must_use]
+    pub fn label(&self) -> String {
+        if self.query.is_empty() {
+            format!("{} {}", self.method, self.path)
+        } else {
+            format!("{} {}?{}", self.method, self.path, self.query)
+        }
+    }
+}
+
+/// A named sequence of steps against one service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scenario {
+    /// Unique name, also the golden file's name.
+    pub name: &'static str,
+    /// The service the steps are sent to.
+    pub service: Service,
+    /// The steps, in order.
+    pub steps: Vec<Step>,
+}
+
+impl Scenario {
+    /// An S3 scenario with no steps.
+    #[must_use]
+    pub fn s3(name: &'static str) -> Self {
+        Self {
+            name,
+            service: Service::S3,
+            steps: Vec::new(),
+        }
+    }
+
+    /// A DynamoDB scenario with no steps.
+    #[must_use]
+    pub fn dynamodb(name: &'static str) -> Self {
+        Self {
+            name,
+            service: Service::DynamoDb,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Append a step.
+    #[must_use]
+    pub fn step(mut self, step: Step) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Append several steps.
+    #[must_use]
+    pub fn steps(mut self, steps: impl IntoIterator<Item = Step>) -> Self {
+        self.steps.extend(steps);
+        self
+    }
+}
+
+/// Replace each `{name}` in `text` with its variable's value. Unknown names
+/// are left as they are.
+#[must_use]
+pub fn substitute(text: &str, vars: &[(String, String)]) -> String {
+    vars.iter().fold(text.to_owned(), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_build_dynamodb_step() {
+        let step = Step::dynamodb("GetItem", &serde_json::json!({ "TableName": "{table}" }));
+        assert_eq!(step.label(), "POST /");
+        assert_eq!(
+            step.headers[0],
+            (
+                "x-amz-target".to_owned(),
+                "DynamoDB_20120810.GetItem".to_owned()
+            )
+        );
+        assert_eq!(step.body, br#"{"TableName":"{table}"}"#);
+    }
+
+    #[test]
+    fn test_should_substitute_known_variables_only() {
+        let vars = [
+            ("bucket".to_owned(), "b-1".to_owned()),
+            ("v1".to_owned(), "abc".to_owned()),
+        ];
+        assert_eq!(
+            substitute(r#"/{bucket}/k?versionId={v1}&x={other} {"a":1}"#, &vars),
+            r#"/b-1/k?versionId=abc&x={other} {"a":1}"#
+        );
+    }
+}
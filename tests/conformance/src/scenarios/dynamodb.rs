@@ -0,0 +1,488 @@
+//! DynamoDB scenarios: table lifecycle, condition, update, key condition,
+//! and projection expressions, and transactions.
+
+use serde_json::{Value, json};
+
+use crate::scenario::{Capture, Scenario, Step};
+
+/// Every DynamoDB scenario.
+#[must_use]
+pub fn scenarios() -> Vec<Scenario> {
+    vec![
+        table_lifecycle(),
+        condition_expression(),
+        update_set_add_remove(),
+        update_return_values(),
+        update_list_append_if_not_exists(),
+        query_key_condition(),
+        projection_expression(),
+        reserved_word(),
+        expression_attribute_errors(),
+        transact_write_cancel(),
+        delete_item_return_values(),
+    ]
+}
+
+fn create_table() -> Step {
+    Step::dynamodb(
+        "CreateTable",
+        &json!({
+            "TableName": "{table}",
+            "AttributeDefinitions": [
+                { "AttributeName": "pk", "AttributeType": "S" },
+                { "AttributeName": "sk", "AttributeType": "S" }
+            ],
+            "KeySchema": [
+                { "AttributeName": "pk", "KeyType": "HASH" },
+                { "AttributeName": "sk", "KeyType": "RANGE" }
+            ],
+            "BillingMode": "PAY_PER_REQUEST"
+        }),
+    )
+}
+
+fn wait_active() -> Step {
+    Step::dynamodb("DescribeTable", &json!({ "TableName": "{table}" }))
+        .wait_until(Capture::JsonPointer("/Table/TableStatus"), "ACTIVE")
+}
+
+fn delete_table() -> Step {
+    Step::dynamodb("DeleteTable", &json!({ "TableName": "{table}" }))
+}
+
+/// The key of the item `pk`/`sk`.
+fn key(pk: &str, sk: &str) -> Value {
+    json!({ "pk": { "S": pk }, "sk": { "S": sk } })
+}
+
+/// `body` with the members of `extra` added.
+fn with_members(mut body: Value, extra: &Value) -> Value {
+    if let (Some(body), Some(extra)) = (body.as_object_mut(), extra.as_object()) {
+        body.extend(extra.clone());
+    }
+    body
+}
+
+/// A request body for `table`'s item `pk`/`sk`, with `extra` members.
+fn item_request(pk: &str, sk: &str, extra: &Value) -> Value {
+    with_members(json!({ "TableName": "{table}", "Key": key(pk, sk) }), extra)
+}
+
+fn put_item(item: &Value) -> Step {
+    Step::dynamodb("PutItem", &json!({ "TableName": "{table}", "Item": item })).setup()
+}
+
+fn update_item(pk: &str, sk: &str, extra: &Value) -> Step {
+    Step::dynamodb("UpdateItem", &item_request(pk, sk, extra))
+}
+
+fn get_item(pk: &str, sk: &str) -> Step {
+    Step::dynamodb(
+        "GetItem",
+        &item_request(pk, sk, &json!({ "ConsistentRead": true })),
+    )
+}
+
+/// A table around `steps`, deleted again afterwards.
+fn with_table(name: &'static str, steps: Vec<Step>) -> Scenario {
+    Scenario::dynamodb(name)
+        .step(create_table().setup())
+        .step(wait_active().setup())
+        .steps(steps)
+        .step(delete_table().setup())
+}
+
+fn table_lifecycle() -> Scenario {
+    Scenario::dynamodb("dynamodb_table_lifecycle")
+        .step(create_table())
+        .step(wait_active())
+        .step(create_table())
+        .step(Step::dynamodb(
+            "DescribeTable",
+            &json!({ "TableName": "{table}-missing" }),
+        ))
+        .step(delete_table())
+}
+
+fn condition_expression() -> Scenario {
+    let put = |extra: Value| {
+        let body = json!({
+            "TableName": "{table}",
+            "Item": { "pk": { "S": "p" }, "sk": { "S": "s" }, "n": { "N": "1" } },
+            "ConditionExpression": "attribute_not_exists(pk)"
+        });
+        Step::dynamodb("PutItem", &with_members(body, &extra))
+    };
+    with_table(
+        "dynamodb_condition_expression",
+        vec![
+            put(json!({})),
+            put(json!({})),
+            put(json!({ "ReturnValuesOnConditionCheckFailure": "ALL_OLD" })),
+            update_item(
+                "p",
+                "s",
+                &json!({
+                    "UpdateExpression": "SET n = :two",
+                    "ConditionExpression": "n > :limit",
+                    "ExpressionAttributeValues": { ":two": { "N": "2" }, ":limit": { "N": "5" } }
+                }),
+            ),
+            update_item(
+                "p",
+                "s",
+                &json!({
+                    "UpdateExpression": "SET n = :two",
+                    "ConditionExpression": "n BETWEEN :lo AND :hi AND attribute_type(n, :t)",
+                    "ExpressionAttributeValues": {
+                        ":two": { "N": "2" }, ":lo": { "N": "0" },
+                        ":hi": { "N": "5" }, ":t": { "S": "N" }
+                    },
+                    "ReturnValues": "ALL_NEW"
+                }),
+            ),
+        ],
+    )
+}
+
+fn update_set_add_remove() -> Scenario {
+    with_table(
+        "dynamodb_update_set_add_remove",
+        vec![
+            put_item(&json!({
+                "pk": { "S": "p" }, "sk": { "S": "s" },
+                "count": { "N": "1" }, "gone": { "S": "x" }, "tags": { "SS": ["a"] }
+            })),
+            update_item(
+                "p",
+                "s",
+                &json!({
+                    "UpdateExpression": "SET #c = #c + :one, labelText = :l REMOVE gone ADD tags :t",
+                    "ExpressionAttributeNames": { "#c": "count" },
+                    "ExpressionAttributeValues": {
+                        ":one": { "N": "1" }, ":l": { "S": "new" }, ":t": { "SS": ["b", "c"] }
+                    },
+                    "ReturnValues": "ALL_NEW"
+                }),
+            ),
+            update_item(
+                "p",
+                "s",
+                &json!({
+                    "UpdateExpression": "DELETE tags :t",
+                    "ExpressionAttributeValues": { ":t": { "SS": ["a", "c"] } },
+                    "ReturnValues": "ALL_NEW"
+                }),
+            ),
+            get_item("p", "s"),
+        ],
+    )
+}
+
+fn update_return_values() -> Scenario {
+    let mixed = |return_values: &str| {
+        update_item(
+            "p",
+            "s",
+            &json!({
+                "UpdateExpression": "SET labelText = :l ADD hits :one",
+                "ExpressionAttributeValues": { ":l": { "S": return_values }, ":one": { "N": "1" } },
+                "ReturnValues": return_values
+            }),
+        )
+    };
+    with_table(
+        "dynamodb_update_return_values",
+        vec![
+            // ADD on a missing item creates it.
+            update_item(
+                "new",
+                "s",
+                &json!({
+                    "UpdateExpression": "ADD hits :one",
+                    "ExpressionAttributeValues": { ":one": { "N": "1" } },
+                    "ReturnValues": "UPDATED_OLD"
+                }),
+            ),
+            put_item(&json!({
+                "pk": { "S": "p" }, "sk": { "S": "s" },
+                "otherAttr": { "S": "untouched" }, "hits": { "N": "5" }
+            })),
+            mixed("NONE"),
+            mixed("UPDATED_NEW"),
+            mixed("UPDATED_OLD"),
+            mixed("ALL_NEW"),
+            mixed("ALL_OLD"),
+            update_item(
+                "p",
+                "s",
+                &json!({
+                    "UpdateExpression": "REMOVE otherAttr",
+                    "ReturnValues": "UPDATED_NEW"
+                }),
+            ),
+        ],
+    )
+}
+
+fn update_list_append_if_not_exists() -> Scenario {
+    let append = update_item(
+        "p",
+        "s",
+        &json!({
+            "UpdateExpression":
+                "SET entries = list_append(if_not_exists(entries, :empty), :more), firstSeen = if_not_exists(firstSeen, :now)",
+            "ExpressionAttributeValues": {
+                ":empty": { "L": [] },
+                ":more": { "L": [{ "S": "x" }] },
+                ":now": { "N": "1" }
+            },
+            "ReturnValues": "ALL_NEW"
+        }),
+    );
+    with_table(
+        "dynamodb_list_append_if_not_exists",
+        vec![
+            append.clone(),
+            append,
+            update_item(
+                "p",
+                "s",
+                &json!({
+                    "UpdateExpression": "SET entries[5] = :v, entries[0] = :w",
+                    "ExpressionAttributeValues": { ":v": { "S": "end" }, ":w": { "S": "start" } },
+                    "ReturnValues": "UPDATED_NEW"
+                }),
+            ),
+            update_item(
+                "p",
+                "s",
+                &json!({
+                    "UpdateExpression": "SET missing.nested = :v",
+                    "ExpressionAttributeValues": { ":v": { "S": "x" } }
+                }),
+            ),
+        ],
+    )
+}
+
+fn query_key_condition() -> Scenario {
+    let values = json!({ ":p": { "S": "p" }, ":prefix": { "S": "a#" } });
+    let query = |extra: Value| {
+        let body = json!({
+            "TableName": "{table}",
+            "KeyConditionExpression": "pk = :p AND begins_with(sk, :prefix)",
+            "ExpressionAttributeValues": values,
+            "ConsistentRead": true
+        });
+        Step::dynamodb("Query", &with_members(body, &extra))
+    };
+    with_table(
+        "dynamodb_query_key_condition",
+        vec![
+            put_item(&json!({ "pk": { "S": "p" }, "sk": { "S": "a#1" }, "n": { "N": "1" } })),
+            put_item(&json!({ "pk": { "S": "p" }, "sk": { "S": "a#2" }, "n": { "N": "2" } })),
+            put_item(&json!({ "pk": { "S": "p" }, "sk": { "S": "a#3" }, "n": { "N": "3" } })),
+            put_item(&json!({ "pk": { "S": "p" }, "sk": { "S": "b#1" }, "n": { "N": "4" } })),
+            query(json!({})),
+            query(json!({ "ScanIndexForward": false, "Limit": 1 })),
+            query(json!({
+                "FilterExpression": "n >= :min",
+                "ExpressionAttributeValues": {
+                    ":p": { "S": "p" }, ":prefix": { "S": "a#" }, ":min": { "N": "2" }
+                }
+            })),
+            query(json!({ "Select": "COUNT" })),
+            query(json!({ "KeyConditionExpression": "sk = :prefix" })),
+        ],
+    )
+}
+
+fn projection_expression() -> Scenario {
+    let get = |projection: &str, names: Value| {
+        Step::dynamodb(
+            "GetItem",
+            &item_request(
+                "p",
+                "s",
+                &json!({ "ProjectionExpression": projection, "ExpressionAttributeNames": names }),
+            ),
+        )
+    };
+    with_table(
+        "dynamodb_projection_expression",
+        vec![
+            put_item(&json!({
+                "pk": { "S": "p" }, "sk": { "S": "s" },
+                "map": { "M": { "innerVal": { "S": "i" }, "other": { "N": "2" } } },
+                "list": { "L": [{ "S": "zero" }, { "S": "one" }, { "S": "two" }] },
+                "name": { "S": "n" }
+            })),
+            get(
+                "#m.innerVal, #l[1], pk",
+                json!({ "#m": "map", "#l": "list" }),
+            ),
+            get("#n, absentAttr", json!({ "#n": "name" })),
+            get("#m.innerVal, #m", json!({ "#m": "map" })),
+        ],
+    )
+}
+
+fn reserved_word() -> Scenario {
+    with_table(
+        "dynamodb_reserved_word",
+        vec![
+            update_item(
+                "p",
+                "s",
+                &json!({
+                    "UpdateExpression": "SET status = :s",
+                    "ExpressionAttributeValues": { ":s": { "S": "on" } }
+                }),
+            ),
+            update_item(
+                "p",
+                "s",
+                &json!({
+                    "UpdateExpression": "SET #s = :s",
+                    "ConditionExpression": "attribute_not_exists(name)",
+                    "ExpressionAttributeNames": { "#s": "status" },
+                    "ExpressionAttributeValues": { ":s": { "S": "on" } }
+                }),
+            ),
+            update_item(
+                "p",
+                "s",
+                &json!({
+                    "UpdateExpression": "SET #s = :s",
+                    "ExpressionAttributeNames": { "#s": "status" },
+                    "ExpressionAttributeValues": { ":s": { "S": "on" } },
+                    "ReturnValues": "ALL_NEW"
+                }),
+            ),
+        ],
+    )
+}
+
+fn expression_attribute_errors() -> Scenario {
+    with_table(
+        "dynamodb_expression_attribute_errors",
+        vec![
+            update_item(
+                "p",
+                "s",
+                &json!({
+                    "UpdateExpression": "SET a = :a",
+                    "ExpressionAttributeValues": { ":a": { "S": "x" }, ":unused": { "S": "y" } }
+                }),
+            ),
+            update_item(
+                "p",
+                "s",
+                &json!({
+                    "UpdateExpression": "SET a = :missing",
+                    "ExpressionAttributeValues": { ":a": { "S": "x" } }
+                }),
+            ),
+            update_item(
+                "p",
+                "s",
+                &json!({
+                    "UpdateExpression": "SET a = :a",
+                    "ExpressionAttributeNames": { "#unused": "b" },
+                    "ExpressionAttributeValues": { ":a": { "S": "x" } }
+                }),
+            ),
+            update_item(
+                "p",
+                "s",
+                &json!({
+                    "UpdateExpression": "SET a = :a REMOVE a",
+                    "ExpressionAttributeValues": { ":a": { "S": "x" } }
+                }),
+            ),
+            update_item(
+                "p",
+                "s",
+                &json!({
+                    "UpdateExpression": "SET a = :a,",
+                    "ExpressionAttributeValues": { ":a": { "S": "x" } }
+                }),
+            ),
+        ],
+    )
+}
+
+fn transact_write_cancel() -> Scenario {
+    let transact = Step::dynamodb(
+        "TransactWriteItems",
+        &json!({
+            "TransactItems": [
+                { "Put": {
+                    "TableName": "{table}",
+                    "Item": { "pk": { "S": "p" }, "sk": { "S": "new" } }
+                } },
+                { "ConditionCheck": {
+                    "TableName": "{table}",
+                    "Key": key("p", "guard"),
+                    "ConditionExpression": "isLocked = :no",
+                    "ExpressionAttributeValues": { ":no": { "BOOL": false } },
+                    "ReturnValuesOnConditionCheckFailure": "ALL_OLD"
+                } }
+            ]
+        }),
+    );
+    with_table(
+        "dynamodb_transact_write_cancel",
+        vec![
+            put_item(
+                &json!({ "pk": { "S": "p" }, "sk": { "S": "guard" }, "isLocked": { "BOOL": true } }),
+            ),
+            transact.clone(),
+            get_item("p", "new"),
+            update_item(
+                "p",
+                "guard",
+                &json!({
+                    "UpdateExpression": "SET isLocked = :no",
+                    "ExpressionAttributeValues": { ":no": { "BOOL": false } }
+                }),
+            )
+            .setup(),
+            transact,
+            get_item("p", "new"),
+        ],
+    )
+}
+
+fn delete_item_return_values() -> Scenario {
+    with_table(
+        "dynamodb_delete_item_return_values",
+        vec![
+            put_item(&json!({ "pk": { "S": "p" }, "sk": { "S": "s" }, "v": { "S": "x" } })),
+            Step::dynamodb(
+                "DeleteItem",
+                &item_request(
+                    "p",
+                    "s",
+                    &json!({
+                        "ConditionExpression": "v = :other",
+                        "ExpressionAttributeValues": { ":other": { "S": "y" } }
+                    }),
+                ),
+            ),
+            Step::dynamodb(
+                "DeleteItem",
+                &item_request("p", "s", &json!({ "ReturnValues": "ALL_OLD" })),
+            ),
+            Step::dynamodb(
+                "DeleteItem",
+                &item_request("p", "s", &json!({ "ReturnValues": "ALL_OLD" })),
+            ),
+            Step::dynamodb(
+                "DeleteItem",
+                &item_request("p", "s", &json!({ "ReturnValues": "ALL_NEW" })),
+            ),
+        ],
+    )
+}
@@ -0,0 +1,44 @@
+//! The scenario catalog.
+
+mod dynamodb;
+mod s3;
+
+use crate::scenario::Scenario;
+
+/// Every scenario, S3 first.
+#[must_use]
+pub fn catalog() -> Vec<Scenario> {
+    let mut scenarios = s3::scenarios();
+    scenarios.extend(dynamodb::scenarios());
+    scenarios
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_should_name_scenarios_uniquely_and_validly() {
+        let scenarios = catalog();
+        assert!(scenarios.len() >= 30, "{} scenarios", scenarios.len());
+
+        let mut names = HashSet::new();
+        for scenario in &scenarios {
+            assert!(names.insert(scenario.name), "duplicate {}", scenario.name);
+            // Resource names are `rsconf-<8-char run id>-<name>`, and bucket
+            // names are at most 63 characters.
+            assert!(
+                scenario.name.len() <= 63 - 16,
+                "{} is too long",
+                scenario.name
+            );
+            assert!(
+                scenario.steps.iter().any(|step| step.checked),
+                "{} checks nothing",
+                scenario.name
+            );
+        }
+    }
+}
@@ -0,0 +1,505 @@
+//! S3 scenarios: objects, listings, conditional requests, versioning, and
+//! multipart uploads.
+
+use std::fmt::Write as _;
+
+use crate::scenario::{Capture, Scenario, Step};
+
+/// Size of a multipart part that is not the last one; S3's minimum.
+const PART_SIZE: usize = 5 * 1024 * 1024;
+
+const VERSIONING_ENABLED: &str = "<VersioningConfiguration xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\">\
+     <Status>Enabled</Status></VersioningConfiguration>";
+
+const VERSIONING_SUSPENDED: &str = "<VersioningConfiguration xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\">\
+     <Status>Suspended</Status></VersioningConfiguration>";
+
+/// Every S3 scenario.
+#[must_use]
+pub fn scenarios() -> Vec<Scenario> {
+    vec![
+        bucket_lifecycle(),
+        put_get_object(),
+        get_missing_key(),
+        get_missing_bucket(),
+        list_v2_delimiter(),
+        list_v1_next_marker(),
+        list_encoding_url(),
+        copy_object(),
+        delete_objects(),
+        range_get(),
+        conditional_if_match(),
+        conditional_if_none_match(),
+        conditional_dates(),
+        conditional_put(),
+        versioning_status(),
+        versioning_get_version(),
+        versioning_delete_marker(),
+        versioning_delete_version(),
+        versioning_suspended(),
+        multipart_complete(),
+        multipart_abort(),
+        multipart_entity_too_small(),
+        multipart_invalid_part_order(),
+        list_multipart_uploads(),
+    ]
+}
+
+fn create_bucket() -> Step {
+    Step::new("PUT", "/{bucket}").setup()
+}
+
+fn delete_bucket() -> Step {
+    Step::new("DELETE", "/{bucket}").setup()
+}
+
+fn put(key: &str, body: &str) -> Step {
+    Step::new("PUT", format!("/{{bucket}}/{key}"))
+        .body(body)
+        .setup()
+}
+
+fn delete(key: &str) -> Step {
+    Step::new("DELETE", format!("/{{bucket}}/{key}")).setup()
+}
+
+fn delete_version(key: &str, version: &str) -> Step {
+    Step::new("DELETE", format!("/{{bucket}}/{key}"))
+        .query(format!("versionId={{{version}}}"))
+        .setup()
+}
+
+/// A bucket holding `keys`, each with its own name as content, around
+/// `steps`, deleted again afterwards.
+fn with_objects(name: &'static str, keys: &[&str], steps: Vec<Step>) -> Scenario {
+    Scenario::s3(name)
+        .step(create_bucket())
+        .steps(keys.iter().map(|key| put(key, key)))
+        .steps(steps)
+        .steps(keys.iter().map(|key| delete(key)))
+        .step(delete_bucket())
+}
+
+fn bucket_lifecycle() -> Scenario {
+    Scenario::s3("s3_bucket_lifecycle")
+        .step(Step::new("PUT", "/{bucket}"))
+        .step(Step::new("HEAD", "/{bucket}"))
+        .step(Step::new("GET", "/{bucket}").query("list-type=2"))
+        .step(Step::new("DELETE", "/{bucket}"))
+        .step(Step::new("HEAD", "/{bucket}"))
+}
+
+fn put_get_object() -> Scenario {
+    Scenario::s3("s3_put_get_object")
+        .step(create_bucket())
+        .step(
+            Step::new("PUT", "/{bucket}/hello.txt")
+                .header("content-type", "text/plain")
+                .body("hello world"),
+        )
+        .step(Step::new("GET", "/{bucket}/hello.txt"))
+        .step(Step::new("HEAD", "/{bucket}/hello.txt"))
+        .step(Step::new("DELETE", "/{bucket}/hello.txt"))
+        .step(delete_bucket())
+}
+
+fn get_missing_key() -> Scenario {
+    with_objects(
+        "s3_get_missing_key",
+        &[],
+        vec![
+            Step::new("GET", "/{bucket}/missing"),
+            Step::new("HEAD", "/{bucket}/missing"),
+        ],
+    )
+}
+
+fn get_missing_bucket() -> Scenario {
+    Scenario::s3("s3_get_missing_bucket")
+        .step(Step::new("GET", "/{bucket}/key"))
+        .step(Step::new("GET", "/{bucket}").query("list-type=2"))
+}
+
+fn list_v2_delimiter() -> Scenario {
+    with_objects(
+        "s3_list_v2_delimiter",
+        &["a/1", "a/2", "b/1", "c"],
+        vec![
+            Step::new("GET", "/{bucket}").query("list-type=2&delimiter=%2F"),
+            Step::new("GET", "/{bucket}").query("list-type=2&prefix=a%2F&max-keys=1"),
+            Step::new("GET", "/{bucket}").query("list-type=2&start-after=a%2F2"),
+        ],
+    )
+}
+
+fn list_v1_next_marker() -> Scenario {
+    with_objects(
+        "s3_list_v1_next_marker",
+        &["a/1", "a/2", "b", "c"],
+        vec![
+            Step::new("GET", "/{bucket}").query("delimiter=%2F&max-keys=2"),
+            Step::new("GET", "/{bucket}").query("delimiter=%2F&marker=a%2F&max-keys=2"),
+            Step::new("GET", "/{bucket}").query("max-keys=2"),
+        ],
+    )
+}
+
+fn list_encoding_url() -> Scenario {
+    Scenario::s3("s3_list_encoding_url")
+        .step(create_bucket())
+        .step(
+            Step::new("PUT", "/{bucket}/dir/a%20b%2Bc")
+                .body("x")
+                .setup(),
+        )
+        .step(Step::new("GET", "/{bucket}").query("list-type=2&encoding-type=url"))
+        .step(Step::new("GET", "/{bucket}").query("delimiter=%2F&encoding-type=url"))
+        .step(Step::new("GET", "/{bucket}").query("versions&encoding-type=url"))
+        .step(Step::new("DELETE", "/{bucket}/dir/a%20b%2Bc").setup())
+        .step(delete_bucket())
+}
+
+fn copy_object() -> Scenario {
+    with_objects(
+        "s3_copy_object",
+        &["source"],
+        vec![
+            Step::new("PUT", "/{bucket}/copy").header("x-amz-copy-source", "/{bucket}/source"),
+            Step::new("GET", "/{bucket}/copy"),
+            Step::new("PUT", "/{bucket}/copy2").header("x-amz-copy-source", "/{bucket}/missing"),
+            Step::new("DELETE", "/{bucket}/copy").setup(),
+        ],
+    )
+}
+
+fn delete_objects() -> Scenario {
+    with_objects(
+        "s3_delete_objects",
+        &["keep"],
+        vec![
+            put("a", "a"),
+            put("b", "b"),
+            Step::new("POST", "/{bucket}")
+                .query("delete")
+                .body(
+                    "<Delete><Object><Key>a</Key></Object><Object><Key>b</Key></\
+                     Object><Object><Key>never-existed</Key></Object></Delete>",
+                )
+                .content_md5(),
+            Step::new("POST", "/{bucket}")
+                .query("delete")
+                .body("<Delete><Quiet>true</Quiet><Object><Key>a</Key></Object></Delete>")
+                .content_md5(),
+            Step::new("GET", "/{bucket}").query("list-type=2"),
+        ],
+    )
+}
+
+fn range_get() -> Scenario {
+    with_objects(
+        "s3_range_get",
+        &["0123456789"],
+        vec![
+            Step::new("GET", "/{bucket}/0123456789").header("range", "bytes=2-5"),
+            Step::new("GET", "/{bucket}/0123456789").header("range", "bytes=-3"),
+            Step::new("GET", "/{bucket}/0123456789").header("range", "bytes=7-"),
+            Step::new("GET", "/{bucket}/0123456789").header("range", "bytes=100-200"),
+        ],
+    )
+}
+
+fn conditional_if_match() -> Scenario {
+    with_objects(
+        "s3_conditional_if_match",
+        &[],
+        vec![
+            put("k", "v1").capture("etag", Capture::Header("etag")),
+            Step::new("GET", "/{bucket}/k").header("if-match", "{etag}"),
+            Step::new("GET", "/{bucket}/k")
+                .header("if-match", "\"0123456789abcdef0123456789abcdef\""),
+            Step::new("HEAD", "/{bucket}/k")
+                .header("if-match", "\"0123456789abcdef0123456789abcdef\""),
+            delete("k"),
+        ],
+    )
+}
+
+fn conditional_if_none_match() -> Scenario {
+    with_objects(
+        "s3_conditional_if_none_match",
+        &[],
+        vec![
+            put("k", "v1").capture("etag", Capture::Header("etag")),
+            Step::new("GET", "/{bucket}/k").header("if-none-match", "{etag}"),
+            Step::new("HEAD", "/{bucket}/k").header("if-none-match", "{etag}"),
+            Step::new("GET", "/{bucket}/k")
+                .header("if-none-match", "\"0123456789abcdef0123456789abcdef\""),
+            delete("k"),
+        ],
+    )
+}
+
+fn conditional_dates() -> Scenario {
+    with_objects(
+        "s3_conditional_dates",
+        &["k"],
+        vec![
+            Step::new("GET", "/{bucket}/k")
+                .header("if-modified-since", "Fri, 01 Jan 2100 00:00:00 GMT"),
+            Step::new("GET", "/{bucket}/k")
+                .header("if-modified-since", "Sat, 01 Jan 2000 00:00:00 GMT"),
+            Step::new("GET", "/{bucket}/k")
+                .header("if-unmodified-since", "Sat, 01 Jan 2000 00:00:00 GMT"),
+            // If-Match passing overrides a failing If-Unmodified-Since.
+            Step::new("GET", "/{bucket}/k")
+                .header("if-match", "*")
+                .header("if-unmodified-since", "Sat, 01 Jan 2000 00:00:00 GMT"),
+        ],
+    )
+}
+
+fn conditional_put() -> Scenario {
+    with_objects(
+        "s3_conditional_put",
+        &["existing"],
+        vec![
+            Step::new("PUT", "/{bucket}/existing")
+                .header("if-none-match", "*")
+                .body("again"),
+            Step::new("PUT", "/{bucket}/new")
+                .header("if-none-match", "*")
+                .body("first"),
+            Step::new("PUT", "/{bucket}/new")
+                .header("if-match", "\"0123456789abcdef0123456789abcdef\"")
+                .body("second"),
+            delete("new"),
+        ],
+    )
+}
+
+fn enable_versioning() -> Step {
+    Step::new("PUT", "/{bucket}")
+        .query("versioning")
+        .body(VERSIONING_ENABLED)
+        .setup()
+}
+
+fn versioning_status() -> Scenario {
+    Scenario::s3("s3_versioning_status")
+        .step(create_bucket())
+        .step(Step::new("GET", "/{bucket}").query("versioning"))
+        .step(
+            Step::new("PUT", "/{bucket}")
+                .query("versioning")
+                .body(VERSIONING_ENABLED),
+        )
+        .step(Step::new("GET", "/{bucket}").query("versioning"))
+        .step(
+            Step::new("PUT", "/{bucket}")
+                .query("versioning")
+                .body("<VersioningConfiguration><Status>Bogus</Status></VersioningConfiguration>"),
+        )
+        .step(delete_bucket())
+}
+
+fn versioning_get_version() -> Scenario {
+    Scenario::s3("s3_versioning_get_version")
+        .step(create_bucket())
+        .step(enable_versioning())
+        .step(put("k", "first").capture("v1", Capture::Header("x-amz-version-id")))
+        .step(put("k", "second").capture("v2", Capture::Header("x-amz-version-id")))
+        .step(Step::new("GET", "/{bucket}/k"))
+        .step(Step::new("GET", "/{bucket}/k").query("versionId={v1}"))
+        .step(Step::new("HEAD", "/{bucket}/k").query("versionId={v2}"))
+        .step(Step::new("GET", "/{bucket}/k").query("versionId=nonexistent"))
+        .step(Step::new("GET", "/{bucket}").query("versions"))
+        .step(delete_version("k", "v1"))
+        .step(delete_version("k", "v2"))
+        .step(delete_bucket())
+}
+
+fn versioning_delete_marker() -> Scenario {
+    Scenario::s3("s3_versioning_delete_marker")
+        .step(create_bucket())
+        .step(enable_versioning())
+        .step(put("k", "first").capture("v1", Capture::Header("x-amz-version-id")))
+        .step(
+            Step::new("DELETE", "/{bucket}/k")
+                .capture("marker", Capture::Header("x-amz-version-id")),
+        )
+        .step(Step::new("GET", "/{bucket}/k"))
+        .step(Step::new("HEAD", "/{bucket}/k"))
+        .step(Step::new("GET", "/{bucket}/k").query("versionId={marker}"))
+        .step(Step::new("GET", "/{bucket}/k").query("versionId={v1}"))
+        .step(Step::new("GET", "/{bucket}").query("versions"))
+        .step(Step::new("GET", "/{bucket}").query("list-type=2"))
+        .step(delete_version("k", "marker"))
+        .step(delete_version("k", "v1"))
+        .step(delete_bucket())
+}
+
+fn versioning_delete_version() -> Scenario {
+    Scenario::s3("s3_versioning_delete_version")
+        .step(create_bucket())
+        .step(enable_versioning())
+        .step(put("k", "first").capture("v1", Capture::Header("x-amz-version-id")))
+        .step(put("k", "second").capture("v2", Capture::Header("x-amz-version-id")))
+        .step(Step::new("DELETE", "/{bucket}/k").query("versionId={v2}"))
+        .step(Step::new("GET", "/{bucket}/k"))
+        .step(Step::new("GET", "/{bucket}").query("versions"))
+        .step(delete_version("k", "v1"))
+        .step(delete_bucket())
+}
+
+fn versioning_suspended() -> Scenario {
+    Scenario::s3("s3_versioning_suspended")
+        .step(create_bucket())
+        .step(enable_versioning())
+        .step(put("k", "versioned").capture("v1", Capture::Header("x-amz-version-id")))
+        .step(
+            Step::new("PUT", "/{bucket}")
+                .query("versioning")
+                .body(VERSIONING_SUSPENDED)
+                .setup(),
+        )
+        .step(Step::new("PUT", "/{bucket}/k").body("null version"))
+        .step(Step::new("GET", "/{bucket}/k").query("versionId=null"))
+        .step(Step::new("GET", "/{bucket}").query("versions"))
+        .step(Step::new("GET", "/{bucket}").query("versioning"))
+        .step(
+            Step::new("DELETE", "/{bucket}/k")
+                .query("versionId=null")
+                .setup(),
+        )
+        .step(delete_version("k", "v1"))
+        .step(delete_bucket())
+}
+
+fn create_upload(key: &str, var: &'static str) -> Step {
+    Step::new("POST", format!("/{{bucket}}/{key}"))
+        .query("uploads")
+        .capture(var, Capture::XmlElement("UploadId"))
+}
+
+fn upload_part(key: &str, upload: &str, part: u32, body: Vec<u8>) -> Step {
+    Step::new("PUT", format!("/{{bucket}}/{key}"))
+        .query(format!("partNumber={part}&uploadId={{{upload}}}"))
+        .body(body)
+}
+
+fn complete_body(parts: &[(u32, &str)]) -> String {
+    let mut body = String::from("<CompleteMultipartUpload>");
+    for (number, etag) in parts {
+        let _ = write!(
+            body,
+            "<Part><PartNumber>{number}</PartNumber><ETag>{{{etag}}}</ETag></Part>"
+        );
+    }
+    body.push_str("</CompleteMultipartUpload>");
+    body
+}
+
+fn abort_upload(key: &str, upload: &str) -> Step {
+    Step::new("DELETE", format!("/{{bucket}}/{key}"))
+        .query(format!("uploadId={{{upload}}}"))
+        .setup()
+}
+
+fn multipart_complete() -> Scenario {
+    Scenario::s3("s3_multipart_complete")
+        .step(create_bucket())
+        .step(create_upload("big", "upload"))
+        .step(
+            upload_part("big", "upload", 1, vec![b'a'; PART_SIZE])
+                .capture("etag1", Capture::Header("etag")),
+        )
+        .step(
+            upload_part("big", "upload", 2, b"tail".to_vec())
+                .capture("etag2", Capture::Header("etag")),
+        )
+        .step(Step::new("GET", "/{bucket}/big").query("uploadId={upload}"))
+        .step(
+            Step::new("POST", "/{bucket}/big")
+                .query("uploadId={upload}")
+                .body(complete_body(&[(1, "etag1"), (2, "etag2")])),
+        )
+        .step(Step::new("HEAD", "/{bucket}/big"))
+        .step(Step::new("GET", "/{bucket}/big").header("range", "bytes=5242878-5242881"))
+        .step(delete("big"))
+        .step(delete_bucket())
+}
+
+fn multipart_abort() -> Scenario {
+    Scenario::s3("s3_multipart_abort")
+        .step(create_bucket())
+        .step(create_upload("k", "upload"))
+        .step(upload_part("k", "upload", 1, b"part".to_vec()))
+        .step(Step::new("DELETE", "/{bucket}/k").query("uploadId={upload}"))
+        .step(Step::new("GET", "/{bucket}/k").query("uploadId={upload}"))
+        .step(upload_part("k", "upload", 2, b"late".to_vec()))
+        .step(delete_bucket())
+}
+
+fn multipart_entity_too_small() -> Scenario {
+    Scenario::s3("s3_multipart_entity_too_small")
+        .step(create_bucket())
+        .step(create_upload("k", "upload"))
+        .step(
+            upload_part("k", "upload", 1, b"small".to_vec())
+                .capture("etag1", Capture::Header("etag")),
+        )
+        .step(
+            upload_part("k", "upload", 2, b"tail".to_vec())
+                .capture("etag2", Capture::Header("etag")),
+        )
+        .step(
+            Step::new("POST", "/{bucket}/k")
+                .query("uploadId={upload}")
+                .body(complete_body(&[(1, "etag1"), (2, "etag2")])),
+        )
+        .step(abort_upload("k", "upload"))
+        .step(delete_bucket())
+}
+
+fn multipart_invalid_part_order() -> Scenario {
+    Scenario::s3("s3_multipart_invalid_part_order")
+        .step(create_bucket())
+        .step(create_upload("k", "upload"))
+        .step(
+            upload_part("k", "upload", 1, vec![b'a'; PART_SIZE])
+                .capture("etag1", Capture::Header("etag"))
+                .setup(),
+        )
+        .step(
+            upload_part("k", "upload", 2, b"tail".to_vec())
+                .capture("etag2", Capture::Header("etag"))
+                .setup(),
+        )
+        .step(
+            Step::new("POST", "/{bucket}/k")
+                .query("uploadId={upload}")
+                .body(complete_body(&[(2, "etag2"), (1, "etag1")])),
+        )
+        .step(
+            Step::new("POST", "/{bucket}/k")
+                .query("uploadId={upload}")
+                .body(complete_body(&[(1, "etag2")])),
+        )
+        .step(abort_upload("k", "upload"))
+        .step(delete_bucket())
+}
+
+fn list_multipart_uploads() -> Scenario {
+    Scenario::s3("s3_list_multipart_uploads")
+        .step(create_bucket())
+        .step(create_upload("a/1", "upload1").setup())
+        .step(create_upload("a/2", "upload2").setup())
+        .step(create_upload("b", "upload3").setup())
+        .step(Step::new("GET", "/{bucket}").query("uploads"))
+        .step(Step::new("GET", "/{bucket}").query("uploads&delimiter=%2F"))
+        .step(Step::new("GET", "/{bucket}").query("uploads&prefix=a%2F&max-uploads=1"))
+        .step(abort_upload("a/1", "upload1"))
+        .step(abort_upload("a/2", "upload2"))
+        .step(abort_upload("b", "upload3"))
+        .step(delete_bucket())
+}
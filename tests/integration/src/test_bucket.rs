@@ -36,6 +36,29 @@ mod tests {
         cleanup_bucket(&client, &b2).await;
     }
 
+    #[tokio::test]
+    #[ignore = "requires running server"]
+    async fn test_should_reject_location_constraint_for_other_region() {
+        use aws_sdk_s3::types::{BucketLocationConstraint, CreateBucketConfiguration};
+
+        let client = s3_client();
+        let bucket = test_bucket_name("illegal-location");
+        let result = client
+            .create_bucket()
+            .bucket(&bucket)
+            .create_bucket_configuration(
+                CreateBucketConfiguration::builder()
+                    .location_constraint(BucketLocationConstraint::EuWest1)
+                    .build(),
+            )
+            .send()
+            .await;
+        assert!(
+            result.is_err(),
+            "eu-west-1 constraint sent to us-east-1 should fail"
+        );
+    }
+
     #[tokio::test]
     #[ignore = "requires running server"]
     async fn test_should_get_bucket_location() {
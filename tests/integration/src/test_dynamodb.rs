@@ -437,6 +437,72 @@ mod tests {
             .unwrap();
     }
 
+    #[tokio::test]
+    #[ignore = "requires running server"]
+    async fn test_should_sum_filtered_count_query_across_pages() {
+        let client = dynamodb_client();
+        let table_name = test_table_name("qcount");
+
+        create_composite_table(&client, &table_name).await;
+
+        for i in 0..30 {
+            client
+                .put_item()
+                .table_name(&table_name)
+                .item("pk", AttributeValue::S("p1".to_owned()))
+                .item("sk", AttributeValue::S(format!("s{i:02}")))
+                .item("val", AttributeValue::N((i % 7).to_string()))
+                .send()
+                .await
+                .unwrap();
+        }
+
+        // Ground truth: count the matching items from a full scan.
+        let scan = client.scan().table_name(&table_name).send().await.unwrap();
+        let expected = scan
+            .items()
+            .iter()
+            .filter(|item| item.get("val") == Some(&AttributeValue::N("0".to_owned())))
+            .count();
+
+        let (mut count, mut scanned_count) = (0, 0);
+        let mut start = None;
+        loop {
+            let resp = client
+                .query()
+                .table_name(&table_name)
+                .select(aws_sdk_dynamodb::types::Select::Count)
+                .key_condition_expression("pk = :pk")
+                .filter_expression("val = :zero")
+                .expression_attribute_values(":pk", AttributeValue::S("p1".to_owned()))
+                .expression_attribute_values(":zero", AttributeValue::N("0".to_owned()))
+                .limit(4)
+                .set_exclusive_start_key(start)
+                .send()
+                .await
+                .unwrap();
+            assert!(resp.items().is_empty());
+            assert!(resp.scanned_count() <= 4);
+            count += resp.count();
+            scanned_count += resp.scanned_count();
+            start = resp.last_evaluated_key().cloned();
+            if start.is_none() {
+                break;
+            }
+        }
+
+        assert_eq!(usize::try_from(count).unwrap(), expected);
+        assert_eq!(scanned_count, 30);
+
+        // Cleanup.
+        client
+            .delete_table()
+            .table_name(&table_name)
+            .send()
+            .await
+            .unwrap();
+    }
+
     #[tokio::test]
     #[ignore = "requires running server"]
     async fn test_should_scan_all_items() {
@@ -977,9 +1043,21 @@ mod tests {
         let resp = client.describe_endpoints().send().await.unwrap();
 
         let endpoints = resp.endpoints();
-        assert!(
-            !endpoints.is_empty(),
-            "expected at least one endpoint from DescribeEndpoints"
-        );
+        assert_eq!(endpoints.len(), 1);
+        assert!(endpoints[0].cache_period_in_minutes() >= 60);
+
+        // The discovered endpoint must be this server, as an SDK with endpoint
+        // discovery enabled sends every following call to it.
+        let discovered = format!("http://{}", endpoints[0].address());
+        let config = client
+            .config()
+            .to_builder()
+            .endpoint_url(discovered)
+            .build();
+        aws_sdk_dynamodb::Client::from_conf(config)
+            .list_tables()
+            .send()
+            .await
+            .unwrap();
     }
 }
@@ -1,8 +1,9 @@
-//! HTTP precondition integration tests (If-Match, If-None-Match).
+//! HTTP precondition integration tests (If-Match, If-None-Match,
+//! If-Modified-Since, If-Unmodified-Since).
 
 #[cfg(test)]
 mod tests {
-    use aws_sdk_s3::primitives::ByteStream;
+    use aws_sdk_s3::primitives::{ByteStream, DateTime};
 
     use crate::{cleanup_bucket, create_test_bucket, s3_client};
 
@@ -126,4 +127,164 @@ mod tests {
 
         cleanup_bucket(&client, &bucket).await;
     }
+
+    /// One row of a conditional-request matrix: the four headers and the
+    /// status S3 answers with.
+    struct Case<'a> {
+        if_match: Option<&'a str>,
+        if_unmodified_since: Option<DateTime>,
+        if_none_match: Option<&'a str>,
+        if_modified_since: Option<DateTime>,
+        status: u16,
+    }
+
+    fn status_of<T, E>(
+        result: Result<T, aws_sdk_s3::error::SdkError<E, aws_smithy_runtime_api::http::Response>>,
+    ) -> u16 {
+        match result {
+            Ok(_) => 200,
+            Err(e) => e
+                .raw_response()
+                .map_or(0, |response| response.status().as_u16()),
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires running server"]
+    #[allow(clippy::too_many_lines)]
+    async fn test_should_evaluate_combined_preconditions_in_rfc_order() {
+        let client = s3_client();
+        let bucket = create_test_bucket(&client, "condmatrix").await;
+
+        let put = client
+            .put_object()
+            .bucket(&bucket)
+            .key("cond.txt")
+            .body(ByteStream::from_static(b"data"))
+            .send()
+            .await
+            .expect("put");
+        let etag = put.e_tag().expect("etag").to_owned();
+        let head = client
+            .head_object()
+            .bucket(&bucket)
+            .key("cond.txt")
+            .send()
+            .await
+            .expect("head");
+        // HTTP dates have whole seconds, so this is the write's own second.
+        let modified = *head.last_modified().expect("last modified");
+        let before = DateTime::from_secs(modified.secs() - 1);
+        let after = DateTime::from_secs(modified.secs() + 1);
+
+        let none = Case {
+            if_match: None,
+            if_unmodified_since: None,
+            if_none_match: None,
+            if_modified_since: None,
+            status: 200,
+        };
+        let cases = [
+            // If-Match takes priority over If-Unmodified-Since.
+            Case {
+                if_match: Some(&etag),
+                if_unmodified_since: Some(before),
+                ..none
+            },
+            Case {
+                if_match: Some("\"wrong-etag\""),
+                if_unmodified_since: Some(after),
+                status: 412,
+                ..none
+            },
+            Case {
+                if_unmodified_since: Some(before),
+                status: 412,
+                ..none
+            },
+            Case {
+                if_unmodified_since: Some(modified),
+                ..none
+            },
+            // If-None-Match takes priority over If-Modified-Since.
+            Case {
+                if_none_match: Some("\"wrong-etag\""),
+                if_modified_since: Some(modified),
+                ..none
+            },
+            Case {
+                if_none_match: Some(&etag),
+                if_modified_since: Some(before),
+                status: 304,
+                ..none
+            },
+            // Written in the same second as the header: not modified.
+            Case {
+                if_modified_since: Some(modified),
+                status: 304,
+                ..none
+            },
+            Case {
+                if_modified_since: Some(before),
+                ..none
+            },
+            // A passing If-Match still lets If-None-Match answer 304.
+            Case {
+                if_match: Some(&etag),
+                if_none_match: Some(&etag),
+                status: 304,
+                ..none
+            },
+            // A failing If-Match is reported before If-None-Match.
+            Case {
+                if_match: Some("\"wrong-etag\""),
+                if_none_match: Some(&etag),
+                status: 412,
+                ..none
+            },
+        ];
+
+        for (row, case) in cases.iter().enumerate() {
+            let get = client
+                .get_object()
+                .bucket(&bucket)
+                .key("cond.txt")
+                .set_if_match(case.if_match.map(ToOwned::to_owned))
+                .set_if_unmodified_since(case.if_unmodified_since)
+                .set_if_none_match(case.if_none_match.map(ToOwned::to_owned))
+                .set_if_modified_since(case.if_modified_since)
+                .send()
+                .await;
+            assert_eq!(status_of(get), case.status, "GET row {row}");
+
+            let head = client
+                .head_object()
+                .bucket(&bucket)
+                .key("cond.txt")
+                .set_if_match(case.if_match.map(ToOwned::to_owned))
+                .set_if_unmodified_since(case.if_unmodified_since)
+                .set_if_none_match(case.if_none_match.map(ToOwned::to_owned))
+                .set_if_modified_since(case.if_modified_since)
+                .send()
+                .await;
+            assert_eq!(status_of(head), case.status, "HEAD row {row}");
+
+            // A copy is not a GET: what would be 304 fails with 412.
+            let copy = client
+                .copy_object()
+                .bucket(&bucket)
+                .key("copy.txt")
+                .copy_source(format!("{bucket}/cond.txt"))
+                .set_copy_source_if_match(case.if_match.map(ToOwned::to_owned))
+                .set_copy_source_if_unmodified_since(case.if_unmodified_since)
+                .set_copy_source_if_none_match(case.if_none_match.map(ToOwned::to_owned))
+                .set_copy_source_if_modified_since(case.if_modified_since)
+                .send()
+                .await;
+            let expected = if case.status == 304 { 412 } else { case.status };
+            assert_eq!(status_of(copy), expected, "copy row {row}");
+        }
+
+        cleanup_bucket(&client, &bucket).await;
+    }
 }